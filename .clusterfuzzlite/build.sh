@@ -4,4 +4,7 @@ cargo +nightly fuzz build
 for target in fuzz_targets/*; do
     target_name=$(basename ${target%.rs})
     cp target/x86_64-unknown-linux-gnu/release/$target_name $OUT/
+    if [ -f options/$target_name.options ]; then
+        cp options/$target_name.options $OUT/
+    fi
 done
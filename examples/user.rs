@@ -127,3 +127,11 @@ fn test_validate_user_empty_email() {
         assert!(validate_user(user).is_err());
     }
 }
+
+fn main() {
+    let user = example_user();
+    match validate_user(user.clone()) {
+        Ok(valid) => println!("valid user: {:?}", valid),
+        Err(reason) => println!("invalid user: {}", reason),
+    }
+}
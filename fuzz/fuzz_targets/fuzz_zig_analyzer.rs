@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// GENERATED by language-interop-compiler (codegen::fuzz) - do not edit
+#![no_main]
+use language_interop::analyzers::zig_analyzer;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Arbitrary UTF-8 goes straight into the real parser: panics abort the run,
+    // slow inputs trip the timeout in fuzz/options/fuzz_zig_analyzer.options
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(types) = zig_analyzer::analyze_zig_types(input) {
+            for analyzed in &types {
+                let _ = zig_analyzer::compatibility_score(analyzed, "rust");
+            }
+        }
+    }
+});
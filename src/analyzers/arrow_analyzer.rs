@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Apache Arrow schema analyzer for protocol-squisher integration
+// Reads the JSON form of a schema, as used by Arrow's integration tests: the
+// schema itself becomes a type, and every struct column is hoisted into a type
+// of its own
+
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::json::{self, Json};
+
+/// The schema or a struct column
+#[derive(Debug, Clone)]
+pub struct ArrowType {
+    pub name: String,
+    pub kind: ArrowTypeKind,
+    pub fields: Vec<ArrowField>,
+    /// JSON pointer to the fields (`#/schema/fields/2/children`)
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrowTypeKind {
+    Schema,
+    Struct,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowField {
+    pub name: String,
+    /// For a dictionary-encoded column, the type of its values
+    pub field_type: ArrowFieldType,
+    pub nullable: bool,
+    pub dictionary: Option<ArrowDictionary>,
+}
+
+/// How a dictionary-encoded column stores its values
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrowDictionary {
+    pub id: i64,
+    pub index_type: ArrowFieldType,
+    pub ordered: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrowFieldType {
+    Null,
+    Bool,
+    Int { bits: u8, signed: bool },
+    /// Half, single, or double precision
+    Float { bits: u8 },
+    Decimal { precision: u32, scale: i32 },
+    /// `utf8`, `largeutf8`, `utf8view`
+    Utf8,
+    /// `binary`, `largebinary`, `binaryview`, `fixedsizebinary`
+    Binary,
+    /// Days (32 bits) or milliseconds (64 bits) since the epoch
+    Date { bits: u8 },
+    Time { bits: u8 },
+    Timestamp { unit: String, timezone: Option<String> },
+    Duration,
+    Interval,
+    /// `list`, `largelist`, `listview`
+    List(Box<ArrowFieldType>),
+    FixedSizeList(Box<ArrowFieldType>, u32),
+    Map(Box<ArrowFieldType>, Box<ArrowFieldType>),
+    Union(Vec<ArrowFieldType>),
+    /// A struct column, hoisted as `{Parent}{Field}`
+    Struct(String),
+}
+
+/// Analyze the schema of an Arrow schema JSON document
+pub fn analyze_arrow_type(source: &str) -> Result<ArrowType, String> {
+    analyze_arrow_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze the schema and every struct column nested in it; the document may
+/// be the schema object or wrap it under `schema`
+pub fn analyze_arrow_types(source: &str) -> Result<Vec<ArrowType>, String> {
+    let document = json::parse(source)?;
+    let (schema, location) = match document.get("schema") {
+        Some(schema) => (schema, "#/schema"),
+        None => (&document, "#"),
+    };
+    let Some(fields) = schema.get("fields").and_then(Json::as_array) else {
+        return Err("expected a schema object with `fields`".to_string());
+    };
+    // Schemas are unnamed; a `name` metadata entry names the type
+    let name = metadata(schema, "name").map(pascal_case).unwrap_or_else(|| "Root".to_string());
+
+    let mut analyzer = Analyzer { types: Vec::new() };
+    analyzer.define(&name, ArrowTypeKind::Schema, fields, format!("{}/fields", location))?;
+    Ok(analyzer.types)
+}
+
+struct Analyzer {
+    types: Vec<ArrowType>,
+}
+
+impl Analyzer {
+    fn define(&mut self, name: &str, kind: ArrowTypeKind, fields: &[Json], location: String) -> Result<(), String> {
+        let index = self.types.len();
+        self.types.push(ArrowType { name: name.to_string(), kind, fields: Vec::new(), location: location.clone() });
+        for (i, field) in fields.iter().enumerate() {
+            let field = self.field(field, name, &format!("{}/{}", location, i))?;
+            self.types[index].fields.push(field);
+        }
+        Ok(())
+    }
+
+    fn field(&mut self, field: &Json, parent: &str, location: &str) -> Result<ArrowField, String> {
+        let name = field.get("name").and_then(Json::as_str).unwrap_or_default().to_string();
+        let hint = format!("{}{}", parent, pascal_case(&name));
+        let field_type = self.field_type(field, &hint, location)?;
+        let dictionary = match field.get("dictionary") {
+            Some(encoding) => Some(ArrowDictionary {
+                id: encoding.get("id").and_then(Json::as_f64).unwrap_or_default() as i64,
+                index_type: match encoding.get("indexType") {
+                    Some(index) => self.typed(index, &[], &hint, location)?,
+                    None => ArrowFieldType::Int { bits: 32, signed: true },
+                },
+                ordered: encoding.get("isOrdered") == Some(&Json::Bool(true)),
+            }),
+            None => None,
+        };
+        Ok(ArrowField {
+            name,
+            field_type,
+            // Arrow fields are nullable unless they say otherwise
+            nullable: field.get("nullable") != Some(&Json::Bool(false)),
+            dictionary,
+        })
+    }
+
+    /// Type of a field from its `type` and `children`; struct columns become
+    /// types named `hint`
+    fn field_type(&mut self, field: &Json, hint: &str, location: &str) -> Result<ArrowFieldType, String> {
+        let Some(ty) = field.get("type") else {
+            return Err(format!("{}: field without a `type`", location));
+        };
+        let children = field.get("children").and_then(Json::as_array).unwrap_or_default();
+        self.typed(ty, children, hint, location)
+    }
+
+    fn typed(&mut self, ty: &Json, children: &[Json], hint: &str, location: &str) -> Result<ArrowFieldType, String> {
+        let name = ty.get("name").and_then(Json::as_str).unwrap_or_default();
+        let unit = ty.get("unit").and_then(Json::as_str);
+        let bit_width = ty.get("bitWidth").and_then(Json::as_f64).map(|bits| bits as u8);
+        let child = |analyzer: &mut Self, i: usize| -> Result<ArrowFieldType, String> {
+            match children.get(i) {
+                Some(child) => analyzer.field_type(child, hint, &format!("{}/children/{}", location, i)),
+                None => Err(format!("{}: `{}` without child {}", location, name, i)),
+            }
+        };
+        Ok(match name {
+            "null" => ArrowFieldType::Null,
+            "bool" => ArrowFieldType::Bool,
+            "int" => ArrowFieldType::Int { bits: bit_width.unwrap_or(32), signed: ty.get("isSigned") != Some(&Json::Bool(false)) },
+            "floatingpoint" => ArrowFieldType::Float {
+                bits: match ty.get("precision").and_then(Json::as_str) {
+                    Some("HALF") => 16,
+                    Some("SINGLE") => 32,
+                    _ => 64,
+                },
+            },
+            "decimal" => ArrowFieldType::Decimal {
+                precision: ty.get("precision").and_then(Json::as_f64).unwrap_or_default() as u32,
+                scale: ty.get("scale").and_then(Json::as_f64).unwrap_or_default() as i32,
+            },
+            "utf8" | "largeutf8" | "utf8view" => ArrowFieldType::Utf8,
+            "binary" | "largebinary" | "binaryview" | "fixedsizebinary" => ArrowFieldType::Binary,
+            "date" => ArrowFieldType::Date { bits: if unit == Some("DAY") { 32 } else { 64 } },
+            "time" => ArrowFieldType::Time { bits: bit_width.unwrap_or(64) },
+            "timestamp" => ArrowFieldType::Timestamp {
+                unit: unit.unwrap_or("MICROSECOND").to_string(),
+                timezone: ty.get("timezone").and_then(Json::as_str).map(str::to_string),
+            },
+            "duration" => ArrowFieldType::Duration,
+            "interval" => ArrowFieldType::Interval,
+            "list" | "largelist" | "listview" | "largelistview" => ArrowFieldType::List(Box::new(child(self, 0)?)),
+            "fixedsizelist" => {
+                let size = ty.get("listSize").and_then(Json::as_f64).unwrap_or_default() as u32;
+                ArrowFieldType::FixedSizeList(Box::new(child(self, 0)?), size)
+            }
+            // One `entries` struct child holding `key` and `value`
+            "map" => {
+                let entries = children.first().and_then(|e| e.get("children")).and_then(Json::as_array).unwrap_or_default();
+                let [key, value] = entries else {
+                    return Err(format!("{}: `map` without key and value entries", location));
+                };
+                let entries = format!("{}/children/0/children", location);
+                let key = self.field_type(key, &format!("{}Key", hint), &format!("{}/0", entries))?;
+                let value = self.field_type(value, &format!("{}Value", hint), &format!("{}/1", entries))?;
+                ArrowFieldType::Map(Box::new(key), Box::new(value))
+            }
+            "union" => {
+                let members = (0..children.len()).map(|i| child(self, i)).collect::<Result<_, _>>()?;
+                ArrowFieldType::Union(members)
+            }
+            "struct" => {
+                self.define(hint, ArrowTypeKind::Struct, children, format!("{}/children", location))?;
+                ArrowFieldType::Struct(hint.to_string())
+            }
+            // Run ends, then the values they repeat
+            "runendencoded" => child(self, 1)?,
+            other => return Err(format!("{}: unknown Arrow type `{}`", location, other)),
+        })
+    }
+}
+
+/// A schema metadata value, written as `[{ "key": .., "value": .. }]` or as an object
+fn metadata<'a>(schema: &'a Json, key: &str) -> Option<&'a str> {
+    match schema.get("metadata")? {
+        Json::Array(entries) => entries
+            .iter()
+            .find(|entry| entry.get("key").and_then(Json::as_str) == Some(key))
+            .and_then(|entry| entry.get("value"))
+            .and_then(Json::as_str),
+        object => object.get(key).and_then(Json::as_str),
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(arrow_type: &ArrowType, target: &str) -> f32 {
+    average_fidelity(ir_fields(arrow_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an Arrow type into the language-neutral IR
+pub fn to_ir(field_type: &ArrowFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        ArrowFieldType::Null => primitive(PrimitiveType::Unit),
+        ArrowFieldType::Bool => primitive(PrimitiveType::Bool),
+        // 8- and 16-bit columns widen; the IR has nothing narrower than 32 bits
+        ArrowFieldType::Int { bits, signed: true } if *bits <= 32 => primitive(PrimitiveType::I32),
+        ArrowFieldType::Int { bits, signed: false } if *bits <= 32 => primitive(PrimitiveType::U32),
+        ArrowFieldType::Int { signed: true, .. } => primitive(PrimitiveType::I64),
+        ArrowFieldType::Int { signed: false, .. } => primitive(PrimitiveType::U64),
+        ArrowFieldType::Float { bits } if *bits <= 32 => primitive(PrimitiveType::F32),
+        ArrowFieldType::Float { .. } => primitive(PrimitiveType::F64),
+        ArrowFieldType::Utf8 => primitive(PrimitiveType::String),
+        // Temporal columns cross as the integers they're stored as
+        ArrowFieldType::Date { bits } | ArrowFieldType::Time { bits } if *bits <= 32 => primitive(PrimitiveType::I32),
+        ArrowFieldType::Date { .. } | ArrowFieldType::Time { .. } | ArrowFieldType::Timestamp { .. } | ArrowFieldType::Duration => {
+            primitive(PrimitiveType::I64)
+        }
+        ArrowFieldType::Decimal { .. } => primitive(PrimitiveType::Decimal),
+        ArrowFieldType::Binary => primitive(PrimitiveType::Bytes),
+        // No IR form yet for month-day-nanosecond intervals
+        ArrowFieldType::Interval => IrType::Any,
+        ArrowFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        ArrowFieldType::List(items) | ArrowFieldType::FixedSizeList(items, _) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        ArrowFieldType::Union(members) => {
+            let mut present: Vec<IrType> = Vec::new();
+            for ty in members.iter().map(to_ir) {
+                if !present.contains(&ty) {
+                    present.push(ty);
+                }
+            }
+            match present.len() {
+                0 => IrType::Any,
+                1 => present.remove(0),
+                _ => IrType::Union(present),
+            }
+        }
+        ArrowFieldType::Struct(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// Lower the columns of a type into the IR; nullable columns become options
+pub fn ir_fields(arrow_type: &ArrowType) -> Vec<IrField> {
+    arrow_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = match ty {
+                IrType::Any => ty,
+                _ if field.nullable => IrType::Container(ContainerType::Option(Box::new(ty))),
+                _ => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map Arrow types to target language types
+pub fn map_to_target(field_type: &ArrowFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIPS: &str = r#"{
+  "schema": {
+    "fields": [
+      { "name": "id", "nullable": false, "type": { "name": "int", "bitWidth": 64, "isSigned": true }, "children": [] },
+      { "name": "vendor", "nullable": true, "type": { "name": "utf8" }, "children": [],
+        "dictionary": { "id": 0, "indexType": { "name": "int", "bitWidth": 8, "isSigned": true }, "isOrdered": false } },
+      { "name": "fare", "nullable": false, "type": { "name": "floatingpoint", "precision": "SINGLE" }, "children": [] },
+      { "name": "pickup", "nullable": false, "type": { "name": "timestamp", "unit": "MICROSECOND", "timezone": "UTC" }, "children": [] },
+      { "name": "stops", "nullable": false, "type": { "name": "list" }, "children": [
+        { "name": "item", "nullable": false, "type": { "name": "struct" }, "children": [
+          { "name": "lat", "nullable": false, "type": { "name": "floatingpoint", "precision": "DOUBLE" }, "children": [] },
+          { "name": "count", "nullable": true, "type": { "name": "int", "bitWidth": 16, "isSigned": false }, "children": [] }
+        ] }
+      ] },
+      { "name": "tags", "nullable": true, "type": { "name": "map", "keysSorted": false }, "children": [
+        { "name": "entries", "nullable": false, "type": { "name": "struct" }, "children": [
+          { "name": "key", "nullable": false, "type": { "name": "utf8" }, "children": [] },
+          { "name": "value", "nullable": true, "type": { "name": "utf8" }, "children": [] }
+        ] }
+      ] },
+      { "name": "total", "nullable": true, "type": { "name": "decimal", "precision": 10, "scale": 2 }, "children": [] }
+    ],
+    "metadata": [{ "key": "name", "value": "trip" }]
+  }
+}"#;
+
+    #[test]
+    fn test_analyze_arrow() {
+        let types = analyze_arrow_types(TRIPS).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![("Trip", ArrowTypeKind::Schema, "#/schema/fields"), ("TripStops", ArrowTypeKind::Struct, "#/schema/fields/4/children/0/children")]
+        );
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: i64",
+                "vendor: Option<String>",
+                "fare: f32",
+                "pickup: i64",
+                "stops: Vec<TripStops>",
+                "tags: Option<HashMap<String, String>>",
+                "total: Option<rust_decimal::Decimal>",
+            ]
+        );
+        let vendor = types[0].fields[1].dictionary.as_ref().unwrap();
+        assert_eq!(vendor.index_type, ArrowFieldType::Int { bits: 8, signed: true });
+        assert_eq!(types[0].fields[5].field_type, ArrowFieldType::Map(Box::new(ArrowFieldType::Utf8), Box::new(ArrowFieldType::Utf8)));
+        assert_eq!(map_to_target(&types[1].fields[1].field_type, "rust"), "u32");
+        assert_eq!(compatibility_score(&types[1], "julia"), 1.0);
+        // The decimal is exact in Rust but only a float in Gleam
+        assert_eq!(compatibility_score(&types[0], "rust"), 1.0);
+        assert!(compatibility_score(&types[0], "gleam") < 1.0);
+    }
+
+    #[test]
+    fn test_bare_schema_and_errors() {
+        let schema = r#"{ "fields": [{ "name": "ok", "type": { "name": "bool" } }] }"#;
+        let root = analyze_arrow_type(schema).unwrap();
+        assert_eq!((root.name.as_str(), root.location.as_str()), ("Root", "#/fields"));
+        assert!(root.fields[0].nullable);
+        assert_eq!(map_to_target(&root.fields[0].field_type, "gleam"), "Bool");
+        assert!(analyze_arrow_types("[]").is_err());
+        assert!(analyze_arrow_types(r#"{ "fields": [{ "name": "x", "type": { "name": "list" } }] }"#).is_err());
+        assert!(analyze_arrow_types(r#"{ "fields": [{ "name": "x", "type": { "name": "tensor" } }] }"#).is_err());
+    }
+}
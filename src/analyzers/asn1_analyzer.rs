@@ -0,0 +1,727 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// ASN.1 analyzer for protocol-squisher integration
+// Reads type assignments from X.680 modules: SEQUENCE, SET, CHOICE, and
+// ENUMERATED types, with inline ones hoisted under names of their own; value
+// assignments and information object classes are skipped
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+const ASN1: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: &[("/*", "*/")],
+    // Single quotes open bit and hex strings (`'0F'H`)
+    string_quotes: &['"', '\''],
+    ident_extra: &['-'],
+    char_literals: false,
+};
+
+/// Components a type may gain from `COMPONENTS OF`; bounds chains that
+/// include the same sequence many times over
+const MAX_FIELDS: usize = 1024;
+
+/// ASN.1 type assignment
+#[derive(Debug, Clone)]
+pub struct Asn1Type {
+    pub name: String,
+    /// Module the assignment appears in, if the file declares one
+    pub module: Option<String>,
+    pub kind: Asn1TypeKind,
+    pub fields: Vec<Asn1Field>,
+    pub values: Vec<Asn1EnumValue>,
+    /// What any other assignment names
+    pub alias: Option<Asn1FieldType>,
+    /// Has an extension marker (`...`)
+    pub extensible: bool,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Asn1TypeKind {
+    Sequence,
+    /// Components in any order; otherwise a sequence
+    Set,
+    /// Exactly one alternative is present
+    Choice,
+    Enumerated,
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct Asn1Field {
+    pub name: String,
+    pub field_type: Asn1FieldType,
+    /// `OPTIONAL`
+    pub optional: bool,
+    /// `DEFAULT value`: decoders fill in the value when it's absent
+    pub has_default: bool,
+}
+
+/// `suspended(2)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asn1EnumValue {
+    pub name: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Asn1FieldType {
+    Boolean,
+    /// `INTEGER`, with the bounds of its value constraint
+    Integer { min: Option<i64>, max: Option<i64> },
+    Real,
+    Null,
+    BitString,
+    OctetString,
+    /// `OBJECT IDENTIFIER`, `RELATIVE-OID`
+    ObjectIdentifier,
+    /// `UTF8String`, `IA5String`, `PrintableString`, and the other character strings
+    CharacterString,
+    /// `UTCTime`, `GeneralizedTime`, `DATE`, `TIME-OF-DAY`, `DATE-TIME`
+    Time,
+    /// `ANY`, `EXTERNAL`, open types, and class fields
+    Any,
+    /// `SEQUENCE OF T` or `SET OF T`
+    SequenceOf(Box<Asn1FieldType>),
+    /// Another assignment, possibly module-qualified, or a hoisted inline type
+    Named(String),
+}
+
+/// Analyze the first type assignment in an ASN.1 file
+pub fn analyze_asn1_type(source: &str) -> Result<Asn1Type, String> {
+    analyze_asn1_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every type assignment in an ASN.1 file, inside module bodies or not
+pub fn analyze_asn1_types(source: &str) -> Result<Vec<Asn1Type>, String> {
+    let mut parser = Parser { cursor: Cursor::new(tokenize(source, &ASN1)), module: None, types: Vec::new(), components: Vec::new() };
+    while let Some(token) = parser.cursor.peek().cloned() {
+        match token.text.as_str() {
+            "END" => {
+                parser.cursor.advance();
+                parser.module = None;
+            }
+            "IMPORTS" | "EXPORTS" => {
+                while !parser.cursor.at_end() && !parser.cursor.eat_punct(';') {
+                    parser.cursor.advance();
+                }
+            }
+            _ if token.kind == TokenKind::Ident && parser.is_module_header() => parser.parse_module_header()?,
+            _ if token.kind == TokenKind::Ident && starts_upper(&token.text) => parser.parse_assignment()?,
+            // `maxItems INTEGER ::= 10`
+            _ if token.kind == TokenKind::Ident => parser.skip_assignment()?,
+            _ => return Err(parser.cursor.unexpected("an assignment")),
+        }
+    }
+    Ok(parser.resolve())
+}
+
+struct Parser {
+    cursor: Cursor,
+    module: Option<String>,
+    types: Vec<Asn1Type>,
+    /// `COMPONENTS OF` references of each type in `types`, by field position
+    components: Vec<Vec<(usize, String)>>,
+}
+
+impl Parser {
+    /// `Name { oid } DEFINITIONS`
+    fn is_module_header(&self) -> bool {
+        let mut lookahead = self.cursor.clone();
+        lookahead.advance();
+        if lookahead.is_punct('{') {
+            lookahead.skip_balanced('{', '}');
+        }
+        lookahead.is_ident("DEFINITIONS")
+    }
+
+    /// `Name { oid } DEFINITIONS AUTOMATIC TAGS ::= BEGIN`
+    fn parse_module_header(&mut self) -> Result<(), String> {
+        self.module = Some(self.cursor.expect_ident()?);
+        while !self.cursor.at_end() && !self.is_assign() {
+            if self.cursor.is_punct('{') {
+                self.cursor.skip_balanced('{', '}');
+            } else {
+                self.cursor.advance();
+            }
+        }
+        self.expect_assign()?;
+        if !self.cursor.eat_ident("BEGIN") {
+            return Err(self.cursor.unexpected("`BEGIN`"));
+        }
+        Ok(())
+    }
+
+    /// `Name ::= Type`, or `Name {Param} ::= Type` with the parameters dropped
+    fn parse_assignment(&mut self) -> Result<(), String> {
+        let line = self.cursor.line();
+        let name = self.cursor.expect_ident()?;
+        if self.cursor.is_punct('{') {
+            self.cursor.skip_balanced('{', '}');
+        }
+        if !self.is_assign() {
+            // Value sets and object sets: `Algorithms ALGORITHM ::= { ... }`
+            return self.skip_assignment();
+        }
+        self.expect_assign()?;
+        if self.cursor.eat_ident("CLASS") {
+            self.cursor.skip_balanced('{', '}');
+            if self.cursor.eat_ident("WITH") {
+                self.cursor.eat_ident("SYNTAX");
+                self.cursor.skip_balanced('{', '}');
+            }
+            return Ok(());
+        }
+        let index = self.define(&name, Asn1TypeKind::Alias, line);
+        self.skip_tag();
+        match self.structure_kind() {
+            Some(kind) => self.parse_structure(index, kind)?,
+            None => {
+                let alias = self.parse_type(&name)?;
+                self.types[index].alias = Some(alias);
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip to `::=` and past the value after it
+    fn skip_assignment(&mut self) -> Result<(), String> {
+        while !self.is_assign() {
+            if self.cursor.at_end() {
+                return Err(self.cursor.unexpected("`::=`"));
+            }
+            if self.cursor.is_punct('{') {
+                self.cursor.skip_balanced('{', '}');
+            } else {
+                self.cursor.advance();
+            }
+        }
+        self.expect_assign()?;
+        self.skip_value();
+        Ok(())
+    }
+
+    fn define(&mut self, name: &str, kind: Asn1TypeKind, line: usize) -> usize {
+        self.types.push(Asn1Type {
+            name: name.to_string(),
+            module: self.module.clone(),
+            kind,
+            fields: Vec::new(),
+            values: Vec::new(),
+            alias: None,
+            extensible: false,
+            location: format!("line {}", line),
+        });
+        self.components.push(Vec::new());
+        self.types.len() - 1
+    }
+
+    /// `SEQUENCE {`, `SET {`, `CHOICE`, or `ENUMERATED` at the cursor
+    fn structure_kind(&self) -> Option<Asn1TypeKind> {
+        let braced = self.cursor.is_punct_at(1, '{');
+        if self.cursor.is_ident("SEQUENCE") && braced {
+            Some(Asn1TypeKind::Sequence)
+        } else if self.cursor.is_ident("SET") && braced {
+            Some(Asn1TypeKind::Set)
+        } else if self.cursor.is_ident("CHOICE") {
+            Some(Asn1TypeKind::Choice)
+        } else if self.cursor.is_ident("ENUMERATED") {
+            Some(Asn1TypeKind::Enumerated)
+        } else {
+            None
+        }
+    }
+
+    /// The braced body of a SEQUENCE, SET, CHOICE, or ENUMERATED type
+    fn parse_structure(&mut self, index: usize, kind: Asn1TypeKind) -> Result<(), String> {
+        self.cursor.advance();
+        self.types[index].kind = kind;
+        self.cursor.expect_punct('{')?;
+        if kind == Asn1TypeKind::Enumerated {
+            self.parse_enumerations(index)?;
+        } else {
+            let hint = self.types[index].name.clone();
+            while !self.cursor.eat_punct('}') {
+                if self.cursor.at_end() {
+                    return Err(self.cursor.unexpected("`}`"));
+                }
+                // Separators and the brackets of extension addition groups (`[[2: ... ]]`)
+                if self.cursor.eat_punct(',') || self.cursor.eat_punct(']') {
+                    continue;
+                }
+                if self.cursor.is_punct('[') && self.cursor.is_punct_at(1, '[') {
+                    self.cursor.advance();
+                    self.cursor.advance();
+                    if self.cursor.peek().is_some_and(|t| t.kind == TokenKind::Number) && self.cursor.is_punct_at(1, ':') {
+                        self.cursor.advance();
+                        self.cursor.advance();
+                    }
+                    continue;
+                }
+                if self.eat_extension_marker() {
+                    self.types[index].extensible = true;
+                    continue;
+                }
+                if self.cursor.eat_ident("COMPONENTS") {
+                    if !self.cursor.eat_ident("OF") {
+                        return Err(self.cursor.unexpected("`OF`"));
+                    }
+                    let position = self.types[index].fields.len();
+                    if let Asn1FieldType::Named(referenced) = self.parse_type(&hint)? {
+                        self.components[index].push((position, referenced));
+                    }
+                    continue;
+                }
+                let field = self.parse_component(&hint)?;
+                self.types[index].fields.push(field);
+            }
+        }
+        self.skip_constraints();
+        Ok(())
+    }
+
+    /// `name [0] IMPLICIT Type (constraint) OPTIONAL`
+    fn parse_component(&mut self, hint: &str) -> Result<Asn1Field, String> {
+        let name = self.cursor.expect_ident()?;
+        let field_type = self.parse_type(&format!("{}{}", hint, pascal_case(&name)))?;
+        let mut field = Asn1Field { name, field_type, optional: false, has_default: false };
+        if self.cursor.eat_ident("OPTIONAL") {
+            field.optional = true;
+        } else if self.cursor.eat_ident("DEFAULT") {
+            field.has_default = true;
+            self.skip_value();
+        }
+        Ok(field)
+    }
+
+    /// `active, suspended(2), ..., closed(-1)`; unnumbered items take the
+    /// lowest values the numbered ones leave free
+    fn parse_enumerations(&mut self, index: usize) -> Result<(), String> {
+        let mut items: Vec<(String, Option<i64>)> = Vec::new();
+        while !self.cursor.eat_punct('}') {
+            if self.cursor.at_end() {
+                return Err(self.cursor.unexpected("`}`"));
+            }
+            if self.cursor.eat_punct(',') {
+                continue;
+            }
+            if self.eat_extension_marker() {
+                self.types[index].extensible = true;
+                continue;
+            }
+            let name = self.cursor.expect_ident()?;
+            let mut value = None;
+            if self.cursor.eat_punct('(') {
+                value = Some(self.parse_integer()?);
+                self.cursor.expect_punct(')')?;
+            }
+            items.push((name, value));
+        }
+        let mut used: Vec<i64> = items.iter().filter_map(|(_, value)| *value).collect();
+        for (name, value) in items {
+            let value = value.unwrap_or_else(|| {
+                let free = (0..).find(|candidate| !used.contains(candidate)).unwrap_or_default();
+                used.push(free);
+                free
+            });
+            self.types[index].values.push(Asn1EnumValue { name, value });
+        }
+        Ok(())
+    }
+
+    /// A type in component or alias position; inline structures become types named `hint`
+    fn parse_type(&mut self, hint: &str) -> Result<Asn1FieldType, String> {
+        self.skip_tag();
+        if let Some(kind) = self.structure_kind() {
+            let line = self.cursor.line();
+            let index = self.define(hint, kind, line);
+            self.parse_structure(index, kind)?;
+            return Ok(Asn1FieldType::Named(hint.to_string()));
+        }
+        let keyword = self.cursor.expect_ident()?;
+        let field_type = match keyword.as_str() {
+            "BOOLEAN" => Asn1FieldType::Boolean,
+            "INTEGER" => {
+                if self.cursor.is_punct('{') {
+                    // Named numbers don't restrict the values
+                    self.cursor.skip_balanced('{', '}');
+                }
+                let (min, max) = self.parse_value_range();
+                Asn1FieldType::Integer { min, max }
+            }
+            "REAL" => Asn1FieldType::Real,
+            "NULL" => Asn1FieldType::Null,
+            "BIT" | "OCTET" | "CHARACTER" => {
+                if !self.cursor.eat_ident("STRING") {
+                    return Err(self.cursor.unexpected("`STRING`"));
+                }
+                if self.cursor.is_punct('{') {
+                    self.cursor.skip_balanced('{', '}');
+                }
+                match keyword.as_str() {
+                    "BIT" => Asn1FieldType::BitString,
+                    "OCTET" => Asn1FieldType::OctetString,
+                    _ => Asn1FieldType::Any,
+                }
+            }
+            "OBJECT" => {
+                if !self.cursor.eat_ident("IDENTIFIER") {
+                    return Err(self.cursor.unexpected("`IDENTIFIER`"));
+                }
+                Asn1FieldType::ObjectIdentifier
+            }
+            "RELATIVE-OID" => Asn1FieldType::ObjectIdentifier,
+            "UTF8String" | "IA5String" | "PrintableString" | "VisibleString" | "ISO646String" | "NumericString" | "BMPString"
+            | "UniversalString" | "TeletexString" | "T61String" | "VideotexString" | "GraphicString" | "GeneralString" | "ObjectDescriptor" => {
+                Asn1FieldType::CharacterString
+            }
+            "UTCTime" | "GeneralizedTime" | "DATE" | "TIME-OF-DAY" | "DATE-TIME" | "TIME" | "DURATION" => Asn1FieldType::Time,
+            "SEQUENCE" | "SET" => {
+                // `SEQUENCE SIZE (1..10) OF`, `SEQUENCE (SIZE (1..10)) OF`
+                if self.cursor.eat_ident("SIZE") || self.cursor.is_punct('(') {
+                    self.skip_constraints();
+                }
+                if !self.cursor.eat_ident("OF") {
+                    return Err(self.cursor.unexpected("`OF` or `{`"));
+                }
+                // `SEQUENCE OF item Item`: the element name is documentation
+                if self.cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident && !starts_upper(&t.text)) {
+                    self.cursor.advance();
+                }
+                let items = self.parse_type(&format!("{}Item", hint))?;
+                Asn1FieldType::SequenceOf(Box::new(items))
+            }
+            "ANY" => {
+                if self.cursor.eat_ident("DEFINED") {
+                    self.cursor.eat_ident("BY");
+                    self.cursor.advance();
+                }
+                Asn1FieldType::Any
+            }
+            "EXTERNAL" => Asn1FieldType::Any,
+            "EMBEDDED" => {
+                self.cursor.eat_ident("PDV");
+                Asn1FieldType::Any
+            }
+            "INSTANCE" => {
+                self.cursor.eat_ident("OF");
+                self.cursor.expect_ident()?;
+                Asn1FieldType::Any
+            }
+            _ if starts_upper(&keyword) => self.parse_reference(keyword),
+            _ => return Err(format!("line {}: expected a type, found `{}`", self.cursor.line(), keyword)),
+        };
+        self.skip_constraints();
+        Ok(field_type)
+    }
+
+    /// `Type`, `Module.Type`, `Type{Param}`, or a class field like `ALGORITHM.&Type`
+    fn parse_reference(&mut self, mut name: String) -> Asn1FieldType {
+        let mut class_field = false;
+        while self.cursor.is_punct('.') {
+            self.cursor.advance();
+            class_field |= self.cursor.eat_punct('&');
+            match self.cursor.peek() {
+                Some(token) if token.kind == TokenKind::Ident => {
+                    name = token.text.clone();
+                    self.cursor.advance();
+                }
+                _ => break,
+            }
+        }
+        if self.cursor.is_punct('{') {
+            self.cursor.skip_balanced('{', '}');
+        }
+        if class_field {
+            Asn1FieldType::Any
+        } else {
+            Asn1FieldType::Named(name)
+        }
+    }
+
+    /// `(0..255)`, `(MIN..0)`, `(7)`; anything else leaves the bounds open
+    fn parse_value_range(&mut self) -> (Option<i64>, Option<i64>) {
+        if !self.cursor.is_punct('(') {
+            return (None, None);
+        }
+        let mut lookahead = self.cursor.clone();
+        lookahead.advance();
+        let bound = |cursor: &mut Cursor| -> Option<Option<i64>> {
+            if cursor.eat_ident("MIN") || cursor.eat_ident("MAX") {
+                return Some(None);
+            }
+            let negative = cursor.eat_punct('-');
+            let number = cursor.peek().filter(|t| t.kind == TokenKind::Number)?.text.parse::<i64>().ok()?;
+            cursor.advance();
+            Some(Some(if negative { -number } else { number }))
+        };
+        let Some(min) = bound(&mut lookahead) else {
+            return (None, None);
+        };
+        let mut max = min;
+        if lookahead.is_punct('.') && lookahead.is_punct_at(1, '.') {
+            lookahead.advance();
+            lookahead.advance();
+            match bound(&mut lookahead) {
+                Some(upper) => max = upper,
+                None => return (None, None),
+            }
+        }
+        // An extensible constraint (`(0..255, ...)`) admits values outside the root
+        if !lookahead.is_punct(')') {
+            return (None, None);
+        }
+        lookahead.advance();
+        self.cursor = lookahead;
+        (min, max)
+    }
+
+    /// `[0]`, `[APPLICATION 5] IMPLICIT`, `[1] EXPLICIT`
+    fn skip_tag(&mut self) {
+        while self.cursor.is_punct('[') && !self.cursor.is_punct_at(1, '[') {
+            self.cursor.skip_balanced('[', ']');
+            if !self.cursor.eat_ident("IMPLICIT") {
+                self.cursor.eat_ident("EXPLICIT");
+            }
+        }
+    }
+
+    /// Subtype constraints (`(SIZE (1..64))`) and table constraints (`({Algorithms})`)
+    fn skip_constraints(&mut self) {
+        while self.cursor.is_punct('(') {
+            self.cursor.skip_balanced('(', ')');
+        }
+    }
+
+    /// `...`, with an optional exception spec (`... ! 5`)
+    fn eat_extension_marker(&mut self) -> bool {
+        if !(self.cursor.is_punct('.') && self.cursor.is_punct_at(1, '.') && self.cursor.is_punct_at(2, '.')) {
+            return false;
+        }
+        for _ in 0..3 {
+            self.cursor.advance();
+        }
+        if self.cursor.eat_punct('!') {
+            self.cursor.eat_punct('-');
+            self.cursor.advance();
+        }
+        true
+    }
+
+    /// `5`, `-1`, `"text"`, `'0F'H`, `TRUE`, `{ 1 2 840 }`, or a value reference
+    fn skip_value(&mut self) {
+        if self.cursor.is_punct('{') {
+            self.cursor.skip_balanced('{', '}');
+            return;
+        }
+        self.cursor.eat_punct('-');
+        let literal = self.cursor.advance().is_some_and(|t| t.kind == TokenKind::Str);
+        if literal && (self.cursor.is_ident("H") || self.cursor.is_ident("B")) {
+            self.cursor.advance();
+        }
+    }
+
+    fn parse_integer(&mut self) -> Result<i64, String> {
+        let negative = self.cursor.eat_punct('-');
+        let number = self.cursor.advance().ok_or_else(|| self.cursor.unexpected("integer"))?;
+        let value: i64 = number.text.parse().map_err(|_| format!("line {}: invalid integer `{}`", number.line, number.text))?;
+        Ok(if negative { -value } else { value })
+    }
+
+    fn is_assign(&self) -> bool {
+        self.cursor.is_punct(':') && self.cursor.is_punct_at(1, ':') && self.cursor.is_punct_at(2, '=')
+    }
+
+    fn expect_assign(&mut self) -> Result<(), String> {
+        if !self.is_assign() {
+            return Err(self.cursor.unexpected("`::=`"));
+        }
+        for _ in 0..3 {
+            self.cursor.advance();
+        }
+        Ok(())
+    }
+
+    /// Splice `COMPONENTS OF` references into the types that name them
+    fn resolve(mut self) -> Vec<Asn1Type> {
+        let mut resolved = vec![None; self.types.len()];
+        for index in 0..self.types.len() {
+            self.fields_of(index, &mut resolved, &mut Vec::new());
+        }
+        for (defined, fields) in self.types.iter_mut().zip(resolved) {
+            defined.fields = fields.unwrap_or_default();
+        }
+        self.types
+    }
+
+    fn fields_of(&self, index: usize, resolved: &mut Vec<Option<Vec<Asn1Field>>>, active: &mut Vec<usize>) -> Vec<Asn1Field> {
+        if let Some(fields) = &resolved[index] {
+            return fields.clone();
+        }
+        let mut fields = self.types[index].fields.clone();
+        active.push(index);
+        // Back to front, so earlier positions stay put
+        for (position, referenced) in self.components[index].iter().rev() {
+            let found = self.types.iter().position(|t| t.name == *referenced && t.kind == Asn1TypeKind::Sequence);
+            if let Some(found) = found.filter(|found| !active.contains(found)) {
+                let spliced = self.fields_of(found, resolved, active);
+                fields.splice(*position..*position, spliced);
+                fields.truncate(MAX_FIELDS);
+            }
+        }
+        active.pop();
+        resolved[index] = Some(fields.clone());
+        fields
+    }
+}
+
+fn starts_upper(text: &str) -> bool {
+    text.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(asn1_type: &Asn1Type, target: &str) -> f32 {
+    average_fidelity(ir_fields(asn1_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an ASN.1 type into the language-neutral IR
+pub fn to_ir(field_type: &Asn1FieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        Asn1FieldType::Boolean => primitive(PrimitiveType::Bool),
+        // The narrowest IR integer holding the constraint; unconstrained
+        // integers are arbitrary precision, which codecs cap at 64 bits
+        Asn1FieldType::Integer { min, max } => match (min, max) {
+            (Some(min), Some(max)) if *min >= 0 && *max <= i64::from(u32::MAX) => primitive(PrimitiveType::U32),
+            (Some(min), Some(max)) if *min >= i64::from(i32::MIN) && *max <= i64::from(i32::MAX) => primitive(PrimitiveType::I32),
+            (Some(min), _) if *min >= 0 => primitive(PrimitiveType::U64),
+            _ => primitive(PrimitiveType::I64),
+        },
+        Asn1FieldType::Real => primitive(PrimitiveType::F64),
+        Asn1FieldType::Null => primitive(PrimitiveType::Unit),
+        // Object identifiers cross as dotted text, times as their string forms
+        Asn1FieldType::CharacterString | Asn1FieldType::ObjectIdentifier | Asn1FieldType::Time => primitive(PrimitiveType::String),
+        Asn1FieldType::OctetString => IrType::Primitive(PrimitiveType::Bytes),
+        // No IR form yet for bit strings
+        Asn1FieldType::BitString | Asn1FieldType::Any => IrType::Any,
+        Asn1FieldType::SequenceOf(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        Asn1FieldType::Named(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// Lower the components of a type into the IR; `OPTIONAL` components and
+/// the alternatives of a CHOICE may be absent
+pub fn ir_fields(asn1_type: &Asn1Type) -> Vec<IrField> {
+    asn1_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let absent = field.optional || asn1_type.kind == Asn1TypeKind::Choice;
+            let ty = if absent { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map ASN.1 types to target language types
+pub fn map_to_target(field_type: &Asn1FieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTORY: &str = r#"
+Directory { iso(1) member-body(2) 42 } DEFINITIONS AUTOMATIC TAGS ::= BEGIN
+
+IMPORTS AlgorithmIdentifier FROM PKIX1Explicit88 { iso(1) 7 };
+
+maxNameLength INTEGER ::= 64
+
+Person ::= SEQUENCE {
+    name        UTF8String (SIZE (1..maxNameLength)),
+    age         INTEGER (0..150) OPTIONAL,
+    id          [0] IMPLICIT INTEGER,
+    status      Status DEFAULT active,
+    phones      SEQUENCE SIZE (0..4) OF PhoneNumber,
+    contact     CHOICE { email IA5String, phone PhoneNumber },
+    fingerprint OCTET STRING OPTIONAL,
+    ...,
+    [[ nickname VisibleString OPTIONAL ]]
+}
+
+-- Account states
+Status ::= ENUMERATED { active, suspended(5), closed, ... }
+
+PhoneNumber ::= [APPLICATION 1] NumericString (SIZE (7..15))
+
+Employee ::= SEQUENCE { COMPONENTS OF Person, employer Directory.Organization }
+
+END
+"#;
+
+    #[test]
+    fn test_analyze_asn1() {
+        let types = analyze_asn1_types(DIRECTORY).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Person", Asn1TypeKind::Sequence, "line 8"),
+                ("PersonContact", Asn1TypeKind::Choice, "line 14"),
+                ("Status", Asn1TypeKind::Enumerated, "line 21"),
+                ("PhoneNumber", Asn1TypeKind::Alias, "line 23"),
+                ("Employee", Asn1TypeKind::Sequence, "line 25"),
+            ]
+        );
+        assert_eq!(types[0].module.as_deref(), Some("Directory"));
+        assert!(types[0].extensible && types[2].extensible);
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "name: String",
+                "age: Option<u32>",
+                "id: i64",
+                "status: Status",
+                "phones: Vec<PhoneNumber>",
+                "contact: PersonContact",
+                "fingerprint: Option<Vec<u8>>",
+                "nickname: Option<String>",
+            ]
+        );
+        let values: Vec<_> = types[2].values.iter().map(|v| (v.name.as_str(), v.value)).collect();
+        assert_eq!(values, vec![("active", 0), ("suspended", 5), ("closed", 1)]);
+        assert_eq!(map_to_target(&types[1].fields[0].field_type, "typescript"), "string");
+        assert_eq!(ir_fields(&types[1])[1].ty, IrType::Container(ContainerType::Option(Box::new(IrType::Reference("PhoneNumber".to_string())))));
+        assert_eq!(types[3].alias, Some(Asn1FieldType::CharacterString));
+
+        let employee: Vec<_> = types[4].fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(employee.len(), 9);
+        assert_eq!(employee[8], "employer");
+        assert_eq!(types[4].fields[8].field_type, Asn1FieldType::Named("Organization".to_string()));
+        assert_eq!(compatibility_score(&types[1], "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_constraints_and_errors() {
+        let reading = analyze_asn1_type("Reading ::= SEQUENCE { delta INTEGER (-128..127), level INTEGER (0..255, ...) }").unwrap();
+        assert_eq!(reading.fields[0].field_type, Asn1FieldType::Integer { min: Some(-128), max: Some(127) });
+        assert_eq!(reading.fields[1].field_type, Asn1FieldType::Integer { min: None, max: None });
+        assert_eq!(map_to_target(&reading.fields[0].field_type, "gleam"), "Int");
+        assert!(analyze_asn1_type("Alg ::= CLASS { &id OBJECT IDENTIFIER UNIQUE } WITH SYNTAX { ID &id }").is_err());
+        assert!(analyze_asn1_types("Open ::= SEQUENCE { a INTEGER").is_err());
+        assert!(analyze_asn1_types("Bad ::= SEQUENCE { a integer }").is_err());
+        assert!(analyze_asn1_types("Dangling ::=").is_err());
+    }
+}
@@ -0,0 +1,400 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Avro analyzer for protocol-squisher integration
+// Reads `.avsc` schemas (a single schema, a list of them, or a protocol's
+// `types`); nested records, enums, and fixed types are named types of their own
+
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::json::{self, Json};
+
+/// Avro record, enum, or fixed type
+#[derive(Debug, Clone)]
+pub struct AvroType {
+    /// Simple name, without the namespace
+    pub name: String,
+    pub namespace: Option<String>,
+    pub kind: AvroTypeKind,
+    pub fields: Vec<AvroField>,
+    /// Enum symbols
+    pub symbols: Vec<String>,
+    /// Byte length of a fixed type
+    pub size: Option<u64>,
+    /// Full name (`com.example.User`)
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AvroTypeKind {
+    /// `record`, or `error` in a protocol
+    Record,
+    Enum,
+    Fixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct AvroField {
+    pub name: String,
+    pub field_type: AvroFieldType,
+    /// Has a `default`, so readers fill it in when a writer's schema lacks it
+    pub has_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AvroFieldType {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(Box<AvroFieldType>),
+    /// String-keyed map
+    Map(Box<AvroFieldType>),
+    Union(Vec<AvroFieldType>),
+    /// Record, enum, or fixed type by simple name
+    Named(String),
+    /// `date`: days since the epoch
+    Date,
+    TimeMillis,
+    TimeMicros,
+    /// `timestamp-millis` and `local-timestamp-millis`
+    TimestampMillis,
+    /// `timestamp-micros` and `local-timestamp-micros`
+    TimestampMicros,
+    Uuid,
+    /// `decimal` on `bytes` or `fixed`
+    Decimal { precision: u32, scale: u32 },
+    /// Months, days, and milliseconds in a `fixed` of 12 bytes
+    Duration,
+}
+
+/// Analyze the first named type of an Avro schema
+pub fn analyze_avro_type(source: &str) -> Result<AvroType, String> {
+    analyze_avro_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every named type of an Avro schema, outermost first
+pub fn analyze_avro_types(source: &str) -> Result<Vec<AvroType>, String> {
+    let document = json::parse(source)?;
+    let mut analyzer = Analyzer { types: Vec::new() };
+    match &document {
+        // An `.avpr` protocol lists its types
+        Json::Object(_) if document.get("protocol").is_some() => {
+            let namespace = document.get("namespace").and_then(Json::as_str);
+            for schema in document.get("types").and_then(Json::as_array).unwrap_or_default() {
+                analyzer.schema(schema, namespace)?;
+            }
+        }
+        _ => {
+            analyzer.schema(&document, None)?;
+        }
+    }
+    Ok(analyzer.types)
+}
+
+struct Analyzer {
+    types: Vec<AvroType>,
+}
+
+impl Analyzer {
+    /// Type of a schema, defining the named types it declares inline
+    fn schema(&mut self, schema: &Json, namespace: Option<&str>) -> Result<AvroFieldType, String> {
+        match schema {
+            Json::String(name) => Ok(primitive(name).unwrap_or_else(|| AvroFieldType::Named(simple_name(name).to_string()))),
+            Json::Array(members) => {
+                let members = members.iter().map(|member| self.schema(member, namespace)).collect::<Result<Vec<_>, _>>()?;
+                Ok(AvroFieldType::Union(members))
+            }
+            Json::Object(_) => self.complex(schema, namespace),
+            _ => Err(format!("expected a schema, found {}", schema)),
+        }
+    }
+
+    fn complex(&mut self, schema: &Json, namespace: Option<&str>) -> Result<AvroFieldType, String> {
+        let kind = match schema.get("type") {
+            Some(Json::String(kind)) => kind.as_str(),
+            // `{"type": {"type": "array", ...}}` wraps another schema
+            Some(inner) => return self.schema(inner, namespace),
+            None => return Err("schema without a \"type\"".to_string()),
+        };
+        let logical = schema.get("logicalType").and_then(Json::as_str);
+        match kind {
+            "record" | "error" | "enum" | "fixed" => self.define(schema, kind, logical, namespace),
+            "array" => {
+                let items = schema.get("items").ok_or("array without \"items\"")?;
+                Ok(AvroFieldType::Array(Box::new(self.schema(items, namespace)?)))
+            }
+            "map" => {
+                let values = schema.get("values").ok_or("map without \"values\"")?;
+                Ok(AvroFieldType::Map(Box::new(self.schema(values, namespace)?)))
+            }
+            _ => {
+                let base = primitive(kind).unwrap_or_else(|| AvroFieldType::Named(simple_name(kind).to_string()));
+                Ok(logical_type(logical, &base, schema).unwrap_or(base))
+            }
+        }
+    }
+
+    /// Add a record, enum, or fixed type; returns a reference to it
+    fn define(&mut self, schema: &Json, kind: &str, logical: Option<&str>, namespace: Option<&str>) -> Result<AvroFieldType, String> {
+        let full_name = schema.get("name").and_then(Json::as_str).ok_or_else(|| format!("{} without a name", kind))?;
+        // A dotted name carries its own namespace
+        let namespace = match full_name.rsplit_once('.') {
+            Some((prefix, _)) => Some(prefix),
+            None => schema.get("namespace").and_then(Json::as_str).or(namespace).filter(|ns| !ns.is_empty()),
+        };
+        let name = simple_name(full_name).to_string();
+        let mut defined = AvroType {
+            name: name.clone(),
+            namespace: namespace.map(str::to_string),
+            kind: AvroTypeKind::Record,
+            fields: Vec::new(),
+            symbols: Vec::new(),
+            size: None,
+            location: namespace.map_or_else(|| name.clone(), |ns| format!("{}.{}", ns, name)),
+        };
+
+        match kind {
+            "enum" => {
+                defined.kind = AvroTypeKind::Enum;
+                defined.symbols = schema
+                    .get("symbols")
+                    .and_then(Json::as_array)
+                    .ok_or("enum without \"symbols\"")?
+                    .iter()
+                    .filter_map(Json::as_str)
+                    .map(str::to_string)
+                    .collect();
+                self.types.push(defined);
+            }
+            "fixed" => {
+                defined.kind = AvroTypeKind::Fixed;
+                defined.size = Some(schema.get("size").and_then(Json::as_f64).ok_or("fixed without a \"size\"")? as u64);
+                self.types.push(defined);
+                // A logical fixed is its logical type wherever it's used
+                if let Some(logical) = logical_type(logical, &AvroFieldType::Named(name.clone()), schema) {
+                    return Ok(logical);
+                }
+            }
+            _ => {
+                let index = self.types.len();
+                self.types.push(defined);
+                let fields = schema.get("fields").and_then(Json::as_array).ok_or("record without \"fields\"")?;
+                for field in fields {
+                    let field_name = field.get("name").and_then(Json::as_str).ok_or("field without a name")?;
+                    let field_schema = field.get("type").ok_or_else(|| format!("field `{}` without a type", field_name))?;
+                    let field_type = self.schema(field_schema, namespace)?;
+                    self.types[index].fields.push(AvroField {
+                        name: field_name.to_string(),
+                        field_type,
+                        has_default: field.get("default").is_some(),
+                    });
+                }
+            }
+        }
+        Ok(AvroFieldType::Named(name))
+    }
+}
+
+fn primitive(name: &str) -> Option<AvroFieldType> {
+    Some(match name {
+        "null" => AvroFieldType::Null,
+        "boolean" => AvroFieldType::Boolean,
+        "int" => AvroFieldType::Int,
+        "long" => AvroFieldType::Long,
+        "float" => AvroFieldType::Float,
+        "double" => AvroFieldType::Double,
+        "bytes" => AvroFieldType::Bytes,
+        "string" => AvroFieldType::String,
+        _ => return None,
+    })
+}
+
+/// Logical type annotating `base`; readers ignore ones they don't know, or
+/// ones on the wrong underlying type, and so does this
+fn logical_type(logical: Option<&str>, base: &AvroFieldType, schema: &Json) -> Option<AvroFieldType> {
+    let fixed = matches!(base, AvroFieldType::Named(_));
+    Some(match (logical?, base) {
+        ("date", AvroFieldType::Int) => AvroFieldType::Date,
+        ("time-millis", AvroFieldType::Int) => AvroFieldType::TimeMillis,
+        ("time-micros", AvroFieldType::Long) => AvroFieldType::TimeMicros,
+        ("timestamp-millis" | "local-timestamp-millis", AvroFieldType::Long) => AvroFieldType::TimestampMillis,
+        ("timestamp-micros" | "local-timestamp-micros", AvroFieldType::Long) => AvroFieldType::TimestampMicros,
+        ("uuid", AvroFieldType::String) => AvroFieldType::Uuid,
+        ("decimal", AvroFieldType::Bytes) | ("decimal", AvroFieldType::Named(_)) => {
+            let precision = schema.get("precision").and_then(Json::as_f64)? as u32;
+            let scale = schema.get("scale").and_then(Json::as_f64).unwrap_or(0.0) as u32;
+            AvroFieldType::Decimal { precision, scale }
+        }
+        ("duration", _) if fixed && schema.get("size").and_then(Json::as_f64) == Some(12.0) => AvroFieldType::Duration,
+        _ => return None,
+    })
+}
+
+/// `com.example.User` -> `User`
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(avro_type: &AvroType, target: &str) -> f32 {
+    average_fidelity(ir_fields(avro_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an Avro field type into the language-neutral IR
+pub fn to_ir(field_type: &AvroFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        AvroFieldType::Null => primitive(PrimitiveType::Unit),
+        AvroFieldType::Boolean => primitive(PrimitiveType::Bool),
+        AvroFieldType::Int | AvroFieldType::Date | AvroFieldType::TimeMillis => primitive(PrimitiveType::I32),
+        AvroFieldType::Long | AvroFieldType::TimeMicros | AvroFieldType::TimestampMillis | AvroFieldType::TimestampMicros => {
+            primitive(PrimitiveType::I64)
+        }
+        AvroFieldType::Float => primitive(PrimitiveType::F32),
+        AvroFieldType::Double => primitive(PrimitiveType::F64),
+        // The JSON encoding writes bytes as a string of code points
+        AvroFieldType::String => primitive(PrimitiveType::String),
+        AvroFieldType::Bytes => primitive(PrimitiveType::Bytes),
+        AvroFieldType::Uuid => primitive(PrimitiveType::Uuid),
+        AvroFieldType::Array(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        // Map keys are always strings
+        AvroFieldType::Map(values) => {
+            IrType::Container(ContainerType::Map(Box::new(primitive(PrimitiveType::String)), Box::new(to_ir(values))))
+        }
+        AvroFieldType::Decimal { .. } => IrType::Primitive(PrimitiveType::Decimal),
+        // No IR form yet for three-part durations
+        AvroFieldType::Duration => IrType::Any,
+        AvroFieldType::Union(members) => union_to_ir(members),
+        AvroFieldType::Named(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// `["null", T]` is how Avro spells an optional `T`
+fn union_to_ir(members: &[AvroFieldType]) -> IrType {
+    let nullable = members.contains(&AvroFieldType::Null);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != AvroFieldType::Null) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all fields of a record into the IR
+pub fn ir_fields(avro_type: &AvroType) -> Vec<IrField> {
+    avro_type.fields.iter().map(|field| IrField::new(&field.name, to_ir(&field.field_type))).collect()
+}
+
+/// Map Avro types to target language types
+pub fn map_to_target(field_type: &AvroFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDER: &str = r#"{
+  "type": "record",
+  "name": "Order",
+  "namespace": "com.example.shop",
+  "fields": [
+    { "name": "id", "type": { "type": "string", "logicalType": "uuid" } },
+    { "name": "placed_at", "type": { "type": "long", "logicalType": "timestamp-millis" } },
+    { "name": "total", "type": { "type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2 } },
+    { "name": "note", "type": ["null", "string"], "default": null },
+    { "name": "status", "type": { "type": "enum", "name": "Status", "symbols": ["PLACED", "SHIPPED"] } },
+    {
+      "name": "lines",
+      "type": {
+        "type": "array",
+        "items": {
+          "type": "record",
+          "name": "Line",
+          "fields": [
+            { "name": "sku", "type": "string" },
+            { "name": "quantity", "type": "int" },
+            { "name": "checksum", "type": { "type": "fixed", "name": "com.example.Md5", "size": 16 } }
+          ]
+        }
+      }
+    },
+    { "name": "attributes", "type": { "type": "map", "values": "string" } },
+    { "name": "previous", "type": ["null", "Order"] }
+  ]
+}"#;
+
+    #[test]
+    fn test_analyze_avro_record() {
+        let types = analyze_avro_types(ORDER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Order", AvroTypeKind::Record, "com.example.shop.Order"),
+                ("Status", AvroTypeKind::Enum, "com.example.shop.Status"),
+                ("Line", AvroTypeKind::Record, "com.example.shop.Line"),
+                ("Md5", AvroTypeKind::Fixed, "com.example.Md5"),
+            ]
+        );
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: uuid::Uuid",
+                "placed_at: i64",
+                "total: rust_decimal::Decimal",
+                "note: Option<String>",
+                "status: Status",
+                "lines: Vec<Line>",
+                "attributes: HashMap<String, String>",
+                "previous: Option<Order>",
+            ]
+        );
+        assert_eq!(types[0].fields[2].field_type, AvroFieldType::Decimal { precision: 10, scale: 2 });
+        assert!(types[0].fields[3].has_default);
+        assert_eq!(types[1].symbols, vec!["PLACED", "SHIPPED"]);
+        assert_eq!(types[3].size, Some(16));
+        assert_eq!(compatibility_score(&types[2], "rust"), 1.0);
+        // The decimal is exact in Rust but only a float in Gleam
+        assert_eq!(compatibility_score(&types[0], "rust"), 1.0);
+        assert!(compatibility_score(&types[0], "gleam") < 1.0);
+    }
+
+    #[test]
+    fn test_schema_lists_protocols_and_errors() {
+        let list = r#"[{ "type": "enum", "name": "Suit", "symbols": ["HEARTS"] }, { "type": "record", "name": "Card", "fields": [{ "name": "suit", "type": "Suit" }] }]"#;
+        let types = analyze_avro_types(list).unwrap();
+        assert_eq!(types.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["Suit", "Card"]);
+
+        let protocol = r#"{ "protocol": "Mail", "namespace": "org.mail", "types": [{ "type": "error", "name": "Bounce", "fields": [] }] }"#;
+        let bounce = analyze_avro_type(protocol).unwrap();
+        assert_eq!((bounce.kind, bounce.location.as_str()), (AvroTypeKind::Record, "org.mail.Bounce"));
+
+        assert!(analyze_avro_type("\"string\"").is_err());
+        assert!(analyze_avro_types(r#"{ "type": "record", "fields": [] }"#).is_err());
+        assert!(analyze_avro_types(r#"{ "type": "record", "name": "R", "fields": [{ "name": "x" }] }"#).is_err());
+    }
+}
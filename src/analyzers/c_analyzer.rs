@@ -0,0 +1,824 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// C header analyzer for protocol-squisher integration
+// Analyzes struct, union, enum, and typedef declarations in .h files
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::audit::SecurityProfile;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::{Layout, PlatformWidth, TargetTriple};
+
+/// C type declaration extracted from a header
+#[derive(Debug, Clone)]
+pub struct CType {
+    /// Typedef name when there is one, otherwise the tag (`struct user` → `user`)
+    pub name: String,
+    pub kind: CTypeKind,
+    /// Members of a struct or union
+    pub fields: Vec<CField>,
+    /// Constants of an enum
+    pub enumerators: Vec<CEnumerator>,
+    /// Target of a plain `typedef` (`typedef uint32_t user_id;`)
+    pub alias: Option<CFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CTypeKind {
+    Struct,
+    Union,
+    Enum,
+    Typedef,
+}
+
+#[derive(Debug, Clone)]
+pub struct CField {
+    pub name: String,
+    pub field_type: CFieldType,
+    /// Width of a bitfield member (`unsigned flags : 3;`)
+    pub bit_width: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CEnumerator {
+    pub name: String,
+    /// Value as a decimal literal when it can be computed, otherwise the
+    /// expression as written (`FLAG_A | FLAG_B`)
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CFieldType {
+    Int8,
+    Int16,
+    Int32,   // also `int`
+    Int64,   // also `long long`
+    UInt8,
+    UInt16,
+    UInt32,  // also `unsigned`
+    UInt64,
+    Long,    // 32-bit on Windows, 64-bit elsewhere
+    ULong,
+    ISize,   // `intptr_t`, `ssize_t`, `ptrdiff_t`
+    USize,   // `size_t`, `uintptr_t`
+    Char,    // plain `char`, signedness is platform-defined
+    Float,
+    Double,
+    Bool,
+    Void,
+    Pointer { pointee: Box<CFieldType>, is_const: bool },
+    /// Fixed-size array; `None` for flexible array members and macro sizes
+    Array(Box<CFieldType>, Option<usize>),
+    /// Struct, union, enum, or typedef name
+    Named(String),
+    FunctionPointer { ret: Box<CFieldType>, params: Vec<CFieldType> },
+}
+
+/// Keywords that may appear in a declaration's specifier list
+const SPECIFIERS: &[&str] = &[
+    "const", "volatile", "restrict", "signed", "unsigned", "short", "long", "int", "char", "float", "double", "void",
+    "_Bool", "bool", "static", "extern", "inline", "register", "_Atomic",
+];
+
+const QUALIFIERS: &[&str] = &["const", "volatile", "restrict", "_Atomic", "static", "extern", "inline", "register"];
+
+/// Analyze the first C type declaration and extract metadata
+pub fn analyze_c_type(source: &str) -> Result<CType, String> {
+    analyze_c_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct, union, enum, and typedef declaration in a header
+pub fn analyze_c_types(source: &str) -> Result<Vec<CType>, String> {
+    let mut cursor = Cursor::new(without_preprocessor(tokenize(source, &Syntax::C_LIKE)));
+    let mut types = Vec::new();
+
+    while let Some(token) = cursor.peek() {
+        let line = token.line;
+        match token.text.as_str() {
+            "typedef" => {
+                cursor.advance();
+                types.extend(parse_typedef(&mut cursor, line)?);
+            }
+            "struct" | "union" | "enum" if cursor.peek_at(2).is_some_and(|t| t.text == "{") => {
+                let kind = parse_tag_kind(&mut cursor)?;
+                let tag = cursor.expect_ident()?;
+                let declared = parse_body(&mut cursor, kind, tag, line)?;
+                // `struct point { ... } origin;` also declares a variable
+                skip_statement(&mut cursor);
+                types.push(declared);
+            }
+            "extern" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Str) => {
+                // `extern "C" {` guards from C++-compatible headers
+                cursor.advance();
+                cursor.advance();
+                cursor.eat_punct('{');
+            }
+            "}" | ";" => {
+                cursor.advance();
+            }
+            _ => skip_statement(&mut cursor),
+        }
+    }
+
+    Ok(types)
+}
+
+/// Drop `#include`/`#define`/`#if` lines, including backslash continuations
+pub(super) fn without_preprocessor(tokens: Vec<super::lexer::Token>) -> Vec<super::lexer::Token> {
+    let mut kept = Vec::with_capacity(tokens.len());
+    let mut directive_line = None;
+    let mut previous_line = 0;
+    for token in tokens {
+        let starts_line = token.line != previous_line;
+        previous_line = token.line;
+        if let Some(line) = directive_line {
+            if token.line == line {
+                if token.text == "\\" {
+                    directive_line = Some(line + 1);
+                }
+                continue;
+            }
+            directive_line = None;
+        }
+        if starts_line && token.text == "#" {
+            directive_line = Some(token.line);
+            continue;
+        }
+        kept.push(token);
+    }
+    kept
+}
+
+/// Skip a declaration we don't model (prototypes, globals, function bodies)
+pub(super) fn skip_statement(cursor: &mut Cursor) {
+    while let Some(token) = cursor.peek() {
+        if token.kind != TokenKind::Punct {
+            cursor.advance();
+            continue;
+        }
+        match token.text.as_str() {
+            ";" => {
+                cursor.advance();
+                return;
+            }
+            "{" => {
+                cursor.skip_balanced('{', '}');
+                cursor.eat_punct(';');
+                return;
+            }
+            "(" => cursor.skip_balanced('(', ')'),
+            "}" => return,
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+fn parse_tag_kind(cursor: &mut Cursor) -> Result<CTypeKind, String> {
+    let kind = match cursor.peek().map(|t| t.text.as_str()) {
+        Some("struct") => CTypeKind::Struct,
+        Some("union") => CTypeKind::Union,
+        Some("enum") => CTypeKind::Enum,
+        _ => return Err(cursor.unexpected("`struct`, `union`, or `enum`")),
+    };
+    cursor.advance();
+    skip_attributes(cursor);
+    Ok(kind)
+}
+
+/// `__attribute__((packed))`, `__declspec(align(8))`, `alignas(8)`
+fn skip_attributes(cursor: &mut Cursor) {
+    while cursor
+        .peek()
+        .is_some_and(|t| matches!(t.text.as_str(), "__attribute__" | "__declspec" | "alignas" | "_Alignas"))
+    {
+        cursor.advance();
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+    }
+}
+
+/// Braced body of a struct, union, or enum, starting at `{`
+fn parse_body(cursor: &mut Cursor, kind: CTypeKind, name: String, line: usize) -> Result<CType, String> {
+    let mut declared = CType {
+        name,
+        kind,
+        fields: Vec::new(),
+        enumerators: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    };
+    match kind {
+        CTypeKind::Enum => declared.enumerators = parse_enumerators(cursor)?,
+        _ => declared.fields = parse_members(cursor)?,
+    }
+    skip_attributes(cursor);
+    Ok(declared)
+}
+
+fn parse_members(cursor: &mut Cursor) -> Result<Vec<CField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        let (base, base_const) = parse_specifiers(cursor)?;
+        // Anonymous nested struct/union members (C11) aren't modelled
+        if cursor.eat_punct(';') {
+            continue;
+        }
+        loop {
+            let (name, field_type) = parse_declarator(cursor, base.clone(), base_const)?;
+            let name = name.ok_or_else(|| cursor.unexpected("member name"))?;
+            let bit_width = if cursor.eat_punct(':') {
+                let width = cursor.advance().filter(|t| t.kind == TokenKind::Number);
+                Some(width.and_then(|t| t.text.parse().ok()).ok_or_else(|| cursor.unexpected("bitfield width"))?)
+            } else {
+                None
+            };
+            skip_attributes(cursor);
+            fields.push(CField { name, field_type, bit_width });
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        cursor.expect_punct(';')?;
+    }
+    Ok(fields)
+}
+
+fn parse_enumerators(cursor: &mut Cursor) -> Result<Vec<CEnumerator>, String> {
+    cursor.expect_punct('{')?;
+    let mut enumerators: Vec<CEnumerator> = Vec::new();
+    while !cursor.eat_punct('}') {
+        let name = cursor.expect_ident()?;
+        let value = if cursor.eat_punct('=') {
+            let mut expression = Vec::new();
+            let mut depth = 0usize;
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    "," | "}" if depth == 0 => break,
+                    "(" => depth += 1,
+                    ")" => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                expression.push(token.text.clone());
+                cursor.advance();
+            }
+            let text = expression.join(" ").replace("- ", "-");
+            parse_integer(&text).map(|n| n.to_string()).unwrap_or(text)
+        } else {
+            match enumerators.last() {
+                None => "0".to_string(),
+                Some(previous) => match previous.value.parse::<i64>() {
+                    Ok(n) => n.saturating_add(1).to_string(),
+                    Err(_) => format!("({}) + 1", previous.value),
+                },
+            }
+        };
+        enumerators.push(CEnumerator { name, value });
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(enumerators)
+}
+
+/// Decimal, hex, octal, or binary literal with optional `u`/`l` suffixes
+fn parse_integer(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let digits = digits.trim_end_matches(['u', 'U', 'l', 'L']);
+    let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+        i64::from_str_radix(binary, 2).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+fn parse_typedef(cursor: &mut Cursor, line: usize) -> Result<Vec<CType>, String> {
+    // `typedef struct [tag] { ... } name, *name_ptr;`
+    let tagged = matches!(cursor.peek().map(|t| t.text.as_str()), Some("struct" | "union" | "enum"));
+    let body_follows = cursor.is_punct_at(1, '{') || cursor.is_punct_at(2, '{');
+    let (base, base_const, mut body) = if tagged && body_follows {
+        let kind = parse_tag_kind(cursor)?;
+        let tag = if cursor.is_punct('{') { None } else { Some(cursor.expect_ident()?) };
+        let body = parse_body(cursor, kind, tag.clone().unwrap_or_default(), line)?;
+        let reference = tag.unwrap_or_else(|| format!("anonymous {}", tag_keyword(kind)));
+        (CFieldType::Named(reference), false, Some(body))
+    } else {
+        let (base, base_const) = parse_specifiers(cursor)?;
+        (base, base_const, None)
+    };
+
+    let mut declarators = Vec::new();
+    loop {
+        let (name, aliased) = parse_declarator(cursor, base.clone(), base_const)?;
+        declarators.push((name.ok_or_else(|| cursor.unexpected("typedef name"))?, aliased));
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(';')?;
+
+    let mut types = Vec::new();
+    if let Some(mut body) = body.take() {
+        // The first plain declarator names the body itself
+        if let Some(index) = declarators.iter().position(|(_, aliased)| *aliased == base) {
+            body.name = declarators.remove(index).0;
+        }
+        if !body.name.is_empty() {
+            types.push(body);
+        }
+    }
+    types.extend(declarators.into_iter().map(|(name, aliased)| alias(name, aliased, line)));
+    Ok(types)
+}
+
+fn tag_keyword(kind: CTypeKind) -> &'static str {
+    match kind {
+        CTypeKind::Union => "union",
+        CTypeKind::Enum => "enum",
+        _ => "struct",
+    }
+}
+
+fn alias(name: String, aliased: CFieldType, line: usize) -> CType {
+    CType {
+        name,
+        kind: CTypeKind::Typedef,
+        fields: Vec::new(),
+        enumerators: Vec::new(),
+        alias: Some(aliased),
+        location: format!("line {}", line),
+    }
+}
+
+/// Declaration specifiers (`const unsigned long`, `struct user`, `uint32_t`),
+/// and whether they include `const`
+fn parse_specifiers(cursor: &mut Cursor) -> Result<(CFieldType, bool), String> {
+    skip_attributes(cursor);
+    let mut words: Vec<String> = Vec::new();
+    let mut named = None;
+    let mut is_const = false;
+
+    while let Some(token) = cursor.peek() {
+        if token.kind != TokenKind::Ident {
+            break;
+        }
+        let text = token.text.clone();
+        if SPECIFIERS.contains(&text.as_str()) {
+            cursor.advance();
+            is_const |= text == "const";
+            if !QUALIFIERS.contains(&text.as_str()) {
+                words.push(text);
+            }
+        } else if matches!(text.as_str(), "struct" | "union" | "enum") && named.is_none() && words.is_empty() {
+            cursor.advance();
+            skip_attributes(cursor);
+            if cursor.is_punct('{') {
+                // Anonymous nested aggregate: its members aren't modelled separately
+                cursor.skip_balanced('{', '}');
+                named = Some(CFieldType::Named(format!("anonymous {}", text)));
+            } else {
+                let tag = cursor.expect_ident()?;
+                if cursor.is_punct('{') {
+                    cursor.skip_balanced('{', '}');
+                }
+                named = Some(CFieldType::Named(tag));
+            }
+        } else if named.is_none() && words.is_empty() {
+            cursor.advance();
+            named = Some(builtin_typedef(&text).unwrap_or(CFieldType::Named(text)));
+        } else {
+            break;
+        }
+        skip_attributes(cursor);
+    }
+
+    match named {
+        Some(named) => Ok((named, is_const)),
+        None if !words.is_empty() => Ok((keyword_type(&words), is_const)),
+        None => Err(cursor.unexpected("type")),
+    }
+}
+
+/// Types from `<stdint.h>`, `<stddef.h>`, and `<stdbool.h>`
+fn builtin_typedef(name: &str) -> Option<CFieldType> {
+    Some(match name {
+        "int8_t" => CFieldType::Int8,
+        "int16_t" => CFieldType::Int16,
+        "int32_t" => CFieldType::Int32,
+        "int64_t" => CFieldType::Int64,
+        "uint8_t" => CFieldType::UInt8,
+        "uint16_t" => CFieldType::UInt16,
+        "uint32_t" => CFieldType::UInt32,
+        "uint64_t" => CFieldType::UInt64,
+        "intptr_t" | "ssize_t" | "ptrdiff_t" => CFieldType::ISize,
+        "uintptr_t" | "size_t" => CFieldType::USize,
+        _ => return None,
+    })
+}
+
+/// Builtin type from keywords (`unsigned long long` → `UInt64`)
+fn keyword_type(words: &[String]) -> CFieldType {
+    let has = |word: &str| words.iter().any(|w| w == word);
+    let longs = words.iter().filter(|w| *w == "long").count();
+    let unsigned = has("unsigned");
+
+    if has("void") {
+        CFieldType::Void
+    } else if has("_Bool") || has("bool") {
+        CFieldType::Bool
+    } else if has("float") {
+        CFieldType::Float
+    } else if has("double") {
+        CFieldType::Double
+    } else if has("char") {
+        match (unsigned, has("signed")) {
+            (true, _) => CFieldType::UInt8,
+            (false, true) => CFieldType::Int8,
+            _ => CFieldType::Char,
+        }
+    } else if has("short") {
+        if unsigned { CFieldType::UInt16 } else { CFieldType::Int16 }
+    } else if longs >= 2 {
+        if unsigned { CFieldType::UInt64 } else { CFieldType::Int64 }
+    } else if longs == 1 {
+        if unsigned { CFieldType::ULong } else { CFieldType::Long }
+    } else if unsigned {
+        CFieldType::UInt32
+    } else {
+        CFieldType::Int32
+    }
+}
+
+/// Pointer stars, the declared name (possibly absent), array suffixes, and
+/// function-pointer syntax (`int (*on_event)(void *, int)`)
+fn parse_declarator(
+    cursor: &mut Cursor,
+    base: CFieldType,
+    base_const: bool,
+) -> Result<(Option<String>, CFieldType), String> {
+    let mut ty = base;
+    // `const` before a `*` qualifies what that pointer points at
+    let mut pointee_const = base_const;
+    while cursor.eat_punct('*') {
+        ty = CFieldType::Pointer { pointee: Box::new(ty), is_const: pointee_const };
+        pointee_const = false;
+        while let Some(word) = cursor.peek().map(|t| t.text.clone()) {
+            if !QUALIFIERS.contains(&word.as_str()) {
+                break;
+            }
+            pointee_const |= word == "const";
+            cursor.advance();
+        }
+    }
+
+    if cursor.is_punct('(') && cursor.is_punct_at(1, '*') {
+        cursor.advance();
+        cursor.advance();
+        let name = if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) { Some(cursor.expect_ident()?) } else { None };
+        cursor.expect_punct(')')?;
+        let params = parse_params(cursor)?;
+        return Ok((name, CFieldType::FunctionPointer { ret: Box::new(ty), params }));
+    }
+
+    let name = if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) { Some(cursor.expect_ident()?) } else { None };
+
+    let mut dimensions = Vec::new();
+    while cursor.eat_punct('[') {
+        let size = match cursor.peek() {
+            Some(t) if t.kind == TokenKind::Number => parse_integer(&t.text).and_then(|n| usize::try_from(n).ok()),
+            _ => None,
+        };
+        while !cursor.at_end() && !cursor.eat_punct(']') {
+            cursor.advance();
+        }
+        dimensions.push(size);
+    }
+    // `int grid[2][3]` is an array of 2 arrays of 3
+    for size in dimensions.into_iter().rev() {
+        ty = CFieldType::Array(Box::new(ty), size);
+    }
+
+    Ok((name, ty))
+}
+
+fn parse_params(cursor: &mut Cursor) -> Result<Vec<CFieldType>, String> {
+    cursor.expect_punct('(')?;
+    let mut params = Vec::new();
+    while !cursor.eat_punct(')') {
+        if cursor.is_punct('.') {
+            // Variadic `...`
+            while cursor.eat_punct('.') {}
+            continue;
+        }
+        let (base, base_const) = parse_specifiers(cursor)?;
+        let (_, ty) = parse_declarator(cursor, base, base_const)?;
+        if ty != CFieldType::Void {
+            params.push(ty);
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    Ok(params)
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(c_type: &CType, target: &str) -> f32 {
+    average_fidelity(ir_fields(c_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a C field type into the language-neutral IR
+pub fn to_ir(field_type: &CFieldType) -> IrType {
+    match field_type {
+        // `char` is a byte; signed on the common ABIs
+        CFieldType::Int8 | CFieldType::Char => IrType::Primitive(PrimitiveType::I8),
+        CFieldType::Int16 => IrType::Primitive(PrimitiveType::I16),
+        CFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        CFieldType::Int64 | CFieldType::Long | CFieldType::ISize => IrType::Primitive(PrimitiveType::I64),
+        CFieldType::UInt8 => IrType::Primitive(PrimitiveType::U8),
+        CFieldType::UInt16 => IrType::Primitive(PrimitiveType::U16),
+        CFieldType::UInt32 => IrType::Primitive(PrimitiveType::U32),
+        CFieldType::UInt64 | CFieldType::ULong | CFieldType::USize => IrType::Primitive(PrimitiveType::U64),
+        CFieldType::Float => IrType::Primitive(PrimitiveType::F32),
+        CFieldType::Double => IrType::Primitive(PrimitiveType::F64),
+        CFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        CFieldType::Void => IrType::Primitive(PrimitiveType::Unit),
+        // NUL-terminated strings and inline character buffers
+        CFieldType::Pointer { pointee, .. } | CFieldType::Array(pointee, _) if **pointee == CFieldType::Char => {
+            IrType::Primitive(PrimitiveType::String)
+        }
+        CFieldType::Pointer { pointee, .. } if **pointee == CFieldType::Void => IrType::Any,
+        // Pointers may be NULL
+        CFieldType::Pointer { pointee, .. } => IrType::Container(ContainerType::Option(Box::new(to_ir(pointee)))),
+        CFieldType::Array(element, Some(size)) => IrType::Container(ContainerType::Array(Box::new(to_ir(element)), *size)),
+        CFieldType::Array(element, None) => IrType::Container(ContainerType::Vec(Box::new(to_ir(element)))),
+        CFieldType::Named(name) => IrType::Reference(name.clone()),
+        CFieldType::FunctionPointer { .. } => IrType::Any,
+    }
+}
+
+/// Lower struct and union members into the IR (enums and typedefs have none)
+pub fn ir_fields(c_type: &CType) -> Vec<IrField> {
+    c_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`long`, `size_t`), looking through arrays
+pub fn platform_width(field_type: &CFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        CFieldType::Long | CFieldType::ULong => Some(PlatformWidth::CLong),
+        CFieldType::ISize | CFieldType::USize => Some(PlatformWidth::Pointer),
+        CFieldType::Array(element, _) => platform_width(element),
+        _ => None,
+    }
+}
+
+/// Size and alignment of a field on `triple`; `None` for named types, which
+/// need their own declaration
+pub fn field_layout(field_type: &CFieldType, triple: &TargetTriple) -> Option<Layout> {
+    let scalar = |bytes| triple.scalar_layout(bytes);
+    Some(match field_type {
+        CFieldType::Int8 | CFieldType::UInt8 | CFieldType::Char | CFieldType::Bool => scalar(1),
+        CFieldType::Int16 | CFieldType::UInt16 => scalar(2),
+        CFieldType::Int32 | CFieldType::UInt32 | CFieldType::Float => scalar(4),
+        CFieldType::Int64 | CFieldType::UInt64 | CFieldType::Double => scalar(8),
+        CFieldType::Long | CFieldType::ULong => scalar(triple.c_long_bits() as usize / 8),
+        CFieldType::ISize | CFieldType::USize | CFieldType::Pointer { .. } | CFieldType::FunctionPointer { .. } => triple.pointer_layout(),
+        CFieldType::Array(element, size) => {
+            let element = field_layout(element, triple)?;
+            Layout::new(element.size * size.unwrap_or(0), element.align)
+        }
+        CFieldType::Void | CFieldType::Named(_) => return None,
+    })
+}
+
+/// `#[repr(C)]` layout of a struct, union, or enum on `triple`; `None` when a
+/// member's layout is unknown or the struct has bitfields
+pub fn layout(c_type: &CType, triple: &TargetTriple) -> Option<Layout> {
+    if c_type.fields.iter().any(|field| field.bit_width.is_some()) {
+        return None;
+    }
+    let fields = c_type
+        .fields
+        .iter()
+        .map(|field| field_layout(&field.field_type, triple))
+        .collect::<Option<Vec<_>>>()?;
+    match c_type.kind {
+        CTypeKind::Struct => Some(Layout::of_struct(fields)),
+        CTypeKind::Union => Some(Layout::of_union(fields)),
+        CTypeKind::Enum => Some(Layout::new(4, 4)),
+        CTypeKind::Typedef => field_layout(c_type.alias.as_ref()?, triple),
+    }
+}
+
+/// Map C types to target language types
+pub fn map_to_target(field_type: &CFieldType, target: &str) -> String {
+    match target {
+        // `#[repr(C)]`-compatible Rust spelling
+        "rust" => match field_type {
+            CFieldType::Int8 => "i8".to_string(),
+            CFieldType::Int16 => "i16".to_string(),
+            CFieldType::Int32 => "i32".to_string(),
+            CFieldType::Int64 => "i64".to_string(),
+            CFieldType::UInt8 => "u8".to_string(),
+            CFieldType::UInt16 => "u16".to_string(),
+            CFieldType::UInt32 => "u32".to_string(),
+            CFieldType::UInt64 => "u64".to_string(),
+            CFieldType::Long => "std::os::raw::c_long".to_string(),
+            CFieldType::ULong => "std::os::raw::c_ulong".to_string(),
+            CFieldType::ISize => "isize".to_string(),
+            CFieldType::USize => "usize".to_string(),
+            CFieldType::Char => "std::os::raw::c_char".to_string(),
+            CFieldType::Float => "f32".to_string(),
+            CFieldType::Double => "f64".to_string(),
+            CFieldType::Bool => "bool".to_string(),
+            CFieldType::Void => "std::ffi::c_void".to_string(),
+            CFieldType::Pointer { pointee, is_const } => {
+                format!("*{} {}", if *is_const { "const" } else { "mut" }, map_to_target(pointee, target))
+            }
+            CFieldType::Array(element, Some(size)) => format!("[{}; {}]", map_to_target(element, target), size),
+            // Flexible array member: zero-length marker, elements follow the struct
+            CFieldType::Array(element, None) => format!("[{}; 0]", map_to_target(element, target)),
+            CFieldType::Named(name) => name.clone(),
+            CFieldType::FunctionPointer { ret, params } => {
+                let params: Vec<String> = params.iter().map(|p| map_to_target(p, target)).collect();
+                let ret = match **ret {
+                    CFieldType::Void => String::new(),
+                    ref ret => format!(" -> {}", map_to_target(ret, target)),
+                };
+                format!("Option<unsafe extern \"C\" fn({}){}>", params.join(", "), ret)
+            }
+        },
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Generate the `#[repr(C)]` Rust mirror of a C declaration for use in `extern` blocks
+pub fn generate_ffi_bindings(c_type: &CType) -> String {
+    ffi_bindings(c_type, SecurityProfile::Standard)
+}
+
+pub(crate) fn ffi_bindings(c_type: &CType, profile: SecurityProfile) -> String {
+    let mut code = format!("/// Mirror of C `{}` ({})\n", c_type.name, c_type.location);
+    match c_type.kind {
+        CTypeKind::Struct | CTypeKind::Union => {
+            let keyword = if c_type.kind == CTypeKind::Struct { "struct" } else { "union" };
+            code.push_str("#[repr(C)]\n#[derive(Debug, Clone, Copy)]\n");
+            code.push_str(&format!("pub {} {} {{\n", keyword, c_type.name));
+            for field in &c_type.fields {
+                if let Some(width) = field.bit_width {
+                    // Bitfield packing is ABI-specific; the storage unit must be checked by hand
+                    code.push_str(&format!("    /// C bitfield: {} bit(s)\n", width));
+                }
+                let rust_type = match (&field.field_type, profile) {
+                    // A byte the paranoid readers check, rather than a `bool` C could fill with anything
+                    (CFieldType::Bool, SecurityProfile::Paranoid) if field.bit_width.is_none() => "u8".to_string(),
+                    (field_type, _) => map_to_target(field_type, "rust"),
+                };
+                code.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
+            }
+            code.push_str("}\n");
+        }
+        // Rust enums must only hold declared values, so C enums become an integer alias plus constants
+        CTypeKind::Enum => {
+            code.push_str(&format!("pub type {} = std::os::raw::c_int;\n", c_type.name));
+            for enumerator in &c_type.enumerators {
+                code.push_str(&format!("pub const {}: {} = {};\n", enumerator.name, c_type.name, enumerator.value));
+            }
+        }
+        CTypeKind::Typedef => {
+            let aliased = c_type.alias.as_ref().map(|a| map_to_target(a, "rust")).unwrap_or_default();
+            code.push_str(&format!("pub type {} = {};\n", c_type.name, aliased));
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = r#"
+#ifndef USER_H
+#define USER_H
+#include <stdint.h>
+#define MAX_NAME \
+    32
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef uint64_t user_id;
+
+typedef enum { STATUS_ACTIVE = 1, STATUS_SUSPENDED, STATUS_ALL = 0xFF } status_t;
+
+typedef struct user {
+    user_id id;
+    const char *name;
+    char email[64];
+    int32_t scores[4];
+    unsigned int flags : 3;
+    struct address *address;
+    void (*on_change)(struct user *, int);
+} user_t;
+
+int user_validate(const user_t *user);
+
+#ifdef __cplusplus
+}
+#endif
+#endif
+"#;
+
+    #[test]
+    fn test_analyze_header() {
+        let types = analyze_c_types(HEADER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["user_id", "status_t", "user_t"]);
+
+        let status = &types[1];
+        assert_eq!(status.kind, CTypeKind::Enum);
+        let values: Vec<_> = status.enumerators.iter().map(|e| e.value.as_str()).collect();
+        assert_eq!(values, vec!["1", "2", "255"]);
+
+        let user = &types[2];
+        assert_eq!(user.fields.len(), 7);
+        assert_eq!(user.fields[1].field_type, CFieldType::Pointer { pointee: Box::new(CFieldType::Char), is_const: true });
+        assert_eq!(user.fields[4].bit_width, Some(3));
+        assert_eq!(map_to_target(&user.fields[2].field_type, "rust"), "[std::os::raw::c_char; 64]");
+        assert_eq!(map_to_target(&user.fields[5].field_type, "rust"), "*mut address");
+        assert_eq!(
+            map_to_target(&user.fields[6].field_type, "rust"),
+            "Option<unsafe extern \"C\" fn(*mut user, i32)>"
+        );
+    }
+
+    #[test]
+    fn test_ffi_bindings() {
+        let types = analyze_c_types(HEADER).unwrap();
+        let status = generate_ffi_bindings(&types[1]);
+        assert!(status.contains("pub type status_t = std::os::raw::c_int;"));
+        assert!(status.contains("pub const STATUS_SUSPENDED: status_t = 2;"));
+
+        let user = generate_ffi_bindings(&types[2]);
+        assert!(user.contains("#[repr(C)]\n#[derive(Debug, Clone, Copy)]\npub struct user_t {"));
+        assert!(user.contains("    pub name: *const std::os::raw::c_char,"));
+        assert!(user.contains("    /// C bitfield: 3 bit(s)\n    pub flags: u32,"));
+        assert_eq!(generate_ffi_bindings(&types[0]).lines().last(), Some("pub type user_id = u64;"));
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_c_type("struct Pixel { uint8_t r; int16_t dx; char tag; uint16_t w; };").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::I8, PrimitiveType::U16].map(IrType::Primitive).to_vec()
+        );
+        assert_eq!(compatibility_score(&pixel, "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let user = analyze_c_type("struct point { int32_t x; int32_t y; const char *label; void *data; };").unwrap();
+        assert_eq!(user.name, "point");
+        // `void *` has no typed equivalent
+        assert_eq!(compatibility_score(&user, "rust"), 0.875);
+    }
+
+    #[test]
+    fn test_layout_per_triple() {
+        let buffer = analyze_c_type("struct buffer { char tag; size_t len; long offset; double ratio; };").unwrap();
+        let layout = |triple: &str| layout(&buffer, &TargetTriple::parse(triple).unwrap()).unwrap();
+        assert_eq!(layout("x86_64-unknown-linux-gnu"), Layout::new(32, 8));
+        assert_eq!(layout("wasm32-unknown-unknown"), Layout::new(24, 8));
+        // i386 System V aligns `double` to 4
+        assert_eq!(layout("i686-unknown-linux-gnu"), Layout::new(20, 4));
+
+        let widths: Vec<_> = buffer.fields.iter().map(|field| platform_width(&field.field_type)).collect();
+        assert_eq!(widths, vec![None, Some(PlatformWidth::Pointer), Some(PlatformWidth::CLong), None]);
+        // Bitfields are left to the compiler
+        let user = &analyze_c_types(HEADER).unwrap()[2];
+        assert_eq!(super::layout(user, &TargetTriple::parse("x86_64-pc-windows-msvc").unwrap()), None);
+    }
+}
@@ -0,0 +1,451 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Cap'n Proto analyzer for protocol-squisher integration
+// Analyzes structs and enums in `.capnp` schemas; named unions and groups
+// become types of their own, nested like structs (`Person.Employment`)
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+const CAPNP: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[],
+    string_quotes: &['"'],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Cap'n Proto struct, enum, named union, or group
+#[derive(Debug, Clone)]
+pub struct CapnpType {
+    /// Nested types are spelled `Outer.Inner`
+    pub name: String,
+    pub kind: CapnpTypeKind,
+    /// Generic parameters: `struct Map(Key, Value)`
+    pub type_params: Vec<String>,
+    pub fields: Vec<CapnpField>,
+    pub enumerants: Vec<CapnpEnumerant>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapnpTypeKind {
+    Struct,
+    Enum,
+    /// `name :union { ... }`; exactly one member is set
+    Union,
+    /// `name :group { ... }`; shares its parent's layout
+    Group,
+}
+
+#[derive(Debug, Clone)]
+pub struct CapnpField {
+    pub name: String,
+    /// `@N`; named unions and groups have none
+    pub ordinal: Option<u32>,
+    pub field_type: CapnpFieldType,
+    /// Member of the struct's unnamed union
+    pub in_union: bool,
+    pub has_default: bool,
+}
+
+/// `mobile @0;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapnpEnumerant {
+    pub name: String,
+    pub ordinal: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapnpFieldType {
+    Void,
+    Bool,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64,
+    Text,
+    Data,
+    List(Box<CapnpFieldType>),
+    /// `AnyPointer`, `AnyStruct`, `AnyList`, or `Capability`
+    AnyPointer,
+    /// Struct, enum, or interface, with generic arguments
+    Named(String, Vec<CapnpFieldType>),
+    TypeParam(String),
+}
+
+/// Analyze the first struct or enum in a Cap'n Proto schema
+pub fn analyze_capnp_type(source: &str) -> Result<CapnpType, String> {
+    analyze_capnp_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct and enum in a Cap'n Proto schema, nested ones included
+pub fn analyze_capnp_types(source: &str) -> Result<Vec<CapnpType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &CAPNP));
+    let mut parser = Parser { types: Vec::new() };
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "struct" => parser.parse_struct(&mut cursor, "", &[])?,
+            "enum" => parser.parse_enum(&mut cursor, "")?,
+            // Interfaces are RPC surfaces rather than data
+            "interface" => skip_declaration(&mut cursor),
+            // Stray `}`s would stop `skip_statement` in place
+            ";" | "}" => {
+                cursor.advance();
+            }
+            // The file id, `using`, `const`, `annotation`, and `$annotation(...)`
+            _ => skip_statement(&mut cursor),
+        }
+    }
+    Ok(parser.types)
+}
+
+struct Parser {
+    types: Vec<CapnpType>,
+}
+
+impl Parser {
+    fn new_type(&mut self, name: String, kind: CapnpTypeKind, type_params: Vec<String>, line: usize) -> usize {
+        self.types.push(CapnpType {
+            name,
+            kind,
+            type_params,
+            fields: Vec::new(),
+            enumerants: Vec::new(),
+            location: format!("line {}", line),
+        });
+        self.types.len() - 1
+    }
+
+    fn parse_struct(&mut self, cursor: &mut Cursor, outer: &str, outer_params: &[String]) -> Result<(), String> {
+        let line = cursor.line();
+        cursor.advance();
+        let name = qualify(outer, &cursor.expect_ident()?);
+        let mut type_params = Vec::new();
+        if cursor.eat_punct('(') {
+            while !cursor.eat_punct(')') {
+                type_params.push(cursor.expect_ident()?);
+                cursor.eat_punct(',');
+            }
+        }
+        // Nested types see their parents' parameters too
+        let mut params = outer_params.to_vec();
+        params.extend(type_params.iter().cloned());
+        let index = self.new_type(name.clone(), CapnpTypeKind::Struct, type_params, line);
+        skip_to_body(cursor)?;
+        self.parse_members(cursor, index, &name, &params, false)
+    }
+
+    /// Members up to and including the closing `}`
+    fn parse_members(&mut self, cursor: &mut Cursor, index: usize, scope: &str, params: &[String], in_union: bool) -> Result<(), String> {
+        loop {
+            let Some(token) = cursor.peek() else {
+                return Err(cursor.unexpected("`}`"));
+            };
+            let declares = cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident);
+            match token.text.as_str() {
+                "}" => {
+                    cursor.advance();
+                    return Ok(());
+                }
+                ";" => {
+                    cursor.advance();
+                }
+                "struct" if declares => self.parse_struct(cursor, scope, params)?,
+                "enum" if declares => self.parse_enum(cursor, scope)?,
+                "interface" if declares => skip_declaration(cursor),
+                "using" | "const" | "annotation" | "$" => skip_statement(cursor),
+                // The struct's unnamed union
+                "union" if cursor.is_punct_at(1, '{') => {
+                    cursor.advance();
+                    cursor.advance();
+                    self.parse_members(cursor, index, scope, params, true)?;
+                }
+                _ if token.kind == TokenKind::Ident && cursor.is_punct_at(1, ':') => {
+                    let line = token.line;
+                    let name = cursor.expect_ident()?;
+                    cursor.advance();
+                    let kind = match cursor.peek().map(|t| t.text.as_str()) {
+                        Some("union") => CapnpTypeKind::Union,
+                        Some("group") => CapnpTypeKind::Group,
+                        _ => return Err(cursor.unexpected("`union` or `group`")),
+                    };
+                    cursor.advance();
+                    let nested = qualify(scope, &pascal_case(&name));
+                    let nested_index = self.new_type(nested.clone(), kind, Vec::new(), line);
+                    skip_to_body(cursor)?;
+                    self.parse_members(cursor, nested_index, &nested, params, kind == CapnpTypeKind::Union)?;
+                    self.types[index].fields.push(CapnpField {
+                        name,
+                        ordinal: None,
+                        field_type: CapnpFieldType::Named(nested, Vec::new()),
+                        in_union,
+                        has_default: false,
+                    });
+                }
+                _ if token.kind == TokenKind::Ident && cursor.is_punct_at(1, '@') => {
+                    let name = cursor.expect_ident()?;
+                    cursor.advance();
+                    let ordinal = parse_ordinal(cursor)?;
+                    cursor.expect_punct(':')?;
+                    let field_type = parse_type(cursor, params)?;
+                    let has_default = cursor.is_punct('=');
+                    // The default value and annotations
+                    skip_statement(cursor);
+                    self.types[index].fields.push(CapnpField { name, ordinal: Some(ordinal), field_type, in_union, has_default });
+                }
+                _ => return Err(cursor.unexpected("a field")),
+            }
+        }
+    }
+
+    fn parse_enum(&mut self, cursor: &mut Cursor, outer: &str) -> Result<(), String> {
+        let line = cursor.line();
+        cursor.advance();
+        let name = qualify(outer, &cursor.expect_ident()?);
+        let index = self.new_type(name, CapnpTypeKind::Enum, Vec::new(), line);
+        skip_to_body(cursor)?;
+        while !cursor.eat_punct('}') {
+            if cursor.eat_punct(';') {
+                continue;
+            }
+            let name = cursor.expect_ident()?;
+            cursor.expect_punct('@')?;
+            let ordinal = parse_ordinal(cursor)?;
+            skip_statement(cursor);
+            self.types[index].enumerants.push(CapnpEnumerant { name, ordinal });
+        }
+        Ok(())
+    }
+}
+
+fn qualify(outer: &str, name: &str) -> String {
+    if outer.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", outer, name)
+    }
+}
+
+/// Past the type id and annotations to just inside the `{`
+fn skip_to_body(cursor: &mut Cursor) -> Result<(), String> {
+    while !cursor.is_punct('{') {
+        if cursor.at_end() || cursor.is_punct('}') || cursor.is_punct(';') {
+            return Err(cursor.unexpected("`{`"));
+        }
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        } else {
+            cursor.advance();
+        }
+    }
+    cursor.advance();
+    Ok(())
+}
+
+fn skip_declaration(cursor: &mut Cursor) {
+    while !cursor.is_punct('{') && !cursor.at_end() {
+        cursor.advance();
+    }
+    cursor.skip_balanced('{', '}');
+}
+
+fn parse_ordinal(cursor: &mut Cursor) -> Result<u32, String> {
+    let number = cursor.advance().ok_or_else(|| cursor.unexpected("ordinal"))?;
+    number.text.parse().map_err(|_| format!("line {}: invalid ordinal `{}`", number.line, number.text))
+}
+
+/// `Text`, `List(Person)`, `Map(Text, Person)`, `Outer.Inner`
+fn parse_type(cursor: &mut Cursor, params: &[String]) -> Result<CapnpFieldType, String> {
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let mut args = Vec::new();
+    if cursor.eat_punct('(') {
+        while !cursor.eat_punct(')') {
+            args.push(parse_type(cursor, params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+    }
+    Ok(match name.as_str() {
+        "Void" => CapnpFieldType::Void,
+        "Bool" => CapnpFieldType::Bool,
+        "Int8" => CapnpFieldType::Int8,
+        "Int16" => CapnpFieldType::Int16,
+        "Int32" => CapnpFieldType::Int32,
+        "Int64" => CapnpFieldType::Int64,
+        "UInt8" => CapnpFieldType::UInt8,
+        "UInt16" => CapnpFieldType::UInt16,
+        "UInt32" => CapnpFieldType::UInt32,
+        "UInt64" => CapnpFieldType::UInt64,
+        "Float32" => CapnpFieldType::Float32,
+        "Float64" => CapnpFieldType::Float64,
+        "Text" => CapnpFieldType::Text,
+        "Data" => CapnpFieldType::Data,
+        "List" if args.len() == 1 => CapnpFieldType::List(Box::new(args.remove(0))),
+        "AnyPointer" | "AnyStruct" | "AnyList" | "Capability" => CapnpFieldType::AnyPointer,
+        _ if params.contains(&name) => CapnpFieldType::TypeParam(name),
+        _ => CapnpFieldType::Named(name, args),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(capnp_type: &CapnpType, target: &str) -> f32 {
+    average_fidelity(ir_fields(capnp_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Cap'n Proto field type into the language-neutral IR
+pub fn to_ir(field_type: &CapnpFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        CapnpFieldType::Void => primitive(PrimitiveType::Unit),
+        CapnpFieldType::Bool => primitive(PrimitiveType::Bool),
+        // No narrower integers in the IR
+        CapnpFieldType::Int8 | CapnpFieldType::Int16 | CapnpFieldType::Int32 => primitive(PrimitiveType::I32),
+        CapnpFieldType::Int64 => primitive(PrimitiveType::I64),
+        CapnpFieldType::UInt8 | CapnpFieldType::UInt16 | CapnpFieldType::UInt32 => primitive(PrimitiveType::U32),
+        CapnpFieldType::UInt64 => primitive(PrimitiveType::U64),
+        CapnpFieldType::Float32 => primitive(PrimitiveType::F32),
+        CapnpFieldType::Float64 => primitive(PrimitiveType::F64),
+        CapnpFieldType::Text | CapnpFieldType::Data => primitive(PrimitiveType::String),
+        CapnpFieldType::List(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        // An untyped pointer: struct, list, or capability
+        CapnpFieldType::AnyPointer => IrType::Any,
+        CapnpFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        CapnpFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower all fields of a type into the IR; union members may be unset
+pub fn ir_fields(capnp_type: &CapnpType) -> Vec<IrField> {
+    capnp_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = if field.in_union { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map Cap'n Proto types to target language types
+pub fn map_to_target(field_type: &CapnpFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESSBOOK: &str = r#"@0xdbb9ad1f14bf0b36;
+
+using Cxx = import "/capnp/c++.capnp";
+$Cxx.namespace("addressbook");
+
+struct Person {
+  id @0 :UInt32;
+  name @1 :Text;
+  phones @2 :List(PhoneNumber);
+
+  struct PhoneNumber {
+    number @0 :Text;
+    type @1 :Type = mobile;
+
+    enum Type {
+      mobile @0;
+      home @1;
+    }
+  }
+
+  employment :union {
+    unemployed @3 :Void;
+    employer @4 :Text;
+  }
+
+  address :group {
+    street @5 :Text $Cxx.name("streetName");
+    houseNumber @6 :UInt16;
+  }
+
+  union {
+    nickname @7 :Text;
+    anonymous @8 :Void;
+  }
+  extra @9 :AnyPointer;
+}
+
+interface Directory {
+  lookup @0 (name :Text) -> (person :Person);
+}
+"#;
+
+    #[test]
+    fn test_analyze_capnp() {
+        let types = analyze_capnp_types(ADDRESSBOOK).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Person", CapnpTypeKind::Struct),
+                ("Person.PhoneNumber", CapnpTypeKind::Struct),
+                ("Person.PhoneNumber.Type", CapnpTypeKind::Enum),
+                ("Person.Employment", CapnpTypeKind::Union),
+                ("Person.Address", CapnpTypeKind::Group),
+            ]
+        );
+        assert_eq!(types[0].location, "line 6");
+        assert!(types[1].fields[1].has_default);
+        assert_eq!(types[2].enumerants[1], CapnpEnumerant { name: "home".to_string(), ordinal: 1 });
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: u32",
+                "name: String",
+                "phones: Vec<PhoneNumber>",
+                "employment: Employment",
+                "address: Address",
+                "nickname: Option<String>",
+                "anonymous: Option<()>",
+                "extra: serde_json::Value",
+            ]
+        );
+        let employment: Vec<_> = ir_fields(&types[3]).iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(employment, vec!["Option<()>", "Option<String>"]);
+        assert_eq!(types[4].fields[0].ordinal, Some(5));
+        assert_eq!(compatibility_score(&types[4], "rust"), 1.0);
+        assert!(compatibility_score(&types[0], "rust") < 1.0);
+    }
+
+    #[test]
+    fn test_generics_and_errors() {
+        let map = analyze_capnp_type("struct Map(Key, Value) { entries @0 :List(Entry); struct Entry { key @0 :Key; value @1 :Value; } }").unwrap();
+        assert_eq!(map.type_params, vec!["Key", "Value"]);
+        let types = analyze_capnp_types("struct Map(Key, Value) { struct Entry { key @0 :Key; } } struct Index { byName @0 :Map(Text, Index); }").unwrap();
+        assert_eq!(types[1].fields[0].field_type, CapnpFieldType::TypeParam("Key".to_string()));
+        assert_eq!(map_to_target(&types[2].fields[0].field_type, "rust"), "Map");
+        assert!(analyze_capnp_type("interface Only {}").is_err());
+        assert!(analyze_capnp_types("struct Open { a @0 :Text;").is_err());
+        assert!(analyze_capnp_types("struct Bad { a @x :Text; }").is_err());
+    }
+}
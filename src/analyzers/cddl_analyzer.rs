@@ -0,0 +1,862 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// CDDL analyzer for protocol-squisher integration
+// Reads RFC 8610 rules describing CBOR payloads: maps and record-like arrays
+// become types, inline ones hoisted under names of their own, and bare group
+// names are spliced into the maps and arrays that include them
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+const CDDL: Syntax = Syntax {
+    line_comments: &[";"],
+    block_comments: &[],
+    // Single quotes delimit byte strings; they're read as text values too
+    string_quotes: &['"', '\''],
+    ident_extra: &['-', '.', '@', '$'],
+    char_literals: false,
+};
+
+/// Fields a type may gain from splicing groups; bounds groups that include
+/// the same group many times over
+const MAX_FIELDS: usize = 1024;
+
+/// Map, record-like array, group, enum, or any other rule
+#[derive(Debug, Clone)]
+pub struct CddlType {
+    /// Pascal-cased (`sensor-reading` is `SensorReading`)
+    pub name: String,
+    /// Rule name as written
+    pub rule: String,
+    pub kind: CddlTypeKind,
+    pub fields: Vec<CddlField>,
+    /// Labels of an enum: the text values of a choice, or the member names of `&( ... )`
+    pub values: Vec<String>,
+    /// What an alias rule describes
+    pub alias: Option<CddlFieldType>,
+    /// Takes entries beyond its fields: `* tstr => any`, or a `$$socket`
+    pub extensible: bool,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CddlTypeKind {
+    /// `{ ... }`, keyed by name
+    Map,
+    /// `[ ... ]` with named or positional entries; fields are read in order
+    Array,
+    /// `( ... )`, spliced into the maps and arrays that name it
+    Group,
+    Enum,
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct CddlField {
+    /// Map key, or the position of an unnamed array entry (`"0"`)
+    pub name: String,
+    pub field_type: CddlFieldType,
+    pub occurrence: CddlOccurrence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CddlOccurrence {
+    Required,
+    /// `?`
+    Optional,
+    /// `*` or `0*n`
+    ZeroOrMore,
+    /// `+` or `n*m` with `n` at least one
+    OneOrMore,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CddlFieldType {
+    UInt,
+    NInt,
+    Int,
+    /// Integer range from `lo..hi`, `.size`, `.le`, or a single integer value
+    Range(i64, i64),
+    /// `float16`
+    Float16,
+    /// `float32`, `float16-32`
+    Float32,
+    /// `float64`, `float32-64`, `float`
+    Float64,
+    Text,
+    Bytes,
+    Bool,
+    /// `nil`, `null`, `undefined`
+    Nil,
+    Any,
+    /// A text value such as `"active"`
+    TextValue(String),
+    /// `[* T]` or `[+ T]`
+    Array(Box<CddlFieldType>),
+    /// `{* K => V}`
+    Map(Box<CddlFieldType>, Box<CddlFieldType>),
+    /// `T1 / T2`
+    Choice(Vec<CddlFieldType>),
+    /// Another rule, or a hoisted inline type, by rule name
+    Named(String),
+}
+
+/// Analyze the first rule of a CDDL specification
+pub fn analyze_cddl_type(source: &str) -> Result<CddlType, String> {
+    analyze_cddl_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every rule of a CDDL specification
+pub fn analyze_cddl_types(source: &str) -> Result<Vec<CddlType>, String> {
+    let mut parser = Parser { cursor: Cursor::new(tokenize(source, &CDDL)), types: Vec::new(), entries: Vec::new(), enum_groups: Vec::new() };
+    while !parser.cursor.at_end() {
+        parser.parse_rule()?;
+    }
+    Ok(parser.resolve())
+}
+
+/// A group entry before group names are resolved
+#[derive(Debug, Clone)]
+enum Entry {
+    /// `name: type`, `"key" => type`, or a bare type in an array
+    Member { key: Option<String>, field_type: CddlFieldType, occurrence: CddlOccurrence },
+    /// `* tstr => any`: keys of a type rather than a named key
+    Computed,
+    /// A bare rule name: a group to splice in, or a positional type in an array
+    Name(String, CddlOccurrence),
+}
+
+impl Entry {
+    fn loosen(&mut self) {
+        match self {
+            Entry::Member { occurrence, .. } | Entry::Name(_, occurrence) if *occurrence == CddlOccurrence::Required => {
+                *occurrence = CddlOccurrence::Optional;
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Parser {
+    cursor: Cursor,
+    types: Vec<CddlType>,
+    /// Unresolved entries of each type in `types`
+    entries: Vec<Vec<Entry>>,
+    /// Enums defined as `&group`, by type index
+    enum_groups: Vec<(usize, String)>,
+}
+
+impl Parser {
+    /// `name = type`, `name /= type`, or `name //= group`
+    fn parse_rule(&mut self) -> Result<(), String> {
+        let line = self.cursor.line();
+        let rule = self.parse_name()?;
+        if self.cursor.is_punct('<') {
+            self.cursor.skip_balanced('<', '>');
+        }
+        let extends_group = self.cursor.is_punct('/') && self.cursor.is_punct_at(1, '/');
+        let extends_type = !extends_group && self.cursor.eat_punct('/');
+        if extends_group {
+            self.cursor.advance();
+            self.cursor.advance();
+        }
+        self.cursor.expect_punct('=')?;
+        let existing = self.types.iter().position(|t| t.rule == rule);
+        let location = format!("line {}", line);
+
+        if let (true, Some(index)) = (extends_group, existing) {
+            let mut added = self.parse_group_or_entry(&self.types[index].name.clone())?;
+            self.entries[index].iter_mut().for_each(Entry::loosen);
+            added.iter_mut().for_each(Entry::loosen);
+            self.entries[index].extend(added);
+            return Ok(());
+        }
+        if let (true, Some(index)) = (extends_type, existing) {
+            let added = self.parse_type(&self.types[index].name.clone())?;
+            let defined = &mut self.types[index];
+            match (defined.kind, text_values(&added)) {
+                (CddlTypeKind::Enum, Some(values)) => defined.values.extend(values),
+                _ => {
+                    let mut members = match defined.alias.take() {
+                        Some(CddlFieldType::Choice(members)) => members,
+                        Some(other) => vec![other],
+                        None => Vec::new(),
+                    };
+                    members.push(added);
+                    defined.kind = CddlTypeKind::Alias;
+                    defined.alias = Some(CddlFieldType::Choice(members));
+                }
+            }
+            return Ok(());
+        }
+
+        let index = self.define(&rule, CddlTypeKind::Alias, location);
+        let name = self.types[index].name.clone();
+        if extends_group {
+            let mut entries = self.parse_group_or_entry(&name)?;
+            entries.iter_mut().for_each(Entry::loosen);
+            self.types[index].kind = CddlTypeKind::Group;
+            self.entries[index] = entries;
+        } else if self.cursor.is_punct('{') || self.cursor.is_punct('[') {
+            let kind = if self.cursor.eat_punct('{') { CddlTypeKind::Map } else { CddlTypeKind::Array };
+            self.cursor.eat_punct('[');
+            let entries = self.parse_group(if kind == CddlTypeKind::Map { '}' } else { ']' }, &name)?;
+            match collection(kind, &entries) {
+                Some(alias) if !self.cursor.is_punct('/') => self.types[index].alias = Some(alias),
+                Some(alias) => self.types[index].alias = Some(self.parse_choice_tail(alias, &name)?),
+                None => {
+                    self.types[index].kind = kind;
+                    self.entries[index] = entries;
+                }
+            }
+        } else if self.cursor.is_punct('(') {
+            // A group, or a parenthesized type like `(int / tstr)`
+            self.cursor.advance();
+            let entries = self.parse_group(')', &name)?;
+            match entries.as_slice() {
+                [Entry::Member { key: None, field_type, occurrence: CddlOccurrence::Required }] => {
+                    let alias = self.parse_choice_tail(field_type.clone(), &name)?;
+                    self.types[index].alias = Some(alias);
+                }
+                _ => {
+                    self.types[index].kind = CddlTypeKind::Group;
+                    self.entries[index] = entries;
+                }
+            }
+        } else if self.cursor.is_punct('&') && !self.cursor.is_punct_at(1, '(') {
+            self.cursor.advance();
+            let group = self.parse_name()?;
+            self.types[index].kind = CddlTypeKind::Enum;
+            self.enum_groups.push((index, group));
+        } else if self.cursor.is_punct('&') {
+            self.cursor.advance();
+            self.cursor.advance();
+            let entries = self.parse_group(')', &name)?;
+            self.types[index].kind = CddlTypeKind::Enum;
+            self.types[index].values = member_keys(&entries);
+        } else {
+            let alias = self.parse_type(&name)?;
+            match text_values(&alias) {
+                Some(values) if values.len() > 1 => {
+                    self.types[index].kind = CddlTypeKind::Enum;
+                    self.types[index].values = values;
+                }
+                _ => self.types[index].alias = Some(alias),
+            }
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, rule: &str, kind: CddlTypeKind, location: String) -> usize {
+        let mut name = pascal_case(rule.trim_start_matches(['$', '@']));
+        // Hoisted names may collide with each other or with a later rule
+        if self.types.iter().any(|t| t.name == name) {
+            let base = name.clone();
+            let mut suffix = 2;
+            while self.types.iter().any(|t| t.name == name) {
+                name = format!("{}{}", base, suffix);
+                suffix += 1;
+            }
+        }
+        self.types.push(CddlType {
+            name,
+            rule: rule.to_string(),
+            kind,
+            fields: Vec::new(),
+            values: Vec::new(),
+            alias: None,
+            extensible: false,
+            location,
+        });
+        self.entries.push(Vec::new());
+        self.types.len() - 1
+    }
+
+    /// `( group )`, or the single entry `//=` allows without parentheses
+    fn parse_group_or_entry(&mut self, hint: &str) -> Result<Vec<Entry>, String> {
+        if self.cursor.eat_punct('(') {
+            self.parse_group(')', hint)
+        } else {
+            Ok(vec![self.parse_entry(hint)?])
+        }
+    }
+
+    /// Entries up to `close`; group choices (`//`) make every entry optional
+    fn parse_group(&mut self, close: char, hint: &str) -> Result<Vec<Entry>, String> {
+        let mut entries = Vec::new();
+        let mut choice = false;
+        while !self.cursor.eat_punct(close) {
+            if self.cursor.at_end() {
+                return Err(self.cursor.unexpected(&format!("`{}`", close)));
+            }
+            if self.cursor.eat_punct(',') {
+                continue;
+            }
+            if self.cursor.is_punct('/') && self.cursor.is_punct_at(1, '/') {
+                self.cursor.advance();
+                self.cursor.advance();
+                choice = true;
+                continue;
+            }
+            entries.push(self.parse_entry(hint)?);
+        }
+        if choice {
+            entries.iter_mut().for_each(Entry::loosen);
+        }
+        Ok(entries)
+    }
+
+    /// `? name: type`, `* tstr => any`, `1 => float`, `+ item`, or a group name
+    fn parse_entry(&mut self, hint: &str) -> Result<Entry, String> {
+        let occurrence = self.parse_occurrence()?;
+        let keyed = self.cursor.is_punct_at(1, ':')
+            && self.cursor.peek().is_some_and(|t| matches!(t.kind, TokenKind::Ident | TokenKind::Str | TokenKind::Number));
+        if keyed {
+            let key = self.cursor.advance().map(|t| t.text).unwrap_or_default();
+            self.cursor.advance();
+            let field_type = self.parse_type(&format!("{}{}", hint, pascal_case(&key)))?;
+            return Ok(Entry::Member { key: Some(key), field_type, occurrence });
+        }
+
+        let field_type = self.parse_type(&format!("{}Item", hint))?;
+        self.cursor.eat_punct('^');
+        if self.cursor.is_punct('=') && self.cursor.is_punct_at(1, '>') {
+            self.cursor.advance();
+            self.cursor.advance();
+            let key = match &field_type {
+                CddlFieldType::TextValue(text) => Some(text.clone()),
+                CddlFieldType::Range(low, high) if low == high => Some(low.to_string()),
+                _ => None,
+            };
+            let value_hint = format!("{}{}", hint, key.as_deref().map(pascal_case).unwrap_or_else(|| "Value".to_string()));
+            let value = self.parse_type(&value_hint)?;
+            return Ok(match key {
+                Some(key) => Entry::Member { key: Some(key), field_type: value, occurrence },
+                None => Entry::Computed,
+            });
+        }
+        Ok(match field_type {
+            CddlFieldType::Named(name) => Entry::Name(name, occurrence),
+            field_type => Entry::Member { key: None, field_type, occurrence },
+        })
+    }
+
+    fn parse_occurrence(&mut self) -> Result<CddlOccurrence, String> {
+        if self.cursor.eat_punct('?') {
+            return Ok(CddlOccurrence::Optional);
+        }
+        if self.cursor.eat_punct('+') {
+            return Ok(CddlOccurrence::OneOrMore);
+        }
+        // `n*m`: a leading number only counts when a `*` follows it
+        let mut minimum = 0;
+        if self.cursor.peek().is_some_and(|t| t.kind == TokenKind::Number) && self.cursor.is_punct_at(1, '*') {
+            let number = self.cursor.advance().map(|t| t.text).unwrap_or_default();
+            minimum = parse_integer(&number).ok_or_else(|| format!("line {}: invalid occurrence `{}`", self.cursor.line(), number))?;
+        }
+        if !self.cursor.eat_punct('*') {
+            return Ok(CddlOccurrence::Required);
+        }
+        // `*3`, unless the number is a key (`* 1 => tstr`)
+        let is_key = self.cursor.is_punct_at(1, ':') || (self.cursor.is_punct_at(1, '=') && self.cursor.is_punct_at(2, '>'));
+        if self.cursor.peek().is_some_and(|t| t.kind == TokenKind::Number) && !is_key {
+            self.cursor.advance();
+        }
+        Ok(if minimum > 0 { CddlOccurrence::OneOrMore } else { CddlOccurrence::ZeroOrMore })
+    }
+
+    /// `type1 / type1 / ...`
+    fn parse_type(&mut self, hint: &str) -> Result<CddlFieldType, String> {
+        let first = self.parse_type1(hint)?;
+        self.parse_choice_tail(first, hint)
+    }
+
+    fn parse_choice_tail(&mut self, first: CddlFieldType, hint: &str) -> Result<CddlFieldType, String> {
+        let mut members = vec![first];
+        while self.cursor.is_punct('/') && !self.cursor.is_punct_at(1, '/') && !self.cursor.is_punct_at(1, '=') {
+            self.cursor.advance();
+            members.push(self.parse_type1(hint)?);
+        }
+        Ok(if members.len() == 1 { members.remove(0) } else { CddlFieldType::Choice(members) })
+    }
+
+    /// A type with an optional range (`0..255`) or control operator (`uint .size 2`)
+    fn parse_type1(&mut self, hint: &str) -> Result<CddlFieldType, String> {
+        let mut field_type = self.parse_type2(hint)?;
+        if self.cursor.is_punct('.') && self.cursor.is_punct_at(1, '.') {
+            self.cursor.advance();
+            self.cursor.advance();
+            let exclusive = self.cursor.eat_punct('.');
+            let upper = self.parse_type2(hint)?;
+            return Ok(match (field_type, upper) {
+                (CddlFieldType::Range(low, _), CddlFieldType::Range(high, _)) => {
+                    CddlFieldType::Range(low, if exclusive { high.saturating_sub(1) } else { high })
+                }
+                (CddlFieldType::Float64, _) | (_, CddlFieldType::Float64) => CddlFieldType::Float64,
+                // Bounds named by other rules
+                _ => CddlFieldType::Int,
+            });
+        }
+        while self.cursor.is_punct('.') && self.cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            self.cursor.advance();
+            let operator = self.cursor.expect_ident()?;
+            let operand = self.parse_type2(hint)?;
+            field_type = match (operator.as_str(), field_type, operand) {
+                // `.size` counts bytes; eight or more is the full width
+                ("size", CddlFieldType::UInt, CddlFieldType::Range(bytes, _)) if (0..8).contains(&bytes) => {
+                    CddlFieldType::Range(0, (1i64 << (bytes * 8)) - 1)
+                }
+                ("le", CddlFieldType::UInt, CddlFieldType::Range(high, _)) => CddlFieldType::Range(0, high),
+                ("lt", CddlFieldType::UInt, CddlFieldType::Range(high, _)) => CddlFieldType::Range(0, high.saturating_sub(1)),
+                // `.default`, `.regexp`, `.bits`, `.cbor`, and the rest don't change the shape
+                (_, field_type, _) => field_type,
+            };
+        }
+        Ok(field_type)
+    }
+
+    fn parse_type2(&mut self, hint: &str) -> Result<CddlFieldType, String> {
+        let Some(token) = self.cursor.peek().cloned() else {
+            return Err(self.cursor.unexpected("a type"));
+        };
+        match token.kind {
+            TokenKind::Number => {
+                self.cursor.advance();
+                number_value(&token.text, false).ok_or_else(|| format!("line {}: invalid number `{}`", token.line, token.text))
+            }
+            TokenKind::Str => {
+                self.cursor.advance();
+                Ok(CddlFieldType::TextValue(token.text))
+            }
+            TokenKind::Ident => {
+                // `h'0aff'` and `b64'...'` are byte strings
+                if matches!(token.text.as_str(), "h" | "b64") && self.cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Str) {
+                    self.cursor.advance();
+                    self.cursor.advance();
+                    return Ok(CddlFieldType::Bytes);
+                }
+                let name = self.parse_name()?;
+                if self.cursor.is_punct('<') {
+                    self.cursor.skip_balanced('<', '>');
+                }
+                Ok(prelude(&name).unwrap_or(CddlFieldType::Named(name)))
+            }
+            TokenKind::Punct => match token.text.as_str() {
+                "-" => {
+                    self.cursor.advance();
+                    let number = self.cursor.advance().ok_or_else(|| self.cursor.unexpected("a number"))?;
+                    number_value(&number.text, true).ok_or_else(|| format!("line {}: invalid number `-{}`", number.line, number.text))
+                }
+                "$" | "@" => Ok(CddlFieldType::Named(self.parse_name()?)),
+                "(" => {
+                    self.cursor.advance();
+                    let inner = self.parse_type(hint)?;
+                    self.cursor.expect_punct(')')?;
+                    Ok(inner)
+                }
+                "{" | "[" => {
+                    let kind = if self.cursor.eat_punct('{') { CddlTypeKind::Map } else { CddlTypeKind::Array };
+                    self.cursor.eat_punct('[');
+                    let entries = self.parse_group(if kind == CddlTypeKind::Map { '}' } else { ']' }, hint)?;
+                    if let Some(collection) = collection(kind, &entries) {
+                        return Ok(collection);
+                    }
+                    let index = self.define(hint, kind, format!("line {}", token.line));
+                    self.entries[index] = entries;
+                    Ok(CddlFieldType::Named(self.types[index].name.clone()))
+                }
+                // `~map` unwraps a map into its group
+                "~" => {
+                    self.cursor.advance();
+                    Ok(CddlFieldType::Named(self.parse_name()?))
+                }
+                "&" => {
+                    self.cursor.advance();
+                    let index = self.define(hint, CddlTypeKind::Enum, format!("line {}", token.line));
+                    if self.cursor.eat_punct('(') {
+                        let entries = self.parse_group(')', hint)?;
+                        self.types[index].values = member_keys(&entries);
+                    } else {
+                        let group = self.parse_name()?;
+                        self.enum_groups.push((index, group));
+                    }
+                    Ok(CddlFieldType::Named(self.types[index].name.clone()))
+                }
+                "#" => self.parse_tagged(hint),
+                _ => Err(self.cursor.unexpected("a type")),
+            },
+        }
+    }
+
+    /// `#6.1(number)` is its content; `#0` through `#3` are major types
+    fn parse_tagged(&mut self, hint: &str) -> Result<CddlFieldType, String> {
+        self.cursor.advance();
+        let mut major = None;
+        if let Some(number) = self.cursor.peek().filter(|t| t.kind == TokenKind::Number).cloned() {
+            self.cursor.advance();
+            major = number.text.split('.').next().and_then(|m| m.parse::<u8>().ok());
+        }
+        if self.cursor.eat_punct('(') {
+            let inner = self.parse_type(hint)?;
+            self.cursor.expect_punct(')')?;
+            return Ok(inner);
+        }
+        Ok(match major {
+            Some(0) => CddlFieldType::UInt,
+            Some(1) => CddlFieldType::NInt,
+            Some(2) => CddlFieldType::Bytes,
+            Some(3) => CddlFieldType::Text,
+            _ => CddlFieldType::Any,
+        })
+    }
+
+    /// `person`, `sensor-reading`, `$socket`, or `$$group-socket`
+    fn parse_name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        while self.cursor.is_punct('$') || self.cursor.is_punct('@') {
+            name.push_str(&self.cursor.advance().map(|t| t.text).unwrap_or_default());
+        }
+        name.push_str(&self.cursor.expect_ident()?);
+        Ok(name)
+    }
+
+    /// Splice group names into the types that include them
+    fn resolve(mut self) -> Vec<CddlType> {
+        for index in 0..self.types.len() {
+            let mut extensible = false;
+            let mut fields = Vec::new();
+            let kind = self.types[index].kind;
+            self.expand(&self.entries[index], kind, &mut vec![index], &mut fields, &mut extensible);
+            for (position, field) in fields.iter_mut().enumerate() {
+                if field.name.is_empty() {
+                    field.name = position.to_string();
+                }
+            }
+            self.types[index].fields = fields;
+            self.types[index].extensible = extensible;
+        }
+        for (index, group) in std::mem::take(&mut self.enum_groups) {
+            let values = match self.types.iter().find(|t| t.rule == group) {
+                Some(group) => group.fields.iter().map(|f| f.name.clone()).collect(),
+                None => Vec::new(),
+            };
+            self.types[index].values = values;
+        }
+        self.types
+    }
+
+    fn expand(&self, entries: &[Entry], kind: CddlTypeKind, active: &mut Vec<usize>, fields: &mut Vec<CddlField>, extensible: &mut bool) {
+        for entry in entries {
+            if fields.len() >= MAX_FIELDS {
+                return;
+            }
+            match entry {
+                Entry::Member { key, field_type, occurrence } => {
+                    if key.is_none() && kind == CddlTypeKind::Map {
+                        *extensible = true;
+                        continue;
+                    }
+                    fields.push(CddlField { name: key.clone().unwrap_or_default(), field_type: field_type.clone(), occurrence: *occurrence });
+                }
+                Entry::Computed => *extensible = true,
+                Entry::Name(name, occurrence) => {
+                    let group = self.types.iter().position(|t| t.rule == *name && matches!(t.kind, CddlTypeKind::Group | CddlTypeKind::Map));
+                    match group {
+                        // A group can't include itself, directly or not
+                        Some(group) if !active.contains(&group) => {
+                            let start = fields.len();
+                            active.push(group);
+                            self.expand(&self.entries[group], kind, active, fields, extensible);
+                            active.pop();
+                            // An optional group makes each of its entries optional
+                            if *occurrence != CddlOccurrence::Required {
+                                for field in &mut fields[start..] {
+                                    if field.occurrence == CddlOccurrence::Required {
+                                        field.occurrence = CddlOccurrence::Optional;
+                                    }
+                                }
+                            }
+                        }
+                        Some(_) => {}
+                        // `$$sockets` and stray types in a map leave it open
+                        None if kind == CddlTypeKind::Map || name.starts_with('$') => *extensible = true,
+                        None => fields.push(CddlField { name: String::new(), field_type: CddlFieldType::Named(name.clone()), occurrence: *occurrence }),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `{* K => V}` is a table and `[* T]` a list rather than a record
+fn collection(kind: CddlTypeKind, entries: &[Entry]) -> Option<CddlFieldType> {
+    match (kind, entries) {
+        (CddlTypeKind::Map, [Entry::Computed]) => Some(CddlFieldType::Map(Box::new(CddlFieldType::Any), Box::new(CddlFieldType::Any))),
+        (CddlTypeKind::Array, [entry]) => {
+            let (item, occurrence) = match entry {
+                Entry::Member { key: None, field_type, occurrence } => (field_type.clone(), *occurrence),
+                Entry::Name(name, occurrence) => (CddlFieldType::Named(name.clone()), *occurrence),
+                _ => return None,
+            };
+            matches!(occurrence, CddlOccurrence::ZeroOrMore | CddlOccurrence::OneOrMore).then(|| CddlFieldType::Array(Box::new(item)))
+        }
+        _ => None,
+    }
+}
+
+/// Values of a choice made only of text values
+fn text_values(field_type: &CddlFieldType) -> Option<Vec<String>> {
+    match field_type {
+        CddlFieldType::TextValue(text) => Some(vec![text.clone()]),
+        CddlFieldType::Choice(members) => members
+            .iter()
+            .map(|member| match member {
+                CddlFieldType::TextValue(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn member_keys(entries: &[Entry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Member { key: Some(key), .. } => Some(key.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Standard prelude types (RFC 8610 appendix D)
+fn prelude(name: &str) -> Option<CddlFieldType> {
+    Some(match name {
+        "uint" => CddlFieldType::UInt,
+        "nint" => CddlFieldType::NInt,
+        "int" | "integer" => CddlFieldType::Int,
+        "unsigned" => CddlFieldType::UInt,
+        "float16" => CddlFieldType::Float16,
+        "float32" | "float16-32" => CddlFieldType::Float32,
+        "float64" | "float32-64" | "float" => CddlFieldType::Float64,
+        // Integral or floating seconds since the epoch
+        "number" | "time" => CddlFieldType::Choice(vec![CddlFieldType::Int, CddlFieldType::Float64]),
+        "tstr" | "text" | "tdate" | "uri" | "b64url" | "b64legacy" | "regexp" | "mime-message" => CddlFieldType::Text,
+        "bstr" | "bytes" | "encoded-cbor" | "biguint" | "bignint" => CddlFieldType::Bytes,
+        "bool" => CddlFieldType::Bool,
+        "true" | "false" => CddlFieldType::Bool,
+        "nil" | "null" | "undefined" => CddlFieldType::Nil,
+        "any" => CddlFieldType::Any,
+        // Bignums, decimal fractions, and bigfloats have no fixed width
+        "bigint" | "decfrac" | "bigfloat" => CddlFieldType::Any,
+        _ => return None,
+    })
+}
+
+/// An integer value as a one-value range, or a float value
+fn number_value(text: &str, negative: bool) -> Option<CddlFieldType> {
+    if let Some(value) = parse_integer(text) {
+        return Some(CddlFieldType::Range(if negative { -value } else { value }, if negative { -value } else { value }));
+    }
+    text.parse::<f64>().ok().map(|_| CddlFieldType::Float64)
+}
+
+fn parse_integer(text: &str) -> Option<i64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => match text.strip_prefix("0b") {
+            Some(binary) => i64::from_str_radix(binary, 2).ok(),
+            None => text.parse().ok(),
+        },
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(cddl_type: &CddlType, target: &str) -> f32 {
+    average_fidelity(ir_fields(cddl_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a CDDL type into the language-neutral IR
+pub fn to_ir(field_type: &CddlFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        CddlFieldType::UInt => primitive(PrimitiveType::U64),
+        CddlFieldType::NInt | CddlFieldType::Int => primitive(PrimitiveType::I64),
+        // The narrowest IR integer holding the whole range
+        CddlFieldType::Range(low, high) => {
+            if *low >= 0 && *high <= i64::from(u32::MAX) {
+                primitive(PrimitiveType::U32)
+            } else if *low >= i64::from(i32::MIN) && *high <= i64::from(i32::MAX) {
+                primitive(PrimitiveType::I32)
+            } else if *low >= 0 {
+                primitive(PrimitiveType::U64)
+            } else {
+                primitive(PrimitiveType::I64)
+            }
+        }
+        CddlFieldType::Float16 | CddlFieldType::Float32 => primitive(PrimitiveType::F32),
+        CddlFieldType::Float64 => primitive(PrimitiveType::F64),
+        CddlFieldType::Text | CddlFieldType::TextValue(_) => primitive(PrimitiveType::String),
+        CddlFieldType::Bool => primitive(PrimitiveType::Bool),
+        CddlFieldType::Nil => primitive(PrimitiveType::Unit),
+        CddlFieldType::Bytes => IrType::Primitive(PrimitiveType::Bytes),
+        CddlFieldType::Any => IrType::Any,
+        CddlFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        CddlFieldType::Array(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        CddlFieldType::Choice(members) => choice_to_ir(members),
+        CddlFieldType::Named(name) => IrType::Reference(pascal_case(name.trim_start_matches(['$', '@']))),
+    }
+}
+
+/// A `nil` member makes the rest optional
+fn choice_to_ir(members: &[CddlFieldType]) -> IrType {
+    let nullable = members.contains(&CddlFieldType::Nil);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != CddlFieldType::Nil) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower the fields of a map, array, or group into the IR; optional entries
+/// become options, and repeated array entries become lists
+pub fn ir_fields(cddl_type: &CddlType) -> Vec<IrField> {
+    cddl_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let repeated = matches!(field.occurrence, CddlOccurrence::ZeroOrMore | CddlOccurrence::OneOrMore);
+            let ty = match ty {
+                _ if repeated && cddl_type.kind != CddlTypeKind::Map => IrType::Container(ContainerType::Vec(Box::new(ty))),
+                IrType::Container(ContainerType::Option(_)) | IrType::Any => ty,
+                // A map key can appear at most once, so `*` means it may be absent
+                _ if field.occurrence != CddlOccurrence::Required && field.occurrence != CddlOccurrence::OneOrMore => {
+                    IrType::Container(ContainerType::Option(Box::new(ty)))
+                }
+                _ => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map CDDL types to target language types
+pub fn map_to_target(field_type: &CddlFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENSOR: &str = r#"
+; Readings reported by a constrained sensor node
+sensor-reading = {
+  device: device-id,
+  ? label: tstr,
+  temperature: float16,
+  humidity: 0..100,
+  battery: uint .size 1,
+  samples: [* int],
+  status: status,
+  location: { lat: float, lon: float } / nil,
+  unit: &( celsius: 0, fahrenheit: 1 ),
+  timestamps,
+  * tstr => any,
+}
+
+timestamps = (
+  taken: time,
+  ? sent: #6.1(uint),
+)
+
+device-id = bstr .size 8
+status = "ok" / "degraded" / "failed"
+status /= "offline"
+
+point = [x: int, y: int, ? z: int]
+envelope = [uint, point, * header]
+header = { * tstr => tstr }
+"#;
+
+    #[test]
+    fn test_analyze_cddl() {
+        let types = analyze_cddl_types(SENSOR).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("SensorReading", CddlTypeKind::Map, "line 3"),
+                ("SensorReadingLocation", CddlTypeKind::Map, "line 11"),
+                ("SensorReadingUnit", CddlTypeKind::Enum, "line 12"),
+                ("Timestamps", CddlTypeKind::Group, "line 17"),
+                ("DeviceId", CddlTypeKind::Alias, "line 22"),
+                ("Status", CddlTypeKind::Enum, "line 23"),
+                ("Point", CddlTypeKind::Array, "line 26"),
+                ("Envelope", CddlTypeKind::Array, "line 27"),
+                ("Header", CddlTypeKind::Alias, "line 28"),
+            ]
+        );
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "device: DeviceId",
+                "label: Option<String>",
+                "temperature: f32",
+                "humidity: u32",
+                "battery: u32",
+                "samples: Vec<i64>",
+                "status: Status",
+                "location: Option<SensorReadingLocation>",
+                "unit: SensorReadingUnit",
+                "taken: IntOrNumber",
+                "sent: Option<u64>",
+            ]
+        );
+        assert!(types[0].extensible);
+        assert_eq!(types[0].fields[4].field_type, CddlFieldType::Range(0, 255));
+        assert_eq!(types[2].values, vec!["celsius", "fahrenheit"]);
+        assert_eq!(types[5].values, vec!["ok", "degraded", "failed", "offline"]);
+        assert_eq!(types[4].alias, Some(CddlFieldType::Bytes));
+
+        let envelope: Vec<_> = ir_fields(&types[7]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(envelope, vec!["0: u64", "1: Point", "2: Vec<Header>"]);
+        assert_eq!(compatibility_score(&types[6], "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_group_choices_and_errors() {
+        let shape = analyze_cddl_type("shape = { kind: \"circle\", radius: float // kind: \"square\", side: float }").unwrap();
+        assert!(shape.fields.iter().all(|f| f.occurrence == CddlOccurrence::Optional));
+        let colors = analyze_cddl_types("color = &colors\ncolors = ( red: 0, green: 1 )").unwrap();
+        assert_eq!(colors[0].values, vec!["red", "green"]);
+        let list = analyze_cddl_type("names = [+ tstr]").unwrap();
+        assert_eq!(map_to_target(list.alias.as_ref().unwrap(), "gleam"), "List(String)");
+        assert!(analyze_cddl_type("; nothing here").is_err());
+        assert!(analyze_cddl_types("open = { a: int").is_err());
+        assert!(analyze_cddl_types("= int").is_err());
+        assert!(analyze_cddl_types("loop = ( loop )\nuser = { loop }").is_ok());
+    }
+}
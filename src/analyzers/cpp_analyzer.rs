@@ -0,0 +1,826 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// C++ header analyzer for protocol-squisher integration
+// Analyzes struct and class definitions, including standard library containers
+
+use super::c_analyzer::{skip_statement, without_preprocessor};
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::PlatformWidth;
+
+/// C++ struct or class definition
+#[derive(Debug, Clone)]
+pub struct CppType {
+    /// Unqualified name; nested classes are spelled `Outer::Inner`
+    pub name: String,
+    /// Enclosing namespaces (`app::model`), if any
+    pub namespace: Option<String>,
+    pub kind: CppTypeKind,
+    /// Parameters of a class template (`template <typename T>` → `T`)
+    pub type_params: Vec<String>,
+    /// Base classes as written; their members are not merged into `fields`
+    pub bases: Vec<String>,
+    /// Non-static data members
+    pub fields: Vec<CppField>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CppTypeKind {
+    Struct,
+    Class,
+}
+
+/// Member access; `struct` members default to public, `class` members to private
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CppAccess {
+    Public,
+    Protected,
+    Private,
+}
+
+#[derive(Debug, Clone)]
+pub struct CppField {
+    pub name: String,
+    pub field_type: CppFieldType,
+    pub access: CppAccess,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CppFieldType {
+    Int8,
+    Int16,
+    Int32,   // also `int`
+    Int64,   // also `long long`
+    UInt8,
+    UInt16,
+    UInt32,  // also `unsigned`
+    UInt64,
+    Long,    // 32-bit on Windows, 64-bit elsewhere
+    ULong,
+    Size,    // `std::size_t`
+    Char,
+    Float,
+    Double,
+    Bool,
+    /// `std::string`, `std::string_view`, and the wide/UTF variants
+    String,
+    /// `std::vector`, `std::deque`, `std::list`, `std::set`, `std::span`
+    Vector(Box<CppFieldType>),
+    /// `std::array<T, N>` or a built-in array member; `None` when the size is a constant expression
+    Array(Box<CppFieldType>, Option<usize>),
+    Optional(Box<CppFieldType>),
+    Variant(Vec<CppFieldType>),
+    /// `std::map` (ordered) and `std::unordered_map`
+    Map { key: Box<CppFieldType>, value: Box<CppFieldType>, ordered: bool },
+    UniquePtr(Box<CppFieldType>),
+    SharedPtr(Box<CppFieldType>),
+    /// Raw, non-owning pointer
+    Pointer(Box<CppFieldType>),
+    /// `std::function<...>`
+    Function,
+    /// `std::monostate`, the empty alternative of a variant
+    Monostate,
+    /// User type or unmodelled library type, with its template arguments
+    Named(String, Vec<CppFieldType>),
+    TypeParam(String),
+}
+
+/// Words that begin a member declaration we don't model (methods, aliases, statics)
+const SKIPPED_MEMBERS: &[&str] = &[
+    "using", "typedef", "friend", "static_assert", "template", "static", "virtual", "explicit", "inline",
+    "constexpr", "consteval", "operator", "enum", "union",
+];
+
+/// Analyze the first C++ struct or class and extract metadata
+pub fn analyze_cpp_type(source: &str) -> Result<CppType, String> {
+    analyze_cpp_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct and class definition in a header, nested classes included
+pub fn analyze_cpp_types(source: &str) -> Result<Vec<CppType>, String> {
+    let mut cursor = Cursor::new(without_preprocessor(tokenize(source, &Syntax::C_LIKE)));
+    let mut types = Vec::new();
+    // One entry per open brace at namespace scope; `None` for `extern "C"` blocks
+    let mut scopes: Vec<Option<String>> = Vec::new();
+
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "namespace" => {
+                cursor.advance();
+                let mut name = Vec::new();
+                while let Some(segment) = cursor.peek().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.clone()) {
+                    cursor.advance();
+                    name.push(segment);
+                    if !eat_scope(&mut cursor) {
+                        break;
+                    }
+                }
+                if cursor.eat_punct('{') {
+                    // Anonymous namespaces add no qualification
+                    scopes.push(Some(name.join("::")).filter(|n| !n.is_empty()));
+                } else {
+                    // `namespace fs = std::filesystem;`
+                    skip_statement(&mut cursor);
+                }
+            }
+            "extern" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Str) && cursor.is_punct_at(2, '{') => {
+                cursor.advance();
+                cursor.advance();
+                cursor.advance();
+                scopes.push(None);
+            }
+            "template" if cursor.is_punct_at(1, '<') => {
+                cursor.advance();
+                let type_params = parse_template_params(&mut cursor)?;
+                if is_class_definition(&cursor) {
+                    let namespace = qualification(&scopes);
+                    parse_class(&mut cursor, namespace, String::new(), type_params, &mut types)?;
+                } else {
+                    // Function and alias templates
+                    skip_statement(&mut cursor);
+                }
+            }
+            "struct" | "class" if is_class_definition(&cursor) => {
+                let namespace = qualification(&scopes);
+                parse_class(&mut cursor, namespace, String::new(), Vec::new(), &mut types)?;
+            }
+            "}" => {
+                cursor.advance();
+                scopes.pop();
+            }
+            ";" => {
+                cursor.advance();
+            }
+            _ => skip_statement(&mut cursor),
+        }
+    }
+
+    Ok(types)
+}
+
+fn qualification(scopes: &[Option<String>]) -> Option<String> {
+    let names: Vec<&str> = scopes.iter().flatten().map(String::as_str).collect();
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join("::"))
+    }
+}
+
+/// `::` lexes as two `:` tokens
+fn eat_scope(cursor: &mut Cursor) -> bool {
+    if cursor.is_punct(':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+        true
+    } else {
+        false
+    }
+}
+
+/// `[[nodiscard]]`, `alignas(8)`, `__attribute__((packed))`
+fn skip_attributes(cursor: &mut Cursor) {
+    loop {
+        if cursor.is_punct('[') && cursor.is_punct_at(1, '[') {
+            cursor.skip_balanced('[', ']');
+        } else if cursor
+            .peek()
+            .is_some_and(|t| matches!(t.text.as_str(), "alignas" | "__attribute__" | "__declspec"))
+        {
+            cursor.advance();
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            }
+        } else {
+            return;
+        }
+    }
+}
+
+/// `struct Name {`, `class Name final : public Base {` — not forward declarations or variables
+fn is_class_definition(cursor: &Cursor) -> bool {
+    if !matches!(cursor.peek().map(|t| t.text.as_str()), Some("struct" | "class")) {
+        return false;
+    }
+    let mut offset = 1;
+    while let Some(token) = cursor.peek_at(offset) {
+        match token.text.as_str() {
+            "{" => return true,
+            ":" if !cursor.is_punct_at(offset + 1, ':') => return true,
+            ":" => offset += 2,
+            ";" | "(" | "=" | "}" | "," | "*" | "&" => return false,
+            // Attributes between the keyword and the name
+            "[" | "]" | "alignas" => offset += 1,
+            _ if token.kind == TokenKind::Ident => offset += 1,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Parameter names of `template <typename T, std::size_t N = 4>`, starting at `<`
+fn parse_template_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    cursor.expect_punct('<')?;
+    let mut params = Vec::new();
+    let mut name = None;
+    let mut in_default = false;
+    let mut depth = 0usize;
+    loop {
+        let token = cursor.advance().ok_or_else(|| cursor.unexpected("`>`"))?;
+        match token.text.as_str() {
+            "<" | "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            ">" if depth > 0 => depth -= 1,
+            "=" if depth == 0 => in_default = true,
+            "," | ">" => {
+                params.extend(name.take());
+                in_default = false;
+                if token.text == ">" {
+                    return Ok(params);
+                }
+            }
+            "typename" | "class" => {}
+            _ if token.kind == TokenKind::Ident && depth == 0 && !in_default => name = Some(token.text),
+            _ => {}
+        }
+    }
+}
+
+/// A struct or class definition starting at its keyword; pushes it and any nested classes
+fn parse_class(
+    cursor: &mut Cursor,
+    namespace: Option<String>,
+    outer: String,
+    type_params: Vec<String>,
+    types: &mut Vec<CppType>,
+) -> Result<(), String> {
+    let line = cursor.line();
+    let kind = if cursor.eat_ident("class") {
+        CppTypeKind::Class
+    } else {
+        cursor.expect_ident()?;
+        CppTypeKind::Struct
+    };
+    skip_attributes(cursor);
+    let mut name = cursor.expect_ident()?;
+    while eat_scope(cursor) {
+        // Out-of-line definition of a nested class: `struct Outer::Inner { ... }`
+        name = format!("{}::{}", name, cursor.expect_ident()?);
+    }
+    if !outer.is_empty() {
+        name = format!("{}::{}", outer, name);
+    }
+    cursor.eat_ident("final");
+
+    let mut bases = Vec::new();
+    if cursor.eat_punct(':') {
+        loop {
+            while cursor.peek().is_some_and(|t| matches!(t.text.as_str(), "public" | "protected" | "private" | "virtual")) {
+                cursor.advance();
+            }
+            let mut base = String::new();
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    "{" | "," => break,
+                    "<" => {
+                        cursor.skip_balanced('<', '>');
+                        continue;
+                    }
+                    text => base.push_str(text),
+                }
+                cursor.advance();
+            }
+            bases.push(base);
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+    }
+
+    let index = types.len();
+    types.push(CppType {
+        name: name.clone(),
+        namespace: namespace.clone(),
+        kind,
+        type_params,
+        bases,
+        fields: Vec::new(),
+        location: format!("line {}", line),
+    });
+    let default_access = if kind == CppTypeKind::Class { CppAccess::Private } else { CppAccess::Public };
+    let fields = parse_members(cursor, default_access, &namespace, &name, types, index)?;
+    types[index].fields = fields;
+    // `struct Point { ... } origin;` also declares a variable
+    skip_statement(cursor);
+    Ok(())
+}
+
+/// Body of `types[index]` from `{` to `}`, collecting its data members and
+/// pushing nested classes (which share the enclosing template's parameters)
+fn parse_members(
+    cursor: &mut Cursor,
+    default_access: CppAccess,
+    namespace: &Option<String>,
+    name: &str,
+    types: &mut Vec<CppType>,
+    index: usize,
+) -> Result<Vec<CppField>, String> {
+    let type_params = types[index].type_params.clone();
+    cursor.expect_punct('{')?;
+    let mut access = default_access;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        skip_attributes(cursor);
+        let Some(word) = cursor.peek().map(|t| t.text.clone()) else { continue };
+        match word.as_str() {
+            "public" | "protected" | "private" if cursor.is_punct_at(1, ':') => {
+                access = match word.as_str() {
+                    "public" => CppAccess::Public,
+                    "protected" => CppAccess::Protected,
+                    _ => CppAccess::Private,
+                };
+                cursor.advance();
+                cursor.advance();
+            }
+            ";" => {
+                cursor.advance();
+            }
+            "struct" | "class" if is_class_definition(cursor) => {
+                parse_class(cursor, namespace.clone(), name.to_string(), type_params.clone(), types)?
+            }
+            // Destructors
+            "~" => skip_statement(cursor),
+            _ if SKIPPED_MEMBERS.contains(&word.as_str()) => skip_statement(cursor),
+            _ => match parse_data_member(cursor, &type_params) {
+                Some(declared) => fields.extend(declared.into_iter().map(|(name, field_type)| CppField {
+                    name,
+                    field_type,
+                    access,
+                })),
+                // Constructors, methods, and declarations we can't read
+                None => skip_statement(cursor),
+            },
+        }
+    }
+    Ok(fields)
+}
+
+/// `int x = 0, *y;` → `[("x", Int32), ("y", Pointer(Int32))]`; `None` if this isn't a data member
+fn parse_data_member(cursor: &mut Cursor, type_params: &[String]) -> Option<Vec<(String, CppFieldType)>> {
+    let base = parse_type(cursor, type_params).ok()?;
+    let mut declared = Vec::new();
+    loop {
+        let mut ty = base.clone();
+        loop {
+            if cursor.eat_punct('*') {
+                ty = CppFieldType::Pointer(Box::new(ty));
+            } else if !cursor.eat_punct('&') && !cursor.eat_ident("const") {
+                break;
+            }
+        }
+        let name = cursor.peek().filter(|t| t.kind == TokenKind::Ident && t.text != "operator")?.text.clone();
+        cursor.advance();
+        if cursor.is_punct('(') {
+            // Method declaration or definition
+            return None;
+        }
+        let mut dimensions = Vec::new();
+        while cursor.eat_punct('[') {
+            let size = cursor.peek().filter(|t| t.kind == TokenKind::Number).and_then(|t| t.text.parse().ok());
+            while !cursor.at_end() && !cursor.eat_punct(']') {
+                cursor.advance();
+            }
+            dimensions.push(size);
+        }
+        for size in dimensions.into_iter().rev() {
+            ty = CppFieldType::Array(Box::new(ty), size);
+        }
+        skip_initializer(cursor);
+        declared.push((name, ty));
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.eat_punct(';').then_some(declared)
+}
+
+/// Bitfield width, `= value`, or `{value}` after a member's declarator
+fn skip_initializer(cursor: &mut Cursor) {
+    if cursor.is_punct('{') {
+        cursor.skip_balanced('{', '}');
+        return;
+    }
+    if !cursor.eat_punct('=') && !cursor.eat_punct(':') {
+        return;
+    }
+    let mut depth = 0usize;
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            ";" => return,
+            "," | "}" if depth == 0 => return,
+            "(" | "{" | "<" => depth += 1,
+            ")" | "}" | ">" => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        cursor.advance();
+    }
+}
+
+/// A type without declarator pointers: `const std::vector<std::string>`, `unsigned long`
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CppFieldType, String> {
+    let mut words: Vec<String> = Vec::new();
+    while let Some(text) = cursor.peek().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.clone()) {
+        match text.as_str() {
+            "const" | "volatile" | "mutable" | "typename" | "struct" | "class" => {}
+            "unsigned" | "signed" | "short" | "long" | "int" | "char" | "float" | "double" | "bool" | "void"
+            | "wchar_t" | "char8_t" | "char16_t" | "char32_t" => words.push(text),
+            _ => break,
+        }
+        cursor.advance();
+    }
+    let ty = if words.is_empty() {
+        parse_named(cursor, type_params)?
+    } else {
+        keyword_type(&words).ok_or_else(|| cursor.unexpected("data member type"))?
+    };
+    while cursor.eat_ident("const") || cursor.eat_ident("volatile") {}
+    Ok(ty)
+}
+
+/// Builtin type from keywords (`unsigned long long` → `UInt64`); `void` has no member form
+fn keyword_type(words: &[String]) -> Option<CppFieldType> {
+    let has = |word: &str| words.iter().any(|w| w == word);
+    let longs = words.iter().filter(|w| *w == "long").count();
+    let unsigned = has("unsigned");
+    Some(if has("void") {
+        return None;
+    } else if has("bool") {
+        CppFieldType::Bool
+    } else if has("float") {
+        CppFieldType::Float
+    } else if has("double") {
+        CppFieldType::Double
+    } else if has("char") {
+        match (unsigned, has("signed")) {
+            (true, _) => CppFieldType::UInt8,
+            (false, true) => CppFieldType::Int8,
+            _ => CppFieldType::Char,
+        }
+    } else if has("char8_t") {
+        CppFieldType::UInt8
+    } else if has("char16_t") {
+        CppFieldType::UInt16
+    } else if has("wchar_t") || has("char32_t") {
+        CppFieldType::UInt32
+    } else if has("short") {
+        if unsigned { CppFieldType::UInt16 } else { CppFieldType::Int16 }
+    } else if longs >= 2 {
+        if unsigned { CppFieldType::UInt64 } else { CppFieldType::Int64 }
+    } else if longs == 1 {
+        if unsigned { CppFieldType::ULong } else { CppFieldType::Long }
+    } else if unsigned {
+        CppFieldType::UInt32
+    } else {
+        CppFieldType::Int32
+    })
+}
+
+/// Qualified name with optional template arguments (`std::optional<int>`, `::app::User`)
+fn parse_named(cursor: &mut Cursor, type_params: &[String]) -> Result<CppFieldType, String> {
+    eat_scope(cursor);
+    let mut name = cursor.expect_ident()?;
+    while eat_scope(cursor) {
+        name.push_str("::");
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let unqualified = name.strip_prefix("std::").unwrap_or(&name).to_string();
+
+    if !cursor.is_punct('<') {
+        return Ok(match unqualified.as_str() {
+            "string" | "wstring" | "u8string" | "u16string" | "u32string" | "string_view" => CppFieldType::String,
+            "int8_t" => CppFieldType::Int8,
+            "int16_t" => CppFieldType::Int16,
+            "int32_t" => CppFieldType::Int32,
+            "int64_t" => CppFieldType::Int64,
+            "uint8_t" | "byte" => CppFieldType::UInt8,
+            "uint16_t" => CppFieldType::UInt16,
+            "uint32_t" => CppFieldType::UInt32,
+            "uint64_t" => CppFieldType::UInt64,
+            "size_t" => CppFieldType::Size,
+            "ptrdiff_t" | "intptr_t" | "ssize_t" => CppFieldType::Int64,
+            "monostate" => CppFieldType::Monostate,
+            _ if type_params.contains(&name) => CppFieldType::TypeParam(name),
+            _ => CppFieldType::Named(name, Vec::new()),
+        });
+    }
+
+    match unqualified.as_str() {
+        // Signatures aren't modelled
+        "function" | "move_only_function" => {
+            cursor.skip_balanced('<', '>');
+            return Ok(CppFieldType::Function);
+        }
+        "array" => {
+            cursor.expect_punct('<')?;
+            let element = parse_type(cursor, type_params)?;
+            cursor.expect_punct(',')?;
+            let size = cursor.peek().filter(|t| t.kind == TokenKind::Number).and_then(|t| t.text.parse().ok());
+            // Skip the size expression, which may itself contain `<...>`
+            let mut depth = 0usize;
+            loop {
+                let token = cursor.advance().ok_or_else(|| cursor.unexpected("`>`"))?;
+                match token.text.as_str() {
+                    "<" => depth += 1,
+                    ">" if depth == 0 => break,
+                    ">" => depth -= 1,
+                    _ => {}
+                }
+            }
+            return Ok(CppFieldType::Array(Box::new(element), size));
+        }
+        _ => {}
+    }
+
+    let mut args = parse_template_args(cursor, type_params)?;
+    let one = |args: &mut Vec<CppFieldType>| Box::new(args.drain(..).next().unwrap_or(CppFieldType::Monostate));
+    Ok(match (unqualified.as_str(), args.len()) {
+        ("vector" | "deque" | "list" | "forward_list" | "set" | "unordered_set" | "multiset" | "span", 1..) => {
+            CppFieldType::Vector(one(&mut args))
+        }
+        ("optional", 1) => CppFieldType::Optional(one(&mut args)),
+        ("variant", _) => CppFieldType::Variant(args),
+        ("map" | "unordered_map" | "multimap", 2..) => {
+            let mut args = args.into_iter();
+            let key = Box::new(args.next().unwrap_or(CppFieldType::Monostate));
+            let value = Box::new(args.next().unwrap_or(CppFieldType::Monostate));
+            CppFieldType::Map { key, value, ordered: unqualified != "unordered_map" }
+        }
+        ("unique_ptr", 1..) => CppFieldType::UniquePtr(one(&mut args)),
+        ("shared_ptr", 1) => CppFieldType::SharedPtr(one(&mut args)),
+        _ => CppFieldType::Named(name, args),
+    })
+}
+
+/// `<T, U>` after a template name; non-type arguments are kept as names
+fn parse_template_args(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<CppFieldType>, String> {
+    cursor.expect_punct('<')?;
+    let mut args = Vec::new();
+    while !cursor.eat_punct('>') {
+        match cursor.peek() {
+            Some(token) if token.kind == TokenKind::Number => {
+                args.push(CppFieldType::Named(token.text.clone(), Vec::new()));
+                cursor.advance();
+            }
+            _ => {
+                let mut arg = parse_type(cursor, type_params)?;
+                while cursor.eat_punct('*') {
+                    arg = CppFieldType::Pointer(Box::new(arg));
+                }
+                args.push(arg);
+            }
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+            return Err(cursor.unexpected("`,` or `>`"));
+        }
+    }
+    Ok(args)
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(cpp_type: &CppType, target: &str) -> f32 {
+    average_fidelity(ir_fields(cpp_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a C++ field type into the language-neutral IR
+pub fn to_ir(field_type: &CppFieldType) -> IrType {
+    match field_type {
+        // `char` is a byte; signed on the common ABIs
+        CppFieldType::Int8 | CppFieldType::Char => IrType::Primitive(PrimitiveType::I8),
+        CppFieldType::Int16 => IrType::Primitive(PrimitiveType::I16),
+        CppFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        CppFieldType::Int64 | CppFieldType::Long => IrType::Primitive(PrimitiveType::I64),
+        CppFieldType::UInt8 => IrType::Primitive(PrimitiveType::U8),
+        CppFieldType::UInt16 => IrType::Primitive(PrimitiveType::U16),
+        CppFieldType::UInt32 => IrType::Primitive(PrimitiveType::U32),
+        CppFieldType::UInt64 | CppFieldType::ULong | CppFieldType::Size => IrType::Primitive(PrimitiveType::U64),
+        CppFieldType::Float => IrType::Primitive(PrimitiveType::F32),
+        CppFieldType::Double => IrType::Primitive(PrimitiveType::F64),
+        CppFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        CppFieldType::String => IrType::Primitive(PrimitiveType::String),
+        CppFieldType::Monostate => IrType::Primitive(PrimitiveType::Unit),
+        CppFieldType::Array(element, Some(size)) => IrType::Container(ContainerType::Array(Box::new(to_ir(element)), *size)),
+        CppFieldType::Vector(element) | CppFieldType::Array(element, None) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        // Smart and raw pointers may all be null
+        CppFieldType::Optional(inner)
+        | CppFieldType::UniquePtr(inner)
+        | CppFieldType::SharedPtr(inner)
+        | CppFieldType::Pointer(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        CppFieldType::Variant(alternatives) => variant_to_ir(alternatives),
+        CppFieldType::Map { key, value, .. } => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        CppFieldType::Function => IrType::Any,
+        CppFieldType::Named(name, _) if name.starts_with("std::") => IrType::Any,
+        CppFieldType::Named(name, _) => IrType::Reference(name.clone()),
+        CppFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// `std::variant<std::monostate, A, B>` is an optional union of `A` and `B`
+fn variant_to_ir(alternatives: &[CppFieldType]) -> IrType {
+    let mut distinct: Vec<IrType> = Vec::new();
+    for alternative in alternatives.iter().filter(|a| **a != CppFieldType::Monostate) {
+        let lowered = to_ir(alternative);
+        if !distinct.contains(&lowered) {
+            distinct.push(lowered);
+        }
+    }
+    let nullable = alternatives.contains(&CppFieldType::Monostate);
+    let inner = match distinct.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => distinct.remove(0),
+        _ => IrType::Union(distinct),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all data members of a C++ type into the IR
+pub fn ir_fields(cpp_type: &CppType) -> Vec<IrField> {
+    cpp_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`long`, `std::size_t`)
+pub fn platform_width(field_type: &CppFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        CppFieldType::Long | CppFieldType::ULong => Some(PlatformWidth::CLong),
+        CppFieldType::Size => Some(PlatformWidth::Pointer),
+        CppFieldType::Vector(element) | CppFieldType::Array(element, _) | CppFieldType::Optional(element) => platform_width(element),
+        _ => None,
+    }
+}
+
+/// Map C++ types to target language types
+pub fn map_to_target(field_type: &CppFieldType, target: &str) -> String {
+    match target {
+        "rust" => match field_type {
+            CppFieldType::Int8 => "i8".to_string(),
+            CppFieldType::Int16 => "i16".to_string(),
+            CppFieldType::Int32 | CppFieldType::Char => "i32".to_string(),
+            CppFieldType::Int64 | CppFieldType::Long => "i64".to_string(),
+            CppFieldType::UInt8 => "u8".to_string(),
+            CppFieldType::UInt16 => "u16".to_string(),
+            CppFieldType::UInt32 => "u32".to_string(),
+            CppFieldType::UInt64 | CppFieldType::ULong => "u64".to_string(),
+            CppFieldType::Size => "usize".to_string(),
+            CppFieldType::Vector(element) => format!("Vec<{}>", map_to_target(element, target)),
+            CppFieldType::Array(element, Some(size)) => format!("[{}; {}]", map_to_target(element, target), size),
+            CppFieldType::Array(element, None) => format!("Vec<{}>", map_to_target(element, target)),
+            CppFieldType::Optional(inner) => format!("Option<{}>", map_to_target(inner, target)),
+            CppFieldType::UniquePtr(inner) => format!("Option<Box<{}>>", map_to_target(inner, target)),
+            CppFieldType::SharedPtr(inner) => format!("Option<std::sync::Arc<{}>>", map_to_target(inner, target)),
+            CppFieldType::Map { key, value, ordered } => format!(
+                "std::collections::{}<{}, {}>",
+                if *ordered { "BTreeMap" } else { "HashMap" },
+                map_to_target(key, target),
+                map_to_target(value, target)
+            ),
+            CppFieldType::Named(name, args) if !name.starts_with("std::") => {
+                let name = name.rsplit("::").next().unwrap_or(name);
+                if args.is_empty() {
+                    name.to_string()
+                } else {
+                    let args: Vec<String> = args.iter().map(|a| map_to_target(a, target)).collect();
+                    format!("{}<{}>", name, args.join(", "))
+                }
+            }
+            other => render_type(&to_ir(other), target),
+        },
+        "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = r#"
+#pragma once
+#include <string>
+#include <variant>
+
+namespace app::model {
+
+struct Address;
+
+class User final : public Entity {
+public:
+    User() = default;
+    explicit User(std::string name) : name_(std::move(name)) {}
+    ~User();
+    const std::string& name() const { return name_; }
+
+    std::uint64_t id = 0;
+    std::vector<std::string> tags;
+    std::optional<Address> address;
+    std::variant<std::monostate, int, std::string> badge;
+    std::map<std::string, int> scores{};
+    std::unique_ptr<Address> previous;
+    std::array<double, 3> position;
+    static constexpr int kMaxTags = 8;
+
+    struct Session {
+        std::string token;
+    };
+
+private:
+    std::string name_;
+};
+
+template <typename T>
+struct Page {
+    std::vector<T> items;
+    std::size_t total, offset;
+};
+
+}  // namespace app::model
+"#;
+
+    #[test]
+    fn test_analyze_header() {
+        let types = analyze_cpp_types(HEADER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "User::Session", "Page"]);
+        assert!(types.iter().all(|t| t.namespace.as_deref() == Some("app::model")));
+
+        let user = &types[0];
+        assert_eq!(user.kind, CppTypeKind::Class);
+        assert_eq!(user.bases, vec!["Entity"]);
+        let fields: Vec<_> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["id", "tags", "address", "badge", "scores", "previous", "position", "name_"]);
+        assert_eq!(user.fields[0].field_type, CppFieldType::UInt64);
+        assert_eq!(user.fields[1].field_type, CppFieldType::Vector(Box::new(CppFieldType::String)));
+        assert_eq!(user.fields[0].access, CppAccess::Public);
+        assert_eq!(user.fields[7].access, CppAccess::Private);
+
+        let page = &types[2];
+        assert_eq!(page.type_params, vec!["T"]);
+        assert_eq!(page.fields.len(), 3);
+        assert_eq!(page.fields[0].field_type, CppFieldType::Vector(Box::new(CppFieldType::TypeParam("T".to_string()))));
+    }
+
+    #[test]
+    fn test_std_containers_to_ir() {
+        let user = analyze_cpp_type(HEADER).unwrap();
+        let ty = |i: usize| to_ir(&user.fields[i].field_type);
+        assert_eq!(
+            ty(2),
+            IrType::Container(ContainerType::Option(Box::new(IrType::Reference("Address".to_string()))))
+        );
+        // `std::monostate` makes the variant optional
+        assert_eq!(
+            ty(3),
+            IrType::Container(ContainerType::Option(Box::new(IrType::Union(vec![
+                IrType::Primitive(PrimitiveType::I32),
+                IrType::Primitive(PrimitiveType::String),
+            ]))))
+        );
+        assert_eq!(map_to_target(&user.fields[4].field_type, "rust"), "std::collections::BTreeMap<String, i32>");
+        assert_eq!(map_to_target(&user.fields[5].field_type, "rust"), "Option<Box<Address>>");
+        assert_eq!(map_to_target(&user.fields[6].field_type, "rust"), "[f64; 3]");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_cpp_type("struct Pixel { std::uint8_t r; int16_t dx; char tag; uint16_t w; };").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::I8, PrimitiveType::U16].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let point = analyze_cpp_type("struct Point { int32_t x; int32_t y; std::string label; std::function<void()> cb; };")
+            .unwrap();
+        assert_eq!(point.kind, CppTypeKind::Struct);
+        // `std::function` has no data equivalent
+        assert_eq!(compatibility_score(&point, "rust"), 0.875);
+    }
+}
@@ -0,0 +1,1097 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Crystal analyzer for protocol-squisher integration
+// Analyzes `struct`, `class`, and `record` declarations, plus `lib` structs and unions
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::PlatformWidth;
+
+/// Crystal type declaration
+#[derive(Debug, Clone)]
+pub struct CrystalType {
+    /// Declared name without its namespace (`Api::User` → `User`)
+    pub name: String,
+    pub kind: CrystalTypeKind,
+    pub abstract_: bool,
+    /// Superclass (`class Admin < User`)
+    pub base: Option<String>,
+    pub type_params: Vec<String>,
+    pub fields: Vec<CrystalField>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrystalTypeKind {
+    /// Value type (also `record`)
+    Struct,
+    /// Reference type
+    Class,
+    /// `union` inside a `lib` block
+    Union,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrystalField {
+    pub name: String,
+    pub field_type: CrystalFieldType,
+    pub accessor: CrystalAccessor,
+    pub has_default: bool,
+}
+
+/// How a field was declared
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrystalAccessor {
+    Property,
+    Getter,
+    Setter,
+    /// A bare instance variable (`@id : Int64`, `def initialize(@id : Int64)`)
+    /// or a `lib` struct field
+    Ivar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrystalFieldType {
+    /// `Int8` … `Int128`
+    Int(u16),
+    /// `UInt8` … `UInt128`
+    UInt(u16),
+    /// `LibC::Long`
+    Long,
+    /// `LibC::ULong`
+    ULong,
+    /// `LibC::SizeT`
+    SizeT,
+    /// `LibC::SSizeT`
+    SSizeT,
+    Float32,
+    Float64,
+    Bool,
+    Char,
+    String,
+    Symbol,
+    Nil,
+    /// `Array(T)`, `Deque(T)`, `Slice(T)`
+    Array(Box<CrystalFieldType>),
+    /// `StaticArray(T, N)`
+    StaticArray(Box<CrystalFieldType>, Option<usize>),
+    Set(Box<CrystalFieldType>),
+    Hash(Box<CrystalFieldType>, Box<CrystalFieldType>),
+    /// `Tuple(...)`, `NamedTuple(...)`, `{A, B}`
+    Tuple,
+    /// `Proc(...)`, `A -> B`
+    Proc,
+    /// `Pointer(T)`, `T*`: may be null
+    Pointer(Box<CrystalFieldType>),
+    /// `A | B`; `T?` is `T | Nil`
+    Union(Vec<CrystalFieldType>),
+    /// `JSON::Any`, `YAML::Any`, `typeof(...)`
+    Any,
+    Named(String, Vec<CrystalFieldType>),
+    TypeParam(String),
+}
+
+const CRYSTAL_SYNTAX: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Keywords that open a block closed by `end`
+const BLOCK_KEYWORDS: &[&str] = &[
+    "class", "struct", "module", "enum", "def", "fun", "macro", "lib", "union", "annotation", "begin", "case", "select", "do",
+];
+
+/// Keywords that open a block only at the start of a statement or
+/// expression; elsewhere they are suffix modifiers (`return if done`)
+const CONDITIONAL_KEYWORDS: &[&str] = &["if", "unless", "while", "until"];
+
+/// Analyze the first Crystal struct or class and extract metadata
+pub fn analyze_crystal_type(source: &str) -> Result<CrystalType, String> {
+    analyze_crystal_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct, class, and record in a Crystal file, including
+/// those nested in modules and other types
+pub fn analyze_crystal_types(source: &str) -> Result<Vec<CrystalType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &CRYSTAL_SYNTAX));
+    let mut types = Vec::new();
+    parse_block(&mut cursor, None, false, &mut types)?;
+    Ok(types)
+}
+
+fn starts_statement(cursor: &Cursor) -> bool {
+    match (cursor.previous(), cursor.peek()) {
+        (Some(previous), Some(next)) => {
+            previous.line != next.line || (previous.kind == TokenKind::Punct && previous.text == ";")
+        }
+        _ => true,
+    }
+}
+
+/// Whether the next token is `word` used as a keyword rather than a method
+/// name (`x.class`), symbol (`:end`), named argument (`end: 3`), or macro
+/// control (`{% end %}`)
+fn is_keyword(cursor: &Cursor, word: &str) -> bool {
+    let Some(token) = cursor.peek() else { return false };
+    if token.kind != TokenKind::Ident || token.text != word {
+        return false;
+    }
+    let after_name = cursor.previous().is_some_and(|previous| match previous.kind {
+        TokenKind::Punct => matches!(previous.text.as_str(), "." | ":" | "%"),
+        TokenKind::Ident => previous.text == "def",
+        _ => false,
+    });
+    let named_argument = cursor.is_punct_at(1, ':') && !cursor.is_punct_at(2, ':');
+    !after_name && !named_argument
+}
+
+/// Whether the next token opens a block that runs to a matching `end`
+fn opens_block(cursor: &Cursor) -> bool {
+    let Some(token) = cursor.peek() else { return false };
+    if token.kind != TokenKind::Ident || !is_keyword(cursor, &token.text) {
+        return false;
+    }
+    let word = token.text.as_str();
+    if BLOCK_KEYWORDS.contains(&word) {
+        // `abstract def` has no body
+        return !(word == "def" && cursor.previous().is_some_and(|previous| previous.text == "abstract"));
+    }
+    CONDITIONAL_KEYWORDS.contains(&word)
+        && (starts_statement(cursor) || cursor.previous().is_some_and(|previous| previous.text == "="))
+}
+
+/// Skip past the `end` matching an already-consumed block opener
+fn skip_to_end(cursor: &mut Cursor) {
+    let mut depth = 1;
+    while cursor.peek().is_some() {
+        if is_keyword(cursor, "end") {
+            depth -= 1;
+        } else if opens_block(cursor) {
+            depth += 1;
+        }
+        cursor.advance();
+        if depth == 0 {
+            return;
+        }
+    }
+}
+
+/// Skip the rest of the current statement, and any blocks it opens
+/// (`items.each do |item|`)
+fn skip_line(cursor: &mut Cursor) {
+    loop {
+        let opens = opens_block(cursor);
+        cursor.advance();
+        if opens {
+            skip_to_end(cursor);
+        }
+        if cursor.peek().is_none() || starts_statement(cursor) {
+            return;
+        }
+    }
+}
+
+/// `Api::User` → `User`
+fn parse_path(cursor: &mut Cursor) -> Result<String, String> {
+    cursor.eat_punct(':');
+    cursor.eat_punct(':');
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct(':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+        name = cursor.expect_ident()?;
+    }
+    Ok(name)
+}
+
+/// Statements up to the matching `end` (or end of input at the top level);
+/// `owner` indexes the type whose body this is
+fn parse_block(cursor: &mut Cursor, owner: Option<usize>, in_lib: bool, types: &mut Vec<CrystalType>) -> Result<(), String> {
+    while cursor.peek().is_some() {
+        if !starts_statement(cursor) {
+            if is_keyword(cursor, "end") {
+                cursor.advance();
+                return Ok(());
+            }
+            if opens_block(cursor) {
+                cursor.advance();
+                skip_to_end(cursor);
+            } else {
+                cursor.advance();
+            }
+            continue;
+        }
+
+        let mut abstract_ = false;
+        while cursor.eat_ident("private") || cursor.eat_ident("protected") {}
+        if cursor.is_ident("abstract") && !cursor.is_punct_at(1, ':') {
+            cursor.advance();
+            abstract_ = true;
+        }
+
+        let type_params = owner.map(|index| types[index].type_params.clone()).unwrap_or_default();
+        let Some(token) = cursor.peek() else { break };
+        let word = if token.kind == TokenKind::Ident && is_keyword(cursor, &token.text) {
+            token.text.clone()
+        } else {
+            String::new()
+        };
+        let next_is_ident = cursor.peek_at(1).is_some_and(|next| next.kind == TokenKind::Ident);
+        match word.as_str() {
+            "struct" | "class" | "union" if next_is_ident && (word != "union" || in_lib) => {
+                let kind = match word.as_str() {
+                    "struct" => CrystalTypeKind::Struct,
+                    "class" => CrystalTypeKind::Class,
+                    _ => CrystalTypeKind::Union,
+                };
+                cursor.advance();
+                parse_type(cursor, kind, abstract_, in_lib, types)?;
+            }
+            "record" if next_is_ident => parse_record(cursor, types)?,
+            "module" | "lib" if next_is_ident => {
+                cursor.advance();
+                parse_path(cursor)?;
+                parse_block(cursor, None, word == "lib", types)?;
+            }
+            "property" | "getter" | "setter" if owner.is_some() => {
+                let accessor = match cursor.advance().map(|token| token.text).as_deref() {
+                    Some("property") => CrystalAccessor::Property,
+                    Some("getter") => CrystalAccessor::Getter,
+                    _ => CrystalAccessor::Setter,
+                };
+                // `getter!` declares a nilable variable whose getter raises on nil
+                let nilable = cursor.eat_punct('!');
+                cursor.eat_punct('?');
+                let parenthesized = cursor.eat_punct('(');
+                let fields = parse_declarations(cursor, &type_params, accessor, nilable)?;
+                if parenthesized {
+                    cursor.expect_punct(')')?;
+                }
+                // Lazy initializer
+                if cursor.is_punct('{') {
+                    cursor.skip_balanced('{', '}');
+                } else if is_keyword(cursor, "do") {
+                    cursor.advance();
+                    skip_to_end(cursor);
+                }
+                if let Some(index) = owner {
+                    add_fields(&mut types[index], fields);
+                }
+            }
+            "def" => {
+                cursor.advance();
+                if abstract_ {
+                    skip_line(cursor);
+                    continue;
+                }
+                if let Some(index) = owner.filter(|_| cursor.is_ident("initialize") && cursor.is_punct_at(1, '(')) {
+                    cursor.advance();
+                    let fields = parse_initialize_params(cursor, &type_params)?;
+                    add_fields(&mut types[index], fields);
+                }
+                skip_to_end(cursor);
+            }
+            // Functions in a `lib` are declarations without a body
+            "fun" if in_lib => {
+                cursor.advance();
+                while cursor.peek().is_some() && !starts_statement(cursor) {
+                    cursor.advance();
+                }
+            }
+            "end" => {
+                cursor.advance();
+                return Ok(());
+            }
+            _ if owner.is_some() && cursor.is_punct('@') => {
+                let declaration = cursor.peek_at(1).is_some_and(|name| name.kind == TokenKind::Ident)
+                    && cursor.is_punct_at(2, ':')
+                    && !cursor.is_punct_at(3, ':');
+                if cursor.is_punct_at(1, '[') {
+                    // Annotation: `@[JSON::Field(key: "id")]`
+                    cursor.advance();
+                    cursor.skip_balanced('[', ']');
+                } else if declaration {
+                    cursor.advance();
+                    let fields = parse_declarations(cursor, &type_params, CrystalAccessor::Ivar, false)?;
+                    if let Some(index) = owner {
+                        add_fields(&mut types[index], fields);
+                    }
+                } else {
+                    skip_line(cursor);
+                }
+            }
+            // `lib` struct fields: `x, y : Int32`
+            _ if in_lib && owner.is_some() && token.kind == TokenKind::Ident && !opens_block(cursor) => {
+                let fields = parse_declarations(cursor, &type_params, CrystalAccessor::Ivar, false)?;
+                if let Some(index) = owner {
+                    add_fields(&mut types[index], fields);
+                }
+            }
+            _ if opens_block(cursor) => {
+                cursor.advance();
+                skip_to_end(cursor);
+            }
+            _ => skip_line(cursor),
+        }
+    }
+    Ok(())
+}
+
+/// `Name(T, U) < Base(T)` and the body; the keyword is already consumed
+fn parse_type(cursor: &mut Cursor, kind: CrystalTypeKind, abstract_: bool, in_lib: bool, types: &mut Vec<CrystalType>) -> Result<(), String> {
+    let line = cursor.line();
+    let name = parse_path(cursor)?;
+    let type_params = parse_type_params(cursor)?;
+    let mut base = None;
+    if cursor.eat_punct('<') {
+        base = Some(parse_path(cursor)?);
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+    }
+    types.push(CrystalType {
+        name,
+        kind,
+        abstract_,
+        base,
+        type_params,
+        fields: Vec::new(),
+        location: format!("line {}", line),
+    });
+    parse_block(cursor, Some(types.len() - 1), in_lib, types)
+}
+
+/// `(T, U)` after a type name
+fn parse_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut params = Vec::new();
+    if cursor.eat_punct('(') {
+        while !cursor.eat_punct(')') {
+            // Variadic parameters: `Tuple(*T)`
+            cursor.eat_punct('*');
+            params.push(cursor.expect_ident()?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+    }
+    Ok(params)
+}
+
+/// `record Point(T), x : T, y : T = 0 do ... end`: a struct with a getter per field
+fn parse_record(cursor: &mut Cursor, types: &mut Vec<CrystalType>) -> Result<(), String> {
+    let line = cursor.line();
+    cursor.advance();
+    let name = parse_path(cursor)?;
+    let type_params = parse_type_params(cursor)?;
+    let mut fields = Vec::new();
+    if cursor.eat_punct(',') {
+        fields = parse_declarations(cursor, &type_params, CrystalAccessor::Getter, false)?;
+    }
+    if is_keyword(cursor, "do") {
+        cursor.advance();
+        skip_to_end(cursor);
+    }
+    types.push(CrystalType {
+        name,
+        kind: CrystalTypeKind::Struct,
+        abstract_: false,
+        base: None,
+        type_params,
+        fields,
+        location: format!("line {}", line),
+    });
+    Ok(())
+}
+
+/// Instance variables first declared elsewhere keep their first declaration
+fn add_fields(crystal_type: &mut CrystalType, fields: Vec<CrystalField>) {
+    for field in fields {
+        if !crystal_type.fields.iter().any(|existing| existing.name == field.name) {
+            crystal_type.fields.push(field);
+        }
+    }
+}
+
+/// `a : T = default, b : U` (optionally `@`-prefixed); untyped declarations
+/// take the type of their default value
+fn parse_declarations(
+    cursor: &mut Cursor,
+    type_params: &[String],
+    accessor: CrystalAccessor,
+    nilable: bool,
+) -> Result<Vec<CrystalField>, String> {
+    let mut fields = Vec::new();
+    loop {
+        cursor.eat_punct('@');
+        let mut names = vec![cursor.expect_ident()?];
+        // `lib` structs share one type between several names
+        while accessor == CrystalAccessor::Ivar && cursor.is_punct(',') && cursor.is_punct_at(2, ':') {
+            cursor.advance();
+            names.push(cursor.expect_ident()?);
+        }
+        let mut field_type = if cursor.eat_punct(':') { Some(parse_field_type(cursor, type_params)?) } else { None };
+        let has_default = cursor.eat_punct('=');
+        if has_default {
+            let inferred = skip_expression(cursor);
+            field_type.get_or_insert(inferred);
+        }
+        let mut field_type = field_type.unwrap_or(CrystalFieldType::Any);
+        if nilable {
+            field_type = CrystalFieldType::Union(vec![field_type, CrystalFieldType::Nil]);
+        }
+        for name in names {
+            fields.push(CrystalField { name, field_type: field_type.clone(), accessor, has_default });
+        }
+        if !cursor.eat_punct(',') {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Skip a default value up to the next `,` or statement, inferring its type
+/// from a leading literal
+fn skip_expression(cursor: &mut Cursor) -> CrystalFieldType {
+    let inferred = match cursor.peek() {
+        Some(token) if token.kind == TokenKind::Str => CrystalFieldType::String,
+        Some(token) if token.kind == TokenKind::Number => number_type(&token.text),
+        Some(token) if token.text == "true" || token.text == "false" => CrystalFieldType::Bool,
+        Some(token) if token.text == "nil" => CrystalFieldType::Nil,
+        _ => CrystalFieldType::Any,
+    };
+    let mut first = true;
+    while let Some(token) = cursor.peek() {
+        if !first && (starts_statement(cursor) || (token.kind == TokenKind::Punct && matches!(token.text.as_str(), "," | ")"))) {
+            break;
+        }
+        first = false;
+        match token.text.as_str() {
+            "(" if token.kind == TokenKind::Punct => cursor.skip_balanced('(', ')'),
+            "[" if token.kind == TokenKind::Punct => cursor.skip_balanced('[', ']'),
+            "{" if token.kind == TokenKind::Punct => cursor.skip_balanced('{', '}'),
+            _ if opens_block(cursor) => {
+                cursor.advance();
+                skip_to_end(cursor);
+            }
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+    inferred
+}
+
+/// Type of a number literal: `1` is `Int32`, `1.5` is `Float64`, and suffixes
+/// (`1_i64`, `2u8`, `0.5f32`) name the type
+fn number_type(literal: &str) -> CrystalFieldType {
+    let hex = literal.starts_with("0x");
+    for (marker, signed) in [('i', true), ('u', false)] {
+        if let Some(bits) = literal.rsplit_once(marker).and_then(|(_, bits)| bits.parse().ok()) {
+            return if signed { CrystalFieldType::Int(bits) } else { CrystalFieldType::UInt(bits) };
+        }
+    }
+    if !hex && literal.ends_with("f32") {
+        CrystalFieldType::Float32
+    } else if literal.contains('.') || (!hex && (literal.ends_with("f64") || literal.contains('e'))) {
+        CrystalFieldType::Float64
+    } else {
+        CrystalFieldType::Int(32)
+    }
+}
+
+/// `(@id : Int64, name : String, @email : String? = nil)`: parameters with
+/// an `@` prefix are instance variables
+fn parse_initialize_params(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<CrystalField>, String> {
+    let mut fields = Vec::new();
+    cursor.expect_punct('(')?;
+    while !cursor.eat_punct(')') {
+        if cursor.peek().is_none() {
+            return Err(cursor.unexpected("`)`"));
+        }
+        let ivar = cursor.is_punct('@')
+            && cursor.peek_at(1).is_some_and(|name| name.kind == TokenKind::Ident)
+            && cursor.is_punct_at(2, ':')
+            && !cursor.is_punct_at(3, ':');
+        if ivar {
+            fields.push(parse_ivar_param(cursor, type_params)?);
+        }
+        // Rest of the parameter
+        while cursor.peek().is_some() && !cursor.is_punct(',') && !cursor.is_punct(')') {
+            match cursor.peek().map(|token| (token.kind, token.text.as_str())) {
+                Some((TokenKind::Punct, "(")) => cursor.skip_balanced('(', ')'),
+                Some((TokenKind::Punct, "[")) => cursor.skip_balanced('[', ']'),
+                Some((TokenKind::Punct, "{")) => cursor.skip_balanced('{', '}'),
+                _ => {
+                    cursor.advance();
+                }
+            }
+        }
+        cursor.eat_punct(',');
+    }
+    Ok(fields)
+}
+
+/// One `@name : Type` parameter, up to its default value
+fn parse_ivar_param(cursor: &mut Cursor, type_params: &[String]) -> Result<CrystalField, String> {
+    cursor.expect_punct('@')?;
+    let name = cursor.expect_ident()?;
+    cursor.expect_punct(':')?;
+    let field_type = parse_field_type(cursor, type_params)?;
+    let has_default = cursor.is_punct('=');
+    Ok(CrystalField { name, field_type, accessor: CrystalAccessor::Ivar, has_default })
+}
+
+/// `A | B?`, `A -> B`
+fn parse_field_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CrystalFieldType, String> {
+    let mut members = vec![parse_postfix_type(cursor, type_params)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_postfix_type(cursor, type_params)?);
+    }
+    // Proc type: `A, B -> C` is only reachable through its last argument here
+    if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+        cursor.advance();
+        cursor.advance();
+        if !cursor.is_punct(',') && !cursor.is_punct(')') && !cursor.is_punct('=') && !starts_statement(cursor) {
+            parse_field_type(cursor, type_params)?;
+        }
+        return Ok(CrystalFieldType::Proc);
+    }
+    Ok(if members.len() == 1 { members.remove(0) } else { CrystalFieldType::Union(members) })
+}
+
+/// A type followed by `?` (nilable) or `*` (pointer) suffixes
+fn parse_postfix_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CrystalFieldType, String> {
+    let mut field_type = parse_primary_type(cursor, type_params)?;
+    loop {
+        if cursor.eat_punct('?') {
+            field_type = CrystalFieldType::Union(vec![field_type, CrystalFieldType::Nil]);
+        } else if cursor.eat_punct('*') {
+            field_type = CrystalFieldType::Pointer(Box::new(field_type));
+        } else if cursor.is_punct('[') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Number) && cursor.is_punct_at(2, ']') {
+            // `UInt8[16]` is `StaticArray(UInt8, 16)`
+            cursor.advance();
+            let length = cursor.advance().and_then(|token| token.text.parse().ok());
+            cursor.advance();
+            field_type = CrystalFieldType::StaticArray(Box::new(field_type), length);
+        } else {
+            return Ok(field_type);
+        }
+    }
+}
+
+fn parse_primary_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CrystalFieldType, String> {
+    if cursor.eat_punct('(') {
+        let inner = parse_field_type(cursor, type_params)?;
+        cursor.expect_punct(')')?;
+        return Ok(inner);
+    }
+    if cursor.is_punct('{') {
+        cursor.skip_balanced('{', '}');
+        return Ok(CrystalFieldType::Tuple);
+    }
+    if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+        cursor.advance();
+        cursor.advance();
+        if !cursor.is_punct(',') && !cursor.is_punct(')') && !cursor.is_punct('=') && !starts_statement(cursor) {
+            parse_field_type(cursor, type_params)?;
+        }
+        return Ok(CrystalFieldType::Proc);
+    }
+    if cursor.eat_ident("typeof") {
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        return Ok(CrystalFieldType::Any);
+    }
+
+    cursor.eat_punct(':');
+    cursor.eat_punct(':');
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct(':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+        name.push_str("::");
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let mut args = Vec::new();
+    let mut length = None;
+    if cursor.eat_punct('(') {
+        while !cursor.eat_punct(')') {
+            if let Some(number) = cursor.peek().filter(|t| t.kind == TokenKind::Number).map(|t| t.text.parse().ok()) {
+                cursor.advance();
+                length = number;
+            } else {
+                // Named tuple keys: `NamedTuple(id: Int64)`
+                if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(1, ':') && !cursor.is_punct_at(2, ':') {
+                    cursor.advance();
+                    cursor.advance();
+                }
+                args.push(parse_field_type(cursor, type_params)?);
+            }
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+    }
+
+    if let Some(libc) = name.strip_prefix("LibC::") {
+        let mapped = match libc {
+            "Char" | "SChar" => Some(CrystalFieldType::Int(8)),
+            "UChar" => Some(CrystalFieldType::UInt(8)),
+            "Short" => Some(CrystalFieldType::Int(16)),
+            "UShort" => Some(CrystalFieldType::UInt(16)),
+            "Int" => Some(CrystalFieldType::Int(32)),
+            "UInt" => Some(CrystalFieldType::UInt(32)),
+            "Long" => Some(CrystalFieldType::Long),
+            "ULong" => Some(CrystalFieldType::ULong),
+            "LongLong" => Some(CrystalFieldType::Int(64)),
+            "ULongLong" => Some(CrystalFieldType::UInt(64)),
+            "SizeT" => Some(CrystalFieldType::SizeT),
+            "SSizeT" | "PtrdiffT" => Some(CrystalFieldType::SSizeT),
+            "Float" => Some(CrystalFieldType::Float32),
+            "Double" => Some(CrystalFieldType::Float64),
+            _ => None,
+        };
+        if let Some(mapped) = mapped {
+            return Ok(mapped);
+        }
+    }
+
+    let simple = name.rsplit("::").next().unwrap_or(&name).to_string();
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(CrystalFieldType::Any));
+    Ok(match name.as_str() {
+        "Int8" | "Int16" | "Int32" | "Int64" | "Int128" => CrystalFieldType::Int(simple[3..].parse().unwrap_or(64)),
+        "UInt8" | "UInt16" | "UInt32" | "UInt64" | "UInt128" => CrystalFieldType::UInt(simple[4..].parse().unwrap_or(64)),
+        // Abstract numeric types: any width
+        "Int" => CrystalFieldType::Int(64),
+        "Float32" => CrystalFieldType::Float32,
+        "Float64" | "Float" => CrystalFieldType::Float64,
+        "Bool" => CrystalFieldType::Bool,
+        "Char" => CrystalFieldType::Char,
+        "String" => CrystalFieldType::String,
+        "Symbol" => CrystalFieldType::Symbol,
+        "Nil" | "Void" | "NoReturn" => CrystalFieldType::Nil,
+        "JSON::Any" | "YAML::Any" | "Object" | "Reference" | "Value" | "_" | "self" => CrystalFieldType::Any,
+        "Array" | "Deque" | "Slice" | "Indexable" | "Enumerable" | "Iterable" => CrystalFieldType::Array(next()),
+        "StaticArray" => CrystalFieldType::StaticArray(next(), length),
+        "Set" => CrystalFieldType::Set(next()),
+        "Hash" => {
+            let key = next();
+            CrystalFieldType::Hash(key, next())
+        }
+        "Tuple" | "NamedTuple" => CrystalFieldType::Tuple,
+        "Proc" => CrystalFieldType::Proc,
+        "Pointer" => CrystalFieldType::Pointer(next()),
+        "Union" => CrystalFieldType::Union(args.collect()),
+        _ if type_params.contains(&name) => CrystalFieldType::TypeParam(name),
+        _ => CrystalFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(crystal_type: &CrystalType, target: &str) -> f32 {
+    average_fidelity(ir_fields(crystal_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Crystal field type into the language-neutral IR
+pub fn to_ir(field_type: &CrystalFieldType) -> IrType {
+    match field_type {
+        CrystalFieldType::Int(8) => IrType::Primitive(PrimitiveType::I8),
+        CrystalFieldType::Int(16) => IrType::Primitive(PrimitiveType::I16),
+        CrystalFieldType::Int(bits) if *bits <= 32 => IrType::Primitive(PrimitiveType::I32),
+        CrystalFieldType::Int(64) | CrystalFieldType::Long | CrystalFieldType::SSizeT => IrType::Primitive(PrimitiveType::I64),
+        CrystalFieldType::UInt(8) => IrType::Primitive(PrimitiveType::U8),
+        CrystalFieldType::UInt(16) => IrType::Primitive(PrimitiveType::U16),
+        CrystalFieldType::UInt(bits) if *bits <= 32 => IrType::Primitive(PrimitiveType::U32),
+        CrystalFieldType::UInt(64) | CrystalFieldType::ULong | CrystalFieldType::SizeT => IrType::Primitive(PrimitiveType::U64),
+        CrystalFieldType::Int(128) => IrType::Primitive(PrimitiveType::I128),
+        CrystalFieldType::UInt(128) => IrType::Primitive(PrimitiveType::U128),
+        CrystalFieldType::Int(_) | CrystalFieldType::UInt(_) => IrType::Any,
+        CrystalFieldType::Float32 => IrType::Primitive(PrimitiveType::F32),
+        CrystalFieldType::Float64 => IrType::Primitive(PrimitiveType::F64),
+        CrystalFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        CrystalFieldType::Char | CrystalFieldType::String | CrystalFieldType::Symbol => IrType::Primitive(PrimitiveType::String),
+        CrystalFieldType::Nil => IrType::Primitive(PrimitiveType::Unit),
+        CrystalFieldType::Array(element) | CrystalFieldType::StaticArray(element, _) | CrystalFieldType::Set(element) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        CrystalFieldType::Pointer(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        CrystalFieldType::Union(members) => union_to_ir(members),
+        // Tuples without members and procs have no IR form
+        CrystalFieldType::Tuple | CrystalFieldType::Proc | CrystalFieldType::Any => IrType::Any,
+        CrystalFieldType::Hash(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        CrystalFieldType::Named(name, _) => IrType::Reference(name.rsplit("::").next().unwrap_or(name).to_string()),
+        CrystalFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+fn is_error(field_type: &CrystalFieldType) -> bool {
+    match field_type {
+        CrystalFieldType::Named(name, _) => {
+            let simple = name.rsplit("::").next().unwrap_or(name);
+            simple.ends_with("Error") || simple.ends_with("Exception")
+        }
+        _ => false,
+    }
+}
+
+/// `T | Nil` is `Option<T>` and `T | SomeError` is `Result<T, SomeError>`;
+/// other unions stay unions
+fn union_to_ir(members: &[CrystalFieldType]) -> IrType {
+    let mut flat = Vec::new();
+    let mut pending: Vec<&CrystalFieldType> = members.iter().rev().collect();
+    while let Some(member) = pending.pop() {
+        match member {
+            CrystalFieldType::Union(inner) => pending.extend(inner.iter().rev()),
+            _ => flat.push(member),
+        }
+    }
+    let present: Vec<&CrystalFieldType> = flat.iter().copied().filter(|m| **m != CrystalFieldType::Nil).collect();
+    let nullable = present.len() < flat.len();
+
+    let errors: Vec<&CrystalFieldType> = present.iter().copied().filter(|m| is_error(m)).collect();
+    let inner = if present.len() == 2 && errors.len() == 1 {
+        let ok = present.iter().find(|m| !is_error(m)).map_or(IrType::Any, |m| to_ir(m));
+        IrType::Container(ContainerType::Result(Box::new(ok), Box::new(to_ir(errors[0]))))
+    } else {
+        let mut distinct: Vec<IrType> = Vec::new();
+        for member in present.iter().map(|m| to_ir(m)) {
+            if !distinct.contains(&member) {
+                distinct.push(member);
+            }
+        }
+        match distinct.len() {
+            0 => return IrType::Primitive(PrimitiveType::Unit),
+            1 => distinct.remove(0),
+            _ => IrType::Union(distinct),
+        }
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower struct and class fields into the IR; `lib` union members each
+/// exist only some of the time, so they're optional
+pub fn ir_fields(crystal_type: &CrystalType) -> Vec<IrField> {
+    crystal_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = match crystal_type.kind {
+                CrystalTypeKind::Union => IrType::Container(ContainerType::Option(Box::new(ty))),
+                _ => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`LibC::Long`, `LibC::SizeT`)
+pub fn platform_width(field_type: &CrystalFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        CrystalFieldType::Long | CrystalFieldType::ULong => Some(PlatformWidth::CLong),
+        CrystalFieldType::SizeT | CrystalFieldType::SSizeT => Some(PlatformWidth::Pointer),
+        CrystalFieldType::Array(inner)
+        | CrystalFieldType::StaticArray(inner, _)
+        | CrystalFieldType::Set(inner)
+        | CrystalFieldType::Pointer(inner) => platform_width(inner),
+        CrystalFieldType::Union(members) => members.iter().find_map(platform_width),
+        _ => None,
+    }
+}
+
+/// Map Crystal types to target language types
+pub fn map_to_target(field_type: &CrystalFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+require "json"
+
+module Api
+  # A registered user
+  class User
+    include JSON::Serializable
+
+    @[JSON::Field(key: "displayName")]
+    property display_name : String
+    getter id : Int64
+    property email : String | Nil
+    property nickname : String? = nil
+    getter! token : String
+    property tags : Array(String) = [] of String
+    property meta : Hash(String, JSON::Any)
+    property visits = 0
+    @secret : String = ""
+
+    def initialize(@id : Int64, @display_name : String, @created_at : Time? = nil)
+      if @display_name.empty?
+        raise ArgumentError.new("empty name")
+      end
+      @tags.each do |tag|
+        puts tag unless tag.empty?
+      end
+    end
+
+    def admin? : Bool
+      self.class == Admin
+    end
+  end
+
+  abstract struct Shape(T) < Base
+    getter outcome : T | ParseError
+    abstract def area : Float64
+  end
+end
+
+record Point, x : Int32, y : Float64 = 0.0
+
+lib LibZ
+  struct Stream
+    next_in, next_out : UInt8*
+    avail_in : LibC::UInt
+    total_in : LibC::ULong
+  end
+  fun inflate(strm : Stream*, flush : LibC::Int) : LibC::Int
+end
+"#;
+
+    #[test]
+    fn test_analyze_structs_classes_and_records() {
+        let types = analyze_crystal_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Shape", "Point", "Stream"]);
+
+        let user = &types[0];
+        assert_eq!(user.kind, CrystalTypeKind::Class);
+        let fields: Vec<_> = user.fields.iter().map(|f| (f.name.as_str(), f.accessor)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("display_name", CrystalAccessor::Property),
+                ("id", CrystalAccessor::Getter),
+                ("email", CrystalAccessor::Property),
+                ("nickname", CrystalAccessor::Property),
+                ("token", CrystalAccessor::Getter),
+                ("tags", CrystalAccessor::Property),
+                ("meta", CrystalAccessor::Property),
+                ("visits", CrystalAccessor::Property),
+                ("secret", CrystalAccessor::Ivar),
+                ("created_at", CrystalAccessor::Ivar),
+            ]
+        );
+        assert_eq!(user.fields[7].field_type, CrystalFieldType::Int(32));
+
+        let shape = &types[1];
+        assert!(shape.abstract_);
+        assert_eq!(shape.base.as_deref(), Some("Base"));
+        assert_eq!(shape.type_params, vec!["T"]);
+        assert_eq!(shape.fields.len(), 1);
+
+        assert_eq!(types[2].kind, CrystalTypeKind::Struct);
+        assert_eq!(types[2].fields[1].field_type, CrystalFieldType::Float64);
+
+        let stream: Vec<_> = types[3].fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(stream, vec!["next_in", "next_out", "avail_in", "total_in"]);
+        assert_eq!(platform_width(&types[3].fields[3].field_type), Some(PlatformWidth::CLong));
+    }
+
+    #[test]
+    fn test_unions_map_to_option_and_result() {
+        let types = analyze_crystal_types(SOURCE).unwrap();
+        let user = ir_fields(&types[0]);
+        let optional_string = IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(PrimitiveType::String))));
+        assert_eq!(user[2].ty, optional_string);
+        assert_eq!(user[3].ty, optional_string);
+        assert_eq!(user[4].ty, optional_string);
+
+        let shape = ir_fields(&types[1]);
+        assert_eq!(
+            shape[0].ty,
+            IrType::Container(ContainerType::Result(
+                Box::new(IrType::TypeParam("T".to_string())),
+                Box::new(IrType::Reference("ParseError".to_string()))
+            ))
+        );
+
+        let mixed = analyze_crystal_type("struct Cell\n  getter value : Int32 | String | Nil\nend").unwrap();
+        assert_eq!(map_to_target(&mixed.fields[0].field_type, "rust"), "Option<IntOrString>");
+    }
+
+    #[test]
+    fn test_nilable_spellings() {
+        let cell = analyze_crystal_type(
+            "struct Cell\n  getter a : Int32?\n  getter b : Nil | String\n  getter c : Union(Bool, Nil)\n  getter d : Pointer(UInt8)\n  getter e : Float64*\nend",
+        )
+        .unwrap();
+        assert_eq!(cell.fields[0].field_type, CrystalFieldType::Union(vec![CrystalFieldType::Int(32), CrystalFieldType::Nil]));
+        assert_eq!(cell.fields[1].field_type, CrystalFieldType::Union(vec![CrystalFieldType::Nil, CrystalFieldType::String]));
+        assert_eq!(cell.fields[3].field_type, CrystalFieldType::Pointer(Box::new(CrystalFieldType::UInt(8))));
+        let rust: Vec<_> = cell.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["Option<i32>", "Option<String>", "Option<bool>", "Option<u8>", "Option<f64>"]);
+    }
+
+    #[test]
+    fn test_collections_and_hashes() {
+        let bag = analyze_crystal_type(
+            "struct Bag\n  getter a : Deque(Int32)\n  getter b : Set(String)\n  getter c : Hash(Symbol, Float32)\n  \
+             getter d : StaticArray(UInt8, 16)\n  getter e : UInt8[4]\n  getter f : Slice(UInt8)\nend",
+        )
+        .unwrap();
+        assert_eq!(bag.fields[3].field_type, CrystalFieldType::StaticArray(Box::new(CrystalFieldType::UInt(8)), Some(16)));
+        assert_eq!(bag.fields[4].field_type, CrystalFieldType::StaticArray(Box::new(CrystalFieldType::UInt(8)), Some(4)));
+        let rust: Vec<_> = bag.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["Vec<i32>", "Vec<String>", "HashMap<String, f32>", "Vec<u8>", "Vec<u8>", "Vec<u8>"]);
+    }
+
+    #[test]
+    fn test_mixed_and_error_unions() {
+        let reply = analyze_crystal_type("class Reply\n  getter r : Int32 | IO::Error\n  getter u : Int32 | String | Float64\nend").unwrap();
+        let rust: Vec<_> = reply.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["Result<i32, Error>", "IntOrStringOrNumber"]);
+        assert_eq!(
+            ir_fields(&reply)[1].ty,
+            IrType::Union(vec![
+                IrType::Primitive(PrimitiveType::I32),
+                IrType::Primitive(PrimitiveType::String),
+                IrType::Primitive(PrimitiveType::F64),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tuples_and_procs_are_opaque() {
+        let hooks = analyze_crystal_type(
+            "struct Hooks\n  getter a : {Int32, String}\n  getter b : NamedTuple(id: Int64)\n  getter c : Int32 -> String\n  \
+             getter d : Proc(Int32, Nil)\n  getter e : typeof(1)\nend",
+        )
+        .unwrap();
+        let types: Vec<_> = hooks.fields.iter().map(|f| f.field_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![CrystalFieldType::Tuple, CrystalFieldType::Tuple, CrystalFieldType::Proc, CrystalFieldType::Proc, CrystalFieldType::Any]
+        );
+        assert!(ir_fields(&hooks).iter().all(|f| f.ty == IrType::Any));
+        assert_eq!(compatibility_score(&hooks, "rust"), 0.5);
+    }
+
+    #[test]
+    fn test_defaults_infer_literal_types() {
+        let config = analyze_crystal_type(
+            "class Config\n  property a = 1.5\n  property b = \"x\"\n  property c = 10_i64\n  property d = true\n  \
+             property e = 3_u8\n  property f = :sym\nend",
+        )
+        .unwrap();
+        assert!(config.fields.iter().all(|f| f.has_default));
+        let types: Vec<_> = config.fields.iter().map(|f| f.field_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                CrystalFieldType::Float64,
+                CrystalFieldType::String,
+                CrystalFieldType::Int(64),
+                CrystalFieldType::Bool,
+                CrystalFieldType::UInt(8),
+                CrystalFieldType::Any,
+            ]
+        );
+
+        let init = analyze_crystal_type("class Init\n  def initialize(@a : Int32, @b : String = \"x\", c : Int32 = 0)\n  end\nend").unwrap();
+        let fields: Vec<_> = init.fields.iter().map(|f| (f.name.as_str(), f.accessor, f.has_default)).collect();
+        assert_eq!(fields, vec![("a", CrystalAccessor::Ivar, false), ("b", CrystalAccessor::Ivar, true)]);
+    }
+
+    #[test]
+    fn test_lib_unions_and_platform_widths() {
+        let types = analyze_crystal_types(
+            "lib LibFoo\n  union Value\n    i : LibC::Int\n    d : LibC::Double\n  end\n  struct Buf\n    len : LibC::SizeT\n    \
+             off : LibC::SSizeT\n    n : LibC::Long\n  end\nend",
+        )
+        .unwrap();
+        assert_eq!(types[0].kind, CrystalTypeKind::Union);
+        let optional = |p| IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(p))));
+        let value: Vec<_> = ir_fields(&types[0]).into_iter().map(|f| f.ty).collect();
+        assert_eq!(value, vec![optional(PrimitiveType::I32), optional(PrimitiveType::F64)]);
+
+        let widths: Vec<_> = types[1].fields.iter().map(|f| platform_width(&f.field_type)).collect();
+        assert_eq!(widths, vec![Some(PlatformWidth::Pointer), Some(PlatformWidth::Pointer), Some(PlatformWidth::CLong)]);
+        let rust: Vec<_> = types[1].fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["u64", "i64", "i64"]);
+    }
+
+    #[test]
+    fn test_namespaces_and_generics() {
+        let types = analyze_crystal_types(
+            "class Api::Models::User\n  getter owner : Api::Models::Account\n  getter data : JSON::Any\nend\n\
+             class Box(T, U)\n  getter a : T\n  getter b : Array(U)\nend",
+        )
+        .unwrap();
+        assert_eq!(types[0].name, "User");
+        let user: Vec<_> = ir_fields(&types[0]).into_iter().map(|f| f.ty).collect();
+        assert_eq!(user, vec![IrType::Reference("Account".to_string()), IrType::Any]);
+
+        assert_eq!(types[1].type_params, vec!["T", "U"]);
+        assert_eq!(types[1].fields[0].field_type, CrystalFieldType::TypeParam("T".to_string()));
+        assert_eq!(map_to_target(&types[1].fields[1].field_type, "rust"), "Vec<U>");
+    }
+
+    #[test]
+    fn test_method_and_macro_bodies_hold_no_fields() {
+        let counter = analyze_crystal_type(
+            "class Counter\n  getter a : Int32\n  def go\n    x = 1\n    while x < 3\n      x += 1\n    end\n    \
+             return if x > 2\n    @b = 3\n  end\n  macro m\n    getter z : Int32\n  end\nend",
+        )
+        .unwrap();
+        let names: Vec<_> = counter.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["a"]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(
+            analyze_crystal_type("struct S\n  getter a : Hash(String Int32)\nend").unwrap_err(),
+            "line 2: expected `,` or `)`, found `Int32`"
+        );
+        assert_eq!(
+            analyze_crystal_type("struct S\n  getter a : Array(Int32\nend").unwrap_err(),
+            "line 3: expected `,` or `)`, found `end`"
+        );
+        assert_eq!(analyze_crystal_type("module Empty\nend").unwrap_err(), "Type definition not found");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_crystal_type("struct Pixel\n  property r : UInt8\n  property dx : Int16\n  property c : LibC::Char\n  property id : UInt128\nend").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::I8, PrimitiveType::U128].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let point = &analyze_crystal_types(SOURCE).unwrap()[2];
+        assert_eq!(compatibility_score(point, "rust"), 1.0);
+        // The hash has no exact IR equivalent
+        let user = &analyze_crystal_types(SOURCE).unwrap()[0];
+        assert!(compatibility_score(user, "rust") < 1.0);
+    }
+}
@@ -0,0 +1,568 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// C# analyzer for protocol-squisher integration
+// Analyzes class, struct, record, and interface property declarations
+
+use super::c_analyzer::{skip_statement, without_preprocessor};
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::PlatformWidth;
+
+/// C# type declaration
+#[derive(Debug, Clone)]
+pub struct CSharpType {
+    /// Simple name; nested types are spelled `Outer.Inner`
+    pub name: String,
+    /// Enclosing namespace (`App.Models`), if any
+    pub namespace: Option<String>,
+    pub kind: CSharpTypeKind,
+    pub type_params: Vec<String>,
+    /// Public instance properties, positional record parameters first —
+    /// what `System.Text.Json` serializes by default
+    pub fields: Vec<CSharpField>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CSharpTypeKind {
+    Class,
+    Struct,
+    Record,
+    RecordStruct,
+    Interface,
+}
+
+#[derive(Debug, Clone)]
+pub struct CSharpField {
+    pub name: String,
+    pub field_type: CSharpFieldType,
+    /// Declared `required`
+    pub required: bool,
+    /// Settable only during initialization (`init` accessor or positional record parameter)
+    pub init_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CSharpFieldType {
+    SByte,
+    Byte,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    NInt,
+    NUInt,
+    Float,
+    Double,
+    Decimal,
+    Bool,
+    Char,
+    String,
+    /// `object` and `dynamic`
+    Object,
+    /// `T?`, for value types (`Nullable<T>`) and annotated reference types alike
+    Nullable(Box<CSharpFieldType>),
+    /// Arrays and the list-like collection types (`List<T>`, `IEnumerable<T>`, `HashSet<T>`)
+    List(Box<CSharpFieldType>),
+    /// `Dictionary<K, V>` and its interfaces
+    Dictionary(Box<CSharpFieldType>, Box<CSharpFieldType>),
+    /// User or framework type, with its type arguments
+    Named(String, Vec<CSharpFieldType>),
+    TypeParam(String),
+}
+
+/// Modifiers that may precede a type or member declaration
+const MODIFIERS: &[&str] = &[
+    "public", "private", "protected", "internal", "file", "static", "readonly", "required", "virtual", "override",
+    "abstract", "sealed", "new", "partial", "unsafe", "extern", "volatile", "async", "const",
+];
+
+/// Analyze the first C# type and extract metadata
+pub fn analyze_csharp_type(source: &str) -> Result<CSharpType, String> {
+    analyze_csharp_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every class, struct, record, and interface in a C# file
+pub fn analyze_csharp_types(source: &str) -> Result<Vec<CSharpType>, String> {
+    let mut cursor = Cursor::new(without_preprocessor(tokenize(source, &Syntax::C_LIKE)));
+    let mut types = Vec::new();
+    // One entry per open brace at namespace scope
+    let mut namespaces: Vec<String> = Vec::new();
+    // File-scoped `namespace App.Models;`
+    let mut file_namespace: Option<String> = None;
+
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "namespace" => {
+                cursor.advance();
+                let name = parse_dotted_name(&mut cursor)?;
+                if cursor.eat_punct('{') {
+                    namespaces.push(name);
+                } else {
+                    cursor.expect_punct(';')?;
+                    file_namespace = Some(name);
+                }
+            }
+            "[" => cursor.skip_balanced('[', ']'),
+            "}" => {
+                cursor.advance();
+                namespaces.pop();
+            }
+            ";" => {
+                cursor.advance();
+            }
+            _ => {
+                let namespace = file_namespace.iter().chain(&namespaces).cloned().collect::<Vec<_>>().join(".");
+                let namespace = Some(namespace).filter(|n| !n.is_empty());
+                parse_modifiers(&mut cursor);
+                if type_keyword(&cursor).is_some() {
+                    parse_type_declaration(&mut cursor, namespace, "", &mut types)?;
+                } else {
+                    skip_statement(&mut cursor);
+                }
+            }
+        }
+    }
+
+    Ok(types)
+}
+
+fn parse_dotted_name(cursor: &mut Cursor) -> Result<String, String> {
+    let mut name = cursor.expect_ident()?;
+    while cursor.eat_punct('.') {
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+fn parse_modifiers(cursor: &mut Cursor) -> Vec<String> {
+    let mut modifiers = Vec::new();
+    while let Some(word) = cursor.peek().filter(|t| MODIFIERS.contains(&t.text.as_str())).map(|t| t.text.clone()) {
+        cursor.advance();
+        modifiers.push(word);
+    }
+    modifiers
+}
+
+/// Kind of the type declaration at the cursor, without consuming it
+fn type_keyword(cursor: &Cursor) -> Option<CSharpTypeKind> {
+    Some(match cursor.peek()?.text.as_str() {
+        "class" => CSharpTypeKind::Class,
+        "struct" => CSharpTypeKind::Struct,
+        "interface" => CSharpTypeKind::Interface,
+        "record" => match cursor.peek_at(1).map(|t| t.text.as_str()) {
+            Some("struct") => CSharpTypeKind::RecordStruct,
+            _ => CSharpTypeKind::Record,
+        },
+        _ => return None,
+    })
+}
+
+/// A type declaration starting at its keyword; pushes it and any nested types
+fn parse_type_declaration(
+    cursor: &mut Cursor,
+    namespace: Option<String>,
+    outer: &str,
+    types: &mut Vec<CSharpType>,
+) -> Result<(), String> {
+    let line = cursor.line();
+    let kind = type_keyword(cursor).ok_or_else(|| cursor.unexpected("type declaration"))?;
+    cursor.advance();
+    if cursor.is_ident("struct") || cursor.is_ident("class") {
+        // `record struct` / `record class`
+        cursor.advance();
+    }
+    let name = cursor.expect_ident()?;
+    let name = if outer.is_empty() { name } else { format!("{}.{}", outer, name) };
+
+    let mut type_params = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            // Variance annotations: `in T`, `out T`
+            cursor.eat_ident("in");
+            cursor.eat_ident("out");
+            type_params.push(cursor.expect_ident()?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    if cursor.is_punct('(') {
+        fields = parse_positional_parameters(cursor, &type_params)?;
+    }
+
+    // Base types and `where` constraints
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "{" | ";" => break,
+            "(" => cursor.skip_balanced('(', ')'),
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+
+    let index = types.len();
+    types.push(CSharpType {
+        name: name.clone(),
+        namespace: namespace.clone(),
+        kind,
+        type_params: type_params.clone(),
+        fields: Vec::new(),
+        location: format!("line {}", line),
+    });
+    if cursor.eat_punct('{') {
+        let members_public = kind == CSharpTypeKind::Interface;
+        while !cursor.eat_punct('}') {
+            if cursor.at_end() {
+                return Err(cursor.unexpected("`}`"));
+            }
+            if let Some(field) = parse_member(cursor, &type_params, members_public, &namespace, &name, types)? {
+                fields.push(field);
+            }
+        }
+    } else {
+        cursor.expect_punct(';')?;
+    }
+    types[index].fields = fields;
+    Ok(())
+}
+
+/// `(int Id, string? Name = null)` of a positional record
+fn parse_positional_parameters(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<CSharpField>, String> {
+    cursor.expect_punct('(')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct(')') {
+        while cursor.is_punct('[') {
+            cursor.skip_balanced('[', ']');
+        }
+        let field_type = parse_type(cursor, type_params)?;
+        let name = cursor.expect_ident()?;
+        if cursor.eat_punct('=') {
+            // Default value
+            while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct(')') {
+                if cursor.is_punct('(') {
+                    cursor.skip_balanced('(', ')');
+                } else {
+                    cursor.advance();
+                }
+            }
+        }
+        fields.push(CSharpField { name, field_type, required: false, init_only: true });
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    Ok(fields)
+}
+
+/// One member of a type body; `Some` for a public instance property
+fn parse_member(
+    cursor: &mut Cursor,
+    type_params: &[String],
+    members_public: bool,
+    namespace: &Option<String>,
+    outer: &str,
+    types: &mut Vec<CSharpType>,
+) -> Result<Option<CSharpField>, String> {
+    while cursor.is_punct('[') {
+        cursor.skip_balanced('[', ']');
+    }
+    let modifiers = parse_modifiers(cursor);
+    let has = |word: &str| modifiers.iter().any(|m| m == word);
+
+    if type_keyword(cursor).is_some() {
+        parse_type_declaration(cursor, namespace.clone(), outer, types)?;
+        return Ok(None);
+    }
+    if cursor.is_punct('}') {
+        return Ok(None);
+    }
+    if cursor.is_punct(';') {
+        cursor.advance();
+        return Ok(None);
+    }
+    // Enums, delegates, events, constructors, destructors, and operators aren't data
+    if cursor.peek().is_some_and(|t| matches!(t.text.as_str(), "enum" | "delegate" | "event" | "~" | "implicit" | "explicit")) {
+        skip_statement(cursor);
+        return Ok(None);
+    }
+
+    let Ok(field_type) = parse_type(cursor, type_params) else {
+        skip_statement(cursor);
+        return Ok(None);
+    };
+    let name = match cursor.peek() {
+        Some(t) if t.kind == TokenKind::Ident && !matches!(t.text.as_str(), "this" | "operator") => t.text.clone(),
+        _ => {
+            skip_statement(cursor);
+            return Ok(None);
+        }
+    };
+    cursor.advance();
+    // Explicit interface implementation: `string IUser.Name { get; }`
+    if cursor.is_punct('.') {
+        skip_statement(cursor);
+        return Ok(None);
+    }
+
+    if !cursor.is_punct('{') {
+        // Fields, methods, and expression-bodied properties
+        skip_statement(cursor);
+        return Ok(None);
+    }
+
+    let mut readable = false;
+    let mut init_only = false;
+    let mut depth = 0usize;
+    while let Some(token) = cursor.advance() {
+        match token.text.as_str() {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            "get" if depth == 1 => readable = true,
+            "init" if depth == 1 => init_only = true,
+            _ => {}
+        }
+    }
+    // `= new();` initializer
+    if cursor.eat_punct('=') {
+        skip_statement(cursor);
+    }
+
+    let public = members_public || has("public");
+    if !public || has("static") || has("const") || !readable {
+        return Ok(None);
+    }
+    Ok(Some(CSharpField { name, field_type, required: has("required"), init_only }))
+}
+
+/// A type with its `?` and `[]` suffixes: `List<string>?`, `int?[]`
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CSharpFieldType, String> {
+    let mut ty = parse_base_type(cursor, type_params)?;
+    loop {
+        if cursor.eat_punct('?') {
+            ty = CSharpFieldType::Nullable(Box::new(ty));
+        } else if cursor.is_punct('[') && (cursor.is_punct_at(1, ']') || cursor.is_punct_at(1, ',')) {
+            cursor.skip_balanced('[', ']');
+            ty = CSharpFieldType::List(Box::new(ty));
+        } else {
+            return Ok(ty);
+        }
+    }
+}
+
+fn parse_base_type(cursor: &mut Cursor, type_params: &[String]) -> Result<CSharpFieldType, String> {
+    if cursor.is_ident("void") {
+        return Err(cursor.unexpected("property type"));
+    }
+    let mut name = cursor.expect_ident()?;
+    // `global::System.String`
+    if cursor.is_punct(':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+        name = cursor.expect_ident()?;
+    }
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    if args.is_empty() {
+        return Ok(match simple.as_str() {
+            "sbyte" | "SByte" => CSharpFieldType::SByte,
+            "byte" | "Byte" => CSharpFieldType::Byte,
+            "short" | "Int16" => CSharpFieldType::Short,
+            "ushort" | "UInt16" => CSharpFieldType::UShort,
+            "int" | "Int32" => CSharpFieldType::Int,
+            "uint" | "UInt32" => CSharpFieldType::UInt,
+            "long" | "Int64" => CSharpFieldType::Long,
+            "ulong" | "UInt64" => CSharpFieldType::ULong,
+            "nint" | "IntPtr" => CSharpFieldType::NInt,
+            "nuint" | "UIntPtr" => CSharpFieldType::NUInt,
+            "float" | "Single" => CSharpFieldType::Float,
+            "double" | "Double" => CSharpFieldType::Double,
+            "decimal" | "Decimal" => CSharpFieldType::Decimal,
+            "bool" | "Boolean" => CSharpFieldType::Bool,
+            "char" | "Char" => CSharpFieldType::Char,
+            "string" | "String" => CSharpFieldType::String,
+            "object" | "Object" | "dynamic" => CSharpFieldType::Object,
+            _ if type_params.contains(&name) => CSharpFieldType::TypeParam(name),
+            _ => CSharpFieldType::Named(name, args),
+        });
+    }
+
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(CSharpFieldType::Object));
+    Ok(match simple.as_str() {
+        "Nullable" => CSharpFieldType::Nullable(next()),
+        "List" | "IList" | "IReadOnlyList" | "ICollection" | "IReadOnlyCollection" | "IEnumerable" | "Collection"
+        | "HashSet" | "ISet" | "IReadOnlySet" | "SortedSet" | "ImmutableArray" | "ImmutableList" | "LinkedList"
+        | "Queue" | "Stack" => CSharpFieldType::List(next()),
+        "Dictionary" | "IDictionary" | "IReadOnlyDictionary" | "SortedDictionary" | "ImmutableDictionary" => {
+            let key = next();
+            CSharpFieldType::Dictionary(key, next())
+        }
+        _ => CSharpFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(csharp_type: &CSharpType, target: &str) -> f32 {
+    average_fidelity(ir_fields(csharp_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a C# field type into the language-neutral IR
+pub fn to_ir(field_type: &CSharpFieldType) -> IrType {
+    match field_type {
+        CSharpFieldType::SByte => IrType::Primitive(PrimitiveType::I8),
+        CSharpFieldType::Short => IrType::Primitive(PrimitiveType::I16),
+        CSharpFieldType::Int => IrType::Primitive(PrimitiveType::I32),
+        CSharpFieldType::Byte => IrType::Primitive(PrimitiveType::U8),
+        CSharpFieldType::UShort => IrType::Primitive(PrimitiveType::U16),
+        CSharpFieldType::UInt => IrType::Primitive(PrimitiveType::U32),
+        CSharpFieldType::Long | CSharpFieldType::NInt => IrType::Primitive(PrimitiveType::I64),
+        CSharpFieldType::ULong | CSharpFieldType::NUInt => IrType::Primitive(PrimitiveType::U64),
+        CSharpFieldType::Float => IrType::Primitive(PrimitiveType::F32),
+        CSharpFieldType::Double => IrType::Primitive(PrimitiveType::F64),
+        CSharpFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        // A UTF-16 code unit travels as a one-character string
+        CSharpFieldType::Char | CSharpFieldType::String => IrType::Primitive(PrimitiveType::String),
+        CSharpFieldType::Decimal => IrType::Primitive(PrimitiveType::Decimal),
+        CSharpFieldType::Object => IrType::Any,
+        CSharpFieldType::Dictionary(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        CSharpFieldType::Nullable(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        CSharpFieldType::List(element) => IrType::Container(ContainerType::Vec(Box::new(to_ir(element)))),
+        CSharpFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        CSharpFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower all properties of a C# type into the IR
+pub fn ir_fields(csharp_type: &CSharpType) -> Vec<IrField> {
+    csharp_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`nint`, `nuint`)
+pub fn platform_width(field_type: &CSharpFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        CSharpFieldType::NInt | CSharpFieldType::NUInt => Some(PlatformWidth::Pointer),
+        CSharpFieldType::Nullable(inner) | CSharpFieldType::List(inner) => platform_width(inner),
+        _ => None,
+    }
+}
+
+/// Map C# types to target language types
+pub fn map_to_target(field_type: &CSharpFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+#nullable enable
+using System.Text.Json.Serialization;
+
+namespace App.Models;
+
+public sealed record User(long Id, string Name, string? Email = null);
+
+public class Order<TItem> : Entity, IAuditable where TItem : class
+{
+    private readonly List<string> _notes = new();
+
+    [JsonPropertyName("order_id")]
+    public required Guid OrderId { get; init; }
+    public List<TItem> Items { get; set; } = new();
+    public Dictionary<string, int> Quantities { get; } = new();
+    public int? Priority { get; set; }
+    public decimal Total { get; private set; }
+    public string Summary => $"{Items.Count} items";
+    public static int Count { get; set; }
+    internal string Secret { get; set; } = "";
+
+    public Order() { }
+    public void Add(TItem item) { Items.Add(item); }
+
+    public record struct Line(int Quantity, string[] Tags);
+}
+"#;
+
+    #[test]
+    fn test_analyze_records_and_classes() {
+        let types = analyze_csharp_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Order", "Order.Line"]);
+        assert!(types.iter().all(|t| t.namespace.as_deref() == Some("App.Models")));
+
+        let user = &types[0];
+        assert_eq!(user.kind, CSharpTypeKind::Record);
+        assert_eq!(user.fields[2].field_type, CSharpFieldType::Nullable(Box::new(CSharpFieldType::String)));
+
+        let order = &types[1];
+        assert_eq!(order.type_params, vec!["TItem"]);
+        let fields: Vec<_> = order.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["OrderId", "Items", "Quantities", "Priority", "Total"]);
+        assert!(order.fields[0].required && order.fields[0].init_only);
+        assert_eq!(
+            order.fields[1].field_type,
+            CSharpFieldType::List(Box::new(CSharpFieldType::TypeParam("TItem".to_string())))
+        );
+
+        let line = &types[2];
+        assert_eq!(line.kind, CSharpTypeKind::RecordStruct);
+        assert_eq!(map_to_target(&line.fields[1].field_type, "rust"), "Vec<String>");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_csharp_type("public record Pixel(byte R, short Dx, ushort W, sbyte S);").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::U16, PrimitiveType::I8].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let order = &analyze_csharp_types(SOURCE).unwrap()[1];
+        assert_eq!(map_to_target(&order.fields[3].field_type, "rust"), "Option<i32>");
+        // The decimal maps onto `rust_decimal::Decimal`, but not onto a float
+        assert_eq!(compatibility_score(order, "rust"), 1.0);
+        assert!(compatibility_score(order, "typescript") < 1.0);
+    }
+}
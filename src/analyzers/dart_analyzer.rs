@@ -0,0 +1,725 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Dart analyzer for protocol-squisher integration
+// Analyzes classes and enums with sound null safety, as Flutter models declare
+// them for json_serializable and flutter_rust_bridge
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Dart strings take either quote; `$name` interpolation stays inside the literal
+const DART: Syntax = Syntax { string_quotes: &['"', '\''], ident_extra: &['$'], ..Syntax::C_LIKE };
+
+/// Dart class or enum
+#[derive(Debug, Clone)]
+pub struct DartType {
+    pub name: String,
+    pub kind: DartTypeKind,
+    pub type_params: Vec<String>,
+    /// Instance fields in declaration order; statics and getters are skipped
+    pub fields: Vec<DartField>,
+    /// Enum values in declaration order, as their `@JsonValue` writes them
+    pub values: Vec<String>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DartTypeKind {
+    Class,
+    /// `abstract`, `sealed`, or `interface` classes and mixins
+    Abstract,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+pub struct DartField {
+    /// Key on the wire: the `@JsonKey(name: ...)` rename if there is one
+    pub name: String,
+    pub field_type: DartFieldType,
+    pub is_final: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DartFieldType {
+    Int,
+    Double,
+    /// `num`: an `int` or a `double`
+    Num,
+    Bool,
+    String,
+    BigInt,
+    DateTime,
+    Duration,
+    /// `dynamic` and `Object`
+    Dynamic,
+    /// `T?`
+    Nullable(Box<DartFieldType>),
+    /// `List<T>`, `Iterable<T>`, and queues
+    List(Box<DartFieldType>),
+    /// Typed data like `Uint8List`, by element width
+    TypedData(PrimitiveType),
+    Set(Box<DartFieldType>),
+    Map(Box<DartFieldType>, Box<DartFieldType>),
+    /// `Function` and `void Function(int)`
+    Function,
+    /// `(int, String)` and `({int x})`
+    Record,
+    /// User or library type, with its type arguments
+    Named(String, Vec<DartFieldType>),
+    TypeParam(String),
+}
+
+const MODIFIERS: &[&str] = &[
+    "abstract", "base", "final", "interface", "sealed", "mixin", "static", "late", "const", "external",
+    "covariant", "var", "required", "factory",
+];
+
+/// A member's modifiers and what its annotations say about serialization
+#[derive(Default)]
+struct Modifiers {
+    words: Vec<String>,
+    /// `@JsonKey(name: "...")`, or an enum value's `@JsonValue(...)`
+    rename: Option<String>,
+    /// `@JsonKey(ignore: true)`, or excluded both ways
+    ignored: bool,
+}
+
+impl Modifiers {
+    fn has(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+}
+
+/// Analyze the first Dart class or enum and extract metadata
+pub fn analyze_dart_type(source: &str) -> Result<DartType, String> {
+    analyze_dart_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every class, mixin, and enum in a Dart file
+pub fn analyze_dart_types(source: &str) -> Result<Vec<DartType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &DART));
+    let mut types = Vec::new();
+
+    while !cursor.at_end() {
+        if cursor.eat_punct(';') || cursor.eat_punct('}') {
+            continue;
+        }
+        let modifiers = parse_modifiers(&mut cursor);
+        if cursor.is_ident("class") || (modifiers.has("mixin") && cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident)) {
+            types.push(parse_class(&mut cursor, &modifiers)?);
+        } else if cursor.is_ident("enum") {
+            types.push(parse_enum(&mut cursor)?);
+        } else {
+            // Imports, parts, typedefs, top-level functions and variables, extensions
+            skip_statement(&mut cursor);
+        }
+    }
+
+    Ok(types)
+}
+
+/// `@name` or `@name(...)`; returns the name and the argument tokens
+fn parse_annotation(cursor: &mut Cursor) -> Option<(String, Vec<String>)> {
+    if !cursor.is_punct('@') {
+        return None;
+    }
+    cursor.advance();
+    let mut name = cursor.expect_ident().ok()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name = cursor.expect_ident().ok()?;
+    }
+    let mut args = Vec::new();
+    if cursor.eat_punct('(') {
+        let mut depth = 0usize;
+        while let Some(token) = cursor.advance() {
+            match token.text.as_str() {
+                "(" if token.kind == TokenKind::Punct => depth += 1,
+                ")" if token.kind == TokenKind::Punct && depth == 0 => break,
+                ")" if token.kind == TokenKind::Punct => depth -= 1,
+                _ => {}
+            }
+            args.push(token.text);
+        }
+    }
+    Some((name, args))
+}
+
+fn parse_modifiers(cursor: &mut Cursor) -> Modifiers {
+    let mut modifiers = Modifiers::default();
+    loop {
+        if let Some((name, args)) = parse_annotation(cursor) {
+            if name == "JsonKey" {
+                let arg = |key: &str| args.windows(3).find(|w| w[0] == key && w[1] == ":").map(|w| w[2].clone());
+                modifiers.rename = arg("name").or(modifiers.rename);
+                let excluded = |key: &str| arg(key).as_deref() == Some("false");
+                modifiers.ignored |= arg("ignore").as_deref() == Some("true")
+                    || (excluded("includeFromJson") && excluded("includeToJson"));
+            } else if name == "JsonValue" {
+                modifiers.rename = args.first().cloned();
+            }
+        } else if let Some(word) = cursor.peek().filter(|t| MODIFIERS.contains(&t.text.as_str())).map(|t| t.text.clone()) {
+            cursor.advance();
+            modifiers.words.push(word);
+        } else {
+            return modifiers;
+        }
+    }
+}
+
+/// `<T extends Comparable<T>, U>`
+fn parse_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut type_params = Vec::new();
+    if !cursor.eat_punct('<') {
+        return Ok(type_params);
+    }
+    loop {
+        type_params.push(cursor.expect_ident()?);
+        let mut depth = 0usize;
+        while let Some(token) = cursor.peek() {
+            match token.text.as_str() {
+                "," | ">" if depth == 0 => break,
+                "<" => depth += 1,
+                ">" => depth -= 1,
+                _ => {}
+            }
+            cursor.advance();
+        }
+        if !cursor.eat_punct(',') {
+            cursor.expect_punct('>')?;
+            return Ok(type_params);
+        }
+    }
+}
+
+/// A class or mixin starting at `class` (or at its name, after `mixin`)
+fn parse_class(cursor: &mut Cursor, modifiers: &Modifiers) -> Result<DartType, String> {
+    let line = cursor.line();
+    // `mixin M on Base` as opposed to `mixin class M`
+    let mixin = !cursor.eat_ident("class");
+    let name = cursor.expect_ident()?;
+    let type_params = parse_type_params(cursor)?;
+    let kind = if mixin || ["abstract", "sealed", "interface"].iter().any(|word| modifiers.has(word)) {
+        DartTypeKind::Abstract
+    } else {
+        DartTypeKind::Class
+    };
+
+    // `extends`, `with`, `implements`, `on` clauses; `class A = B with C;` aliases have no body
+    while !cursor.at_end() && !cursor.is_punct('{') && !cursor.is_punct(';') {
+        cursor.advance();
+    }
+    let mut fields = Vec::new();
+    if cursor.is_punct('{') {
+        cursor.advance();
+        parse_members(cursor, &name, &type_params, &mut fields)?;
+    }
+
+    Ok(DartType {
+        name,
+        kind,
+        type_params,
+        fields,
+        values: Vec::new(),
+        location: format!("line {}", line),
+    })
+}
+
+/// `enum Status { active, inactive }`, or an enhanced enum whose values take
+/// constructor arguments and whose body declares fields after a `;`
+fn parse_enum(cursor: &mut Cursor) -> Result<DartType, String> {
+    let line = cursor.line();
+    cursor.advance();
+    let name = cursor.expect_ident()?;
+    let type_params = parse_type_params(cursor)?;
+    while !cursor.at_end() && !cursor.is_punct('{') {
+        cursor.advance();
+    }
+    cursor.expect_punct('{')?;
+
+    let mut values = Vec::new();
+    let mut fields = Vec::new();
+    loop {
+        let modifiers = parse_modifiers(cursor);
+        if cursor.eat_punct('}') {
+            break;
+        }
+        let value = cursor.expect_ident()?;
+        values.push(modifiers.rename.unwrap_or(value));
+        // `mercury<int>(3.3e23)` or `value.named(1)`
+        while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct(';') && !cursor.is_punct('}') {
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            } else {
+                cursor.advance();
+            }
+        }
+        cursor.eat_punct(',');
+        if cursor.eat_punct(';') {
+            parse_members(cursor, &name, &type_params, &mut fields)?;
+            break;
+        }
+    }
+
+    Ok(DartType {
+        name,
+        kind: DartTypeKind::Enum,
+        type_params,
+        fields,
+        values,
+        location: format!("line {}", line),
+    })
+}
+
+/// Members up to and including the closing `}`
+fn parse_members(cursor: &mut Cursor, class: &str, type_params: &[String], fields: &mut Vec<DartField>) -> Result<(), String> {
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        parse_member(cursor, class, type_params, fields)?;
+    }
+    Ok(())
+}
+
+/// One class member; collects instance fields
+fn parse_member(cursor: &mut Cursor, class: &str, type_params: &[String], fields: &mut Vec<DartField>) -> Result<(), String> {
+    let modifiers = parse_modifiers(cursor);
+    match cursor.peek().map(|t| t.text.as_str()) {
+        Some(";") => {
+            cursor.advance();
+            return Ok(());
+        }
+        Some("}") | None => return Ok(()),
+        _ => {}
+    }
+    // Constructors: `User(...)`, `User.fromJson(...)`, `const User._()`
+    let constructor = cursor.is_ident(class) && (cursor.is_punct_at(1, '(') || cursor.is_punct_at(1, '.'));
+    if modifiers.has("static") || modifiers.has("factory") || constructor {
+        skip_statement(cursor);
+        return Ok(());
+    }
+
+    // `var x = 1;` and `final x = 1;` infer their type
+    let untyped = cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident)
+        && matches!(cursor.peek_at(1).map(|t| t.text.as_str()), Some("=" | ";" | ","));
+    let field_type = if untyped {
+        DartFieldType::Dynamic
+    } else if cursor.is_ident("void") && cursor.peek_at(1).is_none_or(|t| t.text != "Function") {
+        skip_statement(cursor);
+        return Ok(());
+    } else {
+        match parse_type(cursor, type_params) {
+            Ok(field_type) => field_type,
+            Err(_) => {
+                skip_statement(cursor);
+                return Ok(());
+            }
+        }
+    };
+
+    // Getters, setters, operators, and methods
+    let accessor = match cursor.peek().map(|t| t.text.as_str()) {
+        Some("get" | "set") => cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident),
+        Some("operator") => !(cursor.is_punct_at(1, ';') || cursor.is_punct_at(1, ',')),
+        _ => false,
+    };
+    let Some(name) = cursor.peek().filter(|t| t.kind == TokenKind::Ident && !accessor).map(|t| t.text.clone()) else {
+        skip_statement(cursor);
+        return Ok(());
+    };
+    cursor.advance();
+    if cursor.is_punct('(') || cursor.is_punct('<') {
+        skip_statement(cursor);
+        return Ok(());
+    }
+
+    // `int a = 1, b;`
+    let mut name = name;
+    loop {
+        if cursor.eat_punct('=') {
+            let mut depth = 0usize;
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    _ if token.kind != TokenKind::Punct => {}
+                    "," | ";" if depth == 0 => break,
+                    "(" | "{" | "[" => depth += 1,
+                    ")" | "}" | "]" => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                cursor.advance();
+            }
+        }
+        if !modifiers.ignored {
+            fields.push(DartField {
+                name: modifiers.rename.clone().unwrap_or_else(|| name.clone()),
+                field_type: field_type.clone(),
+                is_final: modifiers.has("final") || modifiers.has("const"),
+            });
+        }
+        if !cursor.eat_punct(',') {
+            break;
+        }
+        name = cursor.expect_ident()?;
+    }
+    cursor.expect_punct(';')
+}
+
+/// A type with its `?` suffix: `List<String?>?`, `void Function(int)`, `(int, String)`
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<DartFieldType, String> {
+    let mut ty = if cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+        DartFieldType::Record
+    } else if cursor.is_ident("void") {
+        cursor.advance();
+        if !cursor.is_ident("Function") {
+            return Err(cursor.unexpected("field type"));
+        }
+        DartFieldType::Function
+    } else {
+        parse_base_type(cursor, type_params)?
+    };
+    if cursor.eat_punct('?') {
+        ty = nullable(ty);
+    }
+    // `int Function(String)?`
+    while cursor.eat_ident("Function") {
+        parse_type_params(cursor)?;
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        ty = DartFieldType::Function;
+        if cursor.eat_punct('?') {
+            ty = nullable(ty);
+        }
+    }
+    Ok(ty)
+}
+
+/// `T?`; `dynamic` already admits null
+fn nullable(ty: DartFieldType) -> DartFieldType {
+    match ty {
+        DartFieldType::Dynamic | DartFieldType::Nullable(_) => ty,
+        ty => DartFieldType::Nullable(Box::new(ty)),
+    }
+}
+
+fn parse_base_type(cursor: &mut Cursor, type_params: &[String]) -> Result<DartFieldType, String> {
+    let mut name = cursor.expect_ident()?;
+    // Import prefixes: `core.int`, `m.User`
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name = cursor.expect_ident()?;
+    }
+    if name == "Function" {
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        return Ok(DartFieldType::Function);
+    }
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(DartFieldType::Dynamic));
+    Ok(match name.as_str() {
+        "int" => DartFieldType::Int,
+        "double" => DartFieldType::Double,
+        "num" => DartFieldType::Num,
+        "bool" => DartFieldType::Bool,
+        "String" => DartFieldType::String,
+        "BigInt" => DartFieldType::BigInt,
+        "DateTime" => DartFieldType::DateTime,
+        "Duration" => DartFieldType::Duration,
+        "dynamic" | "Object" => DartFieldType::Dynamic,
+        "List" | "Iterable" | "Queue" | "ListQueue" | "UnmodifiableListView" => DartFieldType::List(next()),
+        "Set" | "HashSet" | "LinkedHashSet" | "SplayTreeSet" => DartFieldType::Set(next()),
+        "Map" | "HashMap" | "LinkedHashMap" | "SplayTreeMap" => {
+            let key = next();
+            DartFieldType::Map(key, next())
+        }
+        "Uint8List" | "Uint8ClampedList" => DartFieldType::TypedData(PrimitiveType::U8),
+        "Int8List" => DartFieldType::TypedData(PrimitiveType::I8),
+        "Uint16List" => DartFieldType::TypedData(PrimitiveType::U16),
+        "Int16List" => DartFieldType::TypedData(PrimitiveType::I16),
+        "Uint32List" => DartFieldType::TypedData(PrimitiveType::U32),
+        "Int32List" => DartFieldType::TypedData(PrimitiveType::I32),
+        "Uint64List" => DartFieldType::TypedData(PrimitiveType::U64),
+        "Int64List" => DartFieldType::TypedData(PrimitiveType::I64),
+        "Float32List" => DartFieldType::TypedData(PrimitiveType::F32),
+        "Float64List" => DartFieldType::TypedData(PrimitiveType::F64),
+        _ if type_params.contains(&name) => DartFieldType::TypeParam(name),
+        _ => DartFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(dart_type: &DartType, target: &str) -> f32 {
+    average_fidelity(ir_fields(dart_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Dart field type into the language-neutral IR
+pub fn to_ir(field_type: &DartFieldType) -> IrType {
+    match field_type {
+        // 64-bit on the VM; compiled to JavaScript it is a double
+        DartFieldType::Int | DartFieldType::Duration => IrType::Primitive(PrimitiveType::I64),
+        DartFieldType::Double | DartFieldType::Num => IrType::Primitive(PrimitiveType::F64),
+        DartFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        // ISO 8601 on the wire, as json_serializable writes it
+        DartFieldType::String | DartFieldType::DateTime => IrType::Primitive(PrimitiveType::String),
+        DartFieldType::BigInt => IrType::Primitive(PrimitiveType::BigInt),
+        DartFieldType::Dynamic | DartFieldType::Function | DartFieldType::Record => IrType::Any,
+        DartFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        DartFieldType::Nullable(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        DartFieldType::List(element) | DartFieldType::Set(element) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        DartFieldType::TypedData(element) => IrType::Container(ContainerType::Vec(Box::new(IrType::Primitive(*element)))),
+        DartFieldType::Named(name, _) => IrType::Reference(name.clone()),
+        DartFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower all fields of a Dart type into the IR
+pub fn ir_fields(dart_type: &DartType) -> Vec<IrField> {
+    dart_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Dart types to target language types
+pub fn map_to_target(field_type: &DartFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+import 'package:json_annotation/json_annotation.dart';
+
+part 'user.g.dart';
+
+enum Role { admin, member, guest }
+
+@JsonSerializable()
+class User {
+  static const version = 2;
+  final int id;
+  final String name;
+  String? email;
+  @JsonKey(name: 'created_at')
+  final DateTime createdAt;
+  final List<String> tags;
+  final Map<String, int> scores;
+  late Set<Role>? roles;
+  @JsonKey(includeFromJson: false, includeToJson: false)
+  Object? cache;
+  void Function(String)? onChanged;
+  var counter = 0, label = '{';
+
+  User(this.id, {required this.name, this.email, required this.createdAt, this.tags = const []})
+      : scores = {};
+
+  factory User.fromJson(Map<String, dynamic> json) => _$UserFromJson(json);
+  Map<String, dynamic> toJson() => _$UserToJson(this);
+
+  String get displayName => email ?? name;
+  set nickname(String value) {}
+  @override
+  bool operator ==(Object other) => other is User && other.id == id;
+}
+
+sealed class Result<T> {
+  final T? value;
+  const Result(this.value);
+}
+
+enum Planet {
+  mercury(3.303e+23, 2.4397e6),
+  venus(4.869e+24, 6.0518e6);
+
+  const Planet(this.mass, this.radius);
+  final double mass;
+  final double radius;
+}
+"#;
+
+    #[test]
+    fn test_analyze_classes_and_enums() {
+        let types = analyze_dart_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Role", DartTypeKind::Enum),
+                ("User", DartTypeKind::Class),
+                ("Result", DartTypeKind::Abstract),
+                ("Planet", DartTypeKind::Enum),
+            ]
+        );
+        assert_eq!(types[0].values, vec!["admin", "member", "guest"]);
+
+        let user = &types[1];
+        let fields: Vec<_> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["id", "name", "email", "created_at", "tags", "scores", "roles", "onChanged", "counter", "label"]
+        );
+        assert!(user.fields[0].is_final && !user.fields[2].is_final);
+        assert_eq!(user.fields[2].field_type, DartFieldType::Nullable(Box::new(DartFieldType::String)));
+        assert_eq!(
+            user.fields[5].field_type,
+            DartFieldType::Map(Box::new(DartFieldType::String), Box::new(DartFieldType::Int))
+        );
+        assert_eq!(
+            user.fields[6].field_type,
+            DartFieldType::Nullable(Box::new(DartFieldType::Set(Box::new(DartFieldType::Named("Role".to_string(), vec![])))))
+        );
+        assert_eq!(user.fields[7].field_type, DartFieldType::Nullable(Box::new(DartFieldType::Function)));
+
+        assert_eq!(types[2].type_params, vec!["T"]);
+        assert_eq!(types[3].values, vec!["mercury", "venus"]);
+        let planet: Vec<_> = types[3].fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(planet, vec!["mass", "radius"]);
+    }
+
+    #[test]
+    fn test_nullable_collections() {
+        let cache = analyze_dart_type(
+            "class Cache { List<String?>? a; Map<String, List<int>>? b; HashMap<int, double> c; SplayTreeSet<String> d; Iterable<num> e; Map m; }",
+        )
+        .unwrap();
+        assert_eq!(
+            cache.fields[0].field_type,
+            DartFieldType::Nullable(Box::new(DartFieldType::List(Box::new(DartFieldType::Nullable(Box::new(DartFieldType::String))))))
+        );
+        let rust: Vec<_> = cache.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "Option<Vec<Option<String>>>",
+                "Option<HashMap<String, Vec<i64>>>",
+                "HashMap<i64, f64>",
+                "Vec<String>",
+                "Vec<f64>",
+                "HashMap<serde_json::Value, serde_json::Value>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_functions_and_prefixes() {
+        let hooks = analyze_dart_type(
+            "class Hooks { (int, String) r; ({int x}) n; int Function(String)? f; Function g; core.int h; m.User u; BigInt big; Object? o; }",
+        )
+        .unwrap();
+        let types: Vec<_> = hooks.fields.iter().map(|f| f.field_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                DartFieldType::Record,
+                DartFieldType::Record,
+                DartFieldType::Nullable(Box::new(DartFieldType::Function)),
+                DartFieldType::Function,
+                DartFieldType::Int,
+                DartFieldType::Named("User".to_string(), vec![]),
+                DartFieldType::BigInt,
+                DartFieldType::Dynamic,
+            ]
+        );
+        assert_eq!(map_to_target(&hooks.fields[6].field_type, "rust"), "num_bigint::BigInt");
+    }
+
+    #[test]
+    fn test_enum_values_follow_json_value() {
+        let status = analyze_dart_type("enum Status { @JsonValue('on') active, @JsonValue(2) inactive, unknown }").unwrap();
+        assert_eq!(status.values, vec!["on", "2", "unknown"]);
+        assert!(status.fields.is_empty());
+
+        let code = analyze_dart_type(
+            "enum Code<T> { ok<int>(200), missing<int>(404); const Code(this.value); final T value; \
+             int get doubled => value * 2; static const all = 1; }",
+        )
+        .unwrap();
+        assert_eq!(code.values, vec!["ok", "missing"]);
+        let fields: Vec<_> = code.fields.iter().map(|f| (f.name.as_str(), f.field_type.clone(), f.is_final)).collect();
+        assert_eq!(fields, vec![("value", DartFieldType::TypeParam("T".to_string()), true)]);
+    }
+
+    #[test]
+    fn test_mixins_interfaces_and_aliases() {
+        let types = analyze_dart_types(
+            "mixin Tagged on Base { late final String tag; }\nabstract interface class Shape { double get area; }\n\
+             class Alias = Base with Tagged;\ntypedef Json = Map<String, dynamic>;\n\
+             class Box<T extends Comparable<T>, U> { final T first; final List<U> rest; Box(this.first, this.rest); }",
+        )
+        .unwrap();
+        let kinds: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.fields.len())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Tagged", DartTypeKind::Abstract, 1),
+                ("Shape", DartTypeKind::Abstract, 0),
+                ("Alias", DartTypeKind::Class, 0),
+                ("Box", DartTypeKind::Class, 2),
+            ]
+        );
+        assert_eq!(types[3].type_params, vec!["T", "U"]);
+        assert_eq!(map_to_target(&types[3].fields[1].field_type, "rust"), "Vec<U>");
+    }
+
+    #[test]
+    fn test_typed_data_keeps_its_width() {
+        let samples = analyze_dart_type("class Samples { Uint8List a; Int16List b; Float32List c; Uint64List d; }").unwrap();
+        let rust: Vec<_> = samples.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["Vec<u8>", "Vec<i16>", "Vec<f32>", "Vec<u64>"]);
+        assert_eq!(compatibility_score(&samples, "rust"), 1.0);
+        // `u64` elements lose precision as JavaScript numbers
+        assert!(compatibility_score(&samples, "typescript") < 1.0);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(analyze_dart_type("class A { int a;").unwrap_err(), "expected `}`, found end of input");
+        assert_eq!(analyze_dart_type("class A { int a }").unwrap_err(), "line 1: expected `;`, found `}`");
+        assert_eq!(analyze_dart_type("class A { int a = 1, ; }").unwrap_err(), "line 1: expected identifier, found `;`");
+        assert_eq!(analyze_dart_type("enum E { 1 }").unwrap_err(), "line 1: expected identifier, found `1`");
+        assert_eq!(analyze_dart_type("import 'x.dart';").unwrap_err(), "Type definition not found");
+        // A type that does not parse skips its member rather than the class
+        let partial = analyze_dart_type("class A { Map<String int> a; int b; }").unwrap();
+        assert_eq!(partial.fields.len(), 1);
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let types = analyze_dart_types(SOURCE).unwrap();
+        let user = &types[1];
+        assert_eq!(map_to_target(&user.fields[2].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&user.fields[4].field_type, "rust"), "Vec<String>");
+        assert_eq!(map_to_target(&user.fields[6].field_type, "gleam"), "Option(List(Role))");
+        assert_eq!(map_to_target(&types[2].fields[0].field_type, "typescript"), "T | null");
+
+        let plain = analyze_dart_type("class Point { final double x; final double y; final int? tag; }").unwrap();
+        assert_eq!(compatibility_score(&plain, "rust"), 1.0);
+        assert!(compatibility_score(user, "rust") < 1.0);
+    }
+}
@@ -0,0 +1,569 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Elixir struct analyzer for protocol-squisher integration
+// Analyzes `defstruct` declarations together with their `@type t ::` typespecs
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Elixir struct information extracted from a `defmodule`
+#[derive(Debug, Clone)]
+pub struct ElixirType {
+    /// Last segment of the module name (`MyApp.User` → `User`)
+    pub name: String,
+    /// Fully qualified module name
+    pub module: String,
+    pub fields: Vec<ElixirField>,
+    /// Whether the module declares `@type t` (otherwise every field is `term()`)
+    pub typed: bool,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElixirField {
+    pub name: String,
+    /// Type from `@type t`, or `Any` when the struct has no typespec for it
+    pub field_type: ElixirFieldType,
+    /// Default value as written in `defstruct` (`nil` when omitted)
+    pub default: Option<String>,
+    /// Listed in `@enforce_keys`
+    pub enforced: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElixirFieldType {
+    Integer,  // arbitrary precision
+    Float,
+    Number,   // `integer() | float()`
+    String,   // `String.t()` and `binary()`
+    Boolean,
+    Atom,
+    /// Atom literal (`:active`)
+    AtomLiteral(String),
+    Nil,
+    Any,      // `any()` and `term()`
+    List(Box<ElixirFieldType>),
+    Map,
+    Tuple(Vec<ElixirFieldType>),
+    /// Remote or local type (`Address.t()` → `Address`, `status()` → `status`)
+    Remote(String),
+    Union(Vec<ElixirFieldType>),
+}
+
+const ELIXIR_SYNTAX: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[],
+    string_quotes: &['"', '\''],
+    ident_extra: &['?', '!'],
+    char_literals: false,
+};
+
+/// Module being scanned, with whatever struct metadata has been seen so far
+struct OpenModule {
+    module: String,
+    depth: usize,
+    line: usize,
+    fields: Option<Vec<ElixirField>>,
+    enforced: Vec<String>,
+    typespec: Option<Vec<(String, ElixirFieldType)>>,
+}
+
+impl OpenModule {
+    fn finish(self) -> Option<(usize, ElixirType)> {
+        let mut fields = self.fields?;
+        let typed = self.typespec.is_some();
+        for field in &mut fields {
+            field.enforced = self.enforced.contains(&field.name);
+            if let Some((_, ty)) = self.typespec.iter().flatten().find(|(name, _)| *name == field.name) {
+                field.field_type = ty.clone();
+            }
+        }
+        let declared = ElixirType {
+            name: self.module.rsplit('.').next().unwrap_or(&self.module).to_string(),
+            module: self.module,
+            fields,
+            typed,
+            location: format!("line {}", self.line),
+        };
+        Some((self.line, declared))
+    }
+}
+
+/// Analyze the first Elixir struct and extract metadata
+pub fn analyze_elixir_type(source: &str) -> Result<ElixirType, String> {
+    analyze_elixir_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every module that declares a `defstruct`, including nested modules
+pub fn analyze_elixir_types(source: &str) -> Result<Vec<ElixirType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &ELIXIR_SYNTAX));
+    let mut open: Vec<OpenModule> = Vec::new();
+    let mut types = Vec::new();
+    let mut depth = 0;
+
+    while let Some(token) = cursor.peek() {
+        // Keyword keys (`do:`, `end:`) are not block delimiters
+        let word = if token.kind == TokenKind::Ident && !is_keyword_key(&cursor, 0) {
+            token.text.clone()
+        } else {
+            String::new()
+        };
+
+        match word.as_str() {
+            "defmodule" => {
+                let line = cursor.line();
+                cursor.advance();
+                let module = parse_alias(&mut cursor)?;
+                if cursor.eat_ident("do") {
+                    depth += 1;
+                    let module = match open.last() {
+                        Some(parent) => format!("{}.{}", parent.module, module),
+                        None => module,
+                    };
+                    open.push(OpenModule { module, depth, line, fields: None, enforced: Vec::new(), typespec: None });
+                }
+            }
+            "do" | "fn" => {
+                cursor.advance();
+                depth += 1;
+            }
+            "end" => {
+                cursor.advance();
+                if open.last().is_some_and(|module| module.depth == depth) {
+                    types.extend(open.pop().and_then(OpenModule::finish));
+                }
+                depth = depth.saturating_sub(1);
+            }
+            "defstruct" => {
+                cursor.advance();
+                let fields = parse_defstruct(&mut cursor)?;
+                if let Some(module) = open.last_mut() {
+                    module.fields = Some(fields);
+                }
+            }
+            _ if cursor.is_punct('@') => {
+                cursor.advance();
+                if cursor.eat_ident("enforce_keys") {
+                    let keys = parse_atom_list(&mut cursor)?;
+                    if let Some(module) = open.last_mut() {
+                        module.enforced = keys;
+                    }
+                } else if (cursor.eat_ident("type") || cursor.eat_ident("opaque") || cursor.eat_ident("typep"))
+                    && cursor.eat_ident("t")
+                {
+                    if cursor.is_punct('(') {
+                        cursor.skip_balanced('(', ')');
+                    }
+                    expect_double_colon(&mut cursor)?;
+                    if cursor.is_punct('%') {
+                        let spec = parse_struct_spec(&mut cursor)?;
+                        if let Some(module) = open.last_mut() {
+                            module.typespec = Some(spec);
+                        }
+                    }
+                }
+            }
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+
+    // Unterminated modules still report the struct they declared
+    while let Some(module) = open.pop() {
+        types.extend(module.finish());
+    }
+    // Nested modules close before their parents; report in source order
+    types.sort_by_key(|(line, _)| *line);
+    Ok(types.into_iter().map(|(_, declared)| declared).collect())
+}
+
+/// `key:` in a keyword list, as opposed to `name :: type` or `:atom`
+fn is_keyword_key(cursor: &Cursor, offset: usize) -> bool {
+    let key = cursor.peek_at(offset);
+    let colon = cursor.peek_at(offset + 1);
+    let double_colon = cursor
+        .peek_at(offset + 2)
+        .zip(colon)
+        .is_some_and(|(next, colon)| next.text == ":" && next.start == colon.end);
+    key.is_some_and(|t| t.kind == TokenKind::Ident) && colon.is_some_and(|t| t.text == ":") && !double_colon
+}
+
+/// Dotted alias (`MyApp.Accounts.User`)
+fn parse_alias(cursor: &mut Cursor) -> Result<String, String> {
+    let mut alias = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        alias = format!("{}.{}", alias, cursor.expect_ident()?);
+    }
+    Ok(alias)
+}
+
+fn expect_double_colon(cursor: &mut Cursor) -> Result<(), String> {
+    cursor.expect_punct(':')?;
+    cursor.expect_punct(':')
+}
+
+/// `[:a, :b]`
+fn parse_atom_list(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut atoms = Vec::new();
+    cursor.expect_punct('[')?;
+    while !cursor.eat_punct(']') {
+        cursor.expect_punct(':')?;
+        atoms.push(cursor.expect_ident()?);
+        if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+            return Err(cursor.unexpected("`,` or `]`"));
+        }
+    }
+    Ok(atoms)
+}
+
+/// `defstruct [:a, b: 1]` or `defstruct a: 1, b: nil`
+fn parse_defstruct(cursor: &mut Cursor) -> Result<Vec<ElixirField>, String> {
+    let bracketed = cursor.eat_punct('[');
+    let mut fields = Vec::new();
+    loop {
+        if bracketed && cursor.eat_punct(']') {
+            break;
+        }
+        let (name, default) = if cursor.eat_punct(':') {
+            (cursor.expect_ident()?, None)
+        } else {
+            let name = cursor.expect_ident()?;
+            cursor.expect_punct(':')?;
+            (name, Some(parse_default(cursor)))
+        };
+        fields.push(ElixirField { name, field_type: ElixirFieldType::Any, default, enforced: false });
+
+        if !cursor.eat_punct(',') {
+            if bracketed {
+                cursor.expect_punct(']')?;
+            }
+            break;
+        }
+    }
+    Ok(fields)
+}
+
+/// Source text of a default value, up to the next top-level `,`, closing bracket, or line end
+fn parse_default(cursor: &mut Cursor) -> String {
+    let mut text = String::new();
+    let mut depth = 0usize;
+    let line = cursor.line();
+    while let Some(token) = cursor.peek().cloned() {
+        if depth == 0 && token.line != line {
+            break;
+        }
+        if token.kind == TokenKind::Punct {
+            match token.text.as_str() {
+                "," | "]" | ")" | "}" if depth == 0 => break,
+                "[" | "(" | "{" => depth += 1,
+                "]" | ")" | "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        cursor.advance();
+        match token.kind {
+            TokenKind::Str => text.push_str(&format!("{:?}", token.text)),
+            _ => text.push_str(&token.text),
+        }
+    }
+    text
+}
+
+/// `%__MODULE__{id: integer(), ...}` → field name and type pairs
+fn parse_struct_spec(cursor: &mut Cursor) -> Result<Vec<(String, ElixirFieldType)>, String> {
+    cursor.expect_punct('%')?;
+    parse_alias(cursor)?;
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        let name = cursor.expect_ident()?;
+        cursor.expect_punct(':')?;
+        fields.push((name, parse_type(cursor)?));
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(fields)
+}
+
+fn parse_type(cursor: &mut Cursor) -> Result<ElixirFieldType, String> {
+    let mut members = vec![parse_primary(cursor)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_primary(cursor)?);
+    }
+    Ok(if members.len() == 1 {
+        members.remove(0)
+    } else {
+        ElixirFieldType::Union(members)
+    })
+}
+
+fn parse_primary(cursor: &mut Cursor) -> Result<ElixirFieldType, String> {
+    let token = cursor.peek().cloned().ok_or_else(|| cursor.unexpected("type"))?;
+    match token.kind {
+        TokenKind::Number => {
+            cursor.advance();
+            // Ranges (`1..10`)
+            if cursor.is_punct('.') && cursor.is_punct_at(1, '.') {
+                cursor.advance();
+                cursor.advance();
+                cursor.eat_punct('-');
+                cursor.advance();
+            }
+            Ok(if token.text.contains('.') { ElixirFieldType::Float } else { ElixirFieldType::Integer })
+        }
+        TokenKind::Punct if token.text == "%" => {
+            cursor.advance();
+            let name = if cursor.is_punct('{') { None } else { Some(parse_alias(cursor)?) };
+            cursor.skip_balanced('{', '}');
+            Ok(match name {
+                Some(name) if name != "__MODULE__" => ElixirFieldType::Remote(name),
+                _ => ElixirFieldType::Map,
+            })
+        }
+        TokenKind::Punct if token.text == "[" => {
+            cursor.advance();
+            if cursor.eat_punct(']') {
+                return Ok(ElixirFieldType::List(Box::new(ElixirFieldType::Any)));
+            }
+            // Keyword lists (`[timeout: integer()]`)
+            if is_keyword_key(cursor, 0) {
+                skip_to_close(cursor, '[', ']');
+                return Ok(ElixirFieldType::List(Box::new(ElixirFieldType::Any)));
+            }
+            let element = parse_type(cursor)?;
+            if cursor.eat_punct(',') {
+                skip_to_close(cursor, '[', ']');
+            } else {
+                cursor.expect_punct(']')?;
+            }
+            Ok(ElixirFieldType::List(Box::new(element)))
+        }
+        TokenKind::Punct if token.text == "{" => {
+            cursor.advance();
+            let mut members = Vec::new();
+            while !cursor.eat_punct('}') {
+                members.push(parse_type(cursor)?);
+                if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                    return Err(cursor.unexpected("`,` or `}`"));
+                }
+            }
+            Ok(ElixirFieldType::Tuple(members))
+        }
+        TokenKind::Punct if token.text == ":" => {
+            cursor.advance();
+            match cursor.advance() {
+                Some(atom) if atom.kind == TokenKind::Ident || atom.kind == TokenKind::Str => {
+                    Ok(ElixirFieldType::AtomLiteral(atom.text))
+                }
+                _ => Err(format!("line {}: expected atom after `:`", token.line)),
+            }
+        }
+        TokenKind::Ident => parse_named(cursor),
+        _ => Err(cursor.unexpected("type")),
+    }
+}
+
+/// Built-in, local, or remote type (`integer()`, `list(t)`, `String.t()`)
+fn parse_named(cursor: &mut Cursor) -> Result<ElixirFieldType, String> {
+    let path = parse_alias(cursor)?;
+    let mut args = Vec::new();
+    if cursor.eat_punct('(') {
+        while !cursor.eat_punct(')') {
+            args.push(parse_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+    }
+
+    Ok(match path.as_str() {
+        "integer" | "non_neg_integer" | "pos_integer" | "neg_integer" | "byte" | "char" | "arity" | "timeout" => {
+            ElixirFieldType::Integer
+        }
+        "float" => ElixirFieldType::Float,
+        "number" => ElixirFieldType::Number,
+        "boolean" | "true" | "false" => ElixirFieldType::Boolean,
+        "atom" | "module" | "node" => ElixirFieldType::Atom,
+        "nil" => ElixirFieldType::Nil,
+        "String.t" | "binary" | "bitstring" | "charlist" | "iodata" | "iolist" => ElixirFieldType::String,
+        "map" | "struct" | "keyword" => ElixirFieldType::Map,
+        "any" | "term" => ElixirFieldType::Any,
+        "list" | "nonempty_list" => {
+            let element = args.into_iter().next().unwrap_or(ElixirFieldType::Any);
+            ElixirFieldType::List(Box::new(element))
+        }
+        "tuple" => ElixirFieldType::Tuple(Vec::new()),
+        _ => match path.strip_suffix(".t") {
+            Some(module) => ElixirFieldType::Remote(module.to_string()),
+            None => ElixirFieldType::Remote(path),
+        },
+    })
+}
+
+/// Skip to the `close` matching an already-consumed `open`
+fn skip_to_close(cursor: &mut Cursor, open: char, close: char) {
+    let mut depth = 1;
+    while let Some(token) = cursor.advance() {
+        if token.kind == TokenKind::Punct {
+            if token.text.starts_with(open) {
+                depth += 1;
+            } else if token.text.starts_with(close) {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(elixir_type: &ElixirType, target: &str) -> f32 {
+    match target {
+        "rust" | "gleam" => {
+            average_fidelity(ir_fields(elixir_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+        }
+        _ => 0.0,
+    }
+}
+
+/// Lower an Elixir typespec into the language-neutral IR
+pub fn to_ir(field_type: &ElixirFieldType) -> IrType {
+    match field_type {
+        ElixirFieldType::Integer => IrType::Primitive(PrimitiveType::I64),
+        ElixirFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        ElixirFieldType::String => IrType::Primitive(PrimitiveType::String),
+        ElixirFieldType::Boolean => IrType::Primitive(PrimitiveType::Bool),
+        // Atoms cross the boundary as their names
+        ElixirFieldType::Atom | ElixirFieldType::AtomLiteral(_) => IrType::Primitive(PrimitiveType::String),
+        ElixirFieldType::Nil => IrType::Primitive(PrimitiveType::Unit),
+        // `tuple()` says nothing about its arity or members
+        ElixirFieldType::Tuple(members) if !members.is_empty() => IrType::Tuple(members.iter().map(to_ir).collect()),
+        ElixirFieldType::Number | ElixirFieldType::Any | ElixirFieldType::Map | ElixirFieldType::Tuple(_) => IrType::Any,
+        ElixirFieldType::List(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        ElixirFieldType::Remote(name) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        ElixirFieldType::Union(members) => union_to_ir(members),
+    }
+}
+
+/// `t | nil` is an option; `:a | :b` is an atom enum; anything else stays a union
+fn union_to_ir(members: &[ElixirFieldType]) -> IrType {
+    let nullable = members.contains(&ElixirFieldType::Nil);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != ElixirFieldType::Nil) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower struct fields into the IR
+pub fn ir_fields(elixir_type: &ElixirType) -> Vec<IrField> {
+    elixir_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Elixir typespecs to target language types
+pub fn map_to_target(field_type: &ElixirFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r#"
+defmodule MyApp.User do
+  @moduledoc "A user account"
+  @enforce_keys [:id, :name]
+  defstruct [:id, :name, email: nil, roles: [], status: :active]
+
+  @type t :: %__MODULE__{
+          id: non_neg_integer(),
+          name: String.t(),
+          email: String.t() | nil,
+          roles: [atom()],
+          status: :active | :suspended
+        }
+
+  def new(id, name), do: %__MODULE__{id: id, name: name}
+
+  def display(%__MODULE__{name: name}) do
+    Enum.map([name], fn n -> n end)
+  end
+end
+"#;
+
+    #[test]
+    fn test_analyze_struct_with_typespec() {
+        let user = analyze_elixir_type(USER).unwrap();
+        assert_eq!(user.name, "User");
+        assert_eq!(user.module, "MyApp.User");
+        assert!(user.typed);
+        assert_eq!(user.fields.len(), 5);
+        assert!(user.fields[0].enforced);
+        assert!(!user.fields[2].enforced);
+        assert_eq!(user.fields[4].default.as_deref(), Some(":active"));
+        assert_eq!(map_to_target(&user.fields[2].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&user.fields[3].field_type, "gleam"), "List(String)");
+        assert_eq!(map_to_target(&user.fields[4].field_type, "gleam"), "String");
+        assert_eq!(compatibility_score(&user, "gleam"), 1.0);
+    }
+
+    #[test]
+    fn test_nested_modules_and_untyped_structs() {
+        let source = r#"
+defmodule Shop do
+  defmodule Item do
+    defstruct sku: "", qty: 0
+  end
+
+  defstruct items: []
+end
+"#;
+        let types = analyze_elixir_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.module.as_str()).collect();
+        assert_eq!(names, vec!["Shop", "Shop.Item"]);
+        let item = &types[1];
+        assert!(!item.typed);
+        assert_eq!(item.fields[0].default.as_deref(), Some("\"\""));
+        // No typespec: every field is `term()` and scores as lossy
+        assert_eq!(compatibility_score(item, "rust"), 0.5);
+    }
+
+    #[test]
+    fn test_compare_with_rust() {
+        let user = analyze_elixir_type(USER).unwrap();
+        let rust = crate::analyzers::rust_analyzer::analyze_rust_type(
+            "pub struct User { pub id: i64, pub name: String, pub email: Option<String>, pub roles: Vec<String>, pub status: String }",
+        )
+        .unwrap();
+        let score = ir::compare_fields(&ir_fields(&user), &crate::analyzers::rust_analyzer::ir_fields(&rust));
+        assert_eq!(score, 1.0);
+        assert_eq!(crate::calculate_transport_class("elixir", "gleam"), crate::TransportClass::Concorde);
+    }
+}
@@ -0,0 +1,702 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Erlang analyzer for protocol-squisher integration
+// Analyzes `-record` definitions, `-type`/`-opaque` declarations, and `-spec` signatures
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Erlang record or named type
+#[derive(Debug, Clone)]
+pub struct ErlangType {
+    pub name: String,
+    pub kind: ErlangTypeKind,
+    /// Declared with `-opaque` (callers must not inspect it)
+    pub opaque: bool,
+    pub type_params: Vec<String>,
+    /// Record fields, or the atom keys of a map type
+    pub fields: Vec<ErlangField>,
+    /// Atoms of an enumeration type (`active | suspended`)
+    pub values: Vec<String>,
+    /// Right-hand side of any other type declaration
+    pub definition: Option<ErlangFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErlangTypeKind {
+    /// `-record(name, {...})`
+    Record,
+    /// `-type name() :: #{key := type()}` with atom keys
+    Map,
+    /// `-type name() :: a | b | c`
+    Enum,
+    /// Any other `-type` or `-opaque`
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErlangField {
+    pub name: String,
+    /// Declared type, or `Any` for untyped record fields (`term()`)
+    pub field_type: ErlangFieldType,
+    /// Default value as written (`= []`)
+    pub default: Option<String>,
+    /// Always present: every record field, and `:=` map keys (`=>` keys may be absent)
+    pub required: bool,
+}
+
+/// `-spec name(Args) -> Return.`, first clause only
+#[derive(Debug, Clone)]
+pub struct ErlangSpec {
+    pub name: String,
+    pub params: Vec<ErlangFieldType>,
+    pub returns: ErlangFieldType,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErlangFieldType {
+    /// Arbitrary precision, including ranges and literals
+    Integer,
+    Float,
+    /// `number()`: integer or float
+    Number,
+    /// `binary()`, `iodata()`, `<<_:_*8>>`
+    Binary,
+    /// `string()`: a list of code points
+    Charlist,
+    Boolean,
+    Atom,
+    /// Atom literal (`active`, `'Quoted'`)
+    AtomLiteral(String),
+    /// `any()`, `term()`, `pid()`, `reference()`
+    Any,
+    List(Box<ErlangFieldType>),
+    Map,
+    Tuple(Vec<ErlangFieldType>),
+    /// `#user{}`
+    Record(String),
+    /// `fun((...) -> ...)`
+    Fun,
+    /// Local or remote user type (`status()`, `calendar:datetime()`)
+    Named(String, Vec<ErlangFieldType>),
+    /// Type variable (`T` in `-type box(T) :: {box, T}.`)
+    TypeVar(String),
+    Union(Vec<ErlangFieldType>),
+}
+
+const ERLANG_SYNTAX: Syntax = Syntax {
+    line_comments: &["%"],
+    block_comments: &[],
+    string_quotes: &['"', '\''],
+    ident_extra: &['@'],
+    char_literals: false,
+};
+
+/// Analyze the first Erlang record or type and extract metadata
+pub fn analyze_erlang_type(source: &str) -> Result<ErlangType, String> {
+    analyze_erlang_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `-record`, `-type`, and `-opaque` in an Erlang module or header
+pub fn analyze_erlang_types(source: &str) -> Result<Vec<ErlangType>, String> {
+    analyze_forms(source).map(|(types, _)| types)
+}
+
+/// Analyze every `-spec` in an Erlang module
+pub fn analyze_erlang_specs(source: &str) -> Result<Vec<ErlangSpec>, String> {
+    analyze_forms(source).map(|(_, specs)| specs)
+}
+
+fn analyze_forms(source: &str) -> Result<(Vec<ErlangType>, Vec<ErlangSpec>), String> {
+    let mut cursor = Cursor::new(tokenize(source, &ERLANG_SYNTAX));
+    let mut types = Vec::new();
+    let mut specs = Vec::new();
+    while cursor.peek().is_some() {
+        if cursor.is_punct('-') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            let line = cursor.line();
+            cursor.advance();
+            let attribute = cursor.expect_ident()?;
+            // `-type(name() :: t).` is the parenthesized spelling of `-type name() :: t.`
+            let parenthesized = cursor.is_punct('(') && attribute != "record";
+            if parenthesized {
+                cursor.advance();
+            }
+            match attribute.as_str() {
+                "record" => types.push(parse_record(&mut cursor, line)?),
+                "type" | "opaque" => types.push(parse_type_declaration(&mut cursor, attribute == "opaque", line)?),
+                "spec" => specs.push(parse_spec(&mut cursor, line)?),
+                _ => {}
+            }
+        }
+        skip_form(&mut cursor);
+    }
+    Ok((types, specs))
+}
+
+/// Skip past the `.` that ends the current form (followed by whitespace or end of input,
+/// unlike record field access `R#user.name`)
+fn skip_form(cursor: &mut Cursor) {
+    while let Some(token) = cursor.advance() {
+        if token.kind == TokenKind::Punct && token.text == "." {
+            match cursor.peek() {
+                Some(next) if next.start == token.end => {}
+                _ => return,
+            }
+        }
+    }
+}
+
+fn is_double_colon(cursor: &Cursor) -> bool {
+    cursor.is_punct(':') && cursor.is_punct_at(1, ':')
+}
+
+fn expect_double_colon(cursor: &mut Cursor) -> Result<(), String> {
+    cursor.expect_punct(':')?;
+    cursor.expect_punct(':')
+}
+
+/// Atom name, bare or quoted
+fn expect_atom(cursor: &mut Cursor) -> Result<String, String> {
+    match cursor.peek() {
+        Some(token) if token.kind == TokenKind::Str => Ok(cursor.advance().map(|t| t.text).unwrap_or_default()),
+        _ => cursor.expect_ident(),
+    }
+}
+
+fn is_variable(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_uppercase() || c == '_')
+}
+
+/// `(name, {field = default :: type(), ...})`
+fn parse_record(cursor: &mut Cursor, line: usize) -> Result<ErlangType, String> {
+    cursor.expect_punct('(')?;
+    let name = expect_atom(cursor)?;
+    cursor.expect_punct(',')?;
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        let field = expect_atom(cursor)?;
+        let mut default = None;
+        if cursor.eat_punct('=') {
+            default = Some(parse_default(cursor));
+        }
+        let field_type = if is_double_colon(cursor) {
+            expect_double_colon(cursor)?;
+            parse_type(cursor)?
+        } else {
+            ErlangFieldType::Any
+        };
+        fields.push(ErlangField { name: field, field_type, default, required: true });
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(ErlangType {
+        name,
+        kind: ErlangTypeKind::Record,
+        opaque: false,
+        type_params: Vec::new(),
+        fields,
+        values: Vec::new(),
+        definition: None,
+        location: format!("line {}", line),
+    })
+}
+
+/// Default value expression, up to `::`, `,`, or `}` at its own nesting level
+fn parse_default(cursor: &mut Cursor) -> String {
+    let mut text = Vec::new();
+    let mut depth = 0usize;
+    while let Some(token) = cursor.peek().cloned() {
+        if token.kind == TokenKind::Punct {
+            match token.text.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" => depth = depth.saturating_sub(1),
+                "}" if depth == 0 => break,
+                "}" => depth -= 1,
+                "," if depth == 0 => break,
+                ":" if depth == 0 && cursor.is_punct_at(1, ':') => break,
+                // End of the form: the record is malformed
+                "." if cursor.peek_at(1).is_none_or(|next| next.start > token.end) => break,
+                _ => {}
+            }
+        }
+        cursor.advance();
+        text.push(if token.kind == TokenKind::Str { format!("{:?}", token.text) } else { token.text });
+    }
+    text.concat()
+}
+
+/// `name(Params) :: type()`
+fn parse_type_declaration(cursor: &mut Cursor, opaque: bool, line: usize) -> Result<ErlangType, String> {
+    let name = expect_atom(cursor)?;
+    let mut type_params = Vec::new();
+    cursor.expect_punct('(')?;
+    while !cursor.eat_punct(')') {
+        let param = cursor.expect_ident()?;
+        type_params.push(param);
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    expect_double_colon(cursor)?;
+
+    let mut erlang_type = ErlangType {
+        name,
+        kind: ErlangTypeKind::Alias,
+        opaque,
+        type_params,
+        fields: Vec::new(),
+        values: Vec::new(),
+        definition: None,
+        location: format!("line {}", line),
+    };
+    if cursor.is_punct('#') && cursor.is_punct_at(1, '{') {
+        cursor.advance();
+        cursor.advance();
+        if let Some(fields) = parse_map_fields(cursor)? {
+            erlang_type.kind = ErlangTypeKind::Map;
+            erlang_type.fields = fields;
+            return Ok(erlang_type);
+        }
+        erlang_type.definition = Some(ErlangFieldType::Map);
+        return Ok(erlang_type);
+    }
+
+    let definition = parse_type(cursor)?;
+    let atoms: Option<Vec<String>> = match &definition {
+        ErlangFieldType::AtomLiteral(atom) => Some(vec![atom.clone()]),
+        ErlangFieldType::Union(members) => members
+            .iter()
+            .map(|member| match member {
+                ErlangFieldType::AtomLiteral(atom) => Some(atom.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    };
+    match atoms {
+        Some(values) => {
+            erlang_type.kind = ErlangTypeKind::Enum;
+            erlang_type.values = values;
+        }
+        None => erlang_type.definition = Some(definition),
+    }
+    Ok(erlang_type)
+}
+
+/// Associations of a map type after its `#{`; `None` when a key isn't an atom
+fn parse_map_fields(cursor: &mut Cursor) -> Result<Option<Vec<ErlangField>>, String> {
+    let mut fields = Vec::new();
+    let mut atom_keys = true;
+    while !cursor.eat_punct('}') {
+        let key = parse_type(cursor)?;
+        let required = if cursor.eat_punct(':') {
+            cursor.expect_punct('=')?;
+            true
+        } else {
+            cursor.expect_punct('=')?;
+            cursor.expect_punct('>')?;
+            false
+        };
+        let field_type = parse_type(cursor)?;
+        match key {
+            ErlangFieldType::AtomLiteral(name) => fields.push(ErlangField { name, field_type, default: None, required }),
+            _ => atom_keys = false,
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(if atom_keys { Some(fields) } else { None })
+}
+
+/// `[module:]name(Args) -> Return [when Constraints]`
+fn parse_spec(cursor: &mut Cursor, line: usize) -> Result<ErlangSpec, String> {
+    let mut name = expect_atom(cursor)?;
+    if cursor.is_punct(':') && !cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        name = expect_atom(cursor)?;
+    }
+    let mut params = Vec::new();
+    cursor.expect_punct('(')?;
+    while !cursor.eat_punct(')') {
+        params.push(parse_type(cursor)?);
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    cursor.expect_punct('-')?;
+    cursor.expect_punct('>')?;
+    let returns = parse_type(cursor)?;
+    Ok(ErlangSpec { name, params, returns, location: format!("line {}", line) })
+}
+
+fn parse_type(cursor: &mut Cursor) -> Result<ErlangFieldType, String> {
+    let mut members = vec![parse_range(cursor)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_range(cursor)?);
+    }
+    Ok(if members.len() == 1 { members.remove(0) } else { ErlangFieldType::Union(members) })
+}
+
+/// A primary type, or an integer range `0..255`
+fn parse_range(cursor: &mut Cursor) -> Result<ErlangFieldType, String> {
+    let primary = parse_primary(cursor)?;
+    if cursor.is_punct('.') && cursor.is_punct_at(1, '.') {
+        cursor.advance();
+        cursor.advance();
+        parse_primary(cursor)?;
+        return Ok(ErlangFieldType::Integer);
+    }
+    Ok(primary)
+}
+
+fn parse_primary(cursor: &mut Cursor) -> Result<ErlangFieldType, String> {
+    let token = cursor.peek().cloned().ok_or_else(|| cursor.unexpected("type"))?;
+    match (token.kind, token.text.as_str()) {
+        (TokenKind::Number, _) => {
+            cursor.advance();
+            Ok(if token.text.contains('.') { ErlangFieldType::Float } else { ErlangFieldType::Integer })
+        }
+        (TokenKind::Punct, "-") => {
+            cursor.advance();
+            parse_primary(cursor)
+        }
+        (TokenKind::Str, _) => {
+            cursor.advance();
+            Ok(ErlangFieldType::AtomLiteral(token.text))
+        }
+        (TokenKind::Punct, "(") => {
+            cursor.advance();
+            let inner = parse_type(cursor)?;
+            cursor.expect_punct(')')?;
+            Ok(inner)
+        }
+        (TokenKind::Punct, "[") => {
+            cursor.advance();
+            if cursor.eat_punct(']') {
+                return Ok(ErlangFieldType::List(Box::new(ErlangFieldType::Any)));
+            }
+            let element = parse_type(cursor)?;
+            // Non-empty lists: `[T, ...]`
+            if cursor.eat_punct(',') {
+                while cursor.eat_punct('.') {}
+            }
+            cursor.expect_punct(']')?;
+            Ok(ErlangFieldType::List(Box::new(element)))
+        }
+        (TokenKind::Punct, "{") => {
+            cursor.advance();
+            let mut members = Vec::new();
+            while !cursor.eat_punct('}') {
+                members.push(parse_type(cursor)?);
+                if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                    return Err(cursor.unexpected("`,` or `}`"));
+                }
+            }
+            Ok(ErlangFieldType::Tuple(members))
+        }
+        (TokenKind::Punct, "#") => {
+            cursor.advance();
+            if cursor.is_punct('{') {
+                cursor.skip_balanced('{', '}');
+                return Ok(ErlangFieldType::Map);
+            }
+            let name = expect_atom(cursor)?;
+            // Field overrides (`#user{name :: binary()}`) narrow the record, not change it
+            if cursor.is_punct('{') {
+                cursor.skip_balanced('{', '}');
+            }
+            Ok(ErlangFieldType::Record(name))
+        }
+        (TokenKind::Punct, "<") if cursor.is_punct_at(1, '<') => {
+            cursor.advance();
+            while cursor.peek().is_some() && !(cursor.is_punct('>') && cursor.is_punct_at(1, '>')) {
+                cursor.advance();
+            }
+            cursor.advance();
+            cursor.advance();
+            Ok(ErlangFieldType::Binary)
+        }
+        (TokenKind::Ident, "fun") => {
+            cursor.advance();
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            }
+            Ok(ErlangFieldType::Fun)
+        }
+        (TokenKind::Ident, name) if is_variable(name) => {
+            cursor.advance();
+            // Annotated variables in specs: `Id :: integer()`
+            if is_double_colon(cursor) {
+                expect_double_colon(cursor)?;
+                return parse_type(cursor);
+            }
+            Ok(if name == "_" { ErlangFieldType::Any } else { ErlangFieldType::TypeVar(token.text) })
+        }
+        (TokenKind::Ident, _) => parse_named(cursor),
+        _ => Err(cursor.unexpected("type")),
+    }
+}
+
+/// Atom literal, or a built-in, local, or remote type (`integer()`, `list(T)`, `calendar:datetime()`)
+fn parse_named(cursor: &mut Cursor) -> Result<ErlangFieldType, String> {
+    let mut name = cursor.expect_ident()?;
+    if cursor.is_punct(':') && !cursor.is_punct_at(1, ':') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        let module = name;
+        name = format!("{}:{}", module, cursor.expect_ident()?);
+    }
+    if !cursor.eat_punct('(') {
+        return Ok(match name.as_str() {
+            "true" | "false" => ErlangFieldType::Boolean,
+            _ => ErlangFieldType::AtomLiteral(name),
+        });
+    }
+    let mut args = Vec::new();
+    while !cursor.eat_punct(')') {
+        args.push(parse_type(cursor)?);
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+
+    let builtin = name.strip_prefix("erlang:").unwrap_or(&name);
+    Ok(match builtin {
+        "integer" | "non_neg_integer" | "pos_integer" | "neg_integer" | "byte" | "char" | "arity" | "timeout" => {
+            ErlangFieldType::Integer
+        }
+        "float" => ErlangFieldType::Float,
+        "number" => ErlangFieldType::Number,
+        "binary" | "nonempty_binary" | "bitstring" | "nonempty_bitstring" | "iodata" | "iolist" | "unicode:unicode_binary"
+        | "unicode:chardata" => ErlangFieldType::Binary,
+        "string" | "nonempty_string" | "unicode:charlist" => ErlangFieldType::Charlist,
+        "boolean" => ErlangFieldType::Boolean,
+        "atom" | "module" | "node" => ErlangFieldType::Atom,
+        "any" | "term" | "pid" | "port" | "reference" | "identifier" | "mfa" | "no_return" | "none" => ErlangFieldType::Any,
+        "list" | "nonempty_list" | "maybe_improper_list" => {
+            ErlangFieldType::List(Box::new(args.into_iter().next().unwrap_or(ErlangFieldType::Any)))
+        }
+        "map" => ErlangFieldType::Map,
+        "tuple" => ErlangFieldType::Tuple(Vec::new()),
+        "fun" | "function" => ErlangFieldType::Fun,
+        _ => ErlangFieldType::Named(name, args),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(erlang_type: &ErlangType, target: &str) -> f32 {
+    average_fidelity(ir_fields(erlang_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an Erlang type into the language-neutral IR
+pub fn to_ir(field_type: &ErlangFieldType) -> IrType {
+    match field_type {
+        ErlangFieldType::Integer => IrType::Primitive(PrimitiveType::I64),
+        ErlangFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        ErlangFieldType::Binary | ErlangFieldType::Charlist => IrType::Primitive(PrimitiveType::String),
+        ErlangFieldType::Boolean => IrType::Primitive(PrimitiveType::Bool),
+        // Atoms cross the boundary as their names
+        ErlangFieldType::Atom | ErlangFieldType::AtomLiteral(_) => IrType::Primitive(PrimitiveType::String),
+        ErlangFieldType::List(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        ErlangFieldType::Record(name) => IrType::Reference(name.clone()),
+        ErlangFieldType::Named(name, _) => IrType::Reference(name.rsplit(':').next().unwrap_or(name).to_string()),
+        ErlangFieldType::TypeVar(name) => IrType::TypeParam(name.clone()),
+        ErlangFieldType::Union(members) => union_to_ir(members),
+        // `tuple()` says nothing about its arity or members
+        ErlangFieldType::Tuple(items) if !items.is_empty() => IrType::Tuple(items.iter().map(to_ir).collect()),
+        // Maps and funs have no IR form yet
+        ErlangFieldType::Number | ErlangFieldType::Any | ErlangFieldType::Map | ErlangFieldType::Tuple(_) | ErlangFieldType::Fun => {
+            IrType::Any
+        }
+    }
+}
+
+/// Payload of an `ok`/`error` tagged result member: `Some(None)` for the bare
+/// atom, `Some(Some(T))` for `{tag, T}`
+fn tagged<'a>(member: &'a ErlangFieldType, tag: &str) -> Option<Option<&'a ErlangFieldType>> {
+    match member {
+        ErlangFieldType::AtomLiteral(atom) if atom == tag => Some(None),
+        ErlangFieldType::Tuple(items) => match items.as_slice() {
+            [ErlangFieldType::AtomLiteral(atom), payload] if atom == tag => Some(Some(payload)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `T | undefined` is an option, `{ok, T} | {error, E}` a result, and a
+/// union of atoms a string; anything else stays a union
+fn union_to_ir(members: &[ErlangFieldType]) -> IrType {
+    let is_null = |m: &&ErlangFieldType| matches!(m, ErlangFieldType::AtomLiteral(atom) if atom == "undefined" || atom == "nil");
+    let present: Vec<&ErlangFieldType> = members.iter().filter(|m| !is_null(m)).collect();
+    let nullable = present.len() < members.len();
+
+    let result = match present.as_slice() {
+        [first, second] => match (tagged(first, "ok"), tagged(second, "error")) {
+            (Some(ok), Some(err)) => Some((ok, err)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let inner = match result {
+        Some((ok, err)) => {
+            let side = |payload: Option<&ErlangFieldType>| Box::new(payload.map_or(IrType::Primitive(PrimitiveType::Unit), to_ir));
+            IrType::Container(ContainerType::Result(side(ok), side(err)))
+        }
+        None => {
+            let mut distinct: Vec<IrType> = Vec::new();
+            for ty in present.iter().map(|m| to_ir(m)) {
+                if !distinct.contains(&ty) {
+                    distinct.push(ty);
+                }
+            }
+            match distinct.len() {
+                0 => return IrType::Primitive(PrimitiveType::Unit),
+                1 => distinct.remove(0),
+                _ => IrType::Union(distinct),
+            }
+        }
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower record fields and map keys into the IR; `=>` keys may be absent, so
+/// they're optional (enums and aliases have none)
+pub fn ir_fields(erlang_type: &ErlangType) -> Vec<IrField> {
+    erlang_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = if field.required { ty } else { IrType::Container(ContainerType::Option(Box::new(ty))) };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map Erlang types to target language types
+pub fn map_to_target(field_type: &ErlangFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+-module(accounts).
+-export([new/2, lookup/1]).
+-export_type([status/0, profile/0]).
+
+%% A registered user
+-record(user, {
+    id :: non_neg_integer(),
+    name = <<"">> :: binary(),
+    email :: binary() | undefined,
+    roles = [] :: [atom()],
+    status = active :: status(),
+    address :: #address{},
+    notes
+}).
+
+-type status() :: active | suspended | 'Banned'.
+-type profile() :: #{id := integer(), nickname => unicode:unicode_binary()}.
+-opaque token() :: <<_:256>>.
+-type lookup_result(T) :: {ok, T} | {error, not_found | timeout}.
+
+-spec new(Id :: non_neg_integer(), binary()) -> #user{}.
+new(Id, Name) -> #user{id = Id, name = Name}.
+
+-spec lookup(integer()) -> lookup_result(#user{}).
+lookup(Id) when Id > 0 -> User = find(Id), User#user.name.
+"#;
+
+    #[test]
+    fn test_analyze_records_and_types() {
+        let types = analyze_erlang_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("user", ErlangTypeKind::Record),
+                ("status", ErlangTypeKind::Enum),
+                ("profile", ErlangTypeKind::Map),
+                ("token", ErlangTypeKind::Alias),
+                ("lookup_result", ErlangTypeKind::Alias),
+            ]
+        );
+
+        let user = &types[0];
+        let fields: Vec<_> = user.fields.iter().map(|f| (f.name.as_str(), f.default.as_deref())).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", None),
+                ("name", Some("<<\"\">>")),
+                ("email", None),
+                ("roles", Some("[]")),
+                ("status", Some("active")),
+                ("address", None),
+                ("notes", None),
+            ]
+        );
+        assert_eq!(user.fields[6].field_type, ErlangFieldType::Any);
+        assert_eq!(types[1].values, vec!["active", "suspended", "Banned"]);
+        assert!(types[3].opaque);
+        assert_eq!(types[4].type_params, vec!["T"]);
+
+        let profile = ir_fields(&types[2]);
+        assert_eq!(profile[0].ty, IrType::Primitive(PrimitiveType::I64));
+        assert_eq!(map_to_target(&types[2].fields[1].field_type, "gleam"), "String");
+        assert_eq!(render_type(&profile[1].ty, "rust"), "Option<String>");
+    }
+
+    #[test]
+    fn test_unions_map_to_option_and_result() {
+        let types = analyze_erlang_types(SOURCE).unwrap();
+        assert_eq!(map_to_target(&types[0].fields[2].field_type, "rust"), "Option<String>");
+        assert_eq!(
+            types[4].definition.as_ref().map(to_ir),
+            Some(IrType::Container(ContainerType::Result(
+                Box::new(IrType::TypeParam("T".to_string())),
+                Box::new(IrType::Primitive(PrimitiveType::String))
+            )))
+        );
+        let ok_or_error = parse_type(&mut Cursor::new(tokenize("ok | {error, term()}", &ERLANG_SYNTAX))).unwrap();
+        assert_eq!(map_to_target(&ok_or_error, "rust"), "Result<(), serde_json::Value>");
+    }
+
+    #[test]
+    fn test_specs() {
+        let specs = analyze_erlang_specs(SOURCE).unwrap();
+        let names: Vec<_> = specs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["new", "lookup"]);
+        assert_eq!(specs[0].params, vec![ErlangFieldType::Integer, ErlangFieldType::Binary]);
+        assert_eq!(specs[0].returns, ErlangFieldType::Record("user".to_string()));
+        assert_eq!(
+            specs[1].returns,
+            ErlangFieldType::Named("lookup_result".to_string(), vec![ErlangFieldType::Record("user".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_score_against_gleam() {
+        let user = analyze_erlang_type(SOURCE).unwrap();
+        // `notes` is untyped (`term()`)
+        assert_eq!(compatibility_score(&user, "gleam"), 6.5 / 7.0);
+    }
+}
@@ -0,0 +1,437 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// FlatBuffers analyzer for protocol-squisher integration
+// Analyzes tables, structs, enums, and unions in `.fbs` schemas; field presence
+// follows the generated readers: table scalars always read a value, other
+// table fields may be absent unless `(required)`
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// FlatBuffers table, struct, enum, or union
+#[derive(Debug, Clone)]
+pub struct FlatBuffersType {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub kind: FlatBuffersTypeKind,
+    pub fields: Vec<FlatBuffersField>,
+    pub values: Vec<FlatBuffersEnumValue>,
+    /// Member types of a union
+    pub members: Vec<String>,
+    /// Underlying integer type of an enum
+    pub underlying: Option<FlatBuffersFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlatBuffersTypeKind {
+    /// Evolvable; every field may be left out by the writer
+    Table,
+    /// Fixed layout, stored inline; scalars, structs, and arrays only
+    Struct,
+    Enum,
+    Union,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlatBuffersField {
+    pub name: String,
+    pub field_type: FlatBuffersFieldType,
+    /// Reads as absent: a table field that isn't a scalar or enum and isn't
+    /// `(required)`, or a scalar defaulting to `null`
+    pub optional: bool,
+    /// `(deprecated)`: no accessor is generated
+    pub deprecated: bool,
+    /// Attribute names, such as `required`, `key`, or `id`
+    pub attributes: Vec<String>,
+}
+
+/// `Red = 0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatBuffersEnumValue {
+    pub name: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlatBuffersFieldType {
+    Bool,
+    /// `byte`/`int8`
+    Byte,
+    /// `ubyte`/`uint8`
+    UByte,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Float,
+    Double,
+    String,
+    /// `[T]`
+    Vector(Box<FlatBuffersFieldType>),
+    /// `[T:N]`, in structs only
+    Array(Box<FlatBuffersFieldType>, usize),
+    /// Table, struct, enum, or union, possibly namespaced
+    Named(String),
+}
+
+impl FlatBuffersFieldType {
+    fn is_scalar(&self) -> bool {
+        !matches!(self, FlatBuffersFieldType::String | FlatBuffersFieldType::Vector(_) | FlatBuffersFieldType::Array(..) | FlatBuffersFieldType::Named(_))
+    }
+}
+
+/// Analyze the first declaration of a FlatBuffers schema
+pub fn analyze_flatbuffers_type(source: &str) -> Result<FlatBuffersType, String> {
+    analyze_flatbuffers_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every table, struct, enum, and union in a FlatBuffers schema
+pub fn analyze_flatbuffers_types(source: &str) -> Result<Vec<FlatBuffersType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &Syntax::C_LIKE));
+    let mut types = Vec::new();
+    let mut namespace = None;
+    while let Some(token) = cursor.peek() {
+        let line = token.line;
+        let new_type = |name: String, kind| FlatBuffersType {
+            name,
+            namespace: namespace.clone(),
+            kind,
+            fields: Vec::new(),
+            values: Vec::new(),
+            members: Vec::new(),
+            underlying: None,
+            location: format!("line {}", line),
+        };
+        match token.text.as_str() {
+            "namespace" => {
+                cursor.advance();
+                namespace = if cursor.is_punct(';') { None } else { Some(parse_full_name(&mut cursor)?) };
+                cursor.expect_punct(';')?;
+            }
+            "table" | "struct" => {
+                let kind = if token.text == "table" { FlatBuffersTypeKind::Table } else { FlatBuffersTypeKind::Struct };
+                cursor.advance();
+                let mut defined = new_type(cursor.expect_ident()?, kind);
+                parse_attributes(&mut cursor)?;
+                cursor.expect_punct('{')?;
+                while !cursor.eat_punct('}') {
+                    defined.fields.push(parse_field(&mut cursor)?);
+                }
+                types.push(defined);
+            }
+            "enum" => {
+                cursor.advance();
+                let mut enumeration = new_type(cursor.expect_ident()?, FlatBuffersTypeKind::Enum);
+                cursor.expect_punct(':')?;
+                enumeration.underlying = Some(parse_type(&mut cursor)?);
+                parse_attributes(&mut cursor)?;
+                cursor.expect_punct('{')?;
+                // Values without `=` count up from the previous one, starting at zero
+                let mut next = 0i64;
+                while !cursor.eat_punct('}') {
+                    let name = cursor.expect_ident()?;
+                    if cursor.eat_punct('=') {
+                        next = parse_integer(&mut cursor)?;
+                    }
+                    enumeration.values.push(FlatBuffersEnumValue { name, value: next });
+                    next = next.saturating_add(1);
+                    if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                        return Err(cursor.unexpected("`,` or `}`"));
+                    }
+                }
+                types.push(enumeration);
+            }
+            "union" => {
+                cursor.advance();
+                let mut union = new_type(cursor.expect_ident()?, FlatBuffersTypeKind::Union);
+                parse_attributes(&mut cursor)?;
+                cursor.expect_punct('{')?;
+                while !cursor.eat_punct('}') {
+                    // `Weapon`, or `Alias: Game.Weapon`
+                    let mut member = parse_full_name(&mut cursor)?;
+                    if cursor.eat_punct(':') {
+                        member = parse_full_name(&mut cursor)?;
+                    }
+                    union.members.push(member);
+                    if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                        return Err(cursor.unexpected("`,` or `}`"));
+                    }
+                }
+                types.push(union);
+            }
+            // Services describe RPC, not data
+            "rpc_service" => {
+                while !cursor.is_punct('{') && !cursor.at_end() {
+                    cursor.advance();
+                }
+                cursor.skip_balanced('{', '}');
+            }
+            "include" | "native_include" | "attribute" | "root_type" | "file_identifier" | "file_extension" => skip_statement(&mut cursor),
+            ";" => {
+                cursor.advance();
+            }
+            _ => return Err(cursor.unexpected("a declaration")),
+        }
+    }
+    resolve(&mut types);
+    Ok(types)
+}
+
+/// `name:type = default (attributes);`
+fn parse_field(cursor: &mut Cursor) -> Result<FlatBuffersField, String> {
+    let name = cursor.expect_ident()?;
+    cursor.expect_punct(':')?;
+    let field_type = parse_type(cursor)?;
+    let mut null_default = false;
+    if cursor.eat_punct('=') {
+        null_default = cursor.is_ident("null");
+        if cursor.is_punct('-') || cursor.is_punct('+') {
+            cursor.advance();
+        }
+        cursor.advance().ok_or_else(|| cursor.unexpected("default value"))?;
+    }
+    let attributes = parse_attributes(cursor)?;
+    cursor.expect_punct(';')?;
+    Ok(FlatBuffersField {
+        name,
+        optional: null_default,
+        deprecated: attributes.iter().any(|a| a == "deprecated"),
+        attributes,
+        field_type,
+    })
+}
+
+fn parse_type(cursor: &mut Cursor) -> Result<FlatBuffersFieldType, String> {
+    if cursor.eat_punct('[') {
+        let items = Box::new(parse_type(cursor)?);
+        let field_type = if cursor.eat_punct(':') {
+            let length = cursor.advance().ok_or_else(|| cursor.unexpected("array length"))?;
+            let length = length.text.parse().map_err(|_| format!("line {}: invalid array length `{}`", length.line, length.text))?;
+            FlatBuffersFieldType::Array(items, length)
+        } else {
+            FlatBuffersFieldType::Vector(items)
+        };
+        cursor.expect_punct(']')?;
+        return Ok(field_type);
+    }
+    let name = parse_full_name(cursor)?;
+    Ok(match name.as_str() {
+        "bool" => FlatBuffersFieldType::Bool,
+        "byte" | "int8" => FlatBuffersFieldType::Byte,
+        "ubyte" | "uint8" => FlatBuffersFieldType::UByte,
+        "short" | "int16" => FlatBuffersFieldType::Short,
+        "ushort" | "uint16" => FlatBuffersFieldType::UShort,
+        "int" | "int32" => FlatBuffersFieldType::Int,
+        "uint" | "uint32" => FlatBuffersFieldType::UInt,
+        "long" | "int64" => FlatBuffersFieldType::Long,
+        "ulong" | "uint64" => FlatBuffersFieldType::ULong,
+        "float" | "float32" => FlatBuffersFieldType::Float,
+        "double" | "float64" => FlatBuffersFieldType::Double,
+        "string" => FlatBuffersFieldType::String,
+        _ => FlatBuffersFieldType::Named(name),
+    })
+}
+
+/// `(id: 2, required, priority: 1)`, by name
+fn parse_attributes(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut attributes = Vec::new();
+    if !cursor.eat_punct('(') {
+        return Ok(attributes);
+    }
+    while !cursor.eat_punct(')') {
+        attributes.push(cursor.expect_ident()?);
+        if cursor.eat_punct(':') {
+            // Numbers may be signed; strings and identifiers are one token
+            cursor.eat_punct('-');
+            cursor.advance().ok_or_else(|| cursor.unexpected("attribute value"))?;
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    Ok(attributes)
+}
+
+/// `MyGame.Sample.Monster`
+fn parse_full_name(cursor: &mut Cursor) -> Result<String, String> {
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+fn parse_integer(cursor: &mut Cursor) -> Result<i64, String> {
+    let negative = cursor.eat_punct('-');
+    let number = cursor.advance().ok_or_else(|| cursor.unexpected("integer"))?;
+    let value = match number.text.strip_prefix("0x").or_else(|| number.text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => number.text.parse().ok(),
+    };
+    let value = value.ok_or_else(|| format!("line {}: invalid integer `{}`", number.line, number.text))?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Table fields that aren't scalars or enums may be absent; whether a named
+/// type is an enum is only known once the whole file is read
+fn resolve(types: &mut [FlatBuffersType]) {
+    let enums: Vec<String> = types.iter().filter(|t| t.kind == FlatBuffersTypeKind::Enum).map(|t| t.name.clone()).collect();
+    for defined in types.iter_mut().filter(|t| t.kind == FlatBuffersTypeKind::Table) {
+        for field in &mut defined.fields {
+            let scalar = match &field.field_type {
+                FlatBuffersFieldType::Named(name) => enums.iter().any(|e| e == name.rsplit('.').next().unwrap_or(name)),
+                other => other.is_scalar(),
+            };
+            if !scalar && !field.attributes.iter().any(|a| a == "required") {
+                field.optional = true;
+            }
+        }
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(fbs_type: &FlatBuffersType, target: &str) -> f32 {
+    average_fidelity(ir_fields(fbs_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a FlatBuffers field type into the language-neutral IR
+pub fn to_ir(field_type: &FlatBuffersFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        FlatBuffersFieldType::Bool => primitive(PrimitiveType::Bool),
+        // No narrower integers in the IR
+        FlatBuffersFieldType::Byte | FlatBuffersFieldType::Short | FlatBuffersFieldType::Int => primitive(PrimitiveType::I32),
+        FlatBuffersFieldType::UByte | FlatBuffersFieldType::UShort | FlatBuffersFieldType::UInt => primitive(PrimitiveType::U32),
+        FlatBuffersFieldType::Long => primitive(PrimitiveType::I64),
+        FlatBuffersFieldType::ULong => primitive(PrimitiveType::U64),
+        FlatBuffersFieldType::Float => primitive(PrimitiveType::F32),
+        FlatBuffersFieldType::Double => primitive(PrimitiveType::F64),
+        FlatBuffersFieldType::String => primitive(PrimitiveType::String),
+        FlatBuffersFieldType::Vector(items) | FlatBuffersFieldType::Array(items, _) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(items))))
+        }
+        FlatBuffersFieldType::Named(name) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+    }
+}
+
+/// Lower the fields of a table or struct into the IR; deprecated fields have
+/// no accessors and are left out
+pub fn ir_fields(fbs_type: &FlatBuffersType) -> Vec<IrField> {
+    fbs_type
+        .fields
+        .iter()
+        .filter(|field| !field.deprecated)
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = if field.optional { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map FlatBuffers types to target language types
+pub fn map_to_target(field_type: &FlatBuffersFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONSTER: &str = r#"
+include "weapons.fbs";
+namespace MyGame.Sample;
+attribute "priority";
+
+enum Color : byte { Red = 0, Green, Blue = 2 }
+
+union Equipment { Weapon, Shield: Armory.Shield }
+
+struct Vec3 (force_align: 16) {
+  x:float;
+  y:float;
+  z:float;
+  tags:[ubyte:4];
+}
+
+table Monster {
+  pos:Vec3;
+  mana:short = 150;
+  hp:short = -1;
+  name:string (required, priority: 1);
+  friendly:bool = false (deprecated);
+  inventory:[ubyte];
+  color:Color = Blue;
+  equipped:Equipment;
+  path:[Vec3];
+  level:int = null;
+}
+
+root_type Monster;
+
+rpc_service MonsterStorage {
+  Store(Monster):Stat (streaming: "none");
+}
+"#;
+
+    #[test]
+    fn test_analyze_flatbuffers() {
+        let types = analyze_flatbuffers_types(MONSTER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Color", FlatBuffersTypeKind::Enum),
+                ("Equipment", FlatBuffersTypeKind::Union),
+                ("Vec3", FlatBuffersTypeKind::Struct),
+                ("Monster", FlatBuffersTypeKind::Table),
+            ]
+        );
+        assert_eq!(types[0].namespace.as_deref(), Some("MyGame.Sample"));
+        assert_eq!(types[0].underlying, Some(FlatBuffersFieldType::Byte));
+        assert_eq!(types[0].values[1], FlatBuffersEnumValue { name: "Green".to_string(), value: 1 });
+        assert_eq!(types[1].members, vec!["Weapon", "Armory.Shield"]);
+        assert_eq!(types[2].fields[3].field_type, FlatBuffersFieldType::Array(Box::new(FlatBuffersFieldType::UByte), 4));
+
+        let rust: Vec<_> = ir_fields(&types[3]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "pos: Option<Vec3>",
+                "mana: i32",
+                "hp: i32",
+                "name: String",
+                "inventory: Option<Vec<u32>>",
+                "color: Color",
+                "equipped: Option<Equipment>",
+                "path: Option<Vec<Vec3>>",
+                "level: Option<i32>",
+            ]
+        );
+        assert_eq!(types[3].location, "line 17");
+        assert_eq!(compatibility_score(&types[2], "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_namespaces_and_errors() {
+        let point = analyze_flatbuffers_type("struct Point { x:int32; y:int32; }").unwrap();
+        assert_eq!(point.namespace, None);
+        assert_eq!(map_to_target(&point.fields[0].field_type, "gleam"), "Int");
+        assert!(analyze_flatbuffers_type("root_type Missing;").is_err());
+        assert!(analyze_flatbuffers_types("table Open { a:int;").is_err());
+        assert!(analyze_flatbuffers_types("enum Untyped { A }").is_err());
+        assert!(analyze_flatbuffers_types("message Proto {}").is_err());
+    }
+}
@@ -0,0 +1,772 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// F# type analyzer for protocol-squisher integration
+// Analyzes records, discriminated unions, enums, and abbreviations in .fs/.fsi files
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type, snake_case};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// F# type information extracted from source
+#[derive(Debug, Clone)]
+pub struct FSharpType {
+    pub name: String,
+    pub kind: FSharpTypeKind,
+    /// Type parameters without the quote (`Page<'T>` → `["T"]`)
+    pub type_params: Vec<String>,
+    /// Declared `[<Struct>]`
+    pub is_struct: bool,
+    /// Fields of a record
+    pub fields: Vec<FSharpField>,
+    /// Cases of a union or enum
+    pub cases: Vec<FSharpCase>,
+    /// Right-hand side of an abbreviation (`type UserId = int64`)
+    pub alias: Option<FSharpFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FSharpTypeKind {
+    Record,
+    Union,
+    /// Cases with explicit values (`Red = 0`), compiled to a CLR enum
+    Enum,
+    Abbreviation,
+    /// Declared without a representation, as in signature files
+    Abstract,
+}
+
+#[derive(Debug, Clone)]
+pub struct FSharpField {
+    pub name: String,
+    pub field_type: FSharpFieldType,
+    pub mutable: bool,
+}
+
+/// Union or enum case; unlabeled payload fields get the names the compiler
+/// gives them (`Item`, or `Item1`, `Item2`, ...)
+#[derive(Debug, Clone)]
+pub struct FSharpCase {
+    pub name: String,
+    pub fields: Vec<FSharpField>,
+    /// Enum case value (`Red = 0` → `"0"`)
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FSharpFieldType {
+    SByte,
+    Byte,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    NativeInt,
+    UNativeInt,
+    Float32,
+    Float64,
+    Decimal,
+    BigInt,
+    Bool,
+    Char,
+    String,
+    Unit,
+    /// `obj`, anonymous records, and the `_` wildcard
+    Obj,
+    List(Box<FSharpFieldType>),
+    /// `T[]`, `array<T>`, `ResizeArray<T>`
+    Array(Box<FSharpFieldType>),
+    Seq(Box<FSharpFieldType>),
+    Set(Box<FSharpFieldType>),
+    /// `option` and `voption`
+    Option(Box<FSharpFieldType>),
+    Result(Box<FSharpFieldType>, Box<FSharpFieldType>),
+    /// `Map<K, V>` and the .NET dictionaries
+    Map(Box<FSharpFieldType>, Box<FSharpFieldType>),
+    Tuple(Vec<FSharpFieldType>),
+    Function(Box<FSharpFieldType>, Box<FSharpFieldType>),
+    /// Named type with arguments (`User`, `Models.Address`, `Tree<'a>`)
+    Named(String, Vec<FSharpFieldType>),
+    /// Type variable (`'T`)
+    TypeParam(String),
+}
+
+const FSHARP_SYNTAX: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("(*", "*)")],
+    string_quotes: &['"'],
+    ident_extra: &['\''],
+    char_literals: false,
+};
+
+/// Keywords that make a type a class, interface, or delegate rather than data
+const CLASS_KEYWORDS: &[&str] = &[
+    "class", "struct", "interface", "abstract", "member", "new", "val", "inherit", "static", "default", "override",
+    "delegate", "let", "do",
+];
+
+/// Analyze the first F# type definition and extract metadata
+pub fn analyze_fsharp_type(source: &str) -> Result<FSharpType, String> {
+    analyze_fsharp_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `type ... and ...` definition in an F# source or signature file
+pub fn analyze_fsharp_types(source: &str) -> Result<Vec<FSharpType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &FSHARP_SYNTAX));
+    let mut types = Vec::new();
+    // `[<Struct>]` seen since the last declaration
+    let mut is_struct = false;
+
+    while !cursor.at_end() {
+        if cursor.is_punct('[') && cursor.is_punct_at(1, '<') {
+            is_struct |= skip_attributes(&mut cursor);
+        } else if cursor.is_ident("type") {
+            let column = cursor.peek().map_or(0, |t| column(source, t.start));
+            cursor.advance();
+            loop {
+                let line = cursor.line();
+                if let Some(declared) = parse_definition(&mut cursor, line, is_struct)? {
+                    types.push(declared);
+                }
+                is_struct = false;
+                skip_members(&mut cursor, source, column);
+                if !cursor.eat_ident("and") {
+                    break;
+                }
+            }
+        } else {
+            is_struct = false;
+            cursor.advance();
+        }
+    }
+
+    Ok(types)
+}
+
+/// Zero-based column of a byte offset
+fn column(source: &str, offset: usize) -> usize {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    source[line_start..offset].chars().count()
+}
+
+fn parse_definition(cursor: &mut Cursor, line: usize, is_struct: bool) -> Result<Option<FSharpType>, String> {
+    let is_struct = skip_attributes(cursor) || is_struct;
+    skip_access(cursor);
+    // ML-style prefix parameters (`'a tree`, `('k, 'v) table`)
+    let mut type_params = parse_prefix_params(cursor)?;
+    let name = cursor.expect_ident()?;
+    if cursor.is_punct('<') {
+        type_params = parse_type_params(cursor)?;
+    }
+
+    let mut declared = FSharpType {
+        name,
+        kind: FSharpTypeKind::Abstract,
+        type_params,
+        is_struct,
+        fields: Vec::new(),
+        cases: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    };
+
+    // Classes with a primary constructor, and type extensions (`type T with ...`)
+    if cursor.is_punct('(') || cursor.is_ident("with") {
+        return Ok(None);
+    }
+    if !cursor.eat_punct('=') {
+        return Ok(Some(declared));
+    }
+
+    skip_attributes(cursor);
+    skip_access(cursor);
+    if cursor.is_punct('{') && !cursor.is_punct_at(1, '|') {
+        declared.kind = FSharpTypeKind::Record;
+        declared.fields = parse_record(cursor)?;
+    } else if cursor.peek().is_some_and(|t| CLASS_KEYWORDS.contains(&t.text.as_str())) {
+        return Ok(None);
+    } else if is_case_start(cursor) {
+        declared.cases = parse_cases(cursor)?;
+        declared.kind = if declared.cases.iter().any(|case| case.value.is_some()) {
+            FSharpTypeKind::Enum
+        } else {
+            FSharpTypeKind::Union
+        };
+    } else {
+        declared.kind = FSharpTypeKind::Abbreviation;
+        declared.alias = Some(parse_type(cursor)?);
+    }
+
+    Ok(Some(declared))
+}
+
+/// Skip `[<...>]` attribute lists, reporting whether one of them was `Struct`
+fn skip_attributes(cursor: &mut Cursor) -> bool {
+    let mut is_struct = false;
+    while cursor.is_punct('[') && cursor.is_punct_at(1, '<') {
+        let mut probe = cursor.clone();
+        cursor.skip_balanced('[', ']');
+        while probe.peek().is_some_and(|t| t.start < cursor.peek().map_or(usize::MAX, |end| end.start)) {
+            if probe.is_ident("Struct") || probe.is_ident("StructAttribute") {
+                is_struct = true;
+            }
+            probe.advance();
+        }
+    }
+    is_struct
+}
+
+fn skip_access(cursor: &mut Cursor) {
+    while cursor.eat_ident("private") || cursor.eat_ident("internal") || cursor.eat_ident("public") {}
+}
+
+/// Skip members and `with ... end` blocks until the next token at or left of
+/// the `type` keyword's column, or the next `and` / `type`
+fn skip_members(cursor: &mut Cursor, source: &str, type_column: usize) {
+    let line = cursor.previous().map_or(0, |t| t.line);
+    while let Some(token) = cursor.peek() {
+        let dedented = token.line > line && column(source, token.start) <= type_column;
+        if dedented || (token.kind == TokenKind::Ident && matches!(token.text.as_str(), "and" | "type")) {
+            return;
+        }
+        cursor.advance();
+    }
+}
+
+fn parse_prefix_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut params = Vec::new();
+    if cursor.is_punct('(') && cursor.is_punct_at(1, '\'') {
+        cursor.advance();
+        loop {
+            params.push(parse_type_var(cursor)?);
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        cursor.expect_punct(')')?;
+    } else if cursor.is_punct('\'') {
+        params.push(parse_type_var(cursor)?);
+    }
+    Ok(params)
+}
+
+/// `<'T, 'E when 'E :> exn>`; constraints are skipped
+fn parse_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    cursor.expect_punct('<')?;
+    let mut params = Vec::new();
+    let mut depth = 0;
+    loop {
+        if depth == 0 && (cursor.is_punct('\'') || cursor.is_punct('^')) && cursor.previous().is_some_and(|t| t.text == "<" || t.text == ",") {
+            params.push(parse_type_var(cursor)?);
+            continue;
+        }
+        match cursor.advance() {
+            // Subtype constraints (`'T :> IDisposable`) don't close the list
+            Some(token) if token.text == ":" => {
+                cursor.eat_punct('>');
+            }
+            Some(token) if token.text == "<" => depth += 1,
+            Some(token) if token.text == ">" => {
+                if depth == 0 {
+                    return Ok(params);
+                }
+                depth -= 1;
+            }
+            Some(_) => {}
+            None => return Err(cursor.unexpected("`>`")),
+        }
+    }
+}
+
+fn parse_type_var(cursor: &mut Cursor) -> Result<String, String> {
+    if !cursor.eat_punct('^') {
+        cursor.expect_punct('\'')?;
+    }
+    cursor.expect_ident()
+}
+
+/// A leading `|`, or a capitalized case name followed by `of`, `|`, or `=`
+fn is_case_start(cursor: &Cursor) -> bool {
+    cursor.is_punct('|')
+        || (cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident && t.text.starts_with(|c: char| c.is_uppercase()))
+            && (cursor.peek_at(1).is_some_and(|t| t.text == "of") || cursor.is_punct_at(1, '|') || cursor.is_punct_at(1, '=')))
+}
+
+fn parse_record(cursor: &mut Cursor) -> Result<Vec<FSharpField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        skip_attributes(cursor);
+        let mutable = cursor.eat_ident("mutable");
+        let name = cursor.expect_ident()?;
+        cursor.expect_punct(':')?;
+        let field_type = parse_type(cursor)?;
+        fields.push(FSharpField { name, field_type, mutable });
+        // Fields are separated by `;` or by line breaks
+        let line = cursor.previous().map_or(0, |t| t.line);
+        if !cursor.eat_punct(';') && !cursor.is_punct('}') && cursor.peek().is_some_and(|t| t.line == line) {
+            return Err(cursor.unexpected("`;` or `}`"));
+        }
+    }
+    Ok(fields)
+}
+
+fn parse_cases(cursor: &mut Cursor) -> Result<Vec<FSharpCase>, String> {
+    let mut cases = Vec::new();
+    cursor.eat_punct('|');
+    loop {
+        skip_attributes(cursor);
+        let name = cursor.expect_ident()?;
+        let mut fields = Vec::new();
+        let mut value = None;
+        if cursor.eat_punct('=') {
+            // Everything up to the next case (`-1`, `0x10`, `'a'`)
+            let line = cursor.previous().map_or(0, |t| t.line);
+            let mut text = String::new();
+            while let Some(token) = cursor.peek() {
+                if token.line != line || token.text == "|" {
+                    break;
+                }
+                text.push_str(&token.text);
+                cursor.advance();
+            }
+            value = Some(text);
+        } else if cursor.eat_ident("of") {
+            fields = parse_payload(cursor)?;
+        }
+        cases.push(FSharpCase { name, fields, value });
+        if !cursor.eat_punct('|') {
+            break;
+        }
+    }
+    Ok(cases)
+}
+
+/// Case payload: `int * string`, or labeled `width: float * height: float`
+fn parse_payload(cursor: &mut Cursor) -> Result<Vec<FSharpField>, String> {
+    let mut members = Vec::new();
+    loop {
+        let label = if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(1, ':') {
+            let label = cursor.expect_ident()?;
+            cursor.advance();
+            Some(label)
+        } else {
+            None
+        };
+        members.push((label, parse_application(cursor)?));
+        if !cursor.eat_punct('*') {
+            break;
+        }
+    }
+
+    let single = members.len() == 1;
+    Ok(members
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, field_type))| {
+            let name = label.unwrap_or_else(|| if single { "Item".to_string() } else { format!("Item{}", i + 1) });
+            FSharpField { name, field_type, mutable: false }
+        })
+        .collect())
+}
+
+/// Type expression: functions (`a -> b`) over tuples (`a * b`) of postfix applications (`int list`)
+fn parse_type(cursor: &mut Cursor) -> Result<FSharpFieldType, String> {
+    let mut members = vec![parse_application(cursor)?];
+    while cursor.eat_punct('*') {
+        members.push(parse_application(cursor)?);
+    }
+    let domain = if members.len() == 1 {
+        members.remove(0)
+    } else {
+        FSharpFieldType::Tuple(members)
+    };
+    if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+        cursor.advance();
+        cursor.advance();
+        let range = parse_type(cursor)?;
+        return Ok(FSharpFieldType::Function(Box::new(domain), Box::new(range)));
+    }
+    Ok(domain)
+}
+
+fn parse_application(cursor: &mut Cursor) -> Result<FSharpFieldType, String> {
+    cursor.eat_ident("struct");
+    let mut args = if cursor.eat_punct('(') {
+        let mut args = vec![parse_type(cursor)?];
+        while cursor.eat_punct(',') {
+            args.push(parse_type(cursor)?);
+        }
+        cursor.expect_punct(')')?;
+        args
+    } else {
+        vec![parse_atom(cursor)?]
+    };
+
+    loop {
+        let line = cursor.previous().map_or(0, |t| t.line);
+        let same_line = cursor.peek().is_some_and(|t| t.line == line);
+        if !same_line {
+            break;
+        }
+        if cursor.is_punct('[') && (cursor.is_punct_at(1, ']') || cursor.is_punct_at(1, ',')) {
+            // `T[]`, or `T[,]` for rectangular arrays, which travel as nested ones
+            cursor.advance();
+            let mut rank = 1;
+            while cursor.eat_punct(',') {
+                rank += 1;
+            }
+            cursor.expect_punct(']')?;
+            let mut element = single(args, cursor.line())?;
+            for _ in 0..rank {
+                element = FSharpFieldType::Array(Box::new(element));
+            }
+            args = vec![element];
+        } else if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident && !is_keyword(&t.text)) && !cursor.is_punct_at(1, ':') {
+            // Each following type constructor applies to everything before it
+            let constructor = parse_path(cursor)?;
+            args = vec![apply(constructor, std::mem::take(&mut args))];
+        } else {
+            break;
+        }
+    }
+
+    single(args, cursor.line())
+}
+
+fn single(mut args: Vec<FSharpFieldType>, line: usize) -> Result<FSharpFieldType, String> {
+    match args.len() {
+        1 => Ok(args.remove(0)),
+        _ => Err(format!("line {}: type argument list is missing its constructor", line)),
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "and" | "of" | "type" | "with" | "when" | "let" | "member" | "module" | "open" | "namespace" | "exception" | "interface" | "end" | "mutable" | "static" | "override" | "default" | "abstract" | "val" | "inherit" | "do")
+}
+
+fn parse_atom(cursor: &mut Cursor) -> Result<FSharpFieldType, String> {
+    if cursor.is_punct('\'') || cursor.is_punct('^') {
+        return Ok(FSharpFieldType::TypeParam(parse_type_var(cursor)?));
+    }
+    // Anonymous records (`{| Id: int |}`) have no name to bind to
+    if cursor.is_punct('{') {
+        cursor.skip_balanced('{', '}');
+        return Ok(FSharpFieldType::Obj);
+    }
+    // Flexible types (`#seq<int>`) accept any subtype
+    cursor.eat_punct('#');
+    let path = parse_path(cursor)?;
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+    Ok(apply(path, args))
+}
+
+/// Namespace or module path ending in a type name (`System.Guid`)
+fn parse_path(cursor: &mut Cursor) -> Result<String, String> {
+    let mut path = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        path = format!("{}.{}", path, cursor.expect_ident()?);
+    }
+    Ok(path)
+}
+
+fn apply(constructor: String, mut args: Vec<FSharpFieldType>) -> FSharpFieldType {
+    let simple = constructor.rsplit('.').next().unwrap_or(&constructor);
+    match (simple, args.len()) {
+        ("sbyte" | "int8" | "SByte", 0) => FSharpFieldType::SByte,
+        ("byte" | "uint8" | "Byte", 0) => FSharpFieldType::Byte,
+        ("int16" | "Int16", 0) => FSharpFieldType::Int16,
+        ("uint16" | "UInt16", 0) => FSharpFieldType::UInt16,
+        ("int" | "int32" | "Int32", 0) => FSharpFieldType::Int32,
+        ("uint" | "uint32" | "UInt32", 0) => FSharpFieldType::UInt32,
+        ("int64" | "Int64", 0) => FSharpFieldType::Int64,
+        ("uint64" | "UInt64", 0) => FSharpFieldType::UInt64,
+        ("nativeint" | "IntPtr", 0) => FSharpFieldType::NativeInt,
+        ("unativeint" | "UIntPtr", 0) => FSharpFieldType::UNativeInt,
+        ("float32" | "single" | "Single", 0) => FSharpFieldType::Float32,
+        ("float" | "double" | "Double", 0) => FSharpFieldType::Float64,
+        ("decimal" | "Decimal", 0) => FSharpFieldType::Decimal,
+        ("bigint" | "BigInteger", 0) => FSharpFieldType::BigInt,
+        ("bool" | "Boolean", 0) => FSharpFieldType::Bool,
+        ("char" | "Char", 0) => FSharpFieldType::Char,
+        ("string" | "String", 0) => FSharpFieldType::String,
+        ("unit", 0) => FSharpFieldType::Unit,
+        ("obj" | "Object" | "_", 0) => FSharpFieldType::Obj,
+        ("list" | "List", 1) => FSharpFieldType::List(Box::new(args.remove(0))),
+        ("array" | "Array" | "ResizeArray", 1) => FSharpFieldType::Array(Box::new(args.remove(0))),
+        ("seq" | "Seq" | "IEnumerable", 1) => FSharpFieldType::Seq(Box::new(args.remove(0))),
+        ("Set" | "set" | "HashSet", 1) => FSharpFieldType::Set(Box::new(args.remove(0))),
+        ("option" | "Option" | "voption" | "ValueOption", 1) => FSharpFieldType::Option(Box::new(args.remove(0))),
+        ("Result" | "result", 2) => {
+            let ok = args.remove(0);
+            FSharpFieldType::Result(Box::new(ok), Box::new(args.remove(0)))
+        }
+        ("Map" | "Dictionary" | "IDictionary" | "IReadOnlyDictionary", 2) => {
+            let key = args.remove(0);
+            FSharpFieldType::Map(Box::new(key), Box::new(args.remove(0)))
+        }
+        _ => FSharpFieldType::Named(constructor, args),
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(fsharp_type: &FSharpType, target: &str) -> f32 {
+    average_fidelity(ir_fields(fsharp_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an F# field type into the language-neutral IR
+pub fn to_ir(field_type: &FSharpFieldType) -> IrType {
+    match field_type {
+        FSharpFieldType::SByte | FSharpFieldType::Int16 | FSharpFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        FSharpFieldType::Byte | FSharpFieldType::UInt16 | FSharpFieldType::UInt32 => IrType::Primitive(PrimitiveType::U32),
+        FSharpFieldType::Int64 | FSharpFieldType::NativeInt => IrType::Primitive(PrimitiveType::I64),
+        FSharpFieldType::UInt64 | FSharpFieldType::UNativeInt => IrType::Primitive(PrimitiveType::U64),
+        FSharpFieldType::Float32 => IrType::Primitive(PrimitiveType::F32),
+        FSharpFieldType::Float64 => IrType::Primitive(PrimitiveType::F64),
+        FSharpFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        FSharpFieldType::Char | FSharpFieldType::String => IrType::Primitive(PrimitiveType::String),
+        FSharpFieldType::Unit => IrType::Primitive(PrimitiveType::Unit),
+        FSharpFieldType::Decimal => IrType::Primitive(PrimitiveType::Decimal),
+        FSharpFieldType::BigInt => IrType::Primitive(PrimitiveType::BigInt),
+        // No IR form yet for functions
+        FSharpFieldType::Obj | FSharpFieldType::Function(..) => IrType::Any,
+        FSharpFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        FSharpFieldType::Tuple(members) => IrType::Tuple(members.iter().map(to_ir).collect()),
+        FSharpFieldType::List(inner)
+        | FSharpFieldType::Array(inner)
+        | FSharpFieldType::Seq(inner)
+        | FSharpFieldType::Set(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        FSharpFieldType::Option(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        FSharpFieldType::Result(ok, err) => {
+            IrType::Container(ContainerType::Result(Box::new(to_ir(ok)), Box::new(to_ir(err))))
+        }
+        FSharpFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        FSharpFieldType::TypeParam(name) => IrType::TypeParam(pascal_case(name)),
+    }
+}
+
+/// Lower record fields, or the payload fields of every case, into the IR
+pub fn ir_fields(fsharp_type: &FSharpType) -> Vec<IrField> {
+    let record = fsharp_type.fields.iter().map(|field| (field.name.clone(), field));
+    let payloads = fsharp_type.cases.iter().flat_map(|case| {
+        case.fields
+            .iter()
+            .map(move |field| (format!("{}.{}", case.name, field.name), field))
+    });
+    record
+        .chain(payloads)
+        .map(|(name, field)| IrField::new(&name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map F# types to target language types
+pub fn map_to_target(field_type: &FSharpFieldType, target: &str) -> String {
+    match target {
+        "rust" => rust_type(field_type),
+        "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Rust spelling of a field type, keeping type arguments and the ordered
+/// collections the IR flattens
+fn rust_type(field_type: &FSharpFieldType) -> String {
+    let list = |members: &[FSharpFieldType]| members.iter().map(rust_type).collect::<Vec<_>>().join(", ");
+    match field_type {
+        FSharpFieldType::List(inner) | FSharpFieldType::Array(inner) | FSharpFieldType::Seq(inner) => {
+            format!("Vec<{}>", rust_type(inner))
+        }
+        FSharpFieldType::Set(inner) => format!("std::collections::BTreeSet<{}>", rust_type(inner)),
+        FSharpFieldType::Map(key, value) => {
+            format!("std::collections::BTreeMap<{}, {}>", rust_type(key), rust_type(value))
+        }
+        FSharpFieldType::Option(inner) => format!("Option<{}>", rust_type(inner)),
+        FSharpFieldType::Result(ok, err) => format!("Result<{}, {}>", rust_type(ok), rust_type(err)),
+        FSharpFieldType::Tuple(members) => format!("({})", list(members)),
+        FSharpFieldType::Char => "char".to_string(),
+        FSharpFieldType::Named(name, args) if !args.is_empty() => {
+            format!("{}<{}>", name.rsplit('.').next().unwrap_or(name), list(args))
+        }
+        _ => render_type(&to_ir(field_type), "rust"),
+    }
+}
+
+/// Rust definition of an F# type: records become structs, unions become enums
+/// with tuple or struct variants, and enums keep their discriminants.
+/// Abstract types have no representation to mirror.
+pub fn rust_definition(fsharp_type: &FSharpType) -> Option<String> {
+    let name = &fsharp_type.name;
+    let generics = if fsharp_type.type_params.is_empty() {
+        String::new()
+    } else {
+        let params: Vec<String> = fsharp_type.type_params.iter().map(|param| pascal_case(param)).collect();
+        format!("<{}>", params.join(", "))
+    };
+    // Cases that hold their own type need indirection to have a finite size
+    let field_type = |field: &FSharpField| match &field.field_type {
+        FSharpFieldType::Named(target, _) if target == name => format!("Box<{}>", rust_type(&field.field_type)),
+        other => rust_type(other),
+    };
+
+    Some(match fsharp_type.kind {
+        FSharpTypeKind::Record => {
+            let mut code = format!("#[derive(Debug, Clone, PartialEq)]\npub struct {}{} {{\n", name, generics);
+            for field in &fsharp_type.fields {
+                code.push_str(&format!("    pub {}: {},\n", snake_case(&field.name), field_type(field)));
+            }
+            code.push_str("}\n");
+            code
+        }
+        FSharpTypeKind::Union => {
+            let mut code = format!("#[derive(Debug, Clone, PartialEq)]\npub enum {}{} {{\n", name, generics);
+            for case in &fsharp_type.cases {
+                let positional = case.fields.iter().all(|field| field.name.starts_with("Item"));
+                if case.fields.is_empty() {
+                    code.push_str(&format!("    {},\n", case.name));
+                } else if positional {
+                    let members: Vec<String> = case.fields.iter().map(field_type).collect();
+                    code.push_str(&format!("    {}({}),\n", case.name, members.join(", ")));
+                } else {
+                    let members: Vec<String> = case
+                        .fields
+                        .iter()
+                        .map(|field| format!("{}: {}", snake_case(&field.name), field_type(field)))
+                        .collect();
+                    code.push_str(&format!("    {} {{ {} }},\n", case.name, members.join(", ")));
+                }
+            }
+            code.push_str("}\n");
+            code
+        }
+        FSharpTypeKind::Enum => {
+            let mut code = format!("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n#[repr(i32)]\npub enum {} {{\n", name);
+            for case in &fsharp_type.cases {
+                match case.value.as_deref().and_then(|value| value.parse::<i32>().ok()) {
+                    Some(value) => code.push_str(&format!("    {} = {},\n", case.name, value)),
+                    None => code.push_str(&format!("    {},\n", case.name)),
+                }
+            }
+            code.push_str("}\n");
+            code
+        }
+        FSharpTypeKind::Abbreviation => {
+            format!("pub type {}{} = {};\n", name, generics, rust_type(fsharp_type.alias.as_ref()?))
+        }
+        FSharpTypeKind::Abstract => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rescript_analyzer;
+
+    const SOURCE: &str = r#"
+namespace App.Domain
+
+open System
+
+/// A registered user
+[<CLIMutable>]
+type User =
+    { Id: int
+      Name: string
+      [<JsonPropertyName("email")>] Email: string option
+      mutable Tags: string list }
+    member this.DisplayName = this.Name.ToUpper()
+
+[<Struct>]
+type Point = { X: float; Y: float }
+
+type Shape =
+    | Circle of radius: float
+    | Rectangle of width: float * height: float
+    | Polygon of Point[]
+    | Empty
+
+and Tree<'a> =
+    | Leaf
+    | Node of Tree<'a> * 'a * Tree<'a>
+
+type Color =
+    | Red = 0
+    | Green = 1
+
+type UserId = int64
+type Handler = User -> Result<unit, string>
+
+type Service(name: string) =
+    member _.Name = name
+"#;
+
+    #[test]
+    fn test_analyze_records_and_unions() {
+        let types = analyze_fsharp_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Point", "Shape", "Tree", "Color", "UserId", "Handler"]);
+
+        let user = &types[0];
+        assert_eq!(user.kind, FSharpTypeKind::Record);
+        assert_eq!(user.fields.len(), 4);
+        assert!(user.fields[3].mutable);
+        assert_eq!(user.fields[2].field_type, FSharpFieldType::Option(Box::new(FSharpFieldType::String)));
+        assert!(types[1].is_struct && !user.is_struct);
+
+        let shape = &types[2];
+        assert_eq!(shape.kind, FSharpTypeKind::Union);
+        let fields: Vec<_> = ir_fields(shape).into_iter().map(|f| f.name).collect();
+        assert_eq!(fields, vec!["Circle.radius", "Rectangle.width", "Rectangle.height", "Polygon.Item"]);
+
+        let tree = &types[3];
+        assert_eq!(tree.type_params, vec!["a"]);
+        assert_eq!(tree.cases[1].fields[2].name, "Item3");
+        assert_eq!(types[4].kind, FSharpTypeKind::Enum);
+        assert_eq!(map_to_target(types[6].alias.as_ref().unwrap(), "typescript"), "unknown");
+    }
+
+    #[test]
+    fn test_rust_definitions() {
+        let types = analyze_fsharp_types(SOURCE).unwrap();
+        assert_eq!(
+            rust_definition(&types[0]).unwrap(),
+            "#[derive(Debug, Clone, PartialEq)]\npub struct User {\n    pub id: i32,\n    pub name: String,\n    \
+             pub email: Option<String>,\n    pub tags: Vec<String>,\n}\n"
+        );
+        assert_eq!(
+            rust_definition(&types[2]).unwrap(),
+            "#[derive(Debug, Clone, PartialEq)]\npub enum Shape {\n    Circle { radius: f64 },\n    \
+             Rectangle { width: f64, height: f64 },\n    Polygon(Vec<Point>),\n    Empty,\n}\n"
+        );
+        assert!(rust_definition(&types[3]).unwrap().contains("    Node(Box<Tree<A>>, A, Box<Tree<A>>),\n"));
+        assert!(rust_definition(&types[4]).unwrap().contains("#[repr(i32)]\npub enum Color {\n    Red = 0,\n"));
+        assert_eq!(rust_definition(&types[5]).unwrap(), "pub type UserId = i64;\n");
+    }
+
+    #[test]
+    fn test_score_against_rescript() {
+        // `int` is 32-bit on both sides and records line up field for field
+        let fsharp = analyze_fsharp_type("type User = { Id: int; Name: string; Email: string; Active: bool }").unwrap();
+        assert_eq!(compatibility_score(&fsharp, "rescript"), 1.0);
+        let rescript = rescript_analyzer::analyze_rescript_type("type user = { id: int, name: string, email: string, active: bool }").unwrap();
+        let score = ir::compare_fields(&ir_fields(&fsharp), &rescript_analyzer::ir_fields(&rescript));
+        assert_eq!(score, 1.0);
+        assert_eq!(crate::calculate_transport_class("fsharp", "rescript"), crate::TransportClass::Concorde);
+
+        // ...but 64-bit integers and decimals are not
+        let ledger = analyze_fsharp_type("type Entry = { Id: int64; Amount: decimal }").unwrap();
+        assert!(compatibility_score(&ledger, "rescript") < 1.0);
+    }
+}
@@ -0,0 +1,619 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Java analyzer for protocol-squisher integration
+// Analyzes records and getter-based POJOs, the shapes Jackson serializes
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Java has character literals, so `'{'` must not count as a brace
+const JAVA: Syntax = Syntax { string_quotes: &['"', '\''], ..Syntax::C_LIKE };
+
+/// Java record, class, or interface
+#[derive(Debug, Clone)]
+pub struct JavaType {
+    /// Simple name; nested types are spelled `Outer.Inner`
+    pub name: String,
+    pub package: Option<String>,
+    pub kind: JavaTypeKind,
+    pub type_params: Vec<String>,
+    /// Record components, or the bean properties of a class: public fields and
+    /// `getX()`/`isX()` getters, in declaration order
+    pub fields: Vec<JavaField>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaTypeKind {
+    Record,
+    Class,
+    Interface,
+}
+
+#[derive(Debug, Clone)]
+pub struct JavaField {
+    pub name: String,
+    pub field_type: JavaFieldType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JavaFieldType {
+    Byte,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+    Boolean,
+    Char,
+    /// `Integer`, `Long`, `Boolean`, ...: the primitive, but nullable
+    Boxed(Box<JavaFieldType>),
+    String,
+    Object,
+    BigInteger,
+    BigDecimal,
+    /// Reference type annotated `@Nullable`
+    Nullable(Box<JavaFieldType>),
+    /// `Optional<T>`, `OptionalInt`, ...
+    Optional(Box<JavaFieldType>),
+    /// Arrays, `List<T>`, `Set<T>`, `Collection<T>`
+    List(Box<JavaFieldType>),
+    Map(Box<JavaFieldType>, Box<JavaFieldType>),
+    /// User or library type, with its type arguments
+    Named(String, Vec<JavaFieldType>),
+    TypeParam(String),
+}
+
+const MODIFIERS: &[&str] = &[
+    "public", "private", "protected", "static", "final", "abstract", "transient", "volatile", "synchronized",
+    "native", "strictfp", "default", "sealed", "non",
+];
+
+/// A member's modifiers and annotations
+#[derive(Default)]
+struct Modifiers {
+    words: Vec<String>,
+    nullable: bool,
+    non_null: bool,
+}
+
+impl Modifiers {
+    fn has(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+}
+
+/// Analyze the first Java type and extract metadata
+pub fn analyze_java_type(source: &str) -> Result<JavaType, String> {
+    analyze_java_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every record, class, and interface in a Java file, nested types included
+pub fn analyze_java_types(source: &str) -> Result<Vec<JavaType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &JAVA));
+    let mut types = Vec::new();
+    let mut package = None;
+
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "package" => {
+                cursor.advance();
+                package = Some(parse_dotted_name(&mut cursor)?);
+                cursor.expect_punct(';')?;
+            }
+            ";" | "}" => {
+                cursor.advance();
+            }
+            _ => {
+                parse_modifiers(&mut cursor);
+                if type_keyword(&cursor).is_some() {
+                    parse_type_declaration(&mut cursor, &package, "", &mut types)?;
+                } else {
+                    // Imports, enums, and annotation types
+                    skip_statement(&mut cursor);
+                }
+            }
+        }
+    }
+
+    Ok(types)
+}
+
+fn parse_dotted_name(cursor: &mut Cursor) -> Result<String, String> {
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+/// `@Name` or `@Name(...)`; returns the simple name
+fn parse_annotation(cursor: &mut Cursor) -> Option<String> {
+    // `@interface` declares an annotation type
+    if !cursor.is_punct('@') || cursor.peek_at(1).is_some_and(|t| t.text == "interface") {
+        return None;
+    }
+    cursor.advance();
+    let name = parse_dotted_name(cursor).ok()?;
+    if cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+    }
+    Some(name.rsplit('.').next().unwrap_or(&name).to_string())
+}
+
+fn parse_modifiers(cursor: &mut Cursor) -> Modifiers {
+    let mut modifiers = Modifiers::default();
+    loop {
+        if let Some(annotation) = parse_annotation(cursor) {
+            match annotation.as_str() {
+                "Nullable" | "CheckForNull" => modifiers.nullable = true,
+                "NonNull" | "Nonnull" | "NotNull" => modifiers.non_null = true,
+                _ => {}
+            }
+        } else if let Some(word) = cursor.peek().filter(|t| MODIFIERS.contains(&t.text.as_str())).map(|t| t.text.clone()) {
+            cursor.advance();
+            // `non-sealed`
+            if word == "non" && cursor.eat_punct('-') {
+                cursor.eat_ident("sealed");
+            }
+            modifiers.words.push(word);
+        } else {
+            return modifiers;
+        }
+    }
+}
+
+fn type_keyword(cursor: &Cursor) -> Option<JavaTypeKind> {
+    Some(match cursor.peek()?.text.as_str() {
+        "record" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) => JavaTypeKind::Record,
+        "class" => JavaTypeKind::Class,
+        "interface" => JavaTypeKind::Interface,
+        _ => return None,
+    })
+}
+
+/// A type declaration starting at its keyword; pushes it and any nested types
+fn parse_type_declaration(
+    cursor: &mut Cursor,
+    package: &Option<String>,
+    outer: &str,
+    types: &mut Vec<JavaType>,
+) -> Result<(), String> {
+    let line = cursor.line();
+    let kind = type_keyword(cursor).ok_or_else(|| cursor.unexpected("type declaration"))?;
+    cursor.advance();
+    let name = cursor.expect_ident()?;
+    let name = if outer.is_empty() { name } else { format!("{}.{}", outer, name) };
+
+    let mut type_params = Vec::new();
+    if cursor.eat_punct('<') {
+        loop {
+            type_params.push(cursor.expect_ident()?);
+            // Bounds: `T extends Comparable<T>`
+            let mut depth = 0usize;
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    "," | ">" if depth == 0 => break,
+                    "<" => depth += 1,
+                    ">" => depth -= 1,
+                    _ => {}
+                }
+                cursor.advance();
+            }
+            if !cursor.eat_punct(',') {
+                cursor.expect_punct('>')?;
+                break;
+            }
+        }
+    }
+
+    let mut properties = Vec::new();
+    if kind == JavaTypeKind::Record {
+        properties = parse_record_components(cursor, &type_params)?;
+    }
+
+    // `extends` and `implements` clauses
+    while !cursor.at_end() && !cursor.is_punct('{') {
+        cursor.advance();
+    }
+
+    let index = types.len();
+    types.push(JavaType {
+        name: name.clone(),
+        package: package.clone(),
+        kind,
+        type_params: type_params.clone(),
+        fields: Vec::new(),
+        location: format!("line {}", line),
+    });
+
+    let mut fields: Vec<(JavaField, bool)> = Vec::new();
+    let mut getters: Vec<JavaField> = Vec::new();
+    cursor.expect_punct('{')?;
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        parse_member(cursor, kind, &type_params, package, &name, types, &mut fields, &mut getters)?;
+    }
+
+    // Bean properties: fields exposed through a getter or declared public, then getter-only properties
+    for (field, public) in fields {
+        if public || getters.iter().any(|getter| getter.name == field.name) {
+            properties.push(field);
+        }
+    }
+    for getter in getters {
+        if !properties.iter().any(|property| property.name == getter.name) {
+            properties.push(getter);
+        }
+    }
+    types[index].fields = properties;
+    Ok(())
+}
+
+/// `(long id, @Nullable String email)` of a record header
+fn parse_record_components(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<JavaField>, String> {
+    cursor.expect_punct('(')?;
+    let mut components = Vec::new();
+    while !cursor.eat_punct(')') {
+        let modifiers = parse_modifiers(cursor);
+        let field_type = annotated(parse_type(cursor, type_params)?, &modifiers);
+        let name = cursor.expect_ident()?;
+        components.push(JavaField { name, field_type });
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    Ok(components)
+}
+
+/// Apply nullness annotations to a member's type
+fn annotated(field_type: JavaFieldType, modifiers: &Modifiers) -> JavaFieldType {
+    match field_type {
+        JavaFieldType::Boxed(primitive) if modifiers.non_null => *primitive,
+        JavaFieldType::Nullable(inner) if modifiers.non_null => *inner,
+        // Primitives and `Optional` are never null
+        field_type @ (JavaFieldType::Optional(_) | JavaFieldType::Boxed(_) | JavaFieldType::Nullable(_)) => field_type,
+        field_type if modifiers.nullable && !is_primitive(&field_type) => JavaFieldType::Nullable(Box::new(field_type)),
+        field_type => field_type,
+    }
+}
+
+fn is_primitive(field_type: &JavaFieldType) -> bool {
+    matches!(
+        field_type,
+        JavaFieldType::Byte
+            | JavaFieldType::Short
+            | JavaFieldType::Int
+            | JavaFieldType::Long
+            | JavaFieldType::Float
+            | JavaFieldType::Double
+            | JavaFieldType::Boolean
+            | JavaFieldType::Char
+    )
+}
+
+/// One member of a class or interface body; collects instance fields (with whether
+/// they're public) and getters
+#[allow(clippy::too_many_arguments)]
+fn parse_member(
+    cursor: &mut Cursor,
+    kind: JavaTypeKind,
+    type_params: &[String],
+    package: &Option<String>,
+    outer: &str,
+    types: &mut Vec<JavaType>,
+    fields: &mut Vec<(JavaField, bool)>,
+    getters: &mut Vec<JavaField>,
+) -> Result<(), String> {
+    let modifiers = parse_modifiers(cursor);
+    if type_keyword(cursor).is_some() {
+        return parse_type_declaration(cursor, package, outer, types);
+    }
+    match cursor.peek().map(|t| t.text.as_str()) {
+        Some(";") => {
+            cursor.advance();
+            return Ok(());
+        }
+        // Initializer blocks, generic methods, enums
+        Some("{" | "<" | "enum") | None => {
+            skip_statement(cursor);
+            return Ok(());
+        }
+        Some("}") => return Ok(()),
+        _ => {}
+    }
+
+    let returns_void = cursor.is_ident("void");
+    if returns_void {
+        skip_statement(cursor);
+        return Ok(());
+    }
+    let Ok(field_type) = parse_type(cursor, type_params) else {
+        skip_statement(cursor);
+        return Ok(());
+    };
+    let field_type = annotated(field_type, &modifiers);
+    let Some(name) = cursor.peek().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.clone()) else {
+        // Constructors
+        skip_statement(cursor);
+        return Ok(());
+    };
+    cursor.advance();
+
+    let is_static = modifiers.has("static");
+    if cursor.is_punct('(') {
+        let no_params = cursor.is_punct_at(1, ')');
+        // Interface methods are implicitly public
+        let public = modifiers.has("public") || kind == JavaTypeKind::Interface;
+        if no_params && public && !is_static {
+            if let Some(property) = getter_property(&name, &field_type) {
+                getters.push(JavaField { name: property, field_type });
+            }
+        }
+        skip_statement(cursor);
+        return Ok(());
+    }
+
+    // `int a = 1, b[];`
+    let mut name = name;
+    loop {
+        let mut ty = field_type.clone();
+        while cursor.is_punct('[') && cursor.is_punct_at(1, ']') {
+            cursor.advance();
+            cursor.advance();
+            ty = JavaFieldType::List(Box::new(ty));
+        }
+        if cursor.eat_punct('=') {
+            let mut depth = 0usize;
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    _ if token.kind != TokenKind::Punct => {}
+                    "," | ";" if depth == 0 => break,
+                    "(" | "{" | "[" => depth += 1,
+                    ")" | "}" | "]" => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                cursor.advance();
+            }
+        }
+        if !is_static && !modifiers.has("transient") {
+            fields.push((JavaField { name: name.clone(), field_type: ty }, modifiers.has("public")));
+        }
+        if !cursor.eat_punct(',') {
+            break;
+        }
+        name = cursor.expect_ident()?;
+    }
+    cursor.expect_punct(';')
+}
+
+/// `getFirstName` → `firstName`; `isActive` → `active` for booleans only
+fn getter_property(method: &str, field_type: &JavaFieldType) -> Option<String> {
+    let is_boolean = matches!(field_type, JavaFieldType::Boolean);
+    let rest = method
+        .strip_prefix("get")
+        .or_else(|| method.strip_prefix("is").filter(|_| is_boolean))?;
+    let mut chars = rest.chars();
+    let first = chars.next().filter(|c| c.is_uppercase())?;
+    // `java.beans.Introspector.decapitalize`: `getURL` → `URL`
+    if chars.next().is_some_and(char::is_uppercase) {
+        return Some(rest.to_string());
+    }
+    Some(first.to_lowercase().chain(rest.chars().skip(1)).collect())
+}
+
+/// A type with its array suffixes: `List<String>`, `int[]`, `@Nullable String`
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<JavaFieldType, String> {
+    let modifiers = parse_modifiers(cursor);
+    let mut ty = annotated(parse_base_type(cursor, type_params)?, &modifiers);
+    while cursor.is_punct('[') && cursor.is_punct_at(1, ']') {
+        cursor.advance();
+        cursor.advance();
+        ty = JavaFieldType::List(Box::new(ty));
+    }
+    Ok(ty)
+}
+
+fn parse_base_type(cursor: &mut Cursor, type_params: &[String]) -> Result<JavaFieldType, String> {
+    // Wildcards: `? extends Number` reads as its bound
+    if cursor.eat_punct('?') {
+        if cursor.eat_ident("extends") || cursor.eat_ident("super") {
+            return parse_type(cursor, type_params);
+        }
+        return Ok(JavaFieldType::Object);
+    }
+    if cursor.is_ident("void") {
+        return Err(cursor.unexpected("field type"));
+    }
+    let name = parse_dotted_name(cursor)?;
+    let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    let primitive = |simple: &str| match simple {
+        "byte" | "Byte" => Some(JavaFieldType::Byte),
+        "short" | "Short" => Some(JavaFieldType::Short),
+        "int" | "Integer" => Some(JavaFieldType::Int),
+        "long" | "Long" => Some(JavaFieldType::Long),
+        "float" | "Float" => Some(JavaFieldType::Float),
+        "double" | "Double" => Some(JavaFieldType::Double),
+        "boolean" | "Boolean" => Some(JavaFieldType::Boolean),
+        "char" | "Character" => Some(JavaFieldType::Char),
+        _ => None,
+    };
+    if let Some(primitive) = primitive(&simple) {
+        let boxed = simple.starts_with(char::is_uppercase);
+        return Ok(if boxed { JavaFieldType::Boxed(Box::new(primitive)) } else { primitive });
+    }
+
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(JavaFieldType::Object));
+    Ok(match simple.as_str() {
+        "String" | "CharSequence" => JavaFieldType::String,
+        "Object" => JavaFieldType::Object,
+        "BigInteger" => JavaFieldType::BigInteger,
+        "BigDecimal" => JavaFieldType::BigDecimal,
+        "Optional" => JavaFieldType::Optional(next()),
+        "OptionalInt" => JavaFieldType::Optional(Box::new(JavaFieldType::Int)),
+        "OptionalLong" => JavaFieldType::Optional(Box::new(JavaFieldType::Long)),
+        "OptionalDouble" => JavaFieldType::Optional(Box::new(JavaFieldType::Double)),
+        "List" | "ArrayList" | "LinkedList" | "Collection" | "Iterable" | "Set" | "HashSet" | "LinkedHashSet"
+        | "TreeSet" | "SortedSet" | "Deque" | "ArrayDeque" | "Queue" => JavaFieldType::List(next()),
+        "Map" | "HashMap" | "LinkedHashMap" | "TreeMap" | "SortedMap" | "ConcurrentHashMap" => {
+            let key = next();
+            JavaFieldType::Map(key, next())
+        }
+        _ if type_params.contains(&name) => JavaFieldType::TypeParam(name),
+        _ => JavaFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(java_type: &JavaType, target: &str) -> f32 {
+    average_fidelity(ir_fields(java_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Java field type into the language-neutral IR
+pub fn to_ir(field_type: &JavaFieldType) -> IrType {
+    match field_type {
+        JavaFieldType::Byte | JavaFieldType::Short | JavaFieldType::Int => IrType::Primitive(PrimitiveType::I32),
+        JavaFieldType::Long => IrType::Primitive(PrimitiveType::I64),
+        JavaFieldType::Float => IrType::Primitive(PrimitiveType::F32),
+        JavaFieldType::Double => IrType::Primitive(PrimitiveType::F64),
+        JavaFieldType::Boolean => IrType::Primitive(PrimitiveType::Bool),
+        // A UTF-16 code unit travels as a one-character string
+        JavaFieldType::Char | JavaFieldType::String => IrType::Primitive(PrimitiveType::String),
+        JavaFieldType::BigInteger => IrType::Primitive(PrimitiveType::BigInt),
+        JavaFieldType::BigDecimal => IrType::Primitive(PrimitiveType::Decimal),
+        JavaFieldType::Object => IrType::Any,
+        JavaFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        // An `Optional` never holds null, so the value it boxes is there when it is
+        JavaFieldType::Optional(inner) => match inner.as_ref() {
+            JavaFieldType::Boxed(value) => IrType::Container(ContainerType::Option(Box::new(to_ir(value)))),
+            inner => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        },
+        JavaFieldType::Boxed(inner) | JavaFieldType::Nullable(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        JavaFieldType::List(element) => IrType::Container(ContainerType::Vec(Box::new(to_ir(element)))),
+        JavaFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        JavaFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower all properties of a Java type into the IR
+pub fn ir_fields(java_type: &JavaType) -> Vec<IrField> {
+    java_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Java types to target language types
+pub fn map_to_target(field_type: &JavaFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+package com.example.model;
+
+import java.util.*;
+import javax.annotation.Nullable;
+
+public record User(long id, String name, Optional<String> email, List<Integer> scores) implements Entity {
+    public User {
+        Objects.requireNonNull(name);
+    }
+}
+
+public class Order<T extends Item> {
+    private static final long serialVersionUID = 1L;
+    private final long id;
+    private @Nullable String note;
+    private List<T> items = new ArrayList<>();
+    private transient Object cache;
+    private char separator = '{';
+    public int version;
+
+    public Order(long id) { this.id = id; }
+
+    public long getId() { return id; }
+    public String getNote() { return note; }
+    public List<T> getItems() { return items; }
+    public boolean isEmpty() { return items.isEmpty(); }
+    public Boolean isArchived() { return null; }
+    public void setNote(String note) { this.note = note; }
+    private String getSecret() { return ""; }
+
+    public static class Line {
+        public Integer quantity;
+    }
+}
+"#;
+
+    #[test]
+    fn test_analyze_record_and_pojo() {
+        let types = analyze_java_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Order", "Order.Line"]);
+        assert!(types.iter().all(|t| t.package.as_deref() == Some("com.example.model")));
+
+        let user = &types[0];
+        assert_eq!(user.kind, JavaTypeKind::Record);
+        assert_eq!(user.fields.len(), 4);
+        assert_eq!(
+            user.fields[3].field_type,
+            JavaFieldType::List(Box::new(JavaFieldType::Boxed(Box::new(JavaFieldType::Int))))
+        );
+
+        let order = &types[1];
+        assert_eq!(order.type_params, vec!["T"]);
+        let fields: Vec<_> = order.fields.iter().map(|f| f.name.as_str()).collect();
+        // `isArchived` returns a boxed `Boolean`, which Jackson doesn't treat as a getter
+        assert_eq!(fields, vec!["id", "note", "items", "version", "empty"]);
+        assert_eq!(order.fields[1].field_type, JavaFieldType::Nullable(Box::new(JavaFieldType::String)));
+        assert_eq!(order.fields[2].field_type, JavaFieldType::List(Box::new(JavaFieldType::TypeParam("T".to_string()))));
+    }
+
+    #[test]
+    fn test_score_against_rust_and_gleam() {
+        let types = analyze_java_types(SOURCE).unwrap();
+        let user = &types[0];
+        assert_eq!(map_to_target(&user.fields[2].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&user.fields[3].field_type, "gleam"), "List(Option(Int))");
+        assert_eq!(compatibility_score(user, "rust"), 1.0);
+
+        let line = &types[2];
+        assert_eq!(map_to_target(&line.fields[0].field_type, "rust"), "Option<i32>");
+    }
+
+    #[test]
+    fn test_optionals_are_not_nullable() {
+        let stats = analyze_java_type("public record Stats(Optional<Integer> age, @Nullable Optional<String> nick, OptionalLong total) {}").unwrap();
+        let fields: Vec<_> = ir_fields(&stats).into_iter().map(|field| field.ty).collect();
+        let option = |primitive| IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(primitive))));
+        assert_eq!(fields, vec![option(PrimitiveType::I32), option(PrimitiveType::String), option(PrimitiveType::I64)]);
+    }
+}
@@ -0,0 +1,474 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// JSON Schema analyzer for protocol-squisher integration
+// Treats a schema document as one more type system: the root object and every
+// `$defs`/`definitions` entry become types, and inline object schemas are
+// hoisted into named types of their own
+
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, IrVariant, PrimitiveType};
+use crate::json::{self, Json};
+
+/// Object schema, string enum, or any other named schema
+#[derive(Debug, Clone)]
+pub struct JsonSchemaType {
+    pub name: String,
+    pub kind: JsonSchemaTypeKind,
+    pub fields: Vec<JsonSchemaField>,
+    /// Values of a string `enum`
+    pub values: Vec<String>,
+    /// What a non-object schema describes
+    pub alias: Option<JsonSchemaFieldType>,
+    /// JSON pointer to the schema (`#/$defs/User`)
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonSchemaTypeKind {
+    Object,
+    Enum,
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonSchemaField {
+    pub name: String,
+    pub field_type: JsonSchemaFieldType,
+    /// Listed in the object's `required`
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonSchemaFieldType {
+    String,
+    /// `integer` without a `format` or bounds that narrow it
+    Integer,
+    Int32,
+    UInt32,
+    UInt64,
+    /// `number` (`format: float` narrows it to `Float`)
+    Number,
+    Float,
+    Boolean,
+    Null,
+    /// `{}`, `true`, or a schema without a `type`
+    Any,
+    Array(Box<JsonSchemaFieldType>),
+    /// Object with `additionalProperties` and no `properties`
+    Map(Box<JsonSchemaFieldType>),
+    /// `oneOf`/`anyOf`, or a `type` array
+    Union(Vec<JsonSchemaFieldType>),
+    /// `$ref` or a hoisted inline object, by type name
+    Ref(String),
+}
+
+/// Analyze the first type of a JSON Schema document
+pub fn analyze_json_schema_type(source: &str) -> Result<JsonSchemaType, String> {
+    analyze_json_schema_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze the root schema and every `$defs`/`definitions` entry
+pub fn analyze_json_schema_types(source: &str) -> Result<Vec<JsonSchemaType>, String> {
+    let document = json::parse(source)?;
+    if !matches!(document, Json::Object(_)) {
+        return Err("expected a schema object".to_string());
+    }
+    let root = document.get("title").and_then(Json::as_str).map(pascal_case).unwrap_or_else(|| "Root".to_string());
+    let mut analyzer = Analyzer { root: root.clone(), types: Vec::new() };
+
+    // A bare bundle of definitions has no root type of its own
+    let bundle = document.get("type").is_none() && document.get("properties").is_none();
+    if !bundle {
+        analyzer.define(&root, &document, "#".to_string());
+    }
+    for key in ["$defs", "definitions"] {
+        if let Some(Json::Object(definitions)) = document.get(key) {
+            for (name, schema) in definitions {
+                analyzer.define(&pascal_case(name), schema, format!("#/{}/{}", key, name));
+            }
+        }
+    }
+    Ok(analyzer.types)
+}
+
+/// Define each schema of a named map, such as OpenAPI's `components.schemas`
+/// under `#/components/schemas`; there is no root for `$ref: "#"` to name
+pub(super) fn analyze_schema_map(schemas: &[(String, Json)], pointer: &str) -> Vec<JsonSchemaType> {
+    let mut analyzer = Analyzer { root: String::new(), types: Vec::new() };
+    for (name, schema) in schemas {
+        analyzer.define(&pascal_case(name), schema, format!("{}/{}", pointer, name));
+    }
+    analyzer.types
+}
+
+struct Analyzer {
+    root: String,
+    types: Vec<JsonSchemaType>,
+}
+
+impl Analyzer {
+    /// Add `schema` as the named type `name`
+    fn define(&mut self, name: &str, schema: &Json, location: String) {
+        let mut defined = JsonSchemaType {
+            name: name.to_string(),
+            kind: JsonSchemaTypeKind::Alias,
+            fields: Vec::new(),
+            values: Vec::new(),
+            alias: None,
+            location,
+        };
+        if let Some(values) = string_enum(schema) {
+            defined.kind = JsonSchemaTypeKind::Enum;
+            defined.values = values;
+            self.types.push(defined);
+            return;
+        }
+        if !is_record(schema) {
+            defined.alias = Some(self.field_type(schema, name, &defined.location));
+            self.types.push(defined);
+            return;
+        }
+
+        defined.kind = JsonSchemaTypeKind::Object;
+        let index = self.types.len();
+        self.types.push(defined);
+        let location = self.types[index].location.clone();
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Json::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Json::as_str)
+            .collect();
+        if let Some(Json::Object(properties)) = schema.get("properties") {
+            for (property, property_schema) in properties {
+                let hint = format!("{}{}", name, pascal_case(property));
+                let field_type = self.field_type(property_schema, &hint, &format!("{}/properties/{}", location, property));
+                self.types[index].fields.push(JsonSchemaField {
+                    name: property.clone(),
+                    field_type,
+                    required: required.contains(&property.as_str()),
+                });
+            }
+        }
+    }
+
+    /// Type of a property schema; inline objects become types named `hint`
+    fn field_type(&mut self, schema: &Json, hint: &str, location: &str) -> JsonSchemaFieldType {
+        let field_type = self.non_null_type(schema, hint, location);
+        // OpenAPI 3.0 spells `["string", "null"]` as `nullable: true`
+        if schema.get("nullable") != Some(&Json::Bool(true)) {
+            return field_type;
+        }
+        match field_type {
+            JsonSchemaFieldType::Union(mut members) => {
+                if !members.contains(&JsonSchemaFieldType::Null) {
+                    members.push(JsonSchemaFieldType::Null);
+                }
+                JsonSchemaFieldType::Union(members)
+            }
+            JsonSchemaFieldType::Null | JsonSchemaFieldType::Any => field_type,
+            other => JsonSchemaFieldType::Union(vec![other, JsonSchemaFieldType::Null]),
+        }
+    }
+
+    fn non_null_type(&mut self, schema: &Json, hint: &str, location: &str) -> JsonSchemaFieldType {
+        let Json::Object(_) = schema else {
+            // `true` accepts anything; `false` accepts nothing, which no field can hold
+            return JsonSchemaFieldType::Any;
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Json::as_str) {
+            return match reference {
+                "#" => JsonSchemaFieldType::Ref(self.root.clone()),
+                _ => JsonSchemaFieldType::Ref(pascal_case(reference.rsplit('/').next().unwrap_or(reference))),
+            };
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(members) = schema.get(key).and_then(Json::as_array) {
+                let members = members
+                    .iter()
+                    .enumerate()
+                    .map(|(i, member)| self.field_type(member, &format!("{}{}", hint, i + 1), &format!("{}/{}/{}", location, key, i)))
+                    .collect();
+                return union(members);
+            }
+        }
+        // `allOf` of one schema is that schema; merging several isn't attempted
+        if let Some(members) = schema.get("allOf").and_then(Json::as_array) {
+            return match members {
+                [only] => self.field_type(only, hint, &format!("{}/allOf/0", location)),
+                _ => JsonSchemaFieldType::Any,
+            };
+        }
+        if string_enum(schema).is_some() {
+            return JsonSchemaFieldType::String;
+        }
+        if let Some(constant) = schema.get("const") {
+            return match constant {
+                Json::String(_) => JsonSchemaFieldType::String,
+                Json::Bool(_) => JsonSchemaFieldType::Boolean,
+                Json::Number(n) if n.fract() == 0.0 => JsonSchemaFieldType::Integer,
+                Json::Number(_) => JsonSchemaFieldType::Number,
+                Json::Null => JsonSchemaFieldType::Null,
+                _ => JsonSchemaFieldType::Any,
+            };
+        }
+
+        match schema.get("type") {
+            Some(Json::String(kind)) => self.typed(schema, kind, hint, location),
+            // `["string", "null"]`
+            Some(Json::Array(kinds)) => {
+                let members = kinds.iter().filter_map(Json::as_str).map(|kind| self.typed(schema, kind, hint, location)).collect();
+                union(members)
+            }
+            _ if is_record(schema) => self.typed(schema, "object", hint, location),
+            _ => JsonSchemaFieldType::Any,
+        }
+    }
+
+    fn typed(&mut self, schema: &Json, kind: &str, hint: &str, location: &str) -> JsonSchemaFieldType {
+        let format = schema.get("format").and_then(Json::as_str);
+        match kind {
+            // `date-time`, `uuid`, `byte`, and every other string format are strings on the wire
+            "string" => JsonSchemaFieldType::String,
+            "integer" => match format {
+                Some("int32") => JsonSchemaFieldType::Int32,
+                Some("uint32") => JsonSchemaFieldType::UInt32,
+                Some("uint64") => JsonSchemaFieldType::UInt64,
+                Some("int64") => JsonSchemaFieldType::Integer,
+                _ => integer_by_bounds(schema),
+            },
+            "number" if format == Some("float") => JsonSchemaFieldType::Float,
+            "number" => JsonSchemaFieldType::Number,
+            "boolean" => JsonSchemaFieldType::Boolean,
+            "null" => JsonSchemaFieldType::Null,
+            "array" => match schema.get("items") {
+                Some(items) => JsonSchemaFieldType::Array(Box::new(self.field_type(items, &format!("{}Item", hint), &format!("{}/items", location)))),
+                None => JsonSchemaFieldType::Array(Box::new(JsonSchemaFieldType::Any)),
+            },
+            "object" if schema.get("properties").is_some() => {
+                self.define(hint, schema, location.to_string());
+                JsonSchemaFieldType::Ref(hint.to_string())
+            }
+            "object" => match schema.get("additionalProperties") {
+                Some(values @ Json::Object(_)) => {
+                    JsonSchemaFieldType::Map(Box::new(self.field_type(values, &format!("{}Value", hint), &format!("{}/additionalProperties", location))))
+                }
+                _ => JsonSchemaFieldType::Map(Box::new(JsonSchemaFieldType::Any)),
+            },
+            _ => JsonSchemaFieldType::Any,
+        }
+    }
+}
+
+/// An object schema with its own properties
+fn is_record(schema: &Json) -> bool {
+    let typed_object = match schema.get("type") {
+        Some(Json::String(kind)) => kind == "object",
+        None => true,
+        _ => false,
+    };
+    typed_object && schema.get("properties").is_some()
+}
+
+/// Values of an `enum` made only of strings
+fn string_enum(schema: &Json) -> Option<Vec<String>> {
+    let values = schema.get("enum")?.as_array()?;
+    values.iter().map(|value| value.as_str().map(str::to_string)).collect()
+}
+
+/// Narrow an unformatted `integer` by its `minimum`/`maximum`
+fn integer_by_bounds(schema: &Json) -> JsonSchemaFieldType {
+    let minimum = schema.get("minimum").and_then(Json::as_f64);
+    let maximum = schema.get("maximum").and_then(Json::as_f64);
+    match (minimum, maximum) {
+        (Some(min), Some(max)) if min >= 0.0 && max <= f64::from(u32::MAX) => JsonSchemaFieldType::UInt32,
+        (Some(min), Some(max)) if min >= f64::from(i32::MIN) && max <= f64::from(i32::MAX) => JsonSchemaFieldType::Int32,
+        _ => JsonSchemaFieldType::Integer,
+    }
+}
+
+fn union(mut members: Vec<JsonSchemaFieldType>) -> JsonSchemaFieldType {
+    if members.len() == 1 {
+        members.remove(0)
+    } else {
+        JsonSchemaFieldType::Union(members)
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(schema_type: &JsonSchemaType, target: &str) -> f32 {
+    average_fidelity(ir_fields(schema_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a JSON Schema field type into the language-neutral IR
+pub fn to_ir(field_type: &JsonSchemaFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        JsonSchemaFieldType::String => primitive(PrimitiveType::String),
+        // Unbounded in the schema; 64 bits is what generators pick
+        JsonSchemaFieldType::Integer => primitive(PrimitiveType::I64),
+        JsonSchemaFieldType::Int32 => primitive(PrimitiveType::I32),
+        JsonSchemaFieldType::UInt32 => primitive(PrimitiveType::U32),
+        JsonSchemaFieldType::UInt64 => primitive(PrimitiveType::U64),
+        JsonSchemaFieldType::Number => primitive(PrimitiveType::F64),
+        JsonSchemaFieldType::Float => primitive(PrimitiveType::F32),
+        JsonSchemaFieldType::Boolean => primitive(PrimitiveType::Bool),
+        JsonSchemaFieldType::Null => primitive(PrimitiveType::Unit),
+        JsonSchemaFieldType::Any => IrType::Any,
+        // `additionalProperties` maps are JSON objects, keyed by string
+        JsonSchemaFieldType::Map(values) => {
+            IrType::Container(ContainerType::Map(Box::new(primitive(PrimitiveType::String)), Box::new(to_ir(values))))
+        }
+        JsonSchemaFieldType::Array(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        JsonSchemaFieldType::Union(members) => union_to_ir(members),
+        JsonSchemaFieldType::Ref(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// A `null` member makes the rest optional
+fn union_to_ir(members: &[JsonSchemaFieldType]) -> IrType {
+    let nullable = members.contains(&JsonSchemaFieldType::Null);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != JsonSchemaFieldType::Null) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all fields of a schema into the IR; properties outside `required`
+/// may be absent and become options
+pub fn ir_fields(schema_type: &JsonSchemaType) -> Vec<IrField> {
+    schema_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = match ty {
+                IrType::Container(ContainerType::Option(_)) | IrType::Any => ty,
+                _ if !field.required => IrType::Container(ContainerType::Option(Box::new(ty))),
+                _ => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Lower what a non-object schema describes; objects and enums alias nothing
+pub fn ir_alias(schema_type: &JsonSchemaType) -> Option<IrType> {
+    schema_type.alias.as_ref().map(to_ir)
+}
+
+/// Lower a string `enum` into unit variants tagged with its values; other
+/// schemas have no variants
+pub fn ir_variants(schema_type: &JsonSchemaType) -> Vec<IrVariant> {
+    schema_type
+        .values
+        .iter()
+        .map(|value| IrVariant::new(&pascal_case(value), Vec::new()).with_tag(Some(value.clone())))
+        .collect()
+}
+
+/// Map JSON Schema types to target language types
+pub fn map_to_target(field_type: &JsonSchemaFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "user",
+  "type": "object",
+  "required": ["id", "name", "role"],
+  "properties": {
+    "id": { "type": "integer", "format": "int64" },
+    "name": { "type": "string" },
+    "email": { "type": ["string", "null"], "format": "email" },
+    "age": { "type": "integer", "minimum": 0, "maximum": 150 },
+    "role": { "$ref": "#/$defs/Role" },
+    "tags": { "type": "array", "items": { "type": "string" } },
+    "address": {
+      "type": "object",
+      "required": ["city"],
+      "properties": { "city": { "type": "string" }, "zip": { "type": "string" } }
+    },
+    "contact": { "oneOf": [{ "$ref": "#/$defs/Phone" }, { "type": "string", "format": "email" }] },
+    "manager": { "$ref": "#" },
+    "labels": { "type": "object", "additionalProperties": { "type": "string" } }
+  },
+  "$defs": {
+    "Role": { "enum": ["admin", "member"] },
+    "Phone": { "type": "object", "properties": { "number": { "type": "string" } }, "required": ["number"] },
+    "user-id": { "type": "integer", "format": "uint64" }
+  }
+}"##;
+
+    #[test]
+    fn test_analyze_json_schema() {
+        let types = analyze_json_schema_types(USER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("User", JsonSchemaTypeKind::Object, "#"),
+                ("UserAddress", JsonSchemaTypeKind::Object, "#/properties/address"),
+                ("Role", JsonSchemaTypeKind::Enum, "#/$defs/Role"),
+                ("Phone", JsonSchemaTypeKind::Object, "#/$defs/Phone"),
+                ("UserId", JsonSchemaTypeKind::Alias, "#/$defs/user-id"),
+            ]
+        );
+
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: i64",
+                "name: String",
+                "email: Option<String>",
+                "age: Option<u32>",
+                "role: Role",
+                "tags: Option<Vec<String>>",
+                "address: Option<UserAddress>",
+                "contact: Option<PhoneOrString>",
+                "manager: Option<User>",
+                "labels: Option<HashMap<String, String>>",
+            ]
+        );
+        assert_eq!(types[1].fields.iter().map(|f| f.required).collect::<Vec<_>>(), vec![true, false]);
+        assert_eq!(types[2].values, vec!["admin", "member"]);
+        assert_eq!(types[4].alias, Some(JsonSchemaFieldType::UInt64));
+        assert_eq!(compatibility_score(&types[3], "typescript"), 1.0);
+        assert!(compatibility_score(&types[0], "typescript") < 1.0);
+    }
+
+    #[test]
+    fn test_definitions_bundle_and_errors() {
+        let bundle = r#"{ "definitions": { "point": { "properties": { "x": { "type": "number" }, "y": { "type": "number" } } } } }"#;
+        let point = analyze_json_schema_type(bundle).unwrap();
+        assert_eq!((point.name.as_str(), point.location.as_str()), ("Point", "#/definitions/point"));
+        assert_eq!(map_to_target(&point.fields[0].field_type, "gleam"), "Float");
+        assert!(analyze_json_schema_types("[1, 2]").is_err());
+        assert!(analyze_json_schema_types("{ \"type\": ").is_err());
+    }
+}
@@ -0,0 +1,386 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Shared tokenizer for analyzer front-ends
+// Splits source text into identifiers, literals, and punctuation
+
+/// Kind of lexical token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Ident,
+    Number,
+    Str,
+    Punct,
+}
+
+/// A single token with its position in the source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Token text (string literals are unquoted and unescaped)
+    pub text: String,
+    pub line: usize,
+    /// Byte offsets of the token in the original source
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Comment and literal conventions of a source language
+#[derive(Debug, Clone, Copy)]
+pub struct Syntax {
+    pub line_comments: &'static [&'static str],
+    pub block_comments: &'static [(&'static str, &'static str)],
+    pub string_quotes: &'static [char],
+    /// Characters allowed inside identifiers besides alphanumerics and `_`
+    pub ident_extra: &'static [char],
+    /// `'a'` is a character literal, read as a `Str` token; a `'` that opens
+    /// anything else (a lifetime) stays punctuation
+    pub char_literals: bool,
+}
+
+impl Syntax {
+    /// `//` and `/* */` comments, double-quoted strings (Rust, ReScript, C family)
+    pub const C_LIKE: Syntax = Syntax {
+        line_comments: &["//"],
+        block_comments: &[("/*", "*/")],
+        string_quotes: &['"'],
+        ident_extra: &[],
+        char_literals: false,
+    };
+
+    /// `C_LIKE` with character literals, which may hold a brace or a quote
+    pub const RUST: Syntax = Syntax { char_literals: true, ..Syntax::C_LIKE };
+}
+
+/// Tokenize source text according to the given syntax
+pub fn tokenize(source: &str, syntax: &Syntax) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+
+    'outer: while pos < source.len() {
+        let rest = &source[pos..];
+        let c = rest.chars().next().unwrap_or(' ');
+
+        if c.is_whitespace() {
+            if c == '\n' {
+                line += 1;
+            }
+            pos += c.len_utf8();
+            continue;
+        }
+
+        // Block comments first: their opener may start with a line marker (`#[` in Nim)
+        for (open, close) in syntax.block_comments {
+            if let Some(body) = rest.strip_prefix(open) {
+                let len = body.find(close).map(|i| i + close.len()).unwrap_or(body.len());
+                line += body[..len].matches('\n').count();
+                pos += open.len() + len;
+                continue 'outer;
+            }
+        }
+
+        for marker in syntax.line_comments {
+            if rest.starts_with(marker) {
+                pos += rest.find('\n').unwrap_or(rest.len());
+                continue 'outer;
+            }
+        }
+
+        let start = pos;
+        if syntax.string_quotes.contains(&c) {
+            let mut text = String::new();
+            let mut chars = rest[c.len_utf8()..].char_indices();
+            let mut consumed = rest.len();
+            while let Some((i, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            text.push(match escaped {
+                                'n' => '\n',
+                                't' => '\t',
+                                other => other,
+                            });
+                        }
+                    }
+                    _ if ch == c => {
+                        consumed = c.len_utf8() + i + ch.len_utf8();
+                        break;
+                    }
+                    '\n' => {
+                        line += 1;
+                        text.push(ch);
+                    }
+                    _ => text.push(ch),
+                }
+            }
+            pos += consumed;
+            tokens.push(Token { kind: TokenKind::Str, text, line, start, end: pos });
+            continue;
+        }
+
+        if let Some(len) = char_literal_len(rest).filter(|_| syntax.char_literals) {
+            let body = &rest[1..len - 1];
+            let text = match body.strip_prefix('\\') {
+                Some("n") => "\n",
+                Some("t") => "\t",
+                Some(escaped) if escaped.chars().count() == 1 => escaped,
+                _ => body,
+            };
+            pos += len;
+            tokens.push(Token { kind: TokenKind::Str, text: text.to_string(), line, start, end: pos });
+            continue;
+        }
+
+        let kind = if c.is_alphabetic() || c == '_' {
+            TokenKind::Ident
+        } else if c.is_ascii_digit() {
+            TokenKind::Number
+        } else {
+            TokenKind::Punct
+        };
+
+        let len = match kind {
+            TokenKind::Ident => rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || syntax.ident_extra.contains(&ch)))
+                .unwrap_or(rest.len()),
+            TokenKind::Number => number_len(rest),
+            _ => c.len_utf8(),
+        };
+
+        pos += len;
+        tokens.push(Token { kind, text: rest[..len].to_string(), line, start, end: pos });
+    }
+
+    tokens
+}
+
+/// Byte length of the character literal opening `rest` (`'a'`, `'\''`,
+/// `'\u{1F600}'`); `None` when it doesn't open one
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices();
+    if chars.next()?.1 != '\'' {
+        return None;
+    }
+    let close = match chars.next()? {
+        (_, '\\') => {
+            // Skip the escaped character, which may itself be a quote
+            chars.next()?;
+            chars.take_while(|&(_, ch)| ch != '\n').find(|&(_, ch)| ch == '\'')?.0
+        }
+        (_, '\'') => return None,
+        _ => chars.next().filter(|&(_, ch)| ch == '\'')?.0,
+    };
+    Some(close + 1)
+}
+
+fn number_len(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    let mut len = 0;
+    while len < bytes.len() {
+        let b = bytes[len];
+        let fraction = b == b'.' && bytes.get(len + 1).is_some_and(|next| next.is_ascii_digit());
+        if b.is_ascii_alphanumeric() || b == b'_' || fraction {
+            len += 1;
+        } else {
+            break;
+        }
+    }
+    len
+}
+
+/// Doc comment written as `marker` lines (`///`) directly above the line
+/// holding byte `offset`; lines starting with one of `skip` (attributes) may
+/// stand between it and the item
+pub fn line_doc(source: &str, offset: usize, marker: &str, skip: &[&str]) -> Option<String> {
+    let above = &source[..source[..offset].rfind('\n').unwrap_or(0)];
+    let mut lines = Vec::new();
+    for line in above.lines().rev().map(str::trim) {
+        match line.strip_prefix(marker) {
+            // `////` is an ordinary comment
+            Some(text) if !text.starts_with(&marker[marker.len() - 1..]) => lines.push(text.strip_prefix(' ').unwrap_or(text)),
+            None if skip.iter().any(|prefix| line.starts_with(prefix)) => {}
+            _ => break,
+        }
+    }
+    lines.reverse();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Doc comment written as a `/** ... */` block just before byte `offset`;
+/// decorator lines (`@genType`) may stand between it and the item
+pub fn block_doc(source: &str, offset: usize) -> Option<String> {
+    let mut before = source[..offset].trim_end();
+    while let Some(start) = before.rfind('\n').filter(|&start| before[start..].trim_start().starts_with('@')) {
+        before = before[..start].trim_end();
+    }
+    let body = before.strip_suffix("*/")?;
+    let body = &body[body.rfind("/**")? + 3..];
+    let lines: Vec<&str> = body.lines().map(|line| line.trim().trim_start_matches('*').trim()).collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Cursor over a token list with the lookahead helpers parsers need
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Cursor {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Cursor { tokens, pos: 0 }
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    /// Most recently consumed token
+    pub fn previous(&self) -> Option<&Token> {
+        self.pos.checked_sub(1).and_then(|i| self.tokens.get(i))
+    }
+
+    pub fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Line of the current token (or the last line at end of input)
+    pub fn line(&self) -> usize {
+        self.peek()
+            .or_else(|| self.tokens.last())
+            .map(|t| t.line)
+            .unwrap_or(1)
+    }
+
+    pub fn is_punct(&self, c: char) -> bool {
+        self.is_punct_at(0, c)
+    }
+
+    pub fn is_punct_at(&self, offset: usize, c: char) -> bool {
+        self.peek_at(offset)
+            .is_some_and(|t| t.kind == TokenKind::Punct && t.text.starts_with(c))
+    }
+
+    pub fn is_ident(&self, word: &str) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == TokenKind::Ident && t.text == word)
+    }
+
+    pub fn eat_punct(&mut self, c: char) -> bool {
+        if self.is_punct(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn eat_ident(&mut self, word: &str) -> bool {
+        if self.is_ident(word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect_punct(&mut self, c: char) -> Result<(), String> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", c)))
+        }
+    }
+
+    pub fn expect_ident(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(t) if t.kind == TokenKind::Ident => {
+                let text = t.text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    /// Skip a balanced group starting at the current `open` token
+    pub fn skip_balanced(&mut self, open: char, close: char) {
+        let mut depth = 0;
+        while let Some(token) = self.advance() {
+            if token.kind == TokenKind::Punct {
+                if token.text.starts_with(open) {
+                    depth += 1;
+                } else if token.text.starts_with(close) {
+                    depth -= 1;
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Error message describing what was expected at the current token
+    pub fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(t) => format!("line {}: expected {}, found `{}`", t.line, expected, t.text),
+            None => format!("expected {}, found end of input", expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_comments() {
+        let rust = "/// A user\n///\n/// Stored as JSON\n#[derive(Debug)]\npub struct User;\n//// not docs\nstruct Other;";
+        assert_eq!(line_doc(rust, rust.find("pub").unwrap(), "///", &["#"]), Some("A user\n\nStored as JSON".to_string()));
+        assert_eq!(line_doc(rust, rust.rfind("struct").unwrap(), "///", &["#"]), None);
+
+        let rescript = "/**\n * A user\n * with an email\n */\n@genType\ntype user = { /** Login */ email: string, age: int }";
+        assert_eq!(block_doc(rescript, rescript.find("type").unwrap()), Some("A user\nwith an email".to_string()));
+        assert_eq!(block_doc(rescript, rescript.find("email:").unwrap()), Some("Login".to_string()));
+        assert_eq!(block_doc(rescript, rescript.find("age").unwrap()), None);
+    }
+
+    #[test]
+    fn test_tokenize_skips_comments() {
+        let tokens = tokenize("a /* b */ c // d\ne", &Syntax::C_LIKE);
+        let texts: Vec<_> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["a", "c", "e"]);
+        assert_eq!(tokens[2].line, 2);
+    }
+
+    #[test]
+    fn test_tokenize_strings_and_numbers() {
+        let tokens = tokenize(r#"x = "a\"b" 1.5"#, &Syntax::C_LIKE);
+        assert_eq!(tokens[2].kind, TokenKind::Str);
+        assert_eq!(tokens[2].text, "a\"b");
+        assert_eq!(tokens[3].kind, TokenKind::Number);
+        assert_eq!(tokens[3].text, "1.5");
+    }
+
+    #[test]
+    fn test_tokenize_char_literals() {
+        let tokens = tokenize(r#"f::<'a>('{', '"', '\'', '\n', '\u{7f}')"#, &Syntax::RUST);
+        let texts: Vec<_> = tokens.iter().filter(|t| t.kind == TokenKind::Str).map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["{", "\"", "'", "\n", "\\u{7f}"]);
+        assert!(tokens.iter().any(|t| t.kind == TokenKind::Ident && t.text == "a"));
+        assert_eq!(tokenize("'{'", &Syntax::C_LIKE).len(), 3);
+    }
+}
@@ -0,0 +1,802 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Lua analyzer for protocol-squisher integration
+// Reads Teal `record`, `enum`, and `type` declarations, and EmmyLua (LuaLS)
+// `---@class`/`---@field`, `---@enum`, and `---@alias` annotations in plain Lua
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Long comments open with `--[[`, so they're tried before `--` line comments
+const LUA: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: &[("--[[", "]]")],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Teal record or EmmyLua class, enum, or alias
+#[derive(Debug, Clone)]
+pub struct LuaType {
+    /// Nested Teal records are spelled `Outer.Inner`
+    pub name: String,
+    pub kind: LuaTypeKind,
+    pub type_params: Vec<String>,
+    pub fields: Vec<LuaField>,
+    /// Teal enum strings, or the keys of an EmmyLua `---@enum` table
+    pub values: Vec<String>,
+    /// Target of a `type` or `---@alias`
+    pub alias: Option<LuaFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LuaTypeKind {
+    /// Teal `record` or `interface`
+    Record,
+    /// EmmyLua `---@class`
+    Class,
+    Enum,
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct LuaField {
+    pub name: String,
+    pub field_type: LuaFieldType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaFieldType {
+    /// Float or integer, depending on the value (Lua 5.3+)
+    Number,
+    Integer,
+    Boolean,
+    String,
+    Nil,
+    /// `any`, `unknown`
+    Any,
+    /// Table without a declared shape
+    Table,
+    Function,
+    Userdata,
+    /// String literal type (`"fast"`)
+    Literal(String),
+    /// EmmyLua `T?`
+    Optional(Box<LuaFieldType>),
+    /// `{T}` or `T[]`
+    Array(Box<LuaFieldType>),
+    /// `{K:V}` or `table<K, V>`
+    Map(Box<LuaFieldType>, Box<LuaFieldType>),
+    /// Teal `{A, B}`
+    Tuple(Vec<LuaFieldType>),
+    Union(Vec<LuaFieldType>),
+    /// Record, class, or enum, with its type arguments
+    Named(String, Vec<LuaFieldType>),
+    TypeParam(String),
+}
+
+/// Analyze the first Lua type and extract metadata
+pub fn analyze_lua_type(source: &str) -> Result<LuaType, String> {
+    analyze_lua_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze Teal declarations and EmmyLua annotations together, in source order
+pub fn analyze_lua_types(source: &str) -> Result<Vec<LuaType>, String> {
+    let mut types = analyze_teal_types(source)?;
+    types.extend(analyze_emmylua_types(source)?);
+    types.sort_by_key(|t| t.location.trim_start_matches("line ").parse::<usize>().unwrap_or(0));
+    Ok(types)
+}
+
+/// Analyze every Teal `record`, `interface`, `enum`, and `type`, nested ones included
+pub fn analyze_teal_types(source: &str) -> Result<Vec<LuaType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &LUA));
+    let mut types = Vec::new();
+    while let Some(token) = cursor.peek() {
+        // Teal declarations are always `local` or `global` at the top level
+        if matches!(token.text.as_str(), "local" | "global") && teal_declaration(&cursor, 1) {
+            cursor.advance();
+            parse_teal_declaration(&mut cursor, "", &mut types)?;
+        } else {
+            cursor.advance();
+        }
+    }
+    Ok(types)
+}
+
+/// Whether the token at `offset` starts `record Name`, `enum Name`, or `type Name =`
+fn teal_declaration(cursor: &Cursor, offset: usize) -> bool {
+    let named = cursor.peek_at(offset + 1).is_some_and(|t| t.kind == TokenKind::Ident);
+    match cursor.peek_at(offset).map(|t| t.text.as_str()) {
+        Some("record" | "interface" | "enum") => named,
+        Some("type") => named && cursor.is_punct_at(offset + 2, '='),
+        _ => false,
+    }
+}
+
+fn parse_teal_declaration(cursor: &mut Cursor, outer: &str, types: &mut Vec<LuaType>) -> Result<(), String> {
+    let line = cursor.line();
+    let keyword = cursor.advance().map(|t| t.text).unwrap_or_default();
+    let name = qualify(outer, &cursor.expect_ident()?);
+    match keyword.as_str() {
+        "enum" => parse_teal_enum(cursor, name, line, types),
+        "type" => {
+            cursor.expect_punct('=')?;
+            // `local type Point = record ... end`
+            match cursor.peek().map(|t| t.text.as_str()) {
+                Some("record" | "interface") => {
+                    cursor.advance();
+                    parse_teal_record(cursor, name, line, types)
+                }
+                Some("enum") => {
+                    cursor.advance();
+                    parse_teal_enum(cursor, name, line, types)
+                }
+                _ => {
+                    let mut alias = new_type(name, LuaTypeKind::Alias, line);
+                    alias.alias = Some(parse_teal_type(cursor, &[])?);
+                    types.push(alias);
+                    Ok(())
+                }
+            }
+        }
+        _ => parse_teal_record(cursor, name, line, types),
+    }
+}
+
+fn qualify(outer: &str, name: &str) -> String {
+    if outer.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", outer, name)
+    }
+}
+
+fn new_type(name: String, kind: LuaTypeKind, line: usize) -> LuaType {
+    LuaType {
+        name,
+        kind,
+        type_params: Vec::new(),
+        fields: Vec::new(),
+        values: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    }
+}
+
+/// Record body after its name: `<T> is Base where ... fields ... end`
+fn parse_teal_record(cursor: &mut Cursor, name: String, line: usize, types: &mut Vec<LuaType>) -> Result<(), String> {
+    let mut record = new_type(name.clone(), LuaTypeKind::Record, line);
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            record.type_params.push(cursor.expect_ident()?);
+            // Interface constraints: `<T is Comparable>`
+            while cursor.peek().is_some_and(|t| t.text != "," && t.text != ">") {
+                cursor.advance();
+            }
+            cursor.eat_punct(',');
+        }
+    }
+    // `is Parent` and `where` clauses
+    while cursor.peek().is_some_and(|t| t.line == line && t.text != "end") {
+        cursor.advance();
+    }
+    let index = types.len();
+    types.push(record);
+    let type_params = types[index].type_params.clone();
+
+    loop {
+        let Some(token) = cursor.peek() else {
+            return Err(cursor.unexpected("`end`"));
+        };
+        match token.text.as_str() {
+            "end" => {
+                cursor.advance();
+                return Ok(());
+            }
+            _ if teal_declaration(cursor, 0) => parse_teal_declaration(cursor, &name, types)?,
+            // Metamethods aren't data; the array part (`{Item}`) has no field name
+            "metamethod" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) => {
+                cursor.advance();
+                cursor.advance();
+                cursor.expect_punct(':')?;
+                parse_teal_type(cursor, &type_params)?;
+            }
+            "{" => cursor.skip_balanced('{', '}'),
+            // `["content-type"]: string`
+            "[" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Str) && cursor.is_punct_at(2, ']') => {
+                cursor.advance();
+                let name = cursor.advance().map(|t| t.text).unwrap_or_default();
+                cursor.advance();
+                cursor.expect_punct(':')?;
+                let field_type = parse_teal_type(cursor, &type_params)?;
+                types[index].fields.push(LuaField { name, field_type });
+            }
+            _ if token.kind == TokenKind::Ident && cursor.is_punct_at(1, ':') => {
+                let name = cursor.expect_ident()?;
+                cursor.advance();
+                let field_type = parse_teal_type(cursor, &type_params)?;
+                types[index].fields.push(LuaField { name, field_type });
+            }
+            // `userdata`, `where` clauses
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+/// `enum Name "a" "b" end`
+fn parse_teal_enum(cursor: &mut Cursor, name: String, line: usize, types: &mut Vec<LuaType>) -> Result<(), String> {
+    let mut values = Vec::new();
+    loop {
+        let token = cursor.advance().ok_or_else(|| cursor.unexpected("`end`"))?;
+        match token.kind {
+            TokenKind::Str => values.push(token.text),
+            _ if token.text == "end" => break,
+            _ => return Err(format!("line {}: expected enum string, found `{}`", token.line, token.text)),
+        }
+    }
+    let mut enumeration = new_type(name, LuaTypeKind::Enum, line);
+    enumeration.values = values;
+    types.push(enumeration);
+    Ok(())
+}
+
+/// `A | B` over Teal primaries
+fn parse_teal_type(cursor: &mut Cursor, type_params: &[String]) -> Result<LuaFieldType, String> {
+    let mut members = vec![parse_teal_primary(cursor, type_params)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_teal_primary(cursor, type_params)?);
+    }
+    Ok(union(members))
+}
+
+fn parse_teal_primary(cursor: &mut Cursor, type_params: &[String]) -> Result<LuaFieldType, String> {
+    if cursor.eat_punct('(') {
+        let ty = parse_teal_type(cursor, type_params)?;
+        cursor.expect_punct(')')?;
+        return Ok(ty);
+    }
+    // `{T}`, `{K:V}`, `{A, B}`
+    if cursor.eat_punct('{') {
+        let first = parse_teal_type(cursor, type_params)?;
+        let ty = if cursor.eat_punct(':') {
+            LuaFieldType::Map(Box::new(first), Box::new(parse_teal_type(cursor, type_params)?))
+        } else if cursor.is_punct(',') {
+            let mut items = vec![first];
+            while cursor.eat_punct(',') {
+                items.push(parse_teal_type(cursor, type_params)?);
+            }
+            LuaFieldType::Tuple(items)
+        } else {
+            LuaFieldType::Array(Box::new(first))
+        };
+        cursor.expect_punct('}')?;
+        return Ok(ty);
+    }
+    if cursor.eat_ident("function") {
+        if cursor.is_punct('<') {
+            cursor.skip_balanced('<', '>');
+        }
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        // Return types: `: integer, string` or `: (integer, string)`
+        if cursor.eat_punct(':') {
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            } else {
+                parse_teal_type(cursor, type_params)?;
+                while cursor.eat_punct(',') {
+                    parse_teal_type(cursor, type_params)?;
+                }
+            }
+        }
+        return Ok(LuaFieldType::Function);
+    }
+
+    let mut path = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        path.push('.');
+        path.push_str(&cursor.expect_ident()?);
+    }
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_teal_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+    Ok(named(path, args, type_params))
+}
+
+/// A type name shared by both dialects, builtins included
+fn named(path: String, args: Vec<LuaFieldType>, type_params: &[String]) -> LuaFieldType {
+    match path.as_str() {
+        "number" => LuaFieldType::Number,
+        "integer" => LuaFieldType::Integer,
+        "boolean" => LuaFieldType::Boolean,
+        "string" => LuaFieldType::String,
+        "nil" => LuaFieldType::Nil,
+        "any" | "unknown" => LuaFieldType::Any,
+        "table" if args.len() == 2 => {
+            let mut args = args.into_iter();
+            let key = Box::new(args.next().unwrap_or(LuaFieldType::Any));
+            LuaFieldType::Map(key, Box::new(args.next().unwrap_or(LuaFieldType::Any)))
+        }
+        "table" => LuaFieldType::Table,
+        "function" => LuaFieldType::Function,
+        "userdata" | "lightuserdata" | "thread" => LuaFieldType::Userdata,
+        _ if type_params.contains(&path) => LuaFieldType::TypeParam(path),
+        _ => LuaFieldType::Named(path, args),
+    }
+}
+
+fn union(mut members: Vec<LuaFieldType>) -> LuaFieldType {
+    if members.len() == 1 {
+        members.remove(0)
+    } else {
+        LuaFieldType::Union(members)
+    }
+}
+
+/// Analyze every `---@class`, `---@enum`, and `---@alias` annotation
+///
+/// Annotations live in comments, which the lexer drops, so they're read line
+/// by line: `---@field` lines belong to the class above them until a line that
+/// isn't a `---` comment
+pub fn analyze_emmylua_types(source: &str) -> Result<Vec<LuaType>, String> {
+    let mut types: Vec<LuaType> = Vec::new();
+    // Class collecting `---@field`s, or alias collecting `---|` members
+    let mut open: Option<usize> = None;
+    let mut offset = 0;
+    for (i, text) in source.split_inclusive('\n').enumerate() {
+        let line = i + 1;
+        offset += text.len();
+        let trimmed = text.trim();
+        let located = |err: String| format!("line {}: {}", line, err.split_once(": ").map_or(err.as_str(), |(_, e)| e));
+
+        // `---| "fast" # description`
+        if let Some(member) = trimmed.strip_prefix("---|") {
+            if let Some(index) = open.filter(|&index| types[index].kind == LuaTypeKind::Alias) {
+                let mut cursor = Cursor::new(tokenize(member, &LUA));
+                let ty = parse_emmy_type(&mut cursor, &[]).map_err(located)?;
+                let alias = &mut types[index];
+                alias.alias = Some(match alias.alias.take() {
+                    Some(LuaFieldType::Union(mut members)) => {
+                        members.push(ty);
+                        LuaFieldType::Union(members)
+                    }
+                    Some(first) => LuaFieldType::Union(vec![first, ty]),
+                    None => ty,
+                });
+            }
+            continue;
+        }
+        let Some(annotation) = trimmed.strip_prefix("---").map(str::trim_start).and_then(|a| a.strip_prefix('@')) else {
+            if !trimmed.starts_with("---") {
+                open = None;
+            }
+            continue;
+        };
+        let (tag, rest) = annotation.split_once(char::is_whitespace).unwrap_or((annotation, ""));
+        let mut cursor = Cursor::new(tokenize(rest, &LUA));
+
+        match tag {
+            "class" => {
+                // `---@class (exact) Player : Entity`
+                if cursor.is_punct('(') {
+                    cursor.skip_balanced('(', ')');
+                }
+                let name = parse_emmy_name(&mut cursor).map_err(located)?;
+                let mut class = new_type(name, LuaTypeKind::Class, line);
+                if cursor.eat_punct('<') {
+                    while !cursor.eat_punct('>') && !cursor.at_end() {
+                        class.type_params.push(cursor.expect_ident().map_err(located)?);
+                        cursor.eat_punct(',');
+                    }
+                }
+                types.push(class);
+                open = Some(types.len() - 1);
+            }
+            "field" => {
+                let Some(index) = open.filter(|&index| types[index].kind == LuaTypeKind::Class) else {
+                    continue;
+                };
+                for scope in ["public", "protected", "private", "package"] {
+                    cursor.eat_ident(scope);
+                }
+                // Index signatures (`---@field [string] number`) describe the array or map part
+                if cursor.is_punct('[') {
+                    continue;
+                }
+                let name = cursor.expect_ident().map_err(located)?;
+                let optional = cursor.eat_punct('?');
+                let type_params = types[index].type_params.clone();
+                let field_type = parse_emmy_type(&mut cursor, &type_params).map_err(located)?;
+                let field_type = if optional { optional_of(field_type) } else { field_type };
+                types[index].fields.push(LuaField { name, field_type });
+            }
+            "alias" => {
+                let name = parse_emmy_name(&mut cursor).map_err(located)?;
+                let mut alias = new_type(name, LuaTypeKind::Alias, line);
+                if !cursor.at_end() {
+                    alias.alias = Some(parse_emmy_type(&mut cursor, &[]).map_err(located)?);
+                }
+                types.push(alias);
+                open = Some(types.len() - 1);
+            }
+            "enum" => {
+                if cursor.is_punct('(') {
+                    cursor.skip_balanced('(', ')');
+                }
+                let name = parse_emmy_name(&mut cursor).map_err(located)?;
+                let mut enumeration = new_type(name, LuaTypeKind::Enum, line);
+                enumeration.values = enum_table_keys(&source[offset..]);
+                types.push(enumeration);
+                open = None;
+            }
+            // `---@param`, `---@return`, `---@type`, ...
+            _ => {}
+        }
+    }
+
+    // An alias whose `---|` lines were all literals reads as an enum
+    for alias in types.iter_mut().filter(|t| t.kind == LuaTypeKind::Alias) {
+        if let Some(LuaFieldType::Union(members)) = &alias.alias {
+            let literals: Option<Vec<String>> = members
+                .iter()
+                .map(|member| match member {
+                    LuaFieldType::Literal(value) => Some(value.clone()),
+                    _ => None,
+                })
+                .collect();
+            if let Some(values) = literals {
+                alias.kind = LuaTypeKind::Enum;
+                alias.values = values;
+                alias.alias = None;
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// `Player`, `game.Player`
+fn parse_emmy_name(cursor: &mut Cursor) -> Result<String, String> {
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+/// Keys of the table constructor following an `---@enum` line:
+/// `local State = { Idle = "idle", Running = "running" }`
+fn enum_table_keys(rest: &str) -> Vec<String> {
+    let mut cursor = Cursor::new(tokenize(rest, &LUA));
+    // `local State =` or `State =`, then the constructor
+    while cursor.peek().is_some_and(|t| t.text != "{" && t.line <= 2) {
+        cursor.advance();
+    }
+    if !cursor.eat_punct('{') {
+        return Vec::new();
+    }
+    let mut keys = Vec::new();
+    while let Some(token) = cursor.peek().cloned() {
+        match token.text.as_str() {
+            "}" => break,
+            "{" | "(" => {
+                let close = if token.text == "{" { '}' } else { ')' };
+                cursor.skip_balanced(token.text.chars().next().unwrap_or('{'), close);
+            }
+            // `["key"] = ...`
+            "[" => {
+                cursor.advance();
+                if let Some(key) = cursor.advance().filter(|t| t.kind == TokenKind::Str) {
+                    if cursor.eat_punct(']') && cursor.is_punct('=') {
+                        keys.push(key.text);
+                    }
+                }
+            }
+            _ if token.kind == TokenKind::Ident && cursor.is_punct_at(1, '=') && !cursor.is_punct_at(2, '=') => {
+                cursor.advance();
+                keys.push(token.text);
+            }
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+    keys
+}
+
+/// `A|B` over postfix EmmyLua types
+fn parse_emmy_type(cursor: &mut Cursor, type_params: &[String]) -> Result<LuaFieldType, String> {
+    let mut members = vec![parse_emmy_postfix(cursor, type_params)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_emmy_postfix(cursor, type_params)?);
+    }
+    Ok(union(members))
+}
+
+/// `T[]`, `T?`
+fn parse_emmy_postfix(cursor: &mut Cursor, type_params: &[String]) -> Result<LuaFieldType, String> {
+    let mut ty = parse_emmy_primary(cursor, type_params)?;
+    loop {
+        if cursor.is_punct('[') && cursor.is_punct_at(1, ']') {
+            cursor.advance();
+            cursor.advance();
+            ty = LuaFieldType::Array(Box::new(ty));
+        } else if cursor.eat_punct('?') {
+            ty = optional_of(ty);
+        } else {
+            return Ok(ty);
+        }
+    }
+}
+
+fn parse_emmy_primary(cursor: &mut Cursor, type_params: &[String]) -> Result<LuaFieldType, String> {
+    let token = cursor.peek().cloned().ok_or_else(|| cursor.unexpected("type"))?;
+    match (token.kind, token.text.as_str()) {
+        (TokenKind::Punct, "(") => {
+            cursor.advance();
+            let ty = parse_emmy_type(cursor, type_params)?;
+            cursor.expect_punct(')')?;
+            Ok(ty)
+        }
+        // Table literal types: `{ name: string, [string]: number }`
+        (TokenKind::Punct, "{") => {
+            cursor.skip_balanced('{', '}');
+            Ok(LuaFieldType::Table)
+        }
+        (TokenKind::Str, _) => {
+            cursor.advance();
+            Ok(LuaFieldType::Literal(token.text))
+        }
+        (TokenKind::Number, _) => {
+            cursor.advance();
+            Ok(LuaFieldType::Integer)
+        }
+        (TokenKind::Ident, "fun") => {
+            cursor.advance();
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            }
+            if cursor.eat_punct(':') {
+                parse_emmy_type(cursor, type_params)?;
+                while cursor.eat_punct(',') {
+                    parse_emmy_type(cursor, type_params)?;
+                }
+            }
+            Ok(LuaFieldType::Function)
+        }
+        (TokenKind::Ident, _) => {
+            let path = parse_emmy_name(cursor)?;
+            let mut args = Vec::new();
+            if cursor.eat_punct('<') {
+                while !cursor.eat_punct('>') {
+                    args.push(parse_emmy_type(cursor, type_params)?);
+                    if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                        return Err(cursor.unexpected("`,` or `>`"));
+                    }
+                }
+            }
+            Ok(named(path, args, type_params))
+        }
+        _ => Err(cursor.unexpected("type")),
+    }
+}
+
+/// `T?`; `any` and `nil` already admit nil
+fn optional_of(ty: LuaFieldType) -> LuaFieldType {
+    match ty {
+        LuaFieldType::Any | LuaFieldType::Nil | LuaFieldType::Optional(_) => ty,
+        ty => LuaFieldType::Optional(Box::new(ty)),
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(lua_type: &LuaType, target: &str) -> f32 {
+    average_fidelity(ir_fields(lua_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Lua field type into the language-neutral IR
+pub fn to_ir(field_type: &LuaFieldType) -> IrType {
+    match field_type {
+        // `number` holds integers too, but a double carries every value it can
+        LuaFieldType::Number => IrType::Primitive(PrimitiveType::F64),
+        LuaFieldType::Integer => IrType::Primitive(PrimitiveType::I64),
+        LuaFieldType::Boolean => IrType::Primitive(PrimitiveType::Bool),
+        LuaFieldType::String | LuaFieldType::Literal(_) => IrType::Primitive(PrimitiveType::String),
+        LuaFieldType::Nil => IrType::Primitive(PrimitiveType::Unit),
+        // Functions and userdata don't cross a boundary
+        LuaFieldType::Any | LuaFieldType::Table | LuaFieldType::Function | LuaFieldType::Userdata => IrType::Any,
+        LuaFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        LuaFieldType::Tuple(items) => IrType::Tuple(items.iter().map(to_ir).collect()),
+        LuaFieldType::Optional(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        LuaFieldType::Array(element) => IrType::Container(ContainerType::Vec(Box::new(to_ir(element)))),
+        LuaFieldType::Union(members) => union_to_ir(members),
+        LuaFieldType::Named(path, _) => IrType::Reference(path.rsplit('.').next().unwrap_or(path).to_string()),
+        LuaFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// `T | nil` is an option; anything else stays a union
+fn union_to_ir(members: &[LuaFieldType]) -> IrType {
+    let nullable = members.contains(&LuaFieldType::Nil);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != LuaFieldType::Nil) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all fields of a Lua type into the IR
+pub fn ir_fields(lua_type: &LuaType) -> Vec<IrField> {
+    lua_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Lua types to target language types
+pub fn map_to_target(field_type: &LuaFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_teal_records() {
+        let source = r#"
+local record Point
+   x: number
+   y: number
+end
+
+--[[ The player as the engine sees it ]]
+global record Player<T>
+   name: string
+   hp: integer
+   inventory: {Item}
+   stats: {string:number}
+   pos: Point | nil
+   ["display-name"]: string
+   payload: T
+   on_hit: function(integer, Point): boolean
+   {Buff}
+
+   enum Kind
+      "warrior"
+      "mage"
+   end
+   kind: Kind
+
+   record Item
+      id: integer
+   end
+
+   metamethod __eq: function(Player<T>, Player<T>): boolean
+end
+
+local type Id = integer | string
+
+local function spawn(p: Player<Item>): Player<Item>
+   if p.hp > 0 then
+      return p
+   end
+end
+
+return { Point = Point }
+"#;
+        let types = analyze_lua_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Point", LuaTypeKind::Record),
+                ("Player", LuaTypeKind::Record),
+                ("Player.Kind", LuaTypeKind::Enum),
+                ("Player.Item", LuaTypeKind::Record),
+                ("Id", LuaTypeKind::Alias),
+            ]
+        );
+        assert_eq!(compatibility_score(&types[0], "rust"), 1.0);
+
+        let player = &types[1];
+        let fields: Vec<_> = player.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["name", "hp", "inventory", "stats", "pos", "display-name", "payload", "on_hit", "kind"]);
+        assert_eq!(map_to_target(&player.fields[2].field_type, "rust"), "Vec<Item>");
+        assert_eq!(map_to_target(&player.fields[4].field_type, "rust"), "Option<Point>");
+        assert_eq!(player.fields[6].field_type, LuaFieldType::TypeParam("T".to_string()));
+        assert_eq!(player.fields[8].field_type, LuaFieldType::Named("Kind".to_string(), vec![]));
+        assert_eq!(types[2].values, vec!["warrior", "mage"]);
+        assert!(compatibility_score(player, "rust") < 1.0);
+    }
+
+    #[test]
+    fn test_analyze_emmylua_annotations() {
+        let source = r#"
+---The player as the engine sees it
+---@class (exact) Player : Entity
+---@field name string The display name
+---@field hp integer
+---@field inventory Item[]
+---@field stats table<string, number>
+---@field pos? Vec2
+---@field target Player|nil
+---@field private secret string
+---@field [string] any
+---@field on_hit fun(damage: integer): boolean
+local Player = {}
+
+---@enum State
+local State = {
+   Idle = "idle",
+   Running = "running",
+}
+
+---@alias Mode
+---| "fast" # Skips validation
+---| "slow"
+
+---@alias Id integer|string
+
+---@param p Player
+---@return integer
+function Player.hp_of(p) return p.hp end
+"#;
+        let types = analyze_lua_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Player", LuaTypeKind::Class),
+                ("State", LuaTypeKind::Enum),
+                ("Mode", LuaTypeKind::Enum),
+                ("Id", LuaTypeKind::Alias),
+            ]
+        );
+
+        let player = &types[0];
+        let fields: Vec<_> = player.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["name", "hp", "inventory", "stats", "pos", "target", "secret", "on_hit"]);
+        assert_eq!(player.fields[0].field_type, LuaFieldType::String);
+        assert_eq!(map_to_target(&player.fields[2].field_type, "typescript"), "Array<Item>");
+        assert_eq!(map_to_target(&player.fields[4].field_type, "rust"), "Option<Vec2>");
+        assert_eq!(map_to_target(&player.fields[5].field_type, "rust"), "Option<Player>");
+        assert_eq!(types[1].values, vec!["Idle", "Running"]);
+        assert_eq!(types[2].values, vec!["fast", "slow"]);
+        assert_eq!(types[3].alias, Some(LuaFieldType::Union(vec![LuaFieldType::Integer, LuaFieldType::String])));
+    }
+}
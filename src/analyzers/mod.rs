@@ -1,25 +1,71 @@
 // SPDX-License-Identifier: PMPL-1.0-or-later
 // Analyzer modules for protocol-squisher integration
 
+pub mod arrow_analyzer;
+pub mod asn1_analyzer;
+pub mod avro_analyzer;
+pub mod c_analyzer;
+pub mod capnp_analyzer;
+pub mod cddl_analyzer;
+pub mod cpp_analyzer;
+pub mod crystal_analyzer;
+pub mod csharp_analyzer;
+pub mod dart_analyzer;
+pub mod elixir_analyzer;
+pub mod erlang_analyzer;
+pub mod flatbuffers_analyzer;
+pub mod fsharp_analyzer;
+pub mod java_analyzer;
+pub mod json_schema_analyzer;
+pub(crate) mod lexer;
+pub mod lua_analyzer;
+pub mod nim_analyzer;
+pub mod ocaml_analyzer;
+pub mod openapi_analyzer;
+pub mod php_analyzer;
+pub mod proto_analyzer;
 pub mod rescript_analyzer;
+pub mod ruby_analyzer;
 pub mod rust_analyzer;
+pub mod sql_analyzer;
+pub mod swift_analyzer;
+pub mod thrift_analyzer;
+pub mod typescript_analyzer;
+pub mod xsd_analyzer;
+pub mod zig_analyzer;
 
 // Re-export main types for convenience
-pub use rescript_analyzer::{ReScriptType, ReScriptField, ReScriptFieldType};
-pub use rust_analyzer::{RustType, RustField, RustFieldType, Visibility};
-
-/// Transport class for compatibility classification
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum TransportClass {
-    /// 100% fidelity - all types have direct equivalents
-    Concorde,
-    /// 95-99% fidelity - minor precision differences
-    BusinessClass,
-    /// 80-94% fidelity - some type coercion needed
-    Economy,
-    /// <80% fidelity - significant impedance mismatch
-    Wheelbarrow,
-}
+pub use arrow_analyzer::{ArrowType, ArrowTypeKind, ArrowField, ArrowFieldType, ArrowDictionary};
+pub use asn1_analyzer::{Asn1Type, Asn1TypeKind, Asn1Field, Asn1FieldType, Asn1EnumValue};
+pub use avro_analyzer::{AvroType, AvroTypeKind, AvroField, AvroFieldType};
+pub use c_analyzer::{CType, CTypeKind, CField, CFieldType, CEnumerator};
+pub use capnp_analyzer::{CapnpType, CapnpTypeKind, CapnpField, CapnpFieldType, CapnpEnumerant};
+pub use cddl_analyzer::{CddlType, CddlTypeKind, CddlField, CddlFieldType, CddlOccurrence};
+pub use cpp_analyzer::{CppType, CppTypeKind, CppAccess, CppField, CppFieldType};
+pub use crystal_analyzer::{CrystalType, CrystalTypeKind, CrystalAccessor, CrystalField, CrystalFieldType};
+pub use csharp_analyzer::{CSharpType, CSharpTypeKind, CSharpField, CSharpFieldType};
+pub use dart_analyzer::{DartType, DartTypeKind, DartField, DartFieldType};
+pub use elixir_analyzer::{ElixirType, ElixirField, ElixirFieldType};
+pub use erlang_analyzer::{ErlangType, ErlangTypeKind, ErlangField, ErlangFieldType, ErlangSpec};
+pub use flatbuffers_analyzer::{FlatBuffersType, FlatBuffersTypeKind, FlatBuffersField, FlatBuffersFieldType, FlatBuffersEnumValue};
+pub use fsharp_analyzer::{FSharpType, FSharpTypeKind, FSharpField, FSharpFieldType, FSharpCase};
+pub use java_analyzer::{JavaType, JavaTypeKind, JavaField, JavaFieldType};
+pub use json_schema_analyzer::{JsonSchemaType, JsonSchemaTypeKind, JsonSchemaField, JsonSchemaFieldType};
+pub use lua_analyzer::{LuaType, LuaTypeKind, LuaField, LuaFieldType};
+pub use nim_analyzer::{NimType, NimTypeKind, NimField, NimFieldType, NimEnumValue};
+pub use ocaml_analyzer::{OCamlType, OCamlField, OCamlFieldType, OCamlVariant};
+pub use php_analyzer::{PhpType, PhpTypeKind, PhpVisibility, PhpField, PhpFieldType, PhpCase};
+pub use proto_analyzer::{ProtoType, ProtoTypeKind, ProtoField, ProtoFieldType, ProtoLabel, ProtoEnumValue};
+pub use rescript_analyzer::{ReScriptType, ReScriptTypeKind, ReScriptField, ReScriptFieldType, ReScriptVariant};
+pub use ruby_analyzer::{RubyType, RubyTypeKind, RubyField, RubyFieldType};
+pub use rust_analyzer::{RustType, RustTypeKind, RustField, RustFieldType, RustVariant, RustVariantKind, Visibility};
+pub use sql_analyzer::{SqlType, SqlTypeKind, SqlColumn, SqlColumnType};
+pub use swift_analyzer::{SwiftType, SwiftTypeKind, SwiftField, SwiftFieldType, SwiftCase};
+pub use thrift_analyzer::{ThriftType, ThriftTypeKind, ThriftField, ThriftFieldType, ThriftRequiredness, ThriftEnumValue};
+pub use typescript_analyzer::{TypeScriptType, TypeScriptField, TypeScriptFieldType};
+pub use xsd_analyzer::{XsdType, XsdTypeKind, XsdField, XsdFieldType};
+pub use zig_analyzer::{ZigType, ZigTypeKind, ZigLayout, ZigField, ZigFieldType};
+pub use crate::ir::TransportClass;
 
 /// Calculate transport class between two languages
 pub fn calculate_transport_class(source: &str, target: &str) -> TransportClass {
@@ -30,6 +76,103 @@ pub fn calculate_transport_class(source: &str, target: &str) -> TransportClass {
         ("rust", "julia") | ("julia", "rust") => TransportClass::Concorde,
         ("rescript", "gleam") | ("gleam", "rescript") => TransportClass::Concorde,
         ("rust", "gleam") | ("gleam", "rust") => TransportClass::Concorde,
+        ("typescript", "rescript") | ("rescript", "typescript") => TransportClass::Concorde,
+        // TypeScript numbers are doubles: 64-bit integers lose precision past 2^53
+        ("typescript", "rust") | ("rust", "typescript") => TransportClass::BusinessClass,
+        // ReScript compiles through the OCaml type system: records and variants match 1:1
+        ("ocaml", "rescript") | ("rescript", "ocaml") => TransportClass::Concorde,
+        // OCaml `int` is 63-bit, so values near the i64 bounds don't round-trip
+        ("ocaml", "rust") | ("rust", "ocaml") => TransportClass::BusinessClass,
+        // Elixir and Gleam share BEAM terms, so structs cross without re-encoding
+        ("elixir", "gleam") | ("gleam", "elixir") => TransportClass::Concorde,
+        // Elixir integers are arbitrary precision; Rust needs a fixed width
+        ("elixir", "rust") | ("rust", "elixir") => TransportClass::BusinessClass,
+        // C strings need UTF-8 validation and raw pointers carry no ownership
+        ("c", "rust") | ("rust", "c") => TransportClass::BusinessClass,
+        // Owned containers map 1:1, but `std::function` has no IR form
+        ("cpp", "rust") | ("rust", "cpp") => TransportClass::BusinessClass,
+        // `decimal` needs a hand-written converter
+        ("csharp", "rust") | ("rust", "csharp") => TransportClass::BusinessClass,
+        // Reference fields are nullable unless annotated, so Rust sees more `Option`s than intended
+        ("java", "rust") | ("rust", "java") => TransportClass::BusinessClass,
+        // Gleam ints are arbitrary precision, but JVM objects need re-encoding as BEAM terms
+        ("java", "gleam") | ("gleam", "java") => TransportClass::BusinessClass,
+        // Value types map 1:1, but closures and `Any` have no IR form
+        ("swift", "rust") | ("rust", "swift") => TransportClass::BusinessClass,
+        // Explicit layouts and fixed-width integers: `extern struct` is `#[repr(C)]` as-is
+        ("zig", "rust") | ("rust", "zig") => TransportClass::Concorde,
+        // `int` is pointer-sized and `ref object`s may be nil, so Rust sees more `Option`s than intended
+        ("nim", "rust") | ("rust", "nim") => TransportClass::BusinessClass,
+        // `T?` unions are `Option`s, but mixed unions like `Int32 | String` need a tagged encoding
+        ("crystal", "rust") | ("rust", "crystal") => TransportClass::BusinessClass,
+        // Gleam custom types compile to Erlang records, and Elixir shares the same terms
+        ("erlang", "gleam") | ("gleam", "erlang") => TransportClass::Concorde,
+        ("erlang", "elixir") | ("elixir", "erlang") => TransportClass::Concorde,
+        // Integers are arbitrary precision and atoms become strings
+        ("erlang", "rust") | ("rust", "erlang") => TransportClass::BusinessClass,
+        // ML records and unions on both sides, and `int` is 32-bit in each
+        ("fsharp", "rescript") | ("rescript", "fsharp") => TransportClass::Concorde,
+        // Records and unions map to structs and enums, but .NET strings and arrays may be null
+        ("fsharp", "rust") | ("rust", "fsharp") => TransportClass::BusinessClass,
+        // Flutter↔Rust: null safety maps to `Option`, but `int` is a double once
+        // compiled to the web
+        ("dart", "rust") | ("rust", "dart") => TransportClass::BusinessClass,
+        // Gradual types: integers are arbitrary precision, symbols become strings,
+        // and `untyped` fields cross as opaque JSON
+        ("ruby", "rust") | ("rust", "ruby") => TransportClass::Economy,
+        // Typed properties map cleanly, but `array` fields are lists or maps
+        // depending on their keys and cross as opaque JSON
+        ("php", "rust") | ("rust", "php") => TransportClass::Economy,
+        // Annotated fields map cleanly, but `number` is a double or an integer
+        // depending on the value, and shapeless tables cross as opaque JSON
+        ("lua", "rust") | ("rust", "lua") => TransportClass::Economy,
+        // prost generates a struct per message, but `bytes` cross as base64 strings
+        ("protobuf", "rust") | ("rust", "protobuf") => TransportClass::BusinessClass,
+        // The JSON mapping writes 64-bit integers as strings; ReScript ints are 32-bit
+        ("protobuf", "rescript") | ("rescript", "protobuf") => TransportClass::BusinessClass,
+        // serde reads and writes the documents directly, but unbounded integers
+        // are taken as i64
+        ("jsonschema", "rust") | ("rust", "jsonschema") => TransportClass::BusinessClass,
+        // The schema describes JSON values exactly, except integers past 2^53
+        ("jsonschema", "typescript") | ("typescript", "jsonschema") => TransportClass::BusinessClass,
+        // Component schemas are JSON Schema, with the same gaps on the Rust side
+        ("openapi", "rust") | ("rust", "openapi") => TransportClass::BusinessClass,
+        ("openapi", "typescript") | ("typescript", "openapi") => TransportClass::BusinessClass,
+        // apache-avro reads records through serde, but durations have no IR
+        // form yet and decimals need `rust_decimal`
+        ("avro", "rust") | ("rust", "avro") => TransportClass::BusinessClass,
+        // Avro's own code generator targets Java, where every primitive has a twin
+        ("avro", "java") | ("java", "avro") => TransportClass::BusinessClass,
+        // The Rust generator wraps default-requiredness fields in `Option`, and
+        // `i8`/`i16` widen to i32 in the IR
+        ("thrift", "rust") | ("rust", "thrift") => TransportClass::BusinessClass,
+        // Java is Thrift's reference generator; narrow integers widen in the IR
+        ("thrift", "java") | ("java", "thrift") => TransportClass::BusinessClass,
+        // capnp-rust hands out readers and builders rather than owned structs,
+        // and `AnyPointer` fields have no IR form
+        ("capnp", "rust") | ("rust", "capnp") => TransportClass::Economy,
+        // The reference implementation; unions and groups become accessor pairs
+        ("capnp", "cpp") | ("cpp", "capnp") => TransportClass::BusinessClass,
+        // The object API generates owned structs; narrow integers widen in the IR
+        ("flatbuffers", "rust") | ("rust", "flatbuffers") => TransportClass::BusinessClass,
+        // The reference implementation; the object API holds tables in `unique_ptr`s
+        ("flatbuffers", "cpp") | ("cpp", "flatbuffers") => TransportClass::BusinessClass,
+        // sqlx and diesel map rows onto structs column by column, but `interval`
+        // and `json` have no IR form yet
+        ("sql", "rust") | ("rust", "sql") => TransportClass::BusinessClass,
+        // ciborium reads maps and arrays through serde, but byte strings need
+        // `serde_bytes` and `.size` widths round up to 32 bits
+        ("cddl", "rust") | ("rust", "cddl") => TransportClass::BusinessClass,
+        // rasn derives structs for SEQUENCEs and enums for CHOICEs, but bit
+        // strings have no IR form yet and unconstrained INTEGERs are capped
+        ("asn1", "rust") | ("rust", "asn1") => TransportClass::BusinessClass,
+        // quick-xml's serde support maps elements onto structs, but binary
+        // content needs decoding and unbounded integers are capped
+        ("xsd", "rust") | ("rust", "xsd") => TransportClass::BusinessClass,
+        // arrow-rs and Arrow.jl both read record batches column by column, but
+        // intervals have no IR form yet
+        ("arrow", "rust") | ("rust", "arrow") => TransportClass::BusinessClass,
+        ("arrow", "julia") | ("julia", "arrow") => TransportClass::BusinessClass,
         _ => TransportClass::Wheelbarrow,
     }
 }
@@ -61,7 +204,6 @@ fn generate_mapping_table(rescript: &ReScriptType, rust: &RustType) -> String {
     let mut table = String::new();
 
     for (rs_field, rust_field) in rescript.fields.iter().zip(rust.fields.iter()) {
-        let rs_type = rescript_analyzer::map_to_target(&rs_field.field_type, "rust");
         let rust_type = format!("{:?}", rust_field.field_type).to_lowercase();
 
         table.push_str(&format!(
@@ -85,6 +227,14 @@ fn test_transport_class_rescript_rust() {
         assert_eq!(class, TransportClass::Concorde);
     }
 
+    #[test]
+    fn test_transport_class_from_score() {
+        assert_eq!(TransportClass::from_score(1.0), TransportClass::Concorde);
+        assert_eq!(TransportClass::from_score(0.97), TransportClass::BusinessClass);
+        assert_eq!(TransportClass::from_score(0.85), TransportClass::Economy);
+        assert_eq!(TransportClass::from_score(0.5), TransportClass::Wheelbarrow);
+    }
+
     #[test]
     fn test_transport_class_symmetric() {
         let class1 = calculate_transport_class("rescript", "rust");
@@ -0,0 +1,743 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Nim analyzer for protocol-squisher integration
+// Analyzes `object` (including variant objects) and `enum` declarations in `type` sections
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::PlatformWidth;
+
+/// Nim type declaration
+#[derive(Debug, Clone)]
+pub struct NimType {
+    pub name: String,
+    pub kind: NimTypeKind,
+    /// Marked `*` (visible outside its module)
+    pub exported: bool,
+    /// Parent of an inheriting object (`object of RootObj`)
+    pub base: Option<String>,
+    pub type_params: Vec<String>,
+    pub fields: Vec<NimField>,
+    pub values: Vec<NimEnumValue>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NimTypeKind {
+    Object,
+    /// `ref object`: a nil-able, garbage-collected reference
+    RefObject,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+pub struct NimField {
+    pub name: String,
+    pub field_type: NimFieldType,
+    pub exported: bool,
+    /// `of` labels of the variant-object branch declaring the field (`"circle"`,
+    /// `"else"`); `None` for fields every variant has
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NimEnumValue {
+    pub name: String,
+    /// Explicit ordinal or string value as written (`5`, `"Green"`)
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NimFieldType {
+    /// Pointer-sized signed integer (also `Natural`, `Positive`)
+    Int,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    /// Pointer-sized unsigned integer
+    UInt,
+    UInt8, // also `byte`
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64, // also `float`
+    Bool,
+    Char,
+    String,
+    CString,
+    Option(Box<NimFieldType>),
+    Seq(Box<NimFieldType>),
+    /// `array[N, T]`; `None` when the index is a range or enum
+    Array(Box<NimFieldType>, Option<usize>),
+    /// `set[T]`, `HashSet[T]`
+    Set(Box<NimFieldType>),
+    /// `Table[K, V]` and the ordered and counting variants
+    Table(Box<NimFieldType>, Box<NimFieldType>),
+    /// `ref T`, may be nil
+    Ref(Box<NimFieldType>),
+    /// `ptr T`, untraced and may be nil
+    Ptr(Box<NimFieldType>),
+    /// `Result[T, E]` from the results library
+    Result(Box<NimFieldType>, Box<NimFieldType>),
+    Tuple,
+    Proc,
+    /// `auto`, `pointer`, `JsonNode`
+    Any,
+    Named(String, Vec<NimFieldType>),
+    TypeParam(String),
+}
+
+const NIM_SYNTAX: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[("#[", "]#")],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Analyze the first Nim object or enum and extract metadata
+pub fn analyze_nim_type(source: &str) -> Result<NimType, String> {
+    analyze_nim_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every object and enum declared in the `type` sections of a Nim file
+pub fn analyze_nim_types(source: &str) -> Result<Vec<NimType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &NIM_SYNTAX));
+    let mut types = Vec::new();
+    while let Some(token) = cursor.peek() {
+        if token.kind == TokenKind::Ident && token.text == "type" && starts_line(&cursor) {
+            let indent = column(source, token.start);
+            cursor.advance();
+            parse_type_section(&mut cursor, source, indent, &mut types)?;
+        } else {
+            cursor.advance();
+        }
+    }
+    Ok(types)
+}
+
+/// Column of a byte offset (Nim blocks are delimited by indentation)
+fn column(source: &str, offset: usize) -> usize {
+    offset - source[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+fn starts_line(cursor: &Cursor) -> bool {
+    match (cursor.previous(), cursor.peek()) {
+        (Some(previous), Some(next)) => previous.line != next.line,
+        _ => true,
+    }
+}
+
+/// Whether the next token belongs to the block indented deeper than `indent`:
+/// it continues the current line, or starts a line further right
+fn in_block(cursor: &Cursor, source: &str, indent: usize) -> bool {
+    cursor
+        .peek()
+        .is_some_and(|token| !starts_line(cursor) || column(source, token.start) > indent)
+}
+
+fn skip_block(cursor: &mut Cursor, source: &str, indent: usize) {
+    while in_block(cursor, source, indent) {
+        cursor.advance();
+    }
+}
+
+/// Skip the rest of the current line
+fn skip_line(cursor: &mut Cursor) {
+    cursor.advance();
+    while cursor.peek().is_some() && !starts_line(cursor) {
+        cursor.advance();
+    }
+}
+
+/// Declarations of one `type` section, each indented past the keyword
+fn parse_type_section(cursor: &mut Cursor, source: &str, indent: usize, types: &mut Vec<NimType>) -> Result<(), String> {
+    while in_block(cursor, source, indent) {
+        let Some(token) = cursor.peek() else { break };
+        let declaration = column(source, token.start);
+        if token.kind != TokenKind::Ident {
+            // Quoted names and stray tokens: skip the declaration
+            cursor.advance();
+            skip_block(cursor, source, declaration);
+            continue;
+        }
+        parse_declaration(cursor, source, declaration, types)?;
+    }
+    Ok(())
+}
+
+/// `Name*[T] {.pragma.} = [ref] object [of Base]` or `= enum`; aliases,
+/// distinct types, tuples, and concepts are skipped
+fn parse_declaration(cursor: &mut Cursor, source: &str, indent: usize, types: &mut Vec<NimType>) -> Result<(), String> {
+    let line = cursor.line();
+    let name = cursor.expect_ident()?;
+    let exported = cursor.eat_punct('*');
+    skip_pragma(cursor);
+    let mut type_params = Vec::new();
+    if cursor.eat_punct('[') {
+        // Parameter names open the list and follow each `,` or `;`; the rest are constraints
+        let mut expect_name = true;
+        while !cursor.eat_punct(']') {
+            let token = cursor.advance().ok_or_else(|| cursor.unexpected("`]`"))?;
+            match token.kind {
+                TokenKind::Ident if expect_name => {
+                    type_params.push(token.text);
+                    expect_name = false;
+                }
+                TokenKind::Punct if token.text == "," || token.text == ";" => expect_name = true,
+                _ => {}
+            }
+        }
+    }
+    skip_pragma(cursor);
+    if !cursor.eat_punct('=') {
+        skip_block(cursor, source, indent);
+        return Ok(());
+    }
+
+    let is_ref = cursor.eat_ident("ref") || cursor.eat_ident("ptr");
+    let kind = if cursor.eat_ident("object") {
+        if is_ref { NimTypeKind::RefObject } else { NimTypeKind::Object }
+    } else if !is_ref && cursor.eat_ident("enum") {
+        NimTypeKind::Enum
+    } else {
+        skip_block(cursor, source, indent);
+        return Ok(());
+    };
+
+    let mut nim_type = NimType {
+        name,
+        kind,
+        exported,
+        base: None,
+        type_params,
+        fields: Vec::new(),
+        values: Vec::new(),
+        location: format!("line {}", line),
+    };
+    if kind == NimTypeKind::Enum {
+        skip_pragma(cursor);
+        nim_type.values = parse_enum_values(cursor, source, indent)?;
+    } else {
+        if cursor.eat_ident("of") {
+            nim_type.base = Some(cursor.expect_ident()?);
+        }
+        skip_pragma(cursor);
+        parse_fields(cursor, source, indent, None, &nim_type.type_params, &mut nim_type.fields)?;
+    }
+    types.push(nim_type);
+    Ok(())
+}
+
+/// `{.pure, inheritable.}`
+fn skip_pragma(cursor: &mut Cursor) {
+    if cursor.is_punct('{') && cursor.is_punct_at(1, '.') {
+        cursor.skip_balanced('{', '}');
+    }
+}
+
+/// `red, green = "Green", blue = 5`, on the declaration's line or indented below it
+fn parse_enum_values(cursor: &mut Cursor, source: &str, indent: usize) -> Result<Vec<NimEnumValue>, String> {
+    let mut values = Vec::new();
+    while in_block(cursor, source, indent) {
+        let name = cursor.expect_ident()?;
+        skip_pragma(cursor);
+        let mut value = None;
+        if cursor.eat_punct('=') {
+            let (mut start, mut end) = (None, 0);
+            while in_block(cursor, source, indent) && !cursor.is_punct(',') {
+                if cursor.is_punct('(') {
+                    start.get_or_insert(cursor.peek().map_or(0, |token| token.start));
+                    cursor.skip_balanced('(', ')');
+                } else if let Some(token) = cursor.advance() {
+                    start.get_or_insert(token.start);
+                }
+                end = cursor.previous().map_or(end, |token| token.end);
+            }
+            value = start.map(|start| source[start..end].to_string());
+        }
+        values.push(NimEnumValue { name, value });
+        cursor.eat_punct(',');
+    }
+    Ok(values)
+}
+
+/// Field lines of an object body or variant branch, indented past `indent`
+fn parse_fields(
+    cursor: &mut Cursor,
+    source: &str,
+    indent: usize,
+    branch: Option<&str>,
+    type_params: &[String],
+    fields: &mut Vec<NimField>,
+) -> Result<(), String> {
+    while in_block(cursor, source, indent) {
+        let Some(token) = cursor.peek() else { break };
+        if token.kind == TokenKind::Punct && token.text != "`" {
+            if cursor.eat_punct(';') {
+                continue;
+            }
+            return Err(cursor.unexpected("field"));
+        }
+        match token.text.as_str() {
+            "case" if token.kind == TokenKind::Ident => {
+                parse_case(cursor, source, indent, branch, type_params, fields)?;
+            }
+            // Compile-time conditional fields are kept: they exist on some builds
+            "when" | "elif" | "else" if token.kind == TokenKind::Ident => {
+                while in_block(cursor, source, indent) && !cursor.eat_punct(':') {
+                    cursor.advance();
+                }
+            }
+            "discard" | "nil" if token.kind == TokenKind::Ident => {
+                cursor.advance();
+            }
+            _ => parse_field_line(cursor, branch, type_params, fields)?,
+        }
+    }
+    Ok(())
+}
+
+/// `case kind*: ShapeKind` followed by `of` / `else` branches
+fn parse_case(
+    cursor: &mut Cursor,
+    source: &str,
+    indent: usize,
+    branch: Option<&str>,
+    type_params: &[String],
+    fields: &mut Vec<NimField>,
+) -> Result<(), String> {
+    cursor.advance();
+    parse_field_line(cursor, branch, type_params, fields)?;
+    cursor.eat_punct(':');
+    while in_block(cursor, source, indent) && (cursor.is_ident("of") || cursor.is_ident("else")) {
+        let keyword = cursor.peek().map_or(0, |token| column(source, token.start));
+        let label = if cursor.eat_ident("else") {
+            "else".to_string()
+        } else {
+            cursor.advance();
+            let mut labels = Vec::new();
+            while in_block(cursor, source, indent) && !cursor.is_punct(':') {
+                let token = cursor.advance().ok_or_else(|| cursor.unexpected("`:`"))?;
+                if token.kind == TokenKind::Ident {
+                    labels.push(token.text);
+                }
+            }
+            labels.join(", ")
+        };
+        cursor.expect_punct(':')?;
+        // Nested cases qualify their labels with the outer branch
+        let label = match branch {
+            Some(outer) => format!("{}/{}", outer, label),
+            None => label,
+        };
+        parse_fields(cursor, source, keyword, Some(&label), type_params, fields)?;
+    }
+    Ok(())
+}
+
+/// `a, b*: int = 0 {.pragma.}`
+fn parse_field_line(cursor: &mut Cursor, branch: Option<&str>, type_params: &[String], fields: &mut Vec<NimField>) -> Result<(), String> {
+    let mut names = Vec::new();
+    loop {
+        let name = if cursor.eat_punct('`') {
+            let name = cursor.expect_ident()?;
+            cursor.expect_punct('`')?;
+            name
+        } else {
+            cursor.expect_ident()?
+        };
+        let exported = cursor.eat_punct('*');
+        skip_pragma(cursor);
+        names.push((name, exported));
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(':')?;
+    let field_type = parse_type(cursor, type_params)?;
+    if cursor.is_punct('=') {
+        // Default value
+        skip_line(cursor);
+    }
+    skip_pragma(cursor);
+    for (name, exported) in names {
+        fields.push(NimField {
+            name,
+            field_type: field_type.clone(),
+            exported,
+            branch: branch.map(str::to_string),
+        });
+    }
+    Ok(())
+}
+
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<NimFieldType, String> {
+    for modifier in ["var", "sink", "lent", "distinct"] {
+        cursor.eat_ident(modifier);
+    }
+    if cursor.eat_ident("ref") {
+        return Ok(NimFieldType::Ref(Box::new(parse_type(cursor, type_params)?)));
+    }
+    if cursor.eat_ident("ptr") {
+        return Ok(NimFieldType::Ptr(Box::new(parse_type(cursor, type_params)?)));
+    }
+    if cursor.eat_ident("tuple") {
+        if cursor.is_punct('[') {
+            cursor.skip_balanced('[', ']');
+        }
+        return Ok(NimFieldType::Tuple);
+    }
+    if cursor.eat_ident("proc") {
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        if cursor.eat_punct(':') {
+            parse_type(cursor, type_params)?;
+        }
+        skip_pragma(cursor);
+        return Ok(NimFieldType::Proc);
+    }
+
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let mut args = Vec::new();
+    let mut length = None;
+    if cursor.eat_punct('[') {
+        while !cursor.eat_punct(']') {
+            let number = cursor.peek().filter(|t| t.kind == TokenKind::Number).map(|t| t.text.parse().ok());
+            if number.is_some() || (cursor.is_punct_at(1, '.') && cursor.is_punct_at(2, '.')) {
+                // Array index: `4`, `0..3`, `low..high`
+                let mut tokens = 0;
+                while !cursor.is_punct(',') && !cursor.is_punct(']') {
+                    cursor.advance().ok_or_else(|| cursor.unexpected("`]`"))?;
+                    tokens += 1;
+                }
+                length = number.flatten().filter(|_| tokens == 1);
+            } else {
+                args.push(parse_type(cursor, type_params)?);
+            }
+            if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+                return Err(cursor.unexpected("`,` or `]`"));
+            }
+        }
+    }
+
+    let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(NimFieldType::Any));
+    Ok(match simple.as_str() {
+        "int" | "Natural" | "Positive" => NimFieldType::Int,
+        "int8" | "cschar" => NimFieldType::Int8,
+        "int16" | "cshort" => NimFieldType::Int16,
+        "int32" | "cint" => NimFieldType::Int32,
+        "int64" | "clonglong" => NimFieldType::Int64,
+        "uint" | "csize_t" => NimFieldType::UInt,
+        "uint8" | "byte" | "cuchar" => NimFieldType::UInt8,
+        "uint16" | "cushort" => NimFieldType::UInt16,
+        "uint32" | "cuint" => NimFieldType::UInt32,
+        "uint64" | "culonglong" => NimFieldType::UInt64,
+        "float32" | "cfloat" => NimFieldType::Float32,
+        "float" | "float64" | "cdouble" => NimFieldType::Float64,
+        "bool" => NimFieldType::Bool,
+        "char" | "cchar" => NimFieldType::Char,
+        "string" => NimFieldType::String,
+        "cstring" => NimFieldType::CString,
+        "auto" | "any" | "pointer" | "untyped" | "typed" | "JsonNode" | "RootRef" => NimFieldType::Any,
+        "Option" => NimFieldType::Option(next()),
+        "seq" | "openArray" | "Deque" => NimFieldType::Seq(next()),
+        "array" => {
+            // `array[N, T]`: the index was consumed as the length, so `T` is the only argument
+            let element = if args.len() > 1 { args.nth(1) } else { args.next() };
+            NimFieldType::Array(Box::new(element.unwrap_or(NimFieldType::Any)), length)
+        }
+        "set" | "HashSet" | "OrderedSet" => NimFieldType::Set(next()),
+        "Table" | "OrderedTable" | "TableRef" | "OrderedTableRef" => {
+            let key = next();
+            NimFieldType::Table(key, next())
+        }
+        "CountTable" | "CountTableRef" => NimFieldType::Table(next(), Box::new(NimFieldType::Int)),
+        "Result" => {
+            let ok = next();
+            NimFieldType::Result(ok, next())
+        }
+        _ if type_params.contains(&name) => NimFieldType::TypeParam(name),
+        _ => NimFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(nim_type: &NimType, target: &str) -> f32 {
+    average_fidelity(ir_fields(nim_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Nim field type into the language-neutral IR
+pub fn to_ir(field_type: &NimFieldType) -> IrType {
+    match field_type {
+        NimFieldType::Int8 => IrType::Primitive(PrimitiveType::I8),
+        NimFieldType::Int16 => IrType::Primitive(PrimitiveType::I16),
+        NimFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        NimFieldType::Int | NimFieldType::Int64 => IrType::Primitive(PrimitiveType::I64),
+        NimFieldType::UInt8 => IrType::Primitive(PrimitiveType::U8),
+        NimFieldType::UInt16 => IrType::Primitive(PrimitiveType::U16),
+        NimFieldType::UInt32 => IrType::Primitive(PrimitiveType::U32),
+        NimFieldType::UInt | NimFieldType::UInt64 => IrType::Primitive(PrimitiveType::U64),
+        NimFieldType::Float32 => IrType::Primitive(PrimitiveType::F32),
+        NimFieldType::Float64 => IrType::Primitive(PrimitiveType::F64),
+        NimFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        NimFieldType::Char | NimFieldType::String | NimFieldType::CString => IrType::Primitive(PrimitiveType::String),
+        // References and pointers may be nil
+        NimFieldType::Option(inner) | NimFieldType::Ref(inner) | NimFieldType::Ptr(inner) => {
+            IrType::Container(ContainerType::Option(Box::new(to_ir(inner))))
+        }
+        NimFieldType::Seq(element) | NimFieldType::Array(element, _) | NimFieldType::Set(element) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        NimFieldType::Result(ok, err) => IrType::Container(ContainerType::Result(Box::new(to_ir(ok)), Box::new(to_ir(err)))),
+        // Anonymous tuples and procs have no IR form yet
+        NimFieldType::Tuple | NimFieldType::Proc | NimFieldType::Any => IrType::Any,
+        NimFieldType::Table(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        NimFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        NimFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower object fields into the IR; fields of a variant branch only exist for
+/// some discriminator values, so they're optional (enums have none)
+pub fn ir_fields(nim_type: &NimType) -> Vec<IrField> {
+    nim_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = match field.branch {
+                Some(_) => IrType::Container(ContainerType::Option(Box::new(ty))),
+                None => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`int`, `uint`)
+pub fn platform_width(field_type: &NimFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        NimFieldType::Int | NimFieldType::UInt => Some(PlatformWidth::Pointer),
+        NimFieldType::Option(inner)
+        | NimFieldType::Seq(inner)
+        | NimFieldType::Array(inner, _)
+        | NimFieldType::Set(inner)
+        | NimFieldType::Ref(inner)
+        | NimFieldType::Ptr(inner) => platform_width(inner),
+        _ => None,
+    }
+}
+
+/// Map Nim types to target language types
+pub fn map_to_target(field_type: &NimFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+import std/[options, tables]
+
+type
+  Color* {.pure.} = enum
+    red, green = "Green",
+    blue = 5
+
+  ## A registered user
+  User* = object
+    id*: int64
+    name*, email: string
+    nickname: Option[string]
+    tags: seq[string]
+    scores: array[4, float32]
+    meta: Table[string, string] #[ free-form ]#
+    visits: int = 0
+
+  Node* = ref object of RootObj
+    next: Node
+
+  ShapeKind = enum skCircle, skRect, skPoint
+  Shape*[T] = object
+    label: string
+    case kind*: ShapeKind
+    of skCircle:
+      radius: T
+    of skRect: width, height: T
+    else: discard
+
+  Id = distinct int64
+
+proc area(s: Shape): float = 0.0
+"#;
+
+    #[test]
+    fn test_analyze_objects_and_enums() {
+        let types = analyze_nim_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Color", "User", "Node", "ShapeKind", "Shape"]);
+
+        let color = &types[0];
+        assert!(color.exported);
+        let values: Vec<_> = color.values.iter().map(|v| (v.name.as_str(), v.value.as_deref())).collect();
+        assert_eq!(values, vec![("red", None), ("green", Some("\"Green\"")), ("blue", Some("5"))]);
+        assert_eq!(types[3].values.len(), 3);
+
+        let user = &types[1];
+        let fields: Vec<_> = user.fields.iter().map(|f| (f.name.as_str(), f.exported)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", true),
+                ("name", true),
+                ("email", false),
+                ("nickname", false),
+                ("tags", false),
+                ("scores", false),
+                ("meta", false),
+                ("visits", false),
+            ]
+        );
+        assert_eq!(user.fields[5].field_type, NimFieldType::Array(Box::new(NimFieldType::Float32), Some(4)));
+        assert_eq!(platform_width(&user.fields[7].field_type), Some(PlatformWidth::Pointer));
+
+        assert_eq!(types[2].kind, NimTypeKind::RefObject);
+        assert_eq!(types[2].base.as_deref(), Some("RootObj"));
+
+        let shape = &types[4];
+        assert_eq!(shape.type_params, vec!["T"]);
+        let branches: Vec<_> = shape.fields.iter().map(|f| (f.name.as_str(), f.branch.as_deref())).collect();
+        assert_eq!(
+            branches,
+            vec![
+                ("label", None),
+                ("kind", None),
+                ("radius", Some("skCircle")),
+                ("width", Some("skRect")),
+                ("height", Some("skRect")),
+            ]
+        );
+        assert_eq!(
+            ir_fields(shape)[2].ty,
+            IrType::Container(ContainerType::Option(Box::new(IrType::TypeParam("T".to_string()))))
+        );
+    }
+
+    #[test]
+    fn test_refs_and_pointers_may_be_nil() {
+        let s = analyze_nim_type("type\n  S = object\n    a: ref Node\n    b: ptr int32\n    c: Option[ref Node]\n    d: cstring\n").unwrap();
+        assert_eq!(s.fields[1].field_type, NimFieldType::Ptr(Box::new(NimFieldType::Int32)));
+        let types: Vec<_> = ir_fields(&s).into_iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(types, vec!["Option<Node>", "Option<i32>", "Option<Option<Node>>", "String"]);
+    }
+
+    #[test]
+    fn test_tables_and_sets() {
+        let source = "type\n  S = object\n    a: Table[string, int]\n    b: OrderedTable[int, bool]\n    c: CountTable[string]\n    \
+                      d: HashSet[int]\n    e: set[char]\n";
+        let s = analyze_nim_type(source).unwrap();
+        // A count table maps each key to how often it was seen
+        assert_eq!(s.fields[2].field_type, NimFieldType::Table(Box::new(NimFieldType::String), Box::new(NimFieldType::Int)));
+        let types: Vec<_> = ir_fields(&s).into_iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(types, vec!["HashMap<String, i64>", "HashMap<i64, bool>", "HashMap<String, i64>", "Vec<i64>", "Vec<String>"]);
+        assert_eq!(platform_width(&s.fields[3].field_type), Some(PlatformWidth::Pointer));
+    }
+
+    #[test]
+    fn test_array_indices() {
+        let s = analyze_nim_type("type\n  S = object\n    a: array[0..3, int]\n    b: array[Color, bool]\n    c: array[4, uint8]\n").unwrap();
+        let types: Vec<_> = s.fields.iter().map(|f| f.field_type.clone()).collect();
+        // Only a literal count is a length; ranges and enum indices are not
+        assert_eq!(
+            types,
+            vec![
+                NimFieldType::Array(Box::new(NimFieldType::Int), None),
+                NimFieldType::Array(Box::new(NimFieldType::Bool), None),
+                NimFieldType::Array(Box::new(NimFieldType::UInt8), Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_variant_branches() {
+        let source = "type\n  S = object\n    case kind: K\n    of kA, kB:\n      case sub: Sub\n      of sX:\n        x: int\n      else:\n        \
+                      y: string\n    of kC: discard\n";
+        let s = analyze_nim_type(source).unwrap();
+        let branches: Vec<_> = s.fields.iter().map(|f| (f.name.as_str(), f.branch.as_deref())).collect();
+        assert_eq!(branches, vec![("kind", None), ("sub", Some("kA, kB")), ("x", Some("kA, kB/sX")), ("y", Some("kA, kB/else"))]);
+        assert_eq!(ir_fields(&s)[0].ty, IrType::Reference("K".to_string()));
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let types = analyze_nim_types("type\n  E = enum\n    a = (1, \"one\"), b = (2, \"two\")\n  F = enum x, y, z\n").unwrap();
+        // A value may pair an ordinal with its string
+        assert_eq!(types[0].values[0], NimEnumValue { name: "a".to_string(), value: Some("(1, \"one\")".to_string()) });
+        assert_eq!(types[1].values.iter().map(|v| v.name.as_str()).collect::<Vec<_>>(), vec!["x", "y", "z"]);
+        assert!(ir_fields(&types[1]).is_empty());
+    }
+
+    #[test]
+    fn test_only_objects_and_enums_are_types() {
+        let source = "type\n  Alias = seq[int]\n  Dist = distinct string\n  Tup = tuple[a: int]\n  Cpt = concept x\n    x.foo\n  \
+                      Cb = proc (x: int)\n  S = object\n    `type`: string\n    when defined(debug):\n      trace: seq[string]\n    \
+                      t: tuple[a, b: int]\n    p: proc (x: int): bool {.closure.}\n    r: Result[int, string]\n    d: times.DateTime\n";
+        let types = analyze_nim_types(source).unwrap();
+        assert_eq!(types.len(), 1);
+        let fields: Vec<_> = ir_fields(&types[0]).into_iter().map(|f| (f.name, f.ty)).collect();
+        assert_eq!(fields[0].0, "type");
+        // Fields behind `when` exist on some builds
+        assert_eq!(fields[1].0, "trace");
+        assert_eq!(fields[2].1, IrType::Any);
+        assert_eq!(fields[3].1, IrType::Any);
+        assert_eq!(render_type(&fields[4].1, "rust"), "Result<i64, String>");
+        assert_eq!(fields[5].1, IrType::Reference("DateTime".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(analyze_nim_types("type\n  S = object\n    a int\n").unwrap_err(), "line 3: expected `:`, found `int`");
+        assert_eq!(analyze_nim_types("type\n  S = object\n    a: seq[int\n").unwrap_err(), "expected `,` or `]`, found end of input");
+        assert_eq!(analyze_nim_types("type\n  S = object\n    a: Table[string int]\n").unwrap_err(), "line 3: expected `,` or `]`, found `int`");
+        assert_eq!(analyze_nim_types("type\n  S = object\n    : int\n").unwrap_err(), "line 3: expected field, found `:`");
+        assert_eq!(analyze_nim_types("type\n  S[T = object\n").unwrap_err(), "expected `]`, found end of input");
+        assert_eq!(analyze_nim_types("type\n  S = object\n    case kind: K\n    of kA\n      x: int\n").unwrap_err(), "expected `:`, found end of input");
+        assert_eq!(analyze_nim_type("proc f() = discard").unwrap_err(), "Type definition not found");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_nim_type("type\n  Pixel* = object\n    r*: uint8\n    dx*: int16\n    w*: uint16\n    s*: int8\n").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::U16, PrimitiveType::I8].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let user = &analyze_nim_types(SOURCE).unwrap()[1];
+        assert_eq!(map_to_target(&user.fields[3].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&user.fields[4].field_type, "rust"), "Vec<String>");
+        assert_eq!(compatibility_score(user, "rust"), 1.0);
+    }
+}
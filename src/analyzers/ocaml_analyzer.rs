@@ -0,0 +1,537 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// OCaml type analyzer for protocol-squisher integration
+// Analyzes record and variant `type` definitions in .ml/.mli files
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// OCaml type information extracted from source
+#[derive(Debug, Clone)]
+pub struct OCamlType {
+    pub name: String,
+    /// Type parameters without the quote (`('a, 'b) t` → `["a", "b"]`)
+    pub type_params: Vec<String>,
+    /// Fields of a record type
+    pub fields: Vec<OCamlField>,
+    /// Constructors of a variant type
+    pub variants: Vec<OCamlVariant>,
+    /// Right-hand side of an alias (`type id = int`); `None` with no fields or
+    /// variants means an abstract type
+    pub alias: Option<OCamlFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct OCamlField {
+    pub name: String,
+    pub field_type: OCamlFieldType,
+    pub mutable: bool,
+}
+
+/// Variant constructor; tuple payloads get positional field names ("0", "1", ...)
+#[derive(Debug, Clone)]
+pub struct OCamlVariant {
+    pub name: String,
+    pub fields: Vec<OCamlField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OCamlFieldType {
+    Int,     // 63-bit on 64-bit platforms
+    Int32,
+    Int64,
+    Float,
+    String,
+    Bool,
+    Char,
+    Unit,
+    List(Box<OCamlFieldType>),
+    Array(Box<OCamlFieldType>),
+    Option(Box<OCamlFieldType>),
+    Result(Box<OCamlFieldType>, Box<OCamlFieldType>),
+    Tuple(Vec<OCamlFieldType>),
+    /// Named type with arguments (`user`, `Address.t`, `string Tree.t`)
+    Named(String, Vec<OCamlFieldType>),
+    /// Type variable (`'a`)
+    TypeParam(String),
+}
+
+const OCAML_SYNTAX: Syntax = Syntax {
+    line_comments: &[],
+    block_comments: &[("(*", "*)")],
+    string_quotes: &['"'],
+    ident_extra: &['\''],
+    char_literals: false,
+};
+
+/// Analyze the first OCaml type definition and extract metadata
+pub fn analyze_ocaml_type(source: &str) -> Result<OCamlType, String> {
+    analyze_ocaml_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `type ... and ...` definition in an OCaml source or interface file
+pub fn analyze_ocaml_types(source: &str) -> Result<Vec<OCamlType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &OCAML_SYNTAX));
+    let mut types = Vec::new();
+
+    while !cursor.at_end() {
+        let module_type = cursor.previous().is_some_and(|t| t.text == "module");
+        if cursor.is_ident("type") && !module_type {
+            cursor.advance();
+            cursor.eat_ident("nonrec");
+            loop {
+                let line = cursor.line();
+                if let Some(declared) = parse_definition(&mut cursor, line)? {
+                    types.push(declared);
+                }
+                if !cursor.eat_ident("and") {
+                    break;
+                }
+            }
+        } else {
+            cursor.advance();
+        }
+    }
+
+    Ok(types)
+}
+
+fn parse_definition(cursor: &mut Cursor, line: usize) -> Result<Option<OCamlType>, String> {
+    let type_params = parse_type_params(cursor)?;
+    let name = cursor.expect_ident()?;
+
+    let mut declared = OCamlType {
+        name,
+        type_params,
+        fields: Vec::new(),
+        variants: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    };
+
+    // Extensions (`type t += A`) add constructors to a type declared elsewhere
+    if cursor.is_punct('+') {
+        skip_to_next_item(cursor);
+        return Ok(None);
+    }
+
+    if cursor.eat_punct('=') {
+        cursor.eat_ident("private");
+        if cursor.is_punct('{') {
+            declared.fields = parse_record(cursor)?;
+        } else if is_variant_start(cursor) {
+            declared.variants = parse_variants(cursor)?;
+        } else if cursor.is_punct('.') {
+            // Extensible variant (`type t = ..`): constructors live elsewhere
+            cursor.advance();
+            cursor.eat_punct('.');
+        } else {
+            declared.alias = Some(parse_type(cursor)?);
+            // Re-exported representation (`type t = M.t = { ... }`)
+            if cursor.eat_punct('=') {
+                cursor.eat_ident("private");
+                declared.alias = None;
+                if cursor.is_punct('{') {
+                    declared.fields = parse_record(cursor)?;
+                } else {
+                    declared.variants = parse_variants(cursor)?;
+                }
+            }
+        }
+    }
+
+    skip_attributes(cursor);
+    Ok(Some(declared))
+}
+
+fn parse_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut params = Vec::new();
+    if cursor.is_punct('(') && (cursor.is_punct_at(1, '\'') || cursor.is_punct_at(1, '+') || cursor.is_punct_at(1, '-')) {
+        cursor.advance();
+        loop {
+            cursor.eat_punct('+');
+            cursor.eat_punct('-');
+            params.push(parse_type_var(cursor)?);
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        cursor.expect_punct(')')?;
+    } else if cursor.is_punct('\'') || cursor.is_ident("_") {
+        params.push(parse_type_var(cursor)?);
+    }
+    Ok(params)
+}
+
+fn parse_type_var(cursor: &mut Cursor) -> Result<String, String> {
+    if cursor.eat_ident("_") {
+        return Ok("_".to_string());
+    }
+    cursor.expect_punct('\'')?;
+    cursor.expect_ident()
+}
+
+fn is_variant_start(cursor: &Cursor) -> bool {
+    cursor.is_punct('|')
+        || cursor.peek().is_some_and(|t| {
+            t.kind == TokenKind::Ident
+                && t.text.starts_with(|c: char| c.is_uppercase())
+                && !cursor.is_punct_at(1, '.')
+        })
+}
+
+fn parse_record(cursor: &mut Cursor) -> Result<Vec<OCamlField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        let mutable = cursor.eat_ident("mutable");
+        let name = cursor.expect_ident()?;
+        cursor.expect_punct(':')?;
+        let field_type = parse_type(cursor)?;
+        skip_attributes(cursor);
+        fields.push(OCamlField { name, field_type, mutable });
+        if !cursor.eat_punct(';') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`;` or `}`"));
+        }
+        // Field attributes may also follow the separator
+        skip_attributes(cursor);
+    }
+    Ok(fields)
+}
+
+fn parse_variants(cursor: &mut Cursor) -> Result<Vec<OCamlVariant>, String> {
+    let mut variants = Vec::new();
+    cursor.eat_punct('|');
+    loop {
+        let name = cursor.expect_ident()?;
+        let mut fields = Vec::new();
+        if cursor.eat_ident("of") {
+            if cursor.is_punct('{') {
+                fields = parse_record(cursor)?;
+            } else {
+                let payload = match parse_type(cursor)? {
+                    OCamlFieldType::Tuple(members) => members,
+                    single => vec![single],
+                };
+                fields = payload
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, field_type)| OCamlField { name: i.to_string(), field_type, mutable: false })
+                    .collect();
+            }
+        } else if cursor.is_punct(':') {
+            return Err(format!("line {}: GADT constructors are not supported", cursor.line()));
+        }
+        skip_attributes(cursor);
+        variants.push(OCamlVariant { name, fields });
+        if !cursor.eat_punct('|') {
+            break;
+        }
+    }
+    Ok(variants)
+}
+
+/// Skip `[@attr ...]` and `[@@attr ...]` annotations
+fn skip_attributes(cursor: &mut Cursor) {
+    while cursor.is_punct('[') && cursor.is_punct_at(1, '@') {
+        cursor.skip_balanced('[', ']');
+    }
+}
+
+fn skip_to_next_item(cursor: &mut Cursor) {
+    while let Some(token) = cursor.peek() {
+        if token.kind == TokenKind::Ident
+            && matches!(token.text.as_str(), "type" | "let" | "val" | "module" | "open" | "include" | "exception" | "external" | "end")
+        {
+            return;
+        }
+        cursor.advance();
+    }
+}
+
+/// Type expression: tuples (`a * b`) of postfix applications (`int list option`)
+fn parse_type(cursor: &mut Cursor) -> Result<OCamlFieldType, String> {
+    let mut members = vec![parse_application(cursor)?];
+    while cursor.eat_punct('*') {
+        members.push(parse_application(cursor)?);
+    }
+    if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+        return Err(format!("line {}: function types are not supported", cursor.line()));
+    }
+    Ok(if members.len() == 1 {
+        members.remove(0)
+    } else {
+        OCamlFieldType::Tuple(members)
+    })
+}
+
+fn parse_application(cursor: &mut Cursor) -> Result<OCamlFieldType, String> {
+    let mut args = if cursor.eat_punct('(') {
+        let mut args = vec![parse_type(cursor)?];
+        while cursor.eat_punct(',') {
+            args.push(parse_type(cursor)?);
+        }
+        cursor.expect_punct(')')?;
+        args
+    } else {
+        vec![parse_atom(cursor)?]
+    };
+
+    // Each following (lowercase) type constructor applies to everything before it
+    while cursor
+        .peek()
+        .is_some_and(|t| t.kind == TokenKind::Ident && !is_keyword(&t.text))
+        && !is_constructor_name(cursor)
+    {
+        let constructor = parse_path(cursor)?;
+        args = vec![apply(constructor, std::mem::take(&mut args), cursor.line())?];
+    }
+
+    match args.len() {
+        1 => Ok(args.remove(0)),
+        _ => Err(format!("line {}: type argument list is missing its constructor", cursor.line())),
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "and" | "of" | "type" | "let" | "val" | "module" | "open" | "include" | "exception" | "external" | "end" | "mutable" | "private" | "constraint" | "in" | "sig" | "struct")
+}
+
+/// A capitalized identifier not followed by `.` is a constructor, not a type path
+fn is_constructor_name(cursor: &Cursor) -> bool {
+    cursor.peek().is_some_and(|t| t.text.starts_with(|c: char| c.is_uppercase())) && !cursor.is_punct_at(1, '.')
+}
+
+fn parse_atom(cursor: &mut Cursor) -> Result<OCamlFieldType, String> {
+    if cursor.is_punct('\'') || cursor.is_ident("_") {
+        let var = parse_type_var(cursor)?;
+        return Ok(OCamlFieldType::TypeParam(var));
+    }
+    let path = parse_path(cursor)?;
+    apply(path, Vec::new(), cursor.line())
+}
+
+/// Module path ending in a type name (`Foo.Bar.t`)
+fn parse_path(cursor: &mut Cursor) -> Result<String, String> {
+    let mut path = cursor.expect_ident()?;
+    while cursor.is_punct('.') {
+        cursor.advance();
+        path = format!("{}.{}", path, cursor.expect_ident()?);
+    }
+    Ok(path)
+}
+
+fn apply(constructor: String, mut args: Vec<OCamlFieldType>, line: usize) -> Result<OCamlFieldType, String> {
+    let arity = |expected: usize, args: &Vec<OCamlFieldType>| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(format!("line {}: `{}` expects {} type argument(s), found {}", line, constructor, expected, args.len()))
+        }
+    };
+
+    Ok(match constructor.as_str() {
+        "int" => OCamlFieldType::Int,
+        "int32" | "Int32.t" => OCamlFieldType::Int32,
+        "int64" | "Int64.t" => OCamlFieldType::Int64,
+        "float" => OCamlFieldType::Float,
+        "string" => OCamlFieldType::String,
+        "bool" => OCamlFieldType::Bool,
+        "char" => OCamlFieldType::Char,
+        "unit" => OCamlFieldType::Unit,
+        "list" => {
+            arity(1, &args)?;
+            OCamlFieldType::List(Box::new(args.remove(0)))
+        }
+        "array" => {
+            arity(1, &args)?;
+            OCamlFieldType::Array(Box::new(args.remove(0)))
+        }
+        "option" => {
+            arity(1, &args)?;
+            OCamlFieldType::Option(Box::new(args.remove(0)))
+        }
+        "result" => {
+            arity(2, &args)?;
+            let ok = args.remove(0);
+            OCamlFieldType::Result(Box::new(ok), Box::new(args.remove(0)))
+        }
+        _ => OCamlFieldType::Named(constructor.clone(), args),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(ocaml_type: &OCamlType, target: &str) -> f32 {
+    match target {
+        "rust" | "rescript" => average_fidelity(ir_fields(ocaml_type).iter().map(|field| ir::fidelity(&field.ty, target))),
+        _ => 0.0,
+    }
+}
+
+/// Lower an OCaml field type into the language-neutral IR
+pub fn to_ir(field_type: &OCamlFieldType) -> IrType {
+    match field_type {
+        OCamlFieldType::Int | OCamlFieldType::Int64 => IrType::Primitive(PrimitiveType::I64),
+        OCamlFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        OCamlFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        OCamlFieldType::String | OCamlFieldType::Char => IrType::Primitive(PrimitiveType::String),
+        OCamlFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        OCamlFieldType::Unit => IrType::Primitive(PrimitiveType::Unit),
+        OCamlFieldType::List(inner) | OCamlFieldType::Array(inner) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(inner))))
+        }
+        OCamlFieldType::Option(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        OCamlFieldType::Result(ok, err) => {
+            IrType::Container(ContainerType::Result(Box::new(to_ir(ok)), Box::new(to_ir(err))))
+        }
+        OCamlFieldType::Tuple(members) => IrType::Tuple(members.iter().map(to_ir).collect()),
+        OCamlFieldType::Named(name, _) => IrType::Reference(name.clone()),
+        OCamlFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower record fields, or the payload fields of every constructor, into the IR
+pub fn ir_fields(ocaml_type: &OCamlType) -> Vec<IrField> {
+    let record = ocaml_type.fields.iter().map(|field| (field.name.clone(), field));
+    let payloads = ocaml_type.variants.iter().flat_map(|variant| {
+        variant
+            .fields
+            .iter()
+            .map(move |field| (format!("{}.{}", variant.name, field.name), field))
+    });
+    record
+        .chain(payloads)
+        .map(|(name, field)| IrField::new(&name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map OCaml types to target language types
+pub fn map_to_target(field_type: &OCamlFieldType, target: &str) -> String {
+    match target {
+        "rust" => match field_type {
+            OCamlFieldType::Int | OCamlFieldType::Int64 => "i64".to_string(),
+            OCamlFieldType::Int32 => "i32".to_string(),
+            OCamlFieldType::Float => "f64".to_string(),
+            OCamlFieldType::String => "String".to_string(),
+            OCamlFieldType::Bool => "bool".to_string(),
+            OCamlFieldType::Char => "char".to_string(),
+            OCamlFieldType::Unit => "()".to_string(),
+            OCamlFieldType::List(inner) | OCamlFieldType::Array(inner) => {
+                format!("Vec<{}>", map_to_target(inner, target))
+            }
+            OCamlFieldType::Option(inner) => {
+                format!("Option<{}>", map_to_target(inner, target))
+            }
+            OCamlFieldType::Result(ok, err) => {
+                format!("Result<{}, {}>", map_to_target(ok, target), map_to_target(err, target))
+            }
+            OCamlFieldType::Tuple(members) => {
+                let members: Vec<String> = members.iter().map(|m| map_to_target(m, target)).collect();
+                format!("({})", members.join(", "))
+            }
+            OCamlFieldType::Named(name, args) => {
+                // `Address.t` → `Address`, `user` → `User`
+                let base = name.strip_suffix(".t").unwrap_or(name);
+                let base = base.rsplit('.').next().unwrap_or(base);
+                let base = crate::codegen::pascal_case(base);
+                if args.is_empty() {
+                    base
+                } else {
+                    let args: Vec<String> = args.iter().map(|a| map_to_target(a, target)).collect();
+                    format!("{}<{}>", base, args.join(", "))
+                }
+            }
+            OCamlFieldType::TypeParam(name) => name.to_uppercase(),
+        },
+        "rescript" => match field_type {
+            OCamlFieldType::Int | OCamlFieldType::Int32 => "int".to_string(),
+            OCamlFieldType::Int64 => "bigint".to_string(),
+            OCamlFieldType::Float => "float".to_string(),
+            OCamlFieldType::String => "string".to_string(),
+            OCamlFieldType::Bool => "bool".to_string(),
+            OCamlFieldType::Char => "char".to_string(),
+            OCamlFieldType::Unit => "unit".to_string(),
+            OCamlFieldType::List(inner) => format!("list<{}>", map_to_target(inner, target)),
+            OCamlFieldType::Array(inner) => format!("array<{}>", map_to_target(inner, target)),
+            OCamlFieldType::Option(inner) => format!("option<{}>", map_to_target(inner, target)),
+            OCamlFieldType::Result(ok, err) => {
+                format!("result<{}, {}>", map_to_target(ok, target), map_to_target(err, target))
+            }
+            OCamlFieldType::Tuple(members) => {
+                let members: Vec<String> = members.iter().map(|m| map_to_target(m, target)).collect();
+                format!("({})", members.join(", "))
+            }
+            OCamlFieldType::Named(name, args) => {
+                if args.is_empty() {
+                    name.clone()
+                } else {
+                    let args: Vec<String> = args.iter().map(|a| map_to_target(a, target)).collect();
+                    format!("{}<{}>", name, args.join(", "))
+                }
+            }
+            OCamlFieldType::TypeParam(name) => format!("'{}", name),
+        },
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rescript_analyzer;
+
+    #[test]
+    fn test_analyze_record() {
+        let source = r#"
+(* A user record *)
+type user = {
+  id : int;
+  mutable name : string;
+  email : string option; [@default None]
+  tags : string list;
+} [@@deriving yojson]
+"#;
+        let user = analyze_ocaml_type(source).unwrap();
+        assert_eq!(user.name, "user");
+        assert_eq!(user.fields.len(), 4);
+        assert!(user.fields[1].mutable);
+        assert_eq!(user.fields[2].field_type, OCamlFieldType::Option(Box::new(OCamlFieldType::String)));
+        assert_eq!(map_to_target(&user.fields[3].field_type, "rust"), "Vec<String>");
+        assert_eq!(map_to_target(&user.fields[3].field_type, "rescript"), "list<string>");
+    }
+
+    #[test]
+    fn test_analyze_variants_and_params() {
+        let source = r#"
+type status = Active | Suspended of string | Moved of { street : string; zip : int }
+and ('a, 'e) outcome = ('a, 'e) result
+val status_to_string : status -> string
+"#;
+        let types = analyze_ocaml_types(source).unwrap();
+        assert_eq!(types.len(), 2);
+        let status = &types[0];
+        assert_eq!(status.variants.len(), 3);
+        assert!(status.variants[0].fields.is_empty());
+        assert_eq!(status.variants[1].fields[0].field_type, OCamlFieldType::String);
+        assert_eq!(status.variants[2].fields[1].name, "zip");
+
+        let outcome = &types[1];
+        assert_eq!(outcome.type_params, vec!["a", "e"]);
+        assert_eq!(map_to_target(outcome.alias.as_ref().unwrap(), "rust"), "Result<A, E>");
+    }
+
+    #[test]
+    fn test_score_against_rescript() {
+        let ocaml = analyze_ocaml_type("type user = { id : int; name : string; email : string; active : bool }").unwrap();
+        // OCaml's 63-bit int is wider than ReScript's
+        assert_eq!(compatibility_score(&ocaml, "rescript"), 0.875);
+
+        let rescript = rescript_analyzer::analyze_rescript_type("type user = { id: int, name: string, email: string, active: bool }").unwrap();
+        let score = ir::compare_fields(&ir_fields(&ocaml), &rescript_analyzer::ir_fields(&rescript));
+        assert_eq!(score, 1.0);
+        assert_eq!(crate::calculate_transport_class("ocaml", "rescript"), crate::TransportClass::Concorde);
+    }
+}
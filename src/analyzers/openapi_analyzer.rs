@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// OpenAPI 3 analyzer
+// Reads `components.schemas` from a YAML or JSON API description; each schema
+// is a JSON Schema, so typing, hoisting, and lowering are shared with the
+// JSON Schema analyzer
+
+use super::json_schema_analyzer::{self, JsonSchemaType};
+use crate::json::{self, Json};
+use crate::yaml;
+
+pub use super::json_schema_analyzer::{compatibility_score, ir_alias, ir_fields, ir_variants, map_to_target, to_ir};
+
+/// Analyze the first component schema of an OpenAPI document
+pub fn analyze_openapi_type(source: &str) -> Result<JsonSchemaType, String> {
+    analyze_openapi_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every schema under `components.schemas`
+pub fn analyze_openapi_types(source: &str) -> Result<Vec<JsonSchemaType>, String> {
+    let document = if source.trim_start().starts_with('{') { json::parse(source)? } else { yaml::parse(source)? };
+    // Swagger 2.0 keeps its schemas under `definitions` and isn't covered
+    if document.get("openapi").is_none() {
+        return Err("not an OpenAPI 3 document".to_string());
+    }
+    let schemas = match document.get("components").and_then(|components| components.get("schemas")) {
+        Some(Json::Object(schemas)) => schemas.as_slice(),
+        _ => &[],
+    };
+    Ok(json_schema_analyzer::analyze_schema_map(schemas, "#/components/schemas"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::JsonSchemaTypeKind;
+    use crate::codegen::render_type;
+
+    const PETSTORE: &str = r#"openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+          format: int64
+        name:
+          type: string
+        tag:
+          type: string
+          nullable: true
+        status:
+          $ref: '#/components/schemas/pet-status'
+        owner:
+          type: object
+          properties:
+            email: { type: string, format: email }
+    pet-status:
+      type: string
+      enum:
+        - available
+        - sold
+"#;
+
+    #[test]
+    fn test_analyze_openapi_yaml() {
+        let types = analyze_openapi_types(PETSTORE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Pet", JsonSchemaTypeKind::Object, "#/components/schemas/Pet"),
+                ("PetOwner", JsonSchemaTypeKind::Object, "#/components/schemas/Pet/properties/owner"),
+                ("PetStatus", JsonSchemaTypeKind::Enum, "#/components/schemas/pet-status"),
+            ]
+        );
+        let rust: Vec<_> = ir_fields(&types[0]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec!["id: i64", "name: String", "tag: Option<String>", "status: Option<PetStatus>", "owner: Option<PetOwner>"]
+        );
+        assert_eq!(types[2].values, vec!["available", "sold"]);
+        assert_eq!(compatibility_score(&types[1], "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_json_documents_and_errors() {
+        let json = r#"{ "openapi": "3.1.0", "components": { "schemas": { "Id": { "type": ["string", "null"] } } } }"#;
+        let id = analyze_openapi_type(json).unwrap();
+        assert_eq!(map_to_target(id.alias.as_ref().unwrap(), "rust"), "Option<String>");
+        assert!(analyze_openapi_types("openapi: 3.1.0\npaths: {}\n").unwrap().is_empty());
+        assert!(analyze_openapi_types("swagger: '2.0'\ndefinitions: {}\n").is_err());
+        assert!(analyze_openapi_types("{ \"openapi\": ").is_err());
+    }
+}
@@ -0,0 +1,718 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// PHP analyzer for protocol-squisher integration
+// Analyzes PHP 8 classes by their typed and constructor-promoted properties,
+// and backed or pure enums
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// `#` starts a comment except in `#[...]` attributes, which carry nothing the
+/// analyzer uses and are skipped like block comments; an attribute with nested
+/// brackets leaves a stray `)]` behind, which the member parser steps over
+const PHP: Syntax = Syntax {
+    line_comments: &["//", "#"],
+    block_comments: &[("/*", "*/"), ("#[", "]")],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// PHP class or enum
+#[derive(Debug, Clone)]
+pub struct PhpType {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub kind: PhpTypeKind,
+    /// Instance properties, promoted constructor parameters included, in
+    /// declaration order
+    pub fields: Vec<PhpField>,
+    /// `int` or `string` for a backed enum
+    pub backing: Option<PhpFieldType>,
+    pub cases: Vec<PhpCase>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhpTypeKind {
+    Class,
+    Enum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhpVisibility {
+    Public,
+    Protected,
+    Private,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhpField {
+    pub name: String,
+    pub field_type: PhpFieldType,
+    pub visibility: PhpVisibility,
+    pub readonly: bool,
+}
+
+/// `case Active = 'active';`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhpCase {
+    pub name: String,
+    /// Backing value of a backed enum
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhpFieldType {
+    Int,
+    Float,
+    String,
+    /// `bool`, `true`, `false`
+    Bool,
+    Null,
+    /// `mixed`, or an untyped property
+    Mixed,
+    /// Ordered map: a list when its keys are `0..n`, otherwise a dictionary
+    Array,
+    Iterable,
+    Object,
+    Callable,
+    /// `DateTimeInterface` and its implementations
+    DateTime,
+    /// `?T`
+    Nullable(Box<PhpFieldType>),
+    Union(Vec<PhpFieldType>),
+    /// Class, interface, or enum, as written
+    Named(String),
+}
+
+const MODIFIERS: &[&str] = &["public", "protected", "private", "static", "readonly", "final", "abstract", "var"];
+
+/// A member's modifiers
+#[derive(Default)]
+struct Modifiers {
+    words: Vec<String>,
+}
+
+impl Modifiers {
+    fn has(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+
+    /// Properties and methods are public unless declared otherwise
+    fn visibility(&self) -> PhpVisibility {
+        if self.has("private") {
+            PhpVisibility::Private
+        } else if self.has("protected") {
+            PhpVisibility::Protected
+        } else {
+            PhpVisibility::Public
+        }
+    }
+}
+
+/// Analyze the first PHP class or enum and extract metadata
+pub fn analyze_php_type(source: &str) -> Result<PhpType, String> {
+    analyze_php_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every class and enum in a PHP file
+pub fn analyze_php_types(source: &str) -> Result<Vec<PhpType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &PHP));
+    let mut types = Vec::new();
+    let mut namespace = None;
+
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "namespace" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident || t.text == "{") => {
+                cursor.advance();
+                namespace = if cursor.is_punct('{') { None } else { Some(parse_qualified_name(&mut cursor)?) };
+                // `namespace App\Model { ... }` closes with a `}` skipped below
+                if !cursor.eat_punct('{') {
+                    cursor.expect_punct(';')?;
+                }
+            }
+            // Open and close tags, and the closing brace of a braced namespace
+            ";" | "}" | "<" | "?" | ">" | "php" => {
+                cursor.advance();
+            }
+            _ => {
+                let modifiers = parse_modifiers(&mut cursor);
+                if cursor.is_ident("class") {
+                    parse_class(&mut cursor, &namespace, &mut types)?;
+                } else if cursor.is_ident("enum") && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+                    parse_enum(&mut cursor, &namespace, &mut types)?;
+                } else if modifiers.words.is_empty() {
+                    // Imports, interfaces, traits, functions, and statements
+                    skip_statement(&mut cursor);
+                }
+            }
+        }
+    }
+
+    Ok(types)
+}
+
+/// `App\Model\User`, with or without a leading `\`
+fn parse_qualified_name(cursor: &mut Cursor) -> Result<String, String> {
+    cursor.eat_punct('\\');
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('\\') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('\\');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+fn parse_modifiers(cursor: &mut Cursor) -> Modifiers {
+    let mut modifiers = Modifiers::default();
+    // `static::` is an expression, not a modifier
+    while let Some(word) = cursor
+        .peek()
+        .filter(|t| MODIFIERS.contains(&t.text.as_str()) && !cursor.is_punct_at(1, ':'))
+        .map(|t| t.text.clone())
+    {
+        cursor.advance();
+        modifiers.words.push(word);
+    }
+    modifiers
+}
+
+/// `class Name extends Base implements A, B { ... }`
+fn parse_class(cursor: &mut Cursor, namespace: &Option<String>, types: &mut Vec<PhpType>) -> Result<(), String> {
+    let line = cursor.line();
+    cursor.advance();
+    let name = cursor.expect_ident()?;
+    while !cursor.at_end() && !cursor.is_punct('{') {
+        cursor.advance();
+    }
+
+    let mut fields = Vec::new();
+    cursor.expect_punct('{')?;
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        let modifiers = parse_modifiers(cursor);
+        match cursor.peek().map(|t| t.text.as_str()) {
+            Some("function") => parse_method(cursor, &name, &mut fields)?,
+            Some("const" | "use" | "case") => skip_statement(cursor),
+            Some(_) if !modifiers.words.is_empty() => parse_properties(cursor, &name, &modifiers, &mut fields)?,
+            // Leftovers of an attribute with nested brackets
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+
+    types.push(PhpType {
+        name,
+        namespace: namespace.clone(),
+        kind: PhpTypeKind::Class,
+        fields,
+        backing: None,
+        cases: Vec::new(),
+        location: format!("line {}", line),
+    });
+    Ok(())
+}
+
+/// `enum Status: string implements HasLabel { case Active = 'active'; ... }`
+fn parse_enum(cursor: &mut Cursor, namespace: &Option<String>, types: &mut Vec<PhpType>) -> Result<(), String> {
+    let line = cursor.line();
+    cursor.advance();
+    let name = cursor.expect_ident()?;
+    let backing = if cursor.eat_punct(':') { Some(parse_type(cursor, &name)?) } else { None };
+    while !cursor.at_end() && !cursor.is_punct('{') {
+        cursor.advance();
+    }
+
+    let mut cases = Vec::new();
+    cursor.expect_punct('{')?;
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        if !cursor.eat_ident("case") {
+            // Constants, methods, trait uses
+            parse_modifiers(cursor);
+            skip_statement(cursor);
+            continue;
+        }
+        let case = cursor.expect_ident()?;
+        let value = if cursor.eat_punct('=') {
+            let negative = cursor.eat_punct('-');
+            let token = cursor.advance().ok_or_else(|| cursor.unexpected("case value"))?;
+            Some(if negative { format!("-{}", token.text) } else { token.text })
+        } else {
+            None
+        };
+        cases.push(PhpCase { name: case, value });
+        skip_statement(cursor);
+    }
+
+    types.push(PhpType {
+        name,
+        namespace: namespace.clone(),
+        kind: PhpTypeKind::Enum,
+        fields: Vec::new(),
+        backing,
+        cases,
+        location: format!("line {}", line),
+    });
+    Ok(())
+}
+
+/// `public ?string $email = null, $alias;`, or a PHP 8.4 hooked property
+fn parse_properties(
+    cursor: &mut Cursor,
+    class: &str,
+    modifiers: &Modifiers,
+    fields: &mut Vec<PhpField>,
+) -> Result<(), String> {
+    let field_type = if cursor.is_punct('$') { PhpFieldType::Mixed } else { parse_type(cursor, class)? };
+    loop {
+        cursor.expect_punct('$')?;
+        let name = cursor.expect_ident()?;
+        if cursor.eat_punct('=') {
+            skip_default(cursor);
+        }
+        if !modifiers.has("static") {
+            fields.push(PhpField {
+                name,
+                field_type: field_type.clone(),
+                visibility: modifiers.visibility(),
+                readonly: modifiers.has("readonly"),
+            });
+        }
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    if cursor.is_punct('{') {
+        cursor.skip_balanced('{', '}');
+        return Ok(());
+    }
+    cursor.expect_punct(';')
+}
+
+/// Skip a method; the constructor contributes its promoted parameters
+fn parse_method(cursor: &mut Cursor, class: &str, fields: &mut Vec<PhpField>) -> Result<(), String> {
+    cursor.advance();
+    cursor.eat_punct('&');
+    let constructor = cursor.is_ident("__construct");
+    cursor.advance();
+    if !constructor || !cursor.eat_punct('(') {
+        skip_statement(cursor);
+        return Ok(());
+    }
+
+    while !cursor.eat_punct(')') {
+        let modifiers = parse_modifiers(cursor);
+        let field_type = if cursor.is_punct('$') || cursor.is_punct('&') || cursor.is_punct('.') {
+            PhpFieldType::Mixed
+        } else {
+            parse_type(cursor, class)?
+        };
+        // By-reference and variadic markers
+        while cursor.eat_punct('&') || cursor.eat_punct('.') {}
+        cursor.expect_punct('$')?;
+        let name = cursor.expect_ident()?;
+        if cursor.eat_punct('=') {
+            skip_default(cursor);
+        }
+        let promoted = ["public", "protected", "private", "readonly"].iter().any(|word| modifiers.has(word));
+        if promoted {
+            fields.push(PhpField {
+                name,
+                field_type,
+                visibility: modifiers.visibility(),
+                readonly: modifiers.has("readonly"),
+            });
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    skip_statement(cursor);
+    Ok(())
+}
+
+/// A default value, up to the `,`, `;`, or `)` that ends it
+fn skip_default(cursor: &mut Cursor) {
+    let mut depth = 0usize;
+    while let Some(token) = cursor.peek() {
+        if token.kind == TokenKind::Punct {
+            match token.text.as_str() {
+                "," | ";" | ")" if depth == 0 => return,
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        cursor.advance();
+    }
+}
+
+/// `?int`, `int|string|null`, `(A&B)|null`; `self` and `static` name the class
+fn parse_type(cursor: &mut Cursor, class: &str) -> Result<PhpFieldType, String> {
+    if cursor.eat_punct('?') {
+        return Ok(nullable(parse_intersection(cursor, class)?));
+    }
+    let mut members = vec![parse_intersection(cursor, class)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_intersection(cursor, class)?);
+    }
+    Ok(if members.len() == 1 { members.remove(0) } else { PhpFieldType::Union(members) })
+}
+
+fn parse_intersection(cursor: &mut Cursor, class: &str) -> Result<PhpFieldType, String> {
+    if cursor.eat_punct('(') {
+        let ty = parse_type(cursor, class)?;
+        cursor.expect_punct(')')?;
+        return Ok(ty);
+    }
+    let mut ty = parse_named_type(cursor, class)?;
+    // Intersections describe interfaces, not data; `&$x` is a by-reference parameter
+    while cursor.is_punct('&') && !cursor.is_punct_at(1, '$') && !cursor.is_punct_at(1, '.') {
+        cursor.advance();
+        parse_named_type(cursor, class)?;
+        ty = PhpFieldType::Mixed;
+    }
+    Ok(ty)
+}
+
+fn parse_named_type(cursor: &mut Cursor, class: &str) -> Result<PhpFieldType, String> {
+    let name = parse_qualified_name(cursor)?;
+    let simple = name.rsplit('\\').next().unwrap_or(&name);
+    // Scalar type names are case-insensitive
+    Ok(match simple.to_ascii_lowercase().as_str() {
+        "int" => PhpFieldType::Int,
+        "float" => PhpFieldType::Float,
+        "string" => PhpFieldType::String,
+        "bool" | "true" | "false" => PhpFieldType::Bool,
+        "null" => PhpFieldType::Null,
+        "mixed" => PhpFieldType::Mixed,
+        "array" => PhpFieldType::Array,
+        "iterable" => PhpFieldType::Iterable,
+        "object" => PhpFieldType::Object,
+        "callable" | "closure" => PhpFieldType::Callable,
+        "self" | "static" => PhpFieldType::Named(class.to_string()),
+        "datetimeinterface" | "datetime" | "datetimeimmutable" => PhpFieldType::DateTime,
+        _ => PhpFieldType::Named(name),
+    })
+}
+
+/// `?T`; `mixed` and `null` already admit null
+fn nullable(ty: PhpFieldType) -> PhpFieldType {
+    match ty {
+        PhpFieldType::Mixed | PhpFieldType::Null | PhpFieldType::Nullable(_) => ty,
+        ty => PhpFieldType::Nullable(Box::new(ty)),
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(php_type: &PhpType, target: &str) -> f32 {
+    average_fidelity(ir_fields(php_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a PHP field type into the language-neutral IR
+pub fn to_ir(field_type: &PhpFieldType) -> IrType {
+    match field_type {
+        // `PHP_INT_SIZE` is 8 on every 64-bit build
+        PhpFieldType::Int => IrType::Primitive(PrimitiveType::I64),
+        PhpFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        PhpFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        PhpFieldType::Null => IrType::Primitive(PrimitiveType::Unit),
+        // Dates cross the boundary as ISO 8601
+        PhpFieldType::String | PhpFieldType::DateTime => IrType::Primitive(PrimitiveType::String),
+        // An array encodes as a list or an object depending on its keys, which
+        // the declared type doesn't say
+        PhpFieldType::Mixed
+        | PhpFieldType::Array
+        | PhpFieldType::Iterable
+        | PhpFieldType::Object
+        | PhpFieldType::Callable => IrType::Any,
+        PhpFieldType::Nullable(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        PhpFieldType::Union(members) => union_to_ir(members),
+        PhpFieldType::Named(name) => IrType::Reference(name.rsplit('\\').next().unwrap_or(name).to_string()),
+    }
+}
+
+/// `T|null` is an option; anything else stays a union
+fn union_to_ir(members: &[PhpFieldType]) -> IrType {
+    let nullable = members.contains(&PhpFieldType::Null);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != PhpFieldType::Null) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all properties of a PHP type into the IR
+pub fn ir_fields(php_type: &PhpType) -> Vec<IrField> {
+    php_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map PHP types to target language types
+pub fn map_to_target(field_type: &PhpFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r#"<?php
+declare(strict_types=1);
+
+namespace App\Model;
+
+use Symfony\Component\Validator\Constraints as Assert;
+
+# Serialized to the mobile clients
+final class User extends Model implements \JsonSerializable
+{
+    public const MAX_TAGS = 10;
+    use HasTimestamps;
+
+    #[Assert\Choice(['admin', 'member'])]
+    public string $role = 'member';
+    public ?string $email = null;
+    protected array $tags = [], $aliases = [];
+    public int|string|null $key;
+    private static int $count = 0;
+    public $legacy;
+    public readonly Status $status;
+    public \DateTimeImmutable $createdAt;
+
+    public function __construct(
+        public readonly int $id,
+        private ?self $manager = null,
+        string $unpromoted = 'x',
+        float ...$scores,
+    ) {
+        parent::__construct();
+    }
+
+    public function jsonSerialize(): mixed
+    {
+        return ['id' => $this->id, 'class' => static::class];
+    }
+}
+
+enum Status: string implements HasLabel
+{
+    case Active = 'active';
+    case Suspended = 'suspended';
+
+    const DEFAULT = self::Active;
+
+    public function label(): string
+    {
+        return match ($this) {
+            self::Active => 'Active',
+            self::Suspended => 'Suspended',
+        };
+    }
+}
+
+enum Suit
+{
+    case Hearts;
+    case Spades;
+}
+
+interface HasLabel
+{
+    public function label(): string;
+}
+"#;
+
+    #[test]
+    fn test_analyze_typed_properties() {
+        let types = analyze_php_types(USER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![("User", PhpTypeKind::Class), ("Status", PhpTypeKind::Enum), ("Suit", PhpTypeKind::Enum)]
+        );
+
+        let user = &types[0];
+        assert_eq!(user.namespace.as_deref(), Some("App\\Model"));
+        let fields: Vec<_> = user.fields.iter().map(|f| (f.name.as_str(), f.visibility, f.readonly)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("role", PhpVisibility::Public, false),
+                ("email", PhpVisibility::Public, false),
+                ("tags", PhpVisibility::Protected, false),
+                ("aliases", PhpVisibility::Protected, false),
+                ("key", PhpVisibility::Public, false),
+                ("legacy", PhpVisibility::Public, false),
+                ("status", PhpVisibility::Public, true),
+                ("createdAt", PhpVisibility::Public, false),
+                ("id", PhpVisibility::Public, true),
+                ("manager", PhpVisibility::Private, false),
+            ]
+        );
+        assert_eq!(map_to_target(&user.fields[1].field_type, "rust"), "Option<String>");
+        assert_eq!(user.fields[2].field_type, PhpFieldType::Array);
+        assert!(matches!(to_ir(&user.fields[4].field_type), IrType::Container(ContainerType::Option(_))));
+        assert_eq!(map_to_target(&user.fields[6].field_type, "rust"), "Status");
+        assert_eq!(user.fields[9].field_type, PhpFieldType::Nullable(Box::new(PhpFieldType::Named("User".to_string()))));
+        assert!(compatibility_score(user, "rust") < 1.0);
+    }
+
+    #[test]
+    fn test_nullable_spellings_and_unions() {
+        let cell = analyze_php_type(
+            "<?php class Cell { public ?int $a; public int|null $b; public null|string|int $c; public int|float $d; \
+             public ?mixed $e = null; public (Countable&Traversable)|null $f; public true|false $g; public INT $h; }",
+        )
+        .unwrap();
+        assert_eq!(cell.fields[1].field_type, PhpFieldType::Union(vec![PhpFieldType::Int, PhpFieldType::Null]));
+        assert_eq!(cell.fields[4].field_type, PhpFieldType::Mixed);
+        let rust: Vec<_> = cell.fields.iter().map(|f| map_to_target(&f.field_type, "rust")).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "Option<i64>",
+                "Option<i64>",
+                "Option<StringOrInt>",
+                "IntOrNumber",
+                "serde_json::Value",
+                "Option<serde_json::Value>",
+                "bool",
+                "i64",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_special_and_qualified_types() {
+        let node = analyze_php_type(
+            "<?php class Node { public iterable $a; public object $b; public \\Closure $c; public \\DateTimeInterface $d; \
+             public \\App\\Model\\User $e; public static $f; public self $g; }",
+        )
+        .unwrap();
+        let types: Vec<_> = node.fields.iter().map(|f| (f.name.as_str(), to_ir(&f.field_type))).collect();
+        assert_eq!(
+            types,
+            vec![
+                ("a", IrType::Any),
+                ("b", IrType::Any),
+                ("c", IrType::Any),
+                ("d", IrType::Primitive(PrimitiveType::String)),
+                ("e", IrType::Reference("User".to_string())),
+                ("g", IrType::Reference("Node".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_int_backed_enum_values() {
+        let level = analyze_php_type("<?php enum Level: int { case Low = 1; case Debt = -5; case High = 0x10; }").unwrap();
+        assert_eq!(level.backing, Some(PhpFieldType::Int));
+        let cases: Vec<_> = level.cases.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(cases, vec![("Low", Some("1")), ("Debt", Some("-5")), ("High", Some("0x10"))]);
+        assert!(level.fields.is_empty());
+    }
+
+    #[test]
+    fn test_braced_namespaces_and_hooks() {
+        let types = analyze_php_types(
+            "<?php namespace App { class A { public int $x; } } namespace { class B { public string $y; } }\n\
+             class Hooked { public string $name { get => strtoupper($this->name); } public int $n = 1; }",
+        )
+        .unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.namespace.as_deref())).collect();
+        assert_eq!(names, vec![("A", Some("App")), ("B", None), ("Hooked", None)]);
+        let hooked: Vec<_> = types[2].fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(hooked, vec!["name", "n"]);
+    }
+
+    #[test]
+    fn test_promoted_parameters() {
+        let options = analyze_php_type(
+            "<?php class Options { function __construct(int &$ref, protected string $label = 'x', public array $opts = ['a' => [1, 2]]) {} }",
+        )
+        .unwrap();
+        let fields: Vec<_> = options.fields.iter().map(|f| (f.name.as_str(), f.field_type.clone(), f.visibility)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("label", PhpFieldType::String, PhpVisibility::Protected),
+                ("opts", PhpFieldType::Array, PhpVisibility::Public),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integers_are_64_bit() {
+        let counter = analyze_php_type("<?php class Counter { public int $hits; public ?int $limit; }").unwrap();
+        let widths: Vec<_> = ir_fields(&counter).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            vec![
+                IrType::Primitive(PrimitiveType::I64),
+                IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(PrimitiveType::I64)))),
+            ]
+        );
+        assert_eq!(compatibility_score(&counter, "rust"), 1.0);
+        // Past 2^53 a JavaScript number rounds
+        assert_eq!(compatibility_score(&counter, "typescript"), 0.5);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(analyze_php_type("<?php class A { public int $a").unwrap_err(), "expected `;`, found end of input");
+        assert_eq!(analyze_php_type("<?php class A { public int a; }").unwrap_err(), "line 1: expected `$`, found `a`");
+        assert_eq!(
+            analyze_php_type("<?php class A { function __construct(public int $a public int $b) {} }").unwrap_err(),
+            "line 1: expected `,` or `)`, found `public`"
+        );
+        assert_eq!(analyze_php_type("<?php enum E: string { case A = ").unwrap_err(), "expected case value, found end of input");
+        assert_eq!(analyze_php_type("<?php class A { public ?| $a; }").unwrap_err(), "line 1: expected identifier, found `|`");
+        assert_eq!(analyze_php_type("<?php function f() {}").unwrap_err(), "Type definition not found");
+    }
+
+    #[test]
+    fn test_analyze_enums() {
+        let types = analyze_php_types(USER).unwrap();
+        assert_eq!(types[1].backing, Some(PhpFieldType::String));
+        let cases: Vec<_> = types[1].cases.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(cases, vec![("Active", Some("active")), ("Suspended", Some("suspended"))]);
+        assert_eq!(types[2].backing, None);
+        assert_eq!(types[2].cases[1], PhpCase { name: "Spades".to_string(), value: None });
+
+        let point = analyze_php_type("<?php readonly class Point { public function __construct(public float $x, public float $y) {} }").unwrap();
+        assert_eq!(point.fields.len(), 2);
+        assert_eq!(compatibility_score(&point, "rust"), 1.0);
+    }
+}
@@ -0,0 +1,575 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Protobuf analyzer for protocol-squisher integration
+// Analyzes proto2, proto3, and editions `.proto` files by their messages and
+// enums, lowered the way the proto3 JSON mapping puts them on the wire
+
+use std::collections::HashMap;
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, IrVariant, PrimitiveType};
+
+const PROTO: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Protobuf message or enum
+#[derive(Debug, Clone)]
+pub struct ProtoType {
+    /// Nested types are spelled `Outer.Inner`, without the package
+    pub name: String,
+    pub package: Option<String>,
+    pub kind: ProtoTypeKind,
+    /// In declaration order, `oneof` members included
+    pub fields: Vec<ProtoField>,
+    pub values: Vec<ProtoEnumValue>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtoTypeKind {
+    Message,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProtoField {
+    pub name: String,
+    pub number: u32,
+    pub field_type: ProtoFieldType,
+    pub label: ProtoLabel,
+    /// The `oneof` the field belongs to
+    pub oneof: Option<String>,
+}
+
+/// Cardinality and presence of a field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtoLabel {
+    /// proto3 scalar without `optional`: absent reads as the default value
+    Singular,
+    /// Tracks presence: `optional`, proto2 and editions fields, proto3
+    /// message fields, and `oneof` members
+    Optional,
+    Repeated,
+    /// proto2 `required`
+    Required,
+}
+
+/// `ACTIVE = 1;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtoEnumValue {
+    pub name: String,
+    pub number: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtoFieldType {
+    Double,
+    Float,
+    /// `int32`, `sint32`, `sfixed32`
+    Int32,
+    /// `int64`, `sint64`, `sfixed64`
+    Int64,
+    /// `uint32`, `fixed32`
+    UInt32,
+    /// `uint64`, `fixed64`
+    UInt64,
+    Bool,
+    String,
+    Bytes,
+    Map(Box<ProtoFieldType>, Box<ProtoFieldType>),
+    /// Message, by the name it resolved to (`User.Address`, `google.protobuf.Timestamp`)
+    Message(String),
+    Enum(String),
+}
+
+/// Analyze the first Protobuf type and extract metadata
+pub fn analyze_proto_type(source: &str) -> Result<ProtoType, String> {
+    analyze_proto_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every message and enum in a `.proto` file, nested ones included
+pub fn analyze_proto_types(source: &str) -> Result<Vec<ProtoType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &PROTO));
+    let mut parser = Parser { types: Vec::new(), package: None, explicit_presence: true };
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "syntax" | "edition" => {
+                let edition = token.text == "edition";
+                cursor.advance();
+                cursor.expect_punct('=')?;
+                let value = cursor.advance().ok_or_else(|| cursor.unexpected("syntax"))?;
+                // proto3 is the only syntax where scalars lack presence by default
+                parser.explicit_presence = edition || value.text != "proto3";
+                cursor.expect_punct(';')?;
+            }
+            "package" => {
+                cursor.advance();
+                parser.package = Some(parse_full_name(&mut cursor)?);
+                cursor.expect_punct(';')?;
+            }
+            "message" => parser.parse_message(&mut cursor, "")?,
+            "enum" => parser.parse_enum(&mut cursor, "")?,
+            // Stray `}`s would stop `skip_statement` in place
+            ";" | "}" => {
+                cursor.advance();
+            }
+            // `import`, `option`, `service`, `extend`
+            _ => skip_statement(&mut cursor),
+        }
+    }
+    resolve(&mut parser.types, parser.explicit_presence);
+    Ok(parser.types)
+}
+
+struct Parser {
+    types: Vec<ProtoType>,
+    package: Option<String>,
+    /// Whether singular scalars track presence (proto2 and editions)
+    explicit_presence: bool,
+}
+
+impl Parser {
+    fn new_type(&self, name: String, kind: ProtoTypeKind, line: usize) -> ProtoType {
+        ProtoType {
+            name,
+            package: self.package.clone(),
+            kind,
+            fields: Vec::new(),
+            values: Vec::new(),
+            location: format!("line {}", line),
+        }
+    }
+
+    fn parse_message(&mut self, cursor: &mut Cursor, outer: &str) -> Result<(), String> {
+        let line = cursor.line();
+        cursor.advance();
+        let name = qualify(outer, &cursor.expect_ident()?);
+        cursor.expect_punct('{')?;
+        let index = self.types.len();
+        self.types.push(self.new_type(name.clone(), ProtoTypeKind::Message, line));
+
+        loop {
+            let Some(token) = cursor.peek() else {
+                return Err(cursor.unexpected("`}`"));
+            };
+            let declares = cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(2, '{');
+            match token.text.as_str() {
+                "}" => {
+                    cursor.advance();
+                    return Ok(());
+                }
+                ";" => {
+                    cursor.advance();
+                }
+                "message" if declares => self.parse_message(cursor, &name)?,
+                "enum" if declares => self.parse_enum(cursor, &name)?,
+                "oneof" if declares => {
+                    cursor.advance();
+                    let oneof = cursor.expect_ident()?;
+                    cursor.expect_punct('{')?;
+                    while !cursor.eat_punct('}') {
+                        if cursor.at_end() {
+                            return Err(cursor.unexpected("`}`"));
+                        }
+                        if cursor.is_ident("option") || cursor.is_punct(';') {
+                            skip_statement(cursor);
+                            continue;
+                        }
+                        if let Some(mut field) = self.parse_field(cursor, ProtoLabel::Optional)? {
+                            field.oneof = Some(oneof.clone());
+                            self.types[index].fields.push(field);
+                        }
+                    }
+                }
+                "reserved" | "extensions" | "option" | "extend" => skip_statement(cursor),
+                _ => {
+                    let label = match token.text.as_str() {
+                        "optional" => Some(ProtoLabel::Optional),
+                        "repeated" => Some(ProtoLabel::Repeated),
+                        "required" => Some(ProtoLabel::Required),
+                        _ => None,
+                    };
+                    if label.is_some() {
+                        cursor.advance();
+                    }
+                    let default = if self.explicit_presence { ProtoLabel::Optional } else { ProtoLabel::Singular };
+                    if let Some(field) = self.parse_field(cursor, label.unwrap_or(default))? {
+                        self.types[index].fields.push(field);
+                    }
+                }
+            }
+        }
+    }
+
+    /// `type name = number [options];`; `None` for proto2 groups, which are skipped
+    fn parse_field(&mut self, cursor: &mut Cursor, label: ProtoLabel) -> Result<Option<ProtoField>, String> {
+        if cursor.is_ident("group") && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            skip_statement(cursor);
+            return Ok(None);
+        }
+        let field_type = parse_type(cursor)?;
+        let name = cursor.expect_ident()?;
+        cursor.expect_punct('=')?;
+        let number = cursor.advance().ok_or_else(|| cursor.unexpected("field number"))?;
+        let number = parse_number(&number.text)
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| format!("line {}: invalid field number `{}`", number.line, number.text))?;
+        if cursor.is_punct('[') {
+            cursor.skip_balanced('[', ']');
+        }
+        cursor.expect_punct(';')?;
+        Ok(Some(ProtoField { name, number, field_type, label, oneof: None }))
+    }
+
+    fn parse_enum(&mut self, cursor: &mut Cursor, outer: &str) -> Result<(), String> {
+        let line = cursor.line();
+        cursor.advance();
+        let mut enumeration = self.new_type(qualify(outer, &cursor.expect_ident()?), ProtoTypeKind::Enum, line);
+        cursor.expect_punct('{')?;
+        while !cursor.eat_punct('}') {
+            let token = cursor.peek().ok_or_else(|| cursor.unexpected("`}`"))?;
+            if matches!(token.text.as_str(), "option" | "reserved" | ";") {
+                skip_statement(cursor);
+                continue;
+            }
+            let name = cursor.expect_ident()?;
+            cursor.expect_punct('=')?;
+            let negative = cursor.eat_punct('-');
+            let number = cursor.advance().ok_or_else(|| cursor.unexpected("enum number"))?;
+            let value = parse_number(&number.text)
+                .ok_or_else(|| format!("line {}: invalid enum number `{}`", number.line, number.text))?;
+            if cursor.is_punct('[') {
+                cursor.skip_balanced('[', ']');
+            }
+            cursor.expect_punct(';')?;
+            enumeration.values.push(ProtoEnumValue { name, number: if negative { -value } else { value } });
+        }
+        self.types.push(enumeration);
+        Ok(())
+    }
+}
+
+fn qualify(outer: &str, name: &str) -> String {
+    if outer.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", outer, name)
+    }
+}
+
+/// `shop.v1.User`, or `.shop.v1.User` fully qualified
+fn parse_full_name(cursor: &mut Cursor) -> Result<String, String> {
+    let mut name = String::new();
+    if cursor.eat_punct('.') {
+        name.push('.');
+    }
+    name.push_str(&cursor.expect_ident()?);
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    Ok(name)
+}
+
+/// Decimal, `0x` hex, or `0` octal
+fn parse_number(text: &str) -> Option<i64> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()
+    } else if text.len() > 1 && text.starts_with('0') {
+        i64::from_str_radix(&text[1..], 8).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn parse_type(cursor: &mut Cursor) -> Result<ProtoFieldType, String> {
+    if cursor.is_ident("map") && cursor.is_punct_at(1, '<') {
+        cursor.advance();
+        cursor.advance();
+        let key = parse_type(cursor)?;
+        cursor.expect_punct(',')?;
+        let value = parse_type(cursor)?;
+        cursor.expect_punct('>')?;
+        return Ok(ProtoFieldType::Map(Box::new(key), Box::new(value)));
+    }
+    let name = parse_full_name(cursor)?;
+    Ok(match name.as_str() {
+        "double" => ProtoFieldType::Double,
+        "float" => ProtoFieldType::Float,
+        "int32" | "sint32" | "sfixed32" => ProtoFieldType::Int32,
+        "int64" | "sint64" | "sfixed64" => ProtoFieldType::Int64,
+        "uint32" | "fixed32" => ProtoFieldType::UInt32,
+        "uint64" | "fixed64" => ProtoFieldType::UInt64,
+        "bool" => ProtoFieldType::Bool,
+        "string" => ProtoFieldType::String,
+        "bytes" => ProtoFieldType::Bytes,
+        // Enum or message; `resolve` decides once every type is known
+        _ => ProtoFieldType::Message(name),
+    })
+}
+
+/// Resolve type names by protobuf scoping (innermost scope first), turn enum
+/// references into [`ProtoFieldType::Enum`], and give message fields presence
+fn resolve(types: &mut [ProtoType], explicit_presence: bool) {
+    let kinds: HashMap<String, ProtoTypeKind> = types.iter().map(|t| (t.name.clone(), t.kind)).collect();
+    for proto_type in types.iter_mut() {
+        let scope = proto_type.name.clone();
+        let package = proto_type.package.clone().unwrap_or_default();
+        for field in &mut proto_type.fields {
+            resolve_field_type(&mut field.field_type, &scope, &package, &kinds);
+            // proto3 message fields track presence even without `optional`
+            if !explicit_presence && field.label == ProtoLabel::Singular && matches!(field.field_type, ProtoFieldType::Message(_)) {
+                field.label = ProtoLabel::Optional;
+            }
+        }
+    }
+}
+
+fn resolve_field_type(field_type: &mut ProtoFieldType, scope: &str, package: &str, kinds: &HashMap<String, ProtoTypeKind>) {
+    match field_type {
+        ProtoFieldType::Map(key, value) => {
+            resolve_field_type(key, scope, package, kinds);
+            resolve_field_type(value, scope, package, kinds);
+        }
+        ProtoFieldType::Message(name) => {
+            let relative = match name.strip_prefix('.') {
+                // Fully qualified: only this file's package can be resolved here
+                Some(full) => match full.strip_prefix(package).and_then(|rest| rest.strip_prefix('.')) {
+                    Some(local) if !package.is_empty() => local.to_string(),
+                    _ => full.to_string(),
+                },
+                None => name.clone(),
+            };
+            let mut enclosing = Some(scope);
+            let resolved = loop {
+                let candidate = match enclosing {
+                    Some(outer) => qualify(outer, &relative),
+                    None => relative.clone(),
+                };
+                if let Some(kind) = kinds.get(&candidate) {
+                    break Some((candidate, *kind));
+                }
+                match enclosing {
+                    Some(outer) => enclosing = outer.rsplit_once('.').map(|(parent, _)| parent),
+                    None => break None,
+                }
+            };
+            // Imported types are assumed to be messages
+            *field_type = match resolved {
+                Some((name, ProtoTypeKind::Enum)) => ProtoFieldType::Enum(name),
+                Some((name, ProtoTypeKind::Message)) => ProtoFieldType::Message(name),
+                None => ProtoFieldType::Message(relative),
+            };
+        }
+        _ => {}
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(proto_type: &ProtoType, target: &str) -> f32 {
+    average_fidelity(ir_fields(proto_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Protobuf field type into the language-neutral IR
+pub fn to_ir(field_type: &ProtoFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        ProtoFieldType::Double => primitive(PrimitiveType::F64),
+        ProtoFieldType::Float => primitive(PrimitiveType::F32),
+        ProtoFieldType::Int32 => primitive(PrimitiveType::I32),
+        ProtoFieldType::Int64 => primitive(PrimitiveType::I64),
+        ProtoFieldType::UInt32 => primitive(PrimitiveType::U32),
+        ProtoFieldType::UInt64 => primitive(PrimitiveType::U64),
+        ProtoFieldType::Bool => primitive(PrimitiveType::Bool),
+        // Base64 in the JSON mapping
+        ProtoFieldType::String => primitive(PrimitiveType::String),
+        ProtoFieldType::Bytes => primitive(PrimitiveType::Bytes),
+        ProtoFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        ProtoFieldType::Enum(name) => IrType::Reference(ir_name(name)),
+        ProtoFieldType::Message(name) => match name.strip_prefix("google.protobuf.") {
+            // RFC 3339 timestamps, `"1.5s"` durations, and comma-joined paths
+            Some("Timestamp" | "Duration" | "FieldMask") => primitive(PrimitiveType::String),
+            Some("Any" | "Struct" | "Value" | "ListValue") => IrType::Any,
+            Some("Empty") => primitive(PrimitiveType::Unit),
+            // Wrappers read as their value; the field's presence makes them optional
+            Some("DoubleValue") => primitive(PrimitiveType::F64),
+            Some("FloatValue") => primitive(PrimitiveType::F32),
+            Some("Int32Value") => primitive(PrimitiveType::I32),
+            Some("Int64Value") => primitive(PrimitiveType::I64),
+            Some("UInt32Value") => primitive(PrimitiveType::U32),
+            Some("UInt64Value") => primitive(PrimitiveType::U64),
+            Some("BoolValue") => primitive(PrimitiveType::Bool),
+            Some("StringValue") => primitive(PrimitiveType::String),
+            Some("BytesValue") => primitive(PrimitiveType::Bytes),
+            _ => IrType::Reference(ir_name(name)),
+        },
+    }
+}
+
+/// Name a type goes by in the IR: nested types are joined (`User.Status` is
+/// `UserStatus`), and the package of an imported type, lower-case by
+/// convention, is dropped (`other.v1.Money` is `Money`)
+pub fn ir_name(name: &str) -> String {
+    let joined: String = name.split('.').skip_while(|segment| segment.starts_with(|c: char| c.is_ascii_lowercase())).collect();
+    match joined.is_empty() {
+        true => name.rsplit('.').next().unwrap_or(name).to_string(),
+        false => joined,
+    }
+}
+
+/// Lower an enum's values into unit variants tagged with the value names the
+/// JSON mapping writes; messages have no variants
+pub fn ir_variants(proto_type: &ProtoType) -> Vec<IrVariant> {
+    proto_type
+        .values
+        .iter()
+        .map(|value| IrVariant::new(&pascal_case(&value.name.to_lowercase()), Vec::new()).with_tag(Some(value.name.clone())))
+        .collect()
+}
+
+/// Lower all fields of a Protobuf type into the IR; repeated fields become
+/// lists and fields with presence become options
+pub fn ir_fields(proto_type: &ProtoType) -> Vec<IrField> {
+    proto_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = match field.label {
+                // Maps are already collections
+                ProtoLabel::Repeated if matches!(field.field_type, ProtoFieldType::Map(..)) => ty,
+                ProtoLabel::Repeated => IrType::Container(ContainerType::Vec(Box::new(ty))),
+                ProtoLabel::Optional => IrType::Container(ContainerType::Option(Box::new(ty))),
+                ProtoLabel::Singular | ProtoLabel::Required => ty,
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map Protobuf types to target language types
+pub fn map_to_target(field_type: &ProtoFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_proto3_messages() {
+        let source = r#"
+syntax = "proto3";
+package shop.v1;
+
+import "google/protobuf/timestamp.proto";
+option java_multiple_files = true;
+
+/* A registered customer */
+message User {
+  int64 id = 1;
+  string name = 2;
+  optional string email = 3;
+  repeated string tags = 4;
+  map<string, int32> scores = 5;
+  Address address = 6;
+  google.protobuf.Timestamp created_at = 7 [deprecated = true];
+  Status status = 8;
+  oneof contact {
+    string phone = 9;
+    Address mailing = 10;
+  }
+  reserved 11, 12 to 15;
+  reserved "legacy";
+
+  enum Status {
+    option allow_alias = true;
+    STATUS_UNSPECIFIED = 0;
+    ACTIVE = 1;
+    ENABLED = 1;
+  }
+}
+
+message Address {
+  string city = 1;
+  .shop.v1.User.Status owner_status = 2;
+  bytes avatar = 0x3;
+}
+
+service Users {
+  rpc Get(User) returns (User);
+}
+"#;
+        let types = analyze_proto_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![("User", ProtoTypeKind::Message), ("User.Status", ProtoTypeKind::Enum), ("Address", ProtoTypeKind::Message)]
+        );
+        assert_eq!(types[0].package.as_deref(), Some("shop.v1"));
+
+        let user = &types[0];
+        let rust: Vec<_> = ir_fields(user).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: i64",
+                "name: String",
+                "email: Option<String>",
+                "tags: Vec<String>",
+                "scores: HashMap<String, i32>",
+                "address: Option<Address>",
+                "created_at: Option<String>",
+                "status: UserStatus",
+                "phone: Option<String>",
+                "mailing: Option<Address>",
+            ]
+        );
+        assert_eq!(user.fields[7].field_type, ProtoFieldType::Enum("User.Status".to_string()));
+        assert_eq!(user.fields[9].oneof.as_deref(), Some("contact"));
+        assert_eq!(types[1].values[2], ProtoEnumValue { name: "ENABLED".to_string(), number: 1 });
+        let variants: Vec<_> = ir_variants(&types[1]).iter().map(|v| (v.name.clone(), v.wire_tag().to_string())).collect();
+        assert_eq!(variants[1], ("Active".to_string(), "ACTIVE".to_string()));
+        assert_eq!(ir_name("User.Status"), "UserStatus");
+        assert_eq!(ir_name("other.v1.Money"), "Money");
+        assert_eq!(types[2].fields[1].field_type, ProtoFieldType::Enum("User.Status".to_string()));
+        assert_eq!(types[2].fields[2].number, 3);
+        assert_eq!(compatibility_score(&types[2], "rust"), 1.0);
+        assert_eq!(compatibility_score(user, "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_proto2_fields_track_presence() {
+        let source = r#"
+syntax = "proto2";
+message Legacy {
+  required int32 id = 1;
+  optional uint64 size = 2 [default = 0];
+  repeated Legacy children = 3;
+  optional group Extra = 4 { optional int32 n = 5; }
+  extensions 100 to max;
+}
+enum Sign { NEGATIVE = -1; ZERO = 0; }
+"#;
+        let types = analyze_proto_types(source).unwrap();
+        let legacy = &types[0];
+        let labels: Vec<_> = legacy.fields.iter().map(|f| (f.name.as_str(), f.label)).collect();
+        assert_eq!(labels, vec![("id", ProtoLabel::Required), ("size", ProtoLabel::Optional), ("children", ProtoLabel::Repeated)]);
+        assert_eq!(map_to_target(&legacy.fields[2].field_type, "typescript"), "Legacy");
+        assert_eq!(types[1].values[0].number, -1);
+        assert!(analyze_proto_types("message Broken { int32 id = ; }").is_err());
+    }
+}
@@ -2,21 +2,65 @@
 // ReScript type analyzer for protocol-squisher integration
 // Analyzes ReScript types and generates compatibility metadata
 
-use std::collections::HashMap;
+use super::lexer::{block_doc, tokenize, Cursor, Syntax, Token, TokenKind};
+use crate::ir::{self, average_fidelity, ContainerType, DefaultValue, IrField, IrType, IrVariant, PolyBound, PrimitiveType, UnionShape, VariantTagging};
 
 /// ReScript type information extracted from source
 #[derive(Debug, Clone)]
 pub struct ReScriptType {
     pub name: String,
+    pub kind: ReScriptTypeKind,
+    /// Type variables of a parametric type (`type page<'a>` → `a`)
+    pub type_params: Vec<String>,
+    /// The type an alias (`type userId = int`) stands for
+    pub aliased: Option<ReScriptFieldType>,
+    /// Fields of a record
     pub fields: Vec<ReScriptField>,
+    /// Constructors of a variant
+    pub variants: Vec<ReScriptVariant>,
     pub location: String,
+    /// `/** */` doc comment, without markers
+    pub doc: Option<String>,
+    /// Set for a polymorphic variant (`[#Active | #Suspended(string)]`),
+    /// whose constructors are its tags without the `#`
+    pub polymorphic: Option<PolyBound>,
+    /// `@tag("kind")`: key naming the constructor of a payload, instead of `TAG`
+    pub tag: Option<String>,
+    /// `@unboxed`: a payload crosses alone, without its constructor
+    pub unboxed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReScriptTypeKind {
+    Record,
+    Variant,
+    Alias,
+    /// Abstract (`type email`) or private (`type email = private string`);
+    /// a private representation is kept as the positional field `0`
+    Opaque,
 }
 
 #[derive(Debug, Clone)]
 pub struct ReScriptField {
+    /// Key on the wire: the `@as("...")` rename if there is one
     pub name: String,
     pub field_type: ReScriptFieldType,
     pub optional: bool,
+    /// Default of the matching labeled argument of the type's `make`
+    /// (`~port=8080`; `~port=?` leaves it out)
+    pub default: Option<DefaultValue>,
+    /// `/** */` doc comment of a record field
+    pub doc: Option<String>,
+}
+
+/// Variant constructor; positional payloads get field names "0", "1", ...,
+/// and an inline record (`Moved({to: string})`) keeps its field names
+#[derive(Debug, Clone)]
+pub struct ReScriptVariant {
+    pub name: String,
+    pub fields: Vec<ReScriptField>,
+    /// `@as("active")`: the constructor's tag at run time, instead of its name
+    pub tag: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -26,73 +70,699 @@ pub enum ReScriptFieldType {
     Bool,          // Maps to bool
     Float,         // Maps to float (JavaScript number)
     Record(String),
+    /// User parametric type with its arguments (`page<user>`)
+    Generic(String, Vec<ReScriptFieldType>),
+    /// Type variable (`'a`), named without its quote
+    TypeParam(String),
     Array(Box<ReScriptFieldType>),
     Option(Box<ReScriptFieldType>),
+    /// `Js.Dict.t<v>` (string keys), `Belt.Map.Int.t<v>`, or `Belt.Map.t<k, v, id>`
+    Map(Box<ReScriptFieldType>, Box<ReScriptFieldType>),
+    /// `(int, string)`; `unit` is the empty tuple
+    Tuple(Vec<ReScriptFieldType>),
+    /// `int => string` or `(~id: int, string) => unit`, with its parameter
+    /// and return types; labels are dropped
+    Function(Vec<ReScriptFieldType>, Box<ReScriptFieldType>),
+    /// Use of a type alias, with the type it resolves to
+    Alias(String, Box<ReScriptFieldType>),
+    /// Polymorphic variant written in place (`[#Active | #Inactive]`)
+    PolyVariant(PolyBound, Vec<ReScriptVariant>),
 }
 
-/// Analyze ReScript type definition and extract metadata
+const RESCRIPT_SYNTAX: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    string_quotes: &['"', '`'],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Analyze the first ReScript record or variant type and extract metadata
 pub fn analyze_rescript_type(source: &str) -> Result<ReScriptType, String> {
-    // In real implementation, this would parse .res files
-    // For proof-of-concept, we'll analyze the User type example
-
-    if source.contains("type user") {
-        Ok(ReScriptType {
-            name: "user".to_string(),
-            fields: vec![
-                ReScriptField {
-                    name: "id".to_string(),
-                    field_type: ReScriptFieldType::Int,
-                    optional: false,
-                },
-                ReScriptField {
-                    name: "name".to_string(),
-                    field_type: ReScriptFieldType::String,
-                    optional: false,
-                },
-                ReScriptField {
-                    name: "email".to_string(),
-                    field_type: ReScriptFieldType::String,
-                    optional: false,
-                },
-                ReScriptField {
-                    name: "active".to_string(),
-                    field_type: ReScriptFieldType::Bool,
-                    optional: false,
-                },
-            ],
-            location: "examples/User.res".to_string(),
+    analyze_rescript_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every record and variant `type ... and ...` definition in a .res/.resi file;
+/// aliases and abstract types are skipped. A `let make = (~label=default, ...)`
+/// gives defaults to the record it returns, or else the record declared before it
+pub fn analyze_rescript_types(source: &str) -> Result<Vec<ReScriptType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &RESCRIPT_SYNTAX));
+    let mut types = Vec::new();
+    let mut decorators = Vec::new();
+
+    while !cursor.at_end() {
+        let module_type = cursor.previous().is_some_and(|t| t.text == "module");
+        if cursor.is_ident("type") && !module_type {
+            let mut doc_at = cursor.peek().map_or(0, |t| t.start);
+            cursor.advance();
+            cursor.eat_ident("rec");
+            loop {
+                let line = cursor.line();
+                if let Some(declared) = parse_definition(&mut cursor, source, line)? {
+                    types.push(ReScriptType {
+                        doc: block_doc(source, doc_at),
+                        tag: decorator(&decorators, "tag"),
+                        unboxed: decorators.iter().any(|(name, _)| name == "unboxed"),
+                        ..declared
+                    });
+                }
+                doc_at = cursor.peek().map_or(source.len(), |t| t.start);
+                decorators = parse_decorators(&mut cursor);
+                if !cursor.eat_ident("and") {
+                    break;
+                }
+            }
+        } else if cursor.is_ident("let") && cursor.peek_at(1).is_some_and(|t| t.text == "make") {
+            cursor.advance();
+            cursor.advance();
+            let (returns, defaults) = parse_make_defaults(&mut cursor, source);
+            let made = match returns {
+                Some(name) => types.iter_mut().find(|t: &&mut ReScriptType| t.name == name),
+                None => types.iter_mut().rev().find(|t| t.kind == ReScriptTypeKind::Record),
+            };
+            for field in made.into_iter().flat_map(|made| made.fields.iter_mut()) {
+                field.default = defaults.iter().find(|(label, _)| *label == field.name).map(|(_, default)| default.clone());
+            }
+            decorators.clear();
+        } else if cursor.is_punct('@') {
+            decorators = parse_decorators(&mut cursor);
+        } else {
+            cursor.advance();
+            decorators.clear();
+        }
+    }
+
+    resolve_aliases(&mut types)?;
+    Ok(types)
+}
+
+fn parse_definition(cursor: &mut Cursor, source: &str, line: usize) -> Result<Option<ReScriptType>, String> {
+    let name = cursor.expect_ident()?;
+    let mut type_params = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            cursor.expect_punct('\'')?;
+            type_params.push(cursor.expect_ident()?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+    let mut declared = ReScriptType {
+        name,
+        kind: ReScriptTypeKind::Record,
+        type_params,
+        aliased: None,
+        fields: Vec::new(),
+        variants: Vec::new(),
+        location: format!("line {}", line),
+        doc: None,
+        polymorphic: None,
+        tag: None,
+        unboxed: false,
+    };
+    if !cursor.eat_punct('=') {
+        declared.kind = ReScriptTypeKind::Opaque;
+        return Ok(Some(declared));
+    }
+    let private = cursor.eat_ident("private");
+
+    if cursor.is_punct('{') {
+        declared.fields = parse_record(cursor, source)?;
+    } else if cursor.is_punct('[') && !private {
+        let (bound, variants) = parse_poly_variant(cursor)?;
+        declared.kind = ReScriptTypeKind::Variant;
+        declared.variants = variants;
+        declared.polymorphic = Some(bound);
+    } else if is_variant_start(cursor) {
+        declared.kind = ReScriptTypeKind::Variant;
+        declared.variants = parse_variants(cursor, source)?;
+    } else {
+        // An alias must be a whole type expression; anything else
+        // (polymorphic variants, objects, functions) is skipped
+        let mut lookahead = cursor.clone();
+        match parse_type(&mut lookahead) {
+            Ok(aliased) if lookahead.at_end() || lookahead.is_punct('@') || is_item_start(&lookahead) => {
+                *cursor = lookahead;
+                if private {
+                    declared.kind = ReScriptTypeKind::Opaque;
+                    declared.fields = vec![ReScriptField { name: "0".to_string(), field_type: aliased, optional: false, default: None, doc: None }];
+                } else {
+                    declared.kind = ReScriptTypeKind::Alias;
+                    declared.aliased = Some(aliased);
+                }
+            }
+            _ => {
+                skip_to_next_item(cursor);
+                return Ok(None);
+            }
+        }
+    }
+    Ok(Some(declared))
+}
+
+/// Resolve every use of an alias defined among `types`; a plain alias keeps
+/// its name, a parametric one is expanded with its arguments bound
+fn resolve_aliases(types: &mut [ReScriptType]) -> Result<(), String> {
+    let aliases: Vec<ReScriptType> = types.iter().filter(|t| t.aliased.is_some()).cloned().collect();
+    if aliases.is_empty() {
+        return Ok(());
+    }
+    for rescript_type in types.iter_mut() {
+        let mut active = vec![rescript_type.name.clone()];
+        if let Some(aliased) = &rescript_type.aliased {
+            rescript_type.aliased = Some(resolve_alias(aliased, &aliases, &mut active)?);
+        }
+        let fields = rescript_type
+            .fields
+            .iter_mut()
+            .chain(rescript_type.variants.iter_mut().flat_map(|variant| variant.fields.iter_mut()));
+        for field in fields {
+            field.field_type = resolve_alias(&field.field_type, &aliases, &mut Vec::new())?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_alias(
+    field_type: &ReScriptFieldType,
+    aliases: &[ReScriptType],
+    active: &mut Vec<String>,
+) -> Result<ReScriptFieldType, String> {
+    let mut resolve = |inner: &ReScriptFieldType| resolve_alias(inner, aliases, active);
+    let (name, args) = match field_type {
+        ReScriptFieldType::Record(name) => (name, Vec::new()),
+        ReScriptFieldType::Generic(name, args) => (name, args.iter().map(&mut resolve).collect::<Result<Vec<_>, _>>()?),
+        ReScriptFieldType::Array(inner) => return Ok(ReScriptFieldType::Array(Box::new(resolve(inner)?))),
+        ReScriptFieldType::Option(inner) => return Ok(ReScriptFieldType::Option(Box::new(resolve(inner)?))),
+        ReScriptFieldType::Map(key, value) => {
+            return Ok(ReScriptFieldType::Map(Box::new(resolve(key)?), Box::new(resolve(value)?)));
+        }
+        ReScriptFieldType::Tuple(members) => {
+            return Ok(ReScriptFieldType::Tuple(members.iter().map(resolve).collect::<Result<_, _>>()?));
+        }
+        ReScriptFieldType::Function(params, ret) => {
+            let params = params.iter().map(&mut resolve).collect::<Result<_, _>>()?;
+            return Ok(ReScriptFieldType::Function(params, Box::new(resolve(ret)?)));
+        }
+        _ => return Ok(field_type.clone()),
+    };
+    let alias = match aliases.iter().find(|alias| &alias.name == name && alias.type_params.len() == args.len()) {
+        Some(alias) => alias,
+        None if args.is_empty() => return Ok(field_type.clone()),
+        None => return Ok(ReScriptFieldType::Generic(name.clone(), args)),
+    };
+    if active.contains(name) {
+        return Err(format!("type alias `{}` refers to itself", name));
+    }
+    active.push(name.clone());
+    let aliased = alias.aliased.as_ref().map(|aliased| substitute(aliased, &alias.type_params, &args));
+    let resolved = resolve_alias(aliased.as_ref().unwrap_or(field_type), aliases, active)?;
+    active.pop();
+    Ok(if args.is_empty() { ReScriptFieldType::Alias(name.clone(), Box::new(resolved)) } else { resolved })
+}
+
+fn is_variant_start(cursor: &Cursor) -> bool {
+    let mut lookahead = cursor.clone();
+    skip_decorators(&mut lookahead);
+    lookahead.is_punct('|')
+        || lookahead.peek().is_some_and(|t| {
+            t.kind == TokenKind::Ident && t.text.starts_with(|c: char| c.is_uppercase()) && !lookahead.is_punct_at(1, '.')
         })
+}
+
+/// `{id: int, mutable name: string, email?: string}`
+fn parse_record(cursor: &mut Cursor, source: &str) -> Result<Vec<ReScriptField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        let doc = cursor.peek().and_then(|t| block_doc(source, t.start));
+        let decorators = parse_decorators(cursor);
+        cursor.eat_ident("mutable");
+        let label = cursor.expect_ident()?;
+        let name = decorator(&decorators, "as").unwrap_or(label);
+        let optional = cursor.eat_punct('?');
+        cursor.expect_punct(':')?;
+        let field_type = parse_type(cursor)?;
+        fields.push(ReScriptField { name, field_type, optional, default: None, doc });
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(fields)
+}
+
+/// `| Active | Suspended(string) | Moved({to: string})`
+fn parse_variants(cursor: &mut Cursor, source: &str) -> Result<Vec<ReScriptVariant>, String> {
+    let mut variants = Vec::new();
+    let mut decorators = parse_decorators(cursor);
+    cursor.eat_punct('|');
+    loop {
+        decorators.extend(parse_decorators(cursor));
+        let name = cursor.expect_ident()?;
+        let mut fields = Vec::new();
+        if cursor.eat_punct('(') {
+            if cursor.is_punct('{') {
+                fields = parse_record(cursor, source)?;
+                cursor.eat_punct(',');
+            } else {
+                while !cursor.is_punct(')') {
+                    let field_type = parse_type(cursor)?;
+                    fields.push(ReScriptField { name: fields.len().to_string(), field_type, optional: false, default: None, doc: None });
+                    if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                        return Err(cursor.unexpected("`,` or `)`"));
+                    }
+                }
+            }
+            cursor.expect_punct(')')?;
+        }
+        variants.push(ReScriptVariant { name, fields, tag: decorator(&decorators, "as") });
+        // Decorators not followed by `|` belong to the next `and` type
+        let mut lookahead = cursor.clone();
+        decorators = parse_decorators(&mut lookahead);
+        if !lookahead.eat_punct('|') {
+            break;
+        }
+        *cursor = lookahead;
+    }
+    Ok(variants)
+}
+
+/// Labeled argument defaults of `= (~name, ~port=8080, ~tag=?, ()): t =>`,
+/// with the record type named as the result
+fn parse_make_defaults(cursor: &mut Cursor, source: &str) -> (Option<String>, Vec<(String, DefaultValue)>) {
+    let mut defaults = Vec::new();
+    if !cursor.eat_punct('=') {
+        return (None, defaults);
+    }
+    cursor.eat_ident("async");
+    if !cursor.eat_punct('(') {
+        return (None, defaults);
+    }
+    while !cursor.at_end() && !cursor.eat_punct(')') {
+        if cursor.eat_punct('~') {
+            let Ok(label) = cursor.expect_ident() else { break };
+            if cursor.eat_ident("as") {
+                cursor.advance();
+            }
+            if cursor.eat_punct(':') && parse_type(cursor).is_err() {
+                break;
+            }
+            if cursor.eat_punct('=') {
+                let default = if cursor.eat_punct('?') { DefaultValue::Null } else { literal_default(&argument(cursor), source) };
+                defaults.push((label, default));
+            }
+        }
+        argument(cursor);
+        cursor.eat_punct(',');
+    }
+    // `parse_type` would take the `=>` that follows for a function type
+    let returns = if cursor.eat_punct(':') { cursor.expect_ident().ok() } else { None };
+    (returns, defaults)
+}
+
+/// Tokens of one argument, up to the `,` or `)` that ends it
+fn argument(cursor: &mut Cursor) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth > 0 => depth -= 1,
+            "," | ")" | "}" if depth == 0 => break,
+            _ => {}
+        }
+        tokens.extend(cursor.advance());
+    }
+    tokens
+}
+
+/// A default expression as a literal where it is one (`8080`, `"en"`, `[]`),
+/// otherwise as the computation written
+fn literal_default(tokens: &[Token], source: &str) -> DefaultValue {
+    let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+    let number = |i: usize| tokens[i].kind == TokenKind::Number;
+    match texts.as_slice() {
+        ["true"] => DefaultValue::Bool(true),
+        ["false"] => DefaultValue::Bool(false),
+        ["None"] => DefaultValue::Null,
+        [text] if number(0) => DefaultValue::Number(text.to_string()),
+        // `1.` is a float literal
+        [text, "."] if number(0) => DefaultValue::Number(format!("{}.0", text)),
+        ["-", text] if number(1) => DefaultValue::Number(format!("-{}", text)),
+        [text] if tokens[0].kind == TokenKind::Str => DefaultValue::String(text.to_string()),
+        ["Some", "(", .., ")"] => literal_default(&tokens[2..tokens.len() - 1], source),
+        ["[", "]"] | ["Js", ".", "Dict", ".", "empty", "(", ")"] | ["Dict", ".", "make", "(", ")"] => DefaultValue::Empty,
+        _ => match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => DefaultValue::Computed(source[first.start..last.end].to_string()),
+            _ => DefaultValue::TypeDefault,
+        },
+    }
+}
+
+/// `[#Active | #Suspended(string)]`, open `[> #A]`, or `[< #A | #B]`;
+/// payloads are positional, and tags may be quoted (`#"dark-mode"`)
+fn parse_poly_variant(cursor: &mut Cursor) -> Result<(PolyBound, Vec<ReScriptVariant>), String> {
+    cursor.expect_punct('[')?;
+    let bound = if cursor.eat_punct('>') {
+        PolyBound::AtLeast
+    } else if cursor.eat_punct('<') {
+        PolyBound::AtMost
     } else {
-        Err("Type definition not found".to_string())
+        PolyBound::Exact
+    };
+    cursor.eat_punct('|');
+    let mut variants = Vec::new();
+    while !cursor.eat_punct(']') {
+        skip_decorators(cursor);
+        cursor.expect_punct('#')?;
+        let name = match cursor.peek() {
+            Some(t) if matches!(t.kind, TokenKind::Ident | TokenKind::Str) => t.text.clone(),
+            _ => return Err(cursor.unexpected("a polymorphic variant tag")),
+        };
+        cursor.advance();
+        let mut fields = Vec::new();
+        if cursor.eat_punct('(') {
+            while !cursor.eat_punct(')') {
+                let field_type = parse_type(cursor)?;
+                fields.push(ReScriptField { name: fields.len().to_string(), field_type, optional: false, default: None, doc: None });
+                if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                    return Err(cursor.unexpected("`,` or `)`"));
+                }
+            }
+        }
+        variants.push(ReScriptVariant { name, fields, tag: None });
+        if !cursor.eat_punct('|') && !cursor.is_punct(']') {
+            return Err(cursor.unexpected("`|` or `]`"));
+        }
+    }
+    Ok((bound, variants))
+}
+
+/// Skip `@genType`, `@as("id")`, and other decorators
+fn skip_decorators(cursor: &mut Cursor) {
+    parse_decorators(cursor);
+}
+
+/// Decorators by name, each with its argument when that is a single
+/// literal (`@as("user_id")`, `@unboxed`)
+fn parse_decorators(cursor: &mut Cursor) -> Vec<(String, Option<String>)> {
+    let mut decorators = Vec::new();
+    while cursor.is_punct('@') {
+        cursor.advance();
+        // `@@` file-level attributes and dotted names (`@genType.as`)
+        while cursor.eat_punct('@') {}
+        let mut name = cursor.expect_ident().unwrap_or_default();
+        while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            cursor.advance();
+            name = format!("{name}.{}", cursor.expect_ident().unwrap_or_default());
+        }
+        let mut argument = None;
+        if cursor.is_punct('(') {
+            if cursor.is_punct_at(2, ')') {
+                argument = cursor.peek_at(1).filter(|t| t.kind != TokenKind::Punct).map(|t| t.text.clone());
+            }
+            cursor.skip_balanced('(', ')');
+        }
+        decorators.push((name, argument));
+    }
+    decorators
+}
+
+fn decorator(decorators: &[(String, Option<String>)], name: &str) -> Option<String> {
+    decorators.iter().find(|(decorator, _)| decorator == name).and_then(|(_, argument)| argument.clone())
+}
+
+fn skip_to_next_item(cursor: &mut Cursor) {
+    while !cursor.at_end() && !is_item_start(cursor) {
+        cursor.advance();
+    }
+}
+
+fn is_item_start(cursor: &Cursor) -> bool {
+    cursor.peek().is_some_and(|token| {
+        token.kind == TokenKind::Ident
+            && matches!(token.text.as_str(), "type" | "and" | "let" | "module" | "open" | "include" | "exception" | "external")
+    })
+}
+
+/// Type expression: `int`, `array<option<string>>`, `Address.t`, `(int, string)`
+fn parse_type(cursor: &mut Cursor) -> Result<ReScriptFieldType, String> {
+    if cursor.is_punct('[') {
+        let (bound, variants) = parse_poly_variant(cursor)?;
+        return Ok(ReScriptFieldType::PolyVariant(bound, variants));
+    }
+    if cursor.eat_punct('\'') {
+        return Ok(ReScriptFieldType::TypeParam(cursor.expect_ident()?));
+    }
+    if cursor.eat_punct('(') {
+        // Uncurried `(. int) => string`
+        cursor.eat_punct('.');
+        let mut members = Vec::new();
+        while !cursor.eat_punct(')') {
+            if cursor.eat_punct('~') {
+                cursor.expect_ident()?;
+                cursor.eat_punct(':');
+            }
+            members.push(parse_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+        if let Some(ret) = parse_return(cursor)? {
+            return Ok(ReScriptFieldType::Function(members, Box::new(ret)));
+        }
+        // `(t)` is just a parenthesized `t`
+        return match members.len() {
+            0 => Err(cursor.unexpected("a type")),
+            1 => Ok(members.remove(0)),
+            _ => Ok(ReScriptFieldType::Tuple(members)),
+        };
+    }
+    let line = cursor.line();
+    let mut path = cursor.expect_ident()?;
+    while cursor.is_punct('.') {
+        cursor.advance();
+        path = format!("{}.{}", path, cursor.expect_ident()?);
     }
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+    let builtin = matches!(
+        path.as_str(),
+        "array" | "list" | "option" | "Js.Dict.t" | "Dict.t" | "Belt.Map.String.t" | "Belt.Map.Int.t" | "Belt.Map.t" | "Map.t"
+    );
+    if !builtin && !args.is_empty() {
+        return Ok(ReScriptFieldType::Generic(path, args));
+    }
+
+    let mut args = args.into_iter();
+    let mut arg = || {
+        args.next()
+            .map(Box::new)
+            .ok_or_else(|| format!("line {}: `{}` is missing its type argument", line, path))
+    };
+    let field_type = match path.as_str() {
+        "int" => ReScriptFieldType::Int,
+        "string" => ReScriptFieldType::String,
+        "bool" => ReScriptFieldType::Bool,
+        "float" => ReScriptFieldType::Float,
+        // Immutable lists cross the boundary as arrays
+        "array" | "list" => ReScriptFieldType::Array(arg()?),
+        "option" => ReScriptFieldType::Option(arg()?),
+        "Js.Dict.t" | "Dict.t" | "Belt.Map.String.t" => ReScriptFieldType::Map(Box::new(ReScriptFieldType::String), arg()?),
+        "Belt.Map.Int.t" => ReScriptFieldType::Map(Box::new(ReScriptFieldType::Int), arg()?),
+        // The third argument of `Belt.Map.t` is the comparator's identity
+        "Belt.Map.t" | "Map.t" => {
+            let key = arg()?;
+            ReScriptFieldType::Map(key, arg()?)
+        }
+        "unit" => ReScriptFieldType::Tuple(Vec::new()),
+        _ => ReScriptFieldType::Record(path.clone()),
+    };
+    Ok(match parse_return(cursor)? {
+        Some(ret) => ReScriptFieldType::Function(vec![field_type], Box::new(ret)),
+        None => field_type,
+    })
+}
+
+/// The return type after `=>`, if a function type continues here
+fn parse_return(cursor: &mut Cursor) -> Result<Option<ReScriptFieldType>, String> {
+    if !(cursor.is_punct('=') && cursor.is_punct_at(1, '>')) {
+        return Ok(None);
+    }
+    cursor.advance();
+    cursor.advance();
+    parse_type(cursor).map(Some)
 }
 
 /// Calculate compatibility score with another type system
 pub fn compatibility_score(rescript_type: &ReScriptType, target: &str) -> f32 {
+    // ReScript int/string/bool/float all have direct equivalents in Rust,
+    // Julia, and Gleam = Concorde class (100% fidelity); an open polymorphic
+    // variant has none
     match target {
-        "rust" => {
-            // ReScript int → Rust i64: Perfect (1.0)
-            // ReScript string → Rust String: Perfect (1.0)
-            // ReScript bool → Rust bool: Perfect (1.0)
-            // All fields have direct equivalents = Concorde class (100% fidelity)
-            1.0
-        }
-        "julia" => {
-            // ReScript int → Julia Int64: Perfect (1.0)
-            // ReScript string → Julia String: Perfect (1.0)
-            // ReScript bool → Julia Bool: Perfect (1.0)
-            1.0
-        }
-        "gleam" => {
-            // ReScript int → Gleam Int: Perfect (1.0)
-            // ReScript string → Gleam String: Perfect (1.0)
-            // ReScript bool → Gleam Bool: Perfect (1.0)
-            1.0
-        }
+        "rust" | "julia" | "gleam" => average_fidelity(
+            ir_fields(rescript_type)
+                .iter()
+                .map(|field| ir::fidelity(&field.ty, target))
+                .chain(rescript_type.polymorphic.map(|bound| bound.fidelity(target))),
+        ),
         _ => 0.0,
     }
 }
 
+/// Wire layout ReScript compiles a variant type's constructors to: a
+/// constructor without a payload is its bare tag, one with a payload is
+/// `{TAG: "Name", _0: ...}` (`@tag` renames `TAG`), the payload alone under
+/// `@unboxed`, and `{NAME, VAL}` for a polymorphic variant tag
+pub fn tagging(rescript_type: &ReScriptType) -> Option<VariantTagging> {
+    if rescript_type.kind != ReScriptTypeKind::Variant {
+        return None;
+    }
+    Some(if rescript_type.polymorphic.is_some() {
+        VariantTagging::Adjacent("NAME".to_string(), "VAL".to_string())
+    } else if rescript_type.unboxed {
+        VariantTagging::Untagged
+    } else {
+        VariantTagging::Internal(rescript_type.tag.clone().unwrap_or_else(|| "TAG".to_string()))
+    })
+}
+
+/// What a polymorphic variant written in place collapses to: bare tags are
+/// strings at run time, and a tag with a payload is an object the IR can't
+/// spell, which leaves only the escape hatch
+pub fn poly_shape(variants: &[ReScriptVariant]) -> UnionShape {
+    let members: Vec<(IrType, Option<String>)> = variants
+        .iter()
+        .map(|variant| match variant.fields.is_empty() {
+            true => (IrType::Primitive(PrimitiveType::String), Some(variant.name.clone())),
+            false => (IrType::Any, None),
+        })
+        .collect();
+    ir::union_shape(&members)
+}
+
+/// Whether no tag of a polymorphic variant carries a payload, so its values
+/// are plain strings at run time
+fn bare_tags(variants: &[ReScriptVariant]) -> bool {
+    matches!(poly_shape(variants), UnionShape::Enum(_))
+}
+
+/// Lower a ReScript field type into the language-neutral IR
+pub fn to_ir(field_type: &ReScriptFieldType) -> IrType {
+    match field_type {
+        ReScriptFieldType::Int => IrType::Primitive(PrimitiveType::I64),
+        ReScriptFieldType::String => IrType::Primitive(PrimitiveType::String),
+        ReScriptFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        ReScriptFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        ReScriptFieldType::Record(name) => IrType::Reference(name.clone()),
+        ReScriptFieldType::Generic(name, args) => IrType::Generic(name.clone(), args.iter().map(to_ir).collect()),
+        ReScriptFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+        ReScriptFieldType::Array(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        ReScriptFieldType::Option(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        ReScriptFieldType::Map(key, value) => {
+            IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value))))
+        }
+        ReScriptFieldType::Tuple(members) if members.is_empty() => IrType::Primitive(PrimitiveType::Unit),
+        ReScriptFieldType::Tuple(members) => IrType::Tuple(members.iter().map(to_ir).collect()),
+        ReScriptFieldType::Function(params, ret) => IrType::Function(params.iter().map(to_ir).collect(), Box::new(to_ir(ret))),
+        ReScriptFieldType::Alias(name, aliased) => IrType::Alias(name.clone(), Box::new(to_ir(aliased))),
+        // In place there is no type to name, so only the run-time form is kept
+        ReScriptFieldType::PolyVariant(_, variants) => poly_shape(variants).to_ir(),
+    }
+}
+
+/// Lower a field, making optional fields `Option`
+fn ir_field(name: &str, field: &ReScriptField) -> IrField {
+    let ty = to_ir(&field.field_type);
+    let ty = if field.optional { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+    IrField::new(name, ty).with_default(field.default.clone()).with_doc(field.doc.clone())
+}
+
+/// Lower record fields, or the payload fields of every constructor as
+/// `Constructor.field`, into the IR (optional fields become `Option`)
+pub fn ir_fields(rescript_type: &ReScriptType) -> Vec<IrField> {
+    // Nothing is known about how an abstract type is represented
+    if rescript_type.kind == ReScriptTypeKind::Opaque && rescript_type.fields.is_empty() {
+        return vec![IrField::new("0", IrType::Any)];
+    }
+    let record = rescript_type.fields.iter().map(|field| ir_field(&field.name, field));
+    let payloads = rescript_type.variants.iter().flat_map(|variant| {
+        variant
+            .fields
+            .iter()
+            .map(move |field| ir_field(&format!("{}.{}", variant.name, field.name), field))
+    });
+    record.chain(payloads).collect()
+}
+
+/// Lower the constructors of a variant type into the IR; records have none
+pub fn ir_variants(rescript_type: &ReScriptType) -> Vec<IrVariant> {
+    rescript_type
+        .variants
+        .iter()
+        .map(|variant| {
+            IrVariant::new(&variant.name, variant.fields.iter().map(|field| ir_field(&field.name, field)).collect())
+                .with_tag(variant.tag.clone())
+        })
+        .collect()
+}
+
+/// Bind the type variables of a parametric type (`page<'a>` with `user`), so
+/// it scores and maps like the concrete type it stands for
+pub fn instantiate(rescript_type: &ReScriptType, args: &[ReScriptFieldType]) -> Result<ReScriptType, String> {
+    if args.len() != rescript_type.type_params.len() {
+        return Err(format!(
+            "`{}` takes {} type arguments but {} were given",
+            rescript_type.name,
+            rescript_type.type_params.len(),
+            args.len()
+        ));
+    }
+    let bind = |fields: &[ReScriptField]| -> Vec<ReScriptField> {
+        fields
+            .iter()
+            .map(|field| ReScriptField { field_type: substitute(&field.field_type, &rescript_type.type_params, args), ..field.clone() })
+            .collect()
+    };
+    let mut instance = rescript_type.clone();
+    instance.type_params.clear();
+    instance.fields = bind(&rescript_type.fields);
+    for variant in &mut instance.variants {
+        variant.fields = bind(&variant.fields);
+    }
+    Ok(instance)
+}
+
+fn substitute(field_type: &ReScriptFieldType, params: &[String], args: &[ReScriptFieldType]) -> ReScriptFieldType {
+    let bind = |inner: &ReScriptFieldType| substitute(inner, params, args);
+    match field_type {
+        ReScriptFieldType::TypeParam(name) => match params.iter().position(|param| param == name) {
+            Some(i) => args[i].clone(),
+            None => field_type.clone(),
+        },
+        ReScriptFieldType::Generic(name, inner) => ReScriptFieldType::Generic(name.clone(), inner.iter().map(bind).collect()),
+        ReScriptFieldType::Array(inner) => ReScriptFieldType::Array(Box::new(bind(inner))),
+        ReScriptFieldType::Option(inner) => ReScriptFieldType::Option(Box::new(bind(inner))),
+        ReScriptFieldType::Map(key, value) => ReScriptFieldType::Map(Box::new(bind(key)), Box::new(bind(value))),
+        ReScriptFieldType::Tuple(members) => ReScriptFieldType::Tuple(members.iter().map(bind).collect()),
+        ReScriptFieldType::Function(inner, ret) => ReScriptFieldType::Function(inner.iter().map(bind).collect(), Box::new(bind(ret))),
+        ReScriptFieldType::Alias(name, aliased) => ReScriptFieldType::Alias(name.clone(), Box::new(bind(aliased))),
+        _ => field_type.clone(),
+    }
+}
+
 /// Map ReScript types to target language types
 pub fn map_to_target(field_type: &ReScriptFieldType, target: &str) -> String {
     match target {
@@ -101,44 +771,75 @@ pub fn map_to_target(field_type: &ReScriptFieldType, target: &str) -> String {
             ReScriptFieldType::String => "String".to_string(),
             ReScriptFieldType::Bool => "bool".to_string(),
             ReScriptFieldType::Float => "f64".to_string(),
-            ReScriptFieldType::Record(name) => name.clone(),
+            ReScriptFieldType::Record(name) | ReScriptFieldType::Alias(name, _) => name.clone(),
+            ReScriptFieldType::Generic(name, args) => format!("{}<{}>", name, map_members(args, target)),
+            ReScriptFieldType::TypeParam(name) => name.to_uppercase(),
             ReScriptFieldType::Array(inner) => {
                 format!("Vec<{}>", map_to_target(inner, target))
             }
             ReScriptFieldType::Option(inner) => {
                 format!("Option<{}>", map_to_target(inner, target))
             }
+            ReScriptFieldType::Map(key, value) => format!("HashMap<{}, {}>", map_to_target(key, target), map_to_target(value, target)),
+            ReScriptFieldType::Tuple(members) => format!("({})", map_members(members, target)),
+            ReScriptFieldType::Function(params, ret) => {
+                format!("Box<dyn Fn({}) -> {}>", map_members(params, target), map_to_target(ret, target))
+            }
+            ReScriptFieldType::PolyVariant(_, variants) if bare_tags(variants) => "String".to_string(),
+            ReScriptFieldType::PolyVariant(_, _) => "serde_json::Value".to_string(),
         },
         "julia" => match field_type {
             ReScriptFieldType::Int => "Int64".to_string(),
             ReScriptFieldType::String => "String".to_string(),
             ReScriptFieldType::Bool => "Bool".to_string(),
             ReScriptFieldType::Float => "Float64".to_string(),
-            ReScriptFieldType::Record(name) => name.clone(),
+            ReScriptFieldType::Record(name) | ReScriptFieldType::Alias(name, _) => name.clone(),
+            ReScriptFieldType::Generic(name, args) => format!("{}{{{}}}", name, map_members(args, target)),
+            ReScriptFieldType::TypeParam(name) => name.to_uppercase(),
             ReScriptFieldType::Array(inner) => {
                 format!("Vector{{{}}}", map_to_target(inner, target))
             }
             ReScriptFieldType::Option(inner) => {
                 format!("Union{{Nothing, {}}}", map_to_target(inner, target))
             }
+            ReScriptFieldType::Map(key, value) => format!("Dict{{{}, {}}}", map_to_target(key, target), map_to_target(value, target)),
+            ReScriptFieldType::Tuple(members) => format!("Tuple{{{}}}", map_members(members, target)),
+            ReScriptFieldType::Function(_, _) => "Function".to_string(),
+            ReScriptFieldType::PolyVariant(_, variants) if bare_tags(variants) => "String".to_string(),
+            ReScriptFieldType::PolyVariant(_, _) => "Any".to_string(),
         },
         "gleam" => match field_type {
             ReScriptFieldType::Int => "Int".to_string(),
             ReScriptFieldType::String => "String".to_string(),
             ReScriptFieldType::Bool => "Bool".to_string(),
             ReScriptFieldType::Float => "Float".to_string(),
-            ReScriptFieldType::Record(name) => name.clone(),
+            ReScriptFieldType::Record(name) | ReScriptFieldType::Alias(name, _) => name.clone(),
+            ReScriptFieldType::Generic(name, args) => format!("{}({})", name, map_members(args, target)),
+            ReScriptFieldType::TypeParam(name) => name.clone(),
             ReScriptFieldType::Array(inner) => {
                 format!("List({})", map_to_target(inner, target))
             }
             ReScriptFieldType::Option(inner) => {
                 format!("Option({})", map_to_target(inner, target))
             }
+            ReScriptFieldType::Map(key, value) => format!("Dict({}, {})", map_to_target(key, target), map_to_target(value, target)),
+            ReScriptFieldType::Tuple(members) => format!("#({})", map_members(members, target)),
+            ReScriptFieldType::Function(params, ret) => format!("fn({}) -> {}", map_members(params, target), map_to_target(ret, target)),
+            ReScriptFieldType::PolyVariant(_, variants) if bare_tags(variants) => "String".to_string(),
+            ReScriptFieldType::PolyVariant(_, _) => "Dynamic".to_string(),
         },
         _ => "Unknown".to_string(),
     }
 }
 
+fn map_members(members: &[ReScriptFieldType], target: &str) -> String {
+    members
+        .iter()
+        .map(|member| map_to_target(member, target))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +861,37 @@ fn test_analyze_user_type() {
         assert_eq!(user_type.fields.len(), 4);
     }
 
+    #[test]
+    fn test_analyze_variants() {
+        let source = r#"
+@genType
+type rec status =
+  | @as("active") Active
+  | Suspended(string)
+  | Moved({to: string, at?: float})
+and account = {id: int, mutable status: status, history: list<status>}
+
+type userId = int
+"#;
+        let types = analyze_rescript_types(source).unwrap();
+        assert_eq!(types.len(), 3);
+        assert_eq!(types[2].kind, ReScriptTypeKind::Alias);
+        let status = &types[0];
+        assert_eq!((status.name.as_str(), status.kind, status.location.as_str()), ("status", ReScriptTypeKind::Variant, "line 3"));
+        let names: Vec<_> = ir_fields(status).into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["Suspended.0", "Moved.to", "Moved.at"]);
+        assert_eq!(types[1].kind, ReScriptTypeKind::Record);
+        assert_eq!(map_to_target(&types[1].fields[2].field_type, "rust"), "Vec<status>");
+
+        let rust = crate::analyzers::rust_analyzer::analyze_rust_type(
+            "pub enum Status { Active, Suspended(String), Moved { to: String, at: Option<f64> } }",
+        )
+        .unwrap();
+        let variants = crate::analyzers::rust_analyzer::ir_variants(&rust);
+        assert_eq!(ir::compare_variants(&ir_variants(status), &variants), 1.0);
+        assert!(analyze_rescript_types("type t = A(int").is_err());
+    }
+
     #[test]
     fn test_compatibility_rust() {
         let source = "type user = { id: int }";
@@ -181,4 +913,144 @@ fn test_type_mapping_julia() {
         assert_eq!(map_to_target(&ReScriptFieldType::String, "julia"), "String");
         assert_eq!(map_to_target(&ReScriptFieldType::Bool, "julia"), "Bool");
     }
+
+    #[test]
+    fn test_parametric_types() {
+        let source = "type page<'a, 'cursor> = { items: array<'a>, next: option<'cursor>, pages: Js.Dict.t<page<'a, 'cursor>> }";
+        let page = analyze_rescript_type(source).unwrap();
+        assert_eq!(page.type_params, vec!["a", "cursor"]);
+        assert_eq!(map_to_target(&page.fields[0].field_type, "rust"), "Vec<A>");
+        assert_eq!(compatibility_score(&page, "rust"), 1.0);
+
+        let users = instantiate(&page, &[ReScriptFieldType::Record("user".to_string()), ReScriptFieldType::String]).unwrap();
+        assert_eq!(map_to_target(&users.fields[1].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&users.fields[2].field_type, "julia"), "Dict{String, page{user, String}}");
+        assert!(instantiate(&page, &[ReScriptFieldType::Int]).is_err());
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        let source = "type userId = int\ntype ids<'a> = array<'a>\ntype user = { id: userId, friends: ids<userId> }\ntype handler = string => unit";
+        let types = analyze_rescript_types(source).unwrap();
+        assert_eq!(types.len(), 4);
+        assert_eq!(types[0].kind, ReScriptTypeKind::Alias);
+        let user = &types[2];
+        // Named in generated code, scored as the `int` it stands for
+        assert_eq!(map_to_target(&user.fields[1].field_type, "rust"), "Vec<userId>");
+        let id = to_ir(&user.fields[0].field_type);
+        assert_eq!(id, IrType::Alias("userId".to_string(), Box::new(IrType::Primitive(PrimitiveType::I64))));
+        assert_eq!(ir::compare(&id, &IrType::Primitive(PrimitiveType::I64)), ir::Fidelity::Exact);
+        assert!(analyze_rescript_types("type tree = array<tree>").unwrap_err().contains("refers to itself"));
+    }
+
+    #[test]
+    fn test_maps() {
+        let source = "type index = { byName: Js.Dict.t<int>, byId: Belt.Map.Int.t<string>, byUser: Belt.Map.t<user, float, UserCmp.identity> }";
+        let index = analyze_rescript_type(source).unwrap();
+        let rust: Vec<_> = index.fields.iter().map(|field| map_to_target(&field.field_type, "rust")).collect();
+        assert_eq!(rust, vec!["HashMap<String, i64>", "HashMap<i64, String>", "HashMap<user, f64>"]);
+        assert_eq!(map_to_target(&index.fields[0].field_type, "julia"), "Dict{String, Int64}");
+        // Record keys have no JSON object key form
+        assert_eq!(compatibility_score(&index, "gleam"), 2.5 / 3.0);
+    }
+
+    #[test]
+    fn test_tuples() {
+        let user = analyze_rescript_type("type entry = { pair: (int, string), nested: array<(float, (bool, int))> }").unwrap();
+        assert_eq!(map_to_target(&user.fields[0].field_type, "rust"), "(i64, String)");
+        assert_eq!(map_to_target(&user.fields[1].field_type, "julia"), "Vector{Tuple{Float64, Tuple{Bool, Int64}}}");
+        assert_eq!(map_to_target(&user.fields[0].field_type, "gleam"), "#(Int, String)");
+
+        let rust = crate::analyzers::rust_analyzer::analyze_rust_type("struct Entry { pair: (i64, String) }").unwrap();
+        let rust_pair = crate::analyzers::rust_analyzer::to_ir(&rust.fields[0].field_type);
+        assert_eq!(ir::compare(&to_ir(&user.fields[0].field_type), &rust_pair), ir::Fidelity::Exact);
+        assert!(analyze_rescript_type("type t = { x: () }").is_err());
+    }
+
+    #[test]
+    fn test_function_types() {
+        let widget = analyze_rescript_type("type widget = { onChange: int => string, onSubmit: (. ~id: int, string) => unit }").unwrap();
+        assert_eq!(map_to_target(&widget.fields[0].field_type, "gleam"), "fn(Int) -> String");
+        assert_eq!(map_to_target(&widget.fields[1].field_type, "rust"), "Box<dyn Fn(i64, String) -> ()>");
+
+        let rust = crate::analyzers::rust_analyzer::analyze_rust_type("struct Widget { on_submit: Box<dyn Fn(i32, String)> }").unwrap();
+        let rust_submit = crate::analyzers::rust_analyzer::to_ir(&rust.fields[0].field_type);
+        assert_eq!(
+            to_ir(&widget.fields[1].field_type),
+            IrType::Function(
+                vec![IrType::Primitive(PrimitiveType::I64), IrType::Primitive(PrimitiveType::String)],
+                Box::new(IrType::Primitive(PrimitiveType::Unit))
+            )
+        );
+        // ReScript passes wider ints than a Rust callback in its slot accepts
+        assert_eq!(ir::compare(&to_ir(&widget.fields[1].field_type), &rust_submit), ir::Fidelity::Exact);
+        assert_eq!(ir::compare(&rust_submit, &to_ir(&widget.fields[1].field_type)), ir::Fidelity::Lossy);
+    }
+
+    #[test]
+    fn test_polymorphic_variants() {
+        let source = "type status = [> #Active | #Suspended(string)]\n\
+                      type theme = [#light | #dark]\n\
+                      type prefs = {theme: [< #light | #dark], status: status}";
+        let types = analyze_rescript_types(source).unwrap();
+        assert_eq!((types[0].kind, types[0].polymorphic), (ReScriptTypeKind::Variant, Some(PolyBound::AtLeast)));
+        let names: Vec<_> = ir_variants(&types[0]).into_iter().map(|variant| variant.name).collect();
+        assert_eq!(names, vec!["Active", "Suspended"]);
+        assert_eq!(compatibility_score(&types[0], "rust"), 0.75);
+        assert_eq!(compatibility_score(&types[1], "rust"), 1.0);
+        // In place, bare tags are the strings ReScript compiles them to
+        assert!(matches!(types[2].fields[0].field_type, ReScriptFieldType::PolyVariant(PolyBound::AtMost, _)));
+        assert_eq!(to_ir(&types[2].fields[0].field_type), IrType::Primitive(PrimitiveType::String));
+        assert!(analyze_rescript_types("type t = [#A | B]").is_err());
+    }
+
+    #[test]
+    fn test_runtime_representation() {
+        let source = "type user = {@as(\"user_id\") userId: int, name: string}\n\
+                      @tag(\"kind\")\ntype shape = | @as(\"circle\") Circle({radius: float}) | Point\n\
+                      @unboxed\nand value = Str(string) | Num(float)";
+        let types = analyze_rescript_types(source).unwrap();
+        let names: Vec<_> = types[0].fields.iter().map(|field| field.name.as_str()).collect();
+        assert_eq!(names, vec!["user_id", "name"]);
+        assert_eq!(tagging(&types[0]), None);
+        let tags: Vec<_> = ir_variants(&types[1]).into_iter().map(|variant| variant.wire_tag().to_string()).collect();
+        assert_eq!(tags, vec!["circle", "Point"]);
+        assert_eq!(tagging(&types[1]), Some(VariantTagging::Internal("kind".to_string())));
+        assert_eq!(tagging(&types[2]), Some(VariantTagging::Untagged));
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = "/** A user */\n@genType\ntype user = {\n  /** Login address */\n  email: string,\n  age: int,\n}\nand role = Admin | Member";
+        let types = analyze_rescript_types(source).unwrap();
+        assert_eq!(types[0].doc.as_deref(), Some("A user"));
+        let docs: Vec<Option<String>> = ir_fields(&types[0]).into_iter().map(|field| field.doc).collect();
+        assert_eq!(docs, vec![Some("Login address".to_string()), None]);
+        assert_eq!(types[1].doc, None);
+    }
+
+    #[test]
+    fn test_make_defaults() {
+        let source = "type config = {port: int, host: string, token?: string, ratio: float, tags: array<string>}\n\
+                      type other = {id: int}\n\
+                      let make = (~port=8080, ~host: string=\"localhost\", ~token=?, ~ratio=1., ~tags=[], ()): config =>\n  \
+                      {port, host, ?token, ratio, tags}";
+        let types = analyze_rescript_types(source).unwrap();
+        let defaults: Vec<Option<DefaultValue>> = ir_fields(&types[0]).into_iter().map(|field| field.default).collect();
+        assert_eq!(
+            defaults,
+            vec![
+                Some(DefaultValue::Number("8080".to_string())),
+                Some(DefaultValue::String("localhost".to_string())),
+                Some(DefaultValue::Null),
+                Some(DefaultValue::Number("1.0".to_string())),
+                Some(DefaultValue::Empty),
+            ]
+        );
+        assert_eq!(types[1].fields[0].default, None);
+
+        // Without a result annotation, `make` builds the record declared before it
+        let types = analyze_rescript_types("type t = {retries: int}\nlet make = (~retries=Config.retries, ()) => {retries}").unwrap();
+        assert_eq!(types[0].fields[0].default, Some(DefaultValue::Computed("Config.retries".to_string())));
+    }
 }
@@ -0,0 +1,935 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Ruby analyzer for protocol-squisher integration
+// Reads gradual types from RBS signatures (`attr_reader id: Integer`) or from
+// Sorbet `T::Struct` props and `T::Enum` values in plain Ruby
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// RBS and Ruby share comments and quotes; `=begin`/`=end` only appear in Ruby
+const RUBY: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[("=begin", "=end")],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Ruby class, Sorbet struct or enum, or RBS type alias
+#[derive(Debug, Clone)]
+pub struct RubyType {
+    /// Qualified with its modules: `Shop::User`
+    pub name: String,
+    pub kind: RubyTypeKind,
+    pub type_params: Vec<String>,
+    /// RBS `attr_reader`/`attr_accessor`s, or Sorbet `const`/`prop`s
+    pub fields: Vec<RubyField>,
+    /// `T::Enum` constants, or the symbols of an all-literal alias
+    pub values: Vec<String>,
+    /// Target of a `type` alias
+    pub alias: Option<RubyFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RubyTypeKind {
+    /// RBS class
+    Class,
+    /// Sorbet `T::Struct`, `T::InexactStruct`, or `T::ImmutableStruct`
+    Struct,
+    /// Sorbet `T::Enum`, or an RBS alias of symbol literals
+    Enum,
+    /// RBS `type name = ...`
+    Alias,
+}
+
+#[derive(Debug, Clone)]
+pub struct RubyField {
+    /// Key on the wire: a Sorbet `name:` rename if there is one
+    pub name: String,
+    pub field_type: RubyFieldType,
+    /// `attr_accessor` or `prop`, as opposed to `attr_reader` or `const`
+    pub mutable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RubyFieldType {
+    Integer,
+    Float,
+    /// `BigDecimal` and `Rational`
+    Decimal,
+    String,
+    Symbol,
+    Bool,
+    Nil,
+    /// `untyped`, `T.untyped`, `Object`
+    Untyped,
+    Time,
+    Date,
+    /// `:active` or `"draft"`
+    Literal(String),
+    /// `T?` or `T.nilable(T)`
+    Nullable(Box<RubyFieldType>),
+    Array(Box<RubyFieldType>),
+    Set(Box<RubyFieldType>),
+    Hash(Box<RubyFieldType>, Box<RubyFieldType>),
+    Tuple(Vec<RubyFieldType>),
+    /// `{ id: Integer }` records and Sorbet shapes
+    Record(Vec<(String, RubyFieldType)>),
+    Union(Vec<RubyFieldType>),
+    /// `^(Integer) -> void` or `T.proc`
+    Proc,
+    /// User or library class, with its type arguments
+    Named(String, Vec<RubyFieldType>),
+    TypeParam(String),
+}
+
+/// Analyze the first Ruby type and extract metadata
+pub fn analyze_ruby_type(source: &str) -> Result<RubyType, String> {
+    analyze_ruby_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze Sorbet-typed Ruby if the source declares a `T::Struct` or `T::Enum`,
+/// and RBS otherwise
+pub fn analyze_ruby_types(source: &str) -> Result<Vec<RubyType>, String> {
+    let tokens = tokenize(source, &RUBY);
+    let sorbet = tokens.windows(4).any(|w| {
+        w[0].text == "T" && w[1].text == ":" && w[2].text == ":" && superclass_kind(&format!("T::{}", w[3].text)).is_some()
+    });
+    if sorbet {
+        parse_sorbet(Cursor::new(tokens))
+    } else {
+        parse_rbs(Cursor::new(tokens))
+    }
+}
+
+/// Analyze every class and type alias in an RBS signature file
+pub fn analyze_rbs_types(source: &str) -> Result<Vec<RubyType>, String> {
+    parse_rbs(Cursor::new(tokenize(source, &RUBY)))
+}
+
+/// Analyze every `T::Struct` and `T::Enum` in a Sorbet-typed Ruby file
+pub fn analyze_sorbet_types(source: &str) -> Result<Vec<RubyType>, String> {
+    parse_sorbet(Cursor::new(tokenize(source, &RUBY)))
+}
+
+fn new_type(name: String, kind: RubyTypeKind, line: usize) -> RubyType {
+    RubyType {
+        name,
+        kind,
+        type_params: Vec::new(),
+        fields: Vec::new(),
+        values: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    }
+}
+
+fn qualify(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", namespace, name)
+    }
+}
+
+/// `Foo`, `::Foo::Bar`, or an RBS interface `_Each`
+fn parse_constant_path(cursor: &mut Cursor) -> Result<String, String> {
+    if cursor.is_punct(':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+    }
+    let mut path = cursor.expect_ident()?;
+    while cursor.is_punct(':') && cursor.is_punct_at(1, ':') && cursor.peek_at(2).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        cursor.advance();
+        path.push_str("::");
+        path.push_str(&cursor.expect_ident()?);
+    }
+    Ok(path)
+}
+
+/// Kind of a class declared with this superclass, if it's a Sorbet one
+fn superclass_kind(superclass: &str) -> Option<RubyTypeKind> {
+    match superclass.trim_start_matches("::") {
+        "T::Struct" | "T::InexactStruct" | "T::ImmutableStruct" => Some(RubyTypeKind::Struct),
+        "T::Enum" => Some(RubyTypeKind::Enum),
+        _ => None,
+    }
+}
+
+fn parse_rbs(mut cursor: Cursor) -> Result<Vec<RubyType>, String> {
+    let mut types = Vec::new();
+    parse_rbs_body(&mut cursor, "", false, None, &mut types)?;
+    Ok(types)
+}
+
+/// Declarations and members up to the `end` closing the enclosing declaration,
+/// or to the end of input at the top level; attributes go to `owner`
+fn parse_rbs_body(
+    cursor: &mut Cursor,
+    namespace: &str,
+    nested: bool,
+    owner: Option<usize>,
+    types: &mut Vec<RubyType>,
+) -> Result<(), String> {
+    loop {
+        let Some(token) = cursor.peek() else {
+            return if nested { Err(cursor.unexpected("`end`")) } else { Ok(()) };
+        };
+        match token.text.as_str() {
+            "end" => {
+                cursor.advance();
+                if nested {
+                    return Ok(());
+                }
+            }
+            "class" | "module" | "interface" => parse_rbs_declaration(cursor, namespace, types)?,
+            "type" => parse_rbs_alias(cursor, namespace, types)?,
+            "attr_reader" | "attr_accessor" | "attr_writer" => {
+                let type_params = owner.map(|index| types[index].type_params.clone()).unwrap_or_default();
+                if let (Some(field), Some(index)) = (parse_rbs_attr(cursor, &type_params)?, owner) {
+                    types[index].fields.push(field);
+                }
+            }
+            // Methods, ivars, mixins, visibility, aliases
+            _ => skip_rbs_member(cursor),
+        }
+    }
+}
+
+/// `class Name[T] < Base`, `module Name : _Self`, or `interface _Name`, with its body
+fn parse_rbs_declaration(cursor: &mut Cursor, namespace: &str, types: &mut Vec<RubyType>) -> Result<(), String> {
+    let line = cursor.line();
+    let keyword = cursor.advance().map(|t| t.text).unwrap_or_default();
+    let name = qualify(namespace, &parse_constant_path(cursor)?);
+    let type_params = if cursor.is_punct('[') { parse_rbs_type_params(cursor)? } else { Vec::new() };
+
+    // Superclass and self-type constraints
+    while cursor.peek().is_some_and(|t| t.line == line && t.text != "end") {
+        cursor.advance();
+    }
+
+    // Modules and interfaces only scope names; classes carry the attributes
+    let owner = (keyword == "class").then(|| {
+        let mut class = new_type(name.clone(), RubyTypeKind::Class, line);
+        class.type_params = type_params;
+        types.push(class);
+        types.len() - 1
+    });
+    parse_rbs_body(cursor, &name, true, owner, types)
+}
+
+/// `[T, unchecked out U < Comparable[U] = untyped]`
+fn parse_rbs_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    cursor.expect_punct('[')?;
+    let mut type_params = Vec::new();
+    loop {
+        while cursor.is_ident("unchecked") || cursor.is_ident("in") || cursor.is_ident("out") {
+            cursor.advance();
+        }
+        type_params.push(cursor.expect_ident()?);
+        let mut depth = 0usize;
+        while let Some(token) = cursor.peek() {
+            match token.text.as_str() {
+                "," | "]" if depth == 0 => break,
+                "[" => depth += 1,
+                "]" => depth -= 1,
+                _ => {}
+            }
+            cursor.advance();
+        }
+        if !cursor.eat_punct(',') {
+            cursor.expect_punct(']')?;
+            return Ok(type_params);
+        }
+    }
+}
+
+/// `type name[T] = ...`; a union of symbols becomes an enum
+fn parse_rbs_alias(cursor: &mut Cursor, namespace: &str, types: &mut Vec<RubyType>) -> Result<(), String> {
+    let line = cursor.line();
+    cursor.advance();
+    let name = qualify(namespace, &parse_constant_path(cursor)?);
+    let type_params = if cursor.is_punct('[') { parse_rbs_type_params(cursor)? } else { Vec::new() };
+    cursor.expect_punct('=')?;
+    let target = parse_rbs_type(cursor, &type_params)?;
+
+    let literals: Option<Vec<String>> = match &target {
+        RubyFieldType::Union(members) => members
+            .iter()
+            .map(|member| match member {
+                RubyFieldType::Literal(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    };
+    let mut alias = new_type(name, RubyTypeKind::Alias, line);
+    alias.type_params = type_params;
+    match literals {
+        Some(values) => {
+            alias.kind = RubyTypeKind::Enum;
+            alias.values = values;
+        }
+        None => alias.alias = Some(target),
+    }
+    types.push(alias);
+    Ok(())
+}
+
+/// `attr_reader name: Type`; `None` for write-only and class-level attributes
+fn parse_rbs_attr(cursor: &mut Cursor, type_params: &[String]) -> Result<Option<RubyField>, String> {
+    let keyword = cursor.advance().map(|t| t.text).unwrap_or_default();
+    if cursor.is_ident("self") {
+        skip_rbs_member(cursor);
+        return Ok(None);
+    }
+    let name = cursor.expect_ident()?;
+    // `attr_reader name (@raw_name): String`
+    if cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+    }
+    cursor.expect_punct(':')?;
+    let field_type = parse_rbs_type(cursor, type_params)?;
+    Ok((keyword != "attr_writer").then(|| RubyField { name, field_type, mutable: keyword == "attr_accessor" }))
+}
+
+/// Skip a member that may span lines: a method's overloads continue with `|`,
+/// and open brackets or a trailing `->`, `,`, or `:` carry on to the next line
+fn skip_rbs_member(cursor: &mut Cursor) {
+    let mut depth = 0usize;
+    while let Some(token) = cursor.advance() {
+        let punct = token.kind == TokenKind::Punct;
+        match token.text.as_str() {
+            "(" | "[" | "{" if punct => depth += 1,
+            ")" | "]" | "}" if punct => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        let dangling = punct && matches!(token.text.as_str(), "|" | ">" | "," | ":" | "^" | "-");
+        let Some(next) = cursor.peek() else { return };
+        let continued = next.kind == TokenKind::Punct && next.text == "|";
+        if depth == 0 && next.line > token.line && !dangling && !continued {
+            return;
+        }
+    }
+}
+
+/// `A | B`, each an intersection of `?`-suffixed primaries
+fn parse_rbs_type(cursor: &mut Cursor, type_params: &[String]) -> Result<RubyFieldType, String> {
+    let mut members = vec![parse_rbs_intersection(cursor, type_params)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_rbs_intersection(cursor, type_params)?);
+    }
+    Ok(union(members))
+}
+
+fn parse_rbs_intersection(cursor: &mut Cursor, type_params: &[String]) -> Result<RubyFieldType, String> {
+    let mut ty = parse_rbs_optional(cursor, type_params)?;
+    // Intersections describe interfaces, not data
+    while cursor.eat_punct('&') {
+        parse_rbs_optional(cursor, type_params)?;
+        ty = RubyFieldType::Untyped;
+    }
+    Ok(ty)
+}
+
+fn parse_rbs_optional(cursor: &mut Cursor, type_params: &[String]) -> Result<RubyFieldType, String> {
+    let ty = parse_rbs_primary(cursor, type_params)?;
+    Ok(if cursor.eat_punct('?') { nullable(ty) } else { ty })
+}
+
+fn parse_rbs_primary(cursor: &mut Cursor, type_params: &[String]) -> Result<RubyFieldType, String> {
+    let token = cursor.peek().cloned().ok_or_else(|| cursor.unexpected("type"))?;
+    match (token.kind, token.text.as_str()) {
+        (TokenKind::Punct, "(") => {
+            cursor.advance();
+            let ty = parse_rbs_type(cursor, type_params)?;
+            cursor.expect_punct(')')?;
+            Ok(ty)
+        }
+        (TokenKind::Punct, "[") => {
+            cursor.advance();
+            let mut items = Vec::new();
+            while !cursor.eat_punct(']') {
+                items.push(parse_rbs_type(cursor, type_params)?);
+                if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+                    return Err(cursor.unexpected("`,` or `]`"));
+                }
+            }
+            Ok(RubyFieldType::Tuple(items))
+        }
+        (TokenKind::Punct, "{") => {
+            cursor.advance();
+            let mut fields = Vec::new();
+            while !cursor.eat_punct('}') {
+                // `?name: T` marks an optional key
+                let optional = cursor.eat_punct('?');
+                let key = cursor.advance().ok_or_else(|| cursor.unexpected("record key"))?.text;
+                if !cursor.eat_punct(':') {
+                    cursor.expect_punct('=')?;
+                    cursor.expect_punct('>')?;
+                }
+                let ty = parse_rbs_type(cursor, type_params)?;
+                fields.push((key, if optional { nullable(ty) } else { ty }));
+                if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                    return Err(cursor.unexpected("`,` or `}`"));
+                }
+            }
+            Ok(RubyFieldType::Record(fields))
+        }
+        (TokenKind::Punct, "^") => {
+            cursor.advance();
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            }
+            if cursor.eat_punct('?') || cursor.is_punct('{') {
+                cursor.skip_balanced('{', '}');
+            }
+            if cursor.eat_punct('-') {
+                cursor.expect_punct('>')?;
+                parse_rbs_optional(cursor, type_params)?;
+            }
+            Ok(RubyFieldType::Proc)
+        }
+        (TokenKind::Punct, ":") if !cursor.is_punct_at(1, ':') => {
+            cursor.advance();
+            let symbol = cursor.advance().ok_or_else(|| cursor.unexpected("symbol"))?;
+            Ok(RubyFieldType::Literal(symbol.text))
+        }
+        (TokenKind::Str, _) => {
+            cursor.advance();
+            Ok(RubyFieldType::Literal(token.text))
+        }
+        (TokenKind::Number, _) | (TokenKind::Punct, "-") => {
+            cursor.advance();
+            if token.text == "-" {
+                cursor.advance();
+            }
+            Ok(RubyFieldType::Integer)
+        }
+        (TokenKind::Ident, word) => {
+            let keyword = match word {
+                "bool" | "boolish" | "true" | "false" => Some(RubyFieldType::Bool),
+                "nil" => Some(RubyFieldType::Nil),
+                "untyped" | "top" | "bot" | "void" | "self" | "instance" | "class" => Some(RubyFieldType::Untyped),
+                "singleton" => {
+                    cursor.advance();
+                    cursor.skip_balanced('(', ')');
+                    return Ok(RubyFieldType::Untyped);
+                }
+                _ => None,
+            };
+            if let Some(ty) = keyword {
+                cursor.advance();
+                return Ok(ty);
+            }
+            let path = parse_constant_path(cursor)?;
+            let mut args = Vec::new();
+            if cursor.eat_punct('[') {
+                while !cursor.eat_punct(']') {
+                    args.push(parse_rbs_type(cursor, type_params)?);
+                    if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+                        return Err(cursor.unexpected("`,` or `]`"));
+                    }
+                }
+            }
+            Ok(named(path, args, type_params))
+        }
+        (TokenKind::Punct, ":") => {
+            let path = parse_constant_path(cursor)?;
+            Ok(named(path, Vec::new(), type_params))
+        }
+        _ => Err(cursor.unexpected("type")),
+    }
+}
+
+/// A class name as a field type, core classes included
+fn named(path: String, args: Vec<RubyFieldType>, type_params: &[String]) -> RubyFieldType {
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(RubyFieldType::Untyped));
+    match path.trim_start_matches("::").rsplit("::").next().unwrap_or(&path) {
+        "Integer" => RubyFieldType::Integer,
+        "Float" | "Numeric" => RubyFieldType::Float,
+        "BigDecimal" | "Rational" => RubyFieldType::Decimal,
+        "String" => RubyFieldType::String,
+        "Symbol" => RubyFieldType::Symbol,
+        "TrueClass" | "FalseClass" | "Boolean" => RubyFieldType::Bool,
+        "NilClass" => RubyFieldType::Nil,
+        "Object" | "BasicObject" => RubyFieldType::Untyped,
+        "Time" | "DateTime" => RubyFieldType::Time,
+        "Date" => RubyFieldType::Date,
+        "Array" => RubyFieldType::Array(next()),
+        "Set" => RubyFieldType::Set(next()),
+        "Hash" => {
+            let key = next();
+            RubyFieldType::Hash(key, next())
+        }
+        "Proc" | "Method" => RubyFieldType::Proc,
+        _ if type_params.contains(&path) => RubyFieldType::TypeParam(path),
+        _ => RubyFieldType::Named(path, args.collect()),
+    }
+}
+
+fn union(mut members: Vec<RubyFieldType>) -> RubyFieldType {
+    if members.len() == 1 {
+        members.remove(0)
+    } else {
+        RubyFieldType::Union(members)
+    }
+}
+
+/// `T?`; `untyped` and `nil` already admit nil
+fn nullable(ty: RubyFieldType) -> RubyFieldType {
+    match ty {
+        RubyFieldType::Untyped | RubyFieldType::Nil | RubyFieldType::Nullable(_) => ty,
+        ty => RubyFieldType::Nullable(Box::new(ty)),
+    }
+}
+
+/// A Ruby block waiting for its `end`
+struct Block {
+    /// Class or module name, for qualifying nested declarations
+    name: Option<String>,
+    /// Sorbet type whose props or values this block declares
+    owner: Option<usize>,
+    line: usize,
+    /// `while`/`until`/`for`, whose optional `do` shares the `end`
+    looping: bool,
+    /// `enums do ... end`
+    enum_values: bool,
+}
+
+impl Block {
+    fn anonymous(line: usize) -> Self {
+        Block { name: None, owner: None, line, looping: false, enum_values: false }
+    }
+}
+
+/// Walk Ruby source tracking `end`-delimited blocks, collecting `T::Struct`
+/// props and `T::Enum` values
+fn parse_sorbet(mut cursor: Cursor) -> Result<Vec<RubyType>, String> {
+    let mut types = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+
+    while let Some(token) = cursor.peek().cloned() {
+        let previous = cursor.previous().cloned();
+        // Keywords after `.` are method names, after an adjacent `:` symbols,
+        // and before one hash keys
+        let keyword = token.kind == TokenKind::Ident
+            && !previous
+                .as_ref()
+                .is_some_and(|t| t.text == "." || (t.text == ":" && t.end == token.start))
+            && !cursor.peek_at(1).is_some_and(|t| t.text == ":" && t.start == token.end);
+        let statement_start = previous
+            .as_ref()
+            .is_none_or(|t| t.line < token.line || matches!(t.text.as_str(), ";" | "=" | "(" | "|" | "&"));
+        let owner = blocks.last().and_then(|block| block.owner);
+
+        match token.text.as_str() {
+            "class" | "module" if keyword => {
+                cursor.advance();
+                // `class << self`
+                if cursor.is_punct('<') {
+                    blocks.push(Block::anonymous(token.line));
+                    continue;
+                }
+                let name = parse_constant_path(&mut cursor)?;
+                let kind = if token.text == "class" && cursor.eat_punct('<') {
+                    superclass_kind(&parse_constant_path(&mut cursor)?)
+                } else {
+                    None
+                };
+                let namespace: Vec<&str> = blocks.iter().filter_map(|block| block.name.as_deref()).collect();
+                let owner = kind.map(|kind| {
+                    types.push(new_type(qualify(&namespace.join("::"), &name), kind, token.line));
+                    types.len() - 1
+                });
+                blocks.push(Block { name: Some(name), owner, ..Block::anonymous(token.line) });
+            }
+            "end" if keyword => {
+                cursor.advance();
+                blocks.pop();
+            }
+            "def" if keyword => {
+                cursor.advance();
+                if cursor.is_ident("self") && cursor.is_punct_at(1, '.') {
+                    cursor.advance();
+                    cursor.advance();
+                }
+                cursor.advance();
+                if cursor.is_punct('(') {
+                    cursor.skip_balanced('(', ')');
+                }
+                // Endless `def name = expr` takes no `end`
+                let endless = cursor.peek().is_some_and(|t| t.line == token.line && t.text == "=")
+                    && !cursor.is_punct_at(1, '=');
+                if !endless {
+                    blocks.push(Block::anonymous(token.line));
+                }
+            }
+            "do" if keyword => {
+                cursor.advance();
+                // `while cond do` shares the loop's `end`
+                if !blocks.last().is_some_and(|block| block.looping && block.line == token.line) {
+                    blocks.push(Block::anonymous(token.line));
+                }
+            }
+            "begin" | "case" if keyword => {
+                cursor.advance();
+                blocks.push(Block::anonymous(token.line));
+            }
+            "if" | "unless" | "while" | "until" | "for" if keyword && statement_start => {
+                cursor.advance();
+                let looping = matches!(token.text.as_str(), "while" | "until" | "for");
+                blocks.push(Block { looping, ..Block::anonymous(token.line) });
+            }
+            "enums" if keyword && owner.is_some() && cursor.peek_at(1).is_some_and(|t| t.text == "do") => {
+                cursor.advance();
+                cursor.advance();
+                blocks.push(Block { owner, enum_values: true, ..Block::anonymous(token.line) });
+            }
+            "const" | "prop" if keyword && statement_start && owner.is_some_and(|index| types[index].kind == RubyTypeKind::Struct) => {
+                cursor.advance();
+                if let Some(field) = parse_sorbet_prop(&mut cursor, token.text == "prop")? {
+                    if let Some(index) = owner {
+                        types[index].fields.push(field);
+                    }
+                }
+            }
+            // `Active = new` or `Suspended = new('suspended')`
+            name if blocks.last().is_some_and(|block| block.enum_values)
+                && statement_start
+                && name.starts_with(char::is_uppercase)
+                && cursor.is_punct_at(1, '=')
+                && cursor.peek_at(2).is_some_and(|t| t.text == "new") =>
+            {
+                cursor.advance();
+                if let Some(index) = owner {
+                    types[index].values.push(token.text.clone());
+                }
+            }
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+
+    Ok(types)
+}
+
+/// `:name, Type, option: value...` after `const` or `prop`, parenthesized or not
+fn parse_sorbet_prop(cursor: &mut Cursor, mutable: bool) -> Result<Option<RubyField>, String> {
+    let parenthesized = cursor.eat_punct('(');
+    if !cursor.eat_punct(':') {
+        return Ok(None);
+    }
+    let mut name = cursor.advance().ok_or_else(|| cursor.unexpected("prop name"))?.text;
+    cursor.expect_punct(',')?;
+    let field_type = parse_sorbet_type(cursor)?;
+
+    // Options; `name: "key"` renames the prop when serialized
+    while cursor.eat_punct(',') {
+        let rename = cursor.is_ident("name") && cursor.is_punct_at(1, ':');
+        let start = cursor.line();
+        let mut depth = 0usize;
+        let mut first = true;
+        while let Some(token) = cursor.peek() {
+            let punct = token.kind == TokenKind::Punct;
+            match token.text.as_str() {
+                "," | ")" if punct && depth == 0 => break,
+                "(" | "[" | "{" if punct => depth += 1,
+                ")" | "]" | "}" if punct => depth = depth.saturating_sub(1),
+                _ if depth == 0 && token.line > start && !first => break,
+                _ => {}
+            }
+            if rename && token.kind == TokenKind::Str {
+                name = token.text.clone();
+            }
+            first = false;
+            cursor.advance();
+        }
+    }
+    if parenthesized {
+        cursor.eat_punct(')');
+    }
+    Ok(Some(RubyField { name, field_type, mutable }))
+}
+
+/// A Sorbet runtime type: `T.nilable(String)`, `T::Array[Integer]`, `User`
+fn parse_sorbet_type(cursor: &mut Cursor) -> Result<RubyFieldType, String> {
+    if cursor.is_ident("T") && cursor.is_punct_at(1, '.') {
+        cursor.advance();
+        cursor.advance();
+        let method = cursor.expect_ident()?;
+        let mut args = Vec::new();
+        if matches!(method.as_str(), "nilable" | "any") {
+            cursor.expect_punct('(')?;
+            while !cursor.eat_punct(')') {
+                args.push(parse_sorbet_type(cursor)?);
+                if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                    return Err(cursor.unexpected("`,` or `)`"));
+                }
+            }
+        }
+        // `T.proc.params(x: Integer).returns(String)`, `T.class_of(User)`
+        loop {
+            if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            } else if cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+                cursor.advance();
+                cursor.advance();
+            } else {
+                break;
+            }
+        }
+        return Ok(match method.as_str() {
+            "nilable" => nullable(args.pop().unwrap_or(RubyFieldType::Untyped)),
+            "any" => union(args),
+            "proc" => RubyFieldType::Proc,
+            _ => RubyFieldType::Untyped,
+        });
+    }
+    if cursor.eat_punct('[') {
+        let mut items = Vec::new();
+        while !cursor.eat_punct(']') {
+            items.push(parse_sorbet_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+                return Err(cursor.unexpected("`,` or `]`"));
+            }
+        }
+        return Ok(RubyFieldType::Tuple(items));
+    }
+    if cursor.eat_punct('{') {
+        let mut fields = Vec::new();
+        while !cursor.eat_punct('}') {
+            let key = cursor.advance().ok_or_else(|| cursor.unexpected("shape key"))?.text;
+            cursor.expect_punct(':')?;
+            fields.push((key, parse_sorbet_type(cursor)?));
+            if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+                return Err(cursor.unexpected("`,` or `}`"));
+            }
+        }
+        return Ok(RubyFieldType::Record(fields));
+    }
+    let path = parse_constant_path(cursor)?;
+    let mut args = Vec::new();
+    if cursor.eat_punct('[') {
+        while !cursor.eat_punct(']') {
+            args.push(parse_sorbet_type(cursor)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(']') {
+                return Err(cursor.unexpected("`,` or `]`"));
+            }
+        }
+    }
+    Ok(named(path, args, &[]))
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(ruby_type: &RubyType, target: &str) -> f32 {
+    average_fidelity(ir_fields(ruby_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Ruby field type into the language-neutral IR
+pub fn to_ir(field_type: &RubyFieldType) -> IrType {
+    match field_type {
+        // Integers are arbitrary precision; 64 bits covers what crosses a boundary in practice
+        RubyFieldType::Integer => IrType::Primitive(PrimitiveType::I64),
+        RubyFieldType::Float => IrType::Primitive(PrimitiveType::F64),
+        RubyFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        RubyFieldType::Nil => IrType::Primitive(PrimitiveType::Unit),
+        // Symbols cross the boundary as their names, times as ISO 8601
+        RubyFieldType::String
+        | RubyFieldType::Symbol
+        | RubyFieldType::Literal(_)
+        | RubyFieldType::Time
+        | RubyFieldType::Date => IrType::Primitive(PrimitiveType::String),
+        RubyFieldType::Hash(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        RubyFieldType::Decimal => IrType::Primitive(PrimitiveType::Decimal),
+        // Tuples and shaped records have no IR form yet
+        RubyFieldType::Untyped
+        | RubyFieldType::Tuple(_)
+        | RubyFieldType::Record(_)
+        | RubyFieldType::Proc => IrType::Any,
+        RubyFieldType::Nullable(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        RubyFieldType::Array(element) | RubyFieldType::Set(element) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        RubyFieldType::Union(members) => union_to_ir(members),
+        RubyFieldType::Named(path, _) => IrType::Reference(path.rsplit("::").next().unwrap_or(path).to_string()),
+        RubyFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// `T | nil` is an option; `:a | :b` is a string enum; anything else stays a union
+fn union_to_ir(members: &[RubyFieldType]) -> IrType {
+    let nullable = members.contains(&RubyFieldType::Nil);
+    let mut present: Vec<IrType> = Vec::new();
+    for member in members.iter().filter(|m| **m != RubyFieldType::Nil) {
+        let ty = to_ir(member);
+        if !present.contains(&ty) {
+            present.push(ty);
+        }
+    }
+
+    let inner = match present.len() {
+        0 => return IrType::Primitive(PrimitiveType::Unit),
+        1 => present.remove(0),
+        _ => IrType::Union(present),
+    };
+    if nullable {
+        IrType::Container(ContainerType::Option(Box::new(inner)))
+    } else {
+        inner
+    }
+}
+
+/// Lower all fields of a Ruby type into the IR
+pub fn ir_fields(ruby_type: &RubyType) -> Vec<IrField> {
+    ruby_type
+        .fields
+        .iter()
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Ruby types to target language types
+pub fn map_to_target(field_type: &RubyFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RBS: &str = r#"
+module Shop
+  class User
+    attr_reader id: Integer
+    attr_accessor name: String
+    attr_reader email: String?
+    attr_reader tags: Array[Symbol]
+    attr_reader prefs: Hash[Symbol, untyped]
+    attr_writer password: String
+    attr_reader status: :active | :suspended | nil
+    attr_reader self.count: Integer
+    @cache: untyped
+
+    def initialize: (id: Integer, name: String) -> void
+                  | (Hash[Symbol, untyped]) -> void
+    def admin?: () -> bool
+  end
+
+  class Box[out T] < Object
+    attr_reader value: T
+    attr_reader callback: ^(T) -> void
+  end
+
+  type role = :admin | :member
+  type id = Integer | String
+end
+
+interface _Named
+  def name: () -> String
+end
+"#;
+
+    const SORBET: &str = r##"
+# typed: strict
+module Shop
+  class Status < T::Enum
+    enums do
+      Active = new
+      Suspended = new('suspended')
+    end
+  end
+
+  class User < T::Struct
+    extend T::Sig
+
+    const :id, Integer
+    prop :name, String
+    prop :email, T.nilable(String), default: nil
+    const :tags, T::Array[String], factory: -> { [] }
+    prop :status, Status, name: 'state'
+    prop :meta, T::Hash[Symbol, T.untyped]
+    prop :scores, T.any(Integer, Float)
+
+    sig { returns(String) }
+    def display_name
+      if email
+        "#{name} <#{email}>"
+      else
+        name
+      end
+    end
+
+    def self.build(id) = new(id: id, name: "x")
+
+    const :created_at, Time
+  end
+
+  class Plain
+    attr_reader :name
+  end
+end
+"##;
+
+    #[test]
+    fn test_analyze_rbs_signatures() {
+        let types = analyze_ruby_types(RBS).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Shop::User", RubyTypeKind::Class),
+                ("Shop::Box", RubyTypeKind::Class),
+                ("Shop::role", RubyTypeKind::Enum),
+                ("Shop::id", RubyTypeKind::Alias),
+            ]
+        );
+
+        let user = &types[0];
+        let fields: Vec<_> = user.fields.iter().map(|f| (f.name.as_str(), f.mutable)).collect();
+        assert_eq!(
+            fields,
+            vec![("id", false), ("name", true), ("email", false), ("tags", false), ("prefs", false), ("status", false)]
+        );
+        assert_eq!(user.fields[2].field_type, RubyFieldType::Nullable(Box::new(RubyFieldType::String)));
+        assert_eq!(map_to_target(&user.fields[5].field_type, "rust"), "Option<String>");
+
+        assert_eq!(types[1].type_params, vec!["T"]);
+        assert_eq!(types[1].fields[0].field_type, RubyFieldType::TypeParam("T".to_string()));
+        assert_eq!(types[1].fields[1].field_type, RubyFieldType::Proc);
+        assert_eq!(types[2].values, vec!["admin", "member"]);
+        assert_eq!(types[3].alias, Some(RubyFieldType::Union(vec![RubyFieldType::Integer, RubyFieldType::String])));
+    }
+
+    #[test]
+    fn test_analyze_sorbet_structs_and_enums() {
+        let types = analyze_ruby_types(SORBET).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(names, vec![("Shop::Status", RubyTypeKind::Enum), ("Shop::User", RubyTypeKind::Struct)]);
+        assert_eq!(types[0].values, vec!["Active", "Suspended"]);
+
+        let user = &types[1];
+        let fields: Vec<_> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["id", "name", "email", "tags", "state", "meta", "scores", "created_at"]);
+        assert!(!user.fields[0].mutable && user.fields[1].mutable);
+        assert_eq!(map_to_target(&user.fields[2].field_type, "rust"), "Option<String>");
+        assert_eq!(map_to_target(&user.fields[3].field_type, "gleam"), "List(String)");
+        assert_eq!(map_to_target(&user.fields[4].field_type, "rust"), "Status");
+        assert_eq!(user.fields[6].field_type, RubyFieldType::Union(vec![RubyFieldType::Integer, RubyFieldType::Float]));
+        assert!(compatibility_score(user, "rust") < 1.0);
+
+        let point = analyze_sorbet_types("class Point < T::Struct\n  const :x, Float\n  const :y, Float\nend\n").unwrap();
+        assert_eq!(compatibility_score(&point[0], "rust"), 1.0);
+    }
+}
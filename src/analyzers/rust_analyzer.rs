@@ -2,15 +2,56 @@
 // Rust type analyzer for protocol-squisher integration
 // Analyzes Rust types and generates compatibility metadata
 
-use std::collections::HashMap;
+use super::lexer::{line_doc, tokenize, Cursor, Syntax, Token, TokenKind};
+use crate::ir::{self, average_fidelity, ContainerType, DefaultValue, Direction, Fidelity, IrField, IrType, IrVariant, Ownership, PrimitiveType, VariantTagging};
+use crate::codegen::audit::SecurityProfile;
+use crate::converters::{generate_field_codec, ConverterRegistry, FieldConverter};
+use crate::target::{Layout, PlatformWidth, TargetTriple};
 
 /// Rust type information extracted from source
 #[derive(Debug, Clone)]
 pub struct RustType {
     pub name: String,
+    pub kind: RustTypeKind,
+    /// Generic type parameters (`struct Pagination<T>`); lifetimes are not recorded
+    pub type_params: Vec<String>,
+    /// Const generic parameters with their types (`struct Matrix<const N: usize>`)
+    pub const_params: Vec<(String, RustFieldType)>,
+    /// The type a `type` alias stands for
+    pub aliased: Option<RustFieldType>,
+    /// Fields of a struct
     pub fields: Vec<RustField>,
+    /// Variants of an enum
+    pub variants: Vec<RustVariant>,
     pub attributes: Vec<String>,
     pub location: String,
+    /// `///` doc comment, without markers
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RustTypeKind {
+    Struct,
+    Enum,
+    Alias,
+}
+
+/// Enum variant; tuple variant fields are named `0`, `1`, ... like tuple struct fields
+#[derive(Debug, Clone)]
+pub struct RustVariant {
+    pub name: String,
+    pub kind: RustVariantKind,
+    pub fields: Vec<RustField>,
+    /// Explicit discriminant as written (`Active = 1` → `"1"`)
+    pub discriminant: Option<String>,
+    pub attributes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RustVariantKind {
+    Unit,
+    Tuple,
+    Struct,
 }
 
 #[derive(Debug, Clone)]
@@ -18,180 +59,2758 @@ pub struct RustField {
     pub name: String,
     pub field_type: RustFieldType,
     pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    /// What serde fills in when the field is missing: `#[serde(default)]` on
+    /// the field, or on the struct with the struct's `Default` impl
+    pub default: Option<DefaultValue>,
+    /// `///` doc comment of a field on a line of its own
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Visibility {
+    Public,
+    Private,
+    Crate,
+}
+
+#[derive(Debug, Clone)]
+pub enum RustFieldType {
+    I64,
+    I32,
+    U64,
+    U32,
+    /// Bytes, as in `Vec<u8>`
+    U8,
+    I8,
+    I16,
+    U16,
+    I128,
+    U128,
+    /// Pointer-sized: 64 bits in the IR, narrower on 32-bit and wasm triples
+    Isize,
+    Usize,
+    String,
+    Bool,
+    F64,
+    F32,
+    Struct(String),
+    /// User generic with its type arguments (`Pagination<User>`)
+    Generic(String, Vec<RustFieldType>),
+    /// Type parameter of the enclosing definition
+    TypeParam(String),
+    Vec(Box<RustFieldType>),
+    /// `[T; N]` with a literal length
+    Array(Box<RustFieldType>, usize),
+    /// `[T; N]` with the length a const parameter of the enclosing definition
+    ConstArray(Box<RustFieldType>, String),
+    Option(Box<RustFieldType>),
+    Result(Box<RustFieldType>, Box<RustFieldType>),
+    /// `HashMap<K, V>` or `BTreeMap<K, V>`
+    Map(Box<RustFieldType>, Box<RustFieldType>),
+    /// `(i64, String)`; the unit type `()` is the empty tuple
+    Tuple(Vec<RustFieldType>),
+    /// Use of a `type` alias, with the type it resolves to
+    Alias(String, Box<RustFieldType>),
+    /// `Box<T>`, `Rc<T>` or `Arc<T>`; recursive types need one, and the
+    /// wire only sees `T`
+    Pointer(Ownership, Box<RustFieldType>),
+    /// A reference as written (`&'a str`, `&[u8]`) with the owned type that
+    /// carries its value across the boundary (`String`, `Vec<u8>`)
+    Borrowed(String, Box<RustFieldType>),
+    /// `Cow<'a, str>` or `Cow<'a, [T]>` as written, with the owned type on the
+    /// wire (`String`, `Vec<T>`); either borrowed or owned at run time
+    Cow(String, Box<RustFieldType>),
+    /// `PhantomData<T>`, `PhantomPinned`, or a fieldless struct defined
+    /// alongside, as written; there is nothing to carry across
+    ZeroSized(String),
+    /// Point in time from `chrono`, `time` or `std::time`, as written
+    /// (`DateTime<Utc>`, `OffsetDateTime`, `SystemTime`)
+    DateTime(String),
+    /// `uuid::Uuid`; 16 bytes in an FFI wrapper
+    Uuid,
+    /// `rust_decimal::Decimal` or `bigdecimal::BigDecimal`, as written
+    Decimal(String),
+    /// `num_bigint::BigInt` or `BigUint`, as written
+    BigInt(String),
+    /// `dyn Trait` behind a `Box` or reference, named by its trait; extra
+    /// bounds (`+ Send`) are dropped
+    TraitObject(String),
+    /// Closure trait object (`dyn Fn(i32) -> String`, also `FnMut` and
+    /// `FnOnce`) or function pointer (`fn(i32) -> String`), named by how it
+    /// was written, with its parameter and return types
+    Function(String, Vec<RustFieldType>, Box<RustFieldType>),
+    /// `*const T` or `*mut T` as written, with its pointee; like a C
+    /// pointer, it may be null
+    RawPointer(String, Box<RustFieldType>),
+}
+
+/// Signature of a free `pub fn`
+#[derive(Debug, Clone)]
+pub struct RustFunction {
+    pub name: String,
+    /// Parameters in order; one bound by a pattern is named by position (`arg1`)
+    pub params: Vec<(String, RustFieldType)>,
+    /// `()` when nothing is returned
+    pub ret: RustFieldType,
+    pub location: String,
+    /// `///` doc comment, without markers
+    pub doc: Option<String>,
+}
+
+/// Analyze Rust type definition and extract metadata
+pub fn analyze_rust_type(source: &str) -> Result<RustType, String> {
+    analyze_rust_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct, enum and type alias definition in a Rust source file;
+/// fields that name an alias are resolved through it
+pub fn analyze_rust_types(source: &str) -> Result<Vec<RustType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &Syntax::RUST));
+    let mut types = Vec::new();
+    let mut attributes = Vec::new();
+    let mut default_impls = Vec::new();
+
+    while let Some(token) = cursor.peek() {
+        if token.kind == TokenKind::Punct && token.text == "#" {
+            if let Some(attr) = parse_attribute(&mut cursor, source)? {
+                attributes.push(attr);
+            }
+        } else if cursor.is_ident("pub") {
+            parse_visibility(&mut cursor);
+        } else if cursor.is_ident("struct") {
+            let line = cursor.line();
+            let doc = item_doc(&cursor, source);
+            cursor.advance();
+            types.push(RustType { doc, ..parse_struct(&mut cursor, source, std::mem::take(&mut attributes), line)? });
+        } else if cursor.is_ident("enum") {
+            let line = cursor.line();
+            let doc = item_doc(&cursor, source);
+            cursor.advance();
+            types.push(RustType { doc, ..parse_enum(&mut cursor, source, std::mem::take(&mut attributes), line)? });
+        } else if cursor.is_ident("type") {
+            let line = cursor.line();
+            let doc = item_doc(&cursor, source);
+            cursor.advance();
+            types.push(RustType { doc, ..parse_alias(&mut cursor, std::mem::take(&mut attributes), line)? });
+        } else if cursor.is_ident("impl") || cursor.is_ident("trait") {
+            // Associated types aren't definitions of their own
+            let mut default_for = None;
+            while !cursor.at_end() && !cursor.is_punct('{') && !cursor.is_punct(';') {
+                if cursor.is_ident("Default") && cursor.peek_at(1).is_some_and(|t| t.text == "for") {
+                    cursor.advance();
+                    cursor.advance();
+                    default_for = cursor.peek().map(|t| t.text.clone());
+                }
+                cursor.advance();
+            }
+            if let Some(name) = default_for.filter(|_| cursor.is_punct('{')) {
+                default_impls.push((name, default_literals(&cursor, source)));
+            }
+            cursor.skip_balanced('{', '}');
+            attributes.clear();
+        } else if cursor.is_ident("fn") {
+            // Items declared in a body are local to it
+            while !cursor.at_end() && !cursor.is_punct('{') && !cursor.is_punct(';') {
+                cursor.advance();
+            }
+            if cursor.is_punct('{') {
+                cursor.skip_balanced('{', '}');
+            }
+            attributes.clear();
+        } else if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(1, '!') {
+            // `macro_rules! m { ... }` and invocations (`thread_local! { ... }`):
+            // the items in a macro only exist once it's expanded
+            cursor.advance();
+            cursor.advance();
+            if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) {
+                cursor.advance();
+            }
+            if let Some((open, close)) = [('{', '}'), ('(', ')'), ('[', ']')].into_iter().find(|&(open, _)| cursor.is_punct(open)) {
+                cursor.skip_balanced(open, close);
+            }
+            attributes.clear();
+        } else {
+            attributes.clear();
+            cursor.advance();
+        }
+    }
+
+    resolve_aliases(&mut types)?;
+    resolve_unit_structs(&mut types);
+    resolve_defaults(&mut types, &default_impls);
+    Ok(types)
+}
+
+/// Analyze the signatures of the free `pub fn`s in a Rust source file; generic,
+/// `async`, `unsafe` and `extern` functions, methods, and signatures with a
+/// type no binding can carry (`impl Trait`) are left out. Parameter and
+/// return types that name an alias are resolved through it
+pub fn analyze_rust_functions(source: &str) -> Result<Vec<RustFunction>, String> {
+    let aliases: Vec<RustType> = analyze_rust_types(source)?
+        .into_iter()
+        .filter(|rust_type| rust_type.kind == RustTypeKind::Alias)
+        .collect();
+    let mut cursor = Cursor::new(tokenize(source, &Syntax::RUST));
+    let mut functions = Vec::new();
+    let mut public = false;
+    while !cursor.at_end() {
+        if cursor.is_ident("pub") {
+            public = matches!(parse_visibility(&mut cursor), Visibility::Public);
+            continue;
+        }
+        if cursor.is_ident("const") && cursor.peek_at(1).is_some_and(|t| t.text == "fn") {
+            cursor.advance();
+            continue;
+        }
+        if cursor.is_ident("fn") {
+            let line = cursor.line();
+            let doc = item_doc(&cursor, source);
+            cursor.advance();
+            let name = cursor.expect_ident()?;
+            let signature = match public && cursor.is_punct('(') {
+                true => parse_fn_signature(&mut cursor).ok(),
+                false => None,
+            };
+            if let Some((params, ret)) = signature {
+                let resolve = |field_type: &RustFieldType| resolve_alias(field_type, &aliases, &mut Vec::new());
+                functions.push(RustFunction {
+                    name,
+                    params: params
+                        .iter()
+                        .map(|(name, param)| Ok((name.clone(), resolve(param)?)))
+                        .collect::<Result<_, String>>()?,
+                    ret: resolve(&ret)?,
+                    location: format!("line {}", line),
+                    doc,
+                });
+            }
+            // Past the body, or the `;` of a declaration
+            while !cursor.at_end() && !cursor.is_punct('{') && !cursor.eat_punct(';') {
+                cursor.advance();
+            }
+        }
+        // Bodies of items, including `impl` and `mod` blocks, hold nothing free
+        if cursor.is_punct('{') {
+            cursor.skip_balanced('{', '}');
+        } else {
+            cursor.advance();
+        }
+        public = false;
+    }
+    Ok(functions)
+}
+
+/// `(name: T, ...) -> R` after a function's name; no `->` returns `()`
+fn parse_fn_signature(cursor: &mut Cursor) -> Result<(Vec<(String, RustFieldType)>, RustFieldType), String> {
+    cursor.expect_punct('(')?;
+    let mut params = Vec::new();
+    while !cursor.eat_punct(')') {
+        cursor.eat_ident("mut");
+        let name = match cursor.peek_at(1) {
+            Some(next) if next.text == ":" => cursor.expect_ident()?,
+            // A destructuring pattern; skip to its type
+            _ => {
+                while !cursor.at_end() && !cursor.is_punct(':') {
+                    if cursor.is_punct('(') {
+                        cursor.skip_balanced('(', ')');
+                    } else if cursor.is_punct('{') {
+                        cursor.skip_balanced('{', '}');
+                    } else if cursor.is_punct('[') {
+                        cursor.skip_balanced('[', ']');
+                    } else {
+                        cursor.advance();
+                    }
+                }
+                format!("arg{}", params.len())
+            }
+        };
+        cursor.expect_punct(':')?;
+        params.push((name, parse_type(cursor, &[])?));
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    let ret = if cursor.is_punct_at(0, '-') && cursor.is_punct_at(1, '>') {
+        cursor.advance();
+        cursor.advance();
+        parse_type(cursor, &[])?
+    } else {
+        RustFieldType::Tuple(Vec::new())
+    };
+    Ok((params, ret))
+}
+
+/// `///` doc comment above the item or field at `cursor`
+fn item_doc(cursor: &Cursor, source: &str) -> Option<String> {
+    cursor.peek().and_then(|token| line_doc(source, token.start, "///", &["#"]))
+}
+
+/// Field values of the struct literal a `Default` impl body (at `cursor`) returns
+fn default_literals(cursor: &Cursor, source: &str) -> Vec<(String, DefaultValue)> {
+    let mut body = Vec::new();
+    let mut cursor = cursor.clone();
+    let mut depth = 0;
+    while let Some(token) = cursor.advance() {
+        match (token.kind, token.text.as_str()) {
+            (TokenKind::Punct, "{") => depth += 1,
+            (TokenKind::Punct, "}") => depth -= 1,
+            _ => {}
+        }
+        body.push(token);
+        if depth == 0 {
+            break;
+        }
+    }
+    // `Self { port: ...` or `Config { port: ...`, past the `-> Self {` that opens the function
+    let Some(start) = body.windows(4).position(|window| {
+        window[0].kind == TokenKind::Ident
+            && window[0].text.starts_with(char::is_uppercase)
+            && window[1].text == "{"
+            && window[2].kind == TokenKind::Ident
+            && window[3].text == ":"
+    }) else {
+        return Vec::new();
+    };
+
+    let mut literals = Vec::new();
+    let mut rest = &body[start + 2..];
+    // `name: expr,` up to the closing brace or a `..Default::default()` tail
+    while let [name, colon, tail @ ..] = rest {
+        if name.kind != TokenKind::Ident || colon.text != ":" {
+            break;
+        }
+        let mut depth = 0;
+        let len = tail
+            .iter()
+            .position(|t| {
+                match t.text.as_str() {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" if depth > 0 => depth -= 1,
+                    "," | "}" if depth == 0 => return true,
+                    _ => {}
+                }
+                false
+            })
+            .unwrap_or(tail.len());
+        literals.push((name.text.clone(), literal_default(&tail[..len], source)));
+        rest = tail.get(len + 1..).unwrap_or_default();
+    }
+    literals
+}
+
+/// A default expression as a literal where it is one (`8080`, `"en".to_string()`,
+/// `Vec::new()`), otherwise as the computation written
+fn literal_default(tokens: &[Token], source: &str) -> DefaultValue {
+    let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+    let string = || tokens.iter().find(|t| t.kind == TokenKind::Str).map(|t| DefaultValue::String(t.text.clone()));
+    let literal = match texts.as_slice() {
+        ["true"] => Some(DefaultValue::Bool(true)),
+        ["false"] => Some(DefaultValue::Bool(false)),
+        ["None"] => Some(DefaultValue::Null),
+        [number] if tokens[0].kind == TokenKind::Number => Some(DefaultValue::Number(number_literal(number))),
+        ["-", number] if tokens[1].kind == TokenKind::Number => Some(DefaultValue::Number(format!("-{}", number_literal(number)))),
+        ["Some", "(", .., ")"] => Some(literal_default(&tokens[2..tokens.len() - 1], source)),
+        [_] | [_, ".", "to_string" | "to_owned" | "into", "(", ")"] | ["String", ":", ":", "from", "(", _, ")"] => string(),
+        ["String", ":", ":", "new", "(", ")"] => Some(DefaultValue::String(String::new())),
+        ["vec", "!", "[", "]"] | ["Vec" | "HashMap" | "BTreeMap" | "HashSet" | "BTreeSet" | "VecDeque", ":", ":", "new", "(", ")"] => {
+            Some(DefaultValue::Empty)
+        }
+        [.., ":", ":", "default", "(", ")"] => Some(DefaultValue::TypeDefault),
+        _ => None,
+    };
+    match (literal, tokens.first(), tokens.last()) {
+        (Some(literal), _, _) => literal,
+        (None, Some(first), Some(last)) => DefaultValue::Computed(source[first.start..last.end].to_string()),
+        (None, _, _) => DefaultValue::TypeDefault,
+    }
+}
+
+/// `8_080u16` → `8080`
+fn number_literal(text: &str) -> String {
+    const SUFFIXES: [&str; 14] = ["i128", "isize", "i16", "i32", "i64", "i8", "u128", "usize", "u16", "u32", "u64", "u8", "f32", "f64"];
+    let text = SUFFIXES.iter().find_map(|suffix| text.strip_suffix(suffix).filter(|rest| !rest.is_empty())).unwrap_or(text);
+    text.replace('_', "")
+}
+
+/// Record what serde fills in for missing fields; `#[serde(default)]` on the
+/// struct takes each field from the struct's `Default` impl when one is given
+fn resolve_defaults(types: &mut [RustType], default_impls: &[(String, Vec<(String, DefaultValue)>)]) {
+    for rust_type in types.iter_mut() {
+        let computed = attribute_arg(&rust_type.attributes, "serde", "default");
+        let whole = computed.is_some() || attribute_flag(&rust_type.attributes, "serde", "default");
+        let literals = default_impls
+            .iter()
+            .find(|(name, _)| *name == rust_type.name)
+            .map(|(_, literals)| literals.as_slice())
+            .unwrap_or_default();
+        for field in rust_type.fields.iter_mut() {
+            field.default = field_default(field).or_else(|| {
+                whole.then(|| match &computed {
+                    Some(path) => DefaultValue::Computed(format!("{}().{}", path, field.name)),
+                    None => literals
+                        .iter()
+                        .find(|(name, _)| *name == field.name)
+                        .map(|(_, literal)| literal.clone())
+                        .unwrap_or(DefaultValue::TypeDefault),
+                })
+            });
+        }
+        for field in rust_type.variants.iter_mut().flat_map(|variant| variant.fields.iter_mut()) {
+            field.default = field_default(field);
+        }
+    }
+}
+
+/// `#[serde(default)]` or `#[serde(default = "path")]` on the field itself
+fn field_default(field: &RustField) -> Option<DefaultValue> {
+    match attribute_arg(&field.attributes, "serde", "default") {
+        Some(path) => Some(DefaultValue::Computed(format!("{}()", path))),
+        None => attribute_flag(&field.attributes, "serde", "default").then_some(DefaultValue::TypeDefault),
+    }
+}
+
+/// Mark fields holding a fieldless struct defined among `types` as zero-sized
+fn resolve_unit_structs(types: &mut [RustType]) {
+    let units: Vec<String> = types
+        .iter()
+        .filter(|t| t.kind == RustTypeKind::Struct && t.fields.is_empty() && t.type_params.is_empty())
+        .map(|t| t.name.clone())
+        .collect();
+    let fields = types
+        .iter_mut()
+        .flat_map(|t| t.fields.iter_mut().chain(t.variants.iter_mut().flat_map(|variant| variant.fields.iter_mut())));
+    for field in fields {
+        if let RustFieldType::Struct(name) = &field.field_type {
+            if units.contains(name) {
+                field.field_type = RustFieldType::ZeroSized(name.clone());
+            }
+        }
+    }
+}
+
+/// `type UserId = i64;`
+fn parse_alias(cursor: &mut Cursor, attributes: Vec<String>, line: usize) -> Result<RustType, String> {
+    let name = cursor.expect_ident()?;
+    let (type_params, const_params) = parse_type_params(cursor)?;
+    skip_where_clause(cursor);
+    cursor.expect_punct('=')?;
+    let aliased = parse_type(cursor, &in_scope(&type_params, &const_params))?;
+    cursor.expect_punct(';')?;
+
+    Ok(RustType {
+        name,
+        kind: RustTypeKind::Alias,
+        type_params,
+        const_params,
+        aliased: Some(aliased),
+        fields: Vec::new(),
+        variants: Vec::new(),
+        attributes,
+        location: format!("line {}", line),
+        doc: None,
+    })
+}
+
+/// Resolve every use of an alias defined among `types`; a plain alias keeps
+/// its name, a generic one is expanded with its arguments bound
+fn resolve_aliases(types: &mut [RustType]) -> Result<(), String> {
+    let aliases: Vec<RustType> = types.iter().filter(|t| t.aliased.is_some()).cloned().collect();
+    if aliases.is_empty() {
+        return Ok(());
+    }
+    for rust_type in types.iter_mut() {
+        let mut active = vec![rust_type.name.clone()];
+        if let Some(aliased) = &rust_type.aliased {
+            rust_type.aliased = Some(resolve_alias(aliased, &aliases, &mut active)?);
+        }
+        let fields = rust_type
+            .fields
+            .iter_mut()
+            .chain(rust_type.variants.iter_mut().flat_map(|variant| variant.fields.iter_mut()));
+        for field in fields {
+            field.field_type = resolve_alias(&field.field_type, &aliases, &mut Vec::new())?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_alias(field_type: &RustFieldType, aliases: &[RustType], active: &mut Vec<String>) -> Result<RustFieldType, String> {
+    let mut resolve = |inner: &RustFieldType| resolve_alias(inner, aliases, active);
+    let (name, args) = match field_type {
+        RustFieldType::Struct(name) => (name, Vec::new()),
+        RustFieldType::Generic(name, args) => (name, args.iter().map(&mut resolve).collect::<Result<Vec<_>, _>>()?),
+        RustFieldType::Vec(inner) => return Ok(RustFieldType::Vec(Box::new(resolve(inner)?))),
+        RustFieldType::Array(inner, len) => return Ok(RustFieldType::Array(Box::new(resolve(inner)?), *len)),
+        RustFieldType::ConstArray(inner, len) => return Ok(RustFieldType::ConstArray(Box::new(resolve(inner)?), len.clone())),
+        RustFieldType::Option(inner) => return Ok(RustFieldType::Option(Box::new(resolve(inner)?))),
+        RustFieldType::Pointer(ownership, inner) => return Ok(RustFieldType::Pointer(*ownership, Box::new(resolve(inner)?))),
+        RustFieldType::Borrowed(written, owned) => return Ok(RustFieldType::Borrowed(written.clone(), Box::new(resolve(owned)?))),
+        RustFieldType::Cow(written, owned) => return Ok(RustFieldType::Cow(written.clone(), Box::new(resolve(owned)?))),
+        RustFieldType::Result(ok, err) => return Ok(RustFieldType::Result(Box::new(resolve(ok)?), Box::new(resolve(err)?))),
+        RustFieldType::Map(key, value) => return Ok(RustFieldType::Map(Box::new(resolve(key)?), Box::new(resolve(value)?))),
+        RustFieldType::Tuple(members) => return Ok(RustFieldType::Tuple(members.iter().map(resolve).collect::<Result<_, _>>()?)),
+        RustFieldType::Function(written, params, ret) => {
+            let params = params.iter().map(&mut resolve).collect::<Result<_, _>>()?;
+            return Ok(RustFieldType::Function(written.clone(), params, Box::new(resolve(ret)?)));
+        }
+        _ => return Ok(field_type.clone()),
+    };
+    let alias = match aliases.iter().find(|alias| &alias.name == name && alias.type_params.len() == args.len()) {
+        Some(alias) => alias,
+        None if args.is_empty() => return Ok(field_type.clone()),
+        None => return Ok(RustFieldType::Generic(name.clone(), args)),
+    };
+    if active.contains(name) {
+        return Err(format!("type alias `{}` refers to itself", name));
+    }
+    active.push(name.clone());
+    let aliased = alias.aliased.as_ref().map(|aliased| substitute(aliased, &alias.type_params, &args));
+    let resolved = resolve_alias(aliased.as_ref().unwrap_or(field_type), aliases, active)?;
+    active.pop();
+    Ok(if args.is_empty() { RustFieldType::Alias(name.clone(), Box::new(resolved)) } else { resolved })
+}
+
+fn parse_struct(
+    cursor: &mut Cursor,
+    source: &str,
+    attributes: Vec<String>,
+    line: usize,
+) -> Result<RustType, String> {
+    let name = cursor.expect_ident()?;
+    let (type_params, const_params) = parse_type_params(cursor)?;
+    let scope = in_scope(&type_params, &const_params);
+
+    let mut fields = Vec::new();
+    if cursor.is_punct('(') {
+        fields = parse_tuple_fields(cursor, source, &scope)?;
+    }
+
+    skip_where_clause(cursor);
+
+    if cursor.is_punct('{') {
+        fields = parse_named_fields(cursor, source, &scope)?;
+    } else {
+        cursor.eat_punct(';');
+    }
+
+    Ok(RustType {
+        name,
+        kind: RustTypeKind::Struct,
+        type_params,
+        const_params,
+        aliased: None,
+        fields,
+        variants: Vec::new(),
+        attributes,
+        location: format!("line {}", line),
+        doc: None,
+    })
+}
+
+fn parse_enum(
+    cursor: &mut Cursor,
+    source: &str,
+    attributes: Vec<String>,
+    line: usize,
+) -> Result<RustType, String> {
+    let name = cursor.expect_ident()?;
+    let (type_params, const_params) = parse_type_params(cursor)?;
+    let scope = in_scope(&type_params, &const_params);
+    skip_where_clause(cursor);
+    cursor.expect_punct('{')?;
+
+    let mut variants = Vec::new();
+    while !cursor.eat_punct('}') {
+        let attributes = parse_field_attributes(cursor, source)?;
+        let name = cursor.expect_ident()?;
+        let (kind, fields) = if cursor.is_punct('(') {
+            (RustVariantKind::Tuple, parse_tuple_fields(cursor, source, &scope)?)
+        } else if cursor.is_punct('{') {
+            (RustVariantKind::Struct, parse_named_fields(cursor, source, &scope)?)
+        } else {
+            (RustVariantKind::Unit, Vec::new())
+        };
+        let discriminant = if cursor.eat_punct('=') { Some(parse_discriminant(cursor, source)?) } else { None };
+        variants.push(RustVariant { name, kind, fields, discriminant, attributes });
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+
+    Ok(RustType {
+        name,
+        kind: RustTypeKind::Enum,
+        type_params,
+        const_params,
+        aliased: None,
+        fields: Vec::new(),
+        variants,
+        attributes,
+        location: format!("line {}", line),
+        doc: None,
+    })
+}
+
+/// Const parameters with their types (`const N: usize` → `("N", Usize)`)
+type ConstParams = Vec<(String, RustFieldType)>;
+
+/// Type and const parameters of a definition (`<'a, T: Clone, const N: usize>`);
+/// bounds and defaults are skipped, and lifetimes dropped
+fn parse_type_params(cursor: &mut Cursor) -> Result<(Vec<String>, ConstParams), String> {
+    let mut params = Vec::new();
+    let mut consts = Vec::new();
+    if !cursor.eat_punct('<') {
+        return Ok((params, consts));
+    }
+    while !cursor.eat_punct('>') {
+        let lifetime = cursor.eat_punct('\'');
+        let constant = cursor.eat_ident("const");
+        let name = cursor.expect_ident()?;
+        if constant {
+            cursor.expect_punct(':')?;
+            consts.push((name, parse_type(cursor, &[])?));
+        } else if !lifetime {
+            params.push(name);
+        }
+        while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct('>') {
+            if cursor.is_punct('<') {
+                cursor.skip_balanced('<', '>');
+            } else if cursor.is_punct('(') {
+                cursor.skip_balanced('(', ')');
+            } else if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+                // `F: Fn() -> T`
+                cursor.advance();
+                cursor.advance();
+            } else {
+                cursor.advance();
+            }
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+            return Err(cursor.unexpected("`,` or `>`"));
+        }
+    }
+    Ok((params, consts))
+}
+
+/// Names a field type may use: the type parameters, and the const ones as array lengths
+fn in_scope(type_params: &[String], const_params: &[(String, RustFieldType)]) -> Vec<String> {
+    type_params.iter().cloned().chain(const_params.iter().map(|(name, _)| name.clone())).collect()
+}
+
+/// `(pub i64, String)`, with fields named by position
+fn parse_tuple_fields(cursor: &mut Cursor, source: &str, type_params: &[String]) -> Result<Vec<RustField>, String> {
+    cursor.expect_punct('(')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct(')') {
+        let attributes = parse_field_attributes(cursor, source)?;
+        let visibility = parse_visibility(cursor);
+        let field_type = parse_type(cursor, type_params)?;
+        fields.push(RustField { name: fields.len().to_string(), field_type, visibility, attributes, default: None, doc: None });
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    Ok(fields)
+}
+
+/// `{ pub id: i64, name: String }`
+fn parse_named_fields(cursor: &mut Cursor, source: &str, type_params: &[String]) -> Result<Vec<RustField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        let doc = cursor.previous().is_some_and(|t| t.line < cursor.line()).then(|| item_doc(cursor, source)).flatten();
+        let attributes = parse_field_attributes(cursor, source)?;
+        let visibility = parse_visibility(cursor);
+        let field_name = cursor.expect_ident()?;
+        cursor.expect_punct(':')?;
+        let field_type = parse_type(cursor, type_params)?;
+        fields.push(RustField { name: field_name, field_type, visibility, attributes, default: None, doc });
+        if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+            return Err(cursor.unexpected("`,` or `}`"));
+        }
+    }
+    Ok(fields)
+}
+
+/// Source text of a discriminant expression, up to the next variant
+fn parse_discriminant(cursor: &mut Cursor, source: &str) -> Result<String, String> {
+    let start = match cursor.peek() {
+        Some(token) if !cursor.is_punct(',') && !cursor.is_punct('}') => token.start,
+        _ => return Err(cursor.unexpected("discriminant")),
+    };
+    while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct('}') {
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        } else {
+            cursor.advance();
+        }
+    }
+    let end = cursor.previous().map(|t| t.end).unwrap_or(source.len());
+    Ok(source[start..end].to_string())
+}
+
+fn skip_where_clause(cursor: &mut Cursor) {
+    if cursor.eat_ident("where") {
+        while !cursor.at_end() && !cursor.is_punct('{') && !cursor.is_punct(';') {
+            if cursor.is_punct('<') {
+                cursor.skip_balanced('<', '>');
+            } else {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+fn parse_field_attributes(cursor: &mut Cursor, source: &str) -> Result<Vec<String>, String> {
+    let mut attributes = Vec::new();
+    while cursor.is_punct('#') {
+        if let Some(attr) = parse_attribute(cursor, source)? {
+            attributes.push(attr);
+        }
+    }
+    Ok(attributes)
+}
+
+/// Parse `#[...]` and return its source text; inner `#![...]` attributes yield `None`
+fn parse_attribute(cursor: &mut Cursor, source: &str) -> Result<Option<String>, String> {
+    let start = cursor.advance().map(|t| t.start).unwrap_or(0);
+    let inner = cursor.eat_punct('!');
+    if !cursor.is_punct('[') {
+        return Err(cursor.unexpected("`[`"));
+    }
+    cursor.skip_balanced('[', ']');
+    let end = cursor.previous().map(|t| t.end).unwrap_or(source.len());
+    Ok((!inner).then(|| source[start..end].to_string()))
+}
+
+fn parse_visibility(cursor: &mut Cursor) -> Visibility {
+    if !cursor.eat_ident("pub") {
+        return Visibility::Private;
+    }
+    if cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+        Visibility::Crate
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Date-time types of `chrono`, `time` and `std::time`
+const DATE_TIMES: &[&str] = &["DateTime", "NaiveDateTime", "OffsetDateTime", "PrimitiveDateTime", "UtcDateTime", "SystemTime"];
+
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<RustFieldType, String> {
+    if cursor.eat_punct('&') {
+        let lifetime = if cursor.eat_punct('\'') { format!("'{} ", cursor.expect_ident()?) } else { String::new() };
+        let mutable = if cursor.eat_ident("mut") { "mut " } else { "" };
+        let (referent, owned) = parse_referent(cursor, type_params)?;
+        return Ok(RustFieldType::Borrowed(format!("&{}{}{}", lifetime, mutable, referent), Box::new(owned)));
+    }
+    if cursor.eat_punct('*') {
+        let mutability = match () {
+            _ if cursor.eat_ident("const") => "const",
+            _ if cursor.eat_ident("mut") => "mut",
+            _ => return Err(cursor.unexpected("`const` or `mut`")),
+        };
+        let pointee = parse_type(cursor, type_params)?;
+        return Ok(RustFieldType::RawPointer(format!("*{} {}", mutability, rust_type_name(&pointee)), Box::new(pointee)));
+    }
+    if cursor.eat_punct('[') {
+        let element = parse_type(cursor, type_params)?;
+        cursor.expect_punct(';')?;
+        // A length named by a constant can't be checked here; keep it dynamic
+        let len = cursor.peek().filter(|t| t.kind == TokenKind::Number).and_then(|t| t.text.parse().ok());
+        let param = cursor
+            .peek()
+            .filter(|t| t.kind == TokenKind::Ident && type_params.contains(&t.text) && cursor.is_punct_at(1, ']'))
+            .map(|t| t.text.clone());
+        while !cursor.eat_punct(']') {
+            if cursor.at_end() {
+                return Err(cursor.unexpected("`]`"));
+            }
+            cursor.advance();
+        }
+        return Ok(match (len, param) {
+            (Some(len), _) => RustFieldType::Array(Box::new(element), len),
+            (None, Some(param)) => RustFieldType::ConstArray(Box::new(element), param),
+            (None, None) => RustFieldType::Vec(Box::new(element)),
+        });
+    }
+    if cursor.eat_punct('(') {
+        let mut members = Vec::new();
+        let mut trailing_comma = false;
+        while !cursor.eat_punct(')') {
+            members.push(parse_type(cursor, type_params)?);
+            trailing_comma = cursor.eat_punct(',');
+            if !trailing_comma && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+        // `(T)` is just a parenthesized `T`; `(T,)` is a one-element tuple
+        return Ok(match members.len() {
+            1 if !trailing_comma => members.remove(0),
+            _ => RustFieldType::Tuple(members),
+        });
+    }
+
+    let first = match cursor.peek() {
+        Some(t) if t.kind == TokenKind::Ident => t.text.clone(),
+        _ => return Err(cursor.unexpected("type")),
+    };
+    if first == "impl" {
+        return Err(format!("line {}: unsupported type `{}`", cursor.line(), first));
+    }
+    if cursor.eat_ident("fn") {
+        return parse_signature(cursor, "fn".to_string(), type_params);
+    }
+    if cursor.eat_ident("dyn") {
+        return parse_trait_object(cursor, type_params);
+    }
+
+    // Walk the path (`std::collections::HashMap`) and keep the last segment
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct_at(0, ':') && cursor.is_punct_at(1, ':') {
+        cursor.advance();
+        cursor.advance();
+        name = cursor.expect_ident()?;
+    }
+
+    if matches!(name.as_str(), "PhantomData" | "PhantomPinned") && !type_params.contains(&name) {
+        return parse_marker(cursor, name, type_params);
+    }
+
+    // `Cow<'a, B>` holds either a `&'a B` or its owned form
+    if name == "Cow" && !type_params.contains(&name) && cursor.eat_punct('<') {
+        let lifetime = if cursor.eat_punct('\'') {
+            let lifetime = format!("'{}, ", cursor.expect_ident()?);
+            if !cursor.eat_punct(',') {
+                return Err(cursor.unexpected("`,`"));
+            }
+            lifetime
+        } else {
+            String::new()
+        };
+        let (referent, owned) = parse_pointee(cursor, type_params)?;
+        if !cursor.eat_punct('>') {
+            return Err(cursor.unexpected("`>`"));
+        }
+        return Ok(RustFieldType::Cow(format!("Cow<{}{}>", lifetime, referent), Box::new(owned)));
+    }
+
+    let pointer = match name.as_str() {
+        "Box" => Some(Ownership::Boxed),
+        "Rc" => Some(Ownership::Shared),
+        "Arc" => Some(Ownership::AtomicShared),
+        _ => None,
+    };
+    if let Some(ownership) = pointer.filter(|_| !type_params.contains(&name)) {
+        // The pointee may be unsized, as behind a reference (`Arc<str>`, `Rc<[T]>`)
+        if !cursor.eat_punct('<') {
+            return Err(format!("`{}` is missing its inner type argument", name));
+        }
+        let (_, pointee) = parse_pointee(cursor, type_params)?;
+        if !cursor.eat_punct('>') {
+            return Err(cursor.unexpected("`>`"));
+        }
+        return Ok(RustFieldType::Pointer(ownership, Box::new(pointee)));
+    }
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            // Lifetime arguments (`Cow<'a, str>`) don't affect the shape
+            if cursor.eat_punct('\'') {
+                cursor.expect_ident()?;
+            } else {
+                args.push(parse_type(cursor, type_params)?);
+            }
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    // The time zone argument of `DateTime<Utc>` doesn't change the wire form
+    if DATE_TIMES.contains(&name.as_str()) && !type_params.contains(&name) {
+        return Ok(RustFieldType::DateTime(match args.first() {
+            Some(zone) => format!("{}<{}>", name, rust_type_name(zone)),
+            None => name,
+        }));
+    }
+
+    let builtin = matches!(name.as_str(), "Vec" | "Option" | "Result" | "HashMap" | "BTreeMap" | "IndexMap");
+    if !builtin && !args.is_empty() {
+        return Ok(RustFieldType::Generic(name, args));
+    }
+    if type_params.contains(&name) {
+        return Ok(RustFieldType::TypeParam(name));
+    }
+
+    let mut args = args.into_iter();
+    let mut arg = |what: &str| {
+        args.next()
+            .map(Box::new)
+            .ok_or_else(|| format!("`{}` is missing its {} type argument", name, what))
+    };
+
+    Ok(match name.as_str() {
+        "i64" => RustFieldType::I64,
+        "i32" => RustFieldType::I32,
+        "u64" => RustFieldType::U64,
+        "u32" => RustFieldType::U32,
+        "u8" => RustFieldType::U8,
+        "i8" => RustFieldType::I8,
+        "i16" => RustFieldType::I16,
+        "u16" => RustFieldType::U16,
+        "i128" => RustFieldType::I128,
+        "u128" => RustFieldType::U128,
+        "isize" => RustFieldType::Isize,
+        "usize" => RustFieldType::Usize,
+        "String" => RustFieldType::String,
+        "bool" => RustFieldType::Bool,
+        "f64" => RustFieldType::F64,
+        "f32" => RustFieldType::F32,
+        "Uuid" => RustFieldType::Uuid,
+        "Decimal" | "BigDecimal" => RustFieldType::Decimal(name),
+        "BigInt" | "BigUint" => RustFieldType::BigInt(name),
+        "Vec" => RustFieldType::Vec(arg("element")?),
+        "Option" => RustFieldType::Option(arg("inner")?),
+        "Result" => {
+            let ok = arg("ok")?;
+            RustFieldType::Result(ok, arg("error")?)
+        }
+        "HashMap" | "BTreeMap" | "IndexMap" => {
+            let key = arg("key")?;
+            // Floats are neither `Hash` nor `Ord`
+            if matches!(*key, RustFieldType::F32 | RustFieldType::F64) {
+                return Err(format!("`{}` cannot key a `{}`", rust_type_name(&key), name));
+            }
+            RustFieldType::Map(key, arg("value")?)
+        }
+        _ => RustFieldType::Struct(name.clone()),
+    })
+}
+
+/// `dyn Handler<T> + Send + 'static` or `dyn Fn(i64) -> bool`, after `dyn`
+fn parse_trait_object(cursor: &mut Cursor, type_params: &[String]) -> Result<RustFieldType, String> {
+    let mut bounds = Vec::new();
+    let mut signature = None;
+    loop {
+        if cursor.eat_punct('\'') {
+            cursor.expect_ident()?;
+        } else {
+            let mut name = cursor.expect_ident()?;
+            while cursor.is_punct_at(0, ':') && cursor.is_punct_at(1, ':') {
+                cursor.advance();
+                cursor.advance();
+                name = cursor.expect_ident()?;
+            }
+            if cursor.is_punct('<') {
+                cursor.skip_balanced('<', '>');
+            } else if cursor.is_punct('(') {
+                // `Fn(A) -> R` sugar
+                signature = Some(parse_signature(cursor, name.clone(), type_params)?);
+            }
+            bounds.push(name);
+        }
+        if !cursor.eat_punct('+') {
+            break;
+        }
+    }
+    if let Some(signature) = signature {
+        return Ok(signature);
+    }
+    // Auto traits only add guarantees; the principal trait names the object
+    let auto = |name: &String| matches!(name.as_str(), "Send" | "Sync" | "Unpin");
+    let principal = bounds.iter().find(|name| !auto(name)).or(bounds.first());
+    principal
+        .map(|name| RustFieldType::TraitObject(name.clone()))
+        .ok_or_else(|| cursor.unexpected("a trait"))
+}
+
+/// `(A, B) -> R` after `fn` or a closure trait; no `->` returns `()`
+fn parse_signature(cursor: &mut Cursor, written: String, type_params: &[String]) -> Result<RustFieldType, String> {
+    if !cursor.eat_punct('(') {
+        return Err(cursor.unexpected("`(`"));
+    }
+    let mut params = Vec::new();
+    while !cursor.eat_punct(')') {
+        params.push(parse_type(cursor, type_params)?);
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    let ret = if cursor.is_punct_at(0, '-') && cursor.is_punct_at(1, '>') {
+        cursor.advance();
+        cursor.advance();
+        parse_type(cursor, type_params)?
+    } else {
+        RustFieldType::Tuple(Vec::new())
+    };
+    Ok(RustFieldType::Function(written, params, Box::new(ret)))
+}
+
+/// What a reference points at, as written, and the owned type holding the same value
+fn parse_referent(cursor: &mut Cursor, type_params: &[String]) -> Result<(String, RustFieldType), String> {
+    let (referent, owned) = parse_pointee(cursor, type_params)?;
+    let owned = match owned {
+        // `&dyn Trait` is owned as `Box<dyn Trait>`
+        RustFieldType::TraitObject(_) => RustFieldType::Pointer(Ownership::Boxed, Box::new(owned)),
+        RustFieldType::Function(ref written, _, _) if written != "fn" => RustFieldType::Pointer(Ownership::Boxed, Box::new(owned)),
+        _ => owned,
+    };
+    Ok((referent, owned))
+}
+
+/// `PhantomData<T>` after its name; the argument is never carried, so one that
+/// isn't a field type (`fn() -> T`, `*const T`) is written as `_`
+fn parse_marker(cursor: &mut Cursor, name: String, type_params: &[String]) -> Result<RustFieldType, String> {
+    if !cursor.is_punct('<') {
+        return Ok(RustFieldType::ZeroSized(name));
+    }
+    let mut arg = cursor.clone();
+    arg.advance();
+    if let Ok(parsed) = parse_type(&mut arg, type_params) {
+        if arg.eat_punct('>') {
+            *cursor = arg;
+            return Ok(RustFieldType::ZeroSized(format!("{}<{}>", name, rust_type_name(&parsed))));
+        }
+    }
+    cursor.advance();
+    let mut depth = 1;
+    let mut arrow = false;
+    while depth > 0 {
+        let token = cursor.advance().ok_or_else(|| format!("`{}` is missing its closing `>`", name))?;
+        match token.text.as_str() {
+            "<" => depth += 1,
+            // The `>` of `->` closes nothing
+            ">" if !arrow => depth -= 1,
+            _ => {}
+        }
+        arrow = token.text == "-";
+    }
+    Ok(RustFieldType::ZeroSized(format!("{}<_>", name)))
+}
+
+/// What a reference or smart pointer points to, which may be unsized, as
+/// written and as the owned type carrying its value
+fn parse_pointee(cursor: &mut Cursor, type_params: &[String]) -> Result<(String, RustFieldType), String> {
+    // A slice `[T]`; an array `[T; N]` is parsed as a type below
+    let mut slice = cursor.clone();
+    if slice.eat_punct('[') {
+        let element = parse_type(&mut slice, type_params)?;
+        if slice.eat_punct(']') {
+            *cursor = slice;
+            return Ok((format!("[{}]", rust_type_name(&element)), RustFieldType::Vec(Box::new(element))));
+        }
+    }
+    if cursor.eat_ident("str") {
+        return Ok(("str".to_string(), RustFieldType::String));
+    }
+    let referent = parse_type(cursor, type_params)?;
+    Ok((rust_type_name(&referent), referent))
+}
+
+/// Extract `key = "value"` from an attribute such as `#[interop(converter = "epoch_millis")]`
+pub fn attribute_arg(attributes: &[String], path: &str, key: &str) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        let mut cursor = Cursor::new(tokenize(attr, &Syntax::RUST));
+        cursor.eat_punct('#');
+        cursor.eat_punct('[');
+        if !cursor.eat_ident(path) || !cursor.eat_punct('(') {
+            return None;
+        }
+        while !cursor.at_end() {
+            if cursor.eat_ident(key) && cursor.eat_punct('=') {
+                return cursor
+                    .peek()
+                    .filter(|t| t.kind == TokenKind::Str)
+                    .map(|t| t.text.clone());
+            }
+            cursor.advance();
+        }
+        None
+    })
+}
+
+/// Whether a bare flag such as `skip_serializing` appears in `#[serde(...)]`-style attributes
+pub fn attribute_flag(attributes: &[String], path: &str, flag: &str) -> bool {
+    attributes.iter().any(|attr| {
+        let mut cursor = Cursor::new(tokenize(attr, &Syntax::RUST));
+        cursor.eat_punct('#');
+        cursor.eat_punct('[');
+        if !cursor.eat_ident(path) || !cursor.eat_punct('(') {
+            return false;
+        }
+        while !cursor.at_end() {
+            if cursor.eat_ident(flag) && !cursor.is_punct('=') {
+                return true;
+            }
+            cursor.advance();
+        }
+        false
+    })
+}
+
+/// Name of the custom converter attached to a field via `#[interop(converter = "...")]`
+pub fn field_converter(field: &RustField) -> Option<String> {
+    attribute_arg(&field.attributes, "interop", "converter")
+}
+
+/// Key a field points at via `#[interop(refs = "User.id")]`
+pub fn field_reference(field: &RustField) -> Option<String> {
+    attribute_arg(&field.attributes, "interop", "refs")
+}
+
+/// Wire direction of a field: `#[interop(direction = "serialize_only")]`, or
+/// serde's `skip_deserializing` / `skip_serializing`
+pub fn field_direction(field: &RustField) -> Direction {
+    if let Some(direction) = attribute_arg(&field.attributes, "interop", "direction").and_then(|name| Direction::from_name(&name)) {
+        return direction;
+    }
+    let skip_serializing = attribute_flag(&field.attributes, "serde", "skip_serializing");
+    let skip_deserializing = attribute_flag(&field.attributes, "serde", "skip_deserializing");
+    match (skip_serializing, skip_deserializing) {
+        (true, false) => Direction::DeserializeOnly,
+        (false, true) => Direction::SerializeOnly,
+        _ => Direction::Both,
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(rust_type: &RustType, target: &str) -> f32 {
+    compatibility_score_with(rust_type, target, &ConverterRegistry::default())
+}
+
+/// Calculate compatibility score, treating fields with custom converters as exact;
+/// an enum scores the payload fields of its variants
+pub fn compatibility_score_with(rust_type: &RustType, target: &str, converters: &ConverterRegistry) -> f32 {
+    if field_fidelity(&RustFieldType::Bool, target) == Fidelity::Unsupported {
+        return 0.0;
+    }
+
+    average_fidelity(named_fields(rust_type).map(|(name, field)| {
+        let attribute = field_converter(field);
+        match converters.resolve(&rust_type.name, &name, attribute.as_deref()) {
+            Some(converter) if converter.supports("rust") && converter.supports(target) => Fidelity::Exact,
+            _ => field_fidelity(&field.field_type, target),
+        }
+    }))
+}
+
+/// Fidelity of the default mapping of a Rust field type into a target language
+pub fn field_fidelity(field_type: &RustFieldType, target: &str) -> Fidelity {
+    match field_type {
+        // Scored as the fixed-length array it becomes; the length doesn't change the score
+        RustFieldType::ConstArray(inner, _) => ir::fidelity(&IrType::Container(ContainerType::Array(Box::new(to_ir(inner)), 0)), target),
+        RustFieldType::Option(inner) | RustFieldType::Pointer(_, inner) => field_fidelity(inner, target),
+        _ => ir::fidelity(&to_ir(field_type), target),
+    }
+}
+
+/// Lower a Rust field type into the language-neutral IR
+pub fn to_ir(field_type: &RustFieldType) -> IrType {
+    match field_type {
+        RustFieldType::I64 | RustFieldType::Isize => IrType::Primitive(PrimitiveType::I64),
+        RustFieldType::I32 => IrType::Primitive(PrimitiveType::I32),
+        RustFieldType::U64 | RustFieldType::Usize => IrType::Primitive(PrimitiveType::U64),
+        RustFieldType::U32 => IrType::Primitive(PrimitiveType::U32),
+        RustFieldType::U8 => IrType::Primitive(PrimitiveType::U8),
+        RustFieldType::I8 => IrType::Primitive(PrimitiveType::I8),
+        RustFieldType::I16 => IrType::Primitive(PrimitiveType::I16),
+        RustFieldType::U16 => IrType::Primitive(PrimitiveType::U16),
+        RustFieldType::I128 => IrType::Primitive(PrimitiveType::I128),
+        RustFieldType::U128 => IrType::Primitive(PrimitiveType::U128),
+        RustFieldType::String => IrType::Primitive(PrimitiveType::String),
+        RustFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        RustFieldType::F64 => IrType::Primitive(PrimitiveType::F64),
+        RustFieldType::F32 => IrType::Primitive(PrimitiveType::F32),
+        RustFieldType::Struct(name) => IrType::Reference(name.clone()),
+        RustFieldType::Generic(name, args) => IrType::Generic(name.clone(), args.iter().map(to_ir).collect()),
+        RustFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+        // A byte buffer, not a list of numbers: targets have a type for it
+        RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => IrType::Primitive(PrimitiveType::Bytes),
+        RustFieldType::Vec(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        RustFieldType::Array(inner, len) => IrType::Container(ContainerType::Array(Box::new(to_ir(inner)), *len)),
+        // The length is only known once `instantiate_consts` binds it; until
+        // then the field is listed in `sized_fields`
+        RustFieldType::ConstArray(inner, _) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        RustFieldType::Option(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        RustFieldType::Result(ok, err) => {
+            IrType::Container(ContainerType::Result(Box::new(to_ir(ok)), Box::new(to_ir(err))))
+        }
+        RustFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        RustFieldType::Tuple(members) if members.is_empty() => IrType::Primitive(PrimitiveType::Unit),
+        // serde writes a marker as `null`
+        RustFieldType::ZeroSized(_) => IrType::Primitive(PrimitiveType::Unit),
+        RustFieldType::Tuple(members) => IrType::Tuple(members.iter().map(to_ir).collect()),
+        RustFieldType::Alias(name, aliased) => IrType::Alias(name.clone(), Box::new(to_ir(aliased))),
+        RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => to_ir(inner),
+        RustFieldType::TraitObject(name) => IrType::TraitObject(name.clone()),
+        RustFieldType::Function(_, params, ret) => IrType::Function(params.iter().map(to_ir).collect(), Box::new(to_ir(ret))),
+        RustFieldType::RawPointer(_, pointee) => IrType::Container(ContainerType::Option(Box::new(to_ir(pointee)))),
+        RustFieldType::DateTime(_) => IrType::Primitive(PrimitiveType::DateTime),
+        RustFieldType::Uuid => IrType::Primitive(PrimitiveType::Uuid),
+        RustFieldType::Decimal(_) => IrType::Primitive(PrimitiveType::Decimal),
+        RustFieldType::BigInt(_) => IrType::Primitive(PrimitiveType::BigInt),
+    }
+}
+
+/// Integer width that depends on the target triple (`usize`, `isize`),
+/// looking through containers
+pub fn platform_width(field_type: &RustFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        RustFieldType::Isize | RustFieldType::Usize => Some(PlatformWidth::Pointer),
+        RustFieldType::Vec(inner)
+        | RustFieldType::Array(inner, _)
+        | RustFieldType::ConstArray(inner, _)
+        | RustFieldType::Option(inner)
+        | RustFieldType::Pointer(_, inner)
+        | RustFieldType::Borrowed(_, inner)
+        | RustFieldType::Cow(_, inner)
+        | RustFieldType::Alias(_, inner) => platform_width(inner),
+        _ => None,
+    }
+}
+
+/// The wrapped type of a newtype (`pub struct Email(String);`): a struct
+/// with exactly one positional field
+pub fn newtype_inner(rust_type: &RustType) -> Option<&RustFieldType> {
+    match rust_type.fields.as_slice() {
+        [only] if rust_type.kind == RustTypeKind::Struct && only.name == "0" => Some(&only.field_type),
+        _ => None,
+    }
+}
+
+/// Fields holding a borrow anywhere in their type, as `(field, type as
+/// written, owned equivalent)`; payload fields are named `Variant.field`
+pub fn borrowed_fields(rust_type: &RustType) -> Vec<(String, String, String)> {
+    named_fields(rust_type)
+        .filter_map(|(name, field)| {
+            let owned = owned(&field.field_type)?;
+            Some((name, rust_type_name(&field.field_type), rust_type_name(&owned)))
+        })
+        .collect()
+}
+
+/// `field_type` with every borrow replaced by its owned equivalent, or
+/// `None` when it holds no borrow
+fn owned(field_type: &RustFieldType) -> Option<RustFieldType> {
+    let or_same = |inner: &RustFieldType| owned(inner).unwrap_or_else(|| inner.clone());
+    let any_borrowed = |members: &[&RustFieldType]| members.iter().any(|member| owned(member).is_some());
+    match field_type {
+        RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => Some(or_same(inner)),
+        RustFieldType::Vec(inner) => owned(inner).map(|inner| RustFieldType::Vec(Box::new(inner))),
+        RustFieldType::Array(inner, len) => owned(inner).map(|inner| RustFieldType::Array(Box::new(inner), *len)),
+        RustFieldType::ConstArray(inner, len) => owned(inner).map(|inner| RustFieldType::ConstArray(Box::new(inner), len.clone())),
+        RustFieldType::Option(inner) => owned(inner).map(|inner| RustFieldType::Option(Box::new(inner))),
+        RustFieldType::Pointer(ownership, inner) => owned(inner).map(|inner| RustFieldType::Pointer(*ownership, Box::new(inner))),
+        RustFieldType::Result(ok, err) if any_borrowed(&[ok, err]) => {
+            Some(RustFieldType::Result(Box::new(or_same(ok)), Box::new(or_same(err))))
+        }
+        RustFieldType::Map(key, value) if any_borrowed(&[key, value]) => {
+            Some(RustFieldType::Map(Box::new(or_same(key)), Box::new(or_same(value))))
+        }
+        RustFieldType::Tuple(members) if any_borrowed(&members.iter().collect::<Vec<_>>()) => {
+            Some(RustFieldType::Tuple(members.iter().map(or_same).collect()))
+        }
+        RustFieldType::Generic(name, args) if any_borrowed(&args.iter().collect::<Vec<_>>()) => {
+            Some(RustFieldType::Generic(name.clone(), args.iter().map(or_same).collect()))
+        }
+        _ => None,
+    }
+}
+
+/// Date-time, decimal and big integer fields, anywhere in their type, as
+/// `(field, type as written, wire format)`; the format is serde's default for
+/// the type's crate unless `#[serde(with = "...")]` names another
+pub fn wire_format_fields(rust_type: &RustType) -> Vec<(String, String, String)> {
+    named_fields(rust_type)
+        .filter_map(|(name, field)| {
+            let crate_type = crate_formatted(&field.field_type)?;
+            let with = attribute_arg(&field.attributes, "serde", "with");
+            let format = match (crate_type, with) {
+                (RustFieldType::DateTime(_), Some(with)) => date_time_format_with(&with),
+                (RustFieldType::DateTime(written), None) => date_time_format(written).to_string(),
+                (_, Some(with)) => number_format_with(&with),
+                (_, None) => number_format(crate_type).to_string(),
+            };
+            Some((name, rust_type_name(crate_type), format))
+        })
+        .collect()
+}
+
+/// The type inside `field_type` whose wire form its crate decides
+fn crate_formatted(field_type: &RustFieldType) -> Option<&RustFieldType> {
+    match field_type {
+        RustFieldType::DateTime(_) | RustFieldType::Decimal(_) | RustFieldType::BigInt(_) => Some(field_type),
+        RustFieldType::Vec(inner)
+        | RustFieldType::Array(inner, _)
+        | RustFieldType::ConstArray(inner, _)
+        | RustFieldType::Option(inner)
+        | RustFieldType::Pointer(_, inner)
+        | RustFieldType::Borrowed(_, inner)
+        | RustFieldType::Cow(_, inner)
+        | RustFieldType::Alias(_, inner) => crate_formatted(inner),
+        _ => None,
+    }
+}
+
+fn number_format(field_type: &RustFieldType) -> &'static str {
+    match field_type {
+        // `rust_decimal` and `bigdecimal` keep every digit by writing text
+        RustFieldType::Decimal(_) => "a string of decimal digits; targets parsing it as a float lose precision",
+        _ => "a sign and an array of 32-bit digits; add a `serde(with)` module for a string of decimal digits",
+    }
+}
+
+/// Format of a `serde(with)` module such as `rust_decimal::serde::float`
+fn number_format_with(with: &str) -> String {
+    let module = with.rsplit("::").next().unwrap_or(with);
+    if module.starts_with("float") {
+        "a JSON float, rounded to 53 bits of precision".to_string()
+    } else if module.starts_with("str") {
+        "a string of decimal digits".to_string()
+    } else if module.starts_with("arbitrary_precision") {
+        "a JSON number with every digit; most parsers read it back as a float".to_string()
+    } else {
+        format!("whatever `{}` writes", with)
+    }
+}
+
+fn date_time_format(written: &str) -> &'static str {
+    match written.split('<').next() {
+        Some("DateTime") => "an RFC 3339 string",
+        Some("NaiveDateTime") => "an ISO 8601 string without an offset",
+        Some("SystemTime") => "a `{ secs_since_epoch, nanos_since_epoch }` object; targets expecting RFC 3339 need a `serde(with)` module",
+        // `time` serializes components unless told otherwise
+        _ => "an array of date and time components; add `#[serde(with = \"time::serde::rfc3339\")]` for an RFC 3339 string",
+    }
+}
+
+/// Format of a `serde(with)` module such as `chrono::serde::ts_milliseconds`
+fn date_time_format_with(with: &str) -> String {
+    let module = with.rsplit("::").next().unwrap_or(with);
+    let unit = ["nano", "micro", "milli"].into_iter().find(|unit| module.contains(unit)).unwrap_or("");
+    if module.starts_with("ts_") || module.starts_with("timestamp") {
+        format!("a Unix timestamp in {}seconds", unit)
+    } else if module.starts_with("rfc3339") {
+        "an RFC 3339 string".to_string()
+    } else if module.starts_with("rfc2822") {
+        "an RFC 2822 string".to_string()
+    } else if module.starts_with("iso8601") {
+        "an ISO 8601 string".to_string()
+    } else {
+        format!("whatever `{}` writes", with)
+    }
+}
+
+/// Struct fields, or the payload fields of every variant as `Variant.field`;
+/// zero-sized fields serde skips are left out
+fn named_fields(rust_type: &RustType) -> impl Iterator<Item = (String, &RustField)> + '_ {
+    all_named_fields(rust_type).filter(|(_, field)| !skipped_zero_sized(field))
+}
+
+/// A zero-sized field marked `#[serde(skip)]`: the wire never sees it. Any
+/// other zero-sized field is still written, as `null` (or `[]` for `[T; 0]`)
+fn skipped_zero_sized(field: &RustField) -> bool {
+    is_zero_sized(&field.field_type) && attribute_flag(&field.attributes, "serde", "skip")
+}
+
+fn all_named_fields(rust_type: &RustType) -> impl Iterator<Item = (String, &RustField)> + '_ {
+    let fields = rust_type.fields.iter().map(|field| (field.name.clone(), field));
+    let payloads = rust_type.variants.iter().flat_map(|variant| {
+        variant
+            .fields
+            .iter()
+            .map(move |field| (format!("{}.{}", variant.name, field.name), field))
+    });
+    fields.chain(payloads)
+}
+
+/// Struct fields holding an array whose length is a const parameter, as
+/// `(field, parameter)`
+pub fn sized_fields(rust_type: &RustType) -> Vec<(String, String)> {
+    let rule = attribute_arg(&rust_type.attributes, "serde", "rename_all");
+    rust_type
+        .fields
+        .iter()
+        .filter_map(|field| Some((wire_name(field, rule.as_deref()), const_length(&field.field_type)?.to_string())))
+        .collect()
+}
+
+fn const_length(field_type: &RustFieldType) -> Option<&str> {
+    match field_type {
+        RustFieldType::ConstArray(_, len) => Some(len),
+        RustFieldType::Option(inner) | RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) => const_length(inner),
+        _ => None,
+    }
+}
+
+/// Zero-sized fields serde still writes, as `(field, type as written)`;
+/// mapping keeps them though they carry nothing
+pub fn zero_sized_fields(rust_type: &RustType) -> Vec<(String, String)> {
+    named_fields(rust_type)
+        .filter(|(_, field)| is_zero_sized(&field.field_type))
+        .map(|(name, field)| (name, rust_type_name(&field.field_type)))
+        .collect()
+}
+
+/// Whether a field of this type occupies no space: a marker, `()`, or `[T; 0]`
+pub fn is_zero_sized(field_type: &RustFieldType) -> bool {
+    match field_type {
+        RustFieldType::ZeroSized(_) | RustFieldType::Array(_, 0) => true,
+        RustFieldType::Tuple(members) => members.iter().all(is_zero_sized),
+        RustFieldType::Alias(_, aliased) => is_zero_sized(aliased),
+        _ => false,
+    }
+}
+
+/// Lower all fields of a Rust type into the IR under their serde keys; enum
+/// payloads are flattened as `Variant.key`, the way the other analyzers
+/// lower sum types
+pub fn ir_fields(rust_type: &RustType) -> Vec<IrField> {
+    let struct_rule = attribute_arg(&rust_type.attributes, "serde", "rename_all");
+    let fields = rust_type
+        .fields
+        .iter()
+        .filter(|field| !skipped_zero_sized(field))
+        .map(|field| ir_field(&wire_name(field, struct_rule.as_deref()), field));
+    let payloads = rust_type.variants.iter().flat_map(|variant| {
+        let rule = field_rule(rust_type, variant);
+        variant
+            .fields
+            .iter()
+            .filter(|field| !skipped_zero_sized(field))
+            .map(move |field| ir_field(&format!("{}.{}", variant.name, wire_name(field, rule.as_deref())), field))
+    });
+    fields.chain(payloads).collect()
+}
+
+/// serde's key for a field: its `#[serde(rename = "...")]`, else `rule`
+/// (the container's `rename_all`) applied to its name
+fn wire_name(field: &RustField, rule: Option<&str>) -> String {
+    attribute_arg(&field.attributes, "serde", "rename").unwrap_or_else(|| match rule {
+        Some(rule) => rename_field(rule, &field.name),
+        None => field.name.clone(),
+    })
+}
+
+/// The `rename_all` rule over a variant's fields: the variant's own, else
+/// the enum's `rename_all_fields`
+fn field_rule(rust_type: &RustType, variant: &RustVariant) -> Option<String> {
+    attribute_arg(&variant.attributes, "serde", "rename_all").or_else(|| attribute_arg(&rust_type.attributes, "serde", "rename_all_fields"))
+}
+
+/// serde's wire tag for a variant: its `#[serde(rename = "...")]`, else the
+/// enum's `rename_all` applied to its name; `None` when it is written as is
+fn variant_tag(rust_type: &RustType, variant: &RustVariant) -> Option<String> {
+    attribute_arg(&variant.attributes, "serde", "rename")
+        .or_else(|| attribute_arg(&rust_type.attributes, "serde", "rename_all").map(|rule| rename_variant(&rule, &variant.name)))
+}
+
+/// A `snake_case` field name under a serde `rename_all` rule; unknown rules,
+/// which serde rejects, leave it as is
+fn rename_field(rule: &str, name: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => crate::codegen::pascal_case(name),
+        "camelCase" => crate::codegen::camel_case(name),
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// A `PascalCase` variant name under a serde `rename_all` rule
+fn rename_variant(rule: &str, name: &str) -> String {
+    let snake = || {
+        let mut snake = String::new();
+        for (i, c) in name.char_indices() {
+            if i > 0 && c.is_ascii_uppercase() {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    };
+    match rule {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "camelCase" => name[..1].to_ascii_lowercase() + &name[1..],
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// A field lowered into the IR; a smart pointer around the whole field is
+/// recorded as its ownership rather than its type
+fn ir_field(name: &str, field: &RustField) -> IrField {
+    let ownership = match field.field_type {
+        RustFieldType::Pointer(ownership, _) => ownership,
+        _ => Ownership::Owned,
+    };
+    IrField::new(name, to_ir(&field.field_type))
+        .with_direction(field_direction(field))
+        .with_ownership(ownership)
+        .with_default(field.default.clone())
+        .with_doc(field.doc.clone())
+}
+
+/// Lower the variants of an enum into the IR, with their serde tags and
+/// keys; structs have none
+pub fn ir_variants(rust_type: &RustType) -> Vec<IrVariant> {
+    rust_type
+        .variants
+        .iter()
+        .map(|variant| {
+            let rule = field_rule(rust_type, variant);
+            let fields = variant
+                .fields
+                .iter()
+                .filter(|field| !skipped_zero_sized(field))
+                .map(|field| match variant.kind {
+                    RustVariantKind::Tuple => ir_field(&field.name, field),
+                    _ => ir_field(&wire_name(field, rule.as_deref()), field),
+                })
+                .collect();
+            IrVariant::new(&variant.name, fields).with_tag(variant_tag(rust_type, variant))
+        })
+        .collect()
+}
+
+/// serde's wire layout of an enum: externally tagged unless `#[serde(tag = "...")]`,
+/// with `content = "..."`, or `#[serde(untagged)]` says otherwise; structs have none
+pub fn tagging(rust_type: &RustType) -> Option<VariantTagging> {
+    if rust_type.kind != RustTypeKind::Enum {
+        return None;
+    }
+    if attribute_flag(&rust_type.attributes, "serde", "untagged") {
+        return Some(VariantTagging::Untagged);
+    }
+    let tag = attribute_arg(&rust_type.attributes, "serde", "tag");
+    Some(match (tag, attribute_arg(&rust_type.attributes, "serde", "content")) {
+        (Some(tag), Some(content)) => VariantTagging::Adjacent(tag, content),
+        (Some(tag), None) => VariantTagging::Internal(tag),
+        (None, _) => VariantTagging::External,
+    })
+}
+
+/// Bind the type parameters of a generic definition (`Pagination<T>` with
+/// `User`), so it scores and maps like the concrete type it stands for
+pub fn instantiate(rust_type: &RustType, args: &[RustFieldType]) -> Result<RustType, String> {
+    if args.len() != rust_type.type_params.len() {
+        return Err(format!(
+            "`{}` takes {} type arguments but {} were given",
+            rust_type.name,
+            rust_type.type_params.len(),
+            args.len()
+        ));
+    }
+    let bind = |fields: &[RustField]| -> Vec<RustField> {
+        fields
+            .iter()
+            .map(|field| RustField { field_type: substitute(&field.field_type, &rust_type.type_params, args), ..field.clone() })
+            .collect()
+    };
+    let mut instance = rust_type.clone();
+    instance.type_params.clear();
+    instance.fields = bind(&rust_type.fields);
+    for variant in &mut instance.variants {
+        variant.fields = bind(&variant.fields);
+    }
+    Ok(instance)
+}
+
+/// Bind the const parameters of a definition (`Matrix<const N: usize>` with
+/// `3`), so its `[T; N]` fields become fixed-length arrays
+pub fn instantiate_consts(rust_type: &RustType, values: &[usize]) -> Result<RustType, String> {
+    if values.len() != rust_type.const_params.len() {
+        return Err(format!(
+            "`{}` takes {} const arguments but {} were given",
+            rust_type.name,
+            rust_type.const_params.len(),
+            values.len()
+        ));
+    }
+    let params: Vec<&str> = rust_type.const_params.iter().map(|(name, _)| name.as_str()).collect();
+    let bind = |fields: &[RustField]| -> Vec<RustField> {
+        fields
+            .iter()
+            .map(|field| RustField { field_type: bind_consts(&field.field_type, &params, values), ..field.clone() })
+            .collect()
+    };
+    let mut instance = rust_type.clone();
+    instance.const_params.clear();
+    instance.fields = bind(&rust_type.fields);
+    for variant in &mut instance.variants {
+        variant.fields = bind(&variant.fields);
+    }
+    Ok(instance)
+}
+
+fn bind_consts(field_type: &RustFieldType, params: &[&str], values: &[usize]) -> RustFieldType {
+    let bind = |inner: &RustFieldType| bind_consts(inner, params, values);
+    match field_type {
+        RustFieldType::ConstArray(inner, len) => match params.iter().position(|param| param == len) {
+            Some(i) => RustFieldType::Array(Box::new(bind(inner)), values[i]),
+            None => RustFieldType::ConstArray(Box::new(bind(inner)), len.clone()),
+        },
+        RustFieldType::Generic(name, inner) => RustFieldType::Generic(name.clone(), inner.iter().map(bind).collect()),
+        RustFieldType::Vec(inner) => RustFieldType::Vec(Box::new(bind(inner))),
+        RustFieldType::Array(inner, len) => RustFieldType::Array(Box::new(bind(inner)), *len),
+        RustFieldType::Option(inner) => RustFieldType::Option(Box::new(bind(inner))),
+        RustFieldType::Result(ok, err) => RustFieldType::Result(Box::new(bind(ok)), Box::new(bind(err))),
+        RustFieldType::Map(key, value) => RustFieldType::Map(Box::new(bind(key)), Box::new(bind(value))),
+        RustFieldType::Tuple(members) => RustFieldType::Tuple(members.iter().map(bind).collect()),
+        RustFieldType::Alias(name, aliased) => RustFieldType::Alias(name.clone(), Box::new(bind(aliased))),
+        RustFieldType::Pointer(ownership, inner) => RustFieldType::Pointer(*ownership, Box::new(bind(inner))),
+        RustFieldType::Borrowed(written, owned) => RustFieldType::Borrowed(written.clone(), Box::new(bind(owned))),
+        RustFieldType::Cow(written, owned) => RustFieldType::Cow(written.clone(), Box::new(bind(owned))),
+        _ => field_type.clone(),
+    }
+}
+
+fn substitute(field_type: &RustFieldType, params: &[String], args: &[RustFieldType]) -> RustFieldType {
+    let bind = |inner: &RustFieldType| substitute(inner, params, args);
+    match field_type {
+        RustFieldType::TypeParam(name) => match params.iter().position(|param| param == name) {
+            Some(i) => args[i].clone(),
+            None => field_type.clone(),
+        },
+        RustFieldType::Generic(name, inner) => RustFieldType::Generic(name.clone(), inner.iter().map(bind).collect()),
+        RustFieldType::Vec(inner) => RustFieldType::Vec(Box::new(bind(inner))),
+        RustFieldType::Array(inner, len) => RustFieldType::Array(Box::new(bind(inner)), *len),
+        RustFieldType::ConstArray(inner, len) => RustFieldType::ConstArray(Box::new(bind(inner)), len.clone()),
+        RustFieldType::Option(inner) => RustFieldType::Option(Box::new(bind(inner))),
+        RustFieldType::Result(ok, err) => RustFieldType::Result(Box::new(bind(ok)), Box::new(bind(err))),
+        RustFieldType::Map(key, value) => RustFieldType::Map(Box::new(bind(key)), Box::new(bind(value))),
+        RustFieldType::Tuple(members) => RustFieldType::Tuple(members.iter().map(bind).collect()),
+        RustFieldType::Alias(name, aliased) => RustFieldType::Alias(name.clone(), Box::new(bind(aliased))),
+        RustFieldType::Pointer(ownership, inner) => RustFieldType::Pointer(*ownership, Box::new(bind(inner))),
+        RustFieldType::Borrowed(written, owned) => RustFieldType::Borrowed(written.clone(), Box::new(bind(owned))),
+        RustFieldType::Cow(written, owned) => RustFieldType::Cow(written.clone(), Box::new(bind(owned))),
+        RustFieldType::Function(written, inner, ret) => {
+            RustFieldType::Function(written.clone(), inner.iter().map(bind).collect(), Box::new(bind(ret)))
+        }
+        _ => field_type.clone(),
+    }
+}
+
+/// Map Rust types to target language types
+pub fn map_to_target(field_type: &RustFieldType, target: &str) -> String {
+    match target {
+        "rescript" => match field_type {
+            RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::Isize
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Usize => "int".to_string(),
+            RustFieldType::String => "string".to_string(),
+            RustFieldType::Bool => "bool".to_string(),
+            RustFieldType::F64 | RustFieldType::F32 => "float".to_string(),
+            RustFieldType::Struct(name) | RustFieldType::Alias(name, _) => name.to_lowercase(),
+            RustFieldType::Generic(name, args) => format!("{}<{}>", name.to_lowercase(), map_members(args, target)),
+            RustFieldType::TypeParam(name) => format!("'{}", name.to_lowercase()),
+            RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => "Js.TypedArray2.ArrayBuffer.t".to_string(),
+            RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) | RustFieldType::ConstArray(inner, _) => {
+                format!("array<{}>", map_to_target(inner, target))
+            }
+            RustFieldType::Option(inner) | RustFieldType::RawPointer(_, inner) => {
+                format!("option<{}>", map_to_target(inner, target))
+            }
+            RustFieldType::Result(ok, err) => {
+                format!("result<{}, {}>", map_to_target(ok, target), map_to_target(err, target))
+            }
+            // `Js.Dict.t` keys are strings; integer keys get Belt's specialized map
+            RustFieldType::Map(key, value) => match **key {
+                ref key if is_integer(key) => format!("Belt.Map.Int.t<{}>", map_to_target(value, target)),
+                _ => format!("Js.Dict.t<{}>", map_to_target(value, target)),
+            },
+            RustFieldType::Tuple(members) => format!("({})", map_members(members, target)),
+            RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => map_to_target(inner, target),
+            RustFieldType::TraitObject(_) => "JSON.t".to_string(),
+            RustFieldType::Function(_, params, ret) => format!("({}) => {}", map_members(params, target), map_to_target(ret, target)),
+            RustFieldType::DateTime(_) | RustFieldType::Uuid => "string".to_string(),
+            RustFieldType::Decimal(_) => "float".to_string(),
+            RustFieldType::BigInt(_) => "bigint".to_string(),
+            RustFieldType::ZeroSized(_) => "unit".to_string(),
+        },
+        "julia" => match field_type {
+            RustFieldType::I64 => "Int64".to_string(),
+            RustFieldType::I32 => "Int32".to_string(),
+            RustFieldType::U64 => "UInt64".to_string(),
+            RustFieldType::U32 => "UInt32".to_string(),
+            RustFieldType::U8 => "UInt8".to_string(),
+            RustFieldType::I8 => "Int8".to_string(),
+            RustFieldType::I16 => "Int16".to_string(),
+            RustFieldType::U16 => "UInt16".to_string(),
+            RustFieldType::I128 => "Int128".to_string(),
+            RustFieldType::U128 => "UInt128".to_string(),
+            // Julia's `Int` and `UInt` are pointer-sized too
+            RustFieldType::Isize => "Int".to_string(),
+            RustFieldType::Usize => "UInt".to_string(),
+            RustFieldType::String => "String".to_string(),
+            RustFieldType::Bool => "Bool".to_string(),
+            RustFieldType::F64 => "Float64".to_string(),
+            RustFieldType::F32 => "Float32".to_string(),
+            RustFieldType::Struct(name) | RustFieldType::Alias(name, _) => name.clone(),
+            RustFieldType::Generic(name, args) => format!("{}{{{}}}", name, map_members(args, target)),
+            RustFieldType::TypeParam(name) => name.clone(),
+            RustFieldType::Vec(inner) => {
+                format!("Vector{{{}}}", map_to_target(inner, target))
+            }
+            // StaticArrays keeps the length in the type
+            RustFieldType::Array(inner, len) => format!("SVector{{{}, {}}}", len, map_to_target(inner, target)),
+            RustFieldType::ConstArray(inner, len) => format!("SVector{{{}, {}}}", len, map_to_target(inner, target)),
+            RustFieldType::Option(inner) | RustFieldType::RawPointer(_, inner) => {
+                format!("Union{{Nothing, {}}}", map_to_target(inner, target))
+            }
+            RustFieldType::Result(ok, err) => {
+                format!("Union{{Ok{{{}}}, Err{{{}}}}}", map_to_target(ok, target), map_to_target(err, target))
+            }
+            RustFieldType::Map(key, value) => format!("Dict{{{}, {}}}", map_to_target(key, target), map_to_target(value, target)),
+            RustFieldType::Tuple(members) => format!("Tuple{{{}}}", map_members(members, target)),
+            RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => map_to_target(inner, target),
+            RustFieldType::TraitObject(_) => "Any".to_string(),
+            RustFieldType::Function(_, _, _) => "Function".to_string(),
+            RustFieldType::DateTime(_) => "DateTime".to_string(),
+            RustFieldType::Uuid => "UUID".to_string(),
+            // Decimals.jl; `BigInt` is built in
+            RustFieldType::Decimal(_) => "Decimal".to_string(),
+            RustFieldType::BigInt(_) => "BigInt".to_string(),
+            RustFieldType::ZeroSized(_) => "Nothing".to_string(),
+        },
+        "gleam" => match field_type {
+            RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::Isize
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Usize => "Int".to_string(),
+            RustFieldType::String => "String".to_string(),
+            RustFieldType::Bool => "Bool".to_string(),
+            RustFieldType::F64 | RustFieldType::F32 => "Float".to_string(),
+            RustFieldType::Struct(name) | RustFieldType::Alias(name, _) => name.clone(),
+            RustFieldType::Generic(name, args) => format!("{}({})", name, map_members(args, target)),
+            RustFieldType::TypeParam(name) => name.to_lowercase(),
+            RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => "BitArray".to_string(),
+            RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) | RustFieldType::ConstArray(inner, _) => {
+                format!("List({})", map_to_target(inner, target))
+            }
+            RustFieldType::Option(inner) | RustFieldType::RawPointer(_, inner) => {
+                format!("Option({})", map_to_target(inner, target))
+            }
+            RustFieldType::Result(ok, err) => {
+                format!("Result({}, {})", map_to_target(ok, target), map_to_target(err, target))
+            }
+            RustFieldType::Map(key, value) => format!("Dict({}, {})", map_to_target(key, target), map_to_target(value, target)),
+            RustFieldType::Tuple(members) => format!("#({})", map_members(members, target)),
+            RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => map_to_target(inner, target),
+            RustFieldType::TraitObject(_) => "Dynamic".to_string(),
+            RustFieldType::Function(_, params, ret) => format!("fn({}) -> {}", map_members(params, target), map_to_target(ret, target)),
+            RustFieldType::DateTime(_) => "birl.Time".to_string(),
+            RustFieldType::Uuid => "String".to_string(),
+            // Gleam's `Int` is unbounded on the Erlang target
+            RustFieldType::Decimal(_) => "Float".to_string(),
+            RustFieldType::BigInt(_) => "Int".to_string(),
+            RustFieldType::ZeroSized(_) => "Nil".to_string(),
+        },
+        "typescript" => match field_type {
+            // Parenthesized so `| null` doesn't bind to the return type
+            RustFieldType::Option(inner) if callback_signature(inner).is_some() => {
+                format!("({}) | null", map_to_target(inner, target))
+            }
+            RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::Isize
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Usize => "number".to_string(),
+            RustFieldType::String => "string".to_string(),
+            RustFieldType::Bool => "boolean".to_string(),
+            RustFieldType::F64 | RustFieldType::F32 => "number".to_string(),
+            RustFieldType::Struct(name) | RustFieldType::Alias(name, _) => name.clone(),
+            RustFieldType::Generic(name, args) => format!("{}<{}>", name, map_members(args, target)),
+            RustFieldType::TypeParam(name) => name.clone(),
+            // Base64 text, as JSON delivers it
+            RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => "string".to_string(),
+            RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) | RustFieldType::ConstArray(inner, _) => {
+                format!("Array<{}>", map_to_target(inner, target))
+            }
+            RustFieldType::Option(inner) | RustFieldType::RawPointer(_, inner) => {
+                format!("{} | null", map_to_target(inner, target))
+            }
+            RustFieldType::Result(ok, err) => {
+                format!("{{ Ok: {} }} | {{ Err: {} }}", map_to_target(ok, target), map_to_target(err, target))
+            }
+            // Object keys are strings; anything but a number is keyed by its text
+            RustFieldType::Map(key, value) => {
+                let key = if is_integer(key) { "number" } else { "string" };
+                format!("Record<{}, {}>", key, map_to_target(value, target))
+            }
+            RustFieldType::Tuple(members) => format!("[{}]", map_members(members, target)),
+            RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => map_to_target(inner, target),
+            RustFieldType::TraitObject(_) => "unknown".to_string(),
+            RustFieldType::Function(_, params, ret) => {
+                let params: Vec<String> = params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, param)| format!("arg{}: {}", i, map_to_target(param, target)))
+                    .collect();
+                format!("({}) => {}", params.join(", "), map_to_target(ret, target))
+            }
+            RustFieldType::DateTime(_) | RustFieldType::Uuid => "string".to_string(),
+            RustFieldType::Decimal(_) => "number".to_string(),
+            RustFieldType::BigInt(_) => "bigint".to_string(),
+            RustFieldType::ZeroSized(_) => "null".to_string(),
+        },
+        "zig" => match field_type {
+            RustFieldType::I8 => "i8".to_string(),
+            RustFieldType::I16 => "i16".to_string(),
+            RustFieldType::I32 => "i32".to_string(),
+            RustFieldType::I64 => "i64".to_string(),
+            RustFieldType::I128 => "i128".to_string(),
+            RustFieldType::U8 => "u8".to_string(),
+            RustFieldType::U16 => "u16".to_string(),
+            RustFieldType::U32 => "u32".to_string(),
+            RustFieldType::U64 => "u64".to_string(),
+            RustFieldType::U128 => "u128".to_string(),
+            RustFieldType::Isize => "isize".to_string(),
+            RustFieldType::Usize => "usize".to_string(),
+            RustFieldType::String => "[]const u8".to_string(),
+            RustFieldType::Bool => "bool".to_string(),
+            RustFieldType::F64 => "f64".to_string(),
+            RustFieldType::F32 => "f32".to_string(),
+            RustFieldType::Struct(name) | RustFieldType::Alias(name, _) => name.clone(),
+            // Generic containers are type functions: `Page(User)`
+            RustFieldType::Generic(name, args) => format!("{}({})", name, map_members(args, target)),
+            RustFieldType::TypeParam(name) => name.clone(),
+            RustFieldType::Vec(inner) => format!("[]const {}", map_to_target(inner, target)),
+            RustFieldType::Array(inner, len) => format!("[{}]{}", len, map_to_target(inner, target)),
+            RustFieldType::ConstArray(inner, len) => format!("[{}]{}", len, map_to_target(inner, target)),
+            RustFieldType::Option(inner) => format!("?{}", map_to_target(inner, target)),
+            RustFieldType::RawPointer(written, inner) => {
+                format!("?*{}{}", if written.starts_with("*const") { "const " } else { "" }, map_to_target(inner, target))
+            }
+            RustFieldType::Result(ok, _) => format!("anyerror!{}", map_to_target(ok, target)),
+            RustFieldType::Map(key, value) => {
+                format!("std.AutoHashMap({}, {})", map_to_target(key, target), map_to_target(value, target))
+            }
+            RustFieldType::Tuple(members) => format!("struct {{ {} }}", map_members(members, target)),
+            RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => map_to_target(inner, target),
+            RustFieldType::TraitObject(_) => "std.json.Value".to_string(),
+            RustFieldType::Function(_, params, ret) => format!("*const fn ({}) {}", map_members(params, target), map_to_target(ret, target)),
+            RustFieldType::DateTime(_) => "i64".to_string(),
+            RustFieldType::Uuid => "[16]u8".to_string(),
+            RustFieldType::Decimal(_) => "f64".to_string(),
+            RustFieldType::BigInt(_) => "std.math.big.int.Managed".to_string(),
+            RustFieldType::ZeroSized(_) => "void".to_string(),
+        },
+        _ => "Unknown".to_string(),
+    }
+}
+
+fn is_integer(field_type: &RustFieldType) -> bool {
+    matches!(
+        field_type,
+        RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::Isize
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Usize
+    )
+}
+
+fn map_members(members: &[RustFieldType], target: &str) -> String {
+    members
+        .iter()
+        .map(|member| map_to_target(member, target))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check if Rust type has FFI-safe attributes
+pub fn is_ffi_safe(rust_type: &RustType) -> bool {
+    rust_type.attributes.iter().any(|attr| attr.contains("#[repr(C)]"))
+}
+
+/// Generate FFI-safe wrapper type; an error names the first field with no C
+/// layout (a map, a tuple, a generic)
+pub fn generate_ffi_wrapper(rust_type: &RustType) -> Result<String, String> {
+    generate_ffi_wrapper_with(rust_type, &ConverterRegistry::default())
+}
+
+/// Generate FFI-safe wrapper type, carrying converted fields in their wire type
+/// and emitting encode/decode functions that call the user's converter hooks
+pub fn generate_ffi_wrapper_with(rust_type: &RustType, converters: &ConverterRegistry) -> Result<String, String> {
+    ffi_wrapper(rust_type, converters, SecurityProfile::Standard)
+}
+
+pub(crate) fn ffi_wrapper(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Result<String, String> {
+    let mut wrapper = format!("/// FFI-safe {} representation\n", rust_type.name);
+    wrapper.push_str("#[repr(C)]\n");
+    wrapper.push_str(&format!("pub struct {}FFI {{\n", rust_type.name));
+
+    let mut codecs = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        if let Some(converter) = field_converter_for(rust_type, field, converters) {
+            let native = rust_type_name(&field.field_type);
+            if let Some(codec) = generate_field_codec(&rust_type.name, &field.name, &native, converter, field_direction(field), "rust") {
+                codecs.push('\n');
+                codecs.push_str(&codec);
+            }
+        }
+        if let RustFieldType::Cow(written, _) = &field.field_type {
+            wrapper.push_str(&format!(
+                "    /// From `{}`: borrowed or owned, the data stays with the source value\n",
+                written
+            ));
+        }
+        if let Some((params, ret)) = callback_signature(&field.field_type) {
+            let callback = format!("{}{}Callback", rust_type.name, crate::codegen::pascal_case(&field.name));
+            wrapper.push_str(&format!(
+                "    /// `{}`, registered from the foreign side\n    {name}: Option<{callback}>,\n    {name}_context: *mut std::ffi::c_void,\n",
+                rust_type_name(&field.field_type),
+                name = field.name
+            ));
+            codecs.push('\n');
+            let registration = ffi_callback_registration(rust_type, &field.name, &callback, params, ret, profile)
+                .map_err(|reason| unrepresentable(rust_type, field, &reason))?;
+            codecs.push_str(&registration);
+            continue;
+        }
+        let field_type = ffi_field_type(rust_type, field, converters);
+        if holds_itself(field_type, &rust_type.name) {
+            return Err(unrepresentable(rust_type, field, "the wrapper would hold itself; a list breaks the cycle"));
+        }
+        let members = ffi_members(&field.name, field_type, profile).map_err(|reason| unrepresentable(rust_type, field, &reason))?;
+        for (member, ty) in members {
+            wrapper.push_str(&format!("    {}: {},\n", member, ty));
+        }
+    }
+
+    wrapper.push_str("}\n");
+    wrapper.push_str(&codecs);
+    wrapper.push_str(&ffi_conversions(rust_type, converters, profile));
+    Ok(wrapper)
+}
+
+/// Error for a field the wrapper has no member for, with the reason
+pub(crate) fn unrepresentable(rust_type: &RustType, field: &RustField, reason: &str) -> String {
+    format!(
+        "`{}.{}` (`{}`) is not FFI-representable: {}",
+        rust_type.name,
+        field.name,
+        rust_type_name(&field.field_type),
+        reason
+    )
+}
+
+/// `char` parses as a named type; the wrapper carries it as its `u32` scalar value
+fn is_char(field_type: &RustFieldType) -> bool {
+    matches!(field_type, RustFieldType::Struct(name) if name == "char")
+}
+
+fn unaliased(field_type: &RustFieldType) -> &RustFieldType {
+    match field_type {
+        RustFieldType::Alias(_, inner) => unaliased(inner),
+        field_type => field_type,
+    }
+}
+
+/// `std::time::SystemTime`, which the wrapper carries as signed nanoseconds
+/// since the Unix epoch; chrono and `time` values need a converter
+fn is_system_time(field_type: &RustFieldType) -> bool {
+    matches!(field_type, RustFieldType::DateTime(written) if written == "SystemTime")
+}
+
+/// Nanoseconds since the epoch of the `SystemTime` `time`, saturating past
+/// the ±292 years an `i64` holds
+fn system_time_nanos(time: &str) -> String {
+    format!(
+        "match {time}.duration_since(std::time::UNIX_EPOCH) {{ Ok(after) => i64::try_from(after.as_nanos()).unwrap_or(i64::MAX), \
+         Err(before) => i64::try_from(before.duration().as_nanos()).map_or(i64::MIN, |nanos| -nanos) }}"
+    )
+}
+
+/// The `SystemTime` `nanos` nanoseconds from the epoch
+fn system_time_from_nanos(nanos: &str) -> String {
+    format!(
+        "if {nanos} >= 0 {{ std::time::UNIX_EPOCH + std::time::Duration::from_nanos({nanos} as u64) }} \
+         else {{ std::time::UNIX_EPOCH - std::time::Duration::from_nanos({nanos}.unsigned_abs()) }}"
+    )
+}
+
+/// The owned value behind `Box`, `Rc` and `Arc`, as an expression and its
+/// type, looking into an option too: a boxed value moves out, while a shared
+/// one, or a boxed string or slice, is cloned out
+fn unpointered(native: String, field_type: &RustFieldType) -> (String, RustFieldType) {
+    match unaliased(field_type) {
+        RustFieldType::Pointer(ownership, inner) => {
+            let owned = match (ownership, unaliased(inner)) {
+                (Ownership::Boxed, RustFieldType::String | RustFieldType::Vec(_)) | (Ownership::Shared | Ownership::AtomicShared, _) => {
+                    format!("(*{}).to_owned()", native)
+                }
+                _ => format!("(*{})", native),
+            };
+            unpointered(owned, inner)
+        }
+        RustFieldType::Option(inner) if matches!(unaliased(inner), RustFieldType::Pointer(..)) => {
+            let (owned, inner) = unpointered("pointee".to_string(), inner);
+            (format!("{}.map(|pointee| {})", native, owned), RustFieldType::Option(Box::new(inner)))
+        }
+        field_type => (native, field_type.clone()),
+    }
+}
+
+/// What a field points to through aliases and `Box`, `Rc` or `Arc`
+fn pointee(field_type: &RustFieldType) -> &RustFieldType {
+    match field_type {
+        RustFieldType::Alias(_, inner) | RustFieldType::Pointer(_, inner) => pointee(inner),
+        field_type => field_type,
+    }
+}
+
+/// Whether reading a field back goes through the `string` reader
+fn reads_string(field_type: &RustFieldType) -> bool {
+    match field_type {
+        RustFieldType::Alias(_, inner) | RustFieldType::Pointer(_, inner) | RustFieldType::Option(inner) => reads_string(inner),
+        field_type => matches!(field_type, RustFieldType::String),
+    }
+}
+
+/// Whether the wrapper of `name` would hold a field of its own type by value,
+/// which gives it no finite size
+fn holds_itself(field_type: &RustFieldType, name: &str) -> bool {
+    match field_type {
+        RustFieldType::Struct(nested) => nested == name,
+        RustFieldType::Alias(_, inner)
+        | RustFieldType::Pointer(_, inner)
+        | RustFieldType::Option(inner)
+        | RustFieldType::Array(inner, _)
+        | RustFieldType::Borrowed(_, inner) => holds_itself(inner, name),
+        _ => false,
+    }
+}
+
+/// `{prefix}_free` of the wrapper a nested struct crosses as
+fn nested_free(name: &str) -> String {
+    format!("{}_free", crate::codegen::snake_case(name))
+}
+
+/// A number, which both sides read the same without conversion
+fn is_number(field_type: &RustFieldType) -> bool {
+    matches!(
+        unaliased(field_type),
+        RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::Isize
+            | RustFieldType::Usize
+            | RustFieldType::F32
+            | RustFieldType::F64
+    )
+}
+
+/// Element type of a list the wrapper carries as `_ptr` and `_len`: a number,
+/// or a struct as its own wrapper
+fn ffi_element(field_type: &RustFieldType) -> Result<String, String> {
+    match unaliased(field_type) {
+        element if is_number(element) => Ok(rust_type_name(element)),
+        element @ RustFieldType::Struct(name) if !is_char(element) => Ok(format!("{}FFI", name)),
+        element => Err(format!("a list of `{}` has no flat C layout", rust_type_name(element))),
+    }
+}
+
+/// A fixed array of numbers, nested or not, crosses as written: `[T; N]` has
+/// the same layout in Rust and C, and copies in and out as is
+fn ffi_array(field_type: &RustFieldType) -> Result<String, String> {
+    match unaliased(field_type) {
+        RustFieldType::Array(element, len) => Ok(format!("[{}; {}]", ffi_array(element)?, len)),
+        element if is_number(element) => Ok(rust_type_name(element)),
+        element => Err(format!("an array of `{}` has no flat C layout", rust_type_name(element))),
+    }
+}
+
+/// Whether the native type can be named without lifetimes or type arguments,
+/// which the conversions would have to spell out
+fn ffi_nameable(rust_type: &RustType) -> bool {
+    rust_type.kind == RustTypeKind::Struct
+        && rust_type.type_params.is_empty()
+        && rust_type.const_params.is_empty()
+        && !rust_type
+            .fields
+            .iter()
+            .any(|field| matches!(field.field_type, RustFieldType::Borrowed(..) | RustFieldType::Cow(..)))
+}
+
+/// Body of `From<X> for XFFI`: the strings and lists leaked before the struct
+/// expression, its members, and the statements `{prefix}_free` reclaims them with
+struct IntoFfi {
+    leaks: String,
+    members: String,
+    frees: String,
+}
+
+/// Why no conversion is generated: the native type can't be named, or a
+/// field's converter has no Rust hook for the direction
+fn unnameable(rust_type: &RustType) -> String {
+    format!("`{}` has lifetimes or parameters the conversion would have to name", rust_type.name)
+}
+
+fn into_ffi(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Result<IntoFfi, String> {
+    if !ffi_nameable(rust_type) {
+        return Err(unnameable(rust_type));
+    }
+    let mut into = IntoFfi { leaks: String::new(), members: String::new(), frees: String::new() };
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = &field.name;
+        // Callbacks are registered from the foreign side
+        if callback_signature(&field.field_type).is_some() {
+            into.members.push_str(&format!("            {name}: None,\n            {name}_context: std::ptr::null_mut(),\n"));
+            continue;
+        }
+        let native = match field_converter_for(rust_type, field, converters) {
+            Some(converter) if converter.hooks.contains_key("rust") && field_direction(field).serializes() => {
+                format!("encode_{}_{}(&value.{})", rust_type.name.to_lowercase(), name, name)
+            }
+            Some(converter) => return Err(format!("`{}` goes through `{}`, which has no Rust encoder", name, converter.name)),
+            None => format!("value.{}", name),
+        };
+        let flag = |present: String| match profile {
+            SecurityProfile::Paranoid => format!("u8::from({})", present),
+            _ => present,
+        };
+        let free = |element: &str| {
+            format!(
+                "    if !value.{name}_ptr.is_null() {{\n        \
+                 drop(unsafe {{ Box::from_raw(std::ptr::slice_from_raw_parts_mut(value.{name}_ptr as *mut {element}, value.{name}_len)) }});\n    }}\n"
+            )
+        };
+        let (native, field_type) = unpointered(native, ffi_field_type(rust_type, field, converters));
+        match unaliased(&field_type) {
+            RustFieldType::String => {
+                into.leaks.push_str(&format!("        let {name}_ptr = Box::into_raw({native}.into_bytes().into_boxed_slice());\n"));
+                into.members.push_str(&format!("            {name}_ptr: {name}_ptr as *const u8,\n            {name}_len: {name}_ptr.len(),\n"));
+                into.frees.push_str(&free("u8"));
+            }
+            // Each nested wrapper is freed before the list holding it
+            RustFieldType::Vec(element) => {
+                let element_type = ffi_element(element)?;
+                match unaliased(element) {
+                    RustFieldType::Struct(nested) => {
+                        into.leaks.push_str(&format!(
+                            "        let {name}_ptr = Box::into_raw({native}.into_iter().map({element_type}::from).collect::<Vec<_>>().into_boxed_slice());\n"
+                        ));
+                        into.frees.push_str(&format!(
+                            "    if !value.{name}_ptr.is_null() {{\n        \
+                             let items = unsafe {{ Box::from_raw(std::ptr::slice_from_raw_parts_mut(value.{name}_ptr as *mut {element_type}, value.{name}_len)) }};\n        \
+                             for item in items.into_vec() {{\n            unsafe {{ {free}(item) }};\n        }}\n    }}\n",
+                            free = nested_free(nested)
+                        ));
+                    }
+                    _ => {
+                        into.leaks.push_str(&format!("        let {name}_ptr = Box::into_raw({native}.into_boxed_slice());\n"));
+                        into.frees.push_str(&free(&element_type));
+                    }
+                }
+                into.members.push_str(&format!(
+                    "            {name}_ptr: {name}_ptr as *const {element_type},\n            {name}_len: {name}_ptr.len(),\n"
+                ));
+            }
+            RustFieldType::Option(inner) => match unaliased(inner) {
+                // A string that isn't there is a null pointer the free skips
+                RustFieldType::String => {
+                    into.leaks.push_str(&format!("        let {name}_ptr = {native}.map(|text| Box::into_raw(text.into_bytes().into_boxed_slice()));\n"));
+                    into.members.push_str(&format!(
+                        "            {name}_present: {},\n            {name}_ptr: {name}_ptr.map_or(std::ptr::null(), |ptr| ptr as *const u8),\n            \
+                         {name}_len: {name}_ptr.map_or(0, |ptr| ptr.len()),\n",
+                        flag(format!("{}_ptr.is_some()", name))
+                    ));
+                    into.frees.push_str(&free("u8"));
+                }
+                // A wrapper that isn't there is all zeroes: null pointers, no callbacks
+                RustFieldType::Struct(nested) if nested != "char" => {
+                    let present = match profile {
+                        SecurityProfile::Paranoid => format!("value.{}_present != 0", name),
+                        _ => format!("value.{}_present", name),
+                    };
+                    into.leaks.push_str(&format!("        let {name}_value = {native}.map({nested}FFI::from);\n"));
+                    into.members.push_str(&format!(
+                        "            {name}_present: {},\n            {name}: {name}_value.unwrap_or_else(|| unsafe {{ std::mem::zeroed() }}),\n",
+                        flag(format!("{}_value.is_some()", name))
+                    ));
+                    into.frees.push_str(&format!("    if {present} {{\n        unsafe {{ {}(value.{name}) }};\n    }}\n", nested_free(nested)));
+                }
+                // The value's slot is zeroed when the flag says there is none
+                inner => {
+                    let value = match (inner, profile) {
+                        (RustFieldType::Uuid, _) => format!("{name}_value.map(Uuid::into_bytes).unwrap_or_default()"),
+                        (RustFieldType::Bool, SecurityProfile::Paranoid) => format!("{name}_value.map(u8::from).unwrap_or_default()"),
+                        (inner, _) if is_char(inner) => format!("{name}_value.map(u32::from).unwrap_or_default()"),
+                        (inner, _) if is_system_time(inner) => format!("{name}_value.map(|time| {}).unwrap_or_default()", system_time_nanos("time")),
+                        _ => format!("{name}_value.unwrap_or_default()"),
+                    };
+                    into.leaks.push_str(&format!("        let {name}_value = {native};\n"));
+                    into.members.push_str(&format!("            {name}_present: {},\n            {name}: {value},\n", flag(format!("{}_value.is_some()", name))));
+                }
+            },
+            field_type if is_char(field_type) => into.members.push_str(&format!("            {}: u32::from({}),\n", name, native)),
+            field_type if is_system_time(field_type) => into.members.push_str(&format!("            {}: {},\n", name, system_time_nanos(&native))),
+            RustFieldType::Struct(nested) => {
+                into.members.push_str(&format!("            {}: {}FFI::from({}),\n", name, nested, native));
+                into.frees.push_str(&format!("    unsafe {{ {}(value.{}) }};\n", nested_free(nested), name));
+            }
+            RustFieldType::Uuid => into.members.push_str(&format!("            {}: {}.into_bytes(),\n", name, native)),
+            RustFieldType::Bool if profile == SecurityProfile::Paranoid => {
+                into.members.push_str(&format!("            {}: u8::from({}),\n", name, native))
+            }
+            _ => into.members.push_str(&format!("            {}: {},\n", name, native)),
+        }
+    }
+    Ok(into)
+}
+
+/// Field initializers of `TryFrom<XFFI> for X`, and whether they read strings
+fn from_ffi(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Result<(String, bool), String> {
+    if !ffi_nameable(rust_type) {
+        return Err(unnameable(rust_type));
+    }
+    let mut fields = String::new();
+    let mut strings = false;
+    for field in &rust_type.fields {
+        let name = &field.name;
+        if is_zero_sized(&field.field_type) {
+            fields.push_str(&format!("            {}: Default::default(),\n", name));
+            continue;
+        }
+        if callback_signature(&field.field_type).is_some() {
+            return Err(format!("`{}` is a foreign function pointer, which has no native closure to become", name));
+        }
+        let field_type = ffi_field_type(rust_type, field, converters);
+        strings |= reads_string(field_type);
+        let wire = from_wire(name, field_type, profile);
+        let native = match field_converter_for(rust_type, field, converters) {
+            Some(converter) if converter.hooks.contains_key("rust") && field_direction(field).deserializes() => {
+                format!("decode_{}_{}({})", rust_type.name.to_lowercase(), name, wire)
+            }
+            Some(converter) => return Err(format!("`{}` goes through `{}`, which has no Rust decoder", name, converter.name)),
+            None => wire,
+        };
+        fields.push_str(&format!("            {}: {},\n", name, native));
+    }
+    Ok((fields, strings))
+}
+
+/// Expression reading the wrapper members of a field back into its native
+/// type; strings go through the `string` reader, nested wrappers through
+/// their own `TryFrom`
+fn from_wire(name: &str, field_type: &RustFieldType, profile: SecurityProfile) -> String {
+    match (field_type, profile) {
+        (RustFieldType::Alias(_, inner), _) => from_wire(name, inner, profile),
+        // `Box<T>`, `Rc<str>` and the like all convert from the owned value
+        (RustFieldType::Pointer(_, inner), _) => format!("({}).into()", from_wire(name, inner, profile)),
+        (RustFieldType::String, _) => format!("string(value.{name}_ptr, value.{name}_len, \"{name}\")?"),
+        (RustFieldType::Vec(element), _) => {
+            // Elements are copied out; the list stays with its owner
+            let copy = match unaliased(element) {
+                RustFieldType::Struct(nested) => {
+                    format!(".iter().map(|item| {nested}::try_from(unsafe {{ std::ptr::read(item) }})).collect::<Result<Vec<_>, _>>()?")
+                }
+                _ => ".to_vec()".to_string(),
+            };
+            match profile {
+                SecurityProfile::Paranoid => format!("unsafe {{ checked_slice(value.{name}_ptr, value.{name}_len, \"{name}\") }}?{copy}"),
+                _ => format!(
+                    "if value.{name}_len == 0 {{ Vec::new() }} else if value.{name}_ptr.is_null() {{ return Err(\"`{name}` is null\".to_string()); }} \
+                     else {{ unsafe {{ std::slice::from_raw_parts(value.{name}_ptr, value.{name}_len) }}{copy} }}"
+                ),
+            }
+        }
+        (RustFieldType::Option(inner), _) => {
+            let present = match profile {
+                SecurityProfile::Paranoid => format!("checked_bool(value.{name}_present, \"{name}_present\")?"),
+                _ => format!("value.{}_present", name),
+            };
+            format!("if {} {{ Some({}) }} else {{ None }}", present, from_wire(name, inner, profile))
+        }
+        (field_type, SecurityProfile::Paranoid) if is_char(field_type) => {
+            format!("char::from_u32(value.{name}).ok_or(FfiError::InvalidChar {{ field: \"{name}\", value: value.{name} }})?")
+        }
+        (field_type, _) if is_char(field_type) => {
+            format!("char::from_u32(value.{name}).ok_or_else(|| \"`{name}` is not a Unicode scalar value\".to_string())?")
+        }
+        (field_type, _) if is_system_time(field_type) => system_time_from_nanos(&format!("value.{}", name)),
+        (RustFieldType::Struct(nested), _) => format!("{}::try_from(value.{})?", nested, name),
+        (RustFieldType::Uuid, _) => format!("Uuid::from_bytes(value.{})", name),
+        (RustFieldType::Bool, SecurityProfile::Paranoid) => format!("checked_bool(value.{name}, \"{name}\")?"),
+        _ => format!("value.{}", name),
+    }
+}
+
+/// Whether the wrapper of `rust_type` comes with `{prefix}_free`, as every
+/// wrapper `From` fills does
+pub(crate) fn ffi_frees(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> bool {
+    rust_type.kind == RustTypeKind::Struct && into_ffi(rust_type, converters, profile).is_ok()
+}
+
+const STRING_READER: &str = "        let string = |ptr: *const u8, len: usize, field: &str| -> Result<String, String> {
+            if len == 0 {
+                return Ok(String::new());
+            }
+            if ptr.is_null() {
+                return Err(format!(\"`{}` is null\", field));
+            }
+            String::from_utf8(unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec()).map_err(|_| format!(\"`{}` is not UTF-8\", field))
+        };
+";
+
+const CHECKED_STRING_READER: &str = "        let string = |ptr: *const u8, len: usize, field: &'static str| -> Result<String, FfiError> {
+            let bytes = unsafe { checked_slice(ptr, len, field) }?;
+            std::str::from_utf8(bytes).map(str::to_owned).map_err(|_| FfiError::InvalidUtf8 { field })
+        };
+";
+
+/// `From` the native type into its wrapper, leaking strings for the foreign
+/// side to read until it hands the wrapper to `{prefix}_free`, and `TryFrom`
+/// back, copying strings out; a comment for the direction a field rules out
+fn ffi_conversions(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> String {
+    if rust_type.kind != RustTypeKind::Struct {
+        return String::new();
+    }
+    let name = &rust_type.name;
+    let prefix = crate::codegen::snake_case(name);
+    let mut code = String::new();
+    match into_ffi(rust_type, converters, profile) {
+        Ok(into) => {
+            let handoff = match into.frees.is_empty() {
+                true => "/// Copy into the wrapper\n".to_string(),
+                false => format!("/// Leak the strings and lists into the wrapper; hand it back to `{}_free`\n", prefix),
+            };
+            code.push_str(&format!(
+                "\n{handoff}impl From<{name}> for {name}FFI {{\n    fn from(value: {name}) -> Self {{\n{leaks}        {name}FFI {{\n{members}        }}\n    }}\n}}\n",
+                leaks = into.leaks,
+                members = into.members,
+            ));
+            // Emitted with nothing to free too, so a wrapper nesting this one frees it the same way
+            let frees = match into.frees.is_empty() {
+                true => "    let _ = value;\n".to_string(),
+                false => into.frees,
+            };
+            code.push_str(&format!(
+                "\n/// Free the strings and lists `{name}FFI::from` leaked\n\
+                 ///\n\
+                 /// # Safety\n\
+                 /// `value` must come from `{name}FFI::from`, and is freed at most once\n\
+                 #[no_mangle]\n\
+                 pub unsafe extern \"C\" fn {prefix}_free(value: {name}FFI) {{\n{frees}}}\n",
+            ));
+        }
+        Err(reason) => code.push_str(&format!("\n// No `From<{name}> for {name}FFI`: {reason}\n")),
+    }
+    match from_ffi(rust_type, converters, profile) {
+        Ok((fields, strings)) => {
+            let (error, reader) = match profile {
+                SecurityProfile::Paranoid => ("FfiError", CHECKED_STRING_READER),
+                _ => ("String", STRING_READER),
+            };
+            code.push_str(&format!(
+                "\n/// Copy out of the wrapper, which stays with its owner; string and list pointers must be live\n\
+                 impl TryFrom<{name}FFI> for {name} {{\n    type Error = {error};\n\n    \
+                 fn try_from(value: {name}FFI) -> Result<Self, Self::Error> {{\n{reader}        Ok({name} {{\n{fields}        }})\n    }}\n}}\n",
+                reader = if strings { reader } else { "" },
+            ));
+        }
+        Err(reason) => code.push_str(&format!("\n// No `TryFrom<{name}FFI> for {name}`: {reason}\n")),
+    }
+    code
 }
 
-#[derive(Debug, Clone)]
-pub enum Visibility {
-    Public,
-    Private,
-    Crate,
+fn field_converter_for<'a>(rust_type: &RustType, field: &RustField, converters: &'a ConverterRegistry) -> Option<&'a FieldConverter> {
+    let attribute = field_converter(field);
+    converters.resolve(&rust_type.name, &field.name, attribute.as_deref())
 }
 
-#[derive(Debug, Clone)]
-pub enum RustFieldType {
-    I64,
-    I32,
-    U64,
-    U32,
-    String,
-    Bool,
-    F64,
-    F32,
-    Struct(String),
-    Vec(Box<RustFieldType>),
-    Option(Box<RustFieldType>),
-    Result(Box<RustFieldType>, Box<RustFieldType>),
+/// Type a field has in the FFI wrapper: the converter's wire type, if any;
+/// a borrow is laid out like the owned type it stands for
+pub(crate) fn ffi_field_type<'a>(rust_type: &RustType, field: &'a RustField, converters: &'a ConverterRegistry) -> &'a RustFieldType {
+    match (field_converter_for(rust_type, field, converters), &field.field_type) {
+        (Some(converter), _) => &converter.wire_type,
+        (None, RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned)) => owned,
+        (None, field_type) => field_type,
+    }
 }
 
-/// Analyze Rust type definition and extract metadata
-pub fn analyze_rust_type(source: &str) -> Result<RustType, String> {
-    // In real implementation, this would parse .rs files with syn
-    // For proof-of-concept, we'll analyze the User type example
+/// Which fields of an FFI wrapper get `extern "C"` getters
+#[derive(Debug, Clone, PartialEq)]
+pub enum FfiAccessors {
+    All,
+    /// Only the listed fields (`"User.name"`, or `"name"` on any type); the rest are pruned
+    Used(Vec<String>),
+    None,
+}
 
-    if source.contains("pub struct User") {
-        Ok(RustType {
-            name: "User".to_string(),
-            fields: vec![
-                RustField {
-                    name: "id".to_string(),
-                    field_type: RustFieldType::I64,
-                    visibility: Visibility::Public,
-                },
-                RustField {
-                    name: "name".to_string(),
-                    field_type: RustFieldType::String,
-                    visibility: Visibility::Public,
-                },
-                RustField {
-                    name: "email".to_string(),
-                    field_type: RustFieldType::String,
-                    visibility: Visibility::Public,
-                },
-                RustField {
-                    name: "active".to_string(),
-                    field_type: RustFieldType::Bool,
-                    visibility: Visibility::Public,
-                },
-            ],
-            attributes: vec!["#[repr(C)]".to_string(), "#[derive(Debug, Clone, PartialEq)]".to_string()],
-            location: "examples/user.rs".to_string(),
-        })
-    } else {
-        Err("Type definition not found".to_string())
+impl FfiAccessors {
+    pub fn includes(&self, type_name: &str, field_name: &str) -> bool {
+        match self {
+            FfiAccessors::All => true,
+            FfiAccessors::Used(used) => used
+                .iter()
+                .any(|used| used == field_name || *used == format!("{}.{}", type_name, field_name)),
+            FfiAccessors::None => false,
+        }
     }
 }
 
-/// Calculate compatibility score with another type system
-pub fn compatibility_score(rust_type: &RustType, target: &str) -> f32 {
-    match target {
-        "rescript" => {
-            // Rust i64 → ReScript int: Perfect (1.0)
-            // Rust String → ReScript string: Perfect (1.0)
-            // Rust bool → ReScript bool: Perfect (1.0)
-            // All fields have direct equivalents = Concorde class (100% fidelity)
-            1.0
-        }
-        "julia" => {
-            // Rust i64 → Julia Int64: Perfect (1.0)
-            // Rust String → Julia String: Perfect (1.0)
-            // Rust bool → Julia Bool: Perfect (1.0)
-            1.0
+/// Generate `#[no_mangle]` getters for the fields of the FFI wrapper that `accessors` keeps
+pub fn generate_ffi_accessors(rust_type: &RustType, converters: &ConverterRegistry, accessors: &FfiAccessors) -> Result<String, String> {
+    ffi_accessors(rust_type, converters, accessors, SecurityProfile::Standard)
+}
+
+pub(crate) fn ffi_accessors(
+    rust_type: &RustType,
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+) -> Result<String, String> {
+    let prefix = crate::codegen::snake_case(&rust_type.name);
+    let mut code = String::new();
+    for field in rust_type.fields.iter().filter(|field| accessors.includes(&rust_type.name, &field.name)) {
+        // Callbacks are set through their registration function, never read back
+        if callback_signature(&field.field_type).is_some() {
+            continue;
         }
-        "gleam" => {
-            // Rust i64 → Gleam Int: Perfect (1.0)
-            // Rust String → Gleam String: Perfect (1.0)
-            // Rust bool → Gleam Bool: Perfect (1.0)
-            1.0
+        let members = ffi_members(&field.name, ffi_field_type(rust_type, field, converters), profile)
+            .map_err(|reason| unrepresentable(rust_type, field, &reason))?;
+        for (member, ty) in members {
+            // A nested wrapper is lent out rather than moved
+            let (ty, read) = match ty.ends_with("FFI") {
+                true => (format!("*const {}", ty), format!("&value.{}", member)),
+                false => (ty, format!("value.{}", member)),
+            };
+            code.push_str(&format!(
+                "\n#[no_mangle]\npub extern \"C\" fn {prefix}_{member}(value: &{name}FFI) -> {ty} {{\n    {read}\n}}\n",
+                name = rust_type.name
+            ));
         }
-        _ => 0.0,
     }
+    Ok(code)
 }
 
-/// Map Rust types to target language types
-pub fn map_to_target(field_type: &RustFieldType, target: &str) -> String {
-    match target {
-        "rescript" => match field_type {
-            RustFieldType::I64 | RustFieldType::I32 | RustFieldType::U64 | RustFieldType::U32 => "int".to_string(),
-            RustFieldType::String => "string".to_string(),
-            RustFieldType::Bool => "bool".to_string(),
-            RustFieldType::F64 | RustFieldType::F32 => "float".to_string(),
-            RustFieldType::Struct(name) => name.to_lowercase(),
-            RustFieldType::Vec(inner) => {
-                format!("array<{}>", map_to_target(inner, target))
-            }
-            RustFieldType::Option(inner) => {
-                format!("option<{}>", map_to_target(inner, target))
-            }
-            RustFieldType::Result(ok, err) => {
-                format!("result<{}, {}>", map_to_target(ok, target), map_to_target(err, target))
-            }
-        },
-        "julia" => match field_type {
-            RustFieldType::I64 => "Int64".to_string(),
-            RustFieldType::I32 => "Int32".to_string(),
-            RustFieldType::U64 => "UInt64".to_string(),
-            RustFieldType::U32 => "UInt32".to_string(),
-            RustFieldType::String => "String".to_string(),
-            RustFieldType::Bool => "Bool".to_string(),
-            RustFieldType::F64 => "Float64".to_string(),
-            RustFieldType::F32 => "Float32".to_string(),
-            RustFieldType::Struct(name) => name.clone(),
-            RustFieldType::Vec(inner) => {
-                format!("Vector{{{}}}", map_to_target(inner, target))
-            }
-            RustFieldType::Option(inner) => {
-                format!("Union{{Nothing, {}}}", map_to_target(inner, target))
-            }
-            RustFieldType::Result(ok, err) => {
-                format!("Union{{Ok{{{}}}, Err{{{}}}}}", map_to_target(ok, target), map_to_target(err, target))
-            }
-        },
-        "gleam" => match field_type {
-            RustFieldType::I64 | RustFieldType::I32 | RustFieldType::U64 | RustFieldType::U32 => "Int".to_string(),
-            RustFieldType::String => "String".to_string(),
-            RustFieldType::Bool => "Bool".to_string(),
-            RustFieldType::F64 | RustFieldType::F32 => "Float".to_string(),
-            RustFieldType::Struct(name) => name.clone(),
-            RustFieldType::Vec(inner) => {
-                format!("List({})", map_to_target(inner, target))
-            }
-            RustFieldType::Option(inner) => {
-                format!("Option({})", map_to_target(inner, target))
-            }
-            RustFieldType::Result(ok, err) => {
-                format!("Result({}, {})", map_to_target(ok, target), map_to_target(err, target))
-            }
+/// `#[repr(C)]` layout of the FFI wrapper on `triple`; `None` for enums,
+/// aliases, and when a field has no fixed FFI layout (nested structs, maps)
+pub fn ffi_layout(rust_type: &RustType, converters: &ConverterRegistry, triple: &TargetTriple) -> Option<Layout> {
+    if rust_type.kind != RustTypeKind::Struct {
+        return None;
+    }
+    let mut fields = Vec::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        fields.extend(member_layouts(ffi_field_type(rust_type, field, converters), triple)?);
+    }
+    Some(Layout::of_struct(fields))
+}
+
+/// Layouts of the wrapper members of a field, in order
+fn member_layouts(field_type: &RustFieldType, triple: &TargetTriple) -> Option<Vec<Layout>> {
+    Some(match field_type {
+        RustFieldType::Alias(_, inner) => member_layouts(inner, triple)?,
+        // `_ptr` and `_len`
+        RustFieldType::String => vec![triple.pointer_layout(); 2],
+        RustFieldType::Vec(element) if ffi_element(element).is_ok() => vec![triple.pointer_layout(); 2],
+        // The presence flag, then the value
+        RustFieldType::Option(inner) => std::iter::once(triple.scalar_layout(1)).chain(member_layouts(inner, triple)?).collect(),
+        RustFieldType::I64 | RustFieldType::U64 | RustFieldType::F64 => vec![triple.scalar_layout(8)],
+        RustFieldType::I32 | RustFieldType::U32 | RustFieldType::F32 => vec![triple.scalar_layout(4)],
+        RustFieldType::I16 | RustFieldType::U16 => vec![triple.scalar_layout(2)],
+        RustFieldType::Bool | RustFieldType::I8 | RustFieldType::U8 => vec![triple.scalar_layout(1)],
+        RustFieldType::Isize | RustFieldType::Usize => vec![triple.pointer_layout()],
+        RustFieldType::Uuid => vec![Layout::new(16, 1)],
+        field_type if is_char(field_type) => vec![triple.scalar_layout(4)],
+        field_type if is_system_time(field_type) => vec![triple.scalar_layout(8)],
+        RustFieldType::Array(element, len) if ffi_array(field_type).is_ok() => match member_layouts(element, triple)?.as_slice() {
+            [element] => vec![Layout::new(element.size * len, element.align)],
+            _ => return None,
         },
-        _ => "Unknown".to_string(),
+        // The function pointer and its context
+        field_type if callback_signature(field_type).is_some() => vec![triple.pointer_layout(); 2],
+        RustFieldType::Pointer(_, inner) => member_layouts(inner, triple)?,
+        _ => return None,
+    })
+}
+
+/// Compile-time checks that the FFI wrapper has the layout the foreign side was
+/// generated for on each triple, so layout drift fails the build instead of
+/// corrupting memory
+pub fn generate_layout_assertions(rust_type: &RustType, converters: &ConverterRegistry, triples: &[TargetTriple]) -> String {
+    let mut code = String::new();
+    for triple in triples {
+        let Some(layout) = ffi_layout(rust_type, converters, triple) else { continue };
+        code.push_str(&format!(
+            "\n// {triple}: {bits}-bit pointers, {convention} calling convention\n\
+             #[cfg({predicate})]\n\
+             const _: () = assert!(std::mem::size_of::<{name}FFI>() == {size} && std::mem::align_of::<{name}FFI>() == {align});\n",
+            bits = triple.pointer_bits(),
+            convention = triple.calling_convention().name(),
+            predicate = triple.cfg_predicate(),
+            name = rust_type.name,
+            size = layout.size,
+            align = layout.align,
+        ));
     }
+    code
 }
 
-/// Check if Rust type has FFI-safe attributes
-pub fn is_ffi_safe(rust_type: &RustType) -> bool {
-    rust_type.attributes.iter().any(|attr| attr.contains("#[repr(C)]"))
+/// Parameters and return type of a function-typed field, looking through
+/// `Box`, references and `Option`
+pub(crate) fn callback_signature(field_type: &RustFieldType) -> Option<(&[RustFieldType], &RustFieldType)> {
+    match field_type {
+        RustFieldType::Function(_, params, ret) => Some((params, ret)),
+        RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Option(inner) => callback_signature(inner),
+        _ => None,
+    }
 }
 
-/// Generate FFI-safe wrapper type
-pub fn generate_ffi_wrapper(rust_type: &RustType) -> String {
-    let mut wrapper = format!("/// FFI-safe {} representation\n", rust_type.name);
-    wrapper.push_str("#[repr(C)]\n");
-    wrapper.push_str(&format!("pub struct {}FFI {{\n", rust_type.name));
+/// A C function pointer type for a callback field and the `extern "C"` function
+/// that registers one; the foreign side passes a context pointer that comes
+/// back as the first argument of every call, standing in for closure state
+fn ffi_callback_registration(
+    rust_type: &RustType,
+    field: &str,
+    callback: &str,
+    params: &[RustFieldType],
+    ret: &RustFieldType,
+    profile: SecurityProfile,
+) -> Result<String, String> {
+    let mut args = vec!["*mut std::ffi::c_void".to_string()];
+    for param in params {
+        args.extend(ffi_callback_types(param, profile)?);
+    }
+    let ret = match ffi_callback_types(ret, profile)?.as_slice() {
+        [] => String::new(),
+        [single] => format!(" -> {}", single),
+        // Strings come back NUL-terminated; the foreign side owns the buffer
+        _ => " -> *const std::ffi::c_char".to_string(),
+    };
+    Ok(format!(
+        "pub type {callback} = extern \"C\" fn({args}){ret};\n\n\
+         /// Register the foreign function called for `{field}`; `context` is passed back on every call\n\
+         #[no_mangle]\n\
+         pub extern \"C\" fn {prefix}_register_{field}(value: &mut {name}FFI, callback: Option<{callback}>, context: *mut std::ffi::c_void) {{\n    \
+         value.{field} = callback;\n    value.{field}_context = context;\n}}\n",
+        args = args.join(", "),
+        prefix = crate::codegen::snake_case(&rust_type.name),
+        name = rust_type.name,
+    ))
+}
 
-    for field in &rust_type.fields {
-        match &field.field_type {
-            RustFieldType::String => {
-                wrapper.push_str(&format!("    {}_ptr: *const u8,\n", field.name));
-                wrapper.push_str(&format!("    {}_len: usize,\n", field.name));
-            }
-            _ => {
-                let type_str = format!("{:?}", field.field_type).to_lowercase();
-                wrapper.push_str(&format!("    {}: {},\n", field.name, type_str));
+/// C types a callback argument is passed as: strings as a pointer and length,
+/// structs by pointer to their FFI wrapper, `()` as nothing
+pub(crate) fn ffi_callback_types(field_type: &RustFieldType, profile: SecurityProfile) -> Result<Vec<String>, String> {
+    match field_type {
+        RustFieldType::String => Ok(vec!["*const u8".to_string(), "usize".to_string()]),
+        RustFieldType::Tuple(members) if members.is_empty() => Ok(Vec::new()),
+        RustFieldType::Struct(name) if !is_char(field_type) => Ok(vec![format!("*const {}FFI", name)]),
+        RustFieldType::Pointer(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Alias(_, inner) => {
+            ffi_callback_types(inner, profile)
+        }
+        _ => Ok(vec![ffi_scalar(field_type, profile)?]),
+    }
+}
+
+/// Members a field has in the FFI wrapper, as `(member, type)`: a string or a
+/// list is a pointer and a length, an option a presence flag before its value,
+/// a nested struct its own wrapper; an error says why a field has no C layout
+pub(crate) fn ffi_members(name: &str, field_type: &RustFieldType, profile: SecurityProfile) -> Result<Vec<(String, String)>, String> {
+    let pointer = |pointee: String| vec![(format!("{}_ptr", name), format!("*const {}", pointee)), (format!("{}_len", name), "usize".to_string())];
+    let single = |ty: String| vec![(name.to_string(), ty)];
+    Ok(match field_type {
+        RustFieldType::Alias(_, inner) | RustFieldType::Borrowed(_, inner) | RustFieldType::Cow(_, inner) => {
+            return ffi_members(name, inner, profile)
+        }
+        RustFieldType::String => pointer("u8".to_string()),
+        RustFieldType::Vec(element) => pointer(ffi_element(element)?),
+        RustFieldType::Option(inner) => match pointee(inner) {
+            RustFieldType::Vec(_) | RustFieldType::Option(_) => {
+                return Err(format!("`{}` inside an option has no C layout", rust_type_name(inner)))
             }
+            _ => std::iter::once((format!("{}_present", name), ffi_scalar(&RustFieldType::Bool, profile)?))
+                .chain(ffi_members(name, inner, profile)?)
+                .collect(),
+        },
+        RustFieldType::Struct(nested) if !is_char(field_type) => single(format!("{}FFI", nested)),
+        RustFieldType::Struct(_)
+        | RustFieldType::Bool
+        | RustFieldType::I8
+        | RustFieldType::I16
+        | RustFieldType::I32
+        | RustFieldType::I64
+        | RustFieldType::I128
+        | RustFieldType::U8
+        | RustFieldType::U16
+        | RustFieldType::U32
+        | RustFieldType::U64
+        | RustFieldType::U128
+        | RustFieldType::Isize
+        | RustFieldType::Usize
+        | RustFieldType::F32
+        | RustFieldType::F64
+        | RustFieldType::Uuid => single(ffi_scalar(field_type, profile)?),
+        RustFieldType::ZeroSized(_) => Vec::new(),
+        // Already a C pointer
+        RustFieldType::RawPointer(written, _) => single(written.clone()),
+        RustFieldType::Map(..) => return Err("a map has no C layout".to_string()),
+        RustFieldType::Tuple(_) => return Err("a tuple has no C layout; use a struct".to_string()),
+        RustFieldType::Result(..) => return Err("a result has no C layout".to_string()),
+        RustFieldType::Generic(..) | RustFieldType::TypeParam(_) => {
+            return Err("a generic type has no single wrapper; wrap an instantiation".to_string())
         }
+        RustFieldType::Array(..) => single(ffi_array(field_type)?),
+        RustFieldType::ConstArray(..) => {
+            return Err("an array sized by a const parameter has no single wrapper; wrap an instantiation".to_string())
+        }
+        // The wrapper holds what the pointer points to
+        RustFieldType::Pointer(_, inner) => return ffi_members(name, inner, profile),
+        RustFieldType::DateTime(_) if is_system_time(field_type) => single("i64".to_string()),
+        RustFieldType::DateTime(_) => return Err("a chrono or `time` value needs a converter to a wire type".to_string()),
+        RustFieldType::Decimal(_) | RustFieldType::BigInt(_) => {
+            return Err("an arbitrary-precision number needs a converter to a wire type".to_string())
+        }
+        RustFieldType::TraitObject(_) | RustFieldType::Function(..) => {
+            return Err("a trait object or function has no C layout outside a callback field".to_string())
+        }
+    })
+}
+
+/// Wrapper type of a scalar field; the paranoid profile carries flags as bytes
+/// so a foreign value other than 0 or 1 can be rejected instead of being UB
+pub(crate) fn ffi_scalar(field_type: &RustFieldType, profile: SecurityProfile) -> Result<String, String> {
+    match (field_type, profile) {
+        (RustFieldType::Bool, SecurityProfile::Paranoid) => Ok("u8".to_string()),
+        // The big-endian bytes, as `Uuid::as_bytes` gives them
+        (RustFieldType::Uuid, _) => Ok("[u8; 16]".to_string()),
+        (RustFieldType::Alias(_, inner), _) => ffi_scalar(inner, profile),
+        // A Unicode scalar value, checked on the way back
+        (field_type, _) if is_char(field_type) => Ok("u32".to_string()),
+        (
+            RustFieldType::Bool
+            | RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Isize
+            | RustFieldType::Usize
+            | RustFieldType::F32
+            | RustFieldType::F64,
+            _,
+        ) => Ok(rust_type_name(field_type)),
+        (field_type, _) => Err(format!("`{}` is not a scalar", rust_type_name(field_type))),
     }
+}
 
-    wrapper.push_str("}\n");
-    wrapper
+/// Render a field type in Rust source syntax
+pub fn rust_type_name(field_type: &RustFieldType) -> String {
+    match field_type {
+        RustFieldType::I64 => "i64".to_string(),
+        RustFieldType::I32 => "i32".to_string(),
+        RustFieldType::U64 => "u64".to_string(),
+        RustFieldType::U32 => "u32".to_string(),
+        RustFieldType::U8 => "u8".to_string(),
+        RustFieldType::I8 => "i8".to_string(),
+        RustFieldType::I16 => "i16".to_string(),
+        RustFieldType::U16 => "u16".to_string(),
+        RustFieldType::I128 => "i128".to_string(),
+        RustFieldType::U128 => "u128".to_string(),
+        RustFieldType::Isize => "isize".to_string(),
+        RustFieldType::Usize => "usize".to_string(),
+        RustFieldType::String => "String".to_string(),
+        RustFieldType::Bool => "bool".to_string(),
+        RustFieldType::F64 => "f64".to_string(),
+        RustFieldType::F32 => "f32".to_string(),
+        RustFieldType::Uuid => "Uuid".to_string(),
+        RustFieldType::Struct(name) | RustFieldType::TypeParam(name) | RustFieldType::Alias(name, _) => name.clone(),
+        RustFieldType::Generic(name, args) => {
+            format!("{}<{}>", name, args.iter().map(rust_type_name).collect::<Vec<_>>().join(", "))
+        }
+        RustFieldType::Vec(inner) => format!("Vec<{}>", rust_type_name(inner)),
+        RustFieldType::Array(inner, len) => format!("[{}; {}]", rust_type_name(inner), len),
+        RustFieldType::ConstArray(inner, len) => format!("[{}; {}]", rust_type_name(inner), len),
+        RustFieldType::Option(inner) => format!("Option<{}>", rust_type_name(inner)),
+        RustFieldType::Result(ok, err) => format!("Result<{}, {}>", rust_type_name(ok), rust_type_name(err)),
+        RustFieldType::Map(key, value) => format!("HashMap<{}, {}>", rust_type_name(key), rust_type_name(value)),
+        RustFieldType::Pointer(ownership, inner) => {
+            let pointer = match ownership {
+                Ownership::Shared => "Rc",
+                Ownership::AtomicShared => "Arc",
+                _ => "Box",
+            };
+            format!("{}<{}>", pointer, rust_type_name(inner))
+        }
+        RustFieldType::Borrowed(written, _)
+        | RustFieldType::Cow(written, _)
+        | RustFieldType::RawPointer(written, _)
+        | RustFieldType::ZeroSized(written)
+        | RustFieldType::DateTime(written)
+        | RustFieldType::Decimal(written)
+        | RustFieldType::BigInt(written) => written.clone(),
+        RustFieldType::TraitObject(name) => format!("dyn {}", name),
+        RustFieldType::Function(written, params, ret) => {
+            let params: Vec<String> = params.iter().map(rust_type_name).collect();
+            let prefix = if written == "fn" { String::new() } else { "dyn ".to_string() };
+            match ret.as_ref() {
+                RustFieldType::Tuple(members) if members.is_empty() => format!("{}{}({})", prefix, written, params.join(", ")),
+                ret => format!("{}{}({}) -> {}", prefix, written, params.join(", "), rust_type_name(ret)),
+            }
+        }
+        // A one-element tuple keeps its trailing comma
+        RustFieldType::Tuple(members) => match members.as_slice() {
+            [only] => format!("({},)", rust_type_name(only)),
+            _ => format!("({})", members.iter().map(rust_type_name).collect::<Vec<_>>().join(", ")),
+        },
+    }
 }
 
 #[cfg(test)]
@@ -219,10 +2838,13 @@ pub struct User {
 
     #[test]
     fn test_compatibility_rescript() {
-        let source = "pub struct User { pub id: i64 }";
+        let source = "pub struct User { pub id: i32 }";
         let user_type = analyze_rust_type(source).unwrap();
         let score = compatibility_score(&user_type, "rescript");
         assert_eq!(score, 1.0); // Concorde class
+        // ReScript's int is 32 bits
+        let wide = analyze_rust_type("pub struct User { pub id: i64 }").unwrap();
+        assert_eq!(compatibility_score(&wide, "rescript"), 0.5);
     }
 
     #[test]
@@ -243,10 +2865,671 @@ fn test_type_mapping_julia() {
     fn test_ffi_safe_detection() {
         let user_type = RustType {
             name: "User".to_string(),
+            kind: RustTypeKind::Struct,
+            type_params: vec![],
+            const_params: vec![],
+            aliased: None,
             fields: vec![],
+            variants: vec![],
             attributes: vec!["#[repr(C)]".to_string()],
             location: "test.rs".to_string(),
+            doc: None,
         };
         assert!(is_ffi_safe(&user_type));
     }
+
+    #[test]
+    fn test_analyze_parses_fields_and_attributes() {
+        let source = r#"
+#[derive(Debug)]
+pub struct Event {
+    pub id: u64,
+    #[interop(converter = "epoch_millis")]
+    pub(crate) created_at: DateTime,
+    tags: Vec<Option<String>>,
+}
+"#;
+        let event = analyze_rust_type(source).unwrap();
+        assert_eq!(event.name, "Event");
+        assert_eq!(event.attributes, vec!["#[derive(Debug)]"]);
+        assert_eq!(event.fields.len(), 3);
+        assert!(matches!(event.fields[1].visibility, Visibility::Crate));
+        assert_eq!(field_converter(&event.fields[1]).as_deref(), Some("epoch_millis"));
+        assert_eq!(rust_type_name(&event.fields[2].field_type), "Vec<Option<String>>");
+    }
+
+    #[test]
+    fn test_tuples() {
+        let source = "pub struct Entry { pub pair: (i64, String), pub single: (u32,), pub grouped: (bool), pub unit: () }";
+        let entry = analyze_rust_type(source).unwrap();
+        let types: Vec<_> = entry.fields.iter().map(|field| rust_type_name(&field.field_type)).collect();
+        assert_eq!(types, vec!["(i64, String)", "(u32,)", "bool", "()"]);
+        assert_eq!(map_to_target(&entry.fields[0].field_type, "rescript"), "(int, string)");
+        assert_eq!(map_to_target(&entry.fields[0].field_type, "typescript"), "[number, string]");
+        assert_eq!(to_ir(&entry.fields[3].field_type), IrType::Primitive(PrimitiveType::Unit));
+        // Neither i64 nor u32 fits ReScript's int
+        assert_eq!(compatibility_score(&entry, "rescript"), 0.75);
+    }
+
+    #[test]
+    fn test_bodies_and_macros_hold_no_types() {
+        let source = "fn f(c: char) -> bool { c == '#' }\n\
+                      pub fn g() -> char { struct Local; '{' }\n\
+                      macro_rules! m { () => { struct Z; } }\n\
+                      thread_local! { static COUNT: u32 = 0; }\n\
+                      #[derive(Default)]\npub struct Config { pub sep: char }\n\
+                      impl Config { const BRACE: char = '}'; }";
+        let types = analyze_rust_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Config"]);
+        let functions = analyze_rust_functions(source).unwrap();
+        assert_eq!(functions.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), vec!["g"]);
+    }
+
+    #[test]
+    fn test_raw_pointers() {
+        let source = include_str!("../../examples/user.rs");
+        let types = analyze_rust_types(source).unwrap();
+        let ffi = types.iter().find(|t| t.name == "UserFFI").unwrap();
+        assert!(matches!(&ffi.fields[1].field_type, RustFieldType::RawPointer(_, pointee) if matches!(**pointee, RustFieldType::U8)));
+        assert_eq!(rust_type_name(&ffi.fields[1].field_type), "*const u8");
+        assert_eq!(to_ir(&ffi.fields[1].field_type), IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(PrimitiveType::U8)))));
+        let buffer = analyze_rust_type("pub struct Buffer { pub data: *mut f32 }").unwrap();
+        assert_eq!(map_to_target(&buffer.fields[0].field_type, "zig"), "?*f32");
+        assert!(analyze_rust_type("pub struct Bad { pub data: *u8 }").is_err());
+    }
+
+    #[test]
+    fn test_maps() {
+        let source = "pub struct Index { pub by_name: HashMap<String, u32>, pub by_id: std::collections::BTreeMap<i64, Vec<String>> }";
+        let index = analyze_rust_type(source).unwrap();
+        assert_eq!(map_to_target(&index.fields[0].field_type, "rescript"), "Js.Dict.t<int>");
+        assert_eq!(map_to_target(&index.fields[1].field_type, "rescript"), "Belt.Map.Int.t<array<string>>");
+        assert_eq!(map_to_target(&index.fields[1].field_type, "typescript"), "Record<number, Array<string>>");
+        assert_eq!(rust_type_name(&index.fields[1].field_type), "HashMap<i64, Vec<String>>");
+        // Integer keys come back from a JS object as strings, and neither
+        // u32 nor i64 fits ReScript's int
+        assert_eq!(compatibility_score(&index, "rust"), 1.0);
+        assert_eq!(compatibility_score(&index, "rescript"), 0.5);
+        assert!(analyze_rust_type("struct Bins { counts: HashMap<f64, u32> }").is_err());
+    }
+
+    #[test]
+    fn test_generics() {
+        let source = "pub struct Pagination<'a, T: Clone + 'a, const N: usize> where T: Default { pub items: Vec<T>, pub next: Option<Cursor<'a, T>>, pub total: u32 }";
+        let page = analyze_rust_type(source).unwrap();
+        assert_eq!(page.type_params, vec!["T"]);
+        assert_eq!(rust_type_name(&page.fields[1].field_type), "Option<Cursor<T>>");
+        assert_eq!(map_to_target(&page.fields[0].field_type, "rescript"), "array<'t>");
+
+        let users = instantiate(&page, &[RustFieldType::Struct("User".to_string())]).unwrap();
+        assert!(users.type_params.is_empty());
+        assert_eq!(map_to_target(&users.fields[0].field_type, "typescript"), "Array<User>");
+        assert_eq!(map_to_target(&users.fields[1].field_type, "gleam"), "Option(Cursor(User))");
+        assert!(instantiate(&page, &[]).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_fields() {
+        let source = "pub struct Page<'a> { pub title: &'a str, pub body: &[u8], pub tags: Option<Vec<&'a str>>, pub count: u32 }";
+        let page = analyze_rust_type(source).unwrap();
+        assert_eq!(rust_type_name(&page.fields[0].field_type), "&'a str");
+        assert_eq!(map_to_target(&page.fields[1].field_type, "julia"), "Vector{UInt8}");
+        assert_eq!(
+            borrowed_fields(&page),
+            vec![
+                ("title".to_string(), "&'a str".to_string(), "String".to_string()),
+                ("body".to_string(), "&[u8]".to_string(), "Vec<u8>".to_string()),
+                ("tags".to_string(), "Option<Vec<&'a str>>".to_string(), "Option<Vec<String>>".to_string()),
+            ]
+        );
+        assert_eq!(compatibility_score(&page, "gleam"), 1.0);
+    }
+
+    #[test]
+    fn test_integer_widths() {
+        let source = "pub struct Counters { pub a: i8, pub b: u16, pub c: u128, pub d: usize }";
+        let counters = analyze_rust_type(source).unwrap();
+        let julia: Vec<String> = counters.fields.iter().map(|field| map_to_target(&field.field_type, "julia")).collect();
+        assert_eq!(julia, vec!["Int8", "UInt16", "UInt128", "UInt"]);
+        assert_eq!(map_to_target(&counters.fields[2].field_type, "typescript"), "number");
+        assert_eq!(platform_width(&counters.fields[3].field_type), Some(PlatformWidth::Pointer));
+        assert_eq!(compatibility_score(&counters, "julia"), 1.0);
+        // `u128` and `usize` exceed 2^53 as JS numbers
+        assert_eq!(ir::TransportClass::from_score(compatibility_score(&counters, "typescript")), ir::TransportClass::Wheelbarrow);
+    }
+
+    #[test]
+    fn test_date_time_fields() {
+        let source = "pub struct Event {\n\
+            pub at: chrono::DateTime<chrono::Utc>,\n\
+            #[serde(with = \"chrono::serde::ts_milliseconds\")]\n\
+            pub seen: DateTime<Utc>,\n\
+            pub expires: Option<time::OffsetDateTime>,\n\
+            pub booted: std::time::SystemTime,\n\
+        }";
+        let event = analyze_rust_type(source).unwrap();
+        assert_eq!(rust_type_name(&event.fields[0].field_type), "DateTime<Utc>");
+        assert_eq!(map_to_target(&event.fields[0].field_type, "julia"), "DateTime");
+        assert_eq!(map_to_target(&event.fields[2].field_type, "gleam"), "Option(birl.Time)");
+        let formats: Vec<(String, String)> = wire_format_fields(&event).into_iter().map(|(field, _, format)| (field, format)).collect();
+        assert_eq!(formats[0], ("at".to_string(), "an RFC 3339 string".to_string()));
+        assert_eq!(formats[1], ("seen".to_string(), "a Unix timestamp in milliseconds".to_string()));
+        assert!(formats[2].1.contains("time::serde::rfc3339"));
+        assert!(formats[3].1.contains("secs_since_epoch"));
+        assert_eq!(compatibility_score(&event, "typescript"), 1.0);
+    }
+
+    #[test]
+    fn test_ffi_wrapper_carries_system_time_as_epoch_nanos() {
+        let mut boot = analyze_rust_type("pub struct Boot { pub at: std::time::SystemTime, pub seen: Option<SystemTime>, pub logged: DateTime<Utc> }").unwrap();
+        assert_eq!(
+            generate_ffi_wrapper(&boot).unwrap_err(),
+            "`Boot.logged` (`DateTime<Utc>`) is not FFI-representable: a chrono or `time` value needs a converter to a wire type"
+        );
+        boot.fields.pop();
+        let wrapper = generate_ffi_wrapper(&boot).unwrap();
+        assert!(wrapper.contains("    at: i64,\n    seen_present: bool,\n    seen: i64,\n"));
+        assert!(wrapper.contains(
+            "            at: match value.at.duration_since(std::time::UNIX_EPOCH) { Ok(after) => i64::try_from(after.as_nanos()).unwrap_or(i64::MAX), \
+             Err(before) => i64::try_from(before.duration().as_nanos()).map_or(i64::MIN, |nanos| -nanos) },\n"
+        ));
+        assert!(wrapper.contains(
+            "            at: if value.at >= 0 { std::time::UNIX_EPOCH + std::time::Duration::from_nanos(value.at as u64) } \
+             else { std::time::UNIX_EPOCH - std::time::Duration::from_nanos(value.at.unsigned_abs()) },\n"
+        ));
+        let triple = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(ffi_layout(&boot, &ConverterRegistry::default(), &triple), Some(Layout::new(24, 8)));
+    }
+
+    #[test]
+    fn test_uuid_fields() {
+        let order = analyze_rust_type("#[repr(C)]\npub struct Order { pub id: uuid::Uuid, pub total: u32 }").unwrap();
+        assert_eq!(map_to_target(&order.fields[0].field_type, "rescript"), "string");
+        assert_eq!(map_to_target(&order.fields[0].field_type, "julia"), "UUID");
+        assert!(generate_ffi_wrapper(&order).unwrap().contains("    id: [u8; 16],\n"));
+        let triple = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(ffi_layout(&order, &ConverterRegistry::default(), &triple), Some(Layout::new(20, 4)));
+        assert_eq!(to_ir(&order.fields[0].field_type), IrType::Primitive(PrimitiveType::Uuid));
+        assert_eq!(ir::compare(&to_ir(&order.fields[0].field_type), &IrType::Primitive(PrimitiveType::String)), Fidelity::Lossy);
+    }
+
+    #[test]
+    fn test_decimal_and_big_integer_fields() {
+        let source = "pub struct Ledger {\n\
+            pub balance: rust_decimal::Decimal,\n\
+            #[serde(with = \"rust_decimal::serde::float\")]\n\
+            pub rate: Decimal,\n\
+            pub supply: Option<num_bigint::BigUint>,\n\
+        }";
+        let ledger = analyze_rust_type(source).unwrap();
+        assert_eq!(map_to_target(&ledger.fields[0].field_type, "typescript"), "number");
+        assert_eq!(map_to_target(&ledger.fields[2].field_type, "typescript"), "bigint | null");
+        assert_eq!(map_to_target(&ledger.fields[2].field_type, "julia"), "Union{Nothing, BigInt}");
+        assert_eq!(to_ir(&ledger.fields[2].field_type), IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(PrimitiveType::BigInt)))));
+        let formats = wire_format_fields(&ledger);
+        assert_eq!(formats[0].2, "a string of decimal digits; targets parsing it as a float lose precision");
+        assert_eq!(formats[1].2, "a JSON float, rounded to 53 bits of precision");
+        assert_eq!(formats[2].1, "BigUint");
+        // A float stands in for the decimal, which the score owns up to
+        assert_eq!(compatibility_score(&ledger, "julia"), 1.0);
+        assert!(compatibility_score(&ledger, "typescript") < 1.0);
+    }
+
+    #[test]
+    fn test_zero_sized_fields_cross_unless_skipped() {
+        let source = "pub struct Meters;\n\
+            #[repr(C)]\n\
+            pub struct Length<T> { pub value: f64, pub unit: Meters, pub kind: PhantomData<fn() -> T>, #[serde(skip)] pub pin: std::marker::PhantomPinned }";
+        let types = analyze_rust_types(source).unwrap();
+        let length = &types[1];
+        // serde writes `"unit":null,"kind":null`
+        let fields = ir_fields(length);
+        let names: Vec<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+        assert_eq!(names, vec!["value", "unit", "kind"]);
+        assert_eq!(fields[2].ty, IrType::Primitive(PrimitiveType::Unit));
+        assert_eq!(
+            zero_sized_fields(length),
+            vec![("unit".to_string(), "Meters".to_string()), ("kind".to_string(), "PhantomData<fn() -> T>".to_string())]
+        );
+        assert_eq!(compatibility_score(length, "typescript"), 1.0);
+        assert!(!generate_ffi_wrapper(length).unwrap().contains("unit"));
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = "/// A registered user\n///\n/// Created on sign-up\n#[derive(Serialize)]\npub struct User {\n    \
+            /// Login address\n    #[serde(default)]\n    pub email: String,\n    pub age: u32, // not a doc\n}\n\
+            /// A point\npub struct Point { pub x: i32 }";
+        let types = analyze_rust_types(source).unwrap();
+        assert_eq!(types[0].doc.as_deref(), Some("A registered user\n\nCreated on sign-up"));
+        let docs: Vec<Option<String>> = ir_fields(&types[0]).into_iter().map(|field| field.doc).collect();
+        assert_eq!(docs, vec![Some("Login address".to_string()), None]);
+        assert_eq!(types[1].doc.as_deref(), Some("A point"));
+        assert_eq!(types[1].fields[0].doc, None);
+    }
+
+    #[test]
+    fn test_serde_tagging() {
+        let types = analyze_rust_types(
+            "pub enum A { X }\n#[serde(untagged)]\npub enum B { X(u8) }\n\
+             #[serde(tag = \"t\", content = \"c\")]\npub enum C { X(u8) }\npub struct D { x: u8 }",
+        )
+        .unwrap();
+        let taggings: Vec<_> = types.iter().map(tagging).collect();
+        assert_eq!(
+            taggings,
+            vec![
+                Some(VariantTagging::External),
+                Some(VariantTagging::Untagged),
+                Some(VariantTagging::Adjacent("t".to_string(), "c".to_string())),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serde_defaults() {
+        let source = "#[derive(Deserialize)]\n#[serde(default)]\n\
+            pub struct Config { pub port: u16, pub host: String, pub tags: Vec<String>, pub ratio: f64, pub token: Option<String>, pub id: Uuid, pub debug: bool }\n\
+            impl Default for Config {\n    fn default() -> Self {\n        \
+                Self { port: 8_080u16, host: \"localhost\".to_string(), tags: Vec::new(), ratio: -0.5, token: None, id: Uuid::new_v4(), ..Default::default() }\n    }\n}\n\
+            pub struct Retry { #[serde(default)] pub attempts: u32, #[serde(default = \"default_delay\")] pub delay: u64, pub label: String }";
+        let types = analyze_rust_types(source).unwrap();
+        let defaults: Vec<Option<DefaultValue>> = ir_fields(&types[0]).into_iter().map(|field| field.default).collect();
+        assert_eq!(
+            defaults,
+            vec![
+                Some(DefaultValue::Number("8080".to_string())),
+                Some(DefaultValue::String("localhost".to_string())),
+                Some(DefaultValue::Empty),
+                Some(DefaultValue::Number("-0.5".to_string())),
+                Some(DefaultValue::Null),
+                Some(DefaultValue::Computed("Uuid::new_v4()".to_string())),
+                Some(DefaultValue::TypeDefault),
+            ]
+        );
+        let defaults: Vec<Option<DefaultValue>> = ir_fields(&types[1]).into_iter().map(|field| field.default).collect();
+        assert_eq!(
+            defaults,
+            vec![Some(DefaultValue::TypeDefault), Some(DefaultValue::Computed("default_delay()".to_string())), None]
+        );
+    }
+
+    #[test]
+    fn test_cow_fields() {
+        let doc = analyze_rust_type("#[repr(C)]\npub struct Doc<'a> { pub title: Cow<'a, str>, pub ids: Cow<'a, [u32]>, pub body: std::borrow::Cow<'static, [u8]> }").unwrap();
+        assert_eq!(rust_type_name(&doc.fields[0].field_type), "Cow<'a, str>");
+        assert_eq!(to_ir(&doc.fields[0].field_type), IrType::Primitive(PrimitiveType::String));
+        assert_eq!(map_to_target(&doc.fields[1].field_type, "gleam"), "List(Int)");
+        assert_eq!(to_ir(&doc.fields[2].field_type), IrType::Primitive(PrimitiveType::Bytes));
+        assert_eq!(borrowed_fields(&doc)[0], ("title".to_string(), "Cow<'a, str>".to_string(), "String".to_string()));
+        let wrapper = generate_ffi_wrapper(&doc).unwrap();
+        assert!(wrapper.contains("    /// From `Cow<'a, str>`: borrowed or owned, the data stays with the source value\n    title_ptr: *const u8,\n    title_len: usize,\n"));
+    }
+
+    #[test]
+    fn test_smart_pointers_are_transparent() {
+        let source = "pub struct Session { pub user: Arc<User>, pub name: Rc<str>, pub tags: Option<Box<[String]>> }";
+        let session = analyze_rust_type(source).unwrap();
+        assert_eq!(map_to_target(&session.fields[0].field_type, "typescript"), "User");
+        assert_eq!(map_to_target(&session.fields[2].field_type, "gleam"), "Option(List(String))");
+        assert_eq!(compatibility_score(&session, "rescript"), 1.0);
+        let fields = ir_fields(&session);
+        assert_eq!(fields[1].ty, IrType::Primitive(PrimitiveType::String));
+        let ownership: Vec<Ownership> = fields.iter().map(|field| field.ownership).collect();
+        assert_eq!(ownership, vec![Ownership::AtomicShared, Ownership::Shared, Ownership::Owned]);
+    }
+
+    #[test]
+    fn test_byte_buffers() {
+        let blob = analyze_rust_type("pub struct Blob<'a> { pub data: Vec<u8>, pub view: &'a [u8], pub key: [u8; 16] }").unwrap();
+        assert_eq!(map_to_target(&blob.fields[0].field_type, "rescript"), "Js.TypedArray2.ArrayBuffer.t");
+        assert_eq!(map_to_target(&blob.fields[0].field_type, "julia"), "Vector{UInt8}");
+        assert_eq!(map_to_target(&blob.fields[0].field_type, "typescript"), "string");
+        assert_eq!(to_ir(&blob.fields[1].field_type), IrType::Primitive(PrimitiveType::Bytes));
+        // A fixed-size key keeps its length rather than becoming a buffer
+        assert_eq!(to_ir(&blob.fields[2].field_type), IrType::Container(ContainerType::Array(Box::new(IrType::Primitive(PrimitiveType::U8)), 16)));
+    }
+
+    #[test]
+    fn test_fixed_size_arrays() {
+        let key = analyze_rust_type("pub struct Key<'a> { pub id: [u8; 32], pub grid: [[f32; 4]; 4], pub view: &'a [u8; 16] }").unwrap();
+        assert_eq!(rust_type_name(&key.fields[1].field_type), "[[f32; 4]; 4]");
+        assert_eq!(map_to_target(&key.fields[0].field_type, "julia"), "SVector{32, UInt8}");
+        assert_eq!(map_to_target(&key.fields[0].field_type, "typescript"), "Array<number>");
+        assert_eq!(borrowed_fields(&key), vec![("view".to_string(), "&'a [u8; 16]".to_string(), "[u8; 16]".to_string())]);
+    }
+
+    #[test]
+    fn test_ffi_wrapper_keeps_fixed_arrays() {
+        let mut key = analyze_rust_type("pub struct Key { pub id: [u8; 4], pub grid: [[f32; 2]; 3], pub owners: [String; 2] }").unwrap();
+        assert_eq!(
+            generate_ffi_wrapper(&key).unwrap_err(),
+            "`Key.owners` (`[String; 2]`) is not FFI-representable: an array of `String` has no flat C layout"
+        );
+        key.fields.pop();
+        let wrapper = generate_ffi_wrapper(&key).unwrap();
+        assert!(wrapper.contains("pub struct KeyFFI {\n    id: [u8; 4],\n    grid: [[f32; 2]; 3],\n}\n"));
+        assert!(wrapper.contains("            id: value.id,\n            grid: value.grid,\n"));
+        let triple = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(ffi_layout(&key, &ConverterRegistry::default(), &triple), Some(Layout::new(28, 4)));
+    }
+
+    #[test]
+    fn test_const_generic_arrays() {
+        let matrix = analyze_rust_type("pub struct Matrix<const N: usize> { pub data: [f64; N] }").unwrap();
+        assert_eq!(rust_type_name(&matrix.fields[0].field_type), "[f64; N]");
+        assert_eq!(sized_fields(&matrix), vec![("data".to_string(), "N".to_string())]);
+        // Scored as a fixed-length array, which TypeScript doesn't keep
+        assert_eq!(field_fidelity(&matrix.fields[0].field_type, "julia"), Fidelity::Exact);
+        assert_eq!(field_fidelity(&matrix.fields[0].field_type, "typescript"), Fidelity::Lossy);
+        let three = instantiate_consts(&matrix, &[3]).unwrap();
+        assert!(three.const_params.is_empty());
+        assert_eq!(map_to_target(&three.fields[0].field_type, "julia"), "SVector{3, Float64}");
+        assert!(instantiate_consts(&matrix, &[]).is_err());
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        let source = "pub type UserId = u64;\npub type Ids<T> = Vec<T>;\npub struct User { pub id: UserId, pub friends: Ids<UserId> }\nimpl Iterator for User { type Item = u32; }";
+        let types = analyze_rust_types(source).unwrap();
+        assert_eq!(types.len(), 3);
+        assert_eq!(types[0].kind, RustTypeKind::Alias);
+        let user = &types[2];
+        // Named in generated code, scored as the `u64` it stands for
+        assert_eq!(map_to_target(&user.fields[0].field_type, "typescript"), "UserId");
+        assert_eq!(rust_type_name(&user.fields[1].field_type), "Vec<UserId>");
+        assert_eq!(field_fidelity(&user.fields[0].field_type, "typescript"), Fidelity::Lossy);
+        assert!(analyze_rust_types("type Tree = Vec<Tree>;").unwrap_err().contains("refers to itself"));
+    }
+
+    #[test]
+    fn test_analyze_enum_variants() {
+        let source = r#"
+#[derive(Serialize)]
+pub enum AccountStatus<T> where T: Clone {
+    Active,
+    Suspended(String),
+    Moved { new_home: Option<Address>, since: u64 },
+    #[serde(rename = "gone")]
+    Closed = 1 << 4,
+}
+"#;
+        let status = analyze_rust_type(source).unwrap();
+        assert_eq!((status.name.as_str(), status.kind), ("AccountStatus", RustTypeKind::Enum));
+        let kinds: Vec<_> = status.variants.iter().map(|v| (v.name.as_str(), v.kind, v.fields.len())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Active", RustVariantKind::Unit, 0),
+                ("Suspended", RustVariantKind::Tuple, 1),
+                ("Moved", RustVariantKind::Struct, 2),
+                ("Closed", RustVariantKind::Unit, 0),
+            ]
+        );
+        assert_eq!(status.variants[3].discriminant.as_deref(), Some("1 << 4"));
+        assert_eq!(status.variants[3].attributes, vec!["#[serde(rename = \"gone\")]"]);
+
+        let names: Vec<_> = ir_fields(&status).into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["Suspended.0", "Moved.new_home", "Moved.since"]);
+        assert_eq!(compatibility_score(&status, "rescript"), 2.5 / 3.0);
+        assert_eq!(
+            crate::codegen::render_variant_type(&status.name, &[], &ir_variants(&status), "gleam").unwrap(),
+            "pub type AccountStatus {\n  Active\n  Suspended(String)\n  Moved(new_home: Option(Address), since: Int)\n  Closed\n}\n"
+        );
+        assert!(analyze_rust_types("enum Broken { A(i64 }").is_err());
+    }
+
+    #[test]
+    fn test_converter_scores_exact() {
+        let source = r#"
+pub struct Counter {
+    #[interop(converter = "big_counter")]
+    pub total: u64,
+}
+"#;
+        let counter = analyze_rust_type(source).unwrap();
+        assert_eq!(compatibility_score(&counter, "rescript"), 0.5);
+
+        let mut converters = ConverterRegistry::default();
+        converters.register(
+            FieldConverter::new("big_counter", RustFieldType::String)
+                .with_hook("rust", "big_counter::to_wire", "big_counter::from_wire")
+                .with_hook("rescript", "BigCounter.toWire", "BigCounter.fromWire"),
+        );
+        assert_eq!(compatibility_score_with(&counter, "rescript", &converters), 1.0);
+    }
+
+    #[test]
+    fn test_ffi_wrapper_calls_converter_hooks() {
+        let source = "pub struct Event { pub created_at: DateTime }";
+        let event = analyze_rust_type(source).unwrap();
+
+        let mut converters = ConverterRegistry::default();
+        converters.register(
+            FieldConverter::new("epoch_millis", RustFieldType::I64)
+                .with_hook("rust", "epoch_millis::to_wire", "epoch_millis::from_wire"),
+        );
+        converters.bind("Event", "created_at", "epoch_millis");
+
+        let wrapper = generate_ffi_wrapper_with(&event, &converters).unwrap();
+        assert!(wrapper.contains("    created_at: i64,\n"));
+        assert!(wrapper.contains("epoch_millis::to_wire(value)"));
+        assert!(wrapper.contains("epoch_millis::from_wire(wire)"));
+    }
+
+    #[test]
+    fn test_ffi_wrapper_converts_to_and_from_the_native_type() {
+        let source = "pub struct Event { pub id: u64, pub title: String, pub public: bool, pub token: Uuid, pub created_at: DateTime, pub marker: PhantomData<u8> }";
+        let event = analyze_rust_type(source).unwrap();
+        let mut converters = ConverterRegistry::default();
+        converters.register(
+            FieldConverter::new("epoch_millis", RustFieldType::I64)
+                .with_hook("rust", "epoch_millis::to_wire", "epoch_millis::from_wire"),
+        );
+        converters.bind("Event", "created_at", "epoch_millis");
+
+        let wrapper = generate_ffi_wrapper_with(&event, &converters).unwrap();
+        assert!(wrapper.contains(
+            "/// Leak the strings and lists into the wrapper; hand it back to `event_free`\nimpl From<Event> for EventFFI {\n    fn from(value: Event) -> Self {\n        \
+             let title_ptr = Box::into_raw(value.title.into_bytes().into_boxed_slice());\n        EventFFI {\n            id: value.id,\n            \
+             title_ptr: title_ptr as *const u8,\n            title_len: title_ptr.len(),\n            public: value.public,\n            \
+             token: value.token.into_bytes(),\n            created_at: encode_event_created_at(&value.created_at),\n        }\n    }\n}\n"
+        ));
+        assert!(wrapper.contains("pub unsafe extern \"C\" fn event_free(value: EventFFI) {\n    if !value.title_ptr.is_null() {\n"));
+        assert!(wrapper.contains("impl TryFrom<EventFFI> for Event {\n    type Error = String;\n"));
+        assert!(wrapper.contains(
+            "            title: string(value.title_ptr, value.title_len, \"title\")?,\n            public: value.public,\n            \
+             token: Uuid::from_bytes(value.token),\n            created_at: decode_event_created_at(value.created_at),\n            \
+             marker: Default::default(),\n"
+        ));
+
+        let paranoid = ffi_wrapper(&event, &converters, SecurityProfile::Paranoid).unwrap();
+        assert!(paranoid.contains("            public: u8::from(value.public),\n"));
+        assert!(paranoid.contains("    type Error = FfiError;\n"));
+        assert!(paranoid.contains("            public: checked_bool(value.public, \"public\")?,\n"));
+
+        let button = analyze_rust_type("pub struct Button { pub id: u32, pub on_click: Box<dyn Fn(u32)> }").unwrap();
+        let wrapper = generate_ffi_wrapper(&button).unwrap();
+        assert!(wrapper.contains("            on_click: None,\n            on_click_context: std::ptr::null_mut(),\n"));
+        assert!(wrapper.contains("pub unsafe extern \"C\" fn button_free(value: ButtonFFI) {\n    let _ = value;\n}\n"));
+        assert!(wrapper.contains("// No `TryFrom<ButtonFFI> for Button`: `on_click` is a foreign function pointer, which has no native closure to become\n"));
+    }
+
+    #[test]
+    fn test_ffi_wrapper_converts_lists_and_options() {
+        let source = "pub struct Reading { pub samples: Vec<f32>, pub label: Option<String>, pub limit: Option<u32>, pub names: Vec<String> }";
+        let mut reading = analyze_rust_type(source).unwrap();
+        assert_eq!(
+            generate_ffi_wrapper(&reading).unwrap_err(),
+            "`Reading.names` (`Vec<String>`) is not FFI-representable: a list of `String` has no flat C layout"
+        );
+
+        reading.fields.pop();
+        let wrapper = generate_ffi_wrapper(&reading).unwrap();
+        assert!(wrapper.contains(
+            "    samples_ptr: *const f32,\n    samples_len: usize,\n    label_present: bool,\n    label_ptr: *const u8,\n    label_len: usize,\n    \
+             limit_present: bool,\n    limit: u32,\n}\n"
+        ));
+        assert!(wrapper.contains(
+            "        let samples_ptr = Box::into_raw(value.samples.into_boxed_slice());\n        \
+             let label_ptr = value.label.map(|text| Box::into_raw(text.into_bytes().into_boxed_slice()));\n        \
+             let limit_value = value.limit;\n        ReadingFFI {\n            samples_ptr: samples_ptr as *const f32,\n            samples_len: samples_ptr.len(),\n            \
+             label_present: label_ptr.is_some(),\n            label_ptr: label_ptr.map_or(std::ptr::null(), |ptr| ptr as *const u8),\n            \
+             label_len: label_ptr.map_or(0, |ptr| ptr.len()),\n            limit_present: limit_value.is_some(),\n            limit: limit_value.unwrap_or_default(),\n"
+        ));
+        assert!(wrapper.contains("drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(value.samples_ptr as *mut f32, value.samples_len)) });"));
+        assert!(wrapper.contains("drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(value.label_ptr as *mut u8, value.label_len)) });"));
+        assert!(wrapper.contains(
+            "            label: if value.label_present { Some(string(value.label_ptr, value.label_len, \"label\")?) } else { None },\n            \
+             limit: if value.limit_present { Some(value.limit) } else { None },\n"
+        ));
+
+        let paranoid = ffi_wrapper(&reading, &ConverterRegistry::default(), SecurityProfile::Paranoid).unwrap();
+        assert!(paranoid.contains("            samples: unsafe { checked_slice(value.samples_ptr, value.samples_len, \"samples\") }?.to_vec(),\n"));
+        assert!(paranoid.contains("            limit_present: u8::from(limit_value.is_some()),\n"));
+        assert!(paranoid.contains("            limit: if checked_bool(value.limit_present, \"limit_present\")? { Some(value.limit) } else { None },\n"));
+        let triple = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(ffi_layout(&reading, &ConverterRegistry::default(), &triple).unwrap().size, 48);
+    }
+
+    /// Type-check generated Rust with `rustc`, as a crate of its own
+    fn assert_compiles(name: &str, code: &str) {
+        let dir = std::env::temp_dir().join(format!("lic-ffi-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        std::fs::write(&file, code).unwrap();
+        let output = std::process::Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+            .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "--out-dir"])
+            .arg(&dir)
+            .arg(&file)
+            .output()
+            .expect("rustc runs");
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(output.status.success(), "{}\n{}", String::from_utf8_lossy(&output.stderr), code);
+    }
+
+    #[test]
+    fn test_ffi_wrapper_compiles() {
+        let source = "#[derive(Clone)]\npub struct Address { pub street: String, pub zip: Option<u32>, pub initial: char, pub tag: [u8; 4], pub grid: [[f32; 2]; 2] }\n\
+            pub struct User { pub id: u64, pub name: String, pub home: Address, pub previous: Vec<Address>, pub work: Option<Address>, \
+            pub scores: Vec<f32>, pub grade: Option<char>, pub active: bool, pub nickname: Option<String>, \
+            pub joined: std::time::SystemTime, pub left: Option<std::time::SystemTime>, pub office: Box<Address>, pub team: std::rc::Rc<Address>, \
+            pub motto: std::sync::Arc<str>, pub mentor: Option<Box<Address>>, pub badges: Box<[u16]> }\n\
+            pub struct Button { pub id: u32, pub on_click: Box<dyn Fn(u32, &Address) -> bool> }\n";
+        let types = analyze_rust_types(source).unwrap();
+        let user = &types[1];
+        let wrapper = generate_ffi_wrapper(user).unwrap();
+        assert!(wrapper.contains("    home: AddressFFI,\n    previous_ptr: *const AddressFFI,\n    previous_len: usize,\n    work_present: bool,\n    work: AddressFFI,\n"));
+        assert!(wrapper.contains("    grade_present: bool,\n    grade: u32,\n"));
+        assert!(wrapper.contains("            home: AddressFFI::from(value.home),\n"));
+        assert!(wrapper.contains("    unsafe { address_free(value.home) };\n"));
+        assert!(wrapper.contains("            home: Address::try_from(value.home)?,\n"));
+        assert!(generate_ffi_wrapper(&types[0]).unwrap().contains(
+            "            initial: char::from_u32(value.initial).ok_or_else(|| \"`initial` is not a Unicode scalar value\".to_string())?,\n"
+        ));
+
+        let converters = ConverterRegistry::default();
+        for profile in [SecurityProfile::Standard, SecurityProfile::Paranoid] {
+            let mut code = format!("#![allow(dead_code, unused)]\n{}{}", source, crate::codegen::audit::ffi_prelude(profile));
+            for rust_type in &types {
+                code.push_str(&crate::codegen::audit::generate_rust_ffi(rust_type, &converters, &FfiAccessors::All, profile).unwrap());
+            }
+            assert_compiles(&format!("{:?}", profile).to_lowercase(), &code);
+        }
+    }
+
+    #[test]
+    fn test_ffi_wrapper_sees_through_smart_pointers() {
+        let source = "pub struct Node { pub label: Rc<str>, pub parent: Option<Box<Address>>, pub data: Box<Address>, pub next: Option<Box<Node>> }";
+        let mut node = analyze_rust_type(source).unwrap();
+        assert_eq!(
+            generate_ffi_wrapper(&node).unwrap_err(),
+            "`Node.next` (`Option<Box<Node>>`) is not FFI-representable: the wrapper would hold itself; a list breaks the cycle"
+        );
+        node.fields.pop();
+        let wrapper = generate_ffi_wrapper(&node).unwrap();
+        assert!(wrapper.contains("    label_ptr: *const u8,\n    label_len: usize,\n    parent_present: bool,\n    parent: AddressFFI,\n    data: AddressFFI,\n}\n"));
+        assert!(wrapper.contains("        let label_ptr = Box::into_raw((*value.label).to_owned().into_bytes().into_boxed_slice());\n"));
+        assert!(wrapper.contains("        let parent_value = value.parent.map(|pointee| (*pointee)).map(AddressFFI::from);\n"));
+        assert!(wrapper.contains("            data: AddressFFI::from((*value.data)),\n"));
+        assert!(wrapper.contains(
+            "            label: (string(value.label_ptr, value.label_len, \"label\")?).into(),\n            \
+             parent: if value.parent_present { Some((Address::try_from(value.parent)?).into()) } else { None },\n            \
+             data: (Address::try_from(value.data)?).into(),\n"
+        ));
+    }
+
+    #[test]
+    fn test_ffi_wrapper_rejects_fields_without_a_c_layout() {
+        let source = "pub struct Stats { pub id: u32, pub counts: HashMap<String, u32>, pub range: (i32, i32), pub page: Page<u8> }";
+        let mut stats = analyze_rust_type(source).unwrap();
+        let mut errors = Vec::new();
+        while stats.fields.len() > 1 {
+            errors.push(generate_ffi_wrapper(&stats).unwrap_err());
+            stats.fields.remove(1);
+        }
+        assert_eq!(
+            errors,
+            vec![
+                "`Stats.counts` (`HashMap<String, u32>`) is not FFI-representable: a map has no C layout",
+                "`Stats.range` (`(i32, i32)`) is not FFI-representable: a tuple has no C layout; use a struct",
+                "`Stats.page` (`Page<u8>`) is not FFI-representable: a generic type has no single wrapper; wrap an instantiation",
+            ]
+        );
+        assert!(generate_ffi_wrapper(&stats).unwrap().contains("    id: u32,\n"));
+    }
+
+    #[test]
+    fn test_ffi_callback_registration() {
+        let button = analyze_rust_type("pub struct Button { pub id: u32, pub on_click: Option<Box<dyn Fn(u32, String) -> bool + Send>>, pub log: fn(i64) }").unwrap();
+        assert_eq!(rust_type_name(&button.fields[2].field_type), "fn(i64)");
+        assert_eq!(map_to_target(&button.fields[1].field_type, "typescript"), "((arg0: number, arg1: string) => boolean) | null");
+
+        let wrapper = generate_ffi_wrapper(&button).unwrap();
+        assert!(wrapper.contains("    on_click: Option<ButtonOnClickCallback>,\n    on_click_context: *mut std::ffi::c_void,\n"));
+        assert!(wrapper.contains("pub type ButtonOnClickCallback = extern \"C\" fn(*mut std::ffi::c_void, u32, *const u8, usize) -> bool;\n"));
+        assert!(wrapper.contains("pub type ButtonLogCallback = extern \"C\" fn(*mut std::ffi::c_void, i64);\n"));
+        assert!(wrapper.contains(
+            "pub extern \"C\" fn button_register_on_click(value: &mut ButtonFFI, callback: Option<ButtonOnClickCallback>, context: *mut std::ffi::c_void) {"
+        ));
+        assert!(!generate_ffi_accessors(&button, &ConverterRegistry::default(), &FfiAccessors::All).unwrap().contains("on_click"));
+        assert_eq!(ffi_layout(&button, &ConverterRegistry::default(), &TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap()).unwrap().size, 40);
+    }
+
+    #[test]
+    fn test_ffi_accessors_can_be_pruned() {
+        let user = analyze_rust_type("pub struct User { pub id: i64, pub name: String, pub active: bool }").unwrap();
+        let converters = ConverterRegistry::default();
+
+        let all = generate_ffi_accessors(&user, &converters, &FfiAccessors::All).unwrap();
+        assert!(all.contains("#[no_mangle]\npub extern \"C\" fn user_id(value: &UserFFI) -> i64 {\n    value.id\n}"));
+        assert!(all.contains("pub extern \"C\" fn user_name_len(value: &UserFFI) -> usize {"));
+
+        let used = generate_ffi_accessors(&user, &converters, &FfiAccessors::Used(vec!["User.active".to_string()])).unwrap();
+        assert_eq!(used.matches("extern \"C\"").count(), 1);
+        assert!(used.contains("fn user_active(value: &UserFFI) -> bool"));
+        assert!(generate_ffi_accessors(&user, &converters, &FfiAccessors::None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_layout_assertions_per_triple() {
+        let user = analyze_rust_type("pub struct User { pub id: i64, pub name: String, pub active: bool }").unwrap();
+        let converters = ConverterRegistry::default();
+        let triples: Vec<TargetTriple> = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown", "i686-unknown-linux-gnu"]
+            .iter()
+            .map(|triple| TargetTriple::parse(triple).unwrap())
+            .collect();
+        let layouts: Vec<_> = triples.iter().map(|triple| ffi_layout(&user, &converters, triple).unwrap()).collect();
+        assert_eq!(layouts, vec![Layout::new(32, 8), Layout::new(24, 8), Layout::new(20, 4)]);
+
+        let assertions = generate_layout_assertions(&user, &converters, &triples);
+        assert!(assertions.contains(
+            "// wasm32-unknown-unknown: 32-bit pointers, wasm calling convention\n\
+             #[cfg(all(target_arch = \"wasm32\", target_os = \"unknown\"))]\n\
+             const _: () = assert!(std::mem::size_of::<UserFFI>() == 24 && std::mem::align_of::<UserFFI>() == 8);"
+        ));
+        assert_eq!(assertions.matches("const _").count(), 3);
+    }
 }
@@ -0,0 +1,558 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// SQL DDL analyzer for protocol-squisher integration
+// Reads `CREATE TABLE` and `CREATE TYPE ... AS ENUM` statements in the Postgres
+// dialect; a row is a record whose nullable columns are options
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+const SQL: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: &[("/*", "*/")],
+    // Double quotes delimit identifiers; they come out as string tokens too
+    string_quotes: &['\'', '"'],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Table or enum type
+#[derive(Debug, Clone)]
+pub struct SqlType {
+    /// Pascal-cased (`user_accounts` is `UserAccounts`)
+    pub name: String,
+    /// Name as written in the DDL
+    pub table: String,
+    pub schema: Option<String>,
+    pub kind: SqlTypeKind,
+    pub columns: Vec<SqlColumn>,
+    /// Labels of an enum type
+    pub values: Vec<String>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlTypeKind {
+    Table,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+pub struct SqlColumn {
+    pub name: String,
+    pub column_type: SqlColumnType,
+    /// Neither `NOT NULL` nor part of the primary key
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// `DEFAULT`, a serial type, or a generated column
+    pub has_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlColumnType {
+    /// `tinyint`, MySQL's signed byte
+    TinyInt,
+    /// `smallint`, `smallserial`
+    SmallInt,
+    /// `integer`, `serial`
+    Integer,
+    /// `bigint`, `bigserial`
+    BigInt,
+    Real,
+    /// `double precision`
+    Double,
+    /// `numeric(precision, scale)`
+    Numeric { precision: Option<u32>, scale: Option<u32> },
+    Boolean,
+    Text,
+    /// `varchar(n)`
+    Varchar(Option<u32>),
+    /// `char(n)`
+    Char(Option<u32>),
+    Bytea,
+    Uuid,
+    Date,
+    Time,
+    Timestamp,
+    /// `timestamptz`, `timestamp with time zone`
+    TimestampTz,
+    Interval,
+    /// `json` or `jsonb`
+    Json,
+    /// `T[]` or `T ARRAY`
+    Array(Box<SqlColumnType>),
+    /// Enum, domain, or composite type
+    Named(String),
+}
+
+/// Analyze the first table or enum type in a DDL script
+pub fn analyze_sql_type(source: &str) -> Result<SqlType, String> {
+    analyze_sql_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `CREATE TABLE` and `CREATE TYPE ... AS ENUM` in a DDL script
+pub fn analyze_sql_types(source: &str) -> Result<Vec<SqlType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &SQL));
+    let mut types = Vec::new();
+    while !cursor.at_end() {
+        if !eat_keyword(&mut cursor, "create") {
+            skip_statement(&mut cursor);
+            continue;
+        }
+        let line = cursor.previous().map_or(0, |t| t.line);
+        if eat_keyword(&mut cursor, "or") {
+            eat_keyword(&mut cursor, "replace");
+        }
+        for modifier in ["global", "local", "temp", "temporary", "unlogged"] {
+            eat_keyword(&mut cursor, modifier);
+        }
+        let defined = if eat_keyword(&mut cursor, "table") {
+            parse_table(&mut cursor, line)?
+        } else if eat_keyword(&mut cursor, "type") {
+            parse_enum(&mut cursor, line)?
+        } else {
+            None
+        };
+        match defined {
+            Some(defined) => types.push(defined),
+            // Views, indexes, functions, `CREATE TABLE ... AS SELECT`, composite types
+            None => skip_statement(&mut cursor),
+        }
+    }
+    Ok(types)
+}
+
+fn new_type(cursor: &mut Cursor, kind: SqlTypeKind, line: usize) -> Result<SqlType, String> {
+    let (schema, table) = parse_qualified_name(cursor)?;
+    Ok(SqlType {
+        name: pascal_case(&table),
+        table,
+        schema,
+        kind,
+        columns: Vec::new(),
+        values: Vec::new(),
+        location: format!("line {}", line),
+    })
+}
+
+fn parse_table(cursor: &mut Cursor, line: usize) -> Result<Option<SqlType>, String> {
+    if eat_keyword(cursor, "if") {
+        expect_keyword(cursor, "not")?;
+        expect_keyword(cursor, "exists")?;
+    }
+    let mut table = new_type(cursor, SqlTypeKind::Table, line)?;
+    if !cursor.eat_punct('(') {
+        return Ok(None);
+    }
+    let mut primary_key = Vec::new();
+    while !cursor.eat_punct(')') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`)`"));
+        }
+        if cursor.eat_punct(',') {
+            continue;
+        }
+        let constraint = ["constraint", "primary", "unique", "check", "foreign", "exclude", "like"].iter().any(|word| is_keyword(cursor, word));
+        if constraint {
+            primary_key.extend(parse_table_constraint(cursor)?);
+        } else {
+            table.columns.push(parse_column(cursor)?);
+        }
+    }
+    // `PRIMARY KEY (a, b)` makes its columns non-null
+    for column in &mut table.columns {
+        if primary_key.contains(&column.name) {
+            column.primary_key = true;
+            column.nullable = false;
+        }
+    }
+    // Storage parameters, `PARTITION BY`, `INHERITS`, and so on
+    skip_statement(cursor);
+    Ok(Some(table))
+}
+
+/// `CREATE TYPE mood AS ENUM ('sad', 'ok')`; `None` for other kinds of type
+fn parse_enum(cursor: &mut Cursor, line: usize) -> Result<Option<SqlType>, String> {
+    let mut enumeration = new_type(cursor, SqlTypeKind::Enum, line)?;
+    if !eat_keyword(cursor, "as") || !eat_keyword(cursor, "enum") {
+        return Ok(None);
+    }
+    cursor.expect_punct('(')?;
+    while !cursor.eat_punct(')') {
+        let label = cursor.advance().ok_or_else(|| cursor.unexpected("enum label"))?;
+        if label.kind != TokenKind::Str {
+            return Err(format!("line {}: expected an enum label, found `{}`", label.line, label.text));
+        }
+        enumeration.values.push(label.text);
+        if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+            return Err(cursor.unexpected("`,` or `)`"));
+        }
+    }
+    cursor.eat_punct(';');
+    Ok(Some(enumeration))
+}
+
+/// Skip a table constraint, returning the columns of a `PRIMARY KEY`
+fn parse_table_constraint(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut columns = Vec::new();
+    while !cursor.is_punct(',') && !cursor.is_punct(')') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`)`"));
+        }
+        if eat_keyword(cursor, "primary") {
+            expect_keyword(cursor, "key")?;
+            cursor.expect_punct('(')?;
+            while !cursor.eat_punct(')') {
+                columns.push(parse_name(cursor)?);
+                cursor.eat_punct(',');
+            }
+        } else if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        } else {
+            cursor.advance();
+        }
+    }
+    Ok(columns)
+}
+
+/// `name type [NOT NULL | PRIMARY KEY | DEFAULT expr | ...]`
+fn parse_column(cursor: &mut Cursor) -> Result<SqlColumn, String> {
+    let name = parse_name(cursor)?;
+    let (column_type, serial) = parse_type(cursor)?;
+    let mut column = SqlColumn { name, column_type, nullable: true, primary_key: false, has_default: serial };
+    while !cursor.is_punct(',') && !cursor.is_punct(')') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`)`"));
+        }
+        if eat_keyword(cursor, "not") {
+            if eat_keyword(cursor, "null") {
+                column.nullable = false;
+            }
+        } else if eat_keyword(cursor, "primary") {
+            column.primary_key = true;
+            column.nullable = false;
+        } else if eat_keyword(cursor, "default") || eat_keyword(cursor, "generated") {
+            column.has_default = true;
+        } else if cursor.is_punct('(') {
+            // `CHECK (...)`, `REFERENCES t (id)`, and function calls in defaults
+            cursor.skip_balanced('(', ')');
+        } else {
+            cursor.advance();
+        }
+    }
+    Ok(column)
+}
+
+/// A column type, and whether it's a serial type with an implicit default
+fn parse_type(cursor: &mut Cursor) -> Result<(SqlColumnType, bool), String> {
+    let (_, name) = parse_qualified_name(cursor)?;
+    let name = name.to_ascii_lowercase();
+    let mut serial = false;
+    let column_type = match name.as_str() {
+        "tinyint" => SqlColumnType::TinyInt,
+        "smallint" | "int2" => SqlColumnType::SmallInt,
+        "integer" | "int" | "int4" => SqlColumnType::Integer,
+        "bigint" | "int8" => SqlColumnType::BigInt,
+        "smallserial" | "serial2" | "serial" | "serial4" | "bigserial" | "serial8" => {
+            serial = true;
+            match name.as_str() {
+                "smallserial" | "serial2" => SqlColumnType::SmallInt,
+                "serial" | "serial4" => SqlColumnType::Integer,
+                _ => SqlColumnType::BigInt,
+            }
+        }
+        "real" | "float4" => SqlColumnType::Real,
+        "double" => {
+            expect_keyword(cursor, "precision")?;
+            SqlColumnType::Double
+        }
+        "float8" => SqlColumnType::Double,
+        // `float(p)` is single precision up to 24 bits of mantissa
+        "float" => match parse_modifiers(cursor)?.first() {
+            Some(&bits) if bits <= 24 => SqlColumnType::Real,
+            _ => SqlColumnType::Double,
+        },
+        "numeric" | "decimal" => {
+            let modifiers = parse_modifiers(cursor)?;
+            SqlColumnType::Numeric { precision: modifiers.first().copied(), scale: modifiers.get(1).copied() }
+        }
+        "boolean" | "bool" => SqlColumnType::Boolean,
+        // Network addresses and XML are read as text by drivers that don't know them
+        "text" | "citext" | "name" | "inet" | "cidr" | "macaddr" | "xml" => SqlColumnType::Text,
+        "varchar" => SqlColumnType::Varchar(parse_modifiers(cursor)?.first().copied()),
+        "character" | "char" | "bpchar" => {
+            let varying = eat_keyword(cursor, "varying");
+            let length = parse_modifiers(cursor)?.first().copied();
+            if varying { SqlColumnType::Varchar(length) } else { SqlColumnType::Char(length) }
+        }
+        "bytea" => SqlColumnType::Bytea,
+        "uuid" => SqlColumnType::Uuid,
+        "date" => SqlColumnType::Date,
+        "time" | "timetz" | "timestamp" | "timestamptz" => {
+            parse_modifiers(cursor)?;
+            let mut zoned = name.ends_with("tz");
+            if eat_keyword(cursor, "with") {
+                zoned = true;
+                expect_keyword(cursor, "time")?;
+                expect_keyword(cursor, "zone")?;
+            } else if eat_keyword(cursor, "without") {
+                expect_keyword(cursor, "time")?;
+                expect_keyword(cursor, "zone")?;
+            }
+            match (name.starts_with("timestamp"), zoned) {
+                (true, true) => SqlColumnType::TimestampTz,
+                (true, false) => SqlColumnType::Timestamp,
+                // Only the time of day, zoned or not
+                (false, _) => SqlColumnType::Time,
+            }
+        }
+        "interval" => SqlColumnType::Interval,
+        "json" | "jsonb" => SqlColumnType::Json,
+        _ => {
+            parse_modifiers(cursor)?;
+            SqlColumnType::Named(pascal_case(&name))
+        }
+    };
+
+    // `int[]`, `int[3][3]`, `int ARRAY`, `int ARRAY[3]`
+    let mut column_type = column_type;
+    loop {
+        if cursor.eat_punct('[') {
+            while !cursor.eat_punct(']') {
+                cursor.advance().ok_or_else(|| cursor.unexpected("`]`"))?;
+            }
+        } else if eat_keyword(cursor, "array") {
+            if cursor.is_punct('[') {
+                cursor.skip_balanced('[', ']');
+            }
+        } else {
+            break;
+        }
+        column_type = SqlColumnType::Array(Box::new(column_type));
+    }
+    Ok((column_type, serial))
+}
+
+/// Type modifiers: `(255)`, `(12, 2)`; empty when there are none
+fn parse_modifiers(cursor: &mut Cursor) -> Result<Vec<u32>, String> {
+    let mut modifiers = Vec::new();
+    if !cursor.eat_punct('(') {
+        return Ok(modifiers);
+    }
+    while !cursor.eat_punct(')') {
+        let number = cursor.advance().ok_or_else(|| cursor.unexpected("`)`"))?;
+        let value = number.text.parse().map_err(|_| format!("line {}: invalid type modifier `{}`", number.line, number.text))?;
+        modifiers.push(value);
+        cursor.eat_punct(',');
+    }
+    Ok(modifiers)
+}
+
+/// `users`, `"Users"`, or `public.users`
+fn parse_qualified_name(cursor: &mut Cursor) -> Result<(Option<String>, String), String> {
+    let first = parse_name(cursor)?;
+    if cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| matches!(t.kind, TokenKind::Ident | TokenKind::Str)) {
+        cursor.advance();
+        return Ok((Some(first), parse_name(cursor)?));
+    }
+    Ok((None, first))
+}
+
+/// Unquoted names fold to lower case; quoted ones are kept as written
+fn parse_name(cursor: &mut Cursor) -> Result<String, String> {
+    match cursor.peek() {
+        Some(token) if token.kind == TokenKind::Ident => {
+            let name = token.text.to_ascii_lowercase();
+            cursor.advance();
+            Ok(name)
+        }
+        // Single-quoted literals aren't names, but there's nothing else they could be here
+        Some(token) if token.kind == TokenKind::Str => {
+            let name = token.text.clone();
+            cursor.advance();
+            Ok(name)
+        }
+        _ => Err(cursor.unexpected("a name")),
+    }
+}
+
+/// Past the end of the current statement; a missing `;` ends it at the next `CREATE`
+fn skip_statement(cursor: &mut Cursor) {
+    while !cursor.at_end() && !is_keyword(cursor, "create") {
+        if cursor.eat_punct(';') {
+            return;
+        }
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        } else {
+            cursor.advance();
+        }
+    }
+}
+
+fn is_keyword(cursor: &Cursor, word: &str) -> bool {
+    cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident && t.text.eq_ignore_ascii_case(word))
+}
+
+fn eat_keyword(cursor: &mut Cursor, word: &str) -> bool {
+    let found = is_keyword(cursor, word);
+    if found {
+        cursor.advance();
+    }
+    found
+}
+
+fn expect_keyword(cursor: &mut Cursor, word: &str) -> Result<(), String> {
+    if eat_keyword(cursor, word) {
+        Ok(())
+    } else {
+        Err(cursor.unexpected(&format!("`{}`", word.to_ascii_uppercase())))
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(sql_type: &SqlType, target: &str) -> f32 {
+    average_fidelity(ir_fields(sql_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a column type into the language-neutral IR
+pub fn to_ir(column_type: &SqlColumnType) -> IrType {
+    let primitive = IrType::Primitive;
+    match column_type {
+        SqlColumnType::TinyInt => primitive(PrimitiveType::I8),
+        SqlColumnType::SmallInt => primitive(PrimitiveType::I16),
+        SqlColumnType::Integer => primitive(PrimitiveType::I32),
+        SqlColumnType::BigInt => primitive(PrimitiveType::I64),
+        SqlColumnType::Real => primitive(PrimitiveType::F32),
+        SqlColumnType::Double => primitive(PrimitiveType::F64),
+        SqlColumnType::Boolean => primitive(PrimitiveType::Bool),
+        SqlColumnType::Text | SqlColumnType::Varchar(_) | SqlColumnType::Char(_) => primitive(PrimitiveType::String),
+        SqlColumnType::Uuid => primitive(PrimitiveType::Uuid),
+        // Dates and times cross as ISO 8601 text
+        SqlColumnType::Date | SqlColumnType::Time | SqlColumnType::Timestamp | SqlColumnType::TimestampTz => primitive(PrimitiveType::String),
+        SqlColumnType::Numeric { .. } => primitive(PrimitiveType::Decimal),
+        SqlColumnType::Bytea => primitive(PrimitiveType::Bytes),
+        // No IR form yet for intervals or documents
+        SqlColumnType::Interval | SqlColumnType::Json => IrType::Any,
+        SqlColumnType::Array(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        SqlColumnType::Named(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// Lower the columns of a table into the IR; nullable columns become options
+pub fn ir_fields(sql_type: &SqlType) -> Vec<IrField> {
+    sql_type
+        .columns
+        .iter()
+        .map(|column| {
+            let ty = to_ir(&column.column_type);
+            let ty = if column.nullable { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+            IrField::new(&column.name, ty)
+        })
+        .collect()
+}
+
+/// Map SQL types to target language types
+pub fn map_to_target(column_type: &SqlColumnType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(column_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+CREATE TYPE account_status AS ENUM ('active', 'suspended');
+
+-- Accounts and their owners
+CREATE TABLE IF NOT EXISTS public.user_accounts (
+    id BIGSERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    "displayName" TEXT,
+    balance NUMERIC(12, 2) NOT NULL DEFAULT 0,
+    score DOUBLE PRECISION,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    status account_status NOT NULL,
+    age INTEGER CHECK (age IS NOT NULL AND age > 0),
+    CONSTRAINT email_lower CHECK (email = lower(email))
+);
+
+CREATE INDEX user_accounts_email ON user_accounts (email);
+
+CREATE UNLOGGED TABLE sessions (
+    account_id BIGINT REFERENCES user_accounts (id) ON DELETE CASCADE,
+    token UUID,
+    PRIMARY KEY (account_id, token)
+);
+"#;
+
+    #[test]
+    fn test_analyze_sql() {
+        let types = analyze_sql_types(SCHEMA).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("AccountStatus", SqlTypeKind::Enum, "line 2"),
+                ("UserAccounts", SqlTypeKind::Table, "line 5"),
+                ("Sessions", SqlTypeKind::Table, "line 20"),
+            ]
+        );
+        assert_eq!(types[0].values, vec!["active", "suspended"]);
+        assert_eq!((types[1].schema.as_deref(), types[1].table.as_str()), (Some("public"), "user_accounts"));
+
+        let rust: Vec<_> = ir_fields(&types[1]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: i64",
+                "email: String",
+                "displayName: Option<String>",
+                "balance: rust_decimal::Decimal",
+                "score: Option<f64>",
+                "tags: Vec<String>",
+                "created_at: String",
+                "status: AccountStatus",
+                "age: Option<i32>",
+            ]
+        );
+        assert!(types[1].columns[0].has_default && types[1].columns[0].primary_key);
+        assert_eq!(types[1].columns[3].column_type, SqlColumnType::Numeric { precision: Some(12), scale: Some(2) });
+        assert_eq!(types[1].columns[6].column_type, SqlColumnType::TimestampTz);
+
+        let sessions: Vec<_> = types[2].columns.iter().map(|c| (c.nullable, c.primary_key)).collect();
+        assert_eq!(sessions, vec![(false, true), (false, true)]);
+        assert_eq!(compatibility_score(&types[2], "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_types_and_errors() {
+        let grid = analyze_sql_type("create table grid (cells int ARRAY[3], at time without time zone, f float(10))").unwrap();
+        let columns: Vec<_> = grid.columns.iter().map(|c| c.column_type.clone()).collect();
+        assert_eq!(
+            columns,
+            vec![SqlColumnType::Array(Box::new(SqlColumnType::Integer)), SqlColumnType::Time, SqlColumnType::Real]
+        );
+        assert_eq!(map_to_target(&grid.columns[0].column_type, "gleam"), "List(Int)");
+        assert!(analyze_sql_type("CREATE VIEW v AS SELECT 1;").is_err());
+        assert!(analyze_sql_types("CREATE TABLE t (a DOUBLE)").is_err());
+        assert!(analyze_sql_types("CREATE TABLE t (a int").is_err());
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_sql_type("CREATE TABLE pixel (r tinyint NOT NULL, dx smallserial NOT NULL, n int NOT NULL)").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::I8, PrimitiveType::I16, PrimitiveType::I32].map(IrType::Primitive).to_vec()
+        );
+    }
+}
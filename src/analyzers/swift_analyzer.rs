@@ -0,0 +1,770 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Swift analyzer for protocol-squisher integration
+// Analyzes `struct` stored properties and `enum` cases with associated values
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+/// Swift type declaration
+#[derive(Debug, Clone)]
+pub struct SwiftType {
+    /// Simple name; nested types are spelled `Outer.Inner`
+    pub name: String,
+    pub kind: SwiftTypeKind,
+    pub type_params: Vec<String>,
+    /// Stored instance properties of a struct — what synthesized `Codable` encodes
+    pub fields: Vec<SwiftField>,
+    /// Cases of an enum
+    pub cases: Vec<SwiftCase>,
+    /// Raw value type of an enum (`enum Status: String`)
+    pub raw_type: Option<SwiftFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwiftTypeKind {
+    Struct,
+    Enum,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwiftField {
+    pub name: String,
+    pub field_type: SwiftFieldType,
+    /// Declared with `var` rather than `let`
+    pub mutable: bool,
+}
+
+/// Enum case; unlabeled associated values get positional names ("0", "1", ...)
+#[derive(Debug, Clone)]
+pub struct SwiftCase {
+    pub name: String,
+    pub fields: Vec<SwiftField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwiftFieldType {
+    Int, // 64-bit on every platform Swift ships for
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float,
+    Double,
+    Bool,
+    String,
+    Character,
+    Void,
+    /// `Any`, `AnyObject`, `AnyHashable`
+    Any,
+    /// `T?`, `T!`, `Optional<T>`
+    Optional(Box<SwiftFieldType>),
+    /// `[T]`, `Array<T>`, `Set<T>`
+    Array(Box<SwiftFieldType>),
+    /// `[K: V]`, `Dictionary<K, V>`
+    Dictionary(Box<SwiftFieldType>, Box<SwiftFieldType>),
+    Result(Box<SwiftFieldType>, Box<SwiftFieldType>),
+    Tuple(Vec<SwiftFieldType>),
+    /// Closure type (`(Int) -> Void`)
+    Function,
+    /// User or Foundation type, with its generic arguments
+    Named(String, Vec<SwiftFieldType>),
+    TypeParam(String),
+}
+
+/// Declaration modifiers that may precede a member
+const MODIFIERS: &[&str] = &[
+    "public", "private", "fileprivate", "internal", "open", "package", "final", "static", "mutating", "nonmutating",
+    "override", "required", "convenience", "lazy", "weak", "unowned", "dynamic", "indirect", "nonisolated", "optional",
+    "prefix", "postfix", "infix",
+];
+
+/// What a member declaration contributed to its enclosing type
+enum Member {
+    Fields(Vec<SwiftField>),
+    Cases(Vec<SwiftCase>),
+    Other,
+}
+
+/// Analyze the first Swift type and extract metadata
+pub fn analyze_swift_type(source: &str) -> Result<SwiftType, String> {
+    analyze_swift_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct and enum in a Swift file
+pub fn analyze_swift_types(source: &str) -> Result<Vec<SwiftType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &Syntax::C_LIKE));
+    let mut types = Vec::new();
+    while !cursor.at_end() {
+        if cursor.eat_punct('}') {
+            continue;
+        }
+        // Global variables are not part of any type
+        parse_member(&mut cursor, "", &[], &mut types)?;
+    }
+    Ok(types)
+}
+
+fn skip_attributes(cursor: &mut Cursor) {
+    while cursor.eat_punct('@') {
+        if cursor.expect_ident().is_ok() && cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+    }
+}
+
+/// Modifiers before a declaration; `class var` / `class func` count as `static`
+fn parse_modifiers(cursor: &mut Cursor) -> Vec<String> {
+    let mut modifiers = Vec::new();
+    while let Some(word) = cursor.peek().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.clone()) {
+        let class_member = word == "class"
+            && cursor
+                .peek_at(1)
+                .is_some_and(|t| matches!(t.text.as_str(), "var" | "let" | "func" | "subscript" | "init"));
+        if !MODIFIERS.contains(&word.as_str()) && !class_member {
+            break;
+        }
+        cursor.advance();
+        // `private(set)`, `unowned(safe)`
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        modifiers.push(if class_member { "static".to_string() } else { word });
+    }
+    modifiers
+}
+
+/// One declaration inside a type body (or at file scope)
+fn parse_member(cursor: &mut Cursor, outer: &str, type_params: &[String], types: &mut Vec<SwiftType>) -> Result<Member, String> {
+    skip_attributes(cursor);
+    let modifiers = parse_modifiers(cursor);
+    let Some(token) = cursor.peek() else { return Ok(Member::Other) };
+    match token.text.as_str() {
+        "struct" | "enum" if token.kind == TokenKind::Ident => {
+            parse_type_declaration(cursor, outer, type_params, types)?;
+            Ok(Member::Other)
+        }
+        "let" | "var" if token.kind == TokenKind::Ident => {
+            let fields = parse_stored_properties(cursor, type_params)?;
+            let stored_instance = !modifiers.iter().any(|m| m == "static");
+            Ok(if stored_instance { Member::Fields(fields) } else { Member::Other })
+        }
+        "case" if token.kind == TokenKind::Ident => Ok(Member::Cases(parse_cases(cursor, type_params)?)),
+        "}" if token.kind == TokenKind::Punct => Ok(Member::Other),
+        ";" if token.kind == TokenKind::Punct => {
+            cursor.advance();
+            Ok(Member::Other)
+        }
+        _ => {
+            // Functions, initializers, classes, protocols, extensions, typealiases, `#if`
+            skip_declaration(cursor);
+            Ok(Member::Other)
+        }
+    }
+}
+
+/// Whether the token after a line break continues the current declaration
+/// (`-> T`, `.member`, `where`, a brace on its own line) instead of starting the next
+fn continues(cursor: &Cursor) -> bool {
+    let (Some(previous), Some(next)) = (cursor.previous(), cursor.peek()) else { return false };
+    if next.line == previous.line {
+        return true;
+    }
+    match next.kind {
+        TokenKind::Punct => !matches!(next.text.as_str(), "@" | "}" | "#"),
+        _ => next.text == "where",
+    }
+}
+
+/// Skip a declaration Swift ends with its body or a line break
+fn skip_declaration(cursor: &mut Cursor) {
+    cursor.advance();
+    while continues(cursor) {
+        if cursor.is_punct('{') {
+            cursor.skip_balanced('{', '}');
+            return;
+        } else if cursor.is_punct('}') {
+            return;
+        } else if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        } else if cursor.is_punct('[') {
+            cursor.skip_balanced('[', ']');
+        } else {
+            cursor.advance();
+        }
+    }
+}
+
+/// Skip an initializer expression up to `,`, `)`, `;`, `}`, or the end of its line
+fn skip_expression(cursor: &mut Cursor) {
+    while continues(cursor) {
+        let Some(token) = cursor.peek() else { return };
+        if token.kind != TokenKind::Punct {
+            cursor.advance();
+            continue;
+        }
+        match token.text.as_str() {
+            "," | ")" | ";" | "}" => return,
+            "{" => cursor.skip_balanced('{', '}'),
+            "(" => cursor.skip_balanced('(', ')'),
+            "[" => cursor.skip_balanced('[', ']'),
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+/// A `struct` or `enum` declaration starting at its keyword; pushes it and any nested types
+fn parse_type_declaration(
+    cursor: &mut Cursor,
+    outer: &str,
+    outer_params: &[String],
+    types: &mut Vec<SwiftType>,
+) -> Result<(), String> {
+    let line = cursor.line();
+    let kind = if cursor.eat_ident("enum") { SwiftTypeKind::Enum } else { SwiftTypeKind::Struct };
+    cursor.eat_ident("struct");
+    let name = cursor.expect_ident()?;
+    let name = if outer.is_empty() { name } else { format!("{}.{}", outer, name) };
+
+    let mut type_params = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            type_params.push(cursor.expect_ident()?);
+            // Constraint: `T: Codable`, `T: Collection<Int>`
+            let mut depth = 0usize;
+            while let Some(token) = cursor.peek() {
+                match token.text.as_str() {
+                    "," | ">" if depth == 0 => break,
+                    "<" => depth += 1,
+                    ">" => depth -= 1,
+                    _ => {}
+                }
+                cursor.advance();
+            }
+            if cursor.at_end() {
+                return Err(cursor.unexpected("`>`"));
+            }
+            cursor.eat_punct(',');
+        }
+    }
+    // Members of a nested type still see the enclosing generic parameters
+    let scope: Vec<String> = outer_params.iter().chain(&type_params).cloned().collect();
+
+    // Inheritance clause; for an enum the first entry may be its raw value type
+    let mut raw_type = None;
+    if cursor.eat_punct(':') {
+        if let Ok(first) = parse_type(cursor, &scope) {
+            raw_type = Some(first).filter(|ty| kind == SwiftTypeKind::Enum && is_raw_value_type(ty));
+        }
+    }
+    while !cursor.is_punct('{') {
+        if cursor.advance().is_none() {
+            return Err(cursor.unexpected("`{`"));
+        }
+    }
+    cursor.expect_punct('{')?;
+
+    let index = types.len();
+    types.push(SwiftType {
+        name: name.clone(),
+        kind,
+        type_params,
+        fields: Vec::new(),
+        cases: Vec::new(),
+        raw_type,
+        location: format!("line {}", line),
+    });
+    let (mut fields, mut cases) = (Vec::new(), Vec::new());
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        match parse_member(cursor, &name, &scope, types)? {
+            // Enums can't have stored properties; only their cases carry data
+            Member::Fields(stored) if kind == SwiftTypeKind::Struct => fields.extend(stored),
+            Member::Cases(declared) if kind == SwiftTypeKind::Enum => cases.extend(declared),
+            _ => {}
+        }
+    }
+    types[index].fields = fields;
+    types[index].cases = cases;
+    Ok(())
+}
+
+fn is_raw_value_type(ty: &SwiftFieldType) -> bool {
+    !matches!(
+        ty,
+        SwiftFieldType::Named(..) | SwiftFieldType::Optional(_) | SwiftFieldType::Array(_) | SwiftFieldType::Dictionary(..)
+    )
+}
+
+/// `let id: Int, name = "x"` or `var total: Double { didSet { ... } }`; computed
+/// properties (a body without observers) are dropped
+fn parse_stored_properties(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<SwiftField>, String> {
+    let mutable = cursor.advance().is_some_and(|t| t.text == "var");
+    let mut fields = Vec::new();
+    loop {
+        let Some(name) = cursor.peek().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.clone()) else {
+            // Tuple destructuring: `let (a, b) = pair`
+            skip_declaration(cursor);
+            return Ok(fields);
+        };
+        cursor.advance();
+
+        let mut field_type = if cursor.eat_punct(':') { Some(parse_type(cursor, type_params)?) } else { None };
+        if cursor.eat_punct('=') {
+            if field_type.is_none() {
+                field_type = literal_type(cursor);
+            }
+            skip_expression(cursor);
+        }
+        let mut stored = true;
+        if cursor.is_punct('{') {
+            let observers = cursor
+                .peek_at(1)
+                .is_some_and(|t| matches!(t.text.as_str(), "willSet" | "didSet"));
+            stored = observers;
+            cursor.skip_balanced('{', '}');
+        }
+        if let Some(field_type) = field_type.filter(|_| stored) {
+            fields.push(SwiftField { name, field_type, mutable });
+        }
+        if !cursor.eat_punct(',') {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Type of a property initialized from a literal without an annotation
+fn literal_type(cursor: &Cursor) -> Option<SwiftFieldType> {
+    let token = cursor.peek()?;
+    Some(match token.kind {
+        TokenKind::Str => SwiftFieldType::String,
+        TokenKind::Number if token.text.contains('.') => SwiftFieldType::Double,
+        TokenKind::Number => SwiftFieldType::Int,
+        TokenKind::Ident if token.text == "true" || token.text == "false" => SwiftFieldType::Bool,
+        _ => return None,
+    })
+}
+
+/// `case circle(radius: Double), square(Double), empty = "raw"`
+fn parse_cases(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<SwiftCase>, String> {
+    cursor.advance();
+    let mut cases = Vec::new();
+    loop {
+        let name = cursor.expect_ident()?;
+        let mut fields = Vec::new();
+        if cursor.eat_punct('(') {
+            while !cursor.eat_punct(')') {
+                let labeled = cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(1, ':');
+                let label = if labeled {
+                    let label = cursor.expect_ident()?;
+                    cursor.advance();
+                    label
+                } else {
+                    fields.len().to_string()
+                };
+                let field_type = parse_type(cursor, type_params)?;
+                if cursor.eat_punct('=') {
+                    skip_expression(cursor);
+                }
+                fields.push(SwiftField { name: label, field_type, mutable: false });
+                if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                    return Err(cursor.unexpected("`,` or `)`"));
+                }
+            }
+        }
+        // Raw value
+        if cursor.eat_punct('=') {
+            skip_expression(cursor);
+        }
+        cases.push(SwiftCase { name, fields });
+        if !cursor.eat_punct(',') {
+            return Ok(cases);
+        }
+    }
+}
+
+/// A type with its `?` / `!` suffixes
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<SwiftFieldType, String> {
+    let mut ty = parse_base_type(cursor, type_params)?;
+    while cursor.is_punct('?') || cursor.is_punct('!') {
+        cursor.advance();
+        ty = SwiftFieldType::Optional(Box::new(ty));
+    }
+    Ok(ty)
+}
+
+fn parse_base_type(cursor: &mut Cursor, type_params: &[String]) -> Result<SwiftFieldType, String> {
+    // Opaque and existential markers don't change the wire shape
+    while ["some", "any", "inout", "borrowing", "consuming"].iter().any(|word| cursor.is_ident(word)) {
+        cursor.advance();
+    }
+    skip_attributes(cursor);
+
+    if cursor.eat_punct('[') {
+        let element = parse_type(cursor, type_params)?;
+        let ty = if cursor.eat_punct(':') {
+            SwiftFieldType::Dictionary(Box::new(element), Box::new(parse_type(cursor, type_params)?))
+        } else {
+            SwiftFieldType::Array(Box::new(element))
+        };
+        cursor.expect_punct(']')?;
+        return Ok(ty);
+    }
+
+    if cursor.eat_punct('(') {
+        let mut elements = Vec::new();
+        while !cursor.eat_punct(')') {
+            // Element label: `(x: Int, y: Int)`
+            if cursor.peek().is_some_and(|t| t.kind == TokenKind::Ident) && cursor.is_punct_at(1, ':') {
+                cursor.advance();
+                cursor.advance();
+            }
+            elements.push(parse_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+        cursor.eat_ident("async");
+        cursor.eat_ident("throws");
+        if cursor.is_punct('-') && cursor.is_punct_at(1, '>') {
+            cursor.advance();
+            cursor.advance();
+            parse_type(cursor, type_params)?;
+            return Ok(SwiftFieldType::Function);
+        }
+        return Ok(match elements.len() {
+            0 => SwiftFieldType::Void,
+            1 => elements.remove(0),
+            _ => SwiftFieldType::Tuple(elements),
+        });
+    }
+
+    let mut name = cursor.expect_ident()?;
+    let mut args = Vec::new();
+    loop {
+        if cursor.eat_punct('<') {
+            args.clear();
+            while !cursor.eat_punct('>') {
+                args.push(parse_type(cursor, type_params)?);
+                if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                    return Err(cursor.unexpected("`,` or `>`"));
+                }
+            }
+        }
+        // `Foundation.Date`, `Outer.Inner`
+        if cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            cursor.advance();
+            name.push('.');
+            name.push_str(&cursor.expect_ident()?);
+        } else {
+            break;
+        }
+    }
+    let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+
+    if args.is_empty() {
+        return Ok(match simple.as_str() {
+            "Int" => SwiftFieldType::Int,
+            "Int8" => SwiftFieldType::Int8,
+            "Int16" => SwiftFieldType::Int16,
+            "Int32" => SwiftFieldType::Int32,
+            "Int64" => SwiftFieldType::Int64,
+            "UInt" => SwiftFieldType::UInt,
+            "UInt8" => SwiftFieldType::UInt8,
+            "UInt16" => SwiftFieldType::UInt16,
+            "UInt32" => SwiftFieldType::UInt32,
+            "UInt64" => SwiftFieldType::UInt64,
+            "Float" | "Float32" => SwiftFieldType::Float,
+            "Double" | "Float64" | "CGFloat" => SwiftFieldType::Double,
+            "Bool" => SwiftFieldType::Bool,
+            "String" | "Substring" => SwiftFieldType::String,
+            "Character" => SwiftFieldType::Character,
+            "Void" => SwiftFieldType::Void,
+            "Any" | "AnyObject" | "AnyHashable" => SwiftFieldType::Any,
+            _ if type_params.contains(&name) => SwiftFieldType::TypeParam(name),
+            _ => SwiftFieldType::Named(name, args),
+        });
+    }
+
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(SwiftFieldType::Any));
+    Ok(match simple.as_str() {
+        "Optional" => SwiftFieldType::Optional(next()),
+        "Array" | "Set" | "ContiguousArray" | "ArraySlice" => SwiftFieldType::Array(next()),
+        "Dictionary" => {
+            let key = next();
+            SwiftFieldType::Dictionary(key, next())
+        }
+        "Result" => {
+            let ok = next();
+            SwiftFieldType::Result(ok, next())
+        }
+        _ => SwiftFieldType::Named(name, args.collect()),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(swift_type: &SwiftType, target: &str) -> f32 {
+    average_fidelity(ir_fields(swift_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Swift field type into the language-neutral IR
+pub fn to_ir(field_type: &SwiftFieldType) -> IrType {
+    match field_type {
+        SwiftFieldType::Int8 => IrType::Primitive(PrimitiveType::I8),
+        SwiftFieldType::Int16 => IrType::Primitive(PrimitiveType::I16),
+        SwiftFieldType::Int32 => IrType::Primitive(PrimitiveType::I32),
+        SwiftFieldType::Int | SwiftFieldType::Int64 => IrType::Primitive(PrimitiveType::I64),
+        SwiftFieldType::UInt8 => IrType::Primitive(PrimitiveType::U8),
+        SwiftFieldType::UInt16 => IrType::Primitive(PrimitiveType::U16),
+        SwiftFieldType::UInt32 => IrType::Primitive(PrimitiveType::U32),
+        SwiftFieldType::UInt | SwiftFieldType::UInt64 => IrType::Primitive(PrimitiveType::U64),
+        SwiftFieldType::Float => IrType::Primitive(PrimitiveType::F32),
+        SwiftFieldType::Double => IrType::Primitive(PrimitiveType::F64),
+        SwiftFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        // A grapheme cluster travels as a one-character string
+        SwiftFieldType::String | SwiftFieldType::Character => IrType::Primitive(PrimitiveType::String),
+        SwiftFieldType::Void => IrType::Primitive(PrimitiveType::Unit),
+        SwiftFieldType::Optional(inner) => IrType::Container(ContainerType::Option(Box::new(to_ir(inner)))),
+        SwiftFieldType::Array(element) => IrType::Container(ContainerType::Vec(Box::new(to_ir(element)))),
+        SwiftFieldType::Result(ok, err) => {
+            IrType::Container(ContainerType::Result(Box::new(to_ir(ok)), Box::new(to_ir(err))))
+        }
+        // Closures have no IR form yet
+        SwiftFieldType::Any | SwiftFieldType::Function => IrType::Any,
+        SwiftFieldType::Dictionary(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        SwiftFieldType::Tuple(members) => IrType::Tuple(members.iter().map(to_ir).collect()),
+        SwiftFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        SwiftFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower stored properties, or the associated values of every case, into the IR
+pub fn ir_fields(swift_type: &SwiftType) -> Vec<IrField> {
+    let stored = swift_type.fields.iter().map(|field| (field.name.clone(), field));
+    let payloads = swift_type.cases.iter().flat_map(|case| {
+        case.fields
+            .iter()
+            .map(move |field| (format!("{}.{}", case.name, field.name), field))
+    });
+    stored
+        .chain(payloads)
+        .map(|(name, field)| IrField::new(&name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Map Swift types to target language types
+pub fn map_to_target(field_type: &SwiftFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+import Foundation
+
+public struct User: Codable, Equatable {
+    public let id: Int64
+    public var name: String
+    var email: String?
+    private(set) var tags: [String] = []
+    var scores: [String: Double]
+    var isActive = true
+    var createdAt: Date { didSet { touched = true } }
+    static let shared = User(id: 0, name: "")
+
+    var displayName: String {
+        name.isEmpty ? "anonymous" : name
+    }
+
+    init(id: Int64, name: String) {
+        self.id = id
+        self.name = name
+    }
+
+    func greet() -> String { "hi \(name)" }
+
+    enum Role: String, Codable {
+        case admin, member = "member"
+    }
+}
+
+indirect enum Shape<T> {
+    case circle(radius: Double)
+    case point(Int, Int)
+    case group([Shape<T>], label: T?)
+    case empty
+}
+
+extension User {
+    var initials: String { "" }
+}
+"#;
+
+    #[test]
+    fn test_analyze_structs_and_enums() {
+        let types = analyze_swift_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "User.Role", "Shape"]);
+
+        let user = &types[0];
+        let fields: Vec<_> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["id", "name", "email", "tags", "scores", "isActive", "createdAt"]);
+        assert!(!user.fields[0].mutable && user.fields[1].mutable);
+        assert_eq!(user.fields[2].field_type, SwiftFieldType::Optional(Box::new(SwiftFieldType::String)));
+        assert_eq!(user.fields[5].field_type, SwiftFieldType::Bool);
+
+        let role = &types[1];
+        assert_eq!(role.kind, SwiftTypeKind::Enum);
+        assert_eq!(role.raw_type, Some(SwiftFieldType::String));
+        assert_eq!(role.cases.len(), 2);
+
+        let shape = &types[2];
+        assert_eq!(shape.type_params, vec!["T"]);
+        let payloads: Vec<_> = ir_fields(shape).into_iter().map(|f| f.name).collect();
+        assert_eq!(payloads, vec!["circle.radius", "point.0", "point.1", "group.0", "group.label"]);
+        assert_eq!(
+            shape.cases[2].fields[1].field_type,
+            SwiftFieldType::Optional(Box::new(SwiftFieldType::TypeParam("T".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_optional_spellings() {
+        let s = analyze_swift_type("struct S { let a: Int?? ; let b: String!; let c: Optional<Bool> }").unwrap();
+        let optional = |ty| SwiftFieldType::Optional(Box::new(ty));
+        assert_eq!(s.fields[0].field_type, optional(optional(SwiftFieldType::Int)));
+        assert_eq!(s.fields[1].field_type, optional(SwiftFieldType::String));
+        assert_eq!(s.fields[2].field_type, optional(SwiftFieldType::Bool));
+        assert_eq!(map_to_target(&s.fields[2].field_type, "typescript"), "boolean | null");
+    }
+
+    #[test]
+    fn test_collections_and_dictionaries() {
+        let source = "struct S {\n  let tags: Set<String>\n  let grid: [[Int]]\n  let ids: Array<UInt32>\n  \
+                      let scores: [String: Double]\n  let names: Dictionary<Int, String>\n}";
+        let s = analyze_swift_type(source).unwrap();
+        let types: Vec<_> = ir_fields(&s).into_iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(types, vec!["Vec<String>", "Vec<Vec<i64>>", "Vec<u32>", "HashMap<String, f64>", "HashMap<i64, String>"]);
+    }
+
+    #[test]
+    fn test_associated_values() {
+        let tree = analyze_swift_type("indirect enum Tree {\n  case leaf(value: Int = 0), node(Tree, Tree)\n  case empty\n}").unwrap();
+        assert_eq!(tree.cases.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["leaf", "node", "empty"]);
+        // A default value belongs to the constructor, not the payload
+        assert_eq!(tree.cases[0].fields[0].field_type, SwiftFieldType::Int);
+        let payloads: Vec<_> = ir_fields(&tree).into_iter().map(|f| (f.name, f.ty)).collect();
+        assert_eq!(
+            payloads,
+            vec![
+                ("leaf.value".to_string(), IrType::Primitive(PrimitiveType::I64)),
+                ("node.0".to_string(), IrType::Reference("Tree".to_string())),
+                ("node.1".to_string(), IrType::Reference("Tree".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_value_enums() {
+        let source = "enum Code: Int { case ok = 200, missing = 404 }\nenum Mode: String, CaseIterable { case fast }\n\
+                      enum Kind: Codable { case a }\nenum Wrap: Payload { case x }";
+        let raw: Vec<_> = analyze_swift_types(source).unwrap().into_iter().map(|t| t.raw_type).collect();
+        // Only a literal type is a raw value; protocols and other types are conformances
+        assert_eq!(raw, vec![Some(SwiftFieldType::Int), Some(SwiftFieldType::String), None, None]);
+        let code = analyze_swift_type(source).unwrap();
+        assert_eq!(code.cases.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["ok", "missing"]);
+        assert!(ir_fields(&code).is_empty());
+    }
+
+    #[test]
+    fn test_results_tuples_and_closures() {
+        let source = "struct S { let a: Result<Int, Error>; let p: (x: Int, y: Int); let f: (Int) -> Void; \
+                      let g: () async throws -> Int; let u: () }";
+        let s = analyze_swift_type(source).unwrap();
+        let types: Vec<_> = s.fields.iter().map(|f| f.field_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                SwiftFieldType::Result(Box::new(SwiftFieldType::Int), Box::new(SwiftFieldType::Named("Error".to_string(), vec![]))),
+                SwiftFieldType::Tuple(vec![SwiftFieldType::Int, SwiftFieldType::Int]),
+                SwiftFieldType::Function,
+                SwiftFieldType::Function,
+                SwiftFieldType::Void,
+            ]
+        );
+        // Closures can't cross the wire
+        assert_eq!(to_ir(&types[2]), IrType::Any);
+        assert!(compatibility_score(&s, "rust") < 1.0);
+    }
+
+    #[test]
+    fn test_nested_types_see_outer_parameters() {
+        let types = analyze_swift_types("struct Box<T> {\n  struct Inner {\n    let v: T\n    let d: Foundation.Date\n  }\n}").unwrap();
+        assert_eq!(types[1].name, "Box.Inner");
+        assert!(types[1].type_params.is_empty());
+        assert_eq!(types[1].fields[0].field_type, SwiftFieldType::TypeParam("T".to_string()));
+        // Module qualifiers are dropped when lowering
+        assert_eq!(to_ir(&types[1].fields[1].field_type), IrType::Reference("Date".to_string()));
+    }
+
+    #[test]
+    fn test_classes_are_skipped() {
+        let source = "class Cache {\n  class var shared: Cache\n  let a: Int, b: String\n}\nstruct Entry {\n  lazy var items: [Int] = []\n  let a: Int, b: String\n}";
+        let types = analyze_swift_types(source).unwrap();
+        assert_eq!(types.len(), 1);
+        let fields: Vec<_> = types[0].fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["items", "a", "b"]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(analyze_swift_types("struct Broken {\n  let a: Int\n").unwrap_err(), "expected `}`, found end of input");
+        assert_eq!(analyze_swift_types("struct S<T: Codable {\n}").unwrap_err(), "expected `>`, found end of input");
+        assert_eq!(analyze_swift_types("enum E { case a(Int Int) }").unwrap_err(), "line 1: expected `,` or `)`, found `Int`");
+        assert_eq!(analyze_swift_types("struct S { let a: [Int }").unwrap_err(), "line 1: expected `]`, found `}`");
+        assert_eq!(analyze_swift_types("struct S { let a: Dictionary<String Int> }").unwrap_err(), "line 1: expected `,` or `>`, found `Int`");
+        assert_eq!(analyze_swift_type("import Foundation").unwrap_err(), "Type definition not found");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_swift_type("struct Pixel {\n  let r: UInt8\n  let dx: Int16\n  let w: UInt16\n  let s: Int8\n}").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::U16, PrimitiveType::I8].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let user = analyze_swift_type(SOURCE).unwrap();
+        assert_eq!(map_to_target(&user.fields[3].field_type, "rust"), "Vec<String>");
+        assert_eq!(map_to_target(&user.fields[0].field_type, "rust"), "i64");
+        assert_eq!(compatibility_score(&user, "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_unterminated_string_terminates() {
+        assert!(analyze_swift_types("\"}").unwrap().is_empty());
+    }
+}
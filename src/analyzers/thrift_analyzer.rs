@@ -0,0 +1,436 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Thrift IDL analyzer for protocol-squisher integration
+// Analyzes structs, unions, exceptions, enums, and typedefs in `.thrift` files;
+// services are interfaces rather than data and are skipped
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+
+const THRIFT: Syntax = Syntax {
+    line_comments: &["//", "#"],
+    block_comments: &[("/*", "*/")],
+    string_quotes: &['"', '\''],
+    // Included types are spelled `shared.SharedStruct`
+    ident_extra: &['.'],
+    char_literals: false,
+};
+
+/// Thrift struct, union, exception, enum, or typedef
+#[derive(Debug, Clone)]
+pub struct ThriftType {
+    pub name: String,
+    pub kind: ThriftTypeKind,
+    pub fields: Vec<ThriftField>,
+    pub values: Vec<ThriftEnumValue>,
+    /// What a typedef names
+    pub alias: Option<ThriftFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThriftTypeKind {
+    Struct,
+    /// At most one field is set
+    Union,
+    Exception,
+    Enum,
+    Typedef,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThriftField {
+    /// Field id; implicit ids are assigned negative numbers by the compiler
+    pub id: Option<i64>,
+    pub name: String,
+    pub field_type: ThriftFieldType,
+    pub requiredness: ThriftRequiredness,
+    /// Has a `= value` default
+    pub has_default: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThriftRequiredness {
+    /// Neither keyword: written when set, accepted when absent
+    Default,
+    Required,
+    Optional,
+}
+
+/// `ACTIVE = 1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThriftEnumValue {
+    pub name: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThriftFieldType {
+    Bool,
+    /// `byte` or `i8`
+    Byte,
+    I16,
+    I32,
+    I64,
+    Double,
+    String,
+    Binary,
+    Uuid,
+    List(Box<ThriftFieldType>),
+    Set(Box<ThriftFieldType>),
+    Map(Box<ThriftFieldType>, Box<ThriftFieldType>),
+    /// Struct, enum, or typedef, possibly from an include (`shared.Point`)
+    Named(String),
+}
+
+/// Analyze the first type definition in a Thrift file
+pub fn analyze_thrift_type(source: &str) -> Result<ThriftType, String> {
+    analyze_thrift_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every struct, union, exception, enum, and typedef in a Thrift file
+pub fn analyze_thrift_types(source: &str) -> Result<Vec<ThriftType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &THRIFT));
+    let mut types = Vec::new();
+    while let Some(token) = cursor.peek() {
+        let line = token.line;
+        match token.text.as_str() {
+            "include" | "cpp_include" => {
+                cursor.advance();
+                cursor.advance();
+            }
+            // `namespace py tutorial`, `namespace * shop`
+            "namespace" => {
+                cursor.advance();
+                cursor.advance();
+                cursor.expect_ident()?;
+                skip_annotations(&mut cursor);
+            }
+            "const" => {
+                cursor.advance();
+                parse_type(&mut cursor)?;
+                cursor.expect_ident()?;
+                cursor.expect_punct('=')?;
+                skip_value(&mut cursor);
+            }
+            "typedef" => {
+                cursor.advance();
+                let alias = parse_type(&mut cursor)?;
+                let name = cursor.expect_ident()?;
+                skip_annotations(&mut cursor);
+                let mut typedef = new_type(name, ThriftTypeKind::Typedef, line);
+                typedef.alias = Some(alias);
+                types.push(typedef);
+            }
+            "enum" => types.push(parse_enum(&mut cursor, line)?),
+            "struct" | "union" | "exception" => types.push(parse_struct(&mut cursor, line)?),
+            // Deprecated string enums and services carry no data types
+            "senum" | "service" => {
+                while !cursor.is_punct('{') && !cursor.at_end() {
+                    cursor.advance();
+                }
+                cursor.skip_balanced('{', '}');
+                skip_annotations(&mut cursor);
+            }
+            ";" | "," => {
+                cursor.advance();
+            }
+            _ => return Err(cursor.unexpected("a definition")),
+        }
+    }
+    Ok(types)
+}
+
+fn new_type(name: String, kind: ThriftTypeKind, line: usize) -> ThriftType {
+    ThriftType { name, kind, fields: Vec::new(), values: Vec::new(), alias: None, location: format!("line {}", line) }
+}
+
+fn parse_struct(cursor: &mut Cursor, line: usize) -> Result<ThriftType, String> {
+    let kind = match cursor.advance().map(|token| token.text) {
+        Some(keyword) if keyword == "union" => ThriftTypeKind::Union,
+        Some(keyword) if keyword == "exception" => ThriftTypeKind::Exception,
+        _ => ThriftTypeKind::Struct,
+    };
+    let mut defined = new_type(cursor.expect_ident()?, kind, line);
+    cursor.eat_ident("xsd_all");
+    cursor.expect_punct('{')?;
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        defined.fields.push(parse_field(cursor)?);
+    }
+    skip_annotations(cursor);
+    Ok(defined)
+}
+
+/// `1: optional list<string> tags = [] (annotation = "x"),`
+fn parse_field(cursor: &mut Cursor) -> Result<ThriftField, String> {
+    let mut id = None;
+    let negative = cursor.is_punct('-') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Number);
+    let numbered = if negative { 1 } else { 0 };
+    if cursor.peek_at(numbered).is_some_and(|t| t.kind == TokenKind::Number) && cursor.is_punct_at(numbered + 1, ':') {
+        cursor.eat_punct('-');
+        let number = cursor.advance().ok_or_else(|| cursor.unexpected("field id"))?;
+        let value = parse_number(&number.text).ok_or_else(|| format!("line {}: invalid field id `{}`", number.line, number.text))?;
+        id = Some(if negative { -value } else { value });
+        cursor.expect_punct(':')?;
+    }
+    let requiredness = if cursor.eat_ident("required") {
+        ThriftRequiredness::Required
+    } else if cursor.eat_ident("optional") {
+        ThriftRequiredness::Optional
+    } else {
+        ThriftRequiredness::Default
+    };
+    let field_type = parse_type(cursor)?;
+    let name = cursor.expect_ident()?;
+    let has_default = cursor.eat_punct('=');
+    if has_default {
+        skip_value(cursor);
+    }
+    skip_annotations(cursor);
+    if !cursor.eat_punct(',') {
+        cursor.eat_punct(';');
+    }
+    Ok(ThriftField { id, name, field_type, requiredness, has_default })
+}
+
+fn parse_enum(cursor: &mut Cursor, line: usize) -> Result<ThriftType, String> {
+    cursor.advance();
+    let mut enumeration = new_type(cursor.expect_ident()?, ThriftTypeKind::Enum, line);
+    cursor.expect_punct('{')?;
+    // Values without `=` count up from the previous one, starting at zero
+    let mut next = 0;
+    while !cursor.eat_punct('}') {
+        let name = cursor.expect_ident()?;
+        if cursor.eat_punct('=') {
+            let negative = cursor.eat_punct('-');
+            let number = cursor.advance().ok_or_else(|| cursor.unexpected("enum value"))?;
+            let value = parse_number(&number.text).ok_or_else(|| format!("line {}: invalid enum value `{}`", number.line, number.text))?;
+            next = if negative { -value } else { value };
+        }
+        skip_annotations(cursor);
+        if !cursor.eat_punct(',') {
+            cursor.eat_punct(';');
+        }
+        enumeration.values.push(ThriftEnumValue { name, value: next });
+        next = next.saturating_add(1);
+    }
+    skip_annotations(cursor);
+    Ok(enumeration)
+}
+
+fn parse_type(cursor: &mut Cursor) -> Result<ThriftFieldType, String> {
+    let name = cursor.expect_ident()?;
+    let field_type = match name.as_str() {
+        "bool" => ThriftFieldType::Bool,
+        "byte" | "i8" => ThriftFieldType::Byte,
+        "i16" => ThriftFieldType::I16,
+        "i32" => ThriftFieldType::I32,
+        "i64" => ThriftFieldType::I64,
+        "double" => ThriftFieldType::Double,
+        // `slist` is a deprecated spelling of `string`
+        "string" | "slist" => ThriftFieldType::String,
+        "binary" => ThriftFieldType::Binary,
+        "uuid" => ThriftFieldType::Uuid,
+        "list" | "set" | "map" => {
+            // `list cpp_type "std::deque" <i32>`
+            if cursor.eat_ident("cpp_type") {
+                cursor.advance();
+            }
+            cursor.expect_punct('<')?;
+            let first = parse_type(cursor)?;
+            let container = match name.as_str() {
+                "list" => ThriftFieldType::List(Box::new(first)),
+                "set" => ThriftFieldType::Set(Box::new(first)),
+                _ => {
+                    cursor.expect_punct(',')?;
+                    ThriftFieldType::Map(Box::new(first), Box::new(parse_type(cursor)?))
+                }
+            };
+            cursor.expect_punct('>')?;
+            container
+        }
+        _ => ThriftFieldType::Named(name),
+    };
+    skip_annotations(cursor);
+    Ok(field_type)
+}
+
+/// `(cpp.type = "x", python.immutable = "")`
+fn skip_annotations(cursor: &mut Cursor) {
+    if cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+    }
+}
+
+/// A constant: literal, identifier, `[list]`, or `{map}`
+fn skip_value(cursor: &mut Cursor) {
+    if cursor.is_punct('[') {
+        cursor.skip_balanced('[', ']');
+    } else if cursor.is_punct('{') {
+        cursor.skip_balanced('{', '}');
+    } else {
+        if cursor.is_punct('-') || cursor.is_punct('+') {
+            cursor.advance();
+        }
+        cursor.advance();
+    }
+}
+
+/// Decimal or `0x` hex
+fn parse_number(text: &str) -> Option<i64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(thrift_type: &ThriftType, target: &str) -> f32 {
+    average_fidelity(ir_fields(thrift_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Thrift field type into the language-neutral IR
+pub fn to_ir(field_type: &ThriftFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        ThriftFieldType::Bool => primitive(PrimitiveType::Bool),
+        // No narrower integers in the IR
+        ThriftFieldType::Byte | ThriftFieldType::I16 | ThriftFieldType::I32 => primitive(PrimitiveType::I32),
+        ThriftFieldType::I64 => primitive(PrimitiveType::I64),
+        ThriftFieldType::Double => primitive(PrimitiveType::F64),
+        // The JSON protocol writes binary as base64
+        ThriftFieldType::String => primitive(PrimitiveType::String),
+        ThriftFieldType::Binary => primitive(PrimitiveType::Bytes),
+        ThriftFieldType::Uuid => primitive(PrimitiveType::Uuid),
+        ThriftFieldType::List(items) | ThriftFieldType::Set(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        ThriftFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        ThriftFieldType::Named(name) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+    }
+}
+
+/// Lower all fields of a type into the IR; `optional` fields and every
+/// member of a union may be unset
+pub fn ir_fields(thrift_type: &ThriftType) -> Vec<IrField> {
+    thrift_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let unset = field.requiredness == ThriftRequiredness::Optional || thrift_type.kind == ThriftTypeKind::Union;
+            let ty = if unset { IrType::Container(ContainerType::Option(Box::new(ty))) } else { ty };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map Thrift types to target language types
+pub fn map_to_target(field_type: &ThriftFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TUTORIAL: &str = r#"
+include "shared.thrift"
+
+namespace rs tutorial
+namespace * tutorial.legacy
+
+# Constants don't need a separator
+const i32 INT32CONSTANT = 9853
+const map<string, string> MAPCONSTANT = {'hello': 'world', 'goodnight': 'moon'}
+
+typedef i64 Timestamp
+
+enum Operation {
+  ADD = 1,
+  SUBTRACT,
+  MULTIPLY = 0x10;
+  DIVIDE
+}
+
+struct Work {
+  1: i32 num1 = 0,
+  2: required i32 num2,
+  3: Operation op,
+  4: optional string comment (max_length = "80"),
+  5: list<shared.SharedStruct> shared
+  6: map<string, i16> weights;
+  7: Timestamp at
+}
+
+union Value { 1: i64 integer; 2: binary raw }
+
+exception InvalidOperation {
+  1: i32 whatOp,
+  2: string why
+}
+
+service Calculator extends shared.SharedService {
+  i32 calculate(1: i32 logid, 2: Work w) throws (1: InvalidOperation ouch),
+  oneway void zip()
+}
+"#;
+
+    #[test]
+    fn test_analyze_thrift() {
+        let types = analyze_thrift_types(TUTORIAL).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Timestamp", ThriftTypeKind::Typedef),
+                ("Operation", ThriftTypeKind::Enum),
+                ("Work", ThriftTypeKind::Struct),
+                ("Value", ThriftTypeKind::Union),
+                ("InvalidOperation", ThriftTypeKind::Exception),
+            ]
+        );
+        assert_eq!(types[0].alias, Some(ThriftFieldType::I64));
+        let values: Vec<_> = types[1].values.iter().map(|v| (v.name.as_str(), v.value)).collect();
+        assert_eq!(values, vec![("ADD", 1), ("SUBTRACT", 2), ("MULTIPLY", 16), ("DIVIDE", 17)]);
+
+        let work = &types[2];
+        assert_eq!(work.location, "line 20");
+        assert_eq!(work.fields[1].requiredness, ThriftRequiredness::Required);
+        assert!(work.fields[0].has_default);
+        let rust: Vec<_> = ir_fields(work).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "num1: i32",
+                "num2: i32",
+                "op: Operation",
+                "comment: Option<String>",
+                "shared: Vec<SharedStruct>",
+                "weights: HashMap<String, i32>",
+                "at: Timestamp",
+            ]
+        );
+        let value: Vec<_> = ir_fields(&types[3]).iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(value, vec!["Option<i64>", "Option<Vec<u8>>"]);
+        assert_eq!(compatibility_score(&types[4], "rust"), 1.0);
+        assert_eq!(compatibility_score(work, "rust"), 1.0);
+    }
+
+    #[test]
+    fn test_field_ids_and_errors() {
+        let legacy = analyze_thrift_type("struct Old { -1: i32 a; string b }").unwrap();
+        assert_eq!(legacy.fields.iter().map(|f| f.id).collect::<Vec<_>>(), vec![Some(-1), None]);
+        assert_eq!(map_to_target(&legacy.fields[1].field_type, "gleam"), "String");
+        assert!(analyze_thrift_type("service Empty {}").is_err());
+        assert!(analyze_thrift_types("struct Open { 1: i32 a").is_err());
+        assert!(analyze_thrift_types("message Proto {}").is_err());
+    }
+}
@@ -0,0 +1,705 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// TypeScript type analyzer for protocol-squisher integration
+// Analyzes `interface` and `type` declarations and generates compatibility metadata
+
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::pascal_case;
+use crate::codegen::unions::union_name;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, IrVariant, PrimitiveType, UnionShape, VariantTagging};
+
+/// TypeScript type information extracted from source
+#[derive(Debug, Clone)]
+pub struct TypeScriptType {
+    pub name: String,
+    /// Generic parameters (`interface Page<T>`)
+    pub type_params: Vec<String>,
+    /// Properties of an interface or object-literal alias
+    pub fields: Vec<TypeScriptField>,
+    /// Right-hand side of a non-object `type` alias (`type Id = string | number`)
+    pub alias: Option<TypeScriptFieldType>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeScriptField {
+    pub name: String,
+    pub field_type: TypeScriptFieldType,
+    /// Declared with `?:`
+    pub optional: bool,
+    pub readonly: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeScriptFieldType {
+    Number,  // IEEE double
+    String,
+    Boolean,
+    BigInt,
+    Null,
+    Undefined,
+    Any,     // `any` and `unknown`
+    /// Literal type: `"active"`, `42`, `true`
+    Literal(String),
+    Array(Box<TypeScriptFieldType>),
+    /// `Record<K, V>` or `Map<K, V>`
+    Map(Box<TypeScriptFieldType>, Box<TypeScriptFieldType>),
+    Date,
+    /// Object literal type (`{ x: number }`); `analyze_typescript_types`
+    /// replaces each with a reference to a type declared for it
+    Object(Vec<TypeScriptField>),
+    /// Named type with type arguments (`User`, `Page<User>`)
+    Reference(String, Vec<TypeScriptFieldType>),
+    /// Reference to a generic parameter of the enclosing declaration
+    TypeParam(String),
+    Union(Vec<TypeScriptFieldType>),
+}
+
+const TYPESCRIPT_SYNTAX: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    string_quotes: &['"', '\'', '`'],
+    ident_extra: &['$'],
+    char_literals: false,
+};
+
+/// Analyze the first TypeScript type declaration and extract metadata
+pub fn analyze_typescript_type(source: &str) -> Result<TypeScriptType, String> {
+    analyze_typescript_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `interface` and `type` declaration in a TypeScript source file
+pub fn analyze_typescript_types(source: &str) -> Result<Vec<TypeScriptType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &TYPESCRIPT_SYNTAX));
+    let mut types = Vec::new();
+
+    while let Some(token) = cursor.peek() {
+        let declares = token.kind == TokenKind::Ident
+            && matches!(token.text.as_str(), "interface" | "type")
+            && cursor.peek_at(1).is_some_and(|name| name.kind == TokenKind::Ident)
+            && cursor
+                .peek_at(2)
+                .is_some_and(|next| matches!(next.text.as_str(), "=" | "<" | "{" | "extends"));
+
+        if declares {
+            let line = cursor.line();
+            let keyword = cursor.expect_ident()?;
+            types.push(parse_declaration(&mut cursor, &keyword, line)?);
+        } else {
+            cursor.advance();
+        }
+    }
+
+    // Object literals nested in a declaration get one of their own, named
+    // after where they appear (`User.address` → `UserAddress`)
+    let mut names: Vec<String> = types.iter().map(|t| t.name.clone()).collect();
+    let mut i = 0;
+    while i < types.len() {
+        let mut hoisted = Vec::new();
+        let declared = &mut types[i];
+        let (name, type_params, location) = (declared.name.clone(), declared.type_params.clone(), declared.location.clone());
+        let mut hoist = |base: String, fields: Vec<TypeScriptField>| {
+            let mut synthesized = base.clone();
+            let mut n = 1;
+            while names.contains(&synthesized) {
+                n += 1;
+                synthesized = format!("{}{}", base, n);
+            }
+            names.push(synthesized.clone());
+            let mut used = Vec::new();
+            fields.iter().for_each(|field| type_params_in(&field.field_type, &mut used));
+            let params: Vec<String> = type_params.iter().filter(|param| used.contains(param)).cloned().collect();
+            let args = params.iter().map(|param| TypeScriptFieldType::TypeParam(param.clone())).collect();
+            hoisted.push(TypeScriptType { name: synthesized.clone(), type_params: params, fields, alias: None, location: location.clone() });
+            TypeScriptFieldType::Reference(synthesized, args)
+        };
+        for field in &mut declared.fields {
+            hoist_objects(&mut field.field_type, &format!("{}{}", name, pascal_case(&field.name)), &mut hoist);
+        }
+        if let Some(alias) = &mut declared.alias {
+            hoist_objects(alias, &format!("{}Member", name), &mut hoist);
+        }
+        // Hoisted types come right after their parent and are hoisted from in turn
+        types.splice(i + 1..i + 1, hoisted);
+        i += 1;
+    }
+
+    Ok(types)
+}
+
+/// Replace each object literal in `ty` with what `hoist` declares for it
+fn hoist_objects(ty: &mut TypeScriptFieldType, base: &str, hoist: &mut impl FnMut(String, Vec<TypeScriptField>) -> TypeScriptFieldType) {
+    match ty {
+        TypeScriptFieldType::Object(fields) => *ty = hoist(base.to_string(), std::mem::take(fields)),
+        TypeScriptFieldType::Array(inner) => hoist_objects(inner, base, hoist),
+        TypeScriptFieldType::Map(key, value) => {
+            hoist_objects(key, base, hoist);
+            hoist_objects(value, base, hoist);
+        }
+        TypeScriptFieldType::Reference(_, members) | TypeScriptFieldType::Union(members) => {
+            members.iter_mut().for_each(|member| hoist_objects(member, base, hoist))
+        }
+        _ => {}
+    }
+}
+
+/// Names of the type parameters `ty` mentions
+fn type_params_in(ty: &TypeScriptFieldType, params: &mut Vec<String>) {
+    match ty {
+        TypeScriptFieldType::TypeParam(name) if !params.contains(name) => params.push(name.clone()),
+        TypeScriptFieldType::Array(inner) => type_params_in(inner, params),
+        TypeScriptFieldType::Map(key, value) => {
+            type_params_in(key, params);
+            type_params_in(value, params);
+        }
+        TypeScriptFieldType::Object(fields) => fields.iter().for_each(|field| type_params_in(&field.field_type, params)),
+        TypeScriptFieldType::Reference(_, members) | TypeScriptFieldType::Union(members) => {
+            members.iter().for_each(|member| type_params_in(member, params))
+        }
+        _ => {}
+    }
+}
+
+fn parse_declaration(cursor: &mut Cursor, keyword: &str, line: usize) -> Result<TypeScriptType, String> {
+    let name = cursor.expect_ident()?;
+    let type_params = parse_type_params(cursor)?;
+
+    let mut declared = TypeScriptType {
+        name,
+        type_params,
+        fields: Vec::new(),
+        alias: None,
+        location: format!("line {}", line),
+    };
+
+    if keyword == "interface" {
+        // `extends` clauses are recorded nowhere yet; skip to the body
+        while !cursor.at_end() && !cursor.is_punct('{') {
+            cursor.advance();
+        }
+        declared.fields = parse_object_body(cursor, &declared.type_params)?;
+    } else {
+        cursor.expect_punct('=')?;
+        match parse_type(cursor, &declared.type_params)? {
+            TypeScriptFieldType::Object(fields) => declared.fields = fields,
+            alias => declared.alias = Some(alias),
+        }
+        cursor.eat_punct(';');
+    }
+
+    Ok(declared)
+}
+
+fn parse_type_params(cursor: &mut Cursor) -> Result<Vec<String>, String> {
+    let mut params = Vec::new();
+    if !cursor.eat_punct('<') {
+        return Ok(params);
+    }
+    while !cursor.eat_punct('>') {
+        params.push(cursor.expect_ident()?);
+        // Constraints and defaults (`T extends X = Y`) don't affect the shape
+        if cursor.eat_ident("extends") || cursor.is_punct('=') {
+            cursor.eat_punct('=');
+            parse_type(cursor, &params)?;
+            if cursor.eat_punct('=') {
+                parse_type(cursor, &params)?;
+            }
+        }
+        if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+            return Err(cursor.unexpected("`,` or `>`"));
+        }
+    }
+    Ok(params)
+}
+
+fn parse_object_body(cursor: &mut Cursor, type_params: &[String]) -> Result<Vec<TypeScriptField>, String> {
+    cursor.expect_punct('{')?;
+    let mut fields = Vec::new();
+
+    while !cursor.eat_punct('}') {
+        if cursor.eat_punct(';') || cursor.eat_punct(',') {
+            continue;
+        }
+        if cursor.is_punct('[') {
+            // Index signatures (`[key: string]: T`) describe maps, not fields
+            cursor.skip_balanced('[', ']');
+            skip_member(cursor);
+            continue;
+        }
+
+        let readonly =
+            cursor.is_ident("readonly") && cursor.peek_at(1).is_some_and(|t| t.kind != TokenKind::Punct);
+        if readonly {
+            cursor.advance();
+        }
+
+        let name = match cursor.advance() {
+            Some(t) if t.kind == TokenKind::Ident || t.kind == TokenKind::Str => t.text,
+            _ => return Err(cursor.unexpected("property name")),
+        };
+        let optional = cursor.eat_punct('?');
+
+        if cursor.is_punct('(') || cursor.is_punct('<') {
+            // Methods are behaviour, not data
+            skip_member(cursor);
+            continue;
+        }
+
+        cursor.expect_punct(':')?;
+        let field_type = parse_type(cursor, type_params)?;
+        fields.push(TypeScriptField { name, field_type, optional, readonly });
+    }
+
+    Ok(fields)
+}
+
+/// Skip to the end of the current member (`;`, `,`, or the closing `}`)
+fn skip_member(cursor: &mut Cursor) {
+    while let Some(token) = cursor.peek() {
+        match token.text.as_str() {
+            ";" | "," => {
+                cursor.advance();
+                return;
+            }
+            "}" => return,
+            "{" => cursor.skip_balanced('{', '}'),
+            "(" => cursor.skip_balanced('(', ')'),
+            "[" => cursor.skip_balanced('[', ']'),
+            "<" => cursor.skip_balanced('<', '>'),
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<TypeScriptFieldType, String> {
+    cursor.eat_punct('|');
+    let mut members = vec![parse_postfix(cursor, type_params)?];
+    while cursor.eat_punct('|') {
+        members.push(parse_postfix(cursor, type_params)?);
+    }
+    if cursor.is_punct('&') {
+        return Err(format!("line {}: intersection types are not supported", cursor.line()));
+    }
+    Ok(if members.len() == 1 {
+        members.remove(0)
+    } else {
+        TypeScriptFieldType::Union(members)
+    })
+}
+
+fn parse_postfix(cursor: &mut Cursor, type_params: &[String]) -> Result<TypeScriptFieldType, String> {
+    let mut ty = parse_primary(cursor, type_params)?;
+    while cursor.is_punct('[') && cursor.is_punct_at(1, ']') {
+        cursor.advance();
+        cursor.advance();
+        ty = TypeScriptFieldType::Array(Box::new(ty));
+    }
+    Ok(ty)
+}
+
+fn parse_primary(cursor: &mut Cursor, type_params: &[String]) -> Result<TypeScriptFieldType, String> {
+    if cursor.is_punct('{') {
+        return Ok(TypeScriptFieldType::Object(parse_object_body(cursor, type_params)?));
+    }
+    if cursor.eat_punct('(') {
+        let inner = parse_type(cursor, type_params)?;
+        cursor.expect_punct(')')?;
+        return Ok(inner);
+    }
+
+    let token = match cursor.advance() {
+        Some(token) => token,
+        None => return Err(cursor.unexpected("type")),
+    };
+
+    match token.kind {
+        TokenKind::Str => return Ok(TypeScriptFieldType::Literal(format!("\"{}\"", token.text))),
+        TokenKind::Number => return Ok(TypeScriptFieldType::Literal(token.text)),
+        TokenKind::Punct if token.text == "-" => {
+            let number = cursor.advance().map(|t| t.text).unwrap_or_default();
+            return Ok(TypeScriptFieldType::Literal(format!("-{}", number)));
+        }
+        TokenKind::Punct => {
+            return Err(format!("line {}: unsupported type syntax `{}`", token.line, token.text));
+        }
+        _ => {}
+    }
+
+    let mut name = token.text;
+    while cursor.is_punct('.') {
+        cursor.advance();
+        name = format!("{}.{}", name, cursor.expect_ident()?);
+    }
+
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        while !cursor.eat_punct('>') {
+            args.push(parse_type(cursor, type_params)?);
+            if !cursor.eat_punct(',') && !cursor.is_punct('>') {
+                return Err(cursor.unexpected("`,` or `>`"));
+            }
+        }
+    }
+
+    Ok(match (name.as_str(), args.len()) {
+        ("number", 0) => TypeScriptFieldType::Number,
+        ("string", 0) => TypeScriptFieldType::String,
+        ("boolean", 0) => TypeScriptFieldType::Boolean,
+        ("bigint", 0) => TypeScriptFieldType::BigInt,
+        ("null", 0) => TypeScriptFieldType::Null,
+        ("undefined" | "void", 0) => TypeScriptFieldType::Undefined,
+        ("any" | "unknown", 0) => TypeScriptFieldType::Any,
+        ("true" | "false", 0) => TypeScriptFieldType::Literal(name),
+        ("Array" | "ReadonlyArray", 1) => TypeScriptFieldType::Array(Box::new(args.remove(0))),
+        ("Record" | "Map" | "ReadonlyMap", 2) => {
+            let value = args.remove(1);
+            TypeScriptFieldType::Map(Box::new(args.remove(0)), Box::new(value))
+        }
+        ("Date", 0) => TypeScriptFieldType::Date,
+        (param, 0) if type_params.iter().any(|p| p == param) => TypeScriptFieldType::TypeParam(name),
+        _ => TypeScriptFieldType::Reference(name, args),
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(ts_type: &TypeScriptType, target: &str) -> f32 {
+    match target {
+        "rust" | "rescript" => average_fidelity(
+            ir_fields(ts_type)
+                .iter()
+                .map(|field| ir::fidelity(&field.ty, target)),
+        ),
+        _ => 0.0,
+    }
+}
+
+/// Lower a TypeScript field type into the language-neutral IR
+pub fn to_ir(field_type: &TypeScriptFieldType) -> IrType {
+    match field_type {
+        TypeScriptFieldType::Number => IrType::Primitive(PrimitiveType::F64),
+        TypeScriptFieldType::String => IrType::Primitive(PrimitiveType::String),
+        TypeScriptFieldType::Boolean => IrType::Primitive(PrimitiveType::Bool),
+        // Nearest fixed-width integer; values beyond 64 bits are not modeled
+        TypeScriptFieldType::BigInt => IrType::Primitive(PrimitiveType::BigInt),
+        TypeScriptFieldType::Null | TypeScriptFieldType::Undefined => IrType::Primitive(PrimitiveType::Unit),
+        TypeScriptFieldType::Any => IrType::Any,
+        TypeScriptFieldType::Literal(text) => literal_to_ir(text),
+        TypeScriptFieldType::Array(inner) => IrType::Container(ContainerType::Vec(Box::new(to_ir(inner)))),
+        TypeScriptFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        TypeScriptFieldType::Date => IrType::Primitive(PrimitiveType::DateTime),
+        // Only reachable for an object literal outside a declaration, which
+        // has no name to refer to it by
+        TypeScriptFieldType::Object(_) => IrType::Any,
+        TypeScriptFieldType::Reference(name, args) if args.is_empty() => IrType::Reference(name.clone()),
+        TypeScriptFieldType::Reference(name, args) => IrType::Generic(name.clone(), args.iter().map(to_ir).collect()),
+        TypeScriptFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+        TypeScriptFieldType::Union(members) => union_to_ir(members),
+    }
+}
+
+fn literal_to_ir(text: &str) -> IrType {
+    if text.starts_with('"') {
+        IrType::Primitive(PrimitiveType::String)
+    } else if text == "true" || text == "false" {
+        IrType::Primitive(PrimitiveType::Bool)
+    } else {
+        IrType::Primitive(PrimitiveType::F64)
+    }
+}
+
+fn union_to_ir(members: &[TypeScriptFieldType]) -> IrType {
+    union_shape(members).to_ir()
+}
+
+/// What a union collapses to: string literals to an enum, `T | null` to an
+/// optional `T`, members with distinct JSON kinds to a tagged union, and the
+/// rest to an untyped escape hatch
+pub fn union_shape(members: &[TypeScriptFieldType]) -> UnionShape {
+    let members: Vec<(IrType, Option<String>)> = members
+        .iter()
+        .map(|member| {
+            let literal = match member {
+                TypeScriptFieldType::Literal(text) if text.starts_with('"') => Some(text.trim_matches('"').to_string()),
+                _ => None,
+            };
+            (to_ir(member), literal)
+        })
+        .collect();
+    ir::union_shape(&members)
+}
+
+/// Tags of an alias to string literals (`type Status = "active" | "suspended"`)
+fn enum_tags(ts_type: &TypeScriptType) -> Option<Vec<String>> {
+    match ts_type.alias.as_ref()? {
+        TypeScriptFieldType::Union(members) => match union_shape(members) {
+            UnionShape::Enum(tags) => Some(tags),
+            _ => None,
+        },
+        TypeScriptFieldType::Literal(text) if text.starts_with('"') => Some(vec![text.trim_matches('"').to_string()]),
+        _ => None,
+    }
+}
+
+/// Lower an alias to string literals into an enum of them; other types have no variants
+pub fn ir_variants(ts_type: &TypeScriptType) -> Vec<IrVariant> {
+    enum_tags(ts_type)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tag| IrVariant::new(&pascal_case(&tag), Vec::new()).with_tag(Some(tag)))
+        .collect()
+}
+
+/// Wire layout of an enum of string literals: each is its bare tag
+pub fn tagging(ts_type: &TypeScriptType) -> Option<VariantTagging> {
+    enum_tags(ts_type).map(|_| VariantTagging::External)
+}
+
+/// The type a non-object alias stands for, unless it is an enum of string literals
+pub fn ir_alias(ts_type: &TypeScriptType) -> Option<IrType> {
+    match enum_tags(ts_type) {
+        Some(_) => None,
+        None => ts_type.alias.as_ref().map(to_ir),
+    }
+}
+
+/// Lower all properties of a TypeScript type into the IR (optional properties become `Option`)
+pub fn ir_fields(ts_type: &TypeScriptType) -> Vec<IrField> {
+    ts_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = if field.optional && !matches!(ty, IrType::Container(ContainerType::Option(_))) {
+                IrType::Container(ContainerType::Option(Box::new(ty)))
+            } else {
+                ty
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map TypeScript types to target language types
+pub fn map_to_target(field_type: &TypeScriptFieldType, target: &str) -> String {
+    match target {
+        "rust" => match field_type {
+            TypeScriptFieldType::Number => "f64".to_string(),
+            TypeScriptFieldType::String => "String".to_string(),
+            TypeScriptFieldType::Boolean => "bool".to_string(),
+            TypeScriptFieldType::BigInt => "i64".to_string(),
+            TypeScriptFieldType::Null | TypeScriptFieldType::Undefined => "()".to_string(),
+            TypeScriptFieldType::Any => "serde_json::Value".to_string(),
+            TypeScriptFieldType::Literal(text) => map_literal(text, "String", "bool", "f64"),
+            TypeScriptFieldType::Array(inner) => format!("Vec<{}>", map_to_target(inner, target)),
+            TypeScriptFieldType::Map(key, value) => format!("HashMap<{}, {}>", map_to_target(key, target), map_to_target(value, target)),
+            TypeScriptFieldType::Date => "chrono::DateTime<chrono::Utc>".to_string(),
+            TypeScriptFieldType::Object(_) => "serde_json::Value".to_string(),
+            TypeScriptFieldType::Reference(name, args) => with_args(name, args, target, "<", ">"),
+            TypeScriptFieldType::TypeParam(name) => name.clone(),
+            TypeScriptFieldType::Union(members) => map_union(members, target),
+        },
+        "rescript" => match field_type {
+            TypeScriptFieldType::Number => "float".to_string(),
+            TypeScriptFieldType::String => "string".to_string(),
+            TypeScriptFieldType::Boolean => "bool".to_string(),
+            TypeScriptFieldType::BigInt => "bigint".to_string(),
+            TypeScriptFieldType::Null | TypeScriptFieldType::Undefined => "unit".to_string(),
+            TypeScriptFieldType::Any => "JSON.t".to_string(),
+            TypeScriptFieldType::Literal(text) => map_literal(text, "string", "bool", "float"),
+            TypeScriptFieldType::Array(inner) => format!("array<{}>", map_to_target(inner, target)),
+            TypeScriptFieldType::Map(_, value) => format!("Js.Dict.t<{}>", map_to_target(value, target)),
+            // JSON has no date; the RFC 3339 text stays a string
+            TypeScriptFieldType::Date => "string".to_string(),
+            TypeScriptFieldType::Object(_) => "JSON.t".to_string(),
+            TypeScriptFieldType::Reference(name, args) => with_args(&lower_first(name), args, target, "<", ">"),
+            TypeScriptFieldType::TypeParam(name) => format!("'{}", name.to_lowercase()),
+            TypeScriptFieldType::Union(members) => map_union(members, target),
+        },
+        _ => "Unknown".to_string(),
+    }
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+}
+
+fn map_literal(text: &str, string: &str, boolean: &str, number: &str) -> String {
+    match literal_to_ir(text) {
+        IrType::Primitive(PrimitiveType::String) => string.to_string(),
+        IrType::Primitive(PrimitiveType::Bool) => boolean.to_string(),
+        _ => number.to_string(),
+    }
+}
+
+fn with_args(name: &str, args: &[TypeScriptFieldType], target: &str, open: &str, close: &str) -> String {
+    if args.is_empty() {
+        return name.to_string();
+    }
+    let args: Vec<String> = args.iter().map(|arg| map_to_target(arg, target)).collect();
+    format!("{}{}{}{}", name, open, args.join(", "), close)
+}
+
+fn map_union(members: &[TypeScriptFieldType], target: &str) -> String {
+    let present: Vec<&TypeScriptFieldType> = members
+        .iter()
+        .filter(|m| !matches!(m, TypeScriptFieldType::Null | TypeScriptFieldType::Undefined))
+        .collect();
+    let inner = match present.as_slice() {
+        [single] => map_to_target(single, target),
+        _ => match to_ir(&TypeScriptFieldType::Union(present.iter().map(|m| (*m).clone()).collect())) {
+            // Heterogeneous unions lower to a synthesized tagged enum (see `codegen::unions`)
+            IrType::Union(members) => {
+                let name = union_name(&members);
+                match target {
+                    "rust" => name,
+                    _ => lower_first(&name),
+                }
+            }
+            IrType::Any => map_to_target(&TypeScriptFieldType::Any, target),
+            _ => map_to_target(present[0], target),
+        },
+    };
+    if present.len() < members.len() {
+        match target {
+            "rust" => format!("Option<{}>", inner),
+            _ => format!("option<{}>", inner),
+        }
+    } else {
+        inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer;
+    use crate::TransportClass;
+
+    #[test]
+    fn test_analyze_interface() {
+        let source = r#"
+export interface User {
+  readonly id: number;
+  name: string;
+  email?: string;
+  tags: string[];
+  greet(): void;
+}
+"#;
+        let user = analyze_typescript_type(source).unwrap();
+        assert_eq!(user.name, "User");
+        assert_eq!(user.fields.len(), 4);
+        assert!(user.fields[0].readonly);
+        assert!(user.fields[2].optional);
+        assert_eq!(user.fields[3].field_type, TypeScriptFieldType::Array(Box::new(TypeScriptFieldType::String)));
+    }
+
+    #[test]
+    fn test_analyze_generic_alias_and_union() {
+        let source = r#"
+type Page<T> = { items: Array<T>; next: string | null };
+type Status = "active" | "suspended";
+"#;
+        let types = analyze_typescript_types(source).unwrap();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0].type_params, vec!["T"]);
+        assert_eq!(
+            types[0].fields[0].field_type,
+            TypeScriptFieldType::Array(Box::new(TypeScriptFieldType::TypeParam("T".to_string())))
+        );
+        assert_eq!(map_to_target(&types[0].fields[1].field_type, "rust"), "Option<String>");
+        assert_eq!(to_ir(types[1].alias.as_ref().unwrap()), IrType::Primitive(PrimitiveType::String));
+    }
+
+    #[test]
+    fn test_heterogeneous_union_lowering() {
+        let id = analyze_typescript_type("type Id = string | number | null;").unwrap();
+        let alias = id.alias.unwrap();
+        assert_eq!(
+            to_ir(&alias),
+            IrType::Container(ContainerType::Option(Box::new(IrType::Union(vec![
+                IrType::Primitive(PrimitiveType::String),
+                IrType::Primitive(PrimitiveType::F64),
+            ]))))
+        );
+        assert_eq!(map_to_target(&alias, "rust"), "Option<StringOrNumber>");
+        assert_eq!(map_to_target(&alias, "rescript"), "option<stringOrNumber>");
+    }
+
+    #[test]
+    fn test_union_collapse() {
+        let types = analyze_typescript_types(
+            "type Theme = \"light\" | \"high-contrast\";\ntype Account = User | Admin;\ntype Width = number | \"auto\" | null;",
+        )
+        .unwrap();
+        let variants: Vec<_> = ir_variants(&types[0]).into_iter().map(|variant| (variant.name.clone(), variant.wire_tag().to_string())).collect();
+        assert_eq!(variants, vec![("Light".to_string(), "light".to_string()), ("HighContrast".to_string(), "high-contrast".to_string())]);
+        assert_eq!((ir_alias(&types[0]), tagging(&types[0])), (None, Some(VariantTagging::External)));
+        // Two objects look alike on the wire, so only an untyped value holds either
+        assert_eq!(ir_alias(&types[1]), Some(IrType::Any));
+        assert_eq!(map_to_target(types[1].alias.as_ref().unwrap(), "rust"), "serde_json::Value");
+        let width = union_shape(&[TypeScriptFieldType::Number, TypeScriptFieldType::Literal("\"auto\"".to_string()), TypeScriptFieldType::Null]);
+        assert!(matches!(width, UnionShape::Option(inner) if matches!(*inner, UnionShape::Tagged(_))));
+        assert!(ir_variants(&types[2]).is_empty());
+    }
+
+    #[test]
+    fn test_maps_dates_and_generic_references() {
+        let source = "interface Page<T> { items: T[]; next?: Page<T> }\n\
+                      interface Stats { counts: Record<string, number>; flags: Map<string, boolean>; at: Date; page: Page<Stats> }";
+        let types = analyze_typescript_types(source).unwrap();
+        assert_eq!(types[0].type_params, vec!["T"]);
+        let stats: Vec<_> = ir_fields(&types[1]).into_iter().map(|field| field.ty).collect();
+        let map = |value| IrType::Container(ContainerType::Map(Box::new(IrType::Primitive(PrimitiveType::String)), Box::new(IrType::Primitive(value))));
+        assert_eq!(
+            stats,
+            vec![
+                map(PrimitiveType::F64),
+                map(PrimitiveType::Bool),
+                IrType::Primitive(PrimitiveType::DateTime),
+                IrType::Generic("Page".to_string(), vec![IrType::Reference("Stats".to_string())]),
+            ]
+        );
+        assert_eq!(map_to_target(&types[1].fields[0].field_type, "rust"), "HashMap<String, f64>");
+    }
+
+    #[test]
+    fn test_inline_objects_are_declared() {
+        let source = "interface User<T> { name: string; address: { city: string; geo?: { lat: number } }; extra: { value: T }[] }\n\
+                      type Reply = { ok: true; value: number } | { ok: false; error: string };";
+        let types = analyze_typescript_types(source).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.type_params.len())).collect();
+        assert_eq!(
+            names,
+            vec![("User", 1), ("UserAddress", 0), ("UserAddressGeo", 0), ("UserExtra", 1), ("Reply", 0), ("ReplyMember", 0), ("ReplyMember2", 0)]
+        );
+        assert_eq!(types[0].fields[1].field_type, TypeScriptFieldType::Reference("UserAddress".to_string(), Vec::new()));
+        assert_eq!(map_to_target(&types[0].fields[2].field_type, "rust"), "Vec<UserExtra<T>>");
+        assert_eq!(types[2].fields[0].name, "lat");
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let ts = analyze_typescript_type("interface User { id: number; name: string; active: boolean }").unwrap();
+        let rust = rust_analyzer::analyze_rust_type("pub struct User { pub id: i64, pub name: String, pub active: bool }")
+            .unwrap();
+
+        assert_eq!(compatibility_score(&ts, "rust"), 1.0);
+        // number → i64 truncates fractions; i64 → number loses precision past 2^53
+        let score = ir::compare_fields(&ir_fields(&ts), &rust_analyzer::ir_fields(&rust));
+        assert!(score < 1.0);
+        assert_eq!(TransportClass::from_score(score), TransportClass::Economy);
+        assert_eq!(
+            crate::calculate_transport_class("typescript", "rust"),
+            TransportClass::BusinessClass
+        );
+    }
+}
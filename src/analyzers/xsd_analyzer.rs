@@ -0,0 +1,741 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// XML Schema analyzer for protocol-squisher integration
+// Reads complexType, simpleType, group, and global element definitions from
+// an `.xsd` document; inline types are hoisted under names of their own, and
+// group references and extension bases are spliced into the types using them
+
+use crate::codegen::{pascal_case, render_type};
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::xml::{self, Element};
+
+const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Fields a type may gain from groups and bases; bounds chains that
+/// reference the same group many times over
+const MAX_FIELDS: usize = 1024;
+
+/// Complex type, simple type, group, or global element
+#[derive(Debug, Clone)]
+pub struct XsdType {
+    /// Pascal-cased (`purchaseOrderType` is `PurchaseOrderType`)
+    pub name: String,
+    /// The schema's `targetNamespace`
+    pub namespace: Option<String>,
+    pub kind: XsdTypeKind,
+    /// Child elements and attributes, base type content first
+    pub fields: Vec<XsdField>,
+    /// Values of an enumerated simple type
+    pub values: Vec<String>,
+    /// What a simple type or a global element with a `type` describes
+    pub alias: Option<XsdFieldType>,
+    /// `complexContent` extension base
+    pub base: Option<String>,
+    /// Has `xs:any` or `xs:anyAttribute`
+    pub extensible: bool,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum XsdTypeKind {
+    Complex,
+    /// Simple type restricted to `xs:enumeration` values
+    Enum,
+    /// Any other simple type
+    Simple,
+    /// `xs:group` or `xs:attributeGroup`, spliced where referenced
+    Group,
+    /// Global element naming a type declared elsewhere
+    Element,
+}
+
+#[derive(Debug, Clone)]
+pub struct XsdField {
+    pub name: String,
+    pub field_type: XsdFieldType,
+    pub min_occurs: u32,
+    /// `None` for `unbounded`
+    pub max_occurs: Option<u32>,
+    /// An attribute rather than a child element
+    pub attribute: bool,
+    /// `nillable="true"`
+    pub nillable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdFieldType {
+    /// `string`, `token`, `anyURI`, `QName`, and the other string types
+    String,
+    Boolean,
+    /// `int`, `short`, `byte`
+    Int,
+    /// `unsignedInt`, `unsignedShort`, `unsignedByte`
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    /// `integer` and its sign-restricted forms: unbounded
+    Integer,
+    Decimal,
+    Float,
+    Double,
+    /// `dateTime`, `date`, `time`, `duration`, and the `g*` types
+    Temporal,
+    /// `base64Binary`, `hexBinary`
+    Binary,
+    /// `anyType`, `anySimpleType`, or an element without a type
+    Any,
+    /// `xs:list`
+    List(Box<XsdFieldType>),
+    /// `xs:union`
+    Union(Vec<XsdFieldType>),
+    /// Another type or a hoisted inline one
+    Named(String),
+}
+
+/// Analyze the first definition of an XML Schema
+pub fn analyze_xsd_type(source: &str) -> Result<XsdType, String> {
+    analyze_xsd_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every top-level definition of an XML Schema
+pub fn analyze_xsd_types(source: &str) -> Result<Vec<XsdType>, String> {
+    let root = xml::parse(source)?;
+    if root.local_name() != "schema" {
+        return Err(format!("line {}: expected a `schema` element, found `{}`", root.line, root.name));
+    }
+    // Prefixes bound to the XML Schema namespace; `""` when it's the default
+    let prefixes = root
+        .attributes
+        .iter()
+        .filter(|(_, value)| value == XSD_NAMESPACE)
+        .filter_map(|(key, _)| match key.as_str() {
+            "xmlns" => Some(String::new()),
+            _ => key.strip_prefix("xmlns:").map(str::to_string),
+        })
+        .collect();
+    let mut analyzer = Analyzer {
+        prefixes,
+        namespace: root.attribute("targetNamespace").map(str::to_string),
+        types: Vec::new(),
+        declared: Vec::new(),
+        splices: Vec::new(),
+    };
+
+    for node in &root.children {
+        let name = node.attribute("name").unwrap_or_default();
+        match node.local_name() {
+            "complexType" => {
+                let index = analyzer.define(name, XsdTypeKind::Complex, node.line);
+                analyzer.complex(index, node)?;
+            }
+            "simpleType" => analyzer.simple_type(name, node)?,
+            "group" | "attributeGroup" => {
+                let index = analyzer.define(name, XsdTypeKind::Group, node.line);
+                analyzer.content(index, node, false, false)?;
+            }
+            "element" => analyzer.global_element(node)?,
+            // Imports, includes, annotations, global attributes, and notations
+            _ => {}
+        }
+    }
+    Ok(analyzer.resolve())
+}
+
+/// A group reference or extension base to splice in once everything is read
+#[derive(Debug, Clone)]
+struct Splice {
+    position: usize,
+    name: String,
+    kind: XsdTypeKind,
+    optional: bool,
+    repeated: bool,
+}
+
+struct Analyzer {
+    prefixes: Vec<String>,
+    namespace: Option<String>,
+    types: Vec<XsdType>,
+    /// Name of each type before collisions were resolved, for references
+    declared: Vec<String>,
+    splices: Vec<Vec<Splice>>,
+}
+
+impl Analyzer {
+    /// Add a type; a second type with the same name gets a numeric suffix
+    fn define(&mut self, name: &str, kind: XsdTypeKind, line: usize) -> usize {
+        let declared = pascal_case(name);
+        let mut unique = declared.clone();
+        let mut suffix = 2;
+        while self.types.iter().any(|t| t.name == unique) {
+            unique = format!("{}{}", declared, suffix);
+            suffix += 1;
+        }
+        self.types.push(XsdType {
+            name: unique,
+            namespace: self.namespace.clone(),
+            kind,
+            fields: Vec::new(),
+            values: Vec::new(),
+            alias: None,
+            base: None,
+            extensible: false,
+            location: format!("line {}", line),
+        });
+        self.declared.push(declared);
+        self.splices.push(Vec::new());
+        self.types.len() - 1
+    }
+
+    /// `<xs:element name="order" type="OrderType"/>`, or one with an inline type
+    fn global_element(&mut self, node: &Element) -> Result<(), String> {
+        let name = node.attribute("name").unwrap_or_default();
+        if let Some(reference) = node.attribute("type") {
+            let field_type = self.qualified(reference);
+            // `<xs:element name="order" type="order"/>` only binds the root
+            if field_type == XsdFieldType::Named(pascal_case(name)) {
+                return Ok(());
+            }
+            let index = self.define(name, XsdTypeKind::Element, node.line);
+            self.types[index].alias = Some(field_type);
+            return Ok(());
+        }
+        if let Some(complex) = child(node, "complexType") {
+            let index = self.define(name, XsdTypeKind::Complex, node.line);
+            return self.complex(index, complex);
+        }
+        match child(node, "simpleType") {
+            Some(simple) => self.simple_type(name, simple),
+            None => {
+                let index = self.define(name, XsdTypeKind::Element, node.line);
+                self.types[index].alias = Some(XsdFieldType::Any);
+                Ok(())
+            }
+        }
+    }
+
+    /// Content of a `complexType`
+    fn complex(&mut self, index: usize, node: &Element) -> Result<(), String> {
+        for part in &node.children {
+            match part.local_name() {
+                "complexContent" => {
+                    let Some(derivation) = part.children.iter().find(|c| matches!(c.local_name(), "extension" | "restriction")) else {
+                        continue;
+                    };
+                    // A restriction restates the content it keeps; an extension adds to its base's
+                    if derivation.local_name() == "extension" {
+                        if let Some(XsdFieldType::Named(base)) = derivation.attribute("base").map(|b| self.qualified(b)) {
+                            self.types[index].base = Some(base.clone());
+                            let position = self.types[index].fields.len();
+                            self.splices[index].push(Splice { position, name: base, kind: XsdTypeKind::Complex, optional: false, repeated: false });
+                        }
+                    }
+                    self.content(index, derivation, false, false)?;
+                }
+                // Text content of the base type, plus attributes
+                "simpleContent" => {
+                    let Some(derivation) = part.children.iter().find(|c| matches!(c.local_name(), "extension" | "restriction")) else {
+                        continue;
+                    };
+                    let value = derivation.attribute("base").map_or(XsdFieldType::String, |b| self.qualified(b));
+                    self.types[index].fields.push(XsdField {
+                        name: "value".to_string(),
+                        field_type: value,
+                        min_occurs: 1,
+                        max_occurs: Some(1),
+                        attribute: false,
+                        nillable: false,
+                    });
+                    self.content(index, derivation, false, false)?;
+                }
+                _ => self.content(index, &wrap(part), false, false)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Particles and attributes under `node`; `optional` and `repeated` carry
+    /// an enclosing choice or `minOccurs`/`maxOccurs` down to its elements
+    fn content(&mut self, index: usize, node: &Element, optional: bool, repeated: bool) -> Result<(), String> {
+        for part in &node.children {
+            let (min, max) = occurs(part)?;
+            let optional = optional || min == 0;
+            let repeated = repeated || max != Some(1);
+            match part.local_name() {
+                "element" => {
+                    let field = self.element(index, part, optional, repeated)?;
+                    self.types[index].fields.push(field);
+                }
+                "sequence" | "all" => self.content(index, part, optional, repeated)?,
+                // One branch is present, so every element of each may be absent
+                "choice" => self.content(index, part, true, repeated)?,
+                "group" | "attributeGroup" => {
+                    if let Some(reference) = part.attribute("ref") {
+                        let position = self.types[index].fields.len();
+                        let name = pascal_case(xml::local_name(reference));
+                        self.splices[index].push(Splice { position, name, kind: XsdTypeKind::Group, optional, repeated });
+                    } else {
+                        self.content(index, part, optional, repeated)?;
+                    }
+                }
+                "attribute" => {
+                    if let Some(field) = self.attribute(index, part) {
+                        self.types[index].fields.push(field);
+                    }
+                }
+                "any" | "anyAttribute" => self.types[index].extensible = true,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// A local element declaration or `ref`
+    fn element(&mut self, index: usize, node: &Element, optional: bool, repeated: bool) -> Result<XsdField, String> {
+        let (min, max) = occurs(node)?;
+        let (name, field_type) = match node.attribute("ref") {
+            Some(reference) => {
+                let name = xml::local_name(reference).to_string();
+                let field_type = XsdFieldType::Named(pascal_case(&name));
+                (name, field_type)
+            }
+            None => {
+                let name = node.attribute("name").unwrap_or_default().to_string();
+                let hint = format!("{}{}", self.types[index].name, pascal_case(&name));
+                let field_type = self.declared_type(node, &hint)?;
+                (name, field_type)
+            }
+        };
+        Ok(XsdField {
+            name,
+            field_type,
+            min_occurs: if optional { 0 } else { min },
+            max_occurs: if repeated && max == Some(1) { None } else { max },
+            attribute: false,
+            nillable: node.attribute("nillable") == Some("true"),
+        })
+    }
+
+    /// `<xs:attribute name="currency" type="xs:string" use="required"/>`;
+    /// prohibited attributes are left out
+    fn attribute(&mut self, index: usize, node: &Element) -> Option<XsdField> {
+        let usage = node.attribute("use").unwrap_or("optional");
+        if usage == "prohibited" {
+            return None;
+        }
+        let (name, field_type) = match node.attribute("ref") {
+            Some(reference) => (xml::local_name(reference).to_string(), XsdFieldType::String),
+            None => {
+                let name = node.attribute("name").unwrap_or_default().to_string();
+                let hint = format!("{}{}", self.types[index].name, pascal_case(&name));
+                let field_type = self.declared_type(node, &hint).unwrap_or(XsdFieldType::String);
+                (name, field_type)
+            }
+        };
+        // A default or fixed value fills in for an absent attribute
+        let present = usage == "required" || node.attribute("default").is_some() || node.attribute("fixed").is_some();
+        Some(XsdField { name, field_type, min_occurs: u32::from(present), max_occurs: Some(1), attribute: true, nillable: false })
+    }
+
+    /// The `type` of an element or attribute, or its inline type hoisted as `hint`
+    fn declared_type(&mut self, node: &Element, hint: &str) -> Result<XsdFieldType, String> {
+        if let Some(reference) = node.attribute("type") {
+            return Ok(self.qualified(reference));
+        }
+        if let Some(complex) = child(node, "complexType") {
+            let index = self.define(hint, XsdTypeKind::Complex, complex.line);
+            self.complex(index, complex)?;
+            return Ok(XsdFieldType::Named(self.types[index].name.clone()));
+        }
+        match child(node, "simpleType") {
+            Some(simple) => self.inline_simple(simple, hint),
+            None => Ok(XsdFieldType::Any),
+        }
+    }
+
+    /// A named `simpleType`
+    fn simple_type(&mut self, name: &str, node: &Element) -> Result<(), String> {
+        let (field_type, values) = self.simple(node, name)?;
+        match values {
+            Some(values) => {
+                let index = self.define(name, XsdTypeKind::Enum, node.line);
+                self.types[index].values = values;
+            }
+            None => {
+                let index = self.define(name, XsdTypeKind::Simple, node.line);
+                self.types[index].alias = Some(field_type);
+            }
+        }
+        Ok(())
+    }
+
+    /// An anonymous `simpleType`; enumerations become a type named `hint`
+    fn inline_simple(&mut self, node: &Element, hint: &str) -> Result<XsdFieldType, String> {
+        let (field_type, values) = self.simple(node, hint)?;
+        let Some(values) = values else {
+            return Ok(field_type);
+        };
+        let index = self.define(hint, XsdTypeKind::Enum, node.line);
+        self.types[index].values = values;
+        Ok(XsdFieldType::Named(self.types[index].name.clone()))
+    }
+
+    /// What a `simpleType` describes, and its enumeration values if it has any
+    fn simple(&mut self, node: &Element, hint: &str) -> Result<(XsdFieldType, Option<Vec<String>>), String> {
+        let Some(derivation) = node.children.iter().find(|c| matches!(c.local_name(), "restriction" | "list" | "union")) else {
+            return Ok((XsdFieldType::Any, None));
+        };
+        match derivation.local_name() {
+            "restriction" => {
+                let base = match (derivation.attribute("base"), child(derivation, "simpleType")) {
+                    (Some(base), _) => self.qualified(base),
+                    (None, Some(inner)) => self.inline_simple(inner, &format!("{}Base", hint))?,
+                    (None, None) => XsdFieldType::Any,
+                };
+                let values: Vec<String> = derivation
+                    .children
+                    .iter()
+                    .filter(|facet| facet.local_name() == "enumeration")
+                    .filter_map(|facet| facet.attribute("value").map(str::to_string))
+                    .collect();
+                if !values.is_empty() {
+                    return Ok((base, Some(values)));
+                }
+                Ok((narrow(base, derivation), None))
+            }
+            "list" => {
+                let items = match (derivation.attribute("itemType"), child(derivation, "simpleType")) {
+                    (Some(items), _) => self.qualified(items),
+                    (None, Some(inner)) => self.inline_simple(inner, &format!("{}Item", hint))?,
+                    (None, None) => XsdFieldType::Any,
+                };
+                Ok((XsdFieldType::List(Box::new(items)), None))
+            }
+            _ => {
+                let mut members: Vec<XsdFieldType> =
+                    derivation.attribute("memberTypes").unwrap_or_default().split_whitespace().map(|m| self.qualified(m)).collect();
+                for (i, inner) in derivation.children.iter().filter(|c| c.local_name() == "simpleType").enumerate() {
+                    members.push(self.inline_simple(inner, &format!("{}{}", hint, i + 1))?);
+                }
+                Ok((XsdFieldType::Union(members), None))
+            }
+        }
+    }
+
+    /// A `prefix:local` type name: a built-in type when the prefix is bound
+    /// to the XML Schema namespace, otherwise a user type
+    fn qualified(&self, reference: &str) -> XsdFieldType {
+        let (prefix, local) = reference.split_once(':').unwrap_or(("", reference));
+        if !self.prefixes.iter().any(|p| p == prefix) {
+            return XsdFieldType::Named(pascal_case(local));
+        }
+        match local {
+            "string" | "normalizedString" | "token" | "language" | "Name" | "NCName" | "ID" | "IDREF" | "NMTOKEN" | "ENTITY" | "anyURI"
+            | "QName" | "NOTATION" => XsdFieldType::String,
+            "IDREFS" | "NMTOKENS" | "ENTITIES" => XsdFieldType::List(Box::new(XsdFieldType::String)),
+            "boolean" => XsdFieldType::Boolean,
+            "int" | "short" | "byte" => XsdFieldType::Int,
+            "unsignedInt" | "unsignedShort" | "unsignedByte" => XsdFieldType::UnsignedInt,
+            "long" => XsdFieldType::Long,
+            "unsignedLong" => XsdFieldType::UnsignedLong,
+            "integer" | "nonNegativeInteger" | "positiveInteger" | "nonPositiveInteger" | "negativeInteger" => XsdFieldType::Integer,
+            "decimal" => XsdFieldType::Decimal,
+            "float" => XsdFieldType::Float,
+            "double" => XsdFieldType::Double,
+            "dateTime" | "dateTimeStamp" | "date" | "time" | "duration" | "dayTimeDuration" | "yearMonthDuration" | "gYear" | "gYearMonth"
+            | "gMonth" | "gMonthDay" | "gDay" => XsdFieldType::Temporal,
+            "base64Binary" | "hexBinary" => XsdFieldType::Binary,
+            _ => XsdFieldType::Any,
+        }
+    }
+
+    /// Splice group references and extension bases into the types that name them
+    fn resolve(mut self) -> Vec<XsdType> {
+        let mut resolved = vec![None; self.types.len()];
+        for index in 0..self.types.len() {
+            self.fields_of(index, &mut resolved, &mut Vec::new());
+        }
+        for (defined, fields) in self.types.iter_mut().zip(resolved) {
+            defined.fields = fields.unwrap_or_default();
+        }
+        self.types
+    }
+
+    fn fields_of(&self, index: usize, resolved: &mut Vec<Option<Vec<XsdField>>>, active: &mut Vec<usize>) -> Vec<XsdField> {
+        if let Some(fields) = &resolved[index] {
+            return fields.clone();
+        }
+        let mut fields = self.types[index].fields.clone();
+        active.push(index);
+        // Back to front, so earlier positions stay put
+        for splice in self.splices[index].iter().rev() {
+            let found = (0..self.types.len()).find(|&i| self.declared[i] == splice.name && self.types[i].kind == splice.kind);
+            let Some(found) = found.filter(|found| !active.contains(found)) else {
+                continue;
+            };
+            let mut spliced = self.fields_of(found, resolved, active);
+            for field in &mut spliced {
+                if splice.optional {
+                    field.min_occurs = 0;
+                }
+                if splice.repeated && !field.attribute {
+                    field.max_occurs = None;
+                }
+            }
+            fields.splice(splice.position..splice.position, spliced);
+            fields.truncate(MAX_FIELDS);
+        }
+        active.pop();
+        resolved[index] = Some(fields.clone());
+        fields
+    }
+}
+
+/// First child with the given local name
+fn child<'a>(node: &'a Element, name: &str) -> Option<&'a Element> {
+    node.children.iter().find(|c| c.local_name() == name)
+}
+
+/// A lone particle or attribute as the only child of an element, so
+/// `complexType` children go through the same walk as compositor children
+fn wrap(part: &Element) -> Element {
+    Element { name: String::new(), attributes: Vec::new(), children: vec![part.clone()], line: part.line }
+}
+
+/// `minOccurs`/`maxOccurs`, defaulting to exactly one
+fn occurs(node: &Element) -> Result<(u32, Option<u32>), String> {
+    let bound = |key: &str| -> Result<Option<u32>, String> {
+        match node.attribute(key) {
+            None => Ok(Some(1)),
+            Some("unbounded") => Ok(None),
+            Some(value) => value.trim().parse().map(Some).map_err(|_| format!("line {}: invalid {} `{}`", node.line, key, value)),
+        }
+    };
+    Ok((bound("minOccurs")?.unwrap_or(1), bound("maxOccurs")?))
+}
+
+/// Narrow an unbounded integer restriction by its inclusive or exclusive bounds
+fn narrow(base: XsdFieldType, restriction: &Element) -> XsdFieldType {
+    if !matches!(base, XsdFieldType::Integer | XsdFieldType::Long) {
+        return base;
+    }
+    let facet = |name: &str| child(restriction, name).and_then(|f| f.attribute("value")).and_then(|v| v.trim().parse::<i64>().ok());
+    let minimum = facet("minInclusive").or_else(|| facet("minExclusive").map(|v| v.saturating_add(1)));
+    let maximum = facet("maxInclusive").or_else(|| facet("maxExclusive").map(|v| v.saturating_sub(1)));
+    match (minimum, maximum) {
+        (Some(min), Some(max)) if min >= 0 && max <= i64::from(u32::MAX) => XsdFieldType::UnsignedInt,
+        (Some(min), Some(max)) if min >= i64::from(i32::MIN) && max <= i64::from(i32::MAX) => XsdFieldType::Int,
+        _ => base,
+    }
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(xsd_type: &XsdType, target: &str) -> f32 {
+    average_fidelity(ir_fields(xsd_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower an XML Schema type into the language-neutral IR
+pub fn to_ir(field_type: &XsdFieldType) -> IrType {
+    let primitive = IrType::Primitive;
+    match field_type {
+        // Dates and times cross as their lexical forms
+        XsdFieldType::String | XsdFieldType::Temporal => primitive(PrimitiveType::String),
+        XsdFieldType::Boolean => primitive(PrimitiveType::Bool),
+        XsdFieldType::Int => primitive(PrimitiveType::I32),
+        XsdFieldType::UnsignedInt => primitive(PrimitiveType::U32),
+        // Unbounded in the schema; 64 bits is what binding generators pick
+        XsdFieldType::Long | XsdFieldType::Integer => primitive(PrimitiveType::I64),
+        XsdFieldType::UnsignedLong => primitive(PrimitiveType::U64),
+        XsdFieldType::Float => primitive(PrimitiveType::F32),
+        XsdFieldType::Double => primitive(PrimitiveType::F64),
+        XsdFieldType::Decimal => IrType::Primitive(PrimitiveType::Decimal),
+        XsdFieldType::Binary => IrType::Primitive(PrimitiveType::Bytes),
+        XsdFieldType::Any => IrType::Any,
+        XsdFieldType::List(items) => IrType::Container(ContainerType::Vec(Box::new(to_ir(items)))),
+        XsdFieldType::Union(members) => {
+            let mut present: Vec<IrType> = Vec::new();
+            for ty in members.iter().map(to_ir) {
+                if !present.contains(&ty) {
+                    present.push(ty);
+                }
+            }
+            match present.len() {
+                0 => IrType::Any,
+                1 => present.remove(0),
+                _ => IrType::Union(present),
+            }
+        }
+        XsdFieldType::Named(name) => IrType::Reference(name.clone()),
+    }
+}
+
+/// Lower the elements and attributes of a type into the IR; repeated
+/// elements become lists, and optional or nillable ones options
+pub fn ir_fields(xsd_type: &XsdType) -> Vec<IrField> {
+    xsd_type
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = to_ir(&field.field_type);
+            let ty = if field.max_occurs != Some(1) {
+                IrType::Container(ContainerType::Vec(Box::new(ty)))
+            } else if field.min_occurs == 0 || field.nillable {
+                IrType::Container(ContainerType::Option(Box::new(ty)))
+            } else {
+                ty
+            };
+            IrField::new(&field.name, ty)
+        })
+        .collect()
+}
+
+/// Map XML Schema types to target language types
+pub fn map_to_target(field_type: &XsdFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" | "rescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:po="urn:example:po" targetNamespace="urn:example:po">
+  <xs:element name="purchaseOrder" type="po:PurchaseOrderType"/>
+
+  <xs:complexType name="PurchaseOrderType">
+    <xs:complexContent>
+      <xs:extension base="po:DocumentType">
+        <xs:sequence>
+          <xs:element name="shipTo" type="po:Address"/>
+          <xs:element name="comment" type="xs:string" minOccurs="0"/>
+          <xs:element name="item" maxOccurs="unbounded">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="quantity">
+                  <xs:simpleType>
+                    <xs:restriction base="xs:positiveInteger"><xs:maxExclusive value="100"/><xs:minInclusive value="1"/></xs:restriction>
+                  </xs:simpleType>
+                </xs:element>
+                <xs:element name="price" type="po:Price"/>
+              </xs:sequence>
+              <xs:attribute name="partNum" type="xs:string" use="required"/>
+            </xs:complexType>
+          </xs:element>
+          <xs:choice>
+            <xs:element name="email" type="xs:string"/>
+            <xs:element name="phone" type="xs:string" nillable="true"/>
+          </xs:choice>
+          <xs:group ref="po:Audit"/>
+          <xs:any processContents="lax" minOccurs="0"/>
+        </xs:sequence>
+        <xs:attribute name="status" type="po:Status" default="open"/>
+      </xs:extension>
+    </xs:complexContent>
+  </xs:complexType>
+
+  <xs:complexType name="DocumentType">
+    <xs:attribute name="id" type="xs:long" use="required"/>
+  </xs:complexType>
+
+  <xs:complexType name="Price">
+    <xs:simpleContent>
+      <xs:extension base="xs:decimal">
+        <xs:attribute name="currency" type="xs:string"/>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+
+  <!-- Order states -->
+  <xs:simpleType name="Status">
+    <xs:restriction base="xs:token">
+      <xs:enumeration value="open"/>
+      <xs:enumeration value="shipped"/>
+    </xs:restriction>
+  </xs:simpleType>
+
+  <xs:simpleType name="Tags"><xs:list itemType="xs:NCName"/></xs:simpleType>
+
+  <xs:group name="Audit">
+    <xs:sequence>
+      <xs:element name="created" type="xs:dateTime"/>
+      <xs:element name="reviewer" type="xs:string" maxOccurs="3"/>
+    </xs:sequence>
+  </xs:group>
+
+  <xs:complexType name="Address">
+    <xs:all>
+      <xs:element name="city" type="xs:string"/>
+      <xs:element name="zip" type="xs:unsignedInt"/>
+    </xs:all>
+  </xs:complexType>
+</xs:schema>
+"#;
+
+    #[test]
+    fn test_analyze_xsd() {
+        let types = analyze_xsd_types(ORDER).unwrap();
+        let names: Vec<_> = types.iter().map(|t| (t.name.as_str(), t.kind, t.location.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("PurchaseOrder", XsdTypeKind::Element, "line 4"),
+                ("PurchaseOrderType", XsdTypeKind::Complex, "line 6"),
+                ("PurchaseOrderTypeItem", XsdTypeKind::Complex, "line 13"),
+                ("DocumentType", XsdTypeKind::Complex, "line 37"),
+                ("Price", XsdTypeKind::Complex, "line 41"),
+                ("Status", XsdTypeKind::Enum, "line 50"),
+                ("Tags", XsdTypeKind::Simple, "line 57"),
+                ("Audit", XsdTypeKind::Group, "line 59"),
+                ("Address", XsdTypeKind::Complex, "line 66"),
+            ]
+        );
+        assert_eq!(types[0].namespace.as_deref(), Some("urn:example:po"));
+        assert_eq!(types[1].base.as_deref(), Some("DocumentType"));
+        assert!(types[1].extensible);
+
+        let rust: Vec<_> = ir_fields(&types[1]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(
+            rust,
+            vec![
+                "id: i64",
+                "shipTo: Address",
+                "comment: Option<String>",
+                "item: Vec<PurchaseOrderTypeItem>",
+                "email: Option<String>",
+                "phone: Option<String>",
+                "created: String",
+                "reviewer: Vec<String>",
+                "status: Status",
+            ]
+        );
+        assert!(types[1].fields[0].attribute && !types[1].fields[1].attribute);
+        assert_eq!(types[1].fields[8].field_type, XsdFieldType::Named("Status".to_string()));
+
+        let item: Vec<_> = ir_fields(&types[2]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(item, vec!["quantity: u32", "price: Price", "partNum: String"]);
+        let price: Vec<_> = ir_fields(&types[4]).iter().map(|f| format!("{}: {}", f.name, render_type(&f.ty, "rust"))).collect();
+        assert_eq!(price, vec!["value: rust_decimal::Decimal", "currency: Option<String>"]);
+        assert_eq!(types[5].values, vec!["open", "shipped"]);
+        assert_eq!(types[6].alias, Some(XsdFieldType::List(Box::new(XsdFieldType::String))));
+        // An unsignedInt zip doesn't fit ReScript's int
+        assert_eq!(compatibility_score(&types[8], "rescript"), 0.75);
+    }
+
+    #[test]
+    fn test_default_namespace_and_errors() {
+        let schema = r#"<schema xmlns="http://www.w3.org/2001/XMLSchema"><complexType name="point"><sequence>
+            <element name="x" type="double"/><element name="y" type="double"/></sequence></complexType></schema>"#;
+        let point = analyze_xsd_type(schema).unwrap();
+        assert_eq!(point.name, "Point");
+        assert_eq!(map_to_target(&point.fields[0].field_type, "gleam"), "Float");
+        assert!(analyze_xsd_types("<xs:element name=\"a\"/>").is_err());
+        assert!(analyze_xsd_types("<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\">").is_err());
+        assert!(analyze_xsd_types("<schema><element name=\"a\" minOccurs=\"some\"/></schema>").is_ok());
+        assert!(analyze_xsd_types("<schema><complexType name=\"a\"><sequence><element name=\"b\" minOccurs=\"x\"/></sequence></complexType></schema>").is_err());
+    }
+}
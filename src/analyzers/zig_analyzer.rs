@@ -0,0 +1,808 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Zig analyzer for protocol-squisher integration
+// Analyzes `struct` and `union(enum)` container declarations, including generic type functions
+
+use super::c_analyzer::skip_statement;
+use super::lexer::{tokenize, Cursor, Syntax, TokenKind};
+use crate::codegen::render_type;
+use crate::ir::{self, average_fidelity, ContainerType, IrField, IrType, PrimitiveType};
+use crate::target::PlatformWidth;
+
+/// Zig container declaration
+#[derive(Debug, Clone)]
+pub struct ZigType {
+    /// Declared name; nested containers are spelled `Outer.Inner`, anonymous
+    /// field types `Outer.field`
+    pub name: String,
+    pub kind: ZigTypeKind,
+    pub layout: ZigLayout,
+    /// `comptime T: type` parameters of a generic type function
+    pub type_params: Vec<String>,
+    /// Struct fields, or union variants (payload-less variants are `Void`)
+    pub fields: Vec<ZigField>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZigTypeKind {
+    Struct,
+    /// `union(enum)` or `union(Tag)`
+    TaggedUnion,
+    /// Bare `union`: no tag, so the active variant isn't known at runtime
+    Union,
+}
+
+/// Memory layout of a container
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZigLayout {
+    /// Compiler-chosen field order
+    Auto,
+    /// C ABI layout (`extern struct`)
+    Extern,
+    /// Bit-packed (`packed struct(u32)`)
+    Packed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZigField {
+    pub name: String,
+    pub field_type: ZigFieldType,
+    /// Declared with a default value (`= 0`)
+    pub has_default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZigFieldType {
+    /// Signed integer of any width (`i7`, `i64`, `c_int`)
+    Int(u16),
+    /// Unsigned integer of any width (`u1`, `u8`, `c_uint`)
+    UInt(u16),
+    ISize,
+    USize,
+    /// `f16` through `f128`
+    Float(u16),
+    Bool,
+    Void,
+    /// Byte slices and sentinel-terminated byte pointers (`[]const u8`, `[*:0]const u8`)
+    String,
+    /// `?T`
+    Optional(Box<ZigFieldType>),
+    /// `[]T`
+    Slice(Box<ZigFieldType>),
+    /// `[N]T`; `None` for an inferred length (`[_]T`)
+    Array(Box<ZigFieldType>, Option<usize>),
+    /// `*T`, never null
+    Pointer(Box<ZigFieldType>),
+    /// `[*]T`, unknown length
+    ManyPointer(Box<ZigFieldType>),
+    /// `[*c]T`, nullable C pointer
+    CPointer(Box<ZigFieldType>),
+    /// `Error!T`; `None` for an inferred or global (`anyerror`) error set
+    ErrorUnion(Option<String>, Box<ZigFieldType>),
+    /// `std.ArrayList(T)` and friends
+    List(Box<ZigFieldType>),
+    /// `std.AutoHashMap(K, V)`, `std.StringHashMap(V)`
+    Map(Box<ZigFieldType>, Box<ZigFieldType>),
+    /// `*const fn (i32) void`
+    Function,
+    /// `anytype`, `anyopaque`, `type`
+    Any,
+    /// User or standard-library type, with its arguments
+    Named(String, Vec<ZigFieldType>),
+    TypeParam(String),
+}
+
+const ZIG_SYNTAX: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[],
+    string_quotes: &['"', '\''],
+    ident_extra: &[],
+    char_literals: false,
+};
+
+/// Pointer and slice qualifiers that don't change the pointee
+const QUALIFIERS: &[&str] = &["const", "volatile", "allowzero"];
+
+/// Analyze the first Zig container and extract metadata
+pub fn analyze_zig_type(source: &str) -> Result<ZigType, String> {
+    analyze_zig_types(source)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Type definition not found".to_string())
+}
+
+/// Analyze every `struct` and `union` declaration in a Zig file
+pub fn analyze_zig_types(source: &str) -> Result<Vec<ZigType>, String> {
+    let mut cursor = Cursor::new(tokenize(source, &ZIG_SYNTAX));
+    let mut types = Vec::new();
+    while !cursor.at_end() {
+        if cursor.eat_punct('}') {
+            continue;
+        }
+        parse_declaration(&mut cursor, "", &[], &mut types)?;
+    }
+    Ok(types)
+}
+
+/// A container-level declaration: `const X = struct {...};`, a generic type
+/// function, or anything else (skipped)
+fn parse_declaration(cursor: &mut Cursor, outer: &str, type_params: &[String], types: &mut Vec<ZigType>) -> Result<(), String> {
+    cursor.eat_ident("pub");
+    if cursor.eat_ident("const") || cursor.eat_ident("var") {
+        let Ok(name) = cursor.expect_ident() else {
+            skip_statement(cursor);
+            return Ok(());
+        };
+        if cursor.is_punct(':') {
+            // Typed constant, never a type
+            skip_statement(cursor);
+            return Ok(());
+        }
+        if cursor.eat_punct('=') && is_container(cursor) {
+            let name = qualified(outer, &name);
+            parse_container(cursor, &name, type_params, types)?;
+            cursor.eat_punct(';');
+        } else {
+            skip_statement(cursor);
+        }
+        return Ok(());
+    }
+    cursor.eat_ident("inline");
+    if cursor.is_ident("fn") {
+        return parse_function(cursor, outer, types);
+    }
+    skip_statement(cursor);
+    Ok(())
+}
+
+fn qualified(outer: &str, name: &str) -> String {
+    if outer.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", outer, name)
+    }
+}
+
+/// Whether a container expression starts at the cursor
+fn is_container(cursor: &Cursor) -> bool {
+    let offset = usize::from(cursor.is_ident("extern") || cursor.is_ident("packed"));
+    cursor
+        .peek_at(offset)
+        .is_some_and(|t| t.kind == TokenKind::Ident && (t.text == "struct" || t.text == "union"))
+}
+
+/// `fn Name(comptime T: type) type { return struct { ... }; }` declares a generic
+/// type; other functions are skipped
+fn parse_function(cursor: &mut Cursor, outer: &str, types: &mut Vec<ZigType>) -> Result<(), String> {
+    cursor.advance();
+    let Ok(name) = cursor.expect_ident() else {
+        skip_statement(cursor);
+        return Ok(());
+    };
+
+    let mut type_params = Vec::new();
+    if cursor.is_punct('(') {
+        cursor.advance();
+        while !cursor.at_end() && !cursor.is_punct(')') {
+            let comptime = cursor.eat_ident("comptime");
+            let param = cursor.expect_ident().ok();
+            let is_type = cursor.eat_punct(':') && cursor.is_ident("type");
+            if let (true, true, Some(param)) = (comptime, is_type, param) {
+                type_params.push(param);
+            }
+            // Rest of the parameter
+            while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct(')') {
+                if cursor.is_punct('(') {
+                    cursor.skip_balanced('(', ')');
+                } else {
+                    cursor.advance();
+                }
+            }
+            cursor.eat_punct(',');
+        }
+        cursor.eat_punct(')');
+    }
+
+    if !cursor.eat_ident("type") || !cursor.is_punct('{') {
+        skip_statement(cursor);
+        return Ok(());
+    }
+    cursor.advance();
+    let mut depth = 0usize;
+    while let Some(token) = cursor.peek() {
+        if token.kind == TokenKind::Punct && token.text == "{" {
+            depth += 1;
+        } else if token.kind == TokenKind::Punct && token.text == "}" {
+            if depth == 0 {
+                cursor.advance();
+                break;
+            }
+            depth -= 1;
+        } else if depth == 0 && token.kind == TokenKind::Ident && token.text == "return" {
+            cursor.advance();
+            if is_container(cursor) {
+                parse_container(cursor, &qualified(outer, &name), &type_params, types)?;
+            }
+            continue;
+        }
+        cursor.advance();
+    }
+    Ok(())
+}
+
+/// `[extern|packed] struct|union[(...)] { ... }`; pushes the container and any
+/// nested or anonymous containers it declares
+fn parse_container(cursor: &mut Cursor, name: &str, type_params: &[String], types: &mut Vec<ZigType>) -> Result<(), String> {
+    let line = cursor.line();
+    let layout = if cursor.eat_ident("extern") {
+        ZigLayout::Extern
+    } else if cursor.eat_ident("packed") {
+        ZigLayout::Packed
+    } else {
+        ZigLayout::Auto
+    };
+    let kind = if cursor.eat_ident("union") {
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+            ZigTypeKind::TaggedUnion
+        } else {
+            ZigTypeKind::Union
+        }
+    } else {
+        cursor.expect_ident()?;
+        // Backing integer of a packed struct: `packed struct(u32)`
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        ZigTypeKind::Struct
+    };
+    cursor.expect_punct('{')?;
+
+    let index = types.len();
+    types.push(ZigType {
+        name: name.to_string(),
+        kind,
+        layout,
+        type_params: type_params.to_vec(),
+        fields: Vec::new(),
+        location: format!("line {}", line),
+    });
+
+    let mut fields = Vec::new();
+    while !cursor.eat_punct('}') {
+        if cursor.at_end() {
+            return Err(cursor.unexpected("`}`"));
+        }
+        if let Some(field) = parse_member(cursor, name, type_params, types)? {
+            fields.push(field);
+        }
+    }
+    types[index].fields = fields;
+    Ok(())
+}
+
+/// One member of a container body; `Some` for a field or union variant
+fn parse_member(cursor: &mut Cursor, outer: &str, type_params: &[String], types: &mut Vec<ZigType>) -> Result<Option<ZigField>, String> {
+    let token = cursor.peek().ok_or_else(|| cursor.unexpected("field or declaration"))?;
+    let keyword = token.kind == TokenKind::Ident && !cursor.is_punct_at(1, ':') && !cursor.is_punct_at(1, ',') && !cursor.is_punct_at(1, '}');
+    if keyword {
+        match token.text.as_str() {
+            "pub" | "const" | "var" | "fn" | "inline" | "export" | "extern" => {
+                parse_declaration(cursor, outer, type_params, types)?;
+                return Ok(None);
+            }
+            // `test "name" { ... }`, `comptime { ... }`, `usingnamespace x;`
+            "test" | "comptime" | "usingnamespace" => {
+                skip_statement(cursor);
+                return Ok(None);
+            }
+            _ => {}
+        }
+    }
+    if cursor.is_punct(';') {
+        cursor.advance();
+        return Ok(None);
+    }
+
+    // Comptime fields are compile-time constants, not data
+    let comptime = cursor.eat_ident("comptime");
+    let name = if cursor.eat_punct('@') {
+        // `@"quoted name"`
+        match cursor.advance() {
+            Some(token) if token.kind == TokenKind::Str => token.text,
+            _ => return Err(cursor.unexpected("quoted identifier")),
+        }
+    } else {
+        cursor.expect_ident()?
+    };
+
+    let field_type = if cursor.eat_punct(':') {
+        if is_container(cursor) {
+            let anonymous = format!("{}.{}", outer, name);
+            parse_container(cursor, &anonymous, type_params, types)?;
+            ZigFieldType::Named(anonymous, Vec::new())
+        } else {
+            parse_type(cursor, type_params)?
+        }
+    } else {
+        // Union variant without a payload
+        ZigFieldType::Void
+    };
+    if cursor.eat_ident("align") && cursor.is_punct('(') {
+        cursor.skip_balanced('(', ')');
+    }
+    let has_default = cursor.eat_punct('=');
+    if has_default {
+        skip_value(cursor);
+    }
+    if !cursor.eat_punct(',') && !cursor.is_punct('}') {
+        return Err(cursor.unexpected("`,` or `}`"));
+    }
+    Ok(Some(ZigField { name, field_type, has_default }).filter(|_| !comptime))
+}
+
+/// Skip a default value up to the `,` or `}` that ends the field
+fn skip_value(cursor: &mut Cursor) {
+    while let Some(token) = cursor.peek() {
+        if token.kind != TokenKind::Punct {
+            cursor.advance();
+            continue;
+        }
+        match token.text.as_str() {
+            "," | "}" | ";" => return,
+            "{" => cursor.skip_balanced('{', '}'),
+            "(" => cursor.skip_balanced('(', ')'),
+            "[" => cursor.skip_balanced('[', ']'),
+            _ => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+fn skip_qualifiers(cursor: &mut Cursor) {
+    loop {
+        if QUALIFIERS.iter().any(|q| cursor.is_ident(q)) {
+            cursor.advance();
+        } else if cursor.is_ident("align") && cursor.is_punct_at(1, '(') {
+            cursor.advance();
+            cursor.skip_balanced('(', ')');
+        } else {
+            return;
+        }
+    }
+}
+
+fn is_byte(ty: &ZigFieldType) -> bool {
+    *ty == ZigFieldType::UInt(8)
+}
+
+fn parse_type(cursor: &mut Cursor, type_params: &[String]) -> Result<ZigFieldType, String> {
+    if cursor.eat_punct('?') {
+        return Ok(ZigFieldType::Optional(Box::new(parse_type(cursor, type_params)?)));
+    }
+    if cursor.eat_punct('!') {
+        return Ok(ZigFieldType::ErrorUnion(None, Box::new(parse_type(cursor, type_params)?)));
+    }
+    if cursor.eat_punct('*') {
+        skip_qualifiers(cursor);
+        return Ok(ZigFieldType::Pointer(Box::new(parse_type(cursor, type_params)?)));
+    }
+    if cursor.eat_punct('[') {
+        return parse_bracketed(cursor, type_params);
+    }
+    if cursor.is_ident("fn") {
+        cursor.advance();
+        if cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        if cursor.eat_ident("callconv") && cursor.is_punct('(') {
+            cursor.skip_balanced('(', ')');
+        }
+        parse_type(cursor, type_params)?;
+        return Ok(ZigFieldType::Function);
+    }
+    // Inline error set: `error{ OutOfMemory }!T`
+    if cursor.is_ident("error") && cursor.is_punct_at(1, '{') {
+        cursor.advance();
+        cursor.skip_balanced('{', '}');
+        cursor.expect_punct('!')?;
+        return Ok(ZigFieldType::ErrorUnion(None, Box::new(parse_type(cursor, type_params)?)));
+    }
+
+    let mut name = cursor.expect_ident()?;
+    while cursor.is_punct('.') && cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+        cursor.advance();
+        name.push('.');
+        name.push_str(&cursor.expect_ident()?);
+    }
+    let mut args = Vec::new();
+    if cursor.eat_punct('(') {
+        while !cursor.eat_punct(')') {
+            if cursor.at_end() {
+                return Err(cursor.unexpected("`)`"));
+            }
+            // Non-type arguments (`std.BoundedArray(u8, 16)`) are dropped
+            if cursor.peek().is_some_and(|t| t.kind == TokenKind::Number) {
+                cursor.advance();
+            } else {
+                args.push(parse_type(cursor, type_params)?);
+            }
+            if !cursor.eat_punct(',') && !cursor.is_punct(')') {
+                return Err(cursor.unexpected("`,` or `)`"));
+            }
+        }
+    }
+
+    // `FileError!T`, `anyerror!T`
+    if cursor.eat_punct('!') {
+        let set = Some(name).filter(|n| n != "anyerror");
+        return Ok(ZigFieldType::ErrorUnion(set, Box::new(parse_type(cursor, type_params)?)));
+    }
+
+    let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+    let mut args = args.into_iter();
+    let mut next = || Box::new(args.next().unwrap_or(ZigFieldType::Any));
+    Ok(match simple.as_str() {
+        "bool" => ZigFieldType::Bool,
+        "void" | "noreturn" => ZigFieldType::Void,
+        "usize" => ZigFieldType::USize,
+        "isize" => ZigFieldType::ISize,
+        "c_char" => ZigFieldType::Int(8),
+        "c_short" => ZigFieldType::Int(16),
+        "c_ushort" => ZigFieldType::UInt(16),
+        "c_int" => ZigFieldType::Int(32),
+        "c_uint" => ZigFieldType::UInt(32),
+        "c_long" | "c_longlong" => ZigFieldType::Int(64),
+        "c_ulong" | "c_ulonglong" => ZigFieldType::UInt(64),
+        "c_longdouble" => ZigFieldType::Float(128),
+        "comptime_int" => ZigFieldType::Int(128),
+        "comptime_float" => ZigFieldType::Float(128),
+        "anytype" | "anyopaque" | "type" => ZigFieldType::Any,
+        "ArrayList" | "ArrayListUnmanaged" | "ArrayListAligned" | "BoundedArray" | "SegmentedList" | "MultiArrayList" => {
+            ZigFieldType::List(next())
+        }
+        "StringHashMap" | "StringHashMapUnmanaged" | "StringArrayHashMap" | "StringArrayHashMapUnmanaged" => {
+            ZigFieldType::Map(Box::new(ZigFieldType::String), next())
+        }
+        "AutoHashMap" | "AutoHashMapUnmanaged" | "AutoArrayHashMap" | "AutoArrayHashMapUnmanaged" | "HashMap" => {
+            let key = next();
+            ZigFieldType::Map(key, next())
+        }
+        _ if type_params.contains(&name) => ZigFieldType::TypeParam(name),
+        _ => match sized(&simple) {
+            Some(ty) => ty,
+            None => ZigFieldType::Named(name, args.collect()),
+        },
+    })
+}
+
+/// Arbitrary-width primitives: `u7`, `i128`, `f16`
+fn sized(name: &str) -> Option<ZigFieldType> {
+    let (prefix, bits) = name.split_at(1);
+    if bits.is_empty() || bits.starts_with('0') || !bits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let bits: u16 = bits.parse().ok()?;
+    match prefix {
+        "i" => Some(ZigFieldType::Int(bits)),
+        "u" => Some(ZigFieldType::UInt(bits)),
+        "f" if matches!(bits, 16 | 32 | 64 | 80 | 128) => Some(ZigFieldType::Float(bits)),
+        _ => None,
+    }
+}
+
+/// Everything after the `[` of a slice, array, or many-item pointer type
+fn parse_bracketed(cursor: &mut Cursor, type_params: &[String]) -> Result<ZigFieldType, String> {
+    let (many, c_pointer) = if cursor.eat_punct('*') {
+        (true, cursor.eat_ident("c"))
+    } else {
+        (false, false)
+    };
+    let mut length = None;
+    let mut inferred = false;
+    let mut sentinel = false;
+    while !cursor.eat_punct(']') {
+        let token = cursor.advance().ok_or_else(|| cursor.unexpected("`]`"))?;
+        match token.kind {
+            TokenKind::Punct if token.text == ":" => sentinel = true,
+            TokenKind::Number if !sentinel => length = token.text.parse().ok(),
+            TokenKind::Ident if token.text == "_" && !sentinel => inferred = true,
+            _ => {}
+        }
+    }
+    skip_qualifiers(cursor);
+    let element = parse_type(cursor, type_params)?;
+
+    let slice = !many && length.is_none() && !inferred;
+    Ok(if (slice || (many && sentinel)) && is_byte(&element) {
+        ZigFieldType::String
+    } else if c_pointer {
+        ZigFieldType::CPointer(Box::new(element))
+    } else if many {
+        ZigFieldType::ManyPointer(Box::new(element))
+    } else if slice {
+        ZigFieldType::Slice(Box::new(element))
+    } else {
+        ZigFieldType::Array(Box::new(element), length)
+    })
+}
+
+/// Calculate compatibility score with another type system
+pub fn compatibility_score(zig_type: &ZigType, target: &str) -> f32 {
+    average_fidelity(ir_fields(zig_type).iter().map(|field| ir::fidelity(&field.ty, target)))
+}
+
+/// Lower a Zig field type into the language-neutral IR
+pub fn to_ir(field_type: &ZigFieldType) -> IrType {
+    match field_type {
+        // Arbitrary widths round up to the next IR width
+        ZigFieldType::Int(bits) if *bits <= 8 => IrType::Primitive(PrimitiveType::I8),
+        ZigFieldType::Int(bits) if *bits <= 16 => IrType::Primitive(PrimitiveType::I16),
+        ZigFieldType::Int(bits) if *bits <= 32 => IrType::Primitive(PrimitiveType::I32),
+        ZigFieldType::Int(bits) if *bits <= 64 => IrType::Primitive(PrimitiveType::I64),
+        ZigFieldType::UInt(bits) if *bits <= 8 => IrType::Primitive(PrimitiveType::U8),
+        ZigFieldType::UInt(bits) if *bits <= 16 => IrType::Primitive(PrimitiveType::U16),
+        ZigFieldType::UInt(bits) if *bits <= 32 => IrType::Primitive(PrimitiveType::U32),
+        ZigFieldType::UInt(bits) if *bits <= 64 => IrType::Primitive(PrimitiveType::U64),
+        ZigFieldType::ISize => IrType::Primitive(PrimitiveType::I64),
+        ZigFieldType::USize => IrType::Primitive(PrimitiveType::U64),
+        ZigFieldType::Float(16 | 32) => IrType::Primitive(PrimitiveType::F32),
+        ZigFieldType::Float(64) => IrType::Primitive(PrimitiveType::F64),
+        ZigFieldType::Bool => IrType::Primitive(PrimitiveType::Bool),
+        ZigFieldType::Void => IrType::Primitive(PrimitiveType::Unit),
+        ZigFieldType::String => IrType::Primitive(PrimitiveType::String),
+        ZigFieldType::Optional(inner) | ZigFieldType::CPointer(inner) => {
+            IrType::Container(ContainerType::Option(Box::new(to_ir(inner))))
+        }
+        ZigFieldType::Array(element, Some(len)) => IrType::Container(ContainerType::Array(Box::new(to_ir(element)), *len)),
+        ZigFieldType::Slice(element) | ZigFieldType::Array(element, None) | ZigFieldType::List(element) => {
+            IrType::Container(ContainerType::Vec(Box::new(to_ir(element))))
+        }
+        // A non-null single-item pointer carries its pointee
+        ZigFieldType::Pointer(inner) => to_ir(inner),
+        // Error sets are named error codes; the global set is just a name
+        ZigFieldType::ErrorUnion(set, ok) => IrType::Container(ContainerType::Result(
+            Box::new(to_ir(ok)),
+            Box::new(match set {
+                Some(set) => IrType::Reference(set.rsplit('.').next().unwrap_or(set).to_string()),
+                None => IrType::Primitive(PrimitiveType::String),
+            }),
+        )),
+        ZigFieldType::Map(key, value) => IrType::Container(ContainerType::Map(Box::new(to_ir(key)), Box::new(to_ir(value)))),
+        // 128-bit integers, extended floats, unknown-length pointers, and
+        // function pointers have no IR form yet
+        ZigFieldType::Int(_)
+        | ZigFieldType::UInt(_)
+        | ZigFieldType::Float(_)
+        | ZigFieldType::ManyPointer(_)
+        | ZigFieldType::Function
+        | ZigFieldType::Any => IrType::Any,
+        ZigFieldType::Named(name, _) => IrType::Reference(name.rsplit('.').next().unwrap_or(name).to_string()),
+        ZigFieldType::TypeParam(name) => IrType::TypeParam(name.clone()),
+    }
+}
+
+/// Lower struct fields, or the payloads of union variants, into the IR
+pub fn ir_fields(zig_type: &ZigType) -> Vec<IrField> {
+    zig_type
+        .fields
+        .iter()
+        // A payload-less variant is only a tag
+        .filter(|field| zig_type.kind == ZigTypeKind::Struct || field.field_type != ZigFieldType::Void)
+        .map(|field| IrField::new(&field.name, to_ir(&field.field_type)))
+        .collect()
+}
+
+/// Integer width that depends on the target triple (`usize`, `isize`)
+pub fn platform_width(field_type: &ZigFieldType) -> Option<PlatformWidth> {
+    match field_type {
+        ZigFieldType::ISize | ZigFieldType::USize => Some(PlatformWidth::Pointer),
+        ZigFieldType::Optional(inner)
+        | ZigFieldType::Slice(inner)
+        | ZigFieldType::Array(inner, _)
+        | ZigFieldType::List(inner)
+        | ZigFieldType::Pointer(inner) => platform_width(inner),
+        _ => None,
+    }
+}
+
+/// Map Zig types to target language types
+pub fn map_to_target(field_type: &ZigFieldType, target: &str) -> String {
+    match target {
+        "rust" | "gleam" | "typescript" => render_type(&to_ir(field_type), target),
+        _ => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+const std = @import("std");
+
+/// A registered user
+pub const User = extern struct {
+    id: u64,
+    name: [*:0]const u8,
+    email: ?[]const u8 = null,
+    flags: u7 = 0,
+    scores: [4]f32,
+    comptime version: u8 = 1,
+
+    pub const Role = enum { admin, member };
+
+    pub fn deinit(self: *User) void {
+        _ = self;
+    }
+};
+
+pub const Event = union(enum) {
+    click: struct { x: i32, y: i32 },
+    key: u21,
+    tags: std.ArrayList([]const u8),
+    load: anyerror!*const User,
+    close,
+};
+
+pub fn Page(comptime T: type) type {
+    return struct {
+        items: []const T,
+        next: ?usize,
+    };
+}
+
+test "user" {
+    const user = User{ .id = 1 };
+}
+"#;
+
+    #[test]
+    fn test_analyze_containers() {
+        let types = analyze_zig_types(SOURCE).unwrap();
+        let names: Vec<_> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Event", "Event.click", "Page"]);
+
+        let user = &types[0];
+        assert_eq!(user.layout, ZigLayout::Extern);
+        let fields: Vec<_> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, vec!["id", "name", "email", "flags", "scores"]);
+        assert_eq!(user.fields[1].field_type, ZigFieldType::String);
+        assert_eq!(user.fields[2].field_type, ZigFieldType::Optional(Box::new(ZigFieldType::String)));
+        assert!(user.fields[3].has_default);
+        assert_eq!(user.fields[4].field_type, ZigFieldType::Array(Box::new(ZigFieldType::Float(32)), Some(4)));
+
+        let event = &types[1];
+        assert_eq!(event.kind, ZigTypeKind::TaggedUnion);
+        assert_eq!(event.fields[4].field_type, ZigFieldType::Void);
+        assert_eq!(
+            event.fields[3].field_type,
+            ZigFieldType::ErrorUnion(None, Box::new(ZigFieldType::Pointer(Box::new(ZigFieldType::Named("User".to_string(), vec![])))))
+        );
+        assert_eq!(ir_fields(event).len(), 4);
+
+        let page = &types[3];
+        assert_eq!(page.type_params, vec!["T"]);
+        assert_eq!(
+            page.fields[0].field_type,
+            ZigFieldType::Slice(Box::new(ZigFieldType::TypeParam("T".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_optionals_and_pointers() {
+        let s = analyze_zig_type("const S = struct { a: ?*const Node, b: [*c]u8, c: *Node, d: [*]u8, e: *const fn (i32) void };").unwrap();
+        let lowered: Vec<_> = ir_fields(&s).into_iter().map(|f| f.ty).collect();
+        let node = IrType::Reference("Node".to_string());
+        assert_eq!(lowered[0], IrType::Container(ContainerType::Option(Box::new(node.clone()))));
+        // A C pointer may be null; a single-item pointer may not
+        assert_eq!(lowered[1], IrType::Container(ContainerType::Option(Box::new(IrType::Primitive(PrimitiveType::U8)))));
+        assert_eq!(lowered[2], node);
+        assert_eq!(s.fields[3].field_type, ZigFieldType::ManyPointer(Box::new(ZigFieldType::UInt(8))));
+        assert_eq!(lowered[3..], [IrType::Any, IrType::Any]);
+    }
+
+    #[test]
+    fn test_slices_arrays_and_strings() {
+        let source = "const S = struct { a: []i32, b: [_]u8, c: [*:0]u8, d: [16]u8, e: [:0]const u8, f: []align(4) const u8 };";
+        let s = analyze_zig_type(source).unwrap();
+        let types: Vec<_> = s.fields.iter().map(|f| f.field_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                ZigFieldType::Slice(Box::new(ZigFieldType::Int(32))),
+                ZigFieldType::Array(Box::new(ZigFieldType::UInt(8)), None),
+                ZigFieldType::String,
+                ZigFieldType::Array(Box::new(ZigFieldType::UInt(8)), Some(16)),
+                ZigFieldType::String,
+                ZigFieldType::String,
+            ]
+        );
+        assert_eq!(map_to_target(&types[1], "rust"), "Vec<u8>");
+        assert_eq!(map_to_target(&types[3], "rust"), "[u8; 16]");
+    }
+
+    #[test]
+    fn test_lists_and_maps() {
+        let source = "const S = struct { a: std.StringHashMap(i32), b: std.AutoHashMap(u32, bool), c: std.BoundedArray(u8, 16), \
+                      d: std.ArrayListUnmanaged(User) };";
+        let s = analyze_zig_type(source).unwrap();
+        let types: Vec<_> = ir_fields(&s).into_iter().map(|f| render_type(&f.ty, "rust")).collect();
+        // The capacity of a bounded array is not part of its type
+        assert_eq!(types, vec!["HashMap<String, i32>", "HashMap<u32, bool>", "Vec<u8>", "Vec<User>"]);
+    }
+
+    #[test]
+    fn test_error_unions() {
+        let s = analyze_zig_type("const S = struct { a: FileError!u32, b: error{Oops}!void, c: !i32, d: std.fs.File.OpenError!usize };").unwrap();
+        assert_eq!(s.fields[0].field_type, ZigFieldType::ErrorUnion(Some("FileError".to_string()), Box::new(ZigFieldType::UInt(32))));
+        assert_eq!(s.fields[1].field_type, ZigFieldType::ErrorUnion(None, Box::new(ZigFieldType::Void)));
+        let types: Vec<_> = ir_fields(&s).into_iter().map(|f| render_type(&f.ty, "rust")).collect();
+        assert_eq!(types, vec!["Result<u32, FileError>", "Result<(), String>", "Result<i32, String>", "Result<u64, OpenError>"]);
+        assert_eq!(platform_width(&s.fields[3].field_type), None);
+    }
+
+    #[test]
+    fn test_unions_and_layouts() {
+        let source = "const U = union { a: u8, b: f32 };\nconst P = packed struct(u32) { a: u16, b: u16 };\nconst T = union(Tag) { a: u8, b };";
+        let types = analyze_zig_types(source).unwrap();
+        let shapes: Vec<_> = types.iter().map(|t| (t.kind, t.layout)).collect();
+        assert_eq!(
+            shapes,
+            vec![(ZigTypeKind::Union, ZigLayout::Auto), (ZigTypeKind::Struct, ZigLayout::Packed), (ZigTypeKind::TaggedUnion, ZigLayout::Auto)]
+        );
+        assert_eq!(types[2].fields[1].field_type, ZigFieldType::Void);
+        assert_eq!(ir_fields(&types[2]).len(), 1);
+    }
+
+    #[test]
+    fn test_quoted_names_and_platform_widths() {
+        let s = analyze_zig_type("const S = struct { @\"type\": u8, len: usize, offsets: ?[]isize };").unwrap();
+        assert_eq!(s.fields[0].name, "type");
+        assert_eq!(platform_width(&s.fields[0].field_type), None);
+        assert_eq!(platform_width(&s.fields[1].field_type), Some(PlatformWidth::Pointer));
+        assert_eq!(platform_width(&s.fields[2].field_type), Some(PlatformWidth::Pointer));
+    }
+
+    #[test]
+    fn test_wide_primitives_have_no_ir_form() {
+        let s = analyze_zig_type("const S = struct { a: i128, b: f80, c: f16, d: c_ushort };").unwrap();
+        let lowered: Vec<_> = ir_fields(&s).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            lowered,
+            vec![IrType::Any, IrType::Any, IrType::Primitive(PrimitiveType::F32), IrType::Primitive(PrimitiveType::U16)]
+        );
+        assert_eq!(compatibility_score(&s, "rust"), 0.75);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(analyze_zig_types("const S = struct { a: u8,").unwrap_err(), "expected `}`, found end of input");
+        assert_eq!(analyze_zig_types("const S = struct { a: u8 b: u8 };").unwrap_err(), "line 1: expected `,` or `}`, found `b`");
+        assert_eq!(analyze_zig_types("const S = struct { @ident: u8 };").unwrap_err(), "line 1: expected quoted identifier, found `:`");
+        assert_eq!(analyze_zig_types("const S = struct { a: std.ArrayList(u8 };").unwrap_err(), "line 1: expected `,` or `)`, found `}`");
+        assert_eq!(analyze_zig_types("const S = struct { a: };").unwrap_err(), "line 1: expected identifier, found `}`");
+        assert_eq!(analyze_zig_type("const x: u8 = 1;").unwrap_err(), "Type definition not found");
+    }
+
+    #[test]
+    fn test_integers_keep_their_width() {
+        let pixel = analyze_zig_type("pub const Pixel = struct { r: u8, dx: i16, bits: u3, w: u16, big: i33 };").unwrap();
+        let widths: Vec<_> = ir_fields(&pixel).into_iter().map(|f| f.ty).collect();
+        assert_eq!(
+            widths,
+            [PrimitiveType::U8, PrimitiveType::I16, PrimitiveType::U8, PrimitiveType::U16, PrimitiveType::I64].map(IrType::Primitive).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_score_against_rust() {
+        let types = analyze_zig_types(SOURCE).unwrap();
+        // `u7` rounds up to the next width
+        assert_eq!(map_to_target(&types[0].fields[3].field_type, "rust"), "u8");
+        assert_eq!(map_to_target(&types[1].fields[3].field_type, "rust"), "Result<User, String>");
+        assert_eq!(compatibility_score(&types[0], "rust"), 1.0);
+        assert_eq!(compatibility_score(&types[3], "rust"), 1.0);
+    }
+}
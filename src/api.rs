@@ -0,0 +1,2213 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Stable public API
+//!
+//! Build scripts and tools should depend on this module rather than on
+//! `analyzers`, `codegen`, or the per-language types, which change whenever a
+//! parser is reworked. Everything here follows semver: structs and enums are
+//! `#[non_exhaustive]` so fields, languages, and IR variants can be added in
+//! minor releases, and the IR is reached only through the re-exports below.
+//!
+//! ```
+//! use language_interop::api::{Config, Language, Registry};
+//!
+//! let registry = Registry::with_builtins();
+//! let config = Config::new(Language::TypeScript, Language::Rust);
+//! let report = registry
+//!     .report(&config, "interface User { id: number; name: string }")
+//!     .unwrap();
+//! assert_eq!(report.types[0].name, "User");
+//! assert_eq!(report.score(), 1.0);
+//! ```
+
+use std::fmt;
+
+use crate::analyzers::{
+    arrow_analyzer, asn1_analyzer, avro_analyzer, c_analyzer, capnp_analyzer, cddl_analyzer, cpp_analyzer, crystal_analyzer, csharp_analyzer, dart_analyzer, elixir_analyzer, erlang_analyzer, flatbuffers_analyzer, fsharp_analyzer, java_analyzer, json_schema_analyzer, lua_analyzer, nim_analyzer, ocaml_analyzer, openapi_analyzer, php_analyzer, proto_analyzer, rescript_analyzer, ruby_analyzer, rust_analyzer, sql_analyzer, swift_analyzer, thrift_analyzer, typescript_analyzer, xsd_analyzer, zig_analyzer, TransportClass,
+};
+use crate::codegen::newtypes::{newtype_inner, render_alias, render_newtype};
+use crate::codegen::{render_default, render_doc, render_type, rust_serde_as, rust_serde_default};
+use crate::ir;
+
+pub use crate::capabilities::{capabilities, gaps, Capability, CapabilitySet};
+pub use crate::codegen::newtypes::NewtypePolicy;
+pub use crate::codegen::polymorphic::PolyVariantPolicy;
+pub use crate::codegen::typescript_validators::ValidatorLibrary;
+pub use crate::integrity::{check_against, BrokenReference, Problem, Reference};
+pub use crate::ir::{
+    ContainerType, DefaultValue, Direction, Fidelity, IrField, IrType, IrVariant, Ownership, PolyBound, PrimitiveType, UnionShape, VariantTagging,
+};
+pub use crate::target::{PlatformWidth, TargetTriple};
+pub use crate::usage::{ReferenceSearch, Usage, UsageProbe};
+
+/// Languages known to the compiler, as analysis sources or scoring targets
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    ReScript,
+    TypeScript,
+    OCaml,
+    Elixir,
+    C,
+    Cpp,
+    CSharp,
+    Java,
+    Swift,
+    Zig,
+    Nim,
+    Crystal,
+    Erlang,
+    FSharp,
+    Dart,
+    Ruby,
+    Php,
+    Lua,
+    Protobuf,
+    JsonSchema,
+    OpenApi,
+    Avro,
+    Thrift,
+    Capnp,
+    FlatBuffers,
+    Sql,
+    Cddl,
+    Asn1,
+    Xsd,
+    Arrow,
+    Julia,
+    Gleam,
+}
+
+impl Language {
+    /// Every known language, in declaration order
+    pub const ALL: [Language; 33] = [
+        Language::Rust,
+        Language::ReScript,
+        Language::TypeScript,
+        Language::OCaml,
+        Language::Elixir,
+        Language::C,
+        Language::Cpp,
+        Language::CSharp,
+        Language::Java,
+        Language::Swift,
+        Language::Zig,
+        Language::Nim,
+        Language::Crystal,
+        Language::Erlang,
+        Language::FSharp,
+        Language::Dart,
+        Language::Ruby,
+        Language::Php,
+        Language::Lua,
+        Language::Protobuf,
+        Language::JsonSchema,
+        Language::OpenApi,
+        Language::Avro,
+        Language::Thrift,
+        Language::Capnp,
+        Language::FlatBuffers,
+        Language::Sql,
+        Language::Cddl,
+        Language::Asn1,
+        Language::Xsd,
+        Language::Arrow,
+        Language::Julia,
+        Language::Gleam,
+    ];
+
+    /// Lowercase identifier used in reports and the CLI (`"rescript"`)
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::ReScript => "rescript",
+            Language::TypeScript => "typescript",
+            Language::OCaml => "ocaml",
+            Language::Elixir => "elixir",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::CSharp => "csharp",
+            Language::Java => "java",
+            Language::Swift => "swift",
+            Language::Zig => "zig",
+            Language::Nim => "nim",
+            Language::Crystal => "crystal",
+            Language::Erlang => "erlang",
+            Language::FSharp => "fsharp",
+            Language::Dart => "dart",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+            Language::Lua => "lua",
+            Language::Protobuf => "protobuf",
+            Language::JsonSchema => "jsonschema",
+            Language::OpenApi => "openapi",
+            Language::Avro => "avro",
+            Language::Thrift => "thrift",
+            Language::Capnp => "capnp",
+            Language::FlatBuffers => "flatbuffers",
+            Language::Sql => "sql",
+            Language::Cddl => "cddl",
+            Language::Asn1 => "asn1",
+            Language::Xsd => "xsd",
+            Language::Arrow => "arrow",
+            Language::Julia => "julia",
+            Language::Gleam => "gleam",
+        }
+    }
+
+    /// Parse a lowercase identifier (`"typescript"`)
+    pub fn from_name(name: &str) -> Option<Language> {
+        Language::ALL.into_iter().find(|language| language.name() == name)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned by the API
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    language: Language,
+    message: String,
+}
+
+impl Error {
+    pub fn new(language: Language, message: impl Into<String>) -> Self {
+        Error {
+            language,
+            message: message.into(),
+        }
+    }
+
+    /// Language whose source, analyzer, or generator failed
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.language, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A type definition lowered into the IR
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDef {
+    pub name: String,
+    pub language: Language,
+    /// Where the definition starts in the source (`"line 3"`)
+    pub location: String,
+    /// Type parameters of a generic definition (`Pagination<T>`)
+    pub type_params: Vec<String>,
+    /// Const parameters of a definition (`Matrix<const N: usize>`), with
+    /// their types as written
+    pub const_params: Vec<(String, String)>,
+    /// Fields holding an array whose length is a const parameter, as
+    /// `(field, parameter)`; they lower to lists until
+    /// [`TypeDef::instantiate_consts`] binds the length
+    pub sized_fields: Vec<(String, String)>,
+    /// The type a `type` alias stands for; aliases have no fields of their own
+    pub aliased: Option<IrType>,
+    pub fields: Vec<IrField>,
+    /// Variants of a sum type, when the analyzer models them
+    pub variants: Vec<IrVariant>,
+    /// Fields declared to hold another type's key
+    pub references: Vec<Reference>,
+    /// Fields whose integer width depends on the target triple
+    pub platform_widths: Vec<(String, PlatformWidth)>,
+    /// Fields that hold a borrow in the source
+    pub borrows: Vec<Borrow>,
+    /// Fields whose wire form a library's serializer decides
+    pub wire_formats: Vec<WireFormat>,
+    /// Zero-sized fields kept in `fields` because serde writes them, as
+    /// `(field, type as written)`
+    pub zero_sized: Vec<(String, String)>,
+    /// Doc comment text, without comment markers
+    pub doc: Option<String>,
+    /// Set when `variants` are the tags of a polymorphic variant type
+    pub polymorphic: Option<PolyBound>,
+    /// Wire layout of `variants`, when the source side fixes one
+    pub tagging: Option<VariantTagging>,
+    /// Variants without a payload cross as their bare tag string whatever
+    /// the tagging, as ReScript compiles them
+    pub bare_units: bool,
+}
+
+impl TypeDef {
+    pub fn new(name: &str, language: Language, location: &str, fields: Vec<IrField>) -> Self {
+        TypeDef {
+            name: name.to_string(),
+            language,
+            location: location.to_string(),
+            type_params: Vec::new(),
+            const_params: Vec::new(),
+            sized_fields: Vec::new(),
+            aliased: None,
+            fields,
+            variants: Vec::new(),
+            references: Vec::new(),
+            platform_widths: Vec::new(),
+            borrows: Vec::new(),
+            wire_formats: Vec::new(),
+            zero_sized: Vec::new(),
+            doc: None,
+            polymorphic: None,
+            tagging: None,
+            bare_units: false,
+        }
+    }
+
+    pub fn with_variants(mut self, variants: Vec<IrVariant>) -> Self {
+        self.variants = variants;
+        self
+    }
+
+    pub fn with_references(mut self, references: Vec<Reference>) -> Self {
+        self.references = references;
+        self
+    }
+
+    pub fn with_platform_widths(mut self, platform_widths: Vec<(String, PlatformWidth)>) -> Self {
+        self.platform_widths = platform_widths;
+        self
+    }
+
+    pub fn with_type_params(mut self, type_params: Vec<String>) -> Self {
+        self.type_params = type_params;
+        self
+    }
+
+    pub fn with_const_params(mut self, const_params: Vec<(String, String)>, sized_fields: Vec<(String, String)>) -> Self {
+        self.const_params = const_params;
+        self.sized_fields = sized_fields;
+        self
+    }
+
+    pub fn with_borrows(mut self, borrows: Vec<Borrow>) -> Self {
+        self.borrows = borrows;
+        self
+    }
+
+    pub fn with_wire_formats(mut self, wire_formats: Vec<WireFormat>) -> Self {
+        self.wire_formats = wire_formats;
+        self
+    }
+
+    pub fn with_zero_sized(mut self, zero_sized: Vec<(String, String)>) -> Self {
+        self.zero_sized = zero_sized;
+        self
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
+
+    pub fn with_polymorphic(mut self, polymorphic: Option<PolyBound>) -> Self {
+        self.polymorphic = polymorphic;
+        self
+    }
+
+    pub fn with_tagging(mut self, tagging: Option<VariantTagging>, bare_units: bool) -> Self {
+        self.tagging = tagging;
+        self.bare_units = bare_units;
+        self
+    }
+
+    pub fn with_aliased(mut self, aliased: Option<IrType>) -> Self {
+        self.aliased = aliased;
+        self
+    }
+
+    /// This definition with its type parameters bound to `args`, for comparing
+    /// a generic type against a concrete one; the name is kept
+    pub fn instantiate(&self, args: &[IrType]) -> Result<TypeDef, Error> {
+        if args.len() != self.type_params.len() {
+            return Err(Error::new(
+                self.language,
+                format!("`{}` takes {} type arguments but {} were given", self.name, self.type_params.len(), args.len()),
+            ));
+        }
+        let bind = |fields: &[IrField]| -> Vec<IrField> {
+            fields
+                .iter()
+                .map(|field| IrField { ty: ir::substitute(&field.ty, &self.type_params, args), ..field.clone() })
+                .collect()
+        };
+        let mut instance = self.clone();
+        instance.type_params.clear();
+        instance.aliased = self.aliased.as_ref().map(|ty| ir::substitute(ty, &self.type_params, args));
+        instance.fields = bind(&self.fields);
+        for variant in &mut instance.variants {
+            variant.fields = bind(&variant.fields);
+        }
+        Ok(instance)
+    }
+
+    /// This definition with its const parameters bound to `values`, so the
+    /// fields in `sized_fields` become fixed-length arrays; the name is kept
+    pub fn instantiate_consts(&self, values: &[usize]) -> Result<TypeDef, Error> {
+        if values.len() != self.const_params.len() {
+            return Err(Error::new(
+                self.language,
+                format!("`{}` takes {} const arguments but {} were given", self.name, self.const_params.len(), values.len()),
+            ));
+        }
+        let mut instance = self.clone();
+        for (name, param) in &self.sized_fields {
+            let len = self.const_params.iter().position(|(const_param, _)| const_param == param).map(|i| values[i]);
+            if let (Some(len), Some(field)) = (len, instance.fields.iter_mut().find(|field| field.name == *name)) {
+                field.ty = sized(&field.ty, len);
+            }
+        }
+        instance.const_params.clear();
+        instance.sized_fields.clear();
+        Ok(instance)
+    }
+}
+
+/// `ty`, a sized field's list, as the array of `len` items it stands for
+fn sized(ty: &IrType, len: usize) -> IrType {
+    match ty {
+        IrType::Container(ContainerType::Vec(inner)) => IrType::Container(ContainerType::Array(inner.clone(), len)),
+        IrType::Container(ContainerType::Option(inner)) => IrType::Container(ContainerType::Option(Box::new(sized(inner, len)))),
+        _ => ty.clone(),
+    }
+}
+
+/// A source field holding a borrow (`&'a str`); no borrow survives the
+/// boundary, so bindings carry an owned copy instead
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Borrow {
+    pub field: String,
+    /// The field's type as written
+    pub borrowed: String,
+    /// The owned equivalent bindings use (`String`, `Vec<u8>`)
+    pub owned: String,
+}
+
+impl Borrow {
+    pub fn new(field: &str, borrowed: &str, owned: &str) -> Self {
+        Borrow { field: field.to_string(), borrowed: borrowed.to_string(), owned: owned.to_string() }
+    }
+}
+
+/// How a library type (`DateTime<Utc>`) is serialized, which both sides
+/// must agree on beyond what the IR type says
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireFormat {
+    pub field: String,
+    /// The field's type as written
+    pub written: String,
+    /// What crosses the wire (`an RFC 3339 string`)
+    pub format: String,
+}
+
+impl WireFormat {
+    pub fn new(field: &str, written: &str, format: &str) -> Self {
+        WireFormat { field: field.to_string(), written: written.to_string(), format: format.to_string() }
+    }
+}
+
+/// Parses source text of one language into IR type definitions
+pub trait Analyzer {
+    fn language(&self) -> Language;
+
+    /// Every type definition found in `source`
+    fn analyze(&self, source: &str) -> Result<Vec<TypeDef>, Error>;
+}
+
+/// Analyzer backed by one of the crate's own parsers
+struct BuiltinAnalyzer(Language);
+
+impl Analyzer for BuiltinAnalyzer {
+    fn language(&self) -> Language {
+        self.0
+    }
+
+    fn analyze(&self, source: &str) -> Result<Vec<TypeDef>, Error> {
+        let language = self.0;
+        let lower = |name: &str, location: &str, fields| TypeDef::new(name, language, location, fields);
+        let types = match language {
+            Language::Rust => rust_analyzer::analyze_rust_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let references = t
+                            .fields
+                            .iter()
+                            .filter_map(|field| Reference::parse(&field.name, &rust_analyzer::field_reference(field)?))
+                            .collect();
+                        let borrows = rust_analyzer::borrowed_fields(t)
+                            .iter()
+                            .map(|(field, borrowed, owned)| Borrow::new(field, borrowed, owned))
+                            .collect();
+                        let wire_formats = rust_analyzer::wire_format_fields(t)
+                            .iter()
+                            .map(|(field, written, format)| WireFormat::new(field, written, format))
+                            .collect();
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, rust_analyzer::platform_width(&f.field_type))));
+                        let const_params = t.const_params.iter().map(|(name, ty)| (name.clone(), rust_analyzer::rust_type_name(ty))).collect();
+                        lower(&t.name, &t.location, rust_analyzer::ir_fields(t))
+                            .with_type_params(t.type_params.clone())
+                            .with_const_params(const_params, rust_analyzer::sized_fields(t))
+                            .with_borrows(borrows)
+                            .with_wire_formats(wire_formats)
+                            .with_zero_sized(rust_analyzer::zero_sized_fields(t))
+                            .with_platform_widths(widths)
+                            .with_aliased(t.aliased.as_ref().map(rust_analyzer::to_ir))
+                            .with_variants(rust_analyzer::ir_variants(t))
+                            .with_tagging(rust_analyzer::tagging(t), false)
+                            .with_references(references)
+                            .with_doc(t.doc.clone())
+                    })
+                    .collect()
+            }),
+            Language::ReScript => rescript_analyzer::analyze_rescript_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        lower(&t.name, &t.location, rescript_analyzer::ir_fields(t))
+                            .with_type_params(t.type_params.clone())
+                            .with_aliased(t.aliased.as_ref().map(rescript_analyzer::to_ir))
+                            .with_variants(rescript_analyzer::ir_variants(t))
+                            .with_polymorphic(t.polymorphic)
+                            .with_tagging(rescript_analyzer::tagging(t), t.kind == rescript_analyzer::ReScriptTypeKind::Variant)
+                            .with_doc(t.doc.clone())
+                    })
+                    .collect()
+            }),
+            Language::TypeScript => typescript_analyzer::analyze_typescript_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        lower(&t.name, &t.location, typescript_analyzer::ir_fields(t))
+                            .with_type_params(t.type_params.clone())
+                            .with_aliased(typescript_analyzer::ir_alias(t))
+                            .with_variants(typescript_analyzer::ir_variants(t))
+                            .with_tagging(typescript_analyzer::tagging(t), false)
+                    })
+                    .collect()
+            }),
+            Language::OCaml => ocaml_analyzer::analyze_ocaml_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, ocaml_analyzer::ir_fields(t))).collect()),
+            Language::Elixir => elixir_analyzer::analyze_elixir_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, elixir_analyzer::ir_fields(t))).collect()),
+            Language::C => c_analyzer::analyze_c_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, c_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, c_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::Cpp => cpp_analyzer::analyze_cpp_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, cpp_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, cpp_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::CSharp => csharp_analyzer::analyze_csharp_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, csharp_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, csharp_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::Java => java_analyzer::analyze_java_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, java_analyzer::ir_fields(t))).collect()),
+            Language::Swift => swift_analyzer::analyze_swift_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, swift_analyzer::ir_fields(t))).collect()),
+            Language::Zig => zig_analyzer::analyze_zig_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, zig_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, zig_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::Nim => nim_analyzer::analyze_nim_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, nim_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, nim_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::Crystal => crystal_analyzer::analyze_crystal_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        let widths = platform_widths(t.fields.iter().map(|f| (&f.name, crystal_analyzer::platform_width(&f.field_type))));
+                        lower(&t.name, &t.location, crystal_analyzer::ir_fields(t)).with_platform_widths(widths)
+                    })
+                    .collect()
+            }),
+            Language::Erlang => erlang_analyzer::analyze_erlang_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, erlang_analyzer::ir_fields(t))).collect()),
+            Language::FSharp => fsharp_analyzer::analyze_fsharp_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, fsharp_analyzer::ir_fields(t))).collect()),
+            Language::Dart => dart_analyzer::analyze_dart_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, dart_analyzer::ir_fields(t))).collect()),
+            Language::Ruby => ruby_analyzer::analyze_ruby_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, ruby_analyzer::ir_fields(t))).collect()),
+            Language::Php => php_analyzer::analyze_php_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, php_analyzer::ir_fields(t))).collect()),
+            Language::Lua => lua_analyzer::analyze_lua_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, lua_analyzer::ir_fields(t))).collect()),
+            Language::Protobuf => proto_analyzer::analyze_proto_types(source).map(|types| {
+                types
+                    .iter()
+                    .map(|t| {
+                        // The JSON mapping writes enum values as their names
+                        let tagging = (t.kind == proto_analyzer::ProtoTypeKind::Enum).then_some(VariantTagging::External);
+                        lower(&proto_analyzer::ir_name(&t.name), &t.location, proto_analyzer::ir_fields(t))
+                            .with_variants(proto_analyzer::ir_variants(t))
+                            .with_tagging(tagging, false)
+                    })
+                    .collect()
+            }),
+            Language::JsonSchema => json_schema_analyzer::analyze_json_schema_types(source).map(|types| lower_schemas(&types, language)),
+            Language::OpenApi => openapi_analyzer::analyze_openapi_types(source).map(|types| lower_schemas(&types, language)),
+            Language::Avro => avro_analyzer::analyze_avro_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, avro_analyzer::ir_fields(t))).collect()),
+            Language::Thrift => thrift_analyzer::analyze_thrift_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, thrift_analyzer::ir_fields(t))).collect()),
+            Language::Capnp => capnp_analyzer::analyze_capnp_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, capnp_analyzer::ir_fields(t))).collect()),
+            Language::FlatBuffers => flatbuffers_analyzer::analyze_flatbuffers_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, flatbuffers_analyzer::ir_fields(t))).collect()),
+            Language::Sql => sql_analyzer::analyze_sql_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, sql_analyzer::ir_fields(t))).collect()),
+            Language::Cddl => cddl_analyzer::analyze_cddl_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, cddl_analyzer::ir_fields(t))).collect()),
+            Language::Asn1 => asn1_analyzer::analyze_asn1_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, asn1_analyzer::ir_fields(t))).collect()),
+            Language::Xsd => xsd_analyzer::analyze_xsd_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, xsd_analyzer::ir_fields(t))).collect()),
+            Language::Arrow => arrow_analyzer::analyze_arrow_types(source)
+                .map(|types| types.iter().map(|t| lower(&t.name, &t.location, arrow_analyzer::ir_fields(t))).collect()),
+            Language::Julia | Language::Gleam => Err("no analyzer available".to_string()),
+        };
+        types.map_err(|message| Error::new(language, message))
+    }
+}
+
+/// JSON Schema and OpenAPI schemas: objects with fields, string enums with
+/// a variant per value, and anything else as an alias of what it describes
+fn lower_schemas(types: &[json_schema_analyzer::JsonSchemaType], language: Language) -> Vec<TypeDef> {
+    types
+        .iter()
+        .map(|t| {
+            let tagging = (t.kind == json_schema_analyzer::JsonSchemaTypeKind::Enum).then_some(VariantTagging::External);
+            TypeDef::new(&t.name, language, &t.location, json_schema_analyzer::ir_fields(t))
+                .with_aliased(json_schema_analyzer::ir_alias(t))
+                .with_variants(json_schema_analyzer::ir_variants(t))
+                .with_tagging(tagging, false)
+        })
+        .collect()
+}
+
+/// Fields whose width depends on the target triple, from `(name, width)` pairs
+fn platform_widths<'a>(fields: impl Iterator<Item = (&'a String, Option<PlatformWidth>)>) -> Vec<(String, PlatformWidth)> {
+    fields.filter_map(|(name, width)| Some((name.clone(), width?))).collect()
+}
+
+/// Which languages to compare
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub source: Language,
+    pub target: Language,
+    /// Target triples the bindings ship to; reports flag fields whose fidelity changes on any of them
+    pub triples: Vec<TargetTriple>,
+}
+
+impl Config {
+    pub fn new(source: Language, target: Language) -> Self {
+        Config { source, target, triples: Vec::new() }
+    }
+
+    pub fn with_triples(mut self, triples: Vec<TargetTriple>) -> Self {
+        self.triples = triples;
+        self
+    }
+}
+
+/// Analyzers available to a run, one per source language
+#[derive(Default)]
+pub struct Registry {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl Registry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// A registry with an analyzer for every language the crate can parse
+    pub fn with_builtins() -> Self {
+        let mut registry = Registry::new();
+        for language in [
+            Language::Rust,
+            Language::ReScript,
+            Language::TypeScript,
+            Language::OCaml,
+            Language::Elixir,
+            Language::C,
+            Language::Cpp,
+            Language::CSharp,
+            Language::Java,
+            Language::Swift,
+            Language::Zig,
+            Language::Nim,
+            Language::Crystal,
+            Language::Erlang,
+            Language::FSharp,
+            Language::Dart,
+            Language::Ruby,
+            Language::Php,
+            Language::Lua,
+            Language::Protobuf,
+            Language::JsonSchema,
+            Language::OpenApi,
+            Language::Avro,
+            Language::Thrift,
+            Language::Capnp,
+            Language::FlatBuffers,
+            Language::Sql,
+            Language::Cddl,
+            Language::Asn1,
+            Language::Xsd,
+            Language::Arrow,
+        ] {
+            registry.register(Box::new(BuiltinAnalyzer(language)));
+        }
+        registry
+    }
+
+    /// Add an analyzer, replacing any existing one for the same language
+    pub fn register(&mut self, analyzer: Box<dyn Analyzer>) {
+        self.analyzers.retain(|existing| existing.language() != analyzer.language());
+        self.analyzers.push(analyzer);
+    }
+
+    pub fn analyzer(&self, language: Language) -> Option<&dyn Analyzer> {
+        self.analyzers
+            .iter()
+            .find(|analyzer| analyzer.language() == language)
+            .map(|analyzer| analyzer.as_ref())
+    }
+
+    /// Languages that can be analyzed
+    pub fn languages(&self) -> Vec<Language> {
+        self.analyzers.iter().map(|analyzer| analyzer.language()).collect()
+    }
+
+    /// Analyze `source` as `config.source` and score every type against `config.target`
+    pub fn report(&self, config: &Config, source: &str) -> Result<Report, Error> {
+        let analyzer = self
+            .analyzer(config.source)
+            .ok_or_else(|| Error::new(config.source, "no analyzer registered"))?;
+        let types = analyzer.analyze(source)?;
+        Ok(Report::new(config, &types))
+    }
+
+    /// Like [`Registry::report`], but only scores the types `probe` sees used
+    /// on the consuming side (and the types they reach); the rest are listed
+    /// in [`Report::unused`]
+    pub fn report_used(&self, config: &Config, source: &str, probe: &dyn UsageProbe) -> Result<Report, Error> {
+        let analyzer = self
+            .analyzer(config.source)
+            .ok_or_else(|| Error::new(config.source, "no analyzer registered"))?;
+        let types = analyzer.analyze(source)?;
+        let usage = crate::usage::analyze(&types, probe);
+        let used: Vec<TypeDef> = types.into_iter().filter(|def| usage.is_used(&def.name)).collect();
+        let mut report = Report::new(config, &used);
+        report.unused = usage.unused;
+        Ok(report)
+    }
+
+    /// Like [`Registry::report`], but looks up the types `source` refers to in
+    /// `project`, the project's other files: every type reached from `source`
+    /// is scored too, and project types it never reaches are left out
+    pub fn report_project<S: AsRef<str>>(&self, config: &Config, source: &str, project: &[S]) -> Result<Report, Error> {
+        let analyzer = self
+            .analyzer(config.source)
+            .ok_or_else(|| Error::new(config.source, "no analyzer registered"))?;
+        let mut types = analyzer.analyze(source)?;
+        let roots: Vec<String> = types.iter().map(|def| def.name.clone()).collect();
+        for file in project {
+            for def in analyzer.analyze(file.as_ref())? {
+                // The first definition of a name wins, so `source` shadows the project
+                let name = ir::normalize_name(&def.name);
+                if !types.iter().any(|known| ir::normalize_name(&known.name) == name) {
+                    types.push(def);
+                }
+            }
+        }
+        let is_root = |name: &str| roots.iter().any(|root| root == name);
+        let usage = crate::usage::analyze(&types, &is_root);
+        let reached: Vec<TypeDef> = types.into_iter().filter(|def| usage.is_used(&def.name)).collect();
+        Ok(Report::new(config, &reached))
+    }
+}
+
+/// Fidelity of one field in the target language
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldReport {
+    pub name: String,
+    pub ty: IrType,
+    pub fidelity: Fidelity,
+    /// One-way fields only round-trip in one direction, whatever their fidelity
+    pub direction: Direction,
+    /// Value the source side fills in when the field is missing
+    pub default: Option<DefaultValue>,
+}
+
+/// Compatibility of one type with the target language
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeReport {
+    pub name: String,
+    pub location: String,
+    pub score: f32,
+    pub transport: TransportClass,
+    pub fields: Vec<FieldReport>,
+    /// Bound of a polymorphic variant; an open one (`[> ...]`) counts as
+    /// one more mapping, lossy where the target can't keep the set open
+    pub polymorphic: Option<PolyBound>,
+}
+
+/// A field whose fidelity drops on one target triple
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TripleChange {
+    pub triple: TargetTriple,
+    pub type_name: String,
+    pub field: String,
+    pub width: PlatformWidth,
+    /// Width of the field on `triple`
+    pub bits: u32,
+    pub fidelity: Fidelity,
+}
+
+impl fmt::Display for TripleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}.{} is a {}-bit {} integer ({:?})",
+            self.triple, self.type_name, self.field, self.bits, self.width, self.fidelity
+        )
+    }
+}
+
+/// A field holding a trait object: behaviour rather than data, so no
+/// mapping can carry it across
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitObjectField {
+    pub type_name: String,
+    pub field: String,
+    pub trait_name: String,
+}
+
+impl fmt::Display for TraitObjectField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{} holds a `dyn {}`, which can't be serialized; erase it to an opaque handle, \
+             an ID into a registry of implementations, or an enum over them",
+            self.type_name, self.field, self.trait_name
+        )
+    }
+}
+
+/// A field holding a function: code rather than data, so it crosses as a
+/// callback the other side registers instead of as a value
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallbackField {
+    pub type_name: String,
+    pub field: String,
+    pub params: Vec<IrType>,
+    pub ret: IrType,
+}
+
+impl fmt::Display for CallbackField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(|param| render_type(param, "rust")).collect();
+        write!(
+            f,
+            "{}.{} holds a function `fn({}) -> {}`, which can't be serialized; register it as a callback: \
+             a C function pointer plus a context handle passed back on every call",
+            self.type_name,
+            self.field,
+            params.join(", "),
+            render_type(&self.ret, "rust")
+        )
+    }
+}
+
+/// Structured result of a compatibility run
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub source: Language,
+    pub target: Language,
+    pub types: Vec<TypeReport>,
+    /// Declared references whose target is missing or doesn't fit
+    pub broken_references: Vec<BrokenReference>,
+    /// Fields that lose fidelity on one of `Config::triples`
+    pub triple_changes: Vec<TripleChange>,
+    /// Types left out because nothing across the boundary uses them
+    pub unused: Vec<String>,
+    /// Borrowing fields, by type name, that bindings copy into owned values
+    pub borrows: Vec<(String, Borrow)>,
+    /// Fields no target can carry because they hold trait objects
+    pub trait_objects: Vec<TraitObjectField>,
+    /// Fields holding functions, which cross as registered callbacks
+    pub callbacks: Vec<CallbackField>,
+    /// Library-serialized fields, by type name, with the format they cross in
+    pub wire_formats: Vec<(String, WireFormat)>,
+    /// Zero-sized fields that still cross the wire, as `Type.field` and the
+    /// type as written
+    pub zero_sized: Vec<(String, String)>,
+}
+
+impl Report {
+    /// Score already-analyzed types against `config.target`
+    ///
+    /// A field holding another of `types` is only as faithful as the worst
+    /// field of that type, followed recursively
+    pub fn new(config: &Config, types: &[TypeDef]) -> Self {
+        let target = config.target.name();
+        let mut resolver = Resolver::new(types, target);
+        let scored = types
+            .iter()
+            .map(|def| {
+                let fields: Vec<FieldReport> = def
+                    .fields
+                    .iter()
+                    .map(|field| FieldReport {
+                        name: field.name.clone(),
+                        ty: field.ty.clone(),
+                        // A sized field scores as the fixed-length array it becomes
+                        fidelity: match def.sized_fields.iter().any(|(name, _)| *name == field.name) {
+                            true => resolver.fidelity(&sized(&field.ty, 0)),
+                            false => resolver.fidelity(&field.ty),
+                        },
+                        direction: field.direction,
+                        default: field.default.clone(),
+                    })
+                    .collect();
+                let score = match &def.aliased {
+                    Some(aliased) => resolver.fidelity(aliased).weight(),
+                    None => ir::average_fidelity(
+                        fields
+                            .iter()
+                            .map(|field| field.fidelity)
+                            .chain(def.polymorphic.map(|bound| bound.fidelity(target))),
+                    ),
+                };
+                TypeReport {
+                    name: def.name.clone(),
+                    location: def.location.clone(),
+                    score,
+                    transport: TransportClass::from_score(score),
+                    fields,
+                    polymorphic: def.polymorphic,
+                }
+            })
+            .collect();
+
+        Report {
+            source: config.source,
+            target: config.target,
+            types: scored,
+            broken_references: crate::integrity::check(types),
+            triple_changes: triple_changes(&config.triples, types),
+            unused: Vec::new(),
+            borrows: types
+                .iter()
+                .flat_map(|def| def.borrows.iter().map(|borrow| (def.name.clone(), borrow.clone())))
+                .collect(),
+            trait_objects: types
+                .iter()
+                .flat_map(|def| def.fields.iter().map(move |field| (def, field)))
+                .flat_map(|(def, field)| {
+                    ir::trait_objects(&field.ty).into_iter().map(move |trait_name| TraitObjectField {
+                        type_name: def.name.clone(),
+                        field: field.name.clone(),
+                        trait_name: trait_name.to_string(),
+                    })
+                })
+                .collect(),
+            callbacks: types
+                .iter()
+                .flat_map(|def| def.fields.iter().map(move |field| (def, field)))
+                .flat_map(|(def, field)| {
+                    ir::functions(&field.ty).into_iter().filter_map(move |function| match function {
+                        IrType::Function(params, ret) => Some(CallbackField {
+                            type_name: def.name.clone(),
+                            field: field.name.clone(),
+                            params: params.clone(),
+                            ret: (**ret).clone(),
+                        }),
+                        _ => None,
+                    })
+                })
+                .collect(),
+            wire_formats: types
+                .iter()
+                .flat_map(|def| def.wire_formats.iter().map(|format| (def.name.clone(), format.clone())))
+                .collect(),
+            zero_sized: types
+                .iter()
+                .flat_map(|def| def.zero_sized.iter().map(|(field, written)| (format!("{}.{}", def.name, field), written.clone())))
+                .collect(),
+        }
+    }
+
+    /// Average score over every type (1.0 when nothing was analyzed)
+    pub fn score(&self) -> f32 {
+        if self.types.is_empty() {
+            return 1.0;
+        }
+        self.types.iter().map(|t| t.score).sum::<f32>() / self.types.len() as f32
+    }
+
+    pub fn transport_class(&self) -> TransportClass {
+        TransportClass::from_score(self.score())
+    }
+}
+
+/// Fidelity of field types with references resolved into the analyzed types;
+/// names that resolve to nothing are at best lossy
+struct Resolver<'a> {
+    types: &'a [TypeDef],
+    target: &'a str,
+    /// Worst field fidelity of each type, once resolved
+    resolved: Vec<Option<Fidelity>>,
+    /// Types being resolved, so a cycle back into one adds nothing
+    active: Vec<usize>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(types: &'a [TypeDef], target: &'a str) -> Self {
+        Resolver { types, target, resolved: vec![None; types.len()], active: Vec::new() }
+    }
+
+    fn fidelity(&mut self, ty: &IrType) -> Fidelity {
+        let mut names = Vec::new();
+        crate::usage::referenced_names(ty, &mut names);
+        let indices: Vec<Option<usize>> = names
+            .into_iter()
+            .map(|name| {
+                let name = ir::normalize_name(name);
+                self.types.iter().position(|def| ir::normalize_name(&def.name) == name)
+            })
+            .collect();
+        indices.into_iter().fold(ir::fidelity(ty, self.target), |acc, i| match i {
+            Some(i) => acc.worst(self.type_fidelity(i)),
+            // Nothing vouches for the shape of a type that was never analyzed
+            None => acc.worst(Fidelity::Lossy),
+        })
+    }
+
+    fn type_fidelity(&mut self, i: usize) -> Fidelity {
+        if let Some(fidelity) = self.resolved[i] {
+            return fidelity;
+        }
+        if self.active.contains(&i) {
+            return Fidelity::Exact;
+        }
+        self.active.push(i);
+        let types = self.types;
+        let fidelity = types[i]
+            .fields
+            .iter()
+            .map(|field| &field.ty)
+            .chain(&types[i].aliased)
+            .fold(Fidelity::Exact, |acc, ty| acc.worst(self.fidelity(ty)));
+        self.active.pop();
+        self.resolved[i] = Some(fidelity);
+        fidelity
+    }
+}
+
+/// Platform-dependent fields that are narrower than the IR's 64 bits on a triple
+fn triple_changes(triples: &[TargetTriple], types: &[TypeDef]) -> Vec<TripleChange> {
+    let mut changes = Vec::new();
+    for triple in triples {
+        for def in types {
+            for (field, width) in &def.platform_widths {
+                let fidelity = crate::target::width_fidelity(*width, triple);
+                if fidelity != Fidelity::Exact {
+                    changes.push(TripleChange {
+                        triple: triple.clone(),
+                        type_name: def.name.clone(),
+                        field: field.clone(),
+                        width: *width,
+                        bits: triple.width_bits(*width),
+                        fidelity,
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} → {}: {:.1}% ({:?})", self.source, self.target, self.score() * 100.0, self.transport_class())?;
+        for report in &self.types {
+            writeln!(f, "  {} ({}): {:.1}% ({:?})", report.name, report.location, report.score * 100.0, report.transport)?;
+            for field in report.fields.iter().filter(|field| field.fidelity != Fidelity::Exact) {
+                writeln!(f, "    {}: {:?}", field.name, field.fidelity)?;
+            }
+            if let Some(fidelity) = report.polymorphic.map(|bound| bound.fidelity(self.target.name())).filter(|&fidelity| fidelity != Fidelity::Exact) {
+                writeln!(f, "    open to more tags: {:?}", fidelity)?;
+            }
+            for field in report.fields.iter().filter(|field| field.direction != Direction::Both) {
+                writeln!(f, "    {}: {}", field.name, field.direction)?;
+            }
+            for field in &report.fields {
+                if let Some(default) = &field.default {
+                    writeln!(f, "    {}: defaults to {}", field.name, default)?;
+                }
+            }
+        }
+        for broken in &self.broken_references {
+            writeln!(f, "  broken reference {}", broken)?;
+        }
+        for change in &self.triple_changes {
+            writeln!(f, "  on {}", change)?;
+        }
+        // The value survives, but the two sides no longer share it
+        for (type_name, borrow) in &self.borrows {
+            writeln!(
+                f,
+                "  {}.{} borrows `{}`: bound as an owned `{}` copy",
+                type_name, borrow.field, borrow.borrowed, borrow.owned
+            )?;
+        }
+        for field in &self.trait_objects {
+            writeln!(f, "  {}", field)?;
+        }
+        for field in &self.callbacks {
+            writeln!(f, "  {}", field)?;
+        }
+        for (type_name, format) in &self.wire_formats {
+            writeln!(f, "  {}.{} (`{}`) crosses as {}", type_name, format.field, format.written, format.format)?;
+        }
+        // Native bindings drop them; serde still writes them unless told to skip
+        for (field, written) in &self.zero_sized {
+            writeln!(f, "  {} (`{}`) is zero-sized: kept, since serde writes it", field, written)?;
+        }
+        if !self.unused.is_empty() {
+            writeln!(f, "  unused: {}", self.unused.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Emits type declarations for a target language from IR definitions;
+/// [`Generator::TARGETS`] lists the languages [`Generator::generate`] writes
+#[derive(Debug, Clone)]
+pub struct Generator {
+    target: Language,
+    newtypes: NewtypePolicy,
+    polymorphic: PolyVariantPolicy,
+    tagging: Option<VariantTagging>,
+}
+
+impl Generator {
+    /// Languages [`Generator::generate`] declares types in; the other
+    /// languages are analyzed and scored, but have no backend
+    pub const TARGETS: [Language; 8] = [
+        Language::Rust,
+        Language::ReScript,
+        Language::TypeScript,
+        Language::Gleam,
+        Language::Julia,
+        Language::Zig,
+        Language::JsonSchema,
+        Language::OpenApi,
+    ];
+
+    pub fn new(target: Language) -> Self {
+        Generator { target, newtypes: NewtypePolicy::default(), polymorphic: PolyVariantPolicy::default(), tagging: None }
+    }
+
+    /// Declare newtypes as aliases of what they wrap, or as branded types
+    pub fn with_newtypes(mut self, policy: NewtypePolicy) -> Self {
+        self.newtypes = policy;
+        self
+    }
+
+    /// Which tags the Rust enum of a polymorphic variant accepts
+    pub fn with_polymorphic(mut self, policy: PolyVariantPolicy) -> Self {
+        self.polymorphic = policy;
+        self
+    }
+
+    /// Lay out every sum type as serde's `tagging` on the wire, instead of
+    /// the layout its source gives it (externally tagged when it gives none)
+    pub fn with_tagging(mut self, tagging: VariantTagging) -> Self {
+        self.tagging = Some(tagging);
+        self
+    }
+
+    pub fn target(&self) -> Language {
+        self.target
+    }
+
+    /// One declaration per type, separated by blank lines, then one per
+    /// ad-hoc union a target without them has to declare; ReScript structs
+    /// form one recursive group, and schema languages get their schemas
+    pub fn generate(&self, types: &[TypeDef]) -> Result<String, Error> {
+        match self.target {
+            Language::JsonSchema | Language::OpenApi => return self.generate_schemas(types),
+            Language::Protobuf => return Err(Error::new(self.target, "proto schemas need a package and a lockfile: see `generate_proto`")),
+            target if !Self::TARGETS.contains(&target) => {
+                let targets: Vec<&str> = Self::TARGETS.iter().map(|target| target.name()).collect();
+                return Err(Error::new(target, format!("no generator available; declarations are generated for {}", targets.join(", "))));
+            }
+            _ => {}
+        }
+        // Records may then name each other in any order
+        let records = match self.target {
+            Language::ReScript => crate::codegen::rescript::render_records(types),
+            _ => None,
+        };
+        if let Some(records) = records {
+            let mut declarations = vec![records];
+            declarations.extend(self.lowered_unions(types)?);
+            return Ok(declarations.join("\n"));
+        }
+        let mut declarations: Vec<String> = types
+            .iter()
+            .map(|def| self.declaration(def, types))
+            .collect::<Result<_, _>>()?;
+        declarations.extend(self.lowered_unions(types)?);
+        Ok(declarations.join("\n"))
+    }
+
+    /// The enum every distinct ad-hoc union in `types` lowers to, untagged so
+    /// it reads the bare values a native-union producer writes; none for
+    /// TypeScript, which spells unions inline
+    fn lowered_unions(&self, types: &[TypeDef]) -> Result<Vec<String>, Error> {
+        if self.target == Language::TypeScript {
+            return Ok(Vec::new());
+        }
+        let reached = types
+            .iter()
+            .flat_map(|def| def.fields.iter().chain(def.variants.iter().flat_map(|variant| &variant.fields)))
+            .map(|field| &field.ty)
+            .chain(types.iter().filter_map(|def| def.aliased.as_ref()))
+            .flat_map(ir::unions);
+        let mut lowered: Vec<crate::codegen::unions::LoweredUnion> = Vec::new();
+        for members in reached {
+            let union = crate::codegen::unions::lower_union(members, None, crate::codegen::unions::UnionTagging::default());
+            if !lowered.iter().any(|seen| seen.name == union.name) {
+                lowered.push(union);
+            }
+        }
+        lowered
+            .iter()
+            .map(|union| {
+                union
+                    .render(self.target.name())
+                    .ok_or_else(|| Error::new(self.target, format!("no declaration for the ad-hoc union `{}`", union.name)))
+            })
+            .collect()
+    }
+
+    /// A `<Name>Patch` companion per type, every field optional, with a merge function
+    pub fn generate_patches(&self, types: &[TypeDef]) -> Result<String, Error> {
+        let patches: Vec<String> = types
+            .iter()
+            .map(|def| {
+                crate::codegen::patch::generate_patch(def, self.target.name())
+                    .ok_or_else(|| Error::new(self.target, "no patch generator available"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(patches.join("\n"))
+    }
+
+    /// Idiomatic starting definitions for a side that doesn't exist yet: each
+    /// type with a constructor or guard and a JSON codec, headed by the
+    /// transport class the new side starts at
+    pub fn generate_scaffold(&self, source: Language, types: &[TypeDef]) -> Result<String, Error> {
+        let target = self.target.name();
+        let header = crate::codegen::scaffold::scaffold_header(target, types)
+            .ok_or_else(|| Error::new(self.target, "no scaffold generator available"))?;
+        let definitions: Vec<String> = types
+            .iter()
+            .map(|def| {
+                crate::codegen::scaffold::generate_scaffold(def, types, target)
+                    .ok_or_else(|| Error::new(self.target, "no scaffold generator available"))
+            })
+            .collect::<Result<_, _>>()?;
+        let score = ir::average_fidelity(types.iter().flat_map(|def| &def.fields).map(|field| ir::fidelity(&field.ty, target)));
+        let mut code = format!(
+            "// Scaffolded from {} {} type(s): {:.1}% fidelity ({:?})\n",
+            types.len(),
+            source,
+            score * 100.0,
+            ir::TransportClass::from_score(score)
+        );
+        if !header.is_empty() {
+            code.push('\n');
+            code.push_str(&header);
+        }
+        for definition in definitions {
+            code.push('\n');
+            code.push_str(&definition);
+        }
+        Ok(code)
+    }
+
+    /// Bindings to structs compiled from `source` into the JavaScript module at
+    /// `module`: ReScript records and externals for Rust types, which
+    /// [`Generator::generate_binding_exports`] fills in, or Gleam types decoded
+    /// from `@external` functions of Rust or ReScript types
+    pub fn generate_bindings(&self, source: Language, module: &str, types: &[TypeDef]) -> Result<String, Error> {
+        match (self.target, source) {
+            (Language::ReScript, Language::Rust) => {}
+            (Language::Gleam, Language::Rust | Language::ReScript) => {
+                return crate::codegen::gleam::render_module(types, source, module)
+                    .ok_or_else(|| Error::new(self.target, "bindings cover structs only"));
+            }
+            _ => return Err(Error::new(self.target, "bindings are generated for ReScript from Rust, and for Gleam from Rust or ReScript")),
+        }
+        let records = crate::codegen::rescript::render_records(types)
+            .ok_or_else(|| Error::new(self.target, "bindings cover structs only"))?;
+        let mut code = format!("// Bindings to {} Rust type(s) exported from {}\n\n{}", types.len(), module, records);
+        for def in types {
+            code.push('\n');
+            code.push_str(&crate::codegen::rescript::render_externals(def, module));
+        }
+        Ok(code)
+    }
+
+    /// The `#[wasm_bindgen]` functions that [`Generator::generate_bindings`]
+    /// declares as externals, to compile into the Rust crate
+    pub fn generate_binding_exports(&self, types: &[TypeDef]) -> String {
+        let mut code = "use serde::Serialize;\nuse wasm_bindgen::prelude::*;\n".to_string();
+        for def in types {
+            code.push('\n');
+            code.push_str(&crate::codegen::rescript::render_wasm_exports(def));
+        }
+        code
+    }
+
+    /// Like [`Generator::generate`], but types that score Economy or Wheelbarrow
+    /// get tiered bindings: native fields where the mapping is exact, lossless
+    /// string encodings for the rest, and a comment recording each decision
+    pub fn generate_tiered(&self, source: Language, types: &[TypeDef]) -> Result<String, Error> {
+        let declarations: Vec<String> = types
+            .iter()
+            .map(|def| {
+                let plan = crate::codegen::tiers::plan_tiers(def, self.target.name());
+                if !plan.needs_tiers() {
+                    return self.declaration(def, types);
+                }
+                Ok(plan.document(source) + &self.declaration(&plan.apply(def), types)?)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(declarations.join("\n"))
+    }
+
+    /// Julia or Zig structs that keep const parameters as parameters of the
+    /// type; other targets need [`TypeDef::instantiate_consts`] first
+    pub fn generate_sized_bindings(&self, types: &[TypeDef]) -> Result<String, Error> {
+        let bindings: Vec<String> = types
+            .iter()
+            .map(|def| {
+                crate::codegen::sized::generate_sized_binding(def, self.target.name())
+                    .ok_or_else(|| Error::new(self.target, "sized bindings are generated for Julia and Zig structs"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(bindings.join("\n"))
+    }
+
+    /// A `.d.ts` of `types` as their JSON arrives, for TypeScript consumers
+    /// of a side compiled from `source`
+    pub fn generate_declarations(&self, source: Language, types: &[TypeDef]) -> Result<String, Error> {
+        if self.target != Language::TypeScript {
+            return Err(Error::new(self.target, "declarations are generated for TypeScript"));
+        }
+        Ok(format!("// Declarations for {} {} type(s), as their JSON arrives\n\n{}", types.len(), source, self.generate(types)?))
+    }
+
+    /// Runtime validators for [`Generator::generate_declarations`], written
+    /// for `library` and importing the declarations from `module`
+    pub fn generate_validators(&self, library: ValidatorLibrary, module: &str, types: &[TypeDef]) -> Result<String, Error> {
+        if self.target != Language::TypeScript {
+            return Err(Error::new(self.target, "validators are generated for TypeScript"));
+        }
+        let laid_out: Vec<TypeDef> = types.iter().map(|def| self.laid_out(def)).collect();
+        Ok(crate::codegen::typescript_validators::render_validators(&laid_out, library, self.newtypes, module))
+    }
+
+    /// Schemas of `types` in the target's schema language: a JSON Schema
+    /// document per type, one per line, or an OpenAPI 3.1 components fragment
+    pub fn generate_schemas(&self, types: &[TypeDef]) -> Result<String, Error> {
+        match self.target {
+            Language::JsonSchema => {
+                let documents: Vec<String> = types.iter().map(|def| crate::codegen::json_schema::generate_json_schema(def, types)).collect();
+                Ok(documents.join("\n"))
+            }
+            Language::OpenApi => Ok(crate::codegen::openapi::generate_components(types)),
+            _ => Err(Error::new(self.target, "schemas are generated for JSON Schema and OpenAPI")),
+        }
+    }
+
+    /// `spec`, an OpenAPI 3.1 document, with the schemas of `types` merged
+    /// into its components
+    pub fn merge_components(&self, spec: &str, types: &[TypeDef]) -> Result<String, Error> {
+        if self.target != Language::OpenApi {
+            return Err(Error::new(self.target, "components are merged into OpenAPI specs"));
+        }
+        crate::codegen::openapi::merge_components(spec, types).map_err(|msg| Error::new(self.target, msg))
+    }
+
+    /// proto3 schema of `types` in `package`, keeping the field numbers in
+    /// `lock` and recording the ones it hands out
+    pub fn generate_proto(&self, package: &str, lock: &mut crate::codegen::protobuf::ProtoLock, types: &[TypeDef]) -> Result<String, Error> {
+        if self.target != Language::Protobuf {
+            return Err(Error::new(self.target, "proto schemas are generated for Protobuf"));
+        }
+        Ok(crate::codegen::protobuf::generate_proto(types, package, lock))
+    }
+
+    /// GraphQL object and input types for `types`, with what GraphQL can't
+    /// express noted; no analyzer reads GraphQL, so any target will do
+    pub fn generate_graphql(&self, types: &[TypeDef]) -> crate::codegen::graphql::GraphqlSchema {
+        crate::codegen::graphql::generate_graphql(types)
+    }
+
+    /// JSON codecs written to the wire layout of `types`: hand-written serde
+    /// impls for Rust, encoders and decoders matching serde_json for ReScript
+    pub fn generate_codecs(&self, types: &[TypeDef]) -> Result<String, Error> {
+        match self.target {
+            Language::Rust => Ok(crate::codegen::serde_impls::generate_serde(types)),
+            Language::ReScript => {
+                crate::codegen::rescript_codecs::render_codecs(types)
+                    .ok_or_else(|| Error::new(self.target, "codecs cover structs whose fields serde_json and ReScript both carry exactly"))
+            }
+            _ => Err(Error::new(self.target, "codecs are generated for Rust and ReScript")),
+        }
+    }
+
+    /// `def` with the wire tagging its variants are generated in
+    fn laid_out(&self, def: &TypeDef) -> TypeDef {
+        match &self.tagging {
+            _ if def.variants.is_empty() => def.clone(),
+            Some(tagging) => def.clone().with_tagging(Some(tagging.clone()), false),
+            None if def.tagging.is_none() => def.clone().with_tagging(Some(VariantTagging::External), false),
+            None => def.clone(),
+        }
+    }
+
+    /// `def` declared in the target; `types` are the definitions it is
+    /// generated alongside, so fields recursing through them can be boxed
+    fn declaration(&self, def: &TypeDef, types: &[TypeDef]) -> Result<String, Error> {
+        let target = self.target.name();
+        let special = match (&def.aliased, newtype_inner(def)) {
+            (Some(aliased), _) => Some(render_alias(&def.name, &def.type_params, aliased, target)),
+            (None, Some(inner)) => Some(render_newtype(def, inner, self.newtypes, target)),
+            (None, None) => None,
+        };
+        let doc = def.doc.as_ref().map(|doc| render_doc(doc, target, "")).unwrap_or_default();
+        if let Some(code) = special {
+            return code.map(|code| doc + &code).ok_or_else(|| Error::new(self.target, "no generator available"));
+        }
+        if !def.variants.is_empty() {
+            let laid_out = self.laid_out(def);
+            let code = match self.target {
+                Language::Rust => crate::codegen::polymorphic::render_rust_enum(&laid_out, self.polymorphic)
+                    .or_else(|| crate::codegen::variants::render_rust_enum(&laid_out, false)),
+                Language::TypeScript => crate::codegen::variants::render_typescript_union(&laid_out),
+                Language::Gleam => crate::codegen::variants::render_gleam_type(&laid_out),
+                Language::ReScript => Some(
+                    crate::codegen::variants::render_rescript_variant(&laid_out)
+                        .ok_or_else(|| Error::new(self.target, "no ReScript variant compiles to this wire layout"))?,
+                ),
+                _ => None,
+            };
+            if let Some(code) = code {
+                return Ok(doc + &code);
+            }
+        }
+        match self.target {
+            // Records render their own docs
+            Language::ReScript => {
+                return crate::codegen::rescript::render_records(std::slice::from_ref(def))
+                    .ok_or_else(|| Error::new(self.target, "no generator available"));
+            }
+            Language::Julia | Language::Zig => {
+                let code = crate::codegen::sized::generate_sized_binding(def, target)
+                    .ok_or_else(|| Error::new(self.target, "no generator available"))?;
+                return Ok(doc + &code);
+            }
+            _ => {}
+        }
+        let name = crate::codegen::pascal_case(&def.name);
+        let params = crate::codegen::render_type_params(&def.type_params, target);
+        let field = |field: &IrField| (field.name.clone(), render_type(&field.ty, target), field.direction);
+
+        let code = match self.target {
+            Language::Rust => {
+                let serde_as = def.fields.iter().any(|field| rust_serde_as(&field.ty).is_some());
+                let mut code = format!(
+                    "{}#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {}{} {{\n",
+                    if serde_as { "#[serde_with::serde_as]\n" } else { "" },
+                    name,
+                    params
+                );
+                let mut functions = String::new();
+                for (ir_field, (field, ty, direction)) in def.fields.iter().zip(def.fields.iter().map(field)) {
+                    if let Some(doc) = &ir_field.doc {
+                        code.push_str(&render_doc(doc, target, "    "));
+                    }
+                    match direction {
+                        Direction::Both => {}
+                        Direction::SerializeOnly => code.push_str("    #[serde(skip_deserializing)]\n"),
+                        Direction::DeserializeOnly => code.push_str("    #[serde(skip_serializing)]\n"),
+                    }
+                    if let Some((attribute, function)) = rust_serde_default(&def.name, ir_field) {
+                        code.push_str(&format!("    {}\n", attribute));
+                        functions.extend(function.map(|function| format!("\n{}", function)));
+                    }
+                    if let Some(adapter) = rust_serde_as(&ir_field.ty) {
+                        code.push_str(&format!("    #[serde_as(as = \"{}\")]\n", adapter));
+                    }
+                    // serde needs its `rc` feature for the shared pointers
+                    let ty = match ir_field.ownership {
+                        Ownership::Owned => crate::codegen::recursion::render_rust_field(&ir_field.ty, def, types),
+                        Ownership::Boxed => format!("Box<{}>", ty),
+                        Ownership::Shared => format!("std::rc::Rc<{}>", ty),
+                        Ownership::AtomicShared => format!("std::sync::Arc<{}>", ty),
+                    };
+                    code.push_str(&format!("    pub {}: {},\n", crate::codegen::snake_case(&field), ty));
+                }
+                code.push_str("}\n");
+                code.push_str(&functions);
+                code
+            }
+            Language::Gleam => {
+                let fields: Vec<String> = def
+                    .fields
+                    .iter()
+                    .map(field)
+                    .map(|(field, ty, _)| format!("{}: {}", crate::codegen::snake_case(&field), ty))
+                    .collect();
+                // Gleam has no field attributes or field docs, so field docs, one-way
+                // fields, fixed lengths and defaults are documented on the type
+                let docs = def.fields.iter().filter_map(|field| {
+                    Some(render_doc(&format!("`{}`: {}", crate::codegen::snake_case(&field.name), field.doc.as_ref()?), target, ""))
+                });
+                let directions = def
+                    .fields
+                    .iter()
+                    .filter(|field| field.direction != Direction::Both)
+                    .map(|field| format!("/// `{}` is {}\n", crate::codegen::snake_case(&field.name), field.direction));
+                let lengths = def.fields.iter().filter_map(|field| {
+                    fixed_length(&field.ty)
+                        .map(|len| format!("/// `{}` holds exactly {} items\n", crate::codegen::snake_case(&field.name), len))
+                });
+                let defaults = def.fields.iter().filter_map(|field| {
+                    let default = field.default.as_ref()?;
+                    let value = render_default(default, &field.ty, "gleam").unwrap_or_else(|| default.to_string());
+                    Some(format!("/// `{}` defaults to {}\n", crate::codegen::snake_case(&field.name), value))
+                });
+                let notes: String = docs.chain(directions).chain(lengths).chain(defaults).collect();
+                format!("{notes}pub type {name}{params} {{\n  {name}({})\n}}\n", fields.join(", "))
+            }
+            Language::TypeScript => {
+                let mut code = format!("export interface {}{} {{\n", name, params);
+                for (ir_field, (field, ty, direction)) in def.fields.iter().zip(def.fields.iter().map(field)) {
+                    let mut notes: Vec<String> = ir_field.doc.iter().cloned().collect();
+                    if let Some(len) = fixed_length(&ir_field.ty) {
+                        notes.push(format!("Exactly {} items", len));
+                    }
+                    if ir_field.ty == IrType::Primitive(PrimitiveType::Bytes) {
+                        notes.push("Base64-encoded bytes".to_string());
+                    }
+                    if let Some(value) = ir_field.default.as_ref().and_then(|default| render_default(default, &ir_field.ty, target)) {
+                        notes.push(format!("@default {}", value));
+                    }
+                    if !notes.is_empty() {
+                        code.push_str(&render_doc(&notes.join("\n"), target, "  "));
+                    }
+                    // Serialize-only fields arrive but are never sent back; deserialize-only
+                    // ones never arrive, and defaulted ones may be left out
+                    let optional = if direction == Direction::DeserializeOnly || ir_field.default.is_some() { "?" } else { "" };
+                    let line = match direction {
+                        Direction::SerializeOnly => format!("  readonly {}{}: {};\n", field, optional, ty),
+                        _ => format!("  {}{}: {};\n", field, optional, ty),
+                    };
+                    code.push_str(&line);
+                }
+                code.push_str("}\n");
+                code
+            }
+            other => return Err(Error::new(other, "no generator available")),
+        };
+        Ok(doc + &code)
+    }
+}
+
+/// Length of a fixed-size array field, which targets without one declare as a
+/// plain list
+fn fixed_length(ty: &IrType) -> Option<usize> {
+    match ty {
+        IrType::Container(ContainerType::Array(_, len)) => Some(*len),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_names_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(Language::from_name(language.name()), Some(language));
+        }
+        assert_eq!(Language::from_name("cobol"), None);
+    }
+
+    #[test]
+    fn test_report_scores_each_type() {
+        let registry = Registry::with_builtins();
+        let source = "pub struct Order { pub id: u64, pub total: f64 }\npub struct Tag { pub name: String }";
+        let report = registry.report(&Config::new(Language::Rust, Language::ReScript), source).unwrap();
+        assert_eq!(report.types.len(), 2);
+        assert_eq!(report.types[0].fields[0].fidelity, Fidelity::Lossy);
+        assert_eq!(report.types[0].score, 0.75);
+        assert_eq!(report.types[1].transport, TransportClass::Concorde);
+        assert!(report.to_string().contains("    id: Lossy"));
+
+        let missing = registry.report(&Config::new(Language::Julia, Language::Rust), "").unwrap_err();
+        assert_eq!(missing.language(), Language::Julia);
+    }
+
+    #[test]
+    fn test_report_flags_triple_specific_widths() {
+        let triples = ["aarch64-apple-ios", "x86_64-pc-windows-msvc", "wasm32-unknown-unknown"]
+            .iter()
+            .map(|triple| TargetTriple::parse(triple).unwrap())
+            .collect();
+        let config = Config::new(Language::C, Language::Rust).with_triples(triples);
+        let source = "struct chunk { size_t len; long offset; int32_t id; };";
+        let report = Registry::with_builtins().report(&config, source).unwrap();
+
+        let changes: Vec<String> = report.triple_changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "x86_64-pc-windows-msvc: chunk.offset is a 32-bit C long integer (Lossy)",
+                "wasm32-unknown-unknown: chunk.len is a 32-bit pointer-sized integer (Lossy)",
+                "wasm32-unknown-unknown: chunk.offset is a 32-bit C long integer (Lossy)",
+            ]
+        );
+        assert_eq!(report.score(), 1.0);
+        assert!(report.to_string().contains("  on x86_64-pc-windows-msvc: chunk.offset"));
+    }
+
+    #[test]
+    fn test_report_skips_unused_types() {
+        let config = Config::new(Language::Rust, Language::TypeScript);
+        let source = "pub struct Session { pub user: User }\npub struct User { pub id: i32 }\npub struct Metrics { pub hits: u64 }";
+        let consumer = ReferenceSearch::new(["const session: Session = await login();"]);
+        let report = Registry::with_builtins().report_used(&config, source, &consumer).unwrap();
+        let names: Vec<_> = report.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Session", "User"]);
+        assert_eq!(report.unused, vec!["Metrics"]);
+        // The lossy `u64` is gone with the type that held it
+        assert_eq!(report.score(), 1.0);
+        assert!(report.to_string().ends_with("  unused: Metrics\n"));
+    }
+
+    #[test]
+    fn test_report_notes_borrowed_fields() {
+        let config = Config::new(Language::Rust, Language::TypeScript);
+        let report = Registry::with_builtins().report(&config, "pub struct Doc<'a> { pub title: &'a str }").unwrap();
+        assert_eq!(report.score(), 1.0);
+        assert_eq!(report.borrows, vec![("Doc".to_string(), Borrow::new("title", "&'a str", "String"))]);
+        assert!(report.to_string().contains("  Doc.title borrows `&'a str`: bound as an owned `String` copy\n"));
+    }
+
+    #[test]
+    fn test_open_polymorphic_variants_score_lossy() {
+        let registry = Registry::with_builtins();
+        let source = "type status = [> #Active | #Suspended(string)]";
+        let rust = registry.report(&Config::new(Language::ReScript, Language::Rust), source).unwrap();
+        assert_eq!(rust.types[0].polymorphic, Some(PolyBound::AtLeast));
+        assert_eq!(rust.types[0].score, 0.75);
+        assert!(rust.to_string().contains("    open to more tags: Lossy\n"));
+        assert_eq!(registry.report(&Config::new(Language::ReScript, Language::ReScript), source).unwrap().types[0].score, 1.0);
+
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze(source).unwrap();
+        let closed = Generator::new(Language::Rust).with_polymorphic(PolyVariantPolicy::Closed).generate(&types).unwrap();
+        assert!(closed.contains("pub enum Status {\n    Tag(StatusTag),\n    Payload(StatusPayload),\n}\n"));
+        assert!(!closed.contains("Other"));
+    }
+
+    #[test]
+    fn test_tagging_is_honored_by_every_backend() {
+        let source = "#[serde(tag = \"type\")]\npub enum Event { Ping, #[serde(rename = \"moved\")] Moved { to: String } }";
+        let types = Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        assert_eq!(types[0].tagging, Some(VariantTagging::Internal("type".to_string())));
+        let generate = |generator: Generator| generator.generate(&types).unwrap();
+
+        assert!(generate(Generator::new(Language::Rust)).contains("#[serde(tag = \"type\")]\npub enum Event {\n    Ping,\n"));
+        assert!(generate(Generator::new(Language::TypeScript))
+            .contains("export type Event =\n  | { type: \"Ping\" }\n  | { type: \"moved\"; to: string };\n"));
+        assert!(generate(Generator::new(Language::Gleam))
+            .contains("    Moved(to: to) -> json.object([#(\"type\", json.string(\"moved\")), #(\"to\", json.string(to))])\n"));
+
+        let adjacent = VariantTagging::Adjacent("t".to_string(), "c".to_string());
+        assert!(generate(Generator::new(Language::Rust).with_tagging(adjacent.clone())).contains("#[serde(tag = \"t\", content = \"c\")]"));
+        assert!(generate(Generator::new(Language::TypeScript).with_tagging(adjacent)).contains("| { t: \"moved\"; c: { to: string } };"));
+        let untagged = generate(Generator::new(Language::Gleam).with_tagging(VariantTagging::Untagged));
+        assert!(untagged.contains("    Ping -> json.null()\n    Moved(to: to) -> json.object([#(\"to\", json.string(to))])\n"));
+    }
+
+    #[test]
+    fn test_docs_carry_into_bindings() {
+        let registry = Registry::with_builtins();
+        let source = "/// A registered user\npub struct User {\n    /// Login address\n    ///\n    /// Unique\n    pub email: String,\n    pub tags: [u8; 4],\n}";
+        let types = registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.starts_with("/** A registered user */\nexport interface User {\n  /**\n   * Login address\n   *\n   * Unique\n   */\n  email: string;\n"));
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.starts_with("/// A registered user\n/// `email`: Login address\n///\n/// Unique\n/// `tags` holds exactly 4 items\npub type User {"));
+
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze("/** A tag */\ntype tag = {\n  /** Shown to users */\n  label: string,\n}").unwrap();
+        let rust = Generator::new(Language::Rust).generate_scaffold(Language::ReScript, &types).unwrap();
+        assert!(rust.contains("\n/// A tag\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct Tag {\n    /// Shown to users\n    pub label: String,\n"));
+    }
+
+    #[test]
+    fn test_defaults_carry_into_bindings() {
+        let registry = Registry::with_builtins();
+        let source = "type config = {port: int, host: string, token?: string}\n\
+                      let make = (~port=8080, ~host=\"localhost\", ~token=?, ()) => {port, host, ?token}";
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze(source).unwrap();
+        let report = Report::new(&Config::new(Language::ReScript, Language::Rust), &types);
+        assert!(report.to_string().contains("    port: defaults to 8080\n    host: defaults to \"localhost\"\n    token: defaults to null\n"));
+
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("    #[serde(default = \"default_config_port\")]\n    pub port: i64,\n"));
+        assert!(rust.contains("    #[serde(default)]\n    pub token: Option<String>,\n"));
+        assert!(rust.contains("\nfn default_config_host() -> String {\n    \"localhost\".to_string()\n}\n"));
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.contains("  /** @default 8080 */\n  port?: number;\n"));
+        let gleam = Generator::new(Language::Gleam).generate_scaffold(Language::ReScript, &types).unwrap();
+        assert!(gleam.contains("  use port <- decode.optional_field(\"port\", 8080, decode.int)\n"));
+        assert!(gleam.contains("  use token <- decode.optional_field(\"token\", option.None, decode.optional(decode.string))\n"));
+        let guard = Generator::new(Language::TypeScript).generate_scaffold(Language::ReScript, &types).unwrap();
+        assert!(guard.contains(" &&\n    (v.port === undefined || typeof v.port === \"number\")"));
+    }
+
+    #[test]
+    fn test_report_notes_zero_sized_fields() {
+        let registry = Registry::with_builtins();
+        let source = "pub struct Id<T> { pub id: u64, pub marker: PhantomData<T>, #[serde(skip)] pub pin: PhantomPinned }";
+        let types = registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        let names: Vec<&str> = types[0].fields.iter().map(|field| field.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "marker"]);
+        let report = Report::new(&Config::new(Language::Rust, Language::Gleam), &types);
+        assert!(report.to_string().contains("  Id.marker (`PhantomData<T>`) is zero-sized: kept, since serde writes it\n"));
+        assert!(!report.to_string().contains("Id.pin"));
+    }
+
+    #[test]
+    fn test_report_notes_date_time_formats() {
+        let registry = Registry::with_builtins();
+        let source = "pub struct Event { pub at: chrono::DateTime<chrono::Utc> }";
+        let report = registry.report(&Config::new(Language::Rust, Language::TypeScript), source).unwrap();
+        assert_eq!(report.wire_formats, vec![("Event".to_string(), WireFormat::new("at", "DateTime<Utc>", "an RFC 3339 string"))]);
+        assert!(report.to_string().contains("  Event.at (`DateTime<Utc>`) crosses as an RFC 3339 string\n"));
+
+        let types = registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        let gleam = Generator::new(Language::Gleam).generate_scaffold(Language::Rust, &types).unwrap();
+        assert!(gleam.contains("import birl\n"));
+        assert!(gleam.contains("  Event(at: birl.Time)\n"));
+    }
+
+    #[test]
+    fn test_report_explains_trait_objects() {
+        let config = Config::new(Language::Rust, Language::Gleam);
+        let source = "pub struct Plugin { pub name: String, pub handler: Box<dyn Handler + Send>, pub hooks: Vec<&'static dyn Fn(i64) -> bool> }";
+        let report = Registry::with_builtins().report(&config, source).unwrap();
+        assert_eq!(report.types[0].fields[1].fidelity, Fidelity::Unsupported);
+        let traits: Vec<_> = report.trait_objects.iter().map(|field| (field.field.as_str(), field.trait_name.as_str())).collect();
+        assert_eq!(traits, vec![("handler", "Handler")]);
+        assert!(report.to_string().contains("  Plugin.handler holds a `dyn Handler`, which can't be serialized; erase it to an opaque handle"));
+        // Closures are functions, not trait objects
+        assert_eq!(report.callbacks[0].field, "hooks");
+        assert!(report.to_string().contains("  Plugin.hooks holds a function `fn(i64) -> bool`, which can't be serialized; register it as a callback"));
+    }
+
+    #[test]
+    fn test_report_classifies_callbacks() {
+        let config = Config::new(Language::ReScript, Language::Rust);
+        let source = "type widget = {label: string, onChange: int => string, onClose: option<unit => unit>}";
+        let report = Registry::with_builtins().report(&config, source).unwrap();
+        let callbacks: Vec<_> = report.callbacks.iter().map(|callback| callback.field.as_str()).collect();
+        assert_eq!(callbacks, vec!["onChange", "onClose"]);
+        assert_eq!(report.callbacks[0].params, vec![IrType::Primitive(PrimitiveType::I64)]);
+        assert_eq!(report.types[0].fields[1].fidelity, Fidelity::Unsupported);
+    }
+
+    #[test]
+    fn test_rust_from_rescript_references() {
+        let source = "type address = { city: string }\ntype user = { home: option<address>, past: array<address> }";
+        let types = Registry::with_builtins().analyzer(Language::ReScript).unwrap().analyze(source).unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("pub struct Address {\n"));
+        assert!(rust.contains("pub struct User {\n    pub home: Option<Address>,\n    pub past: Vec<Address>,\n}\n"));
+    }
+
+    #[test]
+    fn test_rust_boxes_recursive_fields() {
+        let source = "type rec tree = { label: string, parent: option<tree>, children: array<tree> }";
+        let types = Registry::with_builtins().analyzer(Language::ReScript).unwrap().analyze(source).unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("    pub parent: Option<Box<Tree>>,\n    pub children: Vec<Tree>,\n"));
+    }
+
+    #[test]
+    fn test_protobuf_nested_enums_lower_to_variants() {
+        let source = "syntax = \"proto3\";\nmessage User {\n  Status status = 1;\n  enum Status { STATUS_UNSPECIFIED = 0; ACTIVE = 1; }\n}\n";
+        let types = Registry::with_builtins().analyzer(Language::Protobuf).unwrap().analyze(source).unwrap();
+        let typescript = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(typescript.contains("export interface User {\n  status: UserStatus;\n}\n"));
+        assert!(typescript.contains("export type UserStatus =\n  | \"STATUS_UNSPECIFIED\"\n  | \"ACTIVE\";\n"));
+    }
+
+    #[test]
+    fn test_json_schema_enums_and_aliases_keep_their_kind() {
+        let source = r#"{"$defs": {"role": {"enum": ["admin", "member"]}, "user-id": {"type": "integer", "format": "uint64"}, "contact": {"oneOf": [{"type": "string"}, {"type": "integer", "format": "int32"}]}}}"#;
+        let types = Registry::with_builtins().analyzer(Language::JsonSchema).unwrap().analyze(source).unwrap();
+        let typescript = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(typescript.contains("export type Role =\n  | \"admin\"\n  | \"member\";\n"));
+        assert!(typescript.contains("export type UserId = number;\n"));
+        assert!(typescript.contains("export type Contact = string | number;\n"));
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("pub enum Role {\n    #[serde(rename = \"admin\")]\n    Admin,\n"));
+        assert!(rust.contains("pub type UserId = u64;\n"));
+    }
+
+    #[test]
+    fn test_const_generic_structs() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Matrix<const N: usize> { pub data: [f64; N] }").unwrap();
+        assert_eq!(types[0].sized_fields, vec![("data".to_string(), "N".to_string())]);
+        let zig = Generator::new(Language::Zig).generate_sized_bindings(&types).unwrap();
+        assert!(zig.starts_with("pub fn Matrix(comptime N: usize) type {\n"));
+        assert!(Generator::new(Language::TypeScript).generate_sized_bindings(&types).is_err());
+        // Until the length is bound, the field scores as the array it becomes
+        let report = Report::new(&Config::new(Language::Rust, Language::TypeScript), &types);
+        assert_eq!(report.types[0].fields[0].fidelity, Fidelity::Lossy);
+        let three = types[0].instantiate_consts(&[3]).unwrap();
+        assert!(Generator::new(Language::Rust).generate(&[three]).unwrap().contains("    pub data: [f64; 3],\n"));
+    }
+
+    #[test]
+    fn test_rescript_variants_from_rust() {
+        let source = "#[serde(tag = \"kind\")]\npub enum Shape { Circle { radius: f64 }, Square { side: f64 } }\npub enum Event { Click { x: i32 } }";
+        let types = Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        let generator = Generator::new(Language::ReScript);
+        assert_eq!(generator.generate(&types[..1]).unwrap(), "@tag(\"kind\")\ntype shape =\n  | Circle({radius: float})\n  | Square({side: float})\n");
+        assert!(generator.generate(&types[1..]).is_err());
+        let adjacent = generator.with_tagging(VariantTagging::Adjacent("t".to_string(), "c".to_string()));
+        assert!(adjacent.generate(&types[..1]).is_err());
+    }
+
+    #[test]
+    fn test_schema_and_codec_backends() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct User { pub id: u32, pub name: Option<String> }").unwrap();
+
+        let schemas = Generator::new(Language::JsonSchema).generate_schemas(&types).unwrap();
+        assert!(schemas.starts_with("{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"title\":\"User\","));
+        let openapi = Generator::new(Language::OpenApi);
+        assert!(openapi.generate_schemas(&types).unwrap().contains("\"components\": {\n    \"schemas\": {\n      \"User\": {"));
+        assert!(openapi.merge_components("openapi: 3.1.0\ninfo: {}\n", &types).unwrap().contains("\"User\": {"));
+
+        let mut lock = crate::codegen::protobuf::ProtoLock::default();
+        let proto = Generator::new(Language::Protobuf).generate_proto("shop", &mut lock, &types).unwrap();
+        assert!(proto.contains("message User {\n  uint32 id = 1;\n  optional string name = 2;\n}\n"));
+        assert_eq!(lock.messages[0].numbers.len(), 2);
+        assert!(Generator::new(Language::Rust).generate_proto("shop", &mut lock, &types).is_err());
+
+        assert!(Generator::new(Language::Rust).generate_graphql(&types).sdl.contains("input UserInput {\n"));
+
+        assert!(Generator::new(Language::Rust).generate_codecs(&types).unwrap().contains("impl Serialize for User {"));
+        assert!(Generator::new(Language::ReScript).generate_codecs(&types).unwrap().contains("let rec encodeUser = (value: user) =>"));
+        assert!(Generator::new(Language::Gleam).generate_codecs(&types).is_err());
+        assert!(Generator::new(Language::Rust).generate_schemas(&types).is_err());
+    }
+
+    #[test]
+    fn test_rescript_bindings_from_rust() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Tag { pub name: String }").unwrap();
+        let generator = Generator::new(Language::ReScript);
+        let bindings = generator.generate_bindings(Language::Rust, "./pkg/tags.js", &types).unwrap();
+        assert!(bindings.contains("type rec tag = {\n  name: string,\n}\n"));
+        assert!(bindings.contains("external tagToJson: tag => string = \"tag_to_json\""));
+        assert!(generator.generate_binding_exports(&types).contains("pub fn tag_from_json(json: &str)"));
+        assert!(Generator::new(Language::TypeScript).generate_bindings(Language::Rust, "./pkg/tags.js", &types).is_err());
+    }
+
+    #[test]
+    fn test_declarations_for_typescript_consumers() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze("type address = { city: string }\ntype user = { id: int, home: option<address> }").unwrap();
+        let dts = Generator::new(Language::TypeScript).generate_declarations(Language::ReScript, &types).unwrap();
+        assert!(dts.starts_with("// Declarations for 2 rescript type(s), as their JSON arrives\n\nexport interface Address {\n  city: string;\n}\n"));
+        assert!(dts.contains("export interface User {\n  id: number;\n  home: Address | null;\n}\n"));
+
+        let generic = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Page<T> { pub items: Vec<T> }").unwrap();
+        let dts = Generator::new(Language::TypeScript).generate_declarations(Language::Rust, &generic).unwrap();
+        assert!(dts.contains("export interface Page<T> {\n  items: Array<T>;\n}\n"));
+        assert!(Generator::new(Language::Rust).generate_declarations(Language::Rust, &generic).is_err());
+    }
+
+    #[test]
+    fn test_report_resolves_project_types() {
+        let config = Config::new(Language::Rust, Language::ReScript);
+        let project = ["pub struct Address { pub zip: u64 }\npub struct Metrics { pub hits: u32 }"];
+        let report = Registry::with_builtins()
+            .report_project(&config, "pub struct User { pub address: Address }", &project)
+            .unwrap();
+        let names: Vec<_> = report.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Address"]);
+        // `Address` is no longer opaque: its lossy `u64` reaches `User`
+        assert_eq!(report.types[0].fields[0].fidelity, Fidelity::Lossy);
+
+        // Unresolved, nothing vouches for it
+        let config = Config::new(Language::TypeScript, Language::Rust);
+        let report = Registry::with_builtins().report(&config, "interface User { address: Address; name: string }").unwrap();
+        let fidelity: Vec<_> = report.types[0].fields.iter().map(|field| field.fidelity).collect();
+        assert_eq!(fidelity, vec![Fidelity::Lossy, Fidelity::Exact]);
+    }
+
+    #[test]
+    fn test_generics_carry_their_arguments() {
+        let registry = Registry::with_builtins();
+        let types = registry
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze("pub struct Page<T> { pub items: Vec<T> }\npub enum Reply<E> { Done, Failed(E) }\npub struct Feed { pub page: Page<String>, pub reply: Reply<Page<u8>> }")
+            .unwrap();
+        assert_eq!(types[2].fields[0].ty, IrType::Generic("Page".to_string(), vec![IrType::Primitive(PrimitiveType::String)]));
+
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("pub struct Page<T> {\n    pub items: Vec<T>,\n}\n"));
+        assert!(rust.contains("pub enum Reply<E> {\n    Done,\n    Failed(E),\n}\n"));
+        assert!(rust.contains("    pub page: Page<String>,\n    pub reply: Reply<Page<u8>>,\n"));
+
+        let typescript = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(typescript.contains("export interface Page<T> {\n  items: Array<T>;\n}\n"));
+        assert!(typescript.contains("export type Reply<E> =\n  | \"Done\"\n  | { Failed: E };\n"));
+        assert!(typescript.contains("  page: Page<string>;\n  reply: Reply<Page<number>>;\n"));
+
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.contains("pub type Page(t) {\n  Page(items: List(t))\n}\n"));
+        assert!(gleam.contains("pub type Reply(e) {\n  Done\n  Failed(e)\n}\n"));
+        assert!(gleam.contains("pub fn reply_to_json(value: Reply(e), encode_e: fn(e) -> json.Json) -> json.Json {"));
+        assert!(gleam.contains("    Failed(value0) -> json.object([#(\"Failed\", encode_e(value0))])\n"));
+        assert!(gleam.contains("  Feed(page: Page(String), reply: Reply(Page(Int)))\n"));
+
+        let rescript = Generator::new(Language::ReScript).with_tagging(VariantTagging::Untagged).generate(&types[1..2]);
+        assert!(rescript.unwrap().contains("type reply<'e> =\n  | @as(null) Done\n  | Failed('e)\n"));
+        let records = crate::codegen::rescript::render_records(&[types[0].clone(), types[2].clone()]).unwrap();
+        assert!(records.contains("type rec page<'t> = {\n  items: array<'t>,\n}\n"));
+        assert!(records.contains("  page: page<string>,\n  reply: reply<page<int>>,\n"));
+
+        // ReScript's lowercase parameters are capitalized where the target's are
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze("type page<'a> = { items: array<'a> }\ntype feed = { page: page<string> }").unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("pub struct Page<A> {\n    pub items: Vec<A>,\n}\n"));
+        assert!(rust.contains("    pub page: Page<String>,\n"));
+        let typescript = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(typescript.contains("export interface Page<A> {\n  items: Array<A>;\n}\n"));
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.contains("pub type Page(a) {\n  Page(items: List(a))\n}\n"));
+        assert!(gleam.contains("  Feed(page: Page(String))\n"));
+    }
+
+    #[test]
+    fn test_generate_declares_lowered_unions() {
+        let types = Registry::with_builtins()
+            .analyzer(Language::TypeScript)
+            .unwrap()
+            .analyze("export interface Cell { id: string | number; value: number | boolean | string[]; alt?: string | number }")
+            .unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("    pub id: StringOrNumber,\n    pub value: NumberOrBoolOrStringList,\n    pub alt: Option<StringOrNumber>,\n"));
+        // Declared once however many fields use it, and untagged like the TypeScript values
+        assert_eq!(rust.matches("pub enum StringOrNumber {").count(), 1);
+        assert!(rust.contains("#[serde(untagged)]\npub enum StringOrNumber {\n    String(String),\n    Number(f64),\n}\n"));
+        assert!(rust.contains("#[serde(untagged)]\npub enum NumberOrBoolOrStringList {\n    Number(f64),\n    Bool(bool),\n    StringList(Vec<String>),\n}\n"));
+
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.contains("  Cell(id: StringOrNumber, value: NumberOrBoolOrStringList, alt: Option(StringOrNumber))\n"));
+        assert!(gleam.contains("pub type StringOrNumber {\n  StringOrNumberString(String)\n  StringOrNumberNumber(Float)\n}\n"));
+        assert!(gleam.contains("    NumberOrBoolOrStringListStringList(inner) -> json.array(inner, fn(item) { json.string(item) })\n"));
+
+        let typescript = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(!typescript.contains("StringOrNumber"));
+    }
+
+    /// A documented struct holding another, for the per-target generate tests
+    fn user_and_address() -> Vec<TypeDef> {
+        let source = "/// A signed-up user\npub struct User { pub id: u32, pub email: Option<String>, pub home: Address }\n\
+                      pub struct Address { pub zip: String }";
+        Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_generate_rust() {
+        let rust = Generator::new(Language::Rust).generate(&user_and_address()).unwrap();
+        assert!(rust.starts_with("/// A signed-up user\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct User {\n"));
+        assert!(rust.contains("    pub email: Option<String>,\n    pub home: Address,\n}\n\n"));
+    }
+
+    #[test]
+    fn test_generate_typescript() {
+        assert_eq!(
+            Generator::new(Language::TypeScript).generate(&user_and_address()).unwrap(),
+            "/** A signed-up user */\nexport interface User {\n  id: number;\n  email: string | null;\n  home: Address;\n}\n\n\
+             export interface Address {\n  zip: string;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_gleam() {
+        assert_eq!(
+            Generator::new(Language::Gleam).generate(&user_and_address()).unwrap(),
+            "/// A signed-up user\npub type User {\n  User(id: Int, email: Option(String), home: Address)\n}\n\n\
+             pub type Address {\n  Address(zip: String)\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_rescript() {
+        let generator = Generator::new(Language::ReScript);
+        assert_eq!(
+            generator.generate(&user_and_address()).unwrap(),
+            "/** A signed-up user */\ntype rec user = {\n  id: float,\n  email: Js.Nullable.t<string>,\n  home: address,\n}\n\n\
+             and address = {\n  zip: string,\n}\n"
+        );
+        // Alongside a variant, each record is declared on its own
+        let mut types = user_and_address();
+        types.extend(Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze("pub enum Role { Admin, Guest }").unwrap());
+        let code = generator.generate(&types).unwrap();
+        assert!(code.contains("type rec address = {\n  zip: string,\n}\n"));
+        assert!(code.contains("type role =\n"));
+    }
+
+    #[test]
+    fn test_generate_julia() {
+        assert_eq!(
+            Generator::new(Language::Julia).generate(&user_and_address()).unwrap(),
+            "\"\"\"\nA signed-up user\n\"\"\"\nstruct User\n    id::UInt32\n    email::Union{Nothing, String}\n    home::Address\nend\n\n\
+             struct Address\n    zip::String\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_zig() {
+        assert_eq!(
+            Generator::new(Language::Zig).generate(&user_and_address()).unwrap(),
+            "/// A signed-up user\npub const User = struct {\n    id: u32,\n    email: ?[]const u8,\n    home: Address,\n};\n\n\
+             pub const Address = struct {\n    zip: []const u8,\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_json_schema() {
+        let schemas = Generator::new(Language::JsonSchema).generate(&user_and_address()).unwrap();
+        assert_eq!(schemas.lines().count(), 2);
+        assert!(schemas.contains("\"title\":\"User\",\"description\":\"A signed-up user\",\"type\":\"object\""));
+        assert!(schemas.contains("\"home\":{\"$ref\":\"#/$defs/Address\"}"));
+    }
+
+    #[test]
+    fn test_generate_openapi() {
+        let components = Generator::new(Language::OpenApi).generate(&user_and_address()).unwrap();
+        assert!(components.starts_with("{\n  \"components\": {\n    \"schemas\": {\n      \"User\": {\n"));
+        assert!(components.contains("\"$ref\": \"#/components/schemas/Address\""));
+    }
+
+    #[test]
+    fn test_generate_without_a_backend() {
+        let types = user_and_address();
+        // A proto schema needs a package and a lockfile
+        let proto = Generator::new(Language::Protobuf).generate(&types).unwrap_err();
+        assert!(proto.message.contains("generate_proto"));
+        let cpp = Generator::new(Language::Cpp).generate(&types).unwrap_err();
+        assert!(cpp.message.ends_with("declarations are generated for rust, rescript, typescript, gleam, julia, zig, jsonschema, openapi"));
+        let role = Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze("pub enum Role { Admin, Guest }").unwrap();
+        assert!(Generator::new(Language::Zig).generate(&role).is_err());
+    }
+
+    #[test]
+    fn test_generate_covers_its_targets() {
+        let types = user_and_address();
+        for language in Language::ALL {
+            let generated = Generator::new(language).generate(&types);
+            assert_eq!(generated.is_ok(), Generator::TARGETS.contains(&language), "{}: {:?}", language, generated.err());
+        }
+    }
+
+    #[test]
+    fn test_instantiate_generic_definition() {
+        let registry = Registry::with_builtins();
+        let rust = registry.analyzer(Language::Rust).unwrap();
+        let page = &rust.analyze("pub struct Page<T> { pub items: Vec<T>, pub total: u32 }").unwrap()[0];
+        assert_eq!(page.type_params, vec!["T"]);
+
+        let rescript = registry.analyzer(Language::ReScript).unwrap();
+        let users = &rescript.analyze("type page = { items: array<user>, total: int }").unwrap()[0];
+        assert!(ir::compare_fields(&page.fields, &users.fields) < 1.0);
+        let instance = page.instantiate(&[IrType::Reference("User".to_string())]).unwrap();
+        assert_eq!(ir::compare_fields(&instance.fields, &users.fields), 1.0);
+        assert!(page.instantiate(&[]).is_err());
+    }
+
+    #[test]
+    fn test_custom_analyzer_replaces_builtin() {
+        struct Fixed;
+        impl Analyzer for Fixed {
+            fn language(&self) -> Language {
+                Language::Gleam
+            }
+            fn analyze(&self, _source: &str) -> Result<Vec<TypeDef>, Error> {
+                let id = IrField::new("id", IrType::Primitive(PrimitiveType::I64));
+                Ok(vec![TypeDef::new("user", Language::Gleam, "line 1", vec![id])])
+            }
+        }
+
+        let mut registry = Registry::with_builtins();
+        registry.register(Box::new(Fixed));
+        let types = registry.analyzer(Language::Gleam).unwrap().analyze("").unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("pub struct User {\n    pub id: i64,\n}"));
+        assert!(Generator::new(Language::Cpp).generate(&types).is_err());
+        let patch = Generator::new(Language::ReScript).generate_patches(&types).unwrap();
+        assert!(patch.starts_with("// Partial update of `user`"));
+    }
+
+    #[test]
+    fn test_scaffold_reports_starting_class() {
+        let registry = Registry::with_builtins();
+        let types = registry
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze("pub struct Order { pub id: i64, pub note: Option<String> }")
+            .unwrap();
+        let gleam = Generator::new(Language::Gleam).generate_scaffold(Language::Rust, &types).unwrap();
+        assert!(gleam.starts_with("// Scaffolded from 1 rust type(s): 100.0% fidelity (Concorde)\n\nimport gleam/dynamic/decode\n"));
+        assert!(gleam.contains("pub fn order_decoder() -> decode.Decoder(Order) {"));
+        assert!(Generator::new(Language::Julia).generate_scaffold(Language::Rust, &types).is_err());
+    }
+
+    #[test]
+    fn test_one_way_fields_reach_reports_and_declarations() {
+        let registry = Registry::with_builtins();
+        let source = r#"pub struct Account {
+    pub id: i64,
+    #[serde(skip_deserializing)]
+    pub created_at: String,
+    #[interop(direction = "deserialize_only")]
+    pub password: String,
+}"#;
+        let types = registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap();
+        let directions: Vec<Direction> = types[0].fields.iter().map(|field| field.direction).collect();
+        assert_eq!(directions, vec![Direction::Both, Direction::SerializeOnly, Direction::DeserializeOnly]);
+
+        let report = Report::new(&Config::new(Language::Rust, Language::Gleam), &types);
+        assert_eq!(report.types[0].fields[2].direction, Direction::DeserializeOnly);
+        assert!(report.to_string().contains("    created_at: serialize-only\n    password: deserialize-only\n"));
+
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("    #[serde(skip_deserializing)]\n    pub created_at: String,"));
+        assert!(rust.contains("    #[serde(skip_serializing)]\n    pub password: String,"));
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.contains("  readonly created_at: string;\n  password?: string;\n"));
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.starts_with("/// `created_at` is serialize-only\n/// `password` is deserialize-only\npub type Account {"));
+    }
+
+    #[test]
+    fn test_fixed_arrays_document_their_downgrade() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Key { pub bytes: [u8; 32], pub rest: [u16; LEN] }").unwrap();
+        assert_eq!(render_type(&types[0].fields[0].ty, "rust"), "[u8; 32]");
+        assert_eq!(types[0].fields[1].ty, IrType::Container(ContainerType::Vec(Box::new(IrType::Primitive(ir::PrimitiveType::U16)))));
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.contains("  /** Exactly 32 items */\n  bytes: Array<number>;\n  rest: Array<number>;\n"));
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.starts_with("/// `bytes` holds exactly 32 items\npub type Key {\n  Key(bytes: List(Int), rest: List(Int))"));
+    }
+
+    #[test]
+    fn test_rust_keeps_field_ownership() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Session { pub user: std::sync::Arc<User>, pub id: i32 }").unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("    pub user: std::sync::Arc<User>,\n    pub id: i32,\n"));
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.contains("  user: User;\n"));
+    }
+
+    #[test]
+    fn test_bytes_cross_as_base64() {
+        let registry = Registry::with_builtins();
+        let types = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Blob { pub data: Vec<u8>, pub chunks: Option<Vec<Vec<u8>>> }").unwrap();
+        let rust = Generator::new(Language::Rust).generate(&types).unwrap();
+        assert!(rust.contains("#[serde_with::serde_as]\n#[derive("));
+        assert!(rust.contains("    #[serde_as(as = \"serde_with::base64::Base64\")]\n    pub data: Vec<u8>,\n"));
+        assert!(rust.contains("    #[serde_as(as = \"Option<Vec<serde_with::base64::Base64>>\")]\n    pub chunks: Option<Vec<Vec<u8>>>,\n"));
+        let ts = Generator::new(Language::TypeScript).generate(&types).unwrap();
+        assert!(ts.contains("  /** Base64-encoded bytes */\n  data: string;\n  chunks: Array<string> | null;\n"));
+        let gleam = Generator::new(Language::Gleam).generate(&types).unwrap();
+        assert!(gleam.contains("Blob(data: BitArray, chunks: Option(List(BitArray)))"));
+    }
+}
@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Report archive and trend analysis
+//!
+//! Each run's [`Report`] can be stored as one JSON file in an archive
+//! directory. Loading the archive back gives a time series of fidelity and
+//! type counts per language pair, so interop debt paydown can be charted.
+//! A plain directory of JSON files (rather than a database) keeps the archive
+//! diffable, committable, and free of native dependencies.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::api::{Fidelity, Language, Report};
+use crate::json::{self, Json};
+use crate::TransportClass;
+
+/// Archive file format version, bumped on incompatible changes
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// One type of an archived run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedType {
+    pub name: String,
+    pub location: String,
+    pub score: f32,
+    /// Field name and fidelity in the target language
+    pub fields: Vec<(String, Fidelity)>,
+}
+
+/// A stored report, as read back from the archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedRun {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub source: Language,
+    pub target: Language,
+    pub score: f32,
+    pub types: Vec<ArchivedType>,
+}
+
+impl ArchivedRun {
+    pub fn from_report(report: &Report, timestamp: u64) -> Self {
+        ArchivedRun {
+            timestamp,
+            source: report.source,
+            target: report.target,
+            score: report.score(),
+            types: report
+                .types
+                .iter()
+                .map(|t| ArchivedType {
+                    name: t.name.clone(),
+                    location: t.location.clone(),
+                    score: t.score,
+                    fields: t.fields.iter().map(|f| (f.name.clone(), f.fidelity)).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Fields below `Exact`, split into (lossy, unsupported)
+    pub fn degraded_fields(&self) -> (usize, usize) {
+        let fidelities = self.types.iter().flat_map(|t| t.fields.iter().map(|(_, f)| *f));
+        fidelities.fold((0, 0), |(lossy, unsupported), fidelity| match fidelity {
+            Fidelity::Exact => (lossy, unsupported),
+            Fidelity::Lossy => (lossy + 1, unsupported),
+            Fidelity::Unsupported => (lossy, unsupported + 1),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        let types = self.types.iter().map(|t| {
+            let fields = t
+                .fields
+                .iter()
+                .map(|(name, fidelity)| Json::object([("name", name.as_str().into()), ("fidelity", format!("{:?}", fidelity).into())]));
+            Json::object([
+                ("name", t.name.as_str().into()),
+                ("location", t.location.as_str().into()),
+                ("score", (t.score as f64).into()),
+                ("fields", Json::Array(fields.collect())),
+            ])
+        });
+        Json::object([
+            ("version", (ARCHIVE_FORMAT_VERSION as usize).into()),
+            ("timestamp", (self.timestamp as f64).into()),
+            ("source", self.source.name().into()),
+            ("target", self.target.name().into()),
+            ("score", (self.score as f64).into()),
+            ("transport", format!("{:?}", TransportClass::from_score(self.score)).into()),
+            ("types", Json::Array(types.collect())),
+        ])
+        .to_string()
+    }
+
+    pub fn from_json(source: &str) -> Result<ArchivedRun, String> {
+        let value = json::parse(source)?;
+        let version = value.get("version").and_then(Json::as_f64).ok_or("missing `version`")?;
+        if version as u32 != ARCHIVE_FORMAT_VERSION {
+            return Err(format!("unsupported archive version {}", version));
+        }
+        let language = |key: &str| {
+            value
+                .get(key)
+                .and_then(Json::as_str)
+                .and_then(Language::from_name)
+                .ok_or_else(|| format!("missing or unknown `{}`", key))
+        };
+        let number = |json: &Json, key: &str| json.get(key).and_then(Json::as_f64).ok_or_else(|| format!("missing `{}`", key));
+        let string = |json: &Json, key: &str| {
+            json.get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing `{}`", key))
+        };
+
+        let mut types = Vec::new();
+        for t in value.get("types").and_then(Json::as_array).unwrap_or_default() {
+            let mut fields = Vec::new();
+            for field in t.get("fields").and_then(Json::as_array).unwrap_or_default() {
+                let fidelity = match field.get("fidelity").and_then(Json::as_str) {
+                    Some("Exact") => Fidelity::Exact,
+                    Some("Lossy") => Fidelity::Lossy,
+                    Some("Unsupported") => Fidelity::Unsupported,
+                    _ => return Err("missing or unknown `fidelity`".to_string()),
+                };
+                fields.push((string(field, "name")?, fidelity));
+            }
+            types.push(ArchivedType {
+                name: string(t, "name")?,
+                location: string(t, "location")?,
+                score: number(t, "score")? as f32,
+                fields,
+            });
+        }
+
+        Ok(ArchivedRun {
+            timestamp: number(&value, "timestamp")? as u64,
+            source: language("source")?,
+            target: language("target")?,
+            score: number(&value, "score")? as f32,
+            types,
+        })
+    }
+}
+
+/// Directory of archived runs, one `<timestamp>-<source>-<target>.json` file each
+#[derive(Debug, Clone)]
+pub struct Archive {
+    dir: PathBuf,
+}
+
+impl Archive {
+    /// Open an archive directory, creating it if needed
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Archive> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Archive { dir: dir.as_ref().to_path_buf() })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Store a report taken at `timestamp` and return the file it was written to
+    pub fn store(&self, report: &Report, timestamp: u64) -> io::Result<PathBuf> {
+        let run = ArchivedRun::from_report(report, timestamp);
+        let stem = format!("{}-{}-{}", timestamp, run.source, run.target);
+        let mut path = self.dir.join(format!("{}.json", stem));
+        let mut n = 1;
+        while path.exists() {
+            path = self.dir.join(format!("{}-{}.json", stem, n));
+            n += 1;
+        }
+        fs::write(&path, run.to_json())?;
+        Ok(path)
+    }
+
+    /// Every archived run, oldest first; unreadable files are reported, not skipped
+    pub fn runs(&self) -> io::Result<Vec<ArchivedRun>> {
+        let mut runs = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let run = ArchivedRun::from_json(&fs::read_to_string(&path)?)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), err)))?;
+            runs.push(run);
+        }
+        runs.sort_by_key(|run| run.timestamp);
+        Ok(runs)
+    }
+}
+
+/// One point of a trend series
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub timestamp: u64,
+    pub score: f32,
+    pub type_count: usize,
+    pub lossy_fields: usize,
+    pub unsupported_fields: usize,
+}
+
+/// Fidelity and type-count series for one language pair, oldest first
+pub fn trend(runs: &[ArchivedRun], source: Language, target: Language) -> Vec<TrendPoint> {
+    let mut points: Vec<TrendPoint> = runs
+        .iter()
+        .filter(|run| run.source == source && run.target == target)
+        .map(|run| {
+            let (lossy_fields, unsupported_fields) = run.degraded_fields();
+            TrendPoint {
+                timestamp: run.timestamp,
+                score: run.score,
+                type_count: run.types.len(),
+                lossy_fields,
+                unsupported_fields,
+            }
+        })
+        .collect();
+    points.sort_by_key(|point| point.timestamp);
+    points
+}
+
+/// Language pairs present in the archive, in first-seen order
+pub fn language_pairs(runs: &[ArchivedRun]) -> Vec<(Language, Language)> {
+    let mut pairs = Vec::new();
+    for run in runs {
+        if !pairs.contains(&(run.source, run.target)) {
+            pairs.push((run.source, run.target));
+        }
+    }
+    pairs
+}
+
+/// Width of the fidelity bar in [`render_trend_chart`]
+const CHART_WIDTH: usize = 40;
+
+/// Text chart of a trend: a summary line per metric, then one bar per run
+pub fn render_trend_chart(source: Language, target: Language, points: &[TrendPoint]) -> String {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return format!("{} → {}: no archived runs\n", source, target),
+    };
+
+    let mut chart = format!(
+        "{} → {}: {} run(s), {} … {}\n",
+        source,
+        target,
+        points.len(),
+        format_date(first.timestamp),
+        format_date(last.timestamp)
+    );
+    chart.push_str(&format!(
+        "  fidelity  {:.1}% → {:.1}% ({:+.1})\n",
+        first.score * 100.0,
+        last.score * 100.0,
+        (last.score - first.score) * 100.0
+    ));
+    chart.push_str(&format!(
+        "  types     {} → {} ({:+})\n",
+        first.type_count,
+        last.type_count,
+        last.type_count as i64 - first.type_count as i64
+    ));
+    chart.push_str(&format!(
+        "  degraded  {} → {} field(s)\n\n",
+        first.lossy_fields + first.unsupported_fields,
+        last.lossy_fields + last.unsupported_fields
+    ));
+
+    for point in points {
+        let filled = ((point.score.clamp(0.0, 1.0) * CHART_WIDTH as f32).round()) as usize;
+        chart.push_str(&format!(
+            "  {}  {}{}  {:5.1}%  {} type(s)  {} lossy  {} unsupported\n",
+            format_date(point.timestamp),
+            "█".repeat(filled),
+            "░".repeat(CHART_WIDTH - filled),
+            point.score * 100.0,
+            point.type_count,
+            point.lossy_fields,
+            point.unsupported_fields
+        ));
+    }
+    chart
+}
+
+/// `YYYY-MM-DD` (UTC) for a Unix timestamp
+pub fn format_date(timestamp: u64) -> String {
+    // Civil-from-days (Howard Hinnant), valid for every u64 timestamp in range
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Config, Registry};
+
+    fn report(source: &str) -> Report {
+        Registry::with_builtins()
+            .report(&Config::new(Language::Rust, Language::ReScript), source)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_store_and_reload() {
+        let dir = std::env::temp_dir().join(format!("lic-archive-{}", std::process::id()));
+        let archive = Archive::open(&dir).unwrap();
+        let before = report("pub struct Order { pub id: u64, pub total: f64 }");
+        let after = report("pub struct Order { pub id: i32, pub total: f64 }\npub struct Tag { pub name: String }");
+        archive.store(&after, 1_760_000_000 + 86_400).unwrap();
+        archive.store(&before, 1_760_000_000).unwrap();
+
+        let runs = archive.runs().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0], ArchivedRun::from_report(&before, 1_760_000_000));
+        assert_eq!(runs[0].degraded_fields(), (1, 0));
+        assert_eq!(language_pairs(&runs), vec![(Language::Rust, Language::ReScript)]);
+
+        let points = trend(&runs, Language::Rust, Language::ReScript);
+        let chart = render_trend_chart(Language::Rust, Language::ReScript, &points);
+        assert!(chart.contains("fidelity  75.0% → 100.0% (+25.0)"));
+        assert!(chart.contains("types     1 → 2 (+1)"));
+        assert!(chart.contains("  2025-10-09  ██████████████████████████████░░░░░░░░░░   75.0%"));
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_760_000_000), "2025-10-09");
+    }
+}
@@ -0,0 +1,621 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// `lic` - language interop compiler command line
+// Scores source files against a target language, archives the reports, charts
+// fidelity trends, lists per-pair capabilities, scaffolds missing sides,
+// generates bindings, schemas and codecs, audits generated FFI bindings, exchanges test data snapshots, exports an
+// interop bill of materials, runs the conformance matrix through real
+// toolchains, and re-checks references as files change
+
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use language_interop::api::{self, Config, Language, ReferenceSearch, Registry, TargetTriple};
+use language_interop::analyzers::{c_analyzer, rust_analyzer};
+use language_interop::archive::{self, Archive};
+use language_interop::bom::Bom;
+use language_interop::conformance::{CommandToolchain, Runner, Suite};
+use language_interop::codegen::audit::{self, SecurityProfile};
+use language_interop::codegen::protobuf::ProtoLock;
+use language_interop::codegen::snapshot;
+use language_interop::converters::ConverterRegistry;
+use language_interop::source::{self, LocalFiles, SourceProvider};
+use language_interop::watch::Scheduler;
+
+const USAGE: &str = "\
+usage:
+  lic help
+  lic report --source <lang> --target <lang> [--triple <triple>]... [--used-by <file>]... [--project <file>]... [--archive <dir>] [--from <src>] <file>...
+  lic trends --archive <dir> [--source <lang> --target <lang>]
+  lic capabilities --source <lang> --target <lang>
+  lic scaffold --source <lang> --target <lang> [--used-by <file>]... [--from <src>] <file>...
+  lic bindings --source rust|rescript --target rescript|gleam --module <js> [--exports] [--from <src>] <file>...
+  lic schema --source <lang> --target jsonschema|openapi|protobuf [--package <name>] [--lock <file>] [--into <spec>] [--from <src>] <file>...
+  lic graphql --source <lang> [--from <src>] <file>...
+  lic codecs --source <lang> --target rust|rescript [--from <src>] <file>...
+  lic audit --source <rust|c> [--paranoid] [--from <src>] <file>...
+  lic snapshots --source <lang> [--verify <jsonl>] [--from <src>] <file>...
+  lic bom --source <lang> --target <lang>... [--into <bom.json>] [--from <src>] <file>...
+  lic conformance --suite <lang>=<file>... --toolchain <lang>=<command>... [--jobs <n>] [--sandbox <dir>] [--into <matrix.json>] [--from <src>]
+  lic watch --source <lang> <file>...
+
+<src> is git:<revision>, tar:<archive>, stdin (read as the file `-`), or local";
+
+/// Parsed `--flag value` options and positional arguments
+#[derive(Default)]
+struct Args {
+    source: Option<Language>,
+    target: Option<Language>,
+    /// Every `--target`, for commands that take several
+    targets: Vec<Language>,
+    archive: Option<String>,
+    triples: Vec<TargetTriple>,
+    /// Consumer sources; when given, types none of them mention are skipped
+    used_by: Vec<String>,
+    /// Other project files that referenced types are looked up in
+    project: Vec<String>,
+    /// Emit hardened bindings after the audit findings
+    paranoid: bool,
+    /// JavaScript module that generated bindings import
+    module: Option<String>,
+    /// Print the Rust side of the bindings instead
+    exports: bool,
+    /// Protobuf package of a generated schema
+    package: Option<String>,
+    /// Field number lockfile, updated in place
+    lock: Option<String>,
+    /// Snapshot file to check instead of writing samples
+    verify: Option<String>,
+    /// Where the input files are read from; the working tree by default
+    from: Option<String>,
+    /// BOM, matrix or OpenAPI spec file to write instead of printing
+    into: Option<String>,
+    /// `<lang>=<file>` types for the conformance runner
+    suites: Vec<(Language, String)>,
+    /// `<lang>=<command>` toolchains for the conformance runner
+    toolchains: Vec<CommandToolchain>,
+    jobs: Option<usize>,
+    /// Where conformance sandboxes are created
+    sandbox: Option<String>,
+    files: Vec<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} needs a value", flag));
+        let language = |name: String| Language::from_name(&name).ok_or_else(|| format!("unknown language `{}`", name));
+        match arg.as_str() {
+            "--source" => parsed.source = Some(language(value("--source")?)?),
+            "--target" => {
+                let target = language(value("--target")?)?;
+                parsed.target = Some(target);
+                parsed.targets.push(target);
+            }
+            "--archive" => parsed.archive = Some(value("--archive")?),
+            "--triple" => parsed.triples.push(TargetTriple::parse(&value("--triple")?)?),
+            "--used-by" => parsed.used_by.push(value("--used-by")?),
+            "--project" => parsed.project.push(value("--project")?),
+            "--paranoid" => parsed.paranoid = true,
+            "--module" => parsed.module = Some(value("--module")?),
+            "--exports" => parsed.exports = true,
+            "--package" => parsed.package = Some(value("--package")?),
+            "--lock" => parsed.lock = Some(value("--lock")?),
+            "--verify" => parsed.verify = Some(value("--verify")?),
+            "--from" => parsed.from = Some(value("--from")?),
+            "--into" => parsed.into = Some(value("--into")?),
+            "--suite" => {
+                let spec = value("--suite")?;
+                let (name, file) = spec.split_once('=').ok_or_else(|| format!("expected <lang>=<file>, got `{}`", spec))?;
+                parsed.suites.push((language(name.to_string())?, file.to_string()));
+            }
+            "--toolchain" => parsed.toolchains.push(CommandToolchain::parse(&value("--toolchain")?)?),
+            "--jobs" => {
+                let jobs = value("--jobs")?;
+                parsed.jobs = Some(jobs.parse().map_err(|_| format!("--jobs needs a number, got `{}`", jobs))?);
+            }
+            "--sandbox" => parsed.sandbox = Some(value("--sandbox")?),
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{}`", flag)),
+            _ => parsed.files.push(arg),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Provider named by `--from`, or the working tree
+fn provider(from: &Option<String>) -> Result<Box<dyn SourceProvider>, String> {
+    match from {
+        Some(spec) => source::parse_provider(spec),
+        None => Ok(Box::new(LocalFiles::default())),
+    }
+}
+
+fn read(provider: &dyn SourceProvider, file: &str) -> Result<String, String> {
+    provider.read(file).map_err(|err| format!("{}: {}", provider.describe(file), err))
+}
+
+/// Whole-word search over the `--used-by` files, or `None` without any
+fn usage_probe(provider: &dyn SourceProvider, used_by: &[String]) -> Result<Option<ReferenceSearch>, String> {
+    if used_by.is_empty() {
+        return Ok(None);
+    }
+    let sources = used_by
+        .iter()
+        .map(|file| read(provider, file))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(ReferenceSearch::new(sources)))
+}
+
+fn report(args: Args) -> Result<(), String> {
+    let (source, target) = match (args.source, args.target) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return Err("report needs --source and --target".to_string()),
+    };
+    if args.files.is_empty() {
+        return Err("report needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let config = Config::new(source, target).with_triples(args.triples);
+    let mut combined = String::new();
+    for file in &args.files {
+        combined.push_str(&read(provider.as_ref(), file)?);
+        combined.push('\n');
+    }
+    if !args.project.is_empty() && !args.used_by.is_empty() {
+        return Err("--project and --used-by cannot be combined".to_string());
+    }
+    let project = args
+        .project
+        .iter()
+        .map(|file| read(provider.as_ref(), file))
+        .collect::<Result<Vec<_>, _>>()?;
+    let report = match usage_probe(provider.as_ref(), &args.used_by)? {
+        Some(probe) => registry.report_used(&config, &combined, &probe),
+        None if !project.is_empty() => registry.report_project(&config, &combined, &project),
+        None => registry.report(&config, &combined),
+    }
+    .map_err(|err| err.to_string())?;
+    print!("{}", report);
+
+    if let Some(dir) = args.archive {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let path = Archive::open(&dir)
+            .and_then(|archive| archive.store(&report, timestamp))
+            .map_err(|err| format!("{}: {}", dir, err))?;
+        println!("archived to {}", path.display());
+    }
+    Ok(())
+}
+
+fn trends(args: Args) -> Result<(), String> {
+    let dir = args.archive.ok_or("trends needs --archive")?;
+    let runs = Archive::open(&dir)
+        .and_then(|archive| archive.runs())
+        .map_err(|err| format!("{}: {}", dir, err))?;
+
+    let pairs = match (args.source, args.target) {
+        (Some(source), Some(target)) => vec![(source, target)],
+        (None, None) => archive::language_pairs(&runs),
+        _ => return Err("give both --source and --target, or neither".to_string()),
+    };
+    if pairs.is_empty() {
+        println!("no archived runs in {}", dir);
+    }
+    for (i, (source, target)) in pairs.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let points = archive::trend(&runs, source, target);
+        print!("{}", archive::render_trend_chart(source, target, &points));
+    }
+    Ok(())
+}
+
+fn capabilities(args: Args) -> Result<(), String> {
+    let (source, target) = match (args.source, args.target) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return Err("capabilities needs --source and --target".to_string()),
+    };
+    println!("{} → {}", source, target);
+    println!("  supported: {}", api::capabilities(source, target));
+    let gaps = api::gaps(source, target);
+    if !gaps.is_empty() {
+        println!("  lost:      {}", gaps);
+    }
+    Ok(())
+}
+
+fn scaffold(args: Args) -> Result<(), String> {
+    let (source, target) = match (args.source, args.target) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return Err("scaffold needs --source and --target".to_string()),
+    };
+    if args.files.is_empty() {
+        return Err("scaffold needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+    if let Some(probe) = usage_probe(provider.as_ref(), &args.used_by)? {
+        types = language_interop::usage::prune(&types, &probe);
+    }
+    let code = api::Generator::new(target)
+        .generate_scaffold(source, &types)
+        .map_err(|err| err.to_string())?;
+    print!("{}", code);
+    Ok(())
+}
+
+fn bindings(args: Args) -> Result<(), String> {
+    let (source, target, module) = match (args.source, args.target, &args.module) {
+        (Some(source), Some(target), Some(module)) => (source, target, module),
+        _ => return Err("bindings needs --source, --target and --module".to_string()),
+    };
+    if args.files.is_empty() {
+        return Err("bindings needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+    let generator = api::Generator::new(target);
+    let code = generator.generate_bindings(source, module, &types).map_err(|err| err.to_string())?;
+    match args.exports {
+        true => print!("{}", generator.generate_binding_exports(&types)),
+        false => print!("{}", code),
+    }
+    Ok(())
+}
+
+fn schema(args: Args) -> Result<(), String> {
+    let (source, target) = match (args.source, args.target) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return Err("schema needs --source and --target".to_string()),
+    };
+    if args.files.is_empty() {
+        return Err("schema needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+    let generator = api::Generator::new(target);
+    match (target, &args.into) {
+        (Language::Protobuf, _) => {
+            let package = args.package.as_deref().ok_or("a proto schema needs --package")?;
+            let mut lock = match &args.lock {
+                Some(path) if std::path::Path::new(path).exists() => {
+                    let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+                    ProtoLock::from_json(&text).map_err(|err| format!("{}: {}", path, err))?
+                }
+                _ => ProtoLock::default(),
+            };
+            print!("{}", generator.generate_proto(package, &mut lock, &types).map_err(|err| err.to_string())?);
+            if let Some(path) = &args.lock {
+                std::fs::write(path, lock.to_json()).map_err(|err| format!("{}: {}", path, err))?;
+            }
+        }
+        // The spec is rewritten with the schemas merged in
+        (Language::OpenApi, Some(path)) => {
+            let spec = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+            let merged = generator.merge_components(&spec, &types).map_err(|err| err.to_string())?;
+            std::fs::write(path, merged).map_err(|err| format!("{}: {}", path, err))?;
+            println!("{} schema(s) merged into {}", types.len(), path);
+        }
+        _ => println!("{}", generator.generate_schemas(&types).map_err(|err| err.to_string())?),
+    }
+    Ok(())
+}
+
+fn graphql(args: Args) -> Result<(), String> {
+    let source = args.source.ok_or("graphql needs --source")?;
+    if args.files.is_empty() {
+        return Err("graphql needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+    let schema = api::Generator::new(source).generate_graphql(&types);
+    print!("{}", schema.sdl);
+    // As comments, so the output stays a schema
+    for note in &schema.notes {
+        println!("# {}", note);
+    }
+    Ok(())
+}
+
+fn codecs(args: Args) -> Result<(), String> {
+    let (source, target) = match (args.source, args.target) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return Err("codecs needs --source and --target".to_string()),
+    };
+    if args.files.is_empty() {
+        return Err("codecs needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+    let code = api::Generator::new(target).generate_codecs(&types).map_err(|err| err.to_string())?;
+    print!("{}", code);
+    Ok(())
+}
+
+fn audit(args: Args) -> Result<(), String> {
+    let source = args.source.ok_or("audit needs --source")?;
+    if args.files.is_empty() {
+        return Err("audit needs at least one file".to_string());
+    }
+    let profile = if args.paranoid { SecurityProfile::Paranoid } else { SecurityProfile::Standard };
+
+    let provider = provider(&args.from)?;
+    let converters = ConverterRegistry::default();
+    let mut findings = Vec::new();
+    let mut bindings = String::from(audit::ffi_prelude(profile));
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        let located = |err: String| format!("{}: {}", provider.describe(file), err);
+        match source {
+            Language::Rust => {
+                // Enums have no `#[repr(C)]` wrapper to audit
+                let types = rust_analyzer::analyze_rust_types(&text).map_err(located)?;
+                for rust_type in types.into_iter().filter(|t| t.kind == rust_analyzer::RustTypeKind::Struct) {
+                    findings.extend(audit::audit_rust_ffi(&rust_type, &converters));
+                    bindings.push_str(&audit::generate_rust_ffi(&rust_type, &converters, &rust_analyzer::FfiAccessors::All, profile).map_err(located)?);
+                }
+            }
+            Language::C => {
+                for c_type in c_analyzer::analyze_c_types(&text).map_err(located)? {
+                    findings.extend(audit::audit_c_bindings(&c_type));
+                    bindings.push_str(&audit::generate_c_bindings(&c_type, profile));
+                }
+            }
+            other => return Err(format!("no FFI bindings are generated from {}", other)),
+        }
+    }
+
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    println!("{} risky field(s)", findings.len());
+    if args.paranoid {
+        println!();
+        print!("{}", bindings);
+    }
+    Ok(())
+}
+
+fn snapshots(args: Args) -> Result<(), String> {
+    let source = args.source.ok_or("snapshots needs --source")?;
+    if args.files.is_empty() {
+        return Err("snapshots needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut types = Vec::new();
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        types.extend(analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?);
+    }
+
+    let Some(path) = args.verify else {
+        for def in &types {
+            for sample in snapshot::sample_snapshots(def, &types, source) {
+                println!("{}", sample.to_line());
+            }
+        }
+        return Ok(());
+    };
+    let text = std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err))?;
+    let snapshots = snapshot::parse_snapshots(&text).map_err(|err| format!("{}: {}", path, err))?;
+    let mismatches = snapshot::verify_snapshots(&snapshots, &types);
+    for mismatch in &mismatches {
+        println!("{}", mismatch);
+    }
+    println!("{} problem(s) in {} snapshot(s)", mismatches.len(), snapshots.len());
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err("snapshots don't match this side's types".to_string())
+    }
+}
+
+fn bom(args: Args) -> Result<(), String> {
+    let source = args.source.ok_or("bom needs --source")?;
+    if args.targets.is_empty() {
+        return Err("bom needs at least one --target".to_string());
+    }
+    if args.files.is_empty() {
+        return Err("bom needs at least one file".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let analyzer = registry
+        .analyzer(source)
+        .ok_or_else(|| format!("no analyzer for {}", source))?;
+    let mut bom = match &args.into {
+        Some(path) if std::path::Path::new(path).exists() => {
+            let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+            Bom::from_json(&text).map_err(|err| format!("{}: {}", path, err))?
+        }
+        _ => Bom::default(),
+    };
+    bom.timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    for file in &args.files {
+        let text = read(provider.as_ref(), file)?;
+        let types = analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?;
+        bom.add(&provider.describe(file), &types, &args.targets);
+    }
+
+    match args.into {
+        Some(path) => {
+            std::fs::write(&path, bom.to_json()).map_err(|err| format!("{}: {}", path, err))?;
+            println!("{} type(s) in {}", bom.entries.len(), path);
+        }
+        None => println!("{}", bom.to_json()),
+    }
+    Ok(())
+}
+
+fn conformance(args: Args) -> Result<(), String> {
+    if args.suites.is_empty() {
+        return Err("conformance needs at least one --suite".to_string());
+    }
+
+    let provider = provider(&args.from)?;
+    let registry = Registry::with_builtins();
+    let mut suites: Vec<Suite> = Vec::new();
+    for (language, file) in &args.suites {
+        let analyzer = registry
+            .analyzer(*language)
+            .ok_or_else(|| format!("no analyzer for {}", language))?;
+        let text = read(provider.as_ref(), file)?;
+        let types = analyzer.analyze(&text).map_err(|err| format!("{}: {}", provider.describe(file), err))?;
+        // Several files for one language form one suite
+        match suites.iter_mut().find(|suite| suite.language == *language) {
+            Some(suite) => suite.types.extend(types),
+            None => suites.push(Suite::new(*language, types)),
+        }
+    }
+
+    let sandbox = args
+        .sandbox
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("lic-conformance"));
+    let mut runner = Runner::new(sandbox);
+    if let Some(jobs) = args.jobs {
+        runner = runner.with_jobs(jobs);
+    }
+    for toolchain in args.toolchains {
+        runner = runner.with_toolchain(Box::new(toolchain));
+    }
+    let matrix = runner.run(&suites);
+
+    print!("{}", matrix);
+    if let Some(path) = &args.into {
+        std::fs::write(path, matrix.to_json()).map_err(|err| format!("{}: {}", path, err))?;
+        println!("matrix written to {}", path);
+    }
+    let broken = matrix.broken_claims().count();
+    if broken == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} Concorde claim(s) not confirmed", broken))
+    }
+}
+
+fn watch(args: Args) -> Result<(), String> {
+    let source = args.source.ok_or("watch needs --source")?;
+    if args.files.is_empty() {
+        return Err("watch needs at least one file".to_string());
+    }
+    if args.from.is_some() {
+        return Err("watch only follows the working tree".to_string());
+    }
+
+    let registry = Registry::with_builtins();
+    let mut scheduler = Scheduler::default();
+    let mut seen: Vec<Option<SystemTime>> = vec![None; args.files.len()];
+    loop {
+        let now = Instant::now();
+        for (file, seen) in args.files.iter().zip(&mut seen) {
+            let modified = std::fs::metadata(file).and_then(|meta| meta.modified()).ok();
+            if modified == *seen {
+                continue;
+            }
+            *seen = modified;
+            match std::fs::read_to_string(file) {
+                Ok(text) => scheduler.did_change(file, source, &text, now),
+                Err(_) => scheduler.did_remove(file, now),
+            }
+        }
+        for diagnostics in scheduler.poll(&registry, Instant::now()) {
+            print!("{}", diagnostics);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    // Asked for, usage is output rather than an error
+    if matches!(command.as_deref(), Some("help" | "--help" | "-h")) || std::env::args().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let result = parse_args(args).and_then(|parsed| match command.as_deref() {
+        Some("report") => report(parsed),
+        Some("trends") => trends(parsed),
+        Some("capabilities") => capabilities(parsed),
+        Some("scaffold") => scaffold(parsed),
+        Some("bindings") => bindings(parsed),
+        Some("schema") => schema(parsed),
+        Some("graphql") => graphql(parsed),
+        Some("codecs") => codecs(parsed),
+        Some("audit") => audit(parsed),
+        Some("snapshots") => snapshots(parsed),
+        Some("bom") => bom(parsed),
+        Some("conformance") => conformance(parsed),
+        Some("watch") => watch(parsed),
+        _ => Err(USAGE.to_string()),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("lic: {}", message);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Interop bill of materials
+//!
+//! A [`Bom`] lists every type that crosses a language boundary: where it is
+//! defined, a fingerprint of its wire shape, and each language bindings are
+//! generated for with the fidelity and transport class of that mapping. It is
+//! written as one JSON document so compliance and platform teams can ingest
+//! it next to an SBOM, and diff it between releases to see which shared
+//! contracts changed.
+
+use crate::api::{Fidelity, Language, TypeDef};
+use crate::codegen::snapshot::fingerprint;
+use crate::ir;
+use crate::json::{self, Json};
+use crate::TransportClass;
+
+/// BOM file format version, bumped on incompatible changes
+pub const BOM_FORMAT_VERSION: u32 = 1;
+
+/// One language a shared type is generated for
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomTarget {
+    pub language: Language,
+    pub score: f32,
+    pub transport: TransportClass,
+    /// Fields that don't map exactly
+    pub degraded: Vec<String>,
+}
+
+/// One shared type
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomEntry {
+    pub name: String,
+    pub language: Language,
+    /// File the type was read from, as its source provider describes it
+    pub file: String,
+    pub location: String,
+    /// Same fingerprint the test data snapshots carry
+    pub fingerprint: String,
+    pub field_count: usize,
+    pub targets: Vec<BomTarget>,
+}
+
+/// Every shared type of a codebase and what it is generated for
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bom {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub entries: Vec<BomEntry>,
+}
+
+impl Bom {
+    pub fn new(timestamp: u64) -> Self {
+        Bom { timestamp, entries: Vec::new() }
+    }
+
+    /// Add the types analyzed from `file`, scored against each of `targets`;
+    /// entries already recorded for the file are replaced
+    pub fn add(&mut self, file: &str, types: &[TypeDef], targets: &[Language]) {
+        self.entries.retain(|entry| entry.file != file);
+        for def in types {
+            let targets = targets
+                .iter()
+                .filter(|&&target| target != def.language)
+                .map(|&target| {
+                    let fidelities: Vec<Fidelity> = def.fields.iter().map(|field| ir::fidelity(&field.ty, target.name())).collect();
+                    let score = ir::average_fidelity(fidelities.iter().copied());
+                    BomTarget {
+                        language: target,
+                        score,
+                        transport: TransportClass::from_score(score),
+                        degraded: def
+                            .fields
+                            .iter()
+                            .zip(&fidelities)
+                            .filter(|(_, fidelity)| **fidelity != Fidelity::Exact)
+                            .map(|(field, _)| field.name.clone())
+                            .collect(),
+                    }
+                })
+                .collect();
+            self.entries.push(BomEntry {
+                name: def.name.clone(),
+                language: def.language,
+                file: file.to_string(),
+                location: def.location.clone(),
+                fingerprint: fingerprint(def),
+                field_count: def.fields.len(),
+                targets,
+            });
+        }
+    }
+
+    /// Entries whose mapping to some target falls below `floor`
+    pub fn below(&self, floor: TransportClass) -> impl Iterator<Item = &BomEntry> {
+        let floor = rank(floor);
+        self.entries
+            .iter()
+            .filter(move |entry| entry.targets.iter().any(|target| rank(target.transport) > floor))
+    }
+
+    pub fn to_json(&self) -> String {
+        let entries = self.entries.iter().map(|entry| {
+            let targets = entry.targets.iter().map(|target| {
+                Json::object([
+                    ("language", target.language.name().into()),
+                    ("score", (target.score as f64).into()),
+                    ("transport", format!("{:?}", target.transport).into()),
+                    ("degraded", Json::Array(target.degraded.iter().map(|name| name.as_str().into()).collect())),
+                ])
+            });
+            Json::object([
+                ("name", entry.name.as_str().into()),
+                ("language", entry.language.name().into()),
+                ("file", entry.file.as_str().into()),
+                ("location", entry.location.as_str().into()),
+                ("fingerprint", entry.fingerprint.as_str().into()),
+                ("fields", entry.field_count.into()),
+                ("targets", Json::Array(targets.collect())),
+            ])
+        });
+        Json::object([
+            ("format", "interop-bom".into()),
+            ("version", (BOM_FORMAT_VERSION as usize).into()),
+            (
+                "tool",
+                Json::object([("name", env!("CARGO_PKG_NAME").into()), ("version", env!("CARGO_PKG_VERSION").into())]),
+            ),
+            ("timestamp", (self.timestamp as f64).into()),
+            ("types", Json::Array(entries.collect())),
+        ])
+        .to_string()
+    }
+
+    pub fn from_json(source: &str) -> Result<Bom, String> {
+        let value = json::parse(source)?;
+        if value.get("format").and_then(Json::as_str) != Some("interop-bom") {
+            return Err("not an interop BOM".to_string());
+        }
+        let version = value.get("version").and_then(Json::as_f64).ok_or("missing `version`")?;
+        if version as u32 != BOM_FORMAT_VERSION {
+            return Err(format!("unsupported BOM version {}", version));
+        }
+        let number = |json: &Json, key: &str| json.get(key).and_then(Json::as_f64).ok_or_else(|| format!("missing `{}`", key));
+        let string = |json: &Json, key: &str| {
+            json.get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing `{}`", key))
+        };
+        let language = |json: &Json| {
+            json.get("language")
+                .and_then(Json::as_str)
+                .and_then(Language::from_name)
+                .ok_or("missing or unknown `language`")
+        };
+
+        let mut entries = Vec::new();
+        for entry in value.get("types").and_then(Json::as_array).unwrap_or_default() {
+            let mut targets = Vec::new();
+            for target in entry.get("targets").and_then(Json::as_array).unwrap_or_default() {
+                let transport = match target.get("transport").and_then(Json::as_str) {
+                    Some("Concorde") => TransportClass::Concorde,
+                    Some("BusinessClass") => TransportClass::BusinessClass,
+                    Some("Economy") => TransportClass::Economy,
+                    Some("Wheelbarrow") => TransportClass::Wheelbarrow,
+                    _ => return Err("missing or unknown `transport`".to_string()),
+                };
+                let degraded = target.get("degraded").and_then(Json::as_array).unwrap_or_default();
+                targets.push(BomTarget {
+                    language: language(target)?,
+                    score: number(target, "score")? as f32,
+                    transport,
+                    degraded: degraded.iter().filter_map(Json::as_str).map(str::to_string).collect(),
+                });
+            }
+            entries.push(BomEntry {
+                name: string(entry, "name")?,
+                language: language(entry)?,
+                file: string(entry, "file")?,
+                location: string(entry, "location")?,
+                fingerprint: string(entry, "fingerprint")?,
+                field_count: number(entry, "fields")? as usize,
+                targets,
+            });
+        }
+
+        Ok(Bom { timestamp: number(&value, "timestamp")? as u64, entries })
+    }
+}
+
+/// Position of a transport class from best to worst
+fn rank(transport: TransportClass) -> usize {
+    match transport {
+        TransportClass::Concorde => 0,
+        TransportClass::BusinessClass => 1,
+        TransportClass::Economy => 2,
+        TransportClass::Wheelbarrow => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Registry;
+
+    fn analyze(language: Language, source: &str) -> Vec<TypeDef> {
+        Registry::with_builtins().analyzer(language).unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_bom_entries_and_round_trip() {
+        let rust = analyze(Language::Rust, "pub struct User { pub id: i32, pub tags: Vec<String> }\npub struct Blob { pub size: u64 }");
+        let dart = analyze(Language::Dart, "class Order {\n  final int id;\n  final double total;\n}\n");
+        let mut bom = Bom::new(1_700_000_000);
+        bom.add("src/user.rs", &rust, &[Language::TypeScript, Language::Rust]);
+        bom.add("lib/order.dart", &dart, &[Language::Rust]);
+
+        let names: Vec<_> = bom.entries.iter().map(|e| (e.name.as_str(), e.file.as_str())).collect();
+        assert_eq!(names, vec![("User", "src/user.rs"), ("Blob", "src/user.rs"), ("Order", "lib/order.dart")]);
+        // A type is never listed as generated for its own language
+        assert_eq!(bom.entries[0].targets.len(), 1);
+        assert_eq!(bom.entries[0].targets[0].transport, TransportClass::Concorde);
+        assert_eq!(bom.entries[0].fingerprint, fingerprint(&rust[0]));
+        assert_eq!(bom.entries[1].targets[0].degraded, vec!["size"]);
+        assert_eq!(bom.below(TransportClass::BusinessClass).map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["Blob"]);
+
+        let read = Bom::from_json(&bom.to_json()).unwrap();
+        assert_eq!(read, bom);
+        assert!(Bom::from_json("{\"format\":\"cyclonedx\"}").is_err());
+    }
+
+    #[test]
+    fn test_re_adding_a_file_replaces_its_entries() {
+        let mut bom = Bom::new(0);
+        bom.add("user.rs", &analyze(Language::Rust, "pub struct User { pub id: i64 }"), &[Language::Gleam]);
+        bom.add("user.rs", &analyze(Language::Rust, "pub struct Account { pub id: i64 }"), &[Language::Gleam]);
+        assert_eq!(bom.entries.len(), 1);
+        assert_eq!(bom.entries[0].name, "Account");
+    }
+}
@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Per-pair capability matrix
+//!
+//! A construct is supported end-to-end for a language pair when the source
+//! analyzer extracts it *and* the target language can express it. Querying
+//! [`capabilities`] up front lets tooling report gaps before generation runs.
+
+use std::fmt;
+
+use crate::api::Language;
+
+/// A language construct that may or may not survive a crossing
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Structs, records, interfaces
+    Records,
+    /// C-like enums and variants without payloads
+    Enums,
+    /// Variants with payloads, ad-hoc unions
+    Unions,
+    /// Type parameters
+    Generics,
+    /// Nullable / optional values
+    Optionals,
+    /// Success-or-error values
+    Results,
+    /// Lists, arrays, vectors
+    Collections,
+    /// Futures, promises, async functions
+    Async,
+    /// Function-typed fields and parameters
+    Callbacks,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 9] = [
+        Capability::Records,
+        Capability::Enums,
+        Capability::Unions,
+        Capability::Generics,
+        Capability::Optionals,
+        Capability::Results,
+        Capability::Collections,
+        Capability::Async,
+        Capability::Callbacks,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Records => "records",
+            Capability::Enums => "enums",
+            Capability::Unions => "unions",
+            Capability::Generics => "generics",
+            Capability::Optionals => "optionals",
+            Capability::Results => "results",
+            Capability::Collections => "collections",
+            Capability::Async => "async",
+            Capability::Callbacks => "callbacks",
+        }
+    }
+
+    fn bit(self) -> u16 {
+        1 << Capability::ALL.iter().position(|c| *c == self).unwrap_or(0)
+    }
+}
+
+/// A set of capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CapabilitySet(u16);
+
+impl CapabilitySet {
+    pub const EMPTY: CapabilitySet = CapabilitySet(0);
+
+    pub fn of(capabilities: &[Capability]) -> Self {
+        let mut set = CapabilitySet::EMPTY;
+        for capability in capabilities {
+            set.insert(*capability);
+        }
+        set
+    }
+
+    pub fn insert(&mut self, capability: Capability) {
+        self.0 |= capability.bit();
+    }
+
+    pub fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    pub fn intersection(self, other: CapabilitySet) -> CapabilitySet {
+        CapabilitySet(self.0 & other.0)
+    }
+
+    /// Capabilities in `self` but not in `other`
+    pub fn difference(self, other: CapabilitySet) -> CapabilitySet {
+        CapabilitySet(self.0 & !other.0)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Members in declaration order
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL.into_iter().filter(move |c| self.contains(*c))
+    }
+}
+
+impl fmt::Display for CapabilitySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.iter().map(Capability::name).collect();
+        f.write_str(&names.join(", "))
+    }
+}
+
+/// Constructs the crate's analyzer for `language` extracts from source
+pub fn analyzed(language: Language) -> CapabilitySet {
+    use Capability::*;
+    match language {
+        Language::Rust => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections]),
+        Language::ReScript => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections]),
+        Language::TypeScript => CapabilitySet::of(&[Records, Unions, Generics, Optionals, Collections]),
+        Language::OCaml => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections]),
+        Language::Elixir => CapabilitySet::of(&[Records, Unions, Optionals, Collections]),
+        Language::C => CapabilitySet::of(&[Records, Enums, Optionals, Collections, Callbacks]),
+        Language::Cpp => CapabilitySet::of(&[Records, Unions, Generics, Optionals, Collections, Callbacks]),
+        Language::CSharp => CapabilitySet::of(&[Records, Generics, Optionals, Collections]),
+        Language::Java => CapabilitySet::of(&[Records, Generics, Optionals, Collections]),
+        Language::Swift => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections, Callbacks]),
+        Language::Zig => CapabilitySet::of(&[Records, Unions, Generics, Optionals, Results, Collections]),
+        Language::Nim => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections]),
+        Language::Crystal => CapabilitySet::of(&[Records, Unions, Generics, Optionals, Results, Collections]),
+        Language::Erlang => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections]),
+        Language::FSharp => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections, Callbacks]),
+        Language::Dart => CapabilitySet::of(&[Records, Enums, Generics, Optionals, Collections, Callbacks]),
+        Language::Ruby => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Callbacks]),
+        Language::Php => CapabilitySet::of(&[Records, Enums, Unions, Optionals]),
+        Language::Lua => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Callbacks]),
+        Language::Protobuf => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::JsonSchema => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::OpenApi => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Avro => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Thrift => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Capnp => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections]),
+        Language::FlatBuffers => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Sql => CapabilitySet::of(&[Records, Enums, Optionals, Collections]),
+        Language::Cddl => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Asn1 => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Xsd => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        Language::Arrow => CapabilitySet::of(&[Records, Unions, Optionals, Collections]),
+        Language::Julia | Language::Gleam => CapabilitySet::EMPTY,
+    }
+}
+
+/// Constructs `language` can express natively as a generation target
+pub fn expressible(language: Language) -> CapabilitySet {
+    use Capability::*;
+    match language {
+        Language::Rust | Language::ReScript | Language::TypeScript | Language::OCaml | Language::FSharp | Language::Cpp | Language::Swift => {
+            CapabilitySet::of(&Capability::ALL)
+        }
+        // No discriminated unions until they land in the language
+        Language::CSharp => CapabilitySet::of(&[Records, Enums, Generics, Optionals, Results, Collections, Async, Callbacks]),
+        // Sealed interfaces over records stand in for tagged unions; no error values
+        Language::Java => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        Language::Elixir => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Results, Collections, Async, Callbacks]),
+        // No generics, no tagged unions, no error values, no async in the ABI
+        Language::C => CapabilitySet::of(&[Records, Enums, Optionals, Collections, Callbacks]),
+        // Error unions carry only an error code, not a payload; no async since 0.11
+        Language::Zig => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Callbacks]),
+        // Errors are exceptions; `Result` is a library type
+        Language::Nim => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        // Same term model as Elixir
+        Language::Erlang => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Results, Collections, Async, Callbacks]),
+        // Errors are exceptions; fibers and channels stand in for async
+        Language::Crystal => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        // Sealed class hierarchies stand in for tagged unions; errors are exceptions
+        Language::Dart => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        // Errors are exceptions and there is no async in the core language
+        Language::Ruby => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Callbacks]),
+        // Generics live only in docblocks; errors are exceptions; no async in the core language
+        Language::Php => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections, Callbacks]),
+        // Errors are raised values or `nil, message` pairs; coroutines carry async
+        Language::Lua => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        // A schema language: `oneof` is the only union, and services aren't data
+        Language::Protobuf => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Describes values only: no type parameters, errors, or functions
+        Language::JsonSchema => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Component schemas are JSON Schema
+        Language::OpenApi => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Protocols declare errors and messages, but records carry only data
+        Language::Avro => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Exceptions are thrown from service methods, and services aren't data
+        Language::Thrift => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Interfaces are capabilities passed around like callbacks; no error values
+        Language::Capnp => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Callbacks]),
+        // Unions hold tables only, and `rpc_service`s aren't data
+        Language::FlatBuffers => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Rows and enum types; composite types nest, but there are no sum types
+        Language::Sql => CapabilitySet::of(&[Records, Enums, Optionals, Collections]),
+        // Generic rules take type arguments; CBOR carries no functions or errors
+        Language::Cddl => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections]),
+        // Parameterized types take type arguments; modules describe data, not operations
+        Language::Asn1 => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections]),
+        // Choices and unions, but no type parameters; documents carry no operations
+        Language::Xsd => CapabilitySet::of(&[Records, Enums, Unions, Optionals, Collections]),
+        // Columns are data only; dictionary encoding stores values, not a closed set
+        Language::Arrow => CapabilitySet::of(&[Records, Unions, Optionals, Collections]),
+        Language::Julia => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Collections, Async, Callbacks]),
+        Language::Gleam => CapabilitySet::of(&[Records, Enums, Unions, Generics, Optionals, Results, Collections, Callbacks]),
+    }
+}
+
+/// Constructs supported end-to-end from `source` to `target`
+pub fn capabilities(source: Language, target: Language) -> CapabilitySet {
+    analyzed(source).intersection(expressible(target))
+}
+
+/// Constructs the source analyzer extracts that the target cannot express
+pub fn gaps(source: Language, target: Language) -> CapabilitySet {
+    analyzed(source).difference(expressible(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_capabilities() {
+        let ocaml_to_c = capabilities(Language::OCaml, Language::C);
+        assert!(ocaml_to_c.contains(Capability::Records));
+        assert!(!ocaml_to_c.contains(Capability::Generics));
+        assert_eq!(gaps(Language::OCaml, Language::C).to_string(), "unions, generics, results");
+
+        let c_to_rust = capabilities(Language::C, Language::Rust);
+        assert_eq!(c_to_rust, analyzed(Language::C));
+        assert!(gaps(Language::C, Language::Rust).is_empty());
+        // Nothing is analyzed from a language without an analyzer
+        assert!(capabilities(Language::Gleam, Language::Rust).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut set = CapabilitySet::of(&[Capability::Async]);
+        set.insert(Capability::Records);
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![Capability::Records, Capability::Async]);
+        assert!(set.difference(set).is_empty());
+    }
+}
@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Security audit of the generated FFI boundary
+// Flags generated fields that Rust would trust the foreign side to fill correctly,
+// and under the paranoid profile emits checked readers that validate them instead
+
+use std::fmt;
+
+use crate::analyzers::c_analyzer::{self, CField, CFieldType, CType, CTypeKind};
+use crate::analyzers::rust_analyzer::{self, FfiAccessors, RustFieldType, RustType};
+use crate::converters::ConverterRegistry;
+
+/// How far generated bindings trust values coming from the foreign side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecurityProfile {
+    /// Plain `#[repr(C)]` mirrors, as hand-written FFI would declare them
+    #[default]
+    Standard,
+    /// Flags travel as bytes, and every pointer, length, string, and flag is
+    /// read through a reader that checks it first
+    Paranoid,
+}
+
+/// A way a foreign caller can make Rust misbehave through a generated field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Risk {
+    /// A raw pointer read with an element count from another field
+    UncheckedLength { length: String },
+    /// Bytes assumed to be valid UTF-8
+    UnvalidatedUtf8,
+    /// A `bool` any foreign byte can fill; anything but 0 or 1 is undefined behavior
+    UntrustedBool,
+}
+
+/// One risky field of a generated binding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub type_name: String,
+    pub field: String,
+    pub risk: Risk,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: ", self.type_name, self.field)?;
+        match &self.risk {
+            Risk::UncheckedLength { length } => write!(f, "raw pointer read with the unchecked length `{}`", length),
+            Risk::UnvalidatedUtf8 => f.write_str("bytes assumed to be valid UTF-8"),
+            Risk::UntrustedBool => f.write_str("`bool` filled by foreign code"),
+        }
+    }
+}
+
+/// Audit the FFI wrapper generated for a Rust type
+pub fn audit_rust_ffi(rust_type: &RustType, converters: &ConverterRegistry) -> Vec<Finding> {
+    let finding = |field: &str, risk| Finding { type_name: format!("{}FFI", rust_type.name), field: field.to_string(), risk };
+    let mut findings = Vec::new();
+    for field in &rust_type.fields {
+        let mut field_type = rust_analyzer::ffi_field_type(rust_type, field, converters);
+        // The presence flag of an option is a `bool` too
+        if let RustFieldType::Option(inner) = field_type {
+            findings.push(finding(&format!("{}_present", field.name), Risk::UntrustedBool));
+            field_type = inner;
+        }
+        match field_type {
+            RustFieldType::String => {
+                findings.push(finding(&field.name, Risk::UncheckedLength { length: format!("{}_len", field.name) }));
+                findings.push(finding(&field.name, Risk::UnvalidatedUtf8));
+            }
+            RustFieldType::Vec(_) => findings.push(finding(&field.name, Risk::UncheckedLength { length: format!("{}_len", field.name) })),
+            RustFieldType::Bool => findings.push(finding(&field.name, Risk::UntrustedBool)),
+            _ => {}
+        }
+    }
+    findings
+}
+
+/// Audit the Rust mirror generated for a C struct: pointers with a length
+/// field beside them, C strings, and `bool`s
+pub fn audit_c_bindings(c_type: &CType) -> Vec<Finding> {
+    if c_type.kind != CTypeKind::Struct {
+        return Vec::new();
+    }
+    let finding = |field: &CField, risk| Finding { type_name: c_type.name.clone(), field: field.name.clone(), risk };
+    let mut findings = Vec::new();
+    for field in &c_type.fields {
+        match &field.field_type {
+            CFieldType::Pointer { pointee, .. } => match paired_length(c_type, &field.name) {
+                Some(length) => findings.push(finding(field, Risk::UncheckedLength { length: length.to_string() })),
+                None if **pointee == CFieldType::Char => findings.push(finding(field, Risk::UnvalidatedUtf8)),
+                None => {}
+            },
+            CFieldType::Bool if field.bit_width.is_none() => findings.push(finding(field, Risk::UntrustedBool)),
+            _ => {}
+        }
+    }
+    findings
+}
+
+/// Integer field holding the element count of `pointer` (`items_len`, `n_items`,
+/// or a bare `len` when the struct has a single pointer)
+fn paired_length<'a>(c_type: &'a CType, pointer: &str) -> Option<&'a str> {
+    let lengths: Vec<&CField> = c_type
+        .fields
+        .iter()
+        .filter(|field| field.bit_width.is_none() && is_integer(&field.field_type))
+        .collect();
+    let named = [
+        format!("{}_len", pointer),
+        format!("{}_length", pointer),
+        format!("{}_count", pointer),
+        format!("{}_size", pointer),
+        format!("n_{}", pointer),
+        format!("num_{}", pointer),
+    ];
+    if let Some(field) = lengths.iter().find(|field| named.contains(&field.name)) {
+        return Some(&field.name);
+    }
+    let pointers = c_type
+        .fields
+        .iter()
+        .filter(|field| matches!(field.field_type, CFieldType::Pointer { .. }))
+        .count();
+    if pointers != 1 {
+        return None;
+    }
+    lengths
+        .iter()
+        .find(|field| matches!(field.name.as_str(), "len" | "length" | "count" | "size"))
+        .map(|field| field.name.as_str())
+}
+
+fn is_integer(field_type: &CFieldType) -> bool {
+    matches!(
+        field_type,
+        CFieldType::Int8
+            | CFieldType::Int16
+            | CFieldType::Int32
+            | CFieldType::Int64
+            | CFieldType::UInt8
+            | CFieldType::UInt16
+            | CFieldType::UInt32
+            | CFieldType::UInt64
+            | CFieldType::Long
+            | CFieldType::ULong
+            | CFieldType::ISize
+            | CFieldType::USize
+    )
+}
+
+/// Definitions the checked readers share, emitted once before a file's bindings
+pub fn ffi_prelude(profile: SecurityProfile) -> &'static str {
+    match profile {
+        SecurityProfile::Standard => "",
+        SecurityProfile::Paranoid => PARANOID_PRELUDE,
+    }
+}
+
+const PARANOID_PRELUDE: &str = r#"/// A value from the foreign side that failed validation
+#[derive(Debug, Clone, PartialEq)]
+pub enum FfiError {
+    NullPointer { field: &'static str },
+    Misaligned { field: &'static str },
+    LengthOverflow { field: &'static str, len: usize },
+    InvalidUtf8 { field: &'static str },
+    InvalidBool { field: &'static str, value: u8 },
+    InvalidChar { field: &'static str, value: u32 },
+}
+
+/// Borrow `len` elements at `ptr`, rejecting null or misaligned pointers and
+/// lengths no allocation can have
+///
+/// # Safety
+/// A non-null `ptr` must point to `len` initialized elements that outlive `'a`
+unsafe fn checked_slice<'a, T>(ptr: *const T, len: usize, field: &'static str) -> Result<&'a [T], FfiError> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(FfiError::NullPointer { field });
+    }
+    if (ptr as usize) % std::mem::align_of::<T>() != 0 {
+        return Err(FfiError::Misaligned { field });
+    }
+    if len > isize::MAX as usize / std::mem::size_of::<T>().max(1) {
+        return Err(FfiError::LengthOverflow { field, len });
+    }
+    Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+/// Borrow a NUL-terminated C string, rejecting null pointers and invalid UTF-8
+///
+/// # Safety
+/// A non-null `ptr` must point to a NUL-terminated string that outlives `'a`
+unsafe fn checked_c_str<'a>(ptr: *const std::os::raw::c_char, field: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::NullPointer { field });
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().map_err(|_| FfiError::InvalidUtf8 { field })
+}
+
+fn checked_bool(value: u8, field: &'static str) -> Result<bool, FfiError> {
+    match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(FfiError::InvalidBool { field, value }),
+    }
+}
+"#;
+
+/// FFI wrapper and accessors for a Rust type; the paranoid profile adds
+/// checked readers for every field [`audit_rust_ffi`] flags
+pub fn generate_rust_ffi(
+    rust_type: &RustType,
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+) -> Result<String, String> {
+    let mut code = rust_analyzer::ffi_wrapper(rust_type, converters, profile)?;
+    if profile == SecurityProfile::Paranoid {
+        let mut readers = Vec::new();
+        for field in &rust_type.fields {
+            let name = &field.name;
+            match rust_analyzer::ffi_field_type(rust_type, field, converters) {
+                RustFieldType::String => readers.push(format!(
+                    "    /// `{name}`, checked for null, oversized lengths, and invalid UTF-8\n    \
+                     ///\n    \
+                     /// # Safety\n    \
+                     /// `{name}_ptr` must point to `{name}_len` bytes that outlive `self`\n    \
+                     pub unsafe fn {name}(&self) -> Result<&str, FfiError> {{\n        \
+                     let bytes = unsafe {{ checked_slice(self.{name}_ptr, self.{name}_len, \"{name}\") }}?;\n        \
+                     std::str::from_utf8(bytes).map_err(|_| FfiError::InvalidUtf8 {{ field: \"{name}\" }})\n    \
+                     }}\n"
+                )),
+                RustFieldType::Bool => readers.push(bool_reader(name)),
+                _ => {}
+            }
+        }
+        code.push_str(&impl_block(&format!("{}FFI", rust_type.name), &readers));
+    }
+    code.push_str(&rust_analyzer::ffi_accessors(rust_type, converters, accessors, profile)?);
+    Ok(code)
+}
+
+/// Rust mirror of a C declaration; the paranoid profile adds checked readers
+/// for every field [`audit_c_bindings`] flags
+pub fn generate_c_bindings(c_type: &CType, profile: SecurityProfile) -> String {
+    let mut code = c_analyzer::ffi_bindings(c_type, profile);
+    if profile == SecurityProfile::Paranoid {
+        let mut readers = Vec::new();
+        for finding in audit_c_bindings(c_type) {
+            let name = &finding.field;
+            let pointee = c_type
+                .fields
+                .iter()
+                .find(|field| field.name == *name)
+                .and_then(|field| match &field.field_type {
+                    CFieldType::Pointer { pointee, .. } => Some(pointee.as_ref()),
+                    _ => None,
+                });
+            readers.push(match (finding.risk, pointee) {
+                (Risk::UncheckedLength { length }, Some(pointee)) => {
+                    // Untyped buffers are read as bytes
+                    let (element, ptr) = match pointee {
+                        CFieldType::Void => ("u8".to_string(), format!("self.{} as *const u8", name)),
+                        other => (c_analyzer::map_to_target(other, "rust"), format!("self.{}", name)),
+                    };
+                    format!(
+                        "    /// `{name}`, checked for null, misaligned, and oversized `{length}`\n    \
+                         ///\n    \
+                         /// # Safety\n    \
+                         /// `{name}` must point to `{length}` elements that outlive `self`\n    \
+                         pub unsafe fn {name}(&self) -> Result<&[{element}], FfiError> {{\n        \
+                         unsafe {{ checked_slice({ptr}, self.{length} as usize, \"{name}\") }}\n    \
+                         }}\n"
+                    )
+                }
+                (Risk::UnvalidatedUtf8, _) => format!(
+                    "    /// `{name}`, checked for null and invalid UTF-8\n    \
+                     ///\n    \
+                     /// # Safety\n    \
+                     /// `{name}` must point to a NUL-terminated string that outlives `self`\n    \
+                     pub unsafe fn {name}(&self) -> Result<&str, FfiError> {{\n        \
+                     unsafe {{ checked_c_str(self.{name}, \"{name}\") }}\n    \
+                     }}\n"
+                ),
+                _ => bool_reader(name),
+            });
+        }
+        code.push_str(&impl_block(&c_type.name, &readers));
+    }
+    code
+}
+
+fn bool_reader(name: &str) -> String {
+    format!(
+        "    /// `{name}`, rejecting bytes other than 0 and 1\n    \
+         pub fn {name}(&self) -> Result<bool, FfiError> {{\n        \
+         checked_bool(self.{name}, \"{name}\")\n    \
+         }}\n"
+    )
+}
+
+fn impl_block(type_name: &str, readers: &[String]) -> String {
+    if readers.is_empty() {
+        return String::new();
+    }
+    format!("\nimpl {} {{\n{}}}\n", type_name, readers.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::c_analyzer::analyze_c_types;
+    use crate::analyzers::rust_analyzer::{analyze_rust_type, generate_ffi_accessors, generate_ffi_wrapper_with};
+
+    const HEADER: &str = r#"
+typedef struct packet {
+    const char *label;
+    const uint8_t *payload;
+    size_t payload_len;
+    int32_t *samples;
+    int n_samples;
+    bool urgent;
+    char tag[8];
+} packet_t;
+"#;
+
+    #[test]
+    fn test_audit_flags_risky_fields() {
+        let user = analyze_rust_type("pub struct User { pub id: i64, pub name: String, pub active: bool }").unwrap();
+        let findings: Vec<String> = audit_rust_ffi(&user, &ConverterRegistry::default()).iter().map(Finding::to_string).collect();
+        assert_eq!(
+            findings,
+            vec![
+                "UserFFI.name: raw pointer read with the unchecked length `name_len`",
+                "UserFFI.name: bytes assumed to be valid UTF-8",
+                "UserFFI.active: `bool` filled by foreign code",
+            ]
+        );
+
+        let reading = analyze_rust_type("pub struct Reading { pub samples: Vec<f32>, pub label: Option<String> }").unwrap();
+        let findings: Vec<String> = audit_rust_ffi(&reading, &ConverterRegistry::default()).iter().map(Finding::to_string).collect();
+        assert_eq!(
+            findings,
+            vec![
+                "ReadingFFI.samples: raw pointer read with the unchecked length `samples_len`",
+                "ReadingFFI.label_present: `bool` filled by foreign code",
+                "ReadingFFI.label: raw pointer read with the unchecked length `label_len`",
+                "ReadingFFI.label: bytes assumed to be valid UTF-8",
+            ]
+        );
+
+        let packet = &analyze_c_types(HEADER).unwrap()[0];
+        let risks: Vec<_> = audit_c_bindings(packet).into_iter().map(|f| (f.field, f.risk)).collect();
+        assert_eq!(
+            risks,
+            vec![
+                ("label".to_string(), Risk::UnvalidatedUtf8),
+                ("payload".to_string(), Risk::UncheckedLength { length: "payload_len".to_string() }),
+                ("samples".to_string(), Risk::UncheckedLength { length: "n_samples".to_string() }),
+                ("urgent".to_string(), Risk::UntrustedBool),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paranoid_profile_hardens_bindings() {
+        let user = analyze_rust_type("pub struct User { pub id: i64, pub name: String, pub active: bool }").unwrap();
+        let converters = ConverterRegistry::default();
+        let standard = generate_rust_ffi(&user, &converters, &FfiAccessors::All, SecurityProfile::Standard).unwrap();
+        assert_eq!(
+            standard,
+            generate_ffi_wrapper_with(&user, &converters).unwrap() + &generate_ffi_accessors(&user, &converters, &FfiAccessors::All).unwrap()
+        );
+        assert!(ffi_prelude(SecurityProfile::Standard).is_empty());
+
+        let paranoid = generate_rust_ffi(&user, &converters, &FfiAccessors::All, SecurityProfile::Paranoid).unwrap();
+        assert!(paranoid.contains("    active: u8,\n"));
+        assert!(paranoid.contains("pub extern \"C\" fn user_active(value: &UserFFI) -> u8 {"));
+        assert!(paranoid.contains("checked_slice(self.name_ptr, self.name_len, \"name\")"));
+        assert!(paranoid.contains("pub fn active(&self) -> Result<bool, FfiError> {\n        checked_bool(self.active, \"active\")"));
+
+        let packet = &analyze_c_types(HEADER).unwrap()[0];
+        let hardened = generate_c_bindings(packet, SecurityProfile::Paranoid);
+        assert!(hardened.contains("    pub urgent: u8,\n"));
+        assert!(hardened.contains("pub unsafe fn label(&self) -> Result<&str, FfiError> {\n        unsafe { checked_c_str(self.label, \"label\") }"));
+        assert!(hardened.contains("checked_slice(self.samples, self.n_samples as usize, \"samples\")"));
+        assert!(hardened.contains("pub unsafe fn payload(&self) -> Result<&[u8], FfiError>"));
+        assert!(generate_c_bindings(packet, SecurityProfile::Standard).contains("    pub urgent: bool,\n"));
+    }
+}
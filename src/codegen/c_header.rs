@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// C header for the Rust FFI layer
+// Declares exactly what `generate_ffi_wrapper` and the accessors define: the
+// `#[repr(C)]` wrapper structs with `<stdint.h>` types, string and list
+// pointer/length pairs and option presence flags, callback typedefs, fieldless enums at their `#[repr]` width, and a
+// prototype per exported function, so C sees the layout the Rust side has
+
+use super::audit::SecurityProfile;
+use super::{render_doc, snake_case};
+use crate::analyzers::rust_analyzer::{
+    callback_signature, ffi_callback_types, ffi_field_type, ffi_frees, ffi_members, is_zero_sized, rust_type_name, unrepresentable, FfiAccessors, RustType,
+    RustTypeKind, RustVariantKind,
+};
+use crate::converters::ConverterRegistry;
+
+/// Complete `.h` for the FFI layer of `types`, guarded by `name` (`user_ffi` → `USER_FFI_H`);
+/// an error names a field the Rust side has no wrapper member for
+pub fn generate_header(
+    types: &[RustType],
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+    name: &str,
+) -> Result<String, String> {
+    let guard = format!("{}_H", snake_case(name).to_uppercase());
+    let mut code = format!(
+        "/* C declarations of the Rust FFI layer; keep in step with the Rust side */\n\n\
+         #ifndef {guard}\n#define {guard}\n\n#include <stdbool.h>\n#include <stdint.h>\n\n\
+         #ifdef __cplusplus\nextern \"C\" {{\n#endif\n"
+    );
+    for rust_type in types.iter().filter(|t| t.kind == RustTypeKind::Enum) {
+        code.push('\n');
+        code.push_str(&render_enum(rust_type));
+    }
+    let mut prototypes = String::new();
+    for rust_type in types.iter().filter(|t| t.kind == RustTypeKind::Struct) {
+        code.push('\n');
+        code.push_str(&render_struct(rust_type, converters, profile)?);
+        prototypes.push_str(&render_prototypes(rust_type, converters, accessors, profile)?);
+    }
+    if !prototypes.is_empty() {
+        code.push('\n');
+        code.push_str(&prototypes);
+    }
+    code.push_str(&format!("\n#ifdef __cplusplus\n}}  /* extern \"C\" */\n#endif\n\n#endif  /* {guard} */\n"));
+    Ok(code)
+}
+
+/// C spelling of a type the Rust FFI layer declares; `None` for ones C has no
+/// fixed-width counterpart for (128-bit integers, collections, nested structs)
+fn c_type(ffi: &str) -> Option<String> {
+    let c = match ffi {
+        "bool" => "bool",
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "isize" => "intptr_t",
+        "usize" => "uintptr_t",
+        "f32" => "float",
+        "f64" => "double",
+        "*const u8" => "const uint8_t *",
+        "*mut std::ffi::c_void" => "void *",
+        "*const std::ffi::c_char" => "const char *",
+        pointer => {
+            let pointee = pointer.strip_prefix("*const ")?;
+            return match pointee.ends_with("FFI") {
+                true => Some(format!("const {} *", pointee)),
+                false => c_type(pointee).map(|pointee| format!("const {} *", pointee)),
+            };
+        }
+    };
+    Some(c.to_string())
+}
+
+/// Element type and dimensions of a fixed array (`[[f32; 2]; 3]` → `float`, `[3][2]`),
+/// which C declares after the member name
+fn c_array(ffi: &str) -> Option<(String, String)> {
+    let (element, len) = ffi.strip_prefix('[')?.strip_suffix(']')?.rsplit_once("; ")?;
+    let (element, dimensions) = match c_array(element) {
+        Some((element, dimensions)) => (element, dimensions),
+        None => (c_type(element)?, String::new()),
+    };
+    Some((element, format!("[{}]{}", len, dimensions)))
+}
+
+/// `ty name` with the `*` of a pointer type against the name, as C is written
+fn declare(ty: &str, name: &str) -> String {
+    match ty.ends_with('*') {
+        true => format!("{}{}", ty, name),
+        false => format!("{} {}", ty, name),
+    }
+}
+
+fn callback_name(rust_type: &RustType, field: &str) -> String {
+    format!("{}{}Callback", rust_type.name, super::pascal_case(field))
+}
+
+/// Member declarations of the wrapper, in its field order; `None` once a
+/// field has no C spelling (a nested wrapper, a 128-bit integer)
+fn members(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Result<Option<(String, String)>, String> {
+    let mut typedefs = String::new();
+    let mut members = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = &field.name;
+        if let Some((params, ret)) = callback_signature(&field.field_type) {
+            let callback = callback_name(rust_type, name);
+            let mut args = vec![Some("void *".to_string())];
+            for param in params {
+                args.extend(ffi_callback_types(param, profile).map_err(|reason| unrepresentable(rust_type, field, &reason))?.iter().map(|ty| c_type(ty)));
+            }
+            let Some(args) = args.into_iter().collect::<Option<Vec<_>>>() else { return Ok(None) };
+            let ret = match ffi_callback_types(ret, profile).map_err(|reason| unrepresentable(rust_type, field, &reason))?.as_slice() {
+                [] => "void".to_string(),
+                [single] => match c_type(single) {
+                    Some(ty) => ty,
+                    None => return Ok(None),
+                },
+                _ => "const char *".to_string(),
+            };
+            typedefs.push_str(&format!("typedef {} (*{})({});\n", ret, callback, args.join(", ")));
+            members.push_str(&format!("    /* `{}`, registered from C */\n", rust_type_name(&field.field_type)));
+            members.push_str(&format!("    {} {};\n    void *{}_context;\n", callback, name, name));
+            continue;
+        }
+        for (member, ty) in ffi_members(name, ffi_field_type(rust_type, field, converters), profile).map_err(|reason| unrepresentable(rust_type, field, &reason))? {
+            match (c_array(&ty), c_type(&ty)) {
+                (Some((element, dimensions)), _) => members.push_str(&format!("    {} {}{};\n", element, member, dimensions)),
+                (None, Some(ty)) => members.push_str(&format!("    {};\n", declare(&ty, &member))),
+                (None, None) => return Ok(None),
+            }
+        }
+    }
+    Ok(Some((typedefs, members)))
+}
+
+fn render_struct(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Result<String, String> {
+    let name = format!("{}FFI", rust_type.name);
+    let doc = rust_type.doc.as_ref().map(|doc| render_doc(doc, "c", "")).unwrap_or_default();
+    Ok(match members(rust_type, converters, profile)? {
+        Some((typedefs, members)) => format!("{typedefs}{doc}typedef struct {name} {{\n{members}}} {name};\n"),
+        // Still usable through pointers and the accessors
+        None => format!("{doc}/* Opaque: a field of `{}` has no fixed C layout */\ntypedef struct {name} {name};\n", rust_type.name),
+    })
+}
+
+fn render_prototypes(
+    rust_type: &RustType,
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+) -> Result<String, String> {
+    let prefix = snake_case(&rust_type.name);
+    let wrapper = format!("{}FFI", rust_type.name);
+    let mut code = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = &field.name;
+        if callback_signature(&field.field_type).is_some() {
+            code.push_str(&format!(
+                "void {prefix}_register_{name}({wrapper} *value, {} callback, void *context);\n",
+                callback_name(rust_type, name)
+            ));
+            continue;
+        }
+        if !accessors.includes(&rust_type.name, name) {
+            continue;
+        }
+        for (member, ty) in ffi_members(name, ffi_field_type(rust_type, field, converters), profile).map_err(|reason| unrepresentable(rust_type, field, &reason))? {
+            // A nested wrapper is lent out by pointer
+            let ty = match ty.ends_with("FFI") {
+                true => format!("*const {}", ty),
+                false => ty,
+            };
+            match c_type(&ty) {
+                Some(ty) => code.push_str(&format!("{}(const {wrapper} *value);\n", declare(&ty, &format!("{}_{}", prefix, member)))),
+                // C functions can't return arrays, and the rest have no C type
+                None => code.push_str(&format!("/* `{prefix}_{member}` has no C signature; read `{member}` from the struct */\n")),
+            }
+        }
+    }
+    if ffi_frees(rust_type, converters, profile) {
+        code.push_str(&format!("void {prefix}_free({wrapper} value);\n"));
+    }
+    Ok(code)
+}
+
+/// Fieldless enum as named constants plus a typedef of its `#[repr]` integer;
+/// without one it's a plain C enum, matching `#[repr(C)]`
+fn render_enum(rust_type: &RustType) -> String {
+    let doc = rust_type.doc.as_ref().map(|doc| render_doc(doc, "c", "")).unwrap_or_default();
+    if rust_type.variants.iter().any(|variant| variant.kind != RustVariantKind::Unit) {
+        return format!("{doc}/* `{}` carries data and has no C form */\n", rust_type.name);
+    }
+    let prefix = snake_case(&rust_type.name).to_uppercase();
+    let constants: String = rust_type
+        .variants
+        .iter()
+        .map(|variant| {
+            let constant = format!("{}_{}", prefix, snake_case(&variant.name).to_uppercase());
+            match &variant.discriminant {
+                Some(value) => format!("    {} = {},\n", constant, value),
+                None => format!("    {},\n", constant),
+            }
+        })
+        .collect();
+    let repr = rust_type
+        .attributes
+        .iter()
+        .filter_map(|attr| attr.split_once("repr(").map(|(_, rest)| rest.trim_end_matches([')', ']'])))
+        .flat_map(|reprs| reprs.split(',').map(str::trim))
+        .find_map(|repr| c_type(repr).filter(|_| repr != "bool" && !repr.starts_with('f')));
+    match repr {
+        Some(repr) => format!("{doc}enum {name} {{\n{constants}}};\ntypedef {repr} {name};\n", name = rust_type.name),
+        None => format!("{doc}typedef enum {name} {{\n{constants}}} {name};\n", name = rust_type.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::analyze_rust_types;
+
+    #[test]
+    fn test_header_mirrors_the_ffi_wrapper() {
+        let types = analyze_rust_types(
+            "#[repr(u8)]\npub enum Status { Active = 1, Suspended }\n\
+             /// A signed-up user\n#[repr(C)]\npub struct User { pub id: u64, pub name: String, pub score: f32, pub active: bool, \
+             pub token: Uuid, pub on_change: Box<dyn Fn(i32) -> bool> }\n\
+             pub struct Team { pub members: Vec<User>, pub lead: User }\n\
+             pub struct Reading { pub samples: Vec<f32>, pub limit: Option<u32>, pub grid: [[f32; 2]; 3] }",
+        )
+        .unwrap();
+        let header = generate_header(&types, &ConverterRegistry::default(), &FfiAccessors::All, SecurityProfile::Standard, "user_ffi").unwrap();
+        assert!(header.contains("#ifndef USER_FFI_H\n#define USER_FFI_H\n"));
+        assert!(header.contains("enum Status {\n    STATUS_ACTIVE = 1,\n    STATUS_SUSPENDED,\n};\ntypedef uint8_t Status;\n"));
+        assert!(header.contains(
+            "typedef bool (*UserOnChangeCallback)(void *, int32_t);\n/** A signed-up user */\ntypedef struct UserFFI {\n    uint64_t id;\n    \
+             const uint8_t *name_ptr;\n    uintptr_t name_len;\n    float score;\n    bool active;\n    uint8_t token[16];\n    \
+             /* `Box<dyn Fn(i32) -> bool>`, registered from C */\n    UserOnChangeCallback on_change;\n    void *on_change_context;\n} UserFFI;\n"
+        ));
+        assert!(header.contains("/* Opaque: a field of `Team` has no fixed C layout */\ntypedef struct TeamFFI TeamFFI;\n"));
+        assert!(header.contains(
+            "typedef struct ReadingFFI {\n    const float *samples_ptr;\n    uintptr_t samples_len;\n    bool limit_present;\n    uint32_t limit;\n    float grid[3][2];\n} ReadingFFI;\n"
+        ));
+        assert!(header.contains("const float *reading_samples_ptr(const ReadingFFI *value);\n"));
+        assert!(header.contains("const UserFFI *team_members_ptr(const TeamFFI *value);\nuintptr_t team_members_len(const TeamFFI *value);\nconst UserFFI *team_lead(const TeamFFI *value);\n"));
+        assert!(header.contains("uint64_t user_id(const UserFFI *value);\nconst uint8_t *user_name_ptr(const UserFFI *value);\nuintptr_t user_name_len(const UserFFI *value);\n"));
+        assert!(header.contains("/* `user_token` has no C signature; read `token` from the struct */\n"));
+        assert!(header.contains("void user_register_on_change(UserFFI *value, UserOnChangeCallback callback, void *context);\nvoid user_free(UserFFI value);\n"));
+        assert!(header.ends_with("#endif  /* USER_FFI_H */\n"));
+
+        let paranoid = generate_header(&types, &ConverterRegistry::default(), &FfiAccessors::None, SecurityProfile::Paranoid, "user_ffi").unwrap();
+        assert!(paranoid.contains("    uint8_t active;\n"));
+        assert!(!paranoid.contains("user_id("));
+
+        let tags = analyze_rust_types("pub struct Tags { pub counts: HashMap<String, u32> }").unwrap();
+        assert_eq!(
+            generate_header(&tags, &ConverterRegistry::default(), &FfiAccessors::All, SecurityProfile::Standard, "tags").unwrap_err(),
+            "`Tags.counts` (`HashMap<String, u32>`) is not FFI-representable: a map has no C layout"
+        );
+    }
+}
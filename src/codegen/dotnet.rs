@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// C# P/Invoke declarations for the Rust FFI layer
+// The .NET counterpart of the C header: `[StructLayout(LayoutKind.Sequential)]`
+// mirrors of the `#[repr(C)]` wrappers, enums at their `#[repr]` width, callback
+// delegates, and a `[DllImport]` per accessor and registration function. Strings
+// stay a pointer/length pair on the wire; the policy picks what C# builds on top
+
+use super::audit::SecurityProfile;
+use super::{pascal_case, snake_case};
+use crate::analyzers::rust_analyzer::{
+    callback_signature, ffi_callback_types, ffi_field_type, ffi_scalar, is_zero_sized, rust_type_name, FfiAccessors, RustFieldType, RustType,
+    RustTypeKind, RustVariantKind,
+};
+use crate::converters::ConverterRegistry;
+
+/// What the C# structs offer over a string's `Ptr`/`Len` fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringMarshalling {
+    /// Nothing; callers decode the bytes themselves
+    Raw,
+    /// A `string` property decoding the UTF-8 on every read
+    #[default]
+    Utf8,
+    /// A `ReadOnlySpan<byte>` property over the Rust-owned bytes, without
+    /// copying; needs `AllowUnsafeBlocks`
+    Span,
+}
+
+/// Complete `.cs` for the FFI layer of `types` in `namespace`, importing from `library`
+pub fn generate_pinvoke(
+    types: &[RustType],
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+    strings: StringMarshalling,
+    library: &str,
+    namespace: &str,
+) -> String {
+    let mut code = format!(
+        "// C# declarations of the Rust FFI layer; keep in step with the Rust side\n\n\
+         using System;\nusing System.Runtime.InteropServices;\n\nnamespace {};\n",
+        namespace
+    );
+    for rust_type in types.iter().filter(|t| t.kind == RustTypeKind::Enum) {
+        code.push('\n');
+        code.push_str(&render_enum(rust_type));
+    }
+    let mut imports = String::new();
+    for rust_type in types.iter().filter(|t| t.kind == RustTypeKind::Struct) {
+        let laid_out = match members(rust_type, converters, profile, strings) {
+            Some((delegates, members)) => {
+                code.push('\n');
+                code.push_str(&delegates);
+                code.push_str(&summary(rust_type.doc.as_deref(), ""));
+                code.push_str(&format!("[StructLayout(LayoutKind.Sequential)]\npublic struct {}FFI\n{{\n{}}}\n", rust_type.name, members));
+                true
+            }
+            None => {
+                code.push_str(&format!(
+                    "\n// Opaque: a field of `{}` has no fixed layout; pass `{}FFI` pointers as `IntPtr`\n",
+                    rust_type.name, rust_type.name
+                ));
+                false
+            }
+        };
+        imports.push_str(&render_imports(rust_type, converters, accessors, profile, laid_out));
+    }
+    code.push_str(&format!(
+        "\npublic static class NativeMethods\n{{\n    private const string Library = \"{}\";\n{}}}\n",
+        library, imports
+    ));
+    code
+}
+
+/// C# spelling of a type the Rust FFI layer declares; `None` for ones with no
+/// blittable counterpart
+fn cs_type(ffi: &str) -> Option<&'static str> {
+    Some(match ffi {
+        "bool" => "bool",
+        "i8" => "sbyte",
+        "i16" => "short",
+        "i32" => "int",
+        "i64" => "long",
+        "u8" => "byte",
+        "u16" => "ushort",
+        "u32" => "uint",
+        "u64" => "ulong",
+        "isize" => "nint",
+        "usize" => "nuint",
+        "f32" => "float",
+        "f64" => "double",
+        "*const u8" | "*mut std::ffi::c_void" | "*const std::ffi::c_char" => "IntPtr",
+        pointer if pointer.strip_prefix("*const ").is_some_and(|pointee| pointee.ends_with("FFI")) => "IntPtr",
+        _ => return None,
+    })
+}
+
+/// Parameter `ty name`, with the attribute a `bool` needs to cross as one
+/// byte rather than the four-byte Win32 `BOOL` .NET assumes
+fn declare(ty: &str, name: &str) -> String {
+    match ty {
+        "bool" => format!("[MarshalAs(UnmanagedType.U1)] bool {}", name),
+        ty => format!("{} {}", ty, name),
+    }
+}
+
+fn return_attribute(ty: &str, indent: &str) -> String {
+    match ty {
+        "bool" => format!("{indent}[return: MarshalAs(UnmanagedType.U1)]\n"),
+        _ => String::new(),
+    }
+}
+
+fn summary(doc: Option<&str>, indent: &str) -> String {
+    let Some(doc) = doc else { return String::new() };
+    let lines: String = doc.lines().map(|line| format!("{indent}/// {}\n", line).replace("/// \n", "///\n")).collect();
+    format!("{indent}/// <summary>\n{lines}{indent}/// </summary>\n")
+}
+
+fn callback_name(rust_type: &RustType, field: &str) -> String {
+    format!("{}{}Callback", rust_type.name, pascal_case(field))
+}
+
+/// Delegates and member declarations of the wrapper, in its field order;
+/// `None` once a field has no C# layout
+fn members(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile, strings: StringMarshalling) -> Option<(String, String)> {
+    let mut delegates = String::new();
+    let mut members = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = pascal_case(&field.name);
+        if let Some((params, ret)) = callback_signature(&field.field_type) {
+            let params = params.iter().map(|param| ffi_callback_types(param, profile)).collect::<Result<Vec<_>, _>>().ok()?;
+            let args: Vec<String> = std::iter::once(Some("IntPtr context".to_string()))
+                .chain(
+                    params
+                        .into_iter()
+                        .flatten()
+                        .enumerate()
+                        .map(|(i, ty)| cs_type(&ty).map(|ty| declare(ty, &format!("arg{}", i)))),
+                )
+                .collect::<Option<_>>()?;
+            let ret = match ffi_callback_types(ret, profile).ok()?.as_slice() {
+                [] => "void",
+                [single] => cs_type(single)?,
+                _ => "IntPtr",
+            };
+            delegates.push_str(&format!(
+                "[UnmanagedFunctionPointer(CallingConvention.Cdecl)]\n{}public delegate {} {}({});\n\n",
+                return_attribute(ret, ""),
+                ret,
+                callback_name(rust_type, &field.name),
+                args.join(", ")
+            ));
+            members.push_str(&format!(
+                "    // `{}`, set through `NativeMethods.{}_register_{}`\n    public IntPtr {name};\n    public IntPtr {name}Context;\n",
+                rust_type_name(&field.field_type),
+                snake_case(&rust_type.name),
+                field.name
+            ));
+            continue;
+        }
+        match ffi_field_type(rust_type, field, converters) {
+            RustFieldType::String => {
+                members.push_str(&format!("    public IntPtr {name}Ptr;\n    public nuint {name}Len;\n"));
+                match strings {
+                    StringMarshalling::Raw => {}
+                    StringMarshalling::Utf8 => members.push_str(&format!(
+                        "    public string {name} => Marshal.PtrToStringUTF8({name}Ptr, checked((int){name}Len));\n"
+                    )),
+                    StringMarshalling::Span => members.push_str(&format!(
+                        "    public unsafe ReadOnlySpan<byte> {name} => new ReadOnlySpan<byte>((void*){name}Ptr, checked((int){name}Len));\n"
+                    )),
+                }
+            }
+            RustFieldType::Uuid => members.push_str(&format!("    [MarshalAs(UnmanagedType.ByValArray, SizeConst = 16)]\n    public byte[] {};\n", name)),
+            field_type => match cs_type(&ffi_scalar(field_type, profile).ok()?)? {
+                "bool" => members.push_str(&format!("    [MarshalAs(UnmanagedType.U1)]\n    public bool {};\n", name)),
+                ty => members.push_str(&format!("    public {} {};\n", ty, name)),
+            },
+        }
+    }
+    Some((delegates, members))
+}
+
+/// `[DllImport]`s for the accessors `accessors` keeps and every callback
+/// registration; an opaque wrapper is passed as an `IntPtr`
+fn render_imports(
+    rust_type: &RustType,
+    converters: &ConverterRegistry,
+    accessors: &FfiAccessors,
+    profile: SecurityProfile,
+    laid_out: bool,
+) -> String {
+    let prefix = snake_case(&rust_type.name);
+    let (read, write) = match laid_out {
+        true => (format!("in {}FFI value", rust_type.name), format!("ref {}FFI value", rust_type.name)),
+        false => ("IntPtr value".to_string(), "IntPtr value".to_string()),
+    };
+    let import = |entry: &str, ret: &str, params: &str| {
+        format!(
+            "\n    [DllImport(Library, EntryPoint = \"{entry}\", CallingConvention = CallingConvention.Cdecl)]\n{}    \
+             public static extern {ret} {entry}({params});\n",
+            return_attribute(ret, "    ")
+        )
+    };
+    let mut code = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = &field.name;
+        if callback_signature(&field.field_type).is_some() {
+            let params = format!("{}, {} callback, IntPtr context", write, callback_name(rust_type, name));
+            code.push_str(&import(&format!("{}_register_{}", prefix, name), "void", &params));
+            continue;
+        }
+        if !accessors.includes(&rust_type.name, name) {
+            continue;
+        }
+        let getters = match ffi_field_type(rust_type, field, converters) {
+            RustFieldType::String => vec![(format!("{}_ptr", name), Some("IntPtr")), (format!("{}_len", name), Some("nuint"))],
+            field_type => vec![(name.clone(), ffi_scalar(field_type, profile).ok().and_then(|ffi| cs_type(&ffi)))],
+        };
+        for (member, ty) in getters {
+            match ty {
+                Some(ty) => code.push_str(&import(&format!("{}_{}", prefix, member), ty, &read)),
+                // P/Invoke can't return arrays by value, and the rest have no C# type
+                None => code.push_str(&format!("\n    // `{prefix}_{member}` has no P/Invoke signature; read `{member}` from the struct\n")),
+            }
+        }
+    }
+    code
+}
+
+/// Fieldless enum over its `#[repr]` integer, `int` without one as C does
+fn render_enum(rust_type: &RustType) -> String {
+    let doc = summary(rust_type.doc.as_deref(), "");
+    if rust_type.variants.iter().any(|variant| variant.kind != RustVariantKind::Unit) {
+        return format!("{doc}// `{}` carries data and has no C# form\n", rust_type.name);
+    }
+    let constants: String = rust_type
+        .variants
+        .iter()
+        .map(|variant| match &variant.discriminant {
+            Some(value) => format!("    {} = {},\n", variant.name, value),
+            None => format!("    {},\n", variant.name),
+        })
+        .collect();
+    let repr = rust_type
+        .attributes
+        .iter()
+        .filter_map(|attr| attr.split_once("repr(").map(|(_, rest)| rest.trim_end_matches([')', ']'])))
+        .flat_map(|reprs| reprs.split(',').map(str::trim))
+        .find_map(|repr| cs_type(repr).filter(|ty| !matches!(*ty, "bool" | "float" | "double" | "IntPtr")))
+        .unwrap_or("int");
+    format!("{doc}public enum {} : {}\n{{\n{}}}\n", rust_type.name, repr, constants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::analyze_rust_types;
+
+    const SOURCE: &str = "#[repr(u8)]\npub enum Status { Active = 1, Suspended }\n\
+        /// A signed-up user\n#[repr(C)]\npub struct User { pub id: u64, pub display_name: String, pub active: bool, \
+        pub token: Uuid, pub on_change: Box<dyn Fn(i32) -> bool> }\n\
+        pub struct Team { pub members: Vec<User> }";
+
+    #[test]
+    fn test_pinvoke_mirrors_the_ffi_wrapper() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let code = generate_pinvoke(&types, &ConverterRegistry::default(), &FfiAccessors::All, SecurityProfile::Standard, StringMarshalling::Utf8, "app", "App.Native");
+        assert!(code.contains("namespace App.Native;\n"));
+        assert!(code.contains("public enum Status : byte\n{\n    Active = 1,\n    Suspended,\n}\n"));
+        assert!(code.contains(
+            "[UnmanagedFunctionPointer(CallingConvention.Cdecl)]\n[return: MarshalAs(UnmanagedType.U1)]\npublic delegate bool UserOnChangeCallback(IntPtr context, int arg0);\n\n\
+             /// <summary>\n/// A signed-up user\n/// </summary>\n[StructLayout(LayoutKind.Sequential)]\npublic struct UserFFI\n{\n    public ulong Id;\n    \
+             public IntPtr DisplayNamePtr;\n    public nuint DisplayNameLen;\n    \
+             public string DisplayName => Marshal.PtrToStringUTF8(DisplayNamePtr, checked((int)DisplayNameLen));\n    \
+             [MarshalAs(UnmanagedType.U1)]\n    public bool Active;\n"
+        ));
+        assert!(code.contains("// Opaque: a field of `Team` has no fixed layout"));
+        assert!(code.contains(
+            "    [DllImport(Library, EntryPoint = \"user_active\", CallingConvention = CallingConvention.Cdecl)]\n    \
+             [return: MarshalAs(UnmanagedType.U1)]\n    public static extern bool user_active(in UserFFI value);\n"
+        ));
+        assert!(code.contains("public static extern void user_register_on_change(ref UserFFI value, UserOnChangeCallback callback, IntPtr context);\n"));
+        assert!(code.contains("    // `user_token` has no P/Invoke signature"));
+    }
+
+    #[test]
+    fn test_string_policy_and_paranoid_flags() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let raw = generate_pinvoke(&types, &ConverterRegistry::default(), &FfiAccessors::None, SecurityProfile::Paranoid, StringMarshalling::Raw, "app", "App");
+        assert!(!raw.contains("public string DisplayName"));
+        assert!(raw.contains("    public byte Active;\n"));
+        assert!(!raw.contains("user_id("));
+        let span = generate_pinvoke(&types, &ConverterRegistry::default(), &FfiAccessors::None, SecurityProfile::Standard, StringMarshalling::Span, "app", "App");
+        assert!(span.contains("    public unsafe ReadOnlySpan<byte> DisplayName => new ReadOnlySpan<byte>((void*)DisplayNamePtr, checked((int)DisplayNameLen));\n"));
+    }
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Generated-code footprint estimates
+// Counts what each backend emits and roughly sizes the compiled Rust FFI layer,
+// so embedded users can see what pruning accessors buys them in the cdylib
+
+use std::fmt;
+
+use crate::analyzers::rust_analyzer::{generate_ffi_accessors, generate_ffi_wrapper_with, FfiAccessors, RustType};
+use crate::converters::ConverterRegistry;
+
+/// Machine code of a small, non-inlined function (prologue, body, return)
+const FUNCTION_BYTES: usize = 48;
+/// Dynamic symbol table entry of an exported function, excluding its name
+const SYMBOL_BYTES: usize = 24;
+
+/// Size of the code one backend generates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Footprint {
+    pub backend: String,
+    /// Non-blank lines that aren't comments or attributes
+    pub lines: usize,
+    pub functions: usize,
+    /// `#[no_mangle]` / `extern "C"` symbols the cdylib exports
+    pub exported: usize,
+    /// Rough compiled size in bytes; only estimated for the Rust FFI layer
+    pub estimated_bytes: Option<usize>,
+}
+
+impl Footprint {
+    /// Count lines and functions of generated source
+    pub fn measure(backend: &str, code: &str) -> Self {
+        let lines = code
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with('#'))
+            .count();
+        let functions = code.matches("fn ").count() + code.matches("function ").count();
+        Footprint {
+            backend: backend.to_string(),
+            lines,
+            functions,
+            exported: code.matches("#[no_mangle]").count(),
+            estimated_bytes: None,
+        }
+    }
+
+    /// Measure the Rust FFI layer (wrappers, codecs, accessors) and estimate its
+    /// compiled size: a function body each, plus symbol table space for exports
+    pub fn rust_ffi(types: &[RustType], converters: &ConverterRegistry, accessors: &FfiAccessors) -> Result<Self, String> {
+        let code = types
+            .iter()
+            .map(|t| Ok(generate_ffi_wrapper_with(t, converters)? + &generate_ffi_accessors(t, converters, accessors)?))
+            .collect::<Result<String, String>>()?;
+        let mut footprint = Footprint::measure("rust-ffi", &code);
+        let symbol_names: usize = code
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub extern \"C\" fn ").or_else(|| line.strip_prefix("pub unsafe extern \"C\" fn ")))
+            .map(|rest| rest.find('(').unwrap_or(rest.len()) + 1)
+            .sum();
+        footprint.estimated_bytes =
+            Some(footprint.functions * FUNCTION_BYTES + footprint.exported * SYMBOL_BYTES + symbol_names);
+        Ok(footprint)
+    }
+}
+
+impl fmt::Display for Footprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} lines, {} functions", self.backend, self.lines, self.functions)?;
+        if self.exported > 0 {
+            write!(f, " ({} exported)", self.exported)?;
+        }
+        if let Some(bytes) = self.estimated_bytes {
+            write!(f, ", ~{:.1} KiB compiled", bytes as f32 / 1024.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Footprints of every backend in a run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FootprintReport {
+    pub backends: Vec<Footprint>,
+}
+
+impl FootprintReport {
+    pub fn push(&mut self, footprint: Footprint) {
+        self.backends.push(footprint);
+    }
+
+    pub fn total_lines(&self) -> usize {
+        self.backends.iter().map(|footprint| footprint.lines).sum()
+    }
+}
+
+impl fmt::Display for FootprintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Generated code footprint ({} lines):", self.total_lines())?;
+        for footprint in &self.backends {
+            writeln!(f, "  {}", footprint)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::analyze_rust_types;
+
+    #[test]
+    fn test_pruning_accessors_shrinks_ffi_layer() {
+        let types = analyze_rust_types("pub struct User { pub id: i64, pub name: String, pub active: bool }").unwrap();
+        let converters = ConverterRegistry::default();
+        let all = Footprint::rust_ffi(&types, &converters, &FfiAccessors::All).unwrap();
+        let used = Footprint::rust_ffi(&types, &converters, &FfiAccessors::Used(vec!["id".to_string()])).unwrap();
+        assert_eq!(all.exported, 5);
+        assert_eq!(used.exported, 2);
+        // `from`, `try_from`, `user_free` and `user_id`; the exported names plus terminators
+        assert_eq!(used.estimated_bytes, Some(4 * FUNCTION_BYTES + 2 * SYMBOL_BYTES + 10 + 8));
+        assert!(used.estimated_bytes < all.estimated_bytes);
+        assert_eq!(
+            Footprint::rust_ffi(&types, &converters, &FfiAccessors::None).unwrap().estimated_bytes,
+            Some(3 * FUNCTION_BYTES + SYMBOL_BYTES + 10)
+        );
+    }
+
+    #[test]
+    fn test_report_lists_backends() {
+        let mut report = FootprintReport::default();
+        report.push(Footprint::measure("typescript", "// User\nexport interface User {\n  id: number;\n}\n\nexport function id(u: User) { return u.id; }\n"));
+        assert_eq!(report.backends[0].lines, 4);
+        assert_eq!(report.backends[0].functions, 1);
+        assert_eq!(report.to_string(), "Generated code footprint (4 lines):\n  typescript: 4 lines, 1 functions\n");
+    }
+}
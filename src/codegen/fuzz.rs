@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Analyzer fuzzing harness generator
+// Emits one cargo-fuzz target per analyzer that feeds arbitrary UTF-8 through
+// the real parser, plus libFuzzer options that turn slow inputs into timeouts
+
+/// Analyzer entry point exercised by a generated fuzz target
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzTarget {
+    /// Analyzer module under `language_interop::analyzers`
+    pub module: &'static str,
+    /// Parser function taking `&str`
+    pub analyze: &'static str,
+    /// Whether the parser returns every type in the input (`Vec`) or only the first
+    pub returns_many: bool,
+    /// Target language passed to `compatibility_score` for each analyzed type
+    pub score_against: &'static str,
+}
+
+impl FuzzTarget {
+    /// Name of the cargo-fuzz binary (`fuzz_rust_analyzer`)
+    pub fn name(&self) -> String {
+        format!("fuzz_{}", self.module)
+    }
+}
+
+/// Seconds before libFuzzer reports an input as a hang
+pub const FUZZ_TIMEOUT_SECS: u32 = 2;
+/// Largest input handed to a parser
+pub const FUZZ_MAX_LEN: usize = 10_000;
+
+/// One fuzz target per analyzer; add new analyzers here
+pub fn fuzz_targets() -> Vec<FuzzTarget> {
+    vec![
+        FuzzTarget { module: "rust_analyzer", analyze: "analyze_rust_types", returns_many: true, score_against: "rescript" },
+        FuzzTarget { module: "rescript_analyzer", analyze: "analyze_rescript_type", returns_many: false, score_against: "rust" },
+        FuzzTarget { module: "arrow_analyzer", analyze: "analyze_arrow_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "asn1_analyzer", analyze: "analyze_asn1_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "avro_analyzer", analyze: "analyze_avro_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "c_analyzer", analyze: "analyze_c_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "capnp_analyzer", analyze: "analyze_capnp_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "cddl_analyzer", analyze: "analyze_cddl_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "cpp_analyzer", analyze: "analyze_cpp_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "crystal_analyzer", analyze: "analyze_crystal_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "csharp_analyzer", analyze: "analyze_csharp_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "dart_analyzer", analyze: "analyze_dart_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "elixir_analyzer", analyze: "analyze_elixir_types", returns_many: true, score_against: "gleam" },
+        FuzzTarget { module: "erlang_analyzer", analyze: "analyze_erlang_types", returns_many: true, score_against: "gleam" },
+        FuzzTarget { module: "flatbuffers_analyzer", analyze: "analyze_flatbuffers_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "fsharp_analyzer", analyze: "analyze_fsharp_types", returns_many: true, score_against: "rescript" },
+        FuzzTarget { module: "java_analyzer", analyze: "analyze_java_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "json_schema_analyzer", analyze: "analyze_json_schema_types", returns_many: true, score_against: "typescript" },
+        FuzzTarget { module: "lua_analyzer", analyze: "analyze_lua_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "nim_analyzer", analyze: "analyze_nim_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "ocaml_analyzer", analyze: "analyze_ocaml_types", returns_many: true, score_against: "rescript" },
+        FuzzTarget { module: "openapi_analyzer", analyze: "analyze_openapi_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "php_analyzer", analyze: "analyze_php_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "proto_analyzer", analyze: "analyze_proto_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "ruby_analyzer", analyze: "analyze_ruby_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "sql_analyzer", analyze: "analyze_sql_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "swift_analyzer", analyze: "analyze_swift_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "thrift_analyzer", analyze: "analyze_thrift_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "typescript_analyzer", analyze: "analyze_typescript_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "xsd_analyzer", analyze: "analyze_xsd_types", returns_many: true, score_against: "rust" },
+        FuzzTarget { module: "zig_analyzer", analyze: "analyze_zig_types", returns_many: true, score_against: "rust" },
+    ]
+}
+
+/// Generate the `fuzz_targets/<name>.rs` source for an analyzer
+pub fn generate_fuzz_target(target: &FuzzTarget) -> String {
+    let score = format!(
+        "let _ = {}::compatibility_score(analyzed, \"{}\");",
+        target.module, target.score_against
+    );
+    let body = if target.returns_many {
+        format!(
+            "        if let Ok(types) = {module}::{analyze}(input) {{\n            for analyzed in &types {{\n                {score}\n            }}\n        }}\n",
+            module = target.module,
+            analyze = target.analyze,
+        )
+    } else {
+        format!(
+            "        if let Ok(analyzed) = {module}::{analyze}(input) {{\n            let analyzed = &analyzed;\n            {score}\n        }}\n",
+            module = target.module,
+            analyze = target.analyze,
+        )
+    };
+
+    format!(
+        "// SPDX-License-Identifier: PMPL-1.0-or-later\n\
+         // GENERATED by language-interop-compiler (codegen::fuzz) - do not edit\n\
+         #![no_main]\n\
+         use language_interop::analyzers::{module};\n\
+         use libfuzzer_sys::fuzz_target;\n\
+         \n\
+         fuzz_target!(|data: &[u8]| {{\n    \
+         // Arbitrary UTF-8 goes straight into the real parser: panics abort the run,\n    \
+         // slow inputs trip the timeout in fuzz/options/{name}.options\n    \
+         if let Ok(input) = std::str::from_utf8(data) {{\n\
+         {body}    }}\n\
+         }});\n",
+        module = target.module,
+        name = target.name(),
+    )
+}
+
+/// Generate the libFuzzer `.options` file shared by every analyzer target
+pub fn generate_options() -> String {
+    format!("[libfuzzer]\ntimeout = {}\nmax_len = {}\n", FUZZ_TIMEOUT_SECS, FUZZ_MAX_LEN)
+}
+
+/// Generate the `[[bin]]` sections for `fuzz/Cargo.toml`
+pub fn generate_cargo_bins(targets: &[FuzzTarget]) -> String {
+    targets
+        .iter()
+        .map(|target| {
+            format!(
+                "[[bin]]\nname = \"{name}\"\npath = \"fuzz_targets/{name}.rs\"\ntest = false\ndoc = false\n",
+                name = target.name()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn fuzz_dir() -> &'static Path {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+    }
+
+    #[test]
+    fn test_generated_target_calls_parser() {
+        let source = generate_fuzz_target(&fuzz_targets()[0]);
+        assert!(source.contains("use language_interop::analyzers::rust_analyzer;"));
+        assert!(source.contains("rust_analyzer::analyze_rust_types(input)"));
+        assert!(source.contains("fuzz/options/fuzz_rust_analyzer.options"));
+    }
+
+    #[test]
+    fn test_checked_in_targets_match_generator() {
+        let manifest = std::fs::read_to_string(fuzz_dir().join("Cargo.toml")).unwrap();
+        assert!(manifest.contains(&generate_cargo_bins(&fuzz_targets())));
+
+        for target in fuzz_targets() {
+            let name = target.name();
+            let source = std::fs::read_to_string(fuzz_dir().join(format!("fuzz_targets/{}.rs", name)));
+            assert_eq!(source.ok(), Some(generate_fuzz_target(&target)), "fuzz target {} is stale", name);
+            let options = std::fs::read_to_string(fuzz_dir().join(format!("options/{}.options", name)));
+            assert_eq!(options.ok(), Some(generate_options()), "options for {} are stale", name);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Gleam bindings to Rust and ReScript structs
+// Type definitions with JSON codecs, plus `@external` functions into the
+// compiled source side: a NIF module on Erlang, the JavaScript module on
+// JavaScript. Values coming back are decoded, so Gleam only ever holds ones
+// its decoder has checked
+
+use super::scaffold::{generate_scaffold, scaffold_header};
+use super::snake_case;
+use crate::api::{Language, TypeDef};
+
+/// Gleam module binding `types` to the `source` side compiled into `module`,
+/// a JavaScript path whose file stem also names the Erlang module; `None`
+/// unless every type is a struct. ReScript only compiles to JavaScript, so
+/// its types get JavaScript externals only
+pub fn render_module(types: &[TypeDef], source: Language, module: &str) -> Option<String> {
+    if types.iter().any(|def| !def.variants.is_empty() || def.aliased.is_some()) {
+        return None;
+    }
+    let erlang = match source {
+        Language::Rust => Some(erlang_module(module)),
+        _ => None,
+    };
+    let mut code = match &erlang {
+        Some(erlang) => format!("// Bindings to {} {:?} type(s): `{}` on Erlang, {} on JavaScript\n\n", types.len(), source, erlang, module),
+        None => format!("// Bindings to {} {:?} type(s) exported from {}\n\n", types.len(), source, module),
+    };
+    code.push_str(&scaffold_header("gleam", types)?);
+    code.push_str("import gleam/dynamic.{type Dynamic}\n");
+    for def in types {
+        code.push('\n');
+        code.push_str(&generate_scaffold(def, types, "gleam")?);
+        code.push('\n');
+        code.push_str(&render_externals(def, source, erlang.as_deref(), module));
+    }
+    Some(code)
+}
+
+/// Erlang module name of a JavaScript module path (`./pkg/app.mjs` → `app`)
+fn erlang_module(module: &str) -> String {
+    let file = module.rsplit('/').next().unwrap_or(module);
+    snake_case(file.split('.').next().unwrap_or(file))
+}
+
+/// Parse and serialize functions for `def` and the externals behind them,
+/// named as `source` exports them: `user_from_json` from Rust, `userFromJson`
+/// from ReScript
+fn render_externals(def: &TypeDef, source: Language, erlang: Option<&str>, module: &str) -> String {
+    let snake = snake_case(&def.name);
+    let name = super::pascal_case(&def.name);
+    let (from_json, to_json) = match source {
+        Language::Rust => (format!("{}_from_json", snake), format!("{}_to_json", snake)),
+        _ => (format!("{}FromJson", super::camel_case(&def.name)), format!("{}ToJson", super::camel_case(&def.name))),
+    };
+    let external = |function: &str| {
+        let erlang = erlang.map(|erlang| format!("@external(erlang, \"{}\", \"{}\")\n", erlang, function)).unwrap_or_default();
+        format!("{}@external(javascript, \"{}\", \"{}\")\n", erlang, module, function)
+    };
+    format!(
+        "/// Parse JSON into a `{name}` through the {source} side, which rejects what it would\n\
+         pub fn parse_{snake}(json: String) -> Result({name}, List(decode.DecodeError)) {{\n  \
+         decode.run({snake}_from_json_ffi(json), {snake}_decoder())\n}}\n\n\
+         /// Serialize a `{name}` the way the {source} side does\n\
+         pub fn serialize_{snake}(value: {name}) -> String {{\n  {snake}_to_json_ffi({snake}_to_json(value))\n}}\n\n\
+         {}fn {snake}_from_json_ffi(json: String) -> Dynamic\n\n\
+         {}fn {snake}_to_json_ffi(value: json.Json) -> String\n",
+        external(&from_json),
+        external(&to_json),
+        source = format!("{:?}", source),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Registry;
+
+    #[test]
+    fn test_externals_cover_both_targets() {
+        let registry = Registry::with_builtins();
+        let types = registry
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze("pub struct User { pub id: u32, pub display_name: String }")
+            .unwrap();
+        let code = render_module(&types, Language::Rust, "./pkg/app.mjs").unwrap();
+        assert!(code.starts_with("// Bindings to 1 Rust type(s): `app` on Erlang, ./pkg/app.mjs on JavaScript\n"));
+        assert!(code.contains("pub fn user_decoder() -> decode.Decoder(User) {"));
+        assert!(code.contains("pub fn parse_user(json: String) -> Result(User, List(decode.DecodeError)) {\n  decode.run(user_from_json_ffi(json), user_decoder())\n}\n"));
+        assert!(code.contains(
+            "@external(erlang, \"app\", \"user_from_json\")\n@external(javascript, \"./pkg/app.mjs\", \"user_from_json\")\nfn user_from_json_ffi(json: String) -> Dynamic\n"
+        ));
+
+        let types = registry
+            .analyzer(Language::ReScript)
+            .unwrap()
+            .analyze("type user = { id: int, displayName: string }")
+            .unwrap();
+        let code = render_module(&types, Language::ReScript, "./User.mjs").unwrap();
+        assert!(code.contains("@external(javascript, \"./User.mjs\", \"userToJson\")\nfn user_to_json_ffi(value: json.Json) -> String\n"));
+        assert!(!code.contains("@external(erlang"));
+        assert!(render_module(&registry.analyzer(Language::Rust).unwrap().analyze("pub enum Status { Active }").unwrap(), Language::Rust, "./app.mjs").is_none());
+    }
+}
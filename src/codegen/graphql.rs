@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// GraphQL SDL generation
+// Each struct becomes an object type and an input type, so a schema can both
+// return and accept it. GraphQL has no maps, no tuples, no unions of scalars,
+// and a 32-bit `Int`; what it can't express is written the nearest way it can
+// and recorded in a mapping report next to the SDL
+
+use std::fmt;
+
+use super::variants::{is_positional, payload_key};
+use super::{camel_case, pascal_case, render_type, snake_case, unions::union_name};
+use crate::api::TypeDef;
+use crate::codegen::newtypes::newtype_inner;
+use crate::ir::{normalize_name, ContainerType, DefaultValue, IrField, IrType, IrVariant, PrimitiveType};
+
+/// A construct GraphQL can't express, and what was written instead
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gap {
+    /// A map, written as the `JSON` scalar
+    Map,
+    /// A tuple or `Result`, written as the `JSON` scalar
+    Structural,
+    /// A union GraphQL can't declare (scalar members, or any union in an
+    /// input), written as the `JSON` scalar
+    Union,
+    /// An integer wider than `Int`'s signed 32 bits, written as the `BigInt` scalar
+    WideInteger(PrimitiveType),
+    /// A fixed-length array, written as a list of any length
+    FixedLength(usize),
+    /// A variant without a payload, given a placeholder field, since an
+    /// object type needs at least one
+    UnitVariant,
+    /// An object or input with every field left out, given a placeholder
+    /// field, since GraphQL rejects an empty one
+    NoFields,
+    /// Left out of the schema
+    LeftOut(&'static str),
+}
+
+/// One place the SDL departs from the analyzed type
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingNote {
+    pub type_name: String,
+    /// Field or variant the note is about; `None` for the whole type
+    pub member: Option<String>,
+    pub gap: Gap,
+}
+
+impl fmt::Display for MappingNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.member {
+            Some(member) => write!(f, "{}.{}: ", self.type_name, member)?,
+            None => write!(f, "{}: ", self.type_name)?,
+        }
+        match &self.gap {
+            Gap::Map => f.write_str("map written as the `JSON` scalar"),
+            Gap::Structural => f.write_str("tuple or result written as the `JSON` scalar"),
+            Gap::Union => f.write_str("union written as the `JSON` scalar"),
+            Gap::WideInteger(primitive) => {
+                write!(f, "`{}` is wider than `Int`, written as the `BigInt` scalar", render_type(&IrType::Primitive(*primitive), "rust"))
+            }
+            Gap::FixedLength(len) => write!(f, "list of exactly {} items written as a list of any length", len),
+            Gap::UnitVariant => f.write_str("variant without a payload gets a placeholder `unit` field"),
+            Gap::NoFields => f.write_str("no fields left, given a placeholder `unit` field"),
+            Gap::LeftOut(reason) => write!(f, "left out: {}", reason),
+        }
+    }
+}
+
+/// SDL for a set of types, with what it couldn't express
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphqlSchema {
+    pub sdl: String,
+    pub notes: Vec<MappingNote>,
+}
+
+/// Object and input types for `types`: enums of unit variants become GraphQL
+/// enums, other sum types a union of per-variant objects and a `@oneOf` input
+pub fn generate_graphql(types: &[TypeDef]) -> GraphqlSchema {
+    let mut writer = Writer { types, notes: Vec::new(), unions: Vec::new() };
+    let mut body = String::new();
+    for def in types {
+        let name = pascal_case(&def.name);
+        if !def.type_params.is_empty() {
+            writer.note(&name, None, Gap::LeftOut("GraphQL has no generic types"));
+        } else if def.aliased.is_some() || newtype_inner(def).is_some() {
+            // Fields holding it use the type it stands for
+        } else if def.variants.is_empty() {
+            body.push('\n');
+            body.push_str(&writer.object(def, &name, &def.fields, false));
+            body.push('\n');
+            body.push_str(&writer.object(def, &name, &def.fields, true));
+        } else if def.variants.iter().all(|variant| variant.fields.is_empty()) {
+            body.push('\n');
+            body.push_str(&description(def.doc.as_deref(), ""));
+            let values: String = def.variants.iter().map(|variant| format!("  {}\n", snake_case(&variant.name).to_uppercase())).collect();
+            body.push_str(&format!("enum {} {{\n{}}}\n", name, values));
+        } else {
+            body.push('\n');
+            body.push_str(&writer.sum(def, &name));
+        }
+    }
+    for (name, members) in std::mem::take(&mut writer.unions) {
+        body.push_str(&format!("\nunion {} = {}\n", name, members.join(" | ")));
+    }
+    let scalars: String = ["BigInt", "Base64", "DateTime", "Decimal", "JSON", "UUID"]
+        .iter()
+        .filter(|scalar| body.contains(&format!(": {}", scalar)) || body.contains(&format!("[{}", scalar)))
+        .map(|scalar| format!("scalar {}\n", scalar))
+        .collect();
+    let sdl = match scalars.is_empty() {
+        true => body.trim_start().to_string(),
+        false => scalars + &body,
+    };
+    GraphqlSchema { sdl, notes: writer.notes }
+}
+
+struct Writer<'a> {
+    types: &'a [TypeDef],
+    notes: Vec<MappingNote>,
+    /// Unions of object types fields declared, by name
+    unions: Vec<(String, Vec<String>)>,
+}
+
+impl Writer<'_> {
+    fn note(&mut self, type_name: &str, member: Option<&str>, gap: Gap) {
+        let note = MappingNote { type_name: type_name.to_string(), member: member.map(str::to_string), gap };
+        if !self.notes.contains(&note) {
+            self.notes.push(note);
+        }
+    }
+
+    /// `type Name` or `input NameInput` of `fields`; an input leaves out what
+    /// is only ever written, an object what is only ever read
+    fn object(&mut self, def: &TypeDef, name: &str, fields: &[IrField], input: bool) -> String {
+        let mut members = String::new();
+        for field in fields {
+            let wanted = match input {
+                true => field.direction.deserializes(),
+                false => field.direction.serializes(),
+            };
+            if !wanted {
+                continue;
+            }
+            let member = match field.name.starts_with('_') {
+                true => field.name.clone(),
+                false => camel_case(&field.name),
+            };
+            let Some(ty) = self.field_type(&field.ty, input, name, &member) else {
+                self.note(name, Some(&member), Gap::LeftOut("its type has no GraphQL form"));
+                continue;
+            };
+            // An input field may be left out when it has a default; without a
+            // literal to declare, only a nullable one may
+            let (ty, default) = match (input, field.default.as_ref().map(|default| default_value(default, &field.ty))) {
+                (true, Some(Some(value))) => (ty, format!(" = {}", value)),
+                (true, Some(None)) => (ty.trim_end_matches('!').to_string(), String::new()),
+                _ => (ty, String::new()),
+            };
+            members.push_str(&description(field.doc.as_deref(), "  "));
+            members.push_str(&format!("  {}: {}{}\n", member, ty, default));
+        }
+        let declared = match input {
+            true => format!("{}Input", name),
+            false => name.to_string(),
+        };
+        if members.is_empty() {
+            self.note(&declared, None, Gap::NoFields);
+            members.push_str("  unit: Boolean\n");
+        }
+        let doc = description(def.doc.as_deref().filter(|_| !input && def.variants.is_empty()), "");
+        match input {
+            true => format!("{doc}input {declared} {{\n{members}}}\n"),
+            false => format!("{doc}type {declared} {{\n{members}}}\n"),
+        }
+    }
+
+    /// A sum type with payloads: a union of one object per variant, and an
+    /// input with exactly one variant field set
+    fn sum(&mut self, def: &TypeDef, name: &str) -> String {
+        let mut code = description(def.doc.as_deref(), "");
+        let members: Vec<String> = def.variants.iter().map(|variant| format!("{}{}", name, pascal_case(&variant.name))).collect();
+        code.push_str(&format!("union {} = {}\n", name, members.join(" | ")));
+        let mut inputs = String::new();
+        for (variant, member) in def.variants.iter().zip(&members) {
+            let field = camel_case(&variant.name);
+            if variant.fields.is_empty() {
+                self.note(name, Some(&variant.name), Gap::UnitVariant);
+                code.push_str(&format!("\ntype {} {{\n  unit: Boolean\n}}\n", member));
+                inputs.push_str(&format!("  {}: Boolean\n", field));
+                continue;
+            }
+            code.push('\n');
+            code.push_str(&self.object(def, member, &payload_fields(variant), false));
+            code.push('\n');
+            code.push_str(&self.object(def, member, &payload_fields(variant), true));
+            inputs.push_str(&format!("  {}: {}Input\n", field, member));
+        }
+        code.push_str(&format!("\ninput {}Input @oneOf {{\n{}}}\n", name, inputs));
+        code
+    }
+
+    /// Non-null GraphQL type of a field of `ty`, or `None` when it has no GraphQL form
+    fn field_type(&mut self, ty: &IrType, input: bool, owner: &str, member: &str) -> Option<String> {
+        let json = |writer: &mut Self, gap| {
+            writer.note(owner, Some(member), gap);
+            Some("JSON!".to_string())
+        };
+        match ty {
+            IrType::Container(ContainerType::Option(inner)) => {
+                let inner = self.field_type(inner, input, owner, member)?;
+                Some(inner.trim_end_matches('!').to_string())
+            }
+            IrType::Container(ContainerType::Vec(inner)) => Some(format!("[{}]!", self.field_type(inner, input, owner, member)?)),
+            IrType::Container(ContainerType::Array(inner, len)) => {
+                self.note(owner, Some(member), Gap::FixedLength(*len));
+                Some(format!("[{}]!", self.field_type(inner, input, owner, member)?))
+            }
+            IrType::Container(ContainerType::Map(_, _)) => json(self, Gap::Map),
+            IrType::Container(ContainerType::Result(_, _)) | IrType::Tuple(_) => json(self, Gap::Structural),
+            IrType::Union(members) => {
+                let objects: Option<Vec<String>> = members.iter().map(|member| self.object_name(member)).collect();
+                match objects {
+                    Some(objects) if !input => {
+                        let name = pascal_case(&union_name(members));
+                        if !self.unions.iter().any(|(declared, _)| *declared == name) {
+                            self.unions.push((name.clone(), objects));
+                        }
+                        Some(format!("{}!", name))
+                    }
+                    _ => json(self, Gap::Union),
+                }
+            }
+            IrType::Primitive(PrimitiveType::Unit) => None,
+            IrType::Primitive(primitive) => {
+                let scalar = match primitive {
+                    PrimitiveType::Bool => "Boolean",
+                    PrimitiveType::I8 | PrimitiveType::I16 | PrimitiveType::I32 | PrimitiveType::U8 | PrimitiveType::U16 => "Int",
+                    PrimitiveType::U32 | PrimitiveType::I64 | PrimitiveType::U64 | PrimitiveType::I128 | PrimitiveType::U128 => {
+                        self.note(owner, Some(member), Gap::WideInteger(*primitive));
+                        "BigInt"
+                    }
+                    PrimitiveType::F32 | PrimitiveType::F64 => "Float",
+                    PrimitiveType::String => "String",
+                    PrimitiveType::DateTime => "DateTime",
+                    PrimitiveType::Uuid => "UUID",
+                    PrimitiveType::Decimal => "Decimal",
+                    PrimitiveType::BigInt => "BigInt",
+                    PrimitiveType::Bytes => "Base64",
+                    PrimitiveType::Unit => return None,
+                };
+                Some(format!("{}!", scalar))
+            }
+            IrType::Any => Some("JSON!".to_string()),
+            IrType::Reference(name) | IrType::Alias(name, _) => {
+                let Some(def) = self.lookup(name) else {
+                    return match ty {
+                        IrType::Alias(_, inner) => self.field_type(inner, input, owner, member),
+                        _ => Some(format!("{}!", pascal_case(name))),
+                    };
+                };
+                if let Some(aliased) = def.aliased.as_ref().or_else(|| newtype_inner(def)) {
+                    return self.field_type(&aliased.clone(), input, owner, member);
+                }
+                if !def.type_params.is_empty() {
+                    return None;
+                }
+                // Enums read the same both ways; everything else has an input twin
+                let enumeration = !def.variants.is_empty() && def.variants.iter().all(|variant| variant.fields.is_empty());
+                match input && !enumeration {
+                    true => Some(format!("{}Input!", pascal_case(&def.name))),
+                    false => Some(format!("{}!", pascal_case(&def.name))),
+                }
+            }
+            // Like their generic definitions, instantiations have no GraphQL form
+            IrType::Generic(_, _) | IrType::TypeParam(_) | IrType::Function(_, _) | IrType::TraitObject(_) => None,
+        }
+    }
+
+    /// Name of the object type `ty` refers to, if it is one; only those can be union members
+    fn object_name(&self, ty: &IrType) -> Option<String> {
+        let (IrType::Reference(name) | IrType::Alias(name, _)) = ty else { return None };
+        let def = self.lookup(name)?;
+        let object = def.variants.is_empty() && def.type_params.is_empty() && def.aliased.is_none() && newtype_inner(def).is_none();
+        object.then(|| pascal_case(&def.name))
+    }
+
+    fn lookup(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|def| normalize_name(&def.name) == normalize_name(name))
+    }
+}
+
+/// A variant's fields, positional ones keyed `_0`, `_1`, ...
+fn payload_fields(variant: &IrVariant) -> Vec<IrField> {
+    variant
+        .fields
+        .iter()
+        .map(|field| match is_positional(field) {
+            true => IrField { name: payload_key(field), ..field.clone() },
+            false => field.clone(),
+        })
+        .collect()
+}
+
+/// Default of an input field as a GraphQL literal
+fn default_value(default: &DefaultValue, ty: &IrType) -> Option<String> {
+    Some(match default {
+        DefaultValue::Bool(value) => value.to_string(),
+        DefaultValue::Number(number) => number.clone(),
+        DefaultValue::String(text) => format!("{:?}", text),
+        DefaultValue::Null => "null".to_string(),
+        DefaultValue::Empty if matches!(ty, IrType::Container(ContainerType::Map(_, _))) => "{}".to_string(),
+        DefaultValue::Empty => "[]".to_string(),
+        DefaultValue::TypeDefault | DefaultValue::Computed(_) => return None,
+    })
+}
+
+/// `doc` as a GraphQL description
+fn description(doc: Option<&str>, indent: &str) -> String {
+    match doc {
+        None => String::new(),
+        Some(doc) if !doc.contains('\n') => format!("{indent}{:?}\n", doc),
+        Some(doc) => {
+            let lines: String = doc.lines().map(|line| format!("{indent}{}\n", line).replace(&format!("{indent}\n"), "\n")).collect();
+            format!("{indent}\"\"\"\n{lines}{indent}\"\"\"\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_structs_become_object_and_input_types() {
+        let types = analyze_rust(
+            "/// A person\npub struct User { pub id: u64, pub age: u8, #[serde(default)] pub tags: Vec<String>, pub home: Option<Address>, \
+             pub labels: HashMap<String, String>, pub on_change: Box<dyn Fn(i32)>, pub email: Email }\n\
+             pub struct Address { pub city: String }\npub struct Email(String);\npub struct Page<T> { pub items: Vec<T> }",
+        );
+        let schema = generate_graphql(&types);
+        assert!(schema.sdl.starts_with(
+            "scalar BigInt\nscalar JSON\n\n\"A person\"\ntype User {\n  id: BigInt!\n  age: Int!\n  tags: [String!]!\n  home: Address\n  labels: JSON!\n  email: String!\n}\n\n\
+             input UserInput {\n  id: BigInt!\n  age: Int!\n  tags: [String!]\n  home: AddressInput\n  labels: JSON!\n  email: String!\n}\n"
+        ));
+        let notes: Vec<String> = schema.notes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            notes,
+            vec![
+                "User.id: `u64` is wider than `Int`, written as the `BigInt` scalar",
+                "User.labels: map written as the `JSON` scalar",
+                "User.onChange: left out: its type has no GraphQL form",
+                "Page: left out: GraphQL has no generic types",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sum_types_become_enums_unions_and_one_of_inputs() {
+        let types = analyze_rust("pub enum Color { Red, DarkBlue }\npub enum Shape { Circle { radius: f64 }, Empty, Dot(i32) }");
+        let schema = generate_graphql(&types);
+        assert!(schema.sdl.starts_with("enum Color {\n  RED\n  DARK_BLUE\n}\n\nunion Shape = ShapeCircle | ShapeEmpty | ShapeDot\n"));
+        assert!(schema.sdl.contains("type ShapeEmpty {\n  unit: Boolean\n}\n\ntype ShapeDot {\n  _0: Int!\n}\n"));
+        assert!(schema.sdl.contains("input ShapeInput @oneOf {\n  circle: ShapeCircleInput\n  empty: Boolean\n  dot: ShapeDotInput\n}\n"));
+        assert_eq!(schema.notes[0].gap, Gap::UnitVariant);
+    }
+
+    #[test]
+    fn test_objects_without_fields_get_a_placeholder() {
+        let types = analyze_rust(
+            "pub struct Marker {}
+pub struct Hook { pub run: Box<dyn Fn()> }
+             pub struct Stamp { #[serde(skip_deserializing)] pub at: String }",
+        );
+        let schema = generate_graphql(&types);
+        assert!(schema.sdl.starts_with("type Marker {\n  unit: Boolean\n}\n\ninput MarkerInput {\n  unit: Boolean\n}\n"));
+        assert!(schema.sdl.contains("type Hook {\n  unit: Boolean\n}\n"));
+        assert!(schema.sdl.contains("type Stamp {\n  at: String!\n}\n\ninput StampInput {\n  unit: Boolean\n}\n"));
+        let notes: Vec<String> = schema.notes.iter().map(ToString::to_string).collect();
+        assert!(notes.contains(&"HookInput: no fields left, given a placeholder `unit` field".to_string()));
+        assert!(notes.contains(&"StampInput: no fields left, given a placeholder `unit` field".to_string()));
+        assert!(!notes.iter().any(|note| note.starts_with("Stamp:")));
+    }
+}
@@ -0,0 +1,494 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// JNI bindings for Rust structs, enums and free functions
+// The Java side is one class holding a nested class per struct, an enum per
+// fieldless enum, and a `static native` method per function; the Rust side is
+// the `Java_...` exports behind those methods, with helpers marshalling each
+// type field by field. Java has no unsigned integers, so those widen to the
+// next signed type and narrow back checked; failures surface in Java as an
+// `IllegalArgumentException`
+
+use super::{camel_case, pascal_case, snake_case};
+use crate::analyzers::rust_analyzer::{is_zero_sized, RustFieldType, RustFunction, RustType, RustTypeKind, RustVariantKind};
+
+/// Names of the Rust types with a Java counterpart, and the class nesting them
+struct Classes<'a> {
+    objects: Vec<&'a str>,
+    enums: Vec<&'a str>,
+    /// JNI path of the outer class (`com/example/app/Native`)
+    path: String,
+}
+
+impl<'a> Classes<'a> {
+    /// Structs whose every field marshals, given the types around them, to a fixpoint
+    fn of(types: &'a [RustType], class: &str) -> Self {
+        let enums = types
+            .iter()
+            .filter(|t| {
+                t.kind == RustTypeKind::Enum
+                    && t.type_params.is_empty()
+                    && !t.variants.is_empty()
+                    && t.variants.iter().all(|variant| variant.kind == RustVariantKind::Unit)
+            })
+            .map(|t| t.name.as_str())
+            .collect();
+        let mut classes = Classes {
+            objects: types
+                .iter()
+                .filter(|t| t.kind == RustTypeKind::Struct && t.type_params.is_empty() && t.const_params.is_empty())
+                .filter(|t| t.fields.iter().all(|field| !field.name.starts_with(|c: char| c.is_ascii_digit())))
+                .map(|t| t.name.as_str())
+                .collect(),
+            enums,
+            path: class.replace('.', "/"),
+        };
+        loop {
+            let kept: Vec<&str> = types
+                .iter()
+                .filter(|t| classes.objects.contains(&t.name.as_str()))
+                .filter(|t| t.fields.iter().all(|field| is_zero_sized(&field.field_type) || classes.marshals(&field.field_type)))
+                .map(|t| t.name.as_str())
+                .collect();
+            if kept.len() == classes.objects.len() {
+                return classes;
+            }
+            classes.objects = kept;
+        }
+    }
+
+    fn is_class(&self, name: &str) -> bool {
+        self.objects.contains(&name) || self.enums.contains(&name)
+    }
+
+    /// Whether a value of `ty` has a Java counterpart; `Option` needs one held
+    /// by reference, since a Java primitive can't be null
+    fn marshals(&self, ty: &RustFieldType) -> bool {
+        match ty {
+            RustFieldType::Alias(_, inner) => self.marshals(inner),
+            RustFieldType::Option(inner) => self.is_object(inner),
+            ty => primitive(ty).is_some() || self.is_object(ty),
+        }
+    }
+
+    fn is_object(&self, ty: &RustFieldType) -> bool {
+        match ty {
+            RustFieldType::Alias(_, inner) => self.is_object(inner),
+            RustFieldType::String => true,
+            RustFieldType::Vec(inner) => matches!(**inner, RustFieldType::U8),
+            RustFieldType::Struct(name) => self.is_class(name),
+            _ => false,
+        }
+    }
+
+    /// Functions get a native method when every value marshals; `&mut`
+    /// parameters don't, since changes to the copy would never reach Java
+    fn wraps(&self, function: &RustFunction) -> bool {
+        function.params.iter().all(|(_, ty)| match ty {
+            RustFieldType::Borrowed(written, owned) => !written.starts_with("&mut") && self.marshals(owned),
+            RustFieldType::Cow(_, owned) => self.marshals(owned),
+            ty => self.marshals(ty),
+        }) && (is_unit(&function.ret) || self.marshals(&function.ret))
+    }
+
+    /// JNI type signature of `ty`
+    fn signature(&self, ty: &RustFieldType) -> String {
+        match ty {
+            RustFieldType::Alias(_, inner) | RustFieldType::Option(inner) => self.signature(inner),
+            RustFieldType::String => "Ljava/lang/String;".to_string(),
+            RustFieldType::Vec(_) => "[B".to_string(),
+            RustFieldType::Struct(name) => format!("L{}${};", self.path, name),
+            ty => primitive(ty).map(|p| p.signature.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+fn is_unit(ty: &RustFieldType) -> bool {
+    matches!(ty, RustFieldType::Tuple(members) if members.is_empty())
+}
+
+/// How a Rust scalar crosses: its Java type and JNI signature, the `jni::sys`
+/// type and `JValue` variant carrying it, the `JValueOwned` accessor, and the
+/// Rust type it widens to when Java has no exact match
+struct Primitive {
+    java: &'static str,
+    signature: &'static str,
+    sys: &'static str,
+    variant: &'static str,
+    accessor: &'static str,
+    widened: Option<&'static str>,
+}
+
+fn primitive(ty: &RustFieldType) -> Option<Primitive> {
+    let (java, signature, sys, variant, accessor, widened) = match ty {
+        RustFieldType::Alias(_, inner) => return primitive(inner),
+        RustFieldType::Bool => ("boolean", "Z", "jboolean", "Bool", "z", None),
+        RustFieldType::I8 => ("byte", "B", "jbyte", "Byte", "b", None),
+        RustFieldType::I16 => ("short", "S", "jshort", "Short", "s", None),
+        RustFieldType::I32 => ("int", "I", "jint", "Int", "i", None),
+        RustFieldType::I64 => ("long", "J", "jlong", "Long", "j", None),
+        RustFieldType::F32 => ("float", "F", "jfloat", "Float", "f", None),
+        RustFieldType::F64 => ("double", "D", "jdouble", "Double", "d", None),
+        RustFieldType::U8 => ("short", "S", "jshort", "Short", "s", Some("i16")),
+        RustFieldType::U16 => ("int", "I", "jint", "Int", "i", Some("i32")),
+        RustFieldType::U32 | RustFieldType::U64 | RustFieldType::Usize | RustFieldType::Isize => ("long", "J", "jlong", "Long", "j", Some("i64")),
+        _ => return None,
+    };
+    Some(Primitive { java, signature, sys, variant, accessor, widened })
+}
+
+/// Rust value of a Java primitive `raw`, narrowed back checked where it was widened
+fn from_primitive(ty: &RustFieldType, raw: &str) -> String {
+    let resolved = resolve(ty);
+    match (resolved, primitive(resolved).and_then(|p| p.widened)) {
+        (RustFieldType::Bool, _) => format!("{} != 0", raw),
+        (_, Some(_)) => format!("{}::try_from({})?", crate::analyzers::rust_analyzer::rust_type_name(resolved), raw),
+        (_, None) => raw.to_string(),
+    }
+}
+
+/// Java primitive of the Rust value `value`, widened where Java lacks the type
+fn to_primitive(ty: &RustFieldType, value: &str) -> String {
+    let resolved = resolve(ty);
+    match (resolved, primitive(resolved).and_then(|p| p.widened)) {
+        (RustFieldType::Bool, _) => format!("u8::from({})", value),
+        // Always fits: a `u8`, `u16` or `u32` in the next wider signed type
+        (RustFieldType::U8 | RustFieldType::U16 | RustFieldType::U32, Some(widened)) => format!("{}::from({})", widened, value),
+        (_, Some(widened)) => format!("{}::try_from({})?", widened, value),
+        (_, None) => value.to_string(),
+    }
+}
+
+fn resolve(ty: &RustFieldType) -> &RustFieldType {
+    match ty {
+        RustFieldType::Alias(_, inner) => resolve(inner),
+        ty => ty,
+    }
+}
+
+/// Rust value of the non-null Java object `object`
+fn from_object(ty: &RustFieldType, object: &str) -> String {
+    match resolve(ty) {
+        RustFieldType::String => format!("String::from(env.get_string(&JString::from({}))?)", object),
+        RustFieldType::Vec(_) => format!("env.convert_byte_array(JByteArray::from({}))?", object),
+        RustFieldType::Struct(name) => format!("{}_from_java(env, &{})?", snake_case(name), object),
+        _ => unreachable!("only object types are read from objects"),
+    }
+}
+
+/// Java object of the Rust value behind the reference `value`
+fn to_object(ty: &RustFieldType, value: &str) -> String {
+    match resolve(ty) {
+        RustFieldType::String => format!("JObject::from(env.new_string({})?)", value),
+        RustFieldType::Vec(_) => format!("JObject::from(env.byte_array_from_slice({})?)", value),
+        RustFieldType::Struct(name) => format!("{}_to_java(env, {})?", snake_case(name), value),
+        RustFieldType::Option(inner) => format!("match {} {{ Some(inner) => {}, None => JObject::null() }}", value, to_object(inner, "inner")),
+        _ => unreachable!("only object types become objects"),
+    }
+}
+
+/// Rust value of the possibly null Java object `object`, for a field or parameter of type `ty`
+fn from_nullable(ty: &RustFieldType, object: &str) -> String {
+    match resolve(ty) {
+        RustFieldType::Option(inner) => format!(
+            "match {} {{ object if object.is_null() => None, object => Some({}) }}",
+            object,
+            from_object(inner, "object")
+        ),
+        ty => from_object(ty, object),
+    }
+}
+
+/// JNI symbol of `method` on the Java class `class`, `_` escaped as `_1`
+fn jni_symbol(class: &str, method: &str) -> String {
+    format!("Java_{}_{}", class.replace('_', "_1").replace('.', "_"), method.replace('_', "_1"))
+}
+
+/// Rust side of the bindings: marshalling helpers and a `Java_...` export per
+/// native method of `class` (`com.example.app.Native`)
+pub fn generate_jni(types: &[RustType], functions: &[RustFunction], class: &str) -> String {
+    let classes = Classes::of(types, class);
+    let mut code = "use super::*;\n\
+        use jni::objects::{JByteArray, JClass, JObject, JString, JValue};\n\
+        use jni::sys::{jboolean, jbyte, jdouble, jfloat, jint, jlong, jobject, jshort};\n\
+        use jni::JNIEnv;\n"
+        .to_string();
+    for rust_type in types.iter().filter(|t| t.kind != RustTypeKind::Alias && !classes.is_class(&t.name)) {
+        code.push_str(&format!(
+            "// `{}` has no Java class: generic, tuple and data-carrying types, and ones holding values without a Java counterpart, are left out\n",
+            rust_type.name
+        ));
+    }
+    for function in functions.iter().filter(|f| !classes.wraps(f)) {
+        code.push_str(&format!("// `{}` is left out: it takes `&mut` or passes values without a Java counterpart\n", function.name));
+    }
+    code.push_str("\n/// Marshalling failures become a Java `IllegalArgumentException`\ntype Marshal<T> = Result<T, Box<dyn std::error::Error>>;\n");
+    for rust_type in types {
+        if classes.objects.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&render_object_marshal(rust_type, &classes));
+        } else if classes.enums.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&render_enum_marshal(rust_type, &classes));
+        }
+    }
+    for function in functions.iter().filter(|f| classes.wraps(f)) {
+        code.push('\n');
+        code.push_str(&render_export(function, class));
+    }
+    code
+}
+
+fn render_object_marshal(rust_type: &RustType, classes: &Classes) -> String {
+    let name = &rust_type.name;
+    let snake = snake_case(name);
+    let mut reads = String::new();
+    let mut inits = Vec::new();
+    let mut objects = String::new();
+    let mut args = Vec::new();
+    let mut signature = String::new();
+    for field in &rust_type.fields {
+        let ident = &field.name;
+        if is_zero_sized(&field.field_type) {
+            inits.push(format!("{}: Default::default()", ident));
+            continue;
+        }
+        let ty = &field.field_type;
+        let java = camel_case(ident);
+        let sig = classes.signature(ty);
+        signature.push_str(&sig);
+        reads.push_str(&format!("    let {} = env.get_field(object, \"{}\", \"{}\")?;\n", ident, java, sig));
+        let value = match primitive(ty) {
+            Some(p) => from_primitive(ty, &format!("{}.{}()?", ident, p.accessor)),
+            None => from_nullable(ty, &format!("{}.l()?", ident)),
+        };
+        reads.push_str(&format!("    let {} = {};\n", ident, value));
+        inits.push(ident.clone());
+        match primitive(ty) {
+            Some(p) => args.push(format!("JValue::{}({})", p.variant, to_primitive(ty, &format!("value.{}", ident)))),
+            None => {
+                objects.push_str(&format!("    let {} = {};\n", ident, to_object(ty, &format!("&value.{}", ident))));
+                args.push(format!("JValue::Object(&{})", ident));
+            }
+        }
+    }
+    format!(
+        "fn {snake}_from_java(env: &mut JNIEnv, object: &JObject) -> Marshal<{name}> {{\n{reads}    Ok({name} {{ {} }})\n}}\n\n\
+         fn {snake}_to_java<'local>(env: &mut JNIEnv<'local>, value: &{name}) -> Marshal<JObject<'local>> {{\n{objects}    \
+         Ok(env.new_object(\"{path}${name}\", \"({signature})V\", &[{}])?)\n}}\n",
+        inits.join(", "),
+        args.join(", "),
+        path = classes.path
+    )
+}
+
+fn render_enum_marshal(rust_type: &RustType, classes: &Classes) -> String {
+    let name = &rust_type.name;
+    let snake = snake_case(name);
+    let constant = |variant: &str| snake_case(variant).to_uppercase();
+    let from: String = rust_type
+        .variants
+        .iter()
+        .map(|variant| format!("        \"{}\" => Ok({}::{}),\n", constant(&variant.name), name, variant.name))
+        .collect();
+    let to: String = rust_type
+        .variants
+        .iter()
+        .map(|variant| format!("        {}::{} => \"{}\",\n", name, variant.name, constant(&variant.name)))
+        .collect();
+    format!(
+        "fn {snake}_from_java(env: &mut JNIEnv, object: &JObject) -> Marshal<{name}> {{\n    \
+         let constant = env.call_method(object, \"name\", \"()Ljava/lang/String;\", &[])?.l()?;\n    \
+         match String::from(env.get_string(&JString::from(constant))?).as_str() {{\n{from}        \
+         other => Err(format!(\"no {name} constant {{}}\", other).into()),\n    }}\n}}\n\n\
+         fn {snake}_to_java<'local>(env: &mut JNIEnv<'local>, value: &{name}) -> Marshal<JObject<'local>> {{\n    \
+         let constant = match value {{\n{to}    }};\n    \
+         Ok(env.get_static_field(\"{path}${name}\", constant, \"L{path}${name};\")?.l()?)\n}}\n",
+        path = classes.path
+    )
+}
+
+fn render_export(function: &RustFunction, class: &str) -> String {
+    let mut params = vec!["mut env: JNIEnv<'local>".to_string(), "_class: JClass<'local>".to_string()];
+    let mut converts = String::new();
+    let mut args = Vec::new();
+    for (name, ty) in &function.params {
+        let (owned, borrow) = match ty {
+            RustFieldType::Borrowed(_, owned) => (owned.as_ref(), "&"),
+            RustFieldType::Cow(_, owned) => (owned.as_ref(), ""),
+            ty => (ty, ""),
+        };
+        let value = match primitive(owned) {
+            Some(p) => {
+                params.push(format!("{}: {}", name, p.sys));
+                from_primitive(owned, name)
+            }
+            None => {
+                params.push(format!("{}: JObject<'local>", name));
+                from_nullable(owned, name)
+            }
+        };
+        if value != *name {
+            converts.push_str(&format!("        let {} = {};\n", name, value));
+        }
+        let into = if matches!(ty, RustFieldType::Cow(_, _)) { ".into()" } else { "" };
+        args.push(format!("{}{}{}", borrow, name, into));
+    }
+
+    let call = format!("super::{}({})", function.name, args.join(", "));
+    let (ret, body, fallback) = if is_unit(&function.ret) {
+        (String::new(), format!("{};\n        Ok(())", call), "()")
+    } else if let Some(p) = primitive(&function.ret) {
+        let result = to_primitive(&function.ret, "result");
+        (format!(" -> {}", p.sys), format!("let result = {};\n        Ok({})", call, result), "Default::default()")
+    } else {
+        let result = to_object(&function.ret, "&result");
+        (" -> jobject".to_string(), format!("let result = {};\n        Ok({}.into_raw())", call, result), "std::ptr::null_mut()")
+    };
+    let ok = if ret.is_empty() { "()".to_string() } else { ret[4..].to_string() };
+    format!(
+        "#[no_mangle]\npub extern \"system\" fn {}<'local>({}){} {{\n    \
+         let result = (|| -> Marshal<{}> {{\n        let env = &mut env;\n{}        {}\n    }})();\n    \
+         result.unwrap_or_else(|err| {{\n        \
+         let _ = env.throw_new(\"java/lang/IllegalArgumentException\", err.to_string());\n        {}\n    }})\n}}\n",
+        jni_symbol(class, &camel_case(&function.name)),
+        params.join(", "),
+        ret,
+        ok,
+        converts,
+        body,
+        fallback
+    )
+}
+
+/// Java source of `class` (`com.example.app.Native`): a nested class per
+/// struct and enum, and the native methods, loading the library `library`
+pub fn generate_java(types: &[RustType], functions: &[RustFunction], class: &str, library: &str) -> String {
+    let classes = Classes::of(types, class);
+    let (package, outer) = class.rsplit_once('.').unwrap_or(("", class));
+    let mut code = String::new();
+    if !package.is_empty() {
+        code.push_str(&format!("package {};\n\n", package));
+    }
+    code.push_str(&format!(
+        "/** Bindings to the native library `{library}` */\npublic final class {outer} {{\n    static {{\n        \
+         System.loadLibrary(\"{library}\");\n    }}\n\n    private {outer}() {{}}\n"
+    ));
+    for rust_type in types {
+        let name = &rust_type.name;
+        if classes.enums.contains(&name.as_str()) {
+            code.push('\n');
+            code.push_str(&java_doc(rust_type.doc.as_deref(), "    "));
+            code.push_str(&format!("    public enum {} {{\n", name));
+            for variant in &rust_type.variants {
+                code.push_str(&format!("        {},\n", snake_case(&variant.name).to_uppercase()));
+            }
+            code.push_str("    }\n");
+        } else if classes.objects.contains(&name.as_str()) {
+            let fields: Vec<_> = rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)).collect();
+            code.push('\n');
+            code.push_str(&java_doc(rust_type.doc.as_deref(), "    "));
+            code.push_str(&format!("    public static final class {} {{\n", name));
+            for field in &fields {
+                let doc = match (&field.doc, matches!(resolve(&field.field_type), RustFieldType::Option(_))) {
+                    (Some(doc), true) => Some(format!("{}; null when absent", doc)),
+                    (None, true) => Some("Null when absent".to_string()),
+                    (doc, false) => doc.clone(),
+                };
+                code.push_str(&java_doc(doc.as_deref(), "        "));
+                code.push_str(&format!("        public final {} {};\n", java_type(&field.field_type), camel_case(&field.name)));
+            }
+            let params: Vec<String> = fields.iter().map(|field| format!("{} {}", java_type(&field.field_type), camel_case(&field.name))).collect();
+            code.push_str(&format!("\n        public {}({}) {{\n", name, params.join(", ")));
+            for field in &fields {
+                code.push_str(&format!("            this.{0} = {0};\n", camel_case(&field.name)));
+            }
+            code.push_str("        }\n    }\n");
+        }
+    }
+    for function in functions.iter().filter(|f| classes.wraps(f)) {
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|(name, ty)| {
+                let ty = match ty {
+                    RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned) => owned.as_ref(),
+                    ty => ty,
+                };
+                format!("{} {}", java_type(ty), camel_case(name))
+            })
+            .collect();
+        let ret = if is_unit(&function.ret) { "void".to_string() } else { java_type(&function.ret) };
+        code.push('\n');
+        code.push_str(&java_doc(function.doc.as_deref(), "    "));
+        code.push_str(&format!("    public static native {} {}({});\n", ret, camel_case(&function.name), params.join(", ")));
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn java_type(ty: &RustFieldType) -> String {
+    match resolve(ty) {
+        RustFieldType::Option(inner) => java_type(inner),
+        RustFieldType::String => "String".to_string(),
+        RustFieldType::Vec(_) => "byte[]".to_string(),
+        RustFieldType::Struct(name) => pascal_case(name),
+        ty => primitive(ty).map(|p| p.java.to_string()).unwrap_or_default(),
+    }
+}
+
+fn java_doc(doc: Option<&str>, indent: &str) -> String {
+    doc.map(|doc| super::render_doc(doc, "c", indent)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::{analyze_rust_functions, analyze_rust_types};
+
+    const SOURCE: &str = "pub enum Status { Active, OnHold }\n\
+        /// A bank account\n\
+        pub struct Account { pub owner_id: u32, pub holder: String, pub status: Status, pub note: Option<String>, pub history: Vec<f64> }\n\
+        pub struct Transfer { pub amount: f64, pub to: Option<Status>, pub memo: Option<String> }\n\
+        /// Credit a transfer\n\
+        pub fn apply_transfer(transfer: &Transfer, fee: u8) -> Transfer { todo!() }\n\
+        pub fn audit(enabled: bool) {}";
+
+    #[test]
+    fn test_jni_exports_marshal_fields() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let rust = generate_jni(&types, &functions, "com.example.bank_app.Native");
+        // `Vec<f64>` has no Java counterpart here
+        assert!(rust.contains("// `Account` has no Java class"));
+        assert!(rust.contains(
+            "fn transfer_from_java(env: &mut JNIEnv, object: &JObject) -> Marshal<Transfer> {\n    \
+             let amount = env.get_field(object, \"amount\", \"D\")?;\n    let amount = amount.d()?;\n    \
+             let to = env.get_field(object, \"to\", \"Lcom/example/bank_app/Native$Status;\")?;\n    \
+             let to = match to.l()? { object if object.is_null() => None, object => Some(status_from_java(env, &object)?) };\n"
+        ));
+        assert!(rust.contains("    let memo = match &value.memo { Some(inner) => JObject::from(env.new_string(inner)?), None => JObject::null() };\n"));
+        assert!(rust.contains(
+            "    Ok(env.new_object(\"com/example/bank_app/Native$Transfer\", \"(DLcom/example/bank_app/Native$Status;Ljava/lang/String;)V\", \
+             &[JValue::Double(value.amount), JValue::Object(&to), JValue::Object(&memo)])?)\n"
+        ));
+        assert!(rust.contains("        \"ON_HOLD\" => Ok(Status::OnHold),\n"));
+        assert!(rust.contains(
+            "pub extern \"system\" fn Java_com_example_bank_1app_Native_applyTransfer<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, \
+             transfer: JObject<'local>, fee: jshort) -> jobject {\n"
+        ));
+        assert!(rust.contains("        let fee = u8::try_from(fee)?;\n        let result = super::apply_transfer(&transfer, fee);\n"));
+        assert!(rust.contains("        let enabled = enabled != 0;\n        super::audit(enabled);\n        Ok(())\n"));
+    }
+
+    #[test]
+    fn test_java_class_declares_the_natives() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let java = generate_java(&types, &functions, "com.example.bank_app.Native", "bank");
+        assert!(java.starts_with("package com.example.bank_app;\n\n/** Bindings to the native library `bank` */\npublic final class Native {\n"));
+        assert!(java.contains("    public enum Status {\n        ACTIVE,\n        ON_HOLD,\n    }\n"));
+        assert!(java.contains("        /** Null when absent */\n        public final String memo;\n"));
+        assert!(java.contains("        public Transfer(double amount, Status to, String memo) {\n            this.amount = amount;\n"));
+        assert!(java.contains("    /** Credit a transfer */\n    public static native Transfer applyTransfer(Transfer transfer, short fee);\n"));
+        assert!(!java.contains("class Account"));
+    }
+}
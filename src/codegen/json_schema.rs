@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// JSON Schema generation
+// Emits a draft 2020-12 document per analyzed type, describing the JSON the
+// serde impls write: mapped keys, `Option` fields present and `null`able,
+// required unless a default covers them, enums in the tagging the source
+// chose, and every type the document reaches under `$defs`
+
+use super::{pascal_case, BIGINT_PATTERN, DECIMAL_PATTERN};
+use crate::api::TypeDef;
+use crate::ir::{normalize_name, ContainerType, DefaultValue, Direction, IrField, IrType, IrVariant, PrimitiveType, VariantTagging};
+use crate::json::Json;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Schema document for `def`, with the types it references from `types`
+/// under `$defs`; fields with no data form are left out, noted in `$comment`
+pub fn generate_json_schema(def: &TypeDef, types: &[TypeDef]) -> String {
+    let mut schemas = Schemas { root: Some(def), pointer: "#/$defs/", types, reached: Vec::new() };
+    let mut document = vec![("$schema".to_string(), Json::from(DRAFT)), ("title".to_string(), pascal_case(&def.name).into())];
+    document.extend(entries(schemas.definition(def)));
+    // Rendering a definition may reach further ones, so walk until none are new
+    let mut defs = Vec::new();
+    while let Some(next) = schemas.reached.get(defs.len()).copied() {
+        let schema = schemas.definition(next);
+        defs.push((pascal_case(&next.name), schema));
+    }
+    if !defs.is_empty() {
+        document.push(("$defs".to_string(), Json::Object(defs)));
+    }
+    Json::Object(document).to_string()
+}
+
+/// Schema of every type in `types`, by name, referring to one another as
+/// `pointer` followed by the name
+pub(super) fn schema_map(types: &[TypeDef], pointer: &str) -> Vec<(String, Json)> {
+    let mut schemas = Schemas { root: None, pointer, types, reached: Vec::new() };
+    types.iter().map(|def| (pascal_case(&def.name), schemas.definition(def))).collect()
+}
+
+struct Schemas<'a> {
+    /// Type of the document, which refers to itself as `#`
+    root: Option<&'a TypeDef>,
+    pointer: &'a str,
+    types: &'a [TypeDef],
+    /// Definitions referenced so far, in the order `$defs` lists them
+    reached: Vec<&'a TypeDef>,
+}
+
+impl<'a> Schemas<'a> {
+    fn definition(&mut self, def: &TypeDef) -> Json {
+        let schema = match &def.aliased {
+            Some(aliased) => self.schema(aliased).unwrap_or_else(|| comment("it has no data form")),
+            None if def.variants.is_empty() => self.object(&def.fields, Vec::new(), false),
+            None => self.variants(def),
+        };
+        match &def.doc {
+            Some(doc) => Json::Object([("description".to_string(), doc.as_str().into())].into_iter().chain(entries(schema)).collect()),
+            None => schema,
+        }
+    }
+
+    /// Object with `fields` after `leading` properties, which are always required;
+    /// `underscored` keys positional fields `_0`, `_1`, ...
+    fn object(&mut self, fields: &[IrField], leading: Vec<(String, Json)>, underscored: bool) -> Json {
+        let mut required: Vec<Json> = leading.iter().map(|(key, _)| key.as_str().into()).collect();
+        let mut properties = leading;
+        let mut left_out = Vec::new();
+        for field in fields {
+            let key = match underscored && field.name.starts_with(|c: char| c.is_ascii_digit()) {
+                true => format!("_{}", field.name),
+                false => field.name.clone(),
+            };
+            let Some(schema) = self.schema(&field.ty) else {
+                left_out.push(format!("`{}` is left out: it has no data form", key));
+                continue;
+            };
+            let mut annotations = Vec::new();
+            if let Some(doc) = &field.doc {
+                annotations.push(("description".to_string(), doc.as_str().into()));
+            }
+            if let Some(default) = field.default.as_ref().and_then(|default| default_value(default, &field.ty)) {
+                annotations.push(("default".to_string(), default));
+            }
+            // Written but never read, or read but never written
+            match field.direction {
+                Direction::SerializeOnly => annotations.push(("readOnly".to_string(), true.into())),
+                Direction::DeserializeOnly => annotations.push(("writeOnly".to_string(), true.into())),
+                Direction::Both => {}
+            }
+            if field.default.is_none() && field.direction == Direction::Both {
+                required.push(key.as_str().into());
+            }
+            let schema = match annotations.is_empty() {
+                true => schema,
+                false => Json::Object(entries(schema).into_iter().chain(annotations).collect()),
+            };
+            properties.push((key, schema));
+        }
+        let mut object = vec![("type".to_string(), Json::from("object")), ("properties".to_string(), Json::Object(properties))];
+        if !required.is_empty() {
+            object.push(("required".to_string(), Json::Array(required)));
+        }
+        if !left_out.is_empty() {
+            object.push(("$comment".to_string(), left_out.join("; ").into()));
+        }
+        Json::Object(object)
+    }
+
+    fn variants(&mut self, def: &TypeDef) -> Json {
+        let tagging = def.tagging.clone().unwrap_or(VariantTagging::External);
+        // A unit variant crosses as its bare tag under external tagging, and
+        // whatever the tagging when the source compiles it that way
+        let bare = |variant: &IrVariant| variant.fields.is_empty() && (def.bare_units || tagging == VariantTagging::External);
+        if def.variants.iter().all(bare) {
+            return Json::object([("enum", Json::Array(def.variants.iter().map(|variant| tag(variant).into()).collect()))]);
+        }
+        let mut members = Vec::new();
+        for variant in &def.variants {
+            let constant = Json::object([("const", tag(variant).into())]);
+            let member = match &tagging {
+                _ if bare(variant) => constant,
+                VariantTagging::Untagged if variant.fields.is_empty() => Json::object([("type", "null".into())]),
+                VariantTagging::Untagged => self.payload(variant),
+                VariantTagging::External => {
+                    let payload = self.payload(variant);
+                    Json::object([
+                        ("type", "object".into()),
+                        ("properties", Json::object([(tag(variant), payload)])),
+                        ("required", Json::Array(vec![tag(variant).into()])),
+                        ("additionalProperties", false.into()),
+                    ])
+                }
+                VariantTagging::Internal(key) => self.object(&variant.fields, vec![(key.clone(), constant)], true),
+                VariantTagging::Adjacent(key, _) if variant.fields.is_empty() => self.object(&[], vec![(key.clone(), constant)], false),
+                VariantTagging::Adjacent(key, content) => {
+                    // A missing payload reads as an empty one, so it is needed only for its required fields
+                    let payload = self.payload(variant);
+                    let needed = payload.get("required").is_some() || payload.get("properties").is_none();
+                    let mut object = self.object(&[], vec![(key.clone(), constant)], false);
+                    add_property(&mut object, content, payload, needed);
+                    object
+                }
+            };
+            members.push(member);
+        }
+        Json::object([("oneOf", Json::Array(members))])
+    }
+
+    /// A variant's payload: an object of its fields, or for positional ones the
+    /// single value or an array of them
+    fn payload(&mut self, variant: &IrVariant) -> Json {
+        if !variant.fields.iter().all(|field| field.name.starts_with(|c: char| c.is_ascii_digit())) {
+            return self.object(&variant.fields, Vec::new(), false);
+        }
+        let items: Option<Vec<Json>> = variant.fields.iter().map(|field| self.schema(&field.ty)).collect();
+        match items {
+            Some(mut items) if items.len() == 1 => items.remove(0),
+            Some(items) => {
+                let count = items.len();
+                Json::object([("type", "array".into()), ("prefixItems", Json::Array(items)), ("items", false.into()), ("minItems", count.into())])
+            }
+            None => comment("its payload has no data form"),
+        }
+    }
+
+    /// Schema of a value of `ty`, or `None` for behaviour with no data form
+    fn schema(&mut self, ty: &IrType) -> Option<Json> {
+        Some(match ty {
+            IrType::Primitive(primitive) => primitive_schema(*primitive),
+            IrType::Container(ContainerType::Option(inner)) => nullable(self.schema(inner)?),
+            IrType::Container(ContainerType::Vec(inner)) => Json::object([("type", "array".into()), ("items", self.schema(inner)?)]),
+            IrType::Container(ContainerType::Array(inner, len)) => Json::object([
+                ("type", "array".into()),
+                ("items", self.schema(inner)?),
+                ("minItems", (*len).into()),
+                ("maxItems", (*len).into()),
+            ]),
+            // JSON object keys are strings whatever the map is keyed by
+            IrType::Container(ContainerType::Map(_, value)) => Json::object([("type", "object".into()), ("additionalProperties", self.schema(value)?)]),
+            IrType::Container(ContainerType::Result(ok, err)) => {
+                let arm = |key: &str, schema: Json| {
+                    Json::object([
+                        ("type", "object".into()),
+                        ("properties", Json::object([(key, schema)])),
+                        ("required", Json::Array(vec![key.into()])),
+                        ("additionalProperties", false.into()),
+                    ])
+                };
+                Json::object([("oneOf", Json::Array(vec![arm("Ok", self.schema(ok)?), arm("Err", self.schema(err)?)]))])
+            }
+            IrType::Tuple(items) => {
+                let items: Vec<Json> = items.iter().map(|item| self.schema(item)).collect::<Option<_>>()?;
+                let count = items.len();
+                Json::object([("type", "array".into()), ("prefixItems", Json::Array(items)), ("items", false.into()), ("minItems", count.into())])
+            }
+            IrType::Union(members) => Json::object([("anyOf", Json::Array(members.iter().map(|member| self.schema(member)).collect::<Option<_>>()?))]),
+            // The definition's parameters accept anything, so arguments go unchecked
+            IrType::Reference(name) | IrType::Generic(name, _) => self.reference(name).unwrap_or_else(|| comment(&format!("`{}` was not analyzed", name))),
+            IrType::Alias(name, inner) => match self.reference(name) {
+                Some(reference) => reference,
+                None => self.schema(inner)?,
+            },
+            // A type parameter can be anything until instantiated
+            IrType::TypeParam(_) | IrType::Any => Json::Object(Vec::new()),
+            IrType::Function(_, _) | IrType::TraitObject(_) => return None,
+        })
+    }
+
+    /// `$ref` to the analyzed type `name`, recording it for `$defs`
+    fn reference(&mut self, name: &str) -> Option<Json> {
+        let key = normalize_name(name);
+        if self.root.is_some_and(|root| normalize_name(&root.name) == key) {
+            return Some(Json::object([("$ref", "#".into())]));
+        }
+        let def = self.types.iter().find(|def| normalize_name(&def.name) == key)?;
+        if !self.reached.iter().any(|reached| std::ptr::eq(*reached, def)) {
+            self.reached.push(def);
+        }
+        Some(Json::object([("$ref", format!("{}{}", self.pointer, pascal_case(&def.name)).into())]))
+    }
+}
+
+fn primitive_schema(primitive: PrimitiveType) -> Json {
+    let typed = |kind: &str| ("type", Json::from(kind));
+    let bounded = |min: i128, max: i128| Json::object([typed("integer"), ("minimum", Json::Integer(min)), ("maximum", Json::Integer(max))]);
+    // Validators ignore formats they don't know, so the bounds are spelled out too
+    let formatted = |min: i128, max: i128, format: &str| {
+        Json::object([typed("integer"), ("format", format.into()), ("minimum", Json::Integer(min)), ("maximum", Json::Integer(max))])
+    };
+    match primitive {
+        PrimitiveType::Bool => Json::object([typed("boolean")]),
+        PrimitiveType::I8 => bounded(i8::MIN.into(), i8::MAX.into()),
+        PrimitiveType::I16 => bounded(i16::MIN.into(), i16::MAX.into()),
+        PrimitiveType::U8 => bounded(0, u8::MAX.into()),
+        PrimitiveType::U16 => bounded(0, u16::MAX.into()),
+        PrimitiveType::I32 => formatted(i32::MIN.into(), i32::MAX.into(), "int32"),
+        PrimitiveType::U32 => formatted(0, u32::MAX.into(), "uint32"),
+        // Written digit for digit; the nearest doubles would admit values one past the end
+        PrimitiveType::I64 => formatted(i64::MIN.into(), i64::MAX.into(), "int64"),
+        PrimitiveType::U64 => formatted(0, u64::MAX.into(), "uint64"),
+        PrimitiveType::I128 => Json::object([typed("integer")]),
+        PrimitiveType::U128 => Json::object([typed("integer"), ("minimum", Json::Integer(0))]),
+        PrimitiveType::F32 => Json::object([typed("number"), ("format", "float".into())]),
+        PrimitiveType::F64 => Json::object([typed("number")]),
+        PrimitiveType::String => Json::object([typed("string")]),
+        PrimitiveType::Unit => Json::object([typed("null")]),
+        PrimitiveType::DateTime => Json::object([typed("string"), ("format", "date-time".into())]),
+        PrimitiveType::Uuid => Json::object([typed("string"), ("format", "uuid".into())]),
+        // Exact and arbitrary-precision numbers travel as their digits
+        PrimitiveType::Decimal => Json::object([typed("string"), ("pattern", DECIMAL_PATTERN.into())]),
+        PrimitiveType::BigInt => Json::object([typed("string"), ("pattern", BIGINT_PATTERN.into())]),
+        PrimitiveType::Bytes => Json::object([typed("string"), ("contentEncoding", "base64".into())]),
+    }
+}
+
+/// `schema` or `null`: a second entry in its `type` where that says it all,
+/// otherwise an `anyOf`
+fn nullable(schema: Json) -> Json {
+    let mut entries = entries(schema);
+    let constrained = entries.iter().any(|(key, _)| key == "enum" || key == "const");
+    match entries.iter_mut().find(|(key, _)| key == "type") {
+        Some((_, kind @ Json::String(_))) if !constrained => {
+            *kind = Json::Array(vec![kind.clone(), "null".into()]);
+            Json::Object(entries)
+        }
+        _ => Json::object([("anyOf", Json::Array(vec![Json::Object(entries), Json::object([("type", "null".into())])]))]),
+    }
+}
+
+/// Literal of a declared default, when there is one to write
+fn default_value(default: &DefaultValue, ty: &IrType) -> Option<Json> {
+    Some(match default {
+        DefaultValue::Bool(value) => (*value).into(),
+        DefaultValue::Number(number) if matches!(ty, IrType::Primitive(PrimitiveType::Decimal | PrimitiveType::BigInt)) => number.as_str().into(),
+        DefaultValue::Number(number) => number.parse::<f64>().ok()?.into(),
+        DefaultValue::String(text) => text.as_str().into(),
+        DefaultValue::Null => Json::Null,
+        DefaultValue::Empty if matches!(ty, IrType::Container(ContainerType::Map(_, _))) => Json::Object(Vec::new()),
+        DefaultValue::Empty => Json::Array(Vec::new()),
+        DefaultValue::TypeDefault | DefaultValue::Computed(_) => return None,
+    })
+}
+
+/// Add `key` to an object schema's properties, and to `required` when `required`
+fn add_property(object: &mut Json, key: &str, schema: Json, required: bool) {
+    let Json::Object(entries) = object else { return };
+    for (name, value) in entries.iter_mut() {
+        match (name.as_str(), value) {
+            ("properties", Json::Object(properties)) => properties.push((key.to_string(), schema.clone())),
+            ("required", Json::Array(keys)) if required => keys.push(key.into()),
+            _ => {}
+        }
+    }
+}
+
+/// Wire tag of a variant
+fn tag(variant: &IrVariant) -> &str {
+    variant.tag.as_deref().unwrap_or(&variant.name)
+}
+
+fn comment(text: &str) -> Json {
+    Json::object([("$comment", text.into())])
+}
+
+fn entries(schema: Json) -> Vec<(String, Json)> {
+    match schema {
+        Json::Object(entries) => entries,
+        other => vec![("allOf".to_string(), Json::Array(vec![other]))],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_schema_requires_fields_and_collects_defs() {
+        let types = analyze_rust(
+            "/// A person\npub struct User { pub id: u64, #[serde(default)] pub tags: Vec<String>, pub home: Option<Address>, pub email: Option<String>, \
+             pub boss: Option<Box<User>>, pub color: Color, pub on_change: Box<dyn Fn(i32)> }\n\
+             pub struct Address { pub city: String, pub seen: chrono::DateTime<chrono::Utc> }\npub enum Color { Red, DarkBlue }",
+        );
+        let schema = generate_json_schema(&types[0], &types);
+        assert!(schema.starts_with("{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"title\":\"User\",\"description\":\"A person\",\"type\":\"object\","));
+        assert!(schema.contains("\"home\":{\"anyOf\":[{\"$ref\":\"#/$defs/Address\"},{\"type\":\"null\"}]},\"email\":{\"type\":[\"string\",\"null\"]},"));
+        assert!(schema.contains("\"boss\":{\"anyOf\":[{\"$ref\":\"#\"},{\"type\":\"null\"}]}"));
+        assert!(schema.contains("\"required\":[\"id\",\"home\",\"email\",\"boss\",\"color\"],\"$comment\":\"`on_change` is left out: it has no data form\""));
+        assert!(schema.contains(
+            "\"$defs\":{\"Address\":{\"type\":\"object\",\"properties\":{\"city\":{\"type\":\"string\"},\"seen\":{\"type\":\"string\",\"format\":\"date-time\"}},\
+             \"required\":[\"city\",\"seen\"]},\"Color\":{\"enum\":[\"Red\",\"DarkBlue\"]}}"
+        ));
+        assert!(schema.contains("\"id\":{\"type\":\"integer\",\"format\":\"uint64\",\"minimum\":0,\"maximum\":18446744073709551615},"));
+        assert_eq!(
+            primitive_schema(PrimitiveType::I64).to_string(),
+            "{\"type\":\"integer\",\"format\":\"int64\",\"minimum\":-9223372036854775808,\"maximum\":9223372036854775807}"
+        );
+        assert!(crate::json::parse(&schema).is_ok());
+    }
+
+    #[test]
+    fn test_enum_schemas_follow_the_tagging() {
+        let types = analyze_rust("#[serde(tag = \"kind\")]\npub enum Shape { Circle { radius: f64 }, Empty }\npub enum Event { Click { x: i32 }, Close }");
+        let internal = generate_json_schema(&types[0], &types);
+        assert!(internal.contains(
+            "\"oneOf\":[{\"type\":\"object\",\"properties\":{\"kind\":{\"const\":\"Circle\"},\"radius\":{\"type\":\"number\"}},\"required\":[\"kind\",\"radius\"]},\
+             {\"type\":\"object\",\"properties\":{\"kind\":{\"const\":\"Empty\"}},\"required\":[\"kind\"]}]"
+        ));
+        let external = generate_json_schema(&types[1], &types);
+        assert!(external.contains(
+            "\"oneOf\":[{\"type\":\"object\",\"properties\":{\"Click\":{\"type\":\"object\",\"properties\":{\"x\":{\"type\":\"integer\",\"format\":\"int32\",\"minimum\":-2147483648,\"maximum\":2147483647}},\
+             \"required\":[\"x\"]}},\"required\":[\"Click\"],\"additionalProperties\":false},{\"const\":\"Close\"}]"
+        ));
+    }
+}
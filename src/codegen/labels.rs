@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Internationalized display labels for enums
+// Attaches per-locale label maps to an enum's canonical variants and generates
+// the same lookup table on every side, so UI strings can't drift from the enum
+
+use std::fmt;
+
+/// Labels for every variant in one locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocaleLabels {
+    pub locale: String,
+    /// `(variant, label)` pairs in declaration order
+    pub labels: Vec<(String, String)>,
+}
+
+/// Why a label table doesn't line up with its enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelProblem {
+    /// A variant has no label in a locale (the default locale's label is used instead)
+    Missing { locale: String, variant: String },
+    /// A label names a variant the enum doesn't have (renamed or removed)
+    UnknownVariant { locale: String, variant: String },
+}
+
+impl fmt::Display for LabelProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelProblem::Missing { locale, variant } => write!(f, "{}: no label for `{}`", locale, variant),
+            LabelProblem::UnknownVariant { locale, variant } => write!(f, "{}: label for unknown variant `{}`", locale, variant),
+        }
+    }
+}
+
+/// Display labels of one enum; the first locale added is the default
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelTable {
+    pub enum_name: String,
+    /// Canonical variant names, as declared in the enum
+    pub variants: Vec<String>,
+    pub locales: Vec<LocaleLabels>,
+}
+
+impl LabelTable {
+    pub fn new(enum_name: &str, variants: &[&str]) -> Self {
+        LabelTable {
+            enum_name: enum_name.to_string(),
+            variants: variants.iter().map(|v| v.to_string()).collect(),
+            locales: Vec::new(),
+        }
+    }
+
+    /// Attach the labels of one locale
+    pub fn with_locale(mut self, locale: &str, labels: &[(&str, &str)]) -> Self {
+        self.locales.push(LocaleLabels {
+            locale: locale.to_string(),
+            labels: labels.iter().map(|(v, l)| (v.to_string(), l.to_string())).collect(),
+        });
+        self
+    }
+
+    /// Label of a variant in a locale, falling back to the default locale
+    pub fn label<'a>(&'a self, locale: &str, variant: &str) -> Option<&'a str> {
+        let find = |labels: &'a LocaleLabels| labels.labels.iter().find(|(v, _)| v == variant).map(|(_, l)| l.as_str());
+        self.locales
+            .iter()
+            .find(|labels| labels.locale == locale)
+            .and_then(find)
+            .or_else(|| self.locales.first().and_then(find))
+    }
+
+    /// Labels that are missing or point at variants the enum no longer has
+    pub fn problems(&self) -> Vec<LabelProblem> {
+        let mut problems = Vec::new();
+        for labels in &self.locales {
+            for variant in &self.variants {
+                if !labels.labels.iter().any(|(v, _)| v == variant) {
+                    problems.push(LabelProblem::Missing {
+                        locale: labels.locale.clone(),
+                        variant: variant.clone(),
+                    });
+                }
+            }
+            for (variant, _) in labels.labels.iter().filter(|(v, _)| !self.variants.contains(v)) {
+                problems.push(LabelProblem::UnknownVariant {
+                    locale: labels.locale.clone(),
+                    variant: variant.clone(),
+                });
+            }
+        }
+        problems
+    }
+
+    /// Every locale's labels for the canonical variants, gaps filled from the
+    /// default locale and then the variant name itself
+    fn resolved(&self) -> Vec<(&str, Vec<(&str, &str)>)> {
+        self.locales
+            .iter()
+            .map(|labels| {
+                let entries = self
+                    .variants
+                    .iter()
+                    .map(|variant| (variant.as_str(), self.label(&labels.locale, variant).unwrap_or(variant)))
+                    .collect();
+                (labels.locale.as_str(), entries)
+            })
+            .collect()
+    }
+
+    /// Generate the lookup table for one target language
+    pub fn render(&self, target: &str) -> Option<String> {
+        let snake = super::snake_case(&self.enum_name);
+        let camel = super::camel_case(&self.enum_name);
+        let resolved = self.resolved();
+
+        let code = match target {
+            "rust" => {
+                let upper = snake.to_uppercase();
+                let mut code = String::new();
+                for (locale, entries) in &resolved {
+                    let pairs: Vec<String> = entries.iter().map(|(v, l)| format!("    {:?} => {:?},\n", v, l)).collect();
+                    code.push_str(&format!(
+                        "/// `{}` display labels in `{}`\n\
+                         pub static {}_LABELS_{}: phf::Map<&'static str, &'static str> = phf::phf_map! {{\n{}}};\n\n",
+                        self.enum_name,
+                        locale,
+                        upper,
+                        locale_ident(locale),
+                        pairs.concat()
+                    ));
+                }
+                let arms: Vec<String> = resolved
+                    .iter()
+                    .map(|(locale, _)| format!("        {:?} => &{}_LABELS_{},\n", locale, upper, locale_ident(locale)))
+                    .collect();
+                code.push_str(&format!(
+                    "/// Display label of a `{name}` variant; `None` for an unknown locale or variant\n\
+                     pub fn {snake}_label(variant: &str, locale: &str) -> Option<&'static str> {{\n    \
+                     let labels = match locale {{\n{arms}        _ => return None,\n    }};\n    \
+                     labels.get(variant).copied()\n}}\n",
+                    name = self.enum_name,
+                    arms = arms.concat(),
+                ));
+                code
+            }
+            "typescript" => {
+                let locales: Vec<String> = resolved
+                    .iter()
+                    .map(|(locale, entries)| {
+                        let pairs: Vec<String> = entries.iter().map(|(v, l)| format!("    {:?}: {:?},\n", v, l)).collect();
+                        format!("  {:?}: {{\n{}  }},\n", locale, pairs.concat())
+                    })
+                    .collect();
+                format!(
+                    "// `{name}` display labels per locale\n\
+                     export const {camel}Labels = {{\n{locales}}} as const;\n\n\
+                     export function {camel}Label(variant: string, locale: string): string | undefined {{\n  \
+                     const labels = ({camel}Labels as Record<string, Record<string, string>>)[locale];\n  \
+                     return labels === undefined ? undefined : labels[variant];\n}}\n",
+                    name = self.enum_name,
+                    locales = locales.concat(),
+                )
+            }
+            "rescript" => {
+                let locales: Vec<String> = resolved
+                    .iter()
+                    .map(|(locale, entries)| {
+                        let pairs: Vec<String> = entries.iter().map(|(v, l)| format!("({:?}, {:?})", v, l)).collect();
+                        format!("  ({:?}, Dict.fromArray([{}])),\n", locale, pairs.join(", "))
+                    })
+                    .collect();
+                format!(
+                    "// `{name}` display labels per locale\n\
+                     let {camel}Labels: Dict.t<Dict.t<string>> = Dict.fromArray([\n{locales}])\n\n\
+                     let {camel}Label = (variant: string, locale: string): option<string> =>\n  \
+                     {camel}Labels->Dict.get(locale)->Option.flatMap(labels => labels->Dict.get(variant))\n",
+                    name = self.enum_name,
+                    locales = locales.concat(),
+                )
+            }
+            _ => return None,
+        };
+        Some(code)
+    }
+}
+
+/// `en-US` → `EN_US`, for use in a Rust constant name
+fn locale_ident(locale: &str) -> String {
+    locale.replace(|c: char| !c.is_ascii_alphanumeric(), "_").to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_labels() -> LabelTable {
+        LabelTable::new("OrderStatus", &["Pending", "Shipped"])
+            .with_locale("en", &[("Pending", "Pending"), ("Shipped", "Shipped")])
+            .with_locale("de-DE", &[("Pending", "Ausstehend"), ("Cancelled", "Storniert")])
+    }
+
+    #[test]
+    fn test_problems_and_fallback() {
+        let table = status_labels();
+        assert_eq!(table.label("de-DE", "Pending"), Some("Ausstehend"));
+        assert_eq!(table.label("de-DE", "Shipped"), Some("Shipped"));
+        assert_eq!(table.label("fr", "Shipped"), Some("Shipped"));
+        assert_eq!(table.label("en", "Cancelled"), None);
+
+        let problems: Vec<String> = table.problems().iter().map(ToString::to_string).collect();
+        assert_eq!(problems, vec!["de-DE: no label for `Shipped`", "de-DE: label for unknown variant `Cancelled`"]);
+    }
+
+    #[test]
+    fn test_render_every_side() {
+        let table = status_labels();
+        let rust = table.render("rust").unwrap();
+        assert!(rust.contains("pub static ORDER_STATUS_LABELS_DE_DE: phf::Map<&'static str, &'static str> = phf::phf_map! {\n    \"Pending\" => \"Ausstehend\",\n    \"Shipped\" => \"Shipped\",\n};"));
+        assert!(rust.contains("        \"de-DE\" => &ORDER_STATUS_LABELS_DE_DE,\n"));
+        assert!(!rust.contains("Storniert"));
+
+        let ts = table.render("typescript").unwrap();
+        assert!(ts.contains("export const orderStatusLabels = {\n  \"en\": {\n    \"Pending\": \"Pending\","));
+        assert!(ts.contains("export function orderStatusLabel(variant: string, locale: string): string | undefined {"));
+
+        let rescript = table.render("rescript").unwrap();
+        assert!(rescript.contains("  (\"de-DE\", Dict.fromArray([(\"Pending\", \"Ausstehend\"), (\"Shipped\", \"Shipped\")])),\n"));
+        assert!(table.render("cobol").is_none());
+    }
+}
@@ -0,0 +1,492 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Code generation from the language-neutral IR
+// Renders IR types in target-language syntax and hosts the lowering passes
+// that synthesize constructs a target lacks
+
+pub mod audit;
+pub mod c_header;
+pub mod dotnet;
+pub mod footprint;
+pub mod fuzz;
+pub mod gleam;
+pub mod graphql;
+pub mod jni;
+pub mod json_schema;
+pub mod labels;
+pub mod napi;
+pub mod newtypes;
+pub mod nif;
+pub mod openapi;
+pub mod patch;
+pub mod polymorphic;
+pub mod properties;
+pub mod protobuf;
+pub mod pyo3;
+pub mod recursion;
+pub mod rescript;
+pub mod rescript_codecs;
+pub mod scaffold;
+pub mod serde_impls;
+pub mod sized;
+pub mod snapshot;
+pub mod streaming;
+pub mod string_table;
+pub mod swift;
+pub mod tiers;
+pub mod typescript_validators;
+pub mod unions;
+pub mod variants;
+pub mod wasm_bindgen;
+
+use crate::ir::{ContainerType, DefaultValue, IrField, IrType, IrVariant, PrimitiveType};
+
+/// Digits of a decimal as JSON carries it, where a float would round
+pub const DECIMAL_PATTERN: &str = "^-?[0-9]+(\\.[0-9]+)?$";
+
+/// Digits of an arbitrary-precision integer as JSON carries it
+pub const BIGINT_PATTERN: &str = "^-?[0-9]+$";
+
+/// Render an IR type in the syntax of a target language
+pub fn render_type(ty: &IrType, target: &str) -> String {
+    match target {
+        "rust" => match ty {
+            IrType::Primitive(primitive) => match primitive {
+                PrimitiveType::Bool => "bool",
+                PrimitiveType::I8 => "i8",
+                PrimitiveType::I16 => "i16",
+                PrimitiveType::I32 => "i32",
+                PrimitiveType::I64 => "i64",
+                PrimitiveType::I128 => "i128",
+                PrimitiveType::U8 => "u8",
+                PrimitiveType::U16 => "u16",
+                PrimitiveType::U32 => "u32",
+                PrimitiveType::U64 => "u64",
+                PrimitiveType::U128 => "u128",
+                PrimitiveType::F32 => "f32",
+                PrimitiveType::F64 => "f64",
+                PrimitiveType::String => "String",
+                PrimitiveType::Unit => "()",
+                PrimitiveType::DateTime => "chrono::DateTime<chrono::Utc>",
+                PrimitiveType::Uuid => "uuid::Uuid",
+                PrimitiveType::Decimal => "rust_decimal::Decimal",
+                PrimitiveType::BigInt => "num_bigint::BigInt",
+                PrimitiveType::Bytes => "Vec<u8>",
+            }
+            .to_string(),
+            IrType::Container(ContainerType::Vec(inner)) => format!("Vec<{}>", render_type(inner, target)),
+            IrType::Container(ContainerType::Array(inner, len)) => format!("[{}; {}]", render_type(inner, target), len),
+            IrType::Container(ContainerType::Option(inner)) => format!("Option<{}>", render_type(inner, target)),
+            IrType::Container(ContainerType::Result(ok, err)) => {
+                format!("Result<{}, {}>", render_type(ok, target), render_type(err, target))
+            }
+            IrType::Container(ContainerType::Map(key, value)) => format!("HashMap<{}, {}>", render_type(key, target), render_type(value, target)),
+            // Declarations are PascalCase whatever the source's convention; paths stay as written
+            IrType::Reference(name) | IrType::Alias(name, _) if !name.contains("::") => pascal_case(name),
+            IrType::Reference(name) | IrType::Alias(name, _) => name.clone(),
+            IrType::Generic(name, args) => {
+                format!("{}<{}>", render_type(&IrType::Reference(name.clone()), target), render_members(args, target))
+            }
+            // Other sources name parameters lowercase (`'a`, `a`); Rust's are capitalized
+            IrType::TypeParam(name) => pascal_case(name),
+            IrType::Any => "serde_json::Value".to_string(),
+            IrType::TraitObject(name) => format!("Box<dyn {}>", name),
+            IrType::Function(params, ret) => match ret.as_ref() {
+                IrType::Primitive(PrimitiveType::Unit) => format!("Box<dyn Fn({})>", render_members(params, target)),
+                ret => format!("Box<dyn Fn({}) -> {}>", render_members(params, target), render_type(ret, target)),
+            },
+            IrType::Union(members) => unions::union_name(members),
+            IrType::Tuple(members) => format!("({})", render_members(members, target)),
+        },
+        "gleam" => match ty {
+            IrType::Primitive(primitive) => match primitive {
+                PrimitiveType::Bool => "Bool",
+                PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal => "Float",
+                PrimitiveType::String | PrimitiveType::Uuid => "String",
+                PrimitiveType::Unit => "Nil",
+                PrimitiveType::DateTime => "birl.Time",
+                PrimitiveType::Bytes => "BitArray",
+                _ => "Int",
+            }
+            .to_string(),
+            // Fixed-length arrays downgrade to dynamic ones; the length goes unchecked
+            IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("List({})", render_type(inner, target)),
+            IrType::Container(ContainerType::Option(inner)) => format!("Option({})", render_type(inner, target)),
+            IrType::Container(ContainerType::Result(ok, err)) => {
+                format!("Result({}, {})", render_type(ok, target), render_type(err, target))
+            }
+            IrType::Container(ContainerType::Map(key, value)) => format!("Dict({}, {})", render_type(key, target), render_type(value, target)),
+            // Gleam type names are PascalCase, as their declarations are
+            IrType::Reference(name) | IrType::Alias(name, _) => pascal_case(name),
+            IrType::Generic(name, args) => format!("{}({})", pascal_case(name), render_members(args, target)),
+            IrType::TypeParam(name) => name.to_lowercase(),
+            // Nothing else has trait objects; they render as the dynamic escape hatch
+            IrType::Any | IrType::TraitObject(_) => "Dynamic".to_string(),
+            IrType::Function(params, ret) => format!("fn({}) -> {}", render_members(params, target), render_type(ret, target)),
+            IrType::Union(members) => unions::union_name(members),
+            IrType::Tuple(members) => format!("#({})", render_members(members, target)),
+        },
+        "rescript" => match ty {
+            IrType::Primitive(primitive) => match primitive {
+                PrimitiveType::Bool => "bool",
+                PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal => "float",
+                PrimitiveType::BigInt => "bigint",
+                // JSON has no date; the RFC 3339 text stays a string
+                PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid => "string",
+                PrimitiveType::Unit => "unit",
+                PrimitiveType::Bytes => "Js.TypedArray2.ArrayBuffer.t",
+                _ => "int",
+            }
+            .to_string(),
+            IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("array<{}>", render_type(inner, target)),
+            IrType::Container(ContainerType::Option(inner)) => format!("option<{}>", render_type(inner, target)),
+            IrType::Container(ContainerType::Result(ok, err)) => {
+                format!("result<{}, {}>", render_type(ok, target), render_type(err, target))
+            }
+            // `Js.Dict.t` keys are strings; integer keys get Belt's specialized map
+            IrType::Container(ContainerType::Map(key, value)) => match key.as_ref() {
+                IrType::Primitive(primitive) if primitive.is_integer() => {
+                    format!("Belt.Map.Int.t<{}>", render_type(value, target))
+                }
+                _ => format!("Js.Dict.t<{}>", render_type(value, target)),
+            },
+            // ReScript type names start lowercase; type variables are quoted
+            IrType::Reference(name) | IrType::Alias(name, _) => camel_case(name),
+            IrType::Generic(name, args) => format!("{}<{}>", camel_case(name), render_members(args, target)),
+            IrType::TypeParam(name) => format!("'{}", name.to_lowercase()),
+            IrType::Any | IrType::TraitObject(_) => "JSON.t".to_string(),
+            IrType::Function(params, ret) => format!("({}) => {}", render_members(params, target), render_type(ret, target)),
+            IrType::Union(members) => camel_case(&unions::union_name(members)),
+            IrType::Tuple(members) => format!("({})", render_members(members, target)),
+        },
+        "typescript" => match ty {
+            IrType::Primitive(primitive) => match primitive {
+                PrimitiveType::Bool => "boolean",
+                // An ISO string, as JSON delivers it; callers wrap it in a `Date`
+                PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid => "string",
+                // Base64 text, as JSON delivers it
+                PrimitiveType::Bytes => "string",
+                PrimitiveType::Unit => "null",
+                // Exact and arbitrary-precision numbers travel as their digits
+                PrimitiveType::Decimal | PrimitiveType::BigInt => "string",
+                _ => "number",
+            }
+            .to_string(),
+            IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("Array<{}>", render_type(inner, target)),
+            IrType::Container(ContainerType::Option(inner)) => match inner.as_ref() {
+                IrType::Function(_, _) => format!("({}) | null", render_type(inner, target)),
+                inner => format!("{} | null", render_type(inner, target)),
+            },
+            IrType::Container(ContainerType::Result(ok, err)) => {
+                format!("{{ Ok: {} }} | {{ Err: {} }}", render_type(ok, target), render_type(err, target))
+            }
+            IrType::Container(ContainerType::Map(key, value)) => {
+                // Object keys are strings; anything but a number is keyed by its text
+                let key = match key.as_ref() {
+                    IrType::Primitive(primitive) if primitive.is_integer() => "number",
+                    _ => "string",
+                };
+                format!("Record<{}, {}>", key, render_type(value, target))
+            }
+            // Declarations are PascalCase whatever the source's convention
+            IrType::Reference(name) | IrType::Alias(name, _) => pascal_case(name),
+            IrType::Generic(name, args) => format!("{}<{}>", pascal_case(name), render_members(args, target)),
+            IrType::TypeParam(name) => pascal_case(name),
+            IrType::Any | IrType::TraitObject(_) => "unknown".to_string(),
+            IrType::Function(params, ret) => {
+                let params: Vec<String> = params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, param)| format!("arg{}: {}", i, render_type(param, target)))
+                    .collect();
+                let ret = match ret.as_ref() {
+                    IrType::Primitive(PrimitiveType::Unit) => "void".to_string(),
+                    ret => render_type(ret, target),
+                };
+                format!("({}) => {}", params.join(", "), ret)
+            }
+            IrType::Union(members) => members
+                .iter()
+                .map(|member| render_type(member, target))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            IrType::Tuple(members) => format!("[{}]", render_members(members, target)),
+        },
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Type parameter list of a declaration (`<T>`, `(t)`, `<'t>`); empty when it has none
+pub fn render_type_params(params: &[String], target: &str) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<IrType> = params.iter().map(|param| IrType::TypeParam(param.clone())).collect();
+    match target {
+        "gleam" => format!("({})", render_members(&params, target)),
+        _ => format!("<{}>", render_members(&params, target)),
+    }
+}
+
+fn render_members(members: &[IrType], target: &str) -> String {
+    members
+        .iter()
+        .map(|member| render_type(member, target))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `serde_with` adapter a Rust field of type `ty` needs to cross JSON the way
+/// other targets expect: bytes go as base64 rather than an array of numbers
+pub fn rust_serde_as(ty: &IrType) -> Option<String> {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bytes) => Some("serde_with::base64::Base64".to_string()),
+        IrType::Container(ContainerType::Option(inner)) => rust_serde_as(inner).map(|adapter| format!("Option<{}>", adapter)),
+        IrType::Container(ContainerType::Vec(inner)) => rust_serde_as(inner).map(|adapter| format!("Vec<{}>", adapter)),
+        _ => None,
+    }
+}
+
+/// `doc` as a doc comment in `target`, every line indented by `indent`
+pub fn render_doc(doc: &str, target: &str, indent: &str) -> String {
+    match target {
+        "typescript" | "rescript" | "c" if !doc.contains('\n') => format!("{indent}/** {doc} */\n"),
+        "typescript" | "rescript" | "c" => {
+            let lines: String = doc.lines().map(|line| format!("{indent} *{}{line}\n", if line.is_empty() { "" } else { " " })).collect();
+            format!("{indent}/**\n{lines}{indent} */\n")
+        }
+        // A docstring, which Julia attaches to the definition after it
+        "julia" => {
+            let lines: String = doc.lines().map(|line| format!("{indent}{line}\n")).collect();
+            format!("{indent}\"\"\"\n{lines}{indent}\"\"\"\n")
+        }
+        _ => doc.lines().map(|line| format!("{indent}///{}{line}\n", if line.is_empty() { "" } else { " " })).collect(),
+    }
+}
+
+/// `default` as a literal of type `ty` in `target`; `None` when it has no
+/// literal there (computed values, user types)
+pub fn render_default(default: &DefaultValue, ty: &IrType, target: &str) -> Option<String> {
+    let ty = match ty {
+        IrType::Alias(_, aliased) => return render_default(default, aliased, target),
+        ty => ty,
+    };
+    let default = match default {
+        DefaultValue::TypeDefault => type_default(ty)?,
+        other => other.clone(),
+    };
+    Some(match (default, ty) {
+        (DefaultValue::Bool(value), _) if target == "gleam" => if value { "True" } else { "False" }.to_string(),
+        (DefaultValue::Bool(value), _) => value.to_string(),
+        (DefaultValue::Number(_), IrType::Primitive(PrimitiveType::Decimal | PrimitiveType::BigInt)) if target == "rust" => return None,
+        (DefaultValue::Number(number), IrType::Primitive(PrimitiveType::Decimal | PrimitiveType::BigInt)) if target == "typescript" => format!("{:?}", number),
+        (DefaultValue::Number(number), IrType::Primitive(PrimitiveType::BigInt)) if target != "gleam" => format!("{}n", number),
+        (DefaultValue::Number(number), IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal))
+            if target != "typescript" && !number.contains(['.', 'e', 'E']) =>
+        {
+            format!("{}.0", number)
+        }
+        (DefaultValue::Number(number), _) => number,
+        (DefaultValue::String(text), IrType::Primitive(PrimitiveType::String)) if target == "rust" => format!("{:?}.to_string()", text),
+        (DefaultValue::String(text), IrType::Primitive(PrimitiveType::String)) => format!("{:?}", text),
+        (DefaultValue::String(text), IrType::Primitive(PrimitiveType::Uuid)) if target != "rust" => format!("{:?}", text),
+        (DefaultValue::Null, _) => match target {
+            "rust" | "rescript" => "None".to_string(),
+            "gleam" => "option.None".to_string(),
+            _ => "null".to_string(),
+        },
+        (DefaultValue::Empty, IrType::Container(ContainerType::Vec(_))) => match target {
+            "rust" => "Vec::new()".to_string(),
+            _ => "[]".to_string(),
+        },
+        (DefaultValue::Empty, IrType::Container(ContainerType::Map(_, _))) => match target {
+            "rust" => "Default::default()".to_string(),
+            "rescript" => "Js.Dict.empty()".to_string(),
+            "typescript" => "{}".to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// What a field of type `ty` holds when defaulted to its type's default
+fn type_default(ty: &IrType) -> Option<DefaultValue> {
+    Some(match ty {
+        IrType::Primitive(PrimitiveType::Bool) => DefaultValue::Bool(false),
+        IrType::Primitive(PrimitiveType::String) => DefaultValue::String(String::new()),
+        IrType::Primitive(primitive) if primitive.is_integer() || *primitive == PrimitiveType::BigInt => DefaultValue::Number("0".to_string()),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal) => DefaultValue::Number("0.0".to_string()),
+        IrType::Container(ContainerType::Option(_)) => DefaultValue::Null,
+        IrType::Container(ContainerType::Vec(_) | ContainerType::Map(_, _)) => DefaultValue::Empty,
+        _ => return None,
+    })
+}
+
+/// The `#[serde(default ...)]` attribute a Rust field of `type_name` needs,
+/// with the function serde calls when the default is a literal other than
+/// the type's own
+pub fn rust_serde_default(type_name: &str, field: &IrField) -> Option<(String, Option<String>)> {
+    let default = field.default.as_ref()?;
+    let literal = match default {
+        DefaultValue::Bool(_) | DefaultValue::Number(_) | DefaultValue::String(_) => render_default(default, &field.ty, "rust"),
+        _ => None,
+    };
+    Some(match literal {
+        Some(literal) => {
+            let function = format!("default_{}_{}", snake_case(type_name), snake_case(&field.name));
+            (
+                format!("#[serde(default = \"{}\")]", function),
+                Some(format!("fn {}() -> {} {{\n    {}\n}}\n", function, render_type(&field.ty, "rust"), literal)),
+            )
+        }
+        None => ("#[serde(default)]".to_string(), None),
+    })
+}
+
+/// Render a sum type as a ReScript variant or a Gleam custom type; positional
+/// payloads stay positional, named ones become an inline record or labels
+pub fn render_variant_type(name: &str, params: &[String], variants: &[IrVariant], target: &str) -> Option<String> {
+    // ReScript only lets a type refer to itself when declared `rec`
+    let recursive = variants
+        .iter()
+        .flat_map(|variant| &variant.fields)
+        .any(|field| recursion::mentions(&field.ty, name));
+    let params = render_type_params(params, target);
+    let (mut code, close) = match target {
+        "rescript" if recursive => (format!("type rec {}{} =\n", camel_case(name), params), ""),
+        "rescript" => (format!("type {}{} =\n", camel_case(name), params), ""),
+        "gleam" => (format!("pub type {}{} {{\n", pascal_case(name), params), "}\n"),
+        _ => return None,
+    };
+    for variant in variants {
+        let positional = variant.fields.iter().all(|field| field.name.parse::<usize>().is_ok());
+        let payload: Vec<String> = variant
+            .fields
+            .iter()
+            .map(|field| {
+                let ty = render_type(&field.ty, target);
+                if positional {
+                    ty
+                } else if target == "gleam" {
+                    format!("{}: {}", snake_case(&field.name), ty)
+                } else {
+                    format!("{}: {}", camel_case(&field.name), ty)
+                }
+            })
+            .collect();
+        let payload = payload.join(", ");
+        code.push_str(&match (target, variant.fields.is_empty(), positional) {
+            ("gleam", true, _) => format!("  {}\n", pascal_case(&variant.name)),
+            ("gleam", false, _) => format!("  {}({})\n", pascal_case(&variant.name), payload),
+            (_, true, _) => format!("  | {}\n", variant.name),
+            (_, false, true) => format!("  | {}({})\n", variant.name, payload),
+            // ReScript spells named payloads as an inline record
+            (_, false, false) => format!("  | {}({{{}}})\n", variant.name, payload),
+        });
+    }
+    code.push_str(close);
+    Some(code)
+}
+
+/// Convert `snake_case` or `camelCase` to `PascalCase`
+pub fn pascal_case(name: &str) -> String {
+    name.split(['_', '-', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Convert `snake_case` or `PascalCase` to `camelCase`
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+}
+
+/// Convert `PascalCase` or `camelCase` to `snake_case`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else if c == '-' || c == ' ' {
+            snake.push('_');
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Rust `source` analyzed into IR definitions, for the backends' tests
+#[cfg(test)]
+pub(crate) fn analyze_rust(source: &str) -> Vec<crate::api::TypeDef> {
+    crate::api::Registry::with_builtins().analyzer(crate::api::Language::Rust).unwrap().analyze(source).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::IrField;
+
+    #[test]
+    fn test_render_nested_containers() {
+        let ty = IrType::Container(ContainerType::Vec(Box::new(IrType::Container(ContainerType::Option(
+            Box::new(IrType::Primitive(PrimitiveType::I64)),
+        )))));
+        assert_eq!(render_type(&ty, "rust"), "Vec<Option<i64>>");
+        assert_eq!(render_type(&ty, "gleam"), "List(Option(Int))");
+        assert_eq!(render_type(&ty, "typescript"), "Array<number | null>");
+        assert_eq!(render_type(&ty, "rescript"), "array<option<int>>");
+    }
+
+    #[test]
+    fn test_render_variant_type() {
+        let string = IrType::Primitive(PrimitiveType::String);
+        let variants = vec![
+            IrVariant::new("Active", vec![]),
+            IrVariant::new("Suspended", vec![IrField::new("0", string.clone())]),
+            IrVariant::new("Moved", vec![IrField::new("newHome", string)]),
+        ];
+        assert_eq!(
+            render_variant_type("AccountStatus", &[], &variants, "rescript").unwrap(),
+            "type accountStatus =\n  | Active\n  | Suspended(string)\n  | Moved({newHome: string})\n"
+        );
+        assert_eq!(
+            render_variant_type("AccountStatus", &[], &variants, "gleam").unwrap(),
+            "pub type AccountStatus {\n  Active\n  Suspended(String)\n  Moved(new_home: String)\n}\n"
+        );
+        assert!(render_variant_type("AccountStatus", &[], &variants, "cobol").is_none());
+    }
+
+    #[test]
+    fn test_render_recursive_variant_type() {
+        let tree = IrType::Reference("Tree".to_string());
+        let variants = vec![
+            IrVariant::new("Leaf", vec![IrField::new("0", IrType::Primitive(PrimitiveType::I64))]),
+            IrVariant::new("Node", vec![IrField::new("0", IrType::Container(ContainerType::Vec(Box::new(tree))))]),
+        ];
+        assert_eq!(
+            render_variant_type("Tree", &[], &variants, "rescript").unwrap(),
+            "type rec tree =\n  | Leaf(int)\n  | Node(array<tree>)\n"
+        );
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(pascal_case("created_at"), "CreatedAt");
+        assert_eq!(camel_case("created_at"), "createdAt");
+        assert_eq!(snake_case("StringOrNumber"), "string_or_number");
+    }
+}
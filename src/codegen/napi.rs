@@ -0,0 +1,468 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// napi-rs bindings for Rust structs, enums and free functions
+// Structs get a `#[napi(object)]` mirror converted field by field, fieldless
+// enums a string enum, and functions a wrapper taking and returning mirrors.
+// Where napi has no exact counterpart the mirror widens (64-bit integers to
+// `BigInt`) or carries serde's JSON, and narrowing back is checked, so no value
+// changes on the way through unnoticed. Like the wasm-bindgen glue, this goes in
+// a child module of the one defining the types
+
+use super::{camel_case, render_doc, render_type, snake_case};
+use crate::analyzers::rust_analyzer::{
+    is_zero_sized, rust_type_name, to_ir, RustFieldType, RustFunction, RustType, RustTypeKind, RustVariantKind,
+};
+use crate::ir;
+use crate::usage::referenced_names;
+
+/// Names of the Rust types that get a napi mirror; values of any other type cross as JSON
+struct Mirrors<'a> {
+    objects: Vec<&'a str>,
+    enums: Vec<&'a str>,
+}
+
+impl<'a> Mirrors<'a> {
+    fn of(types: &'a [RustType]) -> Self {
+        let named = |keep: fn(&RustType) -> bool| types.iter().filter(|t| keep(t)).map(|t| t.name.as_str()).collect();
+        Mirrors { objects: named(is_object), enums: named(is_string_enum) }
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.objects.contains(&name) || self.enums.contains(&name)
+    }
+}
+
+/// Behaviour can't cross as a value
+fn crossable(field_type: &RustFieldType) -> bool {
+    let ty = to_ir(field_type);
+    ir::functions(&ty).is_empty() && ir::trait_objects(&ty).is_empty()
+}
+
+/// Structs mirrored as napi objects; generic, tuple and borrowing structs are not
+fn is_object(rust_type: &RustType) -> bool {
+    rust_type.kind == RustTypeKind::Struct
+        && rust_type.type_params.is_empty()
+        && rust_type.const_params.is_empty()
+        && rust_type.fields.iter().all(|field| {
+            !field.name.starts_with(|c: char| c.is_ascii_digit())
+                && !matches!(field.field_type, RustFieldType::Borrowed(_, _) | RustFieldType::Cow(_, _))
+                && crossable(&field.field_type)
+        })
+}
+
+fn is_string_enum(rust_type: &RustType) -> bool {
+    rust_type.kind == RustTypeKind::Enum
+        && rust_type.type_params.is_empty()
+        && !rust_type.variants.is_empty()
+        && rust_type.variants.iter().all(|variant| variant.kind == RustVariantKind::Unit)
+}
+
+/// Functions get a wrapper unless a parameter is `&mut`, whose changes a copy
+/// from JavaScript would swallow, or the result borrows from a parameter
+fn is_wrapped(function: &RustFunction) -> bool {
+    function.params.iter().map(|(_, ty)| ty).chain([&function.ret]).all(crossable)
+        && !function.params.iter().any(|(_, ty)| matches!(ty, RustFieldType::Borrowed(written, _) if written.starts_with("&mut")))
+        && !matches!(function.ret, RustFieldType::Borrowed(_, _) | RustFieldType::Cow(_, _))
+}
+
+fn is_string_key(key: &RustFieldType) -> bool {
+    match key {
+        RustFieldType::Alias(_, inner) => is_string_key(inner),
+        key => matches!(key, RustFieldType::String),
+    }
+}
+
+/// Whether napi carries the value as the Rust type itself
+fn is_same(ty: &RustFieldType) -> bool {
+    match ty {
+        RustFieldType::I8
+        | RustFieldType::I16
+        | RustFieldType::I32
+        | RustFieldType::U8
+        | RustFieldType::U16
+        | RustFieldType::U32
+        | RustFieldType::F64
+        | RustFieldType::Bool
+        | RustFieldType::String => true,
+        RustFieldType::Alias(_, inner) | RustFieldType::Option(inner) => is_same(inner),
+        RustFieldType::Vec(inner) => !matches!(**inner, RustFieldType::U8) && is_same(inner),
+        _ => false,
+    }
+}
+
+/// Type of the napi-side value for `ty`
+fn napi_type(ty: &RustFieldType, mirrors: &Mirrors) -> String {
+    match ty {
+        _ if is_same(ty) => rust_type_name(ty),
+        RustFieldType::F32 => "f64".to_string(),
+        RustFieldType::I64
+        | RustFieldType::U64
+        | RustFieldType::I128
+        | RustFieldType::U128
+        | RustFieldType::Isize
+        | RustFieldType::Usize => "BigInt".to_string(),
+        RustFieldType::Alias(_, inner) => napi_type(inner, mirrors),
+        RustFieldType::Option(inner) => format!("Option<{}>", napi_type(inner, mirrors)),
+        RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => "Buffer".to_string(),
+        RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) => format!("Vec<{}>", napi_type(inner, mirrors)),
+        RustFieldType::Map(key, value) if is_string_key(key) => format!("HashMap<String, {}>", napi_type(value, mirrors)),
+        RustFieldType::Struct(name) if mirrors.contains(name) => format!("{}Js", name),
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+/// Expression turning the Rust value `expr` into its napi value; may use `?`
+fn to_napi(expr: &str, ty: &RustFieldType, mirrors: &Mirrors) -> String {
+    match ty {
+        _ if is_same(ty) => expr.to_string(),
+        RustFieldType::F32 => format!("f64::from({})", expr),
+        RustFieldType::I64 | RustFieldType::U64 | RustFieldType::I128 | RustFieldType::U128 => format!("BigInt::from({})", expr),
+        RustFieldType::Isize => format!("BigInt::from({} as i64)", expr),
+        RustFieldType::Usize => format!("BigInt::from({} as u64)", expr),
+        RustFieldType::Alias(_, inner) => to_napi(expr, inner, mirrors),
+        RustFieldType::Option(inner) => {
+            format!("{}.map(|value| Ok::<_, napi::Error>({})).transpose()?", expr, to_napi("value", inner, mirrors))
+        }
+        RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => format!("Buffer::from({})", expr),
+        RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) => format!(
+            "{}.into_iter().map(|value| Ok::<_, napi::Error>({})).collect::<napi::Result<Vec<_>>>()?",
+            expr,
+            to_napi("value", inner, mirrors)
+        ),
+        RustFieldType::Map(key, value) if is_string_key(key) => map_entries(expr, &to_napi("value", value, mirrors)),
+        RustFieldType::Struct(name) if mirrors.objects.contains(&name.as_str()) => format!("{}Js::try_from({})?", name, expr),
+        RustFieldType::Struct(name) if mirrors.enums.contains(&name.as_str()) => format!("{}Js::from({})", name, expr),
+        _ => format!("serde_json::to_value({})?", expr),
+    }
+}
+
+/// Expression turning the napi value `expr` back into the Rust value; narrowing
+/// that would change the value fails instead
+fn from_napi(expr: &str, ty: &RustFieldType, mirrors: &Mirrors) -> String {
+    match ty {
+        _ if is_same(ty) => expr.to_string(),
+        // A JavaScript number read from an `f32` narrows back to it exactly
+        RustFieldType::F32 => format!("{} as f32", expr),
+        RustFieldType::I64
+        | RustFieldType::U64
+        | RustFieldType::I128
+        | RustFieldType::U128
+        | RustFieldType::Isize
+        | RustFieldType::Usize => format!("{}_from_bigint({})?", rust_type_name(ty), expr),
+        RustFieldType::Alias(_, inner) => from_napi(expr, inner, mirrors),
+        RustFieldType::Option(inner) => {
+            format!("{}.map(|value| Ok::<_, napi::Error>({})).transpose()?", expr, from_napi("value", inner, mirrors))
+        }
+        RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => format!("{}.to_vec()", expr),
+        RustFieldType::Vec(inner) => format!(
+            "{}.into_iter().map(|value| Ok::<_, napi::Error>({})).collect::<napi::Result<Vec<_>>>()?",
+            expr,
+            from_napi("value", inner, mirrors)
+        ),
+        RustFieldType::Array(inner, len) => format!(
+            "<[{}; {len}]>::try_from({}).map_err(|_| napi::Error::from_reason(\"expected {len} elements\"))?",
+            rust_type_name(inner),
+            from_napi(expr, &RustFieldType::Vec(inner.clone()), mirrors)
+        ),
+        RustFieldType::Map(key, value) if is_string_key(key) => map_entries(expr, &from_napi("value", value, mirrors)),
+        RustFieldType::Struct(name) if mirrors.objects.contains(&name.as_str()) => format!("{}::try_from({})?", name, expr),
+        RustFieldType::Struct(name) if mirrors.enums.contains(&name.as_str()) => format!("{}::from({})", name, expr),
+        _ => format!("serde_json::from_value({})?", expr),
+    }
+}
+
+/// Map with each value converted by `value`; the collection type is the
+/// destination's, so `BTreeMap` and `HashMap` convert into each other
+fn map_entries(expr: &str, value: &str) -> String {
+    match value {
+        "value" => format!("{}.into_iter().collect()", expr),
+        value => format!(
+            "{}.into_iter().map(|(key, value)| Ok::<_, napi::Error>((key, {}))).collect::<napi::Result<_>>()?",
+            expr, value
+        ),
+    }
+}
+
+/// Checked narrowing from `BigInt`, one per integer type the glue reads back
+const BIGINT_READERS: &[(&str, &str, &str)] = &[
+    ("i64", "value.get_i64()", "(value, true) => Ok(value)"),
+    ("u64", "value.get_u64()", "(false, value, true) => Ok(value)"),
+    ("i128", "value.get_i128()", "(value, true) => Ok(value)"),
+    ("u128", "value.get_u128()", "(false, value, true) => Ok(value)"),
+    ("isize", "value.get_i64()", "(value, true) if isize::try_from(value).is_ok() => Ok(value as isize)"),
+    ("usize", "value.get_u64()", "(false, value, true) if usize::try_from(value).is_ok() => Ok(value as usize)"),
+];
+
+/// napi-rs glue for `types` and `functions`; anything left out is listed at
+/// the top with the reason
+pub fn generate_glue(types: &[RustType], functions: &[RustFunction]) -> String {
+    let mirrors = Mirrors::of(types);
+    let mut body = String::new();
+    for rust_type in types {
+        if mirrors.objects.contains(&rust_type.name.as_str()) {
+            body.push('\n');
+            body.push_str(&render_object(rust_type, &mirrors));
+        } else if mirrors.enums.contains(&rust_type.name.as_str()) {
+            body.push('\n');
+            body.push_str(&render_string_enum(rust_type));
+        }
+    }
+    for function in functions.iter().filter(|f| is_wrapped(f)) {
+        body.push('\n');
+        body.push_str(&render_function(function, &mirrors));
+    }
+
+    let mut code = "use super::*;\nuse napi::bindgen_prelude::{BigInt, Buffer};\nuse napi_derive::napi;\nuse std::collections::HashMap;\n".to_string();
+    for rust_type in types.iter().filter(|t| t.kind != RustTypeKind::Alias && !mirrors.contains(&t.name)) {
+        code.push_str(&format!(
+            "// `{}` has no mirror: generic, tuple and borrowing structs, data enums and types holding functions cross as JSON or not at all\n",
+            rust_type.name
+        ));
+    }
+    for function in functions.iter().filter(|f| !is_wrapped(f)) {
+        code.push_str(&format!(
+            "// `{}` is left out: it takes `&mut`, returns a borrow, or passes functions\n",
+            function.name
+        ));
+    }
+    for (ty, read, lossless) in BIGINT_READERS {
+        if body.contains(&format!("{}_from_bigint(", ty)) {
+            code.push_str(&format!(
+                "\nfn {ty}_from_bigint(value: BigInt) -> napi::Result<{ty}> {{\n    match {read} {{\n        {lossless},\n        \
+                 _ => Err(napi::Error::from_reason(\"BigInt out of range for {ty}\")),\n    }}\n}}\n"
+            ));
+        }
+    }
+    code.push_str(&body);
+    code
+}
+
+fn render_object(rust_type: &RustType, mirrors: &Mirrors) -> String {
+    let name = &rust_type.name;
+    let mut code = rust_type.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    code.push_str(&format!("#[napi(object, js_name = \"{name}\")]\npub struct {name}Js {{\n"));
+    let mut to = String::new();
+    let mut from = String::new();
+    for field in &rust_type.fields {
+        let ident = &field.name;
+        if is_zero_sized(&field.field_type) {
+            from.push_str(&format!("            {}: Default::default(),\n", ident));
+            continue;
+        }
+        if let Some(doc) = &field.doc {
+            code.push_str(&render_doc(doc, "rust", "    "));
+        }
+        code.push_str(&format!("    pub {}: {},\n", ident, napi_type(&field.field_type, mirrors)));
+        let value = format!("value.{}", ident);
+        to.push_str(&format!("            {}: {},\n", ident, to_napi(&value, &field.field_type, mirrors)));
+        from.push_str(&format!("            {}: {},\n", ident, from_napi(&value, &field.field_type, mirrors)));
+    }
+    code.push_str("}\n");
+    for (source, target, fields) in [(name.clone(), format!("{name}Js"), to), (format!("{name}Js"), name.clone(), from)] {
+        code.push_str(&format!(
+            "\nimpl TryFrom<{source}> for {target} {{\n    type Error = napi::Error;\n\n    \
+             fn try_from(value: {source}) -> napi::Result<Self> {{\n        Ok({target} {{\n{fields}        }})\n    }}\n}}\n"
+        ));
+    }
+    code
+}
+
+fn render_string_enum(rust_type: &RustType) -> String {
+    let name = &rust_type.name;
+    let mut code = rust_type.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    code.push_str(&format!("#[napi(string_enum, js_name = \"{name}\")]\npub enum {name}Js {{\n"));
+    for variant in &rust_type.variants {
+        code.push_str(&format!("    {},\n", variant.name));
+    }
+    code.push_str("}\n");
+    for (source, target) in [(name.clone(), format!("{name}Js")), (format!("{name}Js"), name.clone())] {
+        code.push_str(&format!("\nimpl From<{source}> for {target} {{\n    fn from(value: {source}) -> Self {{\n        match value {{\n"));
+        for variant in &rust_type.variants {
+            code.push_str(&format!("            {source}::{0} => {target}::{0},\n", variant.name));
+        }
+        code.push_str("        }\n    }\n}\n");
+    }
+    code
+}
+
+fn render_function(function: &RustFunction, mirrors: &Mirrors) -> String {
+    let mut code = function.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut params = Vec::new();
+    let mut converts = String::new();
+    let mut args = Vec::new();
+    for (name, ty) in &function.params {
+        let (referent, arg) = match ty {
+            RustFieldType::Borrowed(_, owned) => (owned.as_ref(), format!("&{}", name)),
+            RustFieldType::Cow(_, owned) => (owned.as_ref(), format!("{}.into()", name)),
+            ty => (ty, name.clone()),
+        };
+        params.push(format!("{}: {}", name, napi_type(referent, mirrors)));
+        if !is_same(referent) {
+            converts.push_str(&format!("    let {} = {};\n", name, from_napi(name, referent, mirrors)));
+        }
+        args.push(arg);
+    }
+
+    let call = format!("{}({})", function.name, args.join(", "));
+    let (ret, body) = match &function.ret {
+        RustFieldType::Tuple(members) if members.is_empty() => ("()".to_string(), format!("{};\n    Ok(())", call)),
+        ret => (napi_type(ret, mirrors), format!("Ok({})", to_napi(&call, ret, mirrors))),
+    };
+    code.push_str(&format!(
+        "#[napi(js_name = \"{}\")]\npub fn {}_js({}) -> napi::Result<{}> {{\n{}    {}\n}}\n",
+        camel_case(&function.name),
+        snake_case(&function.name),
+        params.join(", "),
+        ret,
+        converts,
+        body
+    ));
+    code
+}
+
+/// `.d.ts` declarations of what `generate_glue` exports, in the shape napi-rs
+/// gives them: object fields camelCased, `Option` fields optional
+pub fn generate_typings(types: &[RustType], functions: &[RustFunction]) -> String {
+    let mirrors = Mirrors::of(types);
+    let mut code = "/* Declarations of the napi-rs bindings */\n".to_string();
+    for rust_type in types {
+        if mirrors.objects.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&rust_type.doc.as_ref().map(|doc| render_doc(doc, "typescript", "")).unwrap_or_default());
+            code.push_str(&format!("export interface {} {{\n", rust_type.name));
+            for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+                if let Some(doc) = &field.doc {
+                    code.push_str(&render_doc(doc, "typescript", "  "));
+                }
+                let (optional, ty) = match &field.field_type {
+                    RustFieldType::Option(inner) => ("?", ts_type(inner, &mirrors)),
+                    ty => ("", ts_type(ty, &mirrors)),
+                };
+                code.push_str(&format!("  {}{}: {}\n", camel_case(&field.name), optional, ty));
+            }
+            code.push_str("}\n");
+        } else if mirrors.enums.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&rust_type.doc.as_ref().map(|doc| render_doc(doc, "typescript", "")).unwrap_or_default());
+            code.push_str(&format!("export const enum {} {{\n", rust_type.name));
+            for variant in &rust_type.variants {
+                code.push_str(&format!("  {0} = '{0}',\n", variant.name));
+            }
+            code.push_str("}\n");
+        }
+    }
+    for function in functions.iter().filter(|f| is_wrapped(f)) {
+        code.push('\n');
+        code.push_str(&function.doc.as_ref().map(|doc| render_doc(doc, "typescript", "")).unwrap_or_default());
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|(name, ty)| {
+                let ty = match ty {
+                    RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned) => owned.as_ref(),
+                    ty => ty,
+                };
+                format!("{}: {}", camel_case(name), ts_type(ty, &mirrors))
+            })
+            .collect();
+        let ret = match &function.ret {
+            RustFieldType::Tuple(members) if members.is_empty() => "void".to_string(),
+            ret => ts_type(ret, &mirrors),
+        };
+        code.push_str(&format!("export declare function {}({}): {}\n", camel_case(&function.name), params.join(", "), ret));
+    }
+    code
+}
+
+/// TypeScript type of the napi value for `ty`
+fn ts_type(ty: &RustFieldType, mirrors: &Mirrors) -> String {
+    match ty {
+        RustFieldType::I8
+        | RustFieldType::I16
+        | RustFieldType::I32
+        | RustFieldType::U8
+        | RustFieldType::U16
+        | RustFieldType::U32
+        | RustFieldType::F32
+        | RustFieldType::F64 => "number".to_string(),
+        RustFieldType::I64
+        | RustFieldType::U64
+        | RustFieldType::I128
+        | RustFieldType::U128
+        | RustFieldType::Isize
+        | RustFieldType::Usize => "bigint".to_string(),
+        RustFieldType::String => "string".to_string(),
+        RustFieldType::Bool => "boolean".to_string(),
+        RustFieldType::Alias(_, inner) => ts_type(inner, mirrors),
+        RustFieldType::Option(inner) => format!("{} | null", ts_type(inner, mirrors)),
+        RustFieldType::Vec(inner) if matches!(**inner, RustFieldType::U8) => "Buffer".to_string(),
+        RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) => format!("Array<{}>", ts_type(inner, mirrors)),
+        RustFieldType::Map(key, value) if is_string_key(key) => format!("Record<string, {}>", ts_type(value, mirrors)),
+        RustFieldType::Struct(name) if mirrors.contains(name) => name.clone(),
+        // JSON in serde's shape; named types in it are keyed as serde writes
+        // them rather than camelCased like the mirrors, so they stay opaque
+        ty => {
+            let ir = to_ir(ty);
+            let mut names = Vec::new();
+            referenced_names(&ir, &mut names);
+            match names.is_empty() {
+                true => render_type(&ir, "typescript"),
+                false => "unknown".to_string(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::{analyze_rust_functions, analyze_rust_types};
+
+    const SOURCE: &str = "pub enum Status { Active, Suspended }\n\
+        /// A signed-up user\n\
+        pub struct User { pub id: u64, pub name: String, pub status: Status, pub email: Option<String>, \
+        pub scores: BTreeMap<String, f32>, pub tags: [u16; 2], pub joined: (i32, i32) }\n\
+        pub enum Event { Login { at: u64 } }\n\
+        pub fn rename(user: &User, name: &str) -> User { todo!() }\n\
+        pub fn bump(user: &mut User) {}";
+
+    #[test]
+    fn test_glue_mirrors_and_checks_narrowing() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let glue = generate_glue(&types, &functions);
+        assert!(glue.contains("// `Event` has no mirror"));
+        assert!(glue.contains("// `bump` is left out"));
+        assert!(glue.contains("fn u64_from_bigint(value: BigInt) -> napi::Result<u64> {\n    match value.get_u64() {\n        (false, value, true) => Ok(value),\n"));
+        assert!(!glue.contains("fn i128_from_bigint"));
+        assert!(glue.contains("#[napi(string_enum, js_name = \"Status\")]\npub enum StatusJs {\n    Active,\n    Suspended,\n}\n"));
+        assert!(glue.contains(
+            "pub struct UserJs {\n    pub id: BigInt,\n    pub name: String,\n    pub status: StatusJs,\n    pub email: Option<String>,\n    \
+             pub scores: HashMap<String, f64>,\n    pub tags: Vec<u16>,\n    pub joined: serde_json::Value,\n}\n"
+        ));
+        assert!(glue.contains("            id: BigInt::from(value.id),\n            name: value.name,\n            status: StatusJs::from(value.status),\n"));
+        assert!(glue.contains(
+            "            id: u64_from_bigint(value.id)?,\n            name: value.name,\n            status: Status::from(value.status),\n            email: value.email,\n            \
+             scores: value.scores.into_iter().map(|(key, value)| Ok::<_, napi::Error>((key, value as f32))).collect::<napi::Result<_>>()?,\n            \
+             tags: <[u16; 2]>::try_from(value.tags).map_err(|_| napi::Error::from_reason(\"expected 2 elements\"))?,\n            \
+             joined: serde_json::from_value(value.joined)?,\n"
+        ));
+        assert!(glue.contains(
+            "#[napi(js_name = \"rename\")]\npub fn rename_js(user: UserJs, name: String) -> napi::Result<UserJs> {\n    \
+             let user = User::try_from(user)?;\n    Ok(UserJs::try_from(rename(&user, &name))?)\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_typings_match_the_mirrors() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let typings = generate_typings(&types, &functions);
+        assert!(typings.contains("export const enum Status {\n  Active = 'Active',\n  Suspended = 'Suspended',\n}\n"));
+        assert!(typings.contains(
+            "/** A signed-up user */\nexport interface User {\n  id: bigint\n  name: string\n  status: Status\n  email?: string\n  \
+             scores: Record<string, number>\n  tags: Array<number>\n  joined: [number, number]\n}\n"
+        ));
+        assert!(typings.contains("export declare function rename(user: User, name: string): User\n"));
+        assert!(!typings.contains("bump"));
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Newtype and alias declarations
+// A newtype (Rust `struct Email(String)`, a private ReScript type) crosses the
+// wire as the value it wraps; the target either unwraps it or keeps a brand
+
+use super::{camel_case, pascal_case, render_type, render_type_params};
+use crate::api::TypeDef;
+use crate::ir::IrType;
+
+/// How a newtype is declared in the target
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NewtypePolicy {
+    /// An alias of the wrapped type, interchangeable with it
+    #[default]
+    Unwrap,
+    /// A distinct type the target's compiler keeps apart from the wrapped
+    /// one; the wire form is unchanged
+    Brand,
+}
+
+/// The wrapped type of a newtype definition: a single positional field
+pub fn newtype_inner(def: &TypeDef) -> Option<&IrType> {
+    match def.fields.as_slice() {
+        [only] if def.variants.is_empty() && def.aliased.is_none() && only.name == "0" => Some(&only.ty),
+        _ => None,
+    }
+}
+
+/// Declare `name` as an alias of `ty`
+pub fn render_alias(name: &str, params: &[String], ty: &IrType, target: &str) -> Option<String> {
+    let ty = render_type(ty, target);
+    let params = render_type_params(params, target);
+    Some(match target {
+        "rust" => format!("pub type {}{} = {};\n", pascal_case(name), params, ty),
+        "gleam" => format!("pub type {}{} =\n  {}\n", pascal_case(name), params, ty),
+        "rescript" => format!("type {}{} = {}\n", camel_case(name), params, ty),
+        "typescript" => format!("export type {}{} = {};\n", pascal_case(name), params, ty),
+        _ => return None,
+    })
+}
+
+/// Declare the newtype `def` wrapping `inner` under `policy`
+pub fn render_newtype(def: &TypeDef, inner: &IrType, policy: NewtypePolicy, target: &str) -> Option<String> {
+    if policy == NewtypePolicy::Unwrap {
+        return render_alias(&def.name, &def.type_params, inner, target);
+    }
+    let name = pascal_case(&def.name);
+    let ty = render_type(inner, target);
+    Some(match target {
+        "rust" => format!(
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n#[serde(transparent)]\npub struct {}(pub {});\n",
+            name, ty
+        ),
+        "gleam" => format!("pub opaque type {name} {{\n  {name}({})\n}}\n", ty),
+        "rescript" => format!("type {} = private {}\n", camel_case(&def.name), ty),
+        // The brand exists only at compile time; values are still plain on the wire
+        "typescript" => format!("export type {name} = {} & {{ readonly __brand: \"{name}\" }};\n", ty),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    #[test]
+    fn test_newtype_policies() {
+        let registry = Registry::with_builtins();
+        let email = registry.analyzer(Language::Rust).unwrap().analyze("pub struct Email(String);").unwrap().remove(0);
+        let inner = newtype_inner(&email).unwrap();
+        assert_eq!(render_newtype(&email, inner, NewtypePolicy::Unwrap, "typescript").unwrap(), "export type Email = string;\n");
+        assert_eq!(
+            render_newtype(&email, inner, NewtypePolicy::Brand, "typescript").unwrap(),
+            "export type Email = string & { readonly __brand: \"Email\" };\n"
+        );
+        assert_eq!(render_newtype(&email, inner, NewtypePolicy::Brand, "gleam").unwrap(), "pub opaque type Email {\n  Email(String)\n}\n");
+
+        let rescript = registry.analyzer(Language::ReScript).unwrap();
+        let private = rescript.analyze("type email = private string").unwrap().remove(0);
+        assert_eq!(render_newtype(&private, newtype_inner(&private).unwrap(), NewtypePolicy::Brand, "rust").unwrap().lines().last(), Some("pub struct Email(pub String);"));
+        let abstract_type = rescript.analyze("type token").unwrap().remove(0);
+        assert_eq!(newtype_inner(&abstract_type), Some(&IrType::Any));
+    }
+}
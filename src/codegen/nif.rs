@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Erlang NIF scaffold for Rust structs, enums and free functions
+// The Rust side is a rustler module: `Encoder` and `Decoder` impls turning each
+// struct into a map keyed by field-name atoms and each fieldless enum into an
+// atom, and a `#[rustler::nif]` per function. The stub module is the Erlang or
+// Elixir side loading it, with typespecs for the terms those impls produce
+
+use super::{pascal_case, snake_case};
+use crate::analyzers::rust_analyzer::{is_zero_sized, rust_type_name, RustFieldType, RustFunction, RustType, RustTypeKind, RustVariantKind};
+
+/// Names of the Rust types that cross as terms
+struct Terms<'a> {
+    maps: Vec<&'a str>,
+    atoms: Vec<&'a str>,
+}
+
+impl<'a> Terms<'a> {
+    /// Structs whose every field encodes, given the types around them, to a fixpoint
+    fn of(types: &'a [RustType]) -> Self {
+        let atoms = types
+            .iter()
+            .filter(|t| {
+                t.kind == RustTypeKind::Enum
+                    && t.type_params.is_empty()
+                    && !t.variants.is_empty()
+                    && t.variants.iter().all(|variant| variant.kind == RustVariantKind::Unit)
+            })
+            .map(|t| t.name.as_str())
+            .collect();
+        let mut terms = Terms {
+            maps: types
+                .iter()
+                .filter(|t| t.kind == RustTypeKind::Struct && t.type_params.is_empty() && t.const_params.is_empty())
+                .filter(|t| t.fields.iter().all(|field| !field.name.starts_with(|c: char| c.is_ascii_digit())))
+                .map(|t| t.name.as_str())
+                .collect(),
+            atoms,
+        };
+        loop {
+            let kept: Vec<&str> = types
+                .iter()
+                .filter(|t| terms.maps.contains(&t.name.as_str()))
+                .filter(|t| t.fields.iter().all(|field| is_zero_sized(&field.field_type) || terms.encodes(&field.field_type)))
+                .map(|t| t.name.as_str())
+                .collect();
+            if kept.len() == terms.maps.len() {
+                return terms;
+            }
+            terms.maps = kept;
+        }
+    }
+
+    /// Whether rustler encodes and decodes `ty`; 128-bit integers need its
+    /// `big_integer` feature and arrays have no impls, so neither does
+    fn encodes(&self, ty: &RustFieldType) -> bool {
+        match ty {
+            RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::Isize
+            | RustFieldType::Usize
+            | RustFieldType::F32
+            | RustFieldType::F64
+            | RustFieldType::Bool
+            | RustFieldType::String => true,
+            RustFieldType::Alias(_, inner) | RustFieldType::Option(inner) | RustFieldType::Vec(inner) => self.encodes(inner),
+            RustFieldType::Map(key, value) => self.encodes(key) && self.encodes(value),
+            RustFieldType::Tuple(members) => !members.is_empty() && members.iter().all(|member| self.encodes(member)),
+            RustFieldType::Struct(name) => self.maps.contains(&name.as_str()) || self.atoms.contains(&name.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Functions get a NIF when every value encodes; changes through `&mut`
+    /// would be made to a decoded copy, so those don't
+    fn wraps(&self, function: &RustFunction) -> bool {
+        function.params.iter().all(|(_, ty)| match ty {
+            RustFieldType::Borrowed(written, owned) => !written.starts_with("&mut") && self.encodes(owned),
+            RustFieldType::Cow(_, owned) => self.encodes(owned),
+            ty => self.encodes(ty),
+        }) && (is_unit(&function.ret) || self.encodes(&function.ret))
+    }
+}
+
+fn is_unit(ty: &RustFieldType) -> bool {
+    matches!(ty, RustFieldType::Tuple(members) if members.is_empty())
+}
+
+/// Whether `module` is written as an Elixir module (`App.Native`) rather than an Erlang one (`app_native`)
+fn is_elixir(module: &str) -> bool {
+    module.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// rustler NIF module for `types` and `functions`, loaded by the BEAM module
+/// `module`: `app_native` from Erlang, `App.Native` from Elixir
+pub fn generate_nif(types: &[RustType], functions: &[RustFunction], module: &str) -> String {
+    let terms = Terms::of(types);
+    let mut atoms = vec!["ok".to_string()];
+    let mut impls = String::new();
+    for rust_type in types {
+        if terms.maps.contains(&rust_type.name.as_str()) {
+            impls.push('\n');
+            impls.push_str(&render_map_codec(rust_type, &mut atoms));
+        } else if terms.atoms.contains(&rust_type.name.as_str()) {
+            impls.push('\n');
+            impls.push_str(&render_atom_codec(rust_type, &mut atoms));
+        }
+    }
+
+    let mut code = "use super::*;\nuse rustler::{Encoder, Env, NifResult, Term};\n".to_string();
+    for rust_type in types.iter().filter(|t| t.kind != RustTypeKind::Alias && !terms.maps.contains(&t.name.as_str()) && !terms.atoms.contains(&t.name.as_str())) {
+        code.push_str(&format!(
+            "// `{}` has no term form: generic, tuple and data-carrying types, and ones holding values rustler can't encode, are left out\n",
+            rust_type.name
+        ));
+    }
+    for function in functions.iter().filter(|f| !terms.wraps(f)) {
+        code.push_str(&format!("// `{}` is left out: it takes `&mut` or passes values rustler can't encode\n", function.name));
+    }
+    code.push_str(&format!("\nmod atoms {{\n    rustler::atoms! {{ {} }}\n}}\n", atoms.join(", ")));
+    code.push_str(&impls);
+    for function in functions.iter().filter(|f| terms.wraps(f)) {
+        code.push('\n');
+        code.push_str(&render_nif(function));
+    }
+    let name = if is_elixir(module) { format!("Elixir.{}", module) } else { module.to_string() };
+    code.push_str(&format!("\nrustler::init!(\"{}\");\n", name));
+    code
+}
+
+fn atom(name: &str, atoms: &mut Vec<String>) -> String {
+    let atom = snake_case(name);
+    if !atoms.contains(&atom) {
+        atoms.push(atom.clone());
+    }
+    format!("atoms::{}()", atom)
+}
+
+/// Encoder and decoder of a struct as a map keyed by its field names
+fn render_map_codec(rust_type: &RustType, atoms: &mut Vec<String>) -> String {
+    let name = &rust_type.name;
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    let mut fields = String::new();
+    for field in &rust_type.fields {
+        if is_zero_sized(&field.field_type) {
+            fields.push_str(&format!("            {}: Default::default(),\n", field.name));
+            continue;
+        }
+        let key = atom(&field.name, atoms);
+        keys.push(format!("{}.encode(env)", key));
+        values.push(format!("self.{}.encode(env)", field.name));
+        fields.push_str(&format!("            {}: term.map_get({})?.decode()?,\n", field.name, key));
+    }
+    format!(
+        "impl Encoder for {name} {{\n    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {{\n        \
+         let keys = [{}];\n        let values = [{}];\n        \
+         Term::map_from_arrays(env, &keys, &values).expect(\"field names are distinct\")\n    }}\n}}\n\n\
+         impl<'a> rustler::Decoder<'a> for {name} {{\n    fn decode(term: Term<'a>) -> NifResult<Self> {{\n        \
+         Ok({name} {{\n{fields}        }})\n    }}\n}}\n",
+        keys.join(", "),
+        values.join(", ")
+    )
+}
+
+/// Encoder and decoder of a fieldless enum as the atom of each variant
+fn render_atom_codec(rust_type: &RustType, atoms: &mut Vec<String>) -> String {
+    let name = &rust_type.name;
+    let mut encode = String::new();
+    let mut decode = String::new();
+    for variant in &rust_type.variants {
+        let atom = atom(&variant.name, atoms);
+        encode.push_str(&format!("            {}::{} => {}.encode(env),\n", name, variant.name, atom));
+        decode.push_str(&format!("        if atom == {} {{\n            return Ok({}::{});\n        }}\n", atom, name, variant.name));
+    }
+    format!(
+        "impl Encoder for {name} {{\n    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {{\n        match self {{\n{encode}        }}\n    }}\n}}\n\n\
+         impl<'a> rustler::Decoder<'a> for {name} {{\n    fn decode(term: Term<'a>) -> NifResult<Self> {{\n        \
+         let atom: rustler::Atom = term.decode()?;\n{decode}        Err(rustler::Error::BadArg)\n    }}\n}}\n"
+    )
+}
+
+fn render_nif(function: &RustFunction) -> String {
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for (name, ty) in &function.params {
+        let (owned, arg) = match ty {
+            RustFieldType::Borrowed(_, owned) => (owned.as_ref(), format!("&{}", name)),
+            RustFieldType::Cow(_, owned) => (owned.as_ref(), format!("{}.into()", name)),
+            ty => (ty, name.clone()),
+        };
+        params.push(format!("{}: {}", name, rust_type_name(owned)));
+        args.push(arg);
+    }
+    let call = format!("super::{}({})", function.name, args.join(", "));
+    // The BEAM convention for "done, nothing to return"
+    let (ret, body) = match is_unit(&function.ret) {
+        true => ("rustler::Atom".to_string(), format!("{};\n    atoms::ok()", call)),
+        false => (rust_type_name(&function.ret), call),
+    };
+    format!("#[rustler::nif]\nfn {}({}) -> {} {{\n    {}\n}}\n", function.name, params.join(", "), ret, body)
+}
+
+/// Erlang or Elixir module loading the NIF, by `target`, with a typespec per
+/// type and a stub per function; `None` for other targets
+pub fn generate_stub(types: &[RustType], functions: &[RustFunction], module: &str, target: &str) -> Option<String> {
+    let terms = Terms::of(types);
+    let functions: Vec<&RustFunction> = functions.iter().filter(|f| terms.wraps(f)).collect();
+    let typed: Vec<&RustType> = types
+        .iter()
+        .filter(|t| terms.maps.contains(&t.name.as_str()) || terms.atoms.contains(&t.name.as_str()))
+        .collect();
+    let field_types = |rust_type: &RustType| {
+        rust_type
+            .fields
+            .iter()
+            .filter(|field| !is_zero_sized(&field.field_type))
+            .map(|field| (field.name.clone(), spec(&field.field_type, target)))
+            .collect::<Vec<_>>()
+    };
+    let params = |function: &RustFunction| {
+        function
+            .params
+            .iter()
+            .map(|(_, ty)| match ty {
+                RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned) => spec(owned, target),
+                ty => spec(ty, target),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let ret = |function: &RustFunction| match (is_unit(&function.ret), target) {
+        (true, "elixir") => ":ok".to_string(),
+        (true, _) => "ok".to_string(),
+        (false, _) => spec(&function.ret, target),
+    };
+
+    let mut code = String::new();
+    match target {
+        "erlang" => {
+            let exports: Vec<String> = functions.iter().map(|f| format!("{}/{}", f.name, f.params.len())).collect();
+            let types_exported: Vec<String> = typed.iter().map(|t| format!("{}/0", snake_case(&t.name))).collect();
+            code.push_str(&format!("-module({}).\n-export([{}]).\n", module, exports.join(", ")));
+            if !types_exported.is_empty() {
+                code.push_str(&format!("-export_type([{}]).\n", types_exported.join(", ")));
+            }
+            code.push_str("-on_load(init/0).\n");
+            for rust_type in &typed {
+                code.push('\n');
+                code.push_str(&erlang_doc(rust_type.doc.as_deref()));
+                let body = match terms.maps.contains(&rust_type.name.as_str()) {
+                    true => {
+                        let fields: Vec<String> = field_types(rust_type).into_iter().map(|(name, ty)| format!("{} := {}", name, ty)).collect();
+                        format!("#{{{}}}", fields.join(", "))
+                    }
+                    false => rust_type.variants.iter().map(|variant| snake_case(&variant.name)).collect::<Vec<_>>().join(" | "),
+                };
+                code.push_str(&format!("-type {}() :: {}.\n", snake_case(&rust_type.name), body));
+            }
+            code.push_str(&format!(
+                "\ninit() ->\n    erlang:load_nif(filename:join(\"priv\", \"{}\"), 0).\n",
+                module
+            ));
+            for function in &functions {
+                let args: Vec<String> = function.params.iter().map(|(name, _)| format!("_{}", pascal_case(name))).collect();
+                code.push('\n');
+                code.push_str(&erlang_doc(function.doc.as_deref()));
+                code.push_str(&format!(
+                    "-spec {name}({}) -> {}.\n{name}({}) ->\n    erlang:nif_error(nif_not_loaded).\n",
+                    params(function),
+                    ret(function),
+                    args.join(", "),
+                    name = function.name
+                ));
+            }
+        }
+        "elixir" => {
+            let app = snake_case(module.split('.').next().unwrap_or(module));
+            let crate_name = snake_case(&module.replace('.', ""));
+            code.push_str(&format!("defmodule {} do\n  use Rustler, otp_app: :{}, crate: \"{}\"\n", module, app, crate_name));
+            for rust_type in &typed {
+                code.push('\n');
+                if let Some(doc) = &rust_type.doc {
+                    code.push_str(&format!("  @typedoc \"{}\"\n", doc.replace('"', "\\\"")));
+                }
+                let body = match terms.maps.contains(&rust_type.name.as_str()) {
+                    true => {
+                        let fields: Vec<String> = field_types(rust_type).into_iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+                        format!("%{{{}}}", fields.join(", "))
+                    }
+                    false => rust_type.variants.iter().map(|variant| format!(":{}", snake_case(&variant.name))).collect::<Vec<_>>().join(" | "),
+                };
+                code.push_str(&format!("  @type {} :: {}\n", snake_case(&rust_type.name), body));
+            }
+            for function in &functions {
+                let args: Vec<String> = function.params.iter().map(|(name, _)| format!("_{}", name)).collect();
+                code.push('\n');
+                if let Some(doc) = &function.doc {
+                    code.push_str(&format!("  @doc \"{}\"\n", doc.replace('"', "\\\"")));
+                }
+                code.push_str(&format!(
+                    "  @spec {name}({}) :: {}\n  def {name}({}), do: :erlang.nif_error(:nif_not_loaded)\n",
+                    params(function),
+                    ret(function),
+                    args.join(", "),
+                    name = function.name
+                ));
+            }
+            code.push_str("end\n");
+        }
+        _ => return None,
+    }
+    Some(code)
+}
+
+fn erlang_doc(doc: Option<&str>) -> String {
+    let line = |line: &str| if line.is_empty() { "%%\n".to_string() } else { format!("%% {}\n", line) };
+    doc.map(|doc| doc.lines().map(line).collect()).unwrap_or_default()
+}
+
+/// Typespec of the term rustler encodes `ty` as, in `target` syntax
+fn spec(ty: &RustFieldType, target: &str) -> String {
+    let elixir = target == "elixir";
+    match ty {
+        RustFieldType::I8 | RustFieldType::I16 | RustFieldType::I32 | RustFieldType::I64 | RustFieldType::Isize => "integer()".to_string(),
+        RustFieldType::U8 | RustFieldType::U16 | RustFieldType::U32 | RustFieldType::U64 | RustFieldType::Usize => "non_neg_integer()".to_string(),
+        RustFieldType::F32 | RustFieldType::F64 => "float()".to_string(),
+        RustFieldType::Bool => "boolean()".to_string(),
+        RustFieldType::String if elixir => "String.t()".to_string(),
+        RustFieldType::String => "binary()".to_string(),
+        RustFieldType::Alias(_, inner) => spec(inner, target),
+        RustFieldType::Option(inner) => format!("{} | nil", spec(inner, target)),
+        RustFieldType::Vec(inner) => format!("[{}]", spec(inner, target)),
+        RustFieldType::Map(key, value) if elixir => format!("%{{optional({}) => {}}}", spec(key, target), spec(value, target)),
+        RustFieldType::Map(key, value) => format!("#{{{} => {}}}", spec(key, target), spec(value, target)),
+        RustFieldType::Tuple(members) => format!("{{{}}}", members.iter().map(|member| spec(member, target)).collect::<Vec<_>>().join(", ")),
+        RustFieldType::Struct(name) => format!("{}()", snake_case(name)),
+        _ => "term()".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::{analyze_rust_functions, analyze_rust_types};
+
+    const SOURCE: &str = "pub enum Status { Active, OnHold }\n\
+        /// A signed-up user\n\
+        pub struct User { pub id: u64, pub name: String, pub status: Status, pub email: Option<String>, pub tags: [u8; 4] }\n\
+        pub struct Account { pub owner_id: u64, pub balance: f64 }\n\
+        /// Credit an account\n\
+        pub fn credit(account: &Account, amount: f64) -> Account { todo!() }\n\
+        pub fn reset(name: &str) {}";
+
+    #[test]
+    fn test_nif_encodes_fields_as_terms() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let nif = generate_nif(&types, &functions, "App.Native");
+        // Arrays have no rustler impls
+        assert!(nif.contains("// `User` has no term form"));
+        assert!(nif.contains("mod atoms {\n    rustler::atoms! { ok, active, on_hold, owner_id, balance }\n}\n"));
+        assert!(nif.contains("            Status::OnHold => atoms::on_hold().encode(env),\n"));
+        assert!(nif.contains("        if atom == atoms::active() {\n            return Ok(Status::Active);\n        }\n"));
+        assert!(nif.contains("        let keys = [atoms::owner_id().encode(env), atoms::balance().encode(env)];\n"));
+        assert!(nif.contains("            owner_id: term.map_get(atoms::owner_id())?.decode()?,\n"));
+        assert!(nif.contains("#[rustler::nif]\nfn credit(account: Account, amount: f64) -> Account {\n    super::credit(&account, amount)\n}\n"));
+        assert!(nif.contains("fn reset(name: String) -> rustler::Atom {\n    super::reset(&name);\n    atoms::ok()\n}\n"));
+        assert!(nif.ends_with("rustler::init!(\"Elixir.App.Native\");\n"));
+    }
+
+    #[test]
+    fn test_stubs_spec_the_terms() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let erlang = generate_stub(&types, &functions, "app_native", "erlang").unwrap();
+        assert!(erlang.starts_with("-module(app_native).\n-export([credit/2, reset/1]).\n-export_type([status/0, account/0]).\n-on_load(init/0).\n"));
+        assert!(erlang.contains("-type status() :: active | on_hold.\n"));
+        assert!(erlang.contains("-type account() :: #{owner_id := non_neg_integer(), balance := float()}.\n"));
+        assert!(erlang.contains("%% Credit an account\n-spec credit(account(), float()) -> account().\ncredit(_Account, _Amount) ->\n    erlang:nif_error(nif_not_loaded).\n"));
+
+        let elixir = generate_stub(&types, &functions, "App.Native", "elixir").unwrap();
+        assert!(elixir.starts_with("defmodule App.Native do\n  use Rustler, otp_app: :app, crate: \"app_native\"\n"));
+        assert!(elixir.contains("  @type account :: %{owner_id: non_neg_integer(), balance: float()}\n"));
+        assert!(elixir.contains("  @spec reset(String.t()) :: :ok\n  def reset(_name), do: :erlang.nif_error(:nif_not_loaded)\n"));
+        assert!(generate_stub(&types, &functions, "app_native", "gleam").is_none());
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// OpenAPI components generation
+// OpenAPI 3.1 schemas are JSON Schema 2020-12, so each type is the schema the
+// JSON Schema backend writes, with `null` as a second type rather than 3.0's
+// `nullable` and references under `#/components/schemas`. The fragment can be
+// merged into an existing spec, replacing only the schemas it generates
+
+use super::json_schema::schema_map;
+use crate::api::TypeDef;
+use crate::json::{self, Json};
+use crate::yaml;
+
+const POINTER: &str = "#/components/schemas/";
+
+/// `{"components": {"schemas": ...}}` for `types`, to merge into a spec
+pub fn generate_components(types: &[TypeDef]) -> String {
+    let schemas = Json::Object(schema_map(types, POINTER));
+    Json::object([("components", Json::object([("schemas", schemas)]))]).pretty()
+}
+
+/// `spec`, a JSON or YAML OpenAPI document, with the schemas of `types` added
+/// under `components.schemas`; a schema of the same name is replaced, every
+/// other key kept in place. Written as JSON, which YAML tooling reads too
+pub fn merge_components(spec: &str, types: &[TypeDef]) -> Result<String, String> {
+    let mut document = if spec.trim_start().starts_with('{') { json::parse(spec)? } else { yaml::parse(spec)? };
+    match document.get("openapi").and_then(Json::as_str) {
+        Some(version) if version.starts_with("3.1") => {}
+        Some(version) => return Err(format!("components are generated for OpenAPI 3.1, not {}", version)),
+        None => return Err("not an OpenAPI document".to_string()),
+    }
+    let Json::Object(root) = &mut document else { unreachable!("an OpenAPI document is an object") };
+    let schemas = member(member(root, "components")?, "schemas")?;
+    for (name, schema) in schema_map(types, POINTER) {
+        match schemas.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, existing)) => *existing = schema,
+            None => schemas.push((name, schema)),
+        }
+    }
+    Ok(document.pretty())
+}
+
+/// Entries of the object under `key`, added empty if missing
+fn member<'a>(entries: &'a mut Vec<(String, Json)>, key: &str) -> Result<&'a mut Vec<(String, Json)>, String> {
+    let index = match entries.iter().position(|(name, _)| name == key) {
+        Some(index) => index,
+        None => {
+            entries.push((key.to_string(), Json::Object(Vec::new())));
+            entries.len() - 1
+        }
+    };
+    match &mut entries[index].1 {
+        Json::Object(members) => Ok(members),
+        _ => Err(format!("`{}` is not an object", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::openapi_analyzer::analyze_openapi_types;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_components_reference_each_other() {
+        let types = analyze_rust("pub struct User { pub id: u64, pub home: Option<Address>, pub boss: Option<Box<User>> }\npub struct Address { pub city: String }");
+        let components = generate_components(&types);
+        assert!(components.contains(
+            "\"home\": {\n            \"anyOf\": [\n              {\n                \"$ref\": \"#/components/schemas/Address\"\n              },\n              {\n                \"type\": \"null\"\n              }\n            ]\n          },"
+        ));
+        assert!(components.contains("\"$ref\": \"#/components/schemas/User\""));
+        assert!(components.contains("\"required\": [\n          \"id\",\n          \"home\",\n          \"boss\"\n        ]"));
+    }
+
+    #[test]
+    fn test_merge_replaces_generated_schemas_only() {
+        let spec = "openapi: 3.1.0\ninfo:\n  title: Shop\n  version: 1.0.0\npaths: {}\ncomponents:\n  schemas:\n    User:\n      type: string\n    Error:\n      type: object\n";
+        let types = analyze_rust("pub struct User { pub id: u64, pub email: Option<String> }");
+        let merged = merge_components(spec, &types).unwrap();
+        let analyzed = analyze_openapi_types(&merged).unwrap();
+        let names: Vec<&str> = analyzed.iter().map(|schema| schema.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Error"]);
+        assert!(merged.contains("\"email\": {\n            \"type\": [\n              \"string\",\n              \"null\"\n            ]\n          }"));
+        assert!(merge_components("openapi: 3.0.3\ninfo: {}\n", &types).unwrap_err().contains("3.0.3"));
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Partial-update ("patch") companion types
+// Derives `UserPatch` from `User` with every field optional, plus a merge
+// function, so PATCH payloads and optimistic updates stay in step across languages
+
+use super::{camel_case, pascal_case, render_type, snake_case};
+use crate::api::TypeDef;
+use crate::ir::{ContainerType, IrField, IrType};
+
+/// Name of the patch companion of a type (`user` → `UserPatch`)
+pub fn patch_name(type_name: &str) -> String {
+    format!("{}Patch", pascal_case(type_name))
+}
+
+/// The field type inside a patch: optional fields become doubly optional so a
+/// patch can tell "leave unchanged" (absent) from "clear" (`null`)
+fn patched(ty: &IrType) -> IrType {
+    IrType::Container(ContainerType::Option(Box::new(ty.clone())))
+}
+
+fn is_optional(ty: &IrType) -> bool {
+    matches!(ty, IrType::Container(ContainerType::Option(_)))
+}
+
+/// Generate the patch type and its merge function in one target language
+///
+/// Serialize-only fields are left out: the receiving side never reads them back
+pub fn generate_patch(def: &TypeDef, target: &str) -> Option<String> {
+    let name = pascal_case(&def.name);
+    let patch = patch_name(&def.name);
+    let fields: Vec<&IrField> = def.fields.iter().filter(|field| field.direction.deserializes()).collect();
+
+    let code = match target {
+        "rust" => {
+            let mut code = format!(
+                "/// Partial update of [`{name}`]: `None` leaves a field unchanged\n\
+                 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]\n\
+                 pub struct {patch} {{\n"
+            );
+            for field in &fields {
+                // Plain `Option<Option<T>>` can't tell an absent field from `null`
+                let with = if is_optional(&field.ty) { ", with = \"::serde_with::rust::double_option\"" } else { "" };
+                code.push_str(&format!(
+                    "    #[serde(default, skip_serializing_if = \"Option::is_none\"{})]\n    pub {}: {},\n",
+                    with,
+                    snake_case(&field.name),
+                    render_type(&patched(&field.ty), target)
+                ));
+            }
+            code.push_str(&format!(
+                "}}\n\nimpl {patch} {{\n    /// Overwrite the fields of `value` that this patch sets\n    pub fn apply(self, value: &mut {name}) {{\n"
+            ));
+            for field in &fields {
+                let field = snake_case(&field.name);
+                code.push_str(&format!("        if let Some({field}) = self.{field} {{\n            value.{field} = {field};\n        }}\n"));
+            }
+            code.push_str("    }\n}\n");
+            code
+        }
+        "typescript" => {
+            let mut code = format!("/** Partial update of `{name}`: absent fields are left unchanged */\nexport interface {patch} {{\n");
+            for field in &fields {
+                code.push_str(&format!("  {}?: {};\n", field.name, render_type(&field.ty, target)));
+            }
+            code.push_str(&format!("}}\n\nexport function apply{patch}(value: {name}, patch: {patch}): {name} {{\n  return {{\n"));
+            for field in &fields {
+                code.push_str(&format!(
+                    "    {f}: patch.{f} !== undefined ? patch.{f} : value.{f},\n",
+                    f = field.name
+                ));
+            }
+            code.push_str("  };\n}\n");
+            code
+        }
+        "rescript" => {
+            let (record, patch) = (camel_case(&def.name), camel_case(&patch));
+            let mut code = format!("// Partial update of `{record}`: absent fields are left unchanged\ntype {patch} = {{\n");
+            for field in &fields {
+                code.push_str(&format!("  {}?: {},\n", field.name, render_type(&field.ty, target)));
+            }
+            code.push_str(&format!(
+                "}}\n\nlet apply{} = (value: {record}, patch: {patch}): {record} => {{\n",
+                pascal_case(&patch)
+            ));
+            for field in &fields {
+                code.push_str(&format!("  {f}: patch.{f}->Option.getOr(value.{f}),\n", f = field.name));
+            }
+            code.push_str("}\n");
+            code
+        }
+        "gleam" => {
+            let params: Vec<String> = fields
+                .iter()
+                .map(|field| format!("{}: {}", snake_case(&field.name), render_type(&patched(&field.ty), target)))
+                .collect();
+            let mut code = format!(
+                "/// Partial update of `{name}`: `None` leaves a field unchanged\n\
+                 pub type {patch} {{\n  {patch}({})\n}}\n\n\
+                 pub fn apply_{}(value: {name}, patch: {patch}) -> {name} {{\n  {name}(\n",
+                params.join(", "),
+                snake_case(&patch)
+            );
+            for field in &fields {
+                code.push_str(&format!("    {f}: option.unwrap(patch.{f}, value.{f}),\n", f = snake_case(&field.name)));
+            }
+            code.push_str("  )\n}\n");
+            code
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    fn user() -> TypeDef {
+        let registry = Registry::with_builtins();
+        let analyzer = registry.analyzer(Language::Rust).unwrap();
+        analyzer
+            .analyze("pub struct User { pub id: i64, pub display_name: String, pub email: Option<String> }")
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_rust_patch_and_apply() {
+        let rust = generate_patch(&user(), "rust").unwrap();
+        assert!(rust.contains("pub struct UserPatch {"));
+        assert!(rust.contains("    pub id: Option<i64>,\n"));
+        assert!(rust.contains(
+            "    #[serde(default, skip_serializing_if = \"Option::is_none\", with = \"::serde_with::rust::double_option\")]\n    pub email: Option<Option<String>>,"
+        ));
+        assert!(rust.contains("        if let Some(display_name) = self.display_name {\n            value.display_name = display_name;"));
+    }
+
+    #[test]
+    fn test_patch_in_every_language() {
+        let user = user();
+        let ts = generate_patch(&user, "typescript").unwrap();
+        assert!(ts.contains("  email?: string | null;\n"));
+        assert!(ts.contains("export function applyUserPatch(value: User, patch: UserPatch): User {"));
+
+        let rescript = generate_patch(&user, "rescript").unwrap();
+        assert!(rescript.contains("type userPatch = {\n  id?: int,"));
+        assert!(rescript.contains("let applyUserPatch = (value: user, patch: userPatch): user => {"));
+        assert!(rescript.contains("  email: patch.email->Option.getOr(value.email),"));
+
+        let gleam = generate_patch(&user, "gleam").unwrap();
+        assert!(gleam.contains("UserPatch(id: Option(Int), display_name: Option(String), email: Option(Option(String)))"));
+        assert!(gleam.contains("pub fn apply_user_patch(value: User, patch: UserPatch) -> User {"));
+        assert!(generate_patch(&user, "cobol").is_none());
+    }
+
+    #[test]
+    fn test_serialize_only_fields_are_not_patchable() {
+        let registry = Registry::with_builtins();
+        let source = "pub struct User { pub id: i64, #[serde(skip_deserializing)] pub created_at: String }";
+        let user = registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap().remove(0);
+        let ts = generate_patch(&user, "typescript").unwrap();
+        assert!(ts.contains("  id?: number;\n"));
+        assert!(!ts.contains("created_at"));
+    }
+}
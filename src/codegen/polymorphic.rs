@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Lowering of ReScript polymorphic variants to Rust enums
+// ReScript compiles a bare tag (`#Active`) to the string "Active" and a tag
+// with a payload to `{ NAME: "Suspended", VAL: payload }`; the enums here
+// read and write exactly those shapes
+
+use crate::api::TypeDef;
+use crate::ir::PolyBound;
+
+/// Which tags the Rust enum of a polymorphic variant accepts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PolyVariantPolicy {
+    /// A catch-all variant for open types (`[> ...]`) only
+    #[default]
+    FollowBound,
+    /// Only the listed tags; any other fails to deserialize
+    Closed,
+    /// The listed tags plus a catch-all for any other
+    CatchAll,
+}
+
+impl PolyVariantPolicy {
+    fn catch_all(self, bound: PolyBound) -> bool {
+        match self {
+            PolyVariantPolicy::FollowBound => bound == PolyBound::AtLeast,
+            PolyVariantPolicy::Closed => false,
+            PolyVariantPolicy::CatchAll => true,
+        }
+    }
+}
+
+/// Rust enum for the polymorphic variant `def`; `None` when it isn't one
+pub fn render_rust_enum(def: &TypeDef, policy: PolyVariantPolicy) -> Option<String> {
+    super::variants::render_rust_enum(def, policy.catch_all(def.polymorphic?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    fn analyze(source: &str) -> TypeDef {
+        Registry::with_builtins().analyzer(Language::ReScript).unwrap().analyze(source).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_bare_tags_follow_the_bound() {
+        let open = analyze("type theme = [> #light | #\"high-contrast\"]");
+        let rust = render_rust_enum(&open, PolyVariantPolicy::default()).unwrap();
+        assert_eq!(
+            rust,
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub enum Theme {\n    \
+             #[serde(rename = \"light\")]\n    Light,\n    #[serde(rename = \"high-contrast\")]\n    HighContrast,\n    \
+             /// A tag this binding doesn't list\n    #[serde(untagged)]\n    Other(String),\n}\n"
+        );
+        assert!(!render_rust_enum(&open, PolyVariantPolicy::Closed).unwrap().contains("Other"));
+        let exact = analyze("type theme = [#Light | #Dark]");
+        assert!(!render_rust_enum(&exact, PolyVariantPolicy::default()).unwrap().contains("Other"));
+    }
+
+    #[test]
+    fn test_mixed_tags_try_both_shapes() {
+        let status = analyze("type status = [#Active | #Suspended(string) | #Moved(int, string)]");
+        let rust = render_rust_enum(&status, PolyVariantPolicy::Closed).unwrap();
+        assert!(rust.contains("#[serde(untagged)]\npub enum Status {\n    Tag(StatusTag),\n    Payload(StatusPayload),\n}\n"));
+        assert!(rust.contains("pub enum StatusTag {\n    Active,\n}\n"));
+        assert!(rust.contains(
+            "#[serde(tag = \"NAME\", content = \"VAL\")]\npub enum StatusPayload {\n    Suspended(String),\n    Moved(i64, String),\n}\n"
+        ));
+    }
+}
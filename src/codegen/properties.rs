@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Round-trip property tests for analyzed type pairs
+// Where a mapping claims every field exact, the generated tests try to prove
+// it wrong: proptest values go out through serde_json, are read back as the
+// peer language types them and echoed home, and fast-check values go through
+// the ReScript codecs; anything that doesn't come back unchanged fails the claim
+
+use super::recursion::is_recursive;
+use super::rescript_codecs::{fits_int, int_range};
+use super::{camel_case, pascal_case, render_type, snake_case};
+use crate::api::{Language, TypeDef};
+use crate::ir::{self, normalize_name, ContainerType, Fidelity, IrType, PrimitiveType, VariantTagging};
+
+/// Longest tuple proptest implements `Strategy` for
+const MAX_TUPLE: usize = 12;
+
+fn find<'a>(types: &'a [TypeDef], name: &str) -> Option<&'a TypeDef> {
+    types.iter().find(|def| normalize_name(&def.name) == normalize_name(name))
+}
+
+/// Why `def` gets no round-trip test against `peer`, if it doesn't; a field
+/// claimed lossy has nothing exact to assert
+fn untested(def: &TypeDef, types: &[TypeDef], peer: &str, seen: &mut Vec<String>) -> Option<String> {
+    if seen.contains(&def.name) {
+        return None;
+    }
+    seen.push(def.name.clone());
+    for field in def.fields.iter().chain(def.variants.iter().flat_map(|variant| &variant.fields)) {
+        if field.direction != ir::Direction::Both {
+            return Some(format!("`{}` crosses one way only", field.name));
+        }
+        let fidelity = ir::fidelity(&field.ty, peer);
+        if fidelity != Fidelity::Exact {
+            return Some(format!("`{}` is only claimed {:?} in {}", field.name, fidelity, peer));
+        }
+        for name in references(&field.ty) {
+            if let Some(reason) = find(types, name).and_then(|inner| untested(inner, types, peer, seen)) {
+                return Some(reason);
+            }
+        }
+    }
+    None
+}
+
+fn references(ty: &IrType) -> Vec<&str> {
+    match ty {
+        IrType::Reference(name) => vec![name],
+        IrType::Alias(_, inner) | IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _)) => {
+            references(inner)
+        }
+        IrType::Container(ContainerType::Map(key, value) | ContainerType::Result(key, value)) => {
+            references(key).into_iter().chain(references(value)).collect()
+        }
+        IrType::Tuple(members) => members.iter().flat_map(references).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Proptest tests for the Rust `types` against `peers`, the same types as
+/// analyzed from `peer`; expects the Rust definitions in scope, deriving
+/// `Debug`, `PartialEq` and serde's traits, with maps as `HashMap`s
+///
+/// A `peer` module mirrors each peer type with serde derives, holding
+/// values the way `peer` does (ReScript's `int` is 32-bit whatever width
+/// its analyzer records), so a wrong exactness claim fails with a value
+/// the peer can't read or gives back changed.
+pub fn generate_proptests(types: &[TypeDef], peers: &[TypeDef], peer: Language) -> String {
+    let mut strategies = String::new();
+    let mut tests = Vec::new();
+    let mut mirrored: Vec<&TypeDef> = Vec::new();
+    for def in types {
+        let Some(strategy) = arbitrary(def, types) else {
+            strategies.push_str(&format!("\n// `{}` is left out: no strategy covers its fields\n", def.name));
+            continue;
+        };
+        strategies.push('\n');
+        strategies.push_str(&strategy);
+        let Some(paired) = find(peers, &def.name) else {
+            strategies.push_str(&format!("// `{}` is not tested: {} has no type of that name\n", def.name, peer));
+            continue;
+        };
+        if let Some(reason) = untested(def, types, peer.name(), &mut Vec::new()) {
+            strategies.push_str(&format!("// `{}` is not tested: {}\n", def.name, reason));
+            continue;
+        }
+        let Some(reached) = mirror_set(paired, peers, peer) else {
+            strategies.push_str(&format!("// `{}` is not tested: {}'s `{}` has no serde mirror\n", def.name, peer, paired.name));
+            continue;
+        };
+        for def in reached {
+            if !mirrored.iter().any(|seen| seen.name == def.name) {
+                mirrored.push(def);
+            }
+        }
+        tests.push(render_test(def, paired, peer));
+    }
+
+    let mut code = format!(
+        "// Round-trip properties against {peer}: every value goes out through serde_json,\n\
+         // is read back as {peer} types it, echoed home, and must come back unchanged\n\n\
+         use proptest::prelude::*;\n"
+    );
+    if !mirrored.is_empty() {
+        let mirrors: Vec<String> = mirrored.iter().map(|def| render_mirror(def, peers, peer)).collect();
+        code.push_str(&format!("\n/// The types as {} declares them\nmod peer {{\n{}}}\n", peer, mirrors.join("\n")));
+    }
+    code.push_str(&strategies);
+    if !tests.is_empty() {
+        code.push_str(&format!("\nproptest! {{\n{}}}\n", tests.join("\n")));
+    }
+    code
+}
+
+fn render_test(def: &TypeDef, paired: &TypeDef, peer: Language) -> String {
+    let name = pascal_case(&def.name);
+    format!(
+        "    #[test]\n    \
+         fn {snake}_round_trips_through_{peer}(value in arb_{snake}()) {{\n        \
+         let wire = serde_json::to_string(&value).unwrap();\n        \
+         let read: peer::{mirror} = serde_json::from_str(&wire)\n            \
+         .map_err(|err| TestCaseError::fail(format!(\"{peer} can't read {{}}: {{}}\", wire, err)))?;\n        \
+         let echoed = serde_json::to_string(&read).unwrap();\n        \
+         let back: {name} = serde_json::from_str(&echoed)\n            \
+         .map_err(|err| TestCaseError::fail(format!(\"{{}} came back as {{}}: {{}}\", wire, echoed, err)))?;\n        \
+         prop_assert_eq!(back, value);\n    }}\n",
+        snake = snake_case(&def.name),
+        peer = peer.name(),
+        mirror = pascal_case(&paired.name),
+    )
+}
+
+/// `arb_x()` for a struct or fieldless enum whose fields all have strategies
+fn arbitrary(def: &TypeDef, types: &[TypeDef]) -> Option<String> {
+    if !def.type_params.is_empty() || def.aliased.is_some() || !strategy_for(def, types) {
+        return None;
+    }
+    let name = pascal_case(&def.name);
+    let body = if !def.variants.is_empty() {
+        // Fieldless, so the variants can be built from an index without `Clone`
+        let last = def.variants.len() - 1;
+        let arms: String = def
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, variant)| {
+                let pattern = if i == last { "_".to_string() } else { i.to_string() };
+                format!("        {} => {}::{},\n", pattern, name, pascal_case(&variant.name))
+            })
+            .collect();
+        format!("(0..{}usize).prop_map(|i| match i {{\n{}    }})", def.variants.len(), arms)
+    } else {
+        let idents: Vec<String> = def.fields.iter().map(|field| snake_case(&field.name)).collect();
+        let strategies: Vec<String> = def.fields.iter().map(|field| strategy(&field.ty, types)).collect::<Option<_>>()?;
+        let value = format!("{} {{ {} }}", name, idents.join(", "));
+        match strategies.len() {
+            0 => format!("Just(()).prop_map(|_| {})", value),
+            1 => format!("{}.prop_map(|{}| {})", strategies[0], idents[0], value),
+            n if n <= MAX_TUPLE => format!("({}).prop_map(|({})| {})", strategies.join(", "), idents.join(", "), value),
+            // Nest the tuples proptest stops at
+            _ => {
+                let chunks = |items: &[String]| -> Vec<String> { items.chunks(MAX_TUPLE).map(|chunk| format!("({})", chunk.join(", "))).collect() };
+                format!("({}).prop_map(|({})| {})", chunks(&strategies).join(", "), chunks(&idents).join(", "), value)
+            }
+        }
+    };
+    Some(format!("fn arb_{}() -> impl Strategy<Value = {}> {{\n    {}\n}}\n", snake_case(&def.name), name, body))
+}
+
+/// Whether `def` gets a strategy: not recursive, a fieldless enum or a
+/// struct, and every type it holds has one
+fn strategy_for(def: &TypeDef, types: &[TypeDef]) -> bool {
+    !is_recursive(def, types)
+        && def.variants.iter().all(|variant| variant.fields.is_empty())
+        && def.fields.iter().all(|field| strategy(&field.ty, types).is_some())
+}
+
+/// Proptest strategy for a value of `ty`; floats stay finite, as JSON has no NaN
+fn strategy(ty: &IrType, types: &[TypeDef]) -> Option<String> {
+    Some(match ty {
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64) => {
+            format!("any::<{}>().prop_filter(\"JSON numbers are finite\", |x| x.is_finite())", render_type(ty, "rust"))
+        }
+        IrType::Primitive(PrimitiveType::Bool | PrimitiveType::String) => format!("any::<{}>()", render_type(ty, "rust")),
+        IrType::Primitive(primitive) if primitive.is_integer() => format!("any::<{}>()", render_type(ty, "rust")),
+        IrType::Primitive(PrimitiveType::Unit) => "Just(())".to_string(),
+        IrType::Container(ContainerType::Vec(inner)) => format!("proptest::collection::vec({}, 0..4)", strategy(inner, types)?),
+        IrType::Container(ContainerType::Array(inner, len)) => format!(
+            "proptest::collection::vec({}, {len}).prop_map(|items| <[_; {len}]>::try_from(items).unwrap())",
+            strategy(inner, types)?
+        ),
+        IrType::Container(ContainerType::Option(inner)) => format!("proptest::option::of({})", strategy(inner, types)?),
+        IrType::Container(ContainerType::Map(key, value)) => {
+            format!("proptest::collection::hash_map({}, {}, 0..4)", strategy(key, types)?, strategy(value, types)?)
+        }
+        IrType::Container(ContainerType::Result(ok, err)) => {
+            format!("prop_oneof![{}.prop_map(Ok), {}.prop_map(Err)]", strategy(ok, types)?, strategy(err, types)?)
+        }
+        IrType::Tuple(members) if !members.is_empty() && members.len() <= MAX_TUPLE => {
+            let members: Vec<String> = members.iter().map(|member| strategy(member, types)).collect::<Option<_>>()?;
+            format!("({},)", members.join(", "))
+        }
+        IrType::Reference(name) | IrType::Alias(name, _) if find(types, name).is_some() => {
+            let def = find(types, name)?;
+            if !def.type_params.is_empty() || def.aliased.is_some() || !strategy_for(def, types) {
+                return None;
+            }
+            format!("arb_{}()", snake_case(&def.name))
+        }
+        IrType::Alias(_, inner) => strategy(inner, types)?,
+        _ => return None,
+    })
+}
+
+/// `paired` and every peer type it reaches; `None` when one can't be mirrored
+fn mirror_set<'a>(paired: &'a TypeDef, peers: &'a [TypeDef], peer: Language) -> Option<Vec<&'a TypeDef>> {
+    let mut reached = vec![paired];
+    let mut i = 0;
+    while let Some(def) = reached.get(i) {
+        let fields = || def.fields.iter().chain(def.variants.iter().flat_map(|variant| &variant.fields));
+        if !def.type_params.is_empty() || def.aliased.is_some() || def.variants.iter().any(|variant| !variant.fields.is_empty()) {
+            return None;
+        }
+        let held: Vec<&str> = fields().flat_map(|field| references(&field.ty)).collect();
+        if fields().any(|field| mirror_type(&field.ty, peers, peer).is_none()) {
+            return None;
+        }
+        for name in held {
+            let next = find(peers, name)?;
+            if !reached.iter().any(|seen| seen.name == next.name) {
+                reached.push(next);
+            }
+        }
+        i += 1;
+    }
+    Some(reached)
+}
+
+/// Rust type holding a value of `ty` the way `peer` does; dates and UUIDs
+/// stay the text the peer passes along
+fn mirror_type(ty: &IrType, peers: &[TypeDef], peer: Language) -> Option<String> {
+    let render = |inner: &IrType| mirror_type(inner, peers, peer);
+    Some(match ty {
+        IrType::Primitive(primitive) if primitive.is_integer() && peer == Language::ReScript => "i32".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime | PrimitiveType::Uuid) => "String".to_string(),
+        IrType::Primitive(PrimitiveType::Decimal | PrimitiveType::BigInt | PrimitiveType::Bytes) => return None,
+        IrType::Primitive(_) => render_type(ty, "rust"),
+        IrType::Container(ContainerType::Vec(inner)) => format!("Vec<{}>", render(inner)?),
+        IrType::Container(ContainerType::Array(inner, len)) => format!("[{}; {}]", render(inner)?, len),
+        IrType::Container(ContainerType::Option(inner)) => format!("Option<{}>", render(inner)?),
+        IrType::Container(ContainerType::Result(ok, err)) => format!("Result<{}, {}>", render(ok)?, render(err)?),
+        // Keys are JSON object keys, text whatever the declared key type
+        IrType::Container(ContainerType::Map(_, value)) => format!("std::collections::BTreeMap<String, {}>", render(value)?),
+        IrType::Tuple(members) => format!("({})", members.iter().map(render).collect::<Option<Vec<_>>>()?.join(", ")),
+        IrType::Reference(name) | IrType::Alias(name, _) if find(peers, name).is_some() => pascal_case(&find(peers, name)?.name),
+        IrType::Alias(_, inner) => render(inner)?,
+        _ => return None,
+    })
+}
+
+fn render_mirror(def: &TypeDef, peers: &[TypeDef], peer: Language) -> String {
+    let name = pascal_case(&def.name);
+    let rename = |ident: &str, key: &str, indent: &str| match ident == key {
+        true => String::new(),
+        false => format!("{}#[serde(rename = \"{}\")]\n", indent, key),
+    };
+    if !def.variants.is_empty() {
+        // Unit variants the peer compiles to bare strings need no tag
+        let attribute = match &def.tagging {
+            _ if def.bare_units => String::new(),
+            Some(VariantTagging::Internal(tag)) => format!("    #[serde(tag = \"{}\")]\n", tag),
+            Some(VariantTagging::Adjacent(tag, content)) => format!("    #[serde(tag = \"{}\", content = \"{}\")]\n", tag, content),
+            Some(VariantTagging::Untagged) => "    #[serde(untagged)]\n".to_string(),
+            _ => String::new(),
+        };
+        let variants: String = def
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = pascal_case(&variant.name);
+                format!("{}        {},\n", rename(&ident, variant.tag.as_deref().unwrap_or(&variant.name), "        "), ident)
+            })
+            .collect();
+        return format!("    #[derive(serde::Serialize, serde::Deserialize)]\n{attribute}    pub enum {name} {{\n{variants}    }}\n");
+    }
+    let fields: String = def
+        .fields
+        .iter()
+        .map(|field| {
+            let ident = snake_case(&field.name);
+            let ty = mirror_type(&field.ty, peers, peer).unwrap_or_default();
+            format!("{}        pub {}: {},\n", rename(&ident, &field.name, "        "), ident, ty)
+        })
+        .collect();
+    format!("    #[derive(serde::Serialize, serde::Deserialize)]\n    pub struct {name} {{\n{fields}    }}\n")
+}
+
+/// fast-check bindings the ReScript properties use
+const FAST_CHECK: &str = r#"type arbitrary<'a>
+type property
+@module("fast-check") external boolean: unit => arbitrary<bool> = "boolean"
+@module("fast-check") external integer: {"min": int, "max": int} => arbitrary<int> = "integer"
+@module("fast-check") external safeInteger: {"min": float, "max": float} => arbitrary<float> = "integer"
+@module("fast-check") external float32: {"noNaN": bool, "noDefaultInfinity": bool} => arbitrary<float> = "float"
+@module("fast-check") external double: {"noNaN": bool, "noDefaultInfinity": bool} => arbitrary<float> = "double"
+@module("fast-check") external string: unit => arbitrary<string> = "string"
+@module("fast-check") external uuid: unit => arbitrary<string> = "uuid"
+@module("fast-check") external date: {"noInvalidDate": bool} => arbitrary<Date.t> = "date"
+@module("fast-check") external nullable: arbitrary<'a> => arbitrary<Js.Nullable.t<'a>> = "option"
+@module("fast-check") external array: (arbitrary<'a>, {"minLength": int, "maxLength": int}) => arbitrary<array<'a>> = "array"
+@module("fast-check") external dictionary: (arbitrary<string>, arbitrary<'a>) => arbitrary<Js.Dict.t<'a>> = "dictionary"
+@module("fast-check") external constant: 'a => arbitrary<'a> = "constant"
+@send external map: (arbitrary<'a>, 'a => 'b) => arbitrary<'b> = "map"
+@send external chain: (arbitrary<'a>, 'a => arbitrary<'b>) => arbitrary<'b> = "chain"
+@module("fast-check") external property: (arbitrary<'a>, 'a => bool) => property = "property"
+@module("fast-check") external assertProperty: property => unit = "assert"
+"#;
+
+/// fast-check properties for the codec module `codecs` that
+/// [`super::rescript_codecs::render_codecs`] generates from the Rust `types`,
+/// one per type `peers` (the ReScript side's types) has a counterpart for;
+/// `None` when there are no codecs
+///
+/// Each property encodes arbitrary records within the Rust type's ranges,
+/// decodes the text, and requires the same text from re-encoding it, the
+/// byte-for-byte notion the test vectors use: ReScript holds an `f32` as the
+/// double its shortest digits parse to, not the exact single.
+pub fn generate_fast_check(types: &[TypeDef], peers: &[TypeDef], codecs: &str) -> Option<String> {
+    super::rescript_codecs::render_codecs(types)?;
+    let mut arbitraries = Vec::new();
+    let mut notes = String::new();
+    let mut properties = String::new();
+    let mut runs = String::new();
+    for def in types {
+        if is_recursive(def, types) {
+            notes.push_str(&format!("// `{}` is left out: it is recursive\n", def.name));
+            continue;
+        }
+        let record = camel_case(&def.name);
+        let labels: Vec<String> = def.fields.iter().map(|field| camel_case(&field.name)).collect();
+        let value = format!("({{{}}}: {})", labels.join(", "), record);
+        let mut body = match labels.last() {
+            Some(last) => format!("->map({} => {})", last, value),
+            None => format!("constant({})", value),
+        };
+        for (i, field) in def.fields.iter().enumerate().rev() {
+            let arbitrary = fast_check_arbitrary(&field.ty);
+            body = match i + 1 == def.fields.len() {
+                true => format!("{}{}", arbitrary, body),
+                false => format!("{}->chain({} => {})", arbitrary, labels[i], body),
+            };
+        }
+        arbitraries.push(format!("arb{} = () => {}\n", pascal_case(&def.name), body));
+
+        let reason = match find(peers, &def.name) {
+            Some(_) => untested(def, types, "rescript", &mut Vec::new()),
+            None => Some("the ReScript side has no type of that name".to_string()),
+        };
+        if let Some(reason) = reason {
+            notes.push_str(&format!("// `{}` is not tested: {}\n", def.name, reason));
+            continue;
+        }
+        let name = pascal_case(&def.name);
+        properties.push_str(&format!(
+            "\n/** `{record}` values decode from their serde_json text and encode back to it */\n\
+             let {record}RoundTrips = () =>\n  \
+             assertProperty(property(arb{name}(), value => decode{name}(encode{name}(value))->Result.map(encode{name}) == Ok(encode{name}(value))))\n"
+        ));
+        runs.push_str(&format!("  {}RoundTrips()\n", record));
+    }
+
+    let mut code = format!("// Round-trip properties for the codecs in {codecs}\n\nopen {codecs}\n\n{FAST_CHECK}");
+    if !arbitraries.is_empty() {
+        code.push_str(&format!("\nlet rec {}", arbitraries.join("and ")));
+    }
+    if !notes.is_empty() {
+        code.push('\n');
+        code.push_str(&notes);
+    }
+    code.push_str(&properties);
+    if !runs.is_empty() {
+        code.push_str(&format!("\nlet roundTripAll = () => {{\n{}}}\n", runs));
+    }
+    Some(code)
+}
+
+/// fast-check arbitrary for a codable `ty`, integers within what `decodeInt` accepts
+fn fast_check_arbitrary(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "boolean()".to_string(),
+        IrType::Primitive(PrimitiveType::F32) => "float32({\"noNaN\": true, \"noDefaultInfinity\": true})".to_string(),
+        IrType::Primitive(PrimitiveType::F64) => "double({\"noNaN\": true, \"noDefaultInfinity\": true})".to_string(),
+        IrType::Primitive(primitive) if fits_int(*primitive) => {
+            let (min, max) = int_range(*primitive);
+            format!("integer({{\"min\": {}, \"max\": {}}})", min, max)
+        }
+        IrType::Primitive(primitive) if primitive.is_integer() => {
+            let (min, max) = int_range(*primitive);
+            format!("safeInteger({{\"min\": {}., \"max\": {}.}})", min, max)
+        }
+        IrType::Primitive(PrimitiveType::Uuid) => "uuid()".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => "date({\"noInvalidDate\": true})->map(Date.toISOString)".to_string(),
+        IrType::Primitive(_) => "string()".to_string(),
+        IrType::Container(ContainerType::Option(inner)) => format!("nullable({})", fast_check_arbitrary(inner)),
+        IrType::Container(ContainerType::Vec(inner)) => {
+            format!("array({}, {{\"minLength\": 0, \"maxLength\": 4}})", fast_check_arbitrary(inner))
+        }
+        IrType::Container(ContainerType::Array(inner, len)) => {
+            format!("array({}, {{\"minLength\": {len}, \"maxLength\": {len}}})", fast_check_arbitrary(inner))
+        }
+        IrType::Container(ContainerType::Map(_, inner)) => format!("dictionary(string(), {})", fast_check_arbitrary(inner)),
+        IrType::Reference(name) => format!("arb{}()", pascal_case(name)),
+        IrType::Alias(_, inner) => fast_check_arbitrary(inner),
+        _ => unreachable!("only codable types have codecs"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Registry;
+
+    fn analyze(language: Language, source: &str) -> Vec<TypeDef> {
+        Registry::with_builtins().analyzer(language).unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_proptests_read_values_back_as_the_peer_types_them() {
+        let rust = analyze(
+            Language::Rust,
+            "pub struct User { pub id: i32, pub display_name: String, pub tags: Vec<String>, pub home: Option<Address> }\n\
+             pub struct Address { pub city: String }\npub struct Reading { pub value: u64 }\npub struct Solo { pub id: i32 }",
+        );
+        let rescript = analyze(
+            Language::ReScript,
+            "type address = { city: string }\ntype user = { id: int, @as(\"display_name\") displayName: string, tags: array<string>, home: option<address> }\n\
+             type reading = { value: int }",
+        );
+        let code = generate_proptests(&rust, &rescript, Language::ReScript);
+        assert!(code.contains(
+            "fn arb_user() -> impl Strategy<Value = User> {\n    (any::<i32>(), any::<String>(), proptest::collection::vec(any::<String>(), 0..4), \
+             proptest::option::of(arb_address())).prop_map(|(id, display_name, tags, home)| User { id, display_name, tags, home })\n}\n"
+        ));
+        assert!(code.contains("    #[derive(serde::Serialize, serde::Deserialize)]\n    pub struct User {\n        pub id: i32,\n"));
+        assert!(code.contains("        pub home: Option<Address>,\n"));
+        assert!(code.contains("    fn user_round_trips_through_rescript(value in arb_user()) {\n"));
+        assert!(code.contains("        let read: peer::User = serde_json::from_str(&wire)\n"));
+        assert!(code.contains("// `Reading` is not tested: `value` is only claimed Lossy in rescript\n"));
+        assert!(code.contains("// `Solo` is not tested: rescript has no type of that name\n"));
+    }
+
+    #[test]
+    fn test_fast_check_properties_cover_the_codecs() {
+        let rust = analyze(Language::Rust, "pub struct User { pub id: u8, pub score: f32, pub email: Option<String> }\npub struct Team { pub lead: User }");
+        let rescript = analyze(Language::ReScript, "type user = { id: int, score: float, email: option<string> }");
+        let code = generate_fast_check(&rust, &rescript, "Codecs").unwrap();
+        assert!(code.contains("open Codecs\n"));
+        assert!(code.contains(
+            "let rec arbUser = () => integer({\"min\": 0, \"max\": 255})->chain(id => float32({\"noNaN\": true, \"noDefaultInfinity\": true})\
+             ->chain(score => nullable(string())->map(email => ({id, score, email}: user))))\nand arbTeam = () => arbUser()->map(lead => ({lead}: team))\n"
+        ));
+        assert!(code.contains("// `Team` is not tested: the ReScript side has no type of that name\n"));
+        assert!(code.contains("decodeUser(encodeUser(value))->Result.map(encodeUser) == Ok(encodeUser(value))"));
+        assert!(code.contains("let roundTripAll = () => {\n  userRoundTrips()\n}\n"));
+        assert!(generate_fast_check(&analyze(Language::Rust, "pub struct Blob { pub f: Box<dyn Fn()> }"), &[], "Codecs").is_none());
+    }
+}
@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Protobuf schema generation
+// Emits a proto3 `.proto` for analyzed types of any language. Field numbers are
+// what the wire keeps, so they come from a lockfile: a number, once handed out,
+// stays with its field, new fields take the next free one, and the numbers of
+// removed fields are reserved so regenerating never reuses them
+
+use super::{camel_case, pascal_case, snake_case};
+use crate::api::TypeDef;
+use crate::ir::{normalize_name, ContainerType, IrField, IrType, PrimitiveType};
+use crate::json::{self, Json};
+
+/// Lockfile format version, bumped on incompatible changes
+pub const PROTO_LOCK_VERSION: u32 = 1;
+
+/// Numbers protobuf keeps for its own use
+const RESERVED_RANGE: std::ops::RangeInclusive<u32> = 19_000..=19_999;
+
+/// Numbers handed out within one message or enum
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LockedNumbers {
+    /// `Message`, or `Message.Nested` for a variant's payload
+    pub name: String,
+    /// Field (or enum value) name and its number, in the order they were assigned
+    pub numbers: Vec<(String, u32)>,
+    /// Fields that were removed, whose numbers must not come back; a name
+    /// that comes back takes a new number and is no longer reserved
+    pub reserved: Vec<(String, u32)>,
+}
+
+impl LockedNumbers {
+    /// Number of `field`, assigning the next free one if it has none
+    fn number(&mut self, field: &str) -> u32 {
+        if let Some((_, number)) = self.numbers.iter().find(|(name, _)| name == field) {
+            return *number;
+        }
+        let used = self.numbers.iter().chain(&self.reserved).map(|(_, number)| *number);
+        let mut next = used.max().unwrap_or(0) + 1;
+        if RESERVED_RANGE.contains(&next) {
+            next = RESERVED_RANGE.end() + 1;
+        }
+        self.numbers.push((field.to_string(), next));
+        next
+    }
+
+    /// Reserve the numbers of fields no longer in `present`
+    fn retire(&mut self, present: &[&str]) {
+        let (kept, removed): (Vec<_>, Vec<_>) = self.numbers.drain(..).partition(|(name, _)| present.contains(&name.as_str()));
+        self.numbers = kept;
+        self.reserved.extend(removed);
+    }
+}
+
+/// Field numbering of every message and enum generated so far
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProtoLock {
+    pub messages: Vec<LockedNumbers>,
+}
+
+impl ProtoLock {
+    fn entry(&mut self, name: &str) -> &mut LockedNumbers {
+        let index = match self.messages.iter().position(|message| message.name == name) {
+            Some(index) => index,
+            None => {
+                self.messages.push(LockedNumbers { name: name.to_string(), ..LockedNumbers::default() });
+                self.messages.len() - 1
+            }
+        };
+        &mut self.messages[index]
+    }
+
+    pub fn to_json(&self) -> String {
+        let pairs = |pairs: &[(String, u32)]| {
+            Json::Array(
+                pairs
+                    .iter()
+                    .map(|(name, number)| Json::object([("name", name.as_str().into()), ("number", (*number as usize).into())]))
+                    .collect(),
+            )
+        };
+        let messages = self.messages.iter().map(|message| {
+            Json::object([
+                ("name", message.name.as_str().into()),
+                ("numbers", pairs(&message.numbers)),
+                ("reserved", pairs(&message.reserved)),
+            ])
+        });
+        Json::object([("version", (PROTO_LOCK_VERSION as usize).into()), ("messages", Json::Array(messages.collect()))]).to_string()
+    }
+
+    pub fn from_json(source: &str) -> Result<ProtoLock, String> {
+        let value = json::parse(source)?;
+        let version = value.get("version").and_then(Json::as_f64).ok_or("missing `version`")?;
+        if version as u32 != PROTO_LOCK_VERSION {
+            return Err(format!("unsupported lockfile version {}", version));
+        }
+        let pairs = |message: &Json, key: &str| -> Result<Vec<(String, u32)>, String> {
+            message
+                .get(key)
+                .and_then(Json::as_array)
+                .unwrap_or_default()
+                .iter()
+                .map(|pair| match (pair.get("name").and_then(Json::as_str), pair.get("number").and_then(Json::as_f64)) {
+                    (Some(name), Some(number)) => Ok((name.to_string(), number as u32)),
+                    _ => Err(format!("`{}` entries need a `name` and a `number`", key)),
+                })
+                .collect()
+        };
+        let mut messages = Vec::new();
+        for message in value.get("messages").and_then(Json::as_array).unwrap_or_default() {
+            messages.push(LockedNumbers {
+                name: message.get("name").and_then(Json::as_str).ok_or("missing `name`")?.to_string(),
+                numbers: pairs(message, "numbers")?,
+                reserved: pairs(message, "reserved")?,
+            });
+        }
+        Ok(ProtoLock { messages })
+    }
+}
+
+/// proto3 schema for `types` in `package`, numbering fields from `lock` and
+/// recording the numbers it hands out there; a type or field with no proto
+/// form is left out with a comment, and takes no number
+pub fn generate_proto(types: &[TypeDef], package: &str, lock: &mut ProtoLock) -> String {
+    let mut body = String::new();
+    for def in types {
+        body.push('\n');
+        if let Some(reason) = unsupported(def) {
+            body.push_str(&format!("// `{}` is left out: {}\n", def.name, reason));
+            continue;
+        }
+        body.push_str(&comment(def.doc.as_deref(), ""));
+        let all_units = def.variants.iter().all(|variant| variant.fields.is_empty());
+        match def.variants.is_empty() {
+            true => {
+                let name = pascal_case(&def.name);
+                body.push_str(&render_message(&name, &name, &def.fields, types, lock, ""));
+            }
+            false if all_units => body.push_str(&render_enum(def, lock)),
+            false => body.push_str(&render_oneof(def, types, lock)),
+        }
+    }
+    let mut code = format!("// Field numbers are pinned in the lockfile; edit it, not this file\nsyntax = \"proto3\";\n\npackage {};\n", package);
+    let imports: Vec<&str> = [
+        ("google.protobuf.Empty", "google/protobuf/empty.proto"),
+        ("google.protobuf.Timestamp", "google/protobuf/timestamp.proto"),
+        ("google.protobuf.Value", "google/protobuf/struct.proto"),
+    ]
+    .into_iter()
+    .filter(|(used, _)| body.contains(used))
+    .map(|(_, import)| import)
+    .collect();
+    if !imports.is_empty() {
+        code.push('\n');
+        imports.iter().for_each(|import| code.push_str(&format!("import \"{}\";\n", import)));
+    }
+    code + &body
+}
+
+/// Why `def` has no proto form, if it hasn't
+fn unsupported(def: &TypeDef) -> Option<&'static str> {
+    if !def.type_params.is_empty() {
+        Some("protobuf has no generic messages")
+    } else if def.aliased.is_some() {
+        Some("it is an alias; fields holding it use the type it names")
+    } else {
+        None
+    }
+}
+
+fn comment(doc: Option<&str>, indent: &str) -> String {
+    doc.map(|doc| doc.lines().map(|line| format!("{}// {}\n", indent, line.trim()).replace("// \n", "//\n")).collect())
+        .unwrap_or_default()
+}
+
+/// Message `name` with its numbers locked under `key`
+fn render_message(name: &str, key: &str, fields: &[IrField], types: &[TypeDef], lock: &mut ProtoLock, indent: &str) -> String {
+    let locked = lock.entry(key);
+    let mut members = String::new();
+    let mut present = Vec::new();
+    for field in fields {
+        let Some((label, ty)) = field_type(&field.ty, types) else {
+            members.push_str(&format!("{}  // `{}` is left out: its type has no proto form\n", indent, field.name));
+            continue;
+        };
+        present.push(field.name.as_str());
+        let number = locked.number(&field.name);
+        let proto_name = snake_case(&field.name);
+        // proto3 JSON writes lowerCamelCase unless told the source's key
+        let json_name = match camel_case(&proto_name) == field.name {
+            true => String::new(),
+            false => format!(" [json_name = \"{}\"]", field.name),
+        };
+        members.push_str(&comment(field.doc.as_deref(), &format!("{}  ", indent)));
+        members.push_str(&format!("{}  {}{} {} = {}{};\n", indent, label, ty, proto_name, number, json_name));
+    }
+    locked.retire(&present);
+    let reserved = reserved(locked, &format!("{}  ", indent), snake_case);
+    format!("{indent}message {name} {{\n{reserved}{members}{indent}}}\n")
+}
+
+/// `reserved` statements for retired numbers and names, the names as `rename` writes them
+fn reserved(locked: &LockedNumbers, indent: &str, rename: impl Fn(&str) -> String) -> String {
+    if locked.reserved.is_empty() {
+        return String::new();
+    }
+    let numbers: Vec<String> = locked.reserved.iter().map(|(_, number)| number.to_string()).collect();
+    let mut names: Vec<String> = Vec::new();
+    // A name in use again can't be reserved, and one removed twice is reserved once
+    for (name, _) in locked.reserved.iter().filter(|(name, _)| !locked.numbers.iter().any(|(current, _)| current == name)) {
+        let name = format!("\"{}\"", rename(name));
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    let mut code = format!("{indent}reserved {};\n", numbers.join(", "));
+    if !names.is_empty() {
+        code.push_str(&format!("{indent}reserved {};\n", names.join(", ")));
+    }
+    code
+}
+
+/// Fieldless enum; proto3 wants a zero value, which stands for "not set"
+fn render_enum(def: &TypeDef, lock: &mut ProtoLock) -> String {
+    let name = pascal_case(&def.name);
+    let prefix = snake_case(&def.name).to_uppercase();
+    let locked = lock.entry(&name);
+    let value_name = |variant: &str| format!("{}_{}", prefix, snake_case(variant).to_uppercase());
+    let mut values = format!("  {}_UNSPECIFIED = 0;\n", prefix);
+    for variant in &def.variants {
+        let number = locked.number(&variant.name);
+        values.push_str(&format!("  {} = {};\n", value_name(&variant.name), number));
+    }
+    let present: Vec<&str> = def.variants.iter().map(|variant| variant.name.as_str()).collect();
+    locked.retire(&present);
+    format!("enum {name} {{\n{}{values}}}\n", reserved(locked, "  ", value_name))
+}
+
+/// Sum type with payloads: a `oneof` over a nested message per variant, unit
+/// variants as `google.protobuf.Empty`
+fn render_oneof(def: &TypeDef, types: &[TypeDef], lock: &mut ProtoLock) -> String {
+    let name = pascal_case(&def.name);
+    let mut nested = String::new();
+    let mut members = String::new();
+    for variant in &def.variants {
+        let variant_name = pascal_case(&variant.name);
+        let ty = match variant.fields.is_empty() {
+            true => "google.protobuf.Empty".to_string(),
+            false => {
+                let key = format!("{}.{}", name, variant_name);
+                nested.push_str(&render_message(&variant_name, &key, &variant.fields, types, lock, "  "));
+                variant_name.clone()
+            }
+        };
+        let number = lock.entry(&name).number(&variant.name);
+        members.push_str(&format!("    {} {} = {};\n", ty, snake_case(&variant.name), number));
+    }
+    let locked = lock.entry(&name);
+    let present: Vec<&str> = def.variants.iter().map(|variant| variant.name.as_str()).collect();
+    locked.retire(&present);
+    let reserved = reserved(locked, "  ", snake_case);
+    format!("message {name} {{\n{reserved}  oneof value {{\n{members}  }}\n{nested}}}\n")
+}
+
+/// Label and type of a field, or `None` when protobuf can't hold it: lists of
+/// lists or optionals, and maps keyed by anything but integers, strings and bools
+fn field_type(ty: &IrType, types: &[TypeDef]) -> Option<(&'static str, String)> {
+    match ty {
+        IrType::Container(ContainerType::Option(inner)) => Some(("optional ", singular(inner, types)?)),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => Some(("repeated ", singular(inner, types)?)),
+        IrType::Container(ContainerType::Map(key, value)) => {
+            let key = singular(key, types).filter(|key| {
+                matches!(key.as_str(), "string" | "bool" | "int32" | "int64" | "uint32" | "uint64")
+            })?;
+            Some(("", format!("map<{}, {}>", key, singular(value, types)?)))
+        }
+        IrType::Alias(_, inner) if !matches!(**inner, IrType::Primitive(_) | IrType::Reference(_)) => field_type(inner, types),
+        _ => Some(("", singular(ty, types)?)),
+    }
+}
+
+/// Scalar, enum or message type of a single value
+fn singular(ty: &IrType, types: &[TypeDef]) -> Option<String> {
+    Some(match ty {
+        IrType::Primitive(primitive) => match primitive {
+            PrimitiveType::Bool => "bool",
+            PrimitiveType::I8 | PrimitiveType::I16 | PrimitiveType::I32 => "int32",
+            PrimitiveType::I64 => "int64",
+            PrimitiveType::U8 | PrimitiveType::U16 | PrimitiveType::U32 => "uint32",
+            PrimitiveType::U64 => "uint64",
+            PrimitiveType::F32 => "float",
+            PrimitiveType::F64 => "double",
+            // Past 64 bits and exact decimals travel as their digits
+            PrimitiveType::String | PrimitiveType::Uuid | PrimitiveType::Decimal | PrimitiveType::BigInt | PrimitiveType::I128 | PrimitiveType::U128 => {
+                "string"
+            }
+            PrimitiveType::Bytes => "bytes",
+            PrimitiveType::DateTime => "google.protobuf.Timestamp",
+            PrimitiveType::Unit => "google.protobuf.Empty",
+        }
+        .to_string(),
+        IrType::Reference(name) | IrType::Alias(name, _) => {
+            match types.iter().find(|def| normalize_name(&def.name) == normalize_name(name)) {
+                Some(def) if def.aliased.is_some() => singular(def.aliased.as_ref()?, types)?,
+                Some(def) if unsupported(def).is_some() => return None,
+                Some(def) => pascal_case(&def.name),
+                None => match ty {
+                    IrType::Alias(_, inner) => singular(inner, types)?,
+                    _ => pascal_case(name),
+                },
+            }
+        }
+        IrType::Any => "google.protobuf.Value".to_string(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_proto_numbers_fields_and_maps_types() {
+        let types = analyze_rust(
+            "/// A person\npub struct User { pub id: u64, pub display_name: String, pub tags: Vec<String>, pub home: Option<Address>, \
+             pub seen: chrono::DateTime<chrono::Utc>, pub pair: (i32, i32) }\npub struct Address { pub city: String }\n\
+             pub enum Color { Red, DarkBlue }\npub enum Shape { Circle { radius: f64 }, Empty }\npub struct Page<T> { pub items: Vec<T> }",
+        );
+        let proto = generate_proto(&types, "acme.v1", &mut ProtoLock::default());
+        assert!(proto.contains("package acme.v1;\n\nimport \"google/protobuf/empty.proto\";\nimport \"google/protobuf/timestamp.proto\";\n"));
+        assert!(proto.contains(
+            "// A person\nmessage User {\n  uint64 id = 1;\n  string display_name = 2 [json_name = \"display_name\"];\n  repeated string tags = 3;\n  \
+             optional Address home = 4;\n  google.protobuf.Timestamp seen = 5;\n  // `pair` is left out: its type has no proto form\n}\n"
+        ));
+        assert!(proto.contains("enum Color {\n  COLOR_UNSPECIFIED = 0;\n  COLOR_RED = 1;\n  COLOR_DARK_BLUE = 2;\n}\n"));
+        assert!(proto.contains(
+            "message Shape {\n  oneof value {\n    Circle circle = 1;\n    google.protobuf.Empty empty = 2;\n  }\n  message Circle {\n    double radius = 1;\n  }\n}\n"
+        ));
+        assert!(proto.contains("// `Page` is left out: protobuf has no generic messages\n"));
+    }
+
+    #[test]
+    fn test_regenerating_keeps_numbers_and_reserves_removed_ones() {
+        let mut lock = ProtoLock::default();
+        generate_proto(&analyze_rust("pub struct User { pub id: u64, pub name: String }\npub enum Color { Red, Blue }"), "acme", &mut lock);
+        let mut lock = ProtoLock::from_json(&lock.to_json()).unwrap();
+        let proto = generate_proto(&analyze_rust("pub struct User { pub email: String, pub id: u64 }\npub enum Color { Blue, Green }"), "acme", &mut lock);
+        assert!(proto.contains("message User {\n  reserved 2;\n  reserved \"name\";\n  string email = 3;\n  uint64 id = 1;\n}\n"));
+        assert!(proto.contains("enum Color {\n  reserved 1;\n  reserved \"COLOR_RED\";\n  COLOR_UNSPECIFIED = 0;\n  COLOR_BLUE = 2;\n  COLOR_GREEN = 3;\n}\n"));
+        assert_eq!(ProtoLock::from_json(&lock.to_json()).unwrap(), lock);
+        assert!(ProtoLock::from_json("{\"version\":2,\"messages\":[]}").is_err());
+    }
+
+    #[test]
+    fn test_returning_field_is_renumbered_and_unreserved() {
+        let mut lock = ProtoLock::default();
+        generate_proto(&analyze_rust("pub struct User { pub id: u64, pub name: String }"), "acme", &mut lock);
+        generate_proto(&analyze_rust("pub struct User { pub id: u64 }"), "acme", &mut lock);
+        let proto = generate_proto(&analyze_rust("pub struct User { pub id: u64, pub name: String }"), "acme", &mut lock);
+        assert!(proto.contains("message User {\n  reserved 2;\n  uint64 id = 1;\n  string name = 3;\n}\n"));
+        let proto = generate_proto(&analyze_rust("pub struct User { pub id: u64 }"), "acme", &mut lock);
+        assert!(proto.contains("message User {\n  reserved 2, 3;\n  reserved \"name\";\n  uint64 id = 1;\n}\n"));
+    }
+}
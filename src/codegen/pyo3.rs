@@ -0,0 +1,363 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// PyO3 bindings for Rust structs, enums and free functions
+// Structs get a `#[pyclass]` wrapper with a constructor and a getter and setter
+// per field, fieldless enums a `#[pyclass]` enum, and both `FromPyObject` and
+// `IntoPy` so they convert wherever PyO3 expects a Python value. Python ints are
+// unbounded, so every integer width crosses exactly. The glue goes in a child
+// module of the one defining the types, which must be `Clone`
+
+use super::render_doc;
+use crate::analyzers::rust_analyzer::{is_zero_sized, rust_type_name, RustFieldType, RustFunction, RustType, RustTypeKind, RustVariantKind};
+
+/// Names of the Rust types that get a Python class
+struct Classes<'a> {
+    objects: Vec<&'a str>,
+    enums: Vec<&'a str>,
+}
+
+impl<'a> Classes<'a> {
+    /// Structs whose every field converts, given the classes around them; one
+    /// left out takes out the structs holding it, so this runs to a fixpoint
+    fn of(types: &'a [RustType]) -> Self {
+        let enums = types
+            .iter()
+            .filter(|t| {
+                t.kind == RustTypeKind::Enum
+                    && t.type_params.is_empty()
+                    && !t.variants.is_empty()
+                    && t.variants.iter().all(|variant| variant.kind == RustVariantKind::Unit)
+            })
+            .map(|t| t.name.as_str())
+            .collect();
+        let mut classes = Classes {
+            objects: types
+                .iter()
+                .filter(|t| t.kind == RustTypeKind::Struct && t.type_params.is_empty() && t.const_params.is_empty())
+                .filter(|t| t.fields.iter().all(|field| !field.name.starts_with(|c: char| c.is_ascii_digit())))
+                .map(|t| t.name.as_str())
+                .collect(),
+            enums,
+        };
+        loop {
+            let kept: Vec<&str> = types
+                .iter()
+                .filter(|t| classes.objects.contains(&t.name.as_str()))
+                .filter(|t| t.fields.iter().all(|field| is_zero_sized(&field.field_type) || classes.converts(&field.field_type)))
+                .map(|t| t.name.as_str())
+                .collect();
+            if kept.len() == classes.objects.len() {
+                return classes;
+            }
+            classes.objects = kept;
+        }
+    }
+
+    /// Whether PyO3 converts `ty` both ways, with these classes in scope
+    fn converts(&self, ty: &RustFieldType) -> bool {
+        match ty {
+            RustFieldType::I8
+            | RustFieldType::I16
+            | RustFieldType::I32
+            | RustFieldType::I64
+            | RustFieldType::I128
+            | RustFieldType::U8
+            | RustFieldType::U16
+            | RustFieldType::U32
+            | RustFieldType::U64
+            | RustFieldType::U128
+            | RustFieldType::Isize
+            | RustFieldType::Usize
+            | RustFieldType::F32
+            | RustFieldType::F64
+            | RustFieldType::Bool
+            | RustFieldType::String => true,
+            RustFieldType::Alias(_, inner) | RustFieldType::Option(inner) | RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) => {
+                self.converts(inner)
+            }
+            RustFieldType::Map(key, value) => self.converts(key) && self.converts(value),
+            RustFieldType::Tuple(members) => !members.is_empty() && members.iter().all(|member| self.converts(member)),
+            RustFieldType::Struct(name) => self.objects.contains(&name.as_str()) || self.enums.contains(&name.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Functions get a wrapper when every value converts; `&mut` parameters
+    /// don't, since changes to a converted copy would never reach Python
+    fn wraps(&self, function: &RustFunction) -> bool {
+        let params = function.params.iter().all(|(_, ty)| match ty {
+            RustFieldType::Borrowed(written, owned) => !written.starts_with("&mut") && self.converts(owned),
+            RustFieldType::Cow(_, owned) => self.converts(owned),
+            ty => self.converts(ty),
+        });
+        let unit = matches!(&function.ret, RustFieldType::Tuple(members) if members.is_empty());
+        params && (unit || self.converts(&function.ret))
+    }
+}
+
+/// Whether a field is read out by copy rather than `clone`
+fn is_copy(ty: &RustFieldType) -> bool {
+    match ty {
+        // User enums may not be `Copy`, even fieldless ones
+        RustFieldType::String | RustFieldType::Vec(_) | RustFieldType::Map(_, _) | RustFieldType::Struct(_) => false,
+        RustFieldType::Alias(_, inner) | RustFieldType::Option(inner) | RustFieldType::Array(inner, _) => is_copy(inner),
+        RustFieldType::Tuple(members) => members.iter().all(is_copy),
+        _ => true,
+    }
+}
+
+/// PyO3 glue for `types` and `functions`, ending in a `register` function
+/// that adds all of it to a Python module; anything left out is listed at the top
+pub fn generate_glue(types: &[RustType], functions: &[RustFunction]) -> String {
+    let classes = Classes::of(types);
+    let mut code = "use super::*;\nuse pyo3::prelude::*;\n".to_string();
+    for rust_type in types.iter().filter(|t| t.kind != RustTypeKind::Alias && !classes.objects.contains(&t.name.as_str()) && !classes.enums.contains(&t.name.as_str())) {
+        code.push_str(&format!(
+            "// `{}` has no class: generic, tuple and data-carrying types, and ones holding values PyO3 can't convert, are left out\n",
+            rust_type.name
+        ));
+    }
+    for function in functions.iter().filter(|f| !classes.wraps(f)) {
+        code.push_str(&format!("// `{}` is left out: it takes `&mut` or passes values PyO3 can't convert\n", function.name));
+    }
+
+    let mut registrations = Vec::new();
+    for rust_type in types {
+        if classes.objects.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&render_class(rust_type));
+        } else if classes.enums.contains(&rust_type.name.as_str()) {
+            code.push('\n');
+            code.push_str(&render_enum(rust_type));
+        } else {
+            continue;
+        }
+        code.push('\n');
+        code.push_str(&render_conversions(&rust_type.name, classes.enums.contains(&rust_type.name.as_str())));
+        registrations.push(format!("    module.add_class::<{}Py>()?;\n", rust_type.name));
+    }
+    for function in functions.iter().filter(|f| classes.wraps(f)) {
+        code.push('\n');
+        code.push_str(&render_function(function));
+        registrations.push(format!("    module.add_function(wrap_pyfunction!({}_py, module)?)?;\n", function.name));
+    }
+    code.push_str(&format!(
+        "\n/// Add the classes and functions above to `module`\npub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {{\n{}    Ok(())\n}}\n",
+        registrations.concat()
+    ));
+    code
+}
+
+fn render_class(rust_type: &RustType) -> String {
+    let name = &rust_type.name;
+    let mut code = rust_type.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    code.push_str(&format!("#[pyclass(name = \"{name}\")]\npub struct {name}Py(pub {name});\n\n#[pymethods]\nimpl {name}Py {{\n"));
+    let fields: Vec<_> = rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)).collect();
+    let params: Vec<String> = fields.iter().map(|field| format!("{}: {}", field.name, rust_type_name(&field.field_type))).collect();
+    let inits: Vec<String> = rust_type
+        .fields
+        .iter()
+        .map(|field| match is_zero_sized(&field.field_type) {
+            true => format!("{}: Default::default()", field.name),
+            false => field.name.clone(),
+        })
+        .collect();
+    code.push_str(&format!(
+        "    #[new]\n    fn new({}) -> Self {{\n        {name}Py({name} {{ {} }})\n    }}\n",
+        params.join(", "),
+        inits.join(", ")
+    ));
+    for field in fields {
+        let (ident, ty) = (&field.name, rust_type_name(&field.field_type));
+        let read = match is_copy(&field.field_type) {
+            true => format!("self.0.{}", ident),
+            false => format!("self.0.{}.clone()", ident),
+        };
+        code.push_str(&format!(
+            "\n    #[getter]\n    fn {ident}(&self) -> {ty} {{\n        {read}\n    }}\n\n    \
+             #[setter]\n    fn set_{ident}(&mut self, value: {ty}) {{\n        self.0.{ident} = value;\n    }}\n"
+        ));
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn render_enum(rust_type: &RustType) -> String {
+    let name = &rust_type.name;
+    let mut code = rust_type.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    code.push_str(&format!("#[pyclass(name = \"{name}\", eq, eq_int)]\n#[derive(Clone, Copy, PartialEq)]\npub enum {name}Py {{\n"));
+    for variant in &rust_type.variants {
+        code.push_str(&format!("    {},\n", variant.name));
+    }
+    code.push_str("}\n");
+    for (source, target) in [(name.clone(), format!("{name}Py")), (format!("{name}Py"), name.clone())] {
+        code.push_str(&format!("\nimpl From<{source}> for {target} {{\n    fn from(value: {source}) -> Self {{\n        match value {{\n"));
+        for variant in &rust_type.variants {
+            code.push_str(&format!("            {source}::{0} => {target}::{0},\n", variant.name));
+        }
+        code.push_str("        }\n    }\n}\n");
+    }
+    code
+}
+
+/// `FromPyObject` and `IntoPy` for `name` through its class, so it can be a
+/// field, parameter or result anywhere PyO3 converts
+fn render_conversions(name: &str, is_enum: bool) -> String {
+    let (extract, wrap) = match is_enum {
+        true => (format!("{name}::from(*value.downcast::<{name}Py>()?.borrow())"), format!("{name}Py::from(self)")),
+        false => (format!("value.downcast::<{name}Py>()?.borrow().0.clone()"), format!("{name}Py(self)")),
+    };
+    format!(
+        "impl<'py> FromPyObject<'py> for {name} {{\n    fn extract_bound(value: &Bound<'py, PyAny>) -> PyResult<Self> {{\n        \
+         Ok({extract})\n    }}\n}}\n\n\
+         impl IntoPy<PyObject> for {name} {{\n    fn into_py(self, py: Python<'_>) -> PyObject {{\n        \
+         {wrap}.into_py(py)\n    }}\n}}\n"
+    )
+}
+
+fn render_function(function: &RustFunction) -> String {
+    let mut code = function.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for (name, ty) in &function.params {
+        match ty {
+            RustFieldType::Borrowed(_, owned) => {
+                params.push(format!("{}: {}", name, rust_type_name(owned)));
+                args.push(format!("&{}", name));
+            }
+            RustFieldType::Cow(_, owned) => {
+                params.push(format!("{}: {}", name, rust_type_name(owned)));
+                args.push(format!("{}.into()", name));
+            }
+            ty => {
+                params.push(format!("{}: {}", name, rust_type_name(ty)));
+                args.push(name.clone());
+            }
+        }
+    }
+    let ret = match &function.ret {
+        RustFieldType::Tuple(members) if members.is_empty() => String::new(),
+        ret => format!(" -> {}", rust_type_name(ret)),
+    };
+    code.push_str(&format!(
+        "#[pyfunction]\n#[pyo3(name = \"{name}\")]\npub fn {name}_py({}){} {{\n    {name}({})\n}}\n",
+        params.join(", "),
+        ret,
+        args.join(", "),
+        name = function.name
+    ));
+    code
+}
+
+/// `.pyi` stubs for what `generate_glue` adds to the Python module
+pub fn generate_stubs(types: &[RustType], functions: &[RustFunction]) -> String {
+    let classes = Classes::of(types);
+    let mut code = "# Type stubs for the PyO3 bindings\n".to_string();
+    for rust_type in types {
+        let name = &rust_type.name;
+        if classes.objects.contains(&name.as_str()) {
+            code.push_str(&format!("\nclass {}:\n", name));
+            if let Some(doc) = &rust_type.doc {
+                code.push_str(&format!("    \"\"\"{}\"\"\"\n", doc));
+            }
+            let fields: Vec<_> = rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)).collect();
+            for field in &fields {
+                code.push_str(&format!("    {}: {}\n", field.name, py_type(&field.field_type)));
+            }
+            let params: Vec<String> = fields.iter().map(|field| format!(", {}: {}", field.name, py_type(&field.field_type))).collect();
+            code.push_str(&format!("    def __init__(self{}) -> None: ...\n", params.concat()));
+        } else if classes.enums.contains(&name.as_str()) {
+            code.push_str(&format!("\nclass {}:\n", name));
+            if let Some(doc) = &rust_type.doc {
+                code.push_str(&format!("    \"\"\"{}\"\"\"\n", doc));
+            }
+            for variant in &rust_type.variants {
+                code.push_str(&format!("    {}: {}\n", variant.name, name));
+            }
+            code.push_str("    def __int__(self) -> int: ...\n");
+        }
+    }
+    for function in functions.iter().filter(|f| classes.wraps(f)) {
+        let params: Vec<String> = function
+            .params
+            .iter()
+            .map(|(name, ty)| match ty {
+                RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned) => format!("{}: {}", name, py_type(owned)),
+                ty => format!("{}: {}", name, py_type(ty)),
+            })
+            .collect();
+        code.push_str(&format!("\ndef {}({}) -> {}:", function.name, params.join(", "), py_type(&function.ret)));
+        match &function.doc {
+            Some(doc) => code.push_str(&format!("\n    \"\"\"{}\"\"\"\n    ...\n", doc)),
+            None => code.push_str(" ...\n"),
+        }
+    }
+    code
+}
+
+/// Python type PyO3 converts `ty` to and from
+fn py_type(ty: &RustFieldType) -> String {
+    match ty {
+        RustFieldType::F32 | RustFieldType::F64 => "float".to_string(),
+        RustFieldType::Bool => "bool".to_string(),
+        RustFieldType::String => "str".to_string(),
+        RustFieldType::Alias(_, inner) => py_type(inner),
+        RustFieldType::Option(inner) => format!("{} | None", py_type(inner)),
+        RustFieldType::Vec(inner) | RustFieldType::Array(inner, _) => format!("list[{}]", py_type(inner)),
+        RustFieldType::Map(key, value) => format!("dict[{}, {}]", py_type(key), py_type(value)),
+        RustFieldType::Tuple(members) if members.is_empty() => "None".to_string(),
+        RustFieldType::Tuple(members) => format!("tuple[{}]", members.iter().map(py_type).collect::<Vec<_>>().join(", ")),
+        RustFieldType::Struct(name) => name.clone(),
+        _ => "int".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::{analyze_rust_functions, analyze_rust_types};
+
+    const SOURCE: &str = "pub enum Status { Active, Suspended }\n\
+        /// A signed-up user\n\
+        pub struct User { pub id: u64, pub name: String, pub status: Status, pub email: Option<String>, pub scores: HashMap<String, f64> }\n\
+        pub struct Session { pub user: User, pub started: DateTime<Utc> }\n\
+        pub struct Audit { pub session: Session }\n\
+        /// Give a user a new name\n\
+        pub fn rename(user: &User, name: &str) -> User { todo!() }\n\
+        pub fn bump(user: &mut User) {}";
+
+    #[test]
+    fn test_glue_wraps_convertible_types() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let glue = generate_glue(&types, &functions);
+        assert!(glue.contains("// `Session` has no class"));
+        // `Audit` only loses its class because `Session` did
+        assert!(glue.contains("// `Audit` has no class"));
+        assert!(glue.contains("// `bump` is left out"));
+        assert!(glue.contains("#[pyclass(name = \"Status\", eq, eq_int)]\n#[derive(Clone, Copy, PartialEq)]\npub enum StatusPy {\n    Active,\n    Suspended,\n}\n"));
+        assert!(glue.contains(
+            "    #[new]\n    fn new(id: u64, name: String, status: Status, email: Option<String>, scores: HashMap<String, f64>) -> Self {\n        \
+             UserPy(User { id, name, status, email, scores })\n    }\n"
+        ));
+        assert!(glue.contains("    #[getter]\n    fn status(&self) -> Status {\n        self.0.status.clone()\n    }\n"));
+        assert!(glue.contains("    #[setter]\n    fn set_name(&mut self, value: String) {\n        self.0.name = value;\n    }\n"));
+        assert!(glue.contains("impl<'py> FromPyObject<'py> for User {\n    fn extract_bound(value: &Bound<'py, PyAny>) -> PyResult<Self> {\n        Ok(value.downcast::<UserPy>()?.borrow().0.clone())\n"));
+        assert!(glue.contains("        Ok(Status::from(*value.downcast::<StatusPy>()?.borrow()))\n"));
+        assert!(glue.contains("#[pyo3(name = \"rename\")]\npub fn rename_py(user: User, name: String) -> User {\n    rename(&user, &name)\n}\n"));
+        assert!(glue.contains("    module.add_class::<UserPy>()?;\n    module.add_function(wrap_pyfunction!(rename_py, module)?)?;\n    Ok(())\n"));
+    }
+
+    #[test]
+    fn test_stubs_follow_the_classes() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let stubs = generate_stubs(&types, &functions);
+        assert!(stubs.contains(
+            "class User:\n    \"\"\"A signed-up user\"\"\"\n    id: int\n    name: str\n    status: Status\n    email: str | None\n    scores: dict[str, float]\n    \
+             def __init__(self, id: int, name: str, status: Status, email: str | None, scores: dict[str, float]) -> None: ...\n"
+        ));
+        assert!(stubs.contains("class Status:\n    Active: Status\n    Suspended: Status\n"));
+        assert!(stubs.contains("def rename(user: User, name: str) -> User:\n    \"\"\"Give a user a new name\"\"\"\n    ...\n"));
+        assert!(!stubs.contains("Session"));
+    }
+}
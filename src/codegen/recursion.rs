@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Recursive type detection
+// Finds types that reach themselves through their fields, so generated code can
+// box the by-value edges Rust needs and declare ReScript types with `rec`
+
+use super::render_type;
+use crate::api::TypeDef;
+use crate::ir::{normalize_name, ContainerType, IrType};
+
+/// Whether `def` reaches itself through its fields, directly or through `types`
+pub fn is_recursive(def: &TypeDef, types: &[TypeDef]) -> bool {
+    reaches(&member_types(def), &def.name, types, false)
+}
+
+/// Whether `ty` names the type `name` anywhere, without following other types
+pub fn mentions(ty: &IrType, name: &str) -> bool {
+    reaches(&[ty], name, &[], false)
+}
+
+/// Render `ty` as a Rust field of `def`, boxing every by-value reference that
+/// leads back to `def`; lists and maps already allocate, so they stay as-is
+pub fn render_rust_field(ty: &IrType, def: &TypeDef, types: &[TypeDef]) -> String {
+    let render = |inner: &IrType| render_rust_field(inner, def, types);
+    match ty {
+        IrType::Reference(name) | IrType::Alias(name, _) | IrType::Generic(name, _) if reaches(&[ty], &def.name, types, true) => {
+            format!("Box<{}>", render_type(ty, "rust"))
+        }
+        IrType::Container(ContainerType::Option(inner)) => format!("Option<{}>", render(inner)),
+        IrType::Container(ContainerType::Array(inner, len)) => format!("[{}; {}]", render(inner), len),
+        IrType::Container(ContainerType::Result(ok, err)) => format!("Result<{}, {}>", render(ok), render(err)),
+        IrType::Tuple(members) => format!("({})", members.iter().map(render).collect::<Vec<_>>().join(", ")),
+        _ => render_type(ty, "rust"),
+    }
+}
+
+/// Field, payload and aliased types of a definition
+fn member_types(def: &TypeDef) -> Vec<&IrType> {
+    def.fields
+        .iter()
+        .chain(def.variants.iter().flat_map(|variant| &variant.fields))
+        .map(|field| &field.ty)
+        .chain(&def.aliased)
+        .collect()
+}
+
+/// Whether any of `start` leads to the type `name`, following the definitions
+/// in `types`; with `inline`, only through values held in place
+fn reaches(start: &[&IrType], name: &str, types: &[TypeDef], inline: bool) -> bool {
+    let target = normalize_name(name);
+    let mut pending = Vec::new();
+    start.iter().for_each(|ty| referenced(ty, inline, &mut pending));
+    let mut seen = Vec::new();
+    while let Some(next) = pending.pop() {
+        let key = normalize_name(&next);
+        if key == target {
+            return true;
+        }
+        if seen.contains(&key) {
+            continue;
+        }
+        if let Some(def) = types.iter().find(|def| normalize_name(&def.name) == key) {
+            member_types(def).into_iter().for_each(|ty| referenced(ty, inline, &mut pending));
+        }
+        seen.push(key);
+    }
+    false
+}
+
+fn referenced(ty: &IrType, inline: bool, names: &mut Vec<String>) {
+    match ty {
+        IrType::Reference(name) => names.push(name.clone()),
+        IrType::Alias(name, aliased) => {
+            names.push(name.clone());
+            referenced(aliased, inline, names);
+        }
+        IrType::Container(ContainerType::Vec(_) | ContainerType::Map(_, _)) if inline => {}
+        // A fixed-length array holds its elements in place
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _)) => {
+            referenced(inner, inline, names)
+        }
+        IrType::Container(ContainerType::Result(a, b) | ContainerType::Map(a, b)) => {
+            referenced(a, inline, names);
+            referenced(b, inline, names);
+        }
+        IrType::Union(members) | IrType::Tuple(members) => members.iter().for_each(|member| referenced(member, inline, names)),
+        // Where the definition keeps its parameters isn't followed, so arguments count as held in place
+        IrType::Generic(name, args) => {
+            names.push(name.clone());
+            args.iter().for_each(|arg| referenced(arg, inline, names));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_boxes_only_by_value_cycles() {
+        let types = analyze_rust(
+            "pub struct Node { pub children: Vec<Node>, pub parent: Option<Box<Node>>, pub link: Link }\n\
+             pub struct Link { pub target: Option<Box<Node>>, pub label: String }\n\
+             pub struct Leaf { pub value: i64 }",
+        );
+        let node = &types[0];
+        assert!(is_recursive(node, &types));
+        assert!(!is_recursive(&types[2], &types));
+        let fields: Vec<String> = node.fields.iter().map(|field| render_rust_field(&field.ty, node, &types)).collect();
+        assert_eq!(fields, vec!["Vec<Node>", "Option<Box<Node>>", "Box<Link>"]);
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// ReScript bindings for Rust structs
+// Record types shaped like the JSON serde writes, plus `@module` externals for
+// decode and encode functions the Rust side exports through wasm-bindgen, so
+// every value crossing into ReScript has been checked by the Rust definition
+
+use super::{camel_case, render_doc, render_type, render_type_params, snake_case};
+use crate::api::TypeDef;
+use crate::ir::{ContainerType, IrType, PrimitiveType};
+
+/// Record declarations of `types` as one `type rec ... and ...` group, so
+/// fields may name any of them regardless of order; `None` unless every one is a struct
+pub fn render_records(types: &[TypeDef]) -> Option<String> {
+    let mut code = String::new();
+    for (i, def) in types.iter().enumerate() {
+        if !def.variants.is_empty() || def.aliased.is_some() {
+            return None;
+        }
+        if i > 0 {
+            code.push('\n');
+        }
+        code.push_str(&def.doc.as_ref().map(|doc| render_doc(doc, "rescript", "")).unwrap_or_default());
+        code.push_str(&format!(
+            "{} {}{} = {{\n",
+            if i == 0 { "type rec" } else { "and" },
+            camel_case(&def.name),
+            render_type_params(&def.type_params, "rescript")
+        ));
+        for field in &def.fields {
+            if let Some(doc) = &field.doc {
+                code.push_str(&render_doc(doc, "rescript", "  "));
+            }
+            // Record labels are camelCase; `@as` keeps serde's key
+            let label = camel_case(&field.name);
+            let key = if label != field.name { format!("@as(\"{}\") ", field.name) } else { String::new() };
+            code.push_str(&format!("  {}{}: {},\n", key, label, binding_type(&field.ty)));
+        }
+        code.push_str("}\n");
+    }
+    Some(code)
+}
+
+/// `@module` externals into the JavaScript module at `module` for the
+/// functions `render_wasm_exports` defines for `def`
+pub fn render_externals(def: &TypeDef, module: &str) -> String {
+    let record = camel_case(&def.name);
+    let snake = snake_case(&def.name);
+    format!(
+        "/** Parse JSON into a `{record}`; throws when the Rust side's serde rejects it */\n\
+         @module(\"{module}\") external {record}FromJson: string => {record} = \"{snake}_from_json\"\n\
+         /** Serialize a `{record}` the way the Rust side does; throws when serde rejects it */\n\
+         @module(\"{module}\") external {record}ToJson: {record} => string = \"{snake}_to_json\"\n"
+    )
+}
+
+/// `#[wasm_bindgen]` functions behind the externals of `def`; values reach
+/// JavaScript as JSON-compatible objects, so `None` is `null` and maps are objects
+pub fn render_wasm_exports(def: &TypeDef) -> String {
+    let snake = snake_case(&def.name);
+    format!(
+        "#[wasm_bindgen]\npub fn {snake}_from_json(json: &str) -> Result<JsValue, JsError> {{\n    \
+         let value: {name} = serde_json::from_str(json)?;\n    \
+         Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)\n}}\n\n\
+         #[wasm_bindgen]\npub fn {snake}_to_json(value: JsValue) -> Result<String, JsError> {{\n    \
+         let value: {name} = serde_wasm_bindgen::from_value(value)?;\n    \
+         Ok(serde_json::to_string(&value)?)\n}}\n",
+        name = def.name
+    )
+}
+
+/// ReScript type of a value as it arrives from JSON; unlike `render_type`,
+/// a missing value is `null` rather than `undefined` and every map is an object
+pub(super) fn binding_type(ty: &IrType) -> String {
+    match ty {
+        IrType::Container(ContainerType::Option(inner)) => format!("Js.Nullable.t<{}>", binding_type(inner)),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("array<{}>", binding_type(inner)),
+        IrType::Container(ContainerType::Map(_, value)) => format!("Js.Dict.t<{}>", binding_type(value)),
+        IrType::Container(ContainerType::Result(_, _)) => "JSON.t".to_string(),
+        // Base64 text, as JSON delivers it
+        IrType::Primitive(PrimitiveType::Bytes) => "string".to_string(),
+        // JSON numbers wider than `int` arrive as JavaScript numbers
+        IrType::Primitive(primitive) if primitive.is_integer() && !super::rescript_codecs::fits_int(*primitive) => "float".to_string(),
+        IrType::Tuple(members) if !members.is_empty() => {
+            format!("({})", members.iter().map(binding_type).collect::<Vec<_>>().join(", "))
+        }
+        IrType::Generic(name, args) => format!("{}<{}>", camel_case(name), args.iter().map(binding_type).collect::<Vec<_>>().join(", ")),
+        other => render_type(other, "rescript"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_records_follow_serde_json() {
+        let types = analyze_rust(
+            "/// A signed-up user\npub struct User { pub id: u32, pub display_name: String, pub email: Option<String>, \
+             pub scores: HashMap<u32, f64>, pub home: Address }\npub struct Address { pub zip: String }",
+        );
+        assert_eq!(
+            render_records(&types).unwrap(),
+            "/** A signed-up user */\ntype rec user = {\n  id: float,\n  @as(\"display_name\") displayName: string,\n  \
+             email: Js.Nullable.t<string>,\n  scores: Js.Dict.t<float>,\n  home: address,\n}\n\n\
+             and address = {\n  zip: string,\n}\n"
+        );
+        assert!(render_records(&analyze_rust("pub enum Status { Active }")).is_none());
+        let externals = render_externals(&types[0], "./pkg/app.js");
+        assert!(externals.contains("@module(\"./pkg/app.js\") external userFromJson: string => user = \"user_from_json\"\n"));
+        assert!(render_wasm_exports(&types[0]).contains("pub fn user_to_json(value: JsValue) -> Result<String, JsError> {"));
+    }
+}
@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// ReScript JSON codecs written to serde_json's exact output
+// Encoders build the text themselves rather than going through
+// `JSON.stringify`, so keys follow declaration order and floats come out as
+// `ryu` writes them (`1.0`, `1e+16`). A test vector file, one list of serde_json
+// texts per record, lets both sides check the round trip byte for byte
+
+use super::rescript::render_records;
+use super::{camel_case, pascal_case};
+use crate::api::TypeDef;
+use crate::ir::{ContainerType, IrType, PrimitiveType};
+
+/// Helpers every codec module shares
+const PRELUDE: &str = r#"exception DecodeError(string)
+
+let fail = expected => raise(DecodeError("expected " ++ expected))
+
+// serde_json writes floats the way `ryu` does: shortest digits for the width,
+// `1.0` rather than `1`, and below 1e-5 or from 1e16 in exponent form (`1e+16`)
+let formatFloat: (float, bool) => string = %raw(`function (x, single) {
+  if (!isFinite(x)) return "null";
+  if (x === 0) return (1 / x < 0 ? "-" : "") + "0.0";
+  var abs = single ? Math.fround(Math.abs(x)) : Math.abs(x), text = abs.toExponential();
+  for (var precision = 0; single && precision < 9; precision++) {
+    text = abs.toExponential(precision);
+    if (Math.fround(parseFloat(text)) === abs) break;
+  }
+  var parts = text.split("e"), digits = parts[0].replace(".", ""), exponent = parseInt(parts[1], 10);
+  var point = exponent + 1, sign = x < 0 ? "-" : "";
+  if (digits.length <= point && point <= 16) return sign + digits + "0".repeat(point - digits.length) + ".0";
+  if (0 < point && point <= 16) return sign + digits.slice(0, point) + "." + digits.slice(point);
+  if (-5 < point && point <= 0) return sign + "0." + "0".repeat(-point) + digits;
+  return sign + digits[0] + (digits.length > 1 ? "." + digits.slice(1) : "") + (exponent < 0 ? "e" : "e+") + exponent;
+}`)
+
+// `JSON.stringify` escapes strings exactly as serde_json does
+let encodeString = (value: string) => JSON.stringify(JSON.Encode.string(value))
+let encodeArray = (values, encode) => "[" ++ values->Array.map(encode)->Array.join(",") ++ "]"
+let encodeDict = (values, encode) =>
+  "{" ++ values->Js.Dict.entries->Array.map(((key, value)) => encodeString(key) ++ ":" ++ encode(value))->Array.join(",") ++ "}"
+let encodeNullable = (value, encode) =>
+  switch value->Js.Nullable.toOption {
+  | Some(value) => encode(value)
+  | None => "null"
+  }
+
+let decodeBool = json =>
+  switch json {
+  | JSON.Boolean(value) => value
+  | _ => fail("a boolean")
+  }
+let decodeString = json =>
+  switch json {
+  | JSON.String(value) => value
+  | _ => fail("a string")
+  }
+let decodeFloat = json =>
+  switch json {
+  | JSON.Number(value) => value
+  | _ => fail("a number")
+  }
+let decodeInteger = (json, min, max) =>
+  switch json {
+  | JSON.Number(value) if value == Math.floor(value) && value >= min && value <= max => value
+  | _ => fail(`an integer from ${Float.toString(min)} to ${Float.toString(max)}`)
+  }
+let decodeInt = (json, min, max) => decodeInteger(json, min, max)->Float.toInt
+let decodeArray = (json, decode) =>
+  switch json {
+  | JSON.Array(values) => values->Array.map(decode)
+  | _ => fail("an array")
+  }
+let decodeDict = (json, decode) =>
+  switch json {
+  | JSON.Object(values) => values->Dict.toArray->Array.map(((key, value)) => (key, decode(value)))->Js.Dict.fromArray
+  | _ => fail("an object")
+  }
+let decodeNullable = (json, decode) =>
+  switch json {
+  | JSON.Null => Js.Nullable.null
+  | json => Js.Nullable.return(decode(json))
+  }
+let field = (object, key, decode) =>
+  switch object->Dict.get(key) {
+  | Some(json) => decode(json)
+  | None => fail(`field "${key}"`)
+  }
+"#;
+
+/// Codec module for `types`: the records, an `encodeX: x => string` and a
+/// `decodeX: string => result<x, string>` per record, and `checkVectors`
+/// running a test vector file through them; `None` unless every type is a
+/// struct whose fields serde_json and ReScript both carry exactly
+pub fn render_codecs(types: &[TypeDef]) -> Option<String> {
+    if !types.iter().all(|def| def.fields.iter().all(|field| codable(&field.ty, types))) {
+        return None;
+    }
+    let mut code = render_records(types)?;
+    code.push('\n');
+    code.push_str(PRELUDE);
+    for (i, def) in types.iter().enumerate() {
+        let name = pascal_case(&def.name);
+        let record = camel_case(&def.name);
+        let keyword = if i == 0 { "let rec" } else { "and" };
+        let encoded: Vec<String> = def
+            .fields
+            .iter()
+            .map(|field| format!("\"\\\"{}\\\":\" ++ {}", field.name, encode(&field.ty, &format!("value.{}", camel_case(&field.name)))))
+            .collect();
+        let decoded: String = def
+            .fields
+            .iter()
+            .map(|field| format!("      {}: object->field(\"{}\", {}),\n", camel_case(&field.name), field.name, decoder(&field.ty)))
+            .collect();
+        code.push_str(&format!(
+            "\n{keyword} encode{name} = (value: {record}) =>\n  \"{{\" ++ {} ++ \"}}\"\n\
+             and decode{name}Json = (json): {record} =>\n  switch json {{\n  | JSON.Object(object) => {{\n{decoded}    }}\n  \
+             | _ => fail(\"a `{record}` object\")\n  }}\n",
+            if encoded.is_empty() { "\"\"".to_string() } else { encoded.join(" ++ \",\" ++ ") }
+        ));
+    }
+    let mut checks = String::new();
+    for def in types {
+        let name = pascal_case(&def.name);
+        code.push_str(&format!(
+            "\nlet decode{name} = (text: string): result<{}, string> =>\n  \
+             try Ok(decode{name}Json(JSON.parseExn(text))) catch {{\n  | DecodeError(message) => Error(message)\n  \
+             | Js.Exn.Error(error) => Error(error->Js.Exn.message->Option.getOr(\"invalid JSON\"))\n  }}\n",
+            camel_case(&def.name)
+        ));
+        checks.push_str(&format!("  check(\"{}\", text => decode{name}(text)->Result.map(encode{name}))\n", def.name));
+    }
+    code.push_str(&format!(
+        "\n/** Texts of a test vector file that don't decode and re-encode to themselves */\n\
+         let checkVectors = (vectors: Js.Dict.t<array<string>>) => {{\n  let failures = []\n  \
+         let check = (name, roundTrip) =>\n    vectors\n    ->Js.Dict.get(name)\n    ->Option.getOr([])\n    \
+         ->Array.forEach(text =>\n      if roundTrip(text) != Ok(text) {{\n        failures->Array.push(`${{name}}: ${{text}}`)\n      }}\n    )\n\
+         {checks}  failures\n}}\n"
+    ));
+    Some(code)
+}
+
+/// Whether serde_json and the ReScript record carry `ty` the same way; 64-bit
+/// and wider integers are held as a `float`, so they pass only because their
+/// decoder rejects what it can't hold exactly
+fn codable(ty: &IrType, types: &[TypeDef]) -> bool {
+    match ty {
+        IrType::Primitive(primitive) => matches!(
+            primitive,
+            PrimitiveType::Bool | PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid
+        ) || primitive.is_integer(),
+        IrType::Container(ContainerType::Option(inner) | ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => codable(inner, types),
+        IrType::Container(ContainerType::Map(_, value)) => codable(value, types),
+        IrType::Reference(name) => types.iter().any(|def| def.name == *name),
+        IrType::Alias(_, inner) => codable(inner, types),
+        _ => false,
+    }
+}
+
+/// Largest integer a JavaScript number holds exactly
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Range the decoder accepts for an integer type: all of it, clipped to what
+/// a JavaScript number holds exactly, so nothing wider decodes rounded
+pub(super) fn int_range(primitive: PrimitiveType) -> (i64, i64) {
+    let (signed, bits) = primitive.integer_width().unwrap_or((true, 32));
+    let (min, max) = match (signed, bits) {
+        (true, bits) if bits < 64 => (-(1 << (bits - 1)), (1 << (bits - 1)) - 1),
+        (false, bits) if bits < 64 => (0, (1 << bits) - 1),
+        (true, _) => (i64::MIN, i64::MAX),
+        (false, _) => (0, i64::MAX),
+    };
+    (min.max(-MAX_SAFE_INTEGER), max.min(MAX_SAFE_INTEGER))
+}
+
+/// Whether `primitive` is an integer type that fits ReScript's 32-bit `int`;
+/// wider ones are a `float`
+pub(super) fn fits_int(primitive: PrimitiveType) -> bool {
+    let (min, max) = int_range(primitive);
+    primitive.is_integer() && min >= i32::MIN.into() && max <= i32::MAX.into()
+}
+
+/// ReScript expression writing `value` of type `ty` as serde_json would
+fn encode(ty: &IrType, value: &str) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => format!("({} ? \"true\" : \"false\")", value),
+        IrType::Primitive(PrimitiveType::F32) => format!("formatFloat({}, true)", value),
+        IrType::Primitive(PrimitiveType::F64) => format!("formatFloat({}, false)", value),
+        IrType::Primitive(primitive) if fits_int(*primitive) => format!("Int.toString({})", value),
+        // Whole numbers below 1e21 print without a fraction or exponent
+        IrType::Primitive(primitive) if primitive.is_integer() => format!("Float.toString({})", value),
+        IrType::Primitive(_) => format!("encodeString({})", value),
+        IrType::Container(ContainerType::Option(inner)) => format!("encodeNullable({}, value => {})", value, encode(inner, "value")),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => {
+            format!("encodeArray({}, value => {})", value, encode(inner, "value"))
+        }
+        IrType::Container(ContainerType::Map(_, inner)) => format!("encodeDict({}, value => {})", value, encode(inner, "value")),
+        IrType::Reference(name) => format!("encode{}({})", pascal_case(name), value),
+        IrType::Alias(_, inner) => encode(inner, value),
+        _ => unreachable!("only codable types are encoded"),
+    }
+}
+
+/// ReScript function reading a `JSON.t` of type `ty`, raising `DecodeError` otherwise
+fn decoder(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "decodeBool".to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64) => "decodeFloat".to_string(),
+        IrType::Primitive(primitive) if primitive.is_integer() => {
+            let (min, max) = int_range(*primitive);
+            let decode = if fits_int(*primitive) { "decodeInt" } else { "decodeInteger" };
+            format!("json => {}(json, {}., {}.)", decode, min, max)
+        }
+        IrType::Primitive(_) => "decodeString".to_string(),
+        IrType::Container(ContainerType::Option(inner)) => format!("json => decodeNullable(json, {})", decoder(inner)),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("json => decodeArray(json, {})", decoder(inner)),
+        IrType::Container(ContainerType::Map(_, inner)) => format!("json => decodeDict(json, {})", decoder(inner)),
+        IrType::Reference(name) => format!("decode{}Json", pascal_case(name)),
+        IrType::Alias(_, inner) => decoder(inner),
+        _ => unreachable!("only codable types are decoded"),
+    }
+}
+
+/// Test vector file for `types`: per record, the serde_json text of an
+/// all-empty value and of one exercising escapes, float formatting and the
+/// edges of each integer range; `None` when `render_codecs` would be
+pub fn render_test_vectors(types: &[TypeDef]) -> Option<String> {
+    if !types.iter().all(|def| def.variants.is_empty() && def.aliased.is_none() && def.fields.iter().all(|field| codable(&field.ty, types))) {
+        return None;
+    }
+    let entries: Vec<String> = types
+        .iter()
+        .map(|def| {
+            let vectors = [sample(def, types, false, &mut Vec::new()), sample(def, types, true, &mut Vec::new())];
+            let texts: Vec<String> = vectors.iter().map(|text| format!("    {}", json_string(text))).collect();
+            format!("  {}: [\n{}\n  ]", json_string(&def.name), texts.join(",\n"))
+        })
+        .collect();
+    Some(format!("{{\n{}\n}}\n", entries.join(",\n")))
+}
+
+/// A Rust `#[test]` checking serde_json against the vector file at `path`,
+/// for the Rust types `types` were analyzed from
+pub fn render_vector_test(types: &[TypeDef], path: &str) -> String {
+    let checks: String = types
+        .iter()
+        .map(|def| format!("    check::<{}>(&vectors, \"{}\");\n", def.name, def.name))
+        .collect();
+    format!(
+        "#[test]\nfn serde_matches_the_test_vectors() {{\n    \
+         fn check<T: serde::Serialize + serde::de::DeserializeOwned>(vectors: &serde_json::Value, name: &str) {{\n        \
+         for text in vectors[name].as_array().into_iter().flatten().filter_map(|text| text.as_str()) {{\n            \
+         let value: T = serde_json::from_str(text).unwrap_or_else(|err| panic!(\"{{}}: {{}}: {{}}\", name, text, err));\n            \
+         assert_eq!(serde_json::to_string(&value).unwrap(), text, \"{{}}\", name);\n        }}\n    }}\n\n    \
+         let vectors: serde_json::Value = serde_json::from_str(include_str!(\"{path}\")).unwrap();\n{checks}}}\n"
+    )
+}
+
+/// serde_json text of a sample `def`; `seen` breaks reference cycles with `null`s and empty lists
+fn sample(def: &TypeDef, types: &[TypeDef], full: bool, seen: &mut Vec<String>) -> String {
+    seen.push(def.name.clone());
+    let fields: Vec<String> = def
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| format!("{}:{}", json_string(&field.name), sample_value(&field.ty, types, full, i, seen)))
+        .collect();
+    seen.pop();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// The `i`th field's sample; full samples alternate between the two ends of integer ranges
+fn sample_value(ty: &IrType, types: &[TypeDef], full: bool, i: usize, seen: &mut Vec<String>) -> String {
+    let cycles = |ty: &IrType| matches!(ty, IrType::Reference(name) if seen.contains(name));
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => full.to_string(),
+        // Shortest digits differ between the widths, and JavaScript writes `1e+16` out in full
+        IrType::Primitive(PrimitiveType::F32) if full => "0.1".to_string(),
+        IrType::Primitive(PrimitiveType::F64) if full => if i.is_multiple_of(2) { "-1.5e-7" } else { "1e+16" }.to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64) => "0.0".to_string(),
+        IrType::Primitive(primitive) if primitive.is_integer() => {
+            let (min, max) = int_range(*primitive);
+            match (full, i % 2) {
+                (false, _) => "0".to_string(),
+                (true, 0) => max.to_string(),
+                (true, _) => min.to_string(),
+            }
+        }
+        IrType::Primitive(PrimitiveType::DateTime) => json_string(if full { "2024-02-29T23:59:59Z" } else { "1970-01-01T00:00:00Z" }),
+        IrType::Primitive(PrimitiveType::Uuid) => {
+            json_string(if full { "67e55044-10b1-426f-9247-bb680e5fe0c8" } else { "00000000-0000-0000-0000-000000000000" })
+        }
+        IrType::Primitive(_) => json_string(if full { "say \"hi\"\n\u{1}é/✓" } else { "" }),
+        IrType::Container(ContainerType::Option(inner)) if full && !cycles(inner) => sample_value(inner, types, full, i, seen),
+        IrType::Container(ContainerType::Option(_)) => "null".to_string(),
+        IrType::Container(ContainerType::Vec(inner)) if full && !cycles(inner) => format!("[{}]", sample_value(inner, types, full, i, seen)),
+        IrType::Container(ContainerType::Vec(_)) => "[]".to_string(),
+        IrType::Container(ContainerType::Array(inner, len)) => {
+            let item = sample_value(inner, types, full, i, seen);
+            format!("[{}]", vec![item; *len].join(","))
+        }
+        IrType::Container(ContainerType::Map(key, inner)) if full && !cycles(inner) => {
+            let key = match key.as_ref() {
+                IrType::Primitive(primitive) if primitive.is_integer() => "7",
+                _ => "key",
+            };
+            format!("{{{}:{}}}", json_string(key), sample_value(inner, types, full, i, seen))
+        }
+        IrType::Container(ContainerType::Map(_, _)) => "{}".to_string(),
+        IrType::Reference(name) => match types.iter().find(|def| def.name == *name) {
+            Some(def) => sample(def, types, full, seen),
+            None => "null".to_string(),
+        },
+        IrType::Alias(_, inner) => sample_value(inner, types, full, i, seen),
+        _ => "null".to_string(),
+    }
+}
+
+/// `text` as serde_json writes a string: `"` and `\` escaped, control
+/// characters as short escapes or `\u00XX`, everything else as is
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_codecs_follow_serde_json() {
+        let types = analyze_rust(
+            "#[serde(rename_all = \"camelCase\")]\npub struct User { pub id: u32, pub display_name: String, pub score: f32, \
+             #[serde(rename = \"mail\")] pub email: Option<String>, pub home: Address, pub age: i16, pub balance: i64 }\n\
+             pub struct Address { pub zip: String, pub tags: Vec<String> }",
+        );
+        let code = render_codecs(&types).unwrap();
+        assert!(code.starts_with("type rec user = {\n"));
+        assert!(code.contains(
+            "let rec encodeUser = (value: user) =>\n  \"{\" ++ \"\\\"id\\\":\" ++ Float.toString(value.id) ++ \",\" ++ \
+             \"\\\"displayName\\\":\" ++ encodeString(value.displayName) ++ \",\" ++ \"\\\"score\\\":\" ++ formatFloat(value.score, true)"
+        ));
+        // serde's renames are the keys
+        assert!(code.contains("  displayName: string,\n  score: float,\n  mail: Js.Nullable.t<string>,\n"));
+        assert!(code.contains("encodeNullable(value.mail, value => encodeString(value))"));
+        // Integers wider than `int` are floats, decoded only where exact
+        assert!(code.contains("      id: object->field(\"id\", json => decodeInteger(json, 0., 4294967295.)),\n"));
+        assert!(code.contains("      age: object->field(\"age\", json => decodeInt(json, -32768., 32767.)),\n"));
+        assert!(code.contains("      balance: object->field(\"balance\", json => decodeInteger(json, -9007199254740991., 9007199254740991.)),\n"));
+        assert!(code.contains("\"\\\"age\\\":\" ++ Int.toString(value.age)"));
+        assert!(code.contains("      home: object->field(\"home\", decodeAddressJson),\n"));
+        assert!(code.contains("let decodeAddress = (text: string): result<address, string> =>\n"));
+        assert!(code.contains("  check(\"User\", text => decodeUser(text)->Result.map(encodeUser))\n"));
+        assert!(render_codecs(&analyze_rust("pub struct Blob { pub id: i128, pub data: Box<dyn Fn()> }")).is_none());
+    }
+
+    #[test]
+    fn test_vectors_are_serde_json_text() {
+        let types = analyze_rust("pub struct Reading { pub level: i8, pub delta: i16, pub value: f64, pub label: String, pub next: Option<Box<Reading>> }");
+        let wide = render_test_vectors(&analyze_rust("pub struct Total { pub sum: u64, pub offset: i128 }")).unwrap();
+        assert!(wide.contains("    \"{\\\"sum\\\":9007199254740991,\\\"offset\\\":-9007199254740991}\"\n"));
+        let vectors = render_test_vectors(&types).unwrap();
+        assert!(vectors.contains("    \"{\\\"level\\\":0,\\\"delta\\\":0,\\\"value\\\":0.0,\\\"label\\\":\\\"\\\",\\\"next\\\":null}\",\n"));
+        assert!(vectors.contains(
+            "    \"{\\\"level\\\":127,\\\"delta\\\":-32768,\\\"value\\\":-1.5e-7,\\\"label\\\":\\\"say \\\\\\\"hi\\\\\\\"\\\\n\\\\u0001é/✓\\\",\\\"next\\\":null}\"\n"
+        ));
+        assert!(render_vector_test(&types, "vectors.json").contains("    check::<Reading>(&vectors, \"Reading\");\n"));
+    }
+}
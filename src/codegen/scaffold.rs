@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Contract-first scaffolding
+// Writes idiomatic starting definitions (type, constructor or guard, JSON codec)
+// in a language that has no side yet, derived from the side that does
+
+use super::recursion::render_rust_field;
+use super::{pascal_case, render_default, render_doc, render_type, rust_serde_as, rust_serde_default, snake_case, BIGINT_PATTERN, DECIMAL_PATTERN};
+use crate::api::TypeDef;
+use crate::ir::{ContainerType, IrType, PrimitiveType};
+
+/// Imports a scaffolded file of `types` needs once, before its definitions
+pub fn scaffold_header(target: &str, types: &[TypeDef]) -> Option<String> {
+    let holds = |primitive| types.iter().flat_map(|def| &def.fields).any(|field| holds(&field.ty, primitive));
+    match target {
+        "rust" => Some("use serde::{Deserialize, Serialize};\n".to_string()),
+        "gleam" => Some(format!(
+            "{}{}import gleam/dynamic/decode\nimport gleam/json\nimport gleam/option.{{type Option}}\n",
+            if holds(PrimitiveType::DateTime) { "import birl\n" } else { "" },
+            if holds(PrimitiveType::Bytes) { "import gleam/bit_array\n" } else { "" }
+        )),
+        "typescript" => Some(String::new()),
+        _ => None,
+    }
+}
+
+/// Whether `ty` holds `primitive` anywhere, for imports its codec needs
+fn holds(ty: &IrType, primitive: PrimitiveType) -> bool {
+    match ty {
+        IrType::Primitive(found) => *found == primitive,
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _))
+        | IrType::Alias(_, inner) => holds(inner, primitive),
+        IrType::Container(ContainerType::Result(a, b) | ContainerType::Map(a, b)) => holds(a, primitive) || holds(b, primitive),
+        IrType::Union(members) | IrType::Tuple(members) => members.iter().any(|member| holds(member, primitive)),
+        _ => false,
+    }
+}
+
+/// Starting definitions of one type in `target`, keeping the source's field
+/// names on the wire so the new side is compatible from its first commit;
+/// `types` are the definitions scaffolded alongside it, for finding cycles
+pub fn generate_scaffold(def: &TypeDef, types: &[TypeDef], target: &str) -> Option<String> {
+    let name = pascal_case(&def.name);
+    let doc = def.doc.as_ref().map(|doc| render_doc(doc, target, "")).unwrap_or_default();
+    let code = match target {
+        "rust" => {
+            let serde_as = def.fields.iter().any(|field| rust_serde_as(&field.ty).is_some());
+            let mut code = format!(
+                "{}#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n",
+                if serde_as { "#[serde_with::serde_as]\n" } else { "" },
+                name
+            );
+            let mut functions = String::new();
+            for field in &def.fields {
+                let ident = snake_case(&field.name);
+                if let Some(doc) = &field.doc {
+                    code.push_str(&render_doc(doc, target, "    "));
+                }
+                if ident != field.name {
+                    code.push_str(&format!("    #[serde(rename = \"{}\")]\n", field.name));
+                }
+                if let Some((attribute, function)) = rust_serde_default(&def.name, field) {
+                    code.push_str(&format!("    {}\n", attribute));
+                    functions.extend(function.map(|function| format!("\n{}", function)));
+                }
+                if let Some(adapter) = rust_serde_as(&field.ty) {
+                    code.push_str(&format!("    #[serde_as(as = \"{}\")]\n", adapter));
+                }
+                code.push_str(&format!("    pub {}: {},\n", ident, render_rust_field(&field.ty, def, types)));
+            }
+            let params: Vec<String> = def
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", snake_case(&field.name), render_rust_field(&field.ty, def, types)))
+                .collect();
+            let idents: Vec<String> = def.fields.iter().map(|field| snake_case(&field.name)).collect();
+            code.push_str(&format!(
+                "}}\n\nimpl {name} {{\n    pub fn new({}) -> Self {{\n        {name} {{ {} }}\n    }}\n}}\n",
+                params.join(", "),
+                idents.join(", ")
+            ));
+            code.push_str(&functions);
+            code
+        }
+        "gleam" => {
+            let snake = snake_case(&def.name);
+            let params: Vec<String> = def
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", snake_case(&field.name), render_type(&field.ty, target)))
+                .collect();
+            // Gleam has no field docs, so they go on the type
+            let mut code: String = def
+                .fields
+                .iter()
+                .filter_map(|field| Some(render_doc(&format!("`{}`: {}", snake_case(&field.name), field.doc.as_ref()?), target, "")))
+                .collect();
+            code.push_str(&format!("pub type {name} {{\n  {name}({})\n}}\n\n", params.join(", ")));
+            code.push_str(&format!("pub fn {snake}_to_json(value: {name}) -> json.Json {{\n  json.object([\n"));
+            for field in &def.fields {
+                let value = format!("value.{}", snake_case(&field.name));
+                code.push_str(&format!("    #(\"{}\", {}),\n", field.name, gleam_encode(&field.ty, &value)));
+            }
+            code.push_str(&format!("  ])\n}}\n\npub fn {snake}_decoder() -> decode.Decoder({name}) {{\n"));
+            for field in &def.fields {
+                // A missing defaulted field decodes to the same value the source fills in
+                let line = match field.default.as_ref().and_then(|default| render_default(default, &field.ty, target)) {
+                    Some(value) => format!("decode.optional_field(\"{}\", {}, {})", field.name, value, gleam_decoder(&field.ty)),
+                    None => format!("decode.field(\"{}\", {})", field.name, gleam_decoder(&field.ty)),
+                };
+                code.push_str(&format!("  use {} <- {}\n", snake_case(&field.name), line));
+            }
+            let labels: Vec<String> = def.fields.iter().map(|field| format!("{}:", snake_case(&field.name))).collect();
+            code.push_str(&format!("  decode.success({name}({}))\n}}\n", labels.join(", ")));
+            code
+        }
+        "typescript" => {
+            let mut code = format!("export interface {} {{\n", name);
+            for field in &def.fields {
+                if let Some(doc) = &field.doc {
+                    code.push_str(&render_doc(doc, target, "  "));
+                }
+                let optional = if field.default.is_some() { "?" } else { "" };
+                code.push_str(&format!("  {}{}: {};\n", field.name, optional, render_type(&field.ty, target)));
+            }
+            code.push_str(&format!(
+                "}}\n\nexport function is{name}(value: unknown): value is {name} {{\n  \
+                 if (typeof value !== \"object\" || value === null) return false;\n  \
+                 const v = value as Record<string, unknown>;\n  return (\n    true"
+            ));
+            for field in &def.fields {
+                let value = format!("v.{}", field.name);
+                match typescript_check(&field.ty, &value) {
+                    Some(check) if field.default.is_some() => code.push_str(&format!(" &&\n    ({} === undefined || {})", value, check)),
+                    Some(check) => code.push_str(&format!(" &&\n    {}", check)),
+                    None => {}
+                }
+            }
+            code.push_str("\n  );\n}\n");
+            code
+        }
+        _ => return None,
+    };
+    Some(doc + &code)
+}
+
+/// Gleam expression encoding `value` as JSON
+fn gleam_encode(ty: &IrType, value: &str) -> String {
+    match ty {
+        IrType::Container(ContainerType::Option(inner)) => format!("json.nullable({}, {})", value, gleam_encoder(inner)),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => {
+            format!("json.array({}, {})", value, gleam_encoder(inner))
+        }
+        _ => format!("{}({})", gleam_encoder(ty), value),
+    }
+}
+
+/// Gleam function encoding one value as JSON
+fn gleam_encoder(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "json.bool".to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal) => "json.float".to_string(),
+        IrType::Primitive(PrimitiveType::String | PrimitiveType::Uuid) => "json.string".to_string(),
+        IrType::Primitive(PrimitiveType::Unit) => "fn(_) { json.null() }".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => "fn(time) { json.string(birl.to_iso8601(time)) }".to_string(),
+        IrType::Primitive(PrimitiveType::Bytes) => "fn(bits) { json.string(bit_array.base64_encode(bits, True)) }".to_string(),
+        IrType::Primitive(_) => "json.int".to_string(),
+        IrType::Container(ContainerType::Option(_) | ContainerType::Vec(_) | ContainerType::Array(_, _)) => {
+            format!("fn(item) {{ {} }}", gleam_encode(ty, "item"))
+        }
+        IrType::Reference(name) => format!("{}_to_json", snake_case(name)),
+        // No canonical JSON shape: left for the author
+        _ => format!("fn(_) {{ todo as \"encode {}\" }}", render_type(ty, "gleam")),
+    }
+}
+
+fn gleam_decoder(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "decode.bool".to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal) => "decode.float".to_string(),
+        IrType::Primitive(PrimitiveType::String | PrimitiveType::Uuid) => "decode.string".to_string(),
+        IrType::Primitive(PrimitiveType::Unit) => "decode.success(Nil)".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => "decode.then(decode.string, fn(text) { case birl.parse(text) { \
+                                                        Ok(time) -> decode.success(time) \
+                                                        Error(_) -> decode.failure(birl.unix_epoch, \"Time\") } })"
+            .to_string(),
+        IrType::Primitive(PrimitiveType::Bytes) => "decode.then(decode.string, fn(text) { case bit_array.base64_decode(text) { \
+                                                     Ok(bits) -> decode.success(bits) \
+                                                     Error(_) -> decode.failure(<<>>, \"BitArray\") } })"
+            .to_string(),
+        IrType::Primitive(_) => "decode.int".to_string(),
+        IrType::Container(ContainerType::Option(inner)) => format!("decode.optional({})", gleam_decoder(inner)),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("decode.list({})", gleam_decoder(inner)),
+        IrType::Reference(name) => format!("{}_decoder()", snake_case(name)),
+        IrType::Any => "decode.dynamic".to_string(),
+        _ => format!("todo as \"decode {}\"", render_type(ty, "gleam")),
+    }
+}
+
+/// TypeScript condition that `value` has type `ty`; `None` when anything goes
+fn typescript_check(ty: &IrType, value: &str) -> Option<String> {
+    Some(match ty {
+        IrType::Primitive(PrimitiveType::Bool) => format!("typeof {} === \"boolean\"", value),
+        IrType::Primitive(PrimitiveType::String | PrimitiveType::Uuid | PrimitiveType::Bytes) => format!("typeof {} === \"string\"", value),
+        IrType::Primitive(PrimitiveType::Unit) => format!("{} === null", value),
+        IrType::Primitive(PrimitiveType::Decimal) => format!("typeof {} === \"string\" && /{}/.test({})", value, DECIMAL_PATTERN, value),
+        IrType::Primitive(PrimitiveType::BigInt) => format!("typeof {} === \"string\" && /{}/.test({})", value, BIGINT_PATTERN, value),
+        IrType::Primitive(PrimitiveType::DateTime) => {
+            format!("typeof {} === \"string\" && !Number.isNaN(Date.parse({}))", value, value)
+        }
+        IrType::Primitive(_) => format!("typeof {} === \"number\"", value),
+        IrType::Container(ContainerType::Option(inner)) => match typescript_check(inner, value) {
+            Some(check) => format!("({} === null || {})", value, check),
+            None => return None,
+        },
+        IrType::Container(ContainerType::Vec(inner)) => match typescript_check(inner, "item") {
+            Some(check) => format!("Array.isArray({}) && {}.every((item) => {})", value, value, check),
+            None => format!("Array.isArray({})", value),
+        },
+        // The guard restores the length the `Array<T>` type can't express
+        IrType::Container(ContainerType::Array(inner, len)) => match typescript_check(inner, "item") {
+            Some(check) => format!("Array.isArray({}) && {}.length === {} && {}.every((item) => {})", value, value, len, value, check),
+            None => format!("Array.isArray({}) && {}.length === {}", value, value, len),
+        },
+        IrType::Reference(name) => format!("is{}({})", pascal_case(name), value),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    fn user() -> TypeDef {
+        let registry = Registry::with_builtins();
+        let source = "pub struct User { pub id: i64, pub displayName: String, pub email: Option<String>, pub tags: Vec<String>, pub home: Address }";
+        registry.analyzer(Language::Rust).unwrap().analyze(source).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_gleam_scaffold_has_codec() {
+        let gleam = generate_scaffold(&user(), &[], "gleam").unwrap();
+        assert!(gleam.contains(
+            "pub type User {\n  User(id: Int, display_name: String, email: Option(String), tags: List(String), home: Address)\n}"
+        ));
+        assert!(gleam.contains("    #(\"displayName\", json.string(value.display_name)),\n"));
+        assert!(gleam.contains("    #(\"email\", json.nullable(value.email, json.string)),\n"));
+        assert!(gleam.contains("    #(\"home\", address_to_json(value.home)),\n"));
+        assert!(gleam.contains("  use tags <- decode.field(\"tags\", decode.list(decode.string))\n"));
+        assert!(gleam.contains("  decode.success(User(id:, display_name:, email:, tags:, home:))\n"));
+    }
+
+    #[test]
+    fn test_rust_and_typescript_scaffolds() {
+        let user = user();
+        let rust = generate_scaffold(&user, &[], "rust").unwrap();
+        assert!(rust.contains("    #[serde(rename = \"displayName\")]\n    pub display_name: String,\n"));
+        assert!(rust.contains("    pub fn new(id: i64, display_name: String, email: Option<String>, tags: Vec<String>, home: Address) -> Self {"));
+
+        let ts = generate_scaffold(&user, &[], "typescript").unwrap();
+        assert!(ts.contains("export function isUser(value: unknown): value is User {"));
+        assert!(ts.contains(" &&\n    (v.email === null || typeof v.email === \"string\")"));
+        assert!(ts.contains(" &&\n    Array.isArray(v.tags) && v.tags.every((item) => typeof item === \"string\")"));
+        assert!(ts.contains(" &&\n    isAddress(v.home)\n  );"));
+        assert!(generate_scaffold(&user, &[], "cobol").is_none());
+    }
+}
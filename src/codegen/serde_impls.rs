@@ -0,0 +1,717 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Hand-written serde impls for mapped types
+// Instead of deriving and trusting attributes to line up, each type gets a
+// `Serialize` and `Deserialize` written out from its mapping that read and
+// write what `#[derive]` would for the same wire: the keys the source uses,
+// `Option` fields written as `null` and read as `None` when missing,
+// defaults only where the source declares one, and the enum tagging it
+// chose. Nothing is buffered, so no particular format is assumed; the one
+// layout derive reads that these don't is an adjacently tagged positional
+// payload written before its tag, which serde itself never writes
+
+use super::recursion::render_rust_field;
+use super::{pascal_case, render_default, render_doc, render_type, rust_serde_as, snake_case};
+use crate::api::{Language, TypeDef};
+use crate::ir::{ContainerType, DefaultValue, IrField, IrType, IrVariant, VariantTagging};
+
+/// Rust definitions of `types` with their serde impls; a type the impls can't
+/// pin down is left out with a comment saying why
+pub fn generate_serde(types: &[TypeDef]) -> String {
+    let mut code = String::new();
+    for def in types {
+        code.push('\n');
+        match unsupported(def) {
+            Some(reason) => code.push_str(&format!("// `{}` is left out: {}\n", def.name, reason)),
+            None if def.aliased.is_some() => code.push_str(&render_alias(def)),
+            None if def.variants.is_empty() && positional(&def.fields) => code.push_str(&render_tuple_struct(def, types)),
+            None if def.variants.is_empty() => code.push_str(&render_struct(def, types)),
+            None => code.push_str(&render_enum(def, types)),
+        }
+    }
+    // Import only what the impls use, so the file compiles without warnings
+    let used = |candidates: &[(&str, &'static str)]| -> Vec<&'static str> {
+        candidates.iter().filter(|(marker, _)| code.contains(marker)).map(|(_, name)| *name).collect()
+    };
+    let de = used(&[
+        ("de::", "self"),
+        ("Deserialize<'de> for", "Deserialize"),
+        ("Deserialize<'de> for", "Deserializer"),
+        ("EnumAccess", "EnumAccess"),
+        ("MapAccess", "MapAccess"),
+        ("SeqAccess", "SeqAccess"),
+        ("EnumAccess", "VariantAccess"),
+        ("Visitor", "Visitor"),
+    ]);
+    let ser = used(&[
+        ("impl Serialize", "Serialize"),
+        ("serialize_struct(", "SerializeStruct"),
+        ("serialize_struct_variant(", "SerializeStructVariant"),
+        ("serialize_tuple_struct(", "SerializeTupleStruct"),
+        ("serialize_tuple_variant(", "SerializeTupleVariant"),
+        ("impl Serialize", "Serializer"),
+    ]);
+    let mut header = String::new();
+    if !de.is_empty() {
+        header.push_str(&format!("use serde::de::{{{}}};\n", de.join(", ")));
+    }
+    if !ser.is_empty() {
+        header.push_str(&format!("use serde::ser::{{{}}};\n", ser.join(", ")));
+    }
+    if code.contains("fmt::") {
+        header.push_str("use std::fmt;\n");
+    }
+    header + &code
+}
+
+/// Why `def` gets no impls, if it doesn't
+fn unsupported(def: &TypeDef) -> Option<&'static str> {
+    let fields = || def.fields.iter().chain(def.variants.iter().flat_map(|variant| &variant.fields));
+    let opaque = |field: &IrField| matches!(field.ty, IrType::Function(_, _) | IrType::TraitObject(_) | IrType::Any | IrType::Union(_));
+    if !def.type_params.is_empty() {
+        Some("generic definitions are serialized per instantiation")
+    } else if fields().any(|field| rust_serde_as(&field.ty).is_some()) {
+        Some("its bytes cross as base64, which needs `serde_with`")
+    } else if fields().any(opaque) {
+        Some("it holds values without a fixed wire form")
+    } else if matches!(def.tagging, Some(VariantTagging::Internal(_))) && def.variants.iter().any(|variant| positional(&variant.fields)) {
+        Some("an internally tagged positional payload has no object to hold the tag")
+    } else if def.language != Language::Rust && fields().any(|field| matches!(field.default, Some(DefaultValue::Computed(_)))) {
+        Some("a default computed on its side has no Rust expression to call")
+    } else if def.tagging == Some(VariantTagging::Untagged) && def.variants.iter().any(|variant| !variant.fields.is_empty()) {
+        Some("an untagged payload can only be told apart by trying each variant")
+    } else if payload_slots(def).is_none() {
+        Some("its variants give one key different types, and a payload may be read before its tag")
+    } else {
+        None
+    }
+}
+
+/// Whether `fields` are a positional payload (`0`, `1`, ...) rather than named ones
+fn positional(fields: &[IrField]) -> bool {
+    fields.first().is_some_and(|field| field.name.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Rust identifier for the field with wire key `key`
+fn ident(key: &str) -> String {
+    match key.chars().any(|c| c.is_ascii_lowercase()) {
+        true => snake_case(key),
+        false => key.to_ascii_lowercase().replace('-', "_"),
+    }
+}
+
+/// The named payload fields of every variant, one per key, which an enum
+/// reads into slots until its tag says which variant they build; `None`
+/// when two variants give a key different types
+fn payload_slots(def: &TypeDef) -> Option<Vec<&IrField>> {
+    let mut slots: Vec<&IrField> = Vec::new();
+    for field in def.variants.iter().filter(|variant| !positional(&variant.fields)).flat_map(|variant| &variant.fields) {
+        match slots.iter().find(|slot| slot.name == field.name) {
+            Some(slot) if slot.ty != field.ty => return None,
+            Some(_) => {}
+            None => slots.push(field),
+        }
+    }
+    Some(slots)
+}
+
+fn render_alias(def: &TypeDef) -> String {
+    let aliased = def.aliased.as_ref().map(|aliased| render_type(aliased, "rust")).unwrap_or_default();
+    format!("pub type {} = {};\n", pascal_case(&def.name), aliased)
+}
+
+/// The value of `field` out of its slot `slot` once the map is read: its
+/// declared default when missing, `None` for an `Option`, else an error. A
+/// computed default is the Rust expression the source declared (`default_port()`)
+fn take(field: &IrField, slot: &str) -> String {
+    let is_option = matches!(field.ty, IrType::Container(ContainerType::Option(_)));
+    match &field.default {
+        Some(DefaultValue::TypeDefault | DefaultValue::Empty) => format!("{}.unwrap_or_default()", slot),
+        Some(DefaultValue::Computed(expression)) => match expression.strip_suffix("()") {
+            Some(function) if function.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') => {
+                format!("{}.unwrap_or_else({})", slot, function)
+            }
+            _ => format!("{}.unwrap_or_else(|| {})", slot, expression),
+        },
+        Some(DefaultValue::Null) | None if is_option => format!("{}.flatten()", slot),
+        Some(default @ DefaultValue::String(_)) => match render_default(default, &field.ty, "rust") {
+            Some(value) => format!("{}.unwrap_or_else(|| {})", slot, value),
+            None => format!("{}.unwrap_or_default()", slot),
+        },
+        Some(default) => match render_default(default, &field.ty, "rust") {
+            Some(value) => format!("{}.unwrap_or({})", slot, value),
+            None => format!("{}.unwrap_or_default()", slot),
+        },
+        None => format!("{}.ok_or_else(|| de::Error::missing_field(\"{}\"))?", slot, field.name),
+    }
+}
+
+/// Field declarations, `pub` on a struct and bare in an enum variant
+fn render_fields(fields: &[IrField], def: &TypeDef, types: &[TypeDef], indent: &str) -> String {
+    let visibility = if indent.len() > 4 { "" } else { "pub " };
+    let mut code = String::new();
+    for field in fields {
+        if let Some(doc) = &field.doc {
+            code.push_str(&render_doc(doc, "rust", indent));
+        }
+        code.push_str(&format!("{}{}{}: {},\n", indent, visibility, ident(&field.name), render_rust_field(&field.ty, def, types)));
+    }
+    code
+}
+
+/// The types of a positional payload, as the parenthesized list after its name
+fn render_positional(fields: &[IrField], def: &TypeDef, types: &[TypeDef], visibility: &str) -> String {
+    let members: Vec<String> = fields.iter().map(|field| format!("{}{}", visibility, render_rust_field(&field.ty, def, types))).collect();
+    format!("({})", members.join(", "))
+}
+
+/// The opening of a `Deserialize` impl driving the visitor `{name}Visitor`,
+/// up to its `visit_*` methods
+fn visitor_head(name: &str) -> String {
+    format!(
+        "impl<'de> Deserialize<'de> for {name} {{\n    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{\n        \
+         struct {name}Visitor;\n\n        \
+         impl<'de> Visitor<'de> for {name}Visitor {{\n            type Value = {name};\n\n            \
+         fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {{\n                formatter.write_str(\"a `{name}`\")\n            }}\n"
+    )
+}
+
+fn render_struct(def: &TypeDef, types: &[TypeDef]) -> String {
+    let name = pascal_case(&def.name);
+    let doc = def.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut code = format!("{doc}#[derive(Debug, Clone, PartialEq)]\npub struct {name} {{\n{}}}\n\n", render_fields(&def.fields, def, types, "    "));
+
+    code.push_str(&format!(
+        "impl Serialize for {name} {{\n    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n        \
+         let mut state = serializer.serialize_struct(\"{name}\", {})?;\n",
+        def.fields.len()
+    ));
+    for field in &def.fields {
+        code.push_str(&format!("        state.serialize_field(\"{}\", &self.{})?;\n", field.name, ident(&field.name)));
+    }
+    code.push_str("        state.end()\n    }\n}\n\n");
+
+    let slots: String = def.fields.iter().map(|field| format!("                let mut {} = None;\n", ident(&field.name))).collect();
+    let arms: String = def
+        .fields
+        .iter()
+        .map(|field| {
+            format!(
+                "                        \"{key}\" if {ident}.is_some() => return Err(de::Error::duplicate_field(\"{key}\")),\n                        \
+                 \"{key}\" => {ident} = Some(map.next_value()?),\n",
+                key = field.name,
+                ident = ident(&field.name)
+            )
+        })
+        .collect();
+    let inits: String = def
+        .fields
+        .iter()
+        .map(|field| format!("                    {}: {},\n", ident(&field.name), take(field, &ident(&field.name))))
+        .collect();
+    let keys: Vec<String> = def.fields.iter().map(|field| format!("\"{}\"", field.name)).collect();
+    code.push_str(&visitor_head(&name));
+    code.push_str(&format!(
+        "\n            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<{name}, A::Error> {{\n{slots}                \
+         while let Some(key) = map.next_key::<String>()? {{\n                    match key.as_str() {{\n{arms}                        \
+         _ => {{\n                            map.next_value::<de::IgnoredAny>()?;\n                        }}\n                    }}\n                }}\n                \
+         Ok({name} {{\n{inits}                }})\n            }}\n        }}\n\n        \
+         deserializer.deserialize_struct(\"{name}\", &[{}], {name}Visitor)\n    }}\n}}\n",
+        keys.join(", ")
+    ));
+    code
+}
+
+/// A tuple struct, which serde writes as its one field or as a sequence
+fn render_tuple_struct(def: &TypeDef, types: &[TypeDef]) -> String {
+    let name = pascal_case(&def.name);
+    let doc = def.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut code = format!("{doc}#[derive(Debug, Clone, PartialEq)]\npub struct {name}{};\n\n", render_positional(&def.fields, def, types, "pub "));
+    let len = def.fields.len();
+    let serialize = match len {
+        1 => format!("        serializer.serialize_newtype_struct(\"{name}\", &self.0)\n"),
+        _ => {
+            let fields: String = (0..len).map(|i| format!("        state.serialize_field(&self.{})?;\n", i)).collect();
+            format!("        let mut state = serializer.serialize_tuple_struct(\"{name}\", {len})?;\n{fields}        state.end()\n")
+        }
+    };
+    code.push_str(&format!(
+        "impl Serialize for {name} {{\n    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n{serialize}    }}\n}}\n\n"
+    ));
+    code.push_str(&visitor_head(&name));
+    match len {
+        1 => code.push_str(&format!(
+            "\n            fn visit_newtype_struct<E: Deserializer<'de>>(self, deserializer: E) -> Result<{name}, E::Error> {{\n                \
+             Deserialize::deserialize(deserializer).map({name})\n            }}\n        }}\n\n        \
+             deserializer.deserialize_newtype_struct(\"{name}\", {name}Visitor)\n    }}\n}}\n"
+        )),
+        _ => {
+            let elements: String = (0..len)
+                .map(|i| format!("                    seq.next_element()?.ok_or_else(|| de::Error::invalid_length({}, &self))?,\n", i))
+                .collect();
+            code.push_str(&format!(
+                "\n            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<{name}, A::Error> {{\n                \
+                 Ok({name}(\n{elements}                ))\n            }}\n        }}\n\n        \
+                 deserializer.deserialize_tuple_struct(\"{name}\", {len}, {name}Visitor)\n    }}\n}}\n"
+            ))
+        }
+    }
+    code
+}
+
+/// Wire tag of a variant
+fn tag(variant: &IrVariant) -> &str {
+    variant.tag.as_deref().unwrap_or(&variant.name)
+}
+
+/// Bindings for a positional payload's values, `f0`, `f1`, ...
+fn bindings(variant: &IrVariant) -> Vec<String> {
+    (0..variant.fields.len()).map(|i| format!("f{}", i)).collect()
+}
+
+/// `Ok(...)` building a unit or named `variant` of `name` from the slots in
+/// `fields`, its lines indented by `indent`
+fn build(name: &str, variant: &IrVariant, indent: &str) -> String {
+    let rust = pascal_case(&variant.name);
+    if variant.fields.is_empty() {
+        return format!("Ok({}::{})", name, rust);
+    }
+    let inits: String = variant
+        .fields
+        .iter()
+        .map(|field| format!("{}    {}: {},\n", indent, ident(&field.name), take(field, &format!("fields.{}", ident(&field.name)))))
+        .collect();
+    format!("Ok({}::{} {{\n{}{}}})", name, rust, inits, indent)
+}
+
+fn render_enum(def: &TypeDef, types: &[TypeDef]) -> String {
+    let name = pascal_case(&def.name);
+    let tagging = def.tagging.clone().unwrap_or(VariantTagging::External);
+    let doc = def.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut code = format!("{doc}#[derive(Debug, Clone, PartialEq)]\npub enum {name} {{\n");
+    for variant in &def.variants {
+        let rust = pascal_case(&variant.name);
+        match variant.fields.is_empty() {
+            true => code.push_str(&format!("    {},\n", rust)),
+            false if positional(&variant.fields) => code.push_str(&format!("    {}{},\n", rust, render_positional(&variant.fields, def, types, ""))),
+            false => code.push_str(&format!("    {} {{\n{}    }},\n", rust, render_fields(&variant.fields, def, types, "        "))),
+        }
+    }
+    code.push_str("}\n\n");
+    code.push_str(&render_enum_serialize(def, types, &name, &tagging));
+    let slots = payload_slots(def).unwrap_or_default();
+    if !slots.is_empty() {
+        code.push_str(&render_payload_slots(def, types, &name, &slots, &tagging));
+    }
+    code.push_str(&render_enum_deserialize(def, &name, &tagging, !slots.is_empty()));
+    code
+}
+
+/// A unit variant crosses as its bare tag under external tagging, and
+/// whatever the tagging when the source compiles it that way
+fn bare(def: &TypeDef, variant: &IrVariant, tagging: &VariantTagging) -> bool {
+    variant.fields.is_empty() && (def.bare_units || *tagging == VariantTagging::External)
+}
+
+fn render_enum_serialize(def: &TypeDef, types: &[TypeDef], name: &str, tagging: &VariantTagging) -> String {
+    let mut code = format!(
+        "impl Serialize for {name} {{\n    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n        match self {{\n"
+    );
+    for (index, variant) in def.variants.iter().enumerate() {
+        let rust = pascal_case(&variant.name);
+        let tag = tag(variant);
+        let len = variant.fields.len();
+        let is_positional = positional(&variant.fields);
+        let idents: Vec<String> = match is_positional {
+            true => bindings(variant),
+            false => variant.fields.iter().map(|field| ident(&field.name)).collect(),
+        };
+        let pattern = match (idents.is_empty(), is_positional) {
+            (true, _) => format!("{}::{}", name, rust),
+            (false, true) => format!("{}::{}({})", name, rust, idents.join(", ")),
+            (false, false) => format!("{}::{} {{ {} }}", name, rust, idents.join(", ")),
+        };
+        let fields = |state: &str| -> String {
+            variant
+                .fields
+                .iter()
+                .zip(&idents)
+                .map(|(field, ident)| format!("                {}.serialize_field(\"{}\", {})?;\n", state, field.name, ident))
+                .collect()
+        };
+        let body = match tagging {
+            VariantTagging::External if variant.fields.is_empty() => format!("serializer.serialize_unit_variant(\"{name}\", {index}, \"{tag}\")"),
+            _ if bare(def, variant, tagging) => format!("serializer.serialize_str(\"{}\")", tag),
+            VariantTagging::Untagged => "serializer.serialize_unit()".to_string(),
+            VariantTagging::External if is_positional && len == 1 => {
+                format!("serializer.serialize_newtype_variant(\"{name}\", {index}, \"{tag}\", f0)")
+            }
+            VariantTagging::External if is_positional => {
+                let elements: String = idents.iter().map(|ident| format!("                state.serialize_field({})?;\n", ident)).collect();
+                format!(
+                    "{{\n                let mut state = serializer.serialize_tuple_variant(\"{name}\", {index}, \"{tag}\", {len})?;\n{elements}                \
+                     state.end()\n            }}"
+                )
+            }
+            VariantTagging::External => format!(
+                "{{\n                let mut state = serializer.serialize_struct_variant(\"{name}\", {index}, \"{tag}\", {len})?;\n{}                \
+                 state.end()\n            }}",
+                fields("state")
+            ),
+            VariantTagging::Internal(key) => format!(
+                "{{\n                let mut state = serializer.serialize_struct(\"{name}\", {})?;\n                \
+                 state.serialize_field(\"{key}\", \"{tag}\")?;\n{}                state.end()\n            }}",
+                len + 1,
+                fields("state")
+            ),
+            VariantTagging::Adjacent(key, _) if variant.fields.is_empty() => format!(
+                "{{\n                let mut state = serializer.serialize_struct(\"{name}\", 1)?;\n                \
+                 state.serialize_field(\"{key}\", \"{tag}\")?;\n                state.end()\n            }}"
+            ),
+            VariantTagging::Adjacent(key, content) => {
+                // The content is the one value, a tuple of the values, or a
+                // struct borrowing the named fields
+                let (local, value) = match (is_positional, len) {
+                    (true, 1) => (String::new(), "f0".to_string()),
+                    (true, _) => (String::new(), format!("&({})", idents.join(", "))),
+                    (false, _) => {
+                        let members: String = variant
+                            .fields
+                            .iter()
+                            .map(|field| format!("                    {}: &'a {},\n", ident(&field.name), render_rust_field(&field.ty, def, types)))
+                            .collect();
+                        let local = format!(
+                            "                struct Content<'a> {{\n{members}                }}\n\n                \
+                             impl Serialize for Content<'_> {{\n                    \
+                             fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n                        \
+                             let mut state = serializer.serialize_struct(\"{rust}\", {len})?;\n{}                        \
+                             state.end()\n                    }}\n                }}\n\n",
+                            variant
+                                .fields
+                                .iter()
+                                .map(|field| format!("                        state.serialize_field(\"{}\", self.{})?;\n", field.name, ident(&field.name)))
+                                .collect::<String>()
+                        );
+                        (local, format!("&Content {{ {} }}", idents.join(", ")))
+                    }
+                };
+                format!(
+                    "{{\n{local}                let mut state = serializer.serialize_struct(\"{name}\", 2)?;\n                \
+                     state.serialize_field(\"{key}\", \"{tag}\")?;\n                state.serialize_field(\"{content}\", {value})?;\n                \
+                     state.end()\n            }}"
+                )
+            }
+        };
+        // A block arm takes no comma
+        let comma = if body.ends_with('}') { "" } else { "," };
+        code.push_str(&format!("            {} => {}{}\n", pattern, body, comma));
+    }
+    code.push_str("        }\n    }\n}\n\n");
+    code
+}
+
+/// The slots `{name}Fields` holding every named payload field, with a
+/// visitor reading one nested payload when the tagging nests it
+fn render_payload_slots(def: &TypeDef, types: &[TypeDef], name: &str, slots: &[&IrField], tagging: &VariantTagging) -> String {
+    let members: String = slots
+        .iter()
+        .map(|field| format!("    {}: Option<{}>,\n", ident(&field.name), render_rust_field(&field.ty, def, types)))
+        .collect();
+    let arms: String = slots
+        .iter()
+        .map(|field| {
+            format!(
+                "            \"{key}\" if self.{ident}.is_some() => return Err(de::Error::duplicate_field(\"{key}\")),\n            \
+                 \"{key}\" => self.{ident} = Some(map.next_value()?),\n",
+                key = field.name,
+                ident = ident(&field.name)
+            )
+        })
+        .collect();
+    let mut code = format!(
+        "// Payload fields of every `{name}` variant, held until the tag says which they build\n\
+         #[derive(Default)]\nstruct {name}Fields {{\n{members}}}\n\n\
+         impl {name}Fields {{\n    \
+         fn read<'de, A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {{\n        \
+         match key {{\n{arms}            _ => return Ok(false),\n        }}\n        Ok(true)\n    }}\n}}\n\n"
+    );
+    if matches!(tagging, VariantTagging::External | VariantTagging::Adjacent(_, _)) {
+        code.push_str(&format!(
+            "struct {name}FieldsVisitor;\n\n\
+             impl<'de> Visitor<'de> for {name}FieldsVisitor {{\n    type Value = {name}Fields;\n\n    \
+             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {{\n        formatter.write_str(\"a `{name}` payload\")\n    }}\n\n    \
+             fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<{name}Fields, A::Error> {{\n        \
+             let mut fields = {name}Fields::default();\n        \
+             while let Some(key) = map.next_key::<String>()? {{\n            \
+             if !fields.read(&key, &mut map)? {{\n                map.next_value::<de::IgnoredAny>()?;\n            }}\n        }}\n        \
+             Ok(fields)\n    }}\n}}\n\n"
+        ));
+    }
+    if matches!(tagging, VariantTagging::Adjacent(_, _)) {
+        code.push_str(&format!(
+            "impl<'de> Deserialize<'de> for {name}Fields {{\n    \
+             fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{\n        \
+             deserializer.deserialize_map({name}FieldsVisitor)\n    }}\n}}\n\n"
+        ));
+    }
+    code
+}
+
+fn render_enum_deserialize(def: &TypeDef, name: &str, tagging: &VariantTagging, has_slots: bool) -> String {
+    let unknown = "other => Err(de::Error::unknown_variant(other, VARIANTS)),\n";
+    let mut methods = String::new();
+    let bare_variants: Vec<&IrVariant> = def.variants.iter().filter(|variant| bare(def, variant, tagging)).collect();
+    if *tagging != VariantTagging::External && !bare_variants.is_empty() {
+        let arms: String = bare_variants.iter().map(|variant| format!("                    \"{}\" => {},\n", tag(variant), build(name, variant, ""))).collect();
+        methods.push_str(&format!(
+            "\n            fn visit_str<E: de::Error>(self, tag: &str) -> Result<{name}, E> {{\n                \
+             match tag {{\n{arms}                    {unknown}                }}\n            }}\n"
+        ));
+    }
+    match tagging {
+        VariantTagging::Untagged => {
+            // Only unit variants get here; the first that isn't bare reads `null`
+            if let Some(variant) = def.variants.iter().find(|variant| !bare(def, variant, tagging)) {
+                methods.push_str(&format!(
+                    "\n            fn visit_unit<E: de::Error>(self) -> Result<{name}, E> {{\n                {}\n            }}\n",
+                    build(name, variant, "")
+                ));
+            }
+        }
+        VariantTagging::External => {
+            let arms: String = def
+                .variants
+                .iter()
+                .map(|variant| {
+                    let tag = tag(variant);
+                    let rust = pascal_case(&variant.name);
+                    match variant.fields.len() {
+                        0 => format!("                    \"{tag}\" => {{\n                        variant.unit_variant()?;\n                        Ok({name}::{rust})\n                    }}\n"),
+                        1 if positional(&variant.fields) => format!("                    \"{tag}\" => Ok({name}::{rust}(variant.newtype_variant()?)),\n"),
+                        _ if positional(&variant.fields) => {
+                            let bindings = bindings(variant).join(", ");
+                            format!(
+                                "                    \"{tag}\" => {{\n                        let ({bindings}) = variant.newtype_variant()?;\n                        \
+                                 Ok({name}::{rust}({bindings}))\n                    }}\n"
+                            )
+                        }
+                        _ => {
+                            let keys: Vec<String> = variant.fields.iter().map(|field| format!("\"{}\"", field.name)).collect();
+                            format!(
+                                "                    \"{tag}\" => {{\n                        let fields = variant.struct_variant(&[{}], {name}FieldsVisitor)?;\n                        \
+                                 {}\n                    }}\n",
+                                keys.join(", "),
+                                build(name, variant, "                        ")
+                            )
+                        }
+                    }
+                })
+                .collect();
+            methods.push_str(&format!(
+                "\n            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<{name}, A::Error> {{\n                \
+                 let (tag, variant) = data.variant::<String>()?;\n                \
+                 match tag.as_str() {{\n{arms}                    {unknown}                }}\n            }}\n"
+            ));
+        }
+        VariantTagging::Internal(key) => {
+            let other = match has_slots {
+                true => "                        other => {\n                            if !fields.read(other, &mut map)? {\n                                \
+                         map.next_value::<de::IgnoredAny>()?;\n                            }\n                        }\n",
+                false => "                        _ => {\n                            map.next_value::<de::IgnoredAny>()?;\n                        }\n",
+            };
+            let slots = match has_slots {
+                true => format!("                let mut fields = {name}Fields::default();\n"),
+                false => String::new(),
+            };
+            let arms: String = def
+                .variants
+                .iter()
+                .filter(|variant| !bare(def, variant, tagging))
+                .map(|variant| format!("                    \"{}\" => {},\n", tag(variant), build(name, variant, "                    ")))
+                .collect();
+            methods.push_str(&format!(
+                "\n            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<{name}, A::Error> {{\n                \
+                 let mut tag: Option<String> = None;\n{slots}                \
+                 while let Some(key) = map.next_key::<String>()? {{\n                    match key.as_str() {{\n                        \
+                 \"{key}\" if tag.is_some() => return Err(de::Error::duplicate_field(\"{key}\")),\n                        \
+                 \"{key}\" => tag = Some(map.next_value()?),\n{other}                    }}\n                }}\n                \
+                 match tag.ok_or_else(|| de::Error::missing_field(\"{key}\"))?.as_str() {{\n{arms}                    {unknown}                }}\n            }}\n"
+            ));
+        }
+        VariantTagging::Adjacent(key, content) => {
+            // A positional payload is read as soon as its tag is known; a
+            // named one reads the same whichever variant it turns out to be,
+            // so it may come first
+            let tagged: Vec<&IrVariant> = def.variants.iter().filter(|variant| !bare(def, variant, tagging)).collect();
+            let early_arms: String = tagged
+                .iter()
+                .filter(|variant| variant.fields.is_empty() || positional(&variant.fields))
+                .map(|variant| {
+                    let tag = tag(variant);
+                    let rust = pascal_case(&variant.name);
+                    match variant.fields.len() {
+                        0 => format!(
+                            "                                Some(\"{tag}\") => {{\n                                    map.next_value::<de::IgnoredAny>()?;\n                                }}\n"
+                        ),
+                        1 => format!("                                Some(\"{tag}\") => value = Some({name}::{rust}(map.next_value()?)),\n"),
+                        _ => {
+                            let bindings = bindings(variant).join(", ");
+                            format!(
+                                "                                Some(\"{tag}\") => {{\n                                    let ({bindings}) = map.next_value()?;\n                                    \
+                                 value = Some({name}::{rust}({bindings}));\n                                }}\n"
+                            )
+                        }
+                    }
+                })
+                .collect();
+            let early_rest = match has_slots {
+                true => "                                _ => fields = Some(map.next_value()?),\n".to_string(),
+                false => format!("                                _ => return Err(de::Error::custom(\"the content of a `{name}` variant must follow its tag\")),\n"),
+            };
+            let slots = match has_slots {
+                true => format!("                let mut fields: Option<{name}Fields> = None;\n"),
+                false => String::new(),
+            };
+            let take_slots = match has_slots {
+                true => "                let fields = fields.unwrap_or_default();\n",
+                false => "",
+            };
+            let units: String = tagged
+                .iter()
+                .filter(|variant| variant.fields.is_empty())
+                .map(|variant| format!("                    \"{}\" => {},\n", tag(variant), build(name, variant, "")))
+                .collect();
+            let with_content: Vec<String> = tagged.iter().filter(|variant| !variant.fields.is_empty()).map(|variant| format!("\"{}\"", tag(variant))).collect();
+            let missing = match with_content.is_empty() {
+                true => String::new(),
+                false => format!("                    {} if !content => Err(de::Error::missing_field(\"{content}\")),\n", with_content.join(" | ")),
+            };
+            let named: String = tagged
+                .iter()
+                .filter(|variant| !variant.fields.is_empty() && !positional(&variant.fields))
+                .map(|variant| format!("                    \"{}\" => {},\n", tag(variant), build(name, variant, "                    ")))
+                .collect();
+            let late: Vec<String> = tagged.iter().filter(|variant| positional(&variant.fields)).map(|variant| format!("\"{}\"", tag(variant))).collect();
+            let late = match late.is_empty() {
+                true => String::new(),
+                false => format!(
+                    "                    {} => Err(de::Error::custom(\"the content of a `{name}` variant must follow its tag\")),\n",
+                    late.join(" | ")
+                ),
+            };
+            methods.push_str(&format!(
+                "\n            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<{name}, A::Error> {{\n                \
+                 let mut tag: Option<String> = None;\n                let mut content = false;\n{slots}                \
+                 let mut value: Option<{name}> = None;\n                \
+                 while let Some(key) = map.next_key::<String>()? {{\n                    match key.as_str() {{\n                        \
+                 \"{key}\" if tag.is_some() => return Err(de::Error::duplicate_field(\"{key}\")),\n                        \
+                 \"{key}\" => tag = Some(map.next_value()?),\n                        \
+                 \"{content}\" if content => return Err(de::Error::duplicate_field(\"{content}\")),\n                        \
+                 \"{content}\" => {{\n                            content = true;\n                            \
+                 match tag.as_deref() {{\n{early_arms}{early_rest}                            }}\n                        }}\n                        \
+                 _ => {{\n                            map.next_value::<de::IgnoredAny>()?;\n                        }}\n                    }}\n                }}\n                \
+                 if let Some(value) = value {{\n                    return Ok(value);\n                }}\n{take_slots}                \
+                 match tag.ok_or_else(|| de::Error::missing_field(\"{key}\"))?.as_str() {{\n{units}{missing}{named}{late}                    {unknown}                }}\n            }}\n"
+            ));
+        }
+    }
+    let tags: Vec<String> = def.variants.iter().map(|variant| format!("\"{}\"", tag(variant))).collect();
+    let variants = match methods.contains("VARIANTS") || *tagging == VariantTagging::External {
+        true => format!("        const VARIANTS: &[&str] = &[{}];\n", tags.join(", ")),
+        false => String::new(),
+    };
+    let drive = match tagging {
+        VariantTagging::External => format!("deserializer.deserialize_enum(\"{name}\", VARIANTS, {name}Visitor)"),
+        _ => format!("deserializer.deserialize_any({name}Visitor)"),
+    };
+    let head = visitor_head(name);
+    let (open, rest) = head.split_once("        struct").unwrap_or((&head, ""));
+    format!("{open}{variants}        struct{rest}{methods}        }}\n\n        {drive}\n    }}\n}}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    #[test]
+    fn test_struct_impls_follow_the_mapped_keys() {
+        let types = Registry::with_builtins()
+            .analyzer(Language::ReScript)
+            .unwrap()
+            .analyze("type user = { id: int, displayName: string, email: option<string> }")
+            .unwrap();
+        let code = generate_serde(&types);
+        assert!(code.starts_with("use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};\nuse serde::ser::{Serialize, SerializeStruct, Serializer};\nuse std::fmt;\n"));
+        assert!(code.contains("#[derive(Debug, Clone, PartialEq)]\npub struct User {\n    pub id: i64,\n    pub display_name: String,\n    pub email: Option<String>,\n}\n"));
+        assert!(code.contains("        state.serialize_field(\"displayName\", &self.display_name)?;\n"));
+        assert!(code.contains("                        \"displayName\" => display_name = Some(map.next_value()?),\n"));
+        // Written as `null`, and `None` when left out
+        assert!(code.contains("        state.serialize_field(\"email\", &self.email)?;\n"));
+        assert!(code.contains("                    email: email.flatten(),\n"));
+        assert!(code.contains("                    id: id.ok_or_else(|| de::Error::missing_field(\"id\"))?,\n"));
+        assert!(code.contains("deserializer.deserialize_struct(\"User\", &[\"id\", \"displayName\", \"email\"], UserVisitor)"));
+    }
+
+    #[test]
+    fn test_enum_impls_follow_the_tagging() {
+        let types = Registry::with_builtins()
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze(
+                "#[serde(tag = \"kind\")]\npub enum Shape { Circle { radius: f64 }, #[serde(rename = \"none\")] Empty }\n\
+                 pub enum Status { Active, Suspended { reason: String } }\n\
+                 pub enum Pair { Both(i32, i32) }\n#[serde(tag = \"kind\")]\npub enum Cell { Value(f64) }\n\
+                 #[serde(tag = \"kind\")]\npub enum Clash { A { x: i32 }, B { x: String } }",
+            )
+            .unwrap();
+        let code = generate_serde(&types);
+        assert!(!code.contains("serde_json"));
+        assert!(code.contains(
+            "            Shape::Circle { radius } => {\n                let mut state = serializer.serialize_struct(\"Shape\", 2)?;\n                \
+             state.serialize_field(\"kind\", \"Circle\")?;\n                state.serialize_field(\"radius\", radius)?;\n"
+        ));
+        // The tag may follow the fields, so they wait in slots
+        assert!(code.contains("                        other => {\n                            if !fields.read(other, &mut map)? {\n"));
+        assert!(code.contains("                match tag.ok_or_else(|| de::Error::missing_field(\"kind\"))?.as_str() {\n"));
+        assert!(code.contains("                    \"none\" => Ok(Shape::Empty),\n"));
+        assert!(code.contains("            Status::Active => serializer.serialize_unit_variant(\"Status\", 0, \"Active\"),\n"));
+        assert!(code.contains("serializer.serialize_struct_variant(\"Status\", 1, \"Suspended\", 1)?;\n"));
+        assert!(code.contains("pub enum Pair {\n    Both(i32, i32),\n}\n"));
+        assert!(code.contains("            Pair::Both(f0, f1) => {\n                let mut state = serializer.serialize_tuple_variant(\"Pair\", 0, \"Both\", 2)?;\n"));
+        assert!(code.contains("// `Cell` is left out: an internally tagged positional payload has no object to hold the tag\n"));
+        assert!(code.contains("// `Clash` is left out: its variants give one key different types"));
+    }
+
+    #[test]
+    fn test_serde_renames_name_the_wire_keys() {
+        let types = Registry::with_builtins()
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze(
+                "#[serde(rename_all = \"camelCase\")]\npub struct User { pub user_id: u64, #[serde(rename = \"mail\")] pub email: String }\n\
+                 #[serde(rename_all = \"kebab-case\", rename_all_fields = \"SCREAMING_SNAKE_CASE\")]\n\
+                 pub enum Event { KeyDown { key_code: u32 }, #[serde(rename_all = \"PascalCase\")] MouseUp { button_id: u8 } }",
+            )
+            .unwrap();
+        let code = generate_serde(&types);
+        assert!(code.contains("        state.serialize_field(\"userId\", &self.user_id)?;\n        state.serialize_field(\"mail\", &self.mail)?;\n"));
+        assert!(code.contains("serialize_struct_variant(\"Event\", 0, \"key-down\", 1)?;\n                state.serialize_field(\"KEY_CODE\", key_code)?;\n"));
+        assert!(code.contains("                state.serialize_field(\"ButtonId\", button_id)?;\n"));
+    }
+
+    #[test]
+    fn test_computed_defaults_call_the_declared_function() {
+        let registry = Registry::with_builtins();
+        let types = registry
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze("pub struct Retry { #[serde(default = \"default_delay\")] pub delay: u32, #[serde(default)] pub attempts: u8 }")
+            .unwrap();
+        let code = generate_serde(&types);
+        assert!(code.contains("                    delay: delay.unwrap_or_else(default_delay),\n"));
+        assert!(code.contains("                    attempts: attempts.unwrap_or_default(),\n"));
+        let source = "type t = {retries: int}\nlet make = (~retries=Config.retries, ()) => {retries}";
+        let types = registry.analyzer(Language::ReScript).unwrap().analyze(source).unwrap();
+        assert!(generate_serde(&types).contains("// `t` is left out: a default computed on its side has no Rust expression to call\n"));
+    }
+}
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Length-parameterized struct bindings
+// A Rust struct generic over an array length (`Matrix<const N: usize>`) keeps
+// that parameter in targets whose types can carry one: a parametric `struct`
+// in Julia, a `comptime` type function in Zig. Other targets get the struct
+// once `TypeDef::instantiate_consts` has fixed the length
+
+use super::pascal_case;
+use crate::api::TypeDef;
+use crate::ir::{ContainerType, IrType, PrimitiveType};
+
+/// Julia or Zig struct for `def` with its type and const parameters kept,
+/// a Zig type function when it has any; `None` for other targets and for
+/// definitions that aren't structs
+pub fn generate_sized_binding(def: &TypeDef, target: &str) -> Option<String> {
+    if !def.variants.is_empty() || def.aliased.is_some() {
+        return None;
+    }
+    let render: fn(&IrType, Option<&str>) -> String = match target {
+        "julia" => julia_type,
+        "zig" => zig_type,
+        _ => return None,
+    };
+    let name = pascal_case(&def.name);
+    let fields = def.fields.iter().map(|field| {
+        let length = def.sized_fields.iter().find(|(sized, _)| *sized == field.name).map(|(_, param)| param.as_str());
+        (&field.name, render(&field.ty, length))
+    });
+    match target {
+        "julia" => {
+            let params: Vec<&str> = def.type_params.iter().chain(def.const_params.iter().map(|(param, _)| param)).map(String::as_str).collect();
+            let mut code = match params.is_empty() {
+                true => format!("struct {}\n", name),
+                false => format!("struct {}{{{}}}\n", name, params.join(", ")),
+            };
+            for (field, ty) in fields {
+                code.push_str(&format!("    {}::{}\n", field, ty));
+            }
+            code.push_str("end\n");
+            Some(code)
+        }
+        _ => {
+            let params: Vec<String> = def
+                .type_params
+                .iter()
+                .map(|param| format!("comptime {}: type", param))
+                // Zig spells Rust's integer and `bool` types the same
+                .chain(def.const_params.iter().map(|(param, ty)| format!("comptime {}: {}", param, ty)))
+                .collect();
+            // Without parameters there is no type function to call
+            if params.is_empty() {
+                let mut code = format!("pub const {} = struct {{\n", name);
+                for (field, ty) in fields {
+                    code.push_str(&format!("    {}: {},\n", field, ty));
+                }
+                code.push_str("};\n");
+                return Some(code);
+            }
+            let mut code = format!("pub fn {}({}) type {{\n    return struct {{\n", name, params.join(", "));
+            for (field, ty) in fields {
+                code.push_str(&format!("        {}: {},\n", field, ty));
+            }
+            code.push_str("    };\n}\n");
+            Some(code)
+        }
+    }
+}
+
+/// Julia type of `ty`; with `length`, its list is a `SVector` of that many items
+fn julia_type(ty: &IrType, length: Option<&str>) -> String {
+    let render = |inner: &IrType| julia_type(inner, None);
+    // StaticArrays keeps the length in the type
+    if let (IrType::Container(ContainerType::Vec(inner)), Some(length)) = (ty, length) {
+        return format!("SVector{{{}, {}}}", length, render(inner));
+    }
+    match ty {
+        IrType::Container(ContainerType::Option(inner)) => format!("Union{{Nothing, {}}}", julia_type(inner, length)),
+        IrType::Primitive(primitive) => match primitive {
+            PrimitiveType::Bool => "Bool",
+            PrimitiveType::I8 => "Int8",
+            PrimitiveType::I16 => "Int16",
+            PrimitiveType::I32 => "Int32",
+            PrimitiveType::I64 => "Int64",
+            PrimitiveType::I128 => "Int128",
+            PrimitiveType::U8 => "UInt8",
+            PrimitiveType::U16 => "UInt16",
+            PrimitiveType::U32 => "UInt32",
+            PrimitiveType::U64 => "UInt64",
+            PrimitiveType::U128 => "UInt128",
+            PrimitiveType::F32 => "Float32",
+            PrimitiveType::F64 => "Float64",
+            PrimitiveType::String => "String",
+            PrimitiveType::Unit => "Nothing",
+            PrimitiveType::DateTime => "DateTime",
+            PrimitiveType::Uuid => "UUID",
+            // Decimals.jl; `BigInt` is built in
+            PrimitiveType::Decimal => "Decimal",
+            PrimitiveType::BigInt => "BigInt",
+            PrimitiveType::Bytes => "Vector{UInt8}",
+        }
+        .to_string(),
+        IrType::Container(ContainerType::Vec(inner)) => format!("Vector{{{}}}", render(inner)),
+        IrType::Container(ContainerType::Array(inner, len)) => format!("SVector{{{}, {}}}", len, render(inner)),
+        IrType::Container(ContainerType::Result(ok, err)) => format!("Union{{Ok{{{}}}, Err{{{}}}}}", render(ok), render(err)),
+        IrType::Container(ContainerType::Map(key, value)) => format!("Dict{{{}, {}}}", render(key), render(value)),
+        IrType::Reference(name) | IrType::Alias(name, _) => pascal_case(name),
+        IrType::Generic(name, args) => format!("{}{{{}}}", pascal_case(name), args.iter().map(render).collect::<Vec<_>>().join(", ")),
+        IrType::TypeParam(name) => name.clone(),
+        IrType::Any | IrType::TraitObject(_) => "Any".to_string(),
+        IrType::Function(_, _) => "Function".to_string(),
+        IrType::Union(members) => format!("Union{{{}}}", members.iter().map(render).collect::<Vec<_>>().join(", ")),
+        IrType::Tuple(members) => format!("Tuple{{{}}}", members.iter().map(render).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+/// Zig type of `ty`; with `length`, its list is an array of that many items
+fn zig_type(ty: &IrType, length: Option<&str>) -> String {
+    let render = |inner: &IrType| zig_type(inner, None);
+    if let (IrType::Container(ContainerType::Vec(inner)), Some(length)) = (ty, length) {
+        return format!("[{}]{}", length, render(inner));
+    }
+    match ty {
+        IrType::Container(ContainerType::Option(inner)) => format!("?{}", zig_type(inner, length)),
+        IrType::Primitive(primitive) => match primitive {
+            PrimitiveType::Bool => "bool",
+            PrimitiveType::I8 => "i8",
+            PrimitiveType::I16 => "i16",
+            PrimitiveType::I32 => "i32",
+            PrimitiveType::I64 => "i64",
+            PrimitiveType::I128 => "i128",
+            PrimitiveType::U8 => "u8",
+            PrimitiveType::U16 => "u16",
+            PrimitiveType::U32 => "u32",
+            PrimitiveType::U64 => "u64",
+            PrimitiveType::U128 => "u128",
+            PrimitiveType::F32 => "f32",
+            PrimitiveType::F64 => "f64",
+            PrimitiveType::String | PrimitiveType::Bytes => "[]const u8",
+            PrimitiveType::Unit => "void",
+            // Unix seconds
+            PrimitiveType::DateTime => "i64",
+            PrimitiveType::Uuid => "[16]u8",
+            PrimitiveType::Decimal => "f64",
+            PrimitiveType::BigInt => "std.math.big.int.Managed",
+        }
+        .to_string(),
+        IrType::Container(ContainerType::Vec(inner)) => format!("[]const {}", render(inner)),
+        IrType::Container(ContainerType::Array(inner, len)) => format!("[{}]{}", len, render(inner)),
+        IrType::Container(ContainerType::Result(ok, _)) => format!("anyerror!{}", render(ok)),
+        IrType::Container(ContainerType::Map(key, value)) => format!("std.AutoHashMap({}, {})", render(key), render(value)),
+        IrType::Reference(name) | IrType::Alias(name, _) => pascal_case(name),
+        // Zig generics are functions returning the type
+        IrType::Generic(name, args) => format!("{}({})", pascal_case(name), args.iter().map(render).collect::<Vec<_>>().join(", ")),
+        IrType::TypeParam(name) => name.clone(),
+        IrType::Any | IrType::TraitObject(_) | IrType::Union(_) => "std.json.Value".to_string(),
+        IrType::Function(params, ret) => format!("*const fn ({}) {}", params.iter().map(render).collect::<Vec<_>>().join(", "), render(ret)),
+        IrType::Tuple(members) => format!("struct {{ {} }}", members.iter().map(render).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_const_parameters_stay_parameters() {
+        let types = analyze_rust("pub struct Matrix<const N: usize> { pub data: [f64; N], pub label: Option<String> }");
+        assert_eq!(
+            generate_sized_binding(&types[0], "julia").unwrap(),
+            "struct Matrix{N}\n    data::SVector{N, Float64}\n    label::Union{Nothing, String}\nend\n"
+        );
+        assert_eq!(
+            generate_sized_binding(&types[0], "zig").unwrap(),
+            "pub fn Matrix(comptime N: usize) type {\n    return struct {\n        data: [N]f64,\n        label: ?[]const u8,\n    };\n}\n"
+        );
+        // Targets without length-parameterized types need a concrete size
+        assert!(generate_sized_binding(&types[0], "typescript").is_none());
+        let three = types[0].instantiate_consts(&[3]).unwrap();
+        assert!(three.const_params.is_empty());
+        assert_eq!(generate_sized_binding(&three, "julia").unwrap(), "struct Matrix\n    data::SVector{3, Float64}\n    label::Union{Nothing, String}\nend\n");
+        assert_eq!(generate_sized_binding(&three, "zig").unwrap(), "pub const Matrix = struct {\n    data: [3]f64,\n    label: ?[]const u8,\n};\n");
+        assert!(types[0].instantiate_consts(&[]).is_err());
+    }
+}
@@ -0,0 +1,603 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Cross-language test data snapshots
+// One JSON object per line: the type, a fingerprint of its wire shape, which
+// sample it is, which language wrote it, and the encoded value. Every side's
+// generated tests decode the lines for their types and write back their own
+// re-encoding, so CI can chain producers and consumers across languages
+
+use std::fmt;
+
+use super::{pascal_case, render_type};
+use crate::api::{Language, TypeDef};
+use crate::ir::{normalize_name, ContainerType, IrType, PrimitiveType};
+use crate::json::{self, Json};
+
+/// Largest integer every JSON consumer decodes exactly (2^53 - 1)
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+/// How deep samples follow references before settling for `null` or `[]`
+const MAX_SAMPLE_DEPTH: usize = 4;
+
+/// One encoded sample value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub type_name: String,
+    pub fingerprint: String,
+    /// Which sample this is (`zero`, `typical`, `edge`)
+    pub case: String,
+    pub producer: Language,
+    /// The value as compact JSON
+    pub value: String,
+}
+
+impl Snapshot {
+    /// The snapshot as one JSON line, without the trailing newline
+    pub fn to_line(&self) -> String {
+        let value = json::parse(&self.value).unwrap_or(Json::Null);
+        Json::object([
+            ("type", self.type_name.as_str().into()),
+            ("fingerprint", self.fingerprint.as_str().into()),
+            ("case", self.case.as_str().into()),
+            ("producer", self.producer.name().into()),
+            ("value", value),
+        ])
+        .to_string()
+    }
+}
+
+/// Parse a JSON-lines snapshot file; blank lines are skipped
+pub fn parse_snapshots(source: &str) -> Result<Vec<Snapshot>, String> {
+    let mut snapshots = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let located = |err: String| format!("line {}: {}", i + 1, err);
+        let entry = json::parse(line).map_err(located)?;
+        let text = |key: &str| {
+            entry
+                .get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| located(format!("missing `{}`", key)))
+        };
+        let (type_name, fingerprint, case, producer) = (text("type")?, text("fingerprint")?, text("case")?, text("producer")?);
+        snapshots.push(Snapshot {
+            type_name,
+            fingerprint,
+            case,
+            producer: Language::from_name(&producer).ok_or_else(|| located(format!("unknown producer `{}`", producer)))?,
+            value: entry.get("value").ok_or_else(|| located("missing `value`".to_string()))?.to_string(),
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Fingerprint of a type's wire shape: its name (case-insensitive), each
+/// field's exact key, and the JSON form of each field type
+///
+/// Integer widths share one form and so do float widths, so equivalent types
+/// from different languages agree; range problems surface when the values
+/// are verified instead.
+pub fn fingerprint(def: &TypeDef) -> String {
+    let mut fields: Vec<String> = def
+        .fields
+        .iter()
+        .map(|field| format!("{}:{}", field.name, shape(&field.ty)))
+        .collect();
+    fields.sort();
+    let canonical = format!("{}{{{}}}", normalize_name(&def.name), fields.join(","));
+    // FNV-1a, so fingerprints stay stable across toolchains
+    let hash = canonical
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("fp1-{:016x}", hash)
+}
+
+fn shape(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "bool".to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64) => "float".to_string(),
+        IrType::Primitive(PrimitiveType::String) => "string".to_string(),
+        IrType::Primitive(PrimitiveType::Unit) => "null".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => "datetime".to_string(),
+        IrType::Primitive(PrimitiveType::Uuid) => "uuid".to_string(),
+        IrType::Primitive(PrimitiveType::Decimal) => "decimal".to_string(),
+        IrType::Primitive(PrimitiveType::BigInt) => "bigint".to_string(),
+        IrType::Primitive(PrimitiveType::Bytes) => "bytes".to_string(),
+        IrType::Primitive(_) => "int".to_string(),
+        IrType::Container(ContainerType::Vec(inner)) => format!("list({})", shape(inner)),
+        IrType::Container(ContainerType::Array(inner, len)) => format!("array({};{})", shape(inner), len),
+        IrType::Container(ContainerType::Option(inner)) => format!("option({})", shape(inner)),
+        IrType::Container(ContainerType::Result(ok, err)) => format!("result({},{})", shape(ok), shape(err)),
+        IrType::Container(ContainerType::Map(key, value)) => format!("map({},{})", shape(key), shape(value)),
+        IrType::Reference(name) => format!("ref({})", normalize_name(name)),
+        IrType::Generic(name, args) => format!("ref({}<{}>)", normalize_name(name), args.iter().map(shape).collect::<Vec<_>>().join(",")),
+        // Aliases are invisible on the wire
+        IrType::Alias(_, aliased) => shape(aliased),
+        IrType::Union(members) => format!("union({})", members.iter().map(shape).collect::<Vec<_>>().join(",")),
+        IrType::Tuple(members) => format!("tuple({})", members.iter().map(shape).collect::<Vec<_>>().join(",")),
+        _ => "any".to_string(),
+    }
+}
+
+/// Which sample values to build
+#[derive(Clone, Copy)]
+enum Case {
+    /// Zeros, empty strings and lists, absent optionals
+    Zero,
+    Typical,
+    /// Range limits, non-ASCII and escaped text
+    Edge,
+}
+
+/// `zero`, `typical`, and `edge` samples of a type as written by `producer`;
+/// references are filled in from `types`
+pub fn sample_snapshots(def: &TypeDef, types: &[TypeDef], producer: Language) -> Vec<Snapshot> {
+    let fingerprint = fingerprint(def);
+    [("zero", Case::Zero), ("typical", Case::Typical), ("edge", Case::Edge)]
+        .into_iter()
+        .map(|(name, case)| Snapshot {
+            type_name: pascal_case(&def.name),
+            fingerprint: fingerprint.clone(),
+            case: name.to_string(),
+            producer,
+            value: sample_object(def, types, case, 0).unwrap_or(Json::Null).to_string(),
+        })
+        .collect()
+}
+
+fn sample_object(def: &TypeDef, types: &[TypeDef], case: Case, depth: usize) -> Option<Json> {
+    if depth > MAX_SAMPLE_DEPTH {
+        return None;
+    }
+    let mut entries = Vec::new();
+    // Write-only fields never reach the wire
+    for field in def.fields.iter().filter(|field| field.direction.serializes()) {
+        entries.push((field.name.clone(), sample(&field.ty, types, case, depth)?));
+    }
+    Some(Json::Object(entries))
+}
+
+fn sample(ty: &IrType, types: &[TypeDef], case: Case, depth: usize) -> Option<Json> {
+    Some(match ty {
+        IrType::Primitive(primitive) => match (primitive, case) {
+            (PrimitiveType::Bool, Case::Zero) => Json::Bool(false),
+            (PrimitiveType::Bool, _) => Json::Bool(true),
+            (PrimitiveType::String, Case::Zero) => "".into(),
+            (PrimitiveType::String, Case::Typical) => "sample".into(),
+            (PrimitiveType::String, Case::Edge) => "ünïcödé ✓ \"quoted\"\n\ttabbed".into(),
+            (PrimitiveType::Unit, _) => Json::Null,
+            (PrimitiveType::DateTime, Case::Zero) => "1970-01-01T00:00:00Z".into(),
+            (PrimitiveType::DateTime, Case::Typical) => "2024-02-29T12:30:00Z".into(),
+            (PrimitiveType::DateTime, Case::Edge) => "9999-12-31T23:59:59.999999999+14:00".into(),
+            (PrimitiveType::Uuid, Case::Zero) => "00000000-0000-0000-0000-000000000000".into(),
+            (PrimitiveType::Uuid, Case::Typical) => "67e55044-10b1-426f-9247-bb680e5fe0c8".into(),
+            (PrimitiveType::Uuid, Case::Edge) => "FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF".into(),
+            (PrimitiveType::Bytes, Case::Zero) => "".into(),
+            (PrimitiveType::Bytes, Case::Typical) => "c2FtcGxl".into(),
+            // 0x00 0xFF 0xFE: the characters URL-safe base64 replaces
+            (PrimitiveType::Bytes, Case::Edge) => "AP/+".into(),
+            // Past what a double holds, so only exact peers read them back
+            (PrimitiveType::Decimal, Case::Edge) => "79228162514264337593543950335".into(),
+            (PrimitiveType::BigInt, Case::Edge) => "340282366920938463463374607431768211456".into(),
+            (_, Case::Zero) => Json::Number(0.0),
+            (PrimitiveType::F32 | PrimitiveType::F64, Case::Typical) => Json::Number(1.5),
+            (PrimitiveType::F32 | PrimitiveType::F64, Case::Edge) => Json::Number(-0.25),
+            (_, Case::Typical) => Json::Number(42.0),
+            (PrimitiveType::I8, Case::Edge) => Json::Number(f64::from(i8::MIN)),
+            (PrimitiveType::I16, Case::Edge) => Json::Number(f64::from(i16::MIN)),
+            (PrimitiveType::I32, Case::Edge) => Json::Number(f64::from(i32::MIN)),
+            (PrimitiveType::U8, Case::Edge) => Json::Number(f64::from(u8::MAX)),
+            (PrimitiveType::U16, Case::Edge) => Json::Number(f64::from(u16::MAX)),
+            (PrimitiveType::U32, Case::Edge) => Json::Number(f64::from(u32::MAX)),
+            (_, Case::Edge) => Json::Number(MAX_SAFE_INTEGER),
+        },
+        IrType::Container(ContainerType::Vec(inner)) => match case {
+            Case::Zero => Json::Array(Vec::new()),
+            _ => Json::Array(sample(inner, types, case, depth).into_iter().collect()),
+        },
+        IrType::Container(ContainerType::Array(inner, len)) => Json::Array(vec![sample(inner, types, case, depth)?; *len]),
+        IrType::Container(ContainerType::Option(inner)) => match case {
+            Case::Zero => Json::Null,
+            _ => sample(inner, types, case, depth).unwrap_or(Json::Null),
+        },
+        IrType::Container(ContainerType::Result(ok, err)) => match case {
+            Case::Edge => Json::object([("Err", sample(err, types, case, depth)?)]),
+            _ => Json::object([("Ok", sample(ok, types, case, depth)?)]),
+        },
+        IrType::Container(ContainerType::Map(key, value)) => match case {
+            Case::Zero => Json::Object(Vec::new()),
+            // Object keys are strings; numeric keys are written in decimal
+            _ => {
+                let key = match sample(key, types, case, depth)? {
+                    Json::String(key) => key,
+                    Json::Number(key) => key.to_string(),
+                    _ => return None,
+                };
+                Json::Object(vec![(key, sample(value, types, case, depth)?)])
+            }
+        },
+        IrType::Reference(name) => {
+            let def = types.iter().find(|def| normalize_name(&def.name) == normalize_name(name))?;
+            sample_object(def, types, case, depth + 1)?
+        }
+        IrType::Generic(name, args) => {
+            let def = types.iter().find(|def| normalize_name(&def.name) == normalize_name(name))?;
+            sample_object(&def.instantiate(args).ok()?, types, case, depth + 1)?
+        }
+        IrType::Alias(_, aliased) => sample(aliased, types, case, depth)?,
+        IrType::Union(members) => sample(members.first()?, types, case, depth)?,
+        IrType::Tuple(members) => Json::Array(
+            members
+                .iter()
+                .map(|member| sample(member, types, case, depth))
+                .collect::<Option<_>>()?,
+        ),
+        _ => Json::Null,
+    })
+}
+
+/// Why a snapshot failed verification
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotProblem {
+    /// The consumer has no type by this name
+    UnknownType,
+    /// The producer's type had a different wire shape
+    FingerprintMismatch { expected: String },
+    /// The value can't be decoded as the consumer's type
+    Undecodable { path: String, expected: String },
+}
+
+/// A snapshot the consuming side can't accept
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotMismatch {
+    pub type_name: String,
+    pub case: String,
+    pub producer: Language,
+    pub problem: SnapshotProblem,
+}
+
+impl fmt::Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} `{}` from {}: ", self.type_name, self.case, self.producer)?;
+        match &self.problem {
+            SnapshotProblem::UnknownType => f.write_str("no such type on this side"),
+            SnapshotProblem::FingerprintMismatch { expected } => write!(f, "wire shape differs (expected {})", expected),
+            SnapshotProblem::Undecodable { path, expected } => write!(f, "`{}` is not {}", path, expected),
+        }
+    }
+}
+
+/// Check that every snapshot decodes as the matching type in `types`
+pub fn verify_snapshots(snapshots: &[Snapshot], types: &[TypeDef]) -> Vec<SnapshotMismatch> {
+    let mut mismatches = Vec::new();
+    for snapshot in snapshots {
+        let mismatch = |problem| SnapshotMismatch {
+            type_name: snapshot.type_name.clone(),
+            case: snapshot.case.clone(),
+            producer: snapshot.producer,
+            problem,
+        };
+        let Some(def) = types.iter().find(|def| normalize_name(&def.name) == normalize_name(&snapshot.type_name)) else {
+            mismatches.push(mismatch(SnapshotProblem::UnknownType));
+            continue;
+        };
+        let expected = fingerprint(def);
+        if snapshot.fingerprint != expected {
+            mismatches.push(mismatch(SnapshotProblem::FingerprintMismatch { expected }));
+        }
+        let value = json::parse(&snapshot.value).unwrap_or(Json::Null);
+        if let Err((path, expected)) = check_object(&value, def, types, "$") {
+            mismatches.push(mismatch(SnapshotProblem::Undecodable { path, expected }));
+        }
+    }
+    mismatches
+}
+
+type Mismatch = (String, String);
+
+fn check_object(value: &Json, def: &TypeDef, types: &[TypeDef], path: &str) -> Result<(), Mismatch> {
+    if !matches!(value, Json::Object(_)) {
+        return Err((path.to_string(), format!("a {} object", pascal_case(&def.name))));
+    }
+    // Read-only fields are never decoded
+    for field in def.fields.iter().filter(|field| field.direction.deserializes()) {
+        let path = format!("{}.{}", path, field.name);
+        match value.get(&field.name) {
+            Some(inner) => check(inner, &field.ty, types, &path)?,
+            None if matches!(field.ty, IrType::Container(ContainerType::Option(_))) => {}
+            None => return Err((path, "present".to_string())),
+        }
+    }
+    Ok(())
+}
+
+fn check(value: &Json, ty: &IrType, types: &[TypeDef], path: &str) -> Result<(), Mismatch> {
+    let fail = |expected: &str| Err((path.to_string(), expected.to_string()));
+    match ty {
+        IrType::Primitive(primitive) => {
+            let fits = match (primitive, value) {
+                (PrimitiveType::Bool, Json::Bool(_)) => true,
+                (PrimitiveType::String, Json::String(_)) => true,
+                (PrimitiveType::Unit, Json::Null) => true,
+                (PrimitiveType::DateTime, Json::String(text)) => is_rfc3339(text),
+                (PrimitiveType::Uuid, Json::String(text)) => is_uuid(text),
+                (PrimitiveType::Bytes, Json::String(text)) => is_base64(text),
+                // A JSON number, or its digits as a string where doubles fall short
+                (PrimitiveType::Decimal, Json::Number(_)) => true,
+                (PrimitiveType::BigInt, Json::Number(n)) => n.fract() == 0.0,
+                (PrimitiveType::Decimal, Json::String(text)) => is_decimal(text, true),
+                (PrimitiveType::BigInt, Json::String(text)) => is_decimal(text, false),
+                (PrimitiveType::F64, Json::Number(_)) => true,
+                (PrimitiveType::F32, Json::Number(n)) => n.abs() <= f64::from(f32::MAX),
+                (PrimitiveType::I8, Json::Number(n)) => n.fract() == 0.0 && (f64::from(i8::MIN)..=f64::from(i8::MAX)).contains(n),
+                (PrimitiveType::I16, Json::Number(n)) => n.fract() == 0.0 && (f64::from(i16::MIN)..=f64::from(i16::MAX)).contains(n),
+                (PrimitiveType::I32, Json::Number(n)) => n.fract() == 0.0 && (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(n),
+                (PrimitiveType::U8, Json::Number(n)) => n.fract() == 0.0 && (0.0..=f64::from(u8::MAX)).contains(n),
+                (PrimitiveType::U16, Json::Number(n)) => n.fract() == 0.0 && (0.0..=f64::from(u16::MAX)).contains(n),
+                (PrimitiveType::U32, Json::Number(n)) => n.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(n),
+                (PrimitiveType::I64 | PrimitiveType::I128, Json::Number(n)) => n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER,
+                (PrimitiveType::U64 | PrimitiveType::U128, Json::Number(n)) => n.fract() == 0.0 && (0.0..=MAX_SAFE_INTEGER).contains(n),
+                _ => false,
+            };
+            if fits {
+                Ok(())
+            } else {
+                fail(&format!("a valid `{}`", render_type(ty, "rust")))
+            }
+        }
+        IrType::Container(ContainerType::Vec(inner)) => match value {
+            Json::Array(items) => items
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, item)| check(item, inner, types, &format!("{}[{}]", path, i))),
+            _ => fail("an array"),
+        },
+        IrType::Container(ContainerType::Array(inner, len)) => match value {
+            Json::Array(items) if items.len() == *len => items
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, item)| check(item, inner, types, &format!("{}[{}]", path, i))),
+            _ => fail(&format!("an array of {}", len)),
+        },
+        IrType::Tuple(members) => match value {
+            Json::Array(items) if items.len() == members.len() => items
+                .iter()
+                .zip(members)
+                .enumerate()
+                .try_for_each(|(i, (item, member))| check(item, member, types, &format!("{}[{}]", path, i))),
+            _ => fail(&format!("an array of {}", members.len())),
+        },
+        IrType::Container(ContainerType::Option(inner)) => match value {
+            Json::Null => Ok(()),
+            _ => check(value, inner, types, path),
+        },
+        IrType::Container(ContainerType::Result(ok, err)) => match value {
+            Json::Object(entries) if entries.len() == 1 => match value.get("Ok") {
+                Some(inner) => check(inner, ok, types, &format!("{}.Ok", path)),
+                None => match value.get("Err") {
+                    Some(inner) => check(inner, err, types, &format!("{}.Err", path)),
+                    None => fail("`{ Ok }` or `{ Err }`"),
+                },
+            },
+            _ => fail("`{ Ok }` or `{ Err }`"),
+        },
+        IrType::Container(ContainerType::Map(key, inner)) => match value {
+            Json::Object(entries) => entries.iter().try_for_each(|(name, item)| {
+                let path = format!("{}[{:?}]", path, name);
+                let key_value = match key.as_ref() {
+                    IrType::Primitive(PrimitiveType::String) => Json::String(name.clone()),
+                    _ => name.parse().map_or_else(|_| Json::String(name.clone()), Json::Number),
+                };
+                check(&key_value, key, types, &path)?;
+                check(item, inner, types, &path)
+            }),
+            _ => fail("an object"),
+        },
+        IrType::Reference(name) => match types.iter().find(|def| normalize_name(&def.name) == normalize_name(name)) {
+            Some(def) => check_object(value, def, types, path),
+            // Defined in a file this side didn't analyze; nothing to check against
+            None => Ok(()),
+        },
+        IrType::Generic(name, args) => match types.iter().find(|def| normalize_name(&def.name) == normalize_name(name)) {
+            Some(def) => match def.instantiate(args) {
+                Ok(instance) => check_object(value, &instance, types, path),
+                Err(error) => fail(&error.to_string()),
+            },
+            None => Ok(()),
+        },
+        IrType::Alias(_, aliased) => check(value, aliased, types, path),
+        IrType::Union(members) => {
+            if members.iter().any(|member| check(value, member, types, path).is_ok()) {
+                Ok(())
+            } else {
+                fail(&format!("any of `{}`", render_type(ty, "typescript")))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Optionally signed digits, with one `.` where `fraction` allows
+fn is_decimal(text: &str, fraction: bool) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let (whole, part) = match digits.split_once('.') {
+        Some((whole, part)) if fraction => (whole, part),
+        Some(_) => return false,
+        None => (digits, "0"),
+    };
+    [whole, part].iter().all(|run| !run.is_empty() && run.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Standard base64 with padding, as `serde_with` and protobuf JSON write it
+fn is_base64(text: &str) -> bool {
+    let data = text.trim_end_matches('=');
+    text.len().is_multiple_of(4)
+        && text.len() - data.len() <= 2
+        && data.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+/// Hyphenated hex, `8-4-4-4-12` digits, either case
+fn is_uuid(text: &str) -> bool {
+    let groups: Vec<&str> = text.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, width)| group.len() == width && group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// `YYYY-MM-DDTHH:MM:SS[.fraction](Z|±HH:MM)`
+fn is_rfc3339(text: &str) -> bool {
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let Some((date, time)) = text.split_once(['T', 't']) else { return false };
+    let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => time.split_at(i),
+        None => return false,
+    };
+    let clock = clock.split_once('.').map_or(clock, |(whole, fraction)| if digits(fraction) { whole } else { "" });
+    let fields = |part: &str, sep: char, widths: &[usize]| {
+        let pieces: Vec<&str> = part.split(sep).collect();
+        pieces.len() == widths.len() && pieces.iter().zip(widths).all(|(piece, width)| piece.len() == *width && digits(piece))
+    };
+    fields(date, '-', &[4, 2, 2])
+        && fields(clock, ':', &[2, 2, 2])
+        && (offset.eq_ignore_ascii_case("z") || fields(&offset[1..], ':', &[2, 2]))
+}
+
+/// A test in `target` that decodes every snapshot of `def` and re-encodes it as
+/// that side's own snapshot; `None` for targets without a generator
+///
+/// The Rust test reads `$SNAPSHOTS_IN` (default `snapshots.jsonl`) and appends
+/// to `$SNAPSHOTS_OUT`; the TypeScript function takes and returns the lines.
+/// Both expect the type and codec that [`super::scaffold`] generates.
+pub fn generate_snapshot_test(def: &TypeDef, target: &str) -> Option<String> {
+    let name = pascal_case(&def.name);
+    let upper = super::snake_case(&def.name).to_uppercase();
+    let fingerprint = fingerprint(def);
+    let code = match target {
+        "rust" => format!(
+            "const {upper}_FINGERPRINT: &str = \"{fingerprint}\";\n\n\
+             #[test]\n\
+             fn {snake}_snapshots_round_trip() {{\n    \
+             let path = std::env::var(\"SNAPSHOTS_IN\").unwrap_or_else(|_| \"snapshots.jsonl\".to_string());\n    \
+             let input = std::fs::read_to_string(&path).expect(\"snapshot file\");\n    \
+             let mut produced = String::new();\n    \
+             for line in input.lines().filter(|line| !line.trim().is_empty()) {{\n        \
+             let snapshot: serde_json::Value = serde_json::from_str(line).expect(\"snapshot line\");\n        \
+             if snapshot[\"type\"] != \"{name}\" {{\n            continue;\n        }}\n        \
+             let from = format!(\"{{}} `{{}}`\", snapshot[\"producer\"], snapshot[\"case\"]);\n        \
+             assert_eq!(snapshot[\"fingerprint\"], {upper}_FINGERPRINT, \"{{}}: {name} changed shape\", from);\n        \
+             let value: {name} = serde_json::from_value(snapshot[\"value\"].clone())\n            \
+             .unwrap_or_else(|err| panic!(\"{{}}: not a valid {name}: {{}}\", from, err));\n        \
+             let mut echoed = snapshot.clone();\n        \
+             echoed[\"producer\"] = \"rust\".into();\n        \
+             echoed[\"value\"] = serde_json::to_value(&value).unwrap();\n        \
+             produced.push_str(&format!(\"{{}}\\n\", echoed));\n    \
+             }}\n    \
+             if let Ok(out) = std::env::var(\"SNAPSHOTS_OUT\") {{\n        \
+             use std::io::Write;\n        \
+             std::fs::OpenOptions::new()\n            \
+             .create(true)\n            \
+             .append(true)\n            \
+             .open(out)\n            \
+             .and_then(|mut file| file.write_all(produced.as_bytes()))\n            \
+             .expect(\"snapshot output\");\n    \
+             }}\n\
+             }}\n",
+            snake = super::snake_case(&def.name),
+        ),
+        "typescript" => format!(
+            "export const {upper}_FINGERPRINT = \"{fingerprint}\";\n\n\
+             /** Check every `{name}` snapshot in JSON-lines `input`; returns this side's re-encoding */\n\
+             export function check{name}Snapshots(input: string): string {{\n  \
+             let produced = \"\";\n  \
+             for (const line of input.split(\"\\n\")) {{\n    \
+             if (line.trim() === \"\") continue;\n    \
+             const snapshot = JSON.parse(line);\n    \
+             if (snapshot.type !== \"{name}\") continue;\n    \
+             const from = `${{snapshot.producer}} \\`${{snapshot.case}}\\``;\n    \
+             if (snapshot.fingerprint !== {upper}_FINGERPRINT) throw new Error(`${{from}}: {name} changed shape`);\n    \
+             if (!is{name}(snapshot.value)) throw new Error(`${{from}}: not a valid {name}`);\n    \
+             produced += JSON.stringify({{ ...snapshot, producer: \"typescript\" }}) + \"\\n\";\n  \
+             }}\n  \
+             return produced;\n\
+             }}\n"
+        ),
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Registry;
+
+    fn analyze(language: Language, source: &str) -> Vec<TypeDef> {
+        Registry::with_builtins().analyzer(language).unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_language_and_int_width() {
+        let rust = analyze(Language::Rust, "pub struct User { pub id: i64, pub name: String, pub email: Option<String> }");
+        let ocaml = analyze(Language::OCaml, "type user = { email : string option; id : int32; name : string }");
+        assert_eq!(fingerprint(&rust[0]), fingerprint(&ocaml[0]));
+        assert!(fingerprint(&rust[0]).starts_with("fp1-"));
+        let renamed = analyze(Language::Rust, "pub struct User { pub id: i64, pub full_name: String, pub email: Option<String> }");
+        assert_ne!(fingerprint(&rust[0]), fingerprint(&renamed[0]));
+    }
+
+    #[test]
+    fn test_snapshots_round_trip_and_verify() {
+        let rust = analyze(
+            Language::Rust,
+            "pub struct Address { pub city: String }\npub struct User { pub id: i64, pub ratio: f64, pub tags: Vec<String>, pub home: Option<Address> }",
+        );
+        let jsonl: String = sample_snapshots(&rust[1], &rust, Language::Rust)
+            .iter()
+            .map(|snapshot| snapshot.to_line() + "\n")
+            .collect();
+        assert!(jsonl.starts_with("{\"type\":\"User\",\"fingerprint\":\"fp1-"));
+        assert!(jsonl.contains("\"case\":\"edge\",\"producer\":\"rust\",\"value\":{\"id\":9007199254740991,\"ratio\":-0.25,"));
+        assert!(jsonl.contains("\"home\":{\"city\":\"sample\"}"));
+
+        let snapshots = parse_snapshots(&jsonl).unwrap();
+        assert_eq!(snapshots.len(), 3);
+        assert!(verify_snapshots(&snapshots, &rust).is_empty());
+
+        // Same shape with 32-bit ids: the edge value no longer fits
+        let narrow = analyze(Language::Rust, "pub struct User { pub id: i32, pub ratio: f64, pub tags: Vec<String>, pub home: Option<Address> }");
+        let mismatches: Vec<String> = verify_snapshots(&snapshots, &narrow).iter().map(SnapshotMismatch::to_string).collect();
+        assert_eq!(mismatches, vec!["User `edge` from rust: `$.id` is not a valid `i32`"]);
+        assert_eq!(
+            verify_snapshots(&snapshots, &analyze(Language::Rust, "pub struct User { pub id: i64 }"))[0].problem,
+            SnapshotProblem::FingerprintMismatch { expected: fingerprint(&analyze(Language::Rust, "pub struct User { pub id: i64 }")[0]) }
+        );
+        assert_eq!(parse_snapshots("{\"type\":\"User\"}").unwrap_err(), "line 1: missing `fingerprint`");
+    }
+
+    #[test]
+    fn test_date_time_snapshots_are_rfc3339() {
+        let event = analyze(Language::Rust, "pub struct Event { pub at: chrono::DateTime<chrono::Utc> }");
+        let snapshots = sample_snapshots(&event[0], &event, Language::Rust);
+        assert!(verify_snapshots(&snapshots, &event).is_empty());
+        assert!(is_rfc3339("2024-02-29T12:30:00.5-05:00"));
+        assert!(!is_rfc3339("2024-02-29 12:30"));
+        assert!(!is_rfc3339("yesterday"));
+    }
+
+    #[test]
+    fn test_generated_snapshot_tests() {
+        let user = &analyze(Language::Rust, "pub struct User { pub id: i64 }")[0];
+        let rust = generate_snapshot_test(user, "rust").unwrap();
+        assert!(rust.contains(&format!("const USER_FINGERPRINT: &str = \"{}\";", fingerprint(user))));
+        assert!(rust.contains("fn user_snapshots_round_trip() {"));
+        assert!(rust.contains("        let value: User = serde_json::from_value(snapshot[\"value\"].clone())\n"));
+
+        let ts = generate_snapshot_test(user, "typescript").unwrap();
+        assert!(ts.contains("export function checkUserSnapshots(input: string): string {"));
+        assert!(ts.contains("    if (!isUser(snapshot.value)) throw new Error(`${from}: not a valid User`);\n"));
+        assert!(generate_snapshot_test(user, "gleam").is_none());
+    }
+}
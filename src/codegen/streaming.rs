@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Backpressure-aware streaming codecs for large collections
+// Opted-in `Vec<T>` fields get chunked encode/decode APIs on both sides: the
+// wire carries one JSON array frame per chunk, and producers are pull-based
+// (iterators/generators) so memory stays bounded by the chunk size
+
+use crate::analyzers::rust_analyzer::{map_to_target, rust_type_name, RustField, RustFieldType, RustType};
+
+/// Which collection fields are streamed, and how large each frame may get
+#[derive(Debug, Clone)]
+pub struct StreamingConfig {
+    /// Maximum elements per frame
+    pub chunk_size: usize,
+    /// `"Type.field"` entries that opted in
+    fields: Vec<String>,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        StreamingConfig {
+            chunk_size: 1024,
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl StreamingConfig {
+    /// Opt a collection field into streaming
+    pub fn enable(&mut self, type_name: &str, field_name: &str) {
+        let key = format!("{}.{}", type_name, field_name);
+        if !self.fields.contains(&key) {
+            self.fields.push(key);
+        }
+    }
+
+    pub fn is_streamed(&self, type_name: &str, field_name: &str) -> bool {
+        self.fields.contains(&format!("{}.{}", type_name, field_name))
+    }
+}
+
+/// Fields that can be streamed (`Vec<T>`), whether or not they opted in
+pub fn streamable_fields(rust_type: &RustType) -> Vec<&RustField> {
+    rust_type
+        .fields
+        .iter()
+        .filter(|field| matches!(field.field_type, RustFieldType::Vec(_)))
+        .collect()
+}
+
+/// Generate chunked codecs for every opted-in field of a type in one target language
+pub fn generate_streaming_codecs(rust_type: &RustType, config: &StreamingConfig, target: &str) -> Option<String> {
+    let mut code = String::new();
+    for field in streamable_fields(rust_type) {
+        if !config.is_streamed(&rust_type.name, &field.name) {
+            continue;
+        }
+        let element = match &field.field_type {
+            RustFieldType::Vec(element) => element,
+            _ => continue,
+        };
+        if !code.is_empty() {
+            code.push('\n');
+        }
+        code.push_str(&generate_field_codec(&rust_type.name, &field.name, element, config.chunk_size, target)?);
+    }
+    Some(code)
+}
+
+fn generate_field_codec(type_name: &str, field_name: &str, element: &RustFieldType, chunk_size: usize, target: &str) -> Option<String> {
+    let snake = format!("{}_{}", super::snake_case(type_name), field_name);
+    let camel = super::camel_case(&snake);
+
+    let code = match target {
+        "rust" => format!(
+            "/// Maximum elements per `{type_name}.{field_name}` frame\n\
+             pub const {upper}_CHUNK_SIZE: usize = {chunk_size};\n\n\
+             /// Lazily encode `{type_name}.{field_name}` as JSON array frames; nothing is\n\
+             /// serialized until the consumer pulls the next frame\n\
+             pub fn encode_{snake}_chunks<'a>(\n    items: &'a [{element}],\n) -> impl Iterator<Item = serde_json::Result<String>> + 'a {{\n    \
+             items.chunks({upper}_CHUNK_SIZE).map(serde_json::to_string)\n}}\n\n\
+             /// Decode `{type_name}.{field_name}` frames one at a time, yielding elements as they arrive\n\
+             pub fn decode_{snake}_chunks<R: std::io::BufRead>(\n    frames: R,\n) -> impl Iterator<Item = std::io::Result<{element}>> {{\n    \
+             frames.lines().flat_map(|frame| {{\n        \
+             let decoded: std::io::Result<Vec<{element}>> =\n            \
+             frame.and_then(|line| serde_json::from_str(&line).map_err(std::io::Error::other));\n        \
+             match decoded {{\n            \
+             Ok(chunk) => chunk.into_iter().map(Ok).collect::<Vec<_>>(),\n            \
+             Err(err) => vec![Err(err)],\n        \
+             }}\n    \
+             }})\n}}\n",
+            upper = snake.to_uppercase(),
+            element = rust_type_name(element),
+        ),
+        "typescript" => format!(
+            "// Maximum elements per `{type_name}.{field_name}` frame\n\
+             export const {camel}ChunkSize = {chunk_size};\n\n\
+             // Lazily encode `{type_name}.{field_name}` as JSON array frames (pull-based generator)\n\
+             export function* encode{pascal}Chunks(items: Iterable<{element}>): Generator<string> {{\n  \
+             let chunk: {element}[] = [];\n  \
+             for (const item of items) {{\n    \
+             chunk.push(item);\n    \
+             if (chunk.length === {camel}ChunkSize) {{\n      \
+             yield JSON.stringify(chunk);\n      \
+             chunk = [];\n    \
+             }}\n  \
+             }}\n  \
+             if (chunk.length > 0) yield JSON.stringify(chunk);\n\
+             }}\n\n\
+             // Decode `{type_name}.{field_name}` frames as they arrive, yielding one element at a time\n\
+             export async function* decode{pascal}Chunks(frames: AsyncIterable<string>): AsyncGenerator<{element}> {{\n  \
+             for await (const frame of frames) {{\n    \
+             yield* JSON.parse(frame) as {element}[];\n  \
+             }}\n\
+             }}\n",
+            pascal = super::pascal_case(&snake),
+            element = map_to_target(element, "typescript"),
+        ),
+        "rescript" => format!(
+            "// Maximum elements per `{type_name}.{field_name}` frame\n\
+             let {camel}ChunkSize = {chunk_size}\n\n\
+             // Pull-based encoder for `{type_name}.{field_name}`: each call yields the next JSON frame\n\
+             let encode{pascal}Chunks = (items: array<{element}>) => {{\n  \
+             let offset = ref(0)\n  \
+             () =>\n    \
+             if offset.contents >= items->Array.length {{\n      \
+             None\n    \
+             }} else {{\n      \
+             let chunk = items->Array.slice(~start=offset.contents, ~end=offset.contents + {camel}ChunkSize)\n      \
+             offset := offset.contents + {camel}ChunkSize\n      \
+             chunk->JSON.stringifyAny\n    \
+             }}\n\
+             }}\n\n\
+             // Decode one `{type_name}.{field_name}` frame with the element decoder\n\
+             let decode{pascal}Chunk = (frame: string, decodeItem: JSON.t => {element}): array<{element}> =>\n  \
+             switch frame->JSON.parseExn->JSON.Decode.array {{\n  \
+             | Some(items) => items->Array.map(decodeItem)\n  \
+             | None => []\n  \
+             }}\n",
+            pascal = super::pascal_case(&snake),
+            element = map_to_target(element, "rescript"),
+        ),
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::analyze_rust_type;
+
+    fn batch() -> RustType {
+        analyze_rust_type("pub struct Batch { pub id: i64, pub rows: Vec<Row>, pub tags: Vec<String> }").unwrap()
+    }
+
+    #[test]
+    fn test_only_opted_in_fields_stream() {
+        let batch = batch();
+        assert_eq!(streamable_fields(&batch).len(), 2);
+
+        let mut config = StreamingConfig::default();
+        config.enable("Batch", "rows");
+        let rust = generate_streaming_codecs(&batch, &config, "rust").unwrap();
+        assert!(rust.contains("pub const BATCH_ROWS_CHUNK_SIZE: usize = 1024;"));
+        assert!(rust.contains("pub fn encode_batch_rows_chunks<'a>(\n    items: &'a [Row],"));
+        assert!(rust.contains("pub fn decode_batch_rows_chunks<R: std::io::BufRead>("));
+        assert!(!rust.contains("batch_tags"));
+    }
+
+    #[test]
+    fn test_both_sides_use_same_chunk_size() {
+        let batch = batch();
+        let mut config = StreamingConfig { chunk_size: 256, ..StreamingConfig::default() };
+        config.enable("Batch", "tags");
+        let ts = generate_streaming_codecs(&batch, &config, "typescript").unwrap();
+        assert!(ts.contains("export const batchTagsChunkSize = 256;"));
+        assert!(ts.contains("export function* encodeBatchTagsChunks(items: Iterable<string>): Generator<string>"));
+        let rescript = generate_streaming_codecs(&batch, &config, "rescript").unwrap();
+        assert!(rescript.contains("let batchTagsChunkSize = 256"));
+        assert!(generate_streaming_codecs(&batch, &config, "cobol").is_none());
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Interned wire-string tables
+// Replaces repeated string constants (enum tags, status values) with numeric
+// ids on the wire, generating the shared table and lookup code for each side
+
+/// One interned string and how often it was seen
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringTableEntry {
+    pub id: u32,
+    pub value: String,
+    pub occurrences: usize,
+}
+
+/// Shared numeric string table; ids follow first occurrence so regeneration is stable
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringTable {
+    pub name: String,
+    pub entries: Vec<StringTableEntry>,
+}
+
+impl StringTable {
+    pub fn new(name: &str) -> Self {
+        StringTable {
+            name: name.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Build a table from observed values, keeping those seen at least `min_occurrences` times
+    pub fn from_occurrences<'a>(name: &str, values: impl IntoIterator<Item = &'a str>, min_occurrences: usize) -> Self {
+        let mut all = StringTable::new(name);
+        for value in values {
+            all.intern(value);
+        }
+
+        let mut table = StringTable::new(name);
+        for entry in all.entries.into_iter().filter(|e| e.occurrences >= min_occurrences) {
+            let id = table.intern(&entry.value);
+            table.entries[id as usize].occurrences = entry.occurrences;
+        }
+        table
+    }
+
+    /// Add a value (or count another occurrence) and return its id
+    pub fn intern(&mut self, value: &str) -> u32 {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.value == value) {
+            entry.occurrences += 1;
+            return entry.id;
+        }
+        let id = self.entries.len() as u32;
+        self.entries.push(StringTableEntry {
+            id,
+            value: value.to_string(),
+            occurrences: 1,
+        });
+        id
+    }
+
+    pub fn id_of(&self, value: &str) -> Option<u32> {
+        self.entries.iter().find(|e| e.value == value).map(|e| e.id)
+    }
+
+    /// Estimated JSON payload bytes saved by sending ids instead of quoted strings
+    pub fn bytes_saved(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|e| {
+                let quoted = e.value.len() + 2;
+                let numeric = e.id.to_string().len();
+                quoted.saturating_sub(numeric) * e.occurrences
+            })
+            .sum()
+    }
+
+    /// Human-readable id ↔ string mapping for compatibility reports
+    pub fn report(&self) -> String {
+        let mut report = format!("String table `{}` ({} entries, ~{} bytes saved):\n", self.name, self.entries.len(), self.bytes_saved());
+        for entry in &self.entries {
+            report.push_str(&format!("  {} → {:?} (×{})\n", entry.id, entry.value, entry.occurrences));
+        }
+        report
+    }
+
+    /// Generate the table and lookup functions for one side of the boundary
+    pub fn render(&self, target: &str) -> Option<String> {
+        let snake = super::snake_case(&self.name);
+        let camel = super::camel_case(&self.name);
+        let quoted: Vec<String> = self.entries.iter().map(|e| format!("{:?}", e.value)).collect();
+
+        let code = match target {
+            "rust" => format!(
+                "/// Interned wire strings for `{name}` (id = index)\n\
+                 pub const {upper}_STRINGS: [&str; {len}] = [{values}];\n\n\
+                 pub fn {snake}_to_wire(value: &str) -> Option<u32> {{\n    \
+                 {upper}_STRINGS.iter().position(|s| *s == value).map(|i| i as u32)\n}}\n\n\
+                 pub fn {snake}_from_wire(id: u32) -> Option<&'static str> {{\n    \
+                 {upper}_STRINGS.get(id as usize).copied()\n}}\n",
+                name = self.name,
+                upper = snake.to_uppercase(),
+                len = self.entries.len(),
+                values = quoted.join(", "),
+            ),
+            "rescript" => format!(
+                "// Interned wire strings for `{name}` (id = index)\n\
+                 let {camel}Strings = [{values}]\n\n\
+                 let {camel}ToWire = (value: string): option<int> =>\n  \
+                 switch {camel}Strings->Array.indexOf(value) {{\n  | -1 => None\n  | id => Some(id)\n  }}\n\n\
+                 let {camel}FromWire = (id: int): option<string> => {camel}Strings->Array.get(id)\n",
+                name = self.name,
+                values = quoted.join(", "),
+            ),
+            "typescript" => format!(
+                "// Interned wire strings for `{name}` (id = index)\n\
+                 export const {camel}Strings = [{values}] as const;\n\n\
+                 export function {camel}ToWire(value: string): number | undefined {{\n  \
+                 const id = ({camel}Strings as readonly string[]).indexOf(value);\n  \
+                 return id < 0 ? undefined : id;\n}}\n\n\
+                 export function {camel}FromWire(id: number): string | undefined {{\n  \
+                 return {camel}Strings[id];\n}}\n",
+                name = self.name,
+                values = quoted.join(", "),
+            ),
+            _ => return None,
+        };
+        Some(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_table() -> StringTable {
+        let observed = ["active", "suspended", "active", "active", "deleted", "suspended"];
+        StringTable::from_occurrences("Status", observed, 2)
+    }
+
+    #[test]
+    fn test_from_occurrences_keeps_repeated_values() {
+        let table = status_table();
+        assert_eq!(table.entries.len(), 2);
+        assert_eq!(table.id_of("active"), Some(0));
+        assert_eq!(table.id_of("suspended"), Some(1));
+        assert_eq!(table.id_of("deleted"), None);
+        assert_eq!(table.entries[0].occurrences, 3);
+        // "active" is 8 bytes quoted vs 1 digit, three times; "suspended" 11 vs 1, twice
+        assert_eq!(table.bytes_saved(), 7 * 3 + 10 * 2);
+    }
+
+    #[test]
+    fn test_render_both_sides_share_ids() {
+        let table = status_table();
+        let rust = table.render("rust").unwrap();
+        assert!(rust.contains("pub const STATUS_STRINGS: [&str; 2] = [\"active\", \"suspended\"];"));
+        assert!(rust.contains("pub fn status_from_wire(id: u32) -> Option<&'static str>"));
+        let rescript = table.render("rescript").unwrap();
+        assert!(rescript.contains("let statusStrings = [\"active\", \"suspended\"]"));
+        assert!(table.report().contains("  1 → \"suspended\" (×2)"));
+    }
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Swift bridging for the Rust FFI layer
+// A `module.modulemap` exposes the generated C header to Swift as a Clang
+// module; the Swift file mirrors each wrapper as a native struct that copies
+// its fields out, decoding strings, so nothing Swift keeps points into memory
+// Rust owns. Callbacks stay with their C registration functions
+
+use super::audit::SecurityProfile;
+use super::{camel_case, render_doc};
+use crate::analyzers::rust_analyzer::{callback_signature, ffi_field_type, ffi_scalar, is_zero_sized, RustFieldType, RustType, RustTypeKind, RustVariantKind};
+use crate::converters::ConverterRegistry;
+
+/// `module.modulemap` making `header` importable from Swift as `module`
+pub fn generate_modulemap(module: &str, header: &str) -> String {
+    format!("module {} {{\n    header \"{}\"\n    export *\n}}\n", module, header)
+}
+
+/// Swift mirrors of `types`, built from the C declarations the Clang module `module` imports
+pub fn generate_swift(types: &[RustType], converters: &ConverterRegistry, profile: SecurityProfile, module: &str) -> String {
+    let mut code = format!("// Swift mirrors of the Rust FFI layer; keep in step with the Rust side\n\nimport Foundation\nimport {}\n", module);
+    for rust_type in types {
+        let rendered = match rust_type.kind {
+            RustTypeKind::Enum => render_enum(rust_type),
+            RustTypeKind::Struct => render_struct(rust_type, converters, profile),
+            _ => continue,
+        };
+        code.push('\n');
+        code.push_str(&rendered);
+    }
+    code
+}
+
+/// Swift type a C scalar of the wrapper imports as
+fn swift_type(ffi: &str) -> Option<&'static str> {
+    Some(match ffi {
+        "bool" => "Bool",
+        "i8" => "Int8",
+        "i16" => "Int16",
+        "i32" => "Int32",
+        "i64" => "Int64",
+        "u8" => "UInt8",
+        "u16" => "UInt16",
+        "u32" => "UInt32",
+        "u64" => "UInt64",
+        "isize" => "Int",
+        "usize" => "UInt",
+        "f32" => "Float",
+        "f64" => "Double",
+        _ => return None,
+    })
+}
+
+/// Property declarations and the initializer lines copying them from `ffi`;
+/// `None` once a field has no Swift property: no C layout, or a list or
+/// option, which the accessors read
+fn fields(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> Option<(String, String)> {
+    let mut properties = String::new();
+    let mut copies = String::new();
+    for field in rust_type.fields.iter().filter(|field| !is_zero_sized(&field.field_type)) {
+        let name = &field.name;
+        let property = camel_case(name);
+        if callback_signature(&field.field_type).is_some() {
+            properties.push_str(&format!(
+                "    // `{}` is a callback; register it with `{}_register_{}`\n",
+                name,
+                super::snake_case(&rust_type.name),
+                name
+            ));
+            continue;
+        }
+        let (ty, copy) = match ffi_field_type(rust_type, field, converters) {
+            RustFieldType::String => (
+                "String",
+                format!("String(decoding: UnsafeBufferPointer(start: ffi.{name}_ptr, count: Int(ffi.{name}_len)), as: UTF8.self)"),
+            ),
+            // `uint8_t[16]` imports as the 16-tuple `uuid_t` is
+            RustFieldType::Uuid => ("UUID", format!("UUID(uuid: ffi.{})", name)),
+            field_type => {
+                let ffi = ffi_scalar(field_type, profile).ok()?;
+                match (swift_type(&ffi)?, field_type) {
+                    // The paranoid profile carries flags as bytes
+                    ("UInt8", RustFieldType::Bool) => ("Bool", format!("ffi.{} != 0", name)),
+                    (ty, _) => (ty, format!("ffi.{}", name)),
+                }
+            }
+        };
+        if let Some(doc) = &field.doc {
+            properties.push_str(&render_doc(doc, "swift", "    "));
+        }
+        properties.push_str(&format!("    public var {}: {}\n", property, ty));
+        copies.push_str(&format!("        self.{} = {}\n", property, copy));
+    }
+    Some((properties, copies))
+}
+
+fn render_struct(rust_type: &RustType, converters: &ConverterRegistry, profile: SecurityProfile) -> String {
+    let name = &rust_type.name;
+    let Some((properties, copies)) = fields(rust_type, converters, profile) else {
+        return format!("// `{}` has no Swift mirror; pass `{}FFI` pointers to its accessors\n", name, name);
+    };
+    let doc = rust_type.doc.as_ref().map(|doc| render_doc(doc, "swift", "")).unwrap_or_default();
+    format!(
+        "{doc}public struct {name} {{\n{properties}\n    \
+         /// Copy out of a Rust-owned wrapper, which may be freed afterwards\n    \
+         public init(_ ffi: {name}FFI) {{\n{copies}    }}\n\n    \
+         public init(_ pointer: UnsafePointer<{name}FFI>) {{\n        self.init(pointer.pointee)\n    }}\n}}\n"
+    )
+}
+
+/// Fieldless enum over its `#[repr]` integer, `Int32` as a plain C enum;
+/// Swift numbers cases after an explicit one the way C does
+fn render_enum(rust_type: &RustType) -> String {
+    let name = &rust_type.name;
+    if rust_type.variants.iter().any(|variant| variant.kind != RustVariantKind::Unit) {
+        return format!("// `{}` carries data and has no C form\n", name);
+    }
+    let raw = rust_type
+        .attributes
+        .iter()
+        .filter_map(|attr| attr.split_once("repr(").map(|(_, rest)| rest.trim_end_matches([')', ']'])))
+        .flat_map(|reprs| reprs.split(',').map(str::trim))
+        .find_map(|repr| swift_type(repr).filter(|ty| !matches!(*ty, "Bool" | "Float" | "Double")))
+        .unwrap_or("Int32");
+    let cases: String = rust_type
+        .variants
+        .iter()
+        .map(|variant| match &variant.discriminant {
+            Some(value) => format!("    case {} = {}\n", camel_case(&variant.name), value),
+            None => format!("    case {}\n", camel_case(&variant.name)),
+        })
+        .collect();
+    let doc = rust_type.doc.as_ref().map(|doc| render_doc(doc, "swift", "")).unwrap_or_default();
+    format!("{doc}public enum {name}: {raw} {{\n{cases}}}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::analyze_rust_types;
+
+    #[test]
+    fn test_swift_mirrors_copy_out_of_the_wrapper() {
+        let types = analyze_rust_types(
+            "#[repr(u8)]\npub enum Status { Active = 1, OnHold }\n\
+             /// A signed-up user\n#[repr(C)]\npub struct User { pub id: u64, pub display_name: String, pub active: bool, \
+             pub token: Uuid, pub on_change: Box<dyn Fn(i32) -> bool> }\n\
+             pub struct Team { pub members: Vec<User> }",
+        )
+        .unwrap();
+        assert_eq!(generate_modulemap("UserFFI", "user_ffi.h"), "module UserFFI {\n    header \"user_ffi.h\"\n    export *\n}\n");
+        let code = generate_swift(&types, &ConverterRegistry::default(), SecurityProfile::Standard, "UserFFI");
+        assert!(code.contains("import Foundation\nimport UserFFI\n"));
+        assert!(code.contains("public enum Status: UInt8 {\n    case active = 1\n    case onHold\n}\n"));
+        assert!(code.contains(
+            "/// A signed-up user\npublic struct User {\n    public var id: UInt64\n    public var displayName: String\n    public var active: Bool\n    \
+             public var token: UUID\n    // `on_change` is a callback; register it with `user_register_on_change`\n"
+        ));
+        assert!(code.contains(
+            "        self.displayName = String(decoding: UnsafeBufferPointer(start: ffi.display_name_ptr, count: Int(ffi.display_name_len)), as: UTF8.self)\n"
+        ));
+        assert!(code.contains("        self.token = UUID(uuid: ffi.token)\n"));
+        assert!(code.contains("// `Team` has no Swift mirror; pass `TeamFFI` pointers to its accessors\n"));
+
+        let paranoid = generate_swift(&types, &ConverterRegistry::default(), SecurityProfile::Paranoid, "UserFFI");
+        assert!(paranoid.contains("        self.active = ffi.active != 0\n"));
+    }
+}
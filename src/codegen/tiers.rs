@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Graceful degradation for low-fidelity pairs
+// Types that score Economy or Wheelbarrow against a target keep native
+// mappings for the fields that survive and carry the rest as strings: integers
+// the target can't hold exactly as decimal digits, everything else as JSON text
+
+use std::fmt::Write;
+
+use crate::api::{Fidelity, Language, TypeDef};
+use crate::ir::{self, ContainerType, IrType, PrimitiveType, TransportClass};
+
+/// How a field that can't map natively travels on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encoding {
+    /// A number as its decimal digits (`"18446744073709551615"`)
+    Decimal,
+    /// Any value as a string of JSON text (`"{\"a\":1}"`)
+    JsonText,
+}
+
+impl Encoding {
+    fn describe(self) -> &'static str {
+        match self {
+            Encoding::Decimal => "decimal string",
+            Encoding::JsonText => "JSON text",
+        }
+    }
+}
+
+/// The tiering decision for one field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldTier {
+    pub name: String,
+    /// Fidelity of the native mapping
+    pub fidelity: Fidelity,
+    /// Encodings used somewhere inside the field; empty when it maps natively
+    pub encodings: Vec<Encoding>,
+    /// The field's type with encoded parts replaced by strings
+    pub ty: IrType,
+}
+
+impl FieldTier {
+    pub fn is_native(&self) -> bool {
+        self.encodings.is_empty()
+    }
+}
+
+/// Tiering decisions for one type against one target
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierPlan {
+    pub type_name: String,
+    pub target: String,
+    /// Score of the all-native mapping
+    pub score: f32,
+    pub transport: TransportClass,
+    pub fields: Vec<FieldTier>,
+}
+
+/// Decide, field by field, what maps natively to `target` and what is encoded
+pub fn plan_tiers(def: &TypeDef, target: &str) -> TierPlan {
+    let fields: Vec<FieldTier> = def
+        .fields
+        .iter()
+        .map(|field| {
+            let mut encodings = Vec::new();
+            let ty = degrade(&field.ty, target, &mut encodings);
+            encodings.sort();
+            encodings.dedup();
+            FieldTier {
+                name: field.name.clone(),
+                fidelity: ir::fidelity(&field.ty, target),
+                encodings,
+                ty,
+            }
+        })
+        .collect();
+    let score = ir::average_fidelity(fields.iter().map(|field| field.fidelity));
+    TierPlan {
+        type_name: def.name.clone(),
+        target: target.to_string(),
+        score,
+        transport: TransportClass::from_score(score),
+        fields,
+    }
+}
+
+/// Replace the parts of `ty` that don't map exactly with strings, keeping the
+/// containers around them so lists and options stay native
+fn degrade(ty: &IrType, target: &str, encodings: &mut Vec<Encoding>) -> IrType {
+    if ir::fidelity(ty, target) == Fidelity::Exact {
+        return ty.clone();
+    }
+    let string = IrType::Primitive(PrimitiveType::String);
+    match ty {
+        // Already text on the wire
+        IrType::Primitive(PrimitiveType::DateTime | PrimitiveType::Uuid | PrimitiveType::Bytes) => string,
+        IrType::Primitive(primitive)
+            if !matches!(primitive, PrimitiveType::Bool | PrimitiveType::String | PrimitiveType::Unit) =>
+        {
+            encodings.push(Encoding::Decimal);
+            string
+        }
+        // Targets without fixed-length arrays get a dynamic one; the length goes unchecked
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => {
+            IrType::Container(ContainerType::Vec(Box::new(degrade(inner, target, encodings))))
+        }
+        IrType::Container(ContainerType::Option(inner)) => {
+            IrType::Container(ContainerType::Option(Box::new(degrade(inner, target, encodings))))
+        }
+        IrType::Container(ContainerType::Result(ok, err)) => IrType::Container(ContainerType::Result(
+            Box::new(degrade(ok, target, encodings)),
+            Box::new(degrade(err, target, encodings)),
+        )),
+        IrType::Alias(_, aliased) => degrade(aliased, target, encodings),
+        _ => {
+            encodings.push(Encoding::JsonText);
+            string
+        }
+    }
+}
+
+impl TierPlan {
+    /// Whether the native mapping is poor enough to tier: Economy or Wheelbarrow
+    pub fn needs_tiers(&self) -> bool {
+        matches!(self.transport, TransportClass::Economy | TransportClass::Wheelbarrow)
+    }
+
+    /// `def` with every field at its tiered type
+    pub fn apply(&self, def: &TypeDef) -> TypeDef {
+        let mut tiered = def.clone();
+        for (field, tier) in tiered.fields.iter_mut().zip(&self.fields) {
+            field.ty = tier.ty.clone();
+        }
+        tiered
+    }
+
+    /// Comment block recording the decisions, for the top of the generated type
+    pub fn document(&self, source: Language) -> String {
+        let mut doc = format!(
+            "// Tiered: {} `{}` scores {:.1}% ({:?}) as native {}\n",
+            source,
+            self.type_name,
+            self.score * 100.0,
+            self.transport,
+            self.target
+        );
+        let native: Vec<&str> = self.fields.iter().filter(|f| f.is_native()).map(|f| f.name.as_str()).collect();
+        if !native.is_empty() {
+            let _ = writeln!(doc, "//   native: {}", native.join(", "));
+        }
+        for field in self.fields.iter().filter(|f| !f.is_native()) {
+            let encodings: Vec<&str> = field.encodings.iter().map(|e| e.describe()).collect();
+            let _ = writeln!(doc, "//   {}: {} ({:?} as native)", field.name, encodings.join(" and "), field.fidelity);
+        }
+        doc.push_str("// Encoded fields are strings on the wire in both directions; producers encode them the same way\n");
+        doc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Generator, Registry};
+    use crate::ir::IrField;
+
+    fn account() -> TypeDef {
+        let primitive = IrType::Primitive;
+        let container = IrType::Container;
+        let fields = vec![
+            IrField::new("id", primitive(PrimitiveType::I32)),
+            IrField::new("balance", primitive(PrimitiveType::U64)),
+            IrField::new("history", container(ContainerType::Vec(Box::new(primitive(PrimitiveType::I64))))),
+            IrField::new("note", container(ContainerType::Option(Box::new(primitive(PrimitiveType::String))))),
+            IrField::new("meta", IrType::Any),
+        ];
+        TypeDef::new("Account", Language::Rust, "line 1", fields)
+    }
+
+    #[test]
+    fn test_plan_encodes_only_problem_fields() {
+        let plan = plan_tiers(&account(), "typescript");
+        assert!(plan.needs_tiers());
+        let decisions: Vec<_> = plan.fields.iter().map(|f| (f.name.as_str(), f.encodings.clone())).collect();
+        assert_eq!(
+            decisions,
+            vec![
+                ("id", vec![]),
+                ("balance", vec![Encoding::Decimal]),
+                ("history", vec![Encoding::Decimal]),
+                ("note", vec![]),
+                ("meta", vec![Encoding::JsonText]),
+            ]
+        );
+        // Lists stay lists of decimal strings
+        let string = IrType::Primitive(PrimitiveType::String);
+        assert_eq!(plan.fields[2].ty, IrType::Container(ContainerType::Vec(Box::new(string))));
+        let tiered = plan.apply(&account());
+        assert_eq!(plan_tiers(&tiered, "typescript").score, 1.0);
+
+        // Rust holds every integer; only the dynamic value is encoded
+        let rust = plan_tiers(&account(), "rust");
+        assert_eq!(rust.fields.iter().filter(|f| !f.is_native()).map(|f| f.name.as_str()).collect::<Vec<_>>(), vec!["meta"]);
+    }
+
+    #[test]
+    fn test_generate_tiered_documents_decisions() {
+        let code = Generator::new(Language::TypeScript).generate_tiered(Language::Rust, &[account()]).unwrap();
+        assert!(code.starts_with("// Tiered: rust `Account` scores 70.0% (Wheelbarrow) as native typescript\n"));
+        assert!(code.contains("//   native: id, note\n"));
+        assert!(code.contains("//   balance: decimal string (Lossy as native)\n"));
+        assert!(code.contains("//   meta: JSON text (Lossy as native)\n"));
+        assert!(code.contains("  balance: string;\n  history: Array<string>;\n  note: string | null;\n  meta: string;\n"));
+
+        // Pairs that already map well are generated natively
+        let tag = Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze("pub struct Tag { pub id: u64 }").unwrap();
+        let native = Generator::new(Language::Gleam).generate_tiered(Language::Rust, &tag).unwrap();
+        assert_eq!(native, Generator::new(Language::Gleam).generate(&tag).unwrap());
+    }
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Runtime validators for generated TypeScript declarations
+// A `.d.ts` only describes what arrives; zod schemas or io-ts codecs check it.
+// Each validator is typed against its declaration, so the compiler rejects one
+// that drifts from it, and references go through `z.lazy`/`t.recursion` so
+// declaration order and recursive types don't matter
+
+use super::newtypes::{newtype_inner, NewtypePolicy};
+use super::variants::{is_positional, payload_key, typescript_key};
+use super::{pascal_case, BIGINT_PATTERN, DECIMAL_PATTERN};
+use crate::api::TypeDef;
+use crate::ir::{normalize_name, ContainerType, Direction, IrField, IrType, IrVariant, PrimitiveType, VariantTagging};
+
+/// Validation library the validators are written for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValidatorLibrary {
+    /// `export const UserSchema: z.ZodType<User>`
+    Zod,
+    /// `export const UserCodec: t.Type<User>`
+    IoTs,
+}
+
+impl ValidatorLibrary {
+    fn import(self) -> &'static str {
+        match self {
+            ValidatorLibrary::Zod => "import { z } from \"zod\";\n",
+            ValidatorLibrary::IoTs => "import * as t from \"io-ts\";\n",
+        }
+    }
+
+    fn validator_name(self, type_name: &str) -> String {
+        match self {
+            ValidatorLibrary::Zod => format!("{}Schema", pascal_case(type_name)),
+            ValidatorLibrary::IoTs => format!("{}Codec", pascal_case(type_name)),
+        }
+    }
+
+    fn annotation(self, type_name: &str) -> String {
+        match self {
+            ValidatorLibrary::Zod => format!("z.ZodType<{}>", type_name),
+            ValidatorLibrary::IoTs => format!("t.Type<{}>", type_name),
+        }
+    }
+
+    fn primitive(self, primitive: PrimitiveType) -> String {
+        let zod = self == ValidatorLibrary::Zod;
+        let int_range = |min: i64, max: i64| match zod {
+            true => format!("z.number().int().min({}).max({})", min, max),
+            false => "t.number".to_string(),
+        };
+        match primitive {
+            PrimitiveType::Bool if zod => "z.boolean()".to_string(),
+            PrimitiveType::Bool => "t.boolean".to_string(),
+            PrimitiveType::I8 => int_range(i8::MIN.into(), i8::MAX.into()),
+            PrimitiveType::I16 => int_range(i16::MIN.into(), i16::MAX.into()),
+            PrimitiveType::I32 => int_range(i32::MIN.into(), i32::MAX.into()),
+            PrimitiveType::U8 => int_range(0, u8::MAX.into()),
+            PrimitiveType::U16 => int_range(0, u16::MAX.into()),
+            PrimitiveType::U32 => int_range(0, u32::MAX.into()),
+            _ if primitive.is_integer() && zod => "z.number().int()".to_string(),
+            PrimitiveType::DateTime if zod => "z.string().datetime({ offset: true })".to_string(),
+            PrimitiveType::Uuid if zod => "z.string().uuid()".to_string(),
+            PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid | PrimitiveType::Bytes if zod => "z.string()".to_string(),
+            PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid | PrimitiveType::Bytes => "t.string".to_string(),
+            // Digit strings; io-ts has no pattern check, so any string passes
+            PrimitiveType::Decimal if zod => format!("z.string().regex(/{}/)", DECIMAL_PATTERN),
+            PrimitiveType::BigInt if zod => format!("z.string().regex(/{}/)", BIGINT_PATTERN),
+            PrimitiveType::Decimal | PrimitiveType::BigInt => "t.string".to_string(),
+            PrimitiveType::Unit if zod => "z.null()".to_string(),
+            PrimitiveType::Unit => "t.null".to_string(),
+            _ if zod => "z.number()".to_string(),
+            _ => "t.number".to_string(),
+        }
+    }
+
+    fn literal(self, tag: &str) -> String {
+        match self {
+            ValidatorLibrary::Zod => format!("z.literal({:?})", tag),
+            ValidatorLibrary::IoTs => format!("t.literal({:?})", tag),
+        }
+    }
+
+    fn union(self, mut members: Vec<String>) -> String {
+        if members.len() == 1 {
+            return members.remove(0);
+        }
+        match self {
+            ValidatorLibrary::Zod => format!("z.union([{}])", members.join(", ")),
+            ValidatorLibrary::IoTs => format!("t.union([{}])", members.join(", ")),
+        }
+    }
+
+    fn tuple(self, members: Vec<String>) -> String {
+        match self {
+            ValidatorLibrary::Zod => format!("z.tuple([{}])", members.join(", ")),
+            ValidatorLibrary::IoTs => format!("t.tuple([{}])", members.join(", ")),
+        }
+    }
+
+    /// Object of `(key, validator, optional)` properties
+    fn object(self, properties: Vec<(String, String, bool)>) -> String {
+        let render = |properties: Vec<&(String, String, bool)>, optional_suffix: &str| -> String {
+            properties
+                .iter()
+                .map(|(key, validator, optional)| {
+                    format!("{}: {}{}", typescript_key(key), validator, if *optional { optional_suffix } else { "" })
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            ValidatorLibrary::Zod => format!("z.object({{ {} }})", render(properties.iter().collect(), ".optional()")),
+            ValidatorLibrary::IoTs => {
+                let (optional, required): (Vec<_>, Vec<_>) = properties.iter().partition(|(_, _, optional)| *optional);
+                let required = format!("t.type({{ {} }})", render(required, ""));
+                match optional.is_empty() {
+                    true => required,
+                    false => format!("t.intersection([{}, t.partial({{ {} }})])", required, render(optional, "")),
+                }
+            }
+        }
+    }
+
+    fn reference(self, type_name: &str) -> String {
+        match self {
+            ValidatorLibrary::Zod => format!("z.lazy(() => {})", self.validator_name(type_name)),
+            ValidatorLibrary::IoTs => format!("t.recursion(\"{}\", () => {})", pascal_case(type_name), self.validator_name(type_name)),
+        }
+    }
+}
+
+/// Validators for `types`, typed against the declarations imported from
+/// `module` and declaring newtypes under `newtypes`; sum types must already
+/// carry their wire tagging. A type that can't be validated is left out with
+/// a comment
+pub fn render_validators(types: &[TypeDef], library: ValidatorLibrary, newtypes: NewtypePolicy, module: &str) -> String {
+    let mut imported = Vec::new();
+    let mut body = String::new();
+    for def in types {
+        body.push('\n');
+        let name = pascal_case(&def.name);
+        if !def.type_params.is_empty() {
+            body.push_str(&format!("// `{}` is left out: generic types are validated per instantiation\n", name));
+            continue;
+        }
+        let Some(validator) = definition(def, types, library) else {
+            body.push_str(&format!("// `{}` is left out: it holds functions, which JSON can't carry\n", name));
+            continue;
+        };
+        // A brand exists only at compile time, so the validated value is asserted into it
+        let validator = match newtype_inner(def) {
+            Some(_) if newtypes == NewtypePolicy::Brand => format!("{} as {}", validator, library.annotation(&name)),
+            _ => validator,
+        };
+        body.push_str(&format!("export const {}: {} = {};\n", library.validator_name(&def.name), library.annotation(&name), validator));
+        imported.push(name);
+    }
+    format!("{}import type {{ {} }} from \"{}\";\n{}", library.import(), imported.join(", "), module, body)
+}
+
+fn definition(def: &TypeDef, types: &[TypeDef], library: ValidatorLibrary) -> Option<String> {
+    if let Some(aliased) = &def.aliased {
+        return validator(aliased, types, library);
+    }
+    if def.variants.is_empty() {
+        return match newtype_inner(def) {
+            Some(inner) => validator(inner, types, library),
+            None => object(&def.fields, Vec::new(), types, library),
+        };
+    }
+    let tagging = def.tagging.clone().unwrap_or(VariantTagging::External);
+    let members = def.variants.iter().map(|variant| {
+        let tag = library.literal(variant.wire_tag());
+        Some(match &tagging {
+            _ if variant.fields.is_empty() && (def.bare_units || tagging == VariantTagging::External) => tag,
+            VariantTagging::Untagged if variant.fields.is_empty() => library.primitive(PrimitiveType::Unit),
+            VariantTagging::Internal(key) | VariantTagging::Adjacent(key, _) if variant.fields.is_empty() => {
+                library.object(vec![(key.clone(), tag, false)])
+            }
+            VariantTagging::External => library.object(vec![(variant.wire_tag().to_string(), payload(variant, types, library)?, false)]),
+            VariantTagging::Internal(key) => {
+                let fields: Vec<IrField> = variant.fields.iter().map(|field| IrField { name: payload_key(field), ..field.clone() }).collect();
+                object(&fields, vec![(key.clone(), tag, false)], types, library)?
+            }
+            VariantTagging::Adjacent(key, content) => {
+                library.object(vec![(key.clone(), tag, false), (content.clone(), payload(variant, types, library)?, false)])
+            }
+            VariantTagging::Untagged => payload(variant, types, library)?,
+        })
+    });
+    Some(library.union(members.collect::<Option<_>>()?))
+}
+
+/// A lone positional payload is its value, several are a tuple, and named ones an object
+fn payload(variant: &IrVariant, types: &[TypeDef], library: ValidatorLibrary) -> Option<String> {
+    match variant.fields.as_slice() {
+        [only] if is_positional(only) => validator(&only.ty, types, library),
+        fields if fields.iter().all(is_positional) => {
+            Some(library.tuple(fields.iter().map(|field| validator(&field.ty, types, library)).collect::<Option<_>>()?))
+        }
+        fields => object(fields, Vec::new(), types, library),
+    }
+}
+
+/// Object of `fields` after `leading` properties; as in the declaration, fields
+/// that never arrive or have a default may be missing
+fn object(fields: &[IrField], leading: Vec<(String, String, bool)>, types: &[TypeDef], library: ValidatorLibrary) -> Option<String> {
+    let mut properties = leading;
+    for field in fields {
+        let optional = field.direction == Direction::DeserializeOnly || field.default.is_some();
+        properties.push((field.name.clone(), validator(&field.ty, types, library)?, optional));
+    }
+    Some(library.object(properties))
+}
+
+/// Validator of a value of `ty`, or `None` for functions
+fn validator(ty: &IrType, types: &[TypeDef], library: ValidatorLibrary) -> Option<String> {
+    let zod = library == ValidatorLibrary::Zod;
+    Some(match ty {
+        IrType::Primitive(primitive) => library.primitive(*primitive),
+        IrType::Container(ContainerType::Option(inner)) => match zod {
+            true => format!("{}.nullable()", validator(inner, types, library)?),
+            false => format!("t.union([{}, t.null])", validator(inner, types, library)?),
+        },
+        IrType::Container(ContainerType::Vec(inner)) if zod => format!("z.array({})", validator(inner, types, library)?),
+        IrType::Container(ContainerType::Array(inner, len)) if zod => format!("z.array({}).length({})", validator(inner, types, library)?, len),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => format!("t.array({})", validator(inner, types, library)?),
+        IrType::Container(ContainerType::Map(_, value)) if zod => format!("z.record(z.string(), {})", validator(value, types, library)?),
+        IrType::Container(ContainerType::Map(_, value)) => format!("t.record(t.string, {})", validator(value, types, library)?),
+        IrType::Container(ContainerType::Result(ok, err)) => library.union(vec![
+            library.object(vec![("Ok".to_string(), validator(ok, types, library)?, false)]),
+            library.object(vec![("Err".to_string(), validator(err, types, library)?, false)]),
+        ]),
+        IrType::Tuple(members) => library.tuple(members.iter().map(|member| validator(member, types, library)).collect::<Option<_>>()?),
+        IrType::Union(members) => library.union(members.iter().map(|member| validator(member, types, library)).collect::<Option<_>>()?),
+        IrType::Reference(name) | IrType::Alias(name, _) => match types.iter().find(|def| normalize_name(&def.name) == normalize_name(name)) {
+            Some(def) => library.reference(&def.name),
+            None => match ty {
+                IrType::Alias(_, inner) => validator(inner, types, library)?,
+                _ => library.reference(name),
+            },
+        },
+        // The declared validator takes its parameters as `unknown`, so arguments go unchecked
+        IrType::Generic(name, _) => library.reference(types.iter().find(|def| normalize_name(&def.name) == normalize_name(name)).map_or(name, |def| &def.name)),
+        IrType::Any | IrType::TraitObject(_) | IrType::TypeParam(_) if zod => "z.unknown()".to_string(),
+        IrType::Any | IrType::TraitObject(_) | IrType::TypeParam(_) => "t.unknown".to_string(),
+        IrType::Function(_, _) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Generator, Language};
+    use crate::codegen::analyze_rust;
+
+    #[test]
+    fn test_zod_schemas_follow_the_declarations() {
+        let types = analyze_rust(
+            "pub struct User { pub age: u8, #[serde(default)] pub tags: Vec<String>, pub boss: Option<Box<User>>, pub shape: Shape }\n\
+             #[serde(tag = \"kind\")]\npub enum Shape { Circle { radius: f64 }, Empty }\npub struct Page<T> { pub items: Vec<T> }\npub struct Email(String);",
+        );
+        let zod = Generator::new(Language::TypeScript).with_newtypes(NewtypePolicy::Brand).generate_validators(ValidatorLibrary::Zod, "./types", &types).unwrap();
+        assert!(zod.starts_with("import { z } from \"zod\";\nimport type { User, Shape, Email } from \"./types\";\n"));
+        assert!(zod.contains(
+            "export const UserSchema: z.ZodType<User> = z.object({ age: z.number().int().min(0).max(255), tags: z.array(z.string()).optional(), \
+             boss: z.lazy(() => UserSchema).nullable(), shape: z.lazy(() => ShapeSchema) });\n"
+        ));
+        assert!(zod.contains(
+            "export const ShapeSchema: z.ZodType<Shape> = z.union([z.object({ kind: z.literal(\"Circle\"), radius: z.number() }), \
+             z.object({ kind: z.literal(\"Empty\") })]);\n"
+        ));
+        assert!(zod.contains("// `Page` is left out: generic types are validated per instantiation\n"));
+        assert!(zod.contains("export const EmailSchema: z.ZodType<Email> = z.string() as z.ZodType<Email>;\n"));
+    }
+
+    #[test]
+    fn test_io_ts_codecs_split_optional_fields() {
+        let types = analyze_rust("pub struct User { pub id: u64, #[serde(default)] pub tags: Vec<String> }\npub enum Color { Red, DarkBlue }");
+        let io_ts = Generator::new(Language::TypeScript).generate_validators(ValidatorLibrary::IoTs, "./types", &types).unwrap();
+        assert!(io_ts.contains(
+            "export const UserCodec: t.Type<User> = t.intersection([t.type({ id: t.number }), t.partial({ tags: t.array(t.string) })]);\n"
+        ));
+        assert!(io_ts.contains("export const ColorCodec: t.Type<Color> = t.union([t.literal(\"Red\"), t.literal(\"DarkBlue\")]);\n"));
+    }
+
+    #[test]
+    fn test_exact_numbers_are_digit_strings() {
+        let types = analyze_rust("pub struct Ledger { pub balance: rust_decimal::Decimal, pub supply: num_bigint::BigInt }");
+        let generator = Generator::new(Language::TypeScript);
+        assert!(generator.generate(&types).unwrap().contains("balance: string;"));
+        let zod = generator.generate_validators(ValidatorLibrary::Zod, "./types", &types).unwrap();
+        assert!(zod.contains("balance: z.string().regex(/^-?[0-9]+(\\.[0-9]+)?$/)"));
+        assert!(zod.contains("supply: z.string().regex(/^-?[0-9]+$/)"));
+        let io_ts = generator.generate_validators(ValidatorLibrary::IoTs, "./types", &types).unwrap();
+        assert!(io_ts.contains("t.type({ balance: t.string, supply: t.string })"));
+    }
+}
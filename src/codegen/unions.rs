@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Lowering of ad-hoc unions (`string | number`) for targets without them
+// Rust, Gleam and ReScript get a synthesized enum whose variant tags define the wire format
+
+use super::{camel_case, render_doc, render_type, snake_case};
+use crate::ir::{ContainerType, IrType, PrimitiveType};
+
+/// How a synthesized union enum appears on the wire; untagged by default,
+/// which reads what a native-union producer writes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum UnionTagging {
+    /// Bare member values, exactly as a TypeScript producer sends them;
+    /// decoders try variants in order
+    #[default]
+    Untagged,
+    /// `{ "<tag>": "<variant tag>", "<content>": value }`
+    Adjacent { tag: String, content: String },
+}
+
+/// One variant of a synthesized union enum
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionVariant {
+    /// Constructor name (`String`, `Number`)
+    pub name: String,
+    /// Tag written on the wire (`"string"`, `"number"`)
+    pub tag: String,
+    pub ty: IrType,
+}
+
+/// An ad-hoc union lowered to a named, tagged enum
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoweredUnion {
+    pub name: String,
+    pub variants: Vec<UnionVariant>,
+    pub tagging: UnionTagging,
+}
+
+/// Synthesized enum name for a union (`string | number` → `StringOrNumber`)
+pub fn union_name(members: &[IrType]) -> String {
+    members.iter().map(variant_name).collect::<Vec<_>>().join("Or")
+}
+
+fn variant_name(ty: &IrType) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => "Bool".to_string(),
+        IrType::Primitive(PrimitiveType::String) => "String".to_string(),
+        IrType::Primitive(PrimitiveType::Unit) => "Null".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => "DateTime".to_string(),
+        IrType::Primitive(PrimitiveType::Uuid) => "Uuid".to_string(),
+        IrType::Primitive(PrimitiveType::Decimal) => "Decimal".to_string(),
+        IrType::Primitive(PrimitiveType::BigInt) => "BigInt".to_string(),
+        IrType::Primitive(PrimitiveType::Bytes) => "Bytes".to_string(),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64) => "Number".to_string(),
+        IrType::Primitive(_) => "Int".to_string(),
+        IrType::Container(ContainerType::Vec(inner)) => format!("{}List", variant_name(inner)),
+        IrType::Container(ContainerType::Array(inner, _)) => format!("{}Array", variant_name(inner)),
+        IrType::Container(ContainerType::Option(inner)) => format!("Optional{}", variant_name(inner)),
+        IrType::Container(ContainerType::Result(ok, _)) => format!("{}Result", variant_name(ok)),
+        IrType::Container(ContainerType::Map(_, value)) => format!("{}Map", variant_name(value)),
+        IrType::Reference(name) | IrType::TypeParam(name) | IrType::Alias(name, _) | IrType::TraitObject(name) => {
+            super::pascal_case(name)
+        }
+        IrType::Generic(name, args) => format!("{}{}", super::pascal_case(name), args.iter().map(variant_name).collect::<String>()),
+        IrType::Any => "Value".to_string(),
+        IrType::Function(_, _) => "Callback".to_string(),
+        IrType::Union(members) => union_name(members),
+        IrType::Tuple(members) => format!("{}Tuple", members.iter().map(variant_name).collect::<String>()),
+    }
+}
+
+/// Lower union members into a named enum; `name` overrides the synthesized name
+pub fn lower_union(members: &[IrType], name: Option<&str>, tagging: UnionTagging) -> LoweredUnion {
+    let mut variants: Vec<UnionVariant> = Vec::new();
+    for member in members {
+        let mut variant = variant_name(member);
+        // Two members can share a name (`i32 | i64` → `Int`); keep constructors unique
+        if variants.iter().any(|v| v.name == variant) {
+            variant = format!("{}{}", variant, variants.len());
+        }
+        variants.push(UnionVariant {
+            tag: snake_case(&variant),
+            name: variant,
+            ty: member.clone(),
+        });
+    }
+
+    LoweredUnion {
+        name: name.map(str::to_string).unwrap_or_else(|| union_name(members)),
+        variants,
+        tagging,
+    }
+}
+
+impl LoweredUnion {
+    /// Human-readable description of the wire shape, for reports and doc comments
+    pub fn wire_format(&self) -> String {
+        match &self.tagging {
+            UnionTagging::Untagged => {
+                let members: Vec<String> = self
+                    .variants
+                    .iter()
+                    .map(|v| render_type(&v.ty, "typescript"))
+                    .collect();
+                format!("untagged: {}", members.join(" | "))
+            }
+            UnionTagging::Adjacent { tag, content } => {
+                let members: Vec<String> = self
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        format!(
+                            "{{ \"{}\": \"{}\", \"{}\": {} }}",
+                            tag,
+                            v.tag,
+                            content,
+                            render_type(&v.ty, "typescript")
+                        )
+                    })
+                    .collect();
+                format!("adjacently tagged: {}", members.join(" | "))
+            }
+        }
+    }
+
+    /// Render the synthesized enum for a target without native ad-hoc unions
+    pub fn render(&self, target: &str) -> Option<String> {
+        match target {
+            "rust" => Some(self.render_rust()),
+            "gleam" => Some(self.render_gleam()),
+            "rescript" => Some(self.render_rescript()),
+            "typescript" => Some(self.render_typescript()),
+            _ => None,
+        }
+    }
+
+    fn render_rust(&self) -> String {
+        let mut code = format!("/// Synthesized from an ad-hoc union\n/// Wire format: {}\n", self.wire_format());
+        code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        match &self.tagging {
+            UnionTagging::Untagged => code.push_str("#[serde(untagged)]\n"),
+            UnionTagging::Adjacent { tag, content } => {
+                code.push_str(&format!("#[serde(tag = \"{}\", content = \"{}\")]\n", tag, content));
+            }
+        }
+        code.push_str(&format!("pub enum {} {{\n", self.name));
+        for variant in &self.variants {
+            if matches!(self.tagging, UnionTagging::Adjacent { .. }) {
+                code.push_str(&format!("    #[serde(rename = \"{}\")]\n", variant.tag));
+            }
+            match &variant.ty {
+                IrType::Primitive(PrimitiveType::Unit) => code.push_str(&format!("    {},\n", variant.name)),
+                ty => code.push_str(&format!("    {}({}),\n", variant.name, render_type(ty, "rust"))),
+            }
+        }
+        code.push_str("}\n");
+        code
+    }
+
+    fn render_gleam(&self) -> String {
+        let mut code = format!("/// Synthesized from an ad-hoc union\n/// Wire format: {}\n", self.wire_format());
+        code.push_str(&format!("pub type {} {{\n", self.name));
+        for variant in &self.variants {
+            match &variant.ty {
+                IrType::Primitive(PrimitiveType::Unit) => {
+                    code.push_str(&format!("  {}{}\n", self.name, variant.name));
+                }
+                ty => code.push_str(&format!("  {}{}({})\n", self.name, variant.name, render_type(ty, "gleam"))),
+            }
+        }
+        code.push_str("}\n\n");
+
+        let function = snake_case(&self.name);
+        code.push_str(&format!("pub fn {}_to_json(value: {}) -> json.Json {{\n  case value {{\n", function, self.name));
+        for variant in &self.variants {
+            let (pattern, encoded) = match &variant.ty {
+                IrType::Primitive(PrimitiveType::Unit) => (format!("{}{}", self.name, variant.name), "json.null()".to_string()),
+                ty => (format!("{}{}(inner)", self.name, variant.name), gleam_json_encoder(ty, "inner")),
+            };
+            let body = match &self.tagging {
+                UnionTagging::Untagged => encoded,
+                UnionTagging::Adjacent { tag, content } => format!(
+                    "json.object([#(\"{}\", json.string(\"{}\")), #(\"{}\", {})])",
+                    tag, variant.tag, content, encoded
+                ),
+            };
+            code.push_str(&format!("    {} -> {}\n", pattern, body));
+        }
+        code.push_str("  }\n}\n");
+        code
+    }
+
+    /// Untagged, an `@unboxed` variant, which ReScript checks can tell its
+    /// members apart at runtime; adjacently tagged, a `@tag` variant whose
+    /// inline record holds the content
+    fn render_rescript(&self) -> String {
+        let mut code = render_doc(&format!("Synthesized from an ad-hoc union\nWire format: {}", self.wire_format()), "rescript", "");
+        match &self.tagging {
+            UnionTagging::Untagged => code.push_str("@unboxed\n"),
+            UnionTagging::Adjacent { tag, .. } => code.push_str(&format!("@tag(\"{}\")\n", tag)),
+        }
+        code.push_str(&format!("type {} =\n", camel_case(&self.name)));
+        for variant in &self.variants {
+            let ty = render_type(&variant.ty, "rescript");
+            code.push_str(&match (&self.tagging, &variant.ty) {
+                (UnionTagging::Untagged, IrType::Primitive(PrimitiveType::Unit)) => format!("  | @as(null) {}\n", variant.name),
+                (UnionTagging::Untagged, _) => format!("  | {}({})\n", variant.name, ty),
+                (UnionTagging::Adjacent { .. }, IrType::Primitive(PrimitiveType::Unit)) => {
+                    format!("  | @as(\"{}\") {}\n", variant.tag, variant.name)
+                }
+                (UnionTagging::Adjacent { content, .. }, _) => {
+                    format!("  | @as(\"{}\") {}({{@as(\"{}\") value: {}}})\n", variant.tag, variant.name, content, ty)
+                }
+            });
+        }
+        code
+    }
+
+    /// TypeScript view of the wire format, so TS producers emit the synthesized tags
+    fn render_typescript(&self) -> String {
+        let members: Vec<String> = self
+            .variants
+            .iter()
+            .map(|v| match &self.tagging {
+                UnionTagging::Untagged => render_type(&v.ty, "typescript"),
+                UnionTagging::Adjacent { tag, content } => format!(
+                    "{{ {}: \"{}\"; {}: {} }}",
+                    tag,
+                    v.tag,
+                    content,
+                    render_type(&v.ty, "typescript")
+                ),
+            })
+            .collect();
+        format!("export type {} =\n  | {};\n", self.name, members.join("\n  | "))
+    }
+}
+
+pub(super) fn gleam_json_encoder(ty: &IrType, value: &str) -> String {
+    match ty {
+        IrType::Primitive(PrimitiveType::Bool) => format!("json.bool({})", value),
+        IrType::Primitive(PrimitiveType::String | PrimitiveType::Uuid) => format!("json.string({})", value),
+        IrType::Primitive(PrimitiveType::F32 | PrimitiveType::F64 | PrimitiveType::Decimal) => format!("json.float({})", value),
+        IrType::Primitive(PrimitiveType::Unit) => "json.null()".to_string(),
+        IrType::Primitive(PrimitiveType::DateTime) => format!("json.string(birl.to_iso8601({}))", value),
+        IrType::Primitive(PrimitiveType::Bytes) => format!("json.string(bit_array.base64_encode({}, True))", value),
+        IrType::Primitive(_) => format!("json.int({})", value),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Array(inner, _)) => {
+            format!("json.array({}, fn(item) {{ {} }})", value, gleam_json_encoder(inner, "item"))
+        }
+        IrType::Container(ContainerType::Option(inner)) => {
+            format!("json.nullable({}, fn(item) {{ {} }})", value, gleam_json_encoder(inner, "item"))
+        }
+        IrType::TypeParam(name) => format!("encode_{}({})", name.to_lowercase(), value),
+        IrType::Generic(name, args) => {
+            let encoders: String = args.iter().map(|arg| format!(", fn(item) {{ {} }}", gleam_json_encoder(arg, "item"))).collect();
+            format!("{}_to_json({}{})", snake_case(name), value, encoders)
+        }
+        other => format!("{}_to_json({})", snake_case(&render_type(other, "gleam")), value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_or_number() -> Vec<IrType> {
+        vec![
+            IrType::Primitive(PrimitiveType::String),
+            IrType::Primitive(PrimitiveType::F64),
+        ]
+    }
+
+    fn adjacent() -> UnionTagging {
+        UnionTagging::Adjacent {
+            tag: "type".to_string(),
+            content: "value".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_synthesized_name_and_tags() {
+        let lowered = lower_union(&string_or_number(), None, adjacent());
+        assert_eq!(lowered.name, "StringOrNumber");
+        let tags: Vec<_> = lowered.variants.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["string", "number"]);
+    }
+
+    #[test]
+    fn test_render_rust_adjacent() {
+        let rust = lower_union(&string_or_number(), Some("Id"), adjacent())
+            .render("rust")
+            .unwrap();
+        assert!(rust.contains("#[serde(tag = \"type\", content = \"value\")]"));
+        assert!(rust.contains("pub enum Id {"));
+        assert!(rust.contains("    #[serde(rename = \"number\")]\n    Number(f64),"));
+    }
+
+    #[test]
+    fn test_render_gleam_and_typescript_share_tags() {
+        let lowered = lower_union(&string_or_number(), None, adjacent());
+        let gleam = lowered.render("gleam").unwrap();
+        assert!(gleam.contains("  StringOrNumberString(String)"));
+        assert!(gleam.contains(
+            "StringOrNumberNumber(inner) -> json.object([#(\"type\", json.string(\"number\")), #(\"value\", json.float(inner))])"
+        ));
+        let ts = lowered.render("typescript").unwrap();
+        assert!(ts.contains("| { type: \"number\"; value: number }"));
+        let rescript = lowered.render("rescript").unwrap();
+        assert!(rescript.contains("@tag(\"type\")\ntype stringOrNumber =\n  | @as(\"string\") String({@as(\"value\") value: string})\n"));
+    }
+
+    #[test]
+    fn test_untagged_keeps_bare_values() {
+        let lowered = lower_union(&string_or_number(), None, UnionTagging::default());
+        assert_eq!(lowered.wire_format(), "untagged: string | number");
+        assert!(lowered.render("rust").unwrap().contains("#[serde(untagged)]"));
+        assert!(lowered.render("gleam").unwrap().contains("    StringOrNumberNumber(inner) -> json.float(inner)\n"));
+        assert!(lowered.render("rescript").unwrap().contains("@unboxed\ntype stringOrNumber =\n  | String(string)\n  | Number(float)\n"));
+    }
+}
@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Sum types laid out on the wire as `TypeDef::tagging` says
+// Rust gets a serde enum, TypeScript a union of the JSON shapes, Gleam a
+// custom type with an encoder and ReScript a variant annotated to compile to
+// them, so every side reads and writes the same values; with `bare_units`,
+// variants without a payload are their bare tag string, as ReScript compiles them
+
+use super::recursion::mentions;
+use super::unions::gleam_json_encoder;
+use super::{camel_case, pascal_case, render_type, render_type_params, render_variant_type, snake_case};
+use crate::api::TypeDef;
+use crate::ir::{IrField, IrVariant, VariantTagging};
+
+const DERIVE: &str = "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n";
+
+/// Rust enum for `def`'s variants in its wire layout; `None` without one.
+/// `catch_all` adds a variant for tags the binding doesn't list
+pub fn render_rust_enum(def: &TypeDef, catch_all: bool) -> Option<String> {
+    let tagging = def.tagging.as_ref()?;
+    if def.variants.is_empty() {
+        return None;
+    }
+    let name = pascal_case(&def.name);
+    let params = render_type_params(&def.type_params, "rust");
+    // serde writes unit variants as bare strings only when externally tagged
+    if !def.bare_units || *tagging == VariantTagging::External {
+        let variants: Vec<&IrVariant> = def.variants.iter().collect();
+        return Some(tagged_enum(&format!("{name}{params}"), &variants, tagging, catch_all));
+    }
+    let (bare, payload): (Vec<&IrVariant>, Vec<&IrVariant>) = def.variants.iter().partition(|variant| variant.fields.is_empty());
+    Some(match (bare.is_empty(), payload.is_empty()) {
+        (_, true) => bare_enum(&name, &bare, catch_all),
+        (true, false) => tagged_enum(&format!("{name}{params}"), &payload, tagging, catch_all),
+        // Both shapes at once: the string form is tried first, then the object;
+        // only the payloads use the parameters
+        (false, false) => format!(
+            "{DERIVE}#[serde(untagged)]\npub enum {name}{params} {{\n    Tag({name}Tag),\n    Payload({name}Payload{params}),\n}}\n\n{}\n{}",
+            bare_enum(&format!("{name}Tag"), &bare, catch_all),
+            tagged_enum(&format!("{name}Payload{params}"), &payload, tagging, catch_all)
+        ),
+    })
+}
+
+/// Variants without a payload, which are plain strings on the wire
+fn bare_enum(name: &str, variants: &[&IrVariant], catch_all: bool) -> String {
+    let mut code = format!("{DERIVE}pub enum {name} {{\n");
+    for variant in variants {
+        code.push_str(&render_variant(variant, ""));
+    }
+    if catch_all {
+        code.push_str("    /// A tag this binding doesn't list\n    #[serde(untagged)]\n    Other(String),\n");
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn tagged_enum(name: &str, variants: &[&IrVariant], tagging: &VariantTagging, catch_all: bool) -> String {
+    let attribute = match tagging {
+        VariantTagging::External => String::new(),
+        VariantTagging::Internal(tag) => format!("#[serde(tag = \"{tag}\")]\n"),
+        VariantTagging::Adjacent(tag, content) => format!("#[serde(tag = \"{tag}\", content = \"{content}\")]\n"),
+        VariantTagging::Untagged => "#[serde(untagged)]\n".to_string(),
+    };
+    let mut code = format!("{DERIVE}{attribute}pub enum {name} {{\n");
+    for variant in variants {
+        code.push_str(&render_variant(variant, &render_payload(variant, tagging)));
+    }
+    if catch_all {
+        code.push_str(match tagging {
+            VariantTagging::Untagged => "    /// A shape this binding doesn't list\n    Other(serde_json::Value),\n",
+            _ => "    /// A tag this binding doesn't list; its payload is dropped\n    #[serde(other)]\n    Other,\n",
+        });
+    }
+    code.push_str("}\n");
+    code
+}
+
+/// Positional payloads are a tuple, except inside the tagging object where
+/// they are keyed `_0`, `_1`, ...; named ones are a struct variant
+fn render_payload(variant: &IrVariant, tagging: &VariantTagging) -> String {
+    if variant.fields.is_empty() {
+        return String::new();
+    }
+    let positional = variant.fields.iter().all(is_positional);
+    if positional && !matches!(tagging, VariantTagging::Internal(_)) {
+        let payload: Vec<String> = variant.fields.iter().map(|field| render_type(&field.ty, "rust")).collect();
+        return format!("({})", payload.join(", "));
+    }
+    let mut code = " {\n".to_string();
+    for field in &variant.fields {
+        let ident = if positional { format!("_{}", field.name) } else { snake_case(&field.name) };
+        if !positional && ident != field.name {
+            code.push_str(&format!("        #[serde(rename = \"{}\")]\n", field.name));
+        }
+        code.push_str(&format!("        {ident}: {},\n", render_type(&field.ty, "rust")));
+    }
+    code.push_str("    }");
+    code
+}
+
+fn render_variant(variant: &IrVariant, payload: &str) -> String {
+    let ident = pascal_case(&variant.name);
+    let tag = variant.wire_tag();
+    let rename = if ident != tag { format!("    #[serde(rename = \"{tag}\")]\n") } else { String::new() };
+    format!("{rename}    {ident}{payload},\n")
+}
+
+/// TypeScript union of the JSON shapes of `def`'s variants; `None` without a layout
+pub fn render_typescript_union(def: &TypeDef) -> Option<String> {
+    let tagging = def.tagging.as_ref()?;
+    if def.variants.is_empty() {
+        return None;
+    }
+    let members: Vec<String> = def
+        .variants
+        .iter()
+        .map(|variant| {
+            let tag = format!("\"{}\"", variant.wire_tag());
+            if variant.fields.is_empty() {
+                return match tagging {
+                    _ if def.bare_units => tag,
+                    VariantTagging::External => tag,
+                    VariantTagging::Internal(key) | VariantTagging::Adjacent(key, _) => format!("{{ {}: {} }}", typescript_key(key), tag),
+                    VariantTagging::Untagged => "null".to_string(),
+                };
+            }
+            match tagging {
+                VariantTagging::External => format!("{{ {}: {} }}", typescript_key(variant.wire_tag()), typescript_payload(&variant.fields)),
+                VariantTagging::Internal(key) => {
+                    let fields: Vec<String> = variant
+                        .fields
+                        .iter()
+                        .map(|field| format!("{}: {}", typescript_key(&payload_key(field)), render_type(&field.ty, "typescript")))
+                        .collect();
+                    format!("{{ {}: {}; {} }}", typescript_key(key), tag, fields.join("; "))
+                }
+                VariantTagging::Adjacent(key, content) => format!(
+                    "{{ {}: {}; {}: {} }}",
+                    typescript_key(key),
+                    tag,
+                    typescript_key(content),
+                    typescript_payload(&variant.fields)
+                ),
+                VariantTagging::Untagged => typescript_payload(&variant.fields),
+            }
+        })
+        .collect();
+    Some(format!(
+        "export type {}{} =\n  | {};\n",
+        pascal_case(&def.name),
+        render_type_params(&def.type_params, "typescript"),
+        members.join("\n  | ")
+    ))
+}
+
+/// A lone positional payload is its value, several are an array, and named
+/// ones an object
+fn typescript_payload(fields: &[IrField]) -> String {
+    match fields {
+        [only] if is_positional(only) => render_type(&only.ty, "typescript"),
+        _ if fields.iter().all(is_positional) => {
+            format!("[{}]", fields.iter().map(|field| render_type(&field.ty, "typescript")).collect::<Vec<_>>().join(", "))
+        }
+        _ => {
+            let fields: Vec<String> =
+                fields.iter().map(|field| format!("{}: {}", typescript_key(&field.name), render_type(&field.ty, "typescript"))).collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+    }
+}
+
+pub(super) fn typescript_key(key: &str) -> String {
+    let identifier = key.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        key.to_string()
+    } else {
+        format!("\"{}\"", key)
+    }
+}
+
+/// ReScript variant whose runtime values are `def`'s wire layout, through
+/// `@tag`, `@unboxed` and `@as`; `None` for layouts no variant compiles to:
+/// a tag wrapped around a payload, or a unit tagged inside an object
+pub fn render_rescript_variant(def: &TypeDef) -> Option<String> {
+    let tagging = def.tagging.as_ref()?;
+    if def.variants.is_empty() {
+        return None;
+    }
+    // A constructor without a payload compiles to its tag string
+    let units = def.variants.iter().any(|variant| variant.fields.is_empty());
+    let bare = def.bare_units || !units;
+    let (annotation, null_units) = match tagging {
+        _ if def.variants.iter().all(|variant| variant.fields.is_empty()) && (bare || *tagging == VariantTagging::External) => (String::new(), false),
+        VariantTagging::Internal(tag) if bare && tag == "TAG" => (String::new(), false),
+        VariantTagging::Internal(tag) if bare => (format!("@tag(\"{}\")\n", tag), false),
+        // An unboxed constructor holds one value, and a serde unit is `null`
+        VariantTagging::Untagged if def.variants.iter().all(|variant| variant.fields.len() <= 1 || !variant.fields.iter().all(is_positional)) => {
+            ("@unboxed\n".to_string(), !def.bare_units)
+        }
+        _ => return None,
+    };
+    let name = camel_case(&def.name);
+    let params = render_type_params(&def.type_params, "rescript");
+    // ReScript only lets a type refer to itself when declared `rec`
+    let rec = match def.variants.iter().flat_map(|variant| &variant.fields).any(|field| mentions(&field.ty, &def.name)) {
+        true => "rec ",
+        false => "",
+    };
+    let mut code = format!("{annotation}type {rec}{name}{params} =\n");
+    for variant in &def.variants {
+        let constructor = pascal_case(&variant.name);
+        let tag = match variant.fields.is_empty() {
+            true if null_units => "@as(null) ".to_string(),
+            _ if variant.wire_tag() != constructor => format!("@as(\"{}\") ", variant.wire_tag()),
+            _ => String::new(),
+        };
+        let payload = if variant.fields.is_empty() {
+            String::new()
+        } else if variant.fields.iter().all(is_positional) {
+            format!("({})", variant.fields.iter().map(|field| render_type(&field.ty, "rescript")).collect::<Vec<_>>().join(", "))
+        } else {
+            // An inline record; `@as` keeps a key its camelCase label would change
+            let fields: Vec<String> = variant
+                .fields
+                .iter()
+                .map(|field| {
+                    let label = camel_case(&field.name);
+                    let key = if label != field.name { format!("@as(\"{}\") ", field.name) } else { String::new() };
+                    format!("{}{}: {}", key, label, render_type(&field.ty, "rescript"))
+                })
+                .collect();
+            format!("({{{}}})", fields.join(", "))
+        };
+        code.push_str(&format!("  | {}{}{}\n", tag, constructor, payload));
+    }
+    Some(code)
+}
+
+/// Gleam custom type for `def`'s variants and a `<name>_to_json` encoder
+/// writing its wire layout; `None` without one
+pub fn render_gleam_type(def: &TypeDef) -> Option<String> {
+    let tagging = def.tagging.as_ref()?;
+    let mut code = render_variant_type(&def.name, &def.type_params, &def.variants, "gleam")?;
+    let name = pascal_case(&def.name);
+    // A generic type's encoder takes one for each parameter
+    let encoders: String = def
+        .type_params
+        .iter()
+        .map(|param| format!(", encode_{0}: fn({0}) -> json.Json", param.to_lowercase()))
+        .collect();
+    code.push_str(&format!(
+        "\npub fn {}_to_json(value: {}{}{}) -> json.Json {{\n  case value {{\n",
+        snake_case(&name),
+        name,
+        render_type_params(&def.type_params, "gleam"),
+        encoders
+    ));
+    for variant in &def.variants {
+        let tag = format!("json.string(\"{}\")", variant.wire_tag());
+        let tagged = |key: &str, rest: &[String]| {
+            let entries: Vec<String> = std::iter::once(format!("#(\"{}\", {})", key, tag)).chain(rest.iter().cloned()).collect();
+            format!("json.object([{}])", entries.join(", "))
+        };
+        let constructor = pascal_case(&variant.name);
+        if variant.fields.is_empty() {
+            let body = match tagging {
+                _ if def.bare_units => tag.clone(),
+                VariantTagging::External => tag.clone(),
+                VariantTagging::Internal(key) | VariantTagging::Adjacent(key, _) => tagged(key, &[]),
+                VariantTagging::Untagged => "json.null()".to_string(),
+            };
+            code.push_str(&format!("    {} -> {}\n", constructor, body));
+            continue;
+        }
+        let bindings: Vec<String> = variant.fields.iter().map(gleam_binding).collect();
+        let pattern = if variant.fields.iter().all(is_positional) {
+            bindings.join(", ")
+        } else {
+            bindings.iter().map(|binding| format!("{binding}: {binding}")).collect::<Vec<_>>().join(", ")
+        };
+        let body = match tagging {
+            VariantTagging::External => format!("json.object([#(\"{}\", {})])", variant.wire_tag(), gleam_payload(&variant.fields)),
+            VariantTagging::Internal(key) => {
+                let entries: Vec<String> = variant
+                    .fields
+                    .iter()
+                    .map(|field| format!("#(\"{}\", {})", payload_key(field), gleam_json_encoder(&field.ty, &gleam_binding(field))))
+                    .collect();
+                tagged(key, &entries)
+            }
+            VariantTagging::Adjacent(key, content) => tagged(key, &[format!("#(\"{}\", {})", content, gleam_payload(&variant.fields))]),
+            VariantTagging::Untagged => gleam_payload(&variant.fields),
+        };
+        code.push_str(&format!("    {}({}) -> {}\n", constructor, pattern, body));
+    }
+    code.push_str("  }\n}\n");
+    Some(code)
+}
+
+fn gleam_payload(fields: &[IrField]) -> String {
+    match fields {
+        [only] if is_positional(only) => gleam_json_encoder(&only.ty, &gleam_binding(only)),
+        _ if fields.iter().all(is_positional) => {
+            let items: Vec<String> = fields.iter().map(|field| gleam_json_encoder(&field.ty, &gleam_binding(field))).collect();
+            format!("json.preprocessed_array([{}])", items.join(", "))
+        }
+        _ => {
+            let entries: Vec<String> = fields
+                .iter()
+                .map(|field| format!("#(\"{}\", {})", field.name, gleam_json_encoder(&field.ty, &gleam_binding(field))))
+                .collect();
+            format!("json.object([{}])", entries.join(", "))
+        }
+    }
+}
+
+fn gleam_binding(field: &IrField) -> String {
+    if is_positional(field) {
+        format!("value{}", field.name)
+    } else {
+        snake_case(&field.name)
+    }
+}
+
+pub(super) fn is_positional(field: &IrField) -> bool {
+    field.name.parse::<usize>().is_ok()
+}
+
+/// Key of a payload field next to the tag: positional ones are `_0`, `_1`, ...
+pub(super) fn payload_key(field: &IrField) -> String {
+    if is_positional(field) {
+        format!("_{}", field.name)
+    } else {
+        field.name.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    fn analyze(source: &str) -> TypeDef {
+        Registry::with_builtins().analyzer(Language::ReScript).unwrap().analyze(source).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_tagged_constructors_match_the_runtime() {
+        let shape = analyze("@tag(\"kind\")\ntype shape = | @as(\"circle\") Circle({radius: float}) | Square(float) | Point");
+        let rust = render_rust_enum(&shape, false).unwrap();
+        assert!(rust.contains("#[serde(untagged)]\npub enum Shape {\n    Tag(ShapeTag),\n    Payload(ShapePayload),\n}\n"));
+        assert!(rust.contains("pub enum ShapeTag {\n    Point,\n}\n"));
+        assert!(rust.contains(
+            "#[serde(tag = \"kind\")]\npub enum ShapePayload {\n    #[serde(rename = \"circle\")]\n    Circle {\n        radius: f64,\n    },\n    \
+             Square {\n        _0: f64,\n    },\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_unboxed_payloads_cross_alone() {
+        let value = analyze("@unboxed\ntype value = Str(string) | Num(float)");
+        assert_eq!(
+            render_rust_enum(&value, false).unwrap(),
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n#[serde(untagged)]\npub enum Value {\n    Str(String),\n    Num(f64),\n}\n"
+        );
+        assert_eq!(render_rust_enum(&analyze("type point = {x: float}"), false), None);
+    }
+
+    #[test]
+    fn test_rescript_variants_compile_to_the_wire_layout() {
+        let shape = analyze("@tag(\"kind\")\ntype shape = | @as(\"circle\") Circle({radius: float}) | Square(float) | Point");
+        assert_eq!(
+            render_rescript_variant(&shape).unwrap(),
+            "@tag(\"kind\")\ntype shape =\n  | @as(\"circle\") Circle({radius: float})\n  | Square(float)\n  | Point\n"
+        );
+        let registry = Registry::with_builtins();
+        let rust = registry
+            .analyzer(Language::Rust)
+            .unwrap()
+            .analyze("#[serde(untagged)]\npub enum Reading { Value { raw_value: f64 }, Missing }\npub enum Color { Red, DarkBlue }\npub enum Event { Click { x: i32 } }")
+            .unwrap();
+        assert_eq!(
+            render_rescript_variant(&rust[0]).unwrap(),
+            "@unboxed\ntype reading =\n  | Value({@as(\"raw_value\") rawValue: float})\n  | @as(null) Missing\n"
+        );
+        assert_eq!(render_rescript_variant(&rust[1]).unwrap(), "type color =\n  | Red\n  | DarkBlue\n");
+        // `{"Click": {...}}` wraps the payload in its tag, which no variant compiles to
+        assert_eq!(render_rescript_variant(&rust[2].clone().with_tagging(Some(VariantTagging::External), false)), None);
+    }
+}
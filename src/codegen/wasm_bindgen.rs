@@ -0,0 +1,417 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// wasm-bindgen glue for Rust structs and free functions
+// Each struct gets a JavaScript class wrapping it, with a constructor and field
+// getters, and each function a wrapper under its camelCase name. The glue is
+// meant for a child module (`mod wasm;`) of the one defining them, so private
+// fields are in reach; consumer modules give TypeScript and ReScript the same surface
+
+use super::{camel_case, pascal_case, render_doc, render_type, snake_case};
+use crate::analyzers::rust_analyzer::{is_zero_sized, rust_type_name, to_ir, RustFieldType, RustFunction, RustType, RustTypeKind};
+use crate::ir::{self, IrType, PrimitiveType};
+
+/// How a value crosses the wasm boundary
+#[derive(Debug, Clone, PartialEq)]
+enum Abi {
+    /// As itself: wasm-bindgen converts numbers, strings, and options of them
+    Direct,
+    /// As the class wrapping an exported struct
+    Handle(String),
+    /// As a JSON-compatible `JsValue`, through serde
+    Serde,
+}
+
+fn abi(field_type: &RustFieldType, classes: &[&str]) -> Abi {
+    match field_type {
+        RustFieldType::I8
+        | RustFieldType::I16
+        | RustFieldType::I32
+        | RustFieldType::I64
+        | RustFieldType::U8
+        | RustFieldType::U16
+        | RustFieldType::U32
+        | RustFieldType::U64
+        | RustFieldType::Isize
+        | RustFieldType::Usize
+        | RustFieldType::F32
+        | RustFieldType::F64
+        | RustFieldType::Bool
+        | RustFieldType::String => Abi::Direct,
+        RustFieldType::Option(inner) if abi(inner, classes) == Abi::Direct => Abi::Direct,
+        RustFieldType::Alias(_, inner) if abi(inner, classes) == Abi::Direct => Abi::Direct,
+        RustFieldType::Struct(name) if classes.contains(&name.as_str()) => Abi::Handle(name.clone()),
+        _ => Abi::Serde,
+    }
+}
+
+/// Whether a direct value is read out by copy rather than `clone`
+fn is_copy(field_type: &RustFieldType) -> bool {
+    match field_type {
+        RustFieldType::String => false,
+        RustFieldType::Option(inner) | RustFieldType::Alias(_, inner) => is_copy(inner),
+        _ => true,
+    }
+}
+
+/// Behaviour can't cross as a value, and borrows have no owner on the other side
+fn crossable(field_type: &RustFieldType) -> bool {
+    let ty = to_ir(field_type);
+    ir::functions(&ty).is_empty() && ir::trait_objects(&ty).is_empty()
+}
+
+/// Structs the glue wraps in a class; generic, tuple and borrowing structs have no class form
+fn is_exported(rust_type: &RustType) -> bool {
+    rust_type.kind == RustTypeKind::Struct
+        && rust_type.type_params.is_empty()
+        && rust_type.const_params.is_empty()
+        && rust_type.fields.iter().all(|field| {
+            !field.name.starts_with(|c: char| c.is_ascii_digit())
+                && !matches!(field.field_type, RustFieldType::Borrowed(_, _) | RustFieldType::Cow(_, _))
+                && crossable(&field.field_type)
+        })
+}
+
+fn is_exported_fn(function: &RustFunction) -> bool {
+    function.params.iter().map(|(_, ty)| ty).chain([&function.ret]).all(crossable)
+}
+
+/// `#[wasm_bindgen]` glue for `types` and `functions`; anything left out is
+/// listed at the top with the reason
+pub fn generate_glue(types: &[RustType], functions: &[RustFunction]) -> String {
+    let classes: Vec<&str> = types.iter().filter(|t| is_exported(t)).map(|t| t.name.as_str()).collect();
+    let mut code = "use super::*;\nuse serde::Serialize;\nuse wasm_bindgen::prelude::*;\n".to_string();
+    for rust_type in types.iter().filter(|t| t.kind == RustTypeKind::Struct && !is_exported(t)) {
+        code.push_str(&format!(
+            "// `{}` is left out: generic, tuple and borrowing structs, and ones holding functions, have no class form\n",
+            rust_type.name
+        ));
+    }
+    for function in functions.iter().filter(|f| !is_exported_fn(f)) {
+        code.push_str(&format!("// `{}` is left out: functions and trait objects can't cross as values\n", function.name));
+    }
+    for rust_type in types.iter().filter(|t| classes.contains(&t.name.as_str())) {
+        code.push('\n');
+        code.push_str(&render_class(rust_type, &classes));
+    }
+    for function in functions.iter().filter(|f| is_exported_fn(f)) {
+        code.push('\n');
+        code.push_str(&render_function(function, &classes));
+    }
+    code
+}
+
+/// Expression serializing `value` into a JSON-compatible `JsValue`
+fn to_js(value: &str) -> String {
+    format!("{}.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?", value)
+}
+
+fn render_class(rust_type: &RustType, classes: &[&str]) -> String {
+    let name = &rust_type.name;
+    let mut code = rust_type.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    code.push_str(&format!(
+        "#[wasm_bindgen(js_name = {name})]\npub struct {name}Handle({name});\n\n#[wasm_bindgen(js_class = {name})]\nimpl {name}Handle {{\n"
+    ));
+
+    let mut params = Vec::new();
+    let mut inits = Vec::new();
+    let mut getters = String::new();
+    for field in &rust_type.fields {
+        let ident = &field.name;
+        if is_zero_sized(&field.field_type) {
+            inits.push(format!("{}: Default::default()", ident));
+            continue;
+        }
+        let getter = match camel_case(ident) {
+            js if js == *ident => "getter".to_string(),
+            js => format!("getter = {}", js),
+        };
+        let (param, init, ret, body) = match abi(&field.field_type, classes) {
+            Abi::Direct => {
+                let ty = rust_type_name(&field.field_type);
+                let read = if is_copy(&field.field_type) { format!("self.0.{}", ident) } else { format!("self.0.{}.clone()", ident) };
+                (ty.clone(), ident.clone(), ty, read)
+            }
+            Abi::Handle(class) => (
+                format!("&{}Handle", class),
+                format!("{ident}: {ident}.0.clone()"),
+                format!("{}Handle", class),
+                format!("{}Handle(self.0.{}.clone())", class, ident),
+            ),
+            Abi::Serde => (
+                "JsValue".to_string(),
+                format!("{ident}: serde_wasm_bindgen::from_value({ident})?"),
+                "Result<JsValue, JsError>".to_string(),
+                format!("Ok({})", to_js(&format!("self.0.{}", ident))),
+            ),
+        };
+        params.push(format!("{}: {}", ident, param));
+        inits.push(init);
+        getters.push_str(&format!(
+            "\n    #[wasm_bindgen({getter})]\n    pub fn {ident}(&self) -> {ret} {{\n        {body}\n    }}\n"
+        ));
+    }
+
+    let fallible = rust_type.fields.iter().any(|field| abi(&field.field_type, classes) == Abi::Serde && !is_zero_sized(&field.field_type));
+    let value = format!("{name}Handle({name} {{ {} }})", inits.join(", "));
+    let (ret, value) = match fallible {
+        true => (format!("Result<{name}Handle, JsError>"), format!("Ok({})", value)),
+        false => (format!("{name}Handle"), value),
+    };
+    code.push_str(&format!(
+        "    #[wasm_bindgen(constructor)]\n    pub fn new({}) -> {ret} {{\n        {value}\n    }}\n",
+        params.join(", ")
+    ));
+    code.push_str(&getters);
+    code.push_str("}\n");
+    code
+}
+
+fn render_function(function: &RustFunction, classes: &[&str]) -> String {
+    let mut code = function.doc.as_ref().map(|doc| render_doc(doc, "rust", "")).unwrap_or_default();
+    let mut params = Vec::new();
+    let mut decodes = String::new();
+    let mut args = Vec::new();
+    let mut fallible = false;
+    for (name, ty) in &function.params {
+        let (referent, borrow) = match ty {
+            RustFieldType::Borrowed(written, owned) if written.starts_with("&mut") => (owned.as_ref(), "&mut "),
+            RustFieldType::Borrowed(_, owned) => (owned.as_ref(), "&"),
+            RustFieldType::Cow(_, owned) => (owned.as_ref(), ""),
+            ty => (ty, ""),
+        };
+        match abi(referent, classes) {
+            // `&str` crosses as it is; other borrowed direct values are copied in
+            Abi::Direct if matches!(ty, RustFieldType::Borrowed(_, owned) if matches!(**owned, RustFieldType::String)) => {
+                params.push(format!("{}: &str", name));
+                args.push(name.clone());
+            }
+            Abi::Direct => {
+                params.push(format!("{}: {}", name, rust_type_name(referent)));
+                args.push(format!("{}{}", borrow, name));
+            }
+            Abi::Handle(class) if borrow.is_empty() => {
+                params.push(format!("{}: &{}Handle", name, class));
+                args.push(format!("{}.0.clone()", name));
+            }
+            Abi::Handle(class) => {
+                params.push(format!("{}: {}{}Handle", name, borrow, class));
+                args.push(format!("{}{}.0", borrow, name));
+            }
+            Abi::Serde => {
+                fallible = true;
+                params.push(format!("{}: JsValue", name));
+                let mutable = if borrow == "&mut " { "mut " } else { "" };
+                decodes.push_str(&format!(
+                    "    let {mutable}{name}: {} = serde_wasm_bindgen::from_value({name})?;\n",
+                    rust_type_name(referent)
+                ));
+                let into = if matches!(ty, RustFieldType::Cow(_, _)) { ".into()" } else { "" };
+                args.push(format!("{}{}{}", borrow, name, into));
+            }
+        }
+    }
+
+    let call = format!("{}({})", function.name, args.join(", "));
+    let (ret, body) = match (&function.ret, abi(&function.ret, classes)) {
+        (RustFieldType::Tuple(members), _) if members.is_empty() => (None, call),
+        (ty, Abi::Direct) => (Some(rust_type_name(ty)), call),
+        (_, Abi::Handle(class)) => (Some(format!("{}Handle", class)), format!("{}Handle({})", class, call)),
+        (_, Abi::Serde) => {
+            fallible = true;
+            (Some("JsValue".to_string()), to_js(&call))
+        }
+    };
+    let (ret, body) = match (fallible, ret) {
+        (true, Some(ret)) => (format!(" -> Result<{}, JsError>", ret), format!("Ok({})", body)),
+        (true, None) => (" -> Result<(), JsError>".to_string(), format!("{};\n    Ok(())", body)),
+        (false, Some(ret)) => (format!(" -> {}", ret), body),
+        (false, None) => (String::new(), format!("{};", body)),
+    };
+    code.push_str(&format!(
+        "#[wasm_bindgen(js_name = {})]\npub fn {}_js({}){} {{\n{}    {}\n}}\n",
+        camel_case(&function.name),
+        snake_case(&function.name),
+        params.join(", "),
+        ret,
+        decodes,
+        body
+    ));
+    code
+}
+
+/// Module through which `target` code uses the glue that `module` exports:
+/// TypeScript gets the classes and functions retyped where wasm-bindgen only
+/// knows `any`, ReScript gets externals for all of it
+pub fn generate_consumer(types: &[RustType], functions: &[RustFunction], module: &str, target: &str) -> Option<String> {
+    let classes: Vec<&str> = types.iter().filter(|t| is_exported(t)).map(|t| t.name.as_str()).collect();
+    let exported: Vec<&RustType> = types.iter().filter(|t| classes.contains(&t.name.as_str())).collect();
+    let functions: Vec<&RustFunction> = functions.iter().filter(|f| is_exported_fn(f)).collect();
+    let fields = |rust_type: &'_ RustType| {
+        rust_type
+            .fields
+            .iter()
+            .filter(|field| !is_zero_sized(&field.field_type))
+            .map(|field| (field.name.clone(), field.field_type.clone()))
+            .collect::<Vec<_>>()
+    };
+    let mut code = format!("// Consumer of the wasm-bindgen glue exported from {}\n", module);
+    match target {
+        "typescript" => {
+            code.push_str(&format!("import * as glue from \"{}\";\n\nexport {{ default as init }} from \"{}\";\n", module, module));
+            for rust_type in &exported {
+                let name = &rust_type.name;
+                let fields = fields(rust_type);
+                let members: String = fields
+                    .iter()
+                    .map(|(field, ty)| format!("  readonly {}: {};\n", camel_case(field), js_type(ty, &classes, target)))
+                    .collect();
+                let params: Vec<String> =
+                    fields.iter().map(|(field, ty)| format!("{}: {}", camel_case(field), js_type(ty, &classes, target))).collect();
+                code.push_str(&format!(
+                    "\nexport interface {name} {{\n{members}  free(): void;\n}}\n\
+                     export const {name} = glue.{name} as unknown as {{ new ({}): {name} }};\n",
+                    params.join(", ")
+                ));
+            }
+            for function in &functions {
+                let js = camel_case(&function.name);
+                let params: Vec<String> = function
+                    .params
+                    .iter()
+                    .map(|(name, ty)| format!("{}: {}", camel_case(name), js_type(ty, &classes, target)))
+                    .collect();
+                code.push_str(&format!(
+                    "\nexport const {js} = glue.{js} as unknown as ({}) => {};\n",
+                    params.join(", "),
+                    js_type(&function.ret, &classes, target)
+                ));
+            }
+        }
+        "rescript" => {
+            code.push('\n');
+            for rust_type in &exported {
+                code.push_str(&format!("type {}\n", camel_case(&rust_type.name)));
+            }
+            for rust_type in &exported {
+                let name = &rust_type.name;
+                let record = camel_case(name);
+                let fields = fields(rust_type);
+                let params: Vec<String> = fields.iter().map(|(_, ty)| js_type(ty, &classes, target)).collect();
+                code.push_str(&format!(
+                    "\n@module(\"{module}\") @new external make{name}: ({}) => {record} = \"{name}\"\n",
+                    params.join(", ")
+                ));
+                for (field, ty) in &fields {
+                    code.push_str(&format!(
+                        "@get external {record}{}: {record} => {} = \"{}\"\n",
+                        pascal_case(field),
+                        js_type(ty, &classes, target),
+                        camel_case(field)
+                    ));
+                }
+                code.push_str(&format!("@send external free{name}: {record} => unit = \"free\"\n"));
+            }
+            if !functions.is_empty() {
+                code.push('\n');
+            }
+            for function in &functions {
+                let js = camel_case(&function.name);
+                let params: Vec<String> = function.params.iter().map(|(_, ty)| js_type(ty, &classes, target)).collect();
+                let params = if params.is_empty() { "unit".to_string() } else { format!("({})", params.join(", ")) };
+                code.push_str(&format!(
+                    "@module(\"{module}\") external {js}: {params} => {} = \"{js}\"\n",
+                    js_type(&function.ret, &classes, target)
+                ));
+            }
+        }
+        _ => return None,
+    }
+    Some(code)
+}
+
+/// Type a value has in `target` after crossing as its `Abi`
+fn js_type(field_type: &RustFieldType, classes: &[&str], target: &str) -> String {
+    let referent = match field_type {
+        RustFieldType::Borrowed(_, owned) | RustFieldType::Cow(_, owned) => owned.as_ref(),
+        ty => ty,
+    };
+    let ty = to_ir(referent);
+    match (abi(referent, classes), target) {
+        (Abi::Handle(class), "typescript") => class,
+        (Abi::Handle(class), _) => camel_case(&class),
+        // wasm-bindgen passes `None` as `undefined`, and 64-bit integers as `BigInt`
+        (Abi::Direct, _) => match (referent, target) {
+            (RustFieldType::Option(inner), "typescript") => format!("{} | undefined", js_type(inner, classes, target)),
+            (RustFieldType::Option(inner), _) => format!("option<{}>", js_type(inner, classes, target)),
+            (RustFieldType::Alias(_, inner), _) => js_type(inner, classes, target),
+            (RustFieldType::I64 | RustFieldType::U64, _) => "bigint".to_string(),
+            (RustFieldType::Isize | RustFieldType::Usize, "typescript") => "number".to_string(),
+            (RustFieldType::Isize | RustFieldType::Usize, _) => "int".to_string(),
+            _ => render_type(&ty, target),
+        },
+        (Abi::Serde, "typescript") => match ty {
+            IrType::Primitive(PrimitiveType::Unit) => "void".to_string(),
+            ty => render_type(&ty, target),
+        },
+        (Abi::Serde, _) => super::rescript::binding_type(&ty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::rust_analyzer::{analyze_rust_functions, analyze_rust_types};
+
+    const SOURCE: &str = "pub struct Address { pub zip: String }\n\
+        /// A signed-up user\n\
+        pub struct User { pub id: u32, pub display_name: String, pub home: Address, pub scores: HashMap<String, f64> }\n\
+        pub struct Page<T> { pub items: Vec<T> }\n\
+        /// Weighted score\n\
+        pub fn score_user(user: &User, bonus: i32) -> f64 { 0.0 }\n\
+        pub fn rename(user: &mut User, name: &str) {}\n\
+        pub fn top(users: &[User]) -> Vec<String> { vec![] }\n\
+        fn private() {}\n\
+        impl User { pub fn method(&self) {} }";
+
+    #[test]
+    fn test_analyzes_free_functions() {
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let names: Vec<&str> = functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["score_user", "rename", "top"]);
+        assert_eq!(functions[0].doc.as_deref(), Some("Weighted score"));
+        assert_eq!(rust_type_name(&functions[1].params[0].1), "&mut User");
+    }
+
+    #[test]
+    fn test_glue_wraps_structs_and_functions() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let glue = generate_glue(&types, &functions);
+        assert!(glue.contains("// `Page` is left out"));
+        assert!(glue.contains("#[wasm_bindgen(js_name = User)]\npub struct UserHandle(User);\n"));
+        assert!(glue.contains(
+            "    pub fn new(id: u32, display_name: String, home: &AddressHandle, scores: JsValue) -> Result<UserHandle, JsError> {\n        \
+             Ok(UserHandle(User { id, display_name, home: home.0.clone(), scores: serde_wasm_bindgen::from_value(scores)? }))\n"
+        ));
+        assert!(glue.contains("    #[wasm_bindgen(getter = displayName)]\n    pub fn display_name(&self) -> String {\n        self.0.display_name.clone()\n"));
+        assert!(glue.contains("#[wasm_bindgen(js_name = scoreUser)]\npub fn score_user_js(user: &UserHandle, bonus: i32) -> f64 {\n    score_user(&user.0, bonus)\n}\n"));
+        assert!(glue.contains("pub fn rename_js(user: &mut UserHandle, name: &str) {\n    rename(&mut user.0, name);\n}\n"));
+        assert!(glue.contains(
+            "pub fn top_js(users: JsValue) -> Result<JsValue, JsError> {\n    let users: Vec<User> = serde_wasm_bindgen::from_value(users)?;\n    \
+             Ok(top(&users).serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_consumers_share_the_surface() {
+        let types = analyze_rust_types(SOURCE).unwrap();
+        let functions = analyze_rust_functions(SOURCE).unwrap();
+        let ts = generate_consumer(&types, &functions, "./pkg/app.js", "typescript").unwrap();
+        assert!(ts.contains("export interface User {\n  readonly id: number;\n  readonly displayName: string;\n  readonly home: Address;\n  readonly scores: Record<string, number>;\n  free(): void;\n}\n"));
+        assert!(ts.contains("export const scoreUser = glue.scoreUser as unknown as (user: User, bonus: number) => number;\n"));
+        let res = generate_consumer(&types, &functions, "./pkg/app.js", "rescript").unwrap();
+        assert!(res.contains("@module(\"./pkg/app.js\") @new external makeUser: (int, string, address, Js.Dict.t<float>) => user = \"User\"\n"));
+        assert!(res.contains("@get external userDisplayName: user => string = \"displayName\"\n"));
+        assert!(res.contains("@module(\"./pkg/app.js\") external top: (array<user>) => array<string> = \"top\"\n"));
+        assert!(generate_consumer(&types, &functions, "./pkg/app.js", "gleam").is_none());
+    }
+}
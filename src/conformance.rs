@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Conformance matrix
+//!
+//! Scores are predictions; this module checks them against real toolchains.
+//! For each ordered language pair, the producer's test data snapshots (see
+//! [`crate::codegen::snapshot`]) are decoded and re-encoded by the consumer's
+//! toolchain, then decoded again by the producer's, and every value must come
+//! back unchanged. Pairs run concurrently, each toolchain invocation inside
+//! its own sandbox directory, and the results form a [`Matrix`] a nightly job
+//! can publish and fail on when an edge claimed as Concorde doesn't hold.
+
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::api::{Language, TypeDef};
+use crate::codegen::snapshot::{self, Snapshot};
+use crate::ir::{self, normalize_name};
+use crate::json::{self, Json};
+use crate::TransportClass;
+
+/// Matrix file format version, bumped on incompatible changes
+pub const MATRIX_FORMAT_VERSION: u32 = 1;
+
+/// Runs one language's snapshot round-trip tests
+pub trait Toolchain: Send + Sync {
+    fn language(&self) -> Language;
+
+    /// Decode the snapshot lines in `input` and write this side's re-encoding
+    /// to `output`; scratch files belong in `sandbox`, which is empty and not
+    /// shared with any other run
+    fn round_trip(&self, sandbox: &Path, input: &Path, output: &Path) -> Result<(), String>;
+}
+
+/// A toolchain driven by an external command, such as the generated snapshot
+/// tests under `cargo test`
+///
+/// The command gets `$SNAPSHOTS_IN` and `$SNAPSHOTS_OUT` (the variables the
+/// generated tests read) and a `$TMPDIR` inside the sandbox. It runs in the
+/// sandbox unless [`CommandToolchain::in_dir`] names a project directory.
+#[derive(Debug, Clone)]
+pub struct CommandToolchain {
+    language: Language,
+    program: String,
+    args: Vec<String>,
+    dir: Option<PathBuf>,
+}
+
+impl CommandToolchain {
+    pub fn new(language: Language, program: &str, args: &[&str]) -> Self {
+        CommandToolchain {
+            language,
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            dir: None,
+        }
+    }
+
+    /// Parse `<lang>=<command>`, splitting the command on whitespace
+    /// (`rust=cargo test -q snapshots`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, command) = spec.split_once('=').ok_or_else(|| format!("expected <lang>=<command>, got `{}`", spec))?;
+        let language = Language::from_name(name).ok_or_else(|| format!("unknown language `{}`", name))?;
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or_else(|| format!("no command for {}", language))?;
+        Ok(CommandToolchain::new(language, program, &words.collect::<Vec<_>>()))
+    }
+
+    /// Run in `dir` (the project holding the generated tests) instead of the sandbox
+    pub fn in_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+}
+
+impl Toolchain for CommandToolchain {
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn round_trip(&self, sandbox: &Path, input: &Path, output: &Path) -> Result<(), String> {
+        let tmp = sandbox.join("tmp");
+        fs::create_dir_all(&tmp).map_err(|err| format!("{}: {}", tmp.display(), err))?;
+        let result = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(self.dir.as_deref().unwrap_or(sandbox))
+            .env("SNAPSHOTS_IN", input)
+            .env("SNAPSHOTS_OUT", output)
+            .env("TMPDIR", &tmp)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| format!("`{}`: {}", self.program, err))?;
+        if result.status.success() {
+            return Ok(());
+        }
+        // The end of stderr is where test runners put the failure
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let lines: Vec<&str> = stderr.lines().filter(|line| !line.trim().is_empty()).collect();
+        let mut message = format!("`{}` exited with {}", self.program, result.status);
+        if !lines.is_empty() {
+            let _ = write!(message, ": {}", lines[lines.len().saturating_sub(3)..].join(" / "));
+        }
+        Err(message)
+    }
+}
+
+/// One language's side of the suites: the types it shares
+#[derive(Debug, Clone)]
+pub struct Suite {
+    pub language: Language,
+    pub types: Vec<TypeDef>,
+}
+
+impl Suite {
+    pub fn new(language: Language, types: Vec<TypeDef>) -> Self {
+        Suite { language, types }
+    }
+}
+
+/// How one pair's round trip went
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Every snapshot came back unchanged
+    Passed { snapshots: usize },
+    Failed(String),
+    /// Not run: a toolchain is missing, or the sides share no types
+    Skipped(String),
+}
+
+/// One producer → consumer edge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub source: Language,
+    pub target: Language,
+    /// What the analyzers' scores claim for the pair: the class of the
+    /// worst-scoring shared type, Wheelbarrow when nothing is shared
+    pub claimed: TransportClass,
+    pub outcome: Outcome,
+}
+
+impl Cell {
+    /// Claimed Concorde, but the round trip didn't pass
+    pub fn is_broken_claim(&self) -> bool {
+        self.claimed == TransportClass::Concorde && !matches!(self.outcome, Outcome::Passed { .. })
+    }
+}
+
+/// Every edge the runner tried, in suite order
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Matrix {
+    pub cells: Vec<Cell>,
+}
+
+impl Matrix {
+    /// Concorde claims the toolchains didn't confirm, skipped ones included
+    pub fn broken_claims(&self) -> impl Iterator<Item = &Cell> {
+        self.cells.iter().filter(|cell| cell.is_broken_claim())
+    }
+
+    pub fn to_json(&self) -> String {
+        let cells = self.cells.iter().map(|cell| {
+            let (status, detail) = match &cell.outcome {
+                Outcome::Passed { snapshots } => ("passed", Json::from(*snapshots)),
+                Outcome::Failed(reason) => ("failed", reason.as_str().into()),
+                Outcome::Skipped(reason) => ("skipped", reason.as_str().into()),
+            };
+            Json::object([
+                ("source", cell.source.name().into()),
+                ("target", cell.target.name().into()),
+                ("claimed", format!("{:?}", cell.claimed).into()),
+                ("status", status.into()),
+                (if status == "passed" { "snapshots" } else { "reason" }, detail),
+            ])
+        });
+        Json::object([
+            ("format", "interop-conformance".into()),
+            ("version", (MATRIX_FORMAT_VERSION as usize).into()),
+            ("cells", Json::Array(cells.collect())),
+        ])
+        .to_string()
+    }
+}
+
+impl fmt::Display for Matrix {
+    /// Source languages down, targets across: `ok`, `FAIL`, or `-` for skipped;
+    /// failure reasons follow the grid
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut languages: Vec<Language> = Vec::new();
+        for cell in &self.cells {
+            for language in [cell.source, cell.target] {
+                if !languages.contains(&language) {
+                    languages.push(language);
+                }
+            }
+        }
+        let width = languages.iter().map(|l| l.name().len()).max().unwrap_or(0).max(4);
+        write!(f, "{:width$}", "")?;
+        for target in &languages {
+            write!(f, " {:>width$}", target.name())?;
+        }
+        writeln!(f)?;
+        for source in &languages {
+            write!(f, "{:width$}", source.name())?;
+            for target in &languages {
+                let mark = match self.cells.iter().find(|c| c.source == *source && c.target == *target) {
+                    None => "",
+                    Some(cell) => match cell.outcome {
+                        Outcome::Passed { .. } => "ok",
+                        Outcome::Failed(_) => "FAIL",
+                        Outcome::Skipped(_) => "-",
+                    },
+                };
+                write!(f, " {:>width$}", mark)?;
+            }
+            writeln!(f)?;
+        }
+        for cell in &self.cells {
+            if let Outcome::Failed(reason) = &cell.outcome {
+                writeln!(f, "{} → {}: {}", cell.source, cell.target, reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs every pair of suites through their toolchains
+pub struct Runner {
+    toolchains: Vec<Box<dyn Toolchain>>,
+    sandbox_root: PathBuf,
+    jobs: usize,
+}
+
+impl Runner {
+    /// Sandboxes are created under `sandbox_root`, one directory per pair and toolchain
+    pub fn new(sandbox_root: impl Into<PathBuf>) -> Self {
+        Runner {
+            toolchains: Vec::new(),
+            sandbox_root: sandbox_root.into(),
+            jobs: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        }
+    }
+
+    /// Add a toolchain, replacing any existing one for the same language
+    pub fn with_toolchain(mut self, toolchain: Box<dyn Toolchain>) -> Self {
+        self.toolchains.retain(|existing| existing.language() != toolchain.language());
+        self.toolchains.push(toolchain);
+        self
+    }
+
+    /// How many pairs run at once; at least one
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    fn toolchain(&self, language: Language) -> Option<&dyn Toolchain> {
+        self.toolchains.iter().find(|t| t.language() == language).map(|t| t.as_ref())
+    }
+
+    /// Round-trip every ordered pair of `suites` and collect the matrix
+    pub fn run(&self, suites: &[Suite]) -> Matrix {
+        let pairs: Vec<(&Suite, &Suite)> = suites
+            .iter()
+            .flat_map(|source| suites.iter().map(move |target| (source, target)))
+            .filter(|(source, target)| source.language != target.language)
+            .collect();
+        let next = AtomicUsize::new(0);
+        let cells: Mutex<Vec<Option<Cell>>> = Mutex::new(vec![None; pairs.len()]);
+        thread::scope(|scope| {
+            for _ in 0..self.jobs.min(pairs.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(source, target)) = pairs.get(index) else {
+                        break;
+                    };
+                    let cell = Cell {
+                        source: source.language,
+                        target: target.language,
+                        claimed: claimed_class(source, target),
+                        outcome: self.run_pair(source, target),
+                    };
+                    if let Ok(mut cells) = cells.lock() {
+                        cells[index] = Some(cell);
+                    }
+                });
+            }
+        });
+        let cells = cells.into_inner().unwrap_or_default();
+        Matrix { cells: cells.into_iter().flatten().collect() }
+    }
+
+    /// `source` samples → `target` re-encodes → `source` re-encodes again
+    fn run_pair(&self, source: &Suite, target: &Suite) -> Outcome {
+        let (Some(producer), Some(consumer)) = (self.toolchain(source.language), self.toolchain(target.language)) else {
+            let missing = if self.toolchain(source.language).is_none() { source.language } else { target.language };
+            return Outcome::Skipped(format!("no {} toolchain", missing));
+        };
+        let shared = shared_types(source, target);
+        if shared.is_empty() {
+            return Outcome::Skipped("no shared types".to_string());
+        }
+        let samples: Vec<Snapshot> = shared
+            .iter()
+            .flat_map(|(def, _)| snapshot::sample_snapshots(def, &source.types, source.language))
+            .collect();
+
+        let pair_dir = self.sandbox_root.join(format!("{}-{}", source.language, target.language));
+        let result = (|| {
+            let there = self.step(consumer, &pair_dir, &samples, &target.types)?;
+            let back = self.step(producer, &pair_dir, &there, &source.types)?;
+            compare(&samples, &back)
+        })();
+        match result {
+            Ok(()) => Outcome::Passed { snapshots: samples.len() },
+            Err(reason) => Outcome::Failed(reason),
+        }
+    }
+
+    /// One toolchain's leg: write `input` into a fresh sandbox, run, and check
+    /// what it wrote decodes as `types`
+    fn step(&self, toolchain: &dyn Toolchain, pair_dir: &Path, input: &[Snapshot], types: &[TypeDef]) -> Result<Vec<Snapshot>, String> {
+        let language = toolchain.language();
+        let located = |err: String| format!("{}: {}", language, err);
+        let sandbox = pair_dir.join(language.name());
+        if sandbox.exists() {
+            fs::remove_dir_all(&sandbox).map_err(|err| located(format!("{}: {}", sandbox.display(), err)))?;
+        }
+        fs::create_dir_all(&sandbox).map_err(|err| located(format!("{}: {}", sandbox.display(), err)))?;
+        // Toolchains may run elsewhere, so they get absolute paths
+        let sandbox = fs::canonicalize(&sandbox).map_err(|err| located(err.to_string()))?;
+        let (in_path, out_path) = (sandbox.join("in.jsonl"), sandbox.join("out.jsonl"));
+        let lines: String = input.iter().map(|s| format!("{}\n", s.to_line())).collect();
+        fs::write(&in_path, lines).map_err(|err| located(err.to_string()))?;
+
+        toolchain.round_trip(&sandbox, &in_path, &out_path).map_err(located)?;
+        let written = fs::read_to_string(&out_path).map_err(|err| located(format!("no output: {}", err)))?;
+        let output = snapshot::parse_snapshots(&written).map_err(located)?;
+        if let Some(mismatch) = snapshot::verify_snapshots(&output, types).into_iter().next() {
+            return Err(located(mismatch.to_string()));
+        }
+        Ok(output)
+    }
+}
+
+/// Types both sides declare, by normalized name: each of `source`'s with its counterpart in `target`
+fn shared_types<'a>(source: &'a Suite, target: &'a Suite) -> Vec<(&'a TypeDef, &'a TypeDef)> {
+    source
+        .types
+        .iter()
+        .filter_map(|def| {
+            let other = target.types.iter().find(|other| normalize_name(&other.name) == normalize_name(&def.name))?;
+            Some((def, other))
+        })
+        .collect()
+}
+
+/// Class of the worst-scoring shared type, each scored by how its fields,
+/// variants, or aliased type map onto the other side's
+fn claimed_class(source: &Suite, target: &Suite) -> TransportClass {
+    let score = shared_types(source, target)
+        .into_iter()
+        .map(|(def, other)| match (&def.aliased, &other.aliased) {
+            (Some(aliased), Some(other)) => ir::compare(aliased, other).weight(),
+            _ if !def.variants.is_empty() => ir::compare_variants(&def.variants, &other.variants),
+            _ => ir::compare_fields(&def.fields, &other.fields),
+        })
+        .reduce(f32::min);
+    TransportClass::from_score(score.unwrap_or(0.0))
+}
+
+/// Every sample must come back, with the same value
+fn compare(samples: &[Snapshot], back: &[Snapshot]) -> Result<(), String> {
+    let mut problems = Vec::new();
+    for sample in samples {
+        let returned = back.iter().find(|s| s.type_name == sample.type_name && s.case == sample.case);
+        match returned {
+            None => problems.push(format!("{} `{}` was dropped", sample.type_name, sample.case)),
+            Some(returned) if json::parse(&returned.value).ok() != json::parse(&sample.value).ok() => {
+                problems.push(format!("{} `{}` changed value", sample.type_name, sample.case));
+            }
+            Some(_) => {}
+        }
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let mut message = problems[0].clone();
+    if problems.len() > 1 {
+        let _ = write!(message, " (and {} more)", problems.len() - 1);
+    }
+    Err(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Registry;
+
+    fn suite(language: Language, source: &str) -> Suite {
+        Suite::new(language, Registry::with_builtins().analyzer(language).unwrap().analyze(source).unwrap())
+    }
+
+    /// Re-encodes like a faithful generated test, optionally dropping a field
+    struct Echo(Language, Option<&'static str>);
+
+    impl Toolchain for Echo {
+        fn language(&self) -> Language {
+            self.0
+        }
+
+        fn round_trip(&self, sandbox: &Path, input: &Path, output: &Path) -> Result<(), String> {
+            assert!(input.starts_with(sandbox) && fs::read_dir(sandbox).unwrap().count() == 1);
+            let mut lines = String::new();
+            for mut snapshot in snapshot::parse_snapshots(&fs::read_to_string(input).unwrap())? {
+                snapshot.producer = self.0;
+                if let (Some(drop), Ok(Json::Object(entries))) = (self.1, json::parse(&snapshot.value)) {
+                    snapshot.value = Json::Object(entries.into_iter().filter(|(key, _)| key != drop).collect()).to_string();
+                }
+                lines.push_str(&format!("{}\n", snapshot.to_line()));
+            }
+            fs::write(output, lines).map_err(|err| err.to_string())
+        }
+    }
+
+    fn sandbox_root(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("lic-conformance-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_matrix_marks_passes_failures_and_skips() {
+        let suites = [
+            suite(Language::Rust, "pub struct Point { pub x: i32, pub y: i32 }"),
+            suite(Language::Zig, "pub const Point = extern struct { x: i32, y: i32 };"),
+            suite(Language::Erlang, "-record(point, {x :: integer(), y :: integer()})."),
+            suite(Language::TypeScript, "interface Point { x: number; y: number }"),
+        ];
+        let root = sandbox_root("matrix");
+        let matrix = Runner::new(&root)
+            .with_jobs(3)
+            .with_toolchain(Box::new(Echo(Language::Rust, None)))
+            .with_toolchain(Box::new(Echo(Language::Zig, None)))
+            // Forgets `y` when re-encoding
+            .with_toolchain(Box::new(Echo(Language::Erlang, Some("y"))))
+            .run(&suites);
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(matrix.cells.len(), 12);
+        let cell = |source, target| matrix.cells.iter().find(|c| c.source == source && c.target == target).unwrap();
+        assert_eq!(cell(Language::Rust, Language::Zig).outcome, Outcome::Passed { snapshots: 3 });
+        assert_eq!(
+            cell(Language::Rust, Language::Erlang).outcome,
+            Outcome::Failed("erlang: Point `zero` from erlang: `$.y` is not present".to_string())
+        );
+        assert_eq!(cell(Language::Rust, Language::TypeScript).outcome, Outcome::Skipped("no typescript toolchain".to_string()));
+
+        // `i32` fits an Erlang integer and a TypeScript number, so those
+        // edges are claimed as Concorde too; the lossy Erlang toolchain and
+        // the missing TypeScript one leave the claims unconfirmed
+        let broken: Vec<_> = matrix.broken_claims().map(|c| (c.source.name(), c.target.name())).collect();
+        assert_eq!(broken, vec![("rust", "erlang"), ("rust", "typescript"), ("zig", "erlang"), ("zig", "typescript")]);
+        // A number may hold a fraction, which an `i32` can't
+        assert_ne!(cell(Language::TypeScript, Language::Rust).claimed, TransportClass::Concorde);
+        let text = matrix.to_string();
+        assert!(text.contains("rust → erlang: erlang: Point `zero`"));
+        assert!(matrix.to_json().contains("{\"source\":\"rust\",\"target\":\"zig\",\"claimed\":\"Concorde\",\"status\":\"passed\",\"snapshots\":3}"));
+
+        let lossy = Runner::new(&root)
+            .with_toolchain(Box::new(Echo(Language::Rust, None)))
+            .with_toolchain(Box::new(Echo(Language::Zig, Some("x"))))
+            .run(&suites[..2]);
+        let _ = fs::remove_dir_all(&root);
+        let broken: Vec<_> = lossy.broken_claims().map(|c| (c.source.name(), c.target.name())).collect();
+        assert_eq!(broken, vec![("rust", "zig"), ("zig", "rust")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_toolchain_gets_sandboxed_paths() {
+        let suites = [suite(Language::Rust, "pub struct Tag { pub id: i32 }"), suite(Language::Zig, "pub const Tag = struct { id: i32 };")];
+        let echo = |language| CommandToolchain::new(language, "sh", &["-c", "cp \"$SNAPSHOTS_IN\" \"$SNAPSHOTS_OUT\" && test -d \"$TMPDIR\""]);
+        let root = sandbox_root("command");
+        let matrix = Runner::new(&root)
+            .with_toolchain(Box::new(echo(Language::Rust)))
+            .with_toolchain(Box::new(echo(Language::Zig)))
+            .run(&suites);
+        assert!(root.join("rust-zig").join("zig").join("out.jsonl").exists());
+        let _ = fs::remove_dir_all(&root);
+        assert!(matrix.cells.iter().all(|cell| cell.outcome == Outcome::Passed { snapshots: 3 }));
+
+        let failing = Runner::new(&root).with_toolchain(Box::new(CommandToolchain::parse("rust=false").unwrap()));
+        let failing = failing.with_toolchain(Box::new(echo(Language::Zig))).run(&suites);
+        let _ = fs::remove_dir_all(&root);
+        assert!(matches!(&failing.cells[0].outcome, Outcome::Failed(reason) if reason.starts_with("rust: `false` exited with")));
+        assert!(CommandToolchain::parse("rust").is_err());
+    }
+}
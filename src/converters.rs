@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Per-field custom converter hooks
+// Lets users replace the default type mapping of individual fields
+// (e.g. epoch-millis ↔ DateTime) with their own conversion functions
+
+use std::collections::HashMap;
+
+use crate::analyzers::rust_analyzer::{map_to_target, rust_type_name, RustFieldType};
+use crate::codegen::pascal_case;
+use crate::ir::Direction;
+
+/// Encode/decode functions implementing a converter in one language
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConverterHook {
+    /// Function turning the native value into its wire form
+    pub encode: String,
+    /// Function turning the wire form back into the native value
+    pub decode: String,
+}
+
+/// A named, user-provided field converter
+#[derive(Debug, Clone)]
+pub struct FieldConverter {
+    pub name: String,
+    /// Type carried across the boundary (e.g. `i64` for epoch millis)
+    pub wire_type: RustFieldType,
+    /// Hook functions keyed by language ("rust", "rescript", ...)
+    pub hooks: HashMap<String, ConverterHook>,
+}
+
+impl FieldConverter {
+    pub fn new(name: &str, wire_type: RustFieldType) -> Self {
+        FieldConverter {
+            name: name.to_string(),
+            wire_type,
+            hooks: HashMap::new(),
+        }
+    }
+
+    /// Register the encode/decode functions for one language
+    pub fn with_hook(mut self, language: &str, encode: &str, decode: &str) -> Self {
+        self.hooks.insert(
+            language.to_string(),
+            ConverterHook {
+                encode: encode.to_string(),
+                decode: decode.to_string(),
+            },
+        );
+        self
+    }
+
+    /// Whether the converter provides hooks for the given language
+    pub fn supports(&self, language: &str) -> bool {
+        self.hooks.contains_key(language)
+    }
+}
+
+/// Converters known to a run, plus config-level field bindings
+#[derive(Debug, Clone, Default)]
+pub struct ConverterRegistry {
+    converters: HashMap<String, FieldConverter>,
+    /// `"Type.field"` → converter name
+    bindings: HashMap<String, String>,
+}
+
+impl ConverterRegistry {
+    pub fn register(&mut self, converter: FieldConverter) {
+        self.converters.insert(converter.name.clone(), converter);
+    }
+
+    /// Attach a converter to a field from configuration rather than a source attribute
+    pub fn bind(&mut self, type_name: &str, field_name: &str, converter: &str) {
+        self.bindings
+            .insert(format!("{}.{}", type_name, field_name), converter.to_string());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FieldConverter> {
+        self.converters.get(name)
+    }
+
+    /// Find the converter for a field; config bindings take precedence over attributes
+    pub fn resolve(&self, type_name: &str, field_name: &str, attribute: Option<&str>) -> Option<&FieldConverter> {
+        self.bindings
+            .get(&format!("{}.{}", type_name, field_name))
+            .map(String::as_str)
+            .or(attribute)
+            .and_then(|name| self.get(name))
+    }
+}
+
+/// Generate encode/decode functions for one converted field in the given language;
+/// a one-way field only gets the side its direction uses
+pub fn generate_field_codec(
+    type_name: &str,
+    field_name: &str,
+    native_type: &str,
+    converter: &FieldConverter,
+    direction: Direction,
+    language: &str,
+) -> Option<String> {
+    let hook = converter.hooks.get(language)?;
+    let wire = match language {
+        "rust" => rust_type_name(&converter.wire_type),
+        _ => map_to_target(&converter.wire_type, language),
+    };
+    let snake = format!("{}_{}", type_name.to_lowercase(), field_name);
+    let (conv, enc, dec) = (&converter.name, &hook.encode, &hook.decode);
+
+    let (header, encode, decode) = match language {
+        "rust" => (
+            String::new(),
+            format!(
+                "/// Encode `{type_name}.{field_name}` via the `{conv}` converter\n\
+                 pub fn encode_{snake}(value: &{native_type}) -> {wire} {{\n    {enc}(value)\n}}\n"
+            ),
+            format!(
+                "/// Decode `{type_name}.{field_name}` via the `{conv}` converter\n\
+                 pub fn decode_{snake}(wire: {wire}) -> {native_type} {{\n    {dec}(wire)\n}}\n"
+            ),
+        ),
+        "rescript" => {
+            let camel = pascal_case(&snake);
+            (
+                format!("// Encode/decode `{type_name}.{field_name}` via the `{conv}` converter\n"),
+                format!("let encode{camel} = (value: {native_type}): {wire} => {enc}(value)\n"),
+                format!("let decode{camel} = (wire: {wire}): {native_type} => {dec}(wire)\n"),
+            )
+        }
+        "julia" => (
+            format!("# Encode/decode `{type_name}.{field_name}` via the `{conv}` converter\n"),
+            format!("encode_{snake}(value::{native_type})::{wire} = {enc}(value)\n"),
+            format!("decode_{snake}(wire::{wire})::{native_type} = {dec}(wire)\n"),
+        ),
+        "gleam" => (
+            format!("/// Encode/decode `{type_name}.{field_name}` via the `{conv}` converter\n"),
+            format!("pub fn encode_{snake}(value: {native_type}) -> {wire} {{\n  {enc}(value)\n}}\n"),
+            format!("pub fn decode_{snake}(wire: {wire}) -> {native_type} {{\n  {dec}(wire)\n}}\n"),
+        ),
+        _ => return None,
+    };
+
+    let separator = if language == "rust" || language == "gleam" { "\n" } else { "" };
+    let code = match (direction.serializes(), direction.deserializes()) {
+        (true, false) => header + &encode,
+        (false, true) => header + &decode,
+        _ => format!("{header}{encode}{separator}{decode}"),
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch_millis() -> FieldConverter {
+        FieldConverter::new("epoch_millis", RustFieldType::I64)
+            .with_hook("rust", "epoch_millis::to_wire", "epoch_millis::from_wire")
+            .with_hook("rescript", "EpochMillis.toWire", "EpochMillis.fromWire")
+    }
+
+    #[test]
+    fn test_binding_overrides_attribute() {
+        let mut registry = ConverterRegistry::default();
+        registry.register(epoch_millis());
+        registry.register(FieldConverter::new("iso8601", RustFieldType::String));
+        registry.bind("Event", "created_at", "epoch_millis");
+
+        let converter = registry.resolve("Event", "created_at", Some("iso8601")).unwrap();
+        assert_eq!(converter.name, "epoch_millis");
+        let converter = registry.resolve("Event", "updated_at", Some("iso8601")).unwrap();
+        assert_eq!(converter.name, "iso8601");
+        assert!(registry.resolve("Event", "updated_at", Some("missing")).is_none());
+    }
+
+    #[test]
+    fn test_generate_rescript_codec() {
+        let code = generate_field_codec("Event", "created_at", "Js.Date.t", &epoch_millis(), Direction::Both, "rescript").unwrap();
+        assert!(code.contains("let encodeEventCreatedAt = (value: Js.Date.t): int => EpochMillis.toWire(value)"));
+        assert!(code.contains("let decodeEventCreatedAt = (wire: int): Js.Date.t => EpochMillis.fromWire(wire)"));
+    }
+
+    #[test]
+    fn test_codec_requires_hook() {
+        assert!(generate_field_codec("Event", "created_at", "DateTime", &epoch_millis(), Direction::Both, "gleam").is_none());
+    }
+
+    #[test]
+    fn test_one_way_codec_emits_one_side() {
+        let code = generate_field_codec("Event", "created_at", "DateTime", &epoch_millis(), Direction::SerializeOnly, "rust").unwrap();
+        assert!(code.contains("pub fn encode_event_created_at(value: &DateTime) -> i64 {"));
+        assert!(!code.contains("decode_event_created_at"));
+
+        let code = generate_field_codec("Event", "created_at", "Js.Date.t", &epoch_millis(), Direction::DeserializeOnly, "rescript").unwrap();
+        assert!(code.starts_with("// Encode/decode `Event.created_at`"));
+        assert!(code.contains("let decodeEventCreatedAt"));
+        assert!(!code.contains("let encodeEventCreatedAt"));
+    }
+}
@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Cross-type referential integrity
+//!
+//! A field can declare that it holds another type's key, foreign-key style
+//! (`#[interop(refs = "User.id")]` on `user_id: i64`). [`check`] confirms the
+//! referenced type and field exist and that the key fits in the referencing
+//! field; [`check_against`] applies references declared in one language to
+//! the types analyzed from another, so a rename or retype on either side of a
+//! crossing shows up before generated bindings disagree.
+
+use std::fmt;
+
+use crate::api::{Language, TypeDef};
+use crate::ir::{self, normalize_name, ContainerType, Fidelity, IrType};
+
+/// A field's declared pointer at another type's key
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// Referencing field on the declaring type
+    pub field: String,
+    pub target_type: String,
+    pub target_field: String,
+}
+
+impl Reference {
+    pub fn new(field: &str, target_type: &str, target_field: &str) -> Self {
+        Reference {
+            field: field.to_string(),
+            target_type: target_type.to_string(),
+            target_field: target_field.to_string(),
+        }
+    }
+
+    /// Parse a `"Type.field"` target; `None` unless both parts are present
+    pub fn parse(field: &str, target: &str) -> Option<Self> {
+        let (target_type, target_field) = target.split_once('.')?;
+        let (target_type, target_field) = (target_type.trim(), target_field.trim());
+        if target_type.is_empty() || target_field.is_empty() || target_field.contains('.') {
+            return None;
+        }
+        Some(Reference::new(field, target_type, target_field))
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.target_type, self.target_field)
+    }
+}
+
+/// Why a reference doesn't hold
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// No type with the referenced name
+    MissingType,
+    /// The referenced type has no such field
+    MissingField,
+    /// The key doesn't fit the referencing field without loss
+    Incompatible { key: IrType, field: IrType, fidelity: Fidelity },
+}
+
+/// A reference that fails in one language
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenReference {
+    pub language: Language,
+    /// Type declaring the reference
+    pub type_name: String,
+    pub reference: Reference,
+    pub problem: Problem,
+}
+
+impl fmt::Display for BrokenReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}.{} → {}: ", self.language, self.type_name, self.reference.field, self.reference)?;
+        match &self.problem {
+            Problem::MissingType => write!(f, "no type `{}`", self.reference.target_type),
+            Problem::MissingField => write!(f, "`{}` has no field `{}`", self.reference.target_type, self.reference.target_field),
+            Problem::Incompatible { fidelity, .. } => write!(f, "key type doesn't fit ({:?})", fidelity),
+        }
+    }
+}
+
+/// Check the references declared on `types` against those same types
+pub fn check(types: &[TypeDef]) -> Vec<BrokenReference> {
+    check_against(types, types)
+}
+
+/// Check the references declared on `declared` against `types`, which may come
+/// from another language; names are matched across naming conventions
+/// (`User.id` ≈ `user.id`), and declaring types absent from `types` are skipped
+pub fn check_against(declared: &[TypeDef], types: &[TypeDef]) -> Vec<BrokenReference> {
+    let find_type = |name: &str| types.iter().find(|def| normalize_name(&def.name) == normalize_name(name));
+    let find_field = |def: &TypeDef, name: &str| {
+        def.fields
+            .iter()
+            .find(|field| normalize_name(&field.name) == normalize_name(name))
+            .map(|field| field.ty.clone())
+    };
+
+    let mut broken = Vec::new();
+    for def in declared {
+        let Some(referencing) = find_type(&def.name) else { continue };
+        for reference in &def.references {
+            let Some(field) = find_field(referencing, &reference.field) else { continue };
+            let problem = match find_type(&reference.target_type) {
+                None => Some(Problem::MissingType),
+                Some(target) => match find_field(target, &reference.target_field) {
+                    None => Some(Problem::MissingField),
+                    Some(key) => incompatibility(key, field),
+                },
+            };
+            if let Some(problem) = problem {
+                broken.push(BrokenReference {
+                    language: referencing.language,
+                    type_name: referencing.name.clone(),
+                    reference: reference.clone(),
+                    problem,
+                });
+            }
+        }
+    }
+    broken
+}
+
+/// Every key value must fit the referencing field; an optional field is a nullable reference
+fn incompatibility(key: IrType, field: IrType) -> Option<Problem> {
+    let slot = match &field {
+        IrType::Container(ContainerType::Option(inner)) => inner.as_ref(),
+        other => other,
+    };
+    match ir::compare(&key, slot) {
+        Fidelity::Exact => None,
+        fidelity => Some(Problem::Incompatible { key, field, fidelity }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Config, Registry};
+
+    const RUST: &str = r#"
+pub struct User { pub id: i64, pub name: String }
+
+pub struct Order {
+    #[interop(refs = "User.id")]
+    pub user_id: Option<i64>,
+    #[interop(refs = "Product.sku")]
+    pub product: String,
+    #[interop(refs = "User.email")]
+    pub contact: String,
+}
+"#;
+
+    fn analyze(language: Language, source: &str) -> Vec<TypeDef> {
+        let registry = Registry::with_builtins();
+        registry.analyzer(language).unwrap().analyze(source).unwrap()
+    }
+
+    #[test]
+    fn test_check_within_language() {
+        let types = analyze(Language::Rust, RUST);
+        assert_eq!(types[1].references.len(), 3);
+
+        let broken = check(&types);
+        let problems: Vec<_> = broken.iter().map(|b| &b.problem).collect();
+        assert_eq!(problems, vec![&Problem::MissingType, &Problem::MissingField]);
+        assert_eq!(broken[0].to_string(), "rust: Order.product → Product.sku: no type `Product`");
+
+        let report = Registry::with_builtins().report(&Config::new(Language::Rust, Language::Gleam), RUST).unwrap();
+        assert_eq!(report.broken_references, broken);
+        assert!(report.to_string().contains("  broken reference rust: Order.contact → User.email: `User` has no field `email`"));
+    }
+
+    #[test]
+    fn test_check_against_other_language() {
+        let rust = analyze(Language::Rust, RUST);
+        // `id` is a string on the TypeScript side, so `user_id` can't hold it
+        let typescript = analyze(
+            Language::TypeScript,
+            "interface User { id: string; email: string }\ninterface Product { sku: string }\ninterface Order { userId: number; product: string; contact: string }",
+        );
+        let broken = check_against(&rust, &typescript);
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].language, Language::TypeScript);
+        assert_eq!(broken[0].reference.field, "user_id");
+        assert!(matches!(broken[0].problem, Problem::Incompatible { fidelity: Fidelity::Unsupported, .. }));
+    }
+
+    #[test]
+    fn test_parse_reference() {
+        assert_eq!(Reference::parse("user_id", "User.id"), Some(Reference::new("user_id", "User", "id")));
+        assert_eq!(Reference::parse("user_id", "User"), None);
+        assert_eq!(Reference::parse("user_id", "a.b.c"), None);
+    }
+}
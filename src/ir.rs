@@ -0,0 +1,886 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Language-neutral intermediate representation
+// Analyzers lower their field types into this model so that types from
+// different languages can be compared and scored against each other;
+// `no_std + alloc`, so firmware host tools can embed the scoring core
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Language-neutral field type
+///
+/// Non-exhaustive: new type forms are added in minor releases
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IrType {
+    Primitive(PrimitiveType),
+    Container(ContainerType),
+    /// Reference to a named user type (struct, record, interface)
+    Reference(String),
+    /// Named generic user type applied to arguments (`Page<User>`)
+    Generic(String, Vec<IrType>),
+    /// Type parameter of a generic definition
+    TypeParam(String),
+    /// No static type information (`any`, `unknown`, dynamic values)
+    Any,
+    /// Ad-hoc union of heterogeneous types (`string | number`)
+    Union(Vec<IrType>),
+    /// Fixed-length sequence of heterogeneous types (`(i64, String)`)
+    Tuple(Vec<IrType>),
+    /// Named alias (`type UserId = i64`) with the type it stands for; scored
+    /// and compared as that type, rendered by name
+    Alias(String, Box<IrType>),
+    /// Dynamically dispatched value (`Box<dyn Handler>`), named by its trait;
+    /// behaviour with no data form, so it has no mapping anywhere
+    TraitObject(String),
+    /// Function value (`Box<dyn Fn(i32) -> String>`, `int => string`) with its
+    /// parameter and return types; it crosses as a registered callback, never as data
+    Function(Vec<IrType>, Box<IrType>),
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimitiveType {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    String,
+    Unit,
+    /// Point in time (`chrono::DateTime`, `time::OffsetDateTime`); JSON
+    /// carries it as an RFC 3339 string
+    DateTime,
+    /// 128-bit UUID; JSON carries it as a hyphenated hex string
+    Uuid,
+    /// Exact base-10 number (`rust_decimal::Decimal`, `BigDecimal`)
+    Decimal,
+    /// Arbitrary-precision integer (`num_bigint::BigInt`, JS `bigint`)
+    BigInt,
+    /// Raw bytes (`Vec<u8>`, protobuf `bytes`); JSON carries them as base64
+    Bytes,
+}
+
+impl PrimitiveType {
+    /// Signedness and bit width of an integer type
+    pub fn integer_width(self) -> Option<(bool, u32)> {
+        use PrimitiveType::*;
+        Some(match self {
+            I8 => (true, 8),
+            I16 => (true, 16),
+            I32 => (true, 32),
+            I64 => (true, 64),
+            I128 => (true, 128),
+            U8 => (false, 8),
+            U16 => (false, 16),
+            U32 => (false, 32),
+            U64 => (false, 64),
+            U128 => (false, 128),
+            _ => return None,
+        })
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.integer_width().is_some()
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerType {
+    Vec(Box<IrType>),
+    Option(Box<IrType>),
+    Result(Box<IrType>, Box<IrType>),
+    /// Key-value map (`HashMap<K, V>`, `Js.Dict.t<V>`)
+    Map(Box<IrType>, Box<IrType>),
+    /// Fixed-length array (`[u8; 32]`, `[4]f32`)
+    Array(Box<IrType>, usize),
+}
+
+/// A named field lowered into the IR
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrField {
+    pub name: String,
+    pub ty: IrType,
+    pub direction: Direction,
+    /// How the declaring side holds the value; `ty` is what it points to
+    pub ownership: Ownership,
+    /// Value the field takes when the wire leaves it out
+    pub default: Option<DefaultValue>,
+    /// Doc comment text, without comment markers
+    pub doc: Option<String>,
+}
+
+impl IrField {
+    pub fn new(name: &str, ty: IrType) -> Self {
+        IrField { name: name.to_string(), ty, direction: Direction::Both, ownership: Ownership::Owned, default: None, doc: None }
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_ownership(mut self, ownership: Ownership) -> Self {
+        self.ownership = ownership;
+        self
+    }
+
+    pub fn with_default(mut self, default: Option<DefaultValue>) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
+}
+
+/// Default of a field, as far as the declaring side states it
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaultValue {
+    /// Whatever the field type defaults to: zero, `false`, empty or absent
+    /// (`#[serde(default)]`, `#[derive(Default)]`)
+    TypeDefault,
+    Bool(bool),
+    /// Numeric literal as written, without type suffix (`8080`, `-0.5`)
+    Number(String),
+    String(String),
+    /// Absent optional (`None`, `=?`)
+    Null,
+    /// Empty list or map
+    Empty,
+    /// Computed on the declaring side (`default_port()`); no literal to carry
+    Computed(String),
+}
+
+impl fmt::Display for DefaultValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefaultValue::TypeDefault => f.write_str("its type's default"),
+            DefaultValue::Bool(value) => write!(f, "{}", value),
+            DefaultValue::Number(value) => f.write_str(value),
+            DefaultValue::String(value) => write!(f, "{:?}", value),
+            DefaultValue::Null => f.write_str("null"),
+            DefaultValue::Empty => f.write_str("empty"),
+            DefaultValue::Computed(expr) => write!(f, "`{}`", expr),
+        }
+    }
+}
+
+/// Smart pointer a field is held through; invisible on the wire, so it
+/// never affects fidelity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ownership {
+    #[default]
+    Owned,
+    /// Heap-allocated with one owner (`Box<T>`)
+    Boxed,
+    /// Reference-counted within one thread (`Rc<T>`)
+    Shared,
+    /// Reference-counted across threads (`Arc<T>`)
+    AtomicShared,
+}
+
+/// A case of a sum type (a Rust enum variant, a ReScript constructor, a Gleam
+/// custom type constructor) with its payload; positional payload fields are
+/// named `0`, `1`, ...
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrVariant {
+    pub name: String,
+    pub fields: Vec<IrField>,
+    /// Tag on the wire when it differs from the name (ReScript `@as("active")`)
+    pub tag: Option<String>,
+}
+
+impl IrVariant {
+    pub fn new(name: &str, fields: Vec<IrField>) -> Self {
+        IrVariant { name: name.to_string(), fields, tag: None }
+    }
+
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Tag written on the wire
+    pub fn wire_tag(&self) -> &str {
+        self.tag.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Wire layout of a sum type's values, named as serde names them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantTagging {
+    /// `{ "Variant": payload }`, and a unit variant as its bare tag
+    External,
+    /// `{ "<tag>": "Variant", ...payload fields }`; positional payloads are
+    /// keyed `_0`, `_1`, ...
+    Internal(String),
+    /// `{ "<tag>": "Variant", "<content>": payload }`
+    Adjacent(String, String),
+    /// The payload alone
+    Untagged,
+}
+
+/// Which tags a polymorphic variant type (ReScript `[#A | #B]`) admits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolyBound {
+    /// `[#A | #B]`: exactly the listed tags
+    Exact,
+    /// `[> #A | #B]`: the listed tags and any others
+    AtLeast,
+    /// `[< #A | #B]`: some of the listed tags
+    AtMost,
+}
+
+impl PolyBound {
+    /// Fidelity of the bound in `target`; an open set of tags only stays open
+    /// where the target has polymorphic variants too
+    pub fn fidelity(self, target: &str) -> Fidelity {
+        match (self, target) {
+            (PolyBound::AtLeast, "rescript") => known_target(target),
+            (PolyBound::AtLeast, _) => known_target(target).worst(Fidelity::Lossy),
+            _ => known_target(target),
+        }
+    }
+}
+
+/// Which way a field crosses the wire, seen from the declaring type's codec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Both,
+    /// Written but never read back (read-only to peers, e.g. computed fields)
+    SerializeOnly,
+    /// Read but never written (write-only to peers, e.g. passwords)
+    DeserializeOnly,
+}
+
+impl Direction {
+    pub fn serializes(self) -> bool {
+        self != Direction::DeserializeOnly
+    }
+
+    pub fn deserializes(self) -> bool {
+        self != Direction::SerializeOnly
+    }
+
+    /// Parse `serialize_only` (`read_only`), `deserialize_only` (`write_only`), or `both`;
+    /// kebab-case is accepted too
+    pub fn from_name(name: &str) -> Option<Direction> {
+        match name.replace('-', "_").as_str() {
+            "both" => Some(Direction::Both),
+            "serialize_only" | "read_only" => Some(Direction::SerializeOnly),
+            "deserialize_only" | "write_only" => Some(Direction::DeserializeOnly),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Both => "both",
+            Direction::SerializeOnly => "serialize-only",
+            Direction::DeserializeOnly => "deserialize-only",
+        })
+    }
+}
+
+/// Fidelity of a single field mapping between two type systems
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fidelity {
+    /// Direct, lossless equivalent
+    Exact,
+    /// Representable, but with range or precision loss
+    Lossy,
+    /// No faithful representation in the target
+    Unsupported,
+}
+
+impl Fidelity {
+    /// Contribution of this field to a compatibility score
+    pub fn weight(self) -> f32 {
+        match self {
+            Fidelity::Exact => 1.0,
+            Fidelity::Lossy => 0.5,
+            Fidelity::Unsupported => 0.0,
+        }
+    }
+
+    /// The less faithful of two fidelities
+    pub fn worst(self, other: Fidelity) -> Fidelity {
+        if other.weight() < self.weight() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The more faithful of two fidelities
+    pub fn best(self, other: Fidelity) -> Fidelity {
+        if other.weight() > self.weight() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Average per-field fidelities into a compatibility score (empty types score 1.0)
+pub fn average_fidelity(fidelities: impl Iterator<Item = Fidelity>) -> f32 {
+    let (total, count) = fidelities.fold((0.0, 0), |(total, count), f| (total + f.weight(), count + 1));
+    if count == 0 {
+        1.0
+    } else {
+        total / count as f32
+    }
+}
+
+/// Transport class for compatibility classification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransportClass {
+    /// 100% fidelity - all types have direct equivalents
+    Concorde,
+    /// 95-99% fidelity - minor precision differences
+    BusinessClass,
+    /// 80-94% fidelity - some type coercion needed
+    Economy,
+    /// <80% fidelity - significant impedance mismatch
+    Wheelbarrow,
+}
+
+impl TransportClass {
+    /// Classify a compatibility score (0.0–1.0) into a transport class
+    pub fn from_score(score: f32) -> TransportClass {
+        if score >= 1.0 {
+            TransportClass::Concorde
+        } else if score >= 0.95 {
+            TransportClass::BusinessClass
+        } else if score >= 0.80 {
+            TransportClass::Economy
+        } else {
+            TransportClass::Wheelbarrow
+        }
+    }
+}
+
+/// Fidelity of the default mapping of an IR type into a target language
+pub fn fidelity(ty: &IrType, target: &str) -> Fidelity {
+    match ty {
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner)) => fidelity(inner, target),
+        IrType::Container(ContainerType::Result(ok, err)) => fidelity(ok, target).worst(fidelity(err, target)),
+        IrType::Container(ContainerType::Map(key, value)) => key_fidelity(key, target).worst(fidelity(value, target)),
+        // Targets without fixed-length arrays fall back to dynamic ones and
+        // lose the length guarantee
+        IrType::Container(ContainerType::Array(inner, _)) => match target {
+            "rust" | "julia" => fidelity(inner, target),
+            _ => fidelity(inner, target).worst(Fidelity::Lossy),
+        },
+        IrType::Primitive(primitive) => primitive_fidelity(*primitive, target),
+        IrType::Alias(_, aliased) => fidelity(aliased, target),
+        IrType::TraitObject(_) | IrType::Function(_, _) => Fidelity::Unsupported,
+        IrType::Reference(_) | IrType::TypeParam(_) => known_target(target),
+        IrType::Generic(_, args) => args.iter().fold(known_target(target), |acc, arg| acc.worst(fidelity(arg, target))),
+        // Dynamic values survive only as an untyped JSON-like escape hatch
+        IrType::Any => known_target(target).worst(Fidelity::Lossy),
+        IrType::Union(members) => union_fidelity(members, target),
+        IrType::Tuple(members) => members
+            .iter()
+            .fold(known_target(target), |acc, member| acc.worst(fidelity(member, target))),
+    }
+}
+
+/// Map keys cross the wire as JSON object keys, so only strings are safe
+/// everywhere; integer keys survive where the target parses them back
+fn key_fidelity(key: &IrType, target: &str) -> Fidelity {
+    match key {
+        IrType::Alias(_, aliased) => key_fidelity(aliased, target),
+        IrType::Primitive(PrimitiveType::String) => known_target(target),
+        IrType::Primitive(primitive) if primitive.is_integer() => {
+            match target {
+                // JS objects only key by string: `Record<number, V>` and
+                // `Belt.Map.Int` keys need converting on every crossing
+                "typescript" | "rescript" => known_target(target).worst(Fidelity::Lossy),
+                _ => primitive_fidelity(*primitive, target),
+            }
+        }
+        _ => known_target(target).worst(Fidelity::Lossy),
+    }
+}
+
+/// Targets with native ad-hoc unions keep them as-is; the rest get a synthesized
+/// tagged enum, which is only lossless when members differ in their wire kind
+fn union_fidelity(members: &[IrType], target: &str) -> Fidelity {
+    let native = matches!(target, "typescript" | "julia");
+    let structural = if native || is_discriminable(members) {
+        Fidelity::Exact
+    } else {
+        Fidelity::Lossy
+    };
+    members
+        .iter()
+        .fold(structural, |acc, member| acc.worst(fidelity(member, target)))
+}
+
+/// Coarse JSON-level kind of a value, used to tell union members apart on the wire
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WireKind {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    Unknown,
+}
+
+pub fn wire_kind(ty: &IrType) -> WireKind {
+    match ty {
+        IrType::Primitive(PrimitiveType::Unit) => WireKind::Null,
+        IrType::Primitive(PrimitiveType::Bool) => WireKind::Bool,
+        IrType::Primitive(PrimitiveType::String | PrimitiveType::DateTime | PrimitiveType::Uuid | PrimitiveType::Bytes) => {
+            WireKind::String
+        }
+        IrType::Primitive(_) => WireKind::Number,
+        IrType::Alias(_, aliased) => wire_kind(aliased),
+        IrType::Container(ContainerType::Vec(_) | ContainerType::Array(_, _)) | IrType::Tuple(_) => WireKind::Array,
+        IrType::Container(ContainerType::Option(inner)) => wire_kind(inner),
+        IrType::Container(ContainerType::Result(_, _) | ContainerType::Map(_, _)) | IrType::Reference(_) | IrType::Generic(_, _) => WireKind::Object,
+        IrType::TypeParam(_) | IrType::Any | IrType::Union(_) | IrType::TraitObject(_) | IrType::Function(_, _) => WireKind::Unknown,
+    }
+}
+
+/// Whether every union member has a distinct wire kind, so untagged values can be decoded
+pub fn is_discriminable(members: &[IrType]) -> bool {
+    let kinds: Vec<WireKind> = members.iter().map(wire_kind).collect();
+    kinds
+        .iter()
+        .enumerate()
+        .all(|(i, kind)| *kind != WireKind::Unknown && !kinds[i + 1..].contains(kind))
+}
+
+/// What a union collapses to in targets without ad-hoc unions
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnionShape {
+    /// Only string literals (`"a" | "b"`, `[#A | #B]`): an enum of these tags,
+    /// which are plain strings on the wire
+    Enum(Vec<String>),
+    /// Members that are all one type (`1 | 2`)
+    Single(IrType),
+    /// Another shape that may also be null (`string | null`)
+    Option(Box<UnionShape>),
+    /// Members a decoder tells apart by their JSON kind (`string | number`)
+    Tagged(Vec<IrType>),
+    /// Members that look alike on the wire (`User | Admin`): only an untyped
+    /// value holds them, which travels Wheelbarrow class
+    EscapeHatch(Vec<IrType>),
+}
+
+impl UnionShape {
+    /// IR type of a value of this shape; an enum is the string it is on the wire
+    pub fn to_ir(&self) -> IrType {
+        match self {
+            UnionShape::Enum(_) => IrType::Primitive(PrimitiveType::String),
+            UnionShape::Single(ty) => ty.clone(),
+            UnionShape::Option(inner) => IrType::Container(ContainerType::Option(Box::new(inner.to_ir()))),
+            UnionShape::Tagged(members) => IrType::Union(members.clone()),
+            UnionShape::EscapeHatch(_) => IrType::Any,
+        }
+    }
+}
+
+/// Decide what a union collapses to; each member comes with its text when it
+/// is a string literal, and `null` members (`Unit`) make the rest optional
+pub fn union_shape(members: &[(IrType, Option<String>)]) -> UnionShape {
+    let present: Vec<&(IrType, Option<String>)> =
+        members.iter().filter(|(ty, _)| *ty != IrType::Primitive(PrimitiveType::Unit)).collect();
+    let shape = if present.is_empty() {
+        UnionShape::Single(IrType::Primitive(PrimitiveType::Unit))
+    } else if present.iter().all(|(_, literal)| literal.is_some()) {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in present.iter().filter_map(|(_, literal)| literal.as_ref()) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        UnionShape::Enum(tags)
+    } else {
+        let mut distinct: Vec<IrType> = Vec::new();
+        for (ty, _) in &present {
+            if !distinct.contains(ty) {
+                distinct.push(ty.clone());
+            }
+        }
+        match distinct.len() {
+            1 => UnionShape::Single(distinct.remove(0)),
+            _ if is_discriminable(&distinct) => UnionShape::Tagged(distinct),
+            _ => UnionShape::EscapeHatch(distinct),
+        }
+    };
+    if !present.is_empty() && present.len() < members.len() {
+        UnionShape::Option(Box::new(shape))
+    } else {
+        shape
+    }
+}
+
+fn known_target(target: &str) -> Fidelity {
+    match target {
+        "rust" | "rescript" | "julia" | "gleam" | "typescript" => Fidelity::Exact,
+        _ => Fidelity::Unsupported,
+    }
+}
+
+fn primitive_fidelity(primitive: PrimitiveType, target: &str) -> Fidelity {
+    match (target, primitive) {
+        // ReScript int is a signed 32-bit JS number; u32, 64-bit and wider integers cannot round-trip
+        ("rescript", PrimitiveType::U32 | PrimitiveType::I64 | PrimitiveType::U64 | PrimitiveType::I128 | PrimitiveType::U128) => Fidelity::Lossy,
+        // TypeScript numbers are IEEE doubles: 64-bit and wider integers lose precision past 2^53
+        ("typescript", PrimitiveType::I64 | PrimitiveType::U64 | PrimitiveType::I128 | PrimitiveType::U128) => Fidelity::Lossy,
+        // Without a decimal type, only a float conversion is left
+        ("typescript" | "rescript" | "gleam", PrimitiveType::Decimal) => Fidelity::Lossy,
+        _ => known_target(target),
+    }
+}
+
+/// Fidelity of carrying a value of type `source` in a slot of type `target`
+pub fn compare(source: &IrType, target: &IrType) -> Fidelity {
+    match (source, target) {
+        (a, b) if a == b => Fidelity::Exact,
+        // An alias matches a reference by name, like two references do
+        (IrType::Alias(a, _), IrType::Reference(b)) | (IrType::Reference(a), IrType::Alias(b, _))
+            if normalize_name(a) == normalize_name(b) =>
+        {
+            Fidelity::Exact
+        }
+        (IrType::Alias(_, a), b) => compare(a, b),
+        (a, IrType::Alias(_, b)) => compare(a, b),
+        // Every source member must land somewhere in the target
+        (IrType::Union(members), _) => members
+            .iter()
+            .fold(Fidelity::Exact, |acc, member| acc.worst(compare(member, target))),
+        (_, IrType::Union(members)) => members
+            .iter()
+            .map(|member| compare(source, member))
+            .fold(Fidelity::Unsupported, Fidelity::best),
+        (IrType::TraitObject(_), _) | (_, IrType::TraitObject(_)) => Fidelity::Unsupported,
+        // A callback registered in one slot is called with the other's
+        // arguments, so parameters fit in reverse
+        (IrType::Function(a_params, a_ret), IrType::Function(b_params, b_ret)) if a_params.len() == b_params.len() => a_params
+            .iter()
+            .zip(b_params)
+            .fold(compare(a_ret, b_ret), |acc, (a, b)| acc.worst(compare(b, a))),
+        (IrType::Function(_, _), _) | (_, IrType::Function(_, _)) => Fidelity::Unsupported,
+        (IrType::Any, _) | (_, IrType::Any) => Fidelity::Lossy,
+        (IrType::TypeParam(_), _) | (_, IrType::TypeParam(_)) => Fidelity::Lossy,
+        (IrType::Reference(a), IrType::Reference(b)) => {
+            if normalize_name(a) == normalize_name(b) {
+                Fidelity::Exact
+            } else {
+                Fidelity::Unsupported
+            }
+        }
+        // The same generic type fits when every argument does
+        (IrType::Generic(a, a_args), IrType::Generic(b, b_args)) if normalize_name(a) == normalize_name(b) && a_args.len() == b_args.len() => a_args
+            .iter()
+            .zip(b_args)
+            .fold(Fidelity::Exact, |acc, (a, b)| acc.worst(compare(a, b))),
+        // Same name, but only one side says what it holds
+        (IrType::Generic(a, _), IrType::Reference(b)) | (IrType::Reference(a), IrType::Generic(b, _)) if normalize_name(a) == normalize_name(b) => {
+            Fidelity::Lossy
+        }
+        (IrType::Primitive(a), IrType::Primitive(b)) => compare_primitive(*a, *b),
+        (IrType::Container(ContainerType::Option(a)), IrType::Container(ContainerType::Option(b)))
+        | (IrType::Container(ContainerType::Vec(a)), IrType::Container(ContainerType::Vec(b))) => compare(a, b),
+        (IrType::Container(ContainerType::Result(a_ok, a_err)), IrType::Container(ContainerType::Result(b_ok, b_err))) => {
+            compare(a_ok, b_ok).worst(compare(a_err, b_err))
+        }
+        (IrType::Container(ContainerType::Map(a_key, a_value)), IrType::Container(ContainerType::Map(b_key, b_value))) => {
+            compare(a_key, b_key).worst(compare(a_value, b_value))
+        }
+        // A fixed array fits any list; the reverse, or another length, can't be checked
+        (IrType::Container(ContainerType::Array(a, n)), IrType::Container(ContainerType::Array(b, m))) if n == m => compare(a, b),
+        (IrType::Container(ContainerType::Array(a, _)), IrType::Container(ContainerType::Vec(b))) => compare(a, b),
+        (
+            IrType::Container(ContainerType::Vec(a) | ContainerType::Array(a, _)),
+            IrType::Container(ContainerType::Array(b, _)),
+        ) => compare(a, b).worst(Fidelity::Lossy),
+        // Tuples are positional: arity must match and every slot must fit
+        (IrType::Tuple(a), IrType::Tuple(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .fold(Fidelity::Exact, |acc, (a, b)| acc.worst(compare(a, b))),
+        // A required value always fits an optional slot; the reverse drops `None`
+        (a, IrType::Container(ContainerType::Option(b))) => compare(a, b),
+        (IrType::Container(ContainerType::Option(a)), b) => compare(a, b).worst(Fidelity::Lossy),
+        _ => Fidelity::Unsupported,
+    }
+}
+
+fn compare_primitive(source: PrimitiveType, target: PrimitiveType) -> Fidelity {
+    use PrimitiveType::*;
+    match (source, target, source.integer_width(), target.integer_width()) {
+        (a, b, _, _) if a == b => Fidelity::Exact,
+        // Widening conversions are lossless: unsigned into a wider signed type too
+        (_, _, Some((a_signed, a_bits)), Some((b_signed, b_bits))) => {
+            if (a_signed == b_signed && a_bits <= b_bits) || (!a_signed && b_signed && a_bits < b_bits) {
+                Fidelity::Exact
+            } else {
+                Fidelity::Lossy
+            }
+        }
+        (F32, F64, _, _) => Fidelity::Exact,
+        // Every fixed-width integer fits a big one, and up to 64 bits fit a decimal
+        (_, BigInt, Some(_), _) => Fidelity::Exact,
+        (_, Decimal, Some((_, bits)), _) if bits <= 64 => Fidelity::Exact,
+        (BigInt | Decimal, I8 | I16 | I32 | I64 | I128 | U8 | U16 | U32 | U64 | U128 | F32 | F64 | Decimal | BigInt, _, _)
+        | (_, Decimal, Some(_), _)
+        | (F32 | F64, Decimal | BigInt, _, _) => Fidelity::Lossy,
+        // The same text, but nothing checks that a string parses as a date, UUID or base64
+        (DateTime | Uuid | Bytes, String, _, _) | (String, DateTime | Uuid | Bytes, _, _) => Fidelity::Lossy,
+        // Exact while the integer fits the float's 24- or 53-bit mantissa
+        (_, F32, Some((_, bits)), _) if bits <= 16 => Fidelity::Exact,
+        (_, F64, Some((_, bits)), _) if bits <= 32 => Fidelity::Exact,
+        (_, F32 | F64, Some(_), _) | (F32 | F64, _, _, Some(_)) | (F64, F32, _, _) => Fidelity::Lossy,
+        _ => Fidelity::Unsupported,
+    }
+}
+
+/// Replace type parameters with concrete types, `params[i]` with `args[i]`;
+/// parameters without an argument are left in place
+pub fn substitute(ty: &IrType, params: &[String], args: &[IrType]) -> IrType {
+    let bind = |inner: &IrType| Box::new(substitute(inner, params, args));
+    match ty {
+        IrType::TypeParam(name) => params
+            .iter()
+            .position(|param| param == name)
+            .and_then(|i| args.get(i))
+            .map_or_else(|| ty.clone(), Clone::clone),
+        IrType::Container(ContainerType::Vec(inner)) => IrType::Container(ContainerType::Vec(bind(inner))),
+        IrType::Container(ContainerType::Option(inner)) => IrType::Container(ContainerType::Option(bind(inner))),
+        IrType::Container(ContainerType::Result(ok, err)) => IrType::Container(ContainerType::Result(bind(ok), bind(err))),
+        IrType::Container(ContainerType::Map(key, value)) => IrType::Container(ContainerType::Map(bind(key), bind(value))),
+        IrType::Container(ContainerType::Array(inner, len)) => IrType::Container(ContainerType::Array(bind(inner), *len)),
+        IrType::Union(members) => IrType::Union(members.iter().map(|member| substitute(member, params, args)).collect()),
+        IrType::Tuple(members) => IrType::Tuple(members.iter().map(|member| substitute(member, params, args)).collect()),
+        IrType::Alias(name, aliased) => IrType::Alias(name.clone(), bind(aliased)),
+        IrType::Generic(name, members) => IrType::Generic(name.clone(), members.iter().map(|member| substitute(member, params, args)).collect()),
+        IrType::Function(params_in, ret) => {
+            IrType::Function(params_in.iter().map(|param| substitute(param, params, args)).collect(), bind(ret))
+        }
+        IrType::Primitive(_) | IrType::Reference(_) | IrType::Any | IrType::TraitObject(_) => ty.clone(),
+    }
+}
+
+/// Traits of the trait objects anywhere in `ty`
+pub fn trait_objects(ty: &IrType) -> Vec<&str> {
+    match ty {
+        IrType::TraitObject(name) => Vec::from([name.as_str()]),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _))
+        | IrType::Alias(_, inner) => trait_objects(inner),
+        IrType::Container(ContainerType::Result(a, b) | ContainerType::Map(a, b)) => {
+            trait_objects(a).into_iter().chain(trait_objects(b)).collect()
+        }
+        IrType::Union(members) | IrType::Tuple(members) | IrType::Generic(_, members) => members.iter().flat_map(trait_objects).collect(),
+        IrType::Primitive(_) | IrType::Reference(_) | IrType::TypeParam(_) | IrType::Any | IrType::Function(_, _) => Vec::new(),
+    }
+}
+
+/// Function types anywhere in `ty`, outermost first
+pub fn functions(ty: &IrType) -> Vec<&IrType> {
+    match ty {
+        IrType::Function(_, _) => Vec::from([ty]),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _))
+        | IrType::Alias(_, inner) => functions(inner),
+        IrType::Container(ContainerType::Result(a, b) | ContainerType::Map(a, b)) => functions(a).into_iter().chain(functions(b)).collect(),
+        IrType::Union(members) | IrType::Tuple(members) | IrType::Generic(_, members) => members.iter().flat_map(functions).collect(),
+        IrType::Primitive(_) | IrType::Reference(_) | IrType::TypeParam(_) | IrType::Any | IrType::TraitObject(_) => Vec::new(),
+    }
+}
+
+/// Member lists of the ad-hoc unions anywhere in `ty`, outermost first
+pub fn unions(ty: &IrType) -> Vec<&[IrType]> {
+    match ty {
+        IrType::Union(members) => core::iter::once(members.as_slice()).chain(members.iter().flat_map(unions)).collect(),
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _))
+        | IrType::Alias(_, inner) => unions(inner),
+        IrType::Container(ContainerType::Result(a, b) | ContainerType::Map(a, b)) => unions(a).into_iter().chain(unions(b)).collect(),
+        IrType::Tuple(members) | IrType::Generic(_, members) => members.iter().flat_map(unions).collect(),
+        IrType::Function(params, ret) => params.iter().flat_map(unions).chain(unions(ret)).collect(),
+        IrType::Primitive(_) | IrType::Reference(_) | IrType::TypeParam(_) | IrType::Any | IrType::TraitObject(_) => Vec::new(),
+    }
+}
+
+/// Normalize a type or field name across naming conventions (`user_id` ≈ `userId`)
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Score how faithfully `source` fields map onto `target` fields, matched by name
+pub fn compare_fields(source: &[IrField], target: &[IrField]) -> f32 {
+    average_fidelity(source.iter().map(|field| {
+        let name = normalize_name(&field.name);
+        target
+            .iter()
+            .find(|other| normalize_name(&other.name) == name)
+            .map(|other| compare(&field.ty, &other.ty))
+            .unwrap_or(Fidelity::Unsupported)
+    }))
+}
+
+/// Score how faithfully `source` variants map onto `target` variants, matched
+/// by name; each variant counts as its least faithful payload field
+pub fn compare_variants(source: &[IrVariant], target: &[IrVariant]) -> f32 {
+    average_fidelity(source.iter().map(|variant| {
+        let name = normalize_name(&variant.name);
+        let Some(other) = target.iter().find(|other| normalize_name(&other.name) == name) else {
+            return Fidelity::Unsupported;
+        };
+        variant.fields.iter().fold(Fidelity::Exact, |acc, field| {
+            let name = normalize_name(&field.name);
+            let fidelity = other
+                .fields
+                .iter()
+                .find(|other| normalize_name(&other.name) == name)
+                .map(|other| compare(&field.ty, &other.ty))
+                .unwrap_or(Fidelity::Unsupported);
+            acc.worst(fidelity)
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn prim(p: PrimitiveType) -> IrType {
+        IrType::Primitive(p)
+    }
+
+    #[test]
+    fn test_compare_numeric_widening() {
+        assert_eq!(compare(&prim(PrimitiveType::I32), &prim(PrimitiveType::I64)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::I64), &prim(PrimitiveType::F64)), Fidelity::Lossy);
+        assert_eq!(compare(&prim(PrimitiveType::String), &prim(PrimitiveType::Bool)), Fidelity::Unsupported);
+    }
+
+    #[test]
+    fn test_compare_integer_widths() {
+        assert_eq!(compare(&prim(PrimitiveType::U8), &prim(PrimitiveType::I16)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::I16), &prim(PrimitiveType::U64)), Fidelity::Lossy);
+        assert_eq!(compare(&prim(PrimitiveType::U64), &prim(PrimitiveType::I128)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::I16), &prim(PrimitiveType::F32)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::U128), &prim(PrimitiveType::F64)), Fidelity::Lossy);
+        assert_eq!(compare(&prim(PrimitiveType::U128), &prim(PrimitiveType::BigInt)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::U64), &prim(PrimitiveType::Decimal)), Fidelity::Exact);
+        assert_eq!(compare(&prim(PrimitiveType::Decimal), &prim(PrimitiveType::F64)), Fidelity::Lossy);
+    }
+
+    #[test]
+    fn test_compare_optionality() {
+        let string = prim(PrimitiveType::String);
+        let optional = IrType::Container(ContainerType::Option(Box::new(string.clone())));
+        assert_eq!(compare(&string, &optional), Fidelity::Exact);
+        assert_eq!(compare(&optional, &string), Fidelity::Lossy);
+    }
+
+    #[test]
+    fn test_fixed_arrays_keep_their_length() {
+        let bytes = |len| IrType::Container(ContainerType::Array(Box::new(prim(PrimitiveType::U32)), len));
+        let list = IrType::Container(ContainerType::Vec(Box::new(prim(PrimitiveType::U32))));
+        assert_eq!(fidelity(&bytes(32), "julia"), Fidelity::Exact);
+        assert_eq!(fidelity(&bytes(32), "typescript"), Fidelity::Lossy);
+        assert_eq!(compare(&bytes(32), &list), Fidelity::Exact);
+        assert_eq!(compare(&list, &bytes(32)), Fidelity::Lossy);
+        assert_eq!(compare(&bytes(32), &bytes(16)), Fidelity::Lossy);
+    }
+
+    #[test]
+    fn test_compare_generics_by_their_arguments() {
+        let page = |arg| IrType::Generic("Page".to_string(), Vec::from([prim(arg)]));
+        assert_eq!(compare(&page(PrimitiveType::U8), &page(PrimitiveType::I64)), Fidelity::Exact);
+        assert_eq!(compare(&page(PrimitiveType::I64), &page(PrimitiveType::U8)), Fidelity::Lossy);
+        assert_eq!(compare(&page(PrimitiveType::String), &IrType::Reference("page".to_string())), Fidelity::Lossy);
+        assert_eq!(fidelity(&page(PrimitiveType::U64), "typescript"), Fidelity::Lossy);
+        let bound = substitute(&IrType::Generic("Page".to_string(), Vec::from([IrType::TypeParam("T".to_string())])), &["T".to_string()], &[prim(PrimitiveType::Bool)]);
+        assert_eq!(bound, page(PrimitiveType::Bool));
+    }
+
+    #[test]
+    fn test_compare_fields_by_normalized_name() {
+        let source = vec![IrField::new("userId", prim(PrimitiveType::I64))];
+        let target = vec![IrField::new("user_id", prim(PrimitiveType::I64))];
+        assert_eq!(compare_fields(&source, &target), 1.0);
+        assert_eq!(compare_fields(&source, &[]), 0.0);
+    }
+
+    #[test]
+    fn test_compare_variants_by_name_and_payload() {
+        let source = vec![
+            IrVariant::new("Active", vec![]),
+            IrVariant::new("Suspended", vec![IrField::new("0", prim(PrimitiveType::String))]),
+            IrVariant::new("Banned", vec![IrField::new("until", prim(PrimitiveType::I64))]),
+        ];
+        let target = vec![
+            IrVariant::new("active", vec![]),
+            IrVariant::new("suspended", vec![IrField::new("0", prim(PrimitiveType::String))]),
+            IrVariant::new("banned", vec![IrField::new("until", prim(PrimitiveType::F64))]),
+        ];
+        assert_eq!(compare_variants(&source, &target), 2.5 / 3.0);
+        assert_eq!(compare_variants(&source, &target[..1]), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_union_fidelity() {
+        let string_or_number = IrType::Union(vec![prim(PrimitiveType::String), prim(PrimitiveType::F64)]);
+        assert_eq!(fidelity(&string_or_number, "rust"), Fidelity::Exact);
+        let int_or_float = IrType::Union(vec![prim(PrimitiveType::I64), prim(PrimitiveType::F64)]);
+        assert_eq!(fidelity(&int_or_float, "gleam"), Fidelity::Lossy);
+        assert_eq!(fidelity(&int_or_float, "typescript"), Fidelity::Lossy);
+        assert_eq!(fidelity(&int_or_float, "julia"), Fidelity::Exact);
+
+        assert_eq!(compare(&prim(PrimitiveType::String), &string_or_number), Fidelity::Exact);
+        assert_eq!(compare(&string_or_number, &prim(PrimitiveType::String)), Fidelity::Unsupported);
+    }
+
+    #[test]
+    fn test_union_shape() {
+        let literal = |tag: &str| (prim(PrimitiveType::String), Some(tag.to_string()));
+        let null = (prim(PrimitiveType::Unit), None);
+        let reference = |name: &str| (IrType::Reference(name.to_string()), None);
+        assert_eq!(union_shape(&[literal("a"), literal("b"), literal("a")]), UnionShape::Enum(vec!["a".to_string(), "b".to_string()]));
+        let optional = union_shape(&[(prim(PrimitiveType::F64), None), null.clone()]);
+        assert_eq!(optional, UnionShape::Option(Box::new(UnionShape::Single(prim(PrimitiveType::F64)))));
+        assert_eq!(optional.to_ir(), IrType::Container(ContainerType::Option(Box::new(prim(PrimitiveType::F64)))));
+        let tagged = union_shape(&[literal("auto"), (prim(PrimitiveType::F64), None)]);
+        assert_eq!(tagged.to_ir(), IrType::Union(vec![prim(PrimitiveType::String), prim(PrimitiveType::F64)]));
+        let hatch = union_shape(&[reference("User"), reference("Admin")]);
+        assert!(matches!(hatch, UnionShape::EscapeHatch(_)));
+        assert_eq!(TransportClass::from_score(fidelity(&hatch.to_ir(), "rust").weight()), TransportClass::Wheelbarrow);
+    }
+
+    #[test]
+    fn test_typescript_fidelity_of_wide_integers() {
+        assert_eq!(fidelity(&prim(PrimitiveType::I64), "typescript"), Fidelity::Lossy);
+        assert_eq!(fidelity(&prim(PrimitiveType::I64), "rust"), Fidelity::Exact);
+        assert_eq!(fidelity(&prim(PrimitiveType::Bool), "cobol"), Fidelity::Unsupported);
+    }
+}
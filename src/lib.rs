@@ -43,6 +43,13 @@
 //! println!("{}", report);
 //! ```
 //!
+//! # Stable API
+//!
+//! Downstream tools should go through [`api`] (`Config`, `Analyzer`,
+//! `Registry`, `Report`, `Generator`), which is covered by semver. The
+//! per-language analyzer modules are public for experimentation but may
+//! change between minor releases.
+//!
 //! # Transport Classes
 //!
 //! - **Concorde**: 100% fidelity (ReScript ↔ Rust)
@@ -50,16 +57,62 @@
 //! - **Economy**: 80-94% fidelity
 //! - **Wheelbarrow**: <80% fidelity
 //!
+//! # `no_std`
+//!
+//! The IR and scoring core ([`ir`]) only needs `alloc`. Build with
+//! `default-features = false` to embed compatibility checking in firmware
+//! host tools; the parsers, generators, and [`api`] need the `std` feature.
+//!
 //! # Integration with protocol-squisher
 //!
 //! This library extends protocol-squisher (located at `~/Documents/hyperpolymath-repos/protocol-squisher`)
 //! with language type analyzers, enabling automatic FFI binding generation.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod analyzers;
+#[cfg(feature = "std")]
+pub mod api;
+#[cfg(feature = "std")]
+pub mod archive;
+#[cfg(feature = "std")]
+pub mod bom;
+#[cfg(feature = "std")]
+pub mod capabilities;
+#[cfg(feature = "std")]
+pub mod codegen;
+#[cfg(feature = "std")]
+pub mod conformance;
+#[cfg(feature = "std")]
+pub mod converters;
+#[cfg(feature = "std")]
+pub mod integrity;
+pub mod ir;
+#[cfg(feature = "std")]
+pub(crate) mod json;
+#[cfg(feature = "std")]
+pub mod source;
+#[cfg(feature = "std")]
+pub mod target;
+#[cfg(feature = "std")]
+pub mod usage;
 
 // Re-export main types
+pub use ir::TransportClass;
+#[cfg(feature = "std")]
 pub use analyzers::{
-    TransportClass,
     calculate_transport_class,
     compatibility_report,
 };
+#[cfg(feature = "std")]
+pub use converters::{ConverterRegistry, FieldConverter};
+pub use ir::{Fidelity, IrType};
+#[cfg(feature = "std")]
+pub mod watch;
+#[cfg(feature = "std")]
+pub(crate) mod xml;
+#[cfg(feature = "std")]
+pub(crate) mod yaml;
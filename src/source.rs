@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Pluggable analysis input
+//!
+//! Analyzers take text; a [`SourceProvider`] decides where that text comes
+//! from. Besides the working tree, sources can be read from a git revision
+//! (so a CI job can compare the schema at `main` against the PR branch from a
+//! single checkout), from a tar archive, or from standard input.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Reads source files by path
+pub trait SourceProvider {
+    /// Text of one source file; `path` is relative to the provider's root
+    fn read(&self, path: &str) -> io::Result<String>;
+
+    /// Where `path` is read from, for messages (`main:src/user.rs`)
+    fn describe(&self, path: &str) -> String {
+        path.to_string()
+    }
+}
+
+/// Files in a directory of the working tree
+#[derive(Debug, Clone)]
+pub struct LocalFiles {
+    root: PathBuf,
+}
+
+impl LocalFiles {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalFiles { root: root.into() }
+    }
+}
+
+impl Default for LocalFiles {
+    /// The current directory
+    fn default() -> Self {
+        LocalFiles::new(".")
+    }
+}
+
+impl SourceProvider for LocalFiles {
+    fn read(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(self.root.join(path))
+    }
+}
+
+/// Files as committed at a git revision, read with `git show` so nothing is
+/// checked out
+#[derive(Debug, Clone)]
+pub struct GitRevision {
+    repo: PathBuf,
+    revision: String,
+}
+
+impl GitRevision {
+    /// `revision` is anything `git rev-parse` accepts (`main`, `HEAD~2`, a hash);
+    /// paths are relative to `repo`. A revision starting with `-` is refused,
+    /// since git would read it as an option
+    pub fn new(repo: impl Into<PathBuf>, revision: &str) -> io::Result<Self> {
+        if revision.is_empty() || revision.starts_with('-') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("`{}` is not a git revision", revision)));
+        }
+        Ok(GitRevision { repo: repo.into(), revision: revision.to_string() })
+    }
+}
+
+impl SourceProvider for GitRevision {
+    fn read(&self, path: &str) -> io::Result<String> {
+        if Path::new(path).is_absolute() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "paths in a git revision must be relative"));
+        }
+        // `./` resolves against `repo` rather than the top of the work tree
+        let path = path.strip_prefix("./").unwrap_or(path);
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .arg("show")
+            .arg("--end-of-options")
+            .arg(format!("{}:./{}", self.revision, path))
+            .output()?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(io::Error::new(io::ErrorKind::NotFound, message));
+        }
+        String::from_utf8(output.stdout).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn describe(&self, path: &str) -> String {
+        format!("{}:{}", self.revision, path)
+    }
+}
+
+/// Regular files of an uncompressed tar archive, held in memory
+#[derive(Debug, Clone)]
+pub struct TarArchive {
+    name: String,
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl TarArchive {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        TarArchive::from_bytes(&path.display().to_string(), &fs::read(path)?)
+    }
+
+    /// Parse ustar, GNU, and pax archives; `name` only appears in messages
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", name, message));
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return Err(invalid("gzip-compressed archives must be decompressed first"));
+        }
+
+        let mut entries = BTreeMap::new();
+        // Set by a GNU `L` or pax `x` header for the entry that follows it
+        let mut long_name: Option<String> = None;
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let header = bytes.get(offset..offset + 512).ok_or_else(|| invalid("truncated header"))?;
+            if header.iter().all(|&b| b == 0) {
+                break;
+            }
+            let size = octal(&header[124..136]).ok_or_else(|| invalid("malformed entry size"))?;
+            let data_start = offset + 512;
+            let data = bytes
+                .get(data_start..data_start + size)
+                .ok_or_else(|| invalid("truncated entry"))?;
+            offset = data_start + size.div_ceil(512) * 512;
+
+            match header[156] {
+                b'L' => long_name = Some(c_string(data)),
+                b'x' => long_name = pax_path(data).or(long_name),
+                b'0' | 0 => {
+                    let name = long_name.take().unwrap_or_else(|| {
+                        let name = c_string(&header[..100]);
+                        let prefix = c_string(&header[345..500]);
+                        if header[257..262] == *b"ustar" && !prefix.is_empty() {
+                            format!("{}/{}", prefix, name)
+                        } else {
+                            name
+                        }
+                    });
+                    entries.insert(normalize(&name).to_string(), data.to_vec());
+                }
+                // Directories, links, devices
+                _ => long_name = None,
+            }
+        }
+        Ok(TarArchive { name: name.to_string(), entries })
+    }
+
+    /// Paths of every regular file, sorted
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+impl SourceProvider for TarArchive {
+    fn read(&self, path: &str) -> io::Result<String> {
+        let bytes = self
+            .entries
+            .get(normalize(path))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("not in {}", self.name)))?;
+        String::from_utf8(bytes.clone()).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn describe(&self, path: &str) -> String {
+        format!("{}:{}", self.name, normalize(path))
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn octal(field: &[u8]) -> Option<usize> {
+    let text = c_string(field);
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(0);
+    }
+    usize::from_str_radix(text, 8).ok()
+}
+
+/// `path` from pax extended header records (`<len> path=<value>\n`)
+fn pax_path(data: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .find_map(|record| record.split_once(' ')?.1.strip_prefix("path=").map(str::to_string))
+}
+
+/// Standard input, read once on first use; the only path is `-`
+#[derive(Debug, Default)]
+pub struct Stdin {
+    text: OnceLock<Result<String, String>>,
+}
+
+impl SourceProvider for Stdin {
+    fn read(&self, path: &str) -> io::Result<String> {
+        if path != "-" {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "standard input is read as `-`"));
+        }
+        let text = self.text.get_or_init(|| {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text).map_err(|err| err.to_string())
+        });
+        text.clone().map_err(io::Error::other)
+    }
+
+    fn describe(&self, _path: &str) -> String {
+        "<stdin>".to_string()
+    }
+}
+
+/// Provider for a command-line spec: `git:<revision>` (relative to the
+/// current directory), `tar:<archive>`, `stdin`, or `local`
+pub fn parse_provider(spec: &str) -> Result<Box<dyn SourceProvider>, String> {
+    if let Some(revision) = spec.strip_prefix("git:") {
+        return GitRevision::new(".", revision)
+            .map(|revision| Box::new(revision) as Box<dyn SourceProvider>)
+            .map_err(|err| err.to_string());
+    }
+    if let Some(archive) = spec.strip_prefix("tar:") {
+        return TarArchive::open(archive)
+            .map(|archive| Box::new(archive) as Box<dyn SourceProvider>)
+            .map_err(|err| format!("{}: {}", archive, err));
+    }
+    match spec {
+        "stdin" | "-" => Ok(Box::new(Stdin::default())),
+        "local" => Ok(Box::new(LocalFiles::default())),
+        _ => Err(format!("unknown source `{}` (expected git:<rev>, tar:<file>, stdin, or local)", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One tar entry: header plus data padded to a block
+    fn entry(name: &str, kind: u8, data: &[u8]) -> Vec<u8> {
+        let mut header = vec![0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        let mut block = header;
+        block.extend_from_slice(data);
+        block.resize(512 + data.len().div_ceil(512) * 512, 0);
+        block
+    }
+
+    #[test]
+    fn test_tar_archive() {
+        let long = format!("schemas/{}/user.rs", "nested".repeat(20));
+        let mut bytes = Vec::new();
+        bytes.extend(entry("./src/", b'5', b""));
+        bytes.extend(entry("./src/user.rs", b'0', b"pub struct User { pub id: i64 }"));
+        bytes.extend(entry("././@LongLink", b'L', format!("{}\0", long).as_bytes()));
+        bytes.extend(entry("schemas/truncated", b'0', &[b'x'; 600]));
+        bytes.extend([0u8; 1024]);
+
+        let archive = TarArchive::from_bytes("schema.tar", &bytes).unwrap();
+        assert_eq!(archive.paths().collect::<Vec<_>>(), vec![long.as_str(), "src/user.rs"]);
+        assert_eq!(archive.read("./src/user.rs").unwrap(), "pub struct User { pub id: i64 }");
+        assert_eq!(archive.read(&long).unwrap().len(), 600);
+        assert_eq!(archive.describe("./src/user.rs"), "schema.tar:src/user.rs");
+        assert_eq!(archive.read("src/order.rs").unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        assert!(TarArchive::from_bytes("schema.tar.gz", &[0x1f, 0x8b, 8, 0]).is_err());
+        assert!(TarArchive::from_bytes("cut.tar", &bytes[..700]).is_err());
+        assert!(TarArchive::from_bytes("cut.tar", &bytes[..3300]).is_err());
+    }
+
+    #[test]
+    fn test_parse_provider() {
+        assert_eq!(parse_provider("git:main").unwrap().describe("src/user.rs"), "main:src/user.rs");
+        assert_eq!(parse_provider("stdin").unwrap().describe("-"), "<stdin>");
+        assert!(parse_provider("stdin").unwrap().read("src/user.rs").is_err());
+        let Err(missing) = parse_provider("tar:/nonexistent/schema.tar") else { panic!("opened a missing archive") };
+        assert!(missing.starts_with("/nonexistent/schema.tar: "));
+        assert!(parse_provider("svn:trunk").is_err());
+
+        let local = LocalFiles::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(local.read("Cargo.toml").unwrap().contains("[package]"));
+        assert!(GitRevision::new(".", "HEAD").unwrap().read("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_git_revisions_are_not_options() {
+        let Err(option) = parse_provider("git:--output=/tmp/lic-injected") else { panic!("took an option as a revision") };
+        assert_eq!(option, "`--output=/tmp/lic-injected` is not a git revision");
+        assert!(parse_provider("git:").is_err());
+        assert_eq!(GitRevision::new(".", "-p").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(!Path::new("/tmp/lic-injected").exists());
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Cross-compilation target triples
+// Pointer width, C `long` width, scalar alignment, and calling convention per
+// triple, so layouts and FFI code can be checked for every platform a binding ships to
+
+use std::fmt;
+
+use crate::ir::Fidelity;
+
+/// A Rust target triple: `arch-vendor-os[-env]` (`x86_64-pc-windows-msvc`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetTriple {
+    pub arch: String,
+    pub vendor: String,
+    pub os: String,
+    pub env: Option<String>,
+}
+
+impl TargetTriple {
+    /// Parse a triple; two-part triples (`wasm32-wasip1`) get an `unknown` vendor
+    pub fn parse(triple: &str) -> Result<TargetTriple, String> {
+        let parts: Vec<&str> = triple.split('-').collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(format!("malformed target triple `{}`", triple));
+        }
+        let (arch, vendor, os, env) = match parts.as_slice() {
+            [arch, os] => (arch, &"unknown", os, None),
+            [arch, vendor, os] => (arch, vendor, os, None),
+            [arch, vendor, os, env] => (arch, vendor, os, Some(env.to_string())),
+            _ => return Err(format!("malformed target triple `{}`", triple)),
+        };
+        Ok(TargetTriple {
+            arch: arch.to_string(),
+            vendor: vendor.to_string(),
+            os: os.to_string(),
+            env,
+        })
+    }
+
+    pub fn is_windows(&self) -> bool {
+        self.os == "windows"
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        self.arch.starts_with("wasm")
+    }
+
+    /// Width of pointers, `usize`, and `size_t` in bits
+    pub fn pointer_bits(&self) -> u32 {
+        match self.arch.as_str() {
+            "avr" | "msp430" => 16,
+            // ILP32 on a 64-bit core (watchOS)
+            "arm64_32" => 32,
+            arch if arch.contains("64") || arch == "s390x" => 64,
+            _ => 32,
+        }
+    }
+
+    /// Width of C `long`: 32-bit on Windows (LLP64), pointer-sized elsewhere (LP64)
+    pub fn c_long_bits(&self) -> u32 {
+        if self.is_windows() {
+            32
+        } else {
+            self.pointer_bits().max(32)
+        }
+    }
+
+    /// Width of a platform-dependent integer on this triple
+    pub fn width_bits(&self, width: PlatformWidth) -> u32 {
+        match width {
+            PlatformWidth::Pointer => self.pointer_bits(),
+            PlatformWidth::CLong => self.c_long_bits(),
+        }
+    }
+
+    /// Alignment of 8-byte scalars (`i64`, `u64`, `f64`): the i386 System V ABI
+    /// only aligns them to 4
+    pub fn align_of_8_byte(&self) -> usize {
+        if self.cfg_arch() == "x86" && !self.is_windows() {
+            4
+        } else {
+            8
+        }
+    }
+
+    /// Layout of a scalar (integer, float, pointer) of `bytes` bytes
+    pub fn scalar_layout(&self, bytes: usize) -> Layout {
+        Layout::new(bytes, if bytes == 8 { self.align_of_8_byte() } else { bytes })
+    }
+
+    /// Layout of a pointer, `usize`, or `size_t`
+    pub fn pointer_layout(&self) -> Layout {
+        self.scalar_layout(self.pointer_bits() as usize / 8)
+    }
+
+    pub fn calling_convention(&self) -> CallingConvention {
+        match (self.cfg_arch(), self.is_windows()) {
+            (_, _) if self.is_wasm() => CallingConvention::Wasm,
+            ("x86_64", true) => CallingConvention::Win64,
+            ("x86_64", false) => CallingConvention::SysV64,
+            ("aarch64", _) => CallingConvention::Aapcs64,
+            ("arm", _) => CallingConvention::Aapcs,
+            ("x86", _) => CallingConvention::Cdecl,
+            _ => CallingConvention::Other,
+        }
+    }
+
+    /// `target_arch` value rustc uses for this triple (`i686` → `x86`)
+    pub fn cfg_arch(&self) -> &str {
+        let arch = self.arch.as_str();
+        match arch {
+            "i386" | "i586" | "i686" => "x86",
+            "arm64" | "arm64e" => "aarch64",
+            _ if arch.starts_with("armv") || arch.starts_with("thumbv") || arch == "arm" => "arm",
+            _ if arch.starts_with("riscv64") => "riscv64",
+            _ if arch.starts_with("riscv32") => "riscv32",
+            _ => arch,
+        }
+    }
+
+    /// `#[cfg(...)]` predicate that selects this triple in generated Rust
+    pub fn cfg_predicate(&self) -> String {
+        let mut predicates = vec![
+            format!("target_arch = \"{}\"", self.cfg_arch()),
+            format!("target_os = \"{}\"", if self.os == "darwin" { "macos" } else { &self.os }),
+        ];
+        if let Some(env) = &self.env {
+            predicates.push(format!("target_env = \"{}\"", env));
+        }
+        format!("all({})", predicates.join(", "))
+    }
+}
+
+impl fmt::Display for TargetTriple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.arch, self.vendor, self.os)?;
+        if let Some(env) = &self.env {
+            write!(f, "-{}", env)?;
+        }
+        Ok(())
+    }
+}
+
+/// How `extern "C"` functions pass arguments on a triple
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallingConvention {
+    /// System V AMD64 (Linux, macOS, BSDs)
+    SysV64,
+    /// Microsoft x64: four register arguments, shadow space, no 128-bit returns in registers
+    Win64,
+    /// ARM 64-bit procedure call standard (Apple's variant differs only in varargs)
+    Aapcs64,
+    /// 32-bit ARM procedure call standard
+    Aapcs,
+    /// 32-bit x86 cdecl; `extern "system"` is stdcall on Windows
+    Cdecl,
+    /// WebAssembly C ABI: structs are passed by pointer, only wasm value types cross the host boundary
+    Wasm,
+    Other,
+}
+
+impl CallingConvention {
+    pub fn name(self) -> &'static str {
+        match self {
+            CallingConvention::SysV64 => "sysv64",
+            CallingConvention::Win64 => "win64",
+            CallingConvention::Aapcs64 => "aapcs64",
+            CallingConvention::Aapcs => "aapcs",
+            CallingConvention::Cdecl => "cdecl",
+            CallingConvention::Wasm => "wasm",
+            CallingConvention::Other => "C",
+        }
+    }
+}
+
+/// Integer whose width depends on the target; the IR records it at its 64-bit width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformWidth {
+    /// `usize`, `size_t`, `intptr_t`, `nint`
+    Pointer,
+    /// C `long` / `unsigned long`
+    CLong,
+}
+
+impl fmt::Display for PlatformWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PlatformWidth::Pointer => "pointer-sized",
+            PlatformWidth::CLong => "C long",
+        })
+    }
+}
+
+/// Fidelity of a platform-dependent field on a triple: narrower than the
+/// 64 bits the IR assumes means 64-bit peers can send values that don't fit
+pub fn width_fidelity(width: PlatformWidth, triple: &TargetTriple) -> Fidelity {
+    if triple.width_bits(width) < 64 {
+        Fidelity::Lossy
+    } else {
+        Fidelity::Exact
+    }
+}
+
+/// Size and alignment of a type in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: usize,
+    pub align: usize,
+}
+
+impl Layout {
+    pub fn new(size: usize, align: usize) -> Self {
+        Layout { size, align }
+    }
+
+    /// `#[repr(C)]` struct layout: fields in order, each at its alignment,
+    /// the whole padded to the largest alignment
+    pub fn of_struct(fields: impl IntoIterator<Item = Layout>) -> Layout {
+        let (mut size, mut align) = (0usize, 1);
+        for field in fields {
+            size = size.next_multiple_of(field.align) + field.size;
+            align = align.max(field.align);
+        }
+        Layout::new(size.next_multiple_of(align), align)
+    }
+
+    /// `#[repr(C)]` union layout: the largest member, padded to the largest alignment
+    pub fn of_union(fields: impl IntoIterator<Item = Layout>) -> Layout {
+        let (size, align) = fields
+            .into_iter()
+            .fold((0, 1), |(size, align), field| (size.max(field.size), align.max(field.align)));
+        Layout::new(size.next_multiple_of(align), align)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(name: &str) -> TargetTriple {
+        TargetTriple::parse(name).unwrap()
+    }
+
+    #[test]
+    fn test_triple_properties() {
+        let wasm = triple("wasm32-unknown-unknown");
+        assert_eq!(wasm.pointer_bits(), 32);
+        assert_eq!(wasm.calling_convention(), CallingConvention::Wasm);
+
+        let ios = triple("aarch64-apple-ios");
+        assert_eq!((ios.pointer_bits(), ios.c_long_bits()), (64, 64));
+        assert_eq!(ios.calling_convention(), CallingConvention::Aapcs64);
+
+        let windows = triple("x86_64-pc-windows-msvc");
+        assert_eq!((windows.pointer_bits(), windows.c_long_bits()), (64, 32));
+        assert_eq!(windows.calling_convention(), CallingConvention::Win64);
+        assert_eq!(windows.cfg_predicate(), "all(target_arch = \"x86_64\", target_os = \"windows\", target_env = \"msvc\")");
+        assert_eq!(windows.to_string(), "x86_64-pc-windows-msvc");
+
+        assert_eq!(triple("i686-unknown-linux-gnu").align_of_8_byte(), 4);
+        assert_eq!(triple("wasm32-wasip1").vendor, "unknown");
+        assert!(TargetTriple::parse("x86_64").is_err());
+        assert!(TargetTriple::parse("x86_64--linux").is_err());
+    }
+
+    #[test]
+    fn test_struct_layout_padding() {
+        // { u8, u64, u16 } on a 64-bit triple
+        let fields = [Layout::new(1, 1), Layout::new(8, 8), Layout::new(2, 2)];
+        assert_eq!(Layout::of_struct(fields), Layout::new(24, 8));
+        assert_eq!(Layout::of_union(fields), Layout::new(8, 8));
+        assert_eq!(Layout::of_struct([]), Layout::new(0, 1));
+    }
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Interop surface pruning
+//!
+//! Not every shared type actually crosses the boundary. A [`UsageProbe`]
+//! says which types the consuming side mentions at its call sites;
+//! [`analyze`] follows their fields to every type they reach and reports
+//! the rest as dead, so reports and generated bindings can leave it out.
+//! [`ReferenceSearch`] is the built-in probe (a whole-word search of the
+//! consumer's sources); language tooling plugs in as any `Fn(&str) -> bool`,
+//! such as a wrapper around an LSP find-references query.
+
+use std::collections::HashSet;
+
+use crate::api::TypeDef;
+use crate::ir::{normalize_name, ContainerType, IrType};
+
+/// Tells whether code on the consuming side of a boundary uses a type
+pub trait UsageProbe {
+    fn is_used(&self, type_name: &str) -> bool;
+}
+
+impl<F: Fn(&str) -> bool> UsageProbe for F {
+    fn is_used(&self, type_name: &str) -> bool {
+        self(type_name)
+    }
+}
+
+/// Probe that searches source text for a type's name as a whole word, in any
+/// of the spellings bindings use (`user_profile`, `UserProfile`, `userProfile`)
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceSearch {
+    words: HashSet<String>,
+}
+
+impl ReferenceSearch {
+    pub fn new<S: AsRef<str>>(sources: impl IntoIterator<Item = S>) -> Self {
+        let mut words = HashSet::new();
+        for source in sources {
+            words.extend(
+                source
+                    .as_ref()
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|word| !word.is_empty())
+                    .map(normalize_name),
+            );
+        }
+        ReferenceSearch { words }
+    }
+}
+
+impl UsageProbe for ReferenceSearch {
+    fn is_used(&self, type_name: &str) -> bool {
+        self.words.contains(&normalize_name(type_name))
+    }
+}
+
+/// Which analyzed types the consuming side reaches
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Usage {
+    /// Types the probe found, and the types their fields reach, in analysis order
+    pub used: Vec<String>,
+    /// Everything else
+    pub unused: Vec<String>,
+}
+
+impl Usage {
+    pub fn is_used(&self, type_name: &str) -> bool {
+        self.used.iter().any(|used| used == type_name)
+    }
+}
+
+/// Split `types` into those reachable from what the probe sees and the rest
+pub fn analyze(types: &[TypeDef], probe: &dyn UsageProbe) -> Usage {
+    let index = |name: &str| {
+        let name = normalize_name(name);
+        types.iter().position(|def| normalize_name(&def.name) == name)
+    };
+
+    let mut reached = vec![false; types.len()];
+    let mut pending: Vec<usize> = (0..types.len()).filter(|&i| probe.is_used(&types[i].name)).collect();
+    while let Some(i) = pending.pop() {
+        if std::mem::replace(&mut reached[i], true) {
+            continue;
+        }
+        let def = &types[i];
+        let mut names = Vec::new();
+        for field in &def.fields {
+            referenced_names(&field.ty, &mut names);
+        }
+        names.extend(def.references.iter().map(|reference| reference.target_type.as_str()));
+        pending.extend(names.into_iter().filter_map(index).filter(|&j| !reached[j]));
+    }
+
+    let (used, unused): (Vec<_>, Vec<_>) = types.iter().zip(reached).partition(|(_, reached)| *reached);
+    Usage {
+        used: used.into_iter().map(|(def, _)| def.name.clone()).collect(),
+        unused: unused.into_iter().map(|(def, _)| def.name.clone()).collect(),
+    }
+}
+
+/// Only the types the consuming side reaches
+pub fn prune(types: &[TypeDef], probe: &dyn UsageProbe) -> Vec<TypeDef> {
+    let usage = analyze(types, probe);
+    types.iter().filter(|def| usage.is_used(&def.name)).cloned().collect()
+}
+
+pub(crate) fn referenced_names<'a>(ty: &'a IrType, names: &mut Vec<&'a str>) {
+    match ty {
+        IrType::Reference(name) => names.push(name),
+        IrType::Generic(name, args) => {
+            names.push(name);
+            args.iter().for_each(|arg| referenced_names(arg, names));
+        }
+        IrType::Container(ContainerType::Vec(inner) | ContainerType::Option(inner) | ContainerType::Array(inner, _)) => {
+            referenced_names(inner, names)
+        }
+        IrType::Container(ContainerType::Result(ok, err) | ContainerType::Map(ok, err)) => {
+            referenced_names(ok, names);
+            referenced_names(err, names);
+        }
+        IrType::Union(members) | IrType::Tuple(members) => members.iter().for_each(|member| referenced_names(member, names)),
+        IrType::Function(params, ret) => {
+            params.iter().for_each(|param| referenced_names(param, names));
+            referenced_names(ret, names);
+        }
+        IrType::Alias(name, aliased) => {
+            names.push(name);
+            referenced_names(aliased, names);
+        }
+        IrType::Primitive(_) | IrType::TypeParam(_) | IrType::Any | IrType::TraitObject(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Language, Registry};
+
+    const SHARED: &str = r#"
+pub struct UserProfile { pub id: i64, pub address: Option<Address> }
+pub struct Address { pub city: String, pub country: Country }
+pub struct Country { pub code: String }
+pub struct AuditEntry { pub at: i64 }
+pub struct Order { pub id: i64, #[interop(refs = "Invoice.id")] pub invoice_id: i64 }
+pub struct Invoice { pub id: i64 }
+"#;
+
+    fn shared() -> Vec<TypeDef> {
+        Registry::with_builtins().analyzer(Language::Rust).unwrap().analyze(SHARED).unwrap()
+    }
+
+    #[test]
+    fn test_reference_search_follows_fields() {
+        let consumer = "import { fetchUserProfile } from './api';\nconst p: userProfile = await fetchUserProfile(id);";
+        let usage = analyze(&shared(), &ReferenceSearch::new([consumer]));
+        assert_eq!(usage.used, vec!["UserProfile", "Address", "Country"]);
+        assert_eq!(usage.unused, vec!["AuditEntry", "Order", "Invoice"]);
+        // Substrings don't count: `fetchUserProfile` alone isn't a use
+        assert!(!ReferenceSearch::new(["fetchUserProfile()"]).is_used("UserProfile"));
+    }
+
+    #[test]
+    fn test_tooling_probe_and_declared_references() {
+        let lsp = |name: &str| name == "Order";
+        let pruned = prune(&shared(), &lsp);
+        let names: Vec<_> = pruned.iter().map(|def| def.name.as_str()).collect();
+        assert_eq!(names, vec!["Order", "Invoice"]);
+    }
+}
@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+//! Incremental re-analysis for editors and watch mode
+//!
+//! A language server or file watcher reports edits to a [`Scheduler`] as they
+//! arrive. Once a file's edits have settled for the debounce window,
+//! [`Scheduler::poll`] re-analyzes it and then re-checks only its reverse
+//! dependencies: the files whose types mention a type it defines (or used
+//! to). Edited files go before dependents, and each poll stops at its time
+//! budget, so a change that fans out across a large workspace never holds up
+//! diagnostics for the file being typed in.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::api::{Error, Language, Registry, TypeDef};
+use crate::integrity::{check_against, BrokenReference};
+use crate::ir::normalize_name;
+use crate::usage::referenced_names;
+
+/// Timing of a [`Scheduler`]; the defaults keep edit-to-diagnostic latency
+/// under ~100ms
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerConfig {
+    /// Quiet time after a file's last edit before it is re-analyzed
+    pub debounce: Duration,
+    /// Work one poll may do before yielding; at least one file always runs
+    pub budget: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            debounce: Duration::from_millis(30),
+            budget: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Why a file is waiting to be re-analyzed, most urgent first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Its own text changed
+    Edited,
+    /// A type it mentions changed in another file
+    Dependent,
+}
+
+/// Fresh diagnostics for one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub path: String,
+    pub priority: Priority,
+    /// Types the file defines; the last ones that parsed if `error` is set
+    pub types: Vec<TypeDef>,
+    pub error: Option<Error>,
+    /// References from this file's types that the workspace doesn't satisfy
+    pub broken: Vec<BrokenReference>,
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            Some(error) => writeln!(f, "{}: {}", self.path, error)?,
+            None => writeln!(f, "{}: {} type(s)", self.path, self.types.len())?,
+        }
+        for broken in &self.broken {
+            writeln!(f, "  {}", broken)?;
+        }
+        Ok(())
+    }
+}
+
+struct File {
+    language: Language,
+    text: String,
+    /// Whether `types` reflects `text`
+    parsed: bool,
+    types: Vec<TypeDef>,
+}
+
+struct Pending {
+    priority: Priority,
+    due: Instant,
+}
+
+/// Debounced, prioritized re-analysis of a workspace
+pub struct Scheduler {
+    config: SchedulerConfig,
+    files: BTreeMap<String, File>,
+    pending: BTreeMap<String, Pending>,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Scheduler {
+            config,
+            files: BTreeMap::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Record new text for a file; each edit pushes its re-analysis back by the
+    /// debounce window
+    pub fn did_change(&mut self, path: &str, language: Language, text: &str, now: Instant) {
+        let file = self.files.entry(path.to_string()).or_insert_with(|| File {
+            language,
+            text: String::new(),
+            parsed: false,
+            types: Vec::new(),
+        });
+        file.language = language;
+        file.text = text.to_string();
+        file.parsed = false;
+        self.pending.insert(
+            path.to_string(),
+            Pending {
+                priority: Priority::Edited,
+                due: now + self.config.debounce,
+            },
+        );
+    }
+
+    /// Forget a deleted file; files that mentioned its types are re-checked
+    pub fn did_remove(&mut self, path: &str, now: Instant) {
+        self.pending.remove(path);
+        if let Some(file) = self.files.remove(path) {
+            self.schedule_dependents(path, &file.types, now);
+        }
+    }
+
+    /// When the next poll has work to do, if any is queued
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.due).min()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Every type the workspace currently defines
+    pub fn types(&self) -> impl Iterator<Item = &TypeDef> {
+        self.files.values().flat_map(|file| &file.types)
+    }
+
+    /// Files whose types mention a type `path` defines
+    pub fn dependents(&self, path: &str) -> Vec<String> {
+        match self.files.get(path) {
+            Some(file) => self.dependents_of(path, &file.types),
+            None => Vec::new(),
+        }
+    }
+
+    /// Re-analyze settled files, most urgent first, until the queue is empty
+    /// or the budget is spent
+    pub fn poll(&mut self, registry: &Registry, now: Instant) -> Vec<Diagnostics> {
+        let started = Instant::now();
+        let mut results = Vec::new();
+        while let Some(path) = self.next_due(now) {
+            let Some(pending) = self.pending.remove(&path) else { break };
+            results.push(self.run(&path, pending.priority, registry, now));
+            if started.elapsed() >= self.config.budget {
+                break;
+            }
+        }
+        results
+    }
+
+    fn next_due(&self, now: Instant) -> Option<String> {
+        self.pending
+            .iter()
+            .filter(|(_, pending)| pending.due <= now)
+            .min_by_key(|(path, pending)| (pending.priority, *path))
+            .map(|(path, _)| path.clone())
+    }
+
+    fn run(&mut self, path: &str, priority: Priority, registry: &Registry, now: Instant) -> Diagnostics {
+        let Some(file) = self.files.get_mut(path) else {
+            unreachable!("pending files are tracked")
+        };
+        let mut error = None;
+        if !file.parsed {
+            file.parsed = true;
+            let analyzed = match registry.analyzer(file.language) {
+                Some(analyzer) => analyzer.analyze(&file.text),
+                None => Err(Error::new(file.language, "no analyzer registered")),
+            };
+            match analyzed {
+                // Both the old and the new definitions may have had dependents
+                Ok(types) if types != file.types => {
+                    let previous = std::mem::replace(&mut file.types, types);
+                    self.schedule_dependents(path, &previous, now);
+                    let current = self.files[path].types.clone();
+                    self.schedule_dependents(path, &current, now);
+                }
+                Ok(_) => {}
+                // Keep the last good types so dependents don't churn mid-edit
+                Err(err) => error = Some(err),
+            }
+        }
+
+        let file = &self.files[path];
+        let workspace: Vec<TypeDef> = self.types().cloned().collect();
+        Diagnostics {
+            path: path.to_string(),
+            priority,
+            types: file.types.clone(),
+            error,
+            broken: check_against(&file.types, &workspace),
+        }
+    }
+
+    fn schedule_dependents(&mut self, path: &str, defined: &[TypeDef], now: Instant) {
+        for dependent in self.dependents_of(path, defined) {
+            // An edited file already re-checks its references when it runs
+            self.pending.entry(dependent).or_insert(Pending {
+                priority: Priority::Dependent,
+                due: now,
+            });
+        }
+    }
+
+    fn dependents_of(&self, path: &str, defined: &[TypeDef]) -> Vec<String> {
+        let defined: HashSet<String> = defined.iter().map(|def| normalize_name(&def.name)).collect();
+        self.files
+            .iter()
+            .filter(|(other, file)| *other != path && file.types.iter().any(|def| mentions(def, &defined)))
+            .map(|(other, _)| other.clone())
+            .collect()
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler::new(SchedulerConfig::default())
+    }
+}
+
+/// Whether a type's fields or declared references name one of `types`
+fn mentions(def: &TypeDef, types: &HashSet<String>) -> bool {
+    let mut names = Vec::new();
+    for field in &def.fields {
+        referenced_names(&field.ty, &mut names);
+    }
+    names.extend(def.references.iter().map(|reference| reference.target_type.as_str()));
+    names.into_iter().any(|name| types.contains(&normalize_name(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrity::Problem;
+
+    const ADDRESS: &str = "pub struct Address { pub id: i64, pub city: String }";
+    const USER: &str = "pub struct User { pub id: i64, #[interop(refs = \"Address.id\")] pub address_id: i64 }";
+    const AUDIT: &str = "pub struct AuditEntry { pub at: i64 }";
+
+    fn workspace(t0: Instant) -> (Scheduler, Registry) {
+        let mut scheduler = Scheduler::default();
+        scheduler.did_change("address.rs", Language::Rust, ADDRESS, t0);
+        scheduler.did_change("user.rs", Language::Rust, USER, t0);
+        scheduler.did_change("audit.rs", Language::Rust, AUDIT, t0);
+        (scheduler, Registry::with_builtins())
+    }
+
+    #[test]
+    fn test_debounce_then_reverse_dependencies_only() {
+        let t0 = Instant::now();
+        let (mut scheduler, registry) = workspace(t0);
+        assert!(scheduler.poll(&registry, t0).is_empty());
+        let settled = t0 + Duration::from_millis(30);
+        assert_eq!(scheduler.next_deadline(), Some(settled));
+        assert_eq!(scheduler.poll(&registry, settled).len(), 3);
+        assert!(scheduler.is_idle());
+        assert_eq!(scheduler.dependents("address.rs"), vec!["user.rs"]);
+
+        // Renaming the key breaks the reference from `User`; `AuditEntry` isn't touched
+        let t1 = settled + Duration::from_millis(5);
+        scheduler.did_change("address.rs", Language::Rust, "pub struct Address {", t1);
+        scheduler.did_change("address.rs", Language::Rust, &ADDRESS.replace("id", "code"), t1 + Duration::from_millis(10));
+        assert!(scheduler.poll(&registry, t1 + Duration::from_millis(30)).is_empty());
+        let results = scheduler.poll(&registry, t1 + Duration::from_millis(40));
+        let order: Vec<_> = results.iter().map(|d| (d.path.as_str(), d.priority)).collect();
+        assert_eq!(order, vec![("address.rs", Priority::Edited), ("user.rs", Priority::Dependent)]);
+        assert_eq!(results[1].broken[0].problem, Problem::MissingField);
+        assert!(scheduler.is_idle());
+    }
+
+    #[test]
+    fn test_budget_and_parse_errors() {
+        let t0 = Instant::now();
+        let (mut scheduler, registry) = workspace(t0);
+        scheduler.config.budget = Duration::ZERO;
+        let settled = t0 + Duration::from_millis(30);
+        // One file per poll; the rest stay due
+        for expected in ["address.rs", "audit.rs", "user.rs"] {
+            let results = scheduler.poll(&registry, settled);
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].path, expected);
+        }
+
+        // A half-typed file reports its error but keeps its last good types
+        scheduler.did_change("address.rs", Language::Rust, "pub struct Address { pub id: ", settled);
+        let results = scheduler.poll(&registry, settled + Duration::from_millis(30));
+        assert!(results[0].error.is_some());
+        assert_eq!(results[0].types[0].name, "Address");
+        assert!(scheduler.is_idle());
+
+        scheduler.did_remove("address.rs", settled);
+        let results = scheduler.poll(&registry, settled);
+        assert_eq!(results[0].path, "user.rs");
+        assert_eq!(results[0].broken[0].problem, Problem::MissingType);
+    }
+}
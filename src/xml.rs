@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Minimal XML reader
+// Covers what schema documents use: elements, attributes, and the predefined
+// and numeric character references. Text content is dropped, as are comments,
+// processing instructions, CDATA sections, and the document type declaration
+
+/// Deeply nested input is rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+/// An element with its attributes and child elements, in source order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    /// Qualified name as written (`xs:element`)
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub line: usize,
+}
+
+impl Element {
+    /// Name without its namespace prefix
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// `xs:string` is `string`
+pub fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parse a document into its root element
+pub fn parse(source: &str) -> Result<Element, String> {
+    let mut parser = Parser { source, pos: 0, line: 1, depth: 0 };
+    parser.skip_misc()?;
+    if !parser.rest().starts_with('<') {
+        return Err(parser.error("a root element"));
+    }
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < source.len() {
+        return Err(parser.error("end of input"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.pos..]
+    }
+
+    fn error(&self, expected: &str) -> String {
+        format!("line {}: expected {}", self.line, expected)
+    }
+
+    fn advance(&mut self, len: usize) {
+        let len = len.min(self.source.len() - self.pos);
+        self.line += self.source[self.pos..self.pos + len].matches('\n').count();
+        self.pos += len;
+    }
+
+    fn skip_whitespace(&mut self) {
+        let len = self.rest().len() - self.rest().trim_start().len();
+        self.advance(len);
+    }
+
+    /// Past `close`, erroring at end of input
+    fn skip_past(&mut self, close: &str) -> Result<(), String> {
+        match self.rest().find(close) {
+            Some(i) => {
+                self.advance(i + close.len());
+                Ok(())
+            }
+            None => Err(self.error(&format!("`{}`", close))),
+        }
+    }
+
+    /// Whitespace, comments, processing instructions, and the doctype
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("\u{feff}") {
+                self.advance('\u{feff}'.len_utf8());
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                // An internal subset sits in brackets and may contain `>`
+                let close = rest.find(['[', '>']).map(|i| rest.as_bytes()[i]);
+                if close == Some(b'[') {
+                    self.skip_past("]")?;
+                }
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// An element starting at `<`
+    fn element(&mut self) -> Result<Element, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!("line {}: nesting deeper than {}", self.line, MAX_DEPTH));
+        }
+        let line = self.line;
+        self.advance(1);
+        let name = self.name()?;
+        let mut element = Element { name, attributes: Vec::new(), children: Vec::new(), line };
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.advance(2);
+                self.depth -= 1;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.advance(1);
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("`=`"));
+            }
+            self.advance(1);
+            self.skip_whitespace();
+            let value = self.attribute_value()?;
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            let Some(next) = self.rest().find('<') else {
+                return Err(self.error(&format!("`</{}>`", element.name)));
+            };
+            self.advance(next);
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.advance(2);
+                let close = self.name()?;
+                if close != element.name {
+                    return Err(format!("line {}: expected `</{}>`, found `</{}>`", self.line, element.name, close));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("`>`"));
+                }
+                self.advance(1);
+                self.depth -= 1;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else {
+                let child = self.element()?;
+                element.children.push(child);
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let len = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/' | '<' | '"' | '\''))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("a name"));
+        }
+        let name = self.rest()[..len].to_string();
+        self.advance(len);
+        Ok(name)
+    }
+
+    fn attribute_value(&mut self) -> Result<String, String> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.error("a quoted value")),
+        };
+        self.advance(1);
+        let Some(end) = self.rest().find(quote) else {
+            return Err(self.error(&format!("`{}`", quote)));
+        };
+        let value = unescape(&self.rest()[..end]);
+        self.advance(end + 1);
+        Ok(value)
+    }
+}
+
+/// Resolve `&lt;`, `&amp;`, `&#10;`, and the other predefined references;
+/// unknown ones are kept as written
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let resolved = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            reference => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => reference.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        };
+        match resolved {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements_and_attributes() {
+        let source = r#"<?xml version="1.0"?>
+<!DOCTYPE schema [ <!ENTITY x "y"> ]>
+<!-- header -->
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="a&amp;b" type='xs:string'/>
+  text <![CDATA[ <not-an-element> ]]>
+  <xs:group name="g"><xs:sequence/></xs:group>
+</xs:schema>
+"#;
+        let root = parse(source).unwrap();
+        assert_eq!((root.local_name(), root.line), ("schema", 4));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].attribute("name"), Some("a&b"));
+        assert_eq!(root.children[1].children[0].name, "xs:sequence");
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse(&"<a>".repeat(200)).is_err());
+    }
+}
@@ -0,0 +1,521 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// Minimal YAML reader
+// Covers what API descriptions and similar documents use: block mappings and
+// sequences, flow collections, plain and quoted scalars, and literal or folded
+// block scalars, read into the same value the JSON parser produces. Anchors,
+// aliases, tags, and multi-document streams are rejected rather than guessed at
+
+use crate::json::Json;
+
+/// Deeply nested input is rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+/// One source line; `text` has its indentation, comment, and trailing
+/// whitespace removed, `raw` is kept for block scalars
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: String,
+    raw: &'a str,
+}
+
+/// Parse a single YAML document
+pub fn parse(source: &str) -> Result<Json, String> {
+    let mut lines = Vec::new();
+    let mut started = false;
+    for (i, raw) in source.lines().enumerate() {
+        let indent = raw.len() - raw.trim_start_matches(' ').len();
+        let text = strip_comment(&raw[indent..]).trim_end().to_string();
+        if indent == 0 && (text == "---" || text.starts_with("--- ")) {
+            if started {
+                return Err(format!("line {}: multiple documents aren't supported", i + 1));
+            }
+            started = true;
+            continue;
+        }
+        if indent == 0 && (text.starts_with('%') || text == "...") {
+            continue;
+        }
+        if !text.is_empty() {
+            started = true;
+        }
+        lines.push(Line { number: i + 1, indent, text, raw });
+    }
+
+    let mut parser = Parser { lines, pos: 0, depth: 0 };
+    let Some(indent) = parser.peek().map(|line| line.indent) else {
+        return Ok(Json::Null);
+    };
+    let value = parser.block(indent)?;
+    match parser.peek() {
+        Some(line) => Err(format!("line {}: unexpected indentation", line.number)),
+        None => Ok(value),
+    }
+}
+
+/// Drop a `#` comment that starts the line or follows whitespace, outside quotes
+fn strip_comment(text: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some('"') if c == '\\' => {
+                chars.next();
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '#' && previous.is_whitespace() => return &text[..i],
+            // Quotes only open a scalar at its start
+            None if (c == '"' || c == '\'') && (previous.is_whitespace() || matches!(previous, ':' | '-' | '[' | '{' | ',')) => {
+                quote = Some(c)
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    text
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Next line with content
+    fn peek(&mut self) -> Option<&Line<'_>> {
+        while self.lines.get(self.pos).is_some_and(|line| line.text.is_empty()) {
+            self.pos += 1;
+        }
+        self.lines.get(self.pos)
+    }
+
+    /// The node whose first line is indented by `indent`
+    fn block(&mut self, indent: usize) -> Result<Json, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            let number = self.peek().map_or(0, |line| line.number);
+            return Err(format!("line {}: nesting deeper than {}", number, MAX_DEPTH));
+        }
+        let value = match self.peek() {
+            None => Ok(Json::Null),
+            Some(line) if is_sequence_item(&line.text) => self.sequence(indent),
+            Some(line) if key_end(&line.text).is_some() => self.mapping(indent),
+            Some(_) => {
+                self.continued(indent.saturating_sub(1)).and_then(|(text, number)| inline(&text, number))
+            }
+        };
+        self.depth -= 1;
+        value
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Json, String> {
+        let mut items = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent != indent || !is_sequence_item(&line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start().to_string();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.nested_or_null(indent, false)?);
+                continue;
+            }
+            // `- name: x` starts a mapping indented to where `name` is
+            let column = indent + line.text.len() - rest.len();
+            let line = &mut self.lines[self.pos];
+            line.indent = column;
+            line.text = rest;
+            items.push(self.block(column)?);
+        }
+        Ok(Json::Array(items))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Json, String> {
+        let mut entries = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent {
+                break;
+            }
+            let number = line.number;
+            if line.indent > indent {
+                return Err(format!("line {}: unexpected indentation", number));
+            }
+            let Some(end) = key_end(&line.text) else {
+                return Err(format!("line {}: expected a mapping key", number));
+            };
+            let key = match scalar(line.text[..end].trim(), number)? {
+                Json::String(key) => key,
+                Json::Null => String::new(),
+                other => other.to_string(),
+            };
+            let value = line.text[end + 1..].trim().to_string();
+            let value = if value.is_empty() {
+                self.pos += 1;
+                // A sequence may sit at its key's own indentation
+                self.nested_or_null(indent, true)?
+            } else if value.starts_with('|') || value.starts_with('>') {
+                self.pos += 1;
+                Json::String(self.block_scalar(indent, &value, number)?)
+            } else {
+                let line = &mut self.lines[self.pos];
+                line.text = value;
+                let (text, number) = self.continued(indent)?;
+                inline(&text, number)?
+            };
+            entries.push((key, value));
+        }
+        Ok(Json::Object(entries))
+    }
+
+    /// The block nested under a line at `indent`, or null when there is none
+    fn nested_or_null(&mut self, indent: usize, sequence_at_indent: bool) -> Result<Json, String> {
+        match self.peek() {
+            Some(next) if next.indent > indent => {
+                let indent = next.indent;
+                self.block(indent)
+            }
+            Some(next) if sequence_at_indent && next.indent == indent && is_sequence_item(&next.text) => self.sequence(indent),
+            _ => Ok(Json::Null),
+        }
+    }
+
+    /// The current line's text joined with the lines indented past `indent`
+    /// that continue it
+    fn continued(&mut self, indent: usize) -> Result<(String, usize), String> {
+        let first = &self.lines[self.pos];
+        let (mut text, number) = (first.text.clone(), first.number);
+        let plain = !text.starts_with(['"', '\'', '[', '{']);
+        self.pos += 1;
+        while let Some(line) = self.peek() {
+            if line.indent <= indent {
+                break;
+            }
+            // A key can't continue a plain scalar: `a: 1` followed by `  b: 2`
+            if plain && key_end(&line.text).is_some() {
+                return Err(format!("line {}: unexpected indentation", line.number));
+            }
+            text.push(' ');
+            text.push_str(line.text.trim());
+            self.pos += 1;
+        }
+        Ok((text, number))
+    }
+
+    /// `|` or `>` with optional chomping (`-`, `+`) and indentation indicators
+    fn block_scalar(&mut self, parent: usize, header: &str, number: usize) -> Result<String, String> {
+        let literal = header.starts_with('|');
+        let mut chomp = ' ';
+        let mut explicit = None;
+        for c in header[1..].chars() {
+            match c {
+                '-' | '+' => chomp = c,
+                '1'..='9' => explicit = c.to_digit(10).map(|d| parent + d as usize),
+                _ => return Err(format!("line {}: invalid block scalar header `{}`", number, header)),
+            }
+        }
+
+        let mut body: Vec<&str> = Vec::new();
+        let mut content_indent = explicit;
+        while let Some(line) = self.lines.get(self.pos) {
+            let blank = line.raw.trim().is_empty();
+            if !blank && line.indent <= parent {
+                break;
+            }
+            if !blank && content_indent.is_none() {
+                content_indent = Some(line.indent);
+            }
+            let start = content_indent.unwrap_or(usize::MAX);
+            body.push(if blank { "" } else { line.raw.get(start..).unwrap_or_else(|| line.raw.trim_start()) });
+            self.pos += 1;
+        }
+
+        let trailing = body.iter().rev().take_while(|line| line.is_empty()).count();
+        let content = &body[..body.len() - trailing];
+        let mut text = String::new();
+        for (i, line) in content.iter().enumerate() {
+            if i > 0 {
+                // Folding joins lines with spaces; blank or more-indented lines keep their breaks
+                let joins = !literal && !line.is_empty() && !content[i - 1].is_empty() && !line.starts_with(' ');
+                text.push(if joins { ' ' } else { '\n' });
+            }
+            text.push_str(line);
+        }
+        match chomp {
+            '-' => {}
+            '+' => text.push_str(&"\n".repeat(trailing + 1)),
+            _ if !content.is_empty() => text.push('\n'),
+            _ => {}
+        }
+        Ok(text)
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Byte offset of the `:` ending a mapping key on this line
+fn key_end(text: &str) -> Option<usize> {
+    if text.starts_with(['[', '{']) || is_sequence_item(text) {
+        return None;
+    }
+    let search_from = match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => text[1..].find(quote)? + 2,
+        _ => 0,
+    };
+    let bytes = text.as_bytes();
+    (search_from..bytes.len()).find(|&i| bytes[i] == b':' && bytes.get(i + 1).is_none_or(|next| *next == b' '))
+}
+
+/// A flow value: scalar, `[...]`, or `{...}`
+fn inline(text: &str, number: usize) -> Result<Json, String> {
+    let mut flow = Flow { chars: text.chars().collect(), pos: 0, depth: 0, number };
+    let value = flow.value(false)?;
+    flow.skip_whitespace();
+    if flow.pos < flow.chars.len() {
+        return Err(flow.error("end of line"));
+    }
+    Ok(value)
+}
+
+struct Flow {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+    number: usize,
+}
+
+impl Flow {
+    fn error(&self, expected: &str) -> String {
+        format!("line {}: expected {}", self.number, expected)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.chars.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `nested` is set inside a flow collection, where `,]}` end plain scalars
+    fn value(&mut self, nested: bool) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.pos) {
+            Some('[') | Some('{') => {
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(format!("line {}: nesting deeper than {}", self.number, MAX_DEPTH));
+                }
+                let value = if self.chars[self.pos] == '[' { self.sequence() } else { self.mapping() };
+                self.depth -= 1;
+                value
+            }
+            Some('"') | Some('\'') => self.quoted().map(Json::String),
+            _ => {
+                let plain = self.plain(nested, false);
+                scalar(&plain, self.number)
+            }
+        }
+    }
+
+    fn sequence(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            if self.eat(']') {
+                return Ok(Json::Array(items));
+            }
+            items.push(self.value(true)?);
+            if !self.eat(',') && !matches!(self.chars.get(self.pos), Some(']')) {
+                return Err(self.error("`,` or `]`"));
+            }
+        }
+    }
+
+    fn mapping(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        loop {
+            if self.eat('}') {
+                return Ok(Json::Object(entries));
+            }
+            self.skip_whitespace();
+            let key = match self.chars.get(self.pos) {
+                Some('"') | Some('\'') => self.quoted()?,
+                _ => self.plain(true, true),
+            };
+            let value = if self.eat(':') { self.value(true)? } else { Json::Null };
+            entries.push((key, value));
+            if !self.eat(',') && !matches!(self.chars.get(self.pos), Some('}')) {
+                return Err(self.error("`,` or `}`"));
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        let quote = self.chars[self.pos];
+        self.pos += 1;
+        let mut text = String::new();
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                // `''` is a single quote inside single quotes
+                '\'' if quote == '\'' && self.chars.get(self.pos) == Some(&'\'') => {
+                    self.pos += 1;
+                    text.push('\'');
+                }
+                _ if c == quote => return Ok(text),
+                '\\' if quote == '"' => {
+                    let escaped = self.chars.get(self.pos).copied().ok_or_else(|| self.error("escape"))?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => text.push('\n'),
+                        't' => text.push('\t'),
+                        'r' => text.push('\r'),
+                        '0' => text.push('\0'),
+                        'u' => {
+                            let digits: String = self.chars.iter().skip(self.pos).take(4).collect();
+                            let code = u32::from_str_radix(&digits, 16).map_err(|_| self.error("4 hex digits"))?;
+                            self.pos += 4;
+                            text.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                        }
+                        other => text.push(other),
+                    }
+                }
+                _ => text.push(c),
+            }
+        }
+        Err(self.error(&format!("closing `{}`", quote)))
+    }
+
+    /// Plain scalar text; in a flow mapping key it also ends at `: `
+    fn plain(&mut self, nested: bool, key: bool) -> String {
+        let start = self.pos;
+        while let Some(&c) = self.chars.get(self.pos) {
+            let next = self.chars.get(self.pos + 1);
+            if (nested && matches!(c, ',' | ']' | '}')) || (key && c == ':' && next.is_none_or(|n| n.is_whitespace() || matches!(n, ',' | '}'))) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect::<String>().trim().to_string()
+    }
+}
+
+/// Resolve a plain scalar by the YAML 1.2 core schema
+fn scalar(text: &str, number: usize) -> Result<Json, String> {
+    if let Some(quote @ ('"' | '\'')) = text.chars().next() {
+        return inline(text, number).and_then(|value| match value {
+            Json::String(_) => Ok(value),
+            _ => Err(format!("line {}: expected closing `{}`", number, quote)),
+        });
+    }
+    if text.starts_with(['&', '*', '!']) {
+        return Err(format!("line {}: anchors, aliases, and tags aren't supported", number));
+    }
+    Ok(match text {
+        "" | "~" | "null" | "Null" | "NULL" => Json::Null,
+        "true" | "True" | "TRUE" => Json::Bool(true),
+        "false" | "False" | "FALSE" => Json::Bool(false),
+        _ => match number_value(text) {
+            Some(n) => Json::Number(n),
+            None => Json::String(text.to_string()),
+        },
+    })
+}
+
+fn number_value(text: &str) -> Option<f64> {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if let Some(hex) = digits.strip_prefix("0x") {
+        let value = i64::from_str_radix(hex, 16).ok()? as f64;
+        return Some(if text.starts_with('-') { -value } else { value });
+    }
+    let numeric = digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    if numeric {
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn test_block_and_flow_collections() {
+        let source = r#"
+%YAML 1.2
+---
+openapi: 3.1.0   # a version, not a number
+info:
+  title: "Shop: API"
+  version: '1.0'
+tags: [users, 'orders', {name: admin, x: 1}]
+servers:
+- url: https://example.com/v1
+  variables: {}
+paths:
+  /users/{id}:
+    get:
+      parameters:
+        - name: id
+          required: true
+          schema: { type: integer, minimum: -1.5e2 }
+        -
+          name: verbose
+empty:
+nothing: ~
+description: |
+  First line
+    indented # not a comment
+
+  after a blank
+summary: >-
+  folded
+  into one
+plain: a long plain
+  scalar that wraps
+"#;
+        let value = parse(source).unwrap();
+        let expected = json::parse(
+            r#"{"openapi":"3.1.0","info":{"title":"Shop: API","version":"1.0"},
+            "tags":["users","orders",{"name":"admin","x":1}],
+            "servers":[{"url":"https://example.com/v1","variables":{}}],
+            "paths":{"/users/{id}":{"get":{"parameters":[
+              {"name":"id","required":true,"schema":{"type":"integer","minimum":-150}},
+              {"name":"verbose"}]}}},
+            "empty":null,"nothing":null,
+            "description":"First line\n  indented # not a comment\n\nafter a blank\n",
+            "summary":"folded into one",
+            "plain":"a long plain scalar that wraps"}"#,
+        )
+        .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_rejects_unsupported_and_malformed_input() {
+        assert_eq!(parse("").unwrap(), Json::Null);
+        assert!(parse("a: 1\n  b: 2").is_err());
+        assert!(parse("base: &base {a: 1}\nother: *base").is_err());
+        assert!(parse("a: 1\n---\nb: 2").is_err());
+        assert!(parse("a: [1, 2").is_err());
+        assert!(parse("a: 'unterminated").is_err());
+        assert!(parse(&format!("a: {}", "[".repeat(MAX_DEPTH + 1))).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: PMPL-1.0-or-later
+// The `lic` binary run end to end: every subcommand on a small project, and
+// the message and status each one exits with when its arguments are wrong
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const USER_RS: &str = "/// A signed-up user\npub struct User {\n    pub id: u32,\n    pub name: String,\n    pub email: Option<String>,\n}\n";
+const USER_TS: &str = "export interface User {\n  id: number;\n  name: string;\n  email: string | null;\n}\n";
+const POINT_H: &str = "struct Point { int x; char *name; };\n";
+
+/// A fresh directory named for the test, holding the project files
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lic-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, text) in [("user.rs", USER_RS), ("user.ts", USER_TS), ("point.h", POINT_H)] {
+        std::fs::write(dir.join(file), text).unwrap();
+    }
+    dir
+}
+
+/// `lic args...` run in `dir`, with `stdin` as its input
+fn lic_with_input(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lic"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn lic(dir: &Path, args: &[&str]) -> Output {
+    lic_with_input(dir, args, "")
+}
+
+/// Stdout of a run that succeeded
+fn success(output: Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "exited with {}: {}", output.status, stderr);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Stderr of a run that failed with status 2, less the `lic: ` prefix
+fn failure(output: Output) -> String {
+    assert_eq!(output.status.code(), Some(2), "stdout: {}", String::from_utf8_lossy(&output.stdout));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    stderr.strip_prefix("lic: ").unwrap_or_else(|| panic!("no `lic: ` prefix on {:?}", stderr)).trim_end().to_string()
+}
+
+#[test]
+fn test_help_goes_to_stdout() {
+    let dir = project("help");
+    for args in [&["--help"][..], &["-h"], &["help"], &["report", "--help"]] {
+        let output = lic(&dir, args);
+        assert!(output.stderr.is_empty(), "{:?}", args);
+        assert!(success(output).starts_with("usage:\n  lic help\n  lic report --source <lang>"), "{:?}", args);
+    }
+    // Without a command, usage is the error
+    assert!(failure(lic(&dir, &[])).starts_with("usage:\n"));
+    assert!(failure(lic(&dir, &["compile"])).starts_with("usage:\n"));
+}
+
+#[test]
+fn test_malformed_arguments() {
+    let dir = project("arguments");
+    assert_eq!(failure(lic(&dir, &["report", "--verbose"])), "unknown option `--verbose`");
+    assert_eq!(failure(lic(&dir, &["report", "--source", "cobol"])), "unknown language `cobol`");
+    assert_eq!(failure(lic(&dir, &["report", "--source"])), "--source needs a value");
+    assert_eq!(failure(lic(&dir, &["report", "--triple", "nonsense"])), "malformed target triple `nonsense`");
+    assert_eq!(failure(lic(&dir, &["conformance", "--jobs", "many"])), "--jobs needs a number, got `many`");
+    assert_eq!(failure(lic(&dir, &["conformance", "--suite", "rust"])), "expected <lang>=<file>, got `rust`");
+    assert_eq!(failure(lic(&dir, &["conformance", "--toolchain", "rust"])), "expected <lang>=<command>, got `rust`");
+}
+
+#[test]
+fn test_report() {
+    let dir = project("report");
+    assert_eq!(
+        success(lic(&dir, &["report", "--source", "rust", "--target", "typescript", "user.rs"])),
+        "rust → typescript: 100.0% (Concorde)\n  User (line 2): 100.0% (Concorde)\n"
+    );
+    let stdin = success(lic_with_input(&dir, &["report", "--source", "rust", "--target", "typescript", "--from", "stdin", "-"], USER_RS));
+    assert!(stdin.starts_with("rust → typescript: 100.0% (Concorde)\n"));
+    // A consumer that mentions no type leaves nothing to score
+    std::fs::write(dir.join("main.ts"), "console.log(1);\n").unwrap();
+    let used = success(lic(&dir, &["report", "--source", "rust", "--target", "typescript", "--used-by", "main.ts", "user.rs"]));
+    assert!(!used.contains("User (line 2)"), "{}", used);
+
+    assert_eq!(failure(lic(&dir, &["report", "--source", "rust", "user.rs"])), "report needs --source and --target");
+    assert_eq!(failure(lic(&dir, &["report", "--source", "rust", "--target", "gleam"])), "report needs at least one file");
+    assert!(failure(lic(&dir, &["report", "--source", "rust", "--target", "gleam", "missing.rs"])).starts_with("missing.rs: "));
+    assert_eq!(
+        failure(lic(&dir, &["report", "--source", "rust", "--target", "gleam", "--project", "user.ts", "--used-by", "main.ts", "user.rs"])),
+        "--project and --used-by cannot be combined"
+    );
+}
+
+#[test]
+fn test_archive_and_trends() {
+    let dir = project("trends");
+    let archived = success(lic(&dir, &["report", "--source", "rust", "--target", "typescript", "--archive", "runs", "user.rs"]));
+    assert!(archived.contains("archived to runs/"), "{}", archived);
+    let chart = success(lic(&dir, &["trends", "--archive", "runs"]));
+    assert!(chart.starts_with("rust → typescript: 1 run(s), "), "{}", chart);
+    assert!(success(lic(&dir, &["trends", "--archive", "runs", "--source", "rust", "--target", "gleam"])).starts_with("rust → gleam: "));
+    std::fs::create_dir(dir.join("empty")).unwrap();
+    assert_eq!(success(lic(&dir, &["trends", "--archive", "empty"])), "no archived runs in empty\n");
+
+    assert_eq!(failure(lic(&dir, &["trends"])), "trends needs --archive");
+    assert_eq!(failure(lic(&dir, &["trends", "--archive", "runs", "--source", "rust"])), "give both --source and --target, or neither");
+}
+
+#[test]
+fn test_capabilities() {
+    let dir = project("capabilities");
+    let capabilities = success(lic(&dir, &["capabilities", "--source", "rust", "--target", "gleam"]));
+    assert!(capabilities.starts_with("rust → gleam\n  supported: records, "), "{}", capabilities);
+    assert_eq!(failure(lic(&dir, &["capabilities", "--source", "rust"])), "capabilities needs --source and --target");
+}
+
+#[test]
+fn test_scaffold() {
+    let dir = project("scaffold");
+    let gleam = success(lic(&dir, &["scaffold", "--source", "rust", "--target", "gleam", "user.rs"]));
+    assert!(gleam.starts_with("// Scaffolded from 1 rust type(s): 100.0% fidelity (Concorde)\n"));
+    assert!(gleam.contains("pub type User {\n  User(id: Int, name: String, email: Option(String))\n}\n"));
+
+    assert_eq!(failure(lic(&dir, &["scaffold", "--target", "gleam", "user.rs"])), "scaffold needs --source and --target");
+    assert_eq!(failure(lic(&dir, &["scaffold", "--source", "rust", "--target", "gleam"])), "scaffold needs at least one file");
+    assert!(failure(lic(&dir, &["scaffold", "--source", "rust", "--target", "cpp", "user.rs"])).contains("no scaffold generator available"));
+}
+
+#[test]
+fn test_bindings() {
+    let dir = project("bindings");
+    let rescript = success(lic(&dir, &["bindings", "--source", "rust", "--target", "rescript", "--module", "./pkg/app.js", "user.rs"]));
+    assert!(rescript.starts_with("// Bindings to 1 Rust type(s) exported from ./pkg/app.js\n"));
+    assert!(rescript.contains("@module(\"./pkg/app.js\") external userFromJson: string => user = \"user_from_json\"\n"));
+    let exports = success(lic(&dir, &["bindings", "--source", "rust", "--target", "rescript", "--module", "./pkg/app.js", "--exports", "user.rs"]));
+    assert!(exports.contains("#[wasm_bindgen]\npub fn user_from_json(json: &str) -> Result<JsValue, JsError> {\n"));
+
+    assert_eq!(failure(lic(&dir, &["bindings", "--source", "rust", "--target", "rescript", "user.rs"])), "bindings needs --source, --target and --module");
+    assert_eq!(failure(lic(&dir, &["bindings", "--source", "rust", "--target", "rescript", "--module", "m.js"])), "bindings needs at least one file");
+    let unsupported = failure(lic(&dir, &["bindings", "--source", "rust", "--target", "julia", "--module", "m.js", "user.rs"]));
+    assert!(unsupported.contains("bindings are generated for ReScript from Rust"), "{}", unsupported);
+}
+
+#[test]
+fn test_schema() {
+    let dir = project("schema");
+    let schema = success(lic(&dir, &["schema", "--source", "rust", "--target", "jsonschema", "user.rs"]));
+    assert!(schema.starts_with("{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"title\":\"User\","));
+
+    std::fs::write(dir.join("spec.yaml"), "openapi: 3.1.0\ninfo: {}\n").unwrap();
+    let merged = success(lic(&dir, &["schema", "--source", "rust", "--target", "openapi", "--into", "spec.yaml", "user.rs"]));
+    assert_eq!(merged, "1 schema(s) merged into spec.yaml\n");
+    assert!(std::fs::read_to_string(dir.join("spec.yaml")).unwrap().contains("\"User\": {"));
+
+    let proto = success(lic(&dir, &["schema", "--source", "rust", "--target", "protobuf", "--package", "shop", "--lock", "proto.lock", "user.rs"]));
+    assert!(proto.contains("package shop;\n"));
+    assert!(proto.contains("message User {\n  uint32 id = 1;\n  string name = 2;\n  optional string email = 3;\n}\n"));
+    assert!(std::fs::read_to_string(dir.join("proto.lock")).unwrap().contains("{\"name\":\"email\",\"number\":3}"));
+
+    assert_eq!(failure(lic(&dir, &["schema", "--source", "rust", "--target", "protobuf", "user.rs"])), "a proto schema needs --package");
+    assert_eq!(failure(lic(&dir, &["schema", "--source", "rust", "user.rs"])), "schema needs --source and --target");
+    assert_eq!(failure(lic(&dir, &["schema", "--source", "rust", "--target", "openapi"])), "schema needs at least one file");
+    assert!(failure(lic(&dir, &["schema", "--source", "rust", "--target", "gleam", "user.rs"])).contains("schemas are generated for JSON Schema and OpenAPI"));
+}
+
+#[test]
+fn test_graphql() {
+    let dir = project("graphql");
+    let sdl = success(lic(&dir, &["graphql", "--source", "rust", "user.rs"]));
+    assert!(sdl.contains("\"A signed-up user\"\ntype User {\n"), "{}", sdl);
+    assert!(sdl.contains("input UserInput {\n"));
+    assert_eq!(failure(lic(&dir, &["graphql", "user.rs"])), "graphql needs --source");
+    assert_eq!(failure(lic(&dir, &["graphql", "--source", "rust"])), "graphql needs at least one file");
+}
+
+#[test]
+fn test_codecs() {
+    let dir = project("codecs");
+    assert!(success(lic(&dir, &["codecs", "--source", "rust", "--target", "rust", "user.rs"])).contains("impl Serialize for User {"));
+    assert!(success(lic(&dir, &["codecs", "--source", "rust", "--target", "rescript", "user.rs"])).contains("exception DecodeError(string)\n"));
+    assert_eq!(failure(lic(&dir, &["codecs", "--source", "rust", "user.rs"])), "codecs needs --source and --target");
+    assert_eq!(failure(lic(&dir, &["codecs", "--source", "rust", "--target", "rust"])), "codecs needs at least one file");
+    assert!(failure(lic(&dir, &["codecs", "--source", "rust", "--target", "gleam", "user.rs"])).contains("codecs are generated for Rust and ReScript"));
+}
+
+#[test]
+fn test_audit() {
+    let dir = project("audit");
+    let c = success(lic(&dir, &["audit", "--source", "c", "point.h"]));
+    assert_eq!(c, "Point.name: bytes assumed to be valid UTF-8\n1 risky field(s)\n");
+    // Paranoid audits print the hardened bindings after the findings
+    let paranoid = success(lic(&dir, &["audit", "--source", "c", "--paranoid", "point.h"]));
+    assert!(paranoid.starts_with(&c));
+    assert!(paranoid.contains("pub enum FfiError {\n"));
+    assert!(success(lic(&dir, &["audit", "--source", "rust", "user.rs"])).ends_with(" risky field(s)\n"));
+
+    assert_eq!(failure(lic(&dir, &["audit", "point.h"])), "audit needs --source");
+    assert_eq!(failure(lic(&dir, &["audit", "--source", "c"])), "audit needs at least one file");
+    assert_eq!(failure(lic(&dir, &["audit", "--source", "typescript", "user.ts"])), "no FFI bindings are generated from typescript");
+}
+
+#[test]
+fn test_snapshots() {
+    let dir = project("snapshots");
+    let samples = success(lic(&dir, &["snapshots", "--source", "rust", "user.rs"]));
+    assert_eq!(samples.lines().count(), 3);
+    assert!(samples.starts_with("{\"type\":\"User\",\"fingerprint\":\"fp1-"));
+    std::fs::write(dir.join("user.jsonl"), &samples).unwrap();
+    assert_eq!(success(lic(&dir, &["snapshots", "--source", "rust", "--verify", "user.jsonl", "user.rs"])), "0 problem(s) in 3 snapshot(s)\n");
+    // A side whose `User` has drifted rejects every sample
+    std::fs::write(dir.join("drifted.rs"), "pub struct User {\n    pub id: String,\n}\n").unwrap();
+    let mismatched = lic(&dir, &["snapshots", "--source", "rust", "--verify", "user.jsonl", "drifted.rs"]);
+    assert!(String::from_utf8_lossy(&mismatched.stdout).ends_with("6 problem(s) in 3 snapshot(s)\n"));
+    assert_eq!(failure(mismatched), "snapshots don't match this side's types");
+
+    assert_eq!(failure(lic(&dir, &["snapshots", "user.rs"])), "snapshots needs --source");
+    assert_eq!(failure(lic(&dir, &["snapshots", "--source", "rust"])), "snapshots needs at least one file");
+    assert!(failure(lic(&dir, &["snapshots", "--source", "rust", "--verify", "missing.jsonl", "user.rs"])).starts_with("missing.jsonl: "));
+}
+
+#[test]
+fn test_bom() {
+    let dir = project("bom");
+    let bom = success(lic(&dir, &["bom", "--source", "rust", "--target", "typescript", "--target", "gleam", "user.rs"]));
+    assert!(bom.starts_with("{\"format\":\"interop-bom\",\"version\":1,"));
+    assert!(bom.contains("{\"language\":\"gleam\",\"score\":1,\"transport\":\"Concorde\",\"degraded\":[]}"));
+    assert_eq!(success(lic(&dir, &["bom", "--source", "rust", "--target", "gleam", "--into", "bom.json", "user.rs"])), "1 type(s) in bom.json\n");
+    // Written again, the file is updated in place
+    assert_eq!(success(lic(&dir, &["bom", "--source", "typescript", "--target", "rust", "--into", "bom.json", "user.ts"])), "2 type(s) in bom.json\n");
+
+    assert_eq!(failure(lic(&dir, &["bom", "--target", "gleam", "user.rs"])), "bom needs --source");
+    assert_eq!(failure(lic(&dir, &["bom", "--source", "rust", "user.rs"])), "bom needs at least one --target");
+    assert_eq!(failure(lic(&dir, &["bom", "--source", "rust", "--target", "gleam"])), "bom needs at least one file");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_conformance() {
+    let dir = project("conformance");
+    let sandbox = dir.join("sandbox");
+    let sandbox = sandbox.to_str().unwrap();
+    // Each side reads the snapshots back unchanged; toolchains run in the sandbox
+    std::fs::write(dir.join("tag.rs"), "pub struct Tag {\n    pub label: String,\n}\n").unwrap();
+    std::fs::write(dir.join("tag.ts"), "export interface Tag {\n  label: string;\n}\n").unwrap();
+    let echo = dir.join("echo.sh");
+    std::fs::write(&echo, "cp \"$SNAPSHOTS_IN\" \"$SNAPSHOTS_OUT\"\n").unwrap();
+    let suites = ["conformance", "--suite", "rust=tag.rs", "--suite", "typescript=tag.ts", "--sandbox", sandbox];
+    let (rust, typescript) = (format!("rust=sh {}", echo.display()), format!("typescript=sh {}", echo.display()));
+    let toolchains = ["--toolchain", &rust, "--toolchain", &typescript, "--into", "matrix.json"];
+    let matrix = success(lic(&dir, &[&suites[..], &toolchains[..]].concat()));
+    assert!(matrix.ends_with("matrix written to matrix.json\n"), "{}", matrix);
+    assert!(std::fs::read_to_string(dir.join("matrix.json")).unwrap().starts_with('{'));
+    // Without toolchains nothing runs, so no claim is confirmed
+    assert_eq!(failure(lic(&dir, &suites)), "2 Concorde claim(s) not confirmed");
+    // `u32` and `number` score Concorde but write different wire shapes
+    let users = ["conformance", "--suite", "rust=user.rs", "--suite", "typescript=user.ts", "--sandbox", sandbox];
+    let refuted = lic(&dir, &[&users[..], &toolchains[..4]].concat());
+    assert!(String::from_utf8_lossy(&refuted.stdout).contains("rust → typescript: typescript: User `zero` from rust: wire shape differs"));
+    assert_eq!(failure(refuted), "1 Concorde claim(s) not confirmed");
+
+    assert_eq!(failure(lic(&dir, &["conformance"])), "conformance needs at least one --suite");
+    assert!(failure(lic(&dir, &["conformance", "--suite", "rust=missing.rs"])).starts_with("missing.rs: "));
+}
+
+#[test]
+fn test_watch_arguments() {
+    let dir = project("watch");
+    assert_eq!(failure(lic(&dir, &["watch", "user.rs"])), "watch needs --source");
+    assert_eq!(failure(lic(&dir, &["watch", "--source", "rust"])), "watch needs at least one file");
+    assert_eq!(failure(lic(&dir, &["watch", "--source", "rust", "--from", "stdin", "user.rs"])), "watch only follows the working tree");
+}
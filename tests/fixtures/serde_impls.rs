@@ -0,0 +1,556 @@
+use serde::de::{self, Deserialize, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, SerializeStructVariant, SerializeTupleStruct, SerializeTupleVariant, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub user_id: u64,
+    pub display_name: String,
+    pub mail: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl Serialize for User {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("User", 4)?;
+        state.serialize_field("userId", &self.user_id)?;
+        state.serialize_field("displayName", &self.display_name)?;
+        state.serialize_field("mail", &self.mail)?;
+        state.serialize_field("tags", &self.tags)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for User {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UserVisitor;
+
+        impl<'de> Visitor<'de> for UserVisitor {
+            type Value = User;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `User`")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<User, A::Error> {
+                let mut user_id = None;
+                let mut display_name = None;
+                let mut mail = None;
+                let mut tags = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "userId" if user_id.is_some() => return Err(de::Error::duplicate_field("userId")),
+                        "userId" => user_id = Some(map.next_value()?),
+                        "displayName" if display_name.is_some() => return Err(de::Error::duplicate_field("displayName")),
+                        "displayName" => display_name = Some(map.next_value()?),
+                        "mail" if mail.is_some() => return Err(de::Error::duplicate_field("mail")),
+                        "mail" => mail = Some(map.next_value()?),
+                        "tags" if tags.is_some() => return Err(de::Error::duplicate_field("tags")),
+                        "tags" => tags = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(User {
+                    user_id: user_id.ok_or_else(|| de::Error::missing_field("userId"))?,
+                    display_name: display_name.ok_or_else(|| de::Error::missing_field("displayName"))?,
+                    mail: mail.flatten(),
+                    tags: tags.unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("User", &["userId", "displayName", "mail", "tags"], UserVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email(pub String);
+
+impl Serialize for Email {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Email", &self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Email {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EmailVisitor;
+
+        impl<'de> Visitor<'de> for EmailVisitor {
+            type Value = Email;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Email`")
+            }
+
+            fn visit_newtype_struct<E: Deserializer<'de>>(self, deserializer: E) -> Result<Email, E::Error> {
+                Deserialize::deserialize(deserializer).map(Email)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("Email", EmailVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point(pub i32, pub i32);
+
+impl Serialize for Point {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_tuple_struct("Point", 2)?;
+        state.serialize_field(&self.0)?;
+        state.serialize_field(&self.1)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Point {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PointVisitor;
+
+        impl<'de> Visitor<'de> for PointVisitor {
+            type Value = Point;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Point`")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Point, A::Error> {
+                Ok(Point(
+                    seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?,
+                    seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?,
+                ))
+            }
+        }
+
+        deserializer.deserialize_tuple_struct("Point", 2, PointVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+    },
+    Empty,
+    Rect {
+        width: f64,
+        height: f64,
+        label: Option<String>,
+    },
+}
+
+impl Serialize for Shape {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Shape::Circle { radius } => {
+                let mut state = serializer.serialize_struct("Shape", 2)?;
+                state.serialize_field("kind", "circle")?;
+                state.serialize_field("radius", radius)?;
+                state.end()
+            }
+            Shape::Empty => {
+                let mut state = serializer.serialize_struct("Shape", 1)?;
+                state.serialize_field("kind", "none")?;
+                state.end()
+            }
+            Shape::Rect { width, height, label } => {
+                let mut state = serializer.serialize_struct("Shape", 4)?;
+                state.serialize_field("kind", "rect")?;
+                state.serialize_field("width", width)?;
+                state.serialize_field("height", height)?;
+                state.serialize_field("label", label)?;
+                state.end()
+            }
+        }
+    }
+}
+
+// Payload fields of every `Shape` variant, held until the tag says which they build
+#[derive(Default)]
+struct ShapeFields {
+    radius: Option<f64>,
+    width: Option<f64>,
+    height: Option<f64>,
+    label: Option<Option<String>>,
+}
+
+impl ShapeFields {
+    fn read<'de, A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "radius" if self.radius.is_some() => return Err(de::Error::duplicate_field("radius")),
+            "radius" => self.radius = Some(map.next_value()?),
+            "width" if self.width.is_some() => return Err(de::Error::duplicate_field("width")),
+            "width" => self.width = Some(map.next_value()?),
+            "height" if self.height.is_some() => return Err(de::Error::duplicate_field("height")),
+            "height" => self.height = Some(map.next_value()?),
+            "label" if self.label.is_some() => return Err(de::Error::duplicate_field("label")),
+            "label" => self.label = Some(map.next_value()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for Shape {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &["circle", "none", "rect"];
+        struct ShapeVisitor;
+
+        impl<'de> Visitor<'de> for ShapeVisitor {
+            type Value = Shape;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Shape`")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Shape, A::Error> {
+                let mut tag: Option<String> = None;
+                let mut fields = ShapeFields::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "kind" if tag.is_some() => return Err(de::Error::duplicate_field("kind")),
+                        "kind" => tag = Some(map.next_value()?),
+                        other => {
+                            if !fields.read(other, &mut map)? {
+                                map.next_value::<de::IgnoredAny>()?;
+                            }
+                        }
+                    }
+                }
+                match tag.ok_or_else(|| de::Error::missing_field("kind"))?.as_str() {
+                    "circle" => Ok(Shape::Circle {
+                        radius: fields.radius.ok_or_else(|| de::Error::missing_field("radius"))?,
+                    }),
+                    "none" => Ok(Shape::Empty),
+                    "rect" => Ok(Shape::Rect {
+                        width: fields.width.ok_or_else(|| de::Error::missing_field("width"))?,
+                        height: fields.height.ok_or_else(|| de::Error::missing_field("height"))?,
+                        label: fields.label.flatten(),
+                    }),
+                    other => Err(de::Error::unknown_variant(other, VARIANTS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ShapeVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Active,
+    Suspended {
+        reason: String,
+    },
+    Code(u16),
+    Pair(i32, i32),
+}
+
+impl Serialize for Status {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Status::Active => serializer.serialize_unit_variant("Status", 0, "Active"),
+            Status::Suspended { reason } => {
+                let mut state = serializer.serialize_struct_variant("Status", 1, "Suspended", 1)?;
+                state.serialize_field("reason", reason)?;
+                state.end()
+            }
+            Status::Code(f0) => serializer.serialize_newtype_variant("Status", 2, "Code", f0),
+            Status::Pair(f0, f1) => {
+                let mut state = serializer.serialize_tuple_variant("Status", 3, "Pair", 2)?;
+                state.serialize_field(f0)?;
+                state.serialize_field(f1)?;
+                state.end()
+            }
+        }
+    }
+}
+
+// Payload fields of every `Status` variant, held until the tag says which they build
+#[derive(Default)]
+struct StatusFields {
+    reason: Option<String>,
+}
+
+impl StatusFields {
+    fn read<'de, A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "reason" if self.reason.is_some() => return Err(de::Error::duplicate_field("reason")),
+            "reason" => self.reason = Some(map.next_value()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+struct StatusFieldsVisitor;
+
+impl<'de> Visitor<'de> for StatusFieldsVisitor {
+    type Value = StatusFields;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a `Status` payload")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<StatusFields, A::Error> {
+        let mut fields = StatusFields::default();
+        while let Some(key) = map.next_key::<String>()? {
+            if !fields.read(&key, &mut map)? {
+                map.next_value::<de::IgnoredAny>()?;
+            }
+        }
+        Ok(fields)
+    }
+}
+
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &["Active", "Suspended", "Code", "Pair"];
+        struct StatusVisitor;
+
+        impl<'de> Visitor<'de> for StatusVisitor {
+            type Value = Status;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Status`")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Status, A::Error> {
+                let (tag, variant) = data.variant::<String>()?;
+                match tag.as_str() {
+                    "Active" => {
+                        variant.unit_variant()?;
+                        Ok(Status::Active)
+                    }
+                    "Suspended" => {
+                        let fields = variant.struct_variant(&["reason"], StatusFieldsVisitor)?;
+                        Ok(Status::Suspended {
+                            reason: fields.reason.ok_or_else(|| de::Error::missing_field("reason"))?,
+                        })
+                    }
+                    "Code" => Ok(Status::Code(variant.newtype_variant()?)),
+                    "Pair" => {
+                        let (f0, f1) = variant.newtype_variant()?;
+                        Ok(Status::Pair(f0, f1))
+                    }
+                    other => Err(de::Error::unknown_variant(other, VARIANTS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Status", VARIANTS, StatusVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Click {
+        pos_x: i32,
+        label: Option<String>,
+    },
+    Key(String),
+    Move(i32, i32),
+    Idle,
+}
+
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Event::Click { pos_x, label } => {
+                struct Content<'a> {
+                    pos_x: &'a i32,
+                    label: &'a Option<String>,
+                }
+
+                impl Serialize for Content<'_> {
+                    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        let mut state = serializer.serialize_struct("Click", 2)?;
+                        state.serialize_field("posX", self.pos_x)?;
+                        state.serialize_field("label", self.label)?;
+                        state.end()
+                    }
+                }
+
+                let mut state = serializer.serialize_struct("Event", 2)?;
+                state.serialize_field("t", "Click")?;
+                state.serialize_field("c", &Content { pos_x, label })?;
+                state.end()
+            }
+            Event::Key(f0) => {
+                let mut state = serializer.serialize_struct("Event", 2)?;
+                state.serialize_field("t", "Key")?;
+                state.serialize_field("c", f0)?;
+                state.end()
+            }
+            Event::Move(f0, f1) => {
+                let mut state = serializer.serialize_struct("Event", 2)?;
+                state.serialize_field("t", "Move")?;
+                state.serialize_field("c", &(f0, f1))?;
+                state.end()
+            }
+            Event::Idle => {
+                let mut state = serializer.serialize_struct("Event", 1)?;
+                state.serialize_field("t", "Idle")?;
+                state.end()
+            }
+        }
+    }
+}
+
+// Payload fields of every `Event` variant, held until the tag says which they build
+#[derive(Default)]
+struct EventFields {
+    pos_x: Option<i32>,
+    label: Option<Option<String>>,
+}
+
+impl EventFields {
+    fn read<'de, A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "posX" if self.pos_x.is_some() => return Err(de::Error::duplicate_field("posX")),
+            "posX" => self.pos_x = Some(map.next_value()?),
+            "label" if self.label.is_some() => return Err(de::Error::duplicate_field("label")),
+            "label" => self.label = Some(map.next_value()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+struct EventFieldsVisitor;
+
+impl<'de> Visitor<'de> for EventFieldsVisitor {
+    type Value = EventFields;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a `Event` payload")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<EventFields, A::Error> {
+        let mut fields = EventFields::default();
+        while let Some(key) = map.next_key::<String>()? {
+            if !fields.read(&key, &mut map)? {
+                map.next_value::<de::IgnoredAny>()?;
+            }
+        }
+        Ok(fields)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EventFieldsVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &["Click", "Key", "Move", "Idle"];
+        struct EventVisitor;
+
+        impl<'de> Visitor<'de> for EventVisitor {
+            type Value = Event;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Event`")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Event, A::Error> {
+                let mut tag: Option<String> = None;
+                let mut content = false;
+                let mut fields: Option<EventFields> = None;
+                let mut value: Option<Event> = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "t" if tag.is_some() => return Err(de::Error::duplicate_field("t")),
+                        "t" => tag = Some(map.next_value()?),
+                        "c" if content => return Err(de::Error::duplicate_field("c")),
+                        "c" => {
+                            content = true;
+                            match tag.as_deref() {
+                                Some("Key") => value = Some(Event::Key(map.next_value()?)),
+                                Some("Move") => {
+                                    let (f0, f1) = map.next_value()?;
+                                    value = Some(Event::Move(f0, f1));
+                                }
+                                Some("Idle") => {
+                                    map.next_value::<de::IgnoredAny>()?;
+                                }
+                                _ => fields = Some(map.next_value()?),
+                            }
+                        }
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                if let Some(value) = value {
+                    return Ok(value);
+                }
+                let fields = fields.unwrap_or_default();
+                match tag.ok_or_else(|| de::Error::missing_field("t"))?.as_str() {
+                    "Idle" => Ok(Event::Idle),
+                    "Click" | "Key" | "Move" if !content => Err(de::Error::missing_field("c")),
+                    "Click" => Ok(Event::Click {
+                        pos_x: fields.pos_x.ok_or_else(|| de::Error::missing_field("posX"))?,
+                        label: fields.label.flatten(),
+                    }),
+                    "Key" | "Move" => Err(de::Error::custom("the content of a `Event` variant must follow its tag")),
+                    other => Err(de::Error::unknown_variant(other, VARIANTS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(EventVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Level {
+    LowPower,
+    High,
+}
+
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Level::LowPower => serializer.serialize_unit_variant("Level", 0, "LOW_POWER"),
+            Level::High => serializer.serialize_unit_variant("Level", 1, "HIGH"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Level {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &["LOW_POWER", "HIGH"];
+        struct LevelVisitor;
+
+        impl<'de> Visitor<'de> for LevelVisitor {
+            type Value = Level;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a `Level`")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Level, A::Error> {
+                let (tag, variant) = data.variant::<String>()?;
+                match tag.as_str() {
+                    "LOW_POWER" => {
+                        variant.unit_variant()?;
+                        Ok(Level::LowPower)
+                    }
+                    "HIGH" => {
+                        variant.unit_variant()?;
+                        Ok(Level::High)
+                    }
+                    other => Err(de::Error::unknown_variant(other, VARIANTS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Level", VARIANTS, LevelVisitor)
+    }
+}
@@ -0,0 +1,52 @@
+// Types serde derives impls for; `serde_round_trip` maps this same source
+// and checks the impls generated for it read and write what these do
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub user_id: u64,
+    pub display_name: String,
+    #[serde(rename = "mail")]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Email(pub String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Point(pub i32, pub i32);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Shape {
+    Circle { radius: f64 },
+    #[serde(rename = "none")]
+    Empty,
+    Rect { width: f64, height: f64, label: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Status {
+    Active,
+    Suspended { reason: String },
+    Code(u16),
+    Pair(i32, i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c", rename_all_fields = "camelCase")]
+pub enum Event {
+    Click { pos_x: i32, label: Option<String> },
+    Key(String),
+    Move(i32, i32),
+    Idle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Level {
+    LowPower,
+    High,
+}